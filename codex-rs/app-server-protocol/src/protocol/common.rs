@@ -133,12 +133,41 @@ client_request_definitions! {
         params: v2::ReviewStartParams,
         response: v2::TurnStartResponse,
     },
+    /// Answers an `approval/request` notification.
+    ApprovalRespond => "approval/respond" {
+        params: v2::ApprovalRespondParams,
+        response: v2::ApprovalRespondResponse,
+    },
+    /// Opts a conversation into `codex/fileChanged` notifications.
+    SubscribeFileChanges => "codex/subscribeFileChanges" {
+        params: v2::SubscribeFileChangesParams,
+        response: v2::SubscribeFileChangesResponse,
+    },
+    /// Stops every conversation, then the server; see `v2::ShutdownParams`.
+    Shutdown => "codex/shutdown" {
+        params: v2::ShutdownParams,
+        response: v2::ShutdownResponse,
+    },
 
     ModelList => "model/list" {
         params: v2::ModelListParams,
         response: v2::ModelListResponse,
     },
 
+    CommandList => "commands/list" {
+        params: v2::CommandListParams,
+        response: v2::CommandListResponse,
+    },
+
+    WorkspaceFileSearch => "workspace/fileSearch" {
+        params: v2::WorkspaceFileSearchParams,
+        response: v2::WorkspaceFileSearchResponse,
+    },
+    WorkspaceFileSearchCancel => "workspace/fileSearch/cancel" {
+        params: v2::WorkspaceFileSearchCancelParams,
+        response: v2::WorkspaceFileSearchCancelResponse,
+    },
+
     LoginAccount => "account/login/start" {
         params: v2::LoginAccountParams,
         response: v2::LoginAccountResponse,
@@ -500,6 +529,10 @@ server_notification_definitions! {
     ReasoningSummaryTextDelta => "item/reasoning/summaryTextDelta" (v2::ReasoningSummaryTextDeltaNotification),
     ReasoningSummaryPartAdded => "item/reasoning/summaryPartAdded" (v2::ReasoningSummaryPartAddedNotification),
     ReasoningTextDelta => "item/reasoning/textDelta" (v2::ReasoningTextDeltaNotification),
+    /// Sent for threads started with `approvalRequests`; answer with `approval/respond`.
+    ApprovalRequest => "approval/request" (v2::ApprovalRequestNotification),
+    /// Sent for conversations subscribed with `codex/subscribeFileChanges`.
+    FileChanged => "codex/fileChanged" (v2::FileChangedNotification),
 
     /// Notifies the user of world-writable directories on Windows, which cannot be protected by the sandbox.
     WindowsWorldWritableWarning => "windows/worldWritableWarning" (v2::WindowsWorldWritableWarningNotification),
@@ -748,6 +781,31 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn serialize_approval_respond() -> Result<()> {
+        let request = ClientRequest::ApprovalRespond {
+            request_id: RequestId::Integer(6),
+            params: v2::ApprovalRespondParams {
+                thread_id: "thread-1".to_string(),
+                request_id: "approval-1".to_string(),
+                decision: v2::ApprovalRespondDecision::AllowAlways,
+            },
+        };
+        assert_eq!(
+            json!({
+                "method": "approval/respond",
+                "id": 6,
+                "params": {
+                    "threadId": "thread-1",
+                    "requestId": "approval-1",
+                    "decision": "allowAlways"
+                }
+            }),
+            serde_json::to_value(&request)?,
+        );
+        Ok(())
+    }
+
     #[test]
     fn account_serializes_fields_in_camel_case() -> Result<()> {
         let api_key = v2::Account::ApiKey {};
@@ -793,4 +851,72 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn serialize_list_commands() -> Result<()> {
+        let request = ClientRequest::CommandList {
+            request_id: RequestId::Integer(7),
+            params: v2::CommandListParams::default(),
+        };
+        assert_eq!(
+            json!({
+                "method": "commands/list",
+                "id": 7,
+                "params": {
+                    "cwd": null
+                }
+            }),
+            serde_json::to_value(&request)?,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn file_change_subscription_round_trips() -> Result<()> {
+        let conversation_id = ConversationId::from_string("67e55044-10b1-426f-9247-bb680e5fe0c8")?;
+        let request = ClientRequest::SubscribeFileChanges {
+            request_id: RequestId::Integer(8),
+            params: v2::SubscribeFileChangesParams { conversation_id },
+        };
+        let request_json = json!({
+            "method": "codex/subscribeFileChanges",
+            "id": 8,
+            "params": {
+                "conversationId": "67e55044-10b1-426f-9247-bb680e5fe0c8"
+            }
+        });
+        assert_eq!(request_json, serde_json::to_value(&request)?);
+        assert_eq!(
+            request,
+            serde_json::from_value::<ClientRequest>(request_json)?
+        );
+
+        let notification = ServerNotification::FileChanged(v2::FileChangedNotification {
+            conversation_id,
+            turn_id: "turn-1".to_string(),
+            path: PathBuf::from("/repo/new.rs"),
+            kind: v2::FileChangedKind::Renamed,
+            old_path: Some(PathBuf::from("/repo/old.rs")),
+        });
+        let notification_json = json!({
+            "method": "codex/fileChanged",
+            "params": {
+                "conversationId": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+                "turnId": "turn-1",
+                "path": "/repo/new.rs",
+                "kind": "renamed",
+                "oldPath": "/repo/old.rs"
+            }
+        });
+        assert_eq!(notification_json, serde_json::to_value(&notification)?);
+        let jsonrpc = JSONRPCNotification {
+            method: notification.to_string(),
+            params: Some(notification.to_params()?),
+        };
+        assert_eq!(
+            notification_json,
+            serde_json::to_value(ServerNotification::try_from(jsonrpc)?)?
+        );
+        Ok(())
+    }
 }
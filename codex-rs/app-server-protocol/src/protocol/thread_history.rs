@@ -57,6 +57,7 @@ impl ThreadHistoryBuilder {
             EventMsg::EnteredReviewMode(_) => {}
             EventMsg::ExitedReviewMode(_) => {}
             EventMsg::UndoCompleted(_) => {}
+            EventMsg::HistoryFlagSet(_) => {}
             EventMsg::TurnAborted(payload) => self.handle_turn_aborted(payload),
             _ => {}
         }
@@ -14,6 +14,7 @@ use codex_protocol::parse_command::ParsedCommand as CoreParsedCommand;
 use codex_protocol::protocol::CreditsSnapshot as CoreCreditsSnapshot;
 use codex_protocol::protocol::RateLimitSnapshot as CoreRateLimitSnapshot;
 use codex_protocol::protocol::RateLimitWindow as CoreRateLimitWindow;
+use codex_protocol::protocol::ReviewDecision as CoreReviewDecision;
 use codex_protocol::user_input::UserInput as CoreUserInput;
 use mcp_types::ContentBlock as McpContentBlock;
 use schemars::JsonSchema;
@@ -59,6 +60,12 @@ v2_enum_from_core!(
     }
 );
 
+v2_enum_from_core!(
+    pub enum FileChangedKind from codex_protocol::protocol::TurnFileChangeKind {
+        Added, Modified, Deleted, Renamed
+    }
+);
+
 v2_enum_from_core!(
     pub enum CommandRiskLevel from codex_protocol::approvals::SandboxRiskLevel {
         Low,
@@ -365,6 +372,101 @@ pub struct ModelListResponse {
     pub next_cursor: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct CommandListParams {
+    /// Directory used to decide which commands apply (e.g. Git-only ones).
+    /// Defaults to the server's working directory.
+    pub cwd: Option<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum SlashCommandSource {
+    Builtin,
+    /// A saved prompt from `$CODEX_HOME/prompts`, invoked as `/prompts:<name>`.
+    CustomPrompt,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SlashCommand {
+    /// Name without the leading `/`.
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub description: String,
+    pub argument_hint: Option<String>,
+    pub available_during_task: bool,
+    pub source: SlashCommandSource,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct CommandListResponse {
+    /// Commands in presentation order: built-ins, then custom prompts by name.
+    pub data: Vec<SlashCommand>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct WorkspaceFileSearchParams {
+    /// Fuzzy pattern matched against paths relative to `cwd`.
+    pub pattern: String,
+    /// Directory to search. Defaults to the server's working directory.
+    pub cwd: Option<PathBuf>,
+    /// Maximum number of matches to return. Defaults to 50.
+    pub limit: Option<u32>,
+    /// Whether to fill in `indices` for highlighting.
+    #[serde(default)]
+    pub compute_indices: bool,
+    /// Glob patterns for paths to skip, e.g. `target/**`.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Names this search for `workspace/fileSearch/cancel`. Starting a new
+    /// search with the same token cancels the previous one.
+    pub cancellation_token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct WorkspaceFileMatch {
+    /// Path relative to the searched directory.
+    pub path: String,
+    pub score: u32,
+    /// Sorted character indices of `path` that matched the pattern.
+    pub indices: Option<Vec<u32>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct WorkspaceFileSearchResponse {
+    /// Best matches first.
+    pub data: Vec<WorkspaceFileMatch>,
+    /// Number of files that matched before `limit` was applied.
+    pub total_match_count: u32,
+    /// True when the search was cancelled; `data` is then empty.
+    pub cancelled: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct WorkspaceFileSearchCancelParams {
+    pub cancellation_token: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct WorkspaceFileSearchCancelResponse {}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -396,6 +498,10 @@ pub struct ThreadStartParams {
     pub config: Option<HashMap<String, JsonValue>>,
     pub base_instructions: Option<String>,
     pub developer_instructions: Option<String>,
+    /// Deliver approvals for this thread as `approval/request` notifications
+    /// answered with `approval/respond`, instead of the per-item
+    /// `item/*/requestApproval` server requests.
+    pub approval_requests: Option<ApprovalRequestSettings>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -444,6 +550,8 @@ pub struct ThreadResumeParams {
     pub config: Option<HashMap<String, serde_json::Value>>,
     pub base_instructions: Option<String>,
     pub developer_instructions: Option<String>,
+    /// See [`ThreadStartParams::approval_requests`].
+    pub approval_requests: Option<ApprovalRequestSettings>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -1005,6 +1113,144 @@ pub struct FileChangeRequestApprovalResponse {
     pub decision: ApprovalDecision,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum ApprovalRespondDecision {
+    /// Run this command or apply this patch once.
+    Allow,
+    /// Allow, and stop asking for the same command for the rest of the session.
+    AllowAlways,
+    Deny,
+}
+
+impl ApprovalRespondDecision {
+    pub fn to_core(self) -> CoreReviewDecision {
+        match self {
+            ApprovalRespondDecision::Allow => CoreReviewDecision::Approved,
+            ApprovalRespondDecision::AllowAlways => CoreReviewDecision::ApprovedForSession,
+            ApprovalRespondDecision::Deny => CoreReviewDecision::Denied,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ApprovalRequestSettings {
+    /// How long to wait for `approval/respond` before applying `timeoutDecision`.
+    /// Defaults to 5 minutes.
+    pub timeout_ms: Option<u64>,
+    /// Decision applied when a request times out. Defaults to `deny`.
+    pub timeout_decision: Option<ApprovalRespondDecision>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[ts(tag = "type")]
+#[ts(export_to = "v2/")]
+pub enum ApprovalRequestDetails {
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    CommandExecution {
+        item_id: String,
+        command: String,
+        cwd: PathBuf,
+        command_actions: Vec<CommandAction>,
+        /// Set when the command is asking to connect to this `host:port`.
+        network_destination: Option<String>,
+        /// Optional model-provided risk assessment describing the blocked command.
+        risk: Option<SandboxCommandAssessment>,
+    },
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    FileChange {
+        item_id: String,
+        changes: Vec<FileUpdateChange>,
+        /// [UNSTABLE] When set, the agent is asking the user to allow writes under this root
+        /// for the remainder of the session.
+        grant_root: Option<PathBuf>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ApprovalRequestNotification {
+    pub thread_id: String,
+    pub turn_id: String,
+    /// Pass back in `approval/respond`. Unique among the thread's pending approvals.
+    pub request_id: String,
+    pub details: ApprovalRequestDetails,
+    /// Optional explanatory reason (e.g. request for network access).
+    pub reason: Option<String>,
+    /// Sandbox policy the thread's commands currently run under.
+    pub sandbox_policy: SandboxPolicy,
+    /// Milliseconds until `timeoutDecision` is applied on the client's behalf.
+    pub timeout_ms: u64,
+    pub timeout_decision: ApprovalRespondDecision,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ApprovalRespondParams {
+    pub thread_id: String,
+    pub request_id: String,
+    pub decision: ApprovalRespondDecision,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ApprovalRespondResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SubscribeFileChangesParams {
+    pub conversation_id: ConversationId,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SubscribeFileChangesResponse {}
+
+/// Sent at most once per file per turn to conversations subscribed with
+/// `codex/subscribeFileChanges`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct FileChangedNotification {
+    pub conversation_id: ConversationId,
+    pub turn_id: String,
+    /// Current path of the file; for deletions, the path it was removed from.
+    pub path: PathBuf,
+    pub kind: FileChangedKind,
+    /// Path at the start of the turn, set when `kind` is `renamed`.
+    pub old_path: Option<PathBuf>,
+}
+
+/// Interrupts every conversation, closes their rollouts and MCP servers, and
+/// exits once the response has been written.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ShutdownParams {
+    /// How long conversations get to stop before they are aborted.
+    /// Defaults to 10 seconds.
+    pub grace_period_ms: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ShutdownResponse {
+    /// Conversations still running after the grace period, which were aborted.
+    pub force_aborted: Vec<ConversationId>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
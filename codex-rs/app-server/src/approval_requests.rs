@@ -0,0 +1,224 @@
+//! Bookkeeping for threads that receive approvals as `approval/request`
+//! notifications and answer them with `approval/respond`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use codex_app_server_protocol::ApprovalRequestSettings;
+use codex_app_server_protocol::ApprovalRespondDecision;
+use codex_app_server_protocol::SandboxPolicy;
+use codex_protocol::ConversationId;
+use tokio::sync::Mutex;
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+const DEFAULT_APPROVAL_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Settings and outstanding requests for one thread.
+struct ThreadApprovals {
+    timeout: Duration,
+    timeout_decision: ApprovalRespondDecision,
+    sandbox_policy: SandboxPolicy,
+    pending: HashMap<String, oneshot::Sender<ApprovalRespondDecision>>,
+}
+
+/// A request registered with [`ApprovalRequests::register`].
+pub(crate) struct PendingApproval {
+    pub(crate) request_id: String,
+    pub(crate) timeout: Duration,
+    pub(crate) timeout_decision: ApprovalRespondDecision,
+    pub(crate) sandbox_policy: SandboxPolicy,
+    pub(crate) decision: oneshot::Receiver<ApprovalRespondDecision>,
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct ApprovalRequests {
+    threads: Arc<Mutex<HashMap<ConversationId, ThreadApprovals>>>,
+}
+
+impl ApprovalRequests {
+    /// Opts `conversation_id` into notification-based approvals.
+    pub(crate) async fn enable(
+        &self,
+        conversation_id: ConversationId,
+        settings: ApprovalRequestSettings,
+        sandbox_policy: SandboxPolicy,
+    ) {
+        let ApprovalRequestSettings {
+            timeout_ms,
+            timeout_decision,
+        } = settings;
+        let thread = ThreadApprovals {
+            timeout: timeout_ms.map_or(DEFAULT_APPROVAL_TIMEOUT, Duration::from_millis),
+            timeout_decision: timeout_decision.unwrap_or(ApprovalRespondDecision::Deny),
+            sandbox_policy,
+            pending: HashMap::new(),
+        };
+        self.threads.lock().await.insert(conversation_id, thread);
+    }
+
+    /// Records a sandbox change made by `turn/start` so later requests report it.
+    pub(crate) async fn set_sandbox_policy(
+        &self,
+        conversation_id: ConversationId,
+        sandbox_policy: SandboxPolicy,
+    ) {
+        if let Some(thread) = self.threads.lock().await.get_mut(&conversation_id) {
+            thread.sandbox_policy = sandbox_policy;
+        }
+    }
+
+    /// Allocates a request id for a new approval. Returns `None` when the
+    /// thread uses the per-item server requests instead.
+    pub(crate) async fn register(
+        &self,
+        conversation_id: ConversationId,
+    ) -> Option<PendingApproval> {
+        let mut threads = self.threads.lock().await;
+        let thread = threads.get_mut(&conversation_id)?;
+        let request_id = Uuid::new_v4().to_string();
+        let (tx, rx) = oneshot::channel();
+        thread.pending.insert(request_id.clone(), tx);
+        Some(PendingApproval {
+            request_id,
+            timeout: thread.timeout,
+            timeout_decision: thread.timeout_decision,
+            sandbox_policy: thread.sandbox_policy.clone(),
+            decision: rx,
+        })
+    }
+
+    /// Delivers a client's decision. Returns `false` when the request is
+    /// unknown, already answered, or timed out.
+    pub(crate) async fn respond(
+        &self,
+        conversation_id: ConversationId,
+        request_id: &str,
+        decision: ApprovalRespondDecision,
+    ) -> bool {
+        let sender = self
+            .threads
+            .lock()
+            .await
+            .get_mut(&conversation_id)
+            .and_then(|thread| thread.pending.remove(request_id));
+        sender.is_some_and(|sender| sender.send(decision).is_ok())
+    }
+
+    /// Drops a request that timed out so late responses are rejected.
+    pub(crate) async fn expire(&self, conversation_id: ConversationId, request_id: &str) {
+        if let Some(thread) = self.threads.lock().await.get_mut(&conversation_id) {
+            thread.pending.remove(request_id);
+        }
+    }
+
+    /// Forgets a thread that was closed. Requests still waiting for an answer
+    /// are dropped, which denies them, and later responses are rejected.
+    pub(crate) async fn close(&self, conversation_id: ConversationId) {
+        self.threads.lock().await.remove(&conversation_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn respond_resolves_only_pending_requests() {
+        let approvals = ApprovalRequests::default();
+        let conversation_id = ConversationId::new();
+        assert!(approvals.register(conversation_id).await.is_none());
+
+        approvals
+            .enable(
+                conversation_id,
+                ApprovalRequestSettings {
+                    timeout_ms: Some(1_000),
+                    timeout_decision: None,
+                },
+                SandboxPolicy::ReadOnly,
+            )
+            .await;
+        let first = approvals
+            .register(conversation_id)
+            .await
+            .expect("thread is enabled");
+        let second = approvals
+            .register(conversation_id)
+            .await
+            .expect("thread is enabled");
+        assert_ne!(first.request_id, second.request_id);
+        assert_eq!(first.timeout, Duration::from_secs(1));
+        assert_eq!(first.timeout_decision, ApprovalRespondDecision::Deny);
+
+        assert!(
+            approvals
+                .respond(
+                    conversation_id,
+                    &first.request_id,
+                    ApprovalRespondDecision::AllowAlways
+                )
+                .await
+        );
+        assert_eq!(
+            first.decision.await.expect("decision delivered"),
+            ApprovalRespondDecision::AllowAlways
+        );
+        assert!(
+            !approvals
+                .respond(
+                    conversation_id,
+                    &first.request_id,
+                    ApprovalRespondDecision::Allow
+                )
+                .await
+        );
+
+        approvals.expire(conversation_id, &second.request_id).await;
+        assert!(
+            !approvals
+                .respond(
+                    conversation_id,
+                    &second.request_id,
+                    ApprovalRespondDecision::Allow
+                )
+                .await
+        );
+    }
+
+    #[tokio::test]
+    async fn close_drops_pending_requests() {
+        let approvals = ApprovalRequests::default();
+        let conversation_id = ConversationId::new();
+        approvals
+            .enable(
+                conversation_id,
+                ApprovalRequestSettings {
+                    timeout_ms: None,
+                    timeout_decision: Some(ApprovalRespondDecision::Allow),
+                },
+                SandboxPolicy::ReadOnly,
+            )
+            .await;
+        let pending = approvals
+            .register(conversation_id)
+            .await
+            .expect("thread is enabled");
+
+        approvals.close(conversation_id).await;
+
+        assert!(pending.decision.await.is_err());
+        assert!(
+            !approvals
+                .respond(
+                    conversation_id,
+                    &pending.request_id,
+                    ApprovalRespondDecision::Allow
+                )
+                .await
+        );
+        assert!(approvals.register(conversation_id).await.is_none());
+    }
+}
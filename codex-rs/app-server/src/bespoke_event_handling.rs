@@ -1,13 +1,19 @@
+use crate::approval_requests::ApprovalRequests;
+use crate::approval_requests::PendingApproval;
 use crate::codex_message_processor::ApiVersion;
 use crate::codex_message_processor::PendingInterrupts;
 use crate::codex_message_processor::TurnSummary;
 use crate::codex_message_processor::TurnSummaryStore;
+use crate::file_change_subscriptions::FileChangeSubscriptions;
 use crate::outgoing_message::OutgoingMessageSender;
 use codex_app_server_protocol::AccountRateLimitsUpdatedNotification;
 use codex_app_server_protocol::AgentMessageDeltaNotification;
 use codex_app_server_protocol::ApplyPatchApprovalParams;
 use codex_app_server_protocol::ApplyPatchApprovalResponse;
 use codex_app_server_protocol::ApprovalDecision;
+use codex_app_server_protocol::ApprovalRequestDetails;
+use codex_app_server_protocol::ApprovalRequestNotification;
+use codex_app_server_protocol::ApprovalRespondDecision;
 use codex_app_server_protocol::CommandAction as V2ParsedCommand;
 use codex_app_server_protocol::CommandExecutionOutputDeltaNotification;
 use codex_app_server_protocol::CommandExecutionRequestApprovalParams;
@@ -17,6 +23,7 @@ use codex_app_server_protocol::ExecCommandApprovalParams;
 use codex_app_server_protocol::ExecCommandApprovalResponse;
 use codex_app_server_protocol::FileChangeRequestApprovalParams;
 use codex_app_server_protocol::FileChangeRequestApprovalResponse;
+use codex_app_server_protocol::FileChangedNotification;
 use codex_app_server_protocol::FileUpdateChange;
 use codex_app_server_protocol::InterruptConversationResponse;
 use codex_app_server_protocol::ItemCompletedNotification;
@@ -62,6 +69,7 @@ use tracing::error;
 
 type JsonValue = serde_json::Value;
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn apply_bespoke_event_handling(
     event: Event,
     conversation_id: ConversationId,
@@ -69,13 +77,35 @@ pub(crate) async fn apply_bespoke_event_handling(
     outgoing: Arc<OutgoingMessageSender>,
     pending_interrupts: PendingInterrupts,
     turn_summary_store: TurnSummaryStore,
+    approval_requests: ApprovalRequests,
+    file_change_subscriptions: FileChangeSubscriptions,
     api_version: ApiVersion,
 ) {
     let Event { id: event_id, msg } = event;
     match msg {
+        EventMsg::TurnDiff(turn_diff_event) => {
+            let files = file_change_subscriptions
+                .unreported(conversation_id, &event_id, turn_diff_event.files)
+                .await;
+            for file in files {
+                let notification = FileChangedNotification {
+                    conversation_id,
+                    turn_id: event_id.clone(),
+                    path: file.path,
+                    kind: file.kind.into(),
+                    old_path: file.old_path,
+                };
+                outgoing
+                    .send_server_notification(ServerNotification::FileChanged(notification))
+                    .await;
+            }
+        }
         EventMsg::TaskComplete(_ev) => {
             handle_turn_complete(conversation_id, event_id, &outgoing, &turn_summary_store).await;
         }
+        EventMsg::ShutdownComplete => {
+            file_change_subscriptions.unsubscribe(conversation_id).await;
+        }
         EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
             call_id,
             turn_id,
@@ -121,6 +151,49 @@ pub(crate) async fn apply_bespoke_event_handling(
                         .await;
                 }
 
+                if let Some(pending) = approval_requests.register(conversation_id).await {
+                    let notification = approval_request_notification(
+                        conversation_id,
+                        turn_id,
+                        &pending,
+                        ApprovalRequestDetails::FileChange {
+                            item_id: item_id.clone(),
+                            changes: patch_changes.clone(),
+                            grant_root,
+                        },
+                        reason,
+                    );
+                    outgoing
+                        .send_server_notification(ServerNotification::ApprovalRequest(notification))
+                        .await;
+                    tokio::spawn(async move {
+                        let decision =
+                            await_approval_decision(conversation_id, pending, &approval_requests)
+                                .await;
+                        if decision == ApprovalRespondDecision::Deny {
+                            complete_file_change_item(
+                                conversation_id,
+                                item_id,
+                                patch_changes,
+                                PatchApplyStatus::Declined,
+                                outgoing.as_ref(),
+                                &turn_summary_store,
+                            )
+                            .await;
+                        }
+                        if let Err(err) = conversation
+                            .submit(Op::PatchApproval {
+                                id: event_id,
+                                decision: decision.to_core(),
+                            })
+                            .await
+                        {
+                            error!("failed to submit PatchApproval: {err}");
+                        }
+                    });
+                    return;
+                }
+
                 let params = FileChangeRequestApprovalParams {
                     thread_id: conversation_id.to_string(),
                     turn_id: turn_id.clone(),
@@ -154,6 +227,8 @@ pub(crate) async fn apply_bespoke_event_handling(
             reason,
             risk,
             parsed_cmd,
+            network,
+            explanation_pending: _,
         }) => match api_version {
             ApiVersion::V1 => {
                 let params = ExecCommandApprovalParams {
@@ -173,6 +248,44 @@ pub(crate) async fn apply_bespoke_event_handling(
                 });
             }
             ApiVersion::V2 => {
+                if let Some(pending) = approval_requests.register(conversation_id).await {
+                    let notification = approval_request_notification(
+                        conversation_id,
+                        turn_id,
+                        &pending,
+                        ApprovalRequestDetails::CommandExecution {
+                            item_id: call_id,
+                            command: shlex_join(&command),
+                            cwd,
+                            command_actions: parsed_cmd
+                                .into_iter()
+                                .map(V2ParsedCommand::from)
+                                .collect(),
+                            network_destination: network.as_ref().map(ToString::to_string),
+                            risk: risk.map(V2SandboxCommandAssessment::from),
+                        },
+                        reason,
+                    );
+                    outgoing
+                        .send_server_notification(ServerNotification::ApprovalRequest(notification))
+                        .await;
+                    tokio::spawn(async move {
+                        let decision =
+                            await_approval_decision(conversation_id, pending, &approval_requests)
+                                .await;
+                        if let Err(err) = conversation
+                            .submit(Op::ExecApproval {
+                                id: event_id,
+                                decision: decision.to_core(),
+                            })
+                            .await
+                        {
+                            error!("failed to submit ExecApproval: {err}");
+                        }
+                    });
+                    return;
+                }
+
                 let params = CommandExecutionRequestApprovalParams {
                     thread_id: conversation_id.to_string(),
                     turn_id: turn_id.clone(),
@@ -617,6 +730,49 @@ async fn on_exec_approval_response(
     }
 }
 
+fn approval_request_notification(
+    conversation_id: ConversationId,
+    turn_id: String,
+    pending: &PendingApproval,
+    details: ApprovalRequestDetails,
+    reason: Option<String>,
+) -> ApprovalRequestNotification {
+    ApprovalRequestNotification {
+        thread_id: conversation_id.to_string(),
+        turn_id,
+        request_id: pending.request_id.clone(),
+        details,
+        reason,
+        sandbox_policy: pending.sandbox_policy.clone(),
+        timeout_ms: u64::try_from(pending.timeout.as_millis()).unwrap_or(u64::MAX),
+        timeout_decision: pending.timeout_decision,
+    }
+}
+
+/// Waits for `approval/respond`, falling back to the thread's timeout decision.
+async fn await_approval_decision(
+    conversation_id: ConversationId,
+    pending: PendingApproval,
+    approval_requests: &ApprovalRequests,
+) -> ApprovalRespondDecision {
+    let PendingApproval {
+        request_id,
+        timeout,
+        timeout_decision,
+        decision,
+        ..
+    } = pending;
+    match tokio::time::timeout(timeout, decision).await {
+        Ok(Ok(decision)) => decision,
+        // The thread was closed before anyone answered.
+        Ok(Err(_)) => ApprovalRespondDecision::Deny,
+        Err(_) => {
+            approval_requests.expire(conversation_id, &request_id).await;
+            timeout_decision
+        }
+    }
+}
+
 const REVIEW_FALLBACK_MESSAGE: &str = "Reviewer failed to output a response.";
 
 fn render_review_output_text(output: &ReviewOutputEvent) -> String {
@@ -1159,6 +1315,7 @@ mod tests {
             },
             duration: Duration::from_nanos(92708),
             result: Ok(result),
+            cached: false,
         };
 
         let notification = construct_mcp_tool_call_end_notification(end_event.clone()).await;
@@ -1192,6 +1349,7 @@ mod tests {
             },
             duration: Duration::from_millis(1),
             result: Err("boom".to_string()),
+            cached: false,
         };
 
         let notification = construct_mcp_tool_call_end_notification(end_event.clone()).await;
@@ -1,7 +1,11 @@
+use crate::approval_requests::ApprovalRequests;
 use crate::bespoke_event_handling::apply_bespoke_event_handling;
+use crate::commands::supported_commands;
 use crate::error_code::INTERNAL_ERROR_CODE;
 use crate::error_code::INVALID_REQUEST_ERROR_CODE;
+use crate::file_change_subscriptions::FileChangeSubscriptions;
 use crate::fuzzy_file_search::run_fuzzy_file_search;
+use crate::fuzzy_file_search::run_workspace_file_search;
 use crate::models::supported_models;
 use crate::outgoing_message::OutgoingMessageSender;
 use crate::outgoing_message::OutgoingNotification;
@@ -12,6 +16,8 @@ use codex_app_server_protocol::AccountLoginCompletedNotification;
 use codex_app_server_protocol::AccountUpdatedNotification;
 use codex_app_server_protocol::AddConversationListenerParams;
 use codex_app_server_protocol::AddConversationSubscriptionResponse;
+use codex_app_server_protocol::ApprovalRespondParams;
+use codex_app_server_protocol::ApprovalRespondResponse;
 use codex_app_server_protocol::ArchiveConversationParams;
 use codex_app_server_protocol::ArchiveConversationResponse;
 use codex_app_server_protocol::AskForApproval;
@@ -21,6 +27,8 @@ use codex_app_server_protocol::CancelLoginAccountParams;
 use codex_app_server_protocol::CancelLoginAccountResponse;
 use codex_app_server_protocol::CancelLoginChatGptResponse;
 use codex_app_server_protocol::ClientRequest;
+use codex_app_server_protocol::CommandListParams;
+use codex_app_server_protocol::CommandListResponse;
 use codex_app_server_protocol::ConversationGitInfo;
 use codex_app_server_protocol::ConversationSummary;
 use codex_app_server_protocol::ExecOneOffCommandParams;
@@ -71,6 +79,10 @@ use codex_app_server_protocol::ServerNotification;
 use codex_app_server_protocol::SessionConfiguredNotification;
 use codex_app_server_protocol::SetDefaultModelParams;
 use codex_app_server_protocol::SetDefaultModelResponse;
+use codex_app_server_protocol::ShutdownParams;
+use codex_app_server_protocol::ShutdownResponse;
+use codex_app_server_protocol::SubscribeFileChangesParams;
+use codex_app_server_protocol::SubscribeFileChangesResponse;
 use codex_app_server_protocol::Thread;
 use codex_app_server_protocol::ThreadArchiveParams;
 use codex_app_server_protocol::ThreadArchiveResponse;
@@ -92,6 +104,9 @@ use codex_app_server_protocol::UserInfoResponse;
 use codex_app_server_protocol::UserInput as V2UserInput;
 use codex_app_server_protocol::UserSavedConfig;
 use codex_app_server_protocol::WindowsWorldWritableWarningNotification;
+use codex_app_server_protocol::WorkspaceFileSearchCancelParams;
+use codex_app_server_protocol::WorkspaceFileSearchCancelResponse;
+use codex_app_server_protocol::WorkspaceFileSearchParams;
 use codex_app_server_protocol::build_turns_from_event_msgs;
 use codex_backend_client::Client as BackendClient;
 use codex_core::AuthManager;
@@ -170,6 +185,8 @@ pub(crate) type TurnSummaryStore = Arc<Mutex<HashMap<ConversationId, TurnSummary
 
 // Duration before a ChatGPT login attempt is abandoned.
 const LOGIN_CHATGPT_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+// How long conversations get to stop on shutdown before they are aborted.
+const DEFAULT_SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(10);
 struct ActiveLogin {
     shutdown_handle: ShutdownHandle,
     login_id: Uuid,
@@ -193,6 +210,10 @@ pub(crate) struct CodexMessageProcessor {
     // Queue of pending interrupt requests per conversation. We reply when TurnAborted arrives.
     pending_interrupts: PendingInterrupts,
     turn_summary_store: TurnSummaryStore,
+    // Threads that answer approvals through `approval/respond`.
+    approval_requests: ApprovalRequests,
+    // Conversations that receive `codex/fileChanged` notifications.
+    file_change_subscriptions: FileChangeSubscriptions,
     pending_fuzzy_searches: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
     feedback: CodexFeedback,
 }
@@ -246,6 +267,8 @@ impl CodexMessageProcessor {
             active_login: Arc::new(Mutex::new(None)),
             pending_interrupts: Arc::new(Mutex::new(HashMap::new())),
             turn_summary_store: Arc::new(Mutex::new(HashMap::new())),
+            approval_requests: ApprovalRequests::default(),
+            file_change_subscriptions: FileChangeSubscriptions::default(),
             pending_fuzzy_searches: Arc::new(Mutex::new(HashMap::new())),
             feedback,
         }
@@ -370,6 +393,15 @@ impl CodexMessageProcessor {
             ClientRequest::ReviewStart { request_id, params } => {
                 self.review_start(request_id, params).await;
             }
+            ClientRequest::ApprovalRespond { request_id, params } => {
+                self.approval_respond(request_id, params).await;
+            }
+            ClientRequest::SubscribeFileChanges { request_id, params } => {
+                self.subscribe_file_changes(request_id, params).await;
+            }
+            ClientRequest::Shutdown { request_id, params } => {
+                self.shutdown(request_id, params).await;
+            }
             ClientRequest::NewConversation { request_id, params } => {
                 // Do not tokio::spawn() to process new_conversation()
                 // asynchronously because we need to ensure the conversation is
@@ -385,6 +417,15 @@ impl CodexMessageProcessor {
             ClientRequest::ModelList { request_id, params } => {
                 self.list_models(request_id, params).await;
             }
+            ClientRequest::CommandList { request_id, params } => {
+                self.list_commands(request_id, params).await;
+            }
+            ClientRequest::WorkspaceFileSearch { request_id, params } => {
+                self.workspace_file_search(request_id, params).await;
+            }
+            ClientRequest::WorkspaceFileSearchCancel { request_id, params } => {
+                self.cancel_workspace_file_search(request_id, params).await;
+            }
             ClientRequest::LoginAccount { request_id, params } => {
                 self.login_v2(request_id, params).await;
             }
@@ -1381,6 +1422,12 @@ impl CodexMessageProcessor {
                     reasoning_effort: session_configured.reasoning_effort,
                 };
 
+                if let Some(settings) = params.approval_requests {
+                    self.approval_requests
+                        .enable(conversation_id, settings, response.sandbox.clone())
+                        .await;
+                }
+
                 // Auto-attach a conversation listener when starting a thread.
                 // Use the same behavior as the v1 API with experimental_raw_events=false.
                 if let Err(err) = self
@@ -1531,6 +1578,7 @@ impl CodexMessageProcessor {
             config: cli_overrides,
             base_instructions,
             developer_instructions,
+            approval_requests,
         } = params;
 
         let overrides_requested = model.is_some()
@@ -1665,6 +1713,12 @@ impl CodexMessageProcessor {
                     initial_messages,
                     ..
                 } = session_configured;
+                if let Some(settings) = approval_requests {
+                    let sandbox_policy = session_configured.sandbox_policy.clone().into();
+                    self.approval_requests
+                        .enable(conversation_id, settings, sandbox_policy)
+                        .await;
+                }
                 // Auto-attach a conversation listener when resuming a thread.
                 if let Err(err) = self
                     .attach_conversation_listener(conversation_id, false, ApiVersion::V2)
@@ -1931,6 +1985,14 @@ impl CodexMessageProcessor {
         self.outgoing.send_response(request_id, response).await;
     }
 
+    async fn list_commands(&self, request_id: RequestId, params: CommandListParams) {
+        let CommandListParams { cwd } = params;
+        let data = supported_commands(&self.config, cwd.as_deref()).await;
+        self.outgoing
+            .send_response(request_id, CommandListResponse { data })
+            .await;
+    }
+
     async fn handle_resume_conversation(
         &self,
         request_id: RequestId,
@@ -2239,6 +2301,10 @@ impl CodexMessageProcessor {
             .await
         {
             info!("conversation {conversation_id} was active; shutting down");
+            self.file_change_subscriptions
+                .unsubscribe(conversation_id)
+                .await;
+            self.approval_requests.close(conversation_id).await;
             let conversation_clone = conversation.clone();
             let notify = Arc::new(tokio::sync::Notify::new());
             let notify_clone = notify.clone();
@@ -2289,22 +2355,14 @@ impl CodexMessageProcessor {
         }
 
         // Move the rollout file to archived.
-        let result: std::io::Result<()> = async {
-            let archive_folder = self
-                .config
-                .codex_home
-                .join(codex_core::ARCHIVED_SESSIONS_SUBDIR);
-            tokio::fs::create_dir_all(&archive_folder).await?;
-            tokio::fs::rename(&canonical_rollout_path, &archive_folder.join(&file_name)).await?;
-            Ok(())
-        }
-        .await;
-
-        result.map_err(|err| JSONRPCErrorError {
-            code: INTERNAL_ERROR_CODE,
-            message: format!("failed to archive conversation: {err}"),
-            data: None,
-        })
+        RolloutRecorder::archive_conversation(&self.config.codex_home, &canonical_rollout_path)
+            .await
+            .map(|_| ())
+            .map_err(|err| JSONRPCErrorError {
+                code: INTERNAL_ERROR_CODE,
+                message: format!("failed to archive conversation: {err}"),
+                data: None,
+            })
     }
 
     async fn send_user_message(&self, request_id: RequestId, params: SendUserMessageParams) {
@@ -2434,13 +2492,14 @@ impl CodexMessageProcessor {
     }
 
     async fn turn_start(&self, request_id: RequestId, params: TurnStartParams) {
-        let (_, conversation) = match self.conversation_from_thread_id(&params.thread_id).await {
-            Ok(v) => v,
-            Err(error) => {
-                self.outgoing.send_error(request_id, error).await;
-                return;
-            }
-        };
+        let (conversation_id, conversation) =
+            match self.conversation_from_thread_id(&params.thread_id).await {
+                Ok(v) => v,
+                Err(error) => {
+                    self.outgoing.send_error(request_id, error).await;
+                    return;
+                }
+            };
 
         // Map v2 input items to core input items.
         let mapped_items: Vec<CoreInputItem> = params
@@ -2456,6 +2515,12 @@ impl CodexMessageProcessor {
             || params.effort.is_some()
             || params.summary.is_some();
 
+        if let Some(sandbox_policy) = &params.sandbox_policy {
+            self.approval_requests
+                .set_sandbox_policy(conversation_id, sandbox_policy.clone())
+                .await;
+        }
+
         // If any overrides are provided, update the session turn context first.
         if has_any_overrides {
             let _ = conversation
@@ -2505,6 +2570,93 @@ impl CodexMessageProcessor {
         }
     }
 
+    async fn approval_respond(&self, request_id: RequestId, params: ApprovalRespondParams) {
+        let ApprovalRespondParams {
+            thread_id,
+            request_id: approval_id,
+            decision,
+        } = params;
+        let conversation_id = match ConversationId::from_string(&thread_id) {
+            Ok(id) => id,
+            Err(err) => {
+                self.send_invalid_request_error(request_id, format!("invalid thread id: {err}"))
+                    .await;
+                return;
+            }
+        };
+
+        if self
+            .approval_requests
+            .respond(conversation_id, &approval_id, decision)
+            .await
+        {
+            self.outgoing
+                .send_response(request_id, ApprovalRespondResponse {})
+                .await;
+        } else {
+            self.send_invalid_request_error(
+                request_id,
+                format!(
+                    "no pending approval request {approval_id} (already answered or timed out)"
+                ),
+            )
+            .await;
+        }
+    }
+
+    async fn subscribe_file_changes(
+        &self,
+        request_id: RequestId,
+        params: SubscribeFileChangesParams,
+    ) {
+        let SubscribeFileChangesParams { conversation_id } = params;
+        if self
+            .conversation_manager
+            .get_conversation(conversation_id)
+            .await
+            .is_err()
+        {
+            self.send_invalid_request_error(
+                request_id,
+                format!("conversation not found: {conversation_id}"),
+            )
+            .await;
+            return;
+        }
+
+        self.file_change_subscriptions
+            .subscribe(conversation_id)
+            .await;
+        self.outgoing
+            .send_response(request_id, SubscribeFileChangesResponse {})
+            .await;
+    }
+
+    async fn shutdown(&mut self, request_id: RequestId, params: ShutdownParams) {
+        let ShutdownParams { grace_period_ms } = params;
+        let force_aborted = self.shutdown_conversations(grace_period_ms).await;
+        self.outgoing
+            .send_response(request_id, ShutdownResponse { force_aborted })
+            .await;
+    }
+
+    /// Stops every conversation, giving each `grace_period_ms` (or the
+    /// default) to finish, and detaches their listeners. Returns the
+    /// conversations that had to be aborted.
+    pub(crate) async fn shutdown_conversations(
+        &mut self,
+        grace_period_ms: Option<u64>,
+    ) -> Vec<ConversationId> {
+        let grace_period =
+            grace_period_ms.map_or(DEFAULT_SHUTDOWN_GRACE_PERIOD, Duration::from_millis);
+        let force_aborted = self.conversation_manager.shutdown_all(grace_period).await;
+        for (_, cancel_tx) in self.conversation_listeners.drain() {
+            let _ = cancel_tx.send(());
+        }
+        self.file_change_subscriptions.clear().await;
+        force_aborted
+    }
+
     async fn review_start(&self, request_id: RequestId, params: ReviewStartParams) {
         let ReviewStartParams {
             thread_id,
@@ -2663,6 +2815,8 @@ impl CodexMessageProcessor {
         let outgoing_for_task = self.outgoing.clone();
         let pending_interrupts = self.pending_interrupts.clone();
         let turn_summary_store = self.turn_summary_store.clone();
+        let approval_requests = self.approval_requests.clone();
+        let file_change_subscriptions = self.file_change_subscriptions.clone();
         let api_version_for_task = api_version;
         tokio::spawn(async move {
             loop {
@@ -2720,6 +2874,8 @@ impl CodexMessageProcessor {
                             outgoing_for_task.clone(),
                             pending_interrupts.clone(),
                             turn_summary_store.clone(),
+                            approval_requests.clone(),
+                            file_change_subscriptions.clone(),
                             api_version_for_task,
                         )
                         .await;
@@ -2791,6 +2947,87 @@ impl CodexMessageProcessor {
         self.outgoing.send_response(request_id, response).await;
     }
 
+    async fn workspace_file_search(
+        &self,
+        request_id: RequestId,
+        params: WorkspaceFileSearchParams,
+    ) {
+        let WorkspaceFileSearchParams {
+            pattern,
+            cwd,
+            limit,
+            compute_indices,
+            exclude,
+            cancellation_token,
+        } = params;
+        let search_dir = cwd.unwrap_or_else(|| self.config.cwd.clone());
+
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        if let Some(token) = cancellation_token.clone() {
+            let mut pending_fuzzy_searches = self.pending_fuzzy_searches.lock().await;
+            if let Some(existing) = pending_fuzzy_searches.insert(token, cancel_flag.clone()) {
+                existing.store(true, Ordering::Relaxed);
+            }
+        }
+
+        // Run off the request loop so `workspace/fileSearch/cancel` can be
+        // handled while the walk is in progress.
+        let outgoing = self.outgoing.clone();
+        let pending_fuzzy_searches = self.pending_fuzzy_searches.clone();
+        tokio::spawn(async move {
+            let result = run_workspace_file_search(
+                pattern,
+                search_dir,
+                limit,
+                compute_indices,
+                exclude,
+                cancel_flag.clone(),
+            )
+            .await;
+
+            if let Some(token) = cancellation_token {
+                let mut pending_fuzzy_searches = pending_fuzzy_searches.lock().await;
+                if let Some(current_flag) = pending_fuzzy_searches.get(&token)
+                    && Arc::ptr_eq(current_flag, &cancel_flag)
+                {
+                    pending_fuzzy_searches.remove(&token);
+                }
+            }
+
+            match result {
+                Ok(response) => outgoing.send_response(request_id, response).await,
+                Err(err) => {
+                    let error = JSONRPCErrorError {
+                        code: INVALID_REQUEST_ERROR_CODE,
+                        message: format!("file search failed: {err}"),
+                        data: None,
+                    };
+                    outgoing.send_error(request_id, error).await;
+                }
+            }
+        });
+    }
+
+    async fn cancel_workspace_file_search(
+        &self,
+        request_id: RequestId,
+        params: WorkspaceFileSearchCancelParams,
+    ) {
+        let WorkspaceFileSearchCancelParams { cancellation_token } = params;
+        // Unknown tokens are fine: the search may already have finished.
+        if let Some(flag) = self
+            .pending_fuzzy_searches
+            .lock()
+            .await
+            .get(&cancellation_token)
+        {
+            flag.store(true, Ordering::Relaxed);
+        }
+        self.outgoing
+            .send_response(request_id, WorkspaceFileSearchCancelResponse {})
+            .await;
+    }
+
     async fn upload_feedback(&self, request_id: RequestId, params: FeedbackUploadParams) {
         let FeedbackUploadParams {
             classification,
@@ -0,0 +1,39 @@
+use std::path::Path;
+
+use codex_app_server_protocol::SlashCommand;
+use codex_app_server_protocol::SlashCommandSource;
+use codex_core::config::Config;
+use codex_core::custom_prompts::discover_prompts_in;
+use codex_core::git_info::get_git_repo_root;
+use codex_core::slash_commands::CommandAvailability;
+use codex_core::slash_commands::CommandInfo;
+use codex_core::slash_commands::CommandSource;
+use codex_core::slash_commands::list_commands;
+
+/// Slash commands a client should offer for a session rooted at `cwd`
+/// (defaults to the configured working directory).
+pub async fn supported_commands(config: &Config, cwd: Option<&Path>) -> Vec<SlashCommand> {
+    let mut availability = CommandAvailability::from_config(config);
+    if let Some(cwd) = cwd {
+        availability.in_git_repo = get_git_repo_root(cwd).is_some();
+    }
+    let prompts = discover_prompts_in(&config.codex_home.join("prompts")).await;
+    list_commands(availability, &prompts)
+        .into_iter()
+        .map(command_from_info)
+        .collect()
+}
+
+fn command_from_info(info: CommandInfo) -> SlashCommand {
+    SlashCommand {
+        name: info.name,
+        aliases: info.aliases,
+        description: info.description,
+        argument_hint: info.argument_hint,
+        available_during_task: info.available_during_task,
+        source: match info.source {
+            CommandSource::Builtin => SlashCommandSource::Builtin,
+            CommandSource::CustomPrompt => SlashCommandSource::CustomPrompt,
+        },
+    }
+}
@@ -0,0 +1,195 @@
+//! Bookkeeping for conversations that opted into `codex/fileChanged`
+//! notifications with `codex/subscribeFileChanges`.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use codex_protocol::ConversationId;
+use codex_protocol::protocol::TurnFileChange;
+use codex_protocol::protocol::TurnFileChangeKind;
+use tokio::sync::Mutex;
+
+/// Paths already reported during the conversation's current turn, with the
+/// kind of change last reported for each.
+#[derive(Default)]
+struct ReportedFiles {
+    turn_id: String,
+    paths: HashMap<PathBuf, TurnFileChangeKind>,
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct FileChangeSubscriptions {
+    conversations: Arc<Mutex<HashMap<ConversationId, ReportedFiles>>>,
+}
+
+impl FileChangeSubscriptions {
+    pub(crate) async fn subscribe(&self, conversation_id: ConversationId) {
+        self.conversations
+            .lock()
+            .await
+            .entry(conversation_id)
+            .or_default();
+    }
+
+    /// Stop tracking a conversation that was closed.
+    pub(crate) async fn unsubscribe(&self, conversation_id: ConversationId) {
+        self.conversations.lock().await.remove(&conversation_id);
+    }
+
+    pub(crate) async fn clear(&self) {
+        self.conversations.lock().await.clear();
+    }
+
+    /// Filters `files`, the turn's changes so far, down to what has not been
+    /// reported for `turn_id`: new files and files whose kind of change
+    /// differs from the last report. A reported file that no longer differs
+    /// from the start of the turn (say, added and then deleted again) is
+    /// reported once more with its current state. Returns nothing for
+    /// conversations that are not subscribed.
+    pub(crate) async fn unreported(
+        &self,
+        conversation_id: ConversationId,
+        turn_id: &str,
+        files: Vec<TurnFileChange>,
+    ) -> Vec<TurnFileChange> {
+        let mut conversations = self.conversations.lock().await;
+        let Some(reported) = conversations.get_mut(&conversation_id) else {
+            return Vec::new();
+        };
+        if reported.turn_id != turn_id {
+            reported.turn_id = turn_id.to_string();
+            reported.paths.clear();
+        }
+        let mut reverted: Vec<TurnFileChange> = reported
+            .paths
+            .iter()
+            .filter(|(path, _)| !files.iter().any(|file| file.path == **path))
+            .map(|(path, kind)| TurnFileChange {
+                path: path.clone(),
+                kind: match kind {
+                    TurnFileChangeKind::Added | TurnFileChangeKind::Renamed => {
+                        TurnFileChangeKind::Deleted
+                    }
+                    TurnFileChangeKind::Deleted => TurnFileChangeKind::Added,
+                    TurnFileChangeKind::Modified => TurnFileChangeKind::Modified,
+                },
+                old_path: None,
+            })
+            .collect();
+        reverted.sort_by(|a, b| a.path.cmp(&b.path));
+        for file in &reverted {
+            reported.paths.remove(&file.path);
+        }
+        let changed = files
+            .into_iter()
+            .filter(|file| reported.paths.insert(file.path.clone(), file.kind) != Some(file.kind));
+        reverted.into_iter().chain(changed).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::TurnFileChangeKind;
+    use pretty_assertions::assert_eq;
+
+    fn change(path: &str, kind: TurnFileChangeKind) -> TurnFileChange {
+        TurnFileChange {
+            path: PathBuf::from(path),
+            kind,
+            old_path: None,
+        }
+    }
+
+    fn modified(path: &str) -> TurnFileChange {
+        change(path, TurnFileChangeKind::Modified)
+    }
+
+    #[tokio::test]
+    async fn reports_each_file_once_per_turn() {
+        let subscriptions = FileChangeSubscriptions::default();
+        let conversation_id = ConversationId::new();
+        assert_eq!(
+            subscriptions
+                .unreported(conversation_id, "turn-1", vec![modified("a.rs")])
+                .await,
+            Vec::new()
+        );
+
+        subscriptions.subscribe(conversation_id).await;
+        assert_eq!(
+            subscriptions
+                .unreported(conversation_id, "turn-1", vec![modified("a.rs")])
+                .await,
+            vec![modified("a.rs")]
+        );
+        assert_eq!(
+            subscriptions
+                .unreported(
+                    conversation_id,
+                    "turn-1",
+                    vec![modified("a.rs"), modified("b.rs")]
+                )
+                .await,
+            vec![modified("b.rs")]
+        );
+        assert_eq!(
+            subscriptions
+                .unreported(conversation_id, "turn-2", vec![modified("a.rs")])
+                .await,
+            vec![modified("a.rs")]
+        );
+    }
+
+    #[tokio::test]
+    async fn file_added_and_deleted_in_one_turn_is_reported_deleted() {
+        let subscriptions = FileChangeSubscriptions::default();
+        let conversation_id = ConversationId::new();
+        subscriptions.subscribe(conversation_id).await;
+        let added = change("new.rs", TurnFileChangeKind::Added);
+
+        assert_eq!(
+            subscriptions
+                .unreported(conversation_id, "turn-1", vec![added.clone()])
+                .await,
+            vec![added.clone()]
+        );
+        // Deleting it again leaves nothing in the turn diff.
+        assert_eq!(
+            subscriptions
+                .unreported(conversation_id, "turn-1", vec![modified("a.rs")])
+                .await,
+            vec![
+                change("new.rs", TurnFileChangeKind::Deleted),
+                modified("a.rs")
+            ]
+        );
+        // Adding it back is reported again.
+        assert_eq!(
+            subscriptions
+                .unreported(
+                    conversation_id,
+                    "turn-1",
+                    vec![modified("a.rs"), added.clone()]
+                )
+                .await,
+            vec![added]
+        );
+    }
+
+    #[tokio::test]
+    async fn unsubscribed_conversations_get_no_reports() {
+        let subscriptions = FileChangeSubscriptions::default();
+        let conversation_id = ConversationId::new();
+        subscriptions.subscribe(conversation_id).await;
+        subscriptions.unsubscribe(conversation_id).await;
+
+        assert_eq!(
+            subscriptions
+                .unreported(conversation_id, "turn-1", vec![modified("a.rs")])
+                .await,
+            Vec::new()
+        );
+    }
+}
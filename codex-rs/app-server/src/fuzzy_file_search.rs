@@ -4,8 +4,11 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 
 use codex_app_server_protocol::FuzzyFileSearchResult;
+use codex_app_server_protocol::WorkspaceFileMatch;
+use codex_app_server_protocol::WorkspaceFileSearchResponse;
 use codex_file_search as file_search;
 use tokio::task::JoinSet;
 use tracing::warn;
@@ -13,6 +16,10 @@ use tracing::warn;
 const LIMIT_PER_ROOT: usize = 50;
 const MAX_THREADS: usize = 12;
 const COMPUTE_INDICES: bool = true;
+const WORKSPACE_DEFAULT_LIMIT: u32 = 50;
+// The walk is I/O bound; more than two threads rarely helps (see the
+// `codex-file-search` CLI default).
+const WORKSPACE_THREADS: usize = 2;
 
 pub(crate) async fn run_fuzzy_file_search(
     query: String,
@@ -42,7 +49,7 @@ pub(crate) async fn run_fuzzy_file_search(
         let query = query.clone();
         let cancel_flag = cancellation_flag.clone();
         join_set.spawn_blocking(move || {
-            match file_search::run(
+            match file_search::run_pattern(
                 query.as_str(),
                 limit_per_root,
                 &search_dir,
@@ -95,3 +102,61 @@ pub(crate) async fn run_fuzzy_file_search(
 
     files
 }
+
+/// Searches a single directory for `workspace/fileSearch`. A cancelled search
+/// returns no matches and sets `cancelled`.
+pub(crate) async fn run_workspace_file_search(
+    pattern: String,
+    search_dir: PathBuf,
+    limit: Option<u32>,
+    compute_indices: bool,
+    exclude: Vec<String>,
+    cancel_flag: Arc<AtomicBool>,
+) -> anyhow::Result<WorkspaceFileSearchResponse> {
+    if pattern.is_empty() {
+        return Ok(WorkspaceFileSearchResponse {
+            data: Vec::new(),
+            total_match_count: 0,
+            cancelled: false,
+        });
+    }
+
+    let limit = NonZero::new(limit.unwrap_or(WORKSPACE_DEFAULT_LIMIT) as usize)
+        .unwrap_or(NonZeroUsize::MIN);
+    let threads = NonZero::new(WORKSPACE_THREADS).unwrap_or(NonZeroUsize::MIN);
+    let flag = cancel_flag.clone();
+    let results = tokio::task::spawn_blocking(move || {
+        file_search::run_pattern(
+            &pattern,
+            limit,
+            &search_dir,
+            exclude,
+            threads,
+            flag,
+            compute_indices,
+            true,
+        )
+    })
+    .await??;
+
+    if cancel_flag.load(Ordering::Relaxed) {
+        return Ok(WorkspaceFileSearchResponse {
+            data: Vec::new(),
+            total_match_count: 0,
+            cancelled: true,
+        });
+    }
+    Ok(WorkspaceFileSearchResponse {
+        data: results
+            .matches
+            .into_iter()
+            .map(|m| WorkspaceFileMatch {
+                path: m.path,
+                score: m.score,
+                indices: m.indices,
+            })
+            .collect(),
+        total_match_count: u32::try_from(results.total_match_count).unwrap_or(u32::MAX),
+        cancelled: false,
+    })
+}
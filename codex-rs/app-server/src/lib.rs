@@ -4,6 +4,7 @@ use codex_common::CliConfigOverrides;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
+use std::io::BufRead;
 use std::io::ErrorKind;
 use std::io::Result as IoResult;
 use std::path::PathBuf;
@@ -13,24 +14,27 @@ use crate::outgoing_message::OutgoingMessage;
 use crate::outgoing_message::OutgoingMessageSender;
 use codex_app_server_protocol::JSONRPCMessage;
 use codex_feedback::CodexFeedback;
-use tokio::io::AsyncBufReadExt;
 use tokio::io::AsyncWriteExt;
-use tokio::io::BufReader;
 use tokio::io::{self};
 use tokio::sync::mpsc;
+use tokio::sync::oneshot;
 use tracing::Level;
 use tracing::debug;
 use tracing::error;
 use tracing::info;
+use tracing::warn;
 use tracing_subscriber::EnvFilter;
 use tracing_subscriber::Layer;
 use tracing_subscriber::filter::Targets;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 
+mod approval_requests;
 mod bespoke_event_handling;
 mod codex_message_processor;
+mod commands;
 mod error_code;
+mod file_change_subscriptions;
 mod fuzzy_file_search;
 mod message_processor;
 mod models;
@@ -49,27 +53,27 @@ pub async fn run_main(
     let (incoming_tx, mut incoming_rx) = mpsc::channel::<JSONRPCMessage>(CHANNEL_CAPACITY);
     let (outgoing_tx, mut outgoing_rx) = mpsc::channel::<OutgoingMessage>(CHANNEL_CAPACITY);
 
-    // Task: read from stdin, push to `incoming_tx`.
-    let stdin_reader_handle = tokio::spawn({
-        async move {
-            let stdin = io::stdin();
-            let reader = BufReader::new(stdin);
-            let mut lines = reader.lines();
-
-            while let Some(line) = lines.next_line().await.unwrap_or_default() {
-                match serde_json::from_str::<JSONRPCMessage>(&line) {
-                    Ok(msg) => {
-                        if incoming_tx.send(msg).await.is_err() {
-                            // Receiver gone – nothing left to do.
-                            break;
-                        }
+    // Thread: read from stdin, push to `incoming_tx`. This is a plain thread
+    // rather than a task on tokio's stdin because the runtime waits for
+    // blocking reads on shutdown, and after `codex/shutdown` the client may
+    // never close stdin.
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            match serde_json::from_str::<JSONRPCMessage>(&line) {
+                Ok(msg) => {
+                    if incoming_tx.blocking_send(msg).is_err() {
+                        // Receiver gone – nothing left to do.
+                        break;
                     }
-                    Err(e) => error!("Failed to deserialize JSONRPCMessage: {e}"),
                 }
+                Err(e) => error!("Failed to deserialize JSONRPCMessage: {e}"),
             }
-
-            debug!("stdin reader finished (EOF)");
         }
+
+        debug!("stdin reader finished (EOF)");
     });
 
     // Parse CLI overrides once and derive the base Config eagerly so later
@@ -128,46 +132,135 @@ pub async fn run_main(
             feedback.clone(),
         );
         async move {
-            while let Some(msg) = incoming_rx.recv().await {
-                match msg {
-                    JSONRPCMessage::Request(r) => processor.process_request(r).await,
-                    JSONRPCMessage::Response(r) => processor.process_response(r).await,
-                    JSONRPCMessage::Notification(n) => processor.process_notification(n).await,
-                    JSONRPCMessage::Error(e) => processor.process_error(e),
+            let shutdown_signal = shutdown_signal();
+            tokio::pin!(shutdown_signal);
+            loop {
+                tokio::select! {
+                    msg = incoming_rx.recv() => {
+                        let Some(msg) = msg else {
+                            info!("processor task exited (channel closed)");
+                            break;
+                        };
+                        match msg {
+                            JSONRPCMessage::Request(r) => processor.process_request(r).await,
+                            JSONRPCMessage::Response(r) => processor.process_response(r).await,
+                            JSONRPCMessage::Notification(n) => processor.process_notification(n).await,
+                            JSONRPCMessage::Error(e) => processor.process_error(e),
+                        }
+                        if processor.is_shut_down() {
+                            info!("processor task exited (shutdown requested)");
+                            break;
+                        }
+                    }
+                    () = &mut shutdown_signal => {
+                        info!("received shutdown signal");
+                        processor.shutdown().await;
+                        break;
+                    }
                 }
             }
-
-            info!("processor task exited (channel closed)");
+            processor.is_shut_down()
         }
     });
 
-    // Task: write outgoing messages to stdout.
+    // Task: write outgoing messages to stdout. Conversation listeners hold
+    // senders of their own, so after a shutdown the channel may never close;
+    // `drain_rx` tells the task to write what is queued and stop.
+    let (drain_tx, mut drain_rx) = oneshot::channel::<()>();
     let stdout_writer_handle = tokio::spawn(async move {
         let mut stdout = io::stdout();
-        while let Some(outgoing_message) = outgoing_rx.recv().await {
-            let Ok(value) = serde_json::to_value(outgoing_message) else {
-                error!("Failed to convert OutgoingMessage to JSON value");
-                continue;
-            };
-            match serde_json::to_string(&value) {
-                Ok(mut json) => {
-                    json.push('\n');
-                    if let Err(e) = stdout.write_all(json.as_bytes()).await {
-                        error!("Failed to write to stdout: {e}");
-                        break;
+        loop {
+            let outgoing_message = tokio::select! {
+                biased;
+                msg = outgoing_rx.recv() => msg,
+                _ = &mut drain_rx => {
+                    while let Ok(outgoing_message) = outgoing_rx.try_recv() {
+                        if write_outgoing_message(&mut stdout, outgoing_message).await.is_err() {
+                            break;
+                        }
                     }
+                    None
                 }
-                Err(e) => error!("Failed to serialize JSONRPCMessage: {e}"),
+            };
+            let Some(outgoing_message) = outgoing_message else {
+                break;
+            };
+            if write_outgoing_message(&mut stdout, outgoing_message)
+                .await
+                .is_err()
+            {
+                break;
             }
         }
+        if let Err(e) = stdout.flush().await {
+            error!("Failed to flush stdout: {e}");
+        }
 
-        info!("stdout writer exited (channel closed)");
+        info!("stdout writer exited");
     });
 
-    // Wait for all tasks to finish.  The typical exit path is the stdin reader
-    // hitting EOF which, once it drops `incoming_tx`, propagates shutdown to
-    // the processor and then to the stdout task.
-    let _ = tokio::join!(stdin_reader_handle, processor_handle, stdout_writer_handle);
+    // The typical exit path is the stdin reader hitting EOF which, once it
+    // drops `incoming_tx`, propagates shutdown to the processor and then to
+    // the stdout task. After `codex/shutdown` or a signal, stop the writer as
+    // soon as the final response has been written.
+    if processor_handle.await.unwrap_or(false) {
+        let _ = drain_tx.send(());
+    }
+    let _ = stdout_writer_handle.await;
 
     Ok(())
 }
+
+async fn write_outgoing_message(
+    stdout: &mut io::Stdout,
+    outgoing_message: OutgoingMessage,
+) -> IoResult<()> {
+    let Ok(value) = serde_json::to_value(outgoing_message) else {
+        error!("Failed to convert OutgoingMessage to JSON value");
+        return Ok(());
+    };
+    match serde_json::to_string(&value) {
+        Ok(mut json) => {
+            json.push('\n');
+            if let Err(e) = stdout.write_all(json.as_bytes()).await {
+                error!("Failed to write to stdout: {e}");
+                return Err(e);
+            }
+        }
+        Err(e) => error!("Failed to serialize JSONRPCMessage: {e}"),
+    }
+    Ok(())
+}
+
+/// Resolves on SIGINT or, on Unix, SIGTERM.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    let sigterm = async {
+        use tokio::signal::unix::SignalKind;
+        use tokio::signal::unix::signal;
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                warn!("failed to listen for SIGTERM: {e}");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let sigterm = std::future::pending::<()>();
+
+    let sigint = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!("failed to listen for SIGINT: {e}");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    tokio::select! {
+        () = sigterm => {}
+        () = sigint => {}
+    }
+}
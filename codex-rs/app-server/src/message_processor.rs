@@ -24,6 +24,7 @@ pub(crate) struct MessageProcessor {
     outgoing: Arc<OutgoingMessageSender>,
     codex_message_processor: CodexMessageProcessor,
     initialized: bool,
+    shut_down: bool,
 }
 
 impl MessageProcessor {
@@ -36,11 +37,7 @@ impl MessageProcessor {
         feedback: CodexFeedback,
     ) -> Self {
         let outgoing = Arc::new(outgoing);
-        let auth_manager = AuthManager::shared(
-            config.codex_home.clone(),
-            false,
-            config.cli_auth_credentials_store_mode,
-        );
+        let auth_manager = AuthManager::shared_from_config(&config, false);
         let conversation_manager = Arc::new(ConversationManager::new(
             auth_manager.clone(),
             SessionSource::VSCode,
@@ -58,6 +55,7 @@ impl MessageProcessor {
             outgoing,
             codex_message_processor,
             initialized: false,
+            shut_down: false,
         }
     }
 
@@ -134,11 +132,33 @@ impl MessageProcessor {
             }
         }
 
+        if matches!(codex_request, ClientRequest::Shutdown { .. }) {
+            self.shut_down = true;
+        }
         self.codex_message_processor
             .process_request(codex_request)
             .await;
     }
 
+    /// Whether `codex/shutdown` has been handled, after which no further
+    /// messages should be processed.
+    pub(crate) fn is_shut_down(&self) -> bool {
+        self.shut_down
+    }
+
+    /// Runs the `codex/shutdown` path with the default grace period, for when
+    /// the process is asked to stop by a signal rather than a request.
+    pub(crate) async fn shutdown(&mut self) {
+        self.shut_down = true;
+        let force_aborted = self
+            .codex_message_processor
+            .shutdown_conversations(None)
+            .await;
+        if !force_aborted.is_empty() {
+            tracing::warn!("aborted conversations that did not stop in time: {force_aborted:?}");
+        }
+    }
+
     pub(crate) async fn process_notification(&self, notification: JSONRPCNotification) {
         // Currently, we do not expect to receive any notifications from the
         // client, so we just log them.
@@ -13,11 +13,13 @@ use tokio::process::ChildStdout;
 use anyhow::Context;
 use assert_cmd::prelude::*;
 use codex_app_server_protocol::AddConversationListenerParams;
+use codex_app_server_protocol::ApprovalRespondParams;
 use codex_app_server_protocol::ArchiveConversationParams;
 use codex_app_server_protocol::CancelLoginAccountParams;
 use codex_app_server_protocol::CancelLoginChatGptParams;
 use codex_app_server_protocol::ClientInfo;
 use codex_app_server_protocol::ClientNotification;
+use codex_app_server_protocol::CommandListParams;
 use codex_app_server_protocol::FeedbackUploadParams;
 use codex_app_server_protocol::GetAccountParams;
 use codex_app_server_protocol::GetAuthStatusParams;
@@ -40,12 +42,16 @@ use codex_app_server_protocol::SendUserMessageParams;
 use codex_app_server_protocol::SendUserTurnParams;
 use codex_app_server_protocol::ServerRequest;
 use codex_app_server_protocol::SetDefaultModelParams;
+use codex_app_server_protocol::ShutdownParams;
+use codex_app_server_protocol::SubscribeFileChangesParams;
 use codex_app_server_protocol::ThreadArchiveParams;
 use codex_app_server_protocol::ThreadListParams;
 use codex_app_server_protocol::ThreadResumeParams;
 use codex_app_server_protocol::ThreadStartParams;
 use codex_app_server_protocol::TurnInterruptParams;
 use codex_app_server_protocol::TurnStartParams;
+use codex_app_server_protocol::WorkspaceFileSearchCancelParams;
+use codex_app_server_protocol::WorkspaceFileSearchParams;
 use std::process::Command as StdCommand;
 use tokio::process::Command;
 
@@ -54,7 +60,6 @@ pub struct McpProcess {
     /// Retain this child process until the client is dropped. The Tokio runtime
     /// will make a "best effort" to reap the process after it exits, but it is
     /// not a guarantee. See the `kill_on_drop` documentation for details.
-    #[allow(dead_code)]
     process: Child,
     stdin: ChildStdin,
     stdout: BufReader<ChildStdout>,
@@ -328,6 +333,34 @@ impl McpProcess {
         self.send_request("thread/list", params).await
     }
 
+    /// Send a `commands/list` JSON-RPC request.
+    pub async fn send_list_commands_request(
+        &mut self,
+        params: CommandListParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("commands/list", params).await
+    }
+
+    /// Send a `workspace/fileSearch` JSON-RPC request.
+    pub async fn send_workspace_file_search_request(
+        &mut self,
+        params: WorkspaceFileSearchParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("workspace/fileSearch", params).await
+    }
+
+    /// Send a `workspace/fileSearch/cancel` JSON-RPC request.
+    pub async fn send_workspace_file_search_cancel_request(
+        &mut self,
+        params: WorkspaceFileSearchCancelParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("workspace/fileSearch/cancel", params)
+            .await
+    }
+
     /// Send a `model/list` JSON-RPC request.
     pub async fn send_list_models_request(
         &mut self,
@@ -387,6 +420,36 @@ impl McpProcess {
         self.send_request("review/start", params).await
     }
 
+    /// Send an `approval/respond` JSON-RPC request (v2).
+    pub async fn send_approval_respond_request(
+        &mut self,
+        params: ApprovalRespondParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("approval/respond", params).await
+    }
+
+    /// Send a `codex/subscribeFileChanges` JSON-RPC request.
+    pub async fn send_subscribe_file_changes_request(
+        &mut self,
+        params: SubscribeFileChangesParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("codex/subscribeFileChanges", params)
+            .await
+    }
+
+    /// Send a `codex/shutdown` JSON-RPC request.
+    pub async fn send_shutdown_request(&mut self, params: ShutdownParams) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("codex/shutdown", params).await
+    }
+
+    /// Wait for the server process to exit on its own.
+    pub async fn wait_for_exit(&mut self) -> anyhow::Result<std::process::ExitStatus> {
+        Ok(self.process.wait().await?)
+    }
+
     /// Send a `cancelLoginChatGpt` JSON-RPC request.
     pub async fn send_cancel_login_chat_gpt_request(
         &mut self,
@@ -622,6 +685,40 @@ impl McpProcess {
         }
     }
 
+    /// Reads until a `until` notification arrives and returns every `method`
+    /// notification seen on the way.
+    pub async fn read_notifications_until(
+        &mut self,
+        method: &str,
+        until: &str,
+    ) -> anyhow::Result<Vec<JSONRPCNotification>> {
+        let mut collected = Vec::new();
+        loop {
+            let message = self.read_jsonrpc_message().await?;
+            match message {
+                JSONRPCMessage::Notification(notification) => {
+                    if notification.method == until {
+                        return Ok(collected);
+                    }
+                    if notification.method == method {
+                        collected.push(notification);
+                    } else {
+                        self.enqueue_user_message(notification);
+                    }
+                }
+                JSONRPCMessage::Request(_) => {
+                    anyhow::bail!("unexpected JSONRPCMessage::Request: {message:?}");
+                }
+                JSONRPCMessage::Error(_) => {
+                    anyhow::bail!("unexpected JSONRPCMessage::Error: {message:?}");
+                }
+                JSONRPCMessage::Response(_) => {
+                    anyhow::bail!("unexpected JSONRPCMessage::Response: {message:?}");
+                }
+            }
+        }
+    }
+
     fn take_pending_notification_by_method(&mut self, method: &str) -> Option<JSONRPCNotification> {
         if let Some(pos) = self
             .pending_user_messages
@@ -46,6 +46,7 @@ pub fn create_fake_rollout(
         instructions: None,
         source: SessionSource::Cli,
         model_provider: model_provider.map(str::to_string),
+        imported_from: None,
     })?;
 
     let lines = [
@@ -408,6 +408,7 @@ async fn get_account_with_api_key() -> Result<()> {
     let expected = GetAccountResponse {
         account: Some(Account::ApiKey {}),
         requires_openai_auth: true,
+        num_ctx: None,
     };
     assert_eq!(received, expected);
     Ok(())
@@ -442,6 +443,7 @@ async fn get_account_when_auth_not_required() -> Result<()> {
     let expected = GetAccountResponse {
         account: None,
         requires_openai_auth: false,
+        num_ctx: None,
     };
     assert_eq!(received, expected);
     Ok(())
@@ -486,6 +488,7 @@ async fn get_account_with_chatgpt() -> Result<()> {
             plan_type: AccountPlanType::Pro,
         }),
         requires_openai_auth: true,
+        num_ctx: None,
     };
     assert_eq!(received, expected);
     Ok(())
@@ -0,0 +1,398 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_apply_patch_sse_response;
+use app_test_support::create_final_assistant_message_sse_response;
+use app_test_support::create_mock_chat_completions_server;
+use app_test_support::create_shell_sse_response;
+use app_test_support::to_response;
+use codex_app_server_protocol::ApprovalRequestDetails;
+use codex_app_server_protocol::ApprovalRequestNotification;
+use codex_app_server_protocol::ApprovalRequestSettings;
+use codex_app_server_protocol::ApprovalRespondDecision;
+use codex_app_server_protocol::ApprovalRespondParams;
+use codex_app_server_protocol::ApprovalRespondResponse;
+use codex_app_server_protocol::FileUpdateChange;
+use codex_app_server_protocol::ItemCompletedNotification;
+use codex_app_server_protocol::JSONRPCNotification;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::PatchApplyStatus;
+use codex_app_server_protocol::PatchChangeKind;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::SandboxPolicy;
+use codex_app_server_protocol::ThreadArchiveParams;
+use codex_app_server_protocol::ThreadArchiveResponse;
+use codex_app_server_protocol::ThreadItem;
+use codex_app_server_protocol::ThreadStartParams;
+use codex_app_server_protocol::ThreadStartResponse;
+use codex_app_server_protocol::TurnStartParams;
+use codex_app_server_protocol::UserInput as V2UserInput;
+use core_test_support::skip_if_no_network;
+use pretty_assertions::assert_eq;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn approval_requests_are_answered_over_json_rpc_or_time_out() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let python_call = |call_id: &str| {
+        create_shell_sse_response(
+            vec![
+                "python3".to_string(),
+                "-c".to_string(),
+                "print(42)".to_string(),
+            ],
+            None,
+            Some(5000),
+            call_id,
+        )
+    };
+    let responses = vec![
+        python_call("call1")?,
+        python_call("call2")?,
+        create_final_assistant_message_sse_response("done")?,
+    ];
+    let server = create_mock_chat_completions_server(responses).await;
+
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let start_id = mcp
+        .send_thread_start_request(ThreadStartParams {
+            model: Some("mock-model".to_string()),
+            approval_requests: Some(ApprovalRequestSettings {
+                timeout_ms: Some(500),
+                timeout_decision: None,
+            }),
+            ..Default::default()
+        })
+        .await?;
+    let start_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(start_id)),
+    )
+    .await??;
+    let ThreadStartResponse { thread, .. } = to_response::<ThreadStartResponse>(start_resp)?;
+
+    let turn_id = mcp
+        .send_turn_start_request(TurnStartParams {
+            thread_id: thread.id.clone(),
+            input: vec![V2UserInput::Text {
+                text: "run python twice".to_string(),
+            }],
+            ..Default::default()
+        })
+        .await?;
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(turn_id)),
+    )
+    .await??;
+
+    // The first request is answered by the client. Reading the stream also
+    // asserts that no `item/commandExecution/requestApproval` request is sent.
+    let first = read_approval_request(&mut mcp).await?;
+    assert_eq!(first.thread_id, thread.id);
+    assert_eq!(first.sandbox_policy, SandboxPolicy::ReadOnly);
+    assert_eq!(first.timeout_ms, 500);
+    assert_eq!(first.timeout_decision, ApprovalRespondDecision::Deny);
+    let ApprovalRequestDetails::CommandExecution { item_id, .. } = &first.details else {
+        panic!(
+            "expected a command execution approval, got {:?}",
+            first.details
+        );
+    };
+    assert_eq!(item_id, "call1");
+
+    let respond_id = mcp
+        .send_approval_respond_request(ApprovalRespondParams {
+            thread_id: thread.id.clone(),
+            request_id: first.request_id.clone(),
+            decision: ApprovalRespondDecision::Allow,
+        })
+        .await?;
+    let respond_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(respond_id)),
+    )
+    .await??;
+    let _: ApprovalRespondResponse = to_response(respond_resp)?;
+
+    // The second request is left unanswered and falls back to `deny`.
+    let second = read_approval_request(&mut mcp).await?;
+    assert_ne!(second.request_id, first.request_id);
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("turn/completed"),
+    )
+    .await??;
+
+    let late_id = mcp
+        .send_approval_respond_request(ApprovalRespondParams {
+            thread_id: thread.id,
+            request_id: second.request_id,
+            decision: ApprovalRespondDecision::Allow,
+        })
+        .await?;
+    let late_err = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(late_id)),
+    )
+    .await??;
+    assert!(
+        late_err
+            .error
+            .message
+            .contains("no pending approval request"),
+        "unexpected error: {}",
+        late_err.error.message
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn file_change_approval_requests_can_be_denied() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let tmp = TempDir::new()?;
+    let codex_home = tmp.path().join("codex_home");
+    std::fs::create_dir(&codex_home)?;
+    let workspace = tmp.path().join("workspace");
+    std::fs::create_dir(&workspace)?;
+
+    let patch = r#"*** Begin Patch
+*** Add File: README.md
++new line
+*** End Patch
+"#;
+    let responses = vec![
+        create_apply_patch_sse_response(patch, "patch-call")?,
+        create_final_assistant_message_sse_response("patch declined")?,
+    ];
+    let server = create_mock_chat_completions_server(responses).await;
+    create_config_toml(&codex_home, &server.uri())?;
+
+    let mut mcp = McpProcess::new(&codex_home).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let thread_id = start_thread_with_approval_requests(
+        &mut mcp,
+        Some(workspace.to_string_lossy().into_owned()),
+        ApprovalRequestSettings {
+            timeout_ms: Some(60_000),
+            timeout_decision: Some(ApprovalRespondDecision::Allow),
+        },
+    )
+    .await?;
+    start_turn(&mut mcp, &thread_id, "apply patch").await?;
+
+    // Reading the stream also asserts that no
+    // `item/fileChange/requestApproval` request is sent.
+    let request = read_approval_request(&mut mcp).await?;
+    assert_eq!(
+        request.details,
+        ApprovalRequestDetails::FileChange {
+            item_id: "patch-call".to_string(),
+            changes: vec![FileUpdateChange {
+                path: workspace.join("README.md").to_string_lossy().into_owned(),
+                kind: PatchChangeKind::Add,
+                diff: "new line\n".to_string(),
+            }],
+            grant_root: None,
+        }
+    );
+
+    let respond_id = mcp
+        .send_approval_respond_request(ApprovalRespondParams {
+            thread_id,
+            request_id: request.request_id,
+            decision: ApprovalRespondDecision::Deny,
+        })
+        .await?;
+    let respond_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(respond_id)),
+    )
+    .await??;
+    let _: ApprovalRespondResponse = to_response(respond_resp)?;
+
+    let completed = timeout(DEFAULT_READ_TIMEOUT, async {
+        loop {
+            let notification = mcp
+                .read_stream_until_notification_message("item/completed")
+                .await?;
+            let completed: ItemCompletedNotification =
+                serde_json::from_value(notification.params.expect("item/completed params"))?;
+            if let ThreadItem::FileChange { .. } = completed.item {
+                return Ok::<ThreadItem, anyhow::Error>(completed.item);
+            }
+        }
+    })
+    .await??;
+    let ThreadItem::FileChange { id, status, .. } = completed else {
+        unreachable!("loop ensures we break on file change items");
+    };
+    assert_eq!(id, "patch-call");
+    assert_eq!(status, PatchApplyStatus::Declined);
+
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("turn/completed"),
+    )
+    .await??;
+    assert!(!workspace.join("README.md").exists());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn archiving_a_thread_drops_its_pending_approvals() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let responses = vec![create_shell_sse_response(
+        vec![
+            "python3".to_string(),
+            "-c".to_string(),
+            "print(42)".to_string(),
+        ],
+        None,
+        Some(5000),
+        "call1",
+    )?];
+    let server = create_mock_chat_completions_server(responses).await;
+
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let thread_id = start_thread_with_approval_requests(
+        &mut mcp,
+        None,
+        ApprovalRequestSettings {
+            timeout_ms: Some(60_000),
+            timeout_decision: None,
+        },
+    )
+    .await?;
+    start_turn(&mut mcp, &thread_id, "run python").await?;
+    let request = read_approval_request(&mut mcp).await?;
+
+    let archive_id = mcp
+        .send_thread_archive_request(ThreadArchiveParams {
+            thread_id: thread_id.clone(),
+        })
+        .await?;
+    let archive_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(archive_id)),
+    )
+    .await??;
+    let _: ThreadArchiveResponse = to_response(archive_resp)?;
+
+    let late_id = mcp
+        .send_approval_respond_request(ApprovalRespondParams {
+            thread_id,
+            request_id: request.request_id,
+            decision: ApprovalRespondDecision::Allow,
+        })
+        .await?;
+    let late_err = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(late_id)),
+    )
+    .await??;
+    assert!(
+        late_err
+            .error
+            .message
+            .contains("no pending approval request"),
+        "unexpected error: {}",
+        late_err.error.message
+    );
+
+    Ok(())
+}
+
+async fn start_thread_with_approval_requests(
+    mcp: &mut McpProcess,
+    cwd: Option<String>,
+    settings: ApprovalRequestSettings,
+) -> Result<String> {
+    let start_id = mcp
+        .send_thread_start_request(ThreadStartParams {
+            model: Some("mock-model".to_string()),
+            cwd,
+            approval_requests: Some(settings),
+            ..Default::default()
+        })
+        .await?;
+    let start_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(start_id)),
+    )
+    .await??;
+    let ThreadStartResponse { thread, .. } = to_response::<ThreadStartResponse>(start_resp)?;
+    Ok(thread.id)
+}
+
+async fn start_turn(mcp: &mut McpProcess, thread_id: &str, text: &str) -> Result<()> {
+    let turn_id = mcp
+        .send_turn_start_request(TurnStartParams {
+            thread_id: thread_id.to_string(),
+            input: vec![V2UserInput::Text {
+                text: text.to_string(),
+            }],
+            ..Default::default()
+        })
+        .await?;
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(turn_id)),
+    )
+    .await??;
+    Ok(())
+}
+
+async fn read_approval_request(mcp: &mut McpProcess) -> Result<ApprovalRequestNotification> {
+    let notification: JSONRPCNotification = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("approval/request"),
+    )
+    .await??;
+    let params = notification
+        .params
+        .ok_or_else(|| anyhow::anyhow!("approval/request notification missing params"))?;
+    Ok(serde_json::from_value(params)?)
+}
+
+fn create_config_toml(codex_home: &Path, server_uri: &str) -> std::io::Result<()> {
+    let config_toml = codex_home.join("config.toml");
+    std::fs::write(
+        config_toml,
+        format!(
+            r#"
+model = "mock-model"
+approval_policy = "untrusted"
+sandbox_mode = "read-only"
+
+model_provider = "mock_provider"
+
+[model_providers.mock_provider]
+name = "Mock provider for test"
+base_url = "{server_uri}/v1"
+wire_api = "chat"
+request_max_retries = 0
+stream_max_retries = 0
+"#
+        ),
+    )
+}
@@ -0,0 +1,116 @@
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::to_response;
+use codex_app_server_protocol::CommandListParams;
+use codex_app_server_protocol::CommandListResponse;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::SlashCommand;
+use codex_app_server_protocol::SlashCommandSource;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn write_prompts(codex_home: &Path) -> std::io::Result<()> {
+    let prompts = codex_home.join("prompts");
+    std::fs::create_dir_all(&prompts)?;
+    std::fs::write(
+        prompts.join("draftpr.md"),
+        "---\ndescription: Draft a PR\nargument-hint: \"[title]\"\n---\nOpen a draft PR titled $1",
+    )?;
+    // Shadowed by the built-in `/init`, so it is not listed.
+    std::fs::write(prompts.join("init.md"), "ignored")
+}
+
+async fn list_commands(mcp: &mut McpProcess, cwd: &Path) -> Result<Vec<SlashCommand>> {
+    let request_id = mcp
+        .send_list_commands_request(CommandListParams {
+            cwd: Some(cwd.to_path_buf()),
+        })
+        .await?;
+    let response: JSONRPCResponse = timeout(
+        DEFAULT_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    let CommandListResponse { data } = to_response::<CommandListResponse>(response)?;
+    Ok(data)
+}
+
+fn names(commands: &[SlashCommand]) -> Vec<&str> {
+    commands
+        .iter()
+        .map(|command| command.name.as_str())
+        .collect()
+}
+
+#[tokio::test]
+async fn list_commands_merges_builtins_and_custom_prompts() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    write_prompts(codex_home.path())?;
+    let repo = TempDir::new()?;
+    std::fs::create_dir(repo.path().join(".git"))?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_TIMEOUT, mcp.initialize()).await??;
+
+    let commands = list_commands(&mut mcp, repo.path()).await?;
+
+    assert_eq!(names(&commands)[..3], ["model", "approvals", "review"]);
+    let quit = commands
+        .iter()
+        .find(|command| command.name == "quit")
+        .expect("quit is always available");
+    assert_eq!(
+        quit,
+        &SlashCommand {
+            name: "quit".to_string(),
+            aliases: vec!["exit".to_string()],
+            description: "exit Codex".to_string(),
+            argument_hint: None,
+            available_during_task: true,
+            source: SlashCommandSource::Builtin,
+        }
+    );
+    assert_eq!(
+        commands.last(),
+        Some(&SlashCommand {
+            name: "prompts:draftpr".to_string(),
+            aliases: Vec::new(),
+            description: "Draft a PR".to_string(),
+            argument_hint: Some("[title]".to_string()),
+            available_during_task: true,
+            source: SlashCommandSource::CustomPrompt,
+        })
+    );
+    assert!(!names(&commands).contains(&"prompts:init"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_commands_hides_git_commands_outside_a_repo() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let workspace = TempDir::new()?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_TIMEOUT, mcp.initialize()).await??;
+
+    let commands = list_commands(&mut mcp, workspace.path()).await?;
+    let names = names(&commands);
+
+    for git_command in ["review", "sync-base", "diff"] {
+        assert!(
+            !names.contains(&git_command),
+            "/{git_command} should be hidden outside a Git repository: {names:?}"
+        );
+    }
+    assert!(names.contains(&"undo"));
+
+    Ok(())
+}
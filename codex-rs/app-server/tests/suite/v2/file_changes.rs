@@ -0,0 +1,163 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_apply_patch_sse_response;
+use app_test_support::create_final_assistant_message_sse_response;
+use app_test_support::create_mock_chat_completions_server;
+use app_test_support::to_response;
+use codex_app_server_protocol::AskForApproval;
+use codex_app_server_protocol::FileChangedKind;
+use codex_app_server_protocol::FileChangedNotification;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::SandboxMode;
+use codex_app_server_protocol::SubscribeFileChangesParams;
+use codex_app_server_protocol::SubscribeFileChangesResponse;
+use codex_app_server_protocol::ThreadStartParams;
+use codex_app_server_protocol::ThreadStartResponse;
+use codex_app_server_protocol::TurnStartParams;
+use codex_app_server_protocol::TurnStartResponse;
+use codex_app_server_protocol::UserInput as V2UserInput;
+use codex_protocol::ConversationId;
+use core_test_support::skip_if_no_network;
+use pretty_assertions::assert_eq;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn file_changed_is_sent_once_per_file_per_turn() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let tmp = TempDir::new()?;
+    let codex_home = tmp.path().join("codex_home");
+    std::fs::create_dir(&codex_home)?;
+    let workspace = tmp.path().join("workspace");
+    std::fs::create_dir(&workspace)?;
+    std::fs::write(workspace.join("old.txt"), "old\n")?;
+
+    let patch = r#"*** Begin Patch
+*** Add File: README.md
++new line
+*** Update File: old.txt
+*** Move to: new.txt
+@@
+-old
++new
+*** End Patch
+"#;
+    let responses = vec![
+        create_apply_patch_sse_response(patch, "patch-call")?,
+        create_final_assistant_message_sse_response("patch applied")?,
+    ];
+    let server = create_mock_chat_completions_server(responses).await;
+    create_config_toml(&codex_home, &server.uri())?;
+
+    let mut mcp = McpProcess::new(&codex_home).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let start_id = mcp
+        .send_thread_start_request(ThreadStartParams {
+            model: Some("mock-model".to_string()),
+            cwd: Some(workspace.to_string_lossy().into_owned()),
+            approval_policy: Some(AskForApproval::Never),
+            sandbox: Some(SandboxMode::WorkspaceWrite),
+            ..Default::default()
+        })
+        .await?;
+    let start_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(start_id)),
+    )
+    .await??;
+    let ThreadStartResponse { thread, .. } = to_response::<ThreadStartResponse>(start_resp)?;
+    let conversation_id = ConversationId::from_string(&thread.id)?;
+
+    let subscribe_id = mcp
+        .send_subscribe_file_changes_request(SubscribeFileChangesParams { conversation_id })
+        .await?;
+    let subscribe_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(subscribe_id)),
+    )
+    .await??;
+    let _: SubscribeFileChangesResponse = to_response(subscribe_resp)?;
+
+    let turn_req = mcp
+        .send_turn_start_request(TurnStartParams {
+            thread_id: thread.id.clone(),
+            input: vec![V2UserInput::Text {
+                text: "apply patch".into(),
+            }],
+            ..Default::default()
+        })
+        .await?;
+    let turn_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(turn_req)),
+    )
+    .await??;
+    let TurnStartResponse { turn } = to_response::<TurnStartResponse>(turn_resp)?;
+
+    // The turn diff is recomputed after the patch and again when the model
+    // response completes; each file must still be reported only once.
+    let notifications = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_notifications_until("codex/fileChanged", "codex/event/task_complete"),
+    )
+    .await??;
+    let changes = notifications
+        .into_iter()
+        .map(|notification| {
+            let params = notification
+                .params
+                .ok_or_else(|| anyhow::anyhow!("codex/fileChanged notification missing params"))?;
+            Ok(serde_json::from_value(params)?)
+        })
+        .collect::<Result<Vec<FileChangedNotification>>>()?;
+    assert_eq!(
+        changes,
+        vec![
+            FileChangedNotification {
+                conversation_id,
+                turn_id: turn.id.clone(),
+                path: workspace.join("README.md"),
+                kind: FileChangedKind::Added,
+                old_path: None,
+            },
+            FileChangedNotification {
+                conversation_id,
+                turn_id: turn.id,
+                path: workspace.join("new.txt"),
+                kind: FileChangedKind::Renamed,
+                old_path: Some(workspace.join("old.txt")),
+            },
+        ]
+    );
+
+    Ok(())
+}
+
+fn create_config_toml(codex_home: &Path, server_uri: &str) -> std::io::Result<()> {
+    let config_toml = codex_home.join("config.toml");
+    std::fs::write(
+        config_toml,
+        format!(
+            r#"
+model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "workspace-write"
+
+model_provider = "mock_provider"
+
+[model_providers.mock_provider]
+name = "Mock provider for test"
+base_url = "{server_uri}/v1"
+wire_api = "chat"
+request_max_retries = 0
+stream_max_retries = 0
+"#
+        ),
+    )
+}
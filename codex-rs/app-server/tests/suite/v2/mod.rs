@@ -1,10 +1,15 @@
 mod account;
+mod approval_request;
+mod command_list;
+mod file_changes;
 mod model_list;
 mod rate_limits;
 mod review;
+mod shutdown;
 mod thread_archive;
 mod thread_list;
 mod thread_resume;
 mod thread_start;
 mod turn_interrupt;
 mod turn_start;
+mod workspace_file_search;
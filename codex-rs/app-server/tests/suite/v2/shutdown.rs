@@ -0,0 +1,124 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_final_assistant_message_sse_response;
+use app_test_support::create_mock_chat_completions_server;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ShutdownParams;
+use codex_app_server_protocol::ShutdownResponse;
+use codex_app_server_protocol::ThreadStartParams;
+use codex_app_server_protocol::ThreadStartResponse;
+use codex_app_server_protocol::TurnStartParams;
+use codex_app_server_protocol::UserInput as V2UserInput;
+use core_test_support::skip_if_no_network;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn shutdown_closes_conversations_and_exits() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let tmp = TempDir::new()?;
+    let codex_home = tmp.path().join("codex_home");
+    std::fs::create_dir(&codex_home)?;
+    let responses = vec![create_final_assistant_message_sse_response("done")?];
+    let server = create_mock_chat_completions_server(responses).await;
+    create_config_toml(&codex_home, &server.uri())?;
+
+    let mut mcp = McpProcess::new(&codex_home).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let start_id = mcp
+        .send_thread_start_request(ThreadStartParams {
+            model: Some("mock-model".to_string()),
+            ..Default::default()
+        })
+        .await?;
+    let start_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(start_id)),
+    )
+    .await??;
+    let ThreadStartResponse { thread, .. } = to_response::<ThreadStartResponse>(start_resp)?;
+
+    mcp.send_turn_start_request(TurnStartParams {
+        thread_id: thread.id.clone(),
+        input: vec![V2UserInput::Text {
+            text: "hello".into(),
+        }],
+        ..Default::default()
+    })
+    .await?;
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("codex/event/task_complete"),
+    )
+    .await??;
+
+    let shutdown_id = mcp
+        .send_shutdown_request(ShutdownParams {
+            grace_period_ms: Some(5_000),
+        })
+        .await?;
+    let shutdown_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(shutdown_id)),
+    )
+    .await??;
+    assert_eq!(
+        to_response::<ShutdownResponse>(shutdown_resp)?,
+        ShutdownResponse {
+            force_aborted: Vec::new(),
+        }
+    );
+
+    // The server exits even though stdin is still open.
+    let status = timeout(DEFAULT_READ_TIMEOUT, mcp.wait_for_exit()).await??;
+    assert!(status.success());
+
+    let rollout = std::fs::read_to_string(&thread.path)?;
+    let last = rollout
+        .lines()
+        .last()
+        .ok_or_else(|| anyhow::anyhow!("rollout is empty"))?;
+    let mut last: Value = serde_json::from_str(last)?;
+    if let Some(line) = last.as_object_mut() {
+        line.remove("timestamp");
+    }
+    assert_eq!(
+        last,
+        json!({ "type": "session_end", "payload": { "forced": false } })
+    );
+
+    Ok(())
+}
+
+fn create_config_toml(codex_home: &Path, server_uri: &str) -> std::io::Result<()> {
+    let config_toml = codex_home.join("config.toml");
+    std::fs::write(
+        config_toml,
+        format!(
+            r#"
+model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+
+model_provider = "mock_provider"
+
+[model_providers.mock_provider]
+name = "Mock provider for test"
+base_url = "{server_uri}/v1"
+wire_api = "chat"
+request_max_retries = 0
+stream_max_retries = 0
+"#
+        ),
+    )
+}
@@ -0,0 +1,164 @@
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::WorkspaceFileMatch;
+use codex_app_server_protocol::WorkspaceFileSearchCancelParams;
+use codex_app_server_protocol::WorkspaceFileSearchParams;
+use codex_app_server_protocol::WorkspaceFileSearchResponse;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+async fn read_search_response(
+    mcp: &mut McpProcess,
+    request_id: i64,
+) -> Result<WorkspaceFileSearchResponse> {
+    let response: JSONRPCResponse = timeout(
+        DEFAULT_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    to_response::<WorkspaceFileSearchResponse>(response)
+}
+
+fn write_fixture(root: &Path) -> std::io::Result<()> {
+    std::fs::write(root.join("abc"), "x")?;
+    std::fs::write(root.join("abcde"), "x")?;
+    std::fs::write(root.join("abexy"), "x")?;
+    std::fs::write(root.join("zzz.txt"), "x")?;
+    std::fs::create_dir_all(root.join("sub"))?;
+    std::fs::write(root.join("sub").join("abce"), "x")?;
+    std::fs::create_dir_all(root.join("target"))?;
+    std::fs::write(root.join("target").join("abe.o"), "x")
+}
+
+#[tokio::test]
+async fn workspace_file_search_returns_scored_matches_with_indices() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let root = TempDir::new()?;
+    write_fixture(root.path())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_TIMEOUT, mcp.initialize()).await??;
+
+    let request_id = mcp
+        .send_workspace_file_search_request(WorkspaceFileSearchParams {
+            pattern: "abe".to_string(),
+            cwd: Some(root.path().to_path_buf()),
+            limit: Some(2),
+            compute_indices: true,
+            exclude: vec!["target/**".to_string()],
+            cancellation_token: None,
+        })
+        .await?;
+    let response = read_search_response(&mut mcp, request_id).await?;
+
+    assert_eq!(
+        response,
+        WorkspaceFileSearchResponse {
+            data: vec![
+                WorkspaceFileMatch {
+                    path: "abexy".to_string(),
+                    score: 88,
+                    indices: Some(vec![0, 1, 2]),
+                },
+                WorkspaceFileMatch {
+                    path: "abcde".to_string(),
+                    score: 74,
+                    indices: Some(vec![0, 1, 4]),
+                },
+            ],
+            total_match_count: 3,
+            cancelled: false,
+        }
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn workspace_file_search_omits_indices_unless_requested() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let root = TempDir::new()?;
+    write_fixture(root.path())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_TIMEOUT, mcp.initialize()).await??;
+
+    let request_id = mcp
+        .send_workspace_file_search_request(WorkspaceFileSearchParams {
+            pattern: "abe".to_string(),
+            cwd: Some(root.path().to_path_buf()),
+            limit: Some(1),
+            exclude: vec!["target/**".to_string()],
+            ..Default::default()
+        })
+        .await?;
+    let response = read_search_response(&mut mcp, request_id).await?;
+
+    assert_eq!(
+        response,
+        WorkspaceFileSearchResponse {
+            data: vec![WorkspaceFileMatch {
+                path: "abexy".to_string(),
+                score: 88,
+                indices: None,
+            }],
+            total_match_count: 3,
+            cancelled: false,
+        }
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn workspace_file_search_cancel_stops_a_long_walk() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let root = TempDir::new()?;
+    // Enough entries that the walk is still running when the cancel arrives;
+    // workers only check the flag every 1024 entries.
+    for dir in 0..100 {
+        let dir_path = root.path().join(format!("dir{dir}"));
+        std::fs::create_dir(&dir_path)?;
+        for file in 0..200 {
+            std::fs::write(dir_path.join(format!("file{file}.txt")), "")?;
+        }
+    }
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_TIMEOUT, mcp.initialize()).await??;
+
+    let search_id = mcp
+        .send_workspace_file_search_request(WorkspaceFileSearchParams {
+            pattern: "file".to_string(),
+            cwd: Some(root.path().to_path_buf()),
+            compute_indices: true,
+            cancellation_token: Some("search-1".to_string()),
+            ..Default::default()
+        })
+        .await?;
+    mcp.send_workspace_file_search_cancel_request(WorkspaceFileSearchCancelParams {
+        cancellation_token: "search-1".to_string(),
+    })
+    .await?;
+
+    let response = read_search_response(&mut mcp, search_id).await?;
+    assert_eq!(
+        response,
+        WorkspaceFileSearchResponse {
+            data: Vec::new(),
+            total_match_count: 0,
+            cancelled: true,
+        }
+    );
+
+    Ok(())
+}
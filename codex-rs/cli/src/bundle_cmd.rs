@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use codex_common::CliConfigOverrides;
+use codex_core::bundle::export_bundle;
+use codex_core::bundle::import_bundle;
+use codex_core::config::find_codex_home;
+
+/// Export a session as a shareable bundle with secrets scrubbed.
+#[derive(Debug, clap::Parser)]
+pub struct ExportBundleCommand {
+    /// Conversation/session id (UUID) to export.
+    #[arg(value_name = "SESSION_ID")]
+    pub session_id: String,
+
+    /// Where to write the bundle.
+    #[arg(long = "output", short = 'o', value_name = "FILE")]
+    pub output: PathBuf,
+
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+}
+
+/// Import a session bundle created by `codex export-bundle`.
+#[derive(Debug, clap::Parser)]
+pub struct ImportBundleCommand {
+    /// Bundle file to import.
+    #[arg(value_name = "FILE")]
+    pub bundle: PathBuf,
+}
+
+impl ExportBundleCommand {
+    pub async fn run(self) -> Result<()> {
+        let overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
+        let manifest = export_bundle(&codex_home, overrides, &self.session_id, &self.output)
+            .await
+            .with_context(|| format!("failed to export session {}", self.session_id))?;
+        println!(
+            "Exported session {} to {}",
+            manifest.session_id,
+            self.output.display()
+        );
+        Ok(())
+    }
+}
+
+impl ImportBundleCommand {
+    pub fn run(self) -> Result<()> {
+        let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
+        let imported = import_bundle(&codex_home, &self.bundle)
+            .with_context(|| format!("failed to import {}", self.bundle.display()))?;
+        for warning in &imported.warnings {
+            eprintln!("warning: {warning}");
+        }
+        println!(
+            "Imported session {} as {}. Resume it with `codex resume {}`.",
+            imported.manifest.session_id, imported.conversation_id, imported.conversation_id
+        );
+        Ok(())
+    }
+}
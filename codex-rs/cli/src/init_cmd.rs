@@ -0,0 +1,414 @@
+use std::io::BufRead;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use codex_core::config::find_codex_home;
+use codex_core::config::set_project_trust_level;
+use codex_core::git_info::resolve_root_git_project_for_trust;
+use codex_protocol::config_types::TrustLevel;
+
+const AGENTS_FILE: &str = "AGENTS.md";
+
+/// Set up the current project for Codex: write a starter AGENTS.md and
+/// optionally trust the project.
+#[derive(Debug, clap::Parser)]
+pub struct InitCommand {
+    /// Skip the prompts and write conservative defaults.
+    #[arg(long)]
+    pub minimal: bool,
+
+    /// Overwrite files that already exist.
+    #[arg(long)]
+    pub force: bool,
+
+    /// Mark the project as trusted without asking.
+    #[arg(long)]
+    pub trust: bool,
+}
+
+/// A kind of project recognized by its marker files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ProjectKind {
+    Rust,
+    Node {
+        /// `npm`, `pnpm`, or `yarn`, from the lockfile.
+        manager: &'static str,
+        /// Names of the scripts defined in package.json.
+        scripts: Vec<String>,
+    },
+    Python {
+        pytest: bool,
+        ruff: bool,
+    },
+}
+
+impl ProjectKind {
+    fn name(&self) -> &'static str {
+        match self {
+            ProjectKind::Rust => "Rust",
+            ProjectKind::Node { .. } => "Node.js",
+            ProjectKind::Python { .. } => "Python",
+        }
+    }
+
+    /// `(label, command)` pairs for AGENTS.md.
+    fn commands(&self) -> Vec<(&'static str, String)> {
+        match self {
+            ProjectKind::Rust => vec![
+                ("Build", "cargo build".to_string()),
+                ("Test", "cargo test".to_string()),
+                ("Lint", "cargo clippy --all-targets".to_string()),
+                ("Format", "cargo fmt".to_string()),
+            ],
+            ProjectKind::Node { manager, scripts } => {
+                let has = |name: &str| scripts.iter().any(|script| script == name);
+                let mut commands = Vec::new();
+                if has("build") {
+                    commands.push(("Build", format!("{manager} run build")));
+                }
+                if has("test") {
+                    commands.push(("Test", format!("{manager} test")));
+                }
+                if has("lint") {
+                    commands.push(("Lint", format!("{manager} run lint")));
+                }
+                if has("format") {
+                    commands.push(("Format", format!("{manager} run format")));
+                }
+                commands
+            }
+            ProjectKind::Python { pytest, ruff } => {
+                let mut commands = Vec::new();
+                if *pytest {
+                    commands.push(("Test", "pytest".to_string()));
+                }
+                if *ruff {
+                    commands.push(("Lint", "ruff check .".to_string()));
+                    commands.push(("Format", "ruff format .".to_string()));
+                }
+                commands
+            }
+        }
+    }
+}
+
+/// Detect the kinds of project rooted at `dir` from their marker files.
+pub(crate) fn detect_project_kinds(dir: &Path) -> Vec<ProjectKind> {
+    let mut kinds = Vec::new();
+    if dir.join("Cargo.toml").is_file() {
+        kinds.push(ProjectKind::Rust);
+    }
+    if let Ok(package_json) = std::fs::read_to_string(dir.join("package.json")) {
+        let manager = if dir.join("pnpm-lock.yaml").is_file() {
+            "pnpm"
+        } else if dir.join("yarn.lock").is_file() {
+            "yarn"
+        } else {
+            "npm"
+        };
+        let scripts = serde_json::from_str::<serde_json::Value>(&package_json)
+            .ok()
+            .and_then(|value| {
+                value
+                    .get("scripts")
+                    .and_then(serde_json::Value::as_object)
+                    .map(|scripts| scripts.keys().cloned().collect())
+            })
+            .unwrap_or_default();
+        kinds.push(ProjectKind::Node { manager, scripts });
+    }
+    let pyproject = std::fs::read_to_string(dir.join("pyproject.toml")).ok();
+    let python_markers = ["setup.py", "requirements.txt"];
+    if pyproject.is_some() || python_markers.iter().any(|m| dir.join(m).is_file()) {
+        let pyproject = pyproject.unwrap_or_default();
+        kinds.push(ProjectKind::Python {
+            pytest: pyproject.contains("pytest") || dir.join("tests").is_dir(),
+            ruff: pyproject.contains("[tool.ruff"),
+        });
+    }
+    kinds
+}
+
+/// What to include, answered interactively or by `--minimal`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct InitChoices {
+    pub commands: bool,
+    pub scopes_example: bool,
+    pub trust: bool,
+}
+
+/// Render the starter AGENTS.md.
+pub(crate) fn render_agents_md(kinds: &[ProjectKind], choices: InitChoices) -> String {
+    let mut out = String::from("# Agent instructions\n\n## Project\n\n");
+    let names: Vec<&str> = kinds.iter().map(ProjectKind::name).collect();
+    match names.as_slice() {
+        [] => out.push_str("Describe what this project does and how it is laid out.\n"),
+        [name] => out.push_str(&format!("This is a {name} project.\n")),
+        names => out.push_str(&format!(
+            "This repository contains {} code.\n",
+            names.join(" and ")
+        )),
+    }
+
+    out.push_str("\n## Build and test\n\n");
+    let commands: Vec<(&str, String)> = if choices.commands {
+        kinds.iter().flat_map(ProjectKind::commands).collect()
+    } else {
+        Vec::new()
+    };
+    if commands.is_empty() {
+        out.push_str("List the commands that build, test, and lint this project.\n");
+    } else {
+        for (label, command) in commands {
+            out.push_str(&format!("- {label}: `{command}`\n"));
+        }
+        out.push_str("\nRun the tests before finishing a change.\n");
+    }
+
+    if choices.scopes_example {
+        out.push_str(
+            "\n## Path-scoped instructions\n\n\
+             Instructions in a `scopes` block only reach the agent when it works on matching \
+             files. Replace this example with your own:\n\n\
+             ```scopes\n\
+             [[scope]]\n\
+             paths = [\"docs/\"]\n\
+             instructions = \"Keep the docs in sync with user-facing changes.\"\n\
+             ```\n",
+        );
+    }
+    out
+}
+
+/// What `codex init` did, for the closing summary.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct InitSummary {
+    pub written: Vec<PathBuf>,
+    pub skipped: Vec<PathBuf>,
+    pub trusted: Option<PathBuf>,
+}
+
+/// Write the scaffold into `dir`. Existing files are kept unless `force`.
+pub(crate) fn scaffold(
+    dir: &Path,
+    codex_home: &Path,
+    kinds: &[ProjectKind],
+    choices: InitChoices,
+    force: bool,
+) -> Result<InitSummary> {
+    let mut summary = InitSummary::default();
+
+    let agents_path = dir.join(AGENTS_FILE);
+    if agents_path.exists() && !force {
+        summary.skipped.push(agents_path);
+    } else {
+        std::fs::write(&agents_path, render_agents_md(kinds, choices))
+            .with_context(|| format!("failed to write {}", agents_path.display()))?;
+        summary.written.push(agents_path);
+    }
+
+    if choices.trust {
+        // Same target as the trust prompt shown on first launch.
+        let target = resolve_root_git_project_for_trust(dir).unwrap_or_else(|| dir.to_path_buf());
+        set_project_trust_level(codex_home, &target, TrustLevel::Trusted)
+            .with_context(|| format!("failed to trust {}", target.display()))?;
+        summary.trusted = Some(target);
+    }
+
+    Ok(summary)
+}
+
+impl InitCommand {
+    pub fn run(self) -> Result<()> {
+        let dir = std::env::current_dir().context("failed to resolve the current directory")?;
+        let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
+        let kinds = detect_project_kinds(&dir);
+
+        let choices = if self.minimal {
+            InitChoices {
+                commands: true,
+                scopes_example: false,
+                trust: self.trust,
+            }
+        } else {
+            let mut stdin = std::io::stdin().lock();
+            if kinds.is_empty() {
+                println!("No Cargo.toml, package.json, or Python project files found.");
+            } else {
+                let names: Vec<&str> = kinds.iter().map(ProjectKind::name).collect();
+                println!("Detected a {} project.", names.join(" and "));
+            }
+            InitChoices {
+                commands: kinds.is_empty()
+                    || ask(
+                        &mut stdin,
+                        "Add its build and test commands to AGENTS.md?",
+                        true,
+                    )?,
+                scopes_example: ask(
+                    &mut stdin,
+                    "Include an example of path-scoped instructions?",
+                    true,
+                )?,
+                trust: self.trust
+                    || ask(
+                        &mut stdin,
+                        "Trust this project so Codex can edit files and run commands without asking?",
+                        false,
+                    )?,
+            }
+        };
+
+        let summary = scaffold(&dir, &codex_home, &kinds, choices, self.force)?;
+        for path in &summary.written {
+            println!("Created {}", path.display());
+        }
+        for path in &summary.skipped {
+            println!(
+                "Skipped {} (already exists; pass --force to overwrite)",
+                path.display()
+            );
+        }
+        if let Some(path) = &summary.trusted {
+            println!("Trusted {}", path.display());
+        }
+        Ok(())
+    }
+}
+
+/// Ask a yes/no question on stdout and read the answer from `input`. An
+/// empty answer or end of input picks `default`.
+fn ask(input: &mut impl BufRead, question: &str, default: bool) -> Result<bool> {
+    let hint = if default { "[Y/n]" } else { "[y/N]" };
+    print!("{question} {hint} ");
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    Ok(match answer.trim().to_ascii_lowercase().as_str() {
+        "y" | "yes" => true,
+        "n" | "no" => false,
+        _ => default,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    const ALL: InitChoices = InitChoices {
+        commands: true,
+        scopes_example: true,
+        trust: false,
+    };
+
+    #[test]
+    fn detects_rust_projects() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "[package]\n").unwrap();
+        assert_eq!(detect_project_kinds(dir.path()), vec![ProjectKind::Rust]);
+    }
+
+    #[test]
+    fn detects_node_projects_with_manager_and_scripts() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"scripts": {"build": "tsc", "test": "vitest"}}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("pnpm-lock.yaml"), "").unwrap();
+        let kinds = detect_project_kinds(dir.path());
+        assert_eq!(
+            kinds,
+            vec![ProjectKind::Node {
+                manager: "pnpm",
+                scripts: vec!["build".to_string(), "test".to_string()],
+            }]
+        );
+        assert_eq!(
+            kinds[0].commands(),
+            vec![
+                ("Build", "pnpm run build".to_string()),
+                ("Test", "pnpm test".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn detects_python_projects() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("pyproject.toml"),
+            "[tool.pytest.ini_options]\n[tool.ruff]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            detect_project_kinds(dir.path()),
+            vec![ProjectKind::Python {
+                pytest: true,
+                ruff: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn agents_md_lists_detected_commands() {
+        let rendered = render_agents_md(&[ProjectKind::Rust], ALL);
+        assert!(rendered.contains("This is a Rust project."));
+        assert!(rendered.contains("- Test: `cargo test`\n"));
+        assert!(rendered.contains("```scopes\n[[scope]]\n"));
+
+        let minimal = render_agents_md(
+            &[],
+            InitChoices {
+                scopes_example: false,
+                ..ALL
+            },
+        );
+        assert_eq!(
+            minimal,
+            "# Agent instructions\n\n## Project\n\n\
+             Describe what this project does and how it is laid out.\n\n\
+             ## Build and test\n\n\
+             List the commands that build, test, and lint this project.\n"
+        );
+    }
+
+    #[test]
+    fn existing_files_are_kept_unless_forced() {
+        let dir = TempDir::new().unwrap();
+        let home = TempDir::new().unwrap();
+        let agents = dir.path().join(AGENTS_FILE);
+        std::fs::write(&agents, "mine\n").unwrap();
+
+        let summary = scaffold(dir.path(), home.path(), &[], ALL, false).unwrap();
+        assert_eq!(
+            summary,
+            InitSummary {
+                written: Vec::new(),
+                skipped: vec![agents.clone()],
+                trusted: None,
+            }
+        );
+        assert_eq!(std::fs::read_to_string(&agents).unwrap(), "mine\n");
+
+        let summary = scaffold(dir.path(), home.path(), &[], ALL, true).unwrap();
+        assert_eq!(summary.written, vec![agents.clone()]);
+        assert_eq!(
+            std::fs::read_to_string(&agents).unwrap(),
+            render_agents_md(&[], ALL)
+        );
+    }
+
+    #[test]
+    fn ask_uses_default_on_empty_answer() {
+        assert!(ask(&mut "\n".as_bytes(), "q?", true).unwrap());
+        assert!(!ask(&mut "".as_bytes(), "q?", false).unwrap());
+        assert!(ask(&mut "yes\n".as_bytes(), "q?", false).unwrap());
+    }
+}
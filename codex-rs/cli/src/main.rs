@@ -21,19 +21,30 @@ use codex_exec::Cli as ExecCli;
 use codex_responses_api_proxy::Args as ResponsesApiProxyArgs;
 use codex_tui::AppExitInfo;
 use codex_tui::Cli as TuiCli;
+use codex_tui::ReplayCli;
 use codex_tui::update_action::UpdateAction;
 use owo_colors::OwoColorize;
 use std::path::PathBuf;
 use supports_color::Stream;
 
+mod bundle_cmd;
+mod init_cmd;
 mod mcp_cmd;
+mod stats_cmd;
 #[cfg(not(windows))]
 mod wsl_paths;
 
+use crate::bundle_cmd::ExportBundleCommand;
+use crate::bundle_cmd::ImportBundleCommand;
+use crate::init_cmd::InitCommand;
 use crate::mcp_cmd::McpCli;
+use crate::stats_cmd::StatsCommand;
 
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
+use codex_core::config::find_codex_home;
+use codex_core::config::load_config_as_toml_with_cli_overrides;
+use codex_core::config::profile::render_profiles;
 use codex_core::features::is_known_feature_key;
 
 /// Codex CLI
@@ -97,9 +108,24 @@ enum Subcommand {
     #[clap(visible_alias = "a")]
     Apply(ApplyCommand),
 
+    /// Set up the current project: write a starter AGENTS.md and optionally trust it.
+    Init(InitCommand),
+
     /// Resume a previous interactive session (picker by default; use --last to continue the most recent).
     Resume(ResumeCommand),
 
+    /// Re-render a recorded session in a read-only TUI without contacting the model.
+    Replay(ReplayCli),
+
+    /// Export a session as a shareable bundle with secrets scrubbed.
+    ExportBundle(ExportBundleCommand),
+
+    /// Import a session bundle created by `codex export-bundle`.
+    ImportBundle(ImportBundleCommand),
+
+    /// Show the session recall index used by `search_past_sessions`.
+    Stats(StatsCommand),
+
     /// [EXPERIMENTAL] Browse tasks from Codex Cloud and apply changes locally.
     #[clap(name = "cloud", alias = "cloud-tasks")]
     Cloud(CloudTasksCli),
@@ -114,6 +140,9 @@ enum Subcommand {
 
     /// Inspect feature flags.
     Features(FeaturesCli),
+
+    /// Inspect configuration profiles.
+    Config(ConfigCli),
 }
 
 #[derive(Debug, Parser)]
@@ -254,6 +283,7 @@ fn format_exit_messages(exit_info: AppExitInfo, color_enabled: bool) -> Vec<Stri
     let AppExitInfo {
         token_usage,
         conversation_id,
+        ephemeral,
         ..
     } = exit_info;
 
@@ -266,7 +296,11 @@ fn format_exit_messages(exit_info: AppExitInfo, color_enabled: bool) -> Vec<Stri
         codex_core::protocol::FinalOutput::from(token_usage)
     )];
 
-    if let Some(session_id) = conversation_id {
+    if ephemeral {
+        lines.push(
+            "This session was ephemeral: nothing was saved, so it cannot be resumed.".to_string(),
+        );
+    } else if let Some(session_id) = conversation_id {
         let resume_cmd = format!("codex resume {session_id}");
         let command = if color_enabled {
             resume_cmd.cyan().to_string()
@@ -373,6 +407,29 @@ enum FeaturesSubcommand {
     List,
 }
 
+#[derive(Debug, Parser)]
+struct ConfigCli {
+    #[command(subcommand)]
+    sub: ConfigSubcommand,
+}
+
+#[derive(Debug, Parser)]
+enum ConfigSubcommand {
+    /// Print profiles from config.toml as TOML.
+    List(ConfigListArgs),
+}
+
+#[derive(Debug, Args)]
+struct ConfigListArgs {
+    /// Only print this profile.
+    #[arg(long = "profile", value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Flatten `extends` chains into the values the profile actually uses.
+    #[arg(long = "resolved", default_value_t = false)]
+    resolved: bool,
+}
+
 fn stage_str(stage: codex_core::features::Stage) -> &'static str {
     use codex_core::features::Stage;
     match stage {
@@ -466,6 +523,13 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             let exit_info = codex_tui::run_main(interactive, codex_linux_sandbox_exe).await?;
             handle_app_exit(exit_info)?;
         }
+        Some(Subcommand::Replay(mut replay_cli)) => {
+            prepend_config_flags(
+                &mut replay_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            codex_tui::run_replay(replay_cli).await?;
+        }
         Some(Subcommand::Login(mut login_cli)) => {
             prepend_config_flags(
                 &mut login_cli.config_overrides,
@@ -504,6 +568,22 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             );
             run_logout(logout_cli.config_overrides).await;
         }
+        Some(Subcommand::ExportBundle(mut export_cli)) => {
+            prepend_config_flags(
+                &mut export_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            export_cli.run().await?;
+        }
+        Some(Subcommand::ImportBundle(import_cli)) => {
+            import_cli.run()?;
+        }
+        Some(Subcommand::Init(init_cli)) => {
+            init_cli.run()?;
+        }
+        Some(Subcommand::Stats(stats_cli)) => {
+            stats_cli.run()?;
+        }
         Some(Subcommand::Completion(completion_cli)) => {
             print_completion(completion_cli);
         }
@@ -512,6 +592,7 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
                 &mut cloud_cli.config_overrides,
                 root_config_overrides.clone(),
             );
+            cloud_cli.offline |= interactive.offline;
             codex_cloud_tasks::run_main(cloud_cli, codex_linux_sandbox_exe).await?;
         }
         Some(Subcommand::Sandbox(sandbox_args)) => match sandbox_args.cmd {
@@ -565,6 +646,24 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             tokio::task::spawn_blocking(move || codex_stdio_to_uds::run(socket_path.as_path()))
                 .await??;
         }
+        Some(Subcommand::Config(ConfigCli { sub })) => match sub {
+            ConfigSubcommand::List(args) => {
+                let cli_kv_overrides = root_config_overrides
+                    .parse_overrides()
+                    .map_err(anyhow::Error::msg)?;
+                let codex_home = find_codex_home()?;
+                let config_toml =
+                    load_config_as_toml_with_cli_overrides(&codex_home, cli_kv_overrides).await?;
+                print!(
+                    "{}",
+                    render_profiles(
+                        &config_toml.profiles,
+                        args.profile.as_deref(),
+                        args.resolved
+                    )?
+                );
+            }
+        },
         Some(Subcommand::Features(FeaturesCli { sub })) => match sub {
             FeaturesSubcommand::List => {
                 // Respect root-level `-c` overrides plus top-level flags like `--profile`.
@@ -668,6 +767,15 @@ fn merge_resume_cli_flags(interactive: &mut TuiCli, resume_cli: TuiCli) {
     if resume_cli.web_search {
         interactive.web_search = true;
     }
+    if resume_cli.offline {
+        interactive.offline = true;
+    }
+    if resume_cli.ephemeral {
+        interactive.ephemeral = true;
+    }
+    if let Some(speed) = resume_cli.replay_speed {
+        interactive.replay_speed = Some(speed);
+    }
     if !resume_cli.images.is_empty() {
         interactive.images = resume_cli.images;
     }
@@ -739,6 +847,7 @@ mod tests {
                 .map(ConversationId::from_string)
                 .map(Result::unwrap),
             update_action: None,
+            ephemeral: false,
         }
     }
 
@@ -748,6 +857,7 @@ mod tests {
             token_usage: TokenUsage::default(),
             conversation_id: None,
             update_action: None,
+            ephemeral: false,
         };
         let lines = format_exit_messages(exit_info, false);
         assert!(lines.is_empty());
@@ -775,6 +885,23 @@ mod tests {
         assert!(lines[1].contains("\u{1b}[36m"));
     }
 
+    #[test]
+    fn format_exit_messages_explains_ephemeral_sessions() {
+        let exit_info = AppExitInfo {
+            ephemeral: true,
+            ..sample_exit_info(Some("123e4567-e89b-12d3-a456-426614174000"))
+        };
+        let lines = format_exit_messages(exit_info, false);
+        assert_eq!(
+            lines,
+            vec![
+                "Token usage: total=2 input=0 output=2".to_string(),
+                "This session was ephemeral: nothing was saved, so it cannot be resumed."
+                    .to_string(),
+            ]
+        );
+    }
+
     #[test]
     fn resume_model_flag_applies_when_no_root_flags() {
         let interactive = finalize_from_args(["codex", "resume", "-m", "gpt-5.1-test"].as_ref());
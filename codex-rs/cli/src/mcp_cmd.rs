@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::Path;
 
 use anyhow::Context;
 use anyhow::Result;
@@ -11,7 +12,11 @@ use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::config::edit::ConfigEditsBuilder;
 use codex_core::config::find_codex_home;
+use codex_core::config::load_config_as_toml_with_cli_overrides;
 use codex_core::config::load_global_mcp_servers;
+use codex_core::config::profile::McpServersMode;
+use codex_core::config::profile::resolve_profile;
+use codex_core::config::types::CacheToolResults;
 use codex_core::config::types::McpServerConfig;
 use codex_core::config::types::McpServerTransportConfig;
 use codex_core::features::Feature;
@@ -29,6 +34,10 @@ use codex_rmcp_client::supports_oauth_login;
 /// - `get`    — show a single server (with `--json`)
 /// - `add`    — add a server launcher entry to `~/.codex/config.toml`
 /// - `remove` — delete a server entry
+///
+/// `add` and `remove` edit the table the server comes from under the active
+/// profile: the profile's own `mcp_servers` when it declares the server (or
+/// replaces the top-level list), the top-level table otherwise.
 #[derive(Debug, clap::Parser)]
 pub struct McpCli {
     #[clap(flatten)]
@@ -46,10 +55,10 @@ pub enum McpSubcommand {
     /// [experimental] Show details for a configured MCP server.
     Get(GetArgs),
 
-    /// [experimental] Add a global MCP server entry.
+    /// [experimental] Add an MCP server entry.
     Add(AddArgs),
 
-    /// [experimental] Remove a global MCP server entry.
+    /// [experimental] Remove an MCP server entry.
     Remove(RemoveArgs),
 
     /// [experimental] Authenticate with a configured MCP server via OAuth.
@@ -199,7 +208,7 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
     let overrides = config_overrides
         .parse_overrides()
         .map_err(anyhow::Error::msg)?;
-    let config = Config::load_with_cli_overrides(overrides, ConfigOverrides::default())
+    let config = Config::load_with_cli_overrides(overrides.clone(), ConfigOverrides::default())
         .await
         .context("failed to load configuration")?;
 
@@ -211,9 +220,10 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
     validate_server_name(&name)?;
 
     let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
-    let mut servers = load_global_mcp_servers(&codex_home)
-        .await
-        .with_context(|| format!("failed to load MCP servers from {}", codex_home.display()))?;
+    let target_profile = match config.mcp_server_profiles.get(&name) {
+        Some(profile) => Some(profile.clone()),
+        None => replacing_profile(&codex_home, overrides, config.active_profile.as_deref()).await?,
+    };
 
     let transport = match transport_args {
         AddMcpTransportArgs {
@@ -261,17 +271,28 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
         tool_timeout_sec: None,
         enabled_tools: None,
         disabled_tools: None,
+        cache_tool_results: None,
     };
 
-    servers.insert(name.clone(), new_entry);
-
-    ConfigEditsBuilder::new(&codex_home)
-        .replace_mcp_servers(&servers)
-        .apply()
-        .await
-        .with_context(|| format!("failed to write MCP servers to {}", codex_home.display()))?;
-
-    println!("Added global MCP server '{name}'.");
+    if let Some(profile) = target_profile {
+        ConfigEditsBuilder::new(&codex_home)
+            .set_profile_mcp_server(&profile, &name, &new_entry)
+            .apply()
+            .await
+            .with_context(|| format!("failed to write MCP servers to {}", codex_home.display()))?;
+        println!("Added MCP server '{name}' to profile '{profile}'.");
+    } else {
+        let mut servers = load_global_mcp_servers(&codex_home)
+            .await
+            .with_context(|| format!("failed to load MCP servers from {}", codex_home.display()))?;
+        servers.insert(name.clone(), new_entry);
+        ConfigEditsBuilder::new(&codex_home)
+            .replace_mcp_servers(&servers)
+            .apply()
+            .await
+            .with_context(|| format!("failed to write MCP servers to {}", codex_home.display()))?;
+        println!("Added global MCP server '{name}'.");
+    }
 
     if let McpServerTransportConfig::StreamableHttp {
         url,
@@ -312,15 +333,28 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
 }
 
 async fn run_remove(config_overrides: &CliConfigOverrides, remove_args: RemoveArgs) -> Result<()> {
-    config_overrides
+    let overrides = config_overrides
         .parse_overrides()
         .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides(overrides, ConfigOverrides::default())
+        .await
+        .context("failed to load configuration")?;
 
     let RemoveArgs { name } = remove_args;
 
     validate_server_name(&name)?;
 
     let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
+    if let Some(profile) = config.mcp_server_profiles.get(&name) {
+        ConfigEditsBuilder::new(&codex_home)
+            .remove_profile_mcp_server(profile, &name)
+            .apply()
+            .await
+            .with_context(|| format!("failed to write MCP servers to {}", codex_home.display()))?;
+        println!("Removed MCP server '{name}' from profile '{profile}'.");
+        return Ok(());
+    }
+
     let mut servers = load_global_mcp_servers(&codex_home)
         .await
         .with_context(|| format!("failed to load MCP servers from {}", codex_home.display()))?;
@@ -344,6 +378,24 @@ async fn run_remove(config_overrides: &CliConfigOverrides, remove_args: RemoveAr
     Ok(())
 }
 
+/// The active profile when it replaces the top-level `mcp_servers` table, so
+/// that a new server has to be added to the profile to take effect.
+async fn replacing_profile(
+    codex_home: &Path,
+    overrides: Vec<(String, toml::Value)>,
+    active_profile: Option<&str>,
+) -> Result<Option<String>> {
+    let Some(active_profile) = active_profile else {
+        return Ok(None);
+    };
+    let config_toml = load_config_as_toml_with_cli_overrides(codex_home, overrides)
+        .await
+        .context("failed to load configuration")?;
+    let profile = resolve_profile(&config_toml.profiles, active_profile)?;
+    let replaces = profile.mcp_servers_mode.unwrap_or_default() == McpServersMode::Replace;
+    Ok(replaces.then(|| active_profile.to_string()))
+}
+
 async fn run_login(config_overrides: &CliConfigOverrides, login_args: LoginArgs) -> Result<()> {
     let overrides = config_overrides
         .parse_overrides()
@@ -482,6 +534,7 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
                         .tool_timeout_sec
                         .map(|timeout| timeout.as_secs_f64()),
                     "auth_status": auth_status,
+                    "profile": config.mcp_server_profiles.get(name.as_str()),
                 })
             })
             .collect();
@@ -495,10 +548,15 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
         return Ok(());
     }
 
-    let mut stdio_rows: Vec<[String; 7]> = Vec::new();
-    let mut http_rows: Vec<[String; 5]> = Vec::new();
+    let mut stdio_rows: Vec<[String; 8]> = Vec::new();
+    let mut http_rows: Vec<[String; 6]> = Vec::new();
 
     for (name, cfg) in entries {
+        let profile_display = config
+            .mcp_server_profiles
+            .get(name.as_str())
+            .cloned()
+            .unwrap_or_else(|| "-".to_string());
         match &cfg.transport {
             McpServerTransportConfig::Stdio {
                 command,
@@ -536,6 +594,7 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
                     cwd_display,
                     status,
                     auth_status,
+                    profile_display,
                 ]);
             }
             McpServerTransportConfig::StreamableHttp {
@@ -561,6 +620,7 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
                     bearer_token_display,
                     status,
                     auth_status,
+                    profile_display,
                 ]);
             }
         }
@@ -575,6 +635,7 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
             "Cwd".len(),
             "Status".len(),
             "Auth".len(),
+            "Profile".len(),
         ];
         for row in &stdio_rows {
             for (i, cell) in row.iter().enumerate() {
@@ -583,7 +644,7 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
         }
 
         println!(
-            "{name:<name_w$}  {command:<cmd_w$}  {args:<args_w$}  {env:<env_w$}  {cwd:<cwd_w$}  {status:<status_w$}  {auth:<auth_w$}",
+            "{name:<name_w$}  {command:<cmd_w$}  {args:<args_w$}  {env:<env_w$}  {cwd:<cwd_w$}  {status:<status_w$}  {auth:<auth_w$}  {profile:<profile_w$}",
             name = "Name",
             command = "Command",
             args = "Args",
@@ -591,6 +652,7 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
             cwd = "Cwd",
             status = "Status",
             auth = "Auth",
+            profile = "Profile",
             name_w = widths[0],
             cmd_w = widths[1],
             args_w = widths[2],
@@ -598,11 +660,12 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
             cwd_w = widths[4],
             status_w = widths[5],
             auth_w = widths[6],
+            profile_w = widths[7],
         );
 
         for row in &stdio_rows {
             println!(
-                "{name:<name_w$}  {command:<cmd_w$}  {args:<args_w$}  {env:<env_w$}  {cwd:<cwd_w$}  {status:<status_w$}  {auth:<auth_w$}",
+                "{name:<name_w$}  {command:<cmd_w$}  {args:<args_w$}  {env:<env_w$}  {cwd:<cwd_w$}  {status:<status_w$}  {auth:<auth_w$}  {profile:<profile_w$}",
                 name = row[0].as_str(),
                 command = row[1].as_str(),
                 args = row[2].as_str(),
@@ -610,6 +673,7 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
                 cwd = row[4].as_str(),
                 status = row[5].as_str(),
                 auth = row[6].as_str(),
+                profile = row[7].as_str(),
                 name_w = widths[0],
                 cmd_w = widths[1],
                 args_w = widths[2],
@@ -617,6 +681,7 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
                 cwd_w = widths[4],
                 status_w = widths[5],
                 auth_w = widths[6],
+                profile_w = widths[7],
             );
         }
     }
@@ -632,6 +697,7 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
             "Bearer Token Env Var".len(),
             "Status".len(),
             "Auth".len(),
+            "Profile".len(),
         ];
         for row in &http_rows {
             for (i, cell) in row.iter().enumerate() {
@@ -640,32 +706,36 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
         }
 
         println!(
-            "{name:<name_w$}  {url:<url_w$}  {token:<token_w$}  {status:<status_w$}  {auth:<auth_w$}",
+            "{name:<name_w$}  {url:<url_w$}  {token:<token_w$}  {status:<status_w$}  {auth:<auth_w$}  {profile:<profile_w$}",
             name = "Name",
             url = "Url",
             token = "Bearer Token Env Var",
             status = "Status",
             auth = "Auth",
+            profile = "Profile",
             name_w = widths[0],
             url_w = widths[1],
             token_w = widths[2],
             status_w = widths[3],
             auth_w = widths[4],
+            profile_w = widths[5],
         );
 
         for row in &http_rows {
             println!(
-                "{name:<name_w$}  {url:<url_w$}  {token:<token_w$}  {status:<status_w$}  {auth:<auth_w$}",
+                "{name:<name_w$}  {url:<url_w$}  {token:<token_w$}  {status:<status_w$}  {auth:<auth_w$}  {profile:<profile_w$}",
                 name = row[0].as_str(),
                 url = row[1].as_str(),
                 token = row[2].as_str(),
                 status = row[3].as_str(),
                 auth = row[4].as_str(),
+                profile = row[5].as_str(),
                 name_w = widths[0],
                 url_w = widths[1],
                 token_w = widths[2],
                 status_w = widths[3],
                 auth_w = widths[4],
+                profile_w = widths[5],
             );
         }
     }
@@ -720,6 +790,8 @@ async fn run_get(config_overrides: &CliConfigOverrides, get_args: GetArgs) -> Re
             "transport": transport,
             "enabled_tools": server.enabled_tools.clone(),
             "disabled_tools": server.disabled_tools.clone(),
+            "cache_tool_results": server.cache_tool_results.clone(),
+            "profile": config.mcp_server_profiles.get(&get_args.name),
             "startup_timeout_sec": server
                 .startup_timeout_sec
                 .map(|timeout| timeout.as_secs_f64()),
@@ -738,6 +810,9 @@ async fn run_get(config_overrides: &CliConfigOverrides, get_args: GetArgs) -> Re
 
     println!("{}", get_args.name);
     println!("  enabled: {}", server.enabled);
+    if let Some(profile) = config.mcp_server_profiles.get(&get_args.name) {
+        println!("  profile: {profile}");
+    }
     let format_tool_list = |tools: &Option<Vec<String>>| -> String {
         match tools {
             Some(list) if list.is_empty() => "[]".to_string(),
@@ -753,6 +828,13 @@ async fn run_get(config_overrides: &CliConfigOverrides, get_args: GetArgs) -> Re
         let disabled_tools_display = format_tool_list(&server.disabled_tools);
         println!("  disabled_tools: {disabled_tools_display}");
     }
+    match &server.cache_tool_results {
+        Some(CacheToolResults::All(enabled)) => println!("  cache_tool_results: {enabled}"),
+        Some(CacheToolResults::Tools(tools)) => {
+            println!("  cache_tool_results: {}", tools.join(", "));
+        }
+        None => {}
+    }
     match &server.transport {
         McpServerTransportConfig::Stdio {
             command,
@@ -0,0 +1,38 @@
+use anyhow::Context;
+use anyhow::Result;
+use codex_core::config::find_codex_home;
+use codex_core::session_recall::RecallIndex;
+use codex_core::session_recall::purge_index;
+use codex_core::session_recall::recall_index_dir;
+
+/// Show what `search_past_sessions` has indexed, or delete the index.
+#[derive(Debug, clap::Parser)]
+pub struct StatsCommand {
+    /// Delete the session recall index. It is rebuilt on the next search.
+    #[arg(long = "purge-index", default_value_t = false)]
+    pub purge_index: bool,
+}
+
+impl StatsCommand {
+    pub fn run(self) -> Result<()> {
+        let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
+        if self.purge_index {
+            let purged = purge_index(&codex_home).context("failed to delete the recall index")?;
+            if purged {
+                println!("Deleted the session recall index.");
+            } else {
+                println!("No session recall index to delete.");
+            }
+            return Ok(());
+        }
+
+        let index = RecallIndex::load(&recall_index_dir(&codex_home));
+        println!(
+            "Session recall index: {} sessions, {} messages ({})",
+            index.session_count(),
+            index.entry_count(),
+            recall_index_dir(&codex_home).display()
+        );
+        Ok(())
+    }
+}
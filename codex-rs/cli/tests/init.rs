@@ -0,0 +1,94 @@
+use std::path::Path;
+
+use anyhow::Result;
+use predicates::str::contains;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+
+fn codex_command(codex_home: &Path, cwd: &Path) -> Result<assert_cmd::Command> {
+    let mut cmd = assert_cmd::Command::cargo_bin("codex")?;
+    cmd.env("CODEX_HOME", codex_home).current_dir(cwd);
+    Ok(cmd)
+}
+
+#[test]
+fn minimal_init_writes_agents_md_without_trusting() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let project = TempDir::new()?;
+    std::fs::write(project.path().join("Cargo.toml"), "[package]\n")?;
+
+    codex_command(codex_home.path(), project.path())?
+        .args(["init", "--minimal"])
+        .assert()
+        .success()
+        .stdout(contains("Created"));
+
+    let agents = std::fs::read_to_string(project.path().join("AGENTS.md"))?;
+    assert!(agents.contains("- Test: `cargo test`"), "{agents}");
+    assert!(!codex_home.path().join("config.toml").exists());
+    Ok(())
+}
+
+#[test]
+fn init_keeps_existing_agents_md_unless_forced() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let project = TempDir::new()?;
+    let agents = project.path().join("AGENTS.md");
+    std::fs::write(&agents, "hand written\n")?;
+
+    codex_command(codex_home.path(), project.path())?
+        .args(["init", "--minimal"])
+        .assert()
+        .success()
+        .stdout(contains("already exists"));
+    assert_eq!(std::fs::read_to_string(&agents)?, "hand written\n");
+
+    codex_command(codex_home.path(), project.path())?
+        .args(["init", "--minimal", "--force"])
+        .assert()
+        .success();
+    assert!(std::fs::read_to_string(&agents)?.starts_with("# Agent instructions"));
+    Ok(())
+}
+
+#[test]
+fn init_trust_marks_the_project_trusted() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let project = TempDir::new()?;
+
+    codex_command(codex_home.path(), project.path())?
+        .args(["init", "--minimal", "--trust"])
+        .assert()
+        .success()
+        .stdout(contains("Trusted"));
+
+    let config: toml::Value = toml::from_str(&std::fs::read_to_string(
+        codex_home.path().join("config.toml"),
+    )?)?;
+    let project_key = project.path().canonicalize()?.to_string_lossy().to_string();
+    assert_eq!(
+        config["projects"][project_key.as_str()]["trust_level"].as_str(),
+        Some("trusted")
+    );
+    Ok(())
+}
+
+#[test]
+fn interactive_init_reads_answers_from_stdin() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let project = TempDir::new()?;
+    std::fs::write(project.path().join("requirements.txt"), "")?;
+
+    // Commands: yes, scopes example: no, trust: yes.
+    codex_command(codex_home.path(), project.path())?
+        .arg("init")
+        .write_stdin("y\nn\ny\n")
+        .assert()
+        .success()
+        .stdout(contains("Detected a Python project."));
+
+    let agents = std::fs::read_to_string(project.path().join("AGENTS.md"))?;
+    assert!(!agents.contains("```scopes"), "{agents}");
+    assert!(codex_home.path().join("config.toml").exists());
+    Ok(())
+}
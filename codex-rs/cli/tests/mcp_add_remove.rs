@@ -226,3 +226,64 @@ async fn add_cant_add_command_and_url() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn add_and_remove_follow_the_active_profile() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let config_path = codex_home.path().join("config.toml");
+    std::fs::write(
+        &config_path,
+        r#"
+[mcp_servers.docs]
+command = "docs-server"
+
+[profiles.work.mcp_servers.tracker]
+command = "tracker-server"
+
+[profiles.focused]
+mcp_servers_mode = "replace"
+"#,
+    )?;
+    let profile_servers = |profile: &str| -> Result<Vec<String>> {
+        let config: toml::Value = toml::from_str(&std::fs::read_to_string(&config_path)?)?;
+        let mut names: Vec<String> = config["profiles"][profile]
+            .get("mcp_servers")
+            .and_then(toml::Value::as_table)
+            .map(|servers| servers.keys().cloned().collect())
+            .unwrap_or_default();
+        names.sort_unstable();
+        Ok(names)
+    };
+
+    let mut remove_cmd = codex_command(codex_home.path())?;
+    remove_cmd
+        .args(["mcp", "remove", "tracker", "-c", "profile=work"])
+        .assert()
+        .success()
+        .stdout(contains(
+            "Removed MCP server 'tracker' from profile 'work'.",
+        ));
+    assert_eq!(profile_servers("work")?, Vec::<String>::new());
+
+    // A profile that replaces the top-level list gets the new server itself.
+    let mut add_cmd = codex_command(codex_home.path())?;
+    add_cmd
+        .args([
+            "mcp",
+            "add",
+            "search",
+            "-c",
+            "profile=focused",
+            "--",
+            "search-server",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("Added MCP server 'search' to profile 'focused'."));
+    assert_eq!(profile_servers("focused")?, vec!["search".to_string()]);
+
+    let servers = load_global_mcp_servers(codex_home.path()).await?;
+    assert_eq!(servers.keys().collect::<Vec<_>>(), vec!["docs"]);
+
+    Ok(())
+}
@@ -107,7 +107,8 @@ async fn list_and_get_render_expected_output() -> Result<()> {
             },
             "startup_timeout_sec": null,
             "tool_timeout_sec": null,
-            "auth_status": "unsupported"
+            "auth_status": "unsupported",
+            "profile": null
           }
         ]
         )
@@ -163,3 +164,47 @@ async fn get_disabled_server_shows_single_line() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn list_shows_profile_that_contributed_each_server() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    std::fs::write(
+        codex_home.path().join("config.toml"),
+        r#"
+[mcp_servers.docs]
+command = "docs-server"
+
+[profiles.work.mcp_servers.tracker]
+command = "tracker-server"
+"#,
+    )?;
+
+    let mut list_cmd = codex_command(codex_home.path())?;
+    let output = list_cmd
+        .args(["mcp", "list", "--json", "-c", "profile=work"])
+        .output()?;
+    assert!(output.status.success());
+    let parsed: JsonValue = serde_json::from_slice(&output.stdout)?;
+    let profiles: Vec<(JsonValue, JsonValue)> = parsed
+        .as_array()
+        .expect("list output should be an array")
+        .iter()
+        .map(|entry| (entry["name"].clone(), entry["profile"].clone()))
+        .collect();
+    assert_eq!(
+        profiles,
+        vec![
+            (json!("docs"), JsonValue::Null),
+            (json!("tracker"), json!("work")),
+        ]
+    );
+
+    let mut get_cmd = codex_command(codex_home.path())?;
+    get_cmd
+        .args(["mcp", "get", "tracker", "-c", "profile=work"])
+        .assert()
+        .success()
+        .stdout(contains("profile: work"));
+
+    Ok(())
+}
@@ -25,6 +25,7 @@ pub struct TaskId(pub String);
 #[serde(rename_all = "kebab-case")]
 pub enum TaskStatus {
     Pending,
+    Running,
     Ready,
     Applied,
     Error,
@@ -642,7 +642,7 @@ mod api {
                 return match s {
                     "failed" => TaskStatus::Error,
                     "completed" => TaskStatus::Ready,
-                    "in_progress" => TaskStatus::Pending,
+                    "in_progress" => TaskStatus::Running,
                     "pending" => TaskStatus::Pending,
                     "cancelled" => TaskStatus::Error,
                     _ => TaskStatus::Pending,
@@ -37,8 +37,99 @@ pub struct ApplyModalState {
     pub skipped_paths: Vec<String>,
     pub conflict_paths: Vec<String>,
     pub diff_override: Option<String>,
+    /// Files of `diff_override`, in diff order, or why they could not be read.
+    pub files: Result<Vec<String>, PatchError>,
+    /// Files the user left out of the apply.
+    pub excluded: Vec<String>,
+    /// Index into `files` of the highlighted file.
+    pub file_cursor: usize,
 }
 
+impl ApplyModalState {
+    pub fn new(task_id: TaskId, title: String, diff_override: Option<String>) -> Self {
+        let files = diff_override
+            .as_deref()
+            .map_or(Ok(Vec::new()), patch_select::patch_paths);
+        Self {
+            task_id,
+            title,
+            result_message: None,
+            result_level: None,
+            skipped_paths: Vec::new(),
+            conflict_paths: Vec::new(),
+            diff_override,
+            files,
+            excluded: Vec::new(),
+            file_cursor: 0,
+        }
+    }
+
+    /// Forget the last preflight or apply result, keeping the file selection.
+    pub fn clear_result(&mut self) {
+        self.result_message = None;
+        self.result_level = None;
+        self.skipped_paths.clear();
+        self.conflict_paths.clear();
+    }
+
+    pub fn move_file_cursor(&mut self, delta: isize) {
+        let len = self.files.as_ref().map_or(0, Vec::len);
+        if len > 0 {
+            self.file_cursor = self.file_cursor.saturating_add_signed(delta).min(len - 1);
+        }
+    }
+
+    /// Include or leave out the highlighted file.
+    pub fn toggle_file(&mut self) {
+        let Some(path) = self
+            .files
+            .as_ref()
+            .ok()
+            .and_then(|files| files.get(self.file_cursor))
+        else {
+            return;
+        };
+        if let Some(at) = self.excluded.iter().position(|p| p == path) {
+            self.excluded.remove(at);
+        } else {
+            self.excluded.push(path.clone());
+        }
+    }
+
+    pub fn is_excluded(&self, path: &str) -> bool {
+        self.excluded.iter().any(|p| p == path)
+    }
+
+    /// Selected files that are not in `also_exclude`.
+    pub fn selected_files(&self, also_exclude: &[String]) -> Vec<String> {
+        self.files
+            .as_ref()
+            .map(|files| {
+                files
+                    .iter()
+                    .filter(|path| !self.is_excluded(path) && !also_exclude.contains(path))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The diff to send for the selected files minus `also_exclude`; the whole
+    /// diff when nothing is left out.
+    pub fn selected_diff(&self, also_exclude: &[String]) -> Result<Option<String>, PatchError> {
+        let Some(diff) = self.diff_override.as_deref() else {
+            return Ok(None);
+        };
+        if self.excluded.is_empty() && also_exclude.is_empty() {
+            return Ok(Some(diff.to_string()));
+        }
+        let leave_out: Vec<String> = self.excluded.iter().chain(also_exclude).cloned().collect();
+        patch_select::exclude_files(diff, &leave_out).map(Some)
+    }
+}
+
+use crate::patch_select;
+use crate::patch_select::PatchError;
 use crate::scrollable_diff::ScrollableDiff;
 use codex_cloud_tasks_client::CloudBackend;
 use codex_cloud_tasks_client::TaskId;
@@ -478,4 +569,45 @@ mod tests {
         assert_eq!(b.len(), 3);
         assert_eq!(b[2].title, "B-3");
     }
+
+    #[test]
+    fn apply_modal_sends_only_the_picked_files() {
+        let diff = "\
+diff --git a/a.txt b/a.txt
+@@ -1 +1 @@
+-a
++A
+diff --git a/b.txt b/b.txt
+@@ -1 +1 @@
+-b
++B
+diff --git a/c.txt b/c.txt
+@@ -1 +1 @@
+-c
++C
+";
+        let mut modal = ApplyModalState::new(
+            TaskId("T-1".to_string()),
+            "task".to_string(),
+            Some(diff.to_string()),
+        );
+        assert_eq!(modal.selected_diff(&[]), Ok(Some(diff.to_string())));
+
+        // Leave out b.txt; c.txt is then dropped as a preflight problem.
+        modal.move_file_cursor(1);
+        modal.toggle_file();
+        assert_eq!(modal.selected_files(&[]), vec!["a.txt", "c.txt"]);
+        assert_eq!(
+            modal.selected_diff(&["c.txt".to_string()]),
+            Ok(Some(
+                "diff --git a/a.txt b/a.txt\n@@ -1 +1 @@\n-a\n+A\n".to_string()
+            ))
+        );
+
+        // Toggling again puts it back; the cursor stops at the last file.
+        modal.toggle_file();
+        modal.move_file_cursor(5);
+        assert_eq!(modal.file_cursor, 2);
+        assert_eq!(modal.selected_diff(&[]), Ok(Some(diff.to_string())));
+    }
 }
@@ -8,6 +8,10 @@ pub struct Cli {
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 
+    /// Refuse to contact Codex Cloud, as with `network.offline = true`.
+    #[arg(long = "offline", default_value_t = false)]
+    pub offline: bool,
+
     #[command(subcommand)]
     pub command: Option<Command>,
 }
@@ -16,6 +20,8 @@ pub struct Cli {
 pub enum Command {
     /// Submit a new Codex Cloud task without launching the TUI.
     Exec(ExecCommand),
+    /// Preview a task's diff and apply all or some of its files locally.
+    Apply(ApplyCommand),
 }
 
 #[derive(Debug, Args)]
@@ -37,6 +43,25 @@ pub struct ExecCommand {
     pub attempts: usize,
 }
 
+#[derive(Debug, Args)]
+pub struct ApplyCommand {
+    /// Identifier of the Codex Cloud task to apply.
+    #[arg(value_name = "TASK_ID")]
+    pub task_id: String,
+
+    /// Only apply changes to this file. Repeat to select several files.
+    #[arg(long = "file", value_name = "PATH")]
+    pub files: Vec<String>,
+
+    /// Print the diff and the preflight report without touching the working tree.
+    #[arg(long = "preview", default_value_t = false)]
+    pub preview: bool,
+
+    /// When some files conflict, apply the files that apply cleanly instead of nothing.
+    #[arg(long = "partial", default_value_t = false)]
+    pub partial: bool,
+}
+
 fn parse_attempts(input: &str) -> Result<usize, String> {
     let value: usize = input
         .parse()
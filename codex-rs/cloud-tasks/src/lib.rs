@@ -2,6 +2,7 @@ mod app;
 mod cli;
 pub mod env_detect;
 mod new_task;
+pub mod patch_select;
 pub mod scrollable_diff;
 mod ui;
 pub mod util;
@@ -11,6 +12,7 @@ use anyhow::anyhow;
 use codex_login::AuthManager;
 use std::io::IsTerminal;
 use std::io::Read;
+use std::io::Write;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
@@ -120,6 +122,100 @@ async fn run_exec_command(args: crate::cli::ExecCommand) -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn run_apply_command(args: crate::cli::ApplyCommand) -> anyhow::Result<()> {
+    use codex_cloud_tasks_client::ApplyStatus;
+    use codex_cloud_tasks_client::CloudBackend;
+    use patch_select::FileReport;
+    use patch_select::FileState;
+
+    let crate::cli::ApplyCommand {
+        task_id,
+        files,
+        preview,
+        partial,
+    } = args;
+    let ctx = init_backend("codex_cloud_tasks_apply").await?;
+    let id = codex_cloud_tasks_client::TaskId(task_id);
+    let diff = ctx
+        .backend
+        .get_task_diff(id.clone())
+        .await?
+        .ok_or_else(|| anyhow!("task {} has no diff to apply", id.0))?;
+    let all_paths = patch_select::patch_paths(&diff)?;
+    let selected = if files.is_empty() {
+        diff.clone()
+    } else {
+        patch_select::select_files(&diff, &files).map_err(|err| match err {
+            patch_select::PatchError::UnknownPaths(unknown) => {
+                anyhow!("task {} does not change: {}", id.0, unknown.join(", "))
+            }
+            err => err.into(),
+        })?
+    };
+    let selected_paths = patch_select::patch_paths(&selected)?;
+
+    if preview {
+        let mut stdout = std::io::stdout();
+        if stdout.is_terminal() {
+            let changes = patch_select::file_changes(&selected)?;
+            let cwd = std::env::current_dir()?;
+            let width = crossterm::terminal::size().map_or(80, |(cols, _)| cols);
+            let lines = codex_tui::render_file_changes(&changes, &cwd, usize::from(width));
+            codex_tui::insert_history::write_lines_ansi(&mut stdout, &lines)?;
+            stdout.flush()?;
+        } else {
+            print!("{selected}");
+        }
+        println!();
+    }
+
+    // Dry run first so a conflict never leaves the tree half-applied.
+    let preflight = ctx
+        .backend
+        .apply_task_preflight(id.clone(), Some(selected.clone()))
+        .await?;
+    let report = FileReport::new(&all_paths, &selected_paths, &preflight, FileState::Clean);
+    let problems = report.problem_paths(&selected_paths);
+    if preview || !matches!(preflight.status, ApplyStatus::Success) {
+        println!("{}", preflight.message);
+        print!("{}", report.render());
+    }
+    if preview {
+        return Ok(());
+    }
+
+    let to_apply = match preflight.status {
+        ApplyStatus::Success => selected,
+        ApplyStatus::Partial | ApplyStatus::Error if partial => {
+            let clean = patch_select::exclude_files(&selected, &problems)?;
+            if clean.is_empty() {
+                return Err(anyhow!("no file of task {} applies cleanly", id.0));
+            }
+            clean
+        }
+        ApplyStatus::Partial | ApplyStatus::Error => {
+            return Err(anyhow!(
+                "{} of {} files do not apply cleanly; nothing was changed. Re-run with --partial to apply the rest.",
+                problems.len(),
+                selected_paths.len()
+            ));
+        }
+    };
+    let attempted = patch_select::patch_paths(&to_apply)?;
+    let mut outcome = ctx.backend.apply_task(id, Some(to_apply)).await?;
+    if matches!(outcome.status, ApplyStatus::Error) {
+        return Err(anyhow!("{}", outcome.message));
+    }
+    // Files held back by --partial still count as conflicted in the summary.
+    outcome.conflict_paths.extend(preflight.conflict_paths);
+    println!("{}", outcome.message);
+    print!(
+        "{}",
+        FileReport::new(&all_paths, &attempted, &outcome, FileState::Applied).render()
+    );
+    Ok(())
+}
+
 async fn resolve_environment_id(ctx: &BackendContext, requested: &str) -> anyhow::Result<String> {
     let trimmed = requested.trim();
     if trimmed.is_empty() {
@@ -318,9 +414,14 @@ fn spawn_apply(
 
 /// Entry point for the `codex cloud` subcommand.
 pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()> {
+    if let Some(blocked) = util::cloud_tasks_blocked(&cli.config_overrides, cli.offline).await {
+        eprintln!("Codex Cloud is unavailable: {blocked}.");
+        return Ok(());
+    }
     if let Some(command) = cli.command {
         return match command {
             crate::cli::Command::Exec(args) => run_exec_command(args).await,
+            crate::cli::Command::Apply(args) => run_apply_command(args).await,
         };
     }
     let Cli { .. } = cli;
@@ -1123,41 +1224,111 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                         }
                         // If a diff overlay is open, handle its keys first.
                         if app.apply_modal.is_some() {
-                            // Simple apply confirmation modal: y apply, p preflight, n/Esc cancel
+                            // Apply confirmation modal: ↑/↓ pick a file, space toggles it, y apply the
+                            // selected files, p preflight them, c apply the clean ones, n/Esc cancel
                             match key.code {
+                                KeyCode::Up | KeyCode::Char('k') => {
+                                    if let Some(m) = app.apply_modal.as_mut() {
+                                        m.move_file_cursor(-1);
+                                        needs_redraw = true;
+                                    }
+                                }
+                                KeyCode::Down | KeyCode::Char('j') => {
+                                    if let Some(m) = app.apply_modal.as_mut() {
+                                        m.move_file_cursor(1);
+                                        needs_redraw = true;
+                                    }
+                                }
+                                KeyCode::Char(' ') => {
+                                    if let Some(m) = app.apply_modal.as_mut() {
+                                        m.toggle_file();
+                                        needs_redraw = true;
+                                    }
+                                }
                                 KeyCode::Char('y') => {
                                     if let Some(m) = app.apply_modal.as_ref() {
                                         let title = m.title.clone();
-                                        let job = ApplyJob {
-                                            task_id: m.task_id.clone(),
-                                            diff_override: m.diff_override.clone(),
-                                        };
-                                        if spawn_apply(&mut app, &backend, &tx, &frame_tx, job) {
-                                            app.status = format!("Applying '{title}'...");
+                                        let task_id = m.task_id.clone();
+                                        let files = m.selected_files(&[]).len();
+                                        match m.selected_diff(&[]) {
+                                            Err(err) => {
+                                                app.status = format!("Cannot select files: {err}");
+                                            }
+                                            Ok(_) if files == 0 && !m.excluded.is_empty() => {
+                                                app.status = "No file selected; press Space to select one.".to_string();
+                                            }
+                                            Ok(diff_override) => {
+                                                let partial = !m.excluded.is_empty();
+                                                let job = ApplyJob {
+                                                    task_id,
+                                                    diff_override,
+                                                };
+                                                if spawn_apply(&mut app, &backend, &tx, &frame_tx, job) {
+                                                    app.status = if partial {
+                                                        format!("Applying {files} selected file(s) of '{title}'...")
+                                                    } else {
+                                                        format!("Applying '{title}'...")
+                                                    };
+                                                }
+                                            }
                                         }
                                         needs_redraw = true;
                                     }
                                 }
                                 KeyCode::Char('p') => {
-                                    if let Some(m) = app.apply_modal.take() {
+                                    if let Some(m) = app.apply_modal.as_ref() {
                                         let title = m.title.clone();
-                                        let job = ApplyJob {
-                                            task_id: m.task_id.clone(),
-                                            diff_override: m.diff_override.clone(),
-                                        };
-                                        if spawn_preflight(&mut app, &backend, &tx, &frame_tx, title.clone(), job) {
-                                            app.apply_modal = Some(app::ApplyModalState {
-                                                task_id: m.task_id,
-                                                title: title.clone(),
-                                                result_message: None,
-                                                result_level: None,
-                                                skipped_paths: Vec::new(),
-                                                conflict_paths: Vec::new(),
-                                                diff_override: m.diff_override,
-                                            });
-                                            app.status = format!("Preflighting '{title}'...");
+                                        let task_id = m.task_id.clone();
+                                        match m.selected_diff(&[]) {
+                                            Err(err) => {
+                                                app.status = format!("Cannot select files: {err}");
+                                            }
+                                            Ok(diff_override) => {
+                                                let job = ApplyJob {
+                                                    task_id,
+                                                    diff_override,
+                                                };
+                                                if spawn_preflight(&mut app, &backend, &tx, &frame_tx, title.clone(), job) {
+                                                    if let Some(m) = app.apply_modal.as_mut() {
+                                                        m.clear_result();
+                                                    }
+                                                    app.status = format!("Preflighting '{title}'...");
+                                                }
+                                            }
+                                        }
+                                        needs_redraw = true;
+                                    }
+                                }
+                                KeyCode::Char('c') => {
+                                    if let Some(m) = app.apply_modal.as_ref() {
+                                        let problems: Vec<String> = m
+                                            .conflict_paths
+                                            .iter()
+                                            .chain(&m.skipped_paths)
+                                            .cloned()
+                                            .collect();
+                                        if problems.is_empty() || m.diff_override.is_none() {
+                                            app.status = "Nothing to leave out; press Y to apply the selected files.".to_string();
                                         } else {
-                                            app.apply_modal = Some(m);
+                                            let files = m.selected_files(&problems).len();
+                                            match m.selected_diff(&problems) {
+                                                Err(err) => {
+                                                    app.status = format!("Cannot select files: {err}");
+                                                }
+                                                Ok(_) if files == 0 => {
+                                                    app.status = "No selected file applies cleanly.".to_string();
+                                                }
+                                                Ok(clean) => {
+                                                    let title = m.title.clone();
+                                                    let job = ApplyJob {
+                                                        task_id: m.task_id.clone(),
+                                                        diff_override: clean,
+                                                    };
+                                                    if spawn_apply(&mut app, &backend, &tx, &frame_tx, job) {
+                                                        app.status = format!("Applying {files} clean file(s) of '{title}'...");
+                                                    }
+                                                }
+                                            }
                                         }
                                         needs_redraw = true;
                                     }
@@ -1203,15 +1374,11 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                                 diff_override: diff_override.clone(),
                                             };
                                             if spawn_preflight(&mut app, &backend, &tx, &frame_tx, title.clone(), job) {
-                                                app.apply_modal = Some(app::ApplyModalState {
+                                                app.apply_modal = Some(app::ApplyModalState::new(
                                                     task_id,
-                                                    title: title.clone(),
-                                                    result_message: None,
-                                                    result_level: None,
-                                                    skipped_paths: Vec::new(),
-                                                    conflict_paths: Vec::new(),
+                                                    title.clone(),
                                                     diff_override,
-                                                });
+                                                ));
                                                 app.status = format!("Preflighting '{title}'...");
                                             }
                                         } else {
@@ -1548,15 +1715,11 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                                     title.clone(),
                                                     job,
                                                 ) {
-                                                    app.apply_modal = Some(app::ApplyModalState {
+                                                    app.apply_modal = Some(app::ApplyModalState::new(
                                                         task_id,
-                                                        title: title.clone(),
-                                                        result_message: None,
-                                                        result_level: None,
-                                                        skipped_paths: Vec::new(),
-                                                        conflict_paths: Vec::new(),
+                                                        title.clone(),
                                                         diff_override,
-                                                    });
+                                                    ));
                                                     app.status = format!("Preflighting '{title}'...");
                                                 }
                                             }
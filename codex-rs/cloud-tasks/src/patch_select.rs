@@ -0,0 +1,542 @@
+//! Per-file views of a task's unified diff: splitting it into file sections,
+//! narrowing it to a chosen subset, and summarizing what happened to each
+//! file when the subset was applied.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use codex_cloud_tasks_client::ApplyOutcome;
+use codex_core::protocol::FileChange;
+
+/// One `diff --git` section of a unified diff.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FilePatch<'a> {
+    /// Path on the `a/` side; differs from `path` only for renames.
+    pub old_path: String,
+    /// Path on the `b/` side.
+    pub path: String,
+    pub text: &'a str,
+}
+
+impl FilePatch<'_> {
+    fn touches(&self, path: &str) -> bool {
+        self.path == path || self.old_path == path
+    }
+
+    /// This section as the change the TUI's diff renderer draws.
+    fn change(&self) -> FileChange {
+        let (header, hunks) = match self.text.find("\n@@") {
+            Some(at) => self.text.split_at(at + 1),
+            None => (self.text, ""),
+        };
+        let content = |marker: char| -> String {
+            hunks
+                .lines()
+                .filter_map(|line| line.strip_prefix(marker))
+                .map(|line| format!("{line}\n"))
+                .collect()
+        };
+        if header.lines().any(|line| line.starts_with("new file mode")) {
+            FileChange::Add {
+                content: content('+'),
+            }
+        } else if header
+            .lines()
+            .any(|line| line.starts_with("deleted file mode"))
+        {
+            FileChange::Delete {
+                content: content('-'),
+            }
+        } else {
+            FileChange::Update {
+                unified_diff: hunks.to_string(),
+                move_path: (self.old_path != self.path).then(|| PathBuf::from(&self.path)),
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PatchError {
+    /// A `diff --git` header whose paths could not be read.
+    UnreadableHeader(String),
+    /// Requested paths the diff does not touch.
+    UnknownPaths(Vec<String>),
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::UnreadableHeader(header) => {
+                write!(f, "cannot read the file paths of `{header}`")
+            }
+            PatchError::UnknownPaths(paths) => {
+                write!(f, "the diff does not change: {}", paths.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// Split `diff` into its per-file sections. Text before the first
+/// `diff --git` header is dropped; a header whose paths cannot be read is an
+/// error rather than a silently missing file.
+pub fn split_patch(diff: &str) -> Result<Vec<FilePatch<'_>>, PatchError> {
+    let mut starts: Vec<usize> = Vec::new();
+    let mut offset = 0;
+    for line in diff.split_inclusive('\n') {
+        if line.starts_with("diff --git ") {
+            starts.push(offset);
+        }
+        offset += line.len();
+    }
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = starts.get(i + 1).copied().unwrap_or(diff.len());
+            let text = &diff[start..end];
+            let header = text.lines().next().unwrap_or_default();
+            let (old_path, path) = header
+                .strip_prefix("diff --git ")
+                .and_then(split_header_paths)
+                .ok_or_else(|| PatchError::UnreadableHeader(header.to_string()))?;
+            Ok(FilePatch {
+                old_path,
+                path,
+                text,
+            })
+        })
+        .collect()
+}
+
+/// The `a/` and `b/` paths of a `diff --git` header, either of which git may
+/// have C-quoted (`"a/caf\303\251.txt"`).
+fn split_header_paths(paths: &str) -> Option<(String, String)> {
+    let (old_path, rest) = if paths.starts_with('"') {
+        let (old_path, rest) = unquote(paths)?;
+        (
+            old_path.strip_prefix("a/")?.to_string(),
+            rest.strip_prefix(' ')?,
+        )
+    } else {
+        let paths = paths.strip_prefix("a/")?;
+        // Unquoted paths may themselves contain " b/", so prefer the split
+        // where both sides name the same file, as they do unless renamed.
+        let mid = paths.len().saturating_sub(3) / 2;
+        let at = match (paths.get(..mid), paths.get(mid..)) {
+            (Some(old), Some(new)) if new.strip_prefix(" b/") == Some(old) => mid,
+            _ => paths.find(" b/").or_else(|| paths.find(" \"b/"))?,
+        };
+        (paths[..at].to_string(), &paths[at + 1..])
+    };
+    let path = if rest.starts_with('"') {
+        let (path, trailing) = unquote(rest)?;
+        if !trailing.is_empty() {
+            return None;
+        }
+        path.strip_prefix("b/")?.to_string()
+    } else {
+        rest.strip_prefix("b/")?.to_string()
+    };
+    Some((old_path, path))
+}
+
+/// Read the C-quoted string `quoted` starts with, returning it unescaped
+/// together with the text after the closing quote.
+fn unquote(quoted: &str) -> Option<(String, &str)> {
+    let body = quoted.strip_prefix('"')?;
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut chars = body.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => {
+                let text = String::from_utf8(bytes).ok()?;
+                return Some((text, &body[i + 1..]));
+            }
+            '\\' => {
+                let (_, escaped) = chars.next()?;
+                let byte = match escaped {
+                    'a' => 0x07,
+                    'b' => 0x08,
+                    't' => b'\t',
+                    'n' => b'\n',
+                    'v' => 0x0b,
+                    'f' => 0x0c,
+                    'r' => b'\r',
+                    '"' => b'"',
+                    '\\' => b'\\',
+                    '0'..='7' => {
+                        let mut value = escaped.to_digit(8)?;
+                        for _ in 0..2 {
+                            let (_, digit) = chars.next()?;
+                            value = value * 8 + digit.to_digit(8)?;
+                        }
+                        u8::try_from(value).ok()?
+                    }
+                    _ => return None,
+                };
+                bytes.push(byte);
+            }
+            c => bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+    None
+}
+
+/// Paths touched by `diff`, in the order they appear.
+pub fn patch_paths(diff: &str) -> Result<Vec<String>, PatchError> {
+    Ok(split_patch(diff)?
+        .into_iter()
+        .map(|file| file.path)
+        .collect())
+}
+
+/// `diff` as per-file changes, keyed like the ones a Codex turn produces, so
+/// the TUI's diff renderer can draw it.
+pub fn file_changes(diff: &str) -> Result<HashMap<PathBuf, FileChange>, PatchError> {
+    Ok(split_patch(diff)?
+        .iter()
+        .map(|file| (PathBuf::from(&file.old_path), file.change()))
+        .collect())
+}
+
+/// Keep only the sections of `diff` touching one of `paths`. Fails with the
+/// requested paths that the diff does not touch.
+pub fn select_files(diff: &str, paths: &[String]) -> Result<String, PatchError> {
+    let files = split_patch(diff)?;
+    let unknown: Vec<String> = paths
+        .iter()
+        .filter(|path| !files.iter().any(|file| file.touches(path)))
+        .cloned()
+        .collect();
+    if !unknown.is_empty() {
+        return Err(PatchError::UnknownPaths(unknown));
+    }
+    Ok(files
+        .iter()
+        .filter(|file| paths.iter().any(|path| file.touches(path)))
+        .map(|file| file.text)
+        .collect())
+}
+
+/// Drop the sections of `diff` touching any of `paths`.
+pub fn exclude_files(diff: &str, paths: &[String]) -> Result<String, PatchError> {
+    Ok(split_patch(diff)?
+        .iter()
+        .filter(|file| !paths.iter().any(|path| file.touches(path)))
+        .map(|file| file.text)
+        .collect())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileState {
+    /// Preflight found no problems.
+    Clean,
+    Applied,
+    /// Left out, either by the user or by `git apply`.
+    Skipped,
+    Conflicted,
+}
+
+impl FileState {
+    fn label(self) -> &'static str {
+        match self {
+            FileState::Clean => "clean",
+            FileState::Applied => "applied",
+            FileState::Skipped => "skipped",
+            FileState::Conflicted => "conflicted",
+        }
+    }
+}
+
+/// What happened, or would happen, to each file of a task's diff.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileReport {
+    pub files: Vec<(String, FileState)>,
+    /// State of attempted files the outcome did not flag: `Clean` for a
+    /// preflight, `Applied` for a real apply.
+    pub success_state: FileState,
+}
+
+impl FileReport {
+    /// Classify every file of the task's diff after an apply (or preflight)
+    /// of the `attempted` subset. Files outside that subset count as skipped;
+    /// files in it take `success_state` unless the outcome names them.
+    pub fn new(
+        all_paths: &[String],
+        attempted: &[String],
+        outcome: &ApplyOutcome,
+        success_state: FileState,
+    ) -> Self {
+        let files = all_paths
+            .iter()
+            .map(|path| {
+                let state = if outcome.conflict_paths.contains(path) {
+                    FileState::Conflicted
+                } else if outcome.skipped_paths.contains(path) || !attempted.contains(path) {
+                    FileState::Skipped
+                } else {
+                    success_state
+                };
+                (path.clone(), state)
+            })
+            .collect();
+        Self {
+            files,
+            success_state,
+        }
+    }
+
+    /// Paths preflight flagged as conflicted or skipped, among `attempted`.
+    pub fn problem_paths(&self, attempted: &[String]) -> Vec<String> {
+        self.files
+            .iter()
+            .filter(|(path, state)| {
+                attempted.contains(path)
+                    && matches!(state, FileState::Conflicted | FileState::Skipped)
+            })
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+
+    pub fn count(&self, state: FileState) -> usize {
+        self.files.iter().filter(|(_, s)| *s == state).count()
+    }
+
+    /// One line per file followed by a totals line, e.g.
+    /// `2 applied, 1 skipped, 0 conflicted`.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for (path, state) in &self.files {
+            out.push_str(&format!("  {:<10}  {path}\n", state.label()));
+        }
+        out.push_str(&format!(
+            "{} {}, {} skipped, {} conflicted\n",
+            self.count(self.success_state),
+            self.success_state.label(),
+            self.count(FileState::Skipped),
+            self.count(FileState::Conflicted),
+        ));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_cloud_tasks_client::ApplyStatus;
+
+    const FIXTURE: &str = "\
+diff --git a/README.md b/README.md
+index 1111111..2222222 100644
+--- a/README.md
++++ b/README.md
+@@ -1 +1 @@
+-Hello
++Hello, world
+diff --git a/src/old.rs b/src/new.rs
+similarity index 90%
+rename from src/old.rs
+rename to src/new.rs
+diff --git a/src/lib.rs b/src/lib.rs
+index 3333333..4444444 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,2 +1,2 @@
+-pub fn a() {}
++pub fn b() {}
+";
+
+    fn paths(items: &[&str]) -> Vec<String> {
+        items.iter().map(ToString::to_string).collect()
+    }
+
+    fn outcome(skipped: &[&str], conflicts: &[&str]) -> ApplyOutcome {
+        ApplyOutcome {
+            applied: false,
+            status: ApplyStatus::Partial,
+            message: String::new(),
+            skipped_paths: paths(skipped),
+            conflict_paths: paths(conflicts),
+        }
+    }
+
+    #[test]
+    fn lists_paths_in_diff_order() {
+        assert_eq!(
+            patch_paths(FIXTURE),
+            Ok(paths(&["README.md", "src/new.rs", "src/lib.rs"]))
+        );
+    }
+
+    #[test]
+    fn reads_quoted_and_spaced_header_paths() {
+        let diff = "\
+diff --git \"a/caf\\303\\251 \\\"menu\\\".txt\" \"b/caf\\303\\251 \\\"menu\\\".txt\"
+diff --git a/notes b/todo.md b/notes b/todo.md
+diff --git a/old name.txt \"b/new\\tname.txt\"
+";
+        assert_eq!(
+            patch_paths(diff),
+            Ok(paths(&[
+                "caf\u{e9} \"menu\".txt",
+                "notes b/todo.md",
+                "new\tname.txt"
+            ]))
+        );
+    }
+
+    #[test]
+    fn unreadable_header_is_an_error() {
+        let diff = "diff --git a/ok.txt b/ok.txt\ndiff --git \"a/unterminated b/x\n";
+        assert_eq!(
+            patch_paths(diff),
+            Err(PatchError::UnreadableHeader(
+                "diff --git \"a/unterminated b/x".to_string()
+            ))
+        );
+        assert_eq!(
+            exclude_files(diff, &paths(&["ok.txt"])),
+            Err(PatchError::UnreadableHeader(
+                "diff --git \"a/unterminated b/x".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn converts_sections_to_file_changes() {
+        let diff = "\
+diff --git a/new.txt b/new.txt
+new file mode 100644
+index 0000000..1111111
+--- /dev/null
++++ b/new.txt
+@@ -0,0 +1,2 @@
++one
++two
+diff --git a/gone.txt b/gone.txt
+deleted file mode 100644
+index 1111111..0000000
+--- a/gone.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+";
+        let changes = file_changes(&format!("{FIXTURE}{diff}"));
+        let expected: HashMap<PathBuf, FileChange> = [
+            (
+                PathBuf::from("README.md"),
+                FileChange::Update {
+                    unified_diff: "@@ -1 +1 @@\n-Hello\n+Hello, world\n".to_string(),
+                    move_path: None,
+                },
+            ),
+            (
+                PathBuf::from("src/old.rs"),
+                FileChange::Update {
+                    unified_diff: String::new(),
+                    move_path: Some(PathBuf::from("src/new.rs")),
+                },
+            ),
+            (
+                PathBuf::from("src/lib.rs"),
+                FileChange::Update {
+                    unified_diff: "@@ -1,2 +1,2 @@\n-pub fn a() {}\n+pub fn b() {}\n".to_string(),
+                    move_path: None,
+                },
+            ),
+            (
+                PathBuf::from("new.txt"),
+                FileChange::Add {
+                    content: "one\ntwo\n".to_string(),
+                },
+            ),
+            (
+                PathBuf::from("gone.txt"),
+                FileChange::Delete {
+                    content: "bye\n".to_string(),
+                },
+            ),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(changes, Ok(expected));
+    }
+
+    #[test]
+    fn select_keeps_only_requested_sections() {
+        let selected = select_files(FIXTURE, &paths(&["src/lib.rs", "src/old.rs"]));
+        assert_eq!(
+            selected,
+            Ok("\
+diff --git a/src/old.rs b/src/new.rs
+similarity index 90%
+rename from src/old.rs
+rename to src/new.rs
+diff --git a/src/lib.rs b/src/lib.rs
+index 3333333..4444444 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,2 +1,2 @@
+-pub fn a() {}
++pub fn b() {}
+"
+            .to_string())
+        );
+    }
+
+    #[test]
+    fn select_reports_paths_missing_from_the_diff() {
+        assert_eq!(
+            select_files(FIXTURE, &paths(&["README.md", "docs/nope.md"])),
+            Err(PatchError::UnknownPaths(paths(&["docs/nope.md"])))
+        );
+    }
+
+    #[test]
+    fn exclude_drops_requested_sections() {
+        assert_eq!(
+            exclude_files(FIXTURE, &paths(&["src/new.rs", "README.md"]))
+                .and_then(|diff| patch_paths(&diff)),
+            Ok(paths(&["src/lib.rs"]))
+        );
+    }
+
+    #[test]
+    fn preflight_report_lists_each_file() {
+        let all = patch_paths(FIXTURE).unwrap_or_default();
+        let attempted = paths(&["README.md", "src/lib.rs"]);
+        let report = FileReport::new(
+            &all,
+            &attempted,
+            &outcome(&[], &["src/lib.rs"]),
+            FileState::Clean,
+        );
+
+        assert_eq!(report.problem_paths(&attempted), paths(&["src/lib.rs"]));
+        assert_eq!(
+            report.render(),
+            "  clean       README.md\n  skipped     src/new.rs\n  conflicted  src/lib.rs\n1 clean, 1 skipped, 1 conflicted\n"
+        );
+    }
+
+    #[test]
+    fn apply_report_counts_applied_files() {
+        let all = patch_paths(FIXTURE).unwrap_or_default();
+        let report = FileReport::new(
+            &all,
+            &all,
+            &outcome(&["src/new.rs"], &[]),
+            FileState::Applied,
+        );
+
+        assert_eq!(
+            report.render(),
+            "  applied     README.md\n  skipped     src/new.rs\n  applied     src/lib.rs\n2 applied, 1 skipped, 0 conflicted\n"
+        );
+    }
+}
@@ -483,9 +483,13 @@ pub fn draw_apply_modal(frame: &mut Frame, area: Rect, app: &mut App) {
         ))
         .wrap(Wrap { trim: true });
         // Footer instructions
-        let footer =
-            Paragraph::new(Line::from("Press Y to apply, P to preflight, N to cancel.").dim())
-                .wrap(Wrap { trim: true });
+        let has_problems = !m.conflict_paths.is_empty() || !m.skipped_paths.is_empty();
+        let footer_text = if has_problems {
+            "Space to pick files, Y to apply them, C to apply only clean ones, P to preflight, N to cancel."
+        } else {
+            "Space to pick files, Y to apply them, P to preflight, N to cancel."
+        };
+        let footer = Paragraph::new(Line::from(footer_text).dim()).wrap(Wrap { trim: true });
 
         // Split into header/body/footer
         let rows = Layout::default()
@@ -543,6 +547,31 @@ pub fn draw_apply_modal(frame: &mut Frame, area: Rect, app: &mut App) {
                     }
                 }
             }
+            match &m.files {
+                Ok(files) if !files.is_empty() => {
+                    let selected = m.selected_files(&[]).len();
+                    body_lines.push(Line::from(""));
+                    body_lines.push(
+                        Line::from(format!("Files ({selected} of {} selected):", files.len()))
+                            .bold(),
+                    );
+                    for (i, path) in files.iter().enumerate() {
+                        let cursor = if i == m.file_cursor { "› " } else { "  " };
+                        let mark = if m.is_excluded(path) { "[ ] " } else { "[x] " };
+                        let line = Line::from(format!("{cursor}{mark}{path}"));
+                        body_lines.push(if i == m.file_cursor {
+                            line.cyan()
+                        } else {
+                            line
+                        });
+                    }
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    body_lines.push(Line::from(""));
+                    body_lines.push(Line::from(format!("Cannot pick files: {err}")).red());
+                }
+            }
             let body = Paragraph::new(body_lines).wrap(Wrap { trim: true });
             frame.render_widget(body, rows[1]);
         }
@@ -787,6 +816,7 @@ fn render_task_item(_app: &App, t: &codex_cloud_tasks_client::TaskSummary) -> Li
     let status = match t.status {
         TaskStatus::Ready => "READY".green(),
         TaskStatus::Pending => "PENDING".magenta(),
+        TaskStatus::Running => "RUNNING".cyan(),
         TaskStatus::Applied => "APPLIED".blue(),
         TaskStatus::Error => "ERROR".red(),
     };
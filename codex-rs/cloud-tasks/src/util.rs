@@ -2,8 +2,12 @@ use base64::Engine as _;
 use chrono::Utc;
 use reqwest::header::HeaderMap;
 
+use codex_common::CliConfigOverrides;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
+use codex_core::network_policy::NetworkCapability;
+use codex_core::network_policy::NetworkPolicy;
+use codex_core::network_policy::OfflineBlocked;
 use codex_login::AuthManager;
 
 pub fn set_user_agent_suffix(suffix: &str) {
@@ -70,6 +74,27 @@ pub async fn load_auth_manager() -> Option<AuthManager> {
     ))
 }
 
+/// Returns the block reason when `--offline` or `network.offline` (including
+/// `-c network.offline=true`) forbids Codex Cloud access.
+pub async fn cloud_tasks_blocked(
+    config_overrides: &CliConfigOverrides,
+    offline: bool,
+) -> Option<OfflineBlocked> {
+    if offline {
+        return NetworkPolicy::new(true)
+            .check(NetworkCapability::CloudTasks)
+            .err();
+    }
+    let cli_overrides = config_overrides.parse_overrides().ok()?;
+    let config = Config::load_with_cli_overrides(cli_overrides, ConfigOverrides::default())
+        .await
+        .ok()?;
+    config
+        .network_policy()
+        .check(NetworkCapability::CloudTasks)
+        .err()
+}
+
 /// Build headers for ChatGPT-backed requests: `User-Agent`, optional `Authorization`,
 /// and optional `ChatGPT-Account-Id`.
 pub async fn build_chatgpt_headers() -> HeaderMap {
@@ -120,3 +145,18 @@ pub fn task_url(base_url: &str, task_id: &str) -> String {
     }
     format!("{normalized}/codex/tasks/{task_id}")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn offline_flag_blocks_cloud_tasks() {
+        assert_eq!(
+            cloud_tasks_blocked(&CliConfigOverrides::default(), true).await,
+            Some(OfflineBlocked {
+                capability: NetworkCapability::CloudTasks,
+            })
+        );
+    }
+}
@@ -12,6 +12,12 @@ pub fn create_config_summary_entries(config: &Config) -> Vec<(&'static str, Stri
         ("approval", config.approval_policy.to_string()),
         ("sandbox", summarize_sandbox_policy(&config.sandbox_policy)),
     ];
+    if config.persistence.is_ephemeral() {
+        entries.push((
+            "persistence",
+            "ephemeral (nothing is saved to CODEX_HOME)".to_string(),
+        ));
+    }
     if config.model_provider.wire_api == WireApi::Responses
         && config.model_family.supports_reasoning_summaries
     {
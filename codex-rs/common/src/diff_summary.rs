@@ -0,0 +1,157 @@
+//! Per-file line counts for a set of [`FileChange`]s, shared by the TUI diff
+//! view and `codex exec` so both report the same `+N -M` figures.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use codex_protocol::protocol::FileChange;
+use diffy::Hunk;
+
+/// What a [`FileChange`] does to its file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileChangeKind {
+    Add,
+    Modify,
+    Delete,
+    Rename,
+}
+
+impl FileChangeKind {
+    pub fn of(change: &FileChange) -> Self {
+        match change {
+            FileChange::Add { .. } => FileChangeKind::Add,
+            FileChange::Delete { .. } => FileChangeKind::Delete,
+            FileChange::Update {
+                move_path: Some(_), ..
+            } => FileChangeKind::Rename,
+            FileChange::Update {
+                move_path: None, ..
+            } => FileChangeKind::Modify,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FileChangeKind::Add => "add",
+            FileChangeKind::Modify => "modify",
+            FileChangeKind::Delete => "delete",
+            FileChangeKind::Rename => "rename",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChangeSummary {
+    pub path: PathBuf,
+    /// Destination of a rename.
+    pub move_path: Option<PathBuf>,
+    pub kind: FileChangeKind,
+    pub added: usize,
+    pub removed: usize,
+}
+
+impl FileChangeSummary {
+    pub fn new(path: PathBuf, change: &FileChange) -> Self {
+        let (added, removed) = match change {
+            FileChange::Add { content } => (content.lines().count(), 0),
+            FileChange::Delete { content } => (0, content.lines().count()),
+            FileChange::Update { unified_diff, .. } => count_diff_lines(unified_diff),
+        };
+        let move_path = match change {
+            FileChange::Update { move_path, .. } => move_path.clone(),
+            _ => None,
+        };
+        Self {
+            path,
+            move_path,
+            kind: FileChangeKind::of(change),
+            added,
+            removed,
+        }
+    }
+}
+
+/// Summaries for every file in `changes`, sorted by path.
+pub fn summarize_file_changes(changes: &HashMap<PathBuf, FileChange>) -> Vec<FileChangeSummary> {
+    let mut summaries: Vec<FileChangeSummary> = changes
+        .iter()
+        .map(|(path, change)| FileChangeSummary::new(path.clone(), change))
+        .collect();
+    summaries.sort_by(|a, b| a.path.cmp(&b.path));
+    summaries
+}
+
+/// Count inserted and deleted lines in a unified diff. Unparsable diffs
+/// count as `(0, 0)`.
+pub fn count_diff_lines(unified_diff: &str) -> (usize, usize) {
+    let Ok(patch) = diffy::Patch::from_str(unified_diff) else {
+        return (0, 0);
+    };
+    patch
+        .hunks()
+        .iter()
+        .flat_map(Hunk::lines)
+        .fold((0, 0), |(added, removed), line| match line {
+            diffy::Line::Insert(_) => (added + 1, removed),
+            diffy::Line::Delete(_) => (added, removed + 1),
+            diffy::Line::Context(_) => (added, removed),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summaries_are_sorted_and_counted_per_operation() {
+        let changes = HashMap::from([
+            (
+                PathBuf::from("b.txt"),
+                FileChange::Update {
+                    unified_diff: "--- b.txt\n+++ c.txt\n@@ -1,2 +1,2 @@\n keep\n-old\n+new\n"
+                        .to_string(),
+                    move_path: Some(PathBuf::from("c.txt")),
+                },
+            ),
+            (
+                PathBuf::from("a.txt"),
+                FileChange::Add {
+                    content: "one\ntwo\n".to_string(),
+                },
+            ),
+            (
+                PathBuf::from("d.txt"),
+                FileChange::Delete {
+                    content: "gone\n".to_string(),
+                },
+            ),
+        ]);
+
+        assert_eq!(
+            summarize_file_changes(&changes),
+            vec![
+                FileChangeSummary {
+                    path: PathBuf::from("a.txt"),
+                    move_path: None,
+                    kind: FileChangeKind::Add,
+                    added: 2,
+                    removed: 0,
+                },
+                FileChangeSummary {
+                    path: PathBuf::from("b.txt"),
+                    move_path: Some(PathBuf::from("c.txt")),
+                    kind: FileChangeKind::Rename,
+                    added: 1,
+                    removed: 1,
+                },
+                FileChangeSummary {
+                    path: PathBuf::from("d.txt"),
+                    move_path: None,
+                    kind: FileChangeKind::Delete,
+                    added: 0,
+                    removed: 1,
+                },
+            ]
+        );
+    }
+}
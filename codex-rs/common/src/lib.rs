@@ -1,6 +1,9 @@
 #[cfg(feature = "cli")]
 mod approval_mode_cli_arg;
 
+#[cfg(feature = "diff_summary")]
+pub mod diff_summary;
+
 #[cfg(feature = "elapsed")]
 pub mod elapsed;
 
@@ -14,9 +14,11 @@ pub fn get_default_model_for_oss_provider(provider_id: &str) -> Option<&'static
 }
 
 /// Ensures the specified OSS provider is ready (models downloaded, service reachable).
+/// May fill in provider settings detected from the server, such as Ollama's
+/// `num_ctx`.
 pub async fn ensure_oss_provider_ready(
     provider_id: &str,
-    config: &Config,
+    config: &mut Config,
 ) -> Result<(), std::io::Error> {
     match provider_id {
         LMSTUDIO_OSS_PROVIDER_ID => {
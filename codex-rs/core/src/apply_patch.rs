@@ -1,13 +1,20 @@
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::function_tool::FunctionCallError;
+use crate::patch_selection::PatchSelection;
+use crate::patch_selection::patch_from_changes;
+use crate::patch_selection::validate_selection;
+use crate::protocol::EventMsg;
 use crate::protocol::FileChange;
 use crate::protocol::ReviewDecision;
+use crate::protocol::WarningEvent;
 use crate::safety::SafetyCheck;
 use crate::safety::assess_patch_safety;
 use codex_apply_patch::ApplyPatchAction;
 use codex_apply_patch::ApplyPatchFileChange;
+use codex_apply_patch::MaybeApplyPatchVerified;
 use std::collections::HashMap;
+use std::path::Path;
 use std::path::PathBuf;
 
 pub const CODEX_APPLY_PATCH_ARG1: &str = "--codex-run-as-apply-patch";
@@ -31,6 +38,19 @@ pub(crate) enum InternalApplyPatchInvocation {
 pub(crate) struct ApplyPatchExec {
     pub(crate) action: ApplyPatchAction,
     pub(crate) user_explicitly_approved_this_action: bool,
+    /// Told to the model along with the result, e.g. when the user only
+    /// approved some of the hunks.
+    pub(crate) note: Option<String>,
+}
+
+impl ApplyPatchExec {
+    /// `output` of the exec call, preceded by the note for the model.
+    pub(crate) fn annotate_output(&self, output: String) -> String {
+        match &self.note {
+            Some(note) => format!("{note}\n{output}"),
+            None => output,
+        }
+    }
 }
 
 pub(crate) async fn apply_patch(
@@ -51,6 +71,7 @@ pub(crate) async fn apply_patch(
         } => InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
             action,
             user_explicitly_approved_this_action: user_explicitly_approved,
+            note: None,
         }),
         SafetyCheck::AskUser => {
             // Compute a readable summary of path changes to include in the
@@ -71,10 +92,16 @@ pub(crate) async fn apply_patch(
                 .await;
             match rx_approve.await.unwrap_or_default() {
                 ReviewDecision::Approved | ReviewDecision::ApprovedForSession => {
-                    InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
-                        action,
-                        user_explicitly_approved_this_action: true,
-                    })
+                    match sess.take_patch_selection(&turn_context.sub_id).await {
+                        Some(selected) => {
+                            apply_selected_changes(sess, turn_context, &action, &selected).await
+                        }
+                        None => InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
+                            action,
+                            user_explicitly_approved_this_action: true,
+                            note: None,
+                        }),
+                    }
                 }
                 ReviewDecision::Denied | ReviewDecision::Abort => {
                     InternalApplyPatchInvocation::Output(Err(FunctionCallError::RespondToModel(
@@ -89,6 +116,103 @@ pub(crate) async fn apply_patch(
     }
 }
 
+/// Apply only the `selected` part of `action`, which the user approved.
+/// Every file is checked first so that hunks which no longer apply are
+/// reported for each file, not just the first.
+async fn apply_selected_changes(
+    sess: &Session,
+    turn_context: &TurnContext,
+    action: &ApplyPatchAction,
+    selected: &HashMap<PathBuf, FileChange>,
+) -> InternalApplyPatchInvocation {
+    if selected.is_empty() {
+        return InternalApplyPatchInvocation::Output(Err(FunctionCallError::RespondToModel(
+            "patch rejected by user".to_string(),
+        )));
+    }
+
+    let proposed = convert_apply_patch_to_protocol(action);
+    if let Err(err) = validate_selection(&proposed, selected) {
+        let message = format!("The approved changes do not match the proposed patch: {err}");
+        sess.send_event(
+            turn_context,
+            EventMsg::Warning(WarningEvent {
+                message: message.clone(),
+            }),
+        )
+        .await;
+        return InternalApplyPatchInvocation::Output(Err(FunctionCallError::RespondToModel(
+            message,
+        )));
+    }
+
+    let mut paths: Vec<&PathBuf> = selected.keys().collect();
+    paths.sort();
+    let errors: Vec<String> = paths
+        .into_iter()
+        .filter_map(|path| {
+            let file = HashMap::from([(path.clone(), selected[path].clone())]);
+            verify_patch(patch_from_changes(&file, &action.cwd), &action.cwd)
+                .err()
+                .map(|err| format!("{}: {err}", path.display()))
+        })
+        .collect();
+    if !errors.is_empty() {
+        let message = format!(
+            "The selected hunks no longer apply cleanly:\n{}",
+            errors.join("\n")
+        );
+        sess.send_event(
+            turn_context,
+            EventMsg::Warning(WarningEvent {
+                message: message.clone(),
+            }),
+        )
+        .await;
+        return InternalApplyPatchInvocation::Output(Err(FunctionCallError::RespondToModel(
+            message,
+        )));
+    }
+
+    let filtered =
+        verify_patch(patch_from_changes(selected, &action.cwd), &action.cwd).and_then(|filtered| {
+            // Added files must come out exactly as proposed.
+            let changed = convert_apply_patch_to_protocol(&filtered);
+            match selected.iter().find(|(path, change)| {
+                matches!(change, FileChange::Add { .. }) && changed.get(*path) != Some(*change)
+            }) {
+                Some((path, _)) => Err(format!("{} cannot be added as proposed", path.display())),
+                None => Ok(filtered),
+            }
+        });
+    match filtered {
+        Ok(filtered) => {
+            let total = PatchSelection::new(&proposed).unit_count();
+            let applied = PatchSelection::new(selected).unit_count();
+            InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
+                action: filtered,
+                user_explicitly_approved_this_action: true,
+                note: Some(format!(
+                    "The user applied only {applied} of {total} hunks of this patch; the rest were rejected and not applied."
+                )),
+            })
+        }
+        Err(err) => InternalApplyPatchInvocation::Output(Err(FunctionCallError::RespondToModel(
+            format!("The selected hunks no longer apply cleanly: {err}"),
+        ))),
+    }
+}
+
+fn verify_patch(patch: String, cwd: &Path) -> Result<ApplyPatchAction, String> {
+    let command = vec!["apply_patch".to_string(), patch];
+    match codex_apply_patch::maybe_parse_apply_patch_verified(&command, cwd) {
+        MaybeApplyPatchVerified::Body(action) => Ok(action),
+        MaybeApplyPatchVerified::CorrectnessError(err) => Err(err.to_string()),
+        MaybeApplyPatchVerified::ShellParseError(err) => Err(format!("{err:?}")),
+        MaybeApplyPatchVerified::NotApplyPatch => Err("not an apply_patch body".to_string()),
+    }
+}
+
 pub(crate) fn convert_apply_patch_to_protocol(
     action: &ApplyPatchAction,
 ) -> HashMap<PathBuf, FileChange> {
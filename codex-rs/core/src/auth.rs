@@ -1,5 +1,7 @@
 mod storage;
 
+use chrono::DateTime;
+use chrono::TimeDelta;
 use chrono::Utc;
 use reqwest::StatusCode;
 use serde::Deserialize;
@@ -8,6 +10,8 @@ use serde::Serialize;
 use serial_test::serial;
 use std::env;
 use std::fmt::Debug;
+use std::fs::File;
+use std::fs::OpenOptions;
 use std::io::ErrorKind;
 use std::path::Path;
 use std::path::PathBuf;
@@ -29,7 +33,9 @@ use crate::error::RefreshTokenFailedReason;
 use crate::token_data::KnownPlan as InternalKnownPlan;
 use crate::token_data::PlanType as InternalPlanType;
 use crate::token_data::TokenData;
+use crate::token_data::jwt_expiry;
 use crate::token_data::parse_id_token;
+use crate::util::backoff;
 use crate::util::try_parse_error_message;
 use codex_protocol::account::PlanType as AccountPlanType;
 use serde_json::Value;
@@ -43,6 +49,15 @@ pub struct CodexAuth {
     pub(crate) auth_dot_json: Arc<Mutex<Option<AuthDotJson>>>,
     storage: Arc<dyn AuthStorageBackend>,
     pub(crate) client: CodexHttpClient,
+    /// Tokens are refreshed this long before the access token expires.
+    refresh_window: Duration,
+    /// Serializes refreshes between clones of this auth; `refresh_lock_path`
+    /// does the same across processes sharing the auth file.
+    refresh_guard: Arc<tokio::sync::Mutex<()>>,
+    refresh_lock_path: Option<PathBuf>,
+    /// After a transient refresh failure, clones of this auth keep using the
+    /// still-valid access token without retrying until this time.
+    refresh_retry_after: Arc<Mutex<Option<DateTime<Utc>>>>,
 }
 
 impl PartialEq for CodexAuth {
@@ -51,9 +66,23 @@ impl PartialEq for CodexAuth {
     }
 }
 
-// TODO(pakrym): use token exp field to check for expiration instead
+/// Fallback for access tokens without an `exp` claim: refresh once the
+/// tokens are this many days old.
 const TOKEN_REFRESH_INTERVAL: i64 = 8;
 
+/// How long before the access token expires it is refreshed, unless
+/// `auth_refresh_window_secs` says otherwise.
+pub const DEFAULT_REFRESH_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+/// Attempts at a proactive refresh when the current access token can no
+/// longer be used.
+const REFRESH_ATTEMPTS: u64 = 3;
+const REFRESH_TIMEOUT: Duration = Duration::from_secs(60);
+/// Wait after a transient refresh failure before trying again while the
+/// current access token is still valid.
+const REFRESH_FAILURE_BACKOFF: TimeDelta = TimeDelta::seconds(30);
+const REFRESH_LOCK_FILENAME: &str = "auth.json.lock";
+
 const REFRESH_TOKEN_EXPIRED_MESSAGE: &str = "Your access token could not be refreshed because your refresh token has expired. Please log out and sign in again.";
 const REFRESH_TOKEN_REUSED_MESSAGE: &str = "Your access token could not be refreshed because your refresh token was already used. Please log out and sign in again.";
 const REFRESH_TOKEN_INVALIDATED_MESSAGE: &str = "Your access token could not be refreshed because your refresh token was revoked. Please log out and sign in again.";
@@ -99,22 +128,7 @@ impl CodexAuth {
         let token_data = self.get_current_token_data().ok_or_else(|| {
             RefreshTokenError::Transient(std::io::Error::other("Token data is not available."))
         })?;
-        let token = token_data.refresh_token;
-
-        let refresh_response = try_refresh_token(token, &self.client).await?;
-
-        let updated = update_tokens(
-            &self.storage,
-            refresh_response.id_token,
-            refresh_response.access_token,
-            refresh_response.refresh_token,
-        )
-        .await
-        .map_err(RefreshTokenError::from)?;
-
-        if let Ok(mut auth_lock) = self.auth_dot_json.lock() {
-            *auth_lock = Some(updated.clone());
-        }
+        let updated = self.refresh_exclusive(&token_data.access_token).await?;
 
         let access = match updated.tokens {
             Some(t) => t.access_token,
@@ -135,55 +149,103 @@ impl CodexAuth {
         load_auth(codex_home, false, auth_credentials_store_mode)
     }
 
+    /// Current tokens, refreshed first when the access token is about to
+    /// expire. A transient refresh failure keeps the current tokens while the
+    /// access token is still valid; a permanent one (e.g. a revoked refresh
+    /// token) is returned as an error asking the user to sign in again.
     pub async fn get_token_data(&self) -> Result<TokenData, std::io::Error> {
-        let auth_dot_json: Option<AuthDotJson> = self.get_current_auth_json();
-        match auth_dot_json {
-            Some(AuthDotJson {
-                tokens: Some(mut tokens),
-                last_refresh: Some(last_refresh),
-                ..
-            }) => {
-                if last_refresh < Utc::now() - chrono::Duration::days(TOKEN_REFRESH_INTERVAL) {
-                    let refresh_result = tokio::time::timeout(
-                        Duration::from_secs(60),
-                        try_refresh_token(tokens.refresh_token.clone(), &self.client),
-                    )
-                    .await;
-                    let refresh_response = match refresh_result {
-                        Ok(Ok(response)) => response,
-                        Ok(Err(err)) => return Err(err.into()),
-                        Err(_) => {
-                            return Err(std::io::Error::new(
-                                ErrorKind::TimedOut,
-                                "timed out while refreshing OpenAI API key",
-                            ));
-                        }
-                    };
-
-                    let updated_auth_dot_json = update_tokens(
-                        &self.storage,
-                        refresh_response.id_token,
-                        refresh_response.access_token,
-                        refresh_response.refresh_token,
-                    )
-                    .await?;
-
-                    tokens = updated_auth_dot_json
-                        .tokens
-                        .clone()
-                        .ok_or(std::io::Error::other(
-                            "Token data is not available after refresh.",
-                        ))?;
-
-                    #[expect(clippy::unwrap_used)]
-                    let mut auth_lock = self.auth_dot_json.lock().unwrap();
-                    *auth_lock = Some(updated_auth_dot_json);
-                }
+        self.get_token_data_at(Utc::now()).await
+    }
 
-                Ok(tokens)
+    async fn get_token_data_at(&self, now: DateTime<Utc>) -> Result<TokenData, std::io::Error> {
+        let Some(AuthDotJson {
+            tokens: Some(tokens),
+            last_refresh: Some(last_refresh),
+            ..
+        }) = self.get_current_auth_json()
+        else {
+            return Err(std::io::Error::other("Token data is not available."));
+        };
+        let expires_at = jwt_expiry(&tokens.access_token);
+        if !needs_refresh(expires_at, last_refresh, now, self.refresh_window) {
+            return Ok(tokens);
+        }
+        let still_valid = expires_at.is_some_and(|expires_at| now < expires_at);
+        if still_valid && self.refresh_retry_after().is_some_and(|after| now < after) {
+            return Ok(tokens);
+        }
+
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let result = tokio::time::timeout(
+                REFRESH_TIMEOUT,
+                self.refresh_exclusive(&tokens.access_token),
+            )
+            .await
+            .unwrap_or_else(|_| {
+                Err(RefreshTokenError::Transient(std::io::Error::new(
+                    ErrorKind::TimedOut,
+                    "timed out while refreshing OpenAI API key",
+                )))
+            });
+            let err = match result {
+                Ok(updated) => {
+                    self.set_refresh_retry_after(None);
+                    return updated.tokens.ok_or_else(|| {
+                        std::io::Error::other("Token data is not available after refresh.")
+                    });
+                }
+                Err(err @ RefreshTokenError::Permanent(_)) => return Err(err.into()),
+                Err(RefreshTokenError::Transient(err)) => err,
+            };
+            if still_valid {
+                tracing::warn!(
+                    "Failed to refresh token ({err}); using the current one until it expires"
+                );
+                self.set_refresh_retry_after(Some(now + REFRESH_FAILURE_BACKOFF));
+                return Ok(tokens);
             }
-            _ => Err(std::io::Error::other("Token data is not available.")),
+            if attempt >= REFRESH_ATTEMPTS {
+                return Err(err);
+            }
+            tokio::time::sleep(backoff(attempt)).await;
+        }
+    }
+
+    /// Refreshes the tokens while holding the refresh locks. If another
+    /// caller refreshed while we waited, i.e. the stored access token is no
+    /// longer `stale_access_token`, its tokens are adopted instead of spending
+    /// the refresh token a second time.
+    async fn refresh_exclusive(
+        &self,
+        stale_access_token: &str,
+    ) -> Result<AuthDotJson, RefreshTokenError> {
+        let _guard = self.refresh_guard.lock().await;
+        let _file_lock = lock_file(self.refresh_lock_path.clone()).await?;
+
+        if let Some(stored) = self.storage.load()?
+            && let Some(tokens) = &stored.tokens
+            && tokens.access_token != stale_access_token
+        {
+            tracing::info!("Token was refreshed by another process; reusing it");
+            self.set_current_auth_json(stored.clone());
+            return Ok(stored);
         }
+
+        let token_data = self.get_current_token_data().ok_or_else(|| {
+            RefreshTokenError::Transient(std::io::Error::other("Token data is not available."))
+        })?;
+        let refresh_response = try_refresh_token(token_data.refresh_token, &self.client).await?;
+        let updated = update_tokens(
+            &self.storage,
+            refresh_response.id_token,
+            refresh_response.access_token,
+            refresh_response.refresh_token,
+        )
+        .await?;
+        self.set_current_auth_json(updated.clone());
+        Ok(updated)
     }
 
     pub async fn get_token(&self) -> Result<String, std::io::Error> {
@@ -253,6 +315,28 @@ impl CodexAuth {
         self.get_current_auth_json().and_then(|t| t.tokens)
     }
 
+    fn set_current_auth_json(&self, auth_dot_json: AuthDotJson) {
+        if let Ok(mut auth_lock) = self.auth_dot_json.lock() {
+            *auth_lock = Some(auth_dot_json);
+        }
+    }
+
+    fn refresh_retry_after(&self) -> Option<DateTime<Utc>> {
+        #[expect(clippy::unwrap_used)]
+        *self.refresh_retry_after.lock().unwrap()
+    }
+
+    fn set_refresh_retry_after(&self, retry_after: Option<DateTime<Utc>>) {
+        if let Ok(mut lock) = self.refresh_retry_after.lock() {
+            *lock = retry_after;
+        }
+    }
+
+    fn with_refresh_window(mut self, refresh_window: Duration) -> Self {
+        self.refresh_window = refresh_window;
+        self
+    }
+
     /// Consider this private to integration tests.
     pub fn create_dummy_chatgpt_auth_for_testing() -> Self {
         let auth_dot_json = AuthDotJson {
@@ -273,6 +357,10 @@ impl CodexAuth {
             storage: create_auth_storage(PathBuf::new(), AuthCredentialsStoreMode::File),
             auth_dot_json,
             client: crate::default_client::create_client(),
+            refresh_window: DEFAULT_REFRESH_WINDOW,
+            refresh_guard: Arc::default(),
+            refresh_lock_path: None,
+            refresh_retry_after: Arc::default(),
         }
     }
 
@@ -283,6 +371,10 @@ impl CodexAuth {
             storage: create_auth_storage(PathBuf::new(), AuthCredentialsStoreMode::File),
             auth_dot_json: Arc::new(Mutex::new(None)),
             client,
+            refresh_window: DEFAULT_REFRESH_WINDOW,
+            refresh_guard: Arc::default(),
+            refresh_lock_path: None,
+            refresh_retry_after: Arc::default(),
         }
     }
 
@@ -483,9 +575,50 @@ fn load_auth(
             last_refresh,
         }))),
         client,
+        refresh_window: DEFAULT_REFRESH_WINDOW,
+        refresh_guard: Arc::default(),
+        refresh_lock_path: Some(codex_home.join(REFRESH_LOCK_FILENAME)),
+        refresh_retry_after: Arc::default(),
     }))
 }
 
+/// Whether tokens should be refreshed before use at `now`: once the access
+/// token is within `window` of its `exp`, or, for access tokens without one,
+/// after `TOKEN_REFRESH_INTERVAL` days.
+fn needs_refresh(
+    expires_at: Option<DateTime<Utc>>,
+    last_refresh: DateTime<Utc>,
+    now: DateTime<Utc>,
+    window: Duration,
+) -> bool {
+    match expires_at {
+        Some(expires_at) => {
+            let window = TimeDelta::from_std(window).unwrap_or(TimeDelta::MAX);
+            expires_at.signed_duration_since(now) <= window
+        }
+        None => last_refresh < now - TimeDelta::days(TOKEN_REFRESH_INTERVAL),
+    }
+}
+
+/// Takes an exclusive lock on `path`, creating it if needed. The lock is held
+/// until the returned file is dropped.
+async fn lock_file(path: Option<PathBuf>) -> std::io::Result<Option<File>> {
+    let Some(path) = path else {
+        return Ok(None);
+    };
+    tokio::task::spawn_blocking(move || {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)?;
+        file.lock()?;
+        Ok(Some(file))
+    })
+    .await
+    .map_err(std::io::Error::other)?
+}
+
 async fn update_tokens(
     storage: &Arc<dyn AuthStorageBackend>,
     id_token: Option<String>,
@@ -692,6 +825,71 @@ mod tests {
         assert_eq!(tokens.refresh_token, "new-refresh-token");
     }
 
+    #[test]
+    fn needs_refresh_uses_expiry_and_window() {
+        let now = DateTime::from_timestamp(1_700_000_000, 0).expect("valid timestamp");
+        let window = Duration::from_secs(300);
+        let expiring_in = |secs: i64| Some(now + TimeDelta::seconds(secs));
+
+        assert_eq!(
+            [3600, 301, 300, 0, -60].map(|secs| needs_refresh(expiring_in(secs), now, now, window)),
+            [false, false, true, true, true]
+        );
+        assert!(!needs_refresh(
+            expiring_in(60),
+            now,
+            now,
+            Duration::from_secs(30)
+        ));
+
+        // Without an `exp` claim, the age of the tokens decides.
+        assert_eq!(
+            [1, 9].map(|days| needs_refresh(None, now - TimeDelta::days(days), now, window)),
+            [false, true]
+        );
+    }
+
+    #[tokio::test]
+    async fn transient_refresh_failure_backs_off_while_token_is_valid() {
+        let now = DateTime::from_timestamp(1_700_000_000, 0).expect("valid timestamp");
+        let b64 = |b: &[u8]| base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(b);
+        let access_token = format!(
+            "{}.{}.sig",
+            b64(br#"{"alg":"none"}"#.as_slice()),
+            b64(json!({ "exp": (now + TimeDelta::minutes(2)).timestamp() })
+                .to_string()
+                .as_bytes()),
+        );
+        let codex_home = tempdir().unwrap();
+        let mut auth = CodexAuth::create_dummy_chatgpt_auth_for_testing();
+        // The lock file cannot be created, so every refresh attempt fails
+        // transiently without reaching the network.
+        auth.refresh_lock_path = Some(codex_home.path().join("missing").join("auth.json.lock"));
+        {
+            let mut auth_json = auth.auth_dot_json.lock().unwrap();
+            let tokens = auth_json
+                .as_mut()
+                .and_then(|auth_json| auth_json.tokens.as_mut())
+                .unwrap();
+            tokens.access_token = access_token.clone();
+        }
+        let clone = auth.clone();
+
+        let mut retry_afters = Vec::new();
+        for offset in [0, 10, 29, 31] {
+            let at = now + TimeDelta::seconds(offset);
+            let tokens = clone.get_token_data_at(at).await.unwrap();
+            assert_eq!(tokens.access_token, access_token);
+            retry_afters.push(auth.refresh_retry_after());
+        }
+
+        // Calls inside the backoff do not retry; the first one after it does
+        // and backs off again.
+        let first = Some(now + REFRESH_FAILURE_BACKOFF);
+        let second = Some(now + TimeDelta::seconds(31) + REFRESH_FAILURE_BACKOFF);
+        assert_eq!(retry_afters, vec![first, first, first, second]);
+    }
+
     #[test]
     fn login_with_api_key_overwrites_existing_auth_json() {
         let dir = tempdir().unwrap();
@@ -1086,6 +1284,7 @@ pub struct AuthManager {
     inner: RwLock<CachedAuth>,
     enable_codex_api_key_env: bool,
     auth_credentials_store_mode: AuthCredentialsStoreMode,
+    refresh_window: Duration,
 }
 
 impl AuthManager {
@@ -1098,18 +1297,57 @@ impl AuthManager {
         enable_codex_api_key_env: bool,
         auth_credentials_store_mode: AuthCredentialsStoreMode,
     ) -> Self {
-        let auth = load_auth(
-            &codex_home,
+        Self::with_refresh_window(
+            codex_home,
             enable_codex_api_key_env,
             auth_credentials_store_mode,
+            DEFAULT_REFRESH_WINDOW,
         )
-        .ok()
-        .flatten();
-        Self {
+    }
+
+    fn with_refresh_window(
+        codex_home: PathBuf,
+        enable_codex_api_key_env: bool,
+        auth_credentials_store_mode: AuthCredentialsStoreMode,
+        refresh_window: Duration,
+    ) -> Self {
+        let mut manager = Self {
             codex_home,
-            inner: RwLock::new(CachedAuth { auth }),
+            inner: RwLock::new(CachedAuth { auth: None }),
             enable_codex_api_key_env,
             auth_credentials_store_mode,
+            refresh_window,
+        };
+        manager.inner = RwLock::new(CachedAuth {
+            auth: manager.load(),
+        });
+        manager
+    }
+
+    /// Shared manager for the auth store and refresh window in `config`.
+    pub fn shared_from_config(config: &Config, enable_codex_api_key_env: bool) -> Arc<Self> {
+        Arc::new(Self::with_refresh_window(
+            config.codex_home.clone(),
+            enable_codex_api_key_env,
+            config.cli_auth_credentials_store_mode,
+            config.auth_refresh_window,
+        ))
+    }
+
+    /// Load the stored auth. Unreadable credentials (for example an
+    /// encrypted auth.json whose keyring entry is gone) leave the manager
+    /// signed out; the error is logged so the re-login hint is not lost.
+    fn load(&self) -> Option<CodexAuth> {
+        match load_auth(
+            &self.codex_home,
+            self.enable_codex_api_key_env,
+            self.auth_credentials_store_mode,
+        ) {
+            Ok(auth) => auth.map(|auth| auth.with_refresh_window(self.refresh_window)),
+            Err(err) => {
+                tracing::error!("Failed to load auth: {err}");
+                None
+            }
         }
     }
 
@@ -1121,6 +1359,7 @@ impl AuthManager {
             inner: RwLock::new(cached),
             enable_codex_api_key_env: false,
             auth_credentials_store_mode: AuthCredentialsStoreMode::File,
+            refresh_window: DEFAULT_REFRESH_WINDOW,
         })
     }
 
@@ -1132,13 +1371,7 @@ impl AuthManager {
     /// Force a reload of the auth information from auth.json. Returns
     /// whether the auth value changed.
     pub fn reload(&self) -> bool {
-        let new_auth = load_auth(
-            &self.codex_home,
-            self.enable_codex_api_key_env,
-            self.auth_credentials_store_mode,
-        )
-        .ok()
-        .flatten();
+        let new_auth = self.load();
         if let Ok(mut guard) = self.inner.write() {
             let changed = !AuthManager::auths_equal(&guard.auth, &new_auth);
             guard.auth = new_auth;
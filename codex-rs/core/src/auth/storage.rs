@@ -20,6 +20,9 @@ use crate::token_data::TokenData;
 use codex_keyring_store::DefaultKeyringStore;
 use codex_keyring_store::KeyringStore;
 
+#[cfg(feature = "keychain-auth")]
+mod keychain;
+
 /// Determine where Codex should store CLI auth credentials.
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -31,6 +34,11 @@ pub enum AuthCredentialsStoreMode {
     Keyring,
     /// Use keyring when available; otherwise, fall back to a file in CODEX_HOME.
     Auto,
+    /// Persist credentials in CODEX_HOME/auth.json, encrypted with a key kept
+    /// in the keyring. Falls back to a plaintext file if the keyring is
+    /// unavailable when saving.
+    #[cfg(feature = "keychain-auth")]
+    Keychain,
 }
 
 /// Expected structure for $CODEX_HOME/auth.json.
@@ -50,6 +58,32 @@ pub(super) fn get_auth_file(codex_home: &Path) -> PathBuf {
     codex_home.join("auth.json")
 }
 
+fn read_auth_file(auth_file: &Path) -> std::io::Result<String> {
+    let mut file = File::open(auth_file)?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+/// Write `contents` to `CODEX_HOME/auth.json`, readable only by the owner.
+fn write_auth_file(codex_home: &Path, contents: &str) -> std::io::Result<()> {
+    let auth_file = get_auth_file(codex_home);
+
+    if let Some(parent) = auth_file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = OpenOptions::new();
+    options.truncate(true).write(true).create(true);
+    #[cfg(unix)]
+    {
+        options.mode(0o600);
+    }
+    let mut file = options.open(auth_file)?;
+    file.write_all(contents.as_bytes())?;
+    file.flush()?;
+    Ok(())
+}
+
 pub(super) fn delete_file_if_exists(codex_home: &Path) -> std::io::Result<bool> {
     let auth_file = get_auth_file(codex_home);
     match std::fs::remove_file(&auth_file) {
@@ -78,9 +112,13 @@ impl FileAuthStorage {
     /// Attempt to read and refresh the `auth.json` file in the given `CODEX_HOME` directory.
     /// Returns the full AuthDotJson structure after refreshing if necessary.
     pub(super) fn try_read_auth_json(&self, auth_file: &Path) -> std::io::Result<AuthDotJson> {
-        let mut file = File::open(auth_file)?;
-        let mut contents = String::new();
-        file.read_to_string(&mut contents)?;
+        let contents = read_auth_file(auth_file)?;
+        if EncryptedAuthFile::parse(&contents).is_some() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "auth.json is encrypted; set `auth_storage = \"keychain\"` or run `codex login` again",
+            ));
+        }
         let auth_dot_json: AuthDotJson = serde_json::from_str(&contents)?;
 
         Ok(auth_dot_json)
@@ -99,22 +137,8 @@ impl AuthStorageBackend for FileAuthStorage {
     }
 
     fn save(&self, auth_dot_json: &AuthDotJson) -> std::io::Result<()> {
-        let auth_file = get_auth_file(&self.codex_home);
-
-        if let Some(parent) = auth_file.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
         let json_data = serde_json::to_string_pretty(auth_dot_json)?;
-        let mut options = OpenOptions::new();
-        options.truncate(true).write(true).create(true);
-        #[cfg(unix)]
-        {
-            options.mode(0o600);
-        }
-        let mut file = options.open(auth_file)?;
-        file.write_all(json_data.as_bytes())?;
-        file.flush()?;
-        Ok(())
+        write_auth_file(&self.codex_home, &json_data)
     }
 
     fn delete(&self) -> std::io::Result<bool> {
@@ -255,6 +279,24 @@ impl AuthStorageBackend for AutoAuthStorage {
     }
 }
 
+/// On-disk form of an auth.json written in `keychain` mode. Recognized in every
+/// build so that plain file storage can reject it with a clear error.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[cfg_attr(not(feature = "keychain-auth"), allow(dead_code))]
+struct EncryptedAuthFile {
+    /// Envelope version; also what tells an encrypted file apart from a
+    /// plaintext one.
+    codex_encrypted_auth: u32,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl EncryptedAuthFile {
+    fn parse(contents: &str) -> Option<Self> {
+        serde_json::from_str(contents).ok()
+    }
+}
+
 pub(super) fn create_auth_storage(
     codex_home: PathBuf,
     mode: AuthCredentialsStoreMode,
@@ -274,6 +316,11 @@ fn create_auth_storage_with_keyring_store(
             Arc::new(KeyringAuthStorage::new(codex_home, keyring_store))
         }
         AuthCredentialsStoreMode::Auto => Arc::new(AutoAuthStorage::new(codex_home, keyring_store)),
+        #[cfg(feature = "keychain-auth")]
+        AuthCredentialsStoreMode::Keychain => Arc::new(keychain::EncryptedFileAuthStorage::new(
+            codex_home,
+            keyring_store,
+        )),
     }
 }
 
@@ -669,4 +716,140 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn file_storage_rejects_encrypted_auth_file() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        std::fs::write(
+            get_auth_file(codex_home.path()),
+            json!({ "codex_encrypted_auth": 1, "nonce": "", "ciphertext": "" }).to_string(),
+        )?;
+
+        let err = FileAuthStorage::new(codex_home.path().to_path_buf())
+            .load()
+            .expect_err("plaintext storage cannot read an encrypted file");
+
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        Ok(())
+    }
+
+    #[cfg(feature = "keychain-auth")]
+    mod keychain_mode {
+        use super::*;
+        use crate::auth::storage::keychain::EncryptedFileAuthStorage;
+
+        fn encryption_key_name(codex_home: &Path) -> anyhow::Result<String> {
+            Ok(format!("{}|encryption-key", compute_store_key(codex_home)?))
+        }
+
+        #[test]
+        fn encrypted_storage_round_trips_without_plaintext_on_disk() -> anyhow::Result<()> {
+            let codex_home = tempdir()?;
+            let mock_keyring = MockKeyringStore::default();
+            let storage = EncryptedFileAuthStorage::new(
+                codex_home.path().to_path_buf(),
+                Arc::new(mock_keyring.clone()),
+            );
+            let auth = auth_with_prefix("encrypted");
+
+            storage.save(&auth)?;
+
+            let on_disk = std::fs::read_to_string(get_auth_file(codex_home.path()))?;
+            assert!(
+                !on_disk.contains("encrypted-refresh"),
+                "auth.json should not contain plaintext tokens"
+            );
+            assert!(EncryptedAuthFile::parse(&on_disk).is_some());
+            assert!(mock_keyring.contains(&encryption_key_name(codex_home.path())?));
+            assert_eq!(storage.load()?, Some(auth));
+            Ok(())
+        }
+
+        #[test]
+        fn encrypted_storage_migrates_plaintext_on_first_save() -> anyhow::Result<()> {
+            let codex_home = tempdir()?;
+            let mock_keyring = MockKeyringStore::default();
+            let storage = EncryptedFileAuthStorage::new(
+                codex_home.path().to_path_buf(),
+                Arc::new(mock_keyring),
+            );
+            let plaintext = auth_with_prefix("plaintext");
+            FileAuthStorage::new(codex_home.path().to_path_buf()).save(&plaintext)?;
+
+            // Existing plaintext files keep working until the next write.
+            assert_eq!(storage.load()?, Some(plaintext.clone()));
+
+            storage.save(&plaintext)?;
+
+            let on_disk = std::fs::read_to_string(get_auth_file(codex_home.path()))?;
+            assert!(EncryptedAuthFile::parse(&on_disk).is_some());
+            assert_eq!(storage.load()?, Some(plaintext));
+            Ok(())
+        }
+
+        #[test]
+        fn encrypted_storage_missing_key_asks_for_relogin() -> anyhow::Result<()> {
+            let codex_home = tempdir()?;
+            let mock_keyring = MockKeyringStore::default();
+            let storage = EncryptedFileAuthStorage::new(
+                codex_home.path().to_path_buf(),
+                Arc::new(mock_keyring.clone()),
+            );
+            storage.save(&auth_with_prefix("lost-key"))?;
+            mock_keyring.delete(KEYRING_SERVICE, &encryption_key_name(codex_home.path())?)?;
+
+            let err = storage
+                .load()
+                .expect_err("load should fail without the key");
+
+            assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+            assert_eq!(
+                err.to_string(),
+                "auth.json is encrypted but its key is missing from the keyring; run `codex login` to sign in again"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn encrypted_storage_save_falls_back_to_plaintext_without_keyring() -> anyhow::Result<()> {
+            let codex_home = tempdir()?;
+            let mock_keyring = MockKeyringStore::default();
+            let key_name = encryption_key_name(codex_home.path())?;
+            mock_keyring.set_error(
+                &key_name,
+                KeyringError::Invalid("error".into(), "load".into()),
+            );
+            let storage = EncryptedFileAuthStorage::new(
+                codex_home.path().to_path_buf(),
+                Arc::new(mock_keyring),
+            );
+            let auth = auth_with_prefix("fallback");
+
+            storage.save(&auth)?;
+
+            let saved = FileAuthStorage::new(codex_home.path().to_path_buf())
+                .load()?
+                .context("plaintext fallback should exist")?;
+            assert_eq!(saved, auth);
+            Ok(())
+        }
+
+        #[test]
+        fn encrypted_storage_delete_removes_file_and_key() -> anyhow::Result<()> {
+            let codex_home = tempdir()?;
+            let mock_keyring = MockKeyringStore::default();
+            let storage = EncryptedFileAuthStorage::new(
+                codex_home.path().to_path_buf(),
+                Arc::new(mock_keyring.clone()),
+            );
+            storage.save(&auth_with_prefix("delete"))?;
+
+            let removed = storage.delete()?;
+
+            assert!(removed, "delete should report removal");
+            assert!(!mock_keyring.contains(&encryption_key_name(codex_home.path())?));
+            assert!(!get_auth_file(codex_home.path()).exists());
+            Ok(())
+        }
+    }
 }
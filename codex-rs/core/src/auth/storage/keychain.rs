@@ -0,0 +1,212 @@
+//! `auth_storage = "keychain"`: auth.json stays on disk, sealed with
+//! ChaCha20-Poly1305 under a random key kept in the OS keyring. Only built
+//! with the `keychain-auth` feature.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use ring::aead::Aad;
+use ring::aead::CHACHA20_POLY1305;
+use ring::aead::LessSafeKey;
+use ring::aead::NONCE_LEN;
+use ring::aead::Nonce;
+use ring::aead::UnboundKey;
+use ring::rand::SecureRandom;
+use ring::rand::SystemRandom;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::warn;
+
+use super::AuthDotJson;
+use super::AuthStorageBackend;
+use super::EncryptedAuthFile;
+use super::KEYRING_SERVICE;
+use super::compute_store_key;
+use super::delete_file_if_exists;
+use super::get_auth_file;
+use super::read_auth_file;
+use super::write_auth_file;
+use codex_keyring_store::KeyringStore;
+
+impl EncryptedAuthFile {
+    const VERSION: u32 = 1;
+
+    fn seal(key: &[u8], plaintext: &[u8]) -> std::io::Result<Self> {
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| std::io::Error::other("failed to generate auth.json nonce"))?;
+        let mut in_out = plaintext.to_vec();
+        aead_key(key)?
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut in_out,
+            )
+            .map_err(|_| std::io::Error::other("failed to encrypt auth.json"))?;
+        Ok(Self {
+            codex_encrypted_auth: Self::VERSION,
+            nonce: BASE64_STANDARD.encode(nonce),
+            ciphertext: BASE64_STANDARD.encode(in_out),
+        })
+    }
+
+    fn open(&self, key: &[u8]) -> std::io::Result<Vec<u8>> {
+        if self.codex_encrypted_auth != Self::VERSION {
+            return Err(relogin_error(&format!(
+                "unsupported encrypted auth.json version {}",
+                self.codex_encrypted_auth
+            )));
+        }
+        let nonce: [u8; NONCE_LEN] = BASE64_STANDARD
+            .decode(&self.nonce)
+            .ok()
+            .and_then(|nonce| nonce.try_into().ok())
+            .ok_or_else(|| relogin_error("encrypted auth.json has a malformed nonce"))?;
+        let mut in_out = BASE64_STANDARD
+            .decode(&self.ciphertext)
+            .map_err(|_| relogin_error("encrypted auth.json has malformed ciphertext"))?;
+        let plaintext = aead_key(key)?
+            .open_in_place(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut in_out,
+            )
+            .map_err(|_| relogin_error("failed to decrypt auth.json with the keyring key"))?;
+        Ok(plaintext.to_vec())
+    }
+}
+
+fn aead_key(key: &[u8]) -> std::io::Result<LessSafeKey> {
+    UnboundKey::new(&CHACHA20_POLY1305, key)
+        .map(LessSafeKey::new)
+        .map_err(|_| relogin_error("auth.json encryption key has the wrong length"))
+}
+
+/// The error for an encrypted auth.json that cannot be read back. Logging in
+/// again replaces both the file and the key.
+fn relogin_error(reason: &str) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::PermissionDenied,
+        format!("{reason}; run `codex login` to sign in again"),
+    )
+}
+
+/// Keeps auth.json on disk but encrypts it with a random key stored in the
+/// keyring, so the file alone does not expose tokens.
+#[derive(Clone, Debug)]
+pub(super) struct EncryptedFileAuthStorage {
+    codex_home: PathBuf,
+    keyring_store: Arc<dyn KeyringStore>,
+}
+
+impl EncryptedFileAuthStorage {
+    pub(super) fn new(codex_home: PathBuf, keyring_store: Arc<dyn KeyringStore>) -> Self {
+        Self {
+            codex_home,
+            keyring_store,
+        }
+    }
+
+    fn key_name(&self) -> std::io::Result<String> {
+        Ok(format!(
+            "{}|encryption-key",
+            compute_store_key(&self.codex_home)?
+        ))
+    }
+
+    fn load_key(&self) -> std::io::Result<Option<Vec<u8>>> {
+        let key_name = self.key_name()?;
+        match self.keyring_store.load(KEYRING_SERVICE, &key_name) {
+            Ok(Some(encoded)) => BASE64_STANDARD
+                .decode(encoded)
+                .map(Some)
+                .map_err(|_| relogin_error("auth.json encryption key in the keyring is malformed")),
+            Ok(None) => Ok(None),
+            Err(error) => Err(std::io::Error::other(format!(
+                "failed to load auth.json encryption key from keyring: {}",
+                error.message()
+            ))),
+        }
+    }
+
+    fn load_or_create_key(&self) -> std::io::Result<Vec<u8>> {
+        if let Some(key) = self.load_key()? {
+            return Ok(key);
+        }
+        let mut key = vec![0u8; CHACHA20_POLY1305.key_len()];
+        SystemRandom::new()
+            .fill(&mut key)
+            .map_err(|_| std::io::Error::other("failed to generate auth.json encryption key"))?;
+        let key_name = self.key_name()?;
+        self.keyring_store
+            .save(KEYRING_SERVICE, &key_name, &BASE64_STANDARD.encode(&key))
+            .map_err(|error| {
+                std::io::Error::other(format!(
+                    "failed to save auth.json encryption key to keyring: {}",
+                    error.message()
+                ))
+            })?;
+        Ok(key)
+    }
+
+    fn save_encrypted(&self, json_data: &str) -> std::io::Result<()> {
+        let key = self.load_or_create_key()?;
+        let envelope = EncryptedAuthFile::seal(&key, json_data.as_bytes())?;
+        write_auth_file(&self.codex_home, &serde_json::to_string_pretty(&envelope)?)
+    }
+}
+
+impl AuthStorageBackend for EncryptedFileAuthStorage {
+    fn load(&self) -> std::io::Result<Option<AuthDotJson>> {
+        let contents = match read_auth_file(&get_auth_file(&self.codex_home)) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        // A plaintext file is still readable; the next save encrypts it.
+        let Some(envelope) = EncryptedAuthFile::parse(&contents) else {
+            return Ok(Some(serde_json::from_str(&contents)?));
+        };
+        let key = match self.load_key() {
+            Ok(Some(key)) => key,
+            Ok(None) => {
+                return Err(relogin_error(
+                    "auth.json is encrypted but its key is missing from the keyring",
+                ));
+            }
+            Err(err) => {
+                return Err(relogin_error(&format!(
+                    "auth.json is encrypted but the keyring could not be read ({err})"
+                )));
+            }
+        };
+        let plaintext = envelope.open(&key)?;
+        serde_json::from_slice(&plaintext)
+            .map(Some)
+            .map_err(|_| relogin_error("decrypted auth.json is not valid"))
+    }
+
+    fn save(&self, auth: &AuthDotJson) -> std::io::Result<()> {
+        let json_data = serde_json::to_string_pretty(auth)?;
+        match self.save_encrypted(&json_data) {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                warn!("failed to encrypt auth.json, falling back to plaintext: {err}");
+                write_auth_file(&self.codex_home, &json_data)
+            }
+        }
+    }
+
+    fn delete(&self) -> std::io::Result<bool> {
+        let key_name = self.key_name()?;
+        let key_removed = match self.keyring_store.delete(KEYRING_SERVICE, &key_name) {
+            Ok(removed) => removed,
+            Err(err) => {
+                warn!("failed to delete auth.json encryption key from keyring: {err}");
+                false
+            }
+        };
+        let file_removed = delete_file_if_exists(&self.codex_home)?;
+        Ok(key_removed || file_removed)
+    }
+}
@@ -118,6 +118,201 @@ pub fn parse_shell_lc_plain_commands(command: &[String]) -> Option<Vec<Vec<Strin
     try_parse_word_only_commands_sequence(&tree, script)
 }
 
+/// Operator joining a [`ShellSegment`] to the segment before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellConnector {
+    /// `&&`
+    And,
+    /// `||`
+    Or,
+    /// `;` or a newline.
+    Sequence,
+    /// `|` or `|&`.
+    Pipe,
+}
+
+/// One simple command within a compound script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShellSegment {
+    /// Command name and arguments with quoting removed.
+    pub words: Vec<String>,
+    /// Source text of the command, including redirections attached to it.
+    pub text: String,
+    /// How this segment is joined to the previous one; `None` for the first.
+    pub connector: Option<ShellConnector>,
+    /// Start offsets of the enclosing subshells, outermost first.
+    pub subshells: Vec<usize>,
+    /// Output of the segment, or of a subshell around it, goes to a file.
+    pub writes_file: bool,
+}
+
+/// Split a script into the simple commands joined by `&&`, `||`, `;`, `|`
+/// and `|&`, descending into `( ... )` subshells.
+///
+/// Compared to [`try_parse_word_only_commands_sequence`] this also accepts
+/// subshells and file redirections, recording on each segment whether its
+/// output is written to a file. Anything else (substitutions, expansions,
+/// heredocs, control flow, background jobs, ...) returns `None`.
+pub fn try_parse_shell_segments(tree: &Tree, src: &str) -> Option<Vec<ShellSegment>> {
+    let root = tree.root_node();
+    if root.has_error() {
+        return None;
+    }
+    let mut walker = SegmentWalker {
+        src,
+        segments: Vec::new(),
+        pending: None,
+    };
+    walker.visit(root, &SegmentScope::default())?;
+    if walker.segments.is_empty() {
+        return None;
+    }
+    Some(walker.segments)
+}
+
+/// Returns the segments of a `bash -lc "..."` or `zsh -lc "..."` invocation;
+/// see [`try_parse_shell_segments`].
+pub fn parse_shell_lc_segments(command: &[String]) -> Option<Vec<ShellSegment>> {
+    let (_, script) = extract_bash_command(command)?;
+
+    let tree = try_parse_shell(script)?;
+    try_parse_shell_segments(&tree, script)
+}
+
+#[derive(Clone, Default)]
+struct SegmentScope<'tree> {
+    subshells: Vec<usize>,
+    writes_file: bool,
+    /// The `redirected_statement` whose body is being visited, so the
+    /// segment text includes its redirections.
+    redirected: Option<Node<'tree>>,
+}
+
+struct SegmentWalker<'src> {
+    src: &'src str,
+    segments: Vec<ShellSegment>,
+    pending: Option<ShellConnector>,
+}
+
+impl SegmentWalker<'_> {
+    fn visit(&mut self, node: Node<'_>, scope: &SegmentScope<'_>) -> Option<()> {
+        match node.kind() {
+            "program" | "list" | "pipeline" => {
+                let scope = SegmentScope {
+                    redirected: None,
+                    ..scope.clone()
+                };
+                self.visit_children(node, &scope)
+            }
+            "subshell" => {
+                let mut scope = SegmentScope {
+                    redirected: None,
+                    ..scope.clone()
+                };
+                scope.subshells.push(node.start_byte());
+                self.visit_children(node, &scope)
+            }
+            "redirected_statement" => {
+                let mut body = None;
+                let mut writes_file = scope.writes_file;
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    if child.kind() == "file_redirect" {
+                        writes_file |= redirect_writes_file(child, self.src)?;
+                    } else if body.replace(child).is_some() {
+                        return None;
+                    }
+                }
+                let scope = SegmentScope {
+                    subshells: scope.subshells.clone(),
+                    writes_file,
+                    redirected: Some(node),
+                };
+                self.visit(body?, &scope)
+            }
+            "command" => self.push_command(node, scope),
+            _ => None,
+        }
+    }
+
+    fn visit_children(&mut self, node: Node<'_>, scope: &SegmentScope<'_>) -> Option<()> {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if child.is_named() {
+                self.visit(child, scope)?;
+                continue;
+            }
+            let connector = match child.kind() {
+                "&&" => ShellConnector::And,
+                "||" => ShellConnector::Or,
+                "|" | "|&" => ShellConnector::Pipe,
+                ";" => ShellConnector::Sequence,
+                "(" | ")" if node.kind() == "subshell" => continue,
+                kind if kind.trim().is_empty() => ShellConnector::Sequence,
+                _ => return None,
+            };
+            self.pending = Some(connector);
+        }
+        Some(())
+    }
+
+    fn push_command(&mut self, node: Node<'_>, scope: &SegmentScope<'_>) -> Option<()> {
+        let mut words = Vec::new();
+        let mut writes_file = scope.writes_file;
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            if child.kind() == "file_redirect" {
+                writes_file |= redirect_writes_file(child, self.src)?;
+            } else {
+                words.push(plain_word(child, self.src)?);
+            }
+        }
+        if words.is_empty() {
+            return None;
+        }
+        let text = scope
+            .redirected
+            .unwrap_or(node)
+            .utf8_text(self.src.as_bytes())
+            .ok()?
+            .to_owned();
+        let connector = self.pending.take().filter(|_| !self.segments.is_empty());
+        self.segments.push(ShellSegment {
+            words,
+            text,
+            connector,
+            subshells: scope.subshells.clone(),
+            writes_file,
+        });
+        Some(())
+    }
+}
+
+/// Whether a `file_redirect` sends output to a file. Reading from a file,
+/// duplicating or closing descriptors and writing to `/dev/null` do not count.
+/// Returns `None` for redirects whose target is not a plain word.
+fn redirect_writes_file(redirect: Node<'_>, src: &str) -> Option<bool> {
+    let mut operator = None;
+    let mut destination = Vec::new();
+    let mut cursor = redirect.walk();
+    for child in redirect.children(&mut cursor) {
+        if !child.is_named() {
+            operator = Some(child.kind());
+        } else if child.kind() != "file_descriptor" {
+            destination.push(plain_word(child, src)?);
+        }
+    }
+    let is_descriptor = |words: &[String]| matches!(words, [word] if word == "-" || word.chars().all(|c| c.is_ascii_digit()));
+    match operator? {
+        "<" | "<&-" | ">&-" => Some(false),
+        "<&" | ">&" if is_descriptor(&destination) => Some(false),
+        ">" | ">>" | ">|" | "&>" | "&>>" | ">&" | "<>" => {
+            Some(!matches!(destination.as_slice(), [target] if target == "/dev/null"))
+        }
+        _ => None,
+    }
+}
+
 fn parse_plain_command_from_node(cmd: tree_sitter::Node, src: &str) -> Option<Vec<String>> {
     if cmd.kind() != "command" {
         return None;
@@ -125,43 +320,43 @@ fn parse_plain_command_from_node(cmd: tree_sitter::Node, src: &str) -> Option<Ve
     let mut words = Vec::new();
     let mut cursor = cmd.walk();
     for child in cmd.named_children(&mut cursor) {
-        match child.kind() {
-            "command_name" => {
-                let word_node = child.named_child(0)?;
-                if word_node.kind() != "word" {
-                    return None;
-                }
-                words.push(word_node.utf8_text(src.as_bytes()).ok()?.to_owned());
-            }
-            "word" | "number" => {
-                words.push(child.utf8_text(src.as_bytes()).ok()?.to_owned());
-            }
-            "string" => {
-                if child.child_count() == 3
-                    && child.child(0)?.kind() == "\""
-                    && child.child(1)?.kind() == "string_content"
-                    && child.child(2)?.kind() == "\""
-                {
-                    words.push(child.child(1)?.utf8_text(src.as_bytes()).ok()?.to_owned());
-                } else {
-                    return None;
-                }
+        words.push(plain_word(child, src)?);
+    }
+    Some(words)
+}
+
+/// The unquoted text of a command name or argument, or `None` when the node
+/// contains anything other than literal text.
+fn plain_word(node: Node<'_>, src: &str) -> Option<String> {
+    match node.kind() {
+        "command_name" => {
+            let word_node = node.named_child(0)?;
+            if word_node.kind() != "word" {
+                return None;
             }
-            "raw_string" => {
-                let raw_string = child.utf8_text(src.as_bytes()).ok()?;
-                let stripped = raw_string
-                    .strip_prefix('\'')
-                    .and_then(|s| s.strip_suffix('\''));
-                if let Some(s) = stripped {
-                    words.push(s.to_owned());
-                } else {
-                    return None;
-                }
+            Some(word_node.utf8_text(src.as_bytes()).ok()?.to_owned())
+        }
+        "word" | "number" => Some(node.utf8_text(src.as_bytes()).ok()?.to_owned()),
+        "string" => {
+            if node.child_count() == 3
+                && node.child(0)?.kind() == "\""
+                && node.child(1)?.kind() == "string_content"
+                && node.child(2)?.kind() == "\""
+            {
+                Some(node.child(1)?.utf8_text(src.as_bytes()).ok()?.to_owned())
+            } else {
+                None
             }
-            _ => return None,
         }
+        "raw_string" => {
+            let raw_string = node.utf8_text(src.as_bytes()).ok()?;
+            raw_string
+                .strip_prefix('\'')
+                .and_then(|s| s.strip_suffix('\''))
+                .map(str::to_owned)
+        }
+        _ => None,
     }
-    Some(words)
 }
 
 #[cfg(test)]
@@ -250,6 +445,117 @@ mod tests {
         assert!(parse_seq("ls &&").is_none());
     }
 
+    fn parse_segments(src: &str) -> Option<Vec<ShellSegment>> {
+        let tree = try_parse_shell(src)?;
+        try_parse_shell_segments(&tree, src)
+    }
+
+    fn segment(
+        words: &[&str],
+        text: &str,
+        connector: Option<ShellConnector>,
+        subshells: &[usize],
+        writes_file: bool,
+    ) -> ShellSegment {
+        ShellSegment {
+            words: words.iter().map(ToString::to_string).collect(),
+            text: text.to_string(),
+            connector,
+            subshells: subshells.to_vec(),
+            writes_file,
+        }
+    }
+
+    #[test]
+    fn segments_record_connectors_in_source_order() {
+        assert_eq!(
+            parse_segments("echo \"a && b\" || ls | wc -l; pwd").unwrap(),
+            vec![
+                segment(&["echo", "a && b"], "echo \"a && b\"", None, &[], false),
+                segment(&["ls"], "ls", Some(ShellConnector::Or), &[], false),
+                segment(
+                    &["wc", "-l"],
+                    "wc -l",
+                    Some(ShellConnector::Pipe),
+                    &[],
+                    false
+                ),
+                segment(&["pwd"], "pwd", Some(ShellConnector::Sequence), &[], false),
+            ]
+        );
+    }
+
+    #[test]
+    fn segments_descend_into_subshells() {
+        assert_eq!(
+            parse_segments("ls || (cd src && rg foo) | head -n 5").unwrap(),
+            vec![
+                segment(&["ls"], "ls", None, &[], false),
+                segment(
+                    &["cd", "src"],
+                    "cd src",
+                    Some(ShellConnector::Or),
+                    &[6],
+                    false
+                ),
+                segment(
+                    &["rg", "foo"],
+                    "rg foo",
+                    Some(ShellConnector::And),
+                    &[6],
+                    false
+                ),
+                segment(
+                    &["head", "-n", "5"],
+                    "head -n 5",
+                    Some(ShellConnector::Pipe),
+                    &[],
+                    false
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn segments_flag_redirected_output_as_writes() {
+        assert_eq!(
+            parse_segments("cat a.txt > b.txt && sort < b.txt 2>/dev/null").unwrap(),
+            vec![
+                segment(&["cat", "a.txt"], "cat a.txt > b.txt", None, &[], true),
+                segment(
+                    &["sort"],
+                    "sort < b.txt 2>/dev/null",
+                    Some(ShellConnector::And),
+                    &[],
+                    false
+                ),
+            ]
+        );
+        assert_eq!(
+            parse_segments("(echo hi; make 2>&1) >> build.log").unwrap(),
+            vec![
+                segment(&["echo", "hi"], "echo hi", None, &[0], true),
+                segment(
+                    &["make"],
+                    "make 2>&1",
+                    Some(ShellConnector::Sequence),
+                    &[0],
+                    true
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn segments_reject_unsupported_constructs() {
+        assert_eq!(parse_segments("echo $(pwd)"), None);
+        assert_eq!(parse_segments("cat > out.txt <<'EOF'\nhi\nEOF"), None);
+        assert_eq!(parse_segments("sleep 1 &"), None);
+        assert_eq!(parse_segments("for f in a b; do cat $f; done"), None);
+        assert_eq!(parse_segments("ls > $OUT"), None);
+        assert_eq!(parse_segments("ls &&"), None);
+    }
+
     #[test]
     fn parse_zsh_lc_plain_commands() {
         let command = vec!["zsh".to_string(), "-lc".to_string(), "ls".to_string()];
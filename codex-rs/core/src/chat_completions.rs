@@ -14,6 +14,7 @@ use crate::error::UnexpectedResponseError;
 use crate::model_family::ModelFamily;
 use crate::tools::spec::create_tools_json_for_chat_completions_api;
 use crate::util::backoff;
+use crate::util::redact_url;
 use bytes::Bytes;
 use codex_otel::otel_event_manager::OtelEventManager;
 use codex_protocol::models::ContentItem;
@@ -22,11 +23,13 @@ use codex_protocol::models::ReasoningItemContent;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::SessionSource;
 use codex_protocol::protocol::SubAgentSource;
+use codex_protocol::protocol::TokenUsage;
 use eventsource_stream::Eventsource;
 use futures::Stream;
 use futures::StreamExt;
 use futures::TryStreamExt;
 use reqwest::StatusCode;
+use serde::Deserialize;
 use serde_json::json;
 use std::pin::Pin;
 use std::task::Context;
@@ -330,16 +333,24 @@ pub(crate) async fn stream_chat_completions(
     }
 
     let tools_json = create_tools_json_for_chat_completions_api(&prompt.tools)?;
-    let payload = json!({
+    let mut payload = json!({
         "model": model_family.slug,
         "messages": messages,
         "stream": true,
+        // Ask for a trailing usage chunk; providers that do not support it
+        // ignore the option and usage is estimated downstream.
+        "stream_options": { "include_usage": true },
         "tools": tools_json,
     });
+    if let Some(num_ctx) = provider.num_ctx
+        && let Some(obj) = payload.as_object_mut()
+    {
+        obj.insert("options".to_string(), json!({ "num_ctx": num_ctx }));
+    }
 
     debug!(
         "POST to {}: {}",
-        provider.get_full_url(&None),
+        redact_url(&provider.get_full_url(&None)),
         payload.to_string()
     );
 
@@ -490,6 +501,48 @@ async fn append_reasoning_text(
             .await;
     }
 }
+#[derive(Debug, Deserialize)]
+struct ChatUsage {
+    #[serde(default)]
+    prompt_tokens: i64,
+    #[serde(default)]
+    completion_tokens: i64,
+    #[serde(default)]
+    total_tokens: i64,
+    prompt_tokens_details: Option<ChatPromptTokensDetails>,
+    completion_tokens_details: Option<ChatCompletionTokensDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatPromptTokensDetails {
+    #[serde(default)]
+    cached_tokens: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionTokensDetails {
+    #[serde(default)]
+    reasoning_tokens: i64,
+}
+
+impl From<ChatUsage> for TokenUsage {
+    fn from(val: ChatUsage) -> Self {
+        TokenUsage {
+            input_tokens: val.prompt_tokens,
+            cached_input_tokens: val
+                .prompt_tokens_details
+                .map(|d| d.cached_tokens)
+                .unwrap_or(0),
+            output_tokens: val.completion_tokens,
+            reasoning_output_tokens: val
+                .completion_tokens_details
+                .map(|d| d.reasoning_tokens)
+                .unwrap_or(0),
+            total_tokens: val.total_tokens,
+        }
+    }
+}
+
 /// Lightweight SSE processor for the Chat Completions streaming format. The
 /// output is mapped onto Codex's internal [`ResponseEvent`] so that the rest
 /// of the pipeline can stay agnostic of the underlying wire format.
@@ -518,7 +571,13 @@ async fn process_chat_sse<S>(
 
     let mut fn_call_state = FunctionCallState::default();
     let mut assistant_item: Option<ResponseItem> = None;
+    // Providers that report usage attach it to the last chunk(s); absent usage
+    // is estimated downstream.
+    let mut token_usage: Option<TokenUsage> = None;
     let mut reasoning_item: Option<ResponseItem> = None;
+    // Set once a choice reports `finish_reason`. With `include_usage` the usage
+    // arrives in a separate chunk after that, so keep reading until it does.
+    let mut finished = false;
 
     loop {
         let start = std::time::Instant::now();
@@ -539,7 +598,17 @@ async fn process_chat_sse<S>(
                 let _ = tx_event
                     .send(Ok(ResponseEvent::Completed {
                         response_id: String::new(),
-                        token_usage: None,
+                        token_usage: token_usage.take(),
+                    }))
+                    .await;
+                return;
+            }
+            Err(_) if finished => {
+                // The response is complete; only the usage chunk never came.
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::Completed {
+                        response_id: String::new(),
+                        token_usage: token_usage.take(),
                     }))
                     .await;
                 return;
@@ -570,7 +639,7 @@ async fn process_chat_sse<S>(
             let _ = tx_event
                 .send(Ok(ResponseEvent::Completed {
                     response_id: String::new(),
-                    token_usage: None,
+                    token_usage: token_usage.take(),
                 }))
                 .await;
             return;
@@ -583,6 +652,26 @@ async fn process_chat_sse<S>(
         };
         trace!("chat_completions received SSE chunk: {chunk:?}");
 
+        if let Some(usage) = chunk.get("usage").filter(|usage| !usage.is_null()) {
+            match serde_json::from_value::<ChatUsage>(usage.clone()) {
+                Ok(usage) => token_usage = Some(usage.into()),
+                Err(e) => debug!("failed to parse chat completions usage: {e}"),
+            }
+        }
+
+        if finished {
+            if token_usage.is_some() {
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::Completed {
+                        response_id: String::new(),
+                        token_usage: token_usage.take(),
+                    }))
+                    .await;
+                return;
+            }
+            continue;
+        }
+
         let choice_opt = chunk.get("choices").and_then(|c| c.get(0));
 
         if let Some(choice) = choice_opt {
@@ -710,11 +799,16 @@ async fn process_chat_sse<S>(
                     _ => {}
                 }
 
-                // Emit Completed regardless of reason so the agent can advance.
+                // Emit Completed regardless of reason so the agent can advance,
+                // unless the usage chunk is still to come.
+                if token_usage.is_none() {
+                    finished = true;
+                    continue;
+                }
                 let _ = tx_event
                     .send(Ok(ResponseEvent::Completed {
                         response_id: String::new(),
-                        token_usage: None,
+                        token_usage: token_usage.take(),
                     }))
                     .await;
 
@@ -55,14 +55,18 @@ use crate::flags::CODEX_RS_SSE_FIXTURE;
 use crate::model_family::ModelFamily;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::WireApi;
+use crate::model_replay::ModelReplay;
 use crate::openai_model_info::get_model_info;
 use crate::protocol::CreditsSnapshot;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::RateLimitWindow;
 use crate::protocol::TokenUsage;
+use crate::stream_resume::StreamRetryBudget;
+use crate::stream_resume::resume_on_disconnect;
 use crate::token_data::PlanType;
 use crate::tools::spec::create_tools_json_for_responses_api;
 use crate::util::backoff;
+use crate::util::redact_url;
 
 #[derive(Debug, Deserialize)]
 struct ErrorResponse {
@@ -103,6 +107,7 @@ pub struct ModelClient {
     effort: Option<ReasoningEffortConfig>,
     summary: ReasoningSummaryConfig,
     session_source: SessionSource,
+    replay: Option<Arc<ModelReplay>>,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -129,9 +134,22 @@ impl ModelClient {
             effort,
             summary,
             session_source,
+            replay: None,
         }
     }
 
+    /// Answers requests from `replay` when the provider speaks
+    /// [`WireApi::Replay`]. Clients for one session share it, so each request
+    /// gets the next recorded response.
+    pub(crate) fn with_replay(mut self, replay: Option<Arc<ModelReplay>>) -> Self {
+        self.replay = replay;
+        self
+    }
+
+    pub(crate) fn replay(&self) -> Option<&Arc<ModelReplay>> {
+        self.replay.as_ref()
+    }
+
     pub fn get_model_context_window(&self) -> Option<i64> {
         let pct = self.config.model_family.effective_context_window_percent;
         self.config
@@ -155,50 +173,87 @@ impl ModelClient {
     }
 
     pub async fn stream(&self, prompt: &Prompt) -> Result<ResponseStream> {
+        let budget = StreamRetryBudget::new(self.provider.stream_max_retries());
+        self.stream_with_retry_budget(prompt, budget).await
+    }
+
+    /// Like [`Self::stream`], with reconnects drawing on `budget`, which the
+    /// caller can share with its own retries.
+    pub(crate) async fn stream_with_retry_budget(
+        &self,
+        prompt: &Prompt,
+        budget: StreamRetryBudget,
+    ) -> Result<ResponseStream> {
         match self.provider.wire_api {
-            WireApi::Responses => self.stream_responses(prompt).await,
+            WireApi::Responses => self.stream_responses(prompt, budget).await,
+            WireApi::Replay => match &self.replay {
+                Some(replay) => replay.next_response(
+                    self.provider.stream_idle_timeout(),
+                    self.otel_event_manager.clone(),
+                ),
+                None => Err(CodexErr::Fatal(format!(
+                    "model provider `{}` can only answer from a recording",
+                    self.provider.name
+                ))),
+            },
             WireApi::Chat => {
-                // Create the raw streaming connection first.
-                let response_stream = stream_chat_completions(
-                    prompt,
-                    &self.config.model_family,
-                    &self.client,
-                    &self.provider,
-                    &self.otel_event_manager,
-                    &self.session_source,
-                )
-                .await?;
-
-                // Wrap it with the aggregation adapter so callers see *only*
-                // the final assistant message per turn (matching the
-                // behaviour of the Responses API).
-                let mut aggregated = if self.config.show_raw_agent_reasoning {
-                    crate::chat_completions::AggregatedChatStream::streaming_mode(response_stream)
-                } else {
-                    response_stream.aggregate()
-                };
+                let stream = self.stream_chat(prompt).await?;
+                let client = self.clone();
+                let prompt = prompt.clone();
+                Ok(resume_on_disconnect(stream, budget, move || {
+                    let client = client.clone();
+                    let prompt = prompt.clone();
+                    async move { client.stream_chat(&prompt).await }
+                }))
+            }
+        }
+    }
+
+    /// Implementation for the Chat Completions API.
+    async fn stream_chat(&self, prompt: &Prompt) -> Result<ResponseStream> {
+        // Create the raw streaming connection first.
+        let response_stream = stream_chat_completions(
+            prompt,
+            &self.config.model_family,
+            &self.client,
+            &self.provider,
+            &self.otel_event_manager,
+            &self.session_source,
+        )
+        .await?;
 
-                // Bridge the aggregated stream back into a standard
-                // `ResponseStream` by forwarding events through a channel.
-                let (tx, rx) = mpsc::channel::<Result<ResponseEvent>>(16);
+        // Wrap it with the aggregation adapter so callers see *only*
+        // the final assistant message per turn (matching the
+        // behaviour of the Responses API).
+        let mut aggregated = if self.config.show_raw_agent_reasoning {
+            crate::chat_completions::AggregatedChatStream::streaming_mode(response_stream)
+        } else {
+            response_stream.aggregate()
+        };
 
-                tokio::spawn(async move {
-                    use futures::StreamExt;
-                    while let Some(ev) = aggregated.next().await {
-                        // Exit early if receiver hung up.
-                        if tx.send(ev).await.is_err() {
-                            break;
-                        }
-                    }
-                });
+        // Bridge the aggregated stream back into a standard
+        // `ResponseStream` by forwarding events through a channel.
+        let (tx, rx) = mpsc::channel::<Result<ResponseEvent>>(16);
 
-                Ok(ResponseStream { rx_event: rx })
+        tokio::spawn(async move {
+            use futures::StreamExt;
+            while let Some(ev) = aggregated.next().await {
+                // Exit early if receiver hung up.
+                if tx.send(ev).await.is_err() {
+                    break;
+                }
             }
-        }
+        });
+
+        Ok(ResponseStream { rx_event: rx })
     }
 
     /// Implementation for the OpenAI *Responses* experimental API.
-    async fn stream_responses(&self, prompt: &Prompt) -> Result<ResponseStream> {
+    async fn stream_responses(
+        &self,
+        prompt: &Prompt,
+        budget: StreamRetryBudget,
+    ) -> Result<ResponseStream> {
         if let Some(path) = &*CODEX_RS_SSE_FIXTURE {
             // short circuit for tests
             warn!(path, "Streaming from fixture");
@@ -210,8 +265,6 @@ impl ModelClient {
             .await;
         }
 
-        let auth_manager = self.auth_manager.clone();
-
         let full_instructions = prompt.get_full_instructions(&self.config.model_family);
         let tools_json: Vec<Value> = create_tools_json_for_responses_api(&prompt.tools)?;
 
@@ -280,10 +333,23 @@ impl ModelClient {
             attach_item_ids(&mut payload_json, &input_with_instructions);
         }
 
+        let stream = self.connect_responses(&payload_json).await?;
+        let client = self.clone();
+        Ok(resume_on_disconnect(stream, budget, move || {
+            let client = client.clone();
+            let payload_json = payload_json.clone();
+            async move { client.connect_responses(&payload_json).await }
+        }))
+    }
+
+    /// Start a Responses API stream for `payload_json`, retrying connection
+    /// failures, 429s and 5xx responses. Other 4xx responses fail at once.
+    async fn connect_responses(&self, payload_json: &Value) -> Result<ResponseStream> {
+        let auth_manager = self.auth_manager.clone();
         let max_attempts = self.provider.request_max_retries();
         for attempt in 0..=max_attempts {
             match self
-                .attempt_stream_responses(attempt, &payload_json, &auth_manager)
+                .attempt_stream_responses(attempt, payload_json, &auth_manager)
                 .await
             {
                 Ok(stream) => {
@@ -317,7 +383,7 @@ impl ModelClient {
 
         trace!(
             "POST to {}: {}",
-            self.provider.get_full_url(&auth),
+            redact_url(&self.provider.get_full_url(&auth)),
             payload_json.to_string()
         );
 
@@ -560,7 +626,7 @@ impl ModelClient {
                 "POST to {}: {}",
                 self.provider
                     .get_compact_url(&auth)
-                    .unwrap_or("<none>".to_string()),
+                    .map_or_else(|| "<none>".to_string(), |url| redact_url(&url)),
                 serde_json::to_value(&payload).unwrap_or_default()
             );
         }
@@ -800,7 +866,7 @@ fn parse_header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
     headers.get(name)?.to_str().ok()
 }
 
-async fn process_sse<S>(
+pub(crate) async fn process_sse<S>(
     stream: S,
     tx_event: mpsc::Sender<Result<ResponseEvent>>,
     idle_timeout: Duration,
@@ -1250,6 +1316,7 @@ mod tests {
             stream_max_retries: Some(0),
             stream_idle_timeout_ms: Some(1000),
             requires_openai_auth: false,
+            num_ctx: None,
         };
 
         let otel_event_manager = otel_event_manager();
@@ -1314,6 +1381,7 @@ mod tests {
             stream_max_retries: Some(0),
             stream_idle_timeout_ms: Some(1000),
             requires_openai_auth: false,
+            num_ctx: None,
         };
 
         let otel_event_manager = otel_event_manager();
@@ -1351,6 +1419,7 @@ mod tests {
             stream_max_retries: Some(0),
             stream_idle_timeout_ms: Some(1000),
             requires_openai_auth: false,
+            num_ctx: None,
         };
 
         let otel_event_manager = otel_event_manager();
@@ -1390,6 +1459,7 @@ mod tests {
             stream_max_retries: Some(0),
             stream_idle_timeout_ms: Some(1000),
             requires_openai_auth: false,
+            num_ctx: None,
         };
 
         let otel_event_manager = otel_event_manager();
@@ -1425,6 +1495,7 @@ mod tests {
             stream_max_retries: Some(0),
             stream_idle_timeout_ms: Some(1000),
             requires_openai_auth: false,
+            num_ctx: None,
         };
 
         let otel_event_manager = otel_event_manager();
@@ -1460,6 +1531,7 @@ mod tests {
             stream_max_retries: Some(0),
             stream_idle_timeout_ms: Some(1000),
             requires_openai_auth: false,
+            num_ctx: None,
         };
 
         let otel_event_manager = otel_event_manager();
@@ -1564,6 +1636,7 @@ mod tests {
                 stream_max_retries: Some(0),
                 stream_idle_timeout_ms: Some(1000),
                 requires_openai_auth: false,
+                num_ctx: None,
             };
 
             let otel_event_manager = otel_event_manager();
@@ -1,8 +1,12 @@
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::Weak;
 use std::sync::atomic::AtomicU64;
+use std::time::Duration;
+use std::time::Instant;
 
 use crate::AuthManager;
 use crate::client_common::REVIEW_PROMPT;
@@ -16,6 +20,9 @@ use crate::parse_command::parse_command;
 use crate::parse_turn_item;
 use crate::response_processing::process_items;
 use crate::terminal;
+use crate::token_usage::UsageEstimator;
+use crate::token_usage::normalize_usage;
+use crate::truncate::ToolOutputBudgets;
 use crate::truncate::TruncationPolicy;
 use crate::user_notification::UserNotifier;
 use crate::util::error_or_panic;
@@ -30,6 +37,7 @@ use codex_protocol::protocol::ItemStartedEvent;
 use codex_protocol::protocol::RawResponseItemEvent;
 use codex_protocol::protocol::ReviewRequest;
 use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::SessionEndItem;
 use codex_protocol::protocol::SessionSource;
 use codex_protocol::protocol::TaskStartedEvent;
 use codex_protocol::protocol::TurnAbortReason;
@@ -49,6 +57,8 @@ use serde_json::Value;
 use tokio::sync::Mutex;
 use tokio::sync::RwLock;
 use tokio::sync::oneshot;
+use tokio::sync::watch;
+use tokio::task::AbortHandle;
 use tokio_util::sync::CancellationToken;
 use tracing::debug;
 use tracing::error;
@@ -59,19 +69,27 @@ use crate::ModelProviderInfo;
 use crate::client::ModelClient;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
+use crate::command_allowlist::CommandAllowlist;
 use crate::compact::collect_user_messages;
 use crate::config::Config;
 use crate::config::types::ShellEnvironmentPolicy;
+use crate::config::types::TurnLimits;
 use crate::context_manager::ContextManager;
 use crate::environment_context::EnvironmentContext;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
 #[cfg(test)]
 use crate::exec::StreamOutput;
+use crate::git_worktree::SessionBaseCheck;
+use crate::input_prompt::InputPromptBroker;
 use crate::mcp::auth::compute_auth_statuses;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::mcp_tool_cache::McpToolResultCache;
 use crate::model_family::find_family_for_model;
+use crate::model_provider_info::built_in_model_providers;
+use crate::model_replay::ModelReplay;
 use crate::openai_model_info::get_model_info;
+use crate::project_doc::get_scoped_instructions;
 use crate::project_doc::get_user_instructions;
 use crate::protocol::AgentMessageContentDeltaEvent;
 use crate::protocol::AgentReasoningSectionBreakEvent;
@@ -82,7 +100,11 @@ use crate::protocol::DeprecationNoticeEvent;
 use crate::protocol::ErrorEvent;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
+use crate::protocol::ExecApprovalExplanationEvent;
 use crate::protocol::ExecApprovalRequestEvent;
+use crate::protocol::HistoryFlag;
+use crate::protocol::HistoryFlagSetEvent;
+use crate::protocol::NetworkDestination;
 use crate::protocol::Op;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::ReasoningContentDeltaEvent;
@@ -97,23 +119,34 @@ use crate::protocol::TokenCountEvent;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
 use crate::protocol::TurnDiffEvent;
+use crate::protocol::TurnLimitKind;
 use crate::protocol::WarningEvent;
+use crate::resume_briefing::SessionRepoState;
+use crate::resume_briefing::capture_repo_state;
+use crate::resume_briefing::compute_resume_briefing;
 use crate::rollout::RolloutRecorder;
 use crate::rollout::RolloutRecorderParams;
+use crate::scoped_instructions::ScopedInstructions;
+use crate::scoped_instructions::paths_mentioned_in;
+use crate::scoped_instructions::tool_call_targets;
 use crate::shell;
 use crate::state::ActiveTurn;
 use crate::state::SessionServices;
 use crate::state::SessionState;
+use crate::stream_resume::StreamRetryBudget;
 use crate::tasks::GhostSnapshotTask;
 use crate::tasks::ReviewTask;
 use crate::tasks::SessionTask;
 use crate::tasks::SessionTaskContext;
+use crate::tool_inventory::SessionToolInventory;
 use crate::tools::ToolRouter;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::parallel::ToolCallRuntime;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::tools::spec::ToolsConfig;
 use crate::tools::spec::ToolsConfigParams;
+use crate::turn_budget::OutputMeter;
+use crate::turn_budget::TurnBudget;
 use crate::turn_diff_tracker::TurnDiffTracker;
 use crate::unified_exec::UnifiedExecSessionManager;
 use crate::user_instructions::DeveloperInstructions;
@@ -141,6 +174,41 @@ pub struct Codex {
     pub(crate) next_id: AtomicU64,
     pub(crate) tx_sub: Sender<Submission>,
     pub(crate) rx_event: Receiver<Event>,
+    /// Set when this `Codex` runs its own submission loop, rather than
+    /// forwarding to another one.
+    pub(crate) session_loop: Option<SessionLoop>,
+}
+
+/// Handle on the task running a session's submission loop, used to stop the
+/// session when it does not shut down on its own.
+pub(crate) struct SessionLoop {
+    session: Weak<Session>,
+    abort_handle: AbortHandle,
+    exited: watch::Receiver<bool>,
+}
+
+impl SessionLoop {
+    async fn wait_for_exit(&self) {
+        // The sender is dropped without sending when the task is aborted.
+        let mut exited = self.exited.clone();
+        let _ = exited.wait_for(|exited| *exited).await;
+    }
+
+    /// Aborts the loop and the running turn, then closes the session on the
+    /// loop's behalf.
+    async fn abort(&self) {
+        // While the loop runs it holds the session, so this only fails when
+        // the loop finished on its own after all.
+        let Some(session) = self.session.upgrade() else {
+            return;
+        };
+        self.abort_handle.abort();
+        self.wait_for_exit().await;
+        session.abort_all_tasks(TurnAbortReason::Interrupted).await;
+        if let Err(e) = session.close(true).await {
+            warn!("failed to shutdown rollout recorder: {e}");
+        }
+    }
 }
 
 /// Wrapper returned by [`Codex::spawn`] containing the spawned [`Codex`],
@@ -166,10 +234,12 @@ impl Codex {
         let (tx_event, rx_event) = async_channel::unbounded();
 
         let user_instructions = get_user_instructions(&config).await;
+        let scoped_instructions = Arc::new(get_scoped_instructions(&config).await);
 
         let exec_policy = crate::exec_policy::exec_policy_for(&config.features, &config.codex_home)
             .await
             .map_err(|err| CodexErr::Fatal(format!("failed to load execpolicy: {err}")))?;
+        let replay = ModelReplay::for_config(&config)?.map(Arc::new);
 
         let config = Arc::new(config);
 
@@ -180,6 +250,7 @@ impl Codex {
             model_reasoning_summary: config.model_reasoning_summary,
             developer_instructions: config.developer_instructions.clone(),
             user_instructions,
+            scoped_instructions,
             base_instructions: config.base_instructions.clone(),
             compact_prompt: config.compact_prompt.clone(),
             approval_policy: config.approval_policy,
@@ -189,6 +260,7 @@ impl Codex {
             features: config.features.clone(),
             exec_policy,
             session_source,
+            replay,
         };
 
         // Generate a unique ID for the lifetime of this Codex session.
@@ -209,11 +281,21 @@ impl Codex {
         let conversation_id = session.conversation_id;
 
         // This task will run until Op::Shutdown is received.
-        tokio::spawn(submission_loop(session, config, rx_sub));
+        let (exited_tx, exited) = watch::channel(false);
+        let session_weak = Arc::downgrade(&session);
+        let loop_handle = tokio::spawn(async move {
+            submission_loop(session, config, rx_sub).await;
+            let _ = exited_tx.send(true);
+        });
         let codex = Codex {
             next_id: AtomicU64::new(0),
             tx_sub,
             rx_event,
+            session_loop: Some(SessionLoop {
+                session: session_weak,
+                abort_handle: loop_handle.abort_handle(),
+                exited,
+            }),
         };
 
         Ok(CodexSpawnOk {
@@ -251,6 +333,27 @@ impl Codex {
             .map_err(|_| CodexErr::InternalAgentDied)?;
         Ok(event)
     }
+
+    /// Interrupts the running turn and shuts the session down, waiting up to
+    /// `grace` for it to stop. A session still running after that is aborted
+    /// and its rollout closed here instead. Returns whether the session
+    /// stopped within `grace`.
+    pub async fn shutdown(&self, grace: Duration) -> bool {
+        let stopped = tokio::time::timeout(grace, async {
+            // Errors mean the loop is already gone, which is what we want.
+            let _ = self.submit(Op::Interrupt).await;
+            let _ = self.submit(Op::Shutdown).await;
+            if let Some(session_loop) = &self.session_loop {
+                session_loop.wait_for_exit().await;
+            }
+        })
+        .await
+        .is_ok();
+        if !stopped && let Some(session_loop) = &self.session_loop {
+            session_loop.abort().await;
+        }
+        stopped
+    }
 }
 
 /// Context for an initialized model agent
@@ -278,6 +381,8 @@ pub(crate) struct TurnContext {
     pub(crate) base_instructions: Option<String>,
     pub(crate) compact_prompt: Option<String>,
     pub(crate) user_instructions: Option<String>,
+    /// Path-scoped project doc rules, evaluated against the files each turn touches.
+    pub(crate) scoped_instructions: Arc<ScopedInstructions>,
     pub(crate) approval_policy: AskForApproval,
     pub(crate) sandbox_policy: SandboxPolicy,
     pub(crate) shell_environment_policy: ShellEnvironmentPolicy,
@@ -287,6 +392,8 @@ pub(crate) struct TurnContext {
     pub(crate) tool_call_gate: Arc<ReadinessFlag>,
     pub(crate) exec_policy: Arc<ExecPolicy>,
     pub(crate) truncation_policy: TruncationPolicy,
+    pub(crate) tool_output_budgets: ToolOutputBudgets,
+    pub(crate) turn_limits: TurnLimits,
 }
 
 impl TurnContext {
@@ -320,6 +427,9 @@ pub(crate) struct SessionConfiguration {
     /// Model instructions that are appended to the base instructions.
     user_instructions: Option<String>,
 
+    /// Project doc rules that only apply when matching paths are involved.
+    scoped_instructions: Arc<ScopedInstructions>,
+
     /// Base instructions override.
     base_instructions: Option<String>,
 
@@ -349,6 +459,8 @@ pub(crate) struct SessionConfiguration {
     original_config_do_not_use: Arc<Config>,
     /// Source of the session (cli, vscode, exec, mcp, ...)
     session_source: SessionSource,
+    /// Recording that answers model requests under a `replay` provider.
+    replay: Option<Arc<ModelReplay>>,
 }
 
 impl SessionConfiguration {
@@ -422,7 +534,8 @@ impl Session {
             session_configuration.model_reasoning_summary,
             conversation_id,
             session_configuration.session_source.clone(),
-        );
+        )
+        .with_replay(session_configuration.replay.clone());
 
         let tools_config = ToolsConfig::new(&ToolsConfigParams {
             model_family: &model_family,
@@ -437,6 +550,7 @@ impl Session {
             base_instructions: session_configuration.base_instructions.clone(),
             compact_prompt: session_configuration.compact_prompt.clone(),
             user_instructions: session_configuration.user_instructions.clone(),
+            scoped_instructions: Arc::clone(&session_configuration.scoped_instructions),
             approval_policy: session_configuration.approval_policy,
             sandbox_policy: session_configuration.sandbox_policy.clone(),
             shell_environment_policy: config.shell_environment_policy.clone(),
@@ -446,6 +560,8 @@ impl Session {
             tool_call_gate: Arc::new(ReadinessFlag::new()),
             exec_policy: session_configuration.exec_policy.clone(),
             truncation_policy: TruncationPolicy::new(&per_turn_config),
+            tool_output_budgets: ToolOutputBudgets::new(&per_turn_config),
+            turn_limits: per_turn_config.turn_limits,
         }
     }
 
@@ -532,6 +648,35 @@ impl Session {
             });
         }
 
+        // Built-in providers declare optional headers (e.g. OpenAI-Organization)
+        // that most users never set, so only warn for user-defined ones.
+        let missing_env_http_headers =
+            if built_in_model_providers().contains_key(&config.model_provider_id) {
+                Vec::new()
+            } else {
+                config.model_provider.missing_env_http_headers()
+            };
+        for (header, env_var) in missing_env_http_headers {
+            let provider = &config.model_provider.name;
+            post_session_configured_events.push(Event {
+                id: INITIAL_SUBMIT_ID.to_owned(),
+                msg: EventMsg::Warning(WarningEvent {
+                    message: format!(
+                        "Model provider `{provider}` sets header `{header}` from `{env_var}`, but that environment variable is not set; requests will be sent without it."
+                    ),
+                }),
+            });
+        }
+
+        for message in session_configuration.scoped_instructions.warnings() {
+            post_session_configured_events.push(Event {
+                id: INITIAL_SUBMIT_ID.to_owned(),
+                msg: EventMsg::Warning(WarningEvent {
+                    message: message.clone(),
+                }),
+            });
+        }
+
         let otel_event_manager = OtelEventManager::new(
             conversation_id,
             config.model.as_str(),
@@ -565,14 +710,28 @@ impl Session {
         let services = SessionServices {
             mcp_connection_manager: Arc::new(RwLock::new(McpConnectionManager::default())),
             mcp_startup_cancellation_token: CancellationToken::new(),
+            mcp_tool_cache: Mutex::new(McpToolResultCache::new(config.mcp_tool_cache)),
             unified_exec_manager: UnifiedExecSessionManager::default(),
-            notifier: UserNotifier::new(config.notify.clone()),
+            notifier: UserNotifier::new(config.notify.clone(), config.notification_hooks.clone()),
             rollout: Mutex::new(Some(rollout_recorder)),
             user_shell: default_shell,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager,
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            command_allowlist: Mutex::new(CommandAllowlist::new(
+                &config.active_project.always_allow_commands,
+            )),
+            tool_inventory: SessionToolInventory::spawn(
+                config.persistence.cache_home(&config.codex_home),
+                config.tool_inventory.clone(),
+            ),
+            base_check: SessionBaseCheck::new(config.worktree_base_check.clone()),
+            repo_state: SessionRepoState::default(),
+            input_prompts: Arc::new(InputPromptBroker::new(
+                config.input_prompt_detection.clone(),
+                &session_configuration.session_source,
+            )),
         };
 
         let sess = Arc::new(Session {
@@ -621,12 +780,78 @@ impl Session {
             )
             .await;
 
+        let recorded_repo_state = match &initial_history {
+            InitialHistory::Resumed(resumed) => {
+                resumed.history.iter().rev().find_map(|item| match item {
+                    RolloutItem::RepoState(state) => Some(state.clone()),
+                    _ => None,
+                })
+            }
+            InitialHistory::New | InitialHistory::Forked(_) => None,
+        };
+
+        let housekeeping_config = Arc::clone(&config);
+        tokio::spawn(async move {
+            crate::rollout::archive::run_housekeeping_if_due(&housekeeping_config).await;
+        });
+
         // record_initial_history can emit events. We record only after the SessionConfiguredEvent is emitted.
         sess.record_initial_history(initial_history).await;
 
+        // Tell the user what moved in the repository while the session was
+        // away; the model hears about it in the environment context at the
+        // next turn.
+        if let Some(recorded) = recorded_repo_state {
+            sess.services.repo_state.restore(&recorded);
+            if let Some(briefing) = compute_resume_briefing(&recorded).await {
+                sess.services.repo_state.set_unrecorded(briefing.clone());
+                sess.send_event_raw(Event {
+                    id: INITIAL_SUBMIT_ID.to_owned(),
+                    msg: EventMsg::ResumeBriefing(briefing),
+                })
+                .await;
+            }
+        }
+
+        // Look for a stale worktree in the background; the model hears about
+        // it in the environment context at the next turn.
+        let base_check_sess = Arc::clone(&sess);
+        let cwd = session_configuration.cwd.clone();
+        tokio::spawn(async move {
+            base_check_sess
+                .check_base_divergence(&cwd, INITIAL_SUBMIT_ID)
+                .await;
+        });
+
         Ok(sess)
     }
 
+    /// Runs the worktree base check if it is due and tells the user about a
+    /// new finding.
+    async fn check_base_divergence(&self, cwd: &Path, sub_id: &str) {
+        if let Some(divergence) = self
+            .services
+            .base_check
+            .check_if_due(cwd, Instant::now())
+            .await
+        {
+            self.send_event_raw(Event {
+                id: sub_id.to_string(),
+                msg: EventMsg::BaseDivergence(divergence),
+            })
+            .await;
+        }
+    }
+
+    /// Records the repository state at the end of a task so a later resume
+    /// can tell what changed in the meantime.
+    async fn record_repo_state(&self, turn_context: &TurnContext, touched_paths: Vec<PathBuf>) {
+        let touched = self.services.repo_state.add_touched(touched_paths);
+        let state = capture_repo_state(&turn_context.cwd, &touched).await;
+        self.persist_rollout_items(&[RolloutItem::RepoState(state)])
+            .await;
+    }
+
     pub(crate) fn get_tx_event(&self) -> Sender<Event> {
         self.tx_event.clone()
     }
@@ -700,6 +925,20 @@ impl Session {
                     self.record_into_history(&reconstructed_history, &turn_context)
                         .await;
                 }
+                let resumed_environment = rollout_items.iter().rev().find_map(|item| match item {
+                    RolloutItem::TurnContext(ctx) => Some(EnvironmentContext::new(
+                        Some(ctx.cwd.clone()),
+                        Some(ctx.approval_policy),
+                        Some(ctx.sandbox_policy.clone()),
+                        None,
+                    )),
+                    _ => None,
+                });
+                {
+                    let mut state = self.state.lock().await;
+                    state.restore_turn_flags(&rollout_items);
+                    state.resumed_environment = resumed_environment;
+                }
 
                 // If persisting, persist all rollout items as-is (recorder filters)
                 if persist && !rollout_items.is_empty() {
@@ -748,26 +987,27 @@ impl Session {
         Arc::new(turn_context)
     }
 
-    fn build_environment_update_item(
+    /// The environment changes since the model last saw it, if any.
+    async fn build_environment_update_item(
         &self,
         previous: Option<&Arc<TurnContext>>,
         next: &TurnContext,
     ) -> Option<ResponseItem> {
-        let prev = previous?;
-
-        let prev_context = EnvironmentContext::from(prev.as_ref());
-        let next_context = EnvironmentContext::from(next);
-        if prev_context.equals_except_shell(&next_context) {
-            return None;
-        }
-        Some(ResponseItem::from(EnvironmentContext::diff(
-            prev.as_ref(),
-            next,
-        )))
+        let resumed = self.state.lock().await.resumed_environment.take();
+        let before = match resumed {
+            Some(before) => before,
+            None => EnvironmentContext::from(previous?.as_ref()),
+        };
+        EnvironmentContext::from(next)
+            .changes_since(&before)
+            .map(ResponseItem::from)
     }
 
     /// Persist the event to rollout and send it to clients.
     pub(crate) async fn send_event(&self, turn_context: &TurnContext, msg: EventMsg) {
+        self.services
+            .notifier
+            .on_event(&msg, &self.conversation_id.to_string(), &turn_context.cwd);
         let legacy_source = msg.clone();
         let event = Event {
             id: turn_context.sub_id.clone(),
@@ -859,9 +1099,87 @@ impl Session {
         reason: Option<String>,
         risk: Option<SandboxCommandAssessment>,
     ) -> ReviewDecision {
+        let parsed_cmd = parse_command(&command);
+        let event = ExecApprovalRequestEvent {
+            call_id,
+            turn_id: turn_context.sub_id.clone(),
+            command,
+            cwd,
+            reason,
+            risk,
+            parsed_cmd,
+            network: None,
+            explanation_pending: false,
+        };
+        self.request_exec_approval(turn_context, event).await
+    }
+
+    /// True when `command` matches the project's `always_allow_commands` or a
+    /// pattern the user always allowed earlier in this session.
+    pub(crate) async fn is_command_always_allowed(&self, command: &[String]) -> bool {
+        self.services.command_allowlist.lock().await.allows(command)
+    }
+
+    /// Ask the user whether a command already running in the sandbox may
+    /// connect to `destination`.
+    pub async fn request_network_approval(
+        &self,
+        turn_context: &TurnContext,
+        call_id: String,
+        command: Vec<String>,
+        cwd: PathBuf,
+        destination: NetworkDestination,
+    ) -> ReviewDecision {
+        let parsed_cmd = parse_command(&command);
+        let event = ExecApprovalRequestEvent {
+            call_id,
+            turn_id: turn_context.sub_id.clone(),
+            command,
+            cwd,
+            reason: Some(format!("The command wants to connect to {destination}")),
+            risk: None,
+            parsed_cmd,
+            network: Some(destination),
+            explanation_pending: false,
+        };
+        self.request_exec_approval(turn_context, event).await
+    }
+
+    /// Send `event` and wait for the decision. With `approvals.explain`
+    /// enabled, a summary of the command is requested alongside and sent as
+    /// an `ExecApprovalExplanation` event if it arrives before the decision;
+    /// the decision never waits for it.
+    async fn request_exec_approval(
+        &self,
+        turn_context: &TurnContext,
+        mut event: ExecApprovalRequestEvent,
+    ) -> ReviewDecision {
+        let config = turn_context.client.config();
+        let command = event.command.clone();
+        let cwd = event.cwd.clone();
+        // Network prompts are about the destination, not the command.
+        let explanation = config
+            .approval_explanation
+            .as_ref()
+            .filter(|_| event.network.is_none())
+            .map(|settings| {
+                crate::sandboxing::explanation::explain_command(
+                    Arc::clone(&config),
+                    settings,
+                    turn_context.client.provider().clone(),
+                    Arc::clone(&self.services.auth_manager),
+                    &self.services.otel_event_manager,
+                    self.conversation_id,
+                    turn_context.client.get_session_source(),
+                    &command,
+                    &cwd,
+                )
+            });
+        event.explanation_pending = explanation.is_some();
+        let call_id = event.call_id.clone();
         let sub_id = turn_context.sub_id.clone();
         // Add the tx_approve callback to the map before sending the request.
-        let (tx_approve, rx_approve) = oneshot::channel();
+        let (tx_approve, mut rx_approve) = oneshot::channel();
         let event_id = sub_id.clone();
         let prev_entry = {
             let mut active = self.active_turn.lock().await;
@@ -877,17 +1195,24 @@ impl Session {
             warn!("Overwriting existing pending approval for sub_id: {event_id}");
         }
 
-        let parsed_cmd = parse_command(&command);
-        let event = EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
-            call_id,
-            turn_id: turn_context.sub_id.clone(),
-            command,
-            cwd,
-            reason,
-            risk,
-            parsed_cmd,
-        });
-        self.send_event(turn_context, event).await;
+        self.send_event(turn_context, EventMsg::ExecApprovalRequest(event))
+            .await;
+        let Some(explanation) = explanation else {
+            return rx_approve.await.unwrap_or_default();
+        };
+        tokio::select! {
+            decision = &mut rx_approve => return decision.unwrap_or_default(),
+            explanation = explanation => {
+                self.send_event(
+                    turn_context,
+                    EventMsg::ExecApprovalExplanation(ExecApprovalExplanationEvent {
+                        call_id,
+                        explanation,
+                    }),
+                )
+                .await;
+            }
+        }
         rx_approve.await.unwrap_or_default()
     }
 
@@ -928,6 +1253,33 @@ impl Session {
         rx_approve
     }
 
+    /// Approve the patch awaiting approval in `sub_id`, applying only
+    /// `changes`.
+    pub async fn notify_partial_patch_approval(
+        &self,
+        sub_id: &str,
+        changes: HashMap<PathBuf, FileChange>,
+    ) {
+        if let Some(at) = self.active_turn.lock().await.as_mut() {
+            at.turn_state
+                .lock()
+                .await
+                .insert_patch_selection(sub_id.to_string(), changes);
+        }
+        self.notify_approval(sub_id, ReviewDecision::Approved).await;
+    }
+
+    /// The changes picked with `Op::PartialPatchApproval` for the patch that
+    /// was just approved in `sub_id`, if only part of it was.
+    pub(crate) async fn take_patch_selection(
+        &self,
+        sub_id: &str,
+    ) -> Option<HashMap<PathBuf, FileChange>> {
+        let mut active = self.active_turn.lock().await;
+        let mut ts = active.as_mut()?.turn_state.lock().await;
+        ts.take_patch_selection(sub_id)
+    }
+
     pub async fn notify_approval(&self, sub_id: &str, decision: ReviewDecision) {
         let entry = {
             let mut active = self.active_turn.lock().await;
@@ -949,6 +1301,23 @@ impl Session {
         }
     }
 
+    /// Denies every approval the active turn is waiting on and returns how
+    /// many there were.
+    pub(crate) async fn deny_pending_approvals(&self) -> usize {
+        let pending = {
+            let mut active = self.active_turn.lock().await;
+            match active.as_mut() {
+                Some(at) => at.turn_state.lock().await.take_pending_approvals(),
+                None => Vec::new(),
+            }
+        };
+        let count = pending.len();
+        for tx_approve in pending {
+            tx_approve.send(ReviewDecision::Denied).ok();
+        }
+        count
+    }
+
     /// Records input items: always append to conversation history and
     /// persist these response items to rollout.
     pub(crate) async fn record_conversation_items(
@@ -961,6 +1330,24 @@ impl Session {
         self.send_raw_response_items(turn_context, items).await;
     }
 
+    /// Re-evaluate path-scoped project doc rules against `files` and render the
+    /// matching snippets for the next request. The snippets are never recorded,
+    /// so scopes that stop matching drop out of the prompt again.
+    pub(crate) async fn scoped_instructions_for_prompt<'a>(
+        &self,
+        turn_context: &TurnContext,
+        files: impl IntoIterator<Item = &'a PathBuf>,
+    ) -> Option<ResponseItem> {
+        let scoped = &turn_context.scoped_instructions;
+        if scoped.is_empty() {
+            return None;
+        }
+        let active = scoped.active_indices(files);
+        let rendered = scoped.render(&active);
+        self.state.lock().await.set_active_scopes(active);
+        rendered
+    }
+
     fn reconstruct_history_from_rollout(
         &self,
         turn_context: &TurnContext,
@@ -972,7 +1359,7 @@ impl Session {
                 RolloutItem::ResponseItem(response_item) => {
                     history.record_items(
                         std::iter::once(response_item),
-                        turn_context.truncation_policy,
+                        &turn_context.tool_output_budgets,
                     );
                 }
                 RolloutItem::Compacted(compacted) => {
@@ -981,15 +1368,28 @@ impl Session {
                     if let Some(replacement) = &compacted.replacement_history {
                         history.replace(replacement.clone());
                     } else {
+                        let pinned = history.pinned_messages();
                         let user_messages = collect_user_messages(&snapshot);
                         let rebuilt = compact::build_compacted_history(
                             self.build_initial_context(turn_context),
+                            &pinned,
                             &user_messages,
                             &compacted.message,
                         );
                         history.replace(rebuilt);
                     }
                 }
+                // Pins change what a later compaction keeps, so replay them
+                // in order.
+                RolloutItem::EventMsg(EventMsg::HistoryFlagSet(HistoryFlagSetEvent {
+                    flag:
+                        HistoryFlag::Pinned {
+                            nth_user_message,
+                            pinned,
+                        },
+                })) => {
+                    history.set_turn_pinned(*nth_user_message, *pinned);
+                }
                 _ => {}
             }
         }
@@ -1003,7 +1403,13 @@ impl Session {
         turn_context: &TurnContext,
     ) {
         let mut state = self.state.lock().await;
-        state.record_items(items.iter(), turn_context.truncation_policy);
+        state.record_items(items.iter(), &turn_context.tool_output_budgets);
+    }
+
+    /// User messages that compaction must keep verbatim.
+    pub(crate) async fn pinned_messages(&self) -> Vec<ResponseItem> {
+        let mut state = self.state.lock().await;
+        state.pinned_messages()
     }
 
     pub(crate) async fn replace_history(&self, items: Vec<ResponseItem>) {
@@ -1053,12 +1459,17 @@ impl Session {
                 .into(),
             );
         }
-        items.push(ResponseItem::from(EnvironmentContext::new(
+        let mut environment_context = EnvironmentContext::new(
             Some(turn_context.cwd.clone()),
             Some(turn_context.approval_policy),
             Some(turn_context.sandbox_policy.clone()),
             Some(self.user_shell().clone()),
-        )));
+        );
+        if let Some(inventory) = self.services.tool_inventory.get() {
+            environment_context = environment_context.with_tools(inventory);
+            self.services.tool_inventory.mark_recorded();
+        }
+        items.push(ResponseItem::from(environment_context));
         items
     }
 
@@ -1079,19 +1490,27 @@ impl Session {
         state.clone_history()
     }
 
+    /// Record the usage of a completed model request and emit a `TokenCount`
+    /// event. Usage the provider omitted is estimated from `prompt` and the
+    /// output seen by `estimator`.
     pub(crate) async fn update_token_usage_info(
         &self,
         turn_context: &TurnContext,
         token_usage: Option<&TokenUsage>,
+        prompt: &Prompt,
+        estimator: &UsageEstimator,
     ) {
+        let normalized = normalize_usage(token_usage, || {
+            let model_family = turn_context.client.get_model_family();
+            estimator.estimate(&prompt.input, &prompt.get_full_instructions(&model_family))
+        });
         {
             let mut state = self.state.lock().await;
-            if let Some(token_usage) = token_usage {
-                state.update_token_info_from_usage(
-                    token_usage,
-                    turn_context.client.get_model_context_window(),
-                );
-            }
+            state.update_token_info_from_usage(
+                &normalized.usage,
+                turn_context.client.get_model_context_window(),
+            );
+            state.set_last_usage_source(turn_context.client.get_model(), normalized.estimated);
         }
         self.send_token_count_event(turn_context).await;
     }
@@ -1125,6 +1544,7 @@ impl Session {
             }
 
             state.set_token_info(Some(info));
+            state.set_last_usage_source(turn_context.client.get_model(), true);
         }
         self.send_token_count_event(turn_context).await;
     }
@@ -1134,6 +1554,11 @@ impl Session {
         turn_context: &TurnContext,
         new_rate_limits: RateLimitSnapshot,
     ) {
+        let config = turn_context.client.config();
+        let snapshot = new_rate_limits.clone();
+        tokio::spawn(async move {
+            crate::rate_limit_history::record_snapshot(&config, &snapshot).await;
+        });
         {
             let mut state = self.state.lock().await;
             state.set_rate_limits(new_rate_limits);
@@ -1142,11 +1567,17 @@ impl Session {
     }
 
     async fn send_token_count_event(&self, turn_context: &TurnContext) {
-        let (info, rate_limits) = {
+        let event = {
             let state = self.state.lock().await;
-            state.token_info_and_rate_limits()
+            let (info, rate_limits) = state.token_info_and_rate_limits();
+            TokenCountEvent {
+                info,
+                rate_limits,
+                model: state.last_usage_model.clone(),
+                estimated: state.last_usage_estimated,
+            }
         };
-        let event = EventMsg::TokenCount(TokenCountEvent { info, rate_limits });
+        let event = EventMsg::TokenCount(event);
         self.send_event(turn_context, event).await;
     }
 
@@ -1328,6 +1759,10 @@ impl Session {
         }
     }
 
+    pub(crate) fn conversation_id(&self) -> ConversationId {
+        self.conversation_id
+    }
+
     pub(crate) fn notifier(&self) -> &UserNotifier {
         &self.services.notifier
     }
@@ -1343,6 +1778,26 @@ impl Session {
     async fn cancel_mcp_startup(&self) {
         self.services.mcp_startup_cancellation_token.cancel();
     }
+
+    /// Ends the rollout with a session-end record, flushes and closes it, and
+    /// stops the session's MCP servers.
+    async fn close(&self, forced: bool) -> std::io::Result<()> {
+        self.persist_rollout_items(&[RolloutItem::SessionEnd(SessionEndItem { forced })])
+            .await;
+        let recorder = self.services.rollout.lock().await.take();
+
+        self.cancel_mcp_startup().await;
+        self.services
+            .mcp_connection_manager
+            .write()
+            .await
+            .shutdown();
+
+        match recorder {
+            Some(rec) => rec.shutdown().await,
+            None => Ok(()),
+        }
+    }
 }
 
 async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiver<Submission>) {
@@ -1386,9 +1841,18 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::ExecApproval { id, decision } => {
                 handlers::exec_approval(&sess, id, decision).await;
             }
+            Op::ExecInput { id, response } => {
+                handlers::exec_input(&sess, id, response);
+            }
+            Op::AllowCommandPattern { pattern } => {
+                handlers::allow_command_pattern(&sess, pattern).await;
+            }
             Op::PatchApproval { id, decision } => {
                 handlers::patch_approval(&sess, id, decision).await;
             }
+            Op::PartialPatchApproval { id, changes } => {
+                sess.notify_partial_patch_approval(&id, changes).await;
+            }
             Op::AddToHistory { text } => {
                 handlers::add_to_history(&sess, &config, text).await;
             }
@@ -1402,9 +1866,15 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::ListCustomPrompts => {
                 handlers::list_custom_prompts(&sess, sub.id.clone()).await;
             }
+            Op::ListContextScopes => {
+                handlers::list_context_scopes(&sess, sub.id.clone()).await;
+            }
             Op::Undo => {
                 handlers::undo(&sess, sub.id.clone()).await;
             }
+            Op::SetHistoryFlag { flag } => {
+                handlers::set_history_flag(&sess, sub.id.clone(), flag).await;
+            }
             Op::Compact => {
                 handlers::compact(&sess, sub.id.clone()).await;
             }
@@ -1445,9 +1915,14 @@ mod handlers {
     use crate::tasks::UndoTask;
     use crate::tasks::UserShellCommandTask;
     use codex_protocol::custom_prompts::CustomPrompt;
+    use codex_protocol::protocol::ContextScope;
     use codex_protocol::protocol::ErrorEvent;
     use codex_protocol::protocol::Event;
     use codex_protocol::protocol::EventMsg;
+    use codex_protocol::protocol::ExecInputResponse;
+    use codex_protocol::protocol::HistoryFlag;
+    use codex_protocol::protocol::HistoryFlagSetEvent;
+    use codex_protocol::protocol::ListContextScopesResponseEvent;
     use codex_protocol::protocol::ListCustomPromptsResponseEvent;
     use codex_protocol::protocol::Op;
     use codex_protocol::protocol::ReviewDecision;
@@ -1456,6 +1931,7 @@ mod handlers {
 
     use codex_protocol::user_input::UserInput;
     use std::sync::Arc;
+    use tracing::debug;
     use tracing::info;
     use tracing::warn;
 
@@ -1507,12 +1983,37 @@ mod handlers {
 
         // Attempt to inject input into current task
         if let Err(items) = sess.inject_input(items).await {
-            if let Some(env_item) =
-                sess.build_environment_update_item(previous_context.as_ref(), &current_context)
+            if let Some(env_item) = sess
+                .build_environment_update_item(previous_context.as_ref(), &current_context)
+                .await
             {
                 sess.record_conversation_items(&current_context, std::slice::from_ref(&env_item))
                     .await;
             }
+            // The inventory probe may finish after the initial context was
+            // recorded; share it with the model once, at the next turn.
+            if let Some(inventory) = sess.services.tool_inventory.take_unrecorded() {
+                let tools_item = ResponseItem::from(EnvironmentContext::tools_only(inventory));
+                sess.record_conversation_items(&current_context, std::slice::from_ref(&tools_item))
+                    .await;
+            }
+            sess.check_base_divergence(&current_context.cwd, &current_context.sub_id)
+                .await;
+            if let Some(divergence) = sess.services.base_check.take_unrecorded() {
+                let note_item =
+                    ResponseItem::from(EnvironmentContext::base_divergence_only(&divergence));
+                sess.record_conversation_items(&current_context, std::slice::from_ref(&note_item))
+                    .await;
+            }
+            if let Some(briefing) = sess.services.repo_state.take_unrecorded() {
+                let briefing_item =
+                    ResponseItem::from(EnvironmentContext::resume_briefing_only(&briefing));
+                sess.record_conversation_items(
+                    &current_context,
+                    std::slice::from_ref(&briefing_item),
+                )
+                .await;
+            }
 
             sess.spawn_task(Arc::clone(&current_context), items, RegularTask)
                 .await;
@@ -1547,6 +2048,16 @@ mod handlers {
         }
     }
 
+    pub fn exec_input(sess: &Arc<Session>, id: String, response: ExecInputResponse) {
+        if !sess.services.input_prompts.respond(&id, response) {
+            debug!("no command is waiting for input on call {id}");
+        }
+    }
+
+    pub async fn allow_command_pattern(sess: &Arc<Session>, pattern: String) {
+        sess.services.command_allowlist.lock().await.add(&pattern);
+    }
+
     pub async fn patch_approval(sess: &Arc<Session>, id: String, decision: ReviewDecision) {
         match decision {
             ReviewDecision::Abort => {
@@ -1650,6 +2161,74 @@ mod handlers {
         sess.send_event_raw(event).await;
     }
 
+    pub async fn list_context_scopes(sess: &Session, sub_id: String) {
+        let (scoped, active) = {
+            let state = sess.state.lock().await;
+            (
+                Arc::clone(&state.session_configuration.scoped_instructions),
+                state.active_scopes.clone(),
+            )
+        };
+        let scopes: Vec<ContextScope> = scoped
+            .scopes()
+            .iter()
+            .enumerate()
+            .map(|(idx, scope)| ContextScope {
+                source: scope.source.clone(),
+                patterns: scope.patterns.clone(),
+                active: active.contains(&idx),
+            })
+            .collect();
+
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::ListContextScopesResponse(ListContextScopesResponseEvent { scopes }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
+    pub async fn set_history_flag(sess: &Session, sub_id: String, flag: HistoryFlag) {
+        match flag {
+            HistoryFlag::TurnRelevance {
+                nth_user_message,
+                irrelevant,
+            } => {
+                let mut state = sess.state.lock().await;
+                state.set_turn_irrelevant(nth_user_message, irrelevant);
+            }
+            HistoryFlag::Pinned {
+                nth_user_message,
+                pinned,
+            } => {
+                let applied = sess
+                    .state
+                    .lock()
+                    .await
+                    .set_turn_pinned(nth_user_message, pinned);
+                if !applied {
+                    let event = Event {
+                        id: sub_id,
+                        msg: EventMsg::Warning(WarningEvent {
+                            message: "That message was already folded into a compaction summary, so it cannot be pinned.".to_string(),
+                        }),
+                    };
+                    sess.send_event_raw(event).await;
+                    return;
+                }
+            }
+            HistoryFlag::ClearedAbove
+            | HistoryFlag::Uncleared
+            | HistoryFlag::CellDeleted { .. }
+            | HistoryFlag::OutputCellDeleted { .. } => {}
+        }
+
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::HistoryFlagSet(HistoryFlagSetEvent { flag }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
     pub async fn undo(sess: &Arc<Session>, sub_id: String) {
         let turn_context = sess
             .new_turn_with_sub_id(sub_id, SessionSettingsUpdate::default())
@@ -1679,13 +2258,7 @@ mod handlers {
 
         // Gracefully flush and shutdown rollout recorder on session end so tests
         // that inspect the rollout file do not race with the background writer.
-        let recorder_opt = {
-            let mut guard = sess.services.rollout.lock().await;
-            guard.take()
-        };
-        if let Some(rec) = recorder_opt
-            && let Err(e) = rec.shutdown().await
-        {
+        if let Err(e) = sess.close(false).await {
             warn!("failed to shutdown rollout recorder: {e}");
             let event = Event {
                 id: sub_id.clone(),
@@ -1779,7 +2352,8 @@ async fn spawn_review_thread(
         per_turn_config.model_reasoning_summary,
         sess.conversation_id,
         parent_turn_context.client.get_session_source(),
-    );
+    )
+    .with_replay(parent_turn_context.client.replay().cloned());
 
     let review_turn_context = TurnContext {
         sub_id: sub_id.to_string(),
@@ -1787,6 +2361,7 @@ async fn spawn_review_thread(
         tools_config,
         developer_instructions: None,
         user_instructions: None,
+        scoped_instructions: Arc::default(),
         base_instructions: Some(base_instructions.clone()),
         compact_prompt: parent_turn_context.compact_prompt.clone(),
         approval_policy: parent_turn_context.approval_policy,
@@ -1798,6 +2373,8 @@ async fn spawn_review_thread(
         tool_call_gate: Arc::new(ReadinessFlag::new()),
         exec_policy: parent_turn_context.exec_policy.clone(),
         truncation_policy: TruncationPolicy::new(&per_turn_config),
+        tool_output_budgets: ToolOutputBudgets::new(&per_turn_config),
+        turn_limits: per_turn_config.turn_limits,
     };
 
     // Seed the child task with the review prompt as the initial user message.
@@ -1845,6 +2422,7 @@ pub(crate) async fn run_task(
     });
     sess.send_event(&turn_context, event).await;
 
+    let mentioned_paths = paths_mentioned_in(&input, &turn_context.cwd);
     let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input);
     sess.record_input_and_rollout_usermsg(turn_context.as_ref(), &initial_input_for_turn)
         .await;
@@ -1855,8 +2433,28 @@ pub(crate) async fn run_task(
     // Although from the perspective of codex.rs, TurnDiffTracker has the lifecycle of a Task which contains
     // many turns, from the perspective of the user, it is a single turn.
     let turn_diff_tracker = Arc::new(tokio::sync::Mutex::new(TurnDiffTracker::new()));
+    let mut budget = TurnBudget::new(turn_context.turn_limits, Instant::now());
+    // Sent with the wrap-up request only; never recorded in history.
+    let mut wrap_up_instructions: Option<ResponseItem> = None;
+
+    // Files named by the tool calls the model has made so far this turn.
+    let mut planned_paths: Vec<PathBuf> = Vec::new();
 
     loop {
+        // Scoped instructions follow the files this turn is about: paths the
+        // user mentioned, targets of the model's tool calls, and anything the
+        // agent has patched so far.
+        let touched_paths = turn_diff_tracker.lock().await.touched_paths();
+        let scoped_instructions = sess
+            .scoped_instructions_for_prompt(
+                &turn_context,
+                mentioned_paths
+                    .iter()
+                    .chain(planned_paths.iter())
+                    .chain(touched_paths.iter()),
+            )
+            .await;
+
         // Note that pending_input would be something like a message the user
         // submitted through the UI while the model was running. Though the UI
         // may support this, the model might not.
@@ -1871,7 +2469,10 @@ pub(crate) async fn run_task(
         let turn_input: Vec<ResponseItem> = {
             sess.record_conversation_items(&turn_context, &pending_input)
                 .await;
-            sess.clone_history().await.get_history_for_prompt()
+            let mut history = sess.clone_history().await.get_history_for_prompt();
+            history.extend(scoped_instructions);
+            history.extend(wrap_up_instructions.clone());
+            history
         };
 
         let turn_input_messages = turn_input
@@ -1882,20 +2483,35 @@ pub(crate) async fn run_task(
             })
             .map(|user_message| user_message.message())
             .collect::<Vec<String>>();
-        match run_turn(
+        let request_cancellation = cancellation_token.child_token();
+        let deadline = budget.deadline().map(|deadline| {
+            spawn_turn_deadline(
+                Arc::clone(&sess),
+                Arc::clone(&turn_context),
+                deadline,
+                request_cancellation.clone(),
+            )
+        });
+        let output_meter = budget.output_meter(request_cancellation.clone());
+        let turn_result = run_turn(
             Arc::clone(&sess),
             Arc::clone(&turn_context),
             Arc::clone(&turn_diff_tracker),
             turn_input,
-            cancellation_token.child_token(),
+            request_cancellation,
+            output_meter.as_ref(),
         )
-        .await
-        {
+        .await;
+        if let Some(deadline) = deadline {
+            deadline.abort();
+        }
+        match turn_result {
             Ok(turn_output) => {
                 let TurnRunResult {
                     processed_items,
                     total_token_usage,
                 } = turn_output;
+                budget.record_usage(total_token_usage.as_ref());
                 let limit = turn_context
                     .client
                     .get_auto_compact_token_limit()
@@ -1908,6 +2524,23 @@ pub(crate) async fn run_task(
                     .unwrap_or(false);
                 let (responses, items_to_record_in_conversation_history) =
                     process_items(processed_items, &sess, &turn_context).await;
+                for item in &items_to_record_in_conversation_history {
+                    for path in tool_call_targets(item, &turn_context.cwd) {
+                        if !planned_paths.contains(&path) {
+                            planned_paths.push(path);
+                        }
+                    }
+                }
+
+                // The wrap-up request was the last one this turn gets.
+                if let Some(reached) = budget.reached() {
+                    last_agent_message = get_last_assistant_message_from_turn(
+                        &items_to_record_in_conversation_history,
+                    );
+                    sess.send_event(&turn_context, EventMsg::TurnLimitReached(reached))
+                        .await;
+                    break;
+                }
 
                 // as long as compaction works well in getting us way below the token limit, we shouldn't worry about being in an infinite loop.
                 if token_limit_reached {
@@ -1934,13 +2567,32 @@ pub(crate) async fn run_task(
                         });
                     break;
                 }
+                if let Some(limit) = budget.check(Instant::now()) {
+                    wrap_up_instructions = Some(wrap_up_message(&budget, limit));
+                }
                 continue;
             }
             Err(CodexErr::TurnAborted {
                 dangling_artifacts: processed_items,
             }) => {
                 let _ = process_items(processed_items, &sess, &turn_context).await;
-                // Aborted turn is reported via a different event.
+                if cancellation_token.is_cancelled() {
+                    // Aborted turn is reported via a different event.
+                    break;
+                }
+                // Otherwise the request ran into the turn deadline or
+                // streamed past the output limit.
+                if let Some(meter) = &output_meter {
+                    budget.record_streamed(meter.streamed());
+                }
+                if let Some(limit) = budget.check(Instant::now()) {
+                    wrap_up_instructions = Some(wrap_up_message(&budget, limit));
+                    continue;
+                }
+                if let Some(reached) = budget.reached() {
+                    sess.send_event(&turn_context, EventMsg::TurnLimitReached(reached))
+                        .await;
+                }
                 break;
             }
             Err(e) => {
@@ -1955,15 +2607,45 @@ pub(crate) async fn run_task(
         }
     }
 
+    let touched_paths = turn_diff_tracker.lock().await.touched_paths();
+    sess.record_repo_state(&turn_context, touched_paths).await;
+
     last_agent_message
 }
 
+/// Cancels the in-flight model request at `deadline`, first denying any
+/// approval it is still waiting on.
+fn spawn_turn_deadline(
+    sess: Arc<Session>,
+    turn_context: Arc<TurnContext>,
+    deadline: Instant,
+    cancellation_token: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        tokio::time::sleep_until(deadline.into()).await;
+        if sess.deny_pending_approvals().await > 0 {
+            let message =
+                "The turn ran out of time, so the pending approval was denied.".to_string();
+            sess.send_event(&turn_context, EventMsg::Warning(WarningEvent { message }))
+                .await;
+        }
+        cancellation_token.cancel();
+    })
+}
+
+/// Developer message asking the model to stop and summarize, once a turn
+/// limit is hit.
+fn wrap_up_message(budget: &TurnBudget, limit: TurnLimitKind) -> ResponseItem {
+    DeveloperInstructions::new(budget.wrap_up_instructions(limit)).into()
+}
+
 async fn run_turn(
     sess: Arc<Session>,
     turn_context: Arc<TurnContext>,
     turn_diff_tracker: SharedTurnDiffTracker,
     input: Vec<ResponseItem>,
     cancellation_token: CancellationToken,
+    output_meter: Option<&OutputMeter>,
 ) -> CodexResult<TurnRunResult> {
     let mcp_tools = sess
         .services
@@ -2016,7 +2698,9 @@ async fn run_turn(
         output_schema: turn_context.final_output_json_schema.clone(),
     };
 
-    let mut retries = 0;
+    // Turn retries and stream reconnects share one budget.
+    let retry_budget =
+        StreamRetryBudget::new(turn_context.client.get_provider().stream_max_retries());
     loop {
         match try_run_turn(
             Arc::clone(&router),
@@ -2024,7 +2708,9 @@ async fn run_turn(
             Arc::clone(&turn_context),
             Arc::clone(&turn_diff_tracker),
             &prompt,
+            retry_budget.clone(),
             cancellation_token.child_token(),
+            output_meter,
         )
         .await
         {
@@ -2055,9 +2741,8 @@ async fn run_turn(
             Err(e @ CodexErr::RefreshTokenFailed(_)) => return Err(e),
             Err(e) => {
                 // Use the configured provider-specific stream retry budget.
-                let max_retries = turn_context.client.get_provider().stream_max_retries();
-                if retries < max_retries {
-                    retries += 1;
+                if let Some(retries) = retry_budget.take() {
+                    let max_retries = retry_budget.max();
                     let delay = match e {
                         CodexErr::Stream(_, Some(delay)) => delay,
                         _ => backoff(retries),
@@ -2107,7 +2792,9 @@ async fn try_run_turn(
     turn_context: Arc<TurnContext>,
     turn_diff_tracker: SharedTurnDiffTracker,
     prompt: &Prompt,
+    retry_budget: StreamRetryBudget,
     cancellation_token: CancellationToken,
+    output_meter: Option<&OutputMeter>,
 ) -> CodexResult<TurnRunResult> {
     let rollout_item = RolloutItem::TurnContext(TurnContextItem {
         cwd: turn_context.cwd.clone(),
@@ -2122,7 +2809,7 @@ async fn try_run_turn(
     let mut stream = turn_context
         .client
         .clone()
-        .stream(prompt)
+        .stream_with_retry_budget(prompt, retry_budget)
         .or_cancel(&cancellation_token)
         .await??;
 
@@ -2136,6 +2823,7 @@ async fn try_run_turn(
         FuturesOrdered::new();
 
     let mut active_item: Option<TurnItem> = None;
+    let mut usage_estimator = UsageEstimator::default();
 
     loop {
         // Poll the next item from the model stream. We must inspect *both* Ok and Err
@@ -2161,6 +2849,15 @@ async fn try_run_turn(
             }
         };
 
+        if let Some(meter) = output_meter {
+            match &event {
+                ResponseEvent::OutputTextDelta(delta)
+                | ResponseEvent::ReasoningSummaryDelta { delta, .. }
+                | ResponseEvent::ReasoningContentDelta { delta, .. } => meter.record_delta(delta),
+                _ => {}
+            }
+        }
+
         let add_completed = &mut |response_item: ProcessedResponseItem| {
             output.push_back(future::ready(Ok(response_item)).boxed());
         };
@@ -2168,6 +2865,7 @@ async fn try_run_turn(
         match event {
             ResponseEvent::Created => {}
             ResponseEvent::OutputItemDone(item) => {
+                usage_estimator.record_output(&item);
                 let previously_active_item = active_item.take();
                 match ToolRouter::build_tool_call(sess.as_ref(), item.clone()).await {
                     Ok(Some(call)) => {
@@ -2258,15 +2956,23 @@ async fn try_run_turn(
                 response_id: _,
                 token_usage,
             } => {
-                sess.update_token_usage_info(&turn_context, token_usage.as_ref())
-                    .await;
+                sess.update_token_usage_info(
+                    &turn_context,
+                    token_usage.as_ref(),
+                    prompt,
+                    &usage_estimator,
+                )
+                .await;
                 let processed_items = output.try_collect().await?;
-                let unified_diff = {
+                let (unified_diff, files) = {
                     let mut tracker = turn_diff_tracker.lock().await;
-                    tracker.get_unified_diff()
+                    (tracker.get_unified_diff(), tracker.changed_files())
                 };
                 if let Ok(Some(unified_diff)) = unified_diff {
-                    let msg = EventMsg::TurnDiff(TurnDiffEvent { unified_diff });
+                    let msg = EventMsg::TurnDiff(TurnDiffEvent {
+                        unified_diff,
+                        files,
+                    });
                     sess.send_event(&turn_context, msg).await;
                 }
 
@@ -2463,6 +3169,47 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[tokio::test]
+    async fn scoped_instructions_drop_out_when_their_paths_are_no_longer_touched() {
+        let (session, mut turn_context) = make_session_and_context();
+        let mut scoped = ScopedInstructions::default();
+        scoped.extend(crate::scoped_instructions::parse_project_doc(
+            "```scopes\n[[scope]]\npaths = [\"migrations/\"]\ninstructions = \"Regenerate the schema.\"\n```",
+            Path::new("/repo/AGENTS.md"),
+        ));
+        turn_context.scoped_instructions = Arc::new(scoped.clone());
+
+        let migration = PathBuf::from("/repo/migrations/0001.sql");
+        let source = PathBuf::from("/repo/src/main.rs");
+        let first = session
+            .scoped_instructions_for_prompt(&turn_context, [&migration])
+            .await;
+        let second = session
+            .scoped_instructions_for_prompt(&turn_context, [&source])
+            .await;
+
+        assert_eq!((first, second), (scoped.render(&[0]), None));
+        assert_eq!(
+            session.state.lock().await.active_scopes,
+            Vec::<usize>::new()
+        );
+        assert_eq!(session.clone_history().await.get_history(), Vec::new());
+    }
+
+    #[tokio::test]
+    async fn allowed_command_patterns_apply_for_the_rest_of_the_session() {
+        let (session, _turn_context) = make_session_and_context();
+        let session = Arc::new(session);
+        let command: Vec<String> = ["cargo", "test", "-p", "codex-core"]
+            .map(String::from)
+            .to_vec();
+        assert!(!session.is_command_always_allowed(&command).await);
+
+        handlers::allow_command_pattern(&session, "cargo test".to_string()).await;
+
+        assert!(session.is_command_always_allowed(&command).await);
+    }
+
     #[test]
     fn prefers_structured_content_when_present() {
         let ctr = CallToolResult {
@@ -2496,6 +3243,7 @@ mod tests {
             stdout: StreamOutput::new(String::new()),
             stderr: StreamOutput::new(String::new()),
             aggregated_output: StreamOutput::new("Command output".to_string()),
+            stderr_ranges: Vec::new(),
             duration: StdDuration::from_secs(1),
             timed_out: true,
         };
@@ -2610,6 +3358,7 @@ mod tests {
             model_reasoning_summary: config.model_reasoning_summary,
             developer_instructions: config.developer_instructions.clone(),
             user_instructions: config.user_instructions.clone(),
+            scoped_instructions: Arc::default(),
             base_instructions: config.base_instructions.clone(),
             compact_prompt: config.compact_prompt.clone(),
             approval_policy: config.approval_policy,
@@ -2619,6 +3368,7 @@ mod tests {
             features: Features::default(),
             exec_policy: Arc::new(ExecPolicy::empty()),
             session_source: SessionSource::Exec,
+            replay: None,
         };
 
         let state = SessionState::new(session_configuration.clone());
@@ -2626,14 +3376,20 @@ mod tests {
         let services = SessionServices {
             mcp_connection_manager: Arc::new(RwLock::new(McpConnectionManager::default())),
             mcp_startup_cancellation_token: CancellationToken::new(),
+            mcp_tool_cache: Mutex::default(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
-            notifier: UserNotifier::new(None),
+            notifier: UserNotifier::default(),
             rollout: Mutex::new(None),
             user_shell: shell::Shell::Unknown,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager: otel_event_manager.clone(),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            command_allowlist: Mutex::new(CommandAllowlist::default()),
+            tool_inventory: SessionToolInventory::default(),
+            base_check: SessionBaseCheck::default(),
+            repo_state: SessionRepoState::default(),
+            input_prompts: Arc::default(),
         };
 
         let turn_context = Session::make_turn_context(
@@ -2688,6 +3444,7 @@ mod tests {
             model_reasoning_summary: config.model_reasoning_summary,
             developer_instructions: config.developer_instructions.clone(),
             user_instructions: config.user_instructions.clone(),
+            scoped_instructions: Arc::default(),
             base_instructions: config.base_instructions.clone(),
             compact_prompt: config.compact_prompt.clone(),
             approval_policy: config.approval_policy,
@@ -2697,6 +3454,7 @@ mod tests {
             features: Features::default(),
             exec_policy: Arc::new(ExecPolicy::empty()),
             session_source: SessionSource::Exec,
+            replay: None,
         };
 
         let state = SessionState::new(session_configuration.clone());
@@ -2704,14 +3462,20 @@ mod tests {
         let services = SessionServices {
             mcp_connection_manager: Arc::new(RwLock::new(McpConnectionManager::default())),
             mcp_startup_cancellation_token: CancellationToken::new(),
+            mcp_tool_cache: Mutex::default(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
-            notifier: UserNotifier::new(None),
+            notifier: UserNotifier::default(),
             rollout: Mutex::new(None),
             user_shell: shell::Shell::Unknown,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager: otel_event_manager.clone(),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            command_allowlist: Mutex::new(CommandAllowlist::default()),
+            tool_inventory: SessionToolInventory::default(),
+            base_check: SessionBaseCheck::default(),
+            repo_state: SessionRepoState::default(),
+            input_prompts: Arc::default(),
         };
 
         let turn_context = Arc::new(Session::make_turn_context(
@@ -2969,9 +3733,11 @@ mod tests {
 
         let summary1 = "summary one";
         let snapshot1 = live_history.get_history();
+        let pinned1 = live_history.pinned_messages();
         let user_messages1 = collect_user_messages(&snapshot1);
         let rebuilt1 = compact::build_compacted_history(
             session.build_initial_context(turn_context),
+            &pinned1,
             &user_messages1,
             summary1,
         );
@@ -3003,9 +3769,11 @@ mod tests {
 
         let summary2 = "summary two";
         let snapshot2 = live_history.get_history();
+        let pinned2 = live_history.pinned_messages();
         let user_messages2 = collect_user_messages(&snapshot2);
         let rebuilt2 = compact::build_compacted_history(
             session.build_initial_context(turn_context),
+            &pinned2,
             &user_messages2,
             summary2,
         );
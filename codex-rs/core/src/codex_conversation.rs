@@ -4,6 +4,7 @@ use crate::protocol::Event;
 use crate::protocol::Op;
 use crate::protocol::Submission;
 use std::path::PathBuf;
+use std::time::Duration;
 
 pub struct CodexConversation {
     codex: Codex,
@@ -33,6 +34,11 @@ impl CodexConversation {
         self.codex.next_event().await
     }
 
+    /// See [`Codex::shutdown`].
+    pub async fn shutdown(&self, grace: Duration) -> bool {
+        self.codex.shutdown(grace).await
+    }
+
     pub fn rollout_path(&self) -> PathBuf {
         self.rollout_path.clone()
     }
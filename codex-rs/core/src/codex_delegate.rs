@@ -81,6 +81,7 @@ pub(crate) async fn run_codex_conversation_interactive(
         next_id: AtomicU64::new(0),
         tx_sub: tx_ops,
         rx_event: rx_sub,
+        session_loop: None,
     })
 }
 
@@ -146,6 +147,7 @@ pub(crate) async fn run_codex_conversation_one_shot(
         next_id: AtomicU64::new(0),
         rx_event: rx_bridge,
         tx_sub: tx_closed,
+        session_loop: None,
     })
 }
 
@@ -228,14 +230,33 @@ async fn handle_exec_approval(
     cancel_token: &CancellationToken,
 ) {
     // Race approval with cancellation and timeout to avoid hangs.
-    let approval_fut = parent_session.request_command_approval(
-        parent_ctx,
-        parent_ctx.sub_id.clone(),
-        event.command,
-        event.cwd,
-        event.reason,
-        event.risk,
-    );
+    let approval_fut = async {
+        match event.network {
+            Some(destination) => {
+                parent_session
+                    .request_network_approval(
+                        parent_ctx,
+                        parent_ctx.sub_id.clone(),
+                        event.command,
+                        event.cwd,
+                        destination,
+                    )
+                    .await
+            }
+            None => {
+                parent_session
+                    .request_command_approval(
+                        parent_ctx,
+                        parent_ctx.sub_id.clone(),
+                        event.command,
+                        event.cwd,
+                        event.reason,
+                        event.risk,
+                    )
+                    .await
+            }
+        }
+    };
     let decision = await_approval_with_cancel(
         approval_fut,
         parent_session,
@@ -272,7 +293,14 @@ async fn handle_patch_approval(
         cancel_token,
     )
     .await;
-    let _ = codex.submit(Op::PatchApproval { id, decision }).await;
+    let op = match parent_session
+        .take_patch_selection(&parent_ctx.sub_id)
+        .await
+    {
+        Some(changes) => Op::PartialPatchApproval { id, changes },
+        None => Op::PatchApproval { id, decision },
+    };
+    let _ = codex.submit(op).await;
 }
 
 /// Await an approval decision, aborting on cancellation.
@@ -0,0 +1,226 @@
+//! Command patterns that skip the approval prompt, from the project's
+//! `always_allow_commands` in `config.toml` or allowed during the session.
+//!
+//! A pattern is a program followed by the subcommand words that select what
+//! it does, e.g. `cargo test` or `npm run build`. It matches any command that
+//! starts with those words, so `cargo test` also allows
+//! `cargo test -p codex-core`. Commands run through `bash -lc` match when
+//! every command in the script does; scripts with substitutions, redirections
+//! or other constructs the plain-command parser rejects never match.
+
+use crate::bash::extract_bash_command;
+use crate::bash::parse_shell_lc_plain_commands;
+
+/// Words after the program that can still be part of a pattern.
+const MAX_SUBCOMMAND_WORDS: usize = 2;
+
+/// Programs that run whatever they are given. Allowing one without a
+/// subcommand allows nearly any command.
+const BROAD_PROGRAMS: &[&str] = &[
+    "bash", "dash", "fish", "ksh", "sh", "zsh", "env", "eval", "exec", "nohup", "xargs", "node",
+    "npx", "perl", "python", "python3", "ruby", "awk", "sed", "tee", "rm", "find", "chmod",
+    "chown", "dd", "curl", "docker", "git", "make",
+];
+
+/// Programs that run their arguments with elevated privileges; broad with
+/// any subcommand.
+const PRIVILEGED_PROGRAMS: &[&str] = &["doas", "su", "sudo"];
+
+/// The pattern to store when the user always allows `command`, or `None` when
+/// it is not a single plain command (including `bash -lc` scripts made of
+/// several commands or of constructs the parser does not understand).
+pub fn pattern_for_command(command: &[String]) -> Option<String> {
+    let words = match plain_commands(command)?.as_slice() {
+        [words] => words.clone(),
+        _ => return None,
+    };
+    let (program, args) = words.split_first()?;
+    if program.is_empty() || program.contains(char::is_whitespace) {
+        return None;
+    }
+    let mut pattern = vec![program.as_str()];
+    pattern.extend(
+        args.iter()
+            .take_while(|word| is_subcommand_word(word))
+            .take(MAX_SUBCOMMAND_WORDS)
+            .map(String::as_str),
+    );
+    Some(pattern.join(" "))
+}
+
+/// True when `pattern` would allow almost any command, e.g. a bare `bash` or
+/// `rm`, or anything run through `sudo`. Such patterns need an extra
+/// confirmation before they are stored.
+pub fn is_broad_pattern(pattern: &str) -> bool {
+    let words: Vec<&str> = pattern.split_whitespace().collect();
+    let Some(program) = words.first().map(|program| program_name(program)) else {
+        return true;
+    };
+    PRIVILEGED_PROGRAMS.contains(&program)
+        || (words.len() == 1 && BROAD_PROGRAMS.contains(&program))
+}
+
+/// Patterns whose commands run without asking for approval.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandAllowlist {
+    patterns: Vec<Vec<String>>,
+}
+
+impl CommandAllowlist {
+    pub fn new(patterns: &[String]) -> Self {
+        let mut allowlist = Self::default();
+        for pattern in patterns {
+            allowlist.add(pattern);
+        }
+        allowlist
+    }
+
+    /// Allow commands matching `pattern`; blank and repeated patterns are
+    /// ignored.
+    pub fn add(&mut self, pattern: &str) {
+        let words: Vec<String> = pattern.split_whitespace().map(str::to_string).collect();
+        if !words.is_empty() && !self.patterns.contains(&words) {
+            self.patterns.push(words);
+        }
+    }
+
+    /// True when every command `command` runs starts with an allowed pattern.
+    pub fn allows(&self, command: &[String]) -> bool {
+        if self.patterns.is_empty() {
+            return false;
+        }
+        plain_commands(command).is_some_and(|commands| {
+            !commands.is_empty()
+                && commands.iter().all(|words| {
+                    self.patterns
+                        .iter()
+                        .any(|pattern| words.starts_with(pattern))
+                })
+        })
+    }
+}
+
+/// The commands `command` runs: the script's commands for `bash -lc`, the
+/// command itself otherwise.
+fn plain_commands(command: &[String]) -> Option<Vec<Vec<String>>> {
+    if extract_bash_command(command).is_some() {
+        return parse_shell_lc_plain_commands(command);
+    }
+    (!command.is_empty()).then(|| vec![command.to_vec()])
+}
+
+/// Subcommands are lowercase words such as `test`, `run` or `build:prod`;
+/// flags, paths, assignments and other values end the pattern.
+fn is_subcommand_word(word: &str) -> bool {
+    word.starts_with(|c: char| c.is_ascii_lowercase())
+        && word
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_' | ':'))
+}
+
+fn program_name(program: &str) -> &str {
+    program.rsplit('/').next().unwrap_or(program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn cmd(words: &[&str]) -> Vec<String> {
+        words.iter().map(ToString::to_string).collect()
+    }
+
+    fn bash(script: &str) -> Vec<String> {
+        cmd(&["bash", "-lc", script])
+    }
+
+    #[test]
+    fn patterns_keep_the_program_and_its_subcommands() {
+        let patterns: Vec<Option<String>> = [
+            cmd(&["cargo", "test", "-p", "codex-core"]),
+            cmd(&["npm", "run", "build:prod", "--", "--watch"]),
+            cmd(&["git", "commit", "-m", "fix build"]),
+            cmd(&["./gradlew", "assemble", "release", "extra"]),
+            cmd(&["rm", "-rf", "target"]),
+            cmd(&["ls", "src/lib.rs"]),
+        ]
+        .iter()
+        .map(|command| pattern_for_command(command))
+        .collect();
+
+        assert_eq!(
+            patterns,
+            vec![
+                Some("cargo test".to_string()),
+                Some("npm run build:prod".to_string()),
+                Some("git commit".to_string()),
+                Some("./gradlew assemble release".to_string()),
+                Some("rm".to_string()),
+                Some("ls".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn bash_lc_patterns_come_from_the_script() {
+        assert_eq!(
+            [
+                pattern_for_command(&bash("cargo test --all-features")),
+                pattern_for_command(&bash("cargo fmt && cargo test")),
+                pattern_for_command(&bash("echo $(whoami)")),
+                pattern_for_command(&cmd(&["bash", "script.sh"])),
+            ],
+            [
+                Some("cargo test".to_string()),
+                None,
+                None,
+                Some("bash".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn bare_shells_removal_sudo_and_general_tools_are_broad() {
+        assert_eq!(
+            [
+                "bash",
+                "rm",
+                "/bin/rm",
+                "sudo apt install",
+                "cargo",
+                "rm foo",
+                "git",
+                "npx",
+                "sed",
+                "curl",
+                "docker",
+                "make",
+                "cargo",
+                "rm foo",
+                "git commit"
+            ]
+            .map(is_broad_pattern),
+            [
+                true, true, true, true, true, true, true, true, true, true, false, false, false
+            ]
+        );
+    }
+
+    #[test]
+    fn allowlist_matches_commands_starting_with_a_pattern() {
+        let mut allowlist = CommandAllowlist::new(&["cargo test".to_string()]);
+        assert!(allowlist.allows(&cmd(&["cargo", "test", "-p", "codex-core"])));
+        assert!(!allowlist.allows(&cmd(&["cargo", "publish"])));
+
+        // Patterns added during the session apply right away.
+        allowlist.add("cargo fmt");
+        assert!(allowlist.allows(&bash("cargo fmt && cargo test")));
+        assert!(!allowlist.allows(&bash("cargo fmt && rm -rf target")));
+        assert!(!allowlist.allows(&bash("cargo test > $(mktemp)")));
+        assert_eq!(
+            allowlist,
+            CommandAllowlist::new(&["cargo test".to_string(), "cargo fmt".to_string()])
+        );
+    }
+}
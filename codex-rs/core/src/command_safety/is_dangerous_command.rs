@@ -3,7 +3,7 @@ use codex_protocol::protocol::SandboxPolicy;
 
 use crate::sandboxing::SandboxPermissions;
 
-use crate::bash::parse_shell_lc_plain_commands;
+use crate::bash::parse_shell_lc_segments;
 use crate::is_safe_command::is_known_safe_command;
 
 pub fn requires_initial_appoval(
@@ -40,11 +40,12 @@ pub fn command_might_be_dangerous(command: &[String]) -> bool {
         return true;
     }
 
-    // Support `bash -lc "<script>"` where the any part of the script might contain a dangerous command.
-    if let Some(all_commands) = parse_shell_lc_plain_commands(command)
-        && all_commands
+    // Support `bash -lc "<script>"` where any segment of the script might be dangerous,
+    // including segments inside subshells or with redirections attached.
+    if let Some(segments) = parse_shell_lc_segments(command)
+        && segments
             .iter()
-            .any(|cmd| is_dangerous_to_call_with_exec(cmd))
+            .any(|segment| is_dangerous_to_call_with_exec(&segment.words))
     {
         return true;
     }
@@ -101,6 +102,20 @@ mod tests {
         ])));
     }
 
+    #[test]
+    fn dangerous_suffix_behind_redirect_or_subshell_is_dangerous() {
+        assert!(command_might_be_dangerous(&vec_str(&[
+            "bash",
+            "-lc",
+            "echo building > build.log && rm -rf build"
+        ])));
+        assert!(command_might_be_dangerous(&vec_str(&[
+            "bash",
+            "-lc",
+            "ls; (cd repo && git reset --hard) 2>/dev/null"
+        ])));
+    }
+
     #[test]
     fn git_status_is_not_dangerous() {
         assert!(!command_might_be_dangerous(&vec_str(&["git", "status"])));
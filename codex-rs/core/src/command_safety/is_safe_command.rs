@@ -323,6 +323,25 @@ mod tests {
         ])));
     }
 
+    #[test]
+    fn bash_lc_requires_every_segment_to_be_safe() {
+        assert!(!is_known_safe_command(&vec_str(&[
+            "bash",
+            "-lc",
+            "ls && cat README.md > copy.md"
+        ])));
+        assert!(!is_known_safe_command(&vec_str(&[
+            "bash",
+            "-lc",
+            "ls && (pwd; rm -rf build)"
+        ])));
+        assert!(!is_known_safe_command(&vec_str(&[
+            "bash",
+            "-lc",
+            "ls | xargs rm"
+        ])));
+    }
+
     #[test]
     fn bash_lc_unsafe_examples() {
         assert!(
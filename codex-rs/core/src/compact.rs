@@ -15,15 +15,18 @@ use crate::protocol::EventMsg;
 use crate::protocol::TaskStartedEvent;
 use crate::protocol::TurnContextItem;
 use crate::protocol::WarningEvent;
+use crate::token_usage::UsageEstimator;
 use crate::truncate::TruncationPolicy;
 use crate::truncate::approx_token_count;
 use crate::truncate::truncate_text;
+use crate::user_instructions::UserInstructions;
 use crate::util::backoff;
 use codex_app_server_protocol::AuthMode;
 use codex_protocol::items::TurnItem;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::ResponseInputItem;
 use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ENVIRONMENT_CONTEXT_OPEN_TAG;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::user_input::UserInput;
 use futures::prelude::*;
@@ -71,10 +74,12 @@ async fn run_compact_task_inner(
 ) {
     let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input);
 
+    let pinned = sess.pinned_messages().await;
     let mut history = sess.clone_history().await;
+    let dropped_items = count_dropped_items(&history.get_history(), &pinned);
     history.record_items(
         &[initial_input_for_turn.into()],
-        turn_context.truncation_policy,
+        &turn_context.tool_output_budgets,
     );
 
     let mut truncated_count = 0usize;
@@ -162,8 +167,11 @@ async fn run_compact_task_inner(
     let summary_text = format!("{SUMMARY_PREFIX}\n{summary_suffix}");
     let user_messages = collect_user_messages(&history_snapshot);
 
+    // Instructions and tool descriptions are rebuilt from the current turn
+    // rather than left to the summary.
     let initial_context = sess.build_initial_context(turn_context.as_ref());
-    let mut new_history = build_compacted_history(initial_context, &user_messages, &summary_text);
+    let mut new_history =
+        build_compacted_history(initial_context, &pinned, &user_messages, &summary_text);
     let ghost_snapshots: Vec<ResponseItem> = history_snapshot
         .iter()
         .filter(|item| matches!(item, ResponseItem::GhostSnapshot { .. }))
@@ -180,7 +188,7 @@ async fn run_compact_task_inner(
     sess.persist_rollout_items(&[rollout_item]).await;
 
     let event = EventMsg::AgentMessage(AgentMessageEvent {
-        message: "Compact task completed".to_string(),
+        message: compaction_completed_message(dropped_items, pinned.len()),
     });
     sess.send_event(&turn_context, event).await;
 
@@ -229,13 +237,60 @@ pub(crate) fn is_summary_message(message: &str) -> bool {
     message.starts_with(format!("{SUMMARY_PREFIX}\n").as_str())
 }
 
+/// The message that ends a compaction task, with how much was folded into
+/// the summary.
+pub(crate) fn compaction_completed_message(dropped_items: usize, pinned_items: usize) -> String {
+    format!("Compact task completed: compacted {dropped_items} items, kept {pinned_items} pinned")
+}
+
+/// History items that compaction replaces: everything except pinned
+/// messages and ghost snapshots, which are carried over as they are, and the
+/// initial context, which is regenerated.
+pub(crate) fn count_dropped_items(history: &[ResponseItem], pinned: &[ResponseItem]) -> usize {
+    history
+        .iter()
+        .filter(|item| {
+            !matches!(item, ResponseItem::GhostSnapshot { .. })
+                && !is_initial_context(item)
+                && !pinned.contains(item)
+        })
+        .count()
+}
+
+/// Developer instructions, user instructions and the environment context, as
+/// built by `Session::build_initial_context`.
+fn is_initial_context(item: &ResponseItem) -> bool {
+    let ResponseItem::Message { role, content, .. } = item else {
+        return false;
+    };
+    match role.as_str() {
+        "developer" => true,
+        "user" => {
+            UserInstructions::is_user_instructions(content)
+                || content.iter().any(|content| {
+                    matches!(
+                        content,
+                        ContentItem::InputText { text }
+                            if text.starts_with(ENVIRONMENT_CONTEXT_OPEN_TAG)
+                    )
+                })
+        }
+        _ => false,
+    }
+}
+
+/// Builds the history that replaces the transcript after compaction:
+/// `initial_context`, the `pinned` messages verbatim, as many of the most
+/// recent other user messages as fit the budget, then the summary.
 pub(crate) fn build_compacted_history(
     initial_context: Vec<ResponseItem>,
+    pinned: &[ResponseItem],
     user_messages: &[String],
     summary_text: &str,
 ) -> Vec<ResponseItem> {
     build_compacted_history_with_limit(
         initial_context,
+        pinned,
         user_messages,
         summary_text,
         COMPACT_USER_MESSAGE_MAX_TOKENS,
@@ -244,14 +299,23 @@ pub(crate) fn build_compacted_history(
 
 fn build_compacted_history_with_limit(
     mut history: Vec<ResponseItem>,
+    pinned: &[ResponseItem],
     user_messages: &[String],
     summary_text: &str,
     max_tokens: usize,
 ) -> Vec<ResponseItem> {
+    // Pinned messages do not count against the budget.
+    history.extend(pinned.iter().cloned());
+    let pinned_texts = collect_user_messages(pinned);
+
     let mut selected_messages: Vec<String> = Vec::new();
     if max_tokens > 0 {
         let mut remaining = max_tokens;
-        for message in user_messages.iter().rev() {
+        for message in user_messages
+            .iter()
+            .rev()
+            .filter(|message| !pinned_texts.contains(message))
+        {
             if remaining == 0 {
                 break;
             }
@@ -299,6 +363,7 @@ async fn drain_to_completed(
     prompt: &Prompt,
 ) -> CodexResult<()> {
     let mut stream = turn_context.client.clone().stream(prompt).await?;
+    let mut usage_estimator = UsageEstimator::default();
     loop {
         let maybe_event = stream.next().await;
         let Some(event) = maybe_event else {
@@ -309,6 +374,7 @@ async fn drain_to_completed(
         };
         match event {
             Ok(ResponseEvent::OutputItemDone(item)) => {
+                usage_estimator.record_output(&item);
                 sess.record_into_history(std::slice::from_ref(&item), turn_context)
                     .await;
             }
@@ -316,8 +382,13 @@ async fn drain_to_completed(
                 sess.update_rate_limits(turn_context, snapshot).await;
             }
             Ok(ResponseEvent::Completed { token_usage, .. }) => {
-                sess.update_token_usage_info(turn_context, token_usage.as_ref())
-                    .await;
+                sess.update_token_usage_info(
+                    turn_context,
+                    token_usage.as_ref(),
+                    prompt,
+                    &usage_estimator,
+                )
+                .await;
                 return Ok(());
             }
             Ok(_) => continue,
@@ -427,6 +498,7 @@ mod tests {
         let big = "word ".repeat(200);
         let history = super::build_compacted_history_with_limit(
             Vec::new(),
+            &[],
             std::slice::from_ref(&big),
             "SUMMARY",
             max_tokens,
@@ -461,13 +533,119 @@ mod tests {
         assert_eq!(summary_text, "SUMMARY");
     }
 
+    #[test]
+    fn pinned_messages_survive_compaction_verbatim() {
+        fn message(role: &str, text: String) -> ResponseItem {
+            let content = if role == "user" {
+                ContentItem::InputText { text }
+            } else {
+                ContentItem::OutputText { text }
+            };
+            ResponseItem::Message {
+                id: None,
+                role: role.to_string(),
+                content: vec![content],
+            }
+        }
+        fn history_tokens(items: &[ResponseItem]) -> usize {
+            items
+                .iter()
+                .map(|item| approx_token_count(&serde_json::to_string(item).unwrap_or_default()))
+                .sum()
+        }
+
+        let task = message(
+            "user",
+            format!(
+                "Port the billing service to the v2 API. {}",
+                "Keep the CLI flags. ".repeat(300)
+            ),
+        );
+        let pinned_note = message("user", "Never touch migrations/.".to_string());
+        let mut history = vec![task.clone()];
+        for turn in 0..200 {
+            history.push(message(
+                "user",
+                format!("step {turn}: {}", "more detail ".repeat(150)),
+            ));
+            history.push(message(
+                "assistant",
+                format!("done {turn}: {}", "output ".repeat(400)),
+            ));
+            if turn == 50 {
+                history.push(pinned_note.clone());
+            }
+        }
+        let pinned = vec![task.clone(), pinned_note.clone()];
+        let target = history_tokens(&history) / 4;
+
+        let compacted = build_compacted_history(
+            Vec::new(),
+            &pinned,
+            &collect_user_messages(&history),
+            "SUMMARY",
+        );
+
+        assert_eq!(compacted[..2], pinned[..]);
+        assert_eq!(
+            compacted
+                .iter()
+                .filter(|item| **item == task || **item == pinned_note)
+                .count(),
+            2,
+            "pinned messages must not be repeated among the recent ones"
+        );
+        assert!(
+            history_tokens(&compacted) < target,
+            "compacted history should fit {target} tokens, got {}",
+            history_tokens(&compacted)
+        );
+        assert_eq!(
+            count_dropped_items(&history, &pinned),
+            history.len() - pinned.len()
+        );
+    }
+
+    #[test]
+    fn regenerated_initial_context_is_not_counted_as_dropped() {
+        let message = |role: &str, text: &str| ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        };
+        let task = message("user", "fix the build");
+        let history = vec![
+            message("developer", "be brief"),
+            UserInstructions {
+                text: "use tabs".to_string(),
+                directory: "/repo".to_string(),
+            }
+            .into(),
+            message(
+                "user",
+                "<environment_context>\n  <cwd>/repo</cwd>\n</environment_context>",
+            ),
+            task.clone(),
+            message("assistant", "done"),
+            message("user", "thanks"),
+        ];
+
+        assert_eq!(count_dropped_items(&history, &[task]), 2);
+        assert_eq!(
+            compaction_completed_message(2, 1),
+            "Compact task completed: compacted 2 items, kept 1 pinned"
+        );
+    }
+
     #[test]
     fn build_token_limited_compacted_history_appends_summary_message() {
         let initial_context: Vec<ResponseItem> = Vec::new();
         let user_messages = vec!["first user message".to_string()];
         let summary_text = "summary text";
 
-        let history = build_compacted_history(initial_context, &user_messages, summary_text);
+        let history = build_compacted_history(initial_context, &[], &user_messages, summary_text);
         assert!(
             !history.is_empty(),
             "expected compacted history to include summary"
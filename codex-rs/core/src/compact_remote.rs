@@ -3,6 +3,10 @@ use std::sync::Arc;
 use crate::Prompt;
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::compact::compaction_completed_message;
+use crate::compact::count_dropped_items;
+use crate::context_manager::repair_call_pairing;
+use crate::context_manager::restore_split_pairs;
 use crate::error::Result as CodexResult;
 use crate::protocol::AgentMessageEvent;
 use crate::protocol::CompactedItem;
@@ -41,7 +45,10 @@ async fn run_remote_compact_task_inner_impl(
     sess: &Arc<Session>,
     turn_context: &Arc<TurnContext>,
 ) -> CodexResult<()> {
+    let pinned = sess.pinned_messages().await;
     let mut history = sess.clone_history().await;
+    let original_history = history.get_history();
+    let dropped_items = count_dropped_items(&original_history, &pinned);
     let prompt = Prompt {
         input: history.get_history_for_prompt(),
         tools: vec![],
@@ -54,9 +61,15 @@ async fn run_remote_compact_task_inner_impl(
         .client
         .compact_conversation_history(&prompt)
         .await?;
+    // Pinned messages the server folded into its summary go back in front.
+    let missing_pinned: Vec<ResponseItem> = pinned
+        .iter()
+        .filter(|item| !new_history.contains(item))
+        .cloned()
+        .collect();
+    new_history.splice(0..0, missing_pinned);
     // Required to keep `/undo` available after compaction
-    let ghost_snapshots: Vec<ResponseItem> = history
-        .get_history()
+    let ghost_snapshots: Vec<ResponseItem> = original_history
         .iter()
         .filter(|item| matches!(item, ResponseItem::GhostSnapshot { .. }))
         .cloned()
@@ -65,6 +78,12 @@ async fn run_remote_compact_task_inner_impl(
     if !ghost_snapshots.is_empty() {
         new_history.extend(ghost_snapshots);
     }
+    // The server may keep a call while folding its output into the summary
+    // (or vice versa). Tool calls are kept or dropped as a unit, so put the
+    // missing half back, then repair whatever is still broken before
+    // persisting so resumed sessions see the same history.
+    restore_split_pairs(&mut new_history, &original_history);
+    repair_call_pairing(&mut new_history);
     sess.replace_history(new_history.clone()).await;
     sess.recompute_token_usage(turn_context).await;
 
@@ -76,7 +95,7 @@ async fn run_remote_compact_task_inner_impl(
         .await;
 
     let event = EventMsg::AgentMessage(AgentMessageEvent {
-        message: "Compact task completed".to_string(),
+        message: compaction_completed_message(dropped_items, pinned.len()),
     });
     sess.send_event(turn_context, event).await;
 
@@ -1,4 +1,5 @@
 use crate::config::CONFIG_TOML_FILE;
+use crate::config::types::CacheToolResults;
 use crate::config::types::McpServerConfig;
 use crate::config::types::Notice;
 use anyhow::Context;
@@ -38,6 +39,9 @@ pub enum ConfigEdit {
     /// Set trust_level under `[projects."<path>"]`,
     /// migrating inline tables to explicit tables.
     SetProjectTrustLevel { path: PathBuf, level: TrustLevel },
+    /// Append a pattern to always_allow_commands under
+    /// `[projects."<path>"]` unless it is already listed.
+    AddProjectAlwaysAllowCommand { path: PathBuf, pattern: String },
     /// Set the value stored at the exact dotted path.
     SetPath {
         segments: Vec<String>,
@@ -159,6 +163,15 @@ mod document_helpers {
         {
             entry["disabled_tools"] = array_from_iter(disabled_tools.iter().cloned());
         }
+        match &config.cache_tool_results {
+            Some(CacheToolResults::All(enabled)) => {
+                entry["cache_tool_results"] = value(*enabled);
+            }
+            Some(CacheToolResults::Tools(tools)) => {
+                entry["cache_tool_results"] = array_from_iter(tools.iter().cloned());
+            }
+            None => {}
+        }
 
         TomlItem::Table(entry)
     }
@@ -285,6 +298,13 @@ impl ConfigDocument {
                 )?;
                 Ok(true)
             }
+            ConfigEdit::AddProjectAlwaysAllowCommand { path, pattern } => {
+                crate::config::add_project_always_allow_command_inner(
+                    &mut self.doc,
+                    path.as_path(),
+                    pattern,
+                )
+            }
         }
     }
 
@@ -469,6 +489,15 @@ pub async fn apply(
         .context("config persistence task panicked")?
 }
 
+fn profile_mcp_server_segments(profile: &str, name: &str) -> Vec<String> {
+    vec![
+        "profiles".to_string(),
+        profile.to_string(),
+        "mcp_servers".to_string(),
+        name.to_string(),
+    ]
+}
+
 /// Fluent builder to batch config edits and apply them atomically.
 #[derive(Default)]
 pub struct ConfigEditsBuilder {
@@ -538,6 +567,28 @@ impl ConfigEditsBuilder {
         self
     }
 
+    /// Insert or replace server `name` under `[profiles.<profile>.mcp_servers]`.
+    pub fn set_profile_mcp_server(
+        mut self,
+        profile: &str,
+        name: &str,
+        config: &McpServerConfig,
+    ) -> Self {
+        self.edits.push(ConfigEdit::SetPath {
+            segments: profile_mcp_server_segments(profile, name),
+            value: document_helpers::serialize_mcp_server(config),
+        });
+        self
+    }
+
+    /// Remove server `name` from `[profiles.<profile>.mcp_servers]`.
+    pub fn remove_profile_mcp_server(mut self, profile: &str, name: &str) -> Self {
+        self.edits.push(ConfigEdit::ClearPath {
+            segments: profile_mcp_server_segments(profile, name),
+        });
+        self
+    }
+
     pub fn set_project_trust_level<P: Into<PathBuf>>(
         mut self,
         project_path: P,
@@ -550,6 +601,18 @@ impl ConfigEditsBuilder {
         self
     }
 
+    pub fn add_project_always_allow_command<P: Into<PathBuf>>(
+        mut self,
+        project_path: P,
+        pattern: &str,
+    ) -> Self {
+        self.edits.push(ConfigEdit::AddProjectAlwaysAllowCommand {
+            path: project_path.into(),
+            pattern: pattern.to_string(),
+        });
+        self
+    }
+
     /// Enable or disable a feature flag by key under the `[features]` table.
     pub fn set_feature_enabled(mut self, key: &str, enabled: bool) -> Self {
         self.edits.push(ConfigEdit::SetPath {
@@ -577,6 +640,8 @@ impl ConfigEditsBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::ConfigToml;
+    use crate::config::ProjectConfig;
     use crate::config::types::McpServerTransportConfig;
     use codex_protocol::config_types::ReasoningEffort;
     use pretty_assertions::assert_eq;
@@ -903,6 +968,7 @@ existing = "value"
                 tool_timeout_sec: None,
                 enabled_tools: Some(vec!["one".to_string(), "two".to_string()]),
                 disabled_tools: None,
+                cache_tool_results: Some(CacheToolResults::All(true)),
             },
         );
 
@@ -924,6 +990,7 @@ existing = "value"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: Some(vec!["forbidden".to_string()]),
+                cache_tool_results: Some(CacheToolResults::Tools(vec!["lookup".to_string()])),
             },
         );
 
@@ -942,6 +1009,7 @@ bearer_token_env_var = \"TOKEN\"
 enabled = false
 startup_timeout_sec = 5.0
 disabled_tools = [\"forbidden\"]
+cache_tool_results = [\"lookup\"]
 
 [mcp_servers.http.http_headers]
 Z-Header = \"z\"
@@ -951,6 +1019,7 @@ command = \"cmd\"
 args = [\"--flag\"]
 env_vars = [\"FOO\"]
 enabled_tools = [\"one\", \"two\"]
+cache_tool_results = true
 
 [mcp_servers.stdio.env]
 A = \"1\"
@@ -1106,4 +1175,33 @@ model_reasoning_effort = "high"
             std::fs::read_to_string(codex_home.join(CONFIG_TOML_FILE)).expect("read config");
         assert!(!contents.contains("mcp_servers"));
     }
+
+    #[test]
+    fn add_project_always_allow_command_keeps_trust_and_skips_duplicates() {
+        let tmp = tempdir().expect("tmpdir");
+        let codex_home = tmp.path();
+        std::fs::write(
+            codex_home.join(CONFIG_TOML_FILE),
+            "[projects]\n\"/work/repo\" = { trust_level = \"trusted\" }\n",
+        )
+        .expect("seed");
+
+        ConfigEditsBuilder::new(codex_home)
+            .add_project_always_allow_command("/work/repo", "cargo test")
+            .add_project_always_allow_command("/work/repo", "cargo test")
+            .add_project_always_allow_command("/work/repo", "npm run build")
+            .apply_blocking()
+            .expect("persist");
+
+        let contents =
+            std::fs::read_to_string(codex_home.join(CONFIG_TOML_FILE)).expect("read config");
+        let cfg: ConfigToml = toml::from_str(&contents).expect("parse config");
+        assert_eq!(
+            cfg.get_active_project(Path::new("/work/repo")),
+            Some(ProjectConfig {
+                trust_level: Some(TrustLevel::Trusted),
+                always_allow_commands: vec!["cargo test".to_string(), "npm run build".to_string()],
+            })
+        );
+    }
 }
@@ -1,18 +1,38 @@
 use crate::auth::AuthCredentialsStoreMode;
+use crate::auth::DEFAULT_REFRESH_WINDOW;
+use crate::config::types::Animations;
+use crate::config::types::ApprovalsToml;
+use crate::config::types::DEFAULT_EXEC_FOLLOW_LINES;
 use crate::config::types::DEFAULT_OTEL_ENVIRONMENT;
+use crate::config::types::DEFAULT_PASTE_IMAGE_MAX_BYTES;
+use crate::config::types::DEFAULT_WORD_DIFF_EXTENSIONS;
+use crate::config::types::FileSearchToml;
 use crate::config::types::History;
+use crate::config::types::Hyperlinks;
+use crate::config::types::InputPromptDetectionToml;
+use crate::config::types::MIN_CLOUD_TASKS_POLL_INTERVAL_SECS;
 use crate::config::types::McpServerConfig;
+use crate::config::types::McpToolCache;
+use crate::config::types::NetworkToml;
 use crate::config::types::Notice;
+use crate::config::types::NotificationHooks;
 use crate::config::types::Notifications;
 use crate::config::types::OtelConfig;
 use crate::config::types::OtelConfigToml;
 use crate::config::types::OtelExporterKind;
+use crate::config::types::RateLimitWarnings;
+use crate::config::types::RateLimitWarningsToml;
 use crate::config::types::ReasoningSummaryFormat;
 use crate::config::types::SandboxWorkspaceWrite;
+use crate::config::types::SessionRecallToml;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyToml;
+use crate::config::types::ToolInventoryToml;
 use crate::config::types::Tui;
+use crate::config::types::TurnLimits;
+use crate::config::types::TurnSeparators;
 use crate::config::types::UriBasedFileOpener;
+use crate::config::types::WorktreeBaseCheckToml;
 use crate::config_loader::LoadedConfigLayers;
 use crate::config_loader::load_config_as_toml;
 use crate::config_loader::load_config_layers_with_overrides;
@@ -21,21 +41,32 @@ use crate::features::Feature;
 use crate::features::FeatureOverrides;
 use crate::features::Features;
 use crate::features::FeaturesToml;
+use crate::git_info::get_git_repo_root;
 use crate::git_info::resolve_root_git_project_for_trust;
+use crate::git_worktree::BaseCheckSettings;
+use crate::input_prompt::PromptDetectionSettings;
 use crate::model_family::ModelFamily;
 use crate::model_family::derive_default_model_family;
 use crate::model_family::find_family_for_model;
 use crate::model_provider_info::LMSTUDIO_OSS_PROVIDER_ID;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::OLLAMA_OSS_PROVIDER_ID;
+use crate::model_provider_info::WireApi;
 use crate::model_provider_info::built_in_model_providers;
+use crate::network_policy::NetworkPolicy;
 use crate::openai_model_info::get_model_info;
+use crate::persistence_policy::PersistencePolicy;
 use crate::project_doc::DEFAULT_PROJECT_DOC_FILENAME;
 use crate::project_doc::LOCAL_PROJECT_DOC_FILENAME;
 use crate::protocol::AskForApproval;
 use crate::protocol::SandboxPolicy;
+use crate::sandboxing::explanation::ExplanationSettings;
+use crate::tool_inventory::TOOL_CANDIDATES;
 use codex_app_server_protocol::Tools;
 use codex_app_server_protocol::UserSavedConfig;
+use codex_file_search::CODEXIGNORE_FILENAME;
+use codex_file_search::PathFilter;
+use codex_file_search::SearchFilters;
 use codex_protocol::config_types::ForcedLoginMethod;
 use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::config_types::ReasoningSummary;
@@ -52,8 +83,13 @@ use std::collections::HashMap;
 use std::io::ErrorKind;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use crate::config::profile::ConfigProfile;
+use crate::config::profile::ProfileParents;
+use crate::config::profile::mcp_server_source;
+use crate::config::profile::profile_chain;
+use crate::config::profile::resolve_profile;
 use toml::Value as TomlValue;
 use toml_edit::DocumentMut;
 
@@ -63,6 +99,7 @@ pub mod types;
 
 pub const OPENAI_DEFAULT_MODEL: &str = "gpt-5.1-codex";
 const OPENAI_DEFAULT_REVIEW_MODEL: &str = "gpt-5.1-codex";
+const DEFAULT_REPLAY_SPEED: f64 = 1.0;
 pub const GPT_5_CODEX_MEDIUM_MODEL: &str = "gpt-5.1-codex";
 
 /// Maximum number of bytes of the documentation that will be embedded. Larger
@@ -160,6 +197,40 @@ pub struct Config {
     /// and turn completions when not focused.
     pub tui_notifications: Notifications,
 
+    /// File extensions (without the leading dot) whose edits the TUI renders
+    /// as word-level diffs.
+    pub tui_word_diff_extensions: Vec<String>,
+
+    /// How the TUI separates turns in the transcript.
+    pub tui_turn_separators: TurnSeparators,
+
+    /// How often the TUI polls Codex Cloud tasks; `None` disables polling.
+    pub tui_cloud_tasks_poll_interval: Option<Duration>,
+
+    /// Whether the TUI reflects session status in the terminal window title.
+    pub tui_set_terminal_title: bool,
+
+    /// Upper limit for the TUI's decorative animations.
+    pub tui_animations: Animations,
+
+    /// Largest clipboard image, in bytes, the TUI accepts on paste.
+    pub tui_paste_image_max_bytes: usize,
+
+    /// Whether the TUI links file references in the scrollback.
+    pub tui_hyperlinks: Hyperlinks,
+
+    /// Whether the TUI captures the mouse in full-screen views.
+    pub tui_mouse: bool,
+
+    /// Output lines the TUI shows for a running command.
+    pub tui_exec_follow_lines: usize,
+
+    /// When the TUI's rate-limit indicator warns.
+    pub tui_rate_limit_warnings: RateLimitWarnings,
+
+    /// Whether the TUI notes filtered environment variables under commands.
+    pub tui_show_filtered_env_vars: bool,
+
     /// The directory that should be treated as the current working directory
     /// for the session. All relative paths inside the business-logic layer are
     /// resolved against this path.
@@ -171,9 +242,17 @@ pub struct Config {
     /// auto: Use the OS-specific keyring service if available, otherwise use a file.
     pub cli_auth_credentials_store_mode: AuthCredentialsStoreMode,
 
+    /// ChatGPT tokens are refreshed this long before the access token
+    /// expires.
+    pub auth_refresh_window: Duration,
+
     /// Definition for MCP servers that Codex can reach out to for tool calls.
     pub mcp_servers: HashMap<String, McpServerConfig>,
 
+    /// Profile that contributed each entry of `mcp_servers`. Servers from the
+    /// top-level table have no entry.
+    pub mcp_server_profiles: HashMap<String, String>,
+
     /// Preferred store for MCP OAuth credentials.
     /// keyring: Use an OS-specific keyring service.
     ///          Credentials stored in the keyring will only be readable by Codex unless the user explicitly grants access via OS-level keyring access.
@@ -186,6 +265,14 @@ pub struct Config {
     /// Combined provider map (defaults merged with user-defined overrides).
     pub model_providers: HashMap<String, ModelProviderInfo>,
 
+    /// Recording answered by `wire_api = "replay"` providers: a rollout file
+    /// or a capture of Responses API SSE.
+    pub replay_path: Option<PathBuf>,
+
+    /// Recorded gaps between replayed events are divided by this; `0` emits
+    /// them at once.
+    pub replay_speed: f64,
+
     /// Maximum number of bytes to include from an AGENTS.md project doc file.
     pub project_doc_max_bytes: usize,
 
@@ -195,6 +282,11 @@ pub struct Config {
     /// Token budget applied when storing tool/function outputs in the context manager.
     pub tool_output_token_limit: Option<usize>,
 
+    /// Per-tool token budgets for model-visible tool output, keyed by tool
+    /// name. Override the built-in per-tool defaults and
+    /// `tool_output_token_limit`.
+    pub tool_output_limits: HashMap<String, usize>,
+
     /// Directory containing all Codex state (defaults to `~/.codex` but can be
     /// overridden by the `CODEX_HOME` environment variable).
     pub codex_home: PathBuf,
@@ -274,6 +366,49 @@ pub struct Config {
 
     /// OTEL configuration (exporter type, endpoint, headers, etc.).
     pub otel: crate::config::types::OtelConfig,
+
+    /// When `true`, only model provider traffic may leave the machine. See
+    /// [`crate::network_policy`].
+    pub offline: bool,
+
+    /// Executables whose versions are probed at session start and listed in
+    /// the environment context. `None` when `[tool_inventory]` is disabled.
+    pub tool_inventory: Option<Vec<String>>,
+
+    /// How to check whether a linked worktree has fallen behind its base
+    /// branch's upstream. `None` when `[worktree_base_check]` is disabled.
+    pub worktree_base_check: Option<BaseCheckSettings>,
+
+    /// How to spot commands that stopped to wait for input. `None` when
+    /// `[input_prompt_detection]` is disabled.
+    pub input_prompt_detection: Option<PromptDetectionSettings>,
+
+    /// Projects whose sessions `search_past_sessions` never indexes.
+    pub session_recall_exclude_projects: Vec<PathBuf>,
+
+    /// How to summarize commands awaiting approval. `None` unless
+    /// `approvals.explain` is set.
+    pub approval_explanation: Option<ExplanationSettings>,
+
+    /// Caps on a single turn; see [`TurnLimits`].
+    pub turn_limits: TurnLimits,
+
+    /// Limits for MCP servers' cached tool results; see [`McpToolCache`].
+    pub mcp_tool_cache: McpToolCache,
+
+    /// Per-event commands from `[notifications]`; see [`NotificationHooks`].
+    pub notification_hooks: NotificationHooks,
+
+    /// Include/exclude rules and limits for the TUI `@` file search.
+    pub file_search: SearchFilters,
+
+    /// Rules from the repository's `.codexignore`: paths kept out of project
+    /// docs, file search and `@` mentions. The sandbox does not consult them.
+    pub path_filter: PathFilter,
+
+    /// Gate for everything written under `codex_home`. Ephemeral runs
+    /// (`--ephemeral`) persist nothing. See [`crate::persistence_policy`].
+    pub persistence: PersistencePolicy,
 }
 
 impl Config {
@@ -286,6 +421,7 @@ impl Config {
         let root_value = load_resolved_config(
             &codex_home,
             cli_overrides,
+            overrides.config_profile.as_deref(),
             crate::config_loader::LoaderOverrides::default(),
         )
         .await?;
@@ -306,6 +442,7 @@ pub async fn load_config_as_toml_with_cli_overrides(
     let root_value = load_resolved_config(
         codex_home,
         cli_overrides,
+        None,
         crate::config_loader::LoaderOverrides::default(),
     )
     .await?;
@@ -318,18 +455,23 @@ pub async fn load_config_as_toml_with_cli_overrides(
     Ok(cfg)
 }
 
-async fn load_resolved_config(
+/// Load the config layers and apply `cli_overrides` on top. `config_profile`
+/// is the profile selected outside the config (`--profile`); without it the
+/// `profile` key decides which profile `-c mcp_servers.*` overrides target.
+pub(crate) async fn load_resolved_config(
     codex_home: &Path,
     cli_overrides: Vec<(String, TomlValue)>,
+    config_profile: Option<&str>,
     overrides: crate::config_loader::LoaderOverrides,
 ) -> std::io::Result<TomlValue> {
     let layers = load_config_layers_with_overrides(codex_home, overrides).await?;
-    Ok(apply_overlays(layers, cli_overrides))
+    Ok(apply_overlays(layers, cli_overrides, config_profile))
 }
 
 fn apply_overlays(
     layers: LoadedConfigLayers,
     cli_overrides: Vec<(String, TomlValue)>,
+    config_profile: Option<&str>,
 ) -> TomlValue {
     let LoadedConfigLayers {
         mut base,
@@ -337,9 +479,17 @@ fn apply_overlays(
         managed_preferences,
     } = layers;
 
-    for (path, value) in cli_overrides.into_iter() {
+    // Server overrides go last so a `-c profile=...` anywhere on the command
+    // line decides which profile they target.
+    let (server_overrides, other_overrides): (Vec<_>, Vec<_>) = cli_overrides
+        .into_iter()
+        .partition(|(path, _)| path.starts_with("mcp_servers."));
+    for (path, value) in other_overrides {
         apply_toml_override(&mut base, &path, value);
     }
+    for (path, value) in server_overrides {
+        apply_mcp_server_override(&mut base, &path, value, config_profile);
+    }
 
     for overlay in [managed_config, managed_preferences].into_iter().flatten() {
         merge_toml_values(&mut base, &overlay);
@@ -390,6 +540,42 @@ pub(crate) fn set_project_trust_level_inner(
     project_path: &Path,
     trust_level: TrustLevel,
 ) -> anyhow::Result<()> {
+    let proj_tbl = explicit_project_table(doc, project_path)?;
+    proj_tbl["trust_level"] = toml_edit::value(trust_level.to_string());
+    Ok(())
+}
+
+/// Append `pattern` to `always_allow_commands` under `[projects."<path>"]`
+/// unless it is already listed. Returns whether the document changed.
+pub(crate) fn add_project_always_allow_command_inner(
+    doc: &mut DocumentMut,
+    project_path: &Path,
+    pattern: &str,
+) -> anyhow::Result<bool> {
+    let proj_tbl = explicit_project_table(doc, project_path)?;
+    let item = proj_tbl
+        .entry("always_allow_commands")
+        .or_insert(toml_edit::value(toml_edit::Array::new()));
+    let Some(commands) = item.as_array_mut() else {
+        return Err(anyhow::anyhow!(
+            "always_allow_commands for {} is not an array",
+            project_path.display()
+        ));
+    };
+    if commands
+        .iter()
+        .any(|existing| existing.as_str() == Some(pattern))
+    {
+        return Ok(false);
+    }
+    commands.push(pattern);
+    Ok(true)
+}
+
+fn explicit_project_table<'a>(
+    doc: &'a mut DocumentMut,
+    project_path: &Path,
+) -> anyhow::Result<&'a mut toml_edit::Table> {
     // Ensure we render a human-friendly structure:
     //
     // [projects]
@@ -434,14 +620,21 @@ pub(crate) fn set_project_trust_level_inner(
     };
 
     // Ensure the per-project entry is its own explicit table. If it exists but
-    // is not a table (e.g., an inline table), replace it with an explicit table.
+    // is not a table (e.g., an inline table), replace it with an explicit table,
+    // keeping the entries of an inline table.
     let needs_proj_table = !projects_tbl.contains_key(project_key.as_str())
         || projects_tbl
             .get(project_key.as_str())
             .and_then(|i| i.as_table())
             .is_none();
     if needs_proj_table {
-        projects_tbl.insert(project_key.as_str(), toml_edit::table());
+        let proj_tbl = projects_tbl
+            .get(project_key.as_str())
+            .and_then(|i| i.as_inline_table())
+            .map_or_else(toml_edit::table, |inline_tbl| {
+                toml_edit::Item::Table(inline_tbl.clone().into_table())
+            });
+        projects_tbl.insert(project_key.as_str(), proj_tbl);
     }
     let Some(proj_tbl) = projects_tbl
         .get_mut(project_key.as_str())
@@ -450,8 +643,7 @@ pub(crate) fn set_project_trust_level_inner(
         return Err(anyhow::anyhow!("project table missing for {project_key}"));
     };
     proj_tbl.set_implicit(false);
-    proj_tbl["trust_level"] = toml_edit::value(trust_level.to_string());
-    Ok(())
+    Ok(proj_tbl)
 }
 
 /// Patch `CODEX_HOME/config.toml` project state to set trust level.
@@ -510,6 +702,70 @@ pub fn set_default_oss_provider(codex_home: &Path, provider: &str) -> std::io::R
     Ok(())
 }
 
+/// Apply a `-c mcp_servers.<name>...` override. When the active profile's
+/// `extends` chain declares `<name>`, the override is applied to the profile
+/// whose entry is in effect after resolution; other profiles are left alone.
+/// The top-level table only receives it when it declares the server too, when
+/// the override defines the whole server, or when no such profile declares it.
+fn apply_mcp_server_override(
+    root: &mut TomlValue,
+    path: &str,
+    value: TomlValue,
+    config_profile: Option<&str>,
+) {
+    let Some(server_path) = path.strip_prefix("mcp_servers.") else {
+        apply_toml_override(root, path, value);
+        return;
+    };
+    let (server, field) = match server_path.split_once('.') {
+        Some((server, field)) => (server, Some(field)),
+        None => (server_path, None),
+    };
+
+    let active_profile = config_profile.map(str::to_string).or_else(|| {
+        root.get("profile")
+            .and_then(TomlValue::as_str)
+            .map(str::to_string)
+    });
+    let source = active_profile.and_then(|active| {
+        let profiles = root.get("profiles");
+        let profile = |name: &str| profiles.and_then(|profiles| profiles.get(name));
+        let parents_of = |name: &str| {
+            profile(name)
+                .and_then(|profile| profile.get("extends"))
+                .and_then(|extends| extends.clone().try_into::<ProfileParents>().ok())
+                .map(|parents| parents.names().to_vec())
+                .unwrap_or_default()
+        };
+        profile_chain(&active, &parents_of)
+            .into_iter()
+            .find(|name| {
+                profile(name)
+                    .and_then(|profile| profile.get("mcp_servers"))
+                    .and_then(|servers| servers.get(server))
+                    .is_some()
+            })
+    });
+
+    let mut declared_in_profile = false;
+    if let Some(source) = source
+        && let Some(profile) = root
+            .get_mut("profiles")
+            .and_then(|profiles| profiles.get_mut(&source))
+    {
+        apply_toml_override(profile, path, value.clone());
+        declared_in_profile = true;
+    }
+
+    let declared_globally = root
+        .get("mcp_servers")
+        .and_then(|servers| servers.get(server))
+        .is_some();
+    if declared_globally || field.is_none() || !declared_in_profile {
+        apply_toml_override(root, path, value);
+    }
+}
+
 /// Apply a single dotted-path override onto a TOML value.
 fn apply_toml_override(root: &mut TomlValue, path: &str, value: TomlValue) {
     use toml::value::Table;
@@ -564,6 +820,13 @@ pub struct ConfigToml {
     /// Provider to use from the model_providers map.
     pub model_provider: Option<String>,
 
+    /// Recording for the `replay` provider, relative to the working directory.
+    pub replay_path: Option<PathBuf>,
+
+    /// Playback speed for the `replay` provider (default: 1, the recorded
+    /// timing; 0 replays without pauses).
+    pub replay_speed: Option<f64>,
+
     /// Size of the context window for the model, in tokens.
     pub model_context_window: Option<i64>,
 
@@ -611,9 +874,15 @@ pub struct ConfigToml {
     /// file (default): Use a file in the Codex home directory.
     /// keyring: Use an OS-specific keyring service.
     /// auto: Use the keyring if available, otherwise use a file.
-    #[serde(default)]
+    /// keychain: Encrypt the file with a key kept in the keyring (requires the
+    /// `keychain-auth` feature). Also accepted as `auth_storage`.
+    #[serde(default, alias = "auth_storage")]
     pub cli_auth_credentials_store: Option<AuthCredentialsStoreMode>,
 
+    /// Seconds before the ChatGPT access token expires at which it is
+    /// refreshed. Defaults to 300.
+    pub auth_refresh_window_secs: Option<u64>,
+
     /// Definition for MCP servers that Codex can reach out to for tool calls.
     #[serde(default)]
     pub mcp_servers: HashMap<String, McpServerConfig>,
@@ -639,6 +908,11 @@ pub struct ConfigToml {
     /// Token budget applied when storing tool/function outputs in the context manager.
     pub tool_output_token_limit: Option<usize>,
 
+    /// Per-tool token budgets for model-visible tool output, e.g.
+    /// `tool_output_limits.read_file = 20000`.
+    #[serde(default)]
+    pub tool_output_limits: Option<HashMap<String, usize>>,
+
     /// Profile to use from the `profiles` map.
     pub profile: Option<String>,
 
@@ -696,6 +970,36 @@ pub struct ConfigToml {
     /// OTEL configuration.
     pub otel: Option<crate::config::types::OtelConfigToml>,
 
+    /// Outbound network settings.
+    pub network: Option<NetworkToml>,
+
+    /// Tool version inventory shared with the model.
+    pub tool_inventory: Option<ToolInventoryToml>,
+
+    /// Stale worktree detection.
+    pub worktree_base_check: Option<WorktreeBaseCheckToml>,
+
+    /// Detection of commands waiting for input.
+    pub input_prompt_detection: Option<InputPromptDetectionToml>,
+
+    /// Cross-session recall settings.
+    pub session_recall: Option<SessionRecallToml>,
+
+    /// Approval prompt settings.
+    pub approvals: Option<ApprovalsToml>,
+
+    /// Wall-clock and output-token caps on a single turn.
+    pub turn_limits: Option<TurnLimits>,
+
+    /// Limits for MCP servers' cached tool results.
+    pub mcp_tool_cache: Option<McpToolCache>,
+
+    /// Commands to run on task completion, approval requests and errors.
+    pub notifications: Option<NotificationHooks>,
+
+    /// File search include/exclude rules and limits.
+    pub file_search: Option<FileSearchToml>,
+
     /// Tracks whether the Windows onboarding screen has been acknowledged.
     pub windows_wsl_setup_acknowledged: Option<bool>,
 
@@ -739,9 +1043,14 @@ impl From<ConfigToml> for UserSavedConfig {
     }
 }
 
-#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
 pub struct ProjectConfig {
     pub trust_level: Option<TrustLevel>,
+
+    /// Command patterns, such as `cargo test`, that run without asking for
+    /// approval. See [`crate::command_allowlist`].
+    #[serde(default)]
+    pub always_allow_commands: Vec<String>,
 }
 
 impl ProjectConfig {
@@ -834,6 +1143,17 @@ impl ConfigToml {
         }
     }
 
+    /// Record `trust_level` for `project` in memory, keyed the same way
+    /// [`set_project_trust_level`] writes it to `config.toml`. Lets callers
+    /// preview the effective policy of a trust decision before making it.
+    pub fn set_project_trust_level(&mut self, project: &Path, trust_level: TrustLevel) {
+        self.projects
+            .get_or_insert_with(HashMap::new)
+            .entry(project.to_string_lossy().to_string())
+            .or_default()
+            .trust_level = Some(trust_level);
+    }
+
     /// Resolves the cwd to an existing project, or returns None if ConfigToml
     /// does not contain a project corresponding to cwd or a git repo for cwd
     pub fn get_active_project(&self, resolved_cwd: &Path) -> Option<ProjectConfig> {
@@ -863,16 +1183,7 @@ impl ConfigToml {
         let profile = override_profile.or_else(|| self.profile.clone());
 
         match profile {
-            Some(key) => {
-                if let Some(profile) = self.profiles.get(key.as_str()) {
-                    return Ok(profile.clone());
-                }
-
-                Err(std::io::Error::new(
-                    std::io::ErrorKind::NotFound,
-                    format!("config profile `{key}` not found"),
-                ))
-            }
+            Some(key) => resolve_profile(&self.profiles, &key),
             None => Ok(ConfigProfile::default()),
         }
     }
@@ -898,6 +1209,12 @@ pub struct ConfigOverrides {
     pub experimental_sandbox_command_assessment: Option<bool>,
     /// Additional directories that should be treated as writable roots for this session.
     pub additional_writable_roots: Vec<PathBuf>,
+    /// Block all outbound network access except the model provider.
+    pub offline: Option<bool>,
+    /// Persist nothing under `CODEX_HOME` for this run.
+    pub ephemeral: Option<bool>,
+    /// Playback speed for the `replay` provider.
+    pub replay_speed: Option<f64>,
 }
 
 /// Resolves the OSS provider from CLI override, profile config, or global config.
@@ -956,33 +1273,47 @@ impl Config {
             tools_web_search_request: override_tools_web_search_request,
             experimental_sandbox_command_assessment: sandbox_command_assessment_override,
             additional_writable_roots,
+            offline: offline_override,
+            ephemeral,
+            replay_speed: replay_speed_override,
         } = overrides;
 
+        let offline = offline_override
+            .or(cfg.network.as_ref().and_then(|n| n.offline))
+            .unwrap_or(false);
+
         let active_profile_name = config_profile_key
             .as_ref()
             .or(cfg.profile.as_ref())
             .cloned();
         let config_profile = match active_profile_name.as_ref() {
-            Some(key) => cfg
-                .profiles
-                .get(key)
-                .ok_or_else(|| {
-                    std::io::Error::new(
-                        std::io::ErrorKind::NotFound,
-                        format!("config profile `{key}` not found"),
-                    )
-                })?
-                .clone(),
+            Some(key) => resolve_profile(&cfg.profiles, key)?,
             None => ConfigProfile::default(),
         };
 
+        let mcp_servers = config_profile.effective_mcp_servers(&cfg.mcp_servers);
+        let mcp_server_profiles = match (&active_profile_name, &config_profile.mcp_servers) {
+            (Some(profile), Some(servers)) => servers
+                .keys()
+                .filter_map(|name| {
+                    mcp_server_source(&cfg.profiles, profile, name)
+                        .map(|source| (name.clone(), source))
+                })
+                .collect(),
+            _ => HashMap::new(),
+        };
+
         let feature_overrides = FeatureOverrides {
             include_apply_patch_tool: include_apply_patch_tool_override,
             web_search_request: override_tools_web_search_request,
             experimental_sandbox_command_assessment: sandbox_command_assessment_override,
         };
 
-        let features = Features::from_config(&cfg, &config_profile, feature_overrides);
+        let mut features = Features::from_config(&cfg, &config_profile, feature_overrides);
+        if offline {
+            // Hosted web search is outbound traffic beyond the model provider.
+            features.disable(Feature::WebSearchRequest);
+        }
         #[cfg(target_os = "windows")]
         {
             crate::safety::set_windows_sandbox_enabled(features.enabled(Feature::WindowsSandbox));
@@ -1020,20 +1351,27 @@ impl Config {
                 }
             })
             .collect();
-        let active_project = cfg
-            .get_active_project(&resolved_cwd)
-            .unwrap_or(ProjectConfig { trust_level: None });
+        let path_filter = load_path_filter(&resolved_cwd);
+        let active_project = cfg.get_active_project(&resolved_cwd).unwrap_or_default();
 
         let SandboxPolicyResolution {
             policy: mut sandbox_policy,
             forced_auto_mode_downgraded_on_windows,
         } = cfg.derive_sandbox_policy(sandbox_mode, config_profile.sandbox_mode, &resolved_cwd);
-        if let SandboxPolicy::WorkspaceWrite { writable_roots, .. } = &mut sandbox_policy {
+        if let SandboxPolicy::WorkspaceWrite {
+            writable_roots,
+            network_access,
+            ..
+        } = &mut sandbox_policy
+        {
             for path in additional_writable_roots {
                 if !writable_roots.iter().any(|existing| existing == &path) {
                     writable_roots.push(path);
                 }
             }
+            if offline {
+                *network_access = false;
+            }
         }
         let approval_policy = approval_policy_override
             .or(config_profile.approval_policy)
@@ -1060,6 +1398,7 @@ impl Config {
         let mut model_providers = built_in_model_providers();
         // Merge user-defined providers into the built-in list.
         for (key, provider) in cfg.model_providers.into_iter() {
+            provider.validate_http_headers(&key)?;
             model_providers.entry(key).or_insert(provider);
         }
 
@@ -1067,7 +1406,7 @@ impl Config {
             .or(config_profile.model_provider)
             .or(cfg.model_provider)
             .unwrap_or_else(|| "openai".to_string());
-        let model_provider = model_providers
+        let mut model_provider = model_providers
             .get(&model_provider_id)
             .ok_or_else(|| {
                 std::io::Error::new(
@@ -1076,6 +1415,25 @@ impl Config {
                 )
             })?
             .clone();
+        model_provider.apply_ollama_num_ctx_env(&model_provider_id);
+        let replay_speed = replay_speed_override
+            .or(cfg.replay_speed)
+            .unwrap_or(DEFAULT_REPLAY_SPEED);
+        if !(replay_speed.is_finite() && replay_speed >= 0.0) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("replay_speed must be a non-negative number, got {replay_speed}"),
+            ));
+        }
+        let replay_path = cfg.replay_path.map(|path| resolved_cwd.join(path));
+        if model_provider.wire_api == WireApi::Replay && replay_path.is_none() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "model provider `{model_provider_id}` replays a recording; set `replay_path`"
+                ),
+            ));
+        }
 
         let shell_environment_policy = cfg.shell_environment_policy.into();
 
@@ -1193,11 +1551,17 @@ impl Config {
             // The config.toml omits "_mode" because it's a config file. However, "_mode"
             // is important in code to differentiate the mode from the store implementation.
             cli_auth_credentials_store_mode: cfg.cli_auth_credentials_store.unwrap_or_default(),
-            mcp_servers: cfg.mcp_servers,
+            auth_refresh_window: cfg
+                .auth_refresh_window_secs
+                .map_or(DEFAULT_REFRESH_WINDOW, Duration::from_secs),
+            mcp_servers,
+            mcp_server_profiles,
             // The config.toml omits "_mode" because it's a config file. However, "_mode"
             // is important in code to differentiate the mode from the store implementation.
             mcp_oauth_credentials_store_mode: cfg.mcp_oauth_credentials_store.unwrap_or_default(),
             model_providers,
+            replay_path,
+            replay_speed,
             project_doc_max_bytes: cfg.project_doc_max_bytes.unwrap_or(PROJECT_DOC_MAX_BYTES),
             project_doc_fallback_filenames: cfg
                 .project_doc_fallback_filenames
@@ -1213,6 +1577,7 @@ impl Config {
                 })
                 .collect(),
             tool_output_token_limit: cfg.tool_output_token_limit,
+            tool_output_limits: cfg.tool_output_limits.unwrap_or_default(),
             codex_home,
             history,
             file_opener: cfg.file_opener.unwrap_or(UriBasedFileOpener::VsCode),
@@ -1253,6 +1618,55 @@ impl Config {
                 .as_ref()
                 .map(|t| t.notifications.clone())
                 .unwrap_or_default(),
+            tui_word_diff_extensions: cfg
+                .tui
+                .as_ref()
+                .and_then(|t| t.word_diff_extensions.clone())
+                .unwrap_or_else(|| {
+                    DEFAULT_WORD_DIFF_EXTENSIONS
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect()
+                }),
+            tui_turn_separators: cfg
+                .tui
+                .as_ref()
+                .map(|t| t.turn_separators)
+                .unwrap_or_default(),
+            tui_cloud_tasks_poll_interval: cfg
+                .tui
+                .as_ref()
+                .and_then(|t| t.cloud_tasks_poll_interval_secs)
+                .filter(|secs| *secs > 0)
+                .map(|secs| Duration::from_secs(secs.max(MIN_CLOUD_TASKS_POLL_INTERVAL_SECS))),
+            tui_set_terminal_title: cfg
+                .tui
+                .as_ref()
+                .and_then(|t| t.set_terminal_title)
+                .unwrap_or(true),
+            tui_animations: cfg.tui.as_ref().map(|t| t.animations).unwrap_or_default(),
+            tui_paste_image_max_bytes: cfg
+                .tui
+                .as_ref()
+                .and_then(|t| t.paste_image_max_bytes)
+                .unwrap_or(DEFAULT_PASTE_IMAGE_MAX_BYTES),
+            tui_hyperlinks: cfg.tui.as_ref().map(|t| t.hyperlinks).unwrap_or_default(),
+            tui_mouse: cfg.tui.as_ref().and_then(|t| t.mouse).unwrap_or(false),
+            tui_exec_follow_lines: cfg
+                .tui
+                .as_ref()
+                .and_then(|t| t.exec_follow_lines)
+                .unwrap_or(DEFAULT_EXEC_FOLLOW_LINES),
+            tui_rate_limit_warnings: resolve_rate_limit_warnings(
+                cfg.tui
+                    .as_ref()
+                    .and_then(|t| t.rate_limit_warnings.as_ref()),
+            ),
+            tui_show_filtered_env_vars: cfg
+                .tui
+                .as_ref()
+                .and_then(|t| t.show_filtered_env_vars)
+                .unwrap_or(false),
             otel: {
                 let t: OtelConfigToml = cfg.otel.unwrap_or_default();
                 let log_user_prompt = t.log_user_prompt.unwrap_or(false);
@@ -1266,10 +1680,35 @@ impl Config {
                     exporter,
                 }
             },
+            offline,
+            tool_inventory: resolve_tool_inventory(cfg.tool_inventory.as_ref()),
+            worktree_base_check: resolve_worktree_base_check(cfg.worktree_base_check.as_ref()),
+            input_prompt_detection: resolve_input_prompt_detection(
+                cfg.input_prompt_detection.as_ref(),
+            ),
+            session_recall_exclude_projects: resolve_session_recall_exclude_projects(
+                cfg.session_recall.as_ref(),
+            ),
+            approval_explanation: resolve_approval_explanation(cfg.approvals.as_ref()),
+            turn_limits: cfg.turn_limits.unwrap_or_default(),
+            mcp_tool_cache: cfg.mcp_tool_cache.unwrap_or_default(),
+            notification_hooks: cfg.notifications.unwrap_or_default(),
+            file_search: resolve_file_search_filters(cfg.file_search.as_ref())?,
+            path_filter,
+            persistence: if ephemeral.unwrap_or(false) {
+                PersistencePolicy::ephemeral()?
+            } else {
+                PersistencePolicy::persistent()
+            },
         };
         Ok(config)
     }
 
+    /// Policy gating outbound network traffic for this configuration.
+    pub fn network_policy(&self) -> NetworkPolicy {
+        NetworkPolicy::new(self.offline)
+    }
+
     fn load_instructions(codex_dir: Option<&Path>) -> Option<String> {
         let base = codex_dir?;
         for candidate in [LOCAL_PROJECT_DOC_FILENAME, DEFAULT_PROJECT_DOC_FILENAME] {
@@ -1337,6 +1776,135 @@ fn default_review_model() -> String {
     OPENAI_DEFAULT_REVIEW_MODEL.to_string()
 }
 
+/// Built-in tool candidates plus `extra_tools`, or `None` when disabled.
+fn resolve_tool_inventory(toml: Option<&ToolInventoryToml>) -> Option<Vec<String>> {
+    if toml.and_then(|t| t.enabled) == Some(false) {
+        return None;
+    }
+    let mut tools: Vec<String> = TOOL_CANDIDATES.iter().map(ToString::to_string).collect();
+    for tool in toml.map(|t| t.extra_tools.as_slice()).unwrap_or_default() {
+        if !tools.contains(tool) {
+            tools.push(tool.clone());
+        }
+    }
+    Some(tools)
+}
+
+/// Base check settings with defaults applied, or `None` when disabled.
+fn resolve_worktree_base_check(toml: Option<&WorktreeBaseCheckToml>) -> Option<BaseCheckSettings> {
+    if toml.and_then(|t| t.enabled) == Some(false) {
+        return None;
+    }
+    let defaults = BaseCheckSettings::default();
+    Some(BaseCheckSettings {
+        min_commits_behind: toml
+            .and_then(|t| t.min_commits_behind)
+            .unwrap_or(defaults.min_commits_behind),
+        interval: toml
+            .and_then(|t| t.interval_minutes)
+            .map_or(defaults.interval, |minutes| {
+                Duration::from_secs(minutes.saturating_mul(60))
+            }),
+    })
+}
+
+/// Prompt detection settings with defaults applied, or `None` when disabled.
+fn resolve_input_prompt_detection(
+    toml: Option<&InputPromptDetectionToml>,
+) -> Option<PromptDetectionSettings> {
+    if toml.and_then(|t| t.enabled) == Some(false) {
+        return None;
+    }
+    let defaults = PromptDetectionSettings::default();
+    Some(PromptDetectionSettings {
+        idle: toml
+            .and_then(|t| t.idle_seconds)
+            .map_or(defaults.idle, Duration::from_secs),
+        min_confidence: toml
+            .and_then(|t| t.min_confidence)
+            .unwrap_or(defaults.min_confidence),
+    })
+}
+
+/// Explanation settings with defaults applied, or `None` unless enabled.
+fn resolve_approval_explanation(toml: Option<&ApprovalsToml>) -> Option<ExplanationSettings> {
+    if toml.and_then(|t| t.explain) != Some(true) {
+        return None;
+    }
+    let defaults = ExplanationSettings::default();
+    Some(ExplanationSettings {
+        model: toml
+            .and_then(|t| t.explain_model.clone())
+            .unwrap_or(defaults.model),
+        timeout: toml
+            .and_then(|t| t.explain_timeout_ms)
+            .map_or(defaults.timeout, Duration::from_millis),
+    })
+}
+
+/// Thresholds clamped to 0–100, with `critical` never above `warn`.
+fn resolve_rate_limit_warnings(toml: Option<&RateLimitWarningsToml>) -> RateLimitWarnings {
+    let defaults = RateLimitWarnings::default();
+    let warn = toml
+        .and_then(|t| t.warn)
+        .unwrap_or(defaults.warn)
+        .clamp(0.0, 100.0);
+    let critical = toml
+        .and_then(|t| t.critical)
+        .unwrap_or(defaults.critical)
+        .clamp(0.0, warn);
+    RateLimitWarnings { warn, critical }
+}
+
+fn resolve_file_search_filters(toml: Option<&FileSearchToml>) -> std::io::Result<SearchFilters> {
+    let defaults = SearchFilters::default();
+    let Some(toml) = toml else {
+        return Ok(defaults);
+    };
+    let filters = SearchFilters {
+        include: toml.include.clone().unwrap_or_default(),
+        exclude: toml.exclude.clone().unwrap_or_default(),
+        follow_symlinks: toml.follow_symlinks.unwrap_or(defaults.follow_symlinks),
+        max_depth: toml.max_depth,
+        max_file_count: toml.max_file_count,
+        ..defaults
+    };
+    filters.validate().map_err(|err| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("invalid glob in [file_search]: {err}"),
+        )
+    })?;
+    Ok(filters)
+}
+
+/// `.codexignore` rules from the root of the repository containing `cwd`, or
+/// from `cwd` itself outside a repository. An unreadable or invalid file is
+/// reported and treated as absent.
+fn load_path_filter(cwd: &Path) -> PathFilter {
+    let root = get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf());
+    PathFilter::load(&root).unwrap_or_else(|err| {
+        tracing::warn!(
+            "ignoring {}: {err}",
+            root.join(CODEXIGNORE_FILENAME).display()
+        );
+        PathFilter::default()
+    })
+}
+
+/// Excluded project roots, canonicalized where they exist so they compare
+/// against the `cwd` recorded in rollouts.
+fn resolve_session_recall_exclude_projects(toml: Option<&SessionRecallToml>) -> Vec<PathBuf> {
+    toml.and_then(|t| t.exclude_projects.as_ref())
+        .map(|projects| {
+            projects
+                .iter()
+                .map(|path| canonicalize(path).unwrap_or_else(|_| path.clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Returns the path to the Codex configuration directory, which can be
 /// specified by the `CODEX_HOME` environment variable. If not set, defaults to
 /// `~/.codex`.
@@ -1377,10 +1945,16 @@ mod tests {
     use crate::config::edit::ConfigEdit;
     use crate::config::edit::ConfigEditsBuilder;
     use crate::config::edit::apply_blocking;
+    use crate::config::profile::render_profiles;
     use crate::config::types::HistoryPersistence;
     use crate::config::types::McpServerTransportConfig;
     use crate::config::types::Notifications;
     use crate::features::Feature;
+    use crate::input_prompt::PromptConfidence;
+    use crate::network_policy::NetworkCapability;
+    use crate::network_policy::OfflineBlocked;
+    use crate::persistence_policy::EphemeralBlocked;
+    use crate::persistence_policy::PersistenceKind;
 
     use super::*;
     use pretty_assertions::assert_eq;
@@ -1400,6 +1974,9 @@ persistence = "save-all"
             Some(History {
                 persistence: HistoryPersistence::SaveAll,
                 max_bytes: None,
+                max_entries: None,
+                archive_retention_days: None,
+                sensitive_patterns: Vec::new(),
             }),
             history_with_persistence_cfg.history
         );
@@ -1415,22 +1992,364 @@ persistence = "none"
             Some(History {
                 persistence: HistoryPersistence::None,
                 max_bytes: None,
+                max_entries: None,
+                archive_retention_days: None,
+                sensitive_patterns: Vec::new(),
             }),
             history_no_persistence_cfg.history
         );
     }
 
     #[test]
-    fn tui_config_missing_notifications_field_defaults_to_enabled() {
-        let cfg = r#"
-[tui]
-"#;
-
-        let parsed = toml::from_str::<ConfigToml>(cfg)
-            .expect("TUI config without notifications should succeed");
-        let tui = parsed.tui.expect("config should include tui section");
+    fn tui_config_missing_notifications_field_defaults_to_enabled() {
+        let cfg = r#"
+[tui]
+"#;
+
+        let parsed = toml::from_str::<ConfigToml>(cfg)
+            .expect("TUI config without notifications should succeed");
+        let tui = parsed.tui.expect("config should include tui section");
+
+        assert_eq!(tui.notifications, Notifications::Enabled(true));
+    }
+
+    #[test]
+    fn tui_word_diff_extensions_override_defaults() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let defaults = Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(
+            defaults.tui_word_diff_extensions,
+            vec!["md", "txt", "json", "yaml", "toml"]
+        );
+
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[tui]
+word_diff_extensions = ["rst", "adoc"]
+"#,
+        )
+        .expect("TUI config with word_diff_extensions should parse");
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(config.tui_word_diff_extensions, vec!["rst", "adoc"]);
+        Ok(())
+    }
+
+    #[test]
+    fn tui_turn_separators_parse_modes() {
+        let parsed = toml::from_str::<ConfigToml>(
+            r#"
+[tui]
+turn_separators = "stripe"
+"#,
+        )
+        .expect("TUI config with turn_separators should parse");
+        let tui = parsed.tui.expect("config should include tui section");
+        assert_eq!(tui.turn_separators, TurnSeparators::Stripe);
+
+        let parsed = toml::from_str::<ConfigToml>("[tui]\n")
+            .expect("TUI config without turn_separators should parse");
+        let tui = parsed.tui.expect("config should include tui section");
+        assert_eq!(tui.turn_separators, TurnSeparators::Off);
+    }
+
+    #[test]
+    fn tui_cloud_tasks_poll_interval_is_clamped_or_disabled() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let resolve = |toml: &str| -> std::io::Result<Option<Duration>> {
+            let cfg = toml::from_str::<ConfigToml>(toml).expect("TUI config should parse");
+            Ok(Config::load_from_base_config_with_overrides(
+                cfg,
+                ConfigOverrides::default(),
+                codex_home.path().to_path_buf(),
+            )?
+            .tui_cloud_tasks_poll_interval)
+        };
+
+        assert_eq!(resolve("[tui]\n")?, None);
+        assert_eq!(
+            resolve("[tui]\ncloud_tasks_poll_interval_secs = 0\n")?,
+            None
+        );
+        assert_eq!(
+            resolve("[tui]\ncloud_tasks_poll_interval_secs = 3\n")?,
+            Some(Duration::from_secs(MIN_CLOUD_TASKS_POLL_INTERVAL_SECS))
+        );
+        assert_eq!(
+            resolve("[tui]\ncloud_tasks_poll_interval_secs = 120\n")?,
+            Some(Duration::from_secs(120))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn tui_set_terminal_title_defaults_to_enabled() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let resolve = |toml: &str| -> std::io::Result<bool> {
+            let cfg = toml::from_str::<ConfigToml>(toml).expect("TUI config should parse");
+            Ok(Config::load_from_base_config_with_overrides(
+                cfg,
+                ConfigOverrides::default(),
+                codex_home.path().to_path_buf(),
+            )?
+            .tui_set_terminal_title)
+        };
+
+        assert!(resolve("")?);
+        assert!(resolve("[tui]\n")?);
+        assert!(!resolve("[tui]\nset_terminal_title = false\n")?);
+        Ok(())
+    }
+
+    #[test]
+    fn tui_hyperlinks_default_to_auto() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let resolve = |toml: &str| -> std::io::Result<Hyperlinks> {
+            let cfg = toml::from_str::<ConfigToml>(toml).expect("TUI config should parse");
+            Ok(Config::load_from_base_config_with_overrides(
+                cfg,
+                ConfigOverrides::default(),
+                codex_home.path().to_path_buf(),
+            )?
+            .tui_hyperlinks)
+        };
+
+        assert_eq!(
+            [
+                resolve("")?,
+                resolve("[tui]\nhyperlinks = \"on\"\n")?,
+                resolve("[tui]\nhyperlinks = \"off\"\n")?,
+            ],
+            [Hyperlinks::Auto, Hyperlinks::On, Hyperlinks::Off]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn tui_mouse_is_opt_in() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let resolve = |toml: &str| -> std::io::Result<bool> {
+            let cfg = toml::from_str::<ConfigToml>(toml).expect("TUI config should parse");
+            Ok(Config::load_from_base_config_with_overrides(
+                cfg,
+                ConfigOverrides::default(),
+                codex_home.path().to_path_buf(),
+            )?
+            .tui_mouse)
+        };
+
+        assert!(!resolve("")?);
+        assert!(resolve("[tui]\nmouse = true\n")?);
+        Ok(())
+    }
+
+    #[test]
+    fn tool_inventory_extends_candidates_or_disables_probe() {
+        let parsed = toml::from_str::<ConfigToml>(
+            r#"
+[tool_inventory]
+extra_tools = ["terraform", "node"]
+"#,
+        )
+        .expect("tool_inventory config should parse");
+        let mut expected: Vec<String> = TOOL_CANDIDATES.iter().map(ToString::to_string).collect();
+        expected.push("terraform".to_string());
+        assert_eq!(
+            resolve_tool_inventory(parsed.tool_inventory.as_ref()),
+            Some(expected)
+        );
+
+        let parsed = toml::from_str::<ConfigToml>("[tool_inventory]\nenabled = false\n")
+            .expect("tool_inventory config should parse");
+        assert_eq!(resolve_tool_inventory(parsed.tool_inventory.as_ref()), None);
+    }
+
+    #[test]
+    fn worktree_base_check_applies_defaults_or_disables() {
+        let parsed = toml::from_str::<ConfigToml>(
+            r#"
+[worktree_base_check]
+min_commits_behind = 20
+interval_minutes = 2
+"#,
+        )
+        .expect("worktree_base_check config should parse");
+        assert_eq!(
+            resolve_worktree_base_check(parsed.worktree_base_check.as_ref()),
+            Some(BaseCheckSettings {
+                min_commits_behind: 20,
+                interval: Duration::from_secs(120),
+            })
+        );
+
+        assert_eq!(
+            resolve_worktree_base_check(None),
+            Some(BaseCheckSettings::default())
+        );
+
+        let parsed = toml::from_str::<ConfigToml>("[worktree_base_check]\nenabled = false\n")
+            .expect("worktree_base_check config should parse");
+        assert_eq!(
+            resolve_worktree_base_check(parsed.worktree_base_check.as_ref()),
+            None
+        );
+    }
+
+    #[test]
+    fn input_prompt_detection_applies_defaults_or_disables() {
+        let parsed = toml::from_str::<ConfigToml>(
+            r#"
+[input_prompt_detection]
+idle_seconds = 10
+min_confidence = "high"
+"#,
+        )
+        .expect("input_prompt_detection config should parse");
+        assert_eq!(
+            resolve_input_prompt_detection(parsed.input_prompt_detection.as_ref()),
+            Some(PromptDetectionSettings {
+                idle: Duration::from_secs(10),
+                min_confidence: PromptConfidence::High,
+            })
+        );
+
+        assert_eq!(
+            resolve_input_prompt_detection(None),
+            Some(PromptDetectionSettings::default())
+        );
+
+        let parsed = toml::from_str::<ConfigToml>("[input_prompt_detection]\nenabled = false\n")
+            .expect("input_prompt_detection config should parse");
+        assert_eq!(
+            resolve_input_prompt_detection(parsed.input_prompt_detection.as_ref()),
+            None
+        );
+    }
+
+    #[test]
+    fn approval_explanation_is_opt_in() {
+        assert_eq!(resolve_approval_explanation(None), None);
+
+        let parsed = toml::from_str::<ConfigToml>("[approvals]\nexplain = true\n")
+            .expect("approvals config should parse");
+        assert_eq!(
+            resolve_approval_explanation(parsed.approvals.as_ref()),
+            Some(ExplanationSettings::default())
+        );
+
+        let parsed = toml::from_str::<ConfigToml>(
+            r#"
+[approvals]
+explain = true
+explain_model = "gpt-5.1"
+explain_timeout_ms = 1500
+"#,
+        )
+        .expect("approvals config should parse");
+        assert_eq!(
+            resolve_approval_explanation(parsed.approvals.as_ref()),
+            Some(ExplanationSettings {
+                model: "gpt-5.1".to_string(),
+                timeout: Duration::from_millis(1500),
+            })
+        );
+    }
+
+    #[test]
+    fn rate_limit_warnings_parse_and_keep_critical_below_warn() {
+        assert_eq!(
+            resolve_rate_limit_warnings(None),
+            RateLimitWarnings::default()
+        );
+
+        let parsed = toml::from_str::<ConfigToml>(
+            r#"
+[tui.rate_limit_warnings]
+warn = 40
+critical = 60
+"#,
+        )
+        .expect("rate limit warnings should parse");
+        assert_eq!(
+            resolve_rate_limit_warnings(
+                parsed
+                    .tui
+                    .as_ref()
+                    .and_then(|t| t.rate_limit_warnings.as_ref())
+            ),
+            RateLimitWarnings {
+                warn: 40.0,
+                critical: 40.0,
+            }
+        );
+    }
+
+    #[test]
+    fn file_search_filters_parse_from_config() {
+        assert_eq!(
+            resolve_file_search_filters(None).expect("defaults are valid"),
+            SearchFilters::default()
+        );
+
+        let parsed = toml::from_str::<ConfigToml>(
+            r#"
+[file_search]
+exclude = ["target/", "node_modules/"]
+follow_symlinks = false
+max_file_count = 100000
+"#,
+        )
+        .expect("file_search config should parse");
+        assert_eq!(
+            resolve_file_search_filters(parsed.file_search.as_ref()).expect("valid globs"),
+            SearchFilters {
+                include: Vec::new(),
+                exclude: vec!["target/".to_string(), "node_modules/".to_string()],
+                follow_symlinks: false,
+                max_depth: None,
+                max_file_count: Some(100_000),
+                path_filter: PathFilter::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_file_search_globs_fail_config_load() {
+        let parsed = toml::from_str::<ConfigToml>("[file_search]\nexclude = [\"target/[\"]\n")
+            .expect("file_search config should parse");
+
+        let err = resolve_file_search_filters(parsed.file_search.as_ref())
+            .expect_err("unclosed character class is rejected");
+
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(
+            err.to_string().contains("invalid glob in [file_search]"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn session_recall_exclude_projects_default_to_empty() {
+        assert_eq!(
+            resolve_session_recall_exclude_projects(None),
+            Vec::<PathBuf>::new()
+        );
 
-        assert_eq!(tui.notifications, Notifications::Enabled(true));
+        let parsed = toml::from_str::<ConfigToml>(
+            "[session_recall]\nexclude_projects = [\"/nonexistent/secret\"]\n",
+        )
+        .expect("session_recall config should parse");
+        assert_eq!(
+            resolve_session_recall_exclude_projects(parsed.session_recall.as_ref()),
+            vec![PathBuf::from("/nonexistent/secret")]
+        );
     }
 
     #[test]
@@ -1638,6 +2557,18 @@ trust_level = "trusted"
         Ok(())
     }
 
+    #[cfg(feature = "keychain-auth")]
+    #[test]
+    fn auth_storage_keychain_selects_keychain_mode() -> anyhow::Result<()> {
+        let cfg: ConfigToml = toml::from_str(r#"auth_storage = "keychain""#)?;
+
+        assert_eq!(
+            cfg.cli_auth_credentials_store,
+            Some(AuthCredentialsStoreMode::Keychain)
+        );
+        Ok(())
+    }
+
     #[test]
     fn config_honors_explicit_keyring_auth_store_mode() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -1740,6 +2671,357 @@ trust_level = "trusted"
         Ok(())
     }
 
+    const INHERITING_PROFILES: &str = r#"
+[profiles.base]
+model = "o3"
+approval_policy = "on-request"
+features = { web_search_request = true, view_image_tool = false }
+
+[profiles.team]
+extends = "base"
+model_verbosity = "low"
+features = { view_image_tool = true }
+
+[profiles.fast]
+model = "gpt-5-mini"
+model_verbosity = "high"
+
+[profiles.work]
+extends = ["team", "fast"]
+model_verbosity = "medium"
+"#;
+
+    #[test]
+    fn profile_extends_resolves_multi_level_chain() -> std::io::Result<()> {
+        let cfg: ConfigToml = toml::from_str(INHERITING_PROFILES).expect("parse profiles");
+
+        // `fast` is listed after `team`, so its scalar `model` replaces the one
+        // inherited from `base`; `work` sets its own verbosity; `features`
+        // tables merge down the chain.
+        assert_eq!(
+            cfg.get_config_profile(Some("work".to_string()))?,
+            ConfigProfile {
+                model: Some("gpt-5-mini".to_string()),
+                approval_policy: Some(AskForApproval::OnRequest),
+                model_verbosity: Some(Verbosity::Medium),
+                features: Some(FeaturesToml {
+                    entries: BTreeMap::from([
+                        ("view_image_tool".to_string(), true),
+                        ("web_search_request".to_string(), true),
+                    ]),
+                }),
+                ..Default::default()
+            }
+        );
+
+        let codex_home = TempDir::new()?;
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides {
+                config_profile: Some("team".to_string()),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(config.model, "o3");
+        assert_eq!(config.approval_policy, AskForApproval::OnRequest);
+        assert!(config.features.enabled(Feature::ViewImageTool));
+        assert!(config.features.enabled(Feature::WebSearchRequest));
+
+        Ok(())
+    }
+
+    #[test]
+    fn profile_extends_reports_cycles_and_unknown_parents() {
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+[profiles.a]
+extends = "b"
+
+[profiles.b]
+extends = ["c"]
+
+[profiles.c]
+extends = "a"
+
+[profiles.orphan]
+extends = "missing"
+"#,
+        )
+        .expect("parse profiles");
+
+        let cycle = cfg
+            .get_config_profile(Some("a".to_string()))
+            .expect_err("cycle should be rejected");
+        assert_eq!(
+            cycle.to_string(),
+            "config profile inheritance cycle: a -> b -> c -> a"
+        );
+
+        let unknown = cfg
+            .get_config_profile(Some("orphan".to_string()))
+            .expect_err("unknown parent should be rejected");
+        assert_eq!(
+            unknown.to_string(),
+            "config profile `orphan` extends unknown profile `missing`"
+        );
+    }
+
+    #[test]
+    fn render_profiles_flattens_extends_when_resolved() -> std::io::Result<()> {
+        let cfg: ConfigToml = toml::from_str(INHERITING_PROFILES).expect("parse profiles");
+
+        let raw = render_profiles(&cfg.profiles, Some("work"), false)?;
+        let raw: ConfigToml = toml::from_str(&raw).expect("parse raw listing");
+        assert_eq!(
+            raw.profiles,
+            HashMap::from([("work".to_string(), cfg.profiles["work"].clone())])
+        );
+
+        let resolved = render_profiles(&cfg.profiles, Some("work"), true)?;
+        assert!(!resolved.contains("extends"));
+        let resolved: ConfigToml = toml::from_str(&resolved).expect("parse resolved listing");
+        assert_eq!(
+            resolved.profiles,
+            HashMap::from([(
+                "work".to_string(),
+                cfg.get_config_profile(Some("work".to_string()))?
+            )])
+        );
+
+        Ok(())
+    }
+
+    const PROFILE_MCP_SERVERS: &str = r#"
+[mcp_servers.docs]
+command = "docs-server"
+
+[mcp_servers.search]
+url = "https://search.example.com/mcp"
+
+[profiles.focused]
+mcp_servers_mode = "replace"
+
+[profiles.focused.mcp_servers.tracker]
+command = "tracker-server"
+
+[profiles.extra.mcp_servers.search]
+url = "https://search.internal.example.com/mcp"
+
+[profiles.extra.mcp_servers.tracker]
+command = "tracker-server"
+"#;
+
+    fn load_with_profile(
+        codex_home: &Path,
+        profile: &str,
+        cli_overrides: Vec<(String, TomlValue)>,
+    ) -> std::io::Result<Config> {
+        let base: TomlValue = toml::from_str(PROFILE_MCP_SERVERS).expect("parse config");
+        let layers = LoadedConfigLayers {
+            base,
+            managed_config: None,
+            managed_preferences: None,
+        };
+        let cfg: ConfigToml = apply_overlays(layers, cli_overrides, Some(profile))
+            .try_into()
+            .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+        Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides {
+                config_profile: Some(profile.to_string()),
+                ..Default::default()
+            },
+            codex_home.to_path_buf(),
+        )
+    }
+
+    fn sorted_server_names(config: &Config) -> Vec<&str> {
+        let mut names: Vec<&str> = config.mcp_servers.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn profile_mcp_servers_replace_global_table() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let config = load_with_profile(codex_home.path(), "focused", Vec::new())?;
+
+        assert_eq!(sorted_server_names(&config), vec!["tracker"]);
+        assert_eq!(
+            config.mcp_server_profiles,
+            HashMap::from([("tracker".to_string(), "focused".to_string())])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn profile_mcp_servers_merge_over_global_table() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let config = load_with_profile(codex_home.path(), "extra", Vec::new())?;
+
+        assert_eq!(
+            sorted_server_names(&config),
+            vec!["docs", "search", "tracker"]
+        );
+        assert_eq!(
+            config.mcp_servers["search"].transport,
+            McpServerTransportConfig::StreamableHttp {
+                url: "https://search.internal.example.com/mcp".to_string(),
+                bearer_token_env_var: None,
+                http_headers: None,
+                env_http_headers: None,
+            }
+        );
+        assert_eq!(
+            config.mcp_server_profiles,
+            HashMap::from([
+                ("search".to_string(), "extra".to_string()),
+                ("tracker".to_string(), "extra".to_string()),
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn cli_mcp_server_overrides_apply_after_profile_resolution() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let disable = |server: &str| {
+            (
+                format!("mcp_servers.{server}.enabled"),
+                TomlValue::Boolean(false),
+            )
+        };
+
+        // `search` comes from the profile, `docs` from the top-level table.
+        let config = load_with_profile(
+            codex_home.path(),
+            "extra",
+            vec![disable("search"), disable("docs")],
+        )?;
+        assert!(!config.mcp_servers["search"].enabled);
+        assert!(!config.mcp_servers["docs"].enabled);
+        assert!(config.mcp_servers["tracker"].enabled);
+
+        // `tracker` is only declared by profiles, so the override must not
+        // leave a partial entry in the top-level table.
+        let config = load_with_profile(codex_home.path(), "focused", vec![disable("tracker")])?;
+        assert_eq!(sorted_server_names(&config), vec!["tracker"]);
+        assert!(!config.mcp_servers["tracker"].enabled);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cli_mcp_server_overrides_skip_inactive_profiles() {
+        let overlay = |config_profile: Option<&str>, overrides: Vec<(String, TomlValue)>| {
+            let layers = LoadedConfigLayers {
+                base: toml::from_str(PROFILE_MCP_SERVERS).expect("parse config"),
+                managed_config: None,
+                managed_preferences: None,
+            };
+            apply_overlays(layers, overrides, config_profile)
+        };
+        let disable_tracker = (
+            "mcp_servers.tracker.enabled".to_string(),
+            TomlValue::Boolean(false),
+        );
+        let tracker_enabled = |root: &TomlValue, profile: &str| {
+            root["profiles"][profile]["mcp_servers"]["tracker"]
+                .get("enabled")
+                .cloned()
+        };
+
+        let root = overlay(Some("focused"), vec![disable_tracker.clone()]);
+        assert_eq!(
+            (
+                tracker_enabled(&root, "focused"),
+                tracker_enabled(&root, "extra"),
+                root.get("mcp_servers")
+                    .and_then(|servers| servers.get("tracker"))
+                    .cloned(),
+            ),
+            (Some(TomlValue::Boolean(false)), None, None)
+        );
+
+        // The `profile` key selects the target when no profile is passed,
+        // even when it is set by a later `-c`.
+        let root = overlay(
+            None,
+            vec![
+                disable_tracker,
+                (
+                    "profile".to_string(),
+                    TomlValue::String("extra".to_string()),
+                ),
+            ],
+        );
+        assert_eq!(
+            (
+                tracker_enabled(&root, "focused"),
+                tracker_enabled(&root, "extra")
+            ),
+            (None, Some(TomlValue::Boolean(false)))
+        );
+    }
+
+    #[test]
+    fn profile_mcp_servers_follow_extends_chain() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let base: TomlValue = toml::from_str(
+            r#"
+[profiles.team.mcp_servers.tracker]
+command = "tracker-server"
+
+[profiles.work]
+extends = "team"
+"#,
+        )
+        .expect("parse config");
+        let layers = LoadedConfigLayers {
+            base,
+            managed_config: None,
+            managed_preferences: None,
+        };
+        let overrides = vec![(
+            "mcp_servers.tracker.enabled".to_string(),
+            TomlValue::Boolean(false),
+        )];
+        let cfg: ConfigToml = apply_overlays(layers, overrides, Some("work"))
+            .try_into()
+            .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides {
+                config_profile: Some("work".to_string()),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert!(!config.mcp_servers["tracker"].enabled);
+        assert_eq!(
+            config.mcp_server_profiles,
+            HashMap::from([("tracker".to_string(), "team".to_string())])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_profile_still_errors_with_profile_mcp_servers() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let err = load_with_profile(codex_home.path(), "missing", Vec::new())
+            .expect_err("unknown profile should be rejected");
+
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert_eq!(err.to_string(), "config profile `missing` not found");
+
+        Ok(())
+    }
+
     #[test]
     fn cli_override_takes_precedence_over_profile_sandbox_mode() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -1869,7 +3151,8 @@ trust_level = "trusted"
             managed_preferences_base64: None,
         };
 
-        let root_value = load_resolved_config(codex_home.path(), Vec::new(), overrides).await?;
+        let root_value =
+            load_resolved_config(codex_home.path(), Vec::new(), None, overrides).await?;
         let cfg: ConfigToml = root_value.try_into().map_err(|e| {
             tracing::error!("Failed to deserialize overridden config: {e}");
             std::io::Error::new(std::io::ErrorKind::InvalidData, e)
@@ -1922,6 +3205,7 @@ trust_level = "trusted"
                 tool_timeout_sec: Some(Duration::from_secs(5)),
                 enabled_tools: None,
                 disabled_tools: None,
+                cache_tool_results: None,
             },
         );
 
@@ -1986,6 +3270,7 @@ trust_level = "trusted"
         let root_value = load_resolved_config(
             codex_home.path(),
             vec![("model".to_string(), TomlValue::String("cli".to_string()))],
+            None,
             overrides,
         )
         .await?;
@@ -2068,6 +3353,7 @@ bearer_token = "secret"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                cache_tool_results: None,
             },
         )]);
 
@@ -2136,6 +3422,7 @@ ZIG_VAR = "3"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                cache_tool_results: None,
             },
         )]);
 
@@ -2184,6 +3471,7 @@ ZIG_VAR = "3"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                cache_tool_results: None,
             },
         )]);
 
@@ -2230,6 +3518,7 @@ ZIG_VAR = "3"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                cache_tool_results: None,
             },
         )]);
 
@@ -2292,6 +3581,7 @@ startup_timeout_sec = 2.0
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                cache_tool_results: None,
             },
         )]);
         apply_blocking(
@@ -2366,6 +3656,7 @@ X-Auth = "DOCS_AUTH"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                cache_tool_results: None,
             },
         )]);
 
@@ -2393,6 +3684,7 @@ X-Auth = "DOCS_AUTH"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                cache_tool_results: None,
             },
         );
         apply_blocking(
@@ -2458,6 +3750,7 @@ url = "https://example.com/mcp"
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    cache_tool_results: None,
                 },
             ),
             (
@@ -2475,6 +3768,7 @@ url = "https://example.com/mcp"
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    cache_tool_results: None,
                 },
             ),
         ]);
@@ -2555,6 +3849,7 @@ url = "https://example.com/mcp"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                cache_tool_results: None,
             },
         )]);
 
@@ -2597,6 +3892,7 @@ url = "https://example.com/mcp"
                 tool_timeout_sec: None,
                 enabled_tools: Some(vec!["allowed".to_string()]),
                 disabled_tools: Some(vec!["blocked".to_string()]),
+                cache_tool_results: None,
             },
         )]);
 
@@ -2900,6 +4196,7 @@ model_verbosity = "high"
             stream_max_retries: Some(10),
             stream_idle_timeout_ms: Some(300_000),
             requires_openai_auth: false,
+            num_ctx: None,
         };
         let model_provider_map = {
             let mut model_provider_map = built_in_model_providers();
@@ -2970,12 +4267,17 @@ model_verbosity = "high"
                 notify: None,
                 cwd: fixture.cwd(),
                 cli_auth_credentials_store_mode: Default::default(),
+                auth_refresh_window: DEFAULT_REFRESH_WINDOW,
                 mcp_servers: HashMap::new(),
+                mcp_server_profiles: HashMap::new(),
                 mcp_oauth_credentials_store_mode: Default::default(),
                 model_providers: fixture.model_provider_map.clone(),
+                replay_path: None,
+                replay_speed: DEFAULT_REPLAY_SPEED,
                 project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
                 project_doc_fallback_filenames: Vec::new(),
                 tool_output_token_limit: None,
+                tool_output_limits: HashMap::new(),
                 codex_home: fixture.codex_home(),
                 history: History::default(),
                 file_opener: UriBasedFileOpener::VsCode,
@@ -2998,12 +4300,38 @@ model_verbosity = "high"
                 use_experimental_use_rmcp_client: false,
                 features: Features::with_defaults(),
                 active_profile: Some("o3".to_string()),
-                active_project: ProjectConfig { trust_level: None },
+                active_project: ProjectConfig::default(),
                 windows_wsl_setup_acknowledged: false,
                 notices: Default::default(),
                 disable_paste_burst: false,
                 tui_notifications: Default::default(),
+                tui_word_diff_extensions: DEFAULT_WORD_DIFF_EXTENSIONS
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect(),
+                tui_turn_separators: TurnSeparators::Off,
+                tui_cloud_tasks_poll_interval: None,
+                tui_set_terminal_title: true,
+                tui_animations: Animations::Full,
+                tui_paste_image_max_bytes: DEFAULT_PASTE_IMAGE_MAX_BYTES,
+                tui_hyperlinks: Hyperlinks::Auto,
+                tui_mouse: false,
+                tui_exec_follow_lines: DEFAULT_EXEC_FOLLOW_LINES,
+                tui_rate_limit_warnings: RateLimitWarnings::default(),
+                tui_show_filtered_env_vars: false,
                 otel: OtelConfig::default(),
+                offline: false,
+                tool_inventory: Some(TOOL_CANDIDATES.iter().map(ToString::to_string).collect()),
+                worktree_base_check: Some(BaseCheckSettings::default()),
+                input_prompt_detection: Some(PromptDetectionSettings::default()),
+                session_recall_exclude_projects: Vec::new(),
+                approval_explanation: None,
+                turn_limits: TurnLimits::default(),
+                mcp_tool_cache: McpToolCache::default(),
+                notification_hooks: NotificationHooks::default(),
+                file_search: SearchFilters::default(),
+                path_filter: PathFilter::default(),
+                persistence: PersistencePolicy::persistent(),
             },
             o3_profile_config
         );
@@ -3042,12 +4370,17 @@ model_verbosity = "high"
             notify: None,
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
+            auth_refresh_window: DEFAULT_REFRESH_WINDOW,
             mcp_servers: HashMap::new(),
+            mcp_server_profiles: HashMap::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
+            replay_path: None,
+            replay_speed: DEFAULT_REPLAY_SPEED,
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
+            tool_output_limits: HashMap::new(),
             codex_home: fixture.codex_home(),
             history: History::default(),
             file_opener: UriBasedFileOpener::VsCode,
@@ -3070,12 +4403,38 @@ model_verbosity = "high"
             use_experimental_use_rmcp_client: false,
             features: Features::with_defaults(),
             active_profile: Some("gpt3".to_string()),
-            active_project: ProjectConfig { trust_level: None },
+            active_project: ProjectConfig::default(),
             windows_wsl_setup_acknowledged: false,
             notices: Default::default(),
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_word_diff_extensions: DEFAULT_WORD_DIFF_EXTENSIONS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            tui_turn_separators: TurnSeparators::Off,
+            tui_cloud_tasks_poll_interval: None,
+            tui_set_terminal_title: true,
+            tui_animations: Animations::Full,
+            tui_paste_image_max_bytes: DEFAULT_PASTE_IMAGE_MAX_BYTES,
+            tui_hyperlinks: Hyperlinks::Auto,
+            tui_mouse: false,
+            tui_exec_follow_lines: DEFAULT_EXEC_FOLLOW_LINES,
+            tui_rate_limit_warnings: RateLimitWarnings::default(),
+            tui_show_filtered_env_vars: false,
             otel: OtelConfig::default(),
+            offline: false,
+            tool_inventory: Some(TOOL_CANDIDATES.iter().map(ToString::to_string).collect()),
+            worktree_base_check: Some(BaseCheckSettings::default()),
+            input_prompt_detection: Some(PromptDetectionSettings::default()),
+            session_recall_exclude_projects: Vec::new(),
+            approval_explanation: None,
+            turn_limits: TurnLimits::default(),
+            mcp_tool_cache: McpToolCache::default(),
+            notification_hooks: NotificationHooks::default(),
+            file_search: SearchFilters::default(),
+            path_filter: PathFilter::default(),
+            persistence: PersistencePolicy::persistent(),
         };
 
         assert_eq!(expected_gpt3_profile_config, gpt3_profile_config);
@@ -3129,12 +4488,17 @@ model_verbosity = "high"
             notify: None,
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
+            auth_refresh_window: DEFAULT_REFRESH_WINDOW,
             mcp_servers: HashMap::new(),
+            mcp_server_profiles: HashMap::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
+            replay_path: None,
+            replay_speed: DEFAULT_REPLAY_SPEED,
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
+            tool_output_limits: HashMap::new(),
             codex_home: fixture.codex_home(),
             history: History::default(),
             file_opener: UriBasedFileOpener::VsCode,
@@ -3157,12 +4521,38 @@ model_verbosity = "high"
             use_experimental_use_rmcp_client: false,
             features: Features::with_defaults(),
             active_profile: Some("zdr".to_string()),
-            active_project: ProjectConfig { trust_level: None },
+            active_project: ProjectConfig::default(),
             windows_wsl_setup_acknowledged: false,
             notices: Default::default(),
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_word_diff_extensions: DEFAULT_WORD_DIFF_EXTENSIONS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            tui_turn_separators: TurnSeparators::Off,
+            tui_cloud_tasks_poll_interval: None,
+            tui_set_terminal_title: true,
+            tui_animations: Animations::Full,
+            tui_paste_image_max_bytes: DEFAULT_PASTE_IMAGE_MAX_BYTES,
+            tui_hyperlinks: Hyperlinks::Auto,
+            tui_mouse: false,
+            tui_exec_follow_lines: DEFAULT_EXEC_FOLLOW_LINES,
+            tui_rate_limit_warnings: RateLimitWarnings::default(),
+            tui_show_filtered_env_vars: false,
             otel: OtelConfig::default(),
+            offline: false,
+            tool_inventory: Some(TOOL_CANDIDATES.iter().map(ToString::to_string).collect()),
+            worktree_base_check: Some(BaseCheckSettings::default()),
+            input_prompt_detection: Some(PromptDetectionSettings::default()),
+            session_recall_exclude_projects: Vec::new(),
+            approval_explanation: None,
+            turn_limits: TurnLimits::default(),
+            mcp_tool_cache: McpToolCache::default(),
+            notification_hooks: NotificationHooks::default(),
+            file_search: SearchFilters::default(),
+            path_filter: PathFilter::default(),
+            persistence: PersistencePolicy::persistent(),
         };
 
         assert_eq!(expected_zdr_profile_config, zdr_profile_config);
@@ -3202,12 +4592,17 @@ model_verbosity = "high"
             notify: None,
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
+            auth_refresh_window: DEFAULT_REFRESH_WINDOW,
             mcp_servers: HashMap::new(),
+            mcp_server_profiles: HashMap::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
+            replay_path: None,
+            replay_speed: DEFAULT_REPLAY_SPEED,
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
+            tool_output_limits: HashMap::new(),
             codex_home: fixture.codex_home(),
             history: History::default(),
             file_opener: UriBasedFileOpener::VsCode,
@@ -3230,12 +4625,38 @@ model_verbosity = "high"
             use_experimental_use_rmcp_client: false,
             features: Features::with_defaults(),
             active_profile: Some("gpt5".to_string()),
-            active_project: ProjectConfig { trust_level: None },
+            active_project: ProjectConfig::default(),
             windows_wsl_setup_acknowledged: false,
             notices: Default::default(),
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_word_diff_extensions: DEFAULT_WORD_DIFF_EXTENSIONS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            tui_turn_separators: TurnSeparators::Off,
+            tui_cloud_tasks_poll_interval: None,
+            tui_set_terminal_title: true,
+            tui_animations: Animations::Full,
+            tui_paste_image_max_bytes: DEFAULT_PASTE_IMAGE_MAX_BYTES,
+            tui_hyperlinks: Hyperlinks::Auto,
+            tui_mouse: false,
+            tui_exec_follow_lines: DEFAULT_EXEC_FOLLOW_LINES,
+            tui_rate_limit_warnings: RateLimitWarnings::default(),
+            tui_show_filtered_env_vars: false,
             otel: OtelConfig::default(),
+            offline: false,
+            tool_inventory: Some(TOOL_CANDIDATES.iter().map(ToString::to_string).collect()),
+            worktree_base_check: Some(BaseCheckSettings::default()),
+            input_prompt_detection: Some(PromptDetectionSettings::default()),
+            session_recall_exclude_projects: Vec::new(),
+            approval_explanation: None,
+            turn_limits: TurnLimits::default(),
+            mcp_tool_cache: McpToolCache::default(),
+            notification_hooks: NotificationHooks::default(),
+            file_search: SearchFilters::default(),
+            path_filter: PathFilter::default(),
+            persistence: PersistencePolicy::persistent(),
         };
 
         assert_eq!(expected_gpt5_profile_config, gpt5_profile_config);
@@ -3392,6 +4813,20 @@ trust_level = "trusted"
         Ok(())
     }
 
+    #[test]
+    fn in_memory_trust_level_matches_active_project_lookup() {
+        let project = Path::new("/workspace/project");
+        let mut cfg = ConfigToml::default();
+        cfg.set_project_trust_level(project, TrustLevel::Untrusted);
+        assert_eq!(
+            cfg.get_active_project(project),
+            Some(ProjectConfig {
+                trust_level: Some(TrustLevel::Untrusted),
+                always_allow_commands: Vec::new(),
+            })
+        );
+    }
+
     #[test]
     fn test_untrusted_project_gets_workspace_write_sandbox() -> anyhow::Result<()> {
         let config_with_untrusted = r#"
@@ -3512,6 +4947,7 @@ trust_level = "untrusted"
             test_path.to_string_lossy().to_string(),
             ProjectConfig {
                 trust_level: Some(TrustLevel::Untrusted),
+                always_allow_commands: Vec::new(),
             },
         );
 
@@ -3551,6 +4987,102 @@ trust_level = "untrusted"
 
         Ok(())
     }
+
+    #[test]
+    fn offline_mode_disables_search_and_sandbox_network() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+sandbox_mode = "workspace-write"
+
+[sandbox_workspace_write]
+network_access = true
+
+[features]
+web_search_request = true
+
+[network]
+offline = true
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert!(config.offline);
+        assert!(!config.tools_web_search_request);
+        assert!(!config.features.enabled(Feature::WebSearchRequest));
+        if let SandboxPolicy::WorkspaceWrite { network_access, .. } = config.sandbox_policy {
+            assert!(!network_access);
+        }
+        assert_eq!(
+            config.network_policy().check(NetworkCapability::Updates),
+            Err(OfflineBlocked {
+                capability: NetworkCapability::Updates
+            })
+        );
+        assert_eq!(
+            config.network_policy().check(NetworkCapability::Provider),
+            Ok(())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn offline_cli_override_wins_over_config() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = ConfigToml {
+            network: Some(NetworkToml {
+                offline: Some(true),
+            }),
+            ..Default::default()
+        };
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides {
+                offline: Some(false),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert!(!config.offline);
+        Ok(())
+    }
+
+    #[test]
+    fn ephemeral_override_blocks_persistence() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+
+        let config = Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides {
+                ephemeral: Some(true),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert!(config.persistence.is_ephemeral());
+        assert_eq!(
+            config.persistence.check(PersistenceKind::Rollout),
+            Err(EphemeralBlocked {
+                kind: PersistenceKind::Rollout
+            })
+        );
+        assert!(
+            !config
+                .persistence
+                .cache_home(&config.codex_home)
+                .starts_with(codex_home.path())
+        );
+        Ok(())
+    }
 }
 
 #[cfg(test)]
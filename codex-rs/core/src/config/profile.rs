@@ -1,6 +1,10 @@
 use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::path::PathBuf;
 
+use crate::config::types::McpServerConfig;
 use crate::protocol::AskForApproval;
 use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::config_types::ReasoningSummary;
@@ -9,8 +13,10 @@ use codex_protocol::config_types::Verbosity;
 
 /// Collection of common configuration options that a user can define as a unit
 /// in `config.toml`.
-#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
 pub struct ConfigProfile {
+    /// Profile(s) this one inherits from. See [`resolve_profile`].
+    pub extends: Option<ProfileParents>,
     pub model: Option<String>,
     /// The key in the `model_providers` map identifying the
     /// [`ModelProviderInfo`] to use.
@@ -34,6 +40,217 @@ pub struct ConfigProfile {
     #[serde(default)]
     pub features: Option<crate::features::FeaturesToml>,
     pub oss_provider: Option<String>,
+    /// MCP servers for this profile, combined with the top-level
+    /// `mcp_servers` table as `mcp_servers_mode` says.
+    pub mcp_servers: Option<HashMap<String, McpServerConfig>>,
+    pub mcp_servers_mode: Option<McpServersMode>,
+}
+
+/// How a profile's `mcp_servers` combine with the top-level table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum McpServersMode {
+    /// Use only the profile's servers.
+    Replace,
+    /// Add the profile's servers to the top-level ones; a profile entry wins
+    /// over a top-level entry of the same name.
+    #[default]
+    Merge,
+}
+
+/// Value of a profile's `extends` key: one parent or an ordered list.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum ProfileParents {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl ProfileParents {
+    pub fn names(&self) -> &[String] {
+        match self {
+            ProfileParents::One(name) => std::slice::from_ref(name),
+            ProfileParents::Many(names) => names,
+        }
+    }
+}
+
+impl ConfigProfile {
+    /// Layer `self` over `parent`: scalars and arrays set on `self` replace the
+    /// parent's, tables (`features`, `mcp_servers`) merge key by key.
+    fn merged_over(self, parent: ConfigProfile) -> ConfigProfile {
+        let features = match (parent.features, self.features) {
+            (Some(mut parent), Some(child)) => {
+                parent.entries.extend(child.entries);
+                Some(parent)
+            }
+            (parent, child) => child.or(parent),
+        };
+        let mcp_servers = match (parent.mcp_servers, self.mcp_servers) {
+            (Some(mut parent), Some(child)) => {
+                parent.extend(child);
+                Some(parent)
+            }
+            (parent, child) => child.or(parent),
+        };
+        ConfigProfile {
+            extends: None,
+            model: self.model.or(parent.model),
+            model_provider: self.model_provider.or(parent.model_provider),
+            approval_policy: self.approval_policy.or(parent.approval_policy),
+            sandbox_mode: self.sandbox_mode.or(parent.sandbox_mode),
+            model_reasoning_effort: self
+                .model_reasoning_effort
+                .or(parent.model_reasoning_effort),
+            model_reasoning_summary: self
+                .model_reasoning_summary
+                .or(parent.model_reasoning_summary),
+            model_verbosity: self.model_verbosity.or(parent.model_verbosity),
+            chatgpt_base_url: self.chatgpt_base_url.or(parent.chatgpt_base_url),
+            experimental_instructions_file: self
+                .experimental_instructions_file
+                .or(parent.experimental_instructions_file),
+            experimental_compact_prompt_file: self
+                .experimental_compact_prompt_file
+                .or(parent.experimental_compact_prompt_file),
+            include_apply_patch_tool: self
+                .include_apply_patch_tool
+                .or(parent.include_apply_patch_tool),
+            experimental_use_unified_exec_tool: self
+                .experimental_use_unified_exec_tool
+                .or(parent.experimental_use_unified_exec_tool),
+            experimental_use_rmcp_client: self
+                .experimental_use_rmcp_client
+                .or(parent.experimental_use_rmcp_client),
+            experimental_use_freeform_apply_patch: self
+                .experimental_use_freeform_apply_patch
+                .or(parent.experimental_use_freeform_apply_patch),
+            experimental_sandbox_command_assessment: self
+                .experimental_sandbox_command_assessment
+                .or(parent.experimental_sandbox_command_assessment),
+            tools_web_search: self.tools_web_search.or(parent.tools_web_search),
+            tools_view_image: self.tools_view_image.or(parent.tools_view_image),
+            features,
+            oss_provider: self.oss_provider.or(parent.oss_provider),
+            mcp_servers,
+            mcp_servers_mode: self.mcp_servers_mode.or(parent.mcp_servers_mode),
+        }
+    }
+
+    /// The MCP servers in effect under this profile, given the top-level
+    /// `mcp_servers` table.
+    pub fn effective_mcp_servers(
+        &self,
+        global: &HashMap<String, McpServerConfig>,
+    ) -> HashMap<String, McpServerConfig> {
+        let Some(servers) = &self.mcp_servers else {
+            return global.clone();
+        };
+        match self.mcp_servers_mode.unwrap_or_default() {
+            McpServersMode::Replace => servers.clone(),
+            McpServersMode::Merge => {
+                let mut merged = global.clone();
+                merged.extend(servers.clone());
+                merged
+            }
+        }
+    }
+}
+
+/// Resolve profile `name` against its `extends` chain.
+///
+/// Parents listed in `extends` are applied in order (later entries win), and
+/// the profile's own values win over every parent. Unknown parents and
+/// inheritance cycles are reported as errors naming the offending profiles.
+pub fn resolve_profile(
+    profiles: &HashMap<String, ConfigProfile>,
+    name: &str,
+) -> std::io::Result<ConfigProfile> {
+    let Some(profile) = profiles.get(name) else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("config profile `{name}` not found"),
+        ));
+    };
+    resolve_with_chain(profiles, name, profile, &mut vec![name.to_string()])
+}
+
+fn resolve_with_chain(
+    profiles: &HashMap<String, ConfigProfile>,
+    name: &str,
+    profile: &ConfigProfile,
+    chain: &mut Vec<String>,
+) -> std::io::Result<ConfigProfile> {
+    let parents = profile
+        .extends
+        .as_ref()
+        .map(ProfileParents::names)
+        .unwrap_or_default();
+
+    let mut base = ConfigProfile::default();
+    for parent_name in parents {
+        if chain.contains(parent_name) {
+            chain.push(parent_name.clone());
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("config profile inheritance cycle: {}", chain.join(" -> ")),
+            ));
+        }
+        let Some(parent) = profiles.get(parent_name) else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("config profile `{name}` extends unknown profile `{parent_name}`"),
+            ));
+        };
+        chain.push(parent_name.clone());
+        let resolved = resolve_with_chain(profiles, parent_name, parent, chain)?;
+        chain.pop();
+        base = resolved.merged_over(base);
+    }
+
+    Ok(profile.clone().merged_over(base))
+}
+
+/// Names in the `extends` chain of profile `name`, highest priority first:
+/// the profile itself, then its parents from last to first, each followed by
+/// its own chain. `parents_of` returns a profile's `extends` list. Unknown
+/// parents and cycles are not errors here; [`resolve_profile`] reports them.
+pub fn profile_chain(name: &str, parents_of: &dyn Fn(&str) -> Vec<String>) -> Vec<String> {
+    let mut chain = Vec::new();
+    collect_chain(name, parents_of, &mut chain);
+    chain
+}
+
+fn collect_chain(name: &str, parents_of: &dyn Fn(&str) -> Vec<String>, chain: &mut Vec<String>) {
+    if chain.iter().any(|seen| seen == name) {
+        return;
+    }
+    chain.push(name.to_string());
+    for parent in parents_of(name).iter().rev() {
+        collect_chain(parent, parents_of, chain);
+    }
+}
+
+/// The profile in `active`'s `extends` chain whose `mcp_servers` entry for
+/// `server` is in effect once `active` is resolved, if any.
+pub fn mcp_server_source(
+    profiles: &HashMap<String, ConfigProfile>,
+    active: &str,
+    server: &str,
+) -> Option<String> {
+    let parents_of = |name: &str| {
+        profiles
+            .get(name)
+            .and_then(|profile| profile.extends.as_ref())
+            .map(|parents| parents.names().to_vec())
+            .unwrap_or_default()
+    };
+    profile_chain(active, &parents_of).into_iter().find(|name| {
+        profiles
+            .get(name)
+            .and_then(|profile| profile.mcp_servers.as_ref())
+            .is_some_and(|servers| servers.contains_key(server))
+    })
 }
 
 impl From<ConfigProfile> for codex_app_server_protocol::Profile {
@@ -49,3 +266,39 @@ impl From<ConfigProfile> for codex_app_server_protocol::Profile {
         }
     }
 }
+
+/// Render `profiles` (or just `only`) as a `[profiles.*]` TOML document, with
+/// `extends` chains flattened when `resolved` is set.
+pub fn render_profiles(
+    profiles: &HashMap<String, ConfigProfile>,
+    only: Option<&str>,
+    resolved: bool,
+) -> std::io::Result<String> {
+    #[derive(Serialize)]
+    struct ProfilesDoc<'a> {
+        profiles: BTreeMap<&'a str, ConfigProfile>,
+    }
+
+    let names: Vec<&str> = match only {
+        Some(name) if profiles.contains_key(name) => vec![name],
+        Some(name) => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("config profile `{name}` not found"),
+            ));
+        }
+        None => profiles.keys().map(String::as_str).collect(),
+    };
+
+    let mut rendered = BTreeMap::new();
+    for name in names {
+        let profile = if resolved {
+            resolve_profile(profiles, name)?
+        } else {
+            profiles[name].clone()
+        };
+        rendered.insert(name, profile);
+    }
+
+    toml::to_string(&ProfilesDoc { profiles: rendered }).map_err(std::io::Error::other)
+}
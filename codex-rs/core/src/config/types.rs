@@ -9,6 +9,8 @@ use std::path::PathBuf;
 use std::time::Duration;
 use wildmatch::WildMatchPattern;
 
+use crate::input_prompt::PromptConfidence;
+
 use serde::Deserialize;
 use serde::Serialize;
 use serde::de::Error as SerdeError;
@@ -43,6 +45,29 @@ pub struct McpServerConfig {
     /// Explicit deny-list of tools. These tools will be removed after applying `enabled_tools`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub disabled_tools: Option<Vec<String>>,
+
+    /// Memoize this server's tool results for the session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_tool_results: Option<CacheToolResults>,
+}
+
+/// `cache_tool_results` for an MCP server: `true` caches every tool, a list
+/// only the named ones. Meant for tools that are pure functions of their
+/// arguments, such as documentation lookups.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum CacheToolResults {
+    All(bool),
+    Tools(Vec<String>),
+}
+
+impl CacheToolResults {
+    pub fn includes(&self, tool_name: &str) -> bool {
+        match self {
+            Self::All(enabled) => *enabled,
+            Self::Tools(tools) => tools.iter().any(|tool| tool == tool_name),
+        }
+    }
 }
 
 impl<'de> Deserialize<'de> for McpServerConfig {
@@ -84,6 +109,8 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             enabled_tools: Option<Vec<String>>,
             #[serde(default)]
             disabled_tools: Option<Vec<String>>,
+            #[serde(default)]
+            cache_tool_results: Option<CacheToolResults>,
         }
 
         let mut raw = RawMcpServerConfig::deserialize(deserializer)?;
@@ -100,6 +127,7 @@ impl<'de> Deserialize<'de> for McpServerConfig {
         let enabled = raw.enabled.unwrap_or_else(default_enabled);
         let enabled_tools = raw.enabled_tools.clone();
         let disabled_tools = raw.disabled_tools.clone();
+        let cache_tool_results = raw.cache_tool_results.clone();
 
         fn throw_if_set<E, T>(transport: &str, field: &str, value: Option<&T>) -> Result<(), E>
         where
@@ -153,6 +181,7 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             enabled,
             enabled_tools,
             disabled_tools,
+            cache_tool_results,
         })
     }
 }
@@ -259,6 +288,19 @@ pub struct History {
     /// If set, the maximum size of the history file in bytes.
     /// TODO(mbolin): Not currently honored.
     pub max_bytes: Option<usize>,
+
+    /// If set, the oldest entries are dropped once the file holds more than
+    /// this many.
+    pub max_entries: Option<usize>,
+
+    /// If set, archived sessions last modified more than this many days ago
+    /// are deleted.
+    pub archive_retention_days: Option<u64>,
+
+    /// Regexes for secrets that must never be written to the history file, in
+    /// addition to the built-in API key and private key patterns.
+    #[serde(default)]
+    pub sensitive_patterns: Vec<String>,
 }
 
 #[derive(Deserialize, Debug, Copy, Clone, PartialEq, Default)]
@@ -363,6 +405,294 @@ pub struct Tui {
     /// Defaults to `true`.
     #[serde(default)]
     pub notifications: Notifications,
+
+    /// File extensions whose edits are rendered as word-level diffs in the
+    /// transcript. Defaults to prose and config formats.
+    #[serde(default)]
+    pub word_diff_extensions: Option<Vec<String>>,
+
+    /// How to visually separate turns in the transcript. Defaults to `off`.
+    #[serde(default)]
+    pub turn_separators: TurnSeparators,
+
+    /// Poll Codex Cloud tasks in the background every this many seconds and
+    /// report status changes. Unset or `0` disables polling.
+    #[serde(default)]
+    pub cloud_tasks_poll_interval_secs: Option<u64>,
+
+    /// Show the project and session status in the terminal window title.
+    /// Defaults to `true`.
+    #[serde(default)]
+    pub set_terminal_title: Option<bool>,
+
+    /// Decorative animations (shimmer, spinners, ASCII art). Defaults to
+    /// `full`; slow terminals are downgraded automatically.
+    #[serde(default)]
+    pub animations: Animations,
+
+    /// Largest clipboard image, in bytes, accepted when pasting a
+    /// screenshot. Defaults to [`DEFAULT_PASTE_IMAGE_MAX_BYTES`].
+    #[serde(default)]
+    pub paste_image_max_bytes: Option<usize>,
+
+    /// Emit file references in history as OSC 8 hyperlinks. Defaults to
+    /// `auto`, which only does so in terminals known to support them.
+    #[serde(default)]
+    pub hyperlinks: Hyperlinks,
+
+    /// Capture the mouse while a full-screen view (such as the transcript) is
+    /// open, so the wheel scrolls it. Defaults to `false`.
+    #[serde(default)]
+    pub mouse: Option<bool>,
+
+    /// Output lines shown for a running command before earlier lines are
+    /// collapsed. Defaults to [`DEFAULT_EXEC_FOLLOW_LINES`].
+    #[serde(default)]
+    pub exec_follow_lines: Option<usize>,
+
+    /// When the footer's rate-limit indicator changes color.
+    #[serde(default)]
+    pub rate_limit_warnings: Option<RateLimitWarningsToml>,
+
+    /// Note under each command how many inherited environment variables
+    /// `shell_environment_policy` filtered out. Defaults to `false`.
+    #[serde(default)]
+    pub show_filtered_env_vars: Option<bool>,
+}
+
+/// Default for `tui.paste_image_max_bytes` (10 MiB).
+pub const DEFAULT_PASTE_IMAGE_MAX_BYTES: usize = 10 * 1024 * 1024;
+
+/// Default for `tui.exec_follow_lines`.
+pub const DEFAULT_EXEC_FOLLOW_LINES: usize = 20;
+
+/// Thresholds for the footer's rate-limit indicator
+/// (`[tui.rate_limit_warnings]` in config.toml), in percent of a window still
+/// left.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct RateLimitWarningsToml {
+    /// Below this the indicator turns to the warning color. Defaults to 25.
+    pub warn: Option<f64>,
+    /// Below this it turns critical and the TUI suggests `/status` once per
+    /// window. Defaults to 10.
+    pub critical: Option<f64>,
+}
+
+/// Resolved `[tui.rate_limit_warnings]`, in percent left.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitWarnings {
+    pub warn: f64,
+    pub critical: f64,
+}
+
+impl Default for RateLimitWarnings {
+    fn default() -> Self {
+        Self {
+            warn: 25.0,
+            critical: 10.0,
+        }
+    }
+}
+
+/// Upper limit for the TUI's decorative animations.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Animations {
+    #[default]
+    Full,
+    /// Animate at a low frame rate; spinners are static.
+    Reduced,
+    /// No animation frames at all.
+    Off,
+}
+
+/// Whether file references written to the scrollback become OSC 8 hyperlinks.
+/// Unless `off`, alternate-screen views also underline them.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Hyperlinks {
+    /// Only in terminals whose `TERM_PROGRAM` is known to support OSC 8.
+    #[default]
+    Auto,
+    /// In every terminal, overriding the `TERM_PROGRAM` check.
+    On,
+    Off,
+}
+
+/// Visual separation between turns in the TUI transcript.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TurnSeparators {
+    /// Alternate a subtle background tint per turn. Falls back to `line` on
+    /// terminals without color.
+    Stripe,
+    /// A dim horizontal rule with the turn's start time before each turn.
+    Line,
+    #[default]
+    Off,
+}
+
+/// Lower bound for `tui.cloud_tasks_poll_interval_secs`.
+pub const MIN_CLOUD_TASKS_POLL_INTERVAL_SECS: u64 = 10;
+
+/// Extensions rendered as word-level diffs when `tui.word_diff_extensions` is unset.
+pub const DEFAULT_WORD_DIFF_EXTENSIONS: &[&str] = &["md", "txt", "json", "yaml", "toml"];
+
+/// Settings for the tool version inventory (`[tool_inventory]` in config.toml).
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ToolInventoryToml {
+    /// Probe tool versions at session start and share them with the model.
+    /// Defaults to `true`.
+    pub enabled: Option<bool>,
+    /// Executables to probe in addition to the built-in list.
+    #[serde(default)]
+    pub extra_tools: Vec<String>,
+}
+
+/// Settings for the worktree base check (`[worktree_base_check]` in config.toml).
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct WorktreeBaseCheckToml {
+    /// Warn when a linked worktree falls behind the upstream of its base
+    /// branch. Defaults to `true`.
+    pub enabled: Option<bool>,
+    /// Report only when at least this many upstream commits are missing.
+    pub min_commits_behind: Option<usize>,
+    /// Minimum minutes between two checks in the same session.
+    pub interval_minutes: Option<u64>,
+}
+
+/// Settings for spotting commands that wait for input
+/// (`[input_prompt_detection]` in config.toml).
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct InputPromptDetectionToml {
+    /// Watch running commands for prompts. Defaults to `true`.
+    pub enabled: Option<bool>,
+    /// Seconds a command must stay quiet after printing a partial line.
+    pub idle_seconds: Option<u64>,
+    /// Ignore prompts below this confidence: `low`, `medium`, or `high`.
+    pub min_confidence: Option<PromptConfidence>,
+}
+
+/// Settings for the `search_past_sessions` tool (`[session_recall]` in
+/// config.toml). The tool itself is enabled with the `session_recall` feature.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct SessionRecallToml {
+    /// Sessions started in or below these directories are never indexed.
+    pub exclude_projects: Option<Vec<PathBuf>>,
+}
+
+/// Settings for command approval prompts (`[approvals]` in config.toml).
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ApprovalsToml {
+    /// Ask a small model for a plain-English summary of each command that
+    /// needs approval. Defaults to `false`.
+    pub explain: Option<bool>,
+    /// Model used for the summaries.
+    pub explain_model: Option<String>,
+    /// Give up on a summary after this many milliseconds.
+    pub explain_timeout_ms: Option<u64>,
+}
+
+/// Caps on a single turn (`[turn_limits]` in config.toml). Once one is
+/// reached the agent is asked to wrap up, gets one final response, and the
+/// turn ends.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TurnLimits {
+    /// Wall-clock seconds a turn may run.
+    pub max_turn_seconds: Option<u64>,
+    /// Output tokens the model may produce across a turn's requests.
+    pub max_turn_output_tokens: Option<u64>,
+}
+
+/// Limits for MCP tool results cached with `cache_tool_results`
+/// (`[mcp_tool_cache]` in config.toml). One cache is shared by all servers
+/// of a session; the least recently used entry is evicted first.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct McpToolCache {
+    pub max_entries: usize,
+    /// Seconds a cached result stays valid. Unset keeps it for the session.
+    pub ttl_sec: Option<u64>,
+}
+
+impl Default for McpToolCache {
+    fn default() -> Self {
+        Self {
+            max_entries: 256,
+            ttl_sec: None,
+        }
+    }
+}
+
+/// Commands run when the agent finishes a task, asks for approval or hits
+/// an error (`[notifications]` in config.toml). Unlike `notify`, each event
+/// gets its own command template.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct NotificationHooks {
+    pub task_complete: Option<NotificationHook>,
+    pub approval_required: Option<NotificationHook>,
+    pub error: Option<NotificationHook>,
+    /// Hook processes allowed to run at once; events past the cap are
+    /// dropped rather than queued.
+    pub max_concurrent: usize,
+}
+
+impl Default for NotificationHooks {
+    fn default() -> Self {
+        Self {
+            task_complete: None,
+            approval_required: None,
+            error: None,
+            max_concurrent: 4,
+        }
+    }
+}
+
+/// One entry of [`NotificationHooks`].
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NotificationHook {
+    /// Program followed by its arguments. `{cwd}`, `{summary}` and
+    /// `{session_id}` are replaced in every element, and each element is
+    /// passed to the program as one argument.
+    pub command: Vec<String>,
+    /// Join the expanded elements with spaces and run the result with
+    /// `sh -c` (`cmd /C` on Windows). Placeholder values are then subject to
+    /// shell interpretation.
+    #[serde(default)]
+    pub shell: bool,
+    /// Skip the event if this hook last ran less than this many
+    /// milliseconds ago.
+    pub debounce_ms: Option<u64>,
+    /// Kill the hook if it is still running after this many seconds.
+    /// Defaults to 10.
+    pub timeout_sec: Option<u64>,
+}
+
+/// Which files the TUI `@` file search walks (`[file_search]` in
+/// config.toml). Applies on top of `.gitignore` and friends.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct FileSearchToml {
+    /// Only search files matching these globs.
+    pub include: Option<Vec<String>>,
+    /// Never search files or directories matching these globs, even when
+    /// they are not ignored (e.g. `target/`, `node_modules/`).
+    pub exclude: Option<Vec<String>>,
+    /// Follow symlinks. Defaults to `true`.
+    pub follow_symlinks: Option<bool>,
+    /// Deepest directory level to descend to; files directly in the
+    /// working directory are at depth 1.
+    pub max_depth: Option<usize>,
+    /// Stop walking after this many files; results are then partial.
+    pub max_file_count: Option<usize>,
+}
+
+/// Settings for outbound network access (`[network]` in config.toml).
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct NetworkToml {
+    /// Block all outbound traffic except requests to the model provider.
+    /// Also forces sandboxed command network access off.
+    pub offline: Option<bool>,
 }
 
 /// Settings for notices we display to users via the tui and app-server clients
@@ -434,12 +764,12 @@ pub struct ShellEnvironmentPolicyToml {
 
     pub ignore_default_excludes: Option<bool>,
 
-    /// List of regular expressions.
+    /// Glob patterns, e.g. `"AWS_*"`.
     pub exclude: Option<Vec<String>>,
 
     pub r#set: Option<HashMap<String, String>>,
 
-    /// List of regular expressions.
+    /// Glob patterns, e.g. `"*PATH"`.
     pub include_only: Option<Vec<String>>,
 
     pub experimental_use_profile: Option<bool>,
@@ -447,6 +777,16 @@ pub struct ShellEnvironmentPolicyToml {
 
 pub type EnvironmentVariablePattern = WildMatchPattern<'*', '?'>;
 
+/// Compiles a user-supplied `exclude`/`include_only` glob. Variable names are
+/// case-insensitive on Windows only, so matching follows the platform.
+pub fn environment_variable_pattern(pattern: &str) -> EnvironmentVariablePattern {
+    if cfg!(windows) {
+        EnvironmentVariablePattern::new_case_insensitive(pattern)
+    } else {
+        EnvironmentVariablePattern::new(pattern)
+    }
+}
+
 /// Deriving the `env` based on this policy works as follows:
 /// 1. Create an initial map based on the `inherit` policy.
 /// 2. If `ignore_default_excludes` is false, filter the map using the default
@@ -485,14 +825,14 @@ impl From<ShellEnvironmentPolicyToml> for ShellEnvironmentPolicy {
             .exclude
             .unwrap_or_default()
             .into_iter()
-            .map(|s| EnvironmentVariablePattern::new_case_insensitive(&s))
+            .map(|s| environment_variable_pattern(&s))
             .collect();
         let r#set = toml.r#set.unwrap_or_default();
         let include_only = toml
             .include_only
             .unwrap_or_default()
             .into_iter()
-            .map(|s| EnvironmentVariablePattern::new_case_insensitive(&s))
+            .map(|s| environment_variable_pattern(&s))
             .collect();
         let use_profile = toml.experimental_use_profile.unwrap_or(false);
 
@@ -731,6 +1071,34 @@ mod tests {
         assert_eq!(cfg.disabled_tools, Some(vec!["blocked".to_string()]));
     }
 
+    #[test]
+    fn deserialize_server_config_with_cache_tool_results() {
+        let all: McpServerConfig = toml::from_str(
+            r#"
+            command = "echo"
+            cache_tool_results = true
+        "#,
+        )
+        .expect("should deserialize boolean cache_tool_results");
+        let some: McpServerConfig = toml::from_str(
+            r#"
+            command = "echo"
+            cache_tool_results = ["search"]
+        "#,
+        )
+        .expect("should deserialize tool list cache_tool_results");
+
+        assert_eq!(all.cache_tool_results, Some(CacheToolResults::All(true)));
+        assert_eq!(
+            some.cache_tool_results,
+            Some(CacheToolResults::Tools(vec!["search".to_string()]))
+        );
+        assert!(
+            some.cache_tool_results
+                .is_some_and(|cache| cache.includes("search"))
+        );
+    }
+
     #[test]
     fn deserialize_rejects_command_and_url() {
         toml::from_str::<McpServerConfig>(
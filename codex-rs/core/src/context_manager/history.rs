@@ -1,14 +1,32 @@
 use crate::codex::TurnContext;
+use crate::compact::is_summary_message;
 use crate::context_manager::normalize;
+use crate::event_mapping::parse_turn_item;
+use crate::truncate::HistoryTruncation;
 use crate::truncate::TruncationPolicy;
 use crate::truncate::truncate_function_output_items_with_policy;
 use crate::truncate::truncate_text;
+use codex_protocol::items::TurnItem;
+use codex_protocol::models::ContentItem;
 use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::HistoryFlag;
+use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::TokenUsage;
 use codex_protocol::protocol::TokenUsageInfo;
 use codex_utils_tokenizer::Tokenizer;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::ops::Deref;
+use tracing::warn;
+
+/// Replaces tool output in turns the user marked irrelevant.
+const IRRELEVANT_OUTPUT_PLACEHOLDER: &str = "[output omitted: turn marked irrelevant by the user]";
+
+/// User and assistant text in irrelevant turns is cut down to this many bytes
+/// so the model keeps the gist of the exchange.
+const IRRELEVANT_TURN_TEXT_BYTES: usize = 1_000;
 
 /// Transcript of conversation history
 #[derive(Debug, Clone, Default)]
@@ -16,6 +34,17 @@ pub(crate) struct ContextManager {
     /// The oldest items are at the beginning of the vector.
     items: Vec<ResponseItem>,
     token_info: Option<TokenUsageInfo>,
+    /// Session-wide index of the first user turn in `items`. Compaction folds
+    /// older turns into a summary, so `items` only holds the newest ones.
+    turn_offset: usize,
+    /// Session-wide turn indices whose tool outputs are left out of prompts.
+    irrelevant_turns: BTreeSet<usize>,
+    /// Session-wide turn indices the user pinned. The first turn is always
+    /// pinned as well.
+    pinned_turns: BTreeSet<usize>,
+    /// Verbatim user messages of pinned turns, captured before compaction
+    /// can fold their turn into the summary.
+    pinned_messages: BTreeMap<usize, ResponseItem>,
 }
 
 impl ContextManager {
@@ -23,6 +52,10 @@ impl ContextManager {
         Self {
             items: Vec::new(),
             token_info: TokenUsageInfo::new_or_append(&None, &None, None),
+            turn_offset: 0,
+            irrelevant_turns: BTreeSet::new(),
+            pinned_turns: BTreeSet::new(),
+            pinned_messages: BTreeMap::new(),
         }
     }
 
@@ -44,7 +77,7 @@ impl ContextManager {
     }
 
     /// `items` is ordered from oldest to newest.
-    pub(crate) fn record_items<I>(&mut self, items: I, policy: TruncationPolicy)
+    pub(crate) fn record_items<I>(&mut self, items: I, truncation: impl HistoryTruncation)
     where
         I: IntoIterator,
         I::Item: std::ops::Deref<Target = ResponseItem>,
@@ -56,24 +89,131 @@ impl ContextManager {
                 continue;
             }
 
+            let policy = truncation.policy_for_output(self.tool_name_for_output(item_ref));
             let processed = self.process_item(item_ref, policy);
             self.items.push(processed);
         }
     }
 
+    /// The tool whose call produced `item`, when it is a tool output and the
+    /// call is already recorded.
+    fn tool_name_for_output(&self, item: &ResponseItem) -> Option<&str> {
+        let (ResponseItem::FunctionCallOutput { call_id, .. }
+        | ResponseItem::CustomToolCallOutput { call_id, .. }) = item
+        else {
+            return None;
+        };
+        self.items.iter().rev().find_map(|recorded| match recorded {
+            ResponseItem::FunctionCall {
+                call_id: recorded_id,
+                name,
+                ..
+            }
+            | ResponseItem::CustomToolCall {
+                call_id: recorded_id,
+                name,
+                ..
+            } if recorded_id == call_id => Some(name.as_str()),
+            _ => None,
+        })
+    }
+
     pub(crate) fn get_history(&mut self) -> Vec<ResponseItem> {
         self.normalize_history();
         self.contents()
     }
 
     // Returns the history prepared for sending to the model.
-    // With extra response items filtered out and GhostCommits removed.
+    // With extra response items filtered out, GhostCommits removed, and turns
+    // marked irrelevant reduced to their text.
     pub(crate) fn get_history_for_prompt(&mut self) -> Vec<ResponseItem> {
         let mut history = self.get_history();
         Self::remove_ghost_snapshots(&mut history);
+        self.prune_irrelevant_turns(&mut history);
         history
     }
 
+    /// Mark the session's `nth_user_message` turn as irrelevant (or relevant
+    /// again) for future prompts.
+    pub(crate) fn set_turn_irrelevant(&mut self, nth_user_message: usize, irrelevant: bool) {
+        if irrelevant {
+            self.irrelevant_turns.insert(nth_user_message);
+        } else {
+            self.irrelevant_turns.remove(&nth_user_message);
+        }
+    }
+
+    /// Pin (or unpin) the session's `nth_user_message` turn so its user
+    /// message survives compaction verbatim. Returns false when the turn can
+    /// no longer be pinned because an earlier compaction already dropped it.
+    pub(crate) fn set_turn_pinned(&mut self, nth_user_message: usize, pinned: bool) -> bool {
+        if !pinned {
+            self.pinned_turns.remove(&nth_user_message);
+            self.pinned_messages.remove(&nth_user_message);
+            return true;
+        }
+        self.pinned_turns.insert(nth_user_message);
+        self.capture_pinned_messages();
+        if self.pinned_messages.contains_key(&nth_user_message) {
+            return true;
+        }
+        self.pinned_turns.remove(&nth_user_message);
+        false
+    }
+
+    /// User messages that compaction must carry over unchanged, oldest first.
+    pub(crate) fn pinned_messages(&mut self) -> Vec<ResponseItem> {
+        self.capture_pinned_messages();
+        self.pinned_messages.values().cloned().collect()
+    }
+
+    /// Restore turn numbering, relevance flags and pins for a history rebuilt
+    /// from `rollout_items` on resume or fork.
+    pub(crate) fn restore_turn_flags(&mut self, rollout_items: &[RolloutItem]) {
+        // Pinned messages may have been compacted away since, so take them
+        // from the rollout rather than from `items`.
+        let mut user_turns: Vec<&ResponseItem> = Vec::new();
+        for item in rollout_items {
+            match item {
+                RolloutItem::ResponseItem(item) if is_user_turn(item) => user_turns.push(item),
+                RolloutItem::EventMsg(EventMsg::HistoryFlagSet(event)) => match event.flag {
+                    HistoryFlag::TurnRelevance {
+                        nth_user_message,
+                        irrelevant,
+                    } => self.set_turn_irrelevant(nth_user_message, irrelevant),
+                    HistoryFlag::Pinned {
+                        nth_user_message,
+                        pinned,
+                    } => {
+                        if pinned {
+                            self.pinned_turns.insert(nth_user_message);
+                            if let Some(item) = user_turns.get(nth_user_message) {
+                                self.pinned_messages
+                                    .insert(nth_user_message, (*item).clone());
+                            }
+                        } else {
+                            self.pinned_turns.remove(&nth_user_message);
+                            self.pinned_messages.remove(&nth_user_message);
+                        }
+                    }
+                    HistoryFlag::ClearedAbove
+                    | HistoryFlag::Uncleared
+                    | HistoryFlag::CellDeleted { .. }
+                    | HistoryFlag::OutputCellDeleted { .. } => {}
+                },
+                _ => {}
+            }
+        }
+        if let Some(first) = user_turns.first() {
+            self.pinned_messages
+                .entry(0)
+                .or_insert_with(|| (*first).clone());
+        }
+        self.turn_offset = user_turns
+            .len()
+            .saturating_sub(count_user_turns(&self.items));
+    }
+
     // Estimate the number of tokens in the history. Return None if no tokenizer
     // is available. This does not consider the reasoning traces.
     // /!\ The value is a lower bound estimate and does not represent the exact
@@ -108,7 +248,16 @@ impl ContextManager {
         }
     }
 
-    pub(crate) fn replace(&mut self, items: Vec<ResponseItem>) {
+    /// Replaces the transcript wholesale (compaction, undo, resume). Calls
+    /// left without an output are answered as aborted, and outputs that lost
+    /// their call are dropped, so the next request stays valid.
+    pub(crate) fn replace(&mut self, mut items: Vec<ResponseItem>) {
+        repair_call_pairing(&mut items);
+        debug_assert_eq!(normalize::find_pairing_violations(&items), Vec::new());
+        // Replacements keep the newest user turns (compaction) or all of them
+        // (undo), so number the survivors from the end.
+        let total_turns = self.turn_offset + count_user_turns(&self.items);
+        self.turn_offset = total_turns.saturating_sub(count_user_turns(&items));
         self.items = items;
     }
 
@@ -140,10 +289,44 @@ impl ContextManager {
         self.items.clone()
     }
 
+    /// Copy the user messages of pinned turns that are still in `items`.
+    fn capture_pinned_messages(&mut self) {
+        let mut next_turn = self.turn_offset;
+        for item in &self.items {
+            if !is_user_turn(item) {
+                continue;
+            }
+            let turn = next_turn;
+            next_turn += 1;
+            if (turn == 0 || self.pinned_turns.contains(&turn))
+                && !self.pinned_messages.contains_key(&turn)
+            {
+                self.pinned_messages.insert(turn, item.clone());
+            }
+        }
+    }
+
     fn remove_ghost_snapshots(items: &mut Vec<ResponseItem>) {
         items.retain(|item| !matches!(item, ResponseItem::GhostSnapshot { .. }));
     }
 
+    fn prune_irrelevant_turns(&self, items: &mut [ResponseItem]) {
+        if self.irrelevant_turns.is_empty() {
+            return;
+        }
+        let mut next_turn = self.turn_offset;
+        let mut current_turn = None;
+        for item in items.iter_mut() {
+            if is_user_turn(item) {
+                current_turn = Some(next_turn);
+                next_turn += 1;
+            }
+            if current_turn.is_some_and(|turn| self.irrelevant_turns.contains(&turn)) {
+                reduce_irrelevant_item(item);
+            }
+        }
+    }
+
     fn process_item(&self, item: &ResponseItem, policy: TruncationPolicy) -> ResponseItem {
         let policy_with_serialization_budget = policy.mul(1.2);
         match item {
@@ -185,6 +368,64 @@ impl ContextManager {
     }
 }
 
+/// Repairs every call/output pair that violates the pairing invariants (see
+/// [`normalize::PairingViolation`]) and logs what was changed.
+pub(crate) fn repair_call_pairing(items: &mut Vec<ResponseItem>) {
+    let violations = normalize::enforce_call_pairing(items);
+    if !violations.is_empty() {
+        warn!(
+            "repaired {} broken tool call pairing(s) in replacement history: {violations:?}",
+            violations.len()
+        );
+    }
+}
+
+/// Puts back the half of every call/output pair that compaction split off,
+/// taking it verbatim from the pre-compaction `original` history.
+pub(crate) fn restore_split_pairs(compacted: &mut Vec<ResponseItem>, original: &[ResponseItem]) {
+    normalize::restore_split_pairs(compacted, original);
+}
+
+/// Whether `item` starts a user turn. The compaction summary is sent as a user
+/// message but does not start a turn.
+fn is_user_turn(item: &ResponseItem) -> bool {
+    match parse_turn_item(item) {
+        Some(TurnItem::UserMessage(user)) => !is_summary_message(&user.message()),
+        _ => false,
+    }
+}
+
+fn count_user_turns(items: &[ResponseItem]) -> usize {
+    items.iter().filter(|item| is_user_turn(item)).count()
+}
+
+/// Drop tool output from an item in an irrelevant turn and shorten user and
+/// assistant text. Calls stay so call/output pairing remains valid.
+fn reduce_irrelevant_item(item: &mut ResponseItem) {
+    let is_conversation_text = matches!(
+        parse_turn_item(item),
+        Some(TurnItem::UserMessage(_) | TurnItem::AgentMessage(_))
+    );
+    match item {
+        ResponseItem::FunctionCallOutput { output, .. } => {
+            output.content = IRRELEVANT_OUTPUT_PLACEHOLDER.to_string();
+            output.content_items = None;
+        }
+        ResponseItem::CustomToolCallOutput { output, .. } => {
+            *output = IRRELEVANT_OUTPUT_PLACEHOLDER.to_string();
+        }
+        ResponseItem::Message { content, .. } if is_conversation_text => {
+            for part in content.iter_mut() {
+                if let ContentItem::InputText { text } | ContentItem::OutputText { text } = part {
+                    *text =
+                        truncate_text(text, TruncationPolicy::Bytes(IRRELEVANT_TURN_TEXT_BYTES));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
 /// API messages include every non-system item (user/assistant messages, reasoning,
 /// tool calls, tool outputs, shell calls, and web-search calls).
 fn is_api_message(message: &ResponseItem) -> bool {
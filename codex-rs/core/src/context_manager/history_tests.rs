@@ -10,6 +10,9 @@ use codex_protocol::models::LocalShellStatus;
 use codex_protocol::models::ReasoningItemContent;
 use codex_protocol::models::ReasoningItemReasoningSummary;
 use pretty_assertions::assert_eq;
+use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 use regex_lite::Regex;
 
 const EXEC_FORMAT_MAX_BYTES: usize = 10_000;
@@ -334,6 +337,61 @@ fn record_items_respects_custom_token_limit() {
     assert!(stored.content.contains("tokens truncated"));
 }
 
+#[test]
+fn record_items_truncates_each_output_to_its_tool_policy() {
+    struct PerTool;
+
+    impl HistoryTruncation for PerTool {
+        fn policy_for_output(&self, tool_name: Option<&str>) -> TruncationPolicy {
+            match tool_name {
+                Some("docs__search") => TruncationPolicy::Tokens(50),
+                _ => TruncationPolicy::Tokens(10_000),
+            }
+        }
+    }
+
+    let long_output = "tokenized content repeated many times ".repeat(200);
+    let output = |call_id: &str| ResponseItem::FunctionCallOutput {
+        call_id: call_id.to_string(),
+        output: FunctionCallOutputPayload {
+            content: long_output.clone(),
+            success: Some(true),
+            ..Default::default()
+        },
+    };
+    let docs_call = ResponseItem::FunctionCall {
+        id: None,
+        name: "docs__search".to_string(),
+        arguments: "{}".to_string(),
+        call_id: "call-docs".to_string(),
+    };
+    let items = [
+        docs_call,
+        output("call-docs"),
+        function_call("call-shell"),
+        output("call-shell"),
+    ];
+
+    let mut history = ContextManager::new();
+    history.record_items(items.iter(), PerTool);
+
+    let contents: Vec<&str> = history
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            ResponseItem::FunctionCallOutput { output, .. } => Some(output.content.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(contents.len(), 2);
+    assert!(
+        contents[0].contains("tokens truncated"),
+        "expected docs__search output to be truncated, got {}",
+        contents[0]
+    );
+    assert_eq!(contents[1], long_output);
+}
+
 fn assert_truncated_message_matches(message: &str, line: &str, expected_removed: usize) {
     let pattern = truncated_message_pattern(line);
     let regex = Regex::new(&pattern).unwrap_or_else(|err| {
@@ -784,3 +842,537 @@ fn normalize_mixed_inserts_and_removals_panics_in_debug() {
     let mut h = create_history_with_items(items);
     h.normalize_history();
 }
+
+fn function_call(call_id: &str) -> ResponseItem {
+    ResponseItem::FunctionCall {
+        id: None,
+        name: "shell".to_string(),
+        arguments: "{}".to_string(),
+        call_id: call_id.to_string(),
+    }
+}
+
+fn function_call_output(call_id: &str) -> ResponseItem {
+    ResponseItem::FunctionCallOutput {
+        call_id: call_id.to_string(),
+        output: FunctionCallOutputPayload {
+            content: "ok".to_string(),
+            ..Default::default()
+        },
+    }
+}
+
+fn custom_tool_call(call_id: &str) -> ResponseItem {
+    ResponseItem::CustomToolCall {
+        id: None,
+        status: None,
+        call_id: call_id.to_string(),
+        name: "apply_patch".to_string(),
+        input: "*** Begin Patch".to_string(),
+    }
+}
+
+fn custom_tool_call_output(call_id: &str) -> ResponseItem {
+    ResponseItem::CustomToolCallOutput {
+        call_id: call_id.to_string(),
+        output: "ok".to_string(),
+    }
+}
+
+fn local_shell_call(call_id: &str) -> ResponseItem {
+    ResponseItem::LocalShellCall {
+        id: None,
+        call_id: Some(call_id.to_string()),
+        status: LocalShellStatus::Completed,
+        action: LocalShellAction::Exec(LocalShellExecAction {
+            command: vec!["ls".to_string()],
+            timeout_ms: None,
+            working_directory: None,
+            env: None,
+            user: None,
+        }),
+    }
+}
+
+/// `(is_call, namespace, call_id)` for items that take part in pairing.
+fn pairing_key(item: &ResponseItem) -> Option<(bool, &'static str, &str)> {
+    match item {
+        ResponseItem::FunctionCall { call_id, .. }
+        | ResponseItem::LocalShellCall {
+            call_id: Some(call_id),
+            ..
+        } => Some((true, "function", call_id)),
+        ResponseItem::FunctionCallOutput { call_id, .. } => Some((false, "function", call_id)),
+        ResponseItem::CustomToolCall { call_id, .. } => Some((true, "custom", call_id)),
+        ResponseItem::CustomToolCallOutput { call_id, .. } => Some((false, "custom", call_id)),
+        _ => None,
+    }
+}
+
+fn is_message(item: &ResponseItem) -> bool {
+    matches!(item, ResponseItem::Message { .. })
+}
+
+fn is_aborted_output(item: &ResponseItem) -> bool {
+    match item {
+        ResponseItem::FunctionCallOutput { output, .. } => output.content == "aborted",
+        ResponseItem::CustomToolCallOutput { output, .. } => output == "aborted",
+        _ => false,
+    }
+}
+
+/// Independent, quadratic check of the invariants enforced by
+/// `normalize::enforce_call_pairing`.
+fn assert_pairing_valid(items: &[ResponseItem]) {
+    for (idx, item) in items.iter().enumerate() {
+        let Some((is_call, namespace, call_id)) = pairing_key(item) else {
+            continue;
+        };
+        let same_id = |other: &ResponseItem, want_call: bool| {
+            pairing_key(other) == Some((want_call, namespace, call_id))
+        };
+        if is_call {
+            let later_calls = items[idx + 1..]
+                .iter()
+                .filter(|other| same_id(other, true))
+                .count();
+            assert_eq!(later_calls, 0, "duplicate call {call_id} in {items:#?}");
+            let outputs: Vec<usize> = (idx + 1..items.len())
+                .filter(|&j| same_id(&items[j], false))
+                .collect();
+            assert_eq!(outputs.len(), 1, "call {call_id} unanswered in {items:#?}");
+            assert!(
+                !items[idx + 1..outputs[0]].iter().any(is_message),
+                "message splits call {call_id} in {items:#?}"
+            );
+        } else {
+            let calls = items[..idx]
+                .iter()
+                .filter(|other| same_id(other, true))
+                .count();
+            assert_eq!(calls, 1, "output {call_id} has no call in {items:#?}");
+        }
+    }
+}
+
+/// A user turn followed by up to three nested tool calls whose outputs come
+/// back innermost-first, sprinkled with reasoning and assistant messages.
+fn random_history(rng: &mut StdRng) -> Vec<ResponseItem> {
+    let mut items = Vec::new();
+    let mut next_id = 0;
+    for turn in 0..rng.random_range(1..5) {
+        items.push(user_msg(&format!("turn {turn}")));
+        for _ in 0..rng.random_range(0..3) {
+            let depth = rng.random_range(1..4);
+            let mut opened = Vec::new();
+            for _ in 0..depth {
+                let call_id = format!("call-{next_id}");
+                next_id += 1;
+                if rng.random_bool(0.3) {
+                    items.push(reasoning_msg("thinking"));
+                }
+                let (call, output) = match rng.random_range(0..3) {
+                    0 => (function_call(&call_id), function_call_output(&call_id)),
+                    1 => (
+                        custom_tool_call(&call_id),
+                        custom_tool_call_output(&call_id),
+                    ),
+                    _ => (local_shell_call(&call_id), function_call_output(&call_id)),
+                };
+                items.push(call);
+                opened.push(output);
+            }
+            while let Some(output) = opened.pop() {
+                items.push(output);
+            }
+        }
+        items.push(assistant_msg("done"));
+    }
+    items
+}
+
+/// Mimics what a summarizer can do to a history: drop, duplicate, or
+/// reorder items and splice in user or assistant messages.
+fn corrupt_history(rng: &mut StdRng, items: &mut Vec<ResponseItem>) {
+    for _ in 0..rng.random_range(0..4) {
+        if items.is_empty() {
+            return;
+        }
+        let idx = rng.random_range(0..items.len());
+        match rng.random_range(0..5) {
+            0 => {
+                items.remove(idx);
+            }
+            1 => {
+                let item = items[idx].clone();
+                let at = rng.random_range(0..=items.len());
+                items.insert(at, item);
+            }
+            2 => {
+                let other = rng.random_range(0..items.len());
+                items.swap(idx, other);
+            }
+            3 => items.insert(idx, user_msg("summary")),
+            _ => items.insert(idx, assistant_msg("summary")),
+        }
+    }
+}
+
+/// Mimics a summarizer that folds some items into its summary: a random
+/// subset of the items is dropped and the rest are kept verbatim.
+fn summarize_history(rng: &mut StdRng, items: &[ResponseItem]) -> Vec<ResponseItem> {
+    items
+        .iter()
+        .filter(|_| rng.random_bool(0.6))
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn enforce_call_pairing_never_leaves_orphans_in_random_histories() {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    for _ in 0..2_000 {
+        let mut items = random_history(&mut rng);
+        corrupt_history(&mut rng, &mut items);
+        let original = items.clone();
+
+        let violations = normalize::enforce_call_pairing(&mut items);
+
+        assert_pairing_valid(&items);
+        assert_eq!(normalize::find_pairing_violations(&items), Vec::new());
+        assert_eq!(violations.is_empty(), items == original);
+        // Unanswered calls are answered right after the call.
+        for (idx, item) in items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| is_aborted_output(item))
+        {
+            let (_, namespace, call_id) = pairing_key(item).expect("output pairs");
+            assert_eq!(
+                idx.checked_sub(1)
+                    .and_then(|prev| pairing_key(&items[prev])),
+                Some((true, namespace, call_id)),
+                "aborted output {call_id} does not follow its call in {items:#?}"
+            );
+        }
+        let items: Vec<ResponseItem> = items
+            .into_iter()
+            .filter(|item| !is_aborted_output(item))
+            .collect();
+        // Otherwise only calls and outputs are dropped, and order is preserved.
+        let expected_rest: Vec<&ResponseItem> = original
+            .iter()
+            .filter(|item| pairing_key(item).is_none())
+            .collect();
+        let actual_rest: Vec<&ResponseItem> = items
+            .iter()
+            .filter(|item| pairing_key(item).is_none())
+            .collect();
+        assert_eq!(actual_rest, expected_rest);
+        let mut remaining = original.iter();
+        assert!(
+            items
+                .iter()
+                .all(|item| remaining.any(|candidate| candidate == item)),
+            "repaired history is not a subsequence of the input"
+        );
+    }
+}
+
+#[test]
+fn enforce_call_pairing_reports_each_violation() {
+    let mut items = vec![
+        user_msg("run things"),
+        function_call("kept"),
+        function_call_output("kept"),
+        function_call("no-output"),
+        custom_tool_call_output("no-call"),
+        function_call_output("kept"),
+        local_shell_call("split"),
+        user_msg("interjection"),
+        function_call_output("split"),
+        custom_tool_call("narrated"),
+        assistant_msg("Still working."),
+        custom_tool_call_output("narrated"),
+    ];
+
+    let violations = normalize::enforce_call_pairing(&mut items);
+
+    assert_eq!(
+        violations,
+        vec![
+            normalize::PairingViolation::CallWithoutOutput {
+                call_id: "no-output".to_string(),
+            },
+            normalize::PairingViolation::OutputWithoutCall {
+                call_id: "no-call".to_string(),
+            },
+            normalize::PairingViolation::DuplicateCallId {
+                call_id: "kept".to_string(),
+            },
+            normalize::PairingViolation::MessageBetweenCallAndOutput {
+                call_id: "split".to_string(),
+                role: "user".to_string(),
+            },
+            normalize::PairingViolation::MessageBetweenCallAndOutput {
+                call_id: "narrated".to_string(),
+                role: "assistant".to_string(),
+            },
+        ]
+    );
+    assert_eq!(
+        items,
+        vec![
+            user_msg("run things"),
+            function_call("kept"),
+            function_call_output("kept"),
+            function_call("no-output"),
+            ResponseItem::FunctionCallOutput {
+                call_id: "no-output".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "aborted".to_string(),
+                    ..Default::default()
+                },
+            },
+            user_msg("interjection"),
+            assistant_msg("Still working."),
+        ]
+    );
+}
+
+#[test]
+fn restore_split_pairs_keeps_tool_calls_atomic_in_random_compactions() {
+    let mut rng = StdRng::seed_from_u64(0xc0_ffee);
+    for _ in 0..2_000 {
+        let original = random_history(&mut rng);
+        let mut compacted = summarize_history(&mut rng, &original);
+
+        restore_split_pairs(&mut compacted, &original);
+
+        // Every pair is either kept whole or dropped whole, so there is
+        // nothing left to repair.
+        assert_eq!(
+            normalize::enforce_call_pairing(&mut compacted),
+            Vec::new(),
+            "compaction split a pair in {compacted:#?}"
+        );
+        assert_pairing_valid(&compacted);
+    }
+}
+
+#[test]
+fn replace_answers_unanswered_calls_instead_of_dropping_them() {
+    let mut history = ContextManager::new();
+
+    history.replace(vec![
+        user_msg("fix the failing test"),
+        function_call_output("call_ls"),
+        custom_tool_call("call_patch"),
+        user_msg("also check the docs"),
+    ]);
+
+    assert_eq!(
+        history.contents(),
+        vec![
+            user_msg("fix the failing test"),
+            custom_tool_call("call_patch"),
+            ResponseItem::CustomToolCallOutput {
+                call_id: "call_patch".to_string(),
+                output: "aborted".to_string(),
+            },
+            user_msg("also check the docs"),
+        ]
+    );
+}
+
+fn irrelevant_output(call_id: &str) -> ResponseItem {
+    ResponseItem::FunctionCallOutput {
+        call_id: call_id.to_string(),
+        output: FunctionCallOutputPayload {
+            content: IRRELEVANT_OUTPUT_PLACEHOLDER.to_string(),
+            ..Default::default()
+        },
+    }
+}
+
+#[test]
+fn prompt_omits_tool_output_of_irrelevant_turns() {
+    let mut history = create_history_with_items(vec![
+        user_msg("first"),
+        function_call("call_a"),
+        function_call_output("call_a"),
+        assistant_msg("done with first"),
+        user_msg("second"),
+        function_call("call_b"),
+        function_call_output("call_b"),
+    ]);
+
+    history.set_turn_irrelevant(0, true);
+
+    assert_eq!(
+        history.get_history_for_prompt(),
+        vec![
+            user_msg("first"),
+            function_call("call_a"),
+            irrelevant_output("call_a"),
+            assistant_msg("done with first"),
+            user_msg("second"),
+            function_call("call_b"),
+            function_call_output("call_b"),
+        ]
+    );
+
+    history.set_turn_irrelevant(0, false);
+    assert_eq!(history.get_history_for_prompt(), history.contents());
+}
+
+#[test]
+fn irrelevant_turn_text_is_shortened_not_dropped() {
+    let long_reply = "x".repeat(IRRELEVANT_TURN_TEXT_BYTES * 4);
+    let mut history =
+        create_history_with_items(vec![user_msg("first"), assistant_msg(&long_reply)]);
+
+    history.set_turn_irrelevant(0, true);
+
+    assert_eq!(
+        history.get_history_for_prompt(),
+        vec![
+            user_msg("first"),
+            assistant_msg(&truncate::truncate_text(
+                &long_reply,
+                TruncationPolicy::Bytes(IRRELEVANT_TURN_TEXT_BYTES)
+            )),
+        ]
+    );
+}
+
+#[test]
+fn replace_keeps_turn_numbering_for_surviving_turns() {
+    let mut history = create_history_with_items(vec![
+        user_msg("first"),
+        function_call("call_a"),
+        function_call_output("call_a"),
+        user_msg("second"),
+    ]);
+
+    // Compaction keeps only the newest user message.
+    history.replace(vec![user_msg("second")]);
+    history.record_items(
+        [function_call("call_b"), function_call_output("call_b")].iter(),
+        TruncationPolicy::Tokens(10_000),
+    );
+    history.set_turn_irrelevant(1, true);
+
+    assert_eq!(
+        history.get_history_for_prompt(),
+        vec![
+            user_msg("second"),
+            function_call("call_b"),
+            irrelevant_output("call_b"),
+        ]
+    );
+}
+
+#[test]
+fn restore_turn_flags_applies_relevance_events_from_rollout() {
+    let flag = |nth_user_message, irrelevant| {
+        RolloutItem::EventMsg(EventMsg::HistoryFlagSet(
+            codex_protocol::protocol::HistoryFlagSetEvent {
+                flag: HistoryFlag::TurnRelevance {
+                    nth_user_message,
+                    irrelevant,
+                },
+            },
+        ))
+    };
+    let items = vec![
+        user_msg("first"),
+        function_call("call_a"),
+        function_call_output("call_a"),
+        user_msg("second"),
+        function_call("call_b"),
+        function_call_output("call_b"),
+    ];
+    let mut rollout: Vec<RolloutItem> = items
+        .iter()
+        .cloned()
+        .map(RolloutItem::ResponseItem)
+        .collect();
+    rollout.push(flag(0, true));
+    rollout.push(flag(1, true));
+    rollout.push(flag(1, false));
+    let mut history = create_history_with_items(items);
+
+    history.restore_turn_flags(&rollout);
+
+    assert_eq!(
+        history.get_history_for_prompt(),
+        vec![
+            user_msg("first"),
+            function_call("call_a"),
+            irrelevant_output("call_a"),
+            user_msg("second"),
+            function_call("call_b"),
+            function_call_output("call_b"),
+        ]
+    );
+}
+
+#[test]
+fn pinned_messages_keep_the_first_turn_and_survive_replace() {
+    let mut history = create_history_with_items(vec![
+        user_msg("task"),
+        assistant_msg("on it"),
+        user_msg("use the v2 API"),
+        assistant_msg("switched"),
+        user_msg("chatter"),
+    ]);
+
+    assert!(history.set_turn_pinned(1, true));
+    assert_eq!(
+        history.pinned_messages(),
+        vec![user_msg("task"), user_msg("use the v2 API")]
+    );
+
+    // Compaction folds every turn into the summary; the pins stay, and turns
+    // it dropped can no longer be pinned.
+    history.replace(vec![assistant_msg("summary")]);
+    assert!(!history.set_turn_pinned(2, true));
+    assert_eq!(
+        history.pinned_messages(),
+        vec![user_msg("task"), user_msg("use the v2 API")]
+    );
+
+    assert!(history.set_turn_pinned(1, false));
+    assert_eq!(history.pinned_messages(), vec![user_msg("task")]);
+}
+
+#[test]
+fn restore_turn_flags_restores_pins_from_rollout() {
+    let flag = |nth_user_message, pinned| {
+        RolloutItem::EventMsg(EventMsg::HistoryFlagSet(
+            codex_protocol::protocol::HistoryFlagSetEvent {
+                flag: HistoryFlag::Pinned {
+                    nth_user_message,
+                    pinned,
+                },
+            },
+        ))
+    };
+    let mut rollout: Vec<RolloutItem> = [user_msg("first"), user_msg("second"), user_msg("third")]
+        .into_iter()
+        .map(RolloutItem::ResponseItem)
+        .collect();
+    rollout.push(flag(1, true));
+    rollout.push(flag(2, true));
+    rollout.push(flag(2, false));
+    // Only the newest turn survived an earlier compaction.
+    let mut history = create_history_with_items(vec![user_msg("third")]);
+
+    history.restore_turn_flags(&rollout);
+
+    assert_eq!(
+        history.pinned_messages(),
+        vec![user_msg("first"), user_msg("second")]
+    );
+}
@@ -2,3 +2,5 @@ mod history;
 mod normalize;
 
 pub(crate) use history::ContextManager;
+pub(crate) use history::repair_call_pairing;
+pub(crate) use history::restore_split_pairs;
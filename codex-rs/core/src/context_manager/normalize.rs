@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::collections::HashSet;
 
 use codex_protocol::models::FunctionCallOutputPayload;
@@ -147,6 +148,238 @@ pub(crate) fn remove_orphan_outputs(items: &mut Vec<ResponseItem>) {
     });
 }
 
+/// A broken call/output pairing found by [`find_pairing_violations`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PairingViolation {
+    /// A tool call with no output after it.
+    CallWithoutOutput { call_id: String },
+    /// A tool output with no earlier call for the same id.
+    OutputWithoutCall { call_id: String },
+    /// A call or output reusing an id that is already paired.
+    DuplicateCallId { call_id: String },
+    /// A user or assistant message between a call and its output. Only other
+    /// calls, outputs and reasoning may sit between the two; providers
+    /// require the output to answer the call before either side speaks again,
+    /// so the pair is dropped as a unit.
+    MessageBetweenCallAndOutput { call_id: String, role: String },
+}
+
+/// Returns every pairing violation in `items`, in history order.
+pub(crate) fn find_pairing_violations(items: &[ResponseItem]) -> Vec<PairingViolation> {
+    scan_call_pairing(items)
+        .into_iter()
+        .map(|(_, violation)| violation)
+        .collect()
+}
+
+/// Repairs every pairing violation so that each remaining call is followed
+/// by exactly one output, with no message in between. Unanswered calls get
+/// an "aborted" output right after them; every other violation drops the
+/// items involved, calls and outputs together.
+pub(crate) fn enforce_call_pairing(items: &mut Vec<ResponseItem>) -> Vec<PairingViolation> {
+    let mut drop = HashSet::new();
+    let mut answer = HashSet::new();
+    let mut violations = Vec::new();
+    for (indices, violation) in scan_call_pairing(items) {
+        if matches!(violation, PairingViolation::CallWithoutOutput { .. }) {
+            answer.extend(indices);
+        } else {
+            drop.extend(indices);
+        }
+        violations.push(violation);
+    }
+    if drop.is_empty() && answer.is_empty() {
+        return violations;
+    }
+    let mut repaired = Vec::with_capacity(items.len() + answer.len());
+    for (idx, item) in std::mem::take(items).into_iter().enumerate() {
+        if drop.contains(&idx) {
+            continue;
+        }
+        let output = if answer.contains(&idx) {
+            aborted_output_for(&item)
+        } else {
+            None
+        };
+        repaired.push(item);
+        repaired.extend(output);
+    }
+    *items = repaired;
+    violations
+}
+
+/// Keeps tool calls atomic across a compaction: for every call in
+/// `compacted` whose output was left behind, the output is copied from
+/// `original` right after the call, and for every output whose call was left
+/// behind, the call is copied in right before it. Pairs the original history
+/// cannot complete are left for [`enforce_call_pairing`].
+pub(crate) fn restore_split_pairs(compacted: &mut Vec<ResponseItem>, original: &[ResponseItem]) {
+    let mut calls = HashSet::new();
+    let mut outputs = HashSet::new();
+    for item in compacted.iter() {
+        match call_role(item) {
+            Some(CallRole::Call(kind, call_id)) => {
+                calls.insert((kind, call_id.to_string()));
+            }
+            Some(CallRole::Output(kind, call_id)) => {
+                outputs.insert((kind, call_id.to_string()));
+            }
+            None => {}
+        }
+    }
+    let find_in_original = |want_call: bool, kind: CallKind, call_id: &str| {
+        original.iter().find(|item| match call_role(item) {
+            Some(CallRole::Call(k, id)) => want_call && k == kind && id == call_id,
+            Some(CallRole::Output(k, id)) => !want_call && k == kind && id == call_id,
+            None => false,
+        })
+    };
+
+    let mut restored = Vec::with_capacity(compacted.len());
+    for item in std::mem::take(compacted) {
+        match call_role(&item) {
+            Some(CallRole::Call(kind, call_id)) => {
+                let output = if outputs.contains(&(kind, call_id.to_string())) {
+                    None
+                } else {
+                    find_in_original(false, kind, call_id).cloned()
+                };
+                restored.push(item);
+                restored.extend(output);
+            }
+            Some(CallRole::Output(kind, call_id)) => {
+                if !calls.contains(&(kind, call_id.to_string()))
+                    && let Some(call) = find_in_original(true, kind, call_id)
+                {
+                    restored.push(call.clone());
+                }
+                restored.push(item);
+            }
+            None => restored.push(item),
+        }
+    }
+    *compacted = restored;
+}
+
+fn aborted_output_for(call: &ResponseItem) -> Option<ResponseItem> {
+    match call_role(call)? {
+        CallRole::Call(CallKind::Function, call_id) => Some(ResponseItem::FunctionCallOutput {
+            call_id: call_id.to_string(),
+            output: FunctionCallOutputPayload {
+                content: "aborted".to_string(),
+                ..Default::default()
+            },
+        }),
+        CallRole::Call(CallKind::Custom, call_id) => Some(ResponseItem::CustomToolCallOutput {
+            call_id: call_id.to_string(),
+            output: "aborted".to_string(),
+        }),
+        CallRole::Output(..) => None,
+    }
+}
+
+/// Calls and outputs pair up within a namespace: function calls and local
+/// shell calls are answered by `FunctionCallOutput`, custom tool calls by
+/// `CustomToolCallOutput`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum CallKind {
+    Function,
+    Custom,
+}
+
+enum CallRole<'a> {
+    Call(CallKind, &'a str),
+    Output(CallKind, &'a str),
+}
+
+fn call_role(item: &ResponseItem) -> Option<CallRole<'_>> {
+    match item {
+        ResponseItem::FunctionCall { call_id, .. }
+        | ResponseItem::LocalShellCall {
+            call_id: Some(call_id),
+            ..
+        } => Some(CallRole::Call(CallKind::Function, call_id)),
+        ResponseItem::CustomToolCall { call_id, .. } => {
+            Some(CallRole::Call(CallKind::Custom, call_id))
+        }
+        ResponseItem::FunctionCallOutput { call_id, .. } => {
+            Some(CallRole::Output(CallKind::Function, call_id))
+        }
+        ResponseItem::CustomToolCallOutput { call_id, .. } => {
+            Some(CallRole::Output(CallKind::Custom, call_id))
+        }
+        _ => None,
+    }
+}
+
+/// Walks `items` once and returns, for each violation, the indices of the
+/// items involved: the call for an unanswered call, otherwise the items that
+/// must be dropped to repair it.
+fn scan_call_pairing(items: &[ResponseItem]) -> Vec<(Vec<usize>, PairingViolation)> {
+    let mut results: Vec<(usize, Vec<usize>, PairingViolation)> = Vec::new();
+    // Calls still waiting for their output, and the role of the first
+    // message seen since the call.
+    let mut open: HashMap<(CallKind, &str), (usize, Option<&str>)> = HashMap::new();
+    let mut seen: HashSet<(CallKind, &str)> = HashSet::new();
+
+    for (idx, item) in items.iter().enumerate() {
+        if let ResponseItem::Message { role, .. } = item {
+            for (_, interrupted_by) in open.values_mut() {
+                interrupted_by.get_or_insert(role.as_str());
+            }
+            continue;
+        }
+        match call_role(item) {
+            Some(CallRole::Call(kind, call_id)) => {
+                if seen.insert((kind, call_id)) {
+                    open.insert((kind, call_id), (idx, None));
+                } else {
+                    let violation = PairingViolation::DuplicateCallId {
+                        call_id: call_id.to_string(),
+                    };
+                    results.push((idx, vec![idx], violation));
+                }
+            }
+            Some(CallRole::Output(kind, call_id)) => match open.remove(&(kind, call_id)) {
+                Some((_, None)) => {}
+                Some((call_idx, Some(role))) => {
+                    let violation = PairingViolation::MessageBetweenCallAndOutput {
+                        call_id: call_id.to_string(),
+                        role: role.to_string(),
+                    };
+                    results.push((call_idx, vec![call_idx, idx], violation));
+                }
+                None => {
+                    let violation = if seen.contains(&(kind, call_id)) {
+                        PairingViolation::DuplicateCallId {
+                            call_id: call_id.to_string(),
+                        }
+                    } else {
+                        PairingViolation::OutputWithoutCall {
+                            call_id: call_id.to_string(),
+                        }
+                    };
+                    results.push((idx, vec![idx], violation));
+                }
+            },
+            None => {}
+        }
+    }
+
+    for ((_, call_id), (call_idx, _)) in open {
+        let violation = PairingViolation::CallWithoutOutput {
+            call_id: call_id.to_string(),
+        };
+        results.push((call_idx, vec![call_idx], violation));
+    }
+
+    results.sort_by_key(|(position, ..)| *position);
+    results
+        .into_iter()
+        .map(|(_, indices, violation)| (indices, violation))
+        .collect()
+}
+
 pub(crate) fn remove_corresponding_for(items: &mut Vec<ResponseItem>, item: &ResponseItem) {
     match item {
         ResponseItem::FunctionCall { call_id, .. } => {
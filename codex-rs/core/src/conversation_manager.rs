@@ -17,9 +17,11 @@ use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::InitialHistory;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::SessionSource;
+use futures::future::join_all;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
 /// Represents a newly created Codex conversation, including the first event
@@ -166,6 +168,25 @@ impl ConversationManager {
         self.conversations.write().await.remove(conversation_id)
     }
 
+    /// Shuts down every conversation at once, giving each up to `grace` to
+    /// stop, and forgets them. Returns the conversations that had to be
+    /// aborted.
+    pub async fn shutdown_all(&self, grace: Duration) -> Vec<ConversationId> {
+        let conversations: Vec<_> = self.conversations.write().await.drain().collect();
+        let outcomes = join_all(conversations.into_iter().map(
+            |(conversation_id, conversation)| async move {
+                (conversation_id, conversation.shutdown(grace).await)
+            },
+        ))
+        .await;
+        let mut aborted: Vec<ConversationId> = outcomes
+            .into_iter()
+            .filter_map(|(conversation_id, stopped)| (!stopped).then_some(conversation_id))
+            .collect();
+        aborted.sort_by_key(ToString::to_string);
+        aborted
+    }
+
     /// Fork an existing conversation by taking messages up to the given position
     /// (not including the message at the given position) and starting a new
     /// conversation with identical configuration (unless overridden by the
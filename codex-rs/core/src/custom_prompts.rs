@@ -1,7 +1,11 @@
+use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::custom_prompts::CustomPrompt;
+use codex_protocol::custom_prompts::CustomPromptArgument;
+use serde::Deserialize;
 use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
+use strum::IntoEnumIterator;
 use tokio::fs;
 
 /// Return the default prompts directory: `$CODEX_HOME/prompts`.
@@ -62,86 +66,273 @@ pub async fn discover_prompts_in_excluding(
             Ok(s) => s,
             Err(_) => continue,
         };
-        let (description, argument_hint, body) = parse_frontmatter(&content);
+        let parsed = parse_prompt_file(&content);
+        let FrontMatter {
+            description,
+            argument_hint,
+            arguments,
+            model,
+            effort,
+            approval_preset,
+        } = parsed.front_matter;
+        let argument_hint = argument_hint.or_else(|| derive_argument_hint(&arguments));
         out.push(CustomPrompt {
             name,
             path,
-            content: body,
+            content: parsed.body,
             description,
             argument_hint,
+            arguments,
+            model,
+            effort,
+            approval_preset,
+            frontmatter_warning: parsed.warning,
         });
     }
     out.sort_by(|a, b| a.name.cmp(&b.name));
     out
 }
 
-/// Parse optional YAML-like frontmatter at the beginning of `content`.
+/// Metadata read from a prompt file's front-matter.
+#[derive(Debug, Default, PartialEq)]
+struct FrontMatter {
+    description: Option<String>,
+    argument_hint: Option<String>,
+    arguments: Vec<CustomPromptArgument>,
+    model: Option<String>,
+    effort: Option<ReasoningEffort>,
+    approval_preset: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+struct ParsedPrompt {
+    front_matter: FrontMatter,
+    body: String,
+    /// Why the front-matter was ignored, when it could not be parsed.
+    warning: Option<String>,
+}
+
+/// Split `content` into optional front-matter and the prompt body.
+///
+/// Two front-matter styles are supported:
+/// - YAML-like `key: value` lines between `---` delimiters
+/// - TOML between `+++` delimiters
+///
 /// Supported keys:
 /// - `description`: short description shown in the slash popup
 /// - `argument-hint` or `argument_hint`: brief hint string shown after the description
-///   Returns (description, argument_hint, body_without_frontmatter).
-fn parse_frontmatter(content: &str) -> (Option<String>, Option<String>, String) {
+/// - `arguments`: named arguments, each with an optional hint
+/// - `model`, `effort`: model and reasoning effort for the turn that runs the prompt
+/// - `approval-preset` or `approval_preset`: approval preset id the prompt asks for
+///
+/// Files without front-matter are returned unchanged. Malformed front-matter
+/// is reported in `warning` and the whole file is used as the body.
+fn parse_prompt_file(content: &str) -> ParsedPrompt {
     let mut segments = content.split_inclusive('\n');
     let Some(first_segment) = segments.next() else {
-        return (None, None, String::new());
+        return ParsedPrompt {
+            front_matter: FrontMatter::default(),
+            body: String::new(),
+            warning: None,
+        };
     };
-    let first_line = first_segment.trim_end_matches(['\r', '\n']);
-    if first_line.trim() != "---" {
-        return (None, None, content.to_string());
-    }
-
-    let mut desc: Option<String> = None;
-    let mut hint: Option<String> = None;
-    let mut frontmatter_closed = false;
-    let mut consumed = first_segment.len();
+    let (delimiter, parse): (&str, fn(&str) -> Result<FrontMatter, String>) =
+        match first_segment.trim_end_matches(['\r', '\n']).trim() {
+            "---" => ("---", parse_yaml_front_matter),
+            "+++" => ("+++", parse_toml_front_matter),
+            _ => {
+                return ParsedPrompt {
+                    front_matter: FrontMatter::default(),
+                    body: content.to_string(),
+                    warning: None,
+                };
+            }
+        };
 
+    let header_start = first_segment.len();
+    let mut consumed = header_start;
+    let mut header_end = None;
     for segment in segments {
-        let line = segment.trim_end_matches(['\r', '\n']);
-        let trimmed = line.trim();
-
-        if trimmed == "---" {
-            frontmatter_closed = true;
-            consumed += segment.len();
+        let line_start = consumed;
+        consumed += segment.len();
+        if segment.trim_end_matches(['\r', '\n']).trim() == delimiter {
+            header_end = Some(line_start);
             break;
         }
+    }
+
+    let result = match header_end {
+        Some(header_end) => parse(&content[header_start..header_end]),
+        None => Err(format!("missing closing `{delimiter}`")),
+    };
+    match result {
+        Ok(front_matter) => ParsedPrompt {
+            front_matter,
+            body: content[consumed..].to_string(),
+            warning: None,
+        },
+        Err(err) => ParsedPrompt {
+            front_matter: FrontMatter::default(),
+            body: content.to_string(),
+            warning: Some(format!(
+                "ignored malformed front-matter ({err}); using the whole file as the prompt"
+            )),
+        },
+    }
+}
+
+fn parse_yaml_front_matter(header: &str) -> Result<FrontMatter, String> {
+    let mut front_matter = FrontMatter::default();
+    let mut in_arguments = false;
 
+    for line in header.lines() {
+        let trimmed = line.trim();
         if trimmed.is_empty() || trimmed.starts_with('#') {
-            consumed += segment.len();
             continue;
         }
 
-        if let Some((k, v)) = trimmed.split_once(':') {
-            let key = k.trim().to_ascii_lowercase();
-            let mut val = v.trim().to_string();
-            if val.len() >= 2 {
-                let bytes = val.as_bytes();
-                let first = bytes[0];
-                let last = bytes[bytes.len() - 1];
-                if (first == b'\"' && last == b'\"') || (first == b'\'' && last == b'\'') {
-                    val = val[1..val.len().saturating_sub(1)].to_string();
+        if in_arguments && let Some(item) = trimmed.strip_prefix('-') {
+            let (name, hint) = match item.split_once(':') {
+                Some((name, hint)) => (name.trim(), Some(unquote(hint.trim()))),
+                None => (item.trim(), None),
+            };
+            front_matter
+                .arguments
+                .push(prompt_argument(name, hint.filter(|hint| !hint.is_empty()))?);
+            continue;
+        }
+        in_arguments = false;
+
+        let Some((k, v)) = trimmed.split_once(':') else {
+            return Err(format!("expected `key: value`, found `{trimmed}`"));
+        };
+        let key = k.trim().to_ascii_lowercase();
+        let val = unquote(v.trim());
+        match key.as_str() {
+            "description" => front_matter.description = Some(val),
+            "argument-hint" | "argument_hint" => front_matter.argument_hint = Some(val),
+            "arguments" if val.is_empty() => in_arguments = true,
+            "arguments" => {
+                for name in val.split(',') {
+                    front_matter
+                        .arguments
+                        .push(prompt_argument(name.trim(), None)?);
                 }
             }
-            match key.as_str() {
-                "description" => desc = Some(val),
-                "argument-hint" | "argument_hint" => hint = Some(val),
-                _ => {}
-            }
+            "model" => front_matter.model = Some(val),
+            "effort" => front_matter.effort = Some(parse_effort(&val)?),
+            "approval-preset" | "approval_preset" => front_matter.approval_preset = Some(val),
+            _ => {}
         }
+    }
 
-        consumed += segment.len();
+    check_unique_arguments(&front_matter.arguments)?;
+    Ok(front_matter)
+}
+
+fn parse_toml_front_matter(header: &str) -> Result<FrontMatter, String> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum TomlArgument {
+        Name(String),
+        Detailed { name: String, hint: Option<String> },
     }
 
-    if !frontmatter_closed {
-        // Unterminated frontmatter: treat input as-is.
-        return (None, None, content.to_string());
+    #[derive(Deserialize)]
+    struct TomlFrontMatter {
+        description: Option<String>,
+        #[serde(alias = "argument_hint", rename = "argument-hint")]
+        argument_hint: Option<String>,
+        #[serde(default)]
+        arguments: Vec<TomlArgument>,
+        model: Option<String>,
+        effort: Option<String>,
+        #[serde(alias = "approval_preset", rename = "approval-preset")]
+        approval_preset: Option<String>,
     }
 
-    let body = if consumed >= content.len() {
-        String::new()
-    } else {
-        content[consumed..].to_string()
-    };
-    (desc, hint, body)
+    let parsed: TomlFrontMatter =
+        toml::from_str(header).map_err(|err| err.message().to_string())?;
+    let arguments = parsed
+        .arguments
+        .into_iter()
+        .map(|argument| match argument {
+            TomlArgument::Name(name) => prompt_argument(&name, None),
+            TomlArgument::Detailed { name, hint } => prompt_argument(&name, hint),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    check_unique_arguments(&arguments)?;
+    Ok(FrontMatter {
+        description: parsed.description,
+        argument_hint: parsed.argument_hint,
+        arguments,
+        model: parsed.model,
+        effort: parsed.effort.as_deref().map(parse_effort).transpose()?,
+        approval_preset: parsed.approval_preset,
+    })
+}
+
+fn unquote(val: &str) -> String {
+    for quote in ['"', '\''] {
+        if val.len() >= 2
+            && let Some(inner) = val
+                .strip_prefix(quote)
+                .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return inner.to_string();
+        }
+    }
+    val.to_string()
+}
+
+/// Argument names must be usable as `$NAME` placeholders.
+fn prompt_argument(name: &str, hint: Option<String>) -> Result<CustomPromptArgument, String> {
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_uppercase())
+        && chars.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+    if !valid {
+        return Err(format!(
+            "argument `{name}` must be uppercase letters, digits, or `_`, starting with a letter"
+        ));
+    }
+    Ok(CustomPromptArgument {
+        name: name.to_string(),
+        hint,
+    })
+}
+
+fn check_unique_arguments(arguments: &[CustomPromptArgument]) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    match arguments
+        .iter()
+        .find(|argument| !seen.insert(&argument.name))
+    {
+        Some(duplicate) => Err(format!("argument `{}` is declared twice", duplicate.name)),
+        None => Ok(()),
+    }
+}
+
+fn parse_effort(val: &str) -> Result<ReasoningEffort, String> {
+    ReasoningEffort::iter()
+        .find(|effort| effort.to_string() == val.to_ascii_lowercase())
+        .ok_or_else(|| format!("unknown effort `{val}`"))
+}
+
+/// Hint shown in the slash popup when the file declares `arguments` but no
+/// `argument-hint`, e.g. `FILE=<path to review> GOAL=…`.
+fn derive_argument_hint(arguments: &[CustomPromptArgument]) -> Option<String> {
+    if arguments.is_empty() {
+        return None;
+    }
+    let hint = arguments
+        .iter()
+        .map(|argument| match &argument.hint {
+            Some(hint) => format!("{}=<{hint}>", argument.name),
+            None => format!("{}=…", argument.name),
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some(hint)
 }
 
 #[cfg(test)]
@@ -236,9 +427,150 @@ mod tests {
     #[test]
     fn parse_frontmatter_preserves_body_newlines() {
         let content = "---\r\ndescription: \"Line endings\"\r\nargument_hint: \"[arg]\"\r\n---\r\nFirst line\r\nSecond line\r\n";
-        let (desc, hint, body) = parse_frontmatter(content);
-        assert_eq!(desc.as_deref(), Some("Line endings"));
-        assert_eq!(hint.as_deref(), Some("[arg]"));
-        assert_eq!(body, "First line\r\nSecond line\r\n");
+        assert_eq!(
+            parse_prompt_file(content),
+            ParsedPrompt {
+                front_matter: FrontMatter {
+                    description: Some("Line endings".to_string()),
+                    argument_hint: Some("[arg]".to_string()),
+                    ..Default::default()
+                },
+                body: "First line\r\nSecond line\r\n".to_string(),
+                warning: None,
+            }
+        );
+    }
+
+    fn argument(name: &str, hint: Option<&str>) -> CustomPromptArgument {
+        CustomPromptArgument {
+            name: name.to_string(),
+            hint: hint.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn parses_yaml_metadata_keys() {
+        let content = "---\ndescription: Review a file\narguments:\n  - FILE: path to review\n  - GOAL\nmodel: gpt-5-codex-mini\neffort: low\napproval-preset: auto\n---\nReview $FILE for $GOAL\n";
+        assert_eq!(
+            parse_prompt_file(content),
+            ParsedPrompt {
+                front_matter: FrontMatter {
+                    description: Some("Review a file".to_string()),
+                    argument_hint: None,
+                    arguments: vec![
+                        argument("FILE", Some("path to review")),
+                        argument("GOAL", None)
+                    ],
+                    model: Some("gpt-5-codex-mini".to_string()),
+                    effort: Some(ReasoningEffort::Low),
+                    approval_preset: Some("auto".to_string()),
+                },
+                body: "Review $FILE for $GOAL\n".to_string(),
+                warning: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_inline_yaml_argument_list() {
+        let parsed = parse_prompt_file("---\narguments: FILE, GOAL\n---\nbody");
+        assert_eq!(
+            parsed.front_matter.arguments,
+            vec![argument("FILE", None), argument("GOAL", None)]
+        );
+    }
+
+    #[test]
+    fn parses_toml_metadata_keys() {
+        let content = "+++\ndescription = \"Review a file\"\nmodel = \"gpt-5-codex-mini\"\neffort = \"high\"\napproval-preset = \"read-only\"\n\n[[arguments]]\nname = \"FILE\"\nhint = \"path to review\"\n\n[[arguments]]\nname = \"GOAL\"\n+++\nReview $FILE for $GOAL\n";
+        assert_eq!(
+            parse_prompt_file(content),
+            ParsedPrompt {
+                front_matter: FrontMatter {
+                    description: Some("Review a file".to_string()),
+                    argument_hint: None,
+                    arguments: vec![
+                        argument("FILE", Some("path to review")),
+                        argument("GOAL", None)
+                    ],
+                    model: Some("gpt-5-codex-mini".to_string()),
+                    effort: Some(ReasoningEffort::High),
+                    approval_preset: Some("read-only".to_string()),
+                },
+                body: "Review $FILE for $GOAL\n".to_string(),
+                warning: None,
+            }
+        );
+    }
+
+    #[test]
+    fn file_without_front_matter_is_unchanged() {
+        assert_eq!(
+            parse_prompt_file("Just a prompt\n---\n"),
+            ParsedPrompt {
+                front_matter: FrontMatter::default(),
+                body: "Just a prompt\n---\n".to_string(),
+                warning: None,
+            }
+        );
+    }
+
+    #[test]
+    fn malformed_front_matter_degrades_to_whole_file_body() {
+        let cases = [
+            (
+                "---\ndescription: ok\nnot a key\n---\nbody",
+                "expected `key: value`, found `not a key`",
+            ),
+            ("---\ndescription: ok\nbody", "missing closing `---`"),
+            (
+                "---\neffort: extreme\n---\nbody",
+                "unknown effort `extreme`",
+            ),
+            (
+                "---\narguments: file\n---\nbody",
+                "argument `file` must be uppercase letters, digits, or `_`, starting with a letter",
+            ),
+            (
+                "---\narguments: FILE, FILE\n---\nbody",
+                "argument `FILE` is declared twice",
+            ),
+        ];
+        for (content, err) in cases {
+            assert_eq!(
+                parse_prompt_file(content),
+                ParsedPrompt {
+                    front_matter: FrontMatter::default(),
+                    body: content.to_string(),
+                    warning: Some(format!(
+                        "ignored malformed front-matter ({err}); using the whole file as the prompt"
+                    )),
+                },
+                "{content:?}"
+            );
+        }
+
+        let parsed = parse_prompt_file("+++\ndescription = \n+++\nbody");
+        assert_eq!(parsed.front_matter, FrontMatter::default());
+        assert_eq!(parsed.body, "+++\ndescription = \n+++\nbody");
+        assert!(parsed.warning.is_some());
+    }
+
+    #[tokio::test]
+    async fn declared_arguments_provide_default_argument_hint() {
+        let tmp = tempdir().expect("create TempDir");
+        let dir = tmp.path();
+        fs::write(
+            dir.join("review.md"),
+            "---\narguments:\n  - FILE: path to review\n  - GOAL\n---\nReview $FILE for $GOAL",
+        )
+        .unwrap();
+
+        let found = discover_prompts_in(dir).await;
+        assert_eq!(
+            found[0].argument_hint.as_deref(),
+            Some("FILE=<path to review> GOAL=…")
+        );
+        assert_eq!(found[0].frontmatter_warning, None);
     }
 }
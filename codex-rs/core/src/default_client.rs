@@ -1,4 +1,5 @@
 use crate::spawn::CODEX_SANDBOX_ENV_VAR;
+use crate::util::redact_url;
 use http::Error as HttpError;
 use reqwest::IntoUrl;
 use reqwest::Method;
@@ -59,7 +60,8 @@ impl CodexHttpClient {
     where
         U: IntoUrl,
     {
-        let url_str = url.as_str().to_string();
+        // Only used for logging, so strip credentials passed as query params.
+        let url_str = redact_url(url.as_str());
         CodexRequestBuilder::new(self.inner.request(method.clone(), url), method, url_str)
     }
 }
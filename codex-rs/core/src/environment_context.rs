@@ -3,14 +3,19 @@ use serde::Serialize;
 use strum_macros::Display as DeriveDisplay;
 
 use crate::codex::TurnContext;
+use crate::git_worktree::base_divergence_note;
 use crate::protocol::AskForApproval;
 use crate::protocol::SandboxPolicy;
+use crate::resume_briefing::resume_briefing_note;
 use crate::shell::Shell;
+use crate::tool_inventory::ToolInventory;
 use codex_protocol::config_types::SandboxMode;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::BaseDivergenceEvent;
 use codex_protocol::protocol::ENVIRONMENT_CONTEXT_CLOSE_TAG;
 use codex_protocol::protocol::ENVIRONMENT_CONTEXT_OPEN_TAG;
+use codex_protocol::protocol::ResumeBriefingEvent;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, DeriveDisplay)]
@@ -29,6 +34,12 @@ pub(crate) struct EnvironmentContext {
     pub network_access: Option<NetworkAccess>,
     pub writable_roots: Option<Vec<PathBuf>>,
     pub shell: Option<Shell>,
+    /// `name: version` lines from the tool inventory probe.
+    pub tools: Option<Vec<String>>,
+    /// Note that the worktree is behind the upstream of its base branch.
+    pub base_divergence: Option<String>,
+    /// What changed in the repository since a resumed session was last active.
+    pub resume_briefing: Option<String>,
 }
 
 impl EnvironmentContext {
@@ -70,6 +81,37 @@ impl EnvironmentContext {
                 _ => None,
             },
             shell,
+            tools: None,
+            base_divergence: None,
+            resume_briefing: None,
+        }
+    }
+
+    /// Adds the tool inventory, listed one tool per line.
+    pub fn with_tools(mut self, inventory: &ToolInventory) -> Self {
+        self.tools = Some(inventory.format_lines());
+        self
+    }
+
+    /// A context carrying only the tool inventory, for sessions whose probe
+    /// finished after the initial context was recorded.
+    pub fn tools_only(inventory: &ToolInventory) -> Self {
+        EnvironmentContext::new(None, None, None, None).with_tools(inventory)
+    }
+
+    /// A context carrying only the stale worktree note.
+    pub fn base_divergence_only(divergence: &BaseDivergenceEvent) -> Self {
+        Self {
+            base_divergence: Some(base_divergence_note(divergence)),
+            ..EnvironmentContext::new(None, None, None, None)
+        }
+    }
+
+    /// A context carrying only the resume briefing.
+    pub fn resume_briefing_only(briefing: &ResumeBriefingEvent) -> Self {
+        Self {
+            resume_briefing: Some(resume_briefing_note(briefing)),
+            ..EnvironmentContext::new(None, None, None, None)
         }
     }
 
@@ -85,6 +127,9 @@ impl EnvironmentContext {
             writable_roots,
             // should compare all fields except shell
             shell: _,
+            tools,
+            base_divergence,
+            resume_briefing,
         } = other;
 
         self.cwd == *cwd
@@ -92,25 +137,44 @@ impl EnvironmentContext {
             && self.sandbox_mode == *sandbox_mode
             && self.network_access == *network_access
             && self.writable_roots == *writable_roots
+            && self.tools == *tools
+            && self.base_divergence == *base_divergence
+            && self.resume_briefing == *resume_briefing
     }
 
-    pub fn diff(before: &TurnContext, after: &TurnContext) -> Self {
-        let cwd = if before.cwd != after.cwd {
-            Some(after.cwd.clone())
-        } else {
-            None
-        };
-        let approval_policy = if before.approval_policy != after.approval_policy {
-            Some(after.approval_policy)
-        } else {
-            None
-        };
-        let sandbox_policy = if before.sandbox_policy != after.sandbox_policy {
-            Some(after.sandbox_policy.clone())
-        } else {
-            None
-        };
-        EnvironmentContext::new(cwd, approval_policy, sandbox_policy, None)
+    /// The parts of `self` that differ from `before`, or `None` when nothing
+    /// changed. Sandbox mode, network access, and writable roots are sent
+    /// together whenever any of them changed.
+    pub fn changes_since(&self, before: &EnvironmentContext) -> Option<Self> {
+        if self.equals_except_shell(before) {
+            return None;
+        }
+        let sandbox_changed = self.sandbox_mode != before.sandbox_mode
+            || self.network_access != before.network_access
+            || self.writable_roots != before.writable_roots;
+        Some(Self {
+            cwd: if self.cwd != before.cwd {
+                self.cwd.clone()
+            } else {
+                None
+            },
+            approval_policy: if self.approval_policy != before.approval_policy {
+                self.approval_policy
+            } else {
+                None
+            },
+            sandbox_mode: sandbox_changed.then_some(self.sandbox_mode).flatten(),
+            network_access: sandbox_changed
+                .then(|| self.network_access.clone())
+                .flatten(),
+            writable_roots: sandbox_changed
+                .then(|| self.writable_roots.clone())
+                .flatten(),
+            shell: None,
+            tools: None,
+            base_divergence: None,
+            resume_briefing: None,
+        })
     }
 }
 
@@ -139,6 +203,9 @@ impl EnvironmentContext {
     ///   <writable_roots>...</writable_roots>
     ///   <network_access>...</network_access>
     ///   <shell>...</shell>
+    ///   <tools>...</tools>
+    ///   <base_divergence>...</base_divergence>
+    ///   <resume_briefing>...</resume_briefing>
     /// </environment_context>
     /// ```
     pub fn serialize_to_xml(self) -> String {
@@ -174,6 +241,23 @@ impl EnvironmentContext {
         {
             lines.push(format!("  <shell>{shell_name}</shell>"));
         }
+        if let Some(tools) = self.tools {
+            lines.push("  <tools>".to_string());
+            for tool in tools {
+                lines.push(format!("    {tool}"));
+            }
+            lines.push("  </tools>".to_string());
+        }
+        if let Some(base_divergence) = self.base_divergence {
+            lines.push(format!(
+                "  <base_divergence>{base_divergence}</base_divergence>"
+            ));
+        }
+        if let Some(resume_briefing) = self.resume_briefing {
+            lines.push(format!(
+                "  <resume_briefing>{resume_briefing}</resume_briefing>"
+            ));
+        }
         lines.push(ENVIRONMENT_CONTEXT_CLOSE_TAG.to_string());
         lines.join("\n")
     }
@@ -197,6 +281,8 @@ mod tests {
     use crate::shell::ZshShell;
 
     use super::*;
+    use crate::tool_inventory::ToolStatus;
+    use crate::tool_inventory::ToolVersion;
     use pretty_assertions::assert_eq;
 
     fn workspace_write_policy(writable_roots: Vec<&str>, network_access: bool) -> SandboxPolicy {
@@ -342,4 +428,151 @@ mod tests {
 
         assert!(context1.equals_except_shell(&context2));
     }
+
+    #[test]
+    fn serialize_tool_inventory_one_line_per_tool() {
+        let inventory = ToolInventory {
+            tools: vec![
+                ToolVersion {
+                    name: "node".to_string(),
+                    status: ToolStatus::Found {
+                        version: "v20.11.1".to_string(),
+                    },
+                },
+                ToolVersion {
+                    name: "deno".to_string(),
+                    status: ToolStatus::Absent,
+                },
+            ],
+        };
+        let context = EnvironmentContext::new(
+            Some(PathBuf::from("/repo")),
+            None,
+            None,
+            Some(Shell::Bash(BashShell {
+                shell_path: "/bin/bash".into(),
+            })),
+        )
+        .with_tools(&inventory);
+
+        let expected = r#"<environment_context>
+  <cwd>/repo</cwd>
+  <shell>bash</shell>
+  <tools>
+    node: v20.11.1
+    deno: not found
+  </tools>
+</environment_context>"#;
+
+        assert_eq!(context.serialize_to_xml(), expected);
+        assert_eq!(
+            EnvironmentContext::tools_only(&inventory).serialize_to_xml(),
+            "<environment_context>\n  <tools>\n    node: v20.11.1\n    deno: not found\n  </tools>\n</environment_context>"
+        );
+    }
+
+    #[test]
+    fn serialize_base_divergence_note() {
+        let divergence = BaseDivergenceEvent {
+            base: "main".to_string(),
+            upstream: "origin/main".to_string(),
+            behind: 14,
+            tip_subjects: vec!["Merge pull request #42".to_string()],
+        };
+
+        let expected = r#"<environment_context>
+  <base_divergence>base main is 14 commits behind origin/main; consider rebasing or asking the user</base_divergence>
+</environment_context>"#;
+
+        assert_eq!(
+            EnvironmentContext::base_divergence_only(&divergence).serialize_to_xml(),
+            expected
+        );
+    }
+
+    #[test]
+    fn serialize_resume_briefing_note() {
+        let briefing = ResumeBriefingEvent {
+            branch: Some("main".to_string()),
+            previous_head: Some("1111111aaaa".to_string()),
+            head: Some("2222222bbbb".to_string()),
+            new_commits: Some(1),
+            commit_subjects: vec!["Update CI".to_string()],
+            deleted_files: vec!["old.rs".to_string()],
+            ..Default::default()
+        };
+
+        let expected = r#"<environment_context>
+  <resume_briefing>since this session was last active, 1 new commit on main (latest: Update CI); files this session changed were deleted: old.rs; re-read files before editing them</resume_briefing>
+</environment_context>"#;
+
+        assert_eq!(
+            EnvironmentContext::resume_briefing_only(&briefing).serialize_to_xml(),
+            expected
+        );
+    }
+
+    #[test]
+    fn changes_since_is_none_when_nothing_changed() {
+        let context = EnvironmentContext::new(
+            Some(PathBuf::from("/repo")),
+            Some(AskForApproval::OnRequest),
+            Some(workspace_write_policy(vec!["/repo"], false)),
+            None,
+        );
+
+        assert_eq!(context.changes_since(&context.clone()), None);
+    }
+
+    #[test]
+    fn changes_since_sends_only_the_changed_cwd() {
+        let before = EnvironmentContext::new(
+            Some(PathBuf::from("/repo")),
+            Some(AskForApproval::OnRequest),
+            Some(workspace_write_policy(vec!["/repo"], false)),
+            None,
+        );
+        let after = EnvironmentContext::new(
+            Some(PathBuf::from("/repo/sub")),
+            Some(AskForApproval::OnRequest),
+            Some(workspace_write_policy(vec!["/repo"], false)),
+            None,
+        );
+
+        assert_eq!(
+            after.changes_since(&before),
+            Some(EnvironmentContext::new(
+                Some(PathBuf::from("/repo/sub")),
+                None,
+                None,
+                None,
+            ))
+        );
+    }
+
+    #[test]
+    fn changes_since_sends_all_sandbox_fields_together() {
+        let before = EnvironmentContext::new(
+            Some(PathBuf::from("/repo")),
+            Some(AskForApproval::OnRequest),
+            Some(workspace_write_policy(vec!["/repo"], false)),
+            None,
+        );
+        let after = EnvironmentContext::new(
+            Some(PathBuf::from("/repo")),
+            Some(AskForApproval::OnRequest),
+            Some(workspace_write_policy(vec!["/repo"], true)),
+            None,
+        );
+
+        assert_eq!(
+            after.changes_since(&before),
+            Some(EnvironmentContext::new(
+                None,
+                None,
+                Some(workspace_write_policy(vec!["/repo"], true)),
+                None,
+            ))
+        );
+    }
 }
@@ -87,6 +87,14 @@ pub enum CodexErr {
     #[error("timeout waiting for child process to exit")]
     Timeout,
 
+    /// A command stopped to wait for input and was terminated, either by the
+    /// user or because the session has nobody to ask.
+    #[error("command requested input: {prompt}")]
+    CommandRequestedInput {
+        prompt: String,
+        output: Box<ExecToolCallOutput>,
+    },
+
     /// Returned by run_command_stream when the child could not be spawned (its stdout/stderr pipes
     /// could not be captured). Analogous to the previous `CodexError::Spawn` variant.
     #[error("spawn failed: child stdout/stderr not captured")]
@@ -532,6 +540,7 @@ mod tests {
             stdout: StreamOutput::new(String::new()),
             stderr: StreamOutput::new(String::new()),
             aggregated_output: StreamOutput::new("aggregate detail".to_string()),
+            stderr_ranges: Vec::new(),
             duration: Duration::from_millis(10),
             timed_out: false,
         };
@@ -548,6 +557,7 @@ mod tests {
             stdout: StreamOutput::new("stdout detail".to_string()),
             stderr: StreamOutput::new("stderr detail".to_string()),
             aggregated_output: StreamOutput::new(String::new()),
+            stderr_ranges: Vec::new(),
             duration: Duration::from_millis(10),
             timed_out: false,
         };
@@ -564,6 +574,7 @@ mod tests {
             stdout: StreamOutput::new("stdout only".to_string()),
             stderr: StreamOutput::new(String::new()),
             aggregated_output: StreamOutput::new(String::new()),
+            stderr_ranges: Vec::new(),
             duration: Duration::from_millis(8),
             timed_out: false,
         };
@@ -580,6 +591,7 @@ mod tests {
             stdout: StreamOutput::new(String::new()),
             stderr: StreamOutput::new(String::new()),
             aggregated_output: StreamOutput::new(String::new()),
+            stderr_ranges: Vec::new(),
             duration: Duration::from_millis(5),
             timed_out: false,
         };
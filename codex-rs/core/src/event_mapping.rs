@@ -13,6 +13,7 @@ use codex_protocol::user_input::UserInput;
 use tracing::warn;
 use uuid::Uuid;
 
+use crate::scoped_instructions::SCOPED_INSTRUCTIONS_OPEN_TAG;
 use crate::user_instructions::UserInstructions;
 use crate::user_shell_command::is_user_shell_command_text;
 
@@ -20,6 +21,7 @@ fn is_session_prefix(text: &str) -> bool {
     let trimmed = text.trim_start();
     let lowered = trimmed.to_ascii_lowercase();
     lowered.starts_with("<environment_context>")
+        || lowered.starts_with(SCOPED_INSTRUCTIONS_OPEN_TAG)
 }
 
 fn parse_user_message(message: &[ContentItem]) -> Option<UserMessageItem> {
@@ -1,11 +1,14 @@
 #[cfg(unix)]
 use std::os::unix::process::ExitStatusExt;
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::io;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::ExitStatus;
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
 use std::time::Duration;
 use std::time::Instant;
 
@@ -18,10 +21,15 @@ use tokio::process::Child;
 use crate::error::CodexErr;
 use crate::error::Result;
 use crate::error::SandboxErr;
+use crate::input_prompt::PROMPT_POLL_INTERVAL;
+use crate::input_prompt::PromptHook;
+use crate::input_prompt::PromptWatcher;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecCommandOutputDeltaEvent;
+use crate::protocol::ExecInputResponse;
 use crate::protocol::ExecOutputStream;
+use crate::protocol::OutputRange;
 use crate::protocol::SandboxPolicy;
 use crate::sandboxing::CommandSpec;
 use crate::sandboxing::ExecEnv;
@@ -46,6 +54,18 @@ const AGGREGATE_BUFFER_INITIAL_CAPACITY: usize = 8 * 1024; // 8 KiB
 /// Aggregation still collects full output; only the live event stream is capped.
 pub(crate) const MAX_EXEC_OUTPUT_DELTAS_PER_CALL: usize = 10_000;
 
+/// Queued events above which output deltas are merged instead of sent one per
+/// read, so a slow consumer isn't flooded with small chunks.
+const DELTA_COALESCE_BACKLOG: usize = 256;
+
+/// Most bytes of a stream held back while coalescing. Older bytes beyond this
+/// are dropped from the live stream, which the delta offsets reveal.
+const MAX_COALESCED_DELTA_BYTES: usize = 256 * 1024;
+
+/// How often held-back output is retried while the process is quiet, so the
+/// last delta does not wait for the next read.
+const PENDING_DELTA_FLUSH_INTERVAL: Duration = Duration::from_millis(50);
+
 #[derive(Clone, Debug)]
 pub struct ExecParams {
     pub command: Vec<String>,
@@ -82,6 +102,8 @@ pub struct StdoutStream {
     pub sub_id: String,
     pub call_id: String,
     pub tx_event: Sender<Event>,
+    /// Watch the command for input prompts; see [`crate::input_prompt`].
+    pub input_prompts: Option<PromptHook>,
 }
 
 pub async fn process_exec_tool_call(
@@ -221,6 +243,18 @@ async fn exec_windows_sandbox(
     };
 
     let exit_status = synthetic_exit_status(capture.exit_code);
+    // Best-effort aggregate: the capture does not record interleaving, so
+    // stdout comes first, then stderr.
+    let aggregated_chunks = vec![
+        OutputChunk {
+            stream: ExecOutputStream::Stdout,
+            bytes: capture.stdout.clone(),
+        },
+        OutputChunk {
+            stream: ExecOutputStream::Stderr,
+            bytes: capture.stderr.clone(),
+        },
+    ];
     let stdout = StreamOutput {
         text: capture.stdout,
         truncated_after_lines: None,
@@ -229,21 +263,14 @@ async fn exec_windows_sandbox(
         text: capture.stderr,
         truncated_after_lines: None,
     };
-    // Best-effort aggregate: stdout then stderr
-    let mut aggregated = Vec::with_capacity(stdout.text.len() + stderr.text.len());
-    append_all(&mut aggregated, &stdout.text);
-    append_all(&mut aggregated, &stderr.text);
-    let aggregated_output = StreamOutput {
-        text: aggregated,
-        truncated_after_lines: None,
-    };
 
     Ok(RawExecToolCallOutput {
         exit_status,
         stdout,
         stderr,
-        aggregated_output,
+        aggregated_chunks,
         timed_out: capture.timed_out,
+        input_requested: None,
     })
 }
 
@@ -256,10 +283,14 @@ fn finalize_exec_result(
         Ok(raw_output) => {
             #[allow(unused_mut)]
             let mut timed_out = raw_output.timed_out;
+            let input_requested = raw_output.input_requested.clone();
 
             #[cfg(target_family = "unix")]
             {
-                if let Some(signal) = raw_output.exit_status.signal() {
+                // A command stopped at an input prompt was killed on purpose.
+                if input_requested.is_none()
+                    && let Some(signal) = raw_output.exit_status.signal()
+                {
                     if signal == TIMEOUT_CODE {
                         timed_out = true;
                     } else {
@@ -271,16 +302,20 @@ fn finalize_exec_result(
             let mut exit_code = raw_output.exit_status.code().unwrap_or(-1);
             if timed_out {
                 exit_code = EXEC_TIMEOUT_EXIT_CODE;
+            } else if input_requested.is_some() {
+                exit_code = EXIT_CODE_SIGNAL_BASE + SIGKILL_CODE;
             }
 
             let stdout = raw_output.stdout.from_utf8_lossy();
             let stderr = raw_output.stderr.from_utf8_lossy();
-            let aggregated_output = raw_output.aggregated_output.from_utf8_lossy();
+            let (aggregated_text, stderr_ranges) =
+                merge_output_chunks(&raw_output.aggregated_chunks);
             let exec_output = ExecToolCallOutput {
                 exit_code,
                 stdout,
                 stderr,
-                aggregated_output,
+                aggregated_output: StreamOutput::new(aggregated_text),
+                stderr_ranges,
                 duration,
                 timed_out,
             };
@@ -291,6 +326,13 @@ fn finalize_exec_result(
                 }));
             }
 
+            if let Some(prompt) = input_requested {
+                return Err(CodexErr::CommandRequestedInput {
+                    prompt,
+                    output: Box::new(exec_output),
+                });
+            }
+
             if is_likely_sandbox_denied(sandbox_type, &exec_output) {
                 return Err(CodexErr::Sandbox(SandboxErr::Denied {
                     output: Box::new(exec_output),
@@ -393,13 +435,23 @@ pub struct StreamOutput<T: Clone> {
     pub truncated_after_lines: Option<u32>,
 }
 
+/// One read from the child's stdout or stderr. Reads are kept in arrival
+/// order so the aggregated output preserves how the streams interleaved.
+#[derive(Debug)]
+struct OutputChunk {
+    stream: ExecOutputStream,
+    bytes: Vec<u8>,
+}
+
 #[derive(Debug)]
 struct RawExecToolCallOutput {
     pub exit_status: ExitStatus,
     pub stdout: StreamOutput<Vec<u8>>,
     pub stderr: StreamOutput<Vec<u8>>,
-    pub aggregated_output: StreamOutput<Vec<u8>>,
+    pub aggregated_chunks: Vec<OutputChunk>,
     pub timed_out: bool,
+    /// The prompt the command was stopped at, if any.
+    pub input_requested: Option<String>,
 }
 
 impl StreamOutput<String> {
@@ -425,16 +477,81 @@ fn append_all(dst: &mut Vec<u8>, src: &[u8]) {
     dst.extend_from_slice(src);
 }
 
+/// Joins chunks into the aggregated text and records which byte ranges of it
+/// came from stderr. Consecutive chunks from the same stream are decoded
+/// together so a multi-byte character split across reads survives.
+fn merge_output_chunks(chunks: &[OutputChunk]) -> (String, Vec<OutputRange>) {
+    let mut text = String::new();
+    let mut stderr_ranges = Vec::new();
+    for run in chunks.chunk_by(|a, b| a.stream == b.stream) {
+        let mut bytes = Vec::new();
+        for chunk in run {
+            append_all(&mut bytes, &chunk.bytes);
+        }
+        let start = text.len();
+        text.push_str(&String::from_utf8_lossy(&bytes));
+        if run[0].stream == ExecOutputStream::Stderr && text.len() > start {
+            stderr_ranges.push(OutputRange {
+                start,
+                end: text.len(),
+            });
+        }
+    }
+    (text, stderr_ranges)
+}
+
 #[derive(Clone, Debug)]
 pub struct ExecToolCallOutput {
     pub exit_code: i32,
     pub stdout: StreamOutput<String>,
     pub stderr: StreamOutput<String>,
+    /// stdout and stderr interleaved in the order they were read.
     pub aggregated_output: StreamOutput<String>,
+    /// Byte ranges of `aggregated_output` that came from stderr. Empty when
+    /// the streams were not captured separately (e.g. under a PTY).
+    pub stderr_ranges: Vec<OutputRange>,
     pub duration: Duration,
     pub timed_out: bool,
 }
 
+impl ExecToolCallOutput {
+    /// The aggregated output as shown to the model. When the command wrote
+    /// to both streams, every run is preceded by a `[stdout]` or `[stderr]`
+    /// line so errors stay attached to the output around them.
+    pub fn model_transcript(&self) -> Cow<'_, str> {
+        let text = self.aggregated_output.text.as_str();
+        let only_stderr = matches!(
+            self.stderr_ranges.as_slice(),
+            [range] if range.start == 0 && range.end == text.len()
+        );
+        if self.stderr_ranges.is_empty() || only_stderr {
+            return Cow::Borrowed(text);
+        }
+
+        fn push_run(out: &mut String, run: &str, marker: &str) {
+            if run.is_empty() {
+                return;
+            }
+            if !out.is_empty() && !out.ends_with('\n') {
+                out.push('\n');
+            }
+            out.push_str(marker);
+            out.push('\n');
+            out.push_str(run);
+        }
+
+        let mut out = String::with_capacity(text.len() + 16 * self.stderr_ranges.len());
+        let mut cursor = 0;
+        for range in &self.stderr_ranges {
+            push_run(&mut out, &text[cursor..range.start], "[stdout]");
+            push_run(&mut out, &text[range.start..range.end], "[stderr]");
+            cursor = range.end;
+        }
+        push_run(&mut out, &text[cursor..], "[stdout]");
+        Cow::Owned(out)
+    }
+}
+
 #[cfg_attr(not(target_os = "windows"), allow(unused_variables))]
 async fn exec(
     params: ExecParams,
@@ -499,21 +616,33 @@ async fn consume_truncated_output(
         ))
     })?;
 
-    let (agg_tx, agg_rx) = async_channel::unbounded::<Vec<u8>>();
+    let (agg_tx, agg_rx) = async_channel::unbounded::<OutputChunk>();
+
+    let prompt_watch = stdout_stream.as_ref().and_then(|stream| {
+        let hook = stream.input_prompts.as_ref()?;
+        let watcher = hook.broker.watcher()?;
+        Some((stream, hook, Arc::new(StdMutex::new(watcher))))
+    });
+    let watcher = prompt_watch
+        .as_ref()
+        .map(|(_, _, watcher)| Arc::clone(watcher));
 
     let stdout_handle = tokio::spawn(read_capped(
         BufReader::new(stdout_reader),
         stdout_stream.clone(),
         false,
         Some(agg_tx.clone()),
+        watcher.clone(),
     ));
     let stderr_handle = tokio::spawn(read_capped(
         BufReader::new(stderr_reader),
         stdout_stream.clone(),
         true,
         Some(agg_tx.clone()),
+        watcher,
     ));
 
+    let mut input_requested = None;
     let (exit_status, timed_out) = tokio::select! {
         result = tokio::time::timeout(timeout, child.wait()) => {
             match result {
@@ -535,7 +664,16 @@ async fn consume_truncated_output(
             child.start_kill()?;
             (synthetic_exit_status(EXIT_CODE_SIGNAL_BASE + SIGKILL_CODE), false)
         }
+        prompt = wait_for_unanswerable_prompt(prompt_watch.as_ref()) => {
+            kill_child_process_group(&mut child)?;
+            child.start_kill()?;
+            input_requested = Some(prompt);
+            (synthetic_exit_status(EXIT_CODE_SIGNAL_BASE + SIGKILL_CODE), false)
+        }
     };
+    if let Some((stream, hook, _)) = &prompt_watch {
+        hook.broker.cancel(&stream.call_id);
+    }
 
     // Wait for the stdout/stderr collection tasks but guard against them
     // hanging forever. In the normal case, both pipes are closed once the child
@@ -586,38 +724,94 @@ async fn consume_truncated_output(
 
     drop(agg_tx);
 
-    let mut combined_buf = Vec::with_capacity(AGGREGATE_BUFFER_INITIAL_CAPACITY);
+    let mut aggregated_chunks = Vec::new();
     while let Ok(chunk) = agg_rx.recv().await {
-        append_all(&mut combined_buf, &chunk);
+        aggregated_chunks.push(chunk);
     }
-    let aggregated_output = StreamOutput {
-        text: combined_buf,
-        truncated_after_lines: None,
-    };
 
     Ok(RawExecToolCallOutput {
         exit_status,
         stdout,
         stderr,
-        aggregated_output,
+        aggregated_chunks,
         timed_out,
+        input_requested,
     })
 }
 
+/// Resolves with the prompt text once the user (or a headless session)
+/// decides to stop a command waiting for input. Shell commands run with stdin
+/// closed, so there is no way to answer the prompt itself.
+async fn wait_for_unanswerable_prompt(
+    prompt_watch: Option<&(&StdoutStream, &PromptHook, Arc<StdMutex<PromptWatcher>>)>,
+) -> String {
+    let Some((stream, hook, watcher)) = prompt_watch else {
+        return std::future::pending().await;
+    };
+    loop {
+        tokio::time::sleep(PROMPT_POLL_INTERVAL).await;
+        let prompt = match watcher.lock() {
+            Ok(mut watcher) => watcher.poll(Instant::now()),
+            Err(_) => None,
+        };
+        let Some(prompt) = prompt else {
+            continue;
+        };
+        let response = hook
+            .broker
+            .request(
+                &stream.tx_event,
+                &stream.sub_id,
+                &stream.call_id,
+                &hook.command,
+                &prompt,
+                false,
+            )
+            .await;
+        if response == ExecInputResponse::Terminate {
+            return prompt.text;
+        }
+    }
+}
+
 async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
     mut reader: R,
     stream: Option<StdoutStream>,
     is_stderr: bool,
-    aggregate_tx: Option<Sender<Vec<u8>>>,
+    aggregate_tx: Option<Sender<OutputChunk>>,
+    prompt_watcher: Option<Arc<StdMutex<PromptWatcher>>>,
 ) -> io::Result<StreamOutput<Vec<u8>>> {
     let mut buf = Vec::with_capacity(AGGREGATE_BUFFER_INITIAL_CAPACITY);
     let mut tmp = [0u8; READ_CHUNK_SIZE];
     let mut emitted_deltas: usize = 0;
+    let mut pending_delta = PendingDelta::default();
+    let mut stream_offset: u64 = 0;
+    let output_stream = if is_stderr {
+        ExecOutputStream::Stderr
+    } else {
+        ExecOutputStream::Stdout
+    };
 
     // No caps: append all bytes
 
     loop {
-        let n = reader.read(&mut tmp).await?;
+        let n = if pending_delta.is_empty() {
+            reader.read(&mut tmp).await?
+        } else {
+            tokio::select! {
+                read = reader.read(&mut tmp) => read?,
+                () = tokio::time::sleep(PENDING_DELTA_FLUSH_INTERVAL) => {
+                    if let Some(stream) = &stream
+                        && stream.tx_event.len() < DELTA_COALESCE_BACKLOG
+                        && let Some((offset, chunk)) = pending_delta.take()
+                    {
+                        send_output_delta(stream, output_stream.clone(), offset, chunk).await;
+                        emitted_deltas += 1;
+                    }
+                    continue;
+                }
+            }
+        };
         if n == 0 {
             break;
         }
@@ -625,39 +819,104 @@ async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
         if let Some(stream) = &stream
             && emitted_deltas < MAX_EXEC_OUTPUT_DELTAS_PER_CALL
         {
-            let chunk = tmp[..n].to_vec();
-            let msg = EventMsg::ExecCommandOutputDelta(ExecCommandOutputDeltaEvent {
-                call_id: stream.call_id.clone(),
-                stream: if is_stderr {
-                    ExecOutputStream::Stderr
-                } else {
-                    ExecOutputStream::Stdout
-                },
-                chunk,
-            });
-            let event = Event {
-                id: stream.sub_id.clone(),
-                msg,
-            };
-            #[allow(clippy::let_unit_value)]
-            let _ = stream.tx_event.send(event).await;
-            emitted_deltas += 1;
+            pending_delta.push(stream_offset, &tmp[..n], MAX_COALESCED_DELTA_BYTES);
+            // While the consumer is behind, hold output back; it goes out as
+            // one delta once the backlog drains.
+            if stream.tx_event.len() < DELTA_COALESCE_BACKLOG
+                && let Some((offset, chunk)) = pending_delta.take()
+            {
+                send_output_delta(stream, output_stream.clone(), offset, chunk).await;
+                emitted_deltas += 1;
+            }
         }
+        stream_offset += n as u64;
 
         if let Some(tx) = &aggregate_tx {
-            let _ = tx.send(tmp[..n].to_vec()).await;
+            let _ = tx
+                .send(OutputChunk {
+                    stream: output_stream.clone(),
+                    bytes: tmp[..n].to_vec(),
+                })
+                .await;
+        }
+
+        if let Some(watcher) = &prompt_watcher
+            && let Ok(mut watcher) = watcher.lock()
+        {
+            watcher.observe(&tmp[..n], Instant::now());
         }
 
         append_all(&mut buf, &tmp[..n]);
         // Continue reading to EOF to avoid back-pressure
     }
 
+    if let Some(stream) = &stream
+        && let Some((offset, chunk)) = pending_delta.take()
+    {
+        send_output_delta(stream, output_stream, offset, chunk).await;
+    }
+
     Ok(StreamOutput {
         text: buf,
         truncated_after_lines: None,
     })
 }
 
+/// Live output read but not yet sent as an ExecCommandOutputDelta.
+#[derive(Debug, Default)]
+struct PendingDelta {
+    /// Stream offset of `bytes[0]`.
+    offset: u64,
+    bytes: Vec<u8>,
+}
+
+impl PendingDelta {
+    /// Appends `chunk`, which starts at `offset` in the stream, keeping only
+    /// the last `cap` bytes.
+    fn push(&mut self, offset: u64, chunk: &[u8], cap: usize) {
+        if self.bytes.is_empty() {
+            self.offset = offset;
+        }
+        self.bytes.extend_from_slice(chunk);
+        if self.bytes.len() > cap {
+            let excess = self.bytes.len() - cap;
+            self.bytes.drain(..excess);
+            self.offset += excess as u64;
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn take(&mut self) -> Option<(u64, Vec<u8>)> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        Some((self.offset, std::mem::take(&mut self.bytes)))
+    }
+}
+
+async fn send_output_delta(
+    stream: &StdoutStream,
+    output_stream: ExecOutputStream,
+    offset: u64,
+    chunk: Vec<u8>,
+) {
+    let msg = EventMsg::ExecCommandOutputDelta(ExecCommandOutputDeltaEvent {
+        call_id: stream.call_id.clone(),
+        stream: output_stream,
+        chunk,
+        offset: Some(offset),
+    });
+    let event = Event {
+        id: stream.sub_id.clone(),
+        msg,
+    };
+    #[allow(clippy::let_unit_value)]
+    let _ = stream.tx_event.send(event).await;
+}
+
 #[cfg(unix)]
 fn synthetic_exit_status(code: i32) -> ExitStatus {
     use std::os::unix::process::ExitStatusExt;
@@ -720,6 +979,7 @@ mod tests {
             stdout: StreamOutput::new(stdout.to_string()),
             stderr: StreamOutput::new(stderr.to_string()),
             aggregated_output: StreamOutput::new(aggregated.to_string()),
+            stderr_ranges: Vec::new(),
             duration: Duration::from_millis(1),
             timed_out: false,
         }
@@ -777,6 +1037,164 @@ mod tests {
         assert!(is_likely_sandbox_denied(SandboxType::LinuxSeccomp, &output));
     }
 
+    fn chunk(stream: ExecOutputStream, text: &str) -> OutputChunk {
+        OutputChunk {
+            stream,
+            bytes: text.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn merge_output_chunks_keeps_arrival_order_and_marks_stderr() {
+        let chunks = vec![
+            chunk(ExecOutputStream::Stdout, "a\n"),
+            chunk(ExecOutputStream::Stdout, "b"),
+            chunk(ExecOutputStream::Stderr, "err\n"),
+            chunk(ExecOutputStream::Stdout, "c\n"),
+        ];
+
+        assert_eq!(
+            merge_output_chunks(&chunks),
+            (
+                "a\nberr\nc\n".to_string(),
+                vec![OutputRange { start: 3, end: 7 }]
+            )
+        );
+    }
+
+    #[test]
+    fn model_transcript_marks_stream_switches_on_their_own_lines() {
+        let mut output = make_exec_output(1, "a\nb", "err\n", "a\nberr\nc\n");
+        output.stderr_ranges = vec![OutputRange { start: 3, end: 7 }];
+
+        assert_eq!(
+            output.model_transcript(),
+            "[stdout]\na\nb\n[stderr]\nerr\n[stdout]\nc\n"
+        );
+    }
+
+    #[test]
+    fn model_transcript_leaves_single_stream_output_unmarked() {
+        let stdout_only = make_exec_output(0, "ok\n", "", "ok\n");
+        assert_eq!(stdout_only.model_transcript(), "ok\n");
+
+        let mut stderr_only = make_exec_output(1, "", "boom\n", "boom\n");
+        stderr_only.stderr_ranges = vec![OutputRange { start: 0, end: 5 }];
+        assert_eq!(stderr_only.model_transcript(), "boom\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn exec_preserves_interleaving_of_stdout_and_stderr() -> Result<()> {
+        let command = vec![
+            "/bin/bash".to_string(),
+            "-c".to_string(),
+            "echo out1; sleep 0.2; echo err1 >&2; sleep 0.2; echo out2".to_string(),
+        ];
+        let params = ExecParams {
+            command,
+            cwd: std::env::current_dir()?,
+            timeout_ms: Some(5_000),
+            env: std::env::vars().collect(),
+            with_escalated_permissions: None,
+            justification: None,
+            arg0: None,
+        };
+
+        let raw = exec(params, SandboxType::None, &SandboxPolicy::ReadOnly, None).await;
+        let output = finalize_exec_result(raw, SandboxType::None, Duration::ZERO)?;
+
+        assert_eq!(output.aggregated_output.text, "out1\nerr1\nout2\n");
+        assert_eq!(
+            output.stderr_ranges,
+            vec![OutputRange { start: 5, end: 10 }]
+        );
+        assert_eq!(
+            output.model_transcript(),
+            "[stdout]\nout1\n[stderr]\nerr1\n[stdout]\nout2\n"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn held_back_delta_is_flushed_once_the_backlog_drains() -> anyhow::Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        let (mut writer, reader) = tokio::io::duplex(64);
+        let (tx_event, rx_event) = async_channel::unbounded();
+        let (aggregate_tx, aggregate_rx) = async_channel::unbounded();
+        // A consumer that has fallen behind.
+        for _ in 0..DELTA_COALESCE_BACKLOG {
+            let msg = EventMsg::BackgroundEvent(crate::protocol::BackgroundEventEvent {
+                message: "backlog".to_string(),
+            });
+            tx_event
+                .send(Event {
+                    id: "sub".to_string(),
+                    msg,
+                })
+                .await?;
+        }
+        let stream = StdoutStream {
+            sub_id: "sub".to_string(),
+            call_id: "call".to_string(),
+            tx_event,
+            input_prompts: None,
+        };
+        let reader_task = tokio::spawn(read_capped(
+            reader,
+            Some(stream),
+            false,
+            Some(aggregate_tx),
+            None,
+        ));
+        let as_delta = |event: Event| match event.msg {
+            EventMsg::ExecCommandOutputDelta(delta) => Some((
+                delta.stream,
+                String::from_utf8_lossy(&delta.chunk).into_owned(),
+                delta.offset,
+            )),
+            _ => None,
+        };
+
+        // Read while the backlog is full: held back.
+        writer.write_all(b"out1\n").await?;
+        aggregate_rx.recv().await?;
+        for _ in 0..DELTA_COALESCE_BACKLOG {
+            rx_event.recv().await?;
+        }
+        // The process stays quiet; the held-back delta still goes out.
+        let flushed = tokio::time::timeout(Duration::from_secs(5), rx_event.recv()).await??;
+        assert_eq!(
+            as_delta(flushed),
+            Some((ExecOutputStream::Stdout, "out1\n".to_string(), Some(0)))
+        );
+
+        // With the backlog gone, output is sent as it is read.
+        writer.write_all(b"out2\n").await?;
+        aggregate_rx.recv().await?;
+        assert_eq!(
+            as_delta(rx_event.try_recv()?),
+            Some((ExecOutputStream::Stdout, "out2\n".to_string(), Some(5)))
+        );
+
+        drop(writer);
+        let output = reader_task.await??;
+        assert_eq!(output.text, b"out1\nout2\n".to_vec());
+        assert!(rx_event.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn pending_delta_keeps_the_newest_bytes_and_their_offset() {
+        let mut pending = PendingDelta::default();
+        pending.push(100, b"abcd", 6);
+        pending.push(104, b"efgh", 6);
+
+        assert_eq!(pending.take(), Some((102, b"cdefgh".to_vec())));
+        assert_eq!(pending.take(), None);
+    }
+
     #[cfg(unix)]
     #[tokio::test]
     async fn kill_child_process_group_kills_grandchildren_on_timeout() -> Result<()> {
@@ -2,7 +2,6 @@ use crate::config::types::EnvironmentVariablePattern;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyInherit;
 use std::collections::HashMap;
-use std::collections::HashSet;
 
 /// Construct an environment map based on the rules in the specified policy. The
 /// resulting map can be passed directly to `Command::envs()` after calling
@@ -15,7 +14,22 @@ pub fn create_env(policy: &ShellEnvironmentPolicy) -> HashMap<String, String> {
     populate_env(std::env::vars(), policy)
 }
 
+/// Number of inherited environment variables that the policy's exclude and
+/// include-only rules keep out of spawned processes.
+pub fn filtered_env_var_count(policy: &ShellEnvironmentPolicy) -> usize {
+    filter_env(std::env::vars(), policy).1
+}
+
 fn populate_env<I>(vars: I, policy: &ShellEnvironmentPolicy) -> HashMap<String, String>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    filter_env(vars, policy).0
+}
+
+/// Builds the environment and counts the variables removed by filtering
+/// (steps 2, 3 and 5), as opposed to never being inherited.
+fn filter_env<I>(vars: I, policy: &ShellEnvironmentPolicy) -> (HashMap<String, String>, usize)
 where
     I: IntoIterator<Item = (String, String)>,
 {
@@ -28,12 +42,12 @@ where
             const CORE_VARS: &[&str] = &[
                 "HOME", "LOGNAME", "PATH", "SHELL", "USER", "USERNAME", "TMPDIR", "TEMP", "TMP",
             ];
-            let allow: HashSet<&str> = CORE_VARS.iter().copied().collect();
             vars.into_iter()
-                .filter(|(k, _)| allow.contains(k.as_str()))
+                .filter(|(k, _)| CORE_VARS.iter().any(|core| env_var_name_eq(core, k)))
                 .collect()
         }
     };
+    let inherited = env_map.len();
 
     // Internal helper – does `name` match **any** pattern in `patterns`?
     let matches_any = |name: &str, patterns: &[EnvironmentVariablePattern]| -> bool {
@@ -55,6 +69,8 @@ where
         env_map.retain(|k, _| !matches_any(k, &policy.exclude));
     }
 
+    let mut filtered = inherited - env_map.len();
+
     // Step 4 – Apply user-provided overrides.
     for (key, val) in &policy.r#set {
         env_map.insert(key.clone(), val.clone());
@@ -62,16 +78,28 @@ where
 
     // Step 5 – If include_only is non-empty, keep *only* the matching vars.
     if !policy.include_only.is_empty() {
+        let before = env_map.len();
         env_map.retain(|k, _| matches_any(k, &policy.include_only));
+        filtered += before - env_map.len();
     }
 
-    env_map
+    (env_map, filtered)
+}
+
+/// Variable names compare case-insensitively on Windows only.
+fn env_var_name_eq(a: &str, b: &str) -> bool {
+    if cfg!(windows) {
+        a.eq_ignore_ascii_case(b)
+    } else {
+        a == b
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::types::ShellEnvironmentPolicyInherit;
+    use crate::config::types::environment_variable_pattern;
     use maplit::hashmap;
 
     fn make_vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
@@ -191,4 +219,93 @@ mod tests {
         };
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn test_inherit_core_keeps_only_core_vars() {
+        let vars = make_vars(&[("PATH", "/usr/bin"), ("HOME", "/home"), ("EDITOR", "vim")]);
+
+        let policy = ShellEnvironmentPolicy {
+            inherit: ShellEnvironmentPolicyInherit::Core,
+            ignore_default_excludes: true,
+            ..Default::default()
+        };
+
+        let (result, filtered) = filter_env(vars, &policy);
+        let expected: HashMap<String, String> = hashmap! {
+            "PATH".to_string() => "/usr/bin".to_string(),
+            "HOME".to_string() => "/home".to_string(),
+        };
+        assert_eq!((result, filtered), (expected, 0));
+    }
+
+    #[test]
+    fn test_exclude_glob_suffix() {
+        let vars = make_vars(&[
+            ("PATH", "/usr/bin"),
+            ("DB_SECRET", "hunter2"),
+            ("AWS_SECRET", "abc"),
+            ("SECRET_SAUCE", "ketchup"),
+        ]);
+
+        let policy = ShellEnvironmentPolicy {
+            inherit: ShellEnvironmentPolicyInherit::All,
+            ignore_default_excludes: true,
+            exclude: vec![environment_variable_pattern("*_SECRET")],
+            ..Default::default()
+        };
+
+        let (result, filtered) = filter_env(vars, &policy);
+        let expected: HashMap<String, String> = hashmap! {
+            "PATH".to_string() => "/usr/bin".to_string(),
+            "SECRET_SAUCE".to_string() => "ketchup".to_string(),
+        };
+        assert_eq!((result, filtered), (expected, 2));
+    }
+
+    #[test]
+    fn test_exclude_case_follows_platform() {
+        let vars = make_vars(&[("db_secret", "hunter2")]);
+
+        let policy = ShellEnvironmentPolicy {
+            inherit: ShellEnvironmentPolicyInherit::All,
+            ignore_default_excludes: true,
+            exclude: vec![environment_variable_pattern("*_SECRET")],
+            ..Default::default()
+        };
+
+        let result = populate_env(vars.clone(), &policy);
+        let expected: HashMap<String, String> = if cfg!(windows) {
+            HashMap::new()
+        } else {
+            vars.into_iter().collect()
+        };
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_set_takes_precedence_over_inherit_and_exclude() {
+        let vars = make_vars(&[("PATH", "/usr/bin"), ("API_SECRET", "inherited")]);
+
+        let mut policy = ShellEnvironmentPolicy {
+            inherit: ShellEnvironmentPolicyInherit::All,
+            exclude: vec![
+                environment_variable_pattern("*_SECRET"),
+                environment_variable_pattern("PATH"),
+            ],
+            ..Default::default()
+        };
+        policy
+            .r#set
+            .insert("API_SECRET".to_string(), "configured".to_string());
+        policy
+            .r#set
+            .insert("PATH".to_string(), "/opt/bin".to_string());
+
+        let (result, filtered) = filter_env(vars, &policy);
+        let expected: HashMap<String, String> = hashmap! {
+            "PATH".to_string() => "/opt/bin".to_string(),
+            "API_SECRET".to_string() => "configured".to_string(),
+        };
+        assert_eq!((result, filtered), (expected, 2));
+    }
 }
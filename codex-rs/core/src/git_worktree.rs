@@ -0,0 +1,411 @@
+//! Detect when a linked git worktree has fallen behind the upstream of its
+//! base branch.
+//!
+//! Worktrees share refs with the main checkout, so a `git fetch` run anywhere
+//! in the repository updates the remote-tracking branch the worktree is
+//! compared against; the check itself never touches the network. Sessions run
+//! it at start and then at most once per configured interval on turn
+//! boundaries, and report each new finding to the user and the model.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_protocol::protocol::BaseDivergenceEvent;
+
+use crate::git_info::default_branch_name;
+use crate::git_info::get_git_remotes;
+use crate::git_info::run_git_command_with_timeout;
+
+/// Default for `[worktree_base_check].min_commits_behind`.
+pub const DEFAULT_MIN_COMMITS_BEHIND: usize = 5;
+
+/// Default for `[worktree_base_check].interval_minutes`.
+pub const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Number of upstream commit subjects included in a report.
+const TIP_SUBJECTS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaseCheckSettings {
+    /// Report only when the worktree is at least this many commits behind.
+    pub min_commits_behind: usize,
+    /// Minimum time between two checks in the same session.
+    pub interval: Duration,
+}
+
+impl Default for BaseCheckSettings {
+    fn default() -> Self {
+        Self {
+            min_commits_behind: DEFAULT_MIN_COMMITS_BEHIND,
+            interval: DEFAULT_CHECK_INTERVAL,
+        }
+    }
+}
+
+/// Short note for the environment context so the model knows its checkout is
+/// stale.
+pub fn base_divergence_note(divergence: &BaseDivergenceEvent) -> String {
+    let BaseDivergenceEvent {
+        base,
+        upstream,
+        behind,
+        ..
+    } = divergence;
+    let commits = if *behind == 1 { "commit" } else { "commits" };
+    format!(
+        "base {base} is {behind} {commits} behind {upstream}; consider rebasing or asking the user"
+    )
+}
+
+/// Returns how far `cwd`'s worktree is behind the upstream of its base branch,
+/// or `None` when `cwd` is not a linked worktree, the upstream cannot be
+/// determined, or fewer than `min_commits_behind` commits are missing.
+pub async fn detect_base_divergence(
+    cwd: &Path,
+    min_commits_behind: usize,
+) -> Option<BaseDivergenceEvent> {
+    if !is_linked_worktree(cwd).await {
+        return None;
+    }
+    let base = default_branch_name(cwd).await?;
+    let upstream = base_upstream(cwd, &base).await?;
+
+    let range = format!("HEAD..{upstream}");
+    let count = run_git_command_with_timeout(&["rev-list", "--count", &range], cwd).await?;
+    if !count.status.success() {
+        return None;
+    }
+    let behind: usize = String::from_utf8_lossy(&count.stdout).trim().parse().ok()?;
+    if behind == 0 || behind < min_commits_behind {
+        return None;
+    }
+
+    let limit = TIP_SUBJECTS.to_string();
+    let tip_subjects = match run_git_command_with_timeout(
+        &["log", "--format=%s", "-n", &limit, &range],
+        cwd,
+    )
+    .await
+    {
+        Some(out) if out.status.success() => String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|subject| !subject.is_empty())
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    Some(BaseDivergenceEvent {
+        base,
+        upstream,
+        behind,
+        tip_subjects,
+    })
+}
+
+/// A linked worktree (`git worktree add`) has its own git dir under the
+/// common one; the main checkout's git dir is the common dir itself.
+async fn is_linked_worktree(cwd: &Path) -> bool {
+    let Some(out) =
+        run_git_command_with_timeout(&["rev-parse", "--git-dir", "--git-common-dir"], cwd).await
+    else {
+        return false;
+    };
+    if !out.status.success() {
+        return false;
+    }
+    let text = String::from_utf8_lossy(&out.stdout);
+    let mut dirs = text.lines().map(|dir| resolve_git_path(cwd, dir.trim()));
+    match (dirs.next(), dirs.next()) {
+        (Some(git_dir), Some(common_dir)) => git_dir != common_dir,
+        _ => false,
+    }
+}
+
+fn resolve_git_path(cwd: &Path, dir: &str) -> PathBuf {
+    let path = cwd.join(dir);
+    // Normalize to handle macOS /var vs /private/var and resolve ".." segments.
+    std::fs::canonicalize(&path).unwrap_or(path)
+}
+
+/// The configured upstream of `base` (`base@{upstream}`), falling back to
+/// `<remote>/<base>` on the first remote that has it (origin prioritized).
+async fn base_upstream(cwd: &Path, base: &str) -> Option<String> {
+    let spec = format!("{base}@{{upstream}}");
+    if let Some(out) =
+        run_git_command_with_timeout(&["rev-parse", "--abbrev-ref", &spec], cwd).await
+        && out.status.success()
+    {
+        let upstream = String::from_utf8_lossy(&out.stdout).trim().to_string();
+        if !upstream.is_empty() {
+            return Some(upstream);
+        }
+    }
+
+    for remote in get_git_remotes(cwd).await.unwrap_or_default() {
+        let candidate = format!("{remote}/{base}");
+        let full_ref = format!("refs/remotes/{candidate}");
+        if let Some(out) =
+            run_git_command_with_timeout(&["rev-parse", "--verify", "--quiet", &full_ref], cwd)
+                .await
+            && out.status.success()
+        {
+            return Some(candidate);
+        }
+    }
+    None
+}
+
+#[derive(Debug, Default)]
+struct BaseCheckState {
+    last_checked: Option<Instant>,
+    /// Last divergence reported to the user, so unchanged findings are not
+    /// repeated every interval.
+    reported: Option<BaseDivergenceEvent>,
+    /// Reported divergence the model has not been told about yet.
+    unrecorded: Option<BaseDivergenceEvent>,
+}
+
+/// Per-session state for the base divergence check.
+#[derive(Debug, Default)]
+pub(crate) struct SessionBaseCheck {
+    settings: Option<BaseCheckSettings>,
+    state: Mutex<BaseCheckState>,
+}
+
+impl SessionBaseCheck {
+    /// `settings` is `None` when `[worktree_base_check].enabled = false`.
+    pub(crate) fn new(settings: Option<BaseCheckSettings>) -> Self {
+        Self {
+            settings,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Runs the check unless one ran less than the configured interval before
+    /// `now`. Returns the divergence when it differs from the last one
+    /// reported.
+    pub(crate) async fn check_if_due(
+        &self,
+        cwd: &Path,
+        now: Instant,
+    ) -> Option<BaseDivergenceEvent> {
+        let settings = self.settings.as_ref()?;
+        {
+            let mut state = self.state.lock().ok()?;
+            if let Some(last_checked) = state.last_checked
+                && now.saturating_duration_since(last_checked) < settings.interval
+            {
+                return None;
+            }
+            state.last_checked = Some(now);
+        }
+
+        let divergence = detect_base_divergence(cwd, settings.min_commits_behind).await;
+        let mut state = self.state.lock().ok()?;
+        if state.reported == divergence {
+            return None;
+        }
+        state.reported.clone_from(&divergence);
+        state.unrecorded.clone_from(&divergence);
+        divergence
+    }
+
+    /// The latest reported divergence if the model has not been given it yet.
+    pub(crate) fn take_unrecorded(&self) -> Option<BaseDivergenceEvent> {
+        self.state.lock().ok()?.unrecorded.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core_test_support::skip_if_sandbox;
+    use pretty_assertions::assert_eq;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(cwd: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .env("GIT_CONFIG_GLOBAL", "/dev/null")
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .args([
+                "-c",
+                "user.name=Test User",
+                "-c",
+                "user.email=test@example.com",
+            ])
+            .args(args)
+            .current_dir(cwd)
+            .status()
+            .expect("run git");
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    fn commit(cwd: &Path, subject: &str) {
+        git(cwd, &["commit", "--allow-empty", "-m", subject]);
+    }
+
+    /// An upstream repository, a clone of it with a linked worktree on a
+    /// feature branch, and a second clone used to advance upstream `main`.
+    struct Fixture {
+        _tmp: TempDir,
+        clone: PathBuf,
+        worktree: PathBuf,
+        pusher: PathBuf,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let tmp = TempDir::new().expect("tempdir");
+            let upstream = tmp.path().join("upstream.git");
+            let clone = tmp.path().join("clone");
+            let worktree = tmp.path().join("wt");
+            let pusher = tmp.path().join("pusher");
+
+            git(
+                tmp.path(),
+                &["init", "--bare", "-b", "main", upstream.to_str().unwrap()],
+            );
+            git(
+                tmp.path(),
+                &[
+                    "clone",
+                    upstream.to_str().unwrap(),
+                    pusher.to_str().unwrap(),
+                ],
+            );
+            git(&pusher, &["symbolic-ref", "HEAD", "refs/heads/main"]);
+            commit(&pusher, "initial");
+            git(&pusher, &["push", "origin", "main"]);
+
+            git(
+                tmp.path(),
+                &["clone", upstream.to_str().unwrap(), clone.to_str().unwrap()],
+            );
+            git(
+                &clone,
+                &[
+                    "worktree",
+                    "add",
+                    worktree.to_str().unwrap(),
+                    "-b",
+                    "feature",
+                ],
+            );
+
+            Self {
+                _tmp: tmp,
+                clone,
+                worktree,
+                pusher,
+            }
+        }
+
+        /// Lands `subjects` on upstream `main` and fetches them into the clone.
+        fn advance_upstream(&self, subjects: &[&str]) {
+            for subject in subjects {
+                commit(&self.pusher, subject);
+            }
+            git(&self.pusher, &["push", "origin", "main"]);
+            git(&self.clone, &["fetch", "origin"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn detects_worktree_behind_upstream_past_threshold() {
+        skip_if_sandbox!();
+        let fixture = Fixture::new();
+        assert_eq!(detect_base_divergence(&fixture.worktree, 1).await, None);
+
+        fixture.advance_upstream(&["one", "two", "three", "four"]);
+
+        assert_eq!(detect_base_divergence(&fixture.worktree, 5).await, None);
+        assert_eq!(
+            detect_base_divergence(&fixture.worktree, 4).await,
+            Some(BaseDivergenceEvent {
+                base: "main".to_string(),
+                upstream: "origin/main".to_string(),
+                behind: 4,
+                tip_subjects: vec!["four".to_string(), "three".to_string(), "two".to_string()],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn main_checkout_is_not_reported() {
+        skip_if_sandbox!();
+        let fixture = Fixture::new();
+        fixture.advance_upstream(&["one", "two"]);
+
+        assert_eq!(detect_base_divergence(&fixture.clone, 1).await, None);
+    }
+
+    #[tokio::test]
+    async fn session_check_respects_interval_and_reports_changes_once() {
+        skip_if_sandbox!();
+        let fixture = Fixture::new();
+        fixture.advance_upstream(&["one", "two"]);
+        let check = SessionBaseCheck::new(Some(BaseCheckSettings {
+            min_commits_behind: 2,
+            interval: Duration::from_secs(600),
+        }));
+        let start = Instant::now();
+
+        let first = check.check_if_due(&fixture.worktree, start).await;
+        assert_eq!(first.as_ref().map(|d| d.behind), Some(2));
+        assert_eq!(check.take_unrecorded(), first);
+        assert_eq!(check.take_unrecorded(), None);
+
+        fixture.advance_upstream(&["three"]);
+        // Not due yet, even though upstream moved.
+        assert_eq!(
+            check
+                .check_if_due(&fixture.worktree, start + Duration::from_secs(60))
+                .await,
+            None
+        );
+        let later = start + Duration::from_secs(601);
+        assert_eq!(
+            check
+                .check_if_due(&fixture.worktree, later)
+                .await
+                .map(|d| d.behind),
+            Some(3)
+        );
+        // Unchanged findings are not repeated.
+        assert_eq!(
+            check
+                .check_if_due(&fixture.worktree, later + Duration::from_secs(601))
+                .await,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn disabled_check_never_runs() {
+        let check = SessionBaseCheck::new(None);
+        assert_eq!(
+            check.check_if_due(Path::new("."), Instant::now()).await,
+            None
+        );
+    }
+
+    #[test]
+    fn note_names_base_upstream_and_count() {
+        let divergence = BaseDivergenceEvent {
+            base: "main".to_string(),
+            upstream: "origin/main".to_string(),
+            behind: 14,
+            tip_subjects: Vec::new(),
+        };
+        assert_eq!(
+            base_divergence_note(&divergence),
+            "base main is 14 commits behind origin/main; consider rebasing or asking the user"
+        );
+    }
+}
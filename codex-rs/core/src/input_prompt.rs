@@ -0,0 +1,390 @@
+//! Notice when a running command stops to wait for input.
+//!
+//! Commands run by the agent have no terminal to answer from, so a `git push`
+//! asking for credentials or an installer asking `[Y/n]` would otherwise sit
+//! there until the exec timeout. The streaming exec paths feed their output
+//! through a [`PromptWatcher`]; once the last, unterminated line has been idle
+//! long enough and looks like a question, the session either asks the user
+//! (interactive frontends) or stops the command right away (`codex exec`).
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use async_channel::Sender;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ExecInputRequestEvent;
+use codex_protocol::protocol::ExecInputResponse;
+use codex_protocol::protocol::SessionSource;
+use serde::Deserialize;
+use tokio::sync::oneshot;
+
+/// Default for `[input_prompt_detection].idle_seconds`.
+pub const DEFAULT_PROMPT_IDLE: Duration = Duration::from_secs(3);
+
+/// How often the exec paths check a quiet command for a prompt.
+pub(crate) const PROMPT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Longest unterminated line kept while watching for a prompt.
+const MAX_TAIL_CHARS: usize = 512;
+
+/// How sure the detector is that a quiet command is waiting for input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptConfidence {
+    /// Output stopped mid-line and nothing else suggests a question.
+    Low,
+    /// The line ends like a question (`?` or `:`).
+    Medium,
+    /// A well-known prompt such as `[Y/n]` or `Password:`.
+    High,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptDetectionSettings {
+    /// How long the command must be quiet after printing a partial line.
+    pub idle: Duration,
+    /// Prompts below this confidence are ignored.
+    pub min_confidence: PromptConfidence,
+}
+
+impl Default for PromptDetectionSettings {
+    fn default() -> Self {
+        Self {
+            idle: DEFAULT_PROMPT_IDLE,
+            min_confidence: PromptConfidence::Medium,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedPrompt {
+    pub text: String,
+    pub confidence: PromptConfidence,
+}
+
+/// Substrings (lowercased) that identify a prompt on their own.
+const KNOWN_PROMPTS: &[&str] = &[
+    "[y/n]",
+    "(y/n)",
+    "[yes/no]",
+    "(yes/no)",
+    "(yes/no/[fingerprint])",
+    "password:",
+    "password for",
+    "passphrase",
+    "username for",
+    "press enter",
+    "press any key",
+];
+
+/// Rate how likely `line`, the last unterminated line of output, is a prompt.
+/// Returns `None` for blank lines.
+pub fn classify_prompt(line: &str) -> Option<PromptConfidence> {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    let lower = trimmed.to_ascii_lowercase();
+    if KNOWN_PROMPTS.iter().any(|known| lower.contains(known)) {
+        Some(PromptConfidence::High)
+    } else if lower.ends_with('?') || lower.ends_with(':') {
+        Some(PromptConfidence::Medium)
+    } else {
+        Some(PromptConfidence::Low)
+    }
+}
+
+/// Tracks the tail of a command's output and reports each prompt once.
+#[derive(Debug)]
+pub struct PromptWatcher {
+    settings: PromptDetectionSettings,
+    tail: String,
+    last_output: Instant,
+    reported: bool,
+}
+
+impl PromptWatcher {
+    pub fn new(settings: PromptDetectionSettings, now: Instant) -> Self {
+        Self {
+            settings,
+            tail: String::new(),
+            last_output: now,
+            reported: false,
+        }
+    }
+
+    pub fn observe(&mut self, chunk: &[u8], now: Instant) {
+        if chunk.is_empty() {
+            return;
+        }
+        let text = String::from_utf8_lossy(chunk);
+        match text.rfind(['\n', '\r']) {
+            Some(idx) => self.tail = text[idx + 1..].to_string(),
+            None => self.tail.push_str(&text),
+        }
+        let excess = self.tail.chars().count().saturating_sub(MAX_TAIL_CHARS);
+        if excess > 0 {
+            self.tail = self.tail.chars().skip(excess).collect();
+        }
+        self.last_output = now;
+        self.reported = false;
+    }
+
+    /// The prompt the command is waiting on, if it has been quiet long enough
+    /// and was not already reported. New output re-arms the watcher.
+    pub fn poll(&mut self, now: Instant) -> Option<DetectedPrompt> {
+        if self.reported || now.saturating_duration_since(self.last_output) < self.settings.idle {
+            return None;
+        }
+        let confidence = classify_prompt(&self.tail)?;
+        if confidence < self.settings.min_confidence {
+            return None;
+        }
+        self.reported = true;
+        Some(DetectedPrompt {
+            text: self.tail.trim().to_string(),
+            confidence,
+        })
+    }
+}
+
+/// Attached to a [`crate::exec::StdoutStream`] so the shell exec path can
+/// watch a command for prompts.
+#[derive(Clone)]
+pub struct PromptHook {
+    pub(crate) broker: Arc<InputPromptBroker>,
+    pub(crate) command: Vec<String>,
+}
+
+impl PromptHook {
+    pub(crate) fn new(broker: Arc<InputPromptBroker>, command: Vec<String>) -> Self {
+        Self { broker, command }
+    }
+}
+
+/// Session-wide routing between detected prompts and the user's answers.
+#[derive(Debug, Default)]
+pub(crate) struct InputPromptBroker {
+    /// `None` when detection is disabled or the frontend cannot answer.
+    settings: Option<PromptDetectionSettings>,
+    /// Stop the command instead of asking.
+    fail_fast: bool,
+    pending: Mutex<HashMap<String, oneshot::Sender<ExecInputResponse>>>,
+}
+
+impl InputPromptBroker {
+    /// The TUI can answer prompts; `codex exec` has nobody to ask and fails
+    /// fast. Other frontends do not know the request yet, so detection stays
+    /// off for them.
+    pub(crate) fn new(settings: Option<PromptDetectionSettings>, source: &SessionSource) -> Self {
+        let (settings, fail_fast) = match source {
+            SessionSource::Cli => (settings, false),
+            SessionSource::Exec => (settings, true),
+            _ => (None, false),
+        };
+        Self {
+            settings,
+            fail_fast,
+            pending: Mutex::default(),
+        }
+    }
+
+    pub(crate) fn watcher(&self) -> Option<PromptWatcher> {
+        let settings = self.settings.clone()?;
+        Some(PromptWatcher::new(settings, Instant::now()))
+    }
+
+    /// Tell the client about `prompt` and wait for its answer. Headless
+    /// sessions get [`ExecInputResponse::Terminate`] straight away.
+    pub(crate) async fn request(
+        &self,
+        tx_event: &Sender<Event>,
+        sub_id: &str,
+        call_id: &str,
+        command: &[String],
+        prompt: &DetectedPrompt,
+        accepts_input: bool,
+    ) -> ExecInputResponse {
+        let rx = if self.fail_fast {
+            None
+        } else {
+            let (tx, rx) = oneshot::channel();
+            if let Ok(mut pending) = self.pending.lock() {
+                pending.insert(call_id.to_string(), tx);
+            }
+            Some(rx)
+        };
+        let event = Event {
+            id: sub_id.to_string(),
+            msg: EventMsg::ExecInputRequest(ExecInputRequestEvent {
+                call_id: call_id.to_string(),
+                command: command.to_vec(),
+                prompt: prompt.text.clone(),
+                accepts_input,
+                terminated: rx.is_none(),
+            }),
+        };
+        let _ = tx_event.send(event).await;
+        match rx {
+            Some(rx) => rx.await.unwrap_or(ExecInputResponse::Terminate),
+            None => ExecInputResponse::Terminate,
+        }
+    }
+
+    /// Deliver the user's answer. Returns `false` when nothing is waiting on
+    /// `call_id` (the command already finished).
+    pub(crate) fn respond(&self, call_id: &str, response: ExecInputResponse) -> bool {
+        let tx = self
+            .pending
+            .lock()
+            .ok()
+            .and_then(|mut pending| pending.remove(call_id));
+        match tx {
+            Some(tx) => tx.send(response).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Forget a request whose command finished before anyone answered.
+    pub(crate) fn cancel(&self, call_id: &str) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(call_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn classify_prompt_ranks_known_prompts_above_questions() {
+        assert_eq!(
+            classify_prompt("Do you want to continue? [Y/n] "),
+            Some(PromptConfidence::High)
+        );
+        assert_eq!(
+            classify_prompt("[sudo] password for dev: "),
+            Some(PromptConfidence::High)
+        );
+        assert_eq!(
+            classify_prompt("Username for 'https://github.com': "),
+            Some(PromptConfidence::High)
+        );
+        assert_eq!(
+            classify_prompt("Project name? "),
+            Some(PromptConfidence::Medium)
+        );
+        assert_eq!(
+            classify_prompt("Compiling crate 3/10"),
+            Some(PromptConfidence::Low)
+        );
+        assert_eq!(classify_prompt("  "), None);
+    }
+
+    #[test]
+    fn watcher_reports_quiet_prompts_once() {
+        let start = Instant::now();
+        let mut watcher = PromptWatcher::new(PromptDetectionSettings::default(), start);
+        watcher.observe(b"Reading package lists...\nContinue? [Y/n] ", start);
+
+        assert_eq!(watcher.poll(start + Duration::from_secs(1)), None);
+        let later = start + DEFAULT_PROMPT_IDLE;
+        assert_eq!(
+            watcher.poll(later),
+            Some(DetectedPrompt {
+                text: "Continue? [Y/n]".to_string(),
+                confidence: PromptConfidence::High,
+            })
+        );
+        assert_eq!(watcher.poll(later + Duration::from_secs(10)), None);
+
+        // Finished lines and low-confidence stalls are not prompts.
+        watcher.observe(b"y\nBuilding 40%", later);
+        assert_eq!(watcher.poll(later + DEFAULT_PROMPT_IDLE), None);
+        watcher.observe(b"\n", later);
+        assert_eq!(watcher.poll(later + DEFAULT_PROMPT_IDLE), None);
+    }
+
+    #[tokio::test]
+    async fn broker_routes_answers_or_fails_fast() {
+        let (tx_event, rx_event) = async_channel::unbounded();
+        let prompt = DetectedPrompt {
+            text: "Password:".to_string(),
+            confidence: PromptConfidence::High,
+        };
+        let command = vec!["sudo".to_string(), "true".to_string()];
+
+        let headless = InputPromptBroker::new(
+            Some(PromptDetectionSettings::default()),
+            &SessionSource::Exec,
+        );
+        assert_eq!(
+            headless
+                .request(&tx_event, "sub", "call-1", &command, &prompt, false)
+                .await,
+            ExecInputResponse::Terminate
+        );
+        let Event { msg, .. } = rx_event.recv().await.unwrap();
+        assert!(matches!(
+            msg,
+            EventMsg::ExecInputRequest(ExecInputRequestEvent {
+                terminated: true,
+                ..
+            })
+        ));
+
+        let interactive = std::sync::Arc::new(InputPromptBroker::new(
+            Some(PromptDetectionSettings::default()),
+            &SessionSource::Cli,
+        ));
+        let waiting = tokio::spawn({
+            let interactive = std::sync::Arc::clone(&interactive);
+            let tx_event = tx_event.clone();
+            let command = command.clone();
+            async move {
+                interactive
+                    .request(&tx_event, "sub", "call-2", &command, &prompt, true)
+                    .await
+            }
+        });
+        let Event { msg, .. } = rx_event.recv().await.unwrap();
+        assert_eq!(
+            msg_request(msg),
+            Some(ExecInputRequestEvent {
+                call_id: "call-2".to_string(),
+                command,
+                prompt: "Password:".to_string(),
+                accepts_input: true,
+                terminated: false,
+            })
+        );
+        let answer = ExecInputResponse::Input {
+            text: "hunter2".to_string(),
+        };
+        assert!(interactive.respond("call-2", answer.clone()));
+        assert_eq!(waiting.await.unwrap(), answer);
+        assert!(!interactive.respond("call-2", ExecInputResponse::Terminate));
+
+        assert!(
+            InputPromptBroker::new(
+                Some(PromptDetectionSettings::default()),
+                &SessionSource::Mcp
+            )
+            .watcher()
+            .is_none()
+        );
+    }
+
+    fn msg_request(msg: EventMsg) -> Option<ExecInputRequestEvent> {
+        match msg {
+            EventMsg::ExecInputRequest(ev) => Some(ev),
+            _ => None,
+        }
+    }
+}
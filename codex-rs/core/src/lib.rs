@@ -16,6 +16,7 @@ mod codex_conversation;
 mod compact_remote;
 pub use codex_conversation::CodexConversation;
 mod codex_delegate;
+pub mod command_allowlist;
 mod command_safety;
 pub mod config;
 pub mod config_loader;
@@ -29,30 +30,45 @@ mod exec_policy;
 pub mod features;
 mod flags;
 pub mod git_info;
+pub mod git_worktree;
+pub mod input_prompt;
 pub mod landlock;
 pub mod mcp;
 mod mcp_connection_manager;
+mod mcp_tool_cache;
 mod mcp_tool_call;
-mod message_history;
+pub mod message_history;
 mod model_provider_info;
+mod model_replay;
+pub mod network_policy;
+mod notification_hooks;
 pub mod parse_command;
+pub mod patch_selection;
+pub mod persistence_policy;
 pub mod powershell;
+pub mod rate_limit_history;
 mod response_processing;
+pub mod resume_briefing;
 pub mod sandboxing;
 pub mod token_data;
+mod token_usage;
 mod truncate;
+mod turn_budget;
 mod unified_exec;
 mod user_instructions;
 pub use model_provider_info::DEFAULT_LMSTUDIO_PORT;
 pub use model_provider_info::DEFAULT_OLLAMA_PORT;
 pub use model_provider_info::LMSTUDIO_OSS_PROVIDER_ID;
 pub use model_provider_info::ModelProviderInfo;
+pub use model_provider_info::OLLAMA_NUM_CTX_ENV_VAR;
 pub use model_provider_info::OLLAMA_OSS_PROVIDER_ID;
+pub use model_provider_info::REPLAY_PROVIDER_ID;
 pub use model_provider_info::WireApi;
 pub use model_provider_info::built_in_model_providers;
 pub use model_provider_info::create_oss_provider_with_base_url;
 mod conversation_manager;
 mod event_mapping;
+pub mod review_anchor;
 pub mod review_format;
 pub use codex_protocol::protocol::InitialHistory;
 pub use conversation_manager::ConversationManager;
@@ -66,10 +82,15 @@ mod openai_model_info;
 pub mod project_doc;
 mod rollout;
 pub(crate) mod safety;
+mod scoped_instructions;
 pub mod seatbelt;
+pub mod session_recall;
 pub mod shell;
+pub mod slash_commands;
 pub mod spawn;
+mod stream_resume;
 pub mod terminal;
+pub mod tool_inventory;
 mod tools;
 pub mod turn_diff_tracker;
 pub use rollout::ARCHIVED_SESSIONS_SUBDIR;
@@ -77,6 +98,8 @@ pub use rollout::INTERACTIVE_SESSION_SOURCES;
 pub use rollout::RolloutRecorder;
 pub use rollout::SESSIONS_SUBDIR;
 pub use rollout::SessionMeta;
+pub use rollout::archive::ArchiveError;
+pub use rollout::bundle;
 pub use rollout::find_conversation_path_by_id_str;
 pub use rollout::list::ConversationItem;
 pub use rollout::list::ConversationsPage;
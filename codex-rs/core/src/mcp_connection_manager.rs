@@ -51,8 +51,10 @@ use tokio_util::sync::CancellationToken;
 use tracing::warn;
 
 use crate::codex::INITIAL_SUBMIT_ID;
+use crate::config::types::CacheToolResults;
 use crate::config::types::McpServerConfig;
 use crate::config::types::McpServerTransportConfig;
+use crate::mcp_tool_cache::may_have_side_effects;
 
 /// Delimiter used to separate the server name from the tool name in a fully
 /// qualified tool name.
@@ -156,6 +158,8 @@ impl AsyncManagedClient {
 #[derive(Default)]
 pub(crate) struct McpConnectionManager {
     clients: HashMap<String, AsyncManagedClient>,
+    /// `cache_tool_results` of each server that set it.
+    cache_tool_results: HashMap<String, CacheToolResults>,
 }
 
 impl McpConnectionManager {
@@ -171,8 +175,12 @@ impl McpConnectionManager {
             return;
         }
         let mut clients = HashMap::new();
+        let mut cache_tool_results = HashMap::new();
         let mut join_set = JoinSet::new();
         for (server_name, cfg) in mcp_servers.into_iter().filter(|(_, cfg)| cfg.enabled) {
+            if let Some(cache) = &cfg.cache_tool_results {
+                cache_tool_results.insert(server_name.clone(), cache.clone());
+            }
             let cancel_token = cancel_token.child_token();
             let _ = emit_update(
                 &tx_event,
@@ -217,6 +225,7 @@ impl McpConnectionManager {
             });
         }
         self.clients = clients;
+        self.cache_tool_results = cache_tool_results;
         tokio::spawn(async move {
             let outcomes = join_set.join_all().await;
             let mut summary = McpStartupCompleteEvent::default();
@@ -241,6 +250,12 @@ impl McpConnectionManager {
         });
     }
 
+    /// Drops every client, which kills the server processes they started.
+    pub fn shutdown(&mut self) {
+        self.clients.clear();
+        self.cache_tool_results.clear();
+    }
+
     async fn client_by_name(&self, name: &str) -> Result<ManagedClient> {
         self.clients
             .get(name)
@@ -422,6 +437,26 @@ impl McpConnectionManager {
             .with_context(|| format!("tool call failed for `{server}/{tool}`"))
     }
 
+    /// Whether results of `tool` may be served from the session's tool result
+    /// cache: its server opted in with `cache_tool_results` and the tool is
+    /// annotated as free of side effects.
+    pub async fn caches_tool_results(&self, server: &str, tool: &str) -> bool {
+        if !self
+            .cache_tool_results
+            .get(server)
+            .is_some_and(|cache| cache.includes(tool))
+        {
+            return false;
+        }
+        match self.client_by_name(server).await {
+            Ok(client) => client
+                .tools
+                .iter()
+                .any(|info| info.tool_name == tool && !may_have_side_effects(&info.tool)),
+            Err(_) => false,
+        }
+    }
+
     /// List resources from the specified server.
     pub async fn list_resources(
         &self,
@@ -976,6 +1011,7 @@ mod tests {
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                cache_tool_results: None,
             },
             auth_status: McpAuthStatus::Unsupported,
         };
@@ -1020,6 +1056,7 @@ mod tests {
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                cache_tool_results: None,
             },
             auth_status: McpAuthStatus::Unsupported,
         };
@@ -0,0 +1,307 @@
+//! Session-scoped memoization of MCP tool results.
+//!
+//! Servers opt in with `cache_tool_results`; the limits come from
+//! `[mcp_tool_cache]`. Entries are keyed by server, tool and the call's
+//! arguments with object keys sorted, so `{"a":1,"b":2}` and `{"b":2,"a":1}`
+//! share an entry. Only successful results are stored.
+
+use std::time::Duration;
+use std::time::Instant;
+
+use indexmap::IndexMap;
+use mcp_types::CallToolResult;
+use mcp_types::Tool;
+use serde_json::Value;
+
+use crate::config::types::McpToolCache;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    server: String,
+    tool: String,
+    arguments: String,
+}
+
+#[derive(Debug)]
+struct CachedResult {
+    result: CallToolResult,
+    stored_at: Instant,
+}
+
+#[derive(Debug)]
+pub(crate) struct McpToolResultCache {
+    max_entries: usize,
+    ttl: Option<Duration>,
+    /// Least recently used first.
+    entries: IndexMap<CacheKey, CachedResult>,
+}
+
+impl Default for McpToolResultCache {
+    fn default() -> Self {
+        Self::new(McpToolCache::default())
+    }
+}
+
+impl McpToolResultCache {
+    pub(crate) fn new(settings: McpToolCache) -> Self {
+        Self {
+            max_entries: settings.max_entries,
+            ttl: settings.ttl_sec.map(Duration::from_secs),
+            entries: IndexMap::new(),
+        }
+    }
+
+    /// The cached result for this call, if there is one that has not expired.
+    pub(crate) fn get(
+        &mut self,
+        server: &str,
+        tool: &str,
+        arguments: Option<&Value>,
+        now: Instant,
+    ) -> Option<CallToolResult> {
+        let key = cache_key(server, tool, arguments);
+        let index = self.entries.get_index_of(&key)?;
+        let expired = self
+            .ttl
+            .is_some_and(|ttl| now.saturating_duration_since(self.entries[index].stored_at) >= ttl);
+        if expired {
+            self.entries.shift_remove_index(index);
+            return None;
+        }
+        let last = self.entries.len() - 1;
+        self.entries.move_index(index, last);
+        Some(self.entries[last].result.clone())
+    }
+
+    /// Stores a successful result, evicting the least recently used entries
+    /// beyond `max_entries`. Error results are never cached.
+    pub(crate) fn insert(
+        &mut self,
+        server: &str,
+        tool: &str,
+        arguments: Option<&Value>,
+        result: &CallToolResult,
+        now: Instant,
+    ) {
+        if self.max_entries == 0 || result.is_error == Some(true) {
+            return;
+        }
+        let key = cache_key(server, tool, arguments);
+        self.entries.shift_remove(&key);
+        self.entries.insert(
+            key,
+            CachedResult {
+                result: result.clone(),
+                stored_at: now,
+            },
+        );
+        while self.entries.len() > self.max_entries {
+            self.entries.shift_remove_index(0);
+        }
+    }
+}
+
+/// Hook for MCP side-effect metadata: only tools annotated as read-only, or
+/// as both non-destructive and idempotent, are cached, even when their server
+/// opted in. Missing hints take the MCP defaults (not read-only, destructive,
+/// not idempotent), so an unannotated tool may have side effects.
+pub(crate) fn may_have_side_effects(tool: &Tool) -> bool {
+    let Some(annotations) = tool.annotations.as_ref() else {
+        return true;
+    };
+    annotations.read_only_hint != Some(true)
+        && (annotations.destructive_hint != Some(false)
+            || annotations.idempotent_hint != Some(true))
+}
+
+fn cache_key(server: &str, tool: &str, arguments: Option<&Value>) -> CacheKey {
+    CacheKey {
+        server: server.to_string(),
+        tool: tool.to_string(),
+        arguments: arguments
+            .map(|arguments| canonicalize(arguments).to_string())
+            .unwrap_or_default(),
+    }
+}
+
+/// Copy of `value` with the keys of every object in sorted order.
+fn canonicalize(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.clone(), canonicalize(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonicalize).collect()),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_types::ContentBlock;
+    use mcp_types::TextContent;
+    use mcp_types::ToolAnnotations;
+    use mcp_types::ToolInputSchema;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn text_result(text: &str) -> CallToolResult {
+        CallToolResult {
+            content: vec![ContentBlock::TextContent(TextContent {
+                annotations: None,
+                text: text.to_string(),
+                r#type: "text".to_string(),
+            })],
+            is_error: None,
+            structured_content: None,
+        }
+    }
+
+    fn cache(max_entries: usize, ttl_sec: Option<u64>) -> McpToolResultCache {
+        McpToolResultCache::new(McpToolCache {
+            max_entries,
+            ttl_sec,
+        })
+    }
+
+    #[test]
+    fn identical_arguments_hit() {
+        let now = Instant::now();
+        let mut cache = cache(8, None);
+        let args = json!({"query": "ratatui"});
+        cache.insert(
+            "docs",
+            "lookup",
+            Some(&args),
+            &text_result("styles.md"),
+            now,
+        );
+
+        assert_eq!(
+            cache.get("docs", "lookup", Some(&args), now),
+            Some(text_result("styles.md"))
+        );
+        assert_eq!(
+            cache.get("docs", "lookup", Some(&json!({"query": "serde"})), now),
+            None
+        );
+        assert_eq!(cache.get("other", "lookup", Some(&args), now), None);
+    }
+
+    #[test]
+    fn argument_key_order_is_normalized() {
+        let now = Instant::now();
+        let mut cache = cache(8, None);
+        let written: Value =
+            serde_json::from_str(r#"{"b": 2, "a": {"y": [1, {"n": 1, "m": 2}], "x": null}}"#)
+                .expect("valid json");
+        let reordered: Value =
+            serde_json::from_str(r#"{"a": {"x": null, "y": [1, {"m": 2, "n": 1}]}, "b": 2}"#)
+                .expect("valid json");
+        cache.insert("docs", "schema", Some(&written), &text_result("ok"), now);
+
+        assert_eq!(
+            cache.get("docs", "schema", Some(&reordered), now),
+            Some(text_result("ok"))
+        );
+    }
+
+    #[test]
+    fn entries_expire_after_ttl() {
+        let start = Instant::now();
+        let mut cache = cache(8, Some(60));
+        cache.insert("docs", "lookup", None, &text_result("v1"), start);
+
+        assert_eq!(
+            cache.get("docs", "lookup", None, start + Duration::from_secs(59)),
+            Some(text_result("v1"))
+        );
+        assert_eq!(
+            cache.get("docs", "lookup", None, start + Duration::from_secs(60)),
+            None
+        );
+    }
+
+    #[test]
+    fn least_recently_used_entry_is_evicted() {
+        let now = Instant::now();
+        let mut cache = cache(2, None);
+        let first = json!({"page": 1});
+        let second = json!({"page": 2});
+        let third = json!({"page": 3});
+        cache.insert("docs", "lookup", Some(&first), &text_result("1"), now);
+        cache.insert("docs", "lookup", Some(&second), &text_result("2"), now);
+        // Reading the first entry makes the second the least recently used.
+        assert!(cache.get("docs", "lookup", Some(&first), now).is_some());
+        cache.insert("docs", "lookup", Some(&third), &text_result("3"), now);
+
+        assert_eq!(cache.get("docs", "lookup", Some(&second), now), None);
+        assert_eq!(
+            cache.get("docs", "lookup", Some(&first), now),
+            Some(text_result("1"))
+        );
+        assert_eq!(
+            cache.get("docs", "lookup", Some(&third), now),
+            Some(text_result("3"))
+        );
+    }
+
+    #[test]
+    fn error_results_are_not_cached() {
+        let now = Instant::now();
+        let mut cache = cache(8, None);
+        let failed = CallToolResult {
+            is_error: Some(true),
+            ..text_result("boom")
+        };
+        cache.insert("docs", "lookup", None, &failed, now);
+
+        assert_eq!(cache.get("docs", "lookup", None, now), None);
+    }
+
+    #[test]
+    fn only_read_only_or_idempotent_safe_tools_are_side_effect_free() {
+        let tool = |annotations: Option<ToolAnnotations>| Tool {
+            annotations,
+            description: None,
+            input_schema: ToolInputSchema {
+                properties: None,
+                required: None,
+                r#type: "object".to_string(),
+            },
+            name: "lookup".to_string(),
+            output_schema: None,
+            title: None,
+        };
+        let annotations = |read_only, destructive, idempotent| ToolAnnotations {
+            destructive_hint: destructive,
+            idempotent_hint: idempotent,
+            open_world_hint: None,
+            read_only_hint: read_only,
+            title: None,
+        };
+
+        assert_eq!(
+            [
+                tool(None),
+                tool(Some(annotations(None, None, None))),
+                tool(Some(annotations(Some(true), None, None))),
+                tool(Some(annotations(None, Some(false), None))),
+                tool(Some(annotations(None, Some(false), Some(true)))),
+                tool(Some(annotations(None, Some(true), Some(true)))),
+                tool(Some(annotations(Some(true), Some(true), None))),
+            ]
+            .iter()
+            .map(may_have_side_effects)
+            .collect::<Vec<_>>(),
+            vec![true, true, false, true, false, true, false]
+        );
+    }
+}
@@ -55,19 +55,54 @@ pub(crate) async fn handle_mcp_tool_call(
     notify_mcp_tool_call_event(sess, turn_context, tool_call_begin_event).await;
 
     let start = Instant::now();
-    // Perform the tool call.
-    let result = sess
-        .call_tool(&server, &tool_name, arguments_value.clone())
+    let cacheable = sess
+        .services
+        .mcp_connection_manager
+        .read()
         .await
-        .map_err(|e| format!("tool call error: {e:?}"));
-    if let Err(e) = &result {
-        tracing::warn!("MCP tool call error: {e:?}");
-    }
+        .caches_tool_results(&server, &tool_name)
+        .await;
+    let cached_result = if cacheable {
+        sess.services.mcp_tool_cache.lock().await.get(
+            &server,
+            &tool_name,
+            arguments_value.as_ref(),
+            start,
+        )
+    } else {
+        None
+    };
+    let cached = cached_result.is_some();
+    let result = match cached_result {
+        Some(result) => Ok(result),
+        None => {
+            // Perform the tool call.
+            let result = sess
+                .call_tool(&server, &tool_name, arguments_value.clone())
+                .await
+                .map_err(|e| format!("tool call error: {e:?}"));
+            match &result {
+                Ok(result) if cacheable => {
+                    sess.services.mcp_tool_cache.lock().await.insert(
+                        &server,
+                        &tool_name,
+                        arguments_value.as_ref(),
+                        result,
+                        Instant::now(),
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("MCP tool call error: {e:?}"),
+            }
+            result
+        }
+    };
     let tool_call_end_event = EventMsg::McpToolCallEnd(McpToolCallEndEvent {
         call_id: call_id.clone(),
         invocation,
         duration: start.elapsed(),
         result: result.clone(),
+        cached,
     });
 
     notify_mcp_tool_call_event(sess, turn_context, tool_call_end_event.clone()).await;
@@ -5,9 +5,19 @@
 //! JSON-Lines tooling. Each record has the following schema:
 //!
 //! ````text
-//! {"conversation_id":"<uuid>","ts":<unix_seconds>,"text":"<message>"}
+//! {"v":1,"session_id":"<uuid>","ts":<unix_seconds>,"text":"<message>"}
 //! ````
 //!
+//! `v` is the format version ([`HISTORY_FORMAT_VERSION`]); lines without it
+//! were written before versioning and share the same fields. Readers skip
+//! entries from a newer version.
+//!
+//! An entry identical to the previous one is not appended again, prompts that
+//! look like they contain secrets are never written, and with
+//! `history.max_entries` set the oldest entries are trimmed on append. A trim
+//! writes the kept entries to a temporary file that replaces the history file,
+//! which gives it a new identifier (see [`history_metadata`]).
+//!
 //! To minimise the chance of interleaved writes when multiple processes are
 //! appending concurrently, callers should *prepare the full line* (record +
 //! trailing `\n`) and write it with a **single `write(2)` system call** while
@@ -16,10 +26,17 @@
 
 use std::fs::File;
 use std::fs::OpenOptions;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
 use std::io::Result;
+use std::io::Seek;
+use std::io::SeekFrom;
 use std::io::Write;
+use std::path::Path;
 use std::path::PathBuf;
 
+use regex_lite::Regex;
 use serde::Deserialize;
 use serde::Serialize;
 
@@ -29,6 +46,7 @@ use tokio::io::AsyncReadExt;
 
 use crate::config::Config;
 use crate::config::types::HistoryPersistence;
+use crate::persistence_policy::PersistenceKind;
 
 use codex_protocol::ConversationId;
 #[cfg(unix)]
@@ -39,20 +57,46 @@ use std::os::unix::fs::PermissionsExt;
 /// Filename that stores the message history inside `~/.codex`.
 const HISTORY_FILENAME: &str = "history.jsonl";
 
+/// Format version written with every new entry.
+pub const HISTORY_FORMAT_VERSION: u32 = 1;
+
+/// Secrets that are never persisted, in addition to
+/// `history.sensitive_patterns`.
+const DEFAULT_SENSITIVE_PATTERNS: &[&str] = &[
+    // OpenAI and similar `sk-` API keys.
+    r"sk-[A-Za-z0-9_\-]{20,}",
+    // AWS access key ids.
+    r"AKIA[0-9A-Z]{16}",
+    // GitHub tokens.
+    r"gh[pousr]_[A-Za-z0-9]{36,}",
+    // Slack tokens.
+    r"xox[abposr]-[A-Za-z0-9\-]{10,}",
+    r"-----BEGIN [A-Z ]*PRIVATE KEY-----",
+];
+
 const MAX_RETRIES: usize = 10;
 const RETRY_SLEEP: Duration = Duration::from_millis(100);
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Bytes read at a time from the end of the file when looking for the last
+/// entry.
+const TAIL_CHUNK: u64 = 4096;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct HistoryEntry {
+    /// Format version; `0` for entries written before versioning.
+    #[serde(rename = "v", default)]
+    pub version: u32,
     pub session_id: String,
     pub ts: u64,
     pub text: String,
 }
 
 fn history_filepath(config: &Config) -> PathBuf {
-    let mut path = config.codex_home.clone();
-    path.push(HISTORY_FILENAME);
-    path
+    history_path(&config.codex_home)
+}
+
+fn history_path(codex_home: &Path) -> PathBuf {
+    codex_home.join(HISTORY_FILENAME)
 }
 
 /// Append a `text` entry associated with `conversation_id` to the history file. Uses
@@ -72,8 +116,17 @@ pub(crate) async fn append_entry(
             return Ok(());
         }
     }
+    if config
+        .persistence
+        .check(PersistenceKind::MessageHistory)
+        .is_err()
+    {
+        return Ok(());
+    }
 
-    // TODO: check `text` for sensitive patterns
+    if looks_sensitive(text, &config.history.sensitive_patterns) {
+        return Ok(());
+    }
 
     // Resolve `~/.codex/history.jsonl` and ensure the parent directory exists.
     let path = history_filepath(config);
@@ -89,6 +142,7 @@ pub(crate) async fn append_entry(
 
     // Construct the JSON line first so we can write it in a single syscall.
     let entry = HistoryEntry {
+        version: HISTORY_FORMAT_VERSION,
         session_id: conversation_id.to_string(),
         ts,
         text: text.to_string(),
@@ -110,16 +164,26 @@ pub(crate) async fn append_entry(
     // Ensure permissions.
     ensure_owner_only_permissions(&history_file).await?;
 
+    let max_entries = config.history.max_entries;
     // Perform a blocking write under an advisory write lock using std::fs.
     tokio::task::spawn_blocking(move || -> Result<()> {
         // Retry a few times to avoid indefinite blocking when contended.
         for _ in 0..MAX_RETRIES {
             match history_file.try_lock() {
                 Ok(()) => {
-                    // While holding the exclusive lock, write the full line.
-                    history_file.write_all(line.as_bytes())?;
-                    history_file.flush()?;
-                    return Ok(());
+                    // Another writer may have trimmed the history, replacing
+                    // the file, while we waited for the lock.
+                    if !is_current(&history_file, &path) {
+                        history_file = options.open(&path)?;
+                        continue;
+                    }
+                    return append_locked(
+                        &mut history_file,
+                        &path,
+                        &line,
+                        &entry.text,
+                        max_entries,
+                    );
                 }
                 Err(std::fs::TryLockError::WouldBlock) => {
                     std::thread::sleep(RETRY_SLEEP);
@@ -138,6 +202,139 @@ pub(crate) async fn append_entry(
     Ok(())
 }
 
+/// Append `line` to the locked history `file` at `path` unless the last entry
+/// already holds `text`. When that would leave more than `max_entries`
+/// entries, the file is replaced with the newest ones instead.
+fn append_locked(
+    file: &mut File,
+    path: &Path,
+    line: &str,
+    text: &str,
+    max_entries: Option<usize>,
+) -> Result<()> {
+    if last_entry_text(file)?.as_deref() == Some(text) {
+        return Ok(());
+    }
+
+    if let Some(max_entries) = max_entries {
+        let count = count_lines(file)? + 1;
+        if count > max_entries {
+            return replace_with_newest(file, path, line, count - max_entries);
+        }
+    }
+
+    // Write the full line in one call while holding the exclusive lock.
+    file.write_all(line.as_bytes())?;
+    file.flush()
+}
+
+/// Text of the last entry in `file`, read from the end so that appending does
+/// not depend on the size of the history.
+fn last_entry_text(file: &mut File) -> Result<Option<String>> {
+    let mut start = file.seek(SeekFrom::End(0))?;
+    let mut tail: Vec<u8> = Vec::new();
+    loop {
+        let chunk = TAIL_CHUNK.min(start);
+        start -= chunk;
+        let mut buf = vec![0; chunk as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut buf)?;
+        buf.extend_from_slice(&tail);
+        tail = buf;
+        let body = tail.strip_suffix(b"\n").unwrap_or(&tail);
+        if start == 0 || body.contains(&b'\n') {
+            break;
+        }
+    }
+    let body = tail.strip_suffix(b"\n").unwrap_or(&tail);
+    let last = body
+        .rsplit(|&byte| byte == b'\n')
+        .next()
+        .unwrap_or_default();
+    Ok(serde_json::from_slice::<HistoryEntry>(last)
+        .ok()
+        .map(|entry| entry.text))
+}
+
+fn count_lines(file: &mut File) -> Result<usize> {
+    file.seek(SeekFrom::Start(0))?;
+    let mut reader = BufReader::new(file);
+    let mut count = 0;
+    loop {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            return Ok(count);
+        }
+        count += buf.iter().filter(|&&byte| byte == b'\n').count();
+        let consumed = buf.len();
+        reader.consume(consumed);
+    }
+}
+
+/// Replace the history at `path` with the entries of `file` after the first
+/// `skip`, followed by `line`. The entries are written to a temporary file
+/// that is renamed over `path`, so the history is never left half written.
+fn replace_with_newest(file: &mut File, path: &Path, line: &str, skip: usize) -> Result<()> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    // Created with owner-only permissions on Unix.
+    let mut replacement = tempfile::NamedTempFile::new_in(dir)?;
+    file.seek(SeekFrom::Start(0))?;
+    for kept in BufReader::new(file).lines().skip(skip) {
+        writeln!(replacement, "{}", kept?)?;
+    }
+    replacement.write_all(line.as_bytes())?;
+    replacement.as_file().sync_all()?;
+    replacement.persist(path).map_err(|err| err.error)?;
+    Ok(())
+}
+
+/// Whether `file` is still the file at `path`.
+#[cfg(unix)]
+fn is_current(file: &File, path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (file.metadata(), std::fs::metadata(path)) {
+        (Ok(open), Ok(current)) => open.dev() == current.dev() && open.ino() == current.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_current(_file: &File, _path: &Path) -> bool {
+    true
+}
+
+/// True when `text` matches one of the built-in secret patterns or one of
+/// `extra_patterns`. A pattern that fails to compile counts as a match so a
+/// typo never lets secrets through.
+fn looks_sensitive(text: &str, extra_patterns: &[String]) -> bool {
+    DEFAULT_SENSITIVE_PATTERNS
+        .iter()
+        .copied()
+        .chain(extra_patterns.iter().map(String::as_str))
+        .any(|pattern| match Regex::new(pattern) {
+            Ok(regex) => regex.is_match(text),
+            Err(err) => {
+                tracing::warn!("invalid history.sensitive_patterns entry {pattern:?}: {err}");
+                true
+            }
+        })
+}
+
+/// Every readable entry in `~/.codex/history.jsonl`, oldest first. Malformed
+/// lines and entries from a newer format version are skipped; a missing file
+/// yields no entries.
+pub async fn load_entries(codex_home: &Path) -> Vec<HistoryEntry> {
+    let Ok(contents) = fs::read_to_string(history_path(codex_home)).await else {
+        return Vec::new();
+    };
+    contents
+        .lines()
+        .filter_map(|line| serde_json::from_str::<HistoryEntry>(line).ok())
+        .filter(|entry| entry.version <= HISTORY_FORMAT_VERSION)
+        .collect()
+}
+
 /// Asynchronously fetch the history file's *identifier* (inode on Unix) and
 /// the current number of entries by counting newline characters.
 pub(crate) async fn history_metadata(config: &Config) -> (u64, usize) {
@@ -232,6 +429,7 @@ pub(crate) fn lookup(log_id: u64, offset: usize, config: &Config) -> Option<Hist
 
                     if idx == offset {
                         match serde_json::from_str::<HistoryEntry>(&line) {
+                            Ok(entry) if entry.version > HISTORY_FORMAT_VERSION => return None,
                             Ok(entry) => return Some(entry),
                             Err(e) => {
                                 tracing::warn!(error = %e, "failed to parse history entry");
@@ -284,3 +482,187 @@ async fn ensure_owner_only_permissions(_file: &File) -> Result<()> {
     // For now, on non-Unix, simply succeed.
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigOverrides;
+    use crate::config::ConfigToml;
+    use tempfile::TempDir;
+
+    fn config_for(codex_home: &TempDir, ephemeral: bool) -> Config {
+        Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides {
+                ephemeral: Some(ephemeral),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )
+        .expect("config")
+    }
+
+    #[tokio::test]
+    async fn append_entry_consults_persistence_policy() {
+        let conversation_id = ConversationId::new();
+
+        let persistent_home = TempDir::new().expect("tempdir");
+        let config = config_for(&persistent_home, false);
+        append_entry("hello", &conversation_id, &config)
+            .await
+            .expect("append");
+        assert!(history_filepath(&config).exists());
+
+        let ephemeral_home = TempDir::new().expect("tempdir");
+        let config = config_for(&ephemeral_home, true);
+        append_entry("hello", &conversation_id, &config)
+            .await
+            .expect("append");
+        assert!(!history_filepath(&config).exists());
+    }
+
+    async fn texts(codex_home: &TempDir) -> Vec<String> {
+        load_entries(codex_home.path())
+            .await
+            .into_iter()
+            .map(|entry| entry.text)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn multi_line_prompts_round_trip_with_the_format_version() {
+        let home = TempDir::new().expect("tempdir");
+        let config = config_for(&home, false);
+        let text = "first line\r\n  indented \"quoted\"\n\n\ttabbed\n";
+
+        append_entry(text, &ConversationId::new(), &config)
+            .await
+            .expect("append");
+
+        let contents = std::fs::read_to_string(history_filepath(&config)).expect("read");
+        assert!(contents.starts_with(&format!("{{\"v\":{HISTORY_FORMAT_VERSION},")));
+        assert_eq!(contents.lines().count(), 1);
+        let entries = load_entries(home.path()).await;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].version, HISTORY_FORMAT_VERSION);
+        assert_eq!(entries[0].text, text);
+    }
+
+    #[tokio::test]
+    async fn load_entries_reads_unversioned_lines_and_skips_newer_versions() {
+        let home = TempDir::new().expect("tempdir");
+        std::fs::write(
+            history_path(home.path()),
+            concat!(
+                "{\"session_id\":\"a\",\"ts\":1,\"text\":\"legacy\"}\n",
+                "not json\n",
+                "{\"v\":99,\"session_id\":\"b\",\"ts\":2,\"text\":\"future\"}\n",
+            ),
+        )
+        .expect("seed history");
+
+        assert_eq!(
+            load_entries(home.path()).await,
+            vec![HistoryEntry {
+                version: 0,
+                session_id: "a".to_string(),
+                ts: 1,
+                text: "legacy".to_string(),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn back_to_back_duplicates_are_stored_once() {
+        let home = TempDir::new().expect("tempdir");
+        let config = config_for(&home, false);
+        let conversation_id = ConversationId::new();
+
+        for text in [
+            "fix the build",
+            "fix the build",
+            "run tests",
+            "fix the build",
+        ] {
+            append_entry(text, &conversation_id, &config)
+                .await
+                .expect("append");
+        }
+
+        assert_eq!(
+            texts(&home).await,
+            vec!["fix the build", "run tests", "fix the build"]
+        );
+    }
+
+    #[tokio::test]
+    async fn duplicates_are_detected_past_the_first_tail_chunk() {
+        let home = TempDir::new().expect("tempdir");
+        let config = config_for(&home, false);
+        let conversation_id = ConversationId::new();
+        let long = "x".repeat(TAIL_CHUNK as usize * 2 + 10);
+
+        for text in ["short", long.as_str(), long.as_str()] {
+            append_entry(text, &conversation_id, &config)
+                .await
+                .expect("append");
+        }
+
+        assert_eq!(texts(&home).await, vec!["short".to_string(), long]);
+    }
+
+    #[tokio::test]
+    async fn max_entries_drops_the_oldest_entries() {
+        let home = TempDir::new().expect("tempdir");
+        let mut config = config_for(&home, false);
+        config.history.max_entries = Some(2);
+        let conversation_id = ConversationId::new();
+
+        for text in ["one", "two", "three"] {
+            append_entry(text, &conversation_id, &config)
+                .await
+                .expect("append");
+        }
+        assert_eq!(texts(&home).await, vec!["two", "three"]);
+
+        // The trimmed file replaced the original; appends keep going to it.
+        append_entry("four", &conversation_id, &config)
+            .await
+            .expect("append");
+        assert_eq!(texts(&home).await, vec!["three", "four"]);
+        let leftovers: Vec<_> = std::fs::read_dir(home.path())
+            .expect("read codex home")
+            .map(|entry| entry.expect("dir entry").file_name())
+            .collect();
+        assert_eq!(leftovers, vec![std::ffi::OsString::from(HISTORY_FILENAME)]);
+        #[cfg(unix)]
+        assert_eq!(
+            std::fs::metadata(history_filepath(&config))
+                .expect("stat history")
+                .permissions()
+                .mode()
+                & 0o777,
+            0o600
+        );
+    }
+
+    #[tokio::test]
+    async fn prompts_that_look_like_secrets_are_not_persisted() {
+        let home = TempDir::new().expect("tempdir");
+        let mut config = config_for(&home, false);
+        config.history.sensitive_patterns = vec![r"internal-token-\d+".to_string()];
+        let conversation_id = ConversationId::new();
+
+        for text in [
+            "use the key sk-abcdefghijklmnopqrstuvwx",
+            "login with internal-token-42",
+            "explain this repo",
+        ] {
+            append_entry(text, &conversation_id, &config)
+                .await
+                .expect("append");
+        }
+
+        assert_eq!(texts(&home).await, vec!["explain this repo"]);
+    }
+}
@@ -10,6 +10,8 @@ use crate::default_client::CodexHttpClient;
 use crate::default_client::CodexRequestBuilder;
 use crate::error::CodexErr;
 use codex_app_server_protocol::AuthMode;
+use reqwest::header::HeaderName;
+use reqwest::header::HeaderValue;
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::HashMap;
@@ -40,6 +42,10 @@ pub enum WireApi {
     /// Regular Chat Completions compatible with `/v1/chat/completions`.
     #[default]
     Chat,
+
+    /// No network at all: each request is answered from the recording at
+    /// `replay_path`, to reproduce a session offline.
+    Replay,
 }
 
 /// Serializable representation of a provider definition.
@@ -94,6 +100,12 @@ pub struct ModelProviderInfo {
     /// and API key (if needed) comes from the "env_key" environment variable.
     #[serde(default)]
     pub requires_openai_auth: bool,
+
+    /// Context window (`num_ctx`) requested from Ollama with each Chat
+    /// Completions request. When unset for the built-in `ollama` provider,
+    /// `CODEX_OLLAMA_NUM_CTX` is used if present; `--oss` fills it in from
+    /// the model's maximum context.
+    pub num_ctx: Option<u32>,
 }
 
 impl ModelProviderInfo {
@@ -174,7 +186,11 @@ impl ModelProviderInfo {
             .map_or_else(String::new, |params| {
                 let full_params = params
                     .iter()
-                    .map(|(k, v)| format!("{k}={v}"))
+                    .map(|(k, v)| {
+                        let k = encode_query_component(k);
+                        let v = encode_query_component(v);
+                        format!("{k}={v}")
+                    })
                     .collect::<Vec<_>>()
                     .join("&");
                 format!("?{full_params}")
@@ -202,6 +218,7 @@ impl ModelProviderInfo {
         match self.wire_api {
             WireApi::Responses => format!("{base_url}/responses{query_string}"),
             WireApi::Chat => format!("{base_url}/chat/completions{query_string}"),
+            WireApi::Replay => format!("{base_url}{query_string}"),
         }
     }
 
@@ -254,6 +271,52 @@ impl ModelProviderInfo {
         builder
     }
 
+    /// Check that every configured header name (static and env based) and
+    /// every static header value can be sent over HTTP. `provider_id` is the
+    /// key in `model_providers` and is used to point the user at the bad entry.
+    pub(crate) fn validate_http_headers(&self, provider_id: &str) -> std::io::Result<()> {
+        let invalid = |message: String| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("model_providers.{provider_id}: {message}"),
+            )
+        };
+
+        let static_headers = self.http_headers.iter().flatten();
+        let env_headers = self.env_http_headers.iter().flatten();
+        for name in static_headers.clone().chain(env_headers).map(|(k, _)| k) {
+            if HeaderName::from_bytes(name.as_bytes()).is_err() {
+                return Err(invalid(format!("invalid HTTP header name `{name}`")));
+            }
+        }
+        for (name, value) in static_headers {
+            if HeaderValue::from_str(value).is_err() {
+                return Err(invalid(format!("invalid value for HTTP header `{name}`")));
+            }
+        }
+        Ok(())
+    }
+
+    /// Return `(header, env_var)` pairs from `env_http_headers` whose
+    /// environment variable is unset or empty, sorted by header name. Those
+    /// headers are silently omitted from requests, so callers surface them as
+    /// a warning at session start for user-defined providers.
+    pub fn missing_env_http_headers(&self) -> Vec<(String, String)> {
+        let mut missing: Vec<(String, String)> = self
+            .env_http_headers
+            .iter()
+            .flatten()
+            .filter(|(_, env_var)| {
+                std::env::var(env_var)
+                    .map(|val| val.trim().is_empty())
+                    .unwrap_or(true)
+            })
+            .map(|(header, env_var)| (header.clone(), env_var.clone()))
+            .collect();
+        missing.sort();
+        missing
+    }
+
     /// If `env_key` is Some, returns the API key for this provider if present
     /// (and non-empty) in the environment. If `env_key` is required but
     /// cannot be found, returns an error.
@@ -300,6 +363,25 @@ impl ModelProviderInfo {
             .map(Duration::from_millis)
             .unwrap_or(Duration::from_millis(DEFAULT_STREAM_IDLE_TIMEOUT_MS))
     }
+
+    /// Fill in `num_ctx` from `CODEX_OLLAMA_NUM_CTX` when this is the built-in
+    /// Ollama provider and no value is configured. Other providers never
+    /// receive the Ollama-specific option.
+    pub(crate) fn apply_ollama_num_ctx_env(&mut self, provider_id: &str) {
+        if self.num_ctx.is_none() {
+            self.num_ctx =
+                ollama_num_ctx_default(provider_id, std::env::var(OLLAMA_NUM_CTX_ENV_VAR).ok());
+        }
+    }
+}
+
+fn ollama_num_ctx_default(provider_id: &str, env_value: Option<String>) -> Option<u32> {
+    if provider_id != OLLAMA_OSS_PROVIDER_ID {
+        return None;
+    }
+    env_value
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .filter(|num_ctx| *num_ctx > 0)
 }
 
 pub const DEFAULT_LMSTUDIO_PORT: u16 = 1234;
@@ -307,6 +389,11 @@ pub const DEFAULT_OLLAMA_PORT: u16 = 11434;
 
 pub const LMSTUDIO_OSS_PROVIDER_ID: &str = "lmstudio";
 pub const OLLAMA_OSS_PROVIDER_ID: &str = "ollama";
+pub const REPLAY_PROVIDER_ID: &str = "replay";
+
+/// Fallback for [`ModelProviderInfo::num_ctx`]; experimental like the other
+/// `CODEX_OSS_` variables.
+pub const OLLAMA_NUM_CTX_ENV_VAR: &str = "CODEX_OLLAMA_NUM_CTX";
 
 /// Built-in default provider list.
 pub fn built_in_model_providers() -> HashMap<String, ModelProviderInfo> {
@@ -314,7 +401,8 @@ pub fn built_in_model_providers() -> HashMap<String, ModelProviderInfo> {
 
     // We do not want to be in the business of adjucating which third-party
     // providers are bundled with Codex CLI, so we only include the OpenAI and
-    // open source ("oss") providers by default. Users are encouraged to add to
+    // open source ("oss") providers by default, plus the offline `replay`
+    // provider used for debugging. Users are encouraged to add to
     // `model_providers` in config.toml to add their own providers.
     [
         (
@@ -355,6 +443,7 @@ pub fn built_in_model_providers() -> HashMap<String, ModelProviderInfo> {
                 stream_max_retries: None,
                 stream_idle_timeout_ms: None,
                 requires_openai_auth: true,
+                num_ctx: None,
             },
         ),
         (
@@ -365,6 +454,25 @@ pub fn built_in_model_providers() -> HashMap<String, ModelProviderInfo> {
             LMSTUDIO_OSS_PROVIDER_ID,
             create_oss_provider(DEFAULT_LMSTUDIO_PORT, WireApi::Responses),
         ),
+        (
+            REPLAY_PROVIDER_ID,
+            P {
+                name: "Replay".into(),
+                base_url: None,
+                env_key: None,
+                env_key_instructions: None,
+                experimental_bearer_token: None,
+                wire_api: WireApi::Replay,
+                query_params: None,
+                http_headers: None,
+                env_http_headers: None,
+                request_max_retries: None,
+                stream_max_retries: None,
+                stream_idle_timeout_ms: None,
+                requires_openai_auth: false,
+                num_ctx: None,
+            },
+        ),
     ]
     .into_iter()
     .map(|(k, v)| (k.to_string(), v))
@@ -406,6 +514,7 @@ pub fn create_oss_provider_with_base_url(base_url: &str, wire_api: WireApi) -> M
         stream_max_retries: None,
         stream_idle_timeout_ms: None,
         requires_openai_auth: false,
+        num_ctx: None,
     }
 }
 
@@ -421,11 +530,53 @@ fn matches_azure_responses_base_url(base_url: &str) -> bool {
     AZURE_MARKERS.iter().any(|marker| base.contains(marker))
 }
 
+/// Percent-encode a query string key or value. `%XX` escapes that are already
+/// present are kept as is, so values copied from a URL are not encoded twice.
+fn encode_query_component(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(pos) = rest.find('%') {
+        encoded.push_str(&urlencoding::encode(&rest[..pos]));
+        match rest.get(pos..pos + 3) {
+            Some(escape) if escape[1..].chars().all(|c| c.is_ascii_hexdigit()) => {
+                encoded.push_str(escape);
+                rest = &rest[pos + 3..];
+            }
+            _ => {
+                encoded.push_str("%25");
+                rest = &rest[pos + 1..];
+            }
+        }
+    }
+    encoded.push_str(&urlencoding::encode(rest));
+    encoded
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn ollama_num_ctx_env_only_applies_to_ollama() {
+        assert_eq!(
+            ollama_num_ctx_default(OLLAMA_OSS_PROVIDER_ID, Some(" 32768 ".to_string())),
+            Some(32_768)
+        );
+        assert_eq!(
+            ollama_num_ctx_default(OLLAMA_OSS_PROVIDER_ID, Some("0".to_string())),
+            None
+        );
+        assert_eq!(
+            ollama_num_ctx_default(LMSTUDIO_OSS_PROVIDER_ID, Some("32768".to_string())),
+            None
+        );
+        assert_eq!(
+            ollama_num_ctx_default("openai-chat-completions", Some("32768".to_string())),
+            None
+        );
+    }
+
     #[test]
     fn test_deserialize_ollama_model_provider_toml() {
         let azure_provider_toml = r#"
@@ -446,6 +597,7 @@ base_url = "http://localhost:11434/v1"
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
             requires_openai_auth: false,
+            num_ctx: None,
         };
 
         let provider: ModelProviderInfo = toml::from_str(azure_provider_toml).unwrap();
@@ -476,6 +628,7 @@ query_params = { api-version = "2025-04-01-preview" }
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
             requires_openai_auth: false,
+            num_ctx: None,
         };
 
         let provider: ModelProviderInfo = toml::from_str(azure_provider_toml).unwrap();
@@ -509,6 +662,7 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
             requires_openai_auth: false,
+            num_ctx: None,
         };
 
         let provider: ModelProviderInfo = toml::from_str(azure_provider_toml).unwrap();
@@ -532,6 +686,7 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
                 stream_max_retries: None,
                 stream_idle_timeout_ms: None,
                 requires_openai_auth: false,
+                num_ctx: None,
             }
         }
 
@@ -565,6 +720,7 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
             requires_openai_auth: false,
+            num_ctx: None,
         };
         assert!(named_provider.is_azure_responses_endpoint());
 
@@ -581,4 +737,93 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             );
         }
     }
+
+    #[test]
+    fn validate_http_headers_names_provider_and_header() {
+        let provider: ModelProviderInfo = toml::from_str(
+            r#"
+name = "Example"
+http_headers = { "X Bad" = "value" }
+            "#,
+        )
+        .unwrap();
+        let err = provider.validate_http_headers("example").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "model_providers.example: invalid HTTP header name `X Bad`"
+        );
+
+        let provider: ModelProviderInfo = toml::from_str(
+            r#"
+name = "Example"
+http_headers = { "X-Good" = "line\nbreak" }
+            "#,
+        )
+        .unwrap();
+        let err = provider.validate_http_headers("example").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "model_providers.example: invalid value for HTTP header `X-Good`"
+        );
+
+        let provider: ModelProviderInfo = toml::from_str(
+            r#"
+name = "Example"
+http_headers = { "X-Good" = "value" }
+env_http_headers = { "X-Env" = "EXAMPLE_ENV_VAR" }
+            "#,
+        )
+        .unwrap();
+        assert!(provider.validate_http_headers("example").is_ok());
+    }
+
+    #[test]
+    fn missing_env_http_headers_lists_unset_variables() {
+        let provider: ModelProviderInfo = toml::from_str(
+            r#"
+name = "Example"
+env_http_headers = { "X-Set" = "PATH", "X-Unset" = "CODEX_TEST_UNSET_HEADER_VAR" }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            provider.missing_env_http_headers(),
+            vec![(
+                "X-Unset".to_string(),
+                "CODEX_TEST_UNSET_HEADER_VAR".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn encoded_query_params_are_not_encoded_twice() {
+        let provider: ModelProviderInfo = toml::from_str(
+            r#"
+name = "Example"
+base_url = "https://example.com/v1"
+query_params = { "sig" = "a%2Fb%3D 100%" }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            provider.get_full_url(&None),
+            "https://example.com/v1/chat/completions?sig=a%2Fb%3D%20100%25"
+        );
+    }
+
+    #[test]
+    fn query_params_are_url_encoded() {
+        let provider: ModelProviderInfo = toml::from_str(
+            r#"
+name = "Example"
+base_url = "https://example.com/v1"
+query_params = { "scope" = "a b&c" }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            provider.get_full_url(&None),
+            "https://example.com/v1/chat/completions?scope=a%20b%26c"
+        );
+    }
 }
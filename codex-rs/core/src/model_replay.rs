@@ -0,0 +1,521 @@
+//! Offline "model" for `wire_api = "replay"` providers.
+//!
+//! Instead of calling an API, every request the session makes is answered
+//! with the next response from the recording at `replay_path`, so the TUI and
+//! `codex exec` can run a whole session without network access. Two kinds of
+//! recording are accepted:
+//!
+//! - a rollout file (`rollout-*.jsonl`): each run of model output items
+//!   between two inputs is one response, replayed with the recorded pauses
+//!   divided by `replay_speed`;
+//! - a capture of Responses API SSE (`event:`/`data:` lines, as in the test
+//!   fixtures): each response ends at `response.completed` or
+//!   `response.failed`. Captures carry no timestamps, so they replay at once.
+//!
+//! Tool calls in the replayed responses go through the normal dispatch, but
+//! a call whose output the rollout recorded gets that output back without
+//! running anything; see [`ModelReplay::recorded_output`].
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::Utc;
+use codex_otel::otel_event_manager::OtelEventManager;
+use codex_protocol::models::ResponseInputItem;
+use codex_protocol::models::ResponseItem;
+use futures::TryStreamExt;
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tokio_util::io::ReaderStream;
+
+use crate::client::process_sse;
+use crate::client_common::ResponseEvent;
+use crate::client_common::ResponseStream;
+use crate::config::Config;
+use crate::error::CodexErr;
+use crate::error::Result;
+use crate::model_provider_info::WireApi;
+use crate::protocol::EventMsg;
+use crate::protocol::RolloutItem;
+use crate::protocol::RolloutLine;
+use crate::protocol::TokenCountEvent;
+use crate::protocol::TokenUsage;
+
+#[derive(Debug)]
+pub(crate) struct ModelReplay {
+    path: PathBuf,
+    speed: f64,
+    responses: Mutex<VecDeque<RecordedResponse>>,
+    /// Recorded tool outputs by call id.
+    outputs: HashMap<String, ResponseInputItem>,
+    replayed: AtomicUsize,
+}
+
+#[derive(Debug, PartialEq)]
+enum RecordedResponse {
+    /// Output items from a rollout, each with the pause before it.
+    Items {
+        items: Vec<(Duration, ResponseItem)>,
+        token_usage: Option<TokenUsage>,
+    },
+    /// Raw SSE of one response from a capture.
+    Sse(String),
+}
+
+/// Where a recording stopped making sense.
+#[derive(Debug, PartialEq)]
+struct LineError {
+    line: usize,
+    message: String,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Recording {
+    responses: VecDeque<RecordedResponse>,
+    outputs: HashMap<String, ResponseInputItem>,
+}
+
+impl ModelReplay {
+    /// The replay for this session when its provider replays a recording.
+    pub(crate) fn for_config(config: &Config) -> Result<Option<Self>> {
+        if config.model_provider.wire_api != WireApi::Replay {
+            return Ok(None);
+        }
+        let Some(path) = &config.replay_path else {
+            return Err(CodexErr::Fatal(format!(
+                "model provider `{}` replays a recording; set `replay_path`",
+                config.model_provider_id
+            )));
+        };
+        Self::load(path, config.replay_speed).map(Some)
+    }
+
+    /// Reads the whole recording up front, so a malformed file fails the
+    /// session before the first turn, naming the offending line.
+    pub(crate) fn load(path: &Path, speed: f64) -> Result<Self> {
+        let text = std::fs::read_to_string(path).map_err(|err| {
+            CodexErr::Fatal(format!(
+                "failed to read replay file {}: {err}",
+                path.display()
+            ))
+        })?;
+        let is_rollout = text
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .is_some_and(|line| line.trim_start().starts_with('{'));
+        let recording = if is_rollout {
+            parse_rollout(&text)
+        } else {
+            parse_sse_capture(&text)
+        }
+        .map_err(|LineError { line, message }| {
+            CodexErr::Fatal(format!("{}:{line}: {message}", path.display()))
+        })?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            speed,
+            responses: Mutex::new(recording.responses),
+            outputs: recording.outputs,
+            replayed: AtomicUsize::new(0),
+        })
+    }
+
+    /// Streams the next recorded response.
+    pub(crate) fn next_response(
+        &self,
+        idle_timeout: Duration,
+        otel_event_manager: OtelEventManager,
+    ) -> Result<ResponseStream> {
+        let response = self
+            .responses
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_front();
+        let Some(response) = response else {
+            return Err(CodexErr::Fatal(format!(
+                "replay file {} has no more recorded responses",
+                self.path.display()
+            )));
+        };
+        let index = self.replayed.fetch_add(1, Ordering::Relaxed) + 1;
+
+        let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+        match response {
+            RecordedResponse::Sse(text) => {
+                let stream = ReaderStream::new(std::io::Cursor::new(text)).map_err(CodexErr::Io);
+                tokio::spawn(process_sse(
+                    stream,
+                    tx_event,
+                    idle_timeout,
+                    otel_event_manager,
+                ));
+            }
+            RecordedResponse::Items { items, token_usage } => {
+                let speed = self.speed;
+                tokio::spawn(async move {
+                    if tx_event.send(Ok(ResponseEvent::Created)).await.is_err() {
+                        return;
+                    }
+                    for (pause, item) in items {
+                        if speed > 0.0 && !pause.is_zero() {
+                            tokio::time::sleep(pause.div_f64(speed)).await;
+                        }
+                        let event = ResponseEvent::OutputItemDone(item);
+                        if tx_event.send(Ok(event)).await.is_err() {
+                            return;
+                        }
+                    }
+                    let completed = ResponseEvent::Completed {
+                        response_id: format!("replay-{index}"),
+                        token_usage,
+                    };
+                    let _ = tx_event.send(Ok(completed)).await;
+                });
+            }
+        }
+        Ok(ResponseStream { rx_event })
+    }
+
+    /// The output the rollout recorded for this tool call, if any. Returning
+    /// it instead of running the tool keeps the replay deterministic.
+    pub(crate) fn recorded_output(&self, call_id: &str) -> Option<ResponseInputItem> {
+        self.outputs.get(call_id).cloned()
+    }
+}
+
+fn parse_rollout(text: &str) -> std::result::Result<Recording, LineError> {
+    let mut recording = Recording::default();
+    let mut items: Vec<(Duration, ResponseItem)> = Vec::new();
+    let mut token_usage = None;
+    let mut previous: Option<DateTime<Utc>> = None;
+
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let rollout_line: RolloutLine = serde_json::from_str(line).map_err(|err| LineError {
+            line: index + 1,
+            message: err.to_string(),
+        })?;
+        let timestamp = DateTime::parse_from_rfc3339(&rollout_line.timestamp)
+            .ok()
+            .map(|timestamp| timestamp.with_timezone(&Utc));
+        let pause = match (previous, timestamp) {
+            (Some(previous), Some(timestamp)) => {
+                (timestamp - previous).to_std().unwrap_or_default()
+            }
+            _ => Duration::ZERO,
+        };
+        if timestamp.is_some() {
+            previous = timestamp;
+        }
+
+        match rollout_line.item {
+            RolloutItem::ResponseItem(item) if is_model_output(&item) => items.push((pause, item)),
+            RolloutItem::ResponseItem(item) => {
+                if is_model_input(&item) {
+                    finish_response(&mut recording, &mut items, &mut token_usage);
+                }
+                match item {
+                    ResponseItem::FunctionCallOutput { call_id, output } => {
+                        recording.outputs.insert(
+                            call_id.clone(),
+                            ResponseInputItem::FunctionCallOutput { call_id, output },
+                        );
+                    }
+                    ResponseItem::CustomToolCallOutput { call_id, output } => {
+                        recording.outputs.insert(
+                            call_id.clone(),
+                            ResponseInputItem::CustomToolCallOutput { call_id, output },
+                        );
+                    }
+                    _ => {}
+                }
+            }
+            // The summary a compaction request got back.
+            RolloutItem::Compacted(compacted) => {
+                finish_response(&mut recording, &mut items, &mut token_usage);
+                recording.responses.push_back(RecordedResponse::Items {
+                    items: vec![(pause, assistant_message(compacted.message))],
+                    token_usage: None,
+                });
+            }
+            RolloutItem::EventMsg(EventMsg::TokenCount(TokenCountEvent {
+                info: Some(info),
+                ..
+            })) if !items.is_empty() => token_usage = Some(info.last_token_usage),
+            _ => {}
+        }
+    }
+    finish_response(&mut recording, &mut items, &mut token_usage);
+    Ok(recording)
+}
+
+fn finish_response(
+    recording: &mut Recording,
+    items: &mut Vec<(Duration, ResponseItem)>,
+    token_usage: &mut Option<TokenUsage>,
+) {
+    if items.is_empty() {
+        return;
+    }
+    recording.responses.push_back(RecordedResponse::Items {
+        items: std::mem::take(items),
+        token_usage: token_usage.take(),
+    });
+}
+
+fn is_model_output(item: &ResponseItem) -> bool {
+    match item {
+        ResponseItem::Message { role, .. } => role == "assistant",
+        ResponseItem::Reasoning { .. }
+        | ResponseItem::LocalShellCall { .. }
+        | ResponseItem::FunctionCall { .. }
+        | ResponseItem::CustomToolCall { .. }
+        | ResponseItem::WebSearchCall { .. } => true,
+        _ => false,
+    }
+}
+
+/// Items that start a new request. Snapshots and other bookkeeping items are
+/// neither input nor output.
+fn is_model_input(item: &ResponseItem) -> bool {
+    matches!(
+        item,
+        ResponseItem::Message { .. }
+            | ResponseItem::FunctionCallOutput { .. }
+            | ResponseItem::CustomToolCallOutput { .. }
+    )
+}
+
+fn assistant_message(text: String) -> ResponseItem {
+    ResponseItem::Message {
+        id: None,
+        role: "assistant".to_string(),
+        content: vec![codex_protocol::models::ContentItem::OutputText { text }],
+    }
+}
+
+fn parse_sse_capture(text: &str) -> std::result::Result<Recording, LineError> {
+    let mut recording = Recording::default();
+    let mut current = String::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let error = |message: String| LineError {
+            line: index + 1,
+            message,
+        };
+        let mut ends_response = false;
+        if let Some(data) = line.strip_prefix("data:") {
+            let data = data.trim_start();
+            if data != "[DONE]" {
+                let event: Value = serde_json::from_str(data)
+                    .map_err(|err| error(format!("invalid SSE data: {err}")))?;
+                ends_response = matches!(
+                    event.get("type").and_then(Value::as_str),
+                    Some("response.completed" | "response.failed")
+                );
+            }
+        } else if !(line.trim().is_empty()
+            || line.starts_with(':')
+            || line.starts_with("event:")
+            || line.starts_with("id:")
+            || line.starts_with("retry:"))
+        {
+            return Err(error(format!("expected an SSE field, found `{line}`")));
+        }
+
+        current.push_str(line);
+        current.push('\n');
+        if ends_response {
+            // Terminate the event before cutting the response.
+            current.push('\n');
+            recording
+                .responses
+                .push_back(RecordedResponse::Sse(std::mem::take(&mut current)));
+        }
+    }
+    if current.contains("data:") {
+        recording
+            .responses
+            .push_back(RecordedResponse::Sse(current));
+    }
+    Ok(recording)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ContentItem;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use futures::StreamExt;
+    use pretty_assertions::assert_eq;
+
+    const ROLLOUT: &str = r#"{"timestamp":"2025-10-01T12:00:00.000Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"list files"}]}}
+{"timestamp":"2025-10-01T12:00:02.000Z","type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{\"command\":[\"ls\"]}","call_id":"call-1"}}
+{"timestamp":"2025-10-01T12:00:02.100Z","type":"event_msg","payload":{"type":"token_count","info":{"total_token_usage":{"input_tokens":10,"cached_input_tokens":0,"output_tokens":5,"reasoning_output_tokens":0,"total_tokens":15},"last_token_usage":{"input_tokens":10,"cached_input_tokens":0,"output_tokens":5,"reasoning_output_tokens":0,"total_tokens":15},"model_context_window":null},"rate_limits":null}}
+{"timestamp":"2025-10-01T12:00:03.000Z","type":"response_item","payload":{"type":"function_call_output","call_id":"call-1","output":"README.md"}}
+{"timestamp":"2025-10-01T12:00:03.500Z","type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"One file."}]}}
+"#;
+
+    fn message(text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn rollout_splits_responses_at_inputs() {
+        let recording = parse_rollout(ROLLOUT).expect("valid rollout");
+
+        assert_eq!(
+            recording.responses,
+            VecDeque::from([
+                RecordedResponse::Items {
+                    items: vec![(
+                        Duration::from_secs(2),
+                        ResponseItem::FunctionCall {
+                            id: None,
+                            name: "shell".to_string(),
+                            arguments: r#"{"command":["ls"]}"#.to_string(),
+                            call_id: "call-1".to_string(),
+                        },
+                    )],
+                    token_usage: Some(TokenUsage {
+                        input_tokens: 10,
+                        cached_input_tokens: 0,
+                        output_tokens: 5,
+                        reasoning_output_tokens: 0,
+                        total_tokens: 15,
+                    }),
+                },
+                RecordedResponse::Items {
+                    items: vec![(Duration::from_millis(500), message("One file."))],
+                    token_usage: None,
+                },
+            ])
+        );
+        assert_eq!(
+            recording.outputs,
+            HashMap::from([(
+                "call-1".to_string(),
+                ResponseInputItem::FunctionCallOutput {
+                    call_id: "call-1".to_string(),
+                    output: FunctionCallOutputPayload {
+                        content: "README.md".to_string(),
+                        ..Default::default()
+                    },
+                },
+            )])
+        );
+    }
+
+    #[test]
+    fn truncated_lines_report_their_number() {
+        let truncated = &ROLLOUT[..ROLLOUT.len() - 40];
+        assert_eq!(parse_rollout(truncated).map_err(|err| err.line), Err(5));
+
+        let capture = "event: response.created\ndata: {\"type\":\"response.created\"\n";
+        assert_eq!(parse_sse_capture(capture).map_err(|err| err.line), Err(2));
+    }
+
+    #[test]
+    fn capture_splits_responses_after_completed() {
+        let capture = "event: response.created
+data: {\"type\":\"response.created\",\"response\":{}}
+
+event: response.completed
+data: {\"type\":\"response.completed\",\"response\":{\"id\":\"r1\"}}
+
+data: {\"type\":\"response.completed\",\"response\":{\"id\":\"r2\"}}
+";
+        let recording = parse_sse_capture(capture).expect("valid capture");
+
+        assert_eq!(
+            recording.responses,
+            VecDeque::from([
+                RecordedResponse::Sse(
+                    "event: response.created
+data: {\"type\":\"response.created\",\"response\":{}}
+
+event: response.completed
+data: {\"type\":\"response.completed\",\"response\":{\"id\":\"r1\"}}
+
+"
+                    .to_string()
+                ),
+                RecordedResponse::Sse(
+                    "
+data: {\"type\":\"response.completed\",\"response\":{\"id\":\"r2\"}}
+
+"
+                    .to_string()
+                ),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn replays_items_in_order_then_runs_dry() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("rollout.jsonl");
+        std::fs::write(&path, ROLLOUT).expect("write");
+        let replay = ModelReplay::load(&path, 0.0).expect("load");
+        let otel = OtelEventManager::new(
+            codex_protocol::ConversationId::default(),
+            "replay",
+            "replay",
+            None,
+            None,
+            None,
+            false,
+            "test".to_string(),
+        );
+
+        let mut kinds = Vec::new();
+        for _ in 0..2 {
+            let mut stream = replay
+                .next_response(Duration::from_secs(1), otel.clone())
+                .expect("response");
+            while let Some(event) = stream.next().await {
+                kinds.push(match event.expect("event") {
+                    ResponseEvent::Created => "created".to_string(),
+                    ResponseEvent::OutputItemDone(ResponseItem::FunctionCall {
+                        call_id, ..
+                    }) => {
+                        format!("call {call_id}")
+                    }
+                    ResponseEvent::OutputItemDone(_) => "item".to_string(),
+                    ResponseEvent::Completed { response_id, .. } => response_id,
+                    other => format!("{other:?}"),
+                });
+            }
+        }
+
+        assert_eq!(
+            kinds,
+            vec![
+                "created",
+                "call call-1",
+                "replay-1",
+                "created",
+                "item",
+                "replay-2"
+            ]
+        );
+        assert!(replay.recorded_output("call-1").is_some());
+        assert!(replay.next_response(Duration::from_secs(1), otel).is_err());
+    }
+}
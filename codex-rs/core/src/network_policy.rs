@@ -0,0 +1,116 @@
+//! Central gate for outbound network traffic.
+//!
+//! Components that reach out to the network tag the request with a
+//! [`NetworkCapability`] and ask the session's [`NetworkPolicy`] before
+//! connecting. In offline mode (`--offline` or `network.offline = true`) only
+//! model provider traffic is allowed; everything else receives
+//! [`OfflineBlocked`] and is expected to degrade gracefully (skip the check,
+//! hide the feature) instead of failing the run.
+
+use strum_macros::Display;
+
+use crate::default_client::CodexHttpClient;
+use crate::default_client::create_client;
+
+/// What a component intends to use the network for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum NetworkCapability {
+    /// Requests to the configured model provider (including auth refresh).
+    Provider,
+    /// Release and version checks.
+    Updates,
+    /// Hosted web search made available to the model.
+    Search,
+    /// Model or asset downloads (e.g. Ollama registry pulls).
+    Downloads,
+    /// Codex Cloud task APIs.
+    CloudTasks,
+    /// OpenTelemetry log export.
+    Telemetry,
+}
+
+/// Returned when a capability is denied by the active [`NetworkPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("{capability} network access is disabled in offline mode")]
+pub struct OfflineBlocked {
+    pub capability: NetworkCapability,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NetworkPolicy {
+    offline: bool,
+}
+
+impl NetworkPolicy {
+    pub fn new(offline: bool) -> Self {
+        Self { offline }
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    pub fn check(&self, capability: NetworkCapability) -> Result<(), OfflineBlocked> {
+        if self.offline && capability != NetworkCapability::Provider {
+            return Err(OfflineBlocked { capability });
+        }
+        Ok(())
+    }
+
+    /// HTTP client for `capability`, or [`OfflineBlocked`] when the policy
+    /// denies it.
+    pub fn client(&self, capability: NetworkCapability) -> Result<CodexHttpClient, OfflineBlocked> {
+        self.check(capability)?;
+        Ok(create_client())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn online_allows_everything() {
+        let policy = NetworkPolicy::new(false);
+        for capability in [
+            NetworkCapability::Provider,
+            NetworkCapability::Updates,
+            NetworkCapability::Search,
+            NetworkCapability::Downloads,
+            NetworkCapability::CloudTasks,
+            NetworkCapability::Telemetry,
+        ] {
+            assert_eq!(policy.check(capability), Ok(()));
+        }
+    }
+
+    #[test]
+    fn offline_only_allows_provider() {
+        let policy = NetworkPolicy::new(true);
+        assert_eq!(policy.check(NetworkCapability::Provider), Ok(()));
+        assert!(policy.client(NetworkCapability::Provider).is_ok());
+        for capability in [
+            NetworkCapability::Updates,
+            NetworkCapability::Search,
+            NetworkCapability::Downloads,
+            NetworkCapability::CloudTasks,
+            NetworkCapability::Telemetry,
+        ] {
+            assert_eq!(policy.check(capability), Err(OfflineBlocked { capability }));
+            assert!(policy.client(capability).is_err());
+        }
+    }
+
+    #[test]
+    fn blocked_error_names_capability() {
+        let err = OfflineBlocked {
+            capability: NetworkCapability::CloudTasks,
+        };
+        assert_eq!(
+            err.to_string(),
+            "cloud-tasks network access is disabled in offline mode"
+        );
+    }
+}
@@ -0,0 +1,343 @@
+//! Runs the per-event commands configured under `[notifications]`.
+//!
+//! Hooks are spawned detached so a slow or hung command never holds up the
+//! agent loop: each one runs on its own task, is killed after its timeout,
+//! and is dropped outright when `max_concurrent` hooks are already running.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_protocol::protocol::EventMsg;
+use tokio::sync::Semaphore;
+use tracing::warn;
+
+use crate::config::types::NotificationHook;
+use crate::config::types::NotificationHooks;
+
+const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum HookEvent {
+    TaskComplete,
+    ApprovalRequired,
+    Error,
+}
+
+impl HookEvent {
+    fn name(self) -> &'static str {
+        match self {
+            HookEvent::TaskComplete => "task_complete",
+            HookEvent::ApprovalRequired => "approval_required",
+            HookEvent::Error => "error",
+        }
+    }
+
+    /// The hook event for `msg` and the text to substitute for `{summary}`.
+    pub(crate) fn from_event_msg(msg: &EventMsg) -> Option<(Self, String)> {
+        match msg {
+            EventMsg::TaskComplete(ev) => Some((
+                HookEvent::TaskComplete,
+                ev.last_agent_message.clone().unwrap_or_default(),
+            )),
+            EventMsg::ExecApprovalRequest(ev) => Some((
+                HookEvent::ApprovalRequired,
+                ev.reason
+                    .clone()
+                    .unwrap_or_else(|| format!("Run `{}`?", ev.command.join(" "))),
+            )),
+            EventMsg::ApplyPatchApprovalRequest(ev) => Some((
+                HookEvent::ApprovalRequired,
+                ev.reason
+                    .clone()
+                    .unwrap_or_else(|| format!("Edit {} file(s)?", ev.changes.len())),
+            )),
+            EventMsg::Error(ev) => Some((HookEvent::Error, ev.message.clone())),
+            _ => None,
+        }
+    }
+}
+
+/// Values substituted into a hook's command template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HookVars<'a> {
+    pub cwd: &'a str,
+    pub summary: &'a str,
+    pub session_id: &'a str,
+}
+
+#[derive(Debug)]
+pub(crate) struct NotificationHookRunner {
+    hooks: NotificationHooks,
+    permits: Arc<Semaphore>,
+    last_fired: Mutex<HashMap<HookEvent, Instant>>,
+    /// Hooks that already logged a failure this session.
+    warned: Arc<Mutex<HashSet<HookEvent>>>,
+}
+
+impl Default for NotificationHookRunner {
+    fn default() -> Self {
+        Self::new(NotificationHooks::default())
+    }
+}
+
+impl NotificationHookRunner {
+    pub(crate) fn new(hooks: NotificationHooks) -> Self {
+        let permits = Arc::new(Semaphore::new(hooks.max_concurrent));
+        Self {
+            hooks,
+            permits,
+            last_fired: Mutex::new(HashMap::new()),
+            warned: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    fn hook(&self, event: HookEvent) -> Option<&NotificationHook> {
+        match event {
+            HookEvent::TaskComplete => self.hooks.task_complete.as_ref(),
+            HookEvent::ApprovalRequired => self.hooks.approval_required.as_ref(),
+            HookEvent::Error => self.hooks.error.as_ref(),
+        }
+        .filter(|hook| !hook.command.is_empty())
+    }
+
+    /// Spawn the hook for `event`, if one is configured and not debounced.
+    /// Returns immediately.
+    pub(crate) fn dispatch(&self, event: HookEvent, vars: &HookVars<'_>) {
+        let Some(hook) = self.hook(event) else {
+            return;
+        };
+        if !self.should_fire(event, hook, Instant::now()) {
+            return;
+        }
+        let Ok(permit) = Arc::clone(&self.permits).try_acquire_owned() else {
+            warn_once(
+                &self.warned,
+                event,
+                "skipped: too many notification hooks are already running",
+            );
+            return;
+        };
+
+        let argv = build_argv(hook, vars);
+        let timeout = hook
+            .timeout_sec
+            .map_or(DEFAULT_HOOK_TIMEOUT, Duration::from_secs);
+        let warned = Arc::clone(&self.warned);
+        tokio::spawn(async move {
+            let _permit = permit;
+            if let Err(err) = run_hook(&argv, timeout).await {
+                warn_once(&warned, event, &err);
+            }
+        });
+    }
+
+    /// Leading-edge debounce: fire unless this hook fired within its
+    /// `debounce_ms` window.
+    fn should_fire(&self, event: HookEvent, hook: &NotificationHook, now: Instant) -> bool {
+        let Ok(mut last_fired) = self.last_fired.lock() else {
+            return true;
+        };
+        if let (Some(debounce_ms), Some(last)) = (hook.debounce_ms, last_fired.get(&event))
+            && now.saturating_duration_since(*last) < Duration::from_millis(debounce_ms)
+        {
+            return false;
+        }
+        last_fired.insert(event, now);
+        true
+    }
+}
+
+fn warn_once(warned: &Mutex<HashSet<HookEvent>>, event: HookEvent, message: &str) {
+    let first = warned
+        .lock()
+        .map(|mut warned| warned.insert(event))
+        .unwrap_or(true);
+    if first {
+        warn!(
+            "notification hook `{}` {message}; further failures this session are not logged",
+            event.name()
+        );
+    }
+}
+
+async fn run_hook(argv: &[String], timeout: Duration) -> Result<(), String> {
+    let Some((program, args)) = argv.split_first() else {
+        return Ok(());
+    };
+    let mut child = tokio::process::Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|err| format!("failed to spawn `{program}`: {err}"))?;
+    match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(Ok(status)) if status.success() => Ok(()),
+        Ok(Ok(status)) => Err(format!("exited with {status}")),
+        Ok(Err(err)) => Err(format!("failed to wait for `{program}`: {err}")),
+        Err(_) => {
+            let _ = child.kill().await;
+            Err(format!("timed out after {}s", timeout.as_secs()))
+        }
+    }
+}
+
+/// The program and arguments to run. Without `shell`, each template element
+/// stays one argument no matter what the placeholder values contain.
+pub(crate) fn build_argv(hook: &NotificationHook, vars: &HookVars<'_>) -> Vec<String> {
+    let expanded: Vec<String> = hook
+        .command
+        .iter()
+        .map(|part| expand_template(part, vars))
+        .collect();
+    if !hook.shell {
+        return expanded;
+    }
+    let script = expanded.join(" ");
+    if cfg!(windows) {
+        vec!["cmd".to_string(), "/C".to_string(), script]
+    } else {
+        vec!["sh".to_string(), "-c".to_string(), script]
+    }
+}
+
+/// Replace `{cwd}`, `{summary}` and `{session_id}` in `template`. Other
+/// braces are left alone, and substituted values are never expanded again.
+pub(crate) fn expand_template(template: &str, vars: &HookVars<'_>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start..];
+        let replacement = [
+            ("{cwd}", vars.cwd),
+            ("{summary}", vars.summary),
+            ("{session_id}", vars.session_id),
+        ]
+        .into_iter()
+        .find(|(placeholder, _)| after.starts_with(placeholder));
+        match replacement {
+            Some((placeholder, value)) => {
+                out.push_str(value);
+                rest = &after[placeholder.len()..];
+            }
+            None => {
+                out.push('{');
+                rest = &after[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const VARS: HookVars<'static> = HookVars {
+        cwd: "/work/repo",
+        summary: "Said \"done\"; $(rm -rf ~)",
+        session_id: "b5f6c1c2-1111-2222-3333-444455556666",
+    };
+
+    fn hook(command: &[&str], shell: bool, debounce_ms: Option<u64>) -> NotificationHook {
+        NotificationHook {
+            command: command.iter().map(ToString::to_string).collect(),
+            shell,
+            debounce_ms,
+            timeout_sec: None,
+        }
+    }
+
+    #[test]
+    fn expands_known_placeholders_only() {
+        assert_eq!(
+            expand_template("{session_id} in {cwd}: {summary} {other} {", &VARS),
+            "b5f6c1c2-1111-2222-3333-444455556666 in /work/repo: Said \"done\"; $(rm -rf ~) {other} {"
+        );
+    }
+
+    #[test]
+    fn substituted_values_are_not_expanded_again() {
+        let vars = HookVars {
+            summary: "{cwd}",
+            ..VARS
+        };
+        assert_eq!(expand_template("[{summary}]", &vars), "[{cwd}]");
+    }
+
+    #[test]
+    fn argv_keeps_each_element_as_one_argument() {
+        assert_eq!(
+            build_argv(
+                &hook(
+                    &["notify-send", "Codex: {summary}", "--id={session_id}"],
+                    false,
+                    None
+                ),
+                &VARS
+            ),
+            vec![
+                "notify-send".to_string(),
+                "Codex: Said \"done\"; $(rm -rf ~)".to_string(),
+                "--id=b5f6c1c2-1111-2222-3333-444455556666".to_string(),
+            ]
+        );
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn shell_hooks_run_through_sh() {
+        assert_eq!(
+            build_argv(&hook(&["echo", "{cwd}", ">> log"], true, None), &VARS),
+            vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                "echo /work/repo >> log".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn debounce_skips_events_inside_the_window() {
+        let debounced = hook(&["true"], false, Some(1_000));
+        let runner = NotificationHookRunner::default();
+        let start = Instant::now();
+
+        assert!(runner.should_fire(HookEvent::TaskComplete, &debounced, start));
+        assert!(!runner.should_fire(
+            HookEvent::TaskComplete,
+            &debounced,
+            start + Duration::from_millis(500)
+        ));
+        // Other events are debounced separately.
+        assert!(runner.should_fire(
+            HookEvent::Error,
+            &debounced,
+            start + Duration::from_millis(500)
+        ));
+        assert!(runner.should_fire(
+            HookEvent::TaskComplete,
+            &debounced,
+            start + Duration::from_millis(1_000)
+        ));
+    }
+
+    #[test]
+    fn hooks_without_debounce_always_fire() {
+        let plain = hook(&["true"], false, None);
+        let runner = NotificationHookRunner::default();
+        let now = Instant::now();
+
+        assert!(runner.should_fire(HookEvent::TaskComplete, &plain, now));
+        assert!(runner.should_fire(HookEvent::TaskComplete, &plain, now));
+    }
+}
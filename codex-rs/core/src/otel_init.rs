@@ -2,6 +2,7 @@ use crate::config::Config;
 use crate::config::types::OtelExporterKind as Kind;
 use crate::config::types::OtelHttpProtocol as Protocol;
 use crate::default_client::originator;
+use crate::network_policy::NetworkCapability;
 use codex_otel::config::OtelExporter;
 use codex_otel::config::OtelHttpProtocol;
 use codex_otel::config::OtelSettings;
@@ -11,11 +12,16 @@ use std::error::Error;
 
 /// Build an OpenTelemetry provider from the app Config.
 ///
-/// Returns `None` when OTEL export is disabled.
+/// Returns `None` when OTEL export is disabled or blocked by offline mode.
 pub fn build_provider(
     config: &Config,
     service_version: &str,
 ) -> Result<Option<OtelProvider>, Box<dyn Error>> {
+    if let Err(blocked) = config.network_policy().check(NetworkCapability::Telemetry) {
+        tracing::info!("skipping OTEL exporter: {blocked}");
+        return Ok(None);
+    }
+
     let exporter = match &config.otel.exporter {
         Kind::None => OtelExporter::None,
         Kind::OtlpHttp {
@@ -75,3 +81,34 @@ pub fn build_provider(
 pub fn codex_export_filter(meta: &tracing::Metadata<'_>) -> bool {
     meta.target().starts_with("codex_otel")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigOverrides;
+    use crate::config::ConfigToml;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    #[test]
+    fn offline_skips_configured_exporter() -> Result<(), Box<dyn Error>> {
+        let codex_home = TempDir::new()?;
+        let mut config = Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides {
+                offline: Some(true),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+        config.otel.exporter = Kind::OtlpHttp {
+            endpoint: "http://localhost:4318/v1/logs".to_string(),
+            headers: HashMap::new(),
+            protocol: Protocol::Binary,
+            tls: None,
+        };
+
+        assert!(build_provider(&config, "0.0.0")?.is_none());
+        Ok(())
+    }
+}
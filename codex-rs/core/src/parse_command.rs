@@ -1,6 +1,9 @@
+use crate::bash::ShellConnector;
+use crate::bash::ShellSegment;
 use crate::bash::extract_bash_command;
+use crate::bash::parse_shell_lc_segments;
 use crate::bash::try_parse_shell;
-use crate::bash::try_parse_word_only_commands_sequence;
+use crate::bash::try_parse_shell_segments;
 use crate::powershell::extract_powershell_command;
 use codex_protocol::parse_command::ParsedCommand;
 use shlex::split as shlex_split;
@@ -93,6 +96,180 @@ mod tests {
         );
     }
 
+    #[test]
+    fn redirected_writes_are_not_summarized_away() {
+        assert_parsed(
+            &vec_str(&["bash", "-lc", "echo hi > notes.txt && cat notes.txt"]),
+            vec![
+                ParsedCommand::Unknown {
+                    cmd: "echo hi > notes.txt".to_string(),
+                },
+                ParsedCommand::Read {
+                    cmd: "cat notes.txt".to_string(),
+                    name: "notes.txt".to_string(),
+                    path: PathBuf::from("notes.txt"),
+                },
+            ],
+        );
+        assert_parsed(
+            &vec_str(&["bash", "-lc", "cat a.txt > b.txt; ls"]),
+            vec![
+                ParsedCommand::Unknown {
+                    cmd: "cat a.txt > b.txt".to_string(),
+                },
+                ParsedCommand::ListFiles {
+                    cmd: "ls".to_string(),
+                    path: None,
+                },
+            ],
+        );
+    }
+
+    #[test]
+    fn read_only_segments_are_pulled_out_of_subshells() {
+        assert_parsed(
+            &vec_str(&["bash", "-lc", "(cd docs && rg -n TODO) 2>/dev/null"]),
+            vec![ParsedCommand::Search {
+                cmd: "rg -n TODO".to_string(),
+                query: Some("TODO".to_string()),
+                path: None,
+            }],
+        );
+    }
+
+    fn segment(
+        connector: Option<ShellConnector>,
+        subshell_depth: usize,
+        program: &str,
+        writes_file: bool,
+        parsed: ParsedCommand,
+    ) -> CommandSegment {
+        CommandSegment {
+            connector,
+            subshell_depth,
+            program: program.to_string(),
+            writes_file,
+            parsed,
+        }
+    }
+
+    fn unknown(cmd: &str) -> ParsedCommand {
+        ParsedCommand::Unknown {
+            cmd: cmd.to_string(),
+        }
+    }
+
+    #[test]
+    fn compound_command_corpus() {
+        use ShellConnector::*;
+
+        let corpus = vec![
+            (
+                "mkdir -p build && cd build && cmake .. && make -j8",
+                vec![
+                    segment(None, 0, "mkdir", false, unknown("mkdir -p build")),
+                    segment(Some(And), 0, "cd", false, unknown("cd build")),
+                    segment(Some(And), 0, "cmake", false, unknown("cmake ..")),
+                    segment(Some(And), 0, "make", false, unknown("make -j8")),
+                ],
+            ),
+            (
+                "echo \"a && b\"",
+                vec![segment(
+                    None,
+                    0,
+                    "echo",
+                    false,
+                    unknown(&shlex_join(&vec_str(&["echo", "a && b"]))),
+                )],
+            ),
+            (
+                "(cd docs && cat README.md) | head -n 5",
+                vec![
+                    segment(None, 1, "cd", false, unknown("cd docs")),
+                    segment(
+                        Some(And),
+                        1,
+                        "cat",
+                        false,
+                        ParsedCommand::Read {
+                            cmd: "cat README.md".to_string(),
+                            name: "README.md".to_string(),
+                            path: PathBuf::from("docs/README.md"),
+                        },
+                    ),
+                    segment(Some(Pipe), 0, "head", false, unknown("head -n 5")),
+                ],
+            ),
+            (
+                "git log --oneline | grep -i fix | wc -l",
+                vec![
+                    segment(None, 0, "git", false, unknown("git log --oneline")),
+                    segment(
+                        Some(Pipe),
+                        0,
+                        "grep",
+                        false,
+                        ParsedCommand::Search {
+                            cmd: "grep -i fix".to_string(),
+                            query: Some("fix".to_string()),
+                            path: None,
+                        },
+                    ),
+                    segment(Some(Pipe), 0, "wc", false, unknown("wc -l")),
+                ],
+            ),
+            (
+                "cat a.txt > b.txt; ls || (echo 'x; y' >> log)",
+                vec![
+                    segment(None, 0, "cat", true, unknown("cat a.txt > b.txt")),
+                    segment(
+                        Some(Sequence),
+                        0,
+                        "ls",
+                        false,
+                        ParsedCommand::ListFiles {
+                            cmd: "ls".to_string(),
+                            path: None,
+                        },
+                    ),
+                    segment(Some(Or), 1, "echo", true, unknown("echo 'x; y' >> log")),
+                ],
+            ),
+        ];
+
+        for (script, segments) in corpus {
+            assert_eq!(
+                parse_compound_command(&vec_str(&["bash", "-lc", script])),
+                Some(CompoundCommand { segments }),
+                "{script}"
+            );
+        }
+    }
+
+    #[test]
+    fn compound_command_summary_and_read_only() {
+        let build = parse_compound_command(&vec_str(&[
+            "bash",
+            "-lc",
+            "mkdir -p build && cd build && cmake .. && make -j8",
+        ]))
+        .unwrap();
+        assert_eq!(build.summary(), "mkdir, cd, cmake, make");
+        assert!(!build.is_read_only());
+
+        let explore =
+            parse_compound_command(&vec_str(&["bash", "-lc", "ls docs; rg -n TODO"])).unwrap();
+        assert_eq!(explore.summary(), "ls, rg");
+        assert!(explore.is_read_only());
+
+        assert_eq!(parse_compound_command(&vec_str(&["make", "-j8"])), None);
+        assert_eq!(
+            parse_compound_command(&vec_str(&["bash", "-lc", "echo $(pwd)"])),
+            None
+        );
+    }
+
     #[test]
     fn handles_complex_bash_command_head() {
         let inner =
@@ -985,45 +1162,49 @@ pub fn parse_command_impl(command: &[String]) -> Vec<ParsedCommand> {
 }
 
 fn simplify_once(commands: &[ParsedCommand]) -> Option<Vec<ParsedCommand>> {
+    let idx = droppable_index(commands, |_| true)?;
+    let mut out = commands.to_vec();
+    out.remove(idx);
+    Some(out)
+}
+
+/// Index of a helper command that adds nothing to the summary, considering
+/// only entries for which `can_drop` holds.
+fn droppable_index(commands: &[ParsedCommand], can_drop: impl Fn(usize) -> bool) -> Option<usize> {
     if commands.len() <= 1 {
         return None;
     }
 
     // echo ... && ...rest => ...rest
     if let ParsedCommand::Unknown { cmd } = &commands[0]
+        && can_drop(0)
         && shlex_split(cmd).is_some_and(|t| t.first().map(String::as_str) == Some("echo"))
     {
-        return Some(commands[1..].to_vec());
+        return Some(0);
     }
 
     // cd foo && [any command] => [any command] (keep non-cd when a cd is followed by something)
-    if let Some(idx) = commands.iter().position(|pc| match pc {
+    if let Some(idx) = commands.iter().enumerate().position(|(idx, pc)| match pc {
         ParsedCommand::Unknown { cmd } => {
-            shlex_split(cmd).is_some_and(|t| t.first().map(String::as_str) == Some("cd"))
+            can_drop(idx)
+                && shlex_split(cmd).is_some_and(|t| t.first().map(String::as_str) == Some("cd"))
         }
         _ => false,
     }) && commands.len() > idx + 1
     {
-        let mut out = Vec::with_capacity(commands.len() - 1);
-        out.extend_from_slice(&commands[..idx]);
-        out.extend_from_slice(&commands[idx + 1..]);
-        return Some(out);
+        return Some(idx);
     }
 
     // cmd || true => cmd
-    if let Some(idx) = commands
-        .iter()
-        .position(|pc| matches!(pc, ParsedCommand::Unknown { cmd } if cmd == "true"))
-    {
-        let mut out = Vec::with_capacity(commands.len() - 1);
-        out.extend_from_slice(&commands[..idx]);
-        out.extend_from_slice(&commands[idx + 1..]);
-        return Some(out);
+    if let Some(idx) = commands.iter().enumerate().position(
+        |(idx, pc)| matches!(pc, ParsedCommand::Unknown { cmd } if cmd == "true" && can_drop(idx)),
+    ) {
+        return Some(idx);
     }
 
     // nl -[any_flags] && ...rest => ...rest
-    if let Some(idx) = commands.iter().position(|pc| match pc {
-        ParsedCommand::Unknown { cmd } => {
+    commands.iter().enumerate().position(|(idx, pc)| match pc {
+        ParsedCommand::Unknown { cmd } if can_drop(idx) => {
             if let Some(tokens) = shlex_split(cmd) {
                 tokens.first().is_some_and(|s| s.as_str() == "nl")
                     && tokens.iter().skip(1).all(|t| t.starts_with('-'))
@@ -1032,14 +1213,7 @@ fn simplify_once(commands: &[ParsedCommand]) -> Option<Vec<ParsedCommand>> {
             }
         }
         _ => false,
-    }) {
-        let mut out = Vec::with_capacity(commands.len() - 1);
-        out.extend_from_slice(&commands[..idx]);
-        out.extend_from_slice(&commands[idx + 1..]);
-        return Some(out);
-    }
-
-    None
+    })
 }
 
 /// Validates that this is a `sed -n 123,123p` command.
@@ -1237,64 +1411,132 @@ fn parse_find_query_and_path(tail: &[String]) -> (Option<String>, Option<String>
     (query, path)
 }
 
+/// One simple command of a compound shell command, classified on its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandSegment {
+    /// How this segment is joined to the previous one; `None` for the first.
+    pub connector: Option<ShellConnector>,
+    /// Number of `( ... )` subshells around the segment.
+    pub subshell_depth: usize,
+    /// Program name as written, e.g. `make` or `./configure`.
+    pub program: String,
+    /// Output of the segment is redirected to a file.
+    pub writes_file: bool,
+    pub parsed: ParsedCommand,
+}
+
+impl CommandSegment {
+    /// Reads, listings and searches whose output is not written to a file.
+    pub fn is_read_only(&self) -> bool {
+        !self.writes_file
+            && matches!(
+                self.parsed,
+                ParsedCommand::Read { .. }
+                    | ParsedCommand::ListFiles { .. }
+                    | ParsedCommand::Search { .. }
+            )
+    }
+}
+
+/// Per-segment view of a command such as
+/// `mkdir -p build && cd build && cmake .. && make -j8`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompoundCommand {
+    pub segments: Vec<CommandSegment>,
+}
+
+impl CompoundCommand {
+    /// Program names in execution order, with immediate repeats collapsed.
+    pub fn programs(&self) -> Vec<&str> {
+        let mut programs: Vec<&str> = self
+            .segments
+            .iter()
+            .map(|segment| segment.program.as_str())
+            .collect();
+        programs.dedup();
+        programs
+    }
+
+    /// Comma-separated program names, e.g. `mkdir, cd, cmake, make`.
+    pub fn summary(&self) -> String {
+        self.programs().join(", ")
+    }
+
+    /// True when every segment is read-only.
+    pub fn is_read_only(&self) -> bool {
+        self.segments.iter().all(CommandSegment::is_read_only)
+    }
+}
+
+/// Split a `bash -lc`/`zsh -lc` command into its segments and classify each
+/// one independently. Unlike [`parse_command`], nothing is dropped or merged:
+/// `cd` and formatting helpers keep their own segment. Returns `None` when the
+/// command is not a shell script or uses constructs the segment parser does
+/// not understand.
+pub fn parse_compound_command(command: &[String]) -> Option<CompoundCommand> {
+    let segments = parse_shell_lc_segments(command)?;
+    let mut cds: Vec<&ShellSegment> = Vec::new();
+    let mut out = Vec::with_capacity(segments.len());
+    for segment in &segments {
+        let parsed = summarize_segment(segment, &cds);
+        if is_cd_segment(segment) {
+            cds.push(segment);
+        }
+        out.push(CommandSegment {
+            connector: segment.connector,
+            subshell_depth: segment.subshells.len(),
+            program: segment.words.first().cloned().unwrap_or_default(),
+            writes_file: segment.writes_file,
+            parsed,
+        });
+    }
+    Some(CompoundCommand { segments: out })
+}
+
 fn parse_shell_lc_commands(original: &[String]) -> Option<Vec<ParsedCommand>> {
     // Only handle bash/zsh here; PowerShell is stripped separately without bash parsing.
     let (_, script) = extract_bash_command(original)?;
 
     if let Some(tree) = try_parse_shell(script)
-        && let Some(all_commands) = try_parse_word_only_commands_sequence(&tree, script)
-        && !all_commands.is_empty()
+        && let Some(segments) = try_parse_shell_segments(&tree, script)
     {
         let script_tokens = shlex_split(script).unwrap_or_else(|| vec![script.to_string()]);
         // Strip small formatting helpers (e.g., head/tail/awk/wc/etc) so we
         // bias toward the primary command when pipelines are present.
         // First, drop obvious small formatting helpers (e.g., wc/awk/etc).
-        let had_multiple_commands = all_commands.len() > 1;
-        // Commands arrive in source order; drop formatting helpers while preserving it.
-        let filtered_commands = drop_small_formatting_commands(all_commands);
-        if filtered_commands.is_empty() {
+        let had_multiple_commands = segments.len() > 1;
+        // Segments arrive in source order; drop formatting helpers while preserving it.
+        let filtered_segments = drop_small_formatting_commands(segments);
+        if filtered_segments.is_empty() {
             return Some(vec![ParsedCommand::Unknown {
                 cmd: script.to_string(),
             }]);
         }
         // Build parsed commands, tracking `cd` segments to compute effective file paths.
         let mut commands: Vec<ParsedCommand> = Vec::new();
-        let mut cwd: Option<String> = None;
-        for tokens in filtered_commands.into_iter() {
-            if let Some((head, tail)) = tokens.split_first()
-                && head == "cd"
-            {
-                if let Some(dir) = tail.first() {
-                    cwd = Some(match &cwd {
-                        Some(base) => join_paths(base, dir),
-                        None => dir.clone(),
-                    });
-                }
+        let mut writes: Vec<bool> = Vec::new();
+        let mut cds: Vec<&ShellSegment> = Vec::new();
+        for segment in &filtered_segments {
+            if is_cd_segment(segment) {
+                cds.push(segment);
                 continue;
             }
-            let parsed = summarize_main_tokens(&tokens);
-            let parsed = match parsed {
-                ParsedCommand::Read { cmd, name, path } => {
-                    if let Some(base) = &cwd {
-                        let full = join_paths(base, &path.to_string_lossy());
-                        ParsedCommand::Read {
-                            cmd,
-                            name,
-                            path: PathBuf::from(full),
-                        }
-                    } else {
-                        ParsedCommand::Read { cmd, name, path }
-                    }
-                }
-                other => other,
-            };
-            commands.push(parsed);
+            commands.push(summarize_segment(segment, &cds));
+            writes.push(segment.writes_file);
         }
         if commands.len() > 1 {
-            commands.retain(|pc| !matches!(pc, ParsedCommand::Unknown { cmd } if cmd == "true"));
-            // Apply the same simplifications used for non-bash parsing, e.g., drop leading `cd`.
-            while let Some(next) = simplify_once(&commands) {
-                commands = next;
+            (commands, writes) = commands
+                .into_iter()
+                .zip(writes)
+                .filter(|(pc, writes_file)| {
+                    *writes_file || !matches!(pc, ParsedCommand::Unknown { cmd } if cmd == "true")
+                })
+                .unzip();
+            // Apply the same simplifications used for non-bash parsing, e.g., drop leading `cd`,
+            // but keep segments that write files so they are never summarized away.
+            while let Some(idx) = droppable_index(&commands, |idx| !writes[idx]) {
+                commands.remove(idx);
+                writes.remove(idx);
             }
         }
         if commands.len() == 1 {
@@ -1402,9 +1644,42 @@ fn is_small_formatting_command(tokens: &[String]) -> bool {
     }
 }
 
-fn drop_small_formatting_commands(mut commands: Vec<Vec<String>>) -> Vec<Vec<String>> {
-    commands.retain(|tokens| !is_small_formatting_command(tokens));
-    commands
+fn drop_small_formatting_commands(mut segments: Vec<ShellSegment>) -> Vec<ShellSegment> {
+    segments.retain(|segment| segment.writes_file || !is_small_formatting_command(&segment.words));
+    segments
+}
+
+fn is_cd_segment(segment: &ShellSegment) -> bool {
+    !segment.writes_file && segment.words.first().is_some_and(|word| word == "cd")
+}
+
+/// Summarize one segment, resolving read paths against the earlier `cd`
+/// segments that apply to it (those in the same or an enclosing subshell).
+/// Segments whose output goes to a file are always `Unknown`.
+fn summarize_segment(segment: &ShellSegment, cds: &[&ShellSegment]) -> ParsedCommand {
+    if segment.writes_file {
+        return ParsedCommand::Unknown {
+            cmd: segment.text.clone(),
+        };
+    }
+    let cwd = cds
+        .iter()
+        .filter(|cd| segment.subshells.starts_with(&cd.subshells))
+        .filter_map(|cd| cd.words.get(1))
+        .fold(None, |cwd: Option<String>, dir| {
+            Some(match cwd {
+                Some(base) => join_paths(&base, dir),
+                None => dir.clone(),
+            })
+        });
+    match (summarize_main_tokens(&segment.words), cwd) {
+        (ParsedCommand::Read { cmd, name, path }, Some(base)) => ParsedCommand::Read {
+            cmd,
+            name,
+            path: PathBuf::from(join_paths(&base, &path.to_string_lossy())),
+        },
+        (parsed, _) => parsed,
+    }
 }
 
 fn summarize_main_tokens(main_cmd: &[String]) -> ParsedCommand {
@@ -0,0 +1,555 @@
+//! Approving part of a proposed patch.
+//!
+//! A [`PatchSelection`] splits each file of an `ApplyPatchApprovalRequest`
+//! into the hunks of its unified diff so they can be toggled one by one.
+//! Additions, deletions and renames without content changes are a single
+//! unit; binary files cannot be toggled and always go with the patch.
+//! [`PatchSelection::selected_changes`] yields the changes to send back with
+//! `Op::PartialPatchApproval`, and [`patch_from_changes`] turns them into the
+//! `apply_patch` body core applies.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::protocol::FileChange;
+
+/// One file of a proposed patch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectableFile {
+    pub path: PathBuf,
+    pub change: FileChange,
+    /// Hunks of an update's unified diff, each starting with its `@@` line.
+    /// Empty when the file is a single unit.
+    pub hunks: Vec<String>,
+    /// One flag per hunk, or a single flag for a single-unit file.
+    selected: Vec<bool>,
+}
+
+impl SelectableFile {
+    fn new(path: PathBuf, change: FileChange) -> Self {
+        let hunks = match &change {
+            FileChange::Update { unified_diff, .. } => split_hunks(unified_diff),
+            FileChange::Add { .. } | FileChange::Delete { .. } => Vec::new(),
+        };
+        let selected = vec![true; hunks.len().max(1)];
+        Self {
+            path,
+            change,
+            hunks,
+            selected,
+        }
+    }
+
+    /// Binary files are applied whole or not at all, with the rest of the
+    /// patch.
+    pub fn is_toggleable(&self) -> bool {
+        !is_binary(&self.change)
+    }
+
+    pub fn is_hunk_selected(&self, hunk: usize) -> bool {
+        self.selected.get(hunk).copied().unwrap_or(false)
+    }
+
+    /// Hunks, or 1 for a single-unit file.
+    pub fn unit_count(&self) -> usize {
+        self.selected.len()
+    }
+
+    pub fn selected_count(&self) -> usize {
+        self.selected.iter().filter(|selected| **selected).count()
+    }
+
+    fn selected_change(&self) -> Option<FileChange> {
+        if self.selected_count() == 0 {
+            return None;
+        }
+        match &self.change {
+            FileChange::Update {
+                move_path,
+                unified_diff: _,
+            } if !self.hunks.is_empty() => Some(FileChange::Update {
+                unified_diff: self
+                    .hunks
+                    .iter()
+                    .zip(&self.selected)
+                    .filter(|(_, selected)| **selected)
+                    .map(|(hunk, _)| hunk.as_str())
+                    .collect(),
+                move_path: move_path.clone(),
+            }),
+            change => Some(change.clone()),
+        }
+    }
+}
+
+/// Which parts of a proposed patch the user wants applied. Everything starts
+/// out selected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchSelection {
+    files: Vec<SelectableFile>,
+}
+
+impl PatchSelection {
+    pub fn new(changes: &HashMap<PathBuf, FileChange>) -> Self {
+        let mut files: Vec<SelectableFile> = changes
+            .iter()
+            .map(|(path, change)| SelectableFile::new(path.clone(), change.clone()))
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Self { files }
+    }
+
+    /// Files ordered by path.
+    pub fn files(&self) -> &[SelectableFile] {
+        &self.files
+    }
+
+    /// Flip one hunk (or a single-unit file when `hunk` is 0).
+    pub fn toggle_hunk(&mut self, file: usize, hunk: usize) {
+        if let Some(file) = self.files.get_mut(file)
+            && file.is_toggleable()
+            && let Some(selected) = file.selected.get_mut(hunk)
+        {
+            *selected = !*selected;
+        }
+    }
+
+    /// Deselect every hunk of `file` if any is selected, otherwise select
+    /// them all.
+    pub fn toggle_file(&mut self, file: usize) {
+        if let Some(file) = self.files.get_mut(file)
+            && file.is_toggleable()
+        {
+            let select = file.selected_count() == 0;
+            file.selected.fill(select);
+        }
+    }
+
+    pub fn unit_count(&self) -> usize {
+        self.files.iter().map(SelectableFile::unit_count).sum()
+    }
+
+    pub fn selected_count(&self) -> usize {
+        self.files.iter().map(SelectableFile::selected_count).sum()
+    }
+
+    /// The proposed changes with deselected files left out and deselected
+    /// hunks removed from each unified diff.
+    pub fn selected_changes(&self) -> HashMap<PathBuf, FileChange> {
+        self.files
+            .iter()
+            .filter_map(|file| Some((file.path.clone(), file.selected_change()?)))
+            .collect()
+    }
+}
+
+/// Split a unified diff into hunks, each starting with its `@@` line. Lines
+/// before the first hunk (file headers) are dropped.
+pub fn split_hunks(unified_diff: &str) -> Vec<String> {
+    let mut hunks: Vec<String> = Vec::new();
+    for line in unified_diff.split_inclusive('\n') {
+        if line.starts_with("@@") {
+            hunks.push(line.to_string());
+        } else if let Some(hunk) = hunks.last_mut() {
+            hunk.push_str(line);
+        }
+    }
+    hunks
+}
+
+/// Check that `selected` only holds changes the user could pick from
+/// `original`: known files, unchanged additions, deletions and renames, and
+/// update hunks taken from the original diff in their original order.
+pub fn validate_selection(
+    original: &HashMap<PathBuf, FileChange>,
+    selected: &HashMap<PathBuf, FileChange>,
+) -> Result<(), String> {
+    for (path, change) in selected {
+        let display = path.display();
+        let Some(proposed) = original.get(path) else {
+            return Err(format!("{display} is not part of the proposed patch"));
+        };
+        let is_subset = match (proposed, change) {
+            (
+                FileChange::Update {
+                    unified_diff: proposed_diff,
+                    move_path: proposed_move,
+                },
+                FileChange::Update {
+                    unified_diff,
+                    move_path,
+                },
+            ) => {
+                let proposed_hunks = split_hunks(proposed_diff);
+                let hunks = split_hunks(unified_diff);
+                let mut remaining = proposed_hunks.iter();
+                move_path == proposed_move
+                    && proposed_hunks.is_empty() == hunks.is_empty()
+                    && hunks
+                        .iter()
+                        .all(|hunk| remaining.any(|proposed| proposed == hunk))
+            }
+            (proposed, change) => proposed == change,
+        };
+        if !is_subset {
+            return Err(format!(
+                "the change to {display} differs from the proposed patch"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The `apply_patch` body that makes `changes`, files ordered by path.
+/// Relative paths are resolved against `cwd`.
+///
+/// Each unified-diff hunk becomes an `@@` chunk with the same context,
+/// removed and added lines, which `apply_patch` locates by content. A rename
+/// without content changes has no hunk to carry it, so its first line is read
+/// from disk as context; an empty file is deleted and added again instead.
+pub fn patch_from_changes(changes: &HashMap<PathBuf, FileChange>, cwd: &Path) -> String {
+    let mut paths: Vec<&PathBuf> = changes.keys().collect();
+    paths.sort();
+    let mut patch = String::from("*** Begin Patch\n");
+    for path in paths {
+        let display = path.display();
+        match &changes[path] {
+            FileChange::Add { content } => push_add(&mut patch, path, content),
+            FileChange::Delete { .. } => {
+                patch.push_str(&format!("*** Delete File: {display}\n"));
+            }
+            FileChange::Update {
+                unified_diff,
+                move_path,
+            } => {
+                let hunks = split_hunks(unified_diff);
+                let unchanged = if hunks.is_empty() {
+                    first_line(&cwd.join(path))
+                } else {
+                    None
+                };
+                if hunks.is_empty()
+                    && unchanged.is_none()
+                    && let Some(move_path) = move_path
+                {
+                    patch.push_str(&format!("*** Delete File: {display}\n"));
+                    push_add(&mut patch, move_path, "");
+                    continue;
+                }
+                patch.push_str(&format!("*** Update File: {display}\n"));
+                if let Some(move_path) = move_path {
+                    patch.push_str(&format!("*** Move to: {}\n", move_path.display()));
+                }
+                if let Some(line) = unchanged {
+                    patch.push_str(&format!("@@\n {line}\n"));
+                }
+                for hunk in hunks {
+                    patch.push_str("@@\n");
+                    for line in hunk.lines().skip(1) {
+                        // `\ No newline at end of file` has no equivalent.
+                        if !line.starts_with('\\') {
+                            patch.push_str(line);
+                            patch.push('\n');
+                        }
+                    }
+                }
+            }
+        }
+    }
+    patch.push_str("*** End Patch");
+    patch
+}
+
+/// An `Add File` section. Lines are split on `\n` only, the inverse of how
+/// `apply_patch` builds the content of an added file.
+fn push_add(patch: &mut String, path: &Path, content: &str) {
+    patch.push_str(&format!("*** Add File: {}\n", path.display()));
+    for line in content.split_inclusive('\n') {
+        patch.push('+');
+        patch.push_str(line.strip_suffix('\n').unwrap_or(line));
+        patch.push('\n');
+    }
+}
+
+/// The first line of the file at `path`, used as context for a chunk that
+/// changes nothing; `None` when the file is empty or unreadable.
+fn first_line(path: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let line = content.split('\n').next()?;
+    (!content.is_empty()).then(|| line.to_string())
+}
+
+fn is_binary(change: &FileChange) -> bool {
+    match change {
+        FileChange::Add { content } | FileChange::Delete { content } => content.contains('\0'),
+        FileChange::Update { unified_diff, .. } => unified_diff.contains('\0'),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const TWO_HUNKS: &str =
+        "@@ -1,3 +1,3 @@\n foo\n-bar\n+BAR\n baz\n@@ -9,2 +9,2 @@\n qux\n-quux\n+QUUX\n";
+
+    fn update(unified_diff: &str, move_path: Option<&str>) -> FileChange {
+        FileChange::Update {
+            unified_diff: unified_diff.to_string(),
+            move_path: move_path.map(PathBuf::from),
+        }
+    }
+
+    fn changes() -> HashMap<PathBuf, FileChange> {
+        HashMap::from([
+            (
+                PathBuf::from("/repo/b.rs"),
+                update(TWO_HUNKS, Some("/repo/c.rs")),
+            ),
+            (
+                PathBuf::from("/repo/a.txt"),
+                FileChange::Add {
+                    content: "hello\n".to_string(),
+                },
+            ),
+            (
+                PathBuf::from("/repo/logo.png"),
+                FileChange::Add {
+                    content: "\u{0}PNG".to_string(),
+                },
+            ),
+        ])
+    }
+
+    #[test]
+    fn splits_update_diffs_into_hunks() {
+        let selection = PatchSelection::new(&changes());
+        let units: Vec<(&Path, usize, bool)> = selection
+            .files()
+            .iter()
+            .map(|file| (file.path.as_path(), file.unit_count(), file.is_toggleable()))
+            .collect();
+
+        assert_eq!(
+            units,
+            vec![
+                (Path::new("/repo/a.txt"), 1, true),
+                (Path::new("/repo/b.rs"), 2, true),
+                (Path::new("/repo/logo.png"), 1, false),
+            ]
+        );
+        assert_eq!(
+            selection.files()[1].hunks,
+            vec![
+                "@@ -1,3 +1,3 @@\n foo\n-bar\n+BAR\n baz\n".to_string(),
+                "@@ -9,2 +9,2 @@\n qux\n-quux\n+QUUX\n".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn deselected_hunks_and_files_are_left_out() {
+        let mut selection = PatchSelection::new(&changes());
+        selection.toggle_file(0);
+        selection.toggle_hunk(1, 0);
+        // Binary files cannot be deselected.
+        selection.toggle_file(2);
+
+        assert_eq!((selection.selected_count(), selection.unit_count()), (2, 4));
+        assert_eq!(
+            selection.selected_changes(),
+            HashMap::from([
+                (
+                    PathBuf::from("/repo/b.rs"),
+                    update("@@ -9,2 +9,2 @@\n qux\n-quux\n+QUUX\n", Some("/repo/c.rs")),
+                ),
+                (
+                    PathBuf::from("/repo/logo.png"),
+                    FileChange::Add {
+                        content: "\u{0}PNG".to_string(),
+                    },
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn renamed_file_without_selected_hunks_is_not_moved() {
+        let mut selection = PatchSelection::new(&HashMap::from([(
+            PathBuf::from("/repo/b.rs"),
+            update(TWO_HUNKS, Some("/repo/c.rs")),
+        )]));
+        selection.toggle_hunk(0, 0);
+        selection.toggle_hunk(0, 1);
+
+        assert_eq!(selection.selected_changes(), HashMap::new());
+
+        // Toggling the file back on selects every hunk again.
+        selection.toggle_file(0);
+        assert_eq!(
+            selection,
+            PatchSelection::new(&selection.selected_changes())
+        );
+    }
+
+    #[test]
+    fn patch_from_changes_turns_hunks_into_chunks() {
+        let changes = HashMap::from([
+            (
+                PathBuf::from("/repo/b.rs"),
+                update(
+                    "@@ -9,2 +9,2 @@\n qux\n-quux\n+QUUX\n\\ No newline at end of file\n",
+                    Some("/repo/c.rs"),
+                ),
+            ),
+            (
+                PathBuf::from("/repo/a.txt"),
+                FileChange::Add {
+                    content: "hello\nworld\n".to_string(),
+                },
+            ),
+            (
+                PathBuf::from("/repo/old.txt"),
+                FileChange::Delete {
+                    content: "bye\n".to_string(),
+                },
+            ),
+        ]);
+
+        assert_eq!(
+            patch_from_changes(&changes, Path::new("/repo")),
+            "*** Begin Patch\n\
+             *** Add File: /repo/a.txt\n\
+             +hello\n\
+             +world\n\
+             *** Update File: /repo/b.rs\n\
+             *** Move to: /repo/c.rs\n\
+             @@\n qux\n-quux\n+QUUX\n\
+             *** Delete File: /repo/old.txt\n\
+             *** End Patch"
+        );
+    }
+
+    #[test]
+    fn filtered_patch_applies_only_the_selected_hunks() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("lib.rs");
+        let original: String = (1..=12).map(|i| format!("line {i}\n")).collect();
+        std::fs::write(&path, &original).expect("write");
+        let modified = original
+            .replace("line 2\n", "line two\n")
+            .replace("line 11\n", "line eleven\n");
+        let unified_diff = similar::TextDiff::from_lines(&original, &modified)
+            .unified_diff()
+            .context_radius(1)
+            .to_string();
+
+        let mut selection = PatchSelection::new(&HashMap::from([(
+            path.clone(),
+            update(&unified_diff, None),
+        )]));
+        selection.toggle_hunk(0, 0);
+        let patch = patch_from_changes(&selection.selected_changes(), dir.path());
+        let command = vec!["apply_patch".to_string(), patch];
+
+        let codex_apply_patch::MaybeApplyPatchVerified::Body(action) =
+            codex_apply_patch::maybe_parse_apply_patch_verified(&command, dir.path())
+        else {
+            panic!("filtered patch should verify");
+        };
+        let Some(codex_apply_patch::ApplyPatchFileChange::Update { new_content, .. }) =
+            action.changes().get(&path)
+        else {
+            panic!("expected an update of {}", path.display());
+        };
+        assert_eq!(new_content, &original.replace("line 11\n", "line eleven\n"));
+    }
+
+    #[test]
+    fn renames_without_hunks_are_resolved_against_cwd() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(dir.path().join("old.rs"), "fn main() {}\n").expect("write");
+        std::fs::write(dir.path().join("empty.rs"), "").expect("write");
+        let changes = HashMap::from([
+            (PathBuf::from("old.rs"), update("", Some("new.rs"))),
+            (PathBuf::from("empty.rs"), update("", Some("blank.rs"))),
+        ]);
+
+        assert_eq!(
+            patch_from_changes(&changes, dir.path()),
+            "*** Begin Patch\n\
+             *** Delete File: empty.rs\n\
+             *** Add File: blank.rs\n\
+             *** Update File: old.rs\n\
+             *** Move to: new.rs\n\
+             @@\n fn main() {}\n\
+             *** End Patch"
+        );
+    }
+
+    #[test]
+    fn added_file_keeps_its_lines() {
+        let changes = HashMap::from([(
+            PathBuf::from("/repo/a.txt"),
+            FileChange::Add {
+                content: "one\n\ntwo\n".to_string(),
+            },
+        )]);
+
+        assert_eq!(
+            patch_from_changes(&changes, Path::new("/repo")),
+            "*** Begin Patch\n*** Add File: /repo/a.txt\n+one\n+\n+two\n*** End Patch"
+        );
+    }
+
+    #[test]
+    fn selection_must_come_from_the_proposed_patch() {
+        let original = changes();
+        let second_hunk = "@@ -9,2 +9,2 @@\n qux\n-quux\n+QUUX\n";
+
+        let subset = HashMap::from([(
+            PathBuf::from("/repo/b.rs"),
+            update(second_hunk, Some("/repo/c.rs")),
+        )]);
+        assert_eq!(validate_selection(&original, &subset), Ok(()));
+
+        let rejected = [
+            (
+                PathBuf::from("/repo/other.rs"),
+                update(second_hunk, None),
+                "/repo/other.rs is not part of the proposed patch",
+            ),
+            (
+                PathBuf::from("/repo/b.rs"),
+                update(second_hunk, Some("/etc/c.rs")),
+                "the change to /repo/b.rs differs from the proposed patch",
+            ),
+            (
+                PathBuf::from("/repo/b.rs"),
+                update("@@ -9,2 +9,2 @@\n qux\n-quux\n+EVIL\n", Some("/repo/c.rs")),
+                "the change to /repo/b.rs differs from the proposed patch",
+            ),
+            (
+                PathBuf::from("/repo/b.rs"),
+                update("", Some("/repo/c.rs")),
+                "the change to /repo/b.rs differs from the proposed patch",
+            ),
+            (
+                PathBuf::from("/repo/a.txt"),
+                FileChange::Add {
+                    content: "goodbye\n".to_string(),
+                },
+                "the change to /repo/a.txt differs from the proposed patch",
+            ),
+        ];
+        for (path, change, message) in rejected {
+            let selected = HashMap::from([(path, change)]);
+            assert_eq!(
+                validate_selection(&original, &selected),
+                Err(message.to_string())
+            );
+        }
+    }
+}
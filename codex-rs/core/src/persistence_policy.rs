@@ -0,0 +1,159 @@
+//! Central gate for writes under `CODEX_HOME`.
+//!
+//! Components that persist session data tag the write with a
+//! [`PersistenceKind`] and ask the session's [`PersistencePolicy`] first. In
+//! ephemeral mode (`--ephemeral`) nothing is persisted: records such as
+//! rollouts, prompt history and logs receive [`EphemeralBlocked`] and are
+//! dropped, while caches are redirected to a scratch directory that is removed
+//! once the last copy of the policy is dropped.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use strum_macros::Display;
+use tempfile::TempDir;
+
+/// What a component intends to persist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum PersistenceKind {
+    /// Session rollout files under `sessions/`.
+    Rollout,
+    /// Prompt history appended to `history.jsonl`.
+    MessageHistory,
+    /// Rate-limit snapshots kept in `rate_limit_history.jsonl`.
+    RateLimitHistory,
+    /// Debug and feedback logs.
+    Logs,
+    /// High-fidelity TUI session recordings (`CODEX_TUI_RECORD_SESSION`).
+    SessionLog,
+    /// Derived data that is safe to recompute, such as the tool inventory or
+    /// the latest-version check.
+    Caches,
+    /// Preferences the TUI writes back to `config.toml`, such as the selected
+    /// model or dismissed warnings.
+    ConfigEdits,
+}
+
+/// Returned when a write is denied by the active [`PersistencePolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("{kind} is not persisted in ephemeral mode")]
+pub struct EphemeralBlocked {
+    pub kind: PersistenceKind,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PersistencePolicy {
+    /// Present only in ephemeral mode; shared so clones of a `Config` keep the
+    /// directory alive for the whole run.
+    scratch: Option<Arc<TempDir>>,
+}
+
+impl PersistencePolicy {
+    /// Persist everything as usual.
+    pub fn persistent() -> Self {
+        Self::default()
+    }
+
+    /// Persist nothing, with a fresh scratch directory for caches.
+    pub fn ephemeral() -> std::io::Result<Self> {
+        let scratch = tempfile::Builder::new()
+            .prefix("codex-ephemeral-")
+            .tempdir()?;
+        Ok(Self {
+            scratch: Some(Arc::new(scratch)),
+        })
+    }
+
+    pub fn is_ephemeral(&self) -> bool {
+        self.scratch.is_some()
+    }
+
+    pub fn check(&self, kind: PersistenceKind) -> Result<(), EphemeralBlocked> {
+        if self.is_ephemeral() {
+            return Err(EphemeralBlocked { kind });
+        }
+        Ok(())
+    }
+
+    /// Directory that [`PersistenceKind::Caches`] are written under:
+    /// `codex_home`, or the scratch directory in ephemeral mode.
+    pub fn cache_home(&self, codex_home: &Path) -> PathBuf {
+        match &self.scratch {
+            Some(scratch) => scratch.path().to_path_buf(),
+            None => codex_home.to_path_buf(),
+        }
+    }
+}
+
+impl PartialEq for PersistencePolicy {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.scratch, &other.scratch) {
+            (None, None) => true,
+            (Some(a), Some(b)) => a.path() == b.path(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const ALL_KINDS: [PersistenceKind; 7] = [
+        PersistenceKind::Rollout,
+        PersistenceKind::MessageHistory,
+        PersistenceKind::RateLimitHistory,
+        PersistenceKind::Logs,
+        PersistenceKind::SessionLog,
+        PersistenceKind::Caches,
+        PersistenceKind::ConfigEdits,
+    ];
+
+    #[test]
+    fn persistent_allows_everything() {
+        let policy = PersistencePolicy::persistent();
+        for kind in ALL_KINDS {
+            assert_eq!(policy.check(kind), Ok(()));
+        }
+        assert_eq!(
+            policy.cache_home(Path::new("/home/me/.codex")),
+            PathBuf::from("/home/me/.codex")
+        );
+    }
+
+    #[test]
+    fn ephemeral_blocks_everything_and_redirects_caches() {
+        let policy = PersistencePolicy::ephemeral().expect("scratch dir");
+        for kind in ALL_KINDS {
+            assert_eq!(policy.check(kind), Err(EphemeralBlocked { kind }));
+        }
+        let cache_home = policy.cache_home(Path::new("/home/me/.codex"));
+        assert!(cache_home.is_dir());
+        assert!(!cache_home.starts_with("/home/me/.codex"));
+    }
+
+    #[test]
+    fn scratch_dir_is_removed_with_the_last_clone() {
+        let policy = PersistencePolicy::ephemeral().expect("scratch dir");
+        let clone = policy.clone();
+        let scratch = policy.cache_home(Path::new("/unused"));
+        drop(policy);
+        assert!(scratch.is_dir());
+        drop(clone);
+        assert!(!scratch.exists());
+    }
+
+    #[test]
+    fn blocked_error_names_kind() {
+        let err = EphemeralBlocked {
+            kind: PersistenceKind::MessageHistory,
+        };
+        assert_eq!(
+            err.to_string(),
+            "message-history is not persisted in ephemeral mode"
+        );
+    }
+}
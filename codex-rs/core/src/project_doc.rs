@@ -12,8 +12,16 @@
 //!     current working directory (inclusive) and concatenate their contents in
 //!     that order.
 //! 3.  We do **not** walk past the Git root.
+//!
+//! Files excluded by `.codexignore` (see [`Config::path_filter`]) are treated
+//! as absent, so the next candidate name in the same directory is used.
+//!
+//! Fenced `scopes` blocks are stripped from the concatenated text; see
+//! [`crate::scoped_instructions`] for how they are applied per turn.
 
 use crate::config::Config;
+use crate::scoped_instructions::ScopedInstructions;
+use crate::scoped_instructions::parse_project_doc;
 use dunce::canonicalize as normalize_path;
 use std::path::PathBuf;
 use tokio::io::AsyncReadExt;
@@ -91,7 +99,7 @@ pub async fn read_project_docs(config: &Config) -> std::io::Result<Option<String
             );
         }
 
-        let text = String::from_utf8_lossy(&data).to_string();
+        let text = parse_project_doc(&String::from_utf8_lossy(&data), &p).text;
         if !text.trim().is_empty() {
             parts.push(text);
             remaining = remaining.saturating_sub(data.len() as u64);
@@ -105,6 +113,33 @@ pub async fn read_project_docs(config: &Config) -> std::io::Result<Option<String
     }
 }
 
+/// Load the path-scoped instructions declared in any discovered project doc.
+/// Blocks that fail to parse are skipped and reported through
+/// [`ScopedInstructions::warnings`].
+pub(crate) async fn get_scoped_instructions(config: &Config) -> ScopedInstructions {
+    let mut scoped = ScopedInstructions::default();
+    if config.project_doc_max_bytes == 0 {
+        return scoped;
+    }
+
+    let paths = match discover_project_doc_paths(config) {
+        Ok(paths) => paths,
+        Err(e) => {
+            error!("error trying to find project doc: {e:#}");
+            return scoped;
+        }
+    };
+
+    for p in paths {
+        match tokio::fs::read(&p).await {
+            Ok(data) => scoped.extend(parse_project_doc(&String::from_utf8_lossy(&data), &p)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => error!("error reading project doc {}: {e:#}", p.display()),
+        }
+    }
+    scoped
+}
+
 /// Discover the list of AGENTS.md files using the same search rules as
 /// `read_project_docs`, but return the file paths instead of concatenated
 /// contents. The list is ordered from repository root to the current working
@@ -160,6 +195,9 @@ pub fn discover_project_doc_paths(config: &Config) -> std::io::Result<Vec<PathBu
     for d in search_dirs {
         for name in &candidate_filenames {
             let candidate = d.join(name);
+            if config.path_filter.is_excluded(&candidate, false) {
+                continue;
+            }
             match std::fs::symlink_metadata(&candidate) {
                 Ok(md) => {
                     let ft = md.file_type();
@@ -200,6 +238,7 @@ mod tests {
     use super::*;
     use crate::config::ConfigOverrides;
     use crate::config::ConfigToml;
+    use codex_file_search::PathFilter;
     use std::fs;
     use tempfile::TempDir;
 
@@ -447,4 +486,60 @@ mod tests {
                 .eq(DEFAULT_PROJECT_DOC_FILENAME)
         );
     }
+
+    /// Docs excluded by `.codexignore` are skipped; negated patterns keep
+    /// the ones they match.
+    #[tokio::test]
+    async fn codexignore_excludes_docs() {
+        let repo = tempfile::tempdir().expect("tempdir");
+        fs::write(
+            repo.path().join(".git"),
+            "gitdir: /path/to/actual/git/dir\n",
+        )
+        .unwrap();
+        fs::write(repo.path().join("AGENTS.md"), "root doc").unwrap();
+        let nested = repo.path().join("workspace/crate_a");
+        std::fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join("AGENTS.md"), "crate doc").unwrap();
+        fs::write(nested.join("EXAMPLE.md"), "example doc").unwrap();
+
+        let mut cfg = make_config_with_fallback(&repo, 4096, None, &["EXAMPLE.md"]);
+        cfg.cwd = nested;
+        cfg.path_filter = PathFilter::parse(
+            repo.path(),
+            "AGENTS.md\n!workspace/crate_a/AGENTS.md\n/AGENTS.md\n",
+        )
+        .expect("valid patterns");
+
+        let res = get_user_instructions(&cfg).await.expect("doc expected");
+        assert_eq!(res, "crate doc");
+
+        cfg.path_filter = PathFilter::parse(repo.path(), "AGENTS.md\n").expect("valid patterns");
+        let res = get_user_instructions(&cfg)
+            .await
+            .expect("fallback expected");
+        assert_eq!(res, "example doc");
+    }
+
+    /// Scoped blocks are kept out of the always-on instructions and loaded
+    /// separately so they can be injected only when matching files are touched.
+    #[tokio::test]
+    async fn scoped_blocks_are_loaded_separately() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        fs::write(
+            tmp.path().join("AGENTS.md"),
+            "general\n```scopes\n[[scope]]\npaths = [\"migrations/\"]\ninstructions = \"regen schema\"\n```\n",
+        )
+        .unwrap();
+
+        let cfg = make_config(&tmp, 4096, None);
+
+        let res = get_user_instructions(&cfg).await.expect("doc expected");
+        assert_eq!(res, "general");
+
+        let scoped = get_scoped_instructions(&cfg).await;
+        assert_eq!(scoped.scopes().len(), 1);
+        assert_eq!(scoped.scopes()[0].text, "regen schema");
+        assert!(scoped.warnings().is_empty());
+    }
 }
@@ -0,0 +1,269 @@
+//! Rolling record of the rate-limit snapshots received from providers.
+//!
+//! Every snapshot is appended as one JSON line to
+//! `~/.codex/rate_limit_history.jsonl`:
+//!
+//! ````text
+//! {"ts":<unix_seconds>,"provider":"<id>","primary":{...},"secondary":{...}}
+//! ````
+//!
+//! The file is a ring: whenever a write finds more than [`MAX_SAMPLES`]
+//! entries, or entries older than [`RETENTION`], it is rewritten with only the
+//! newest samples inside the retention window. Frontends read it back with
+//! [`load_samples`] to show how usage evolved over the day.
+
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::persistence_policy::PersistenceKind;
+use crate::protocol::RateLimitSnapshot;
+use crate::protocol::RateLimitWindow;
+
+/// Filename that stores the rate-limit history inside `~/.codex`.
+pub const RATE_LIMIT_HISTORY_FILENAME: &str = "rate_limit_history.jsonl";
+
+/// Most samples kept in the file.
+pub const MAX_SAMPLES: usize = 2_000;
+
+/// Samples older than this are dropped.
+pub const RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+const MAX_RETRIES: usize = 10;
+const RETRY_SLEEP: Duration = Duration::from_millis(100);
+
+/// One rate-limit snapshot as received from `provider`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitSample {
+    /// Unix timestamp (seconds) at which the snapshot was received.
+    pub ts: i64,
+    /// Id of the model provider that reported the snapshot.
+    pub provider: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary: Option<RateLimitWindow>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secondary: Option<RateLimitWindow>,
+}
+
+pub fn history_path(codex_home: &Path) -> PathBuf {
+    codex_home.join(RATE_LIMIT_HISTORY_FILENAME)
+}
+
+/// Append `snapshot` for the configured provider, unless the session is
+/// ephemeral or the snapshot carries no window.
+pub(crate) async fn record_snapshot(config: &Config, snapshot: &RateLimitSnapshot) {
+    if snapshot.primary.is_none() && snapshot.secondary.is_none() {
+        return;
+    }
+    if config
+        .persistence
+        .check(PersistenceKind::RateLimitHistory)
+        .is_err()
+    {
+        return;
+    }
+    let sample = RateLimitSample {
+        ts: chrono::Utc::now().timestamp(),
+        provider: config.model_provider_id.clone(),
+        primary: snapshot.primary.clone(),
+        secondary: snapshot.secondary.clone(),
+    };
+    let path = history_path(&config.codex_home);
+    let result =
+        tokio::task::spawn_blocking(move || append_sample(&path, &sample, MAX_SAMPLES)).await;
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => tracing::warn!("failed to record rate-limit snapshot: {err}"),
+        Err(err) => tracing::warn!("rate-limit history task failed: {err}"),
+    }
+}
+
+/// Samples recorded within the retention window, oldest first. A missing or
+/// unreadable file yields no samples; malformed lines are skipped.
+pub fn load_samples(codex_home: &Path) -> Vec<RateLimitSample> {
+    let Ok(contents) = std::fs::read_to_string(history_path(codex_home)) else {
+        return Vec::new();
+    };
+    let cutoff = retention_cutoff(chrono::Utc::now().timestamp());
+    parse_samples(&contents)
+        .into_iter()
+        .filter(|sample| sample.ts >= cutoff)
+        .collect()
+}
+
+/// Append `sample` under an advisory lock, rewriting the file without expired
+/// or excess samples when needed.
+fn append_sample(path: &Path, sample: &RateLimitSample, max_samples: usize) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(sample).map_err(std::io::Error::other)?;
+    line.push('\n');
+
+    let mut file = OpenOptions::new()
+        .read(true)
+        .append(true)
+        .create(true)
+        .open(path)?;
+    lock_with_retries(&file)?;
+    file.write_all(line.as_bytes())?;
+    prune_locked(&mut file, sample.ts, max_samples)?;
+    file.flush()
+}
+
+/// Rewrite the locked `file` if it holds samples beyond the retention window
+/// or more than `max_samples` entries, keeping the newest ones.
+fn prune_locked(file: &mut File, now: i64, max_samples: usize) -> std::io::Result<()> {
+    let mut contents = String::new();
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_string(&mut contents)?;
+    let samples = parse_samples(&contents);
+    let cutoff = retention_cutoff(now);
+    let line_count = contents.lines().count();
+    let has_expired = samples.first().is_some_and(|sample| sample.ts < cutoff);
+    if line_count <= max_samples && samples.len() == line_count && !has_expired {
+        return Ok(());
+    }
+
+    let kept: Vec<&RateLimitSample> = samples
+        .iter()
+        .filter(|sample| sample.ts >= cutoff)
+        .collect();
+    let skip = kept.len().saturating_sub(max_samples);
+    let mut rewritten = String::new();
+    for sample in &kept[skip..] {
+        rewritten.push_str(&serde_json::to_string(sample).map_err(std::io::Error::other)?);
+        rewritten.push('\n');
+    }
+    // The file is opened for appending, so writes after truncation land at
+    // the start again.
+    file.set_len(0)?;
+    file.write_all(rewritten.as_bytes())
+}
+
+fn lock_with_retries(file: &File) -> std::io::Result<()> {
+    for _ in 0..MAX_RETRIES {
+        match file.try_lock() {
+            Ok(()) => return Ok(()),
+            Err(std::fs::TryLockError::WouldBlock) => std::thread::sleep(RETRY_SLEEP),
+            Err(std::fs::TryLockError::Error(err)) => return Err(err),
+        }
+    }
+    Err(std::io::Error::new(
+        std::io::ErrorKind::WouldBlock,
+        "could not acquire exclusive lock on rate-limit history after multiple attempts",
+    ))
+}
+
+fn parse_samples(contents: &str) -> Vec<RateLimitSample> {
+    contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+fn retention_cutoff(now: i64) -> i64 {
+    now.saturating_sub(RETENTION.as_secs() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn sample(ts: i64, used_percent: f64) -> RateLimitSample {
+        RateLimitSample {
+            ts,
+            provider: "openai".to_string(),
+            primary: Some(RateLimitWindow {
+                used_percent,
+                window_minutes: Some(300),
+                resets_at: Some(ts + 3_600),
+            }),
+            secondary: None,
+        }
+    }
+
+    fn read_back(path: &Path) -> Vec<RateLimitSample> {
+        parse_samples(&std::fs::read_to_string(path).expect("read history"))
+    }
+
+    #[test]
+    fn ring_keeps_only_the_newest_samples() {
+        let home = TempDir::new().expect("tempdir");
+        let path = history_path(home.path());
+        let now = chrono::Utc::now().timestamp();
+
+        for i in 0..5 {
+            append_sample(&path, &sample(now + i, i as f64), 3).expect("append");
+        }
+
+        assert_eq!(
+            read_back(&path),
+            vec![
+                sample(now + 2, 2.0),
+                sample(now + 3, 3.0),
+                sample(now + 4, 4.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn expired_and_malformed_samples_are_pruned() {
+        let home = TempDir::new().expect("tempdir");
+        let path = history_path(home.path());
+        let now = chrono::Utc::now().timestamp();
+        let expired = sample(now - RETENTION.as_secs() as i64 - 1, 10.0);
+        let recent = sample(now - 60, 20.0);
+        std::fs::write(
+            &path,
+            format!(
+                "{}\nnot json\n{}\n",
+                serde_json::to_string(&expired).expect("serialize"),
+                serde_json::to_string(&recent).expect("serialize"),
+            ),
+        )
+        .expect("seed history");
+
+        append_sample(&path, &sample(now, 30.0), MAX_SAMPLES).expect("append");
+
+        assert_eq!(read_back(&path), vec![recent, sample(now, 30.0)]);
+        assert_eq!(
+            std::fs::read_to_string(&path)
+                .expect("read")
+                .lines()
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn load_samples_skips_expired_entries() {
+        let home = TempDir::new().expect("tempdir");
+        let now = chrono::Utc::now().timestamp();
+        let expired = sample(now - RETENTION.as_secs() as i64 - 1, 10.0);
+        let recent = sample(now - 60, 20.0);
+        std::fs::write(
+            history_path(home.path()),
+            format!(
+                "{}\n{}\n",
+                serde_json::to_string(&expired).expect("serialize"),
+                serde_json::to_string(&recent).expect("serialize"),
+            ),
+        )
+        .expect("seed history");
+
+        assert_eq!(load_samples(home.path()), vec![recent]);
+    }
+}
@@ -0,0 +1,488 @@
+//! Tell a resumed session what happened to the repository while it was away.
+//!
+//! Each finished task records the repository state in the rollout: `HEAD`,
+//! the branch, the files with uncommitted changes and a content hash of every
+//! file the session has changed so far. On resume the latest record is
+//! compared with the working tree to list new commits, session files that were
+//! modified or deleted by someone else, and uncommitted work that has since
+//! been committed or discarded. Only local git commands are used; outside a
+//! git repository, or when the recorded commit is no longer available (e.g. a
+//! shallow clone), the briefing carries whatever could still be determined.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use codex_protocol::protocol::RepoStateItem;
+use codex_protocol::protocol::ResumeBriefingEvent;
+use codex_protocol::protocol::TouchedFile;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::git_info::current_branch_name;
+use crate::git_info::run_git_command_with_timeout;
+
+/// Upper bound on the length of the note injected into the model context.
+pub const MAX_NOTE_CHARS: usize = 600;
+
+/// Number of new commit subjects included in a briefing.
+const MAX_COMMIT_SUBJECTS: usize = 10;
+
+/// Number of commit subjects repeated in the model note.
+const NOTE_COMMIT_SUBJECTS: usize = 3;
+
+/// Snapshot the repository state at `cwd` along with the current contents of
+/// the files the session has `touched`.
+pub async fn capture_repo_state(cwd: &Path, touched: &BTreeSet<PathBuf>) -> RepoStateItem {
+    let head = resolve_head(cwd).await;
+    let branch = current_branch_name(cwd).await;
+    let dirty_files = dirty_files(cwd).await.unwrap_or_default();
+    let mut touched_files = Vec::with_capacity(touched.len());
+    for path in touched {
+        touched_files.push(TouchedFile {
+            path: path.clone(),
+            sha256: content_hash(path).await,
+        });
+    }
+    RepoStateItem {
+        cwd: cwd.to_path_buf(),
+        head,
+        branch,
+        dirty_files,
+        touched_files,
+    }
+}
+
+/// Compare `recorded` with the repository as it is now. Returns `None` when
+/// nothing relevant changed.
+pub async fn compute_resume_briefing(recorded: &RepoStateItem) -> Option<ResumeBriefingEvent> {
+    let cwd = recorded.cwd.as_path();
+    let head = resolve_head(cwd).await;
+    let mut briefing = ResumeBriefingEvent {
+        previous_branch: recorded.branch.clone(),
+        branch: current_branch_name(cwd).await,
+        previous_head: recorded.head.clone(),
+        head,
+        ..Default::default()
+    };
+
+    match (&briefing.previous_head, &briefing.head) {
+        (Some(previous), Some(head)) if previous == head => briefing.new_commits = Some(0),
+        (Some(previous), Some(_)) => {
+            if let Some((count, subjects)) = commits_since(cwd, previous).await {
+                briefing.new_commits = Some(count);
+                briefing.commit_subjects = subjects;
+            }
+        }
+        _ => {}
+    }
+
+    for touched in &recorded.touched_files {
+        let display = display_path(cwd, &touched.path);
+        match (&touched.sha256, content_hash(&touched.path).await) {
+            (Some(recorded), Some(current)) if *recorded != current => {
+                briefing.modified_files.push(display);
+            }
+            (Some(_), None) => briefing.deleted_files.push(display),
+            (None, Some(_)) => briefing.modified_files.push(display),
+            _ => {}
+        }
+    }
+
+    if !recorded.dirty_files.is_empty()
+        && let Some(dirty_now) = dirty_files(cwd).await
+    {
+        briefing.cleaned_files = recorded
+            .dirty_files
+            .iter()
+            .filter(|path| !dirty_now.contains(path))
+            .cloned()
+            .collect();
+    }
+
+    let unchanged = briefing.previous_head == briefing.head
+        && briefing.previous_branch == briefing.branch
+        && briefing.modified_files.is_empty()
+        && briefing.deleted_files.is_empty()
+        && briefing.cleaned_files.is_empty();
+    (!unchanged).then_some(briefing)
+}
+
+/// Compact form of `briefing` for the environment context, at most
+/// [`MAX_NOTE_CHARS`] characters long.
+pub fn resume_briefing_note(briefing: &ResumeBriefingEvent) -> String {
+    let note = format!(
+        "since this session was last active, {}; re-read files before editing them",
+        resume_briefing_summary(briefing)
+    );
+    if note.chars().count() <= MAX_NOTE_CHARS {
+        return note;
+    }
+    let mut truncated: String = note.chars().take(MAX_NOTE_CHARS - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// One-line summary of `briefing`, e.g. `3 new commits on main (latest: …);
+/// files this session changed were deleted: old.rs`.
+pub fn resume_briefing_summary(briefing: &ResumeBriefingEvent) -> String {
+    let ResumeBriefingEvent {
+        previous_branch,
+        branch,
+        previous_head,
+        head,
+        new_commits,
+        commit_subjects,
+        modified_files,
+        deleted_files,
+        cleaned_files,
+    } = briefing;
+
+    let mut parts = Vec::new();
+    if previous_branch != branch {
+        let name =
+            |branch: &Option<String>| branch.as_deref().unwrap_or("a detached HEAD").to_string();
+        parts.push(format!(
+            "the checkout moved from {} to {}",
+            name(previous_branch),
+            name(branch)
+        ));
+    }
+    if previous_head != head {
+        match (new_commits, previous_head, head) {
+            (Some(count), _, _) if *count > 0 => {
+                let commits = if *count == 1 { "commit" } else { "commits" };
+                let on = branch.as_deref().unwrap_or("HEAD");
+                let mut part = format!("{count} new {commits} on {on}");
+                if !commit_subjects.is_empty() {
+                    let shown: Vec<&str> = commit_subjects
+                        .iter()
+                        .take(NOTE_COMMIT_SUBJECTS)
+                        .map(String::as_str)
+                        .collect();
+                    part.push_str(&format!(" (latest: {})", shown.join("; ")));
+                }
+                parts.push(part);
+            }
+            (Some(_), Some(previous), Some(head)) => parts.push(format!(
+                "HEAD moved from {} to {} without new commits",
+                short_sha(previous),
+                short_sha(head)
+            )),
+            (None, Some(previous), Some(head)) => parts.push(format!(
+                "HEAD moved from {} to {} (history not available locally)",
+                short_sha(previous),
+                short_sha(head)
+            )),
+            _ => {}
+        }
+    }
+    if !modified_files.is_empty() {
+        parts.push(format!(
+            "files this session changed were modified since: {}",
+            modified_files.join(", ")
+        ));
+    }
+    if !deleted_files.is_empty() {
+        parts.push(format!(
+            "files this session changed were deleted: {}",
+            deleted_files.join(", ")
+        ));
+    }
+    if !cleaned_files.is_empty() {
+        parts.push(format!(
+            "no longer uncommitted: {}",
+            cleaned_files.join(", ")
+        ));
+    }
+
+    parts.join("; ")
+}
+
+fn short_sha(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
+
+fn display_path(cwd: &Path, path: &Path) -> String {
+    path.strip_prefix(cwd)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+async fn resolve_head(cwd: &Path) -> Option<String> {
+    let out = run_git_command_with_timeout(&["rev-parse", "--verify", "HEAD"], cwd).await?;
+    if !out.status.success() {
+        return None;
+    }
+    let head = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    (!head.is_empty()).then_some(head)
+}
+
+/// Number and newest subjects of the commits in `previous..HEAD`, or `None`
+/// when `previous` is not available locally.
+async fn commits_since(cwd: &Path, previous: &str) -> Option<(usize, Vec<String>)> {
+    let range = format!("{previous}..HEAD");
+    let count = run_git_command_with_timeout(&["rev-list", "--count", &range], cwd).await?;
+    if !count.status.success() {
+        return None;
+    }
+    let count: usize = String::from_utf8_lossy(&count.stdout).trim().parse().ok()?;
+
+    let limit = MAX_COMMIT_SUBJECTS.to_string();
+    let subjects = match run_git_command_with_timeout(
+        &["log", "--format=%s", "-n", &limit, &range],
+        cwd,
+    )
+    .await
+    {
+        Some(out) if out.status.success() => String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|subject| !subject.is_empty())
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    };
+    Some((count, subjects))
+}
+
+/// Paths with uncommitted changes, relative to the repository root, or `None`
+/// outside a git repository.
+async fn dirty_files(cwd: &Path) -> Option<Vec<String>> {
+    let out = run_git_command_with_timeout(&["status", "--porcelain=v1", "-z"], cwd).await?;
+    if !out.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&out.stdout);
+    let mut entries = text.split('\0');
+    let mut files = Vec::new();
+    while let Some(entry) = entries.next() {
+        let (Some(status), Some(path)) = (entry.get(..2), entry.get(3..)) else {
+            continue;
+        };
+        files.push(path.to_string());
+        // Renames and copies are followed by their source path.
+        if status.contains(['R', 'C']) {
+            entries.next();
+        }
+    }
+    Some(files)
+}
+
+async fn content_hash(path: &Path) -> Option<String> {
+    let contents = tokio::fs::read(path).await.ok()?;
+    Some(format!("{:x}", Sha256::digest(&contents)))
+}
+
+#[derive(Debug, Default)]
+struct RepoStateTracking {
+    /// Every file the session has changed, across tasks and resumes.
+    touched: BTreeSet<PathBuf>,
+    /// Resume briefing the model has not been given yet.
+    unrecorded: Option<ResumeBriefingEvent>,
+}
+
+/// Per-session bookkeeping for the repository state records.
+#[derive(Debug, Default)]
+pub(crate) struct SessionRepoState {
+    state: Mutex<RepoStateTracking>,
+}
+
+impl SessionRepoState {
+    /// Continue from the state recorded by the session being resumed.
+    pub(crate) fn restore(&self, recorded: &RepoStateItem) {
+        if let Ok(mut state) = self.state.lock() {
+            state
+                .touched
+                .extend(recorded.touched_files.iter().map(|file| file.path.clone()));
+        }
+    }
+
+    /// Adds `paths` to the session's touched files and returns all of them.
+    pub(crate) fn add_touched(&self, paths: Vec<PathBuf>) -> BTreeSet<PathBuf> {
+        let Ok(mut state) = self.state.lock() else {
+            return BTreeSet::new();
+        };
+        state.touched.extend(paths);
+        state.touched.clone()
+    }
+
+    pub(crate) fn set_unrecorded(&self, briefing: ResumeBriefingEvent) {
+        if let Ok(mut state) = self.state.lock() {
+            state.unrecorded = Some(briefing);
+        }
+    }
+
+    /// The resume briefing if the model has not been given it yet.
+    pub(crate) fn take_unrecorded(&self) -> Option<ResumeBriefingEvent> {
+        self.state.lock().ok()?.unrecorded.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core_test_support::skip_if_sandbox;
+    use pretty_assertions::assert_eq;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(cwd: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .env("GIT_CONFIG_GLOBAL", "/dev/null")
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .args([
+                "-c",
+                "user.name=Test User",
+                "-c",
+                "user.email=test@example.com",
+            ])
+            .args(args)
+            .current_dir(cwd)
+            .status()
+            .expect("run git");
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    fn write(path: &Path, contents: &str) {
+        std::fs::write(path, contents).expect("write file");
+    }
+
+    /// A repository on `main` whose session changed `src.txt` and `gone.txt`
+    /// and left `notes.txt` uncommitted.
+    struct Fixture {
+        _tmp: TempDir,
+        repo: PathBuf,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let tmp = TempDir::new().expect("tempdir");
+            let repo = std::fs::canonicalize(tmp.path()).expect("canonicalize");
+            git(&repo, &["init", "-b", "main"]);
+            write(&repo.join("src.txt"), "one\n");
+            write(&repo.join("gone.txt"), "doomed\n");
+            write(&repo.join("notes.txt"), "draft\n");
+            git(&repo, &["add", "."]);
+            git(&repo, &["commit", "-m", "initial"]);
+            Self { _tmp: tmp, repo }
+        }
+
+        async fn session_end_state(&self) -> RepoStateItem {
+            write(&self.repo.join("src.txt"), "one\nsession edit\n");
+            write(&self.repo.join("notes.txt"), "draft\nmore\n");
+            let touched = BTreeSet::from([self.repo.join("src.txt"), self.repo.join("gone.txt")]);
+            capture_repo_state(&self.repo, &touched).await
+        }
+    }
+
+    #[tokio::test]
+    async fn unchanged_repository_has_no_briefing() {
+        skip_if_sandbox!();
+        let fixture = Fixture::new();
+        let recorded = fixture.session_end_state().await;
+
+        assert_eq!(
+            recorded.dirty_files,
+            vec!["notes.txt".to_string(), "src.txt".to_string()]
+        );
+        assert_eq!(compute_resume_briefing(&recorded).await, None);
+    }
+
+    #[tokio::test]
+    async fn reports_upstream_commits_and_conflicting_edits() {
+        skip_if_sandbox!();
+        let fixture = Fixture::new();
+        let repo = &fixture.repo;
+        let recorded = fixture.session_end_state().await;
+
+        // A teammate lands work on top of the session's changes: the session's
+        // edit to src.txt is committed and then changed again, gone.txt is
+        // removed and notes.txt is committed as is.
+        git(repo, &["commit", "-am", "Land session work"]);
+        write(&repo.join("src.txt"), "one\nteammate edit\n");
+        git(repo, &["rm", "-q", "gone.txt"]);
+        git(repo, &["commit", "-am", "Rework src and drop gone"]);
+
+        assert_eq!(
+            compute_resume_briefing(&recorded).await,
+            Some(ResumeBriefingEvent {
+                previous_branch: Some("main".to_string()),
+                branch: Some("main".to_string()),
+                previous_head: recorded.head.clone(),
+                head: resolve_head(repo).await,
+                new_commits: Some(2),
+                commit_subjects: vec![
+                    "Rework src and drop gone".to_string(),
+                    "Land session work".to_string(),
+                ],
+                modified_files: vec!["src.txt".to_string()],
+                deleted_files: vec!["gone.txt".to_string()],
+                cleaned_files: vec!["notes.txt".to_string(), "src.txt".to_string()],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_history_degrades_to_partial_briefing() {
+        skip_if_sandbox!();
+        let fixture = Fixture::new();
+        let mut recorded = fixture.session_end_state().await;
+        // As if the recorded commit was never fetched into this clone.
+        recorded.head = Some("0123456789abcdef0123456789abcdef01234567".to_string());
+        write(&fixture.repo.join("src.txt"), "rewritten\n");
+
+        let briefing = compute_resume_briefing(&recorded).await.expect("briefing");
+        assert_eq!(
+            (briefing.new_commits, briefing.modified_files.clone()),
+            (None, vec!["src.txt".to_string()])
+        );
+        let head = briefing.head.clone().expect("head");
+        assert_eq!(
+            resume_briefing_note(&briefing),
+            format!(
+                "since this session was last active, HEAD moved from 0123456 to {head} (history not available locally); files this session changed were modified since: src.txt; re-read files before editing them",
+                head = short_sha(&head)
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn works_outside_git() {
+        let dir = TempDir::new().expect("tempdir");
+        let file = dir.path().join("plan.md");
+        write(&file, "v1");
+        let recorded = capture_repo_state(dir.path(), &BTreeSet::from([file.clone()])).await;
+        assert_eq!(recorded.head, None);
+
+        write(&file, "v2");
+        assert_eq!(
+            compute_resume_briefing(&recorded).await,
+            Some(ResumeBriefingEvent {
+                modified_files: vec!["plan.md".to_string()],
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn note_is_capped() {
+        let briefing = ResumeBriefingEvent {
+            branch: Some("main".to_string()),
+            previous_head: Some("a".repeat(40)),
+            head: Some("b".repeat(40)),
+            new_commits: Some(250),
+            commit_subjects: (0..10).map(|n| format!("Commit number {n}")).collect(),
+            modified_files: (0..200).map(|n| format!("src/module_{n}.rs")).collect(),
+            ..Default::default()
+        };
+        let note = resume_briefing_note(&briefing);
+        assert_eq!(note.chars().count(), MAX_NOTE_CHARS);
+        assert!(note.starts_with(
+            "since this session was last active, 250 new commits on main (latest: Commit number 0; Commit number 1; Commit number 2); files this session changed were modified since: src/module_0.rs, "
+        ));
+        assert!(note.ends_with('…'));
+    }
+}
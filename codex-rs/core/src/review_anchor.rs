@@ -0,0 +1,302 @@
+//! Keep review findings pointing at the right code while the agent edits.
+//!
+//! The reviewer reports each finding as a line range, which goes stale as
+//! soon as anything above it changes. When a review finishes, every finding
+//! gets a [`ReviewAnchor`]: a hash of the flagged lines plus a few lines of
+//! context on either side. [`resolve_anchor`] later finds the finding in the
+//! current file contents, or reports it as stale when neither the flagged
+//! lines nor their surroundings are left.
+
+use codex_protocol::protocol::ReviewAnchor;
+use codex_protocol::protocol::ReviewCodeLocation;
+use codex_protocol::protocol::ReviewFinding;
+use codex_protocol::protocol::ReviewLineRange;
+use sha1::Digest;
+use sha1::Sha1;
+
+/// Lines of context stored on each side of a finding.
+const ANCHOR_CONTEXT_LINES: usize = 3;
+
+/// How far from its recorded position a finding is searched for.
+const REANCHOR_FUZZ_LINES: usize = 500;
+
+/// How many lines a finding may grow by and still be found by its context.
+const MAX_GROWTH_LINES: usize = 50;
+
+/// Where a finding points in the current contents of its file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnchorResolution {
+    /// The finding's current range, which may equal the recorded one.
+    Current(ReviewLineRange),
+    /// The flagged code and its surroundings are gone.
+    Stale,
+}
+
+/// Attach an anchor to every finding whose file can be read.
+pub fn anchor_findings(findings: &mut [ReviewFinding]) {
+    for finding in findings {
+        let location = &mut finding.code_location;
+        if let Ok(contents) = std::fs::read_to_string(&location.absolute_file_path) {
+            location.anchor = anchor_location(location, &contents);
+        }
+    }
+}
+
+/// Build the anchor for `location` from the file contents it refers to.
+/// Returns `None` when the recorded range is not inside the file.
+pub fn anchor_location(location: &ReviewCodeLocation, contents: &str) -> Option<ReviewAnchor> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let (start, len) = range_bounds(&location.line_range)?;
+    if start + len > lines.len() {
+        return None;
+    }
+    let end = start + len;
+    Some(ReviewAnchor {
+        context_before: owned(&lines[start.saturating_sub(ANCHOR_CONTEXT_LINES)..start]),
+        context_after: owned(&lines[end..(end + ANCHOR_CONTEXT_LINES).min(lines.len())]),
+        content_hash: hash_lines(&lines[start..end]),
+    })
+}
+
+/// Locate `finding` in its file as it is on disk now. Findings without an
+/// anchor keep their recorded range; anchored findings whose file is gone
+/// are stale.
+pub fn resolve_finding(finding: &ReviewFinding) -> AnchorResolution {
+    let location = &finding.code_location;
+    match std::fs::read_to_string(&location.absolute_file_path) {
+        Ok(contents) => resolve_anchor(location, &contents),
+        Err(_) if location.anchor.is_some() => AnchorResolution::Stale,
+        Err(_) => AnchorResolution::Current(location.line_range.clone()),
+    }
+}
+
+/// Relocate `location` within `contents`.
+///
+/// The flagged lines are first looked for unchanged, preferring the match
+/// whose context also agrees and then the one nearest the recorded position.
+/// Failing that, the finding is found between its surrounding context, which
+/// covers edits inside the flagged lines. Otherwise it is stale.
+pub fn resolve_anchor(location: &ReviewCodeLocation, contents: &str) -> AnchorResolution {
+    let Some(anchor) = &location.anchor else {
+        return AnchorResolution::Current(location.line_range.clone());
+    };
+    let Some((recorded, len)) = range_bounds(&location.line_range) else {
+        return AnchorResolution::Stale;
+    };
+    let lines: Vec<&str> = contents.lines().collect();
+    let lo = recorded.saturating_sub(REANCHOR_FUZZ_LINES);
+    let hi = recorded + REANCHOR_FUZZ_LINES;
+
+    let unchanged = (lo..=hi.min(lines.len().saturating_sub(len)))
+        .filter(|&start| start + len <= lines.len())
+        .filter(|&start| hash_lines(&lines[start..start + len]) == anchor.content_hash)
+        .max_by_key(|&start| {
+            let context = usize::from(context_before_matches(&lines, start, anchor))
+                + usize::from(context_after_matches(&lines, start + len, anchor));
+            (context, std::cmp::Reverse(start.abs_diff(recorded)))
+        });
+    if let Some(start) = unchanged {
+        return AnchorResolution::Current(line_range(start, len));
+    }
+
+    let befores: Vec<usize> = (lo..=hi.min(lines.len()))
+        .filter(|&start| context_before_matches(&lines, start, anchor))
+        .collect();
+    let afters: Vec<usize> = (lo..=(hi + len + MAX_GROWTH_LINES).min(lines.len()))
+        .filter(|&end| context_after_matches(&lines, end, anchor))
+        .collect();
+    let edited = befores
+        .iter()
+        .filter_map(|&start| {
+            afters
+                .iter()
+                .find(|&&end| end > start && end - start <= len + MAX_GROWTH_LINES)
+                .map(|&end| (start, end))
+        })
+        .min_by_key(|(start, _)| start.abs_diff(recorded));
+    match edited {
+        Some((start, end)) => AnchorResolution::Current(line_range(start, end - start)),
+        None => AnchorResolution::Stale,
+    }
+}
+
+/// Zero-based start and length of an inclusive, one-based range.
+fn range_bounds(range: &ReviewLineRange) -> Option<(usize, usize)> {
+    if range.start == 0 || range.end < range.start {
+        return None;
+    }
+    Some((
+        range.start as usize - 1,
+        (range.end - range.start) as usize + 1,
+    ))
+}
+
+fn line_range(start: usize, len: usize) -> ReviewLineRange {
+    ReviewLineRange {
+        start: u32::try_from(start + 1).unwrap_or(u32::MAX),
+        end: u32::try_from(start + len).unwrap_or(u32::MAX),
+    }
+}
+
+/// Whether the stored leading context ends right before line `start`. An
+/// empty context only matches at the top of the file.
+fn context_before_matches(lines: &[&str], start: usize, anchor: &ReviewAnchor) -> bool {
+    let context = &anchor.context_before;
+    if context.is_empty() {
+        return start == 0;
+    }
+    start >= context.len() && lines_equal(&lines[start - context.len()..start], context)
+}
+
+/// Whether the stored trailing context starts at line `end`. An empty
+/// context only matches at the end of the file.
+fn context_after_matches(lines: &[&str], end: usize, anchor: &ReviewAnchor) -> bool {
+    let context = &anchor.context_after;
+    if context.is_empty() {
+        return end == lines.len();
+    }
+    end + context.len() <= lines.len() && lines_equal(&lines[end..end + context.len()], context)
+}
+
+fn lines_equal(lines: &[&str], expected: &[String]) -> bool {
+    lines
+        .iter()
+        .zip(expected)
+        .all(|(line, expected)| line.trim_end() == expected.trim_end())
+}
+
+fn owned(lines: &[&str]) -> Vec<String> {
+    lines.iter().map(ToString::to_string).collect()
+}
+
+fn hash_lines(lines: &[&str]) -> String {
+    let mut hasher = Sha1::new();
+    for line in lines {
+        hasher.update(line.trim_end().as_bytes());
+        hasher.update(b"\n");
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    const FIXTURE: &str = "\
+use std::io;
+
+fn main() {
+    let value = compute();
+    println!(\"{value}\");
+}
+
+fn compute() -> u32 {
+    let a = 1;
+    let b = 2;
+    a + b
+}
+
+fn unrelated() {}
+";
+
+    /// Location of `let a = 1;` through `a + b` in [`FIXTURE`], anchored.
+    fn anchored_location() -> ReviewCodeLocation {
+        let mut location = ReviewCodeLocation {
+            absolute_file_path: PathBuf::from("/repo/src/main.rs"),
+            line_range: ReviewLineRange { start: 9, end: 11 },
+            anchor: None,
+        };
+        location.anchor = anchor_location(&location, FIXTURE);
+        location
+    }
+
+    #[test]
+    fn anchor_records_context_and_hash() {
+        let location = anchored_location();
+        assert_eq!(
+            location.anchor,
+            Some(ReviewAnchor {
+                context_before: vec![
+                    "}".to_string(),
+                    String::new(),
+                    "fn compute() -> u32 {".to_string(),
+                ],
+                context_after: vec![
+                    "}".to_string(),
+                    String::new(),
+                    "fn unrelated() {}".to_string()
+                ],
+                content_hash: hash_lines(&["    let a = 1;", "    let b = 2;", "    a + b"]),
+            })
+        );
+    }
+
+    #[test]
+    fn unchanged_file_keeps_range() {
+        let location = anchored_location();
+        assert_eq!(
+            resolve_anchor(&location, FIXTURE),
+            AnchorResolution::Current(ReviewLineRange { start: 9, end: 11 })
+        );
+    }
+
+    #[test]
+    fn lines_added_or_removed_above_shift_the_range() {
+        let location = anchored_location();
+        let grown = FIXTURE.replace(
+            "use std::io;\n",
+            "use std::io;\nuse std::fs;\nuse std::env;\n",
+        );
+        assert_eq!(
+            resolve_anchor(&location, &grown),
+            AnchorResolution::Current(ReviewLineRange { start: 11, end: 13 })
+        );
+
+        let shrunk = FIXTURE.replace("use std::io;\n\n", "");
+        assert_eq!(
+            resolve_anchor(&location, &shrunk),
+            AnchorResolution::Current(ReviewLineRange { start: 7, end: 9 })
+        );
+    }
+
+    #[test]
+    fn edits_within_the_finding_are_found_by_context() {
+        let location = anchored_location();
+        let grown = FIXTURE.replace("    let b = 2;\n", "    let b = 2;\n    let c = 3;\n");
+        assert_eq!(
+            resolve_anchor(&location, &grown),
+            AnchorResolution::Current(ReviewLineRange { start: 9, end: 12 })
+        );
+
+        let shrunk = FIXTURE.replace("    let b = 2;\n", "");
+        assert_eq!(
+            resolve_anchor(&location, &shrunk),
+            AnchorResolution::Current(ReviewLineRange { start: 9, end: 10 })
+        );
+    }
+
+    #[test]
+    fn removed_code_is_stale() {
+        let location = anchored_location();
+        let removed = FIXTURE.replace(
+            "fn compute() -> u32 {\n    let a = 1;\n    let b = 2;\n    a + b\n}\n",
+            "",
+        );
+        assert_eq!(resolve_anchor(&location, &removed), AnchorResolution::Stale);
+    }
+
+    #[test]
+    fn unanchored_location_keeps_recorded_range() {
+        let location = ReviewCodeLocation {
+            absolute_file_path: PathBuf::from("/repo/src/main.rs"),
+            line_range: ReviewLineRange { start: 9, end: 11 },
+            anchor: None,
+        };
+        assert_eq!(
+            resolve_anchor(&location, "changed\n"),
+            AnchorResolution::Current(ReviewLineRange { start: 9, end: 11 })
+        );
+    }
+}
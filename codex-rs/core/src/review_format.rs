@@ -23,33 +23,40 @@ pub fn format_review_findings_block(
 ) -> String {
     let mut lines: Vec<String> = Vec::new();
     lines.push(String::new());
-
-    // Header
-    if findings.len() > 1 {
-        lines.push("Full review comments:".to_string());
-    } else {
-        lines.push("Review comment:".to_string());
-    }
+    lines.push(review_findings_header(findings.len()).to_string());
 
     for (idx, item) in findings.iter().enumerate() {
         lines.push(String::new());
 
-        let title = &item.title;
-        let location = format_location(item);
-
-        if let Some(flags) = selection {
+        let marker = selection.map(|flags| {
             // Default to selected if index is out of bounds.
             let checked = flags.get(idx).copied().unwrap_or(true);
-            let marker = if checked { "[x]" } else { "[ ]" };
-            lines.push(format!("- {marker} {title} — {location}"));
-        } else {
-            lines.push(format!("- {title} — {location}"));
-        }
-
-        for body_line in item.body.lines() {
-            lines.push(format!("  {body_line}"));
-        }
+            if checked { "[x]" } else { "[ ]" }
+        });
+        lines.push(format_review_finding(item, marker));
     }
 
     lines.join("\n")
 }
+
+/// Header line for a block of `count` findings.
+pub fn review_findings_header(count: usize) -> &'static str {
+    if count > 1 {
+        "Full review comments:"
+    } else {
+        "Review comment:"
+    }
+}
+
+/// Format one finding as "- Title — path:start-end" followed by its body,
+/// indented by two spaces. `marker`, when given, goes before the title.
+pub fn format_review_finding(item: &ReviewFinding, marker: Option<&str>) -> String {
+    let title = &item.title;
+    let location = format_location(item);
+    let mut lines = vec![match marker {
+        Some(marker) => format!("- {marker} {title} — {location}"),
+        None => format!("- {title} — {location}"),
+    }];
+    lines.extend(item.body.lines().map(|body_line| format!("  {body_line}")));
+    lines.join("\n")
+}
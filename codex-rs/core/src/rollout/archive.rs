@@ -0,0 +1,183 @@
+//! Archived rollouts under [`ARCHIVED_SESSIONS_SUBDIR`].
+//!
+//! Archiving moves a rollout out of `sessions/` into one flat directory, so it
+//! no longer shows up in the resume lists but can still be listed, restored
+//! (to resume it) or deleted for good. With `history.archive_retention_days` set, archived
+//! rollouts whose file was last modified before the retention window are
+//! pruned at most once per [`HOUSEKEEPING_INTERVAL`].
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use tracing::info;
+use tracing::warn;
+
+use super::ARCHIVED_SESSIONS_SUBDIR;
+use super::SESSIONS_SUBDIR;
+use super::list::parse_timestamp_uuid_from_filename;
+use crate::config::Config;
+use crate::persistence_policy::PersistenceKind;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Minimum time between two prunes of the archive.
+pub const HOUSEKEEPING_INTERVAL: Duration = Duration::from_secs(SECONDS_PER_DAY);
+
+/// Touched after every prune; its mtime tells when the next one is due.
+const HOUSEKEEPING_STAMP: &str = ".last_pruned";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveError {
+    #[error("rollout path `{}` must be in `{}`", path.display(), dir.display())]
+    OutsideDirectory { path: PathBuf, dir: PathBuf },
+
+    #[error("`{}` is not a rollout file", .0.display())]
+    NotRollout(PathBuf),
+
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// Moves the rollout at `path` from `sessions/` into the archive and returns
+/// its new location.
+pub(crate) async fn archive_conversation(
+    codex_home: &Path,
+    path: &Path,
+) -> Result<PathBuf, ArchiveError> {
+    let (path, file_name) = rollout_in(&codex_home.join(SESSIONS_SUBDIR), path).await?;
+    let archive_dir = codex_home.join(ARCHIVED_SESSIONS_SUBDIR);
+    tokio::fs::create_dir_all(&archive_dir).await?;
+    let archived = archive_dir.join(file_name);
+    tokio::fs::rename(&path, &archived).await?;
+    Ok(archived)
+}
+
+/// Moves an archived rollout back to its dated directory under `sessions/`
+/// and returns its new location. Resuming appends to the rollout, so it has
+/// to leave the archive first.
+pub(crate) async fn unarchive_conversation(
+    codex_home: &Path,
+    path: &Path,
+) -> Result<PathBuf, ArchiveError> {
+    let (path, file_name) = rollout_in(&codex_home.join(ARCHIVED_SESSIONS_SUBDIR), path).await?;
+    let Some((timestamp, _)) = parse_timestamp_uuid_from_filename(&file_name) else {
+        return Err(ArchiveError::NotRollout(path));
+    };
+    let dir = codex_home
+        .join(SESSIONS_SUBDIR)
+        .join(timestamp.year().to_string())
+        .join(format!("{:02}", u8::from(timestamp.month())))
+        .join(format!("{:02}", timestamp.day()));
+    tokio::fs::create_dir_all(&dir).await?;
+    let restored = dir.join(file_name);
+    tokio::fs::rename(&path, &restored).await?;
+    Ok(restored)
+}
+
+/// Removes an archived rollout for good. Rollouts still under `sessions/`
+/// must be archived first.
+pub(crate) async fn delete_conversation(
+    codex_home: &Path,
+    path: &Path,
+) -> Result<(), ArchiveError> {
+    let (path, _) = rollout_in(&codex_home.join(ARCHIVED_SESSIONS_SUBDIR), path).await?;
+    tokio::fs::remove_file(&path).await?;
+    Ok(())
+}
+
+/// Canonical `path` and its file name, provided it is a rollout file inside
+/// `dir`.
+async fn rollout_in(dir: &Path, path: &Path) -> Result<(PathBuf, String), ArchiveError> {
+    let outside = || ArchiveError::OutsideDirectory {
+        path: path.to_path_buf(),
+        dir: dir.to_path_buf(),
+    };
+    let dir = tokio::fs::canonicalize(dir).await.map_err(|_| outside())?;
+    let canonical = tokio::fs::canonicalize(path).await?;
+    if !canonical.starts_with(&dir) {
+        return Err(outside());
+    }
+    match canonical.file_name().and_then(|name| name.to_str()) {
+        Some(name) if is_rollout_file_name(name) => {
+            let name = name.to_string();
+            Ok((canonical, name))
+        }
+        _ => Err(ArchiveError::NotRollout(path.to_path_buf())),
+    }
+}
+
+fn is_rollout_file_name(name: &str) -> bool {
+    name.starts_with("rollout-") && name.ends_with(".jsonl")
+}
+
+/// Deletes archived rollouts last modified more than `retention` before
+/// `now`. Returns how many were removed.
+pub async fn prune_archived_sessions(
+    codex_home: &Path,
+    retention: Duration,
+    now: SystemTime,
+) -> io::Result<usize> {
+    let archive_dir = codex_home.join(ARCHIVED_SESSIONS_SUBDIR);
+    let Some(cutoff) = now.checked_sub(retention) else {
+        return Ok(0);
+    };
+    let mut entries = match tokio::fs::read_dir(&archive_dir).await {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(err) => return Err(err),
+    };
+
+    let mut removed = 0;
+    while let Some(entry) = entries.next_entry().await? {
+        let is_rollout = entry.file_name().to_str().is_some_and(is_rollout_file_name);
+        if !is_rollout {
+            continue;
+        }
+        let metadata = entry.metadata().await?;
+        if metadata.is_file() && metadata.modified()? < cutoff {
+            tokio::fs::remove_file(entry.path()).await?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Prunes the archive per `history.archive_retention_days` unless it was
+/// pruned less than [`HOUSEKEEPING_INTERVAL`] ago. Does nothing without a
+/// retention setting or when the session persists nothing.
+pub async fn run_housekeeping_if_due(config: &Config) {
+    let Some(days) = config.history.archive_retention_days else {
+        return;
+    };
+    if config.persistence.check(PersistenceKind::Rollout).is_err() {
+        return;
+    }
+
+    let archive_dir = config.codex_home.join(ARCHIVED_SESSIONS_SUBDIR);
+    let stamp = archive_dir.join(HOUSEKEEPING_STAMP);
+    let now = SystemTime::now();
+    if let Ok(metadata) = tokio::fs::metadata(&stamp).await
+        && let Ok(last) = metadata.modified()
+        && now.duration_since(last).unwrap_or_default() < HOUSEKEEPING_INTERVAL
+    {
+        return;
+    }
+
+    let retention = Duration::from_secs(days.saturating_mul(SECONDS_PER_DAY));
+    match prune_archived_sessions(&config.codex_home, retention, now).await {
+        Ok(0) => {}
+        Ok(removed) => info!("pruned {removed} archived sessions older than {days} days"),
+        Err(err) => {
+            warn!("failed to prune archived sessions: {err}");
+            return;
+        }
+    }
+    if tokio::fs::create_dir_all(&archive_dir).await.is_ok()
+        && let Err(err) = tokio::fs::write(&stamp, b"").await
+    {
+        warn!("failed to record archive housekeeping: {err}");
+    }
+}
@@ -12,6 +12,7 @@ use time::format_description::FormatItem;
 use time::macros::format_description;
 use uuid::Uuid;
 
+use super::ARCHIVED_SESSIONS_SUBDIR;
 use super::SESSIONS_SUBDIR;
 use crate::protocol::EventMsg;
 use codex_file_search as file_search;
@@ -141,6 +142,63 @@ pub(crate) async fn get_conversations(
     Ok(result)
 }
 
+/// Like [`get_conversations`], but over the flat [`ARCHIVED_SESSIONS_SUBDIR`]
+/// directory. Files whose names do not follow the rollout naming scheme are
+/// skipped.
+pub(crate) async fn get_archived_conversations(
+    codex_home: &Path,
+    page_size: usize,
+    cursor: Option<&Cursor>,
+) -> io::Result<ConversationsPage> {
+    let root = codex_home.join(ARCHIVED_SESSIONS_SUBDIR);
+    if !root.exists() {
+        return Ok(ConversationsPage::default());
+    }
+
+    let mut files = collect_files(&root, |name_str, path| {
+        if !name_str.starts_with("rollout-") || !name_str.ends_with(".jsonl") {
+            return None;
+        }
+        parse_timestamp_uuid_from_filename(name_str).map(|(ts, id)| (ts, id, path.to_path_buf()))
+    })
+    .await?;
+    files.sort_by_key(|(ts, id, _path)| (Reverse(*ts), Reverse(*id)));
+
+    let mut items: Vec<ConversationItem> = Vec::with_capacity(page_size);
+    let mut scanned_files = 0usize;
+    let mut more_matches_available = false;
+    for (ts, id, path) in files {
+        let past_anchor =
+            cursor.is_none_or(|anchor| ts < anchor.ts || (ts == anchor.ts && id < anchor.id));
+        if !past_anchor {
+            continue;
+        }
+        if items.len() == page_size || scanned_files >= MAX_SCAN_FILES {
+            more_matches_available = true;
+            break;
+        }
+        scanned_files += 1;
+        let summary = read_head_and_tail(&path, HEAD_RECORD_LIMIT, TAIL_RECORD_LIMIT)
+            .await
+            .unwrap_or_default();
+        if let Some(item) = conversation_item(path, summary) {
+            items.push(item);
+        }
+    }
+
+    let next_cursor = if more_matches_available {
+        build_next_cursor(&items)
+    } else {
+        None
+    };
+    Ok(ConversationsPage {
+        items,
+        next_cursor,
+        num_scanned_files: scanned_files,
+        reached_scan_cap: scanned_files >= MAX_SCAN_FILES,
+    })
+}
+
 /// Load the full contents of a single conversation session file at `path`.
 /// Returns the entire file contents as a String.
 #[allow(dead_code)]
@@ -229,23 +287,8 @@ async fn traverse_directories_for_paths(
                     {
                         continue;
                     }
-                    // Apply filters: must have session meta and at least one user message event
-                    if summary.saw_session_meta && summary.saw_user_event {
-                        let HeadTailSummary {
-                            head,
-                            tail,
-                            created_at,
-                            mut updated_at,
-                            ..
-                        } = summary;
-                        updated_at = updated_at.or_else(|| created_at.clone());
-                        items.push(ConversationItem {
-                            path,
-                            head,
-                            tail,
-                            created_at,
-                            updated_at,
-                        });
+                    if let Some(item) = conversation_item(path, summary) {
+                        items.push(item);
                     }
                 }
             }
@@ -270,6 +313,29 @@ async fn traverse_directories_for_paths(
     })
 }
 
+/// The listing entry for a rollout, provided it has session meta and at least
+/// one user message event.
+fn conversation_item(path: PathBuf, summary: HeadTailSummary) -> Option<ConversationItem> {
+    if !summary.saw_session_meta || !summary.saw_user_event {
+        return None;
+    }
+    let HeadTailSummary {
+        head,
+        tail,
+        created_at,
+        updated_at,
+        ..
+    } = summary;
+    let updated_at = updated_at.or_else(|| created_at.clone());
+    Some(ConversationItem {
+        path,
+        head,
+        tail,
+        created_at,
+        updated_at,
+    })
+}
+
 /// Pagination cursor token format: "<file_ts>|<uuid>" where `file_ts` matches the
 /// filename timestamp portion (YYYY-MM-DDThh-mm-ss) used in rollout filenames.
 /// The cursor orders files by timestamp desc, then UUID desc.
@@ -341,7 +407,7 @@ where
     Ok(collected)
 }
 
-fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uuid)> {
+pub(crate) fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uuid)> {
     // Expected: rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl
     let core = name.strip_prefix("rollout-")?.strip_suffix(".jsonl")?;
 
@@ -432,7 +498,7 @@ async fn read_head_and_tail(
             RolloutItem::TurnContext(_) => {
                 // Not included in `head`; skip.
             }
-            RolloutItem::Compacted(_) => {
+            RolloutItem::Compacted(_) | RolloutItem::RepoState(_) | RolloutItem::SessionEnd(_) => {
                 // Not included in `head`; skip.
             }
             RolloutItem::EventMsg(ev) => {
@@ -571,7 +637,7 @@ pub async fn find_conversation_path_by_id_str(
     let exclude: Vec<String> = Vec::new();
     let compute_indices = false;
 
-    let results = file_search::run(
+    let results = file_search::run_pattern(
         id_str,
         limit,
         &root,
@@ -7,6 +7,8 @@ pub const ARCHIVED_SESSIONS_SUBDIR: &str = "archived_sessions";
 pub const INTERACTIVE_SESSION_SOURCES: &[SessionSource] =
     &[SessionSource::Cli, SessionSource::VSCode];
 
+pub mod archive;
+pub mod bundle;
 pub mod list;
 pub(crate) mod policy;
 pub mod recorder;
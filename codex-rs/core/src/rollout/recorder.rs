@@ -19,13 +19,17 @@ use tracing::info;
 use tracing::warn;
 
 use super::SESSIONS_SUBDIR;
+use super::archive;
+use super::archive::ArchiveError;
 use super::list::ConversationsPage;
 use super::list::Cursor;
+use super::list::get_archived_conversations;
 use super::list::get_conversations;
 use super::policy::is_persisted_response_item;
 use crate::config::Config;
 use crate::default_client::originator;
 use crate::git_info::collect_git_info;
+use crate::persistence_policy::PersistenceKind;
 use codex_protocol::protocol::InitialHistory;
 use codex_protocol::protocol::ResumedHistory;
 use codex_protocol::protocol::RolloutItem;
@@ -111,10 +115,61 @@ impl RolloutRecorder {
         .await
     }
 
+    /// List archived conversations under the provided Codex home directory,
+    /// newest first.
+    pub async fn list_archived(
+        codex_home: &Path,
+        page_size: usize,
+        cursor: Option<&Cursor>,
+    ) -> std::io::Result<ConversationsPage> {
+        get_archived_conversations(codex_home, page_size, cursor).await
+    }
+
+    /// Move a rollout under `sessions/` into the archive. Returns the path of
+    /// the archived file.
+    pub async fn archive_conversation(
+        codex_home: &Path,
+        path: &Path,
+    ) -> Result<PathBuf, ArchiveError> {
+        archive::archive_conversation(codex_home, path).await
+    }
+
+    /// Move an archived rollout back under `sessions/` so it can be resumed.
+    /// Returns the restored path.
+    pub async fn unarchive_conversation(
+        codex_home: &Path,
+        path: &Path,
+    ) -> Result<PathBuf, ArchiveError> {
+        archive::unarchive_conversation(codex_home, path).await
+    }
+
+    /// Delete an archived rollout. Active rollouts have to be archived first.
+    pub async fn delete_conversation(codex_home: &Path, path: &Path) -> Result<(), ArchiveError> {
+        archive::delete_conversation(codex_home, path).await
+    }
+
     /// Attempt to create a new [`RolloutRecorder`]. If the sessions directory
     /// cannot be created or the rollout file cannot be opened we return the
     /// error so the caller can decide whether to disable persistence.
+    ///
+    /// When the config's [`crate::persistence_policy::PersistencePolicy`]
+    /// blocks rollouts (ephemeral mode) the recorder accepts items but never
+    /// writes them.
     pub async fn new(config: &Config, params: RolloutRecorderParams) -> std::io::Result<Self> {
+        if let Err(blocked) = config.persistence.check(PersistenceKind::Rollout) {
+            info!("{blocked}; not recording this session");
+            let rollout_path = match params {
+                RolloutRecorderParams::Create {
+                    conversation_id, ..
+                } => config
+                    .persistence
+                    .cache_home(&config.codex_home)
+                    .join(format!("rollout-{conversation_id}.jsonl")),
+                RolloutRecorderParams::Resume { path } => path,
+            };
+            return Ok(Self::discarding(rollout_path));
+        }
+
         let (file, rollout_path, meta) = match params {
             RolloutRecorderParams::Create {
                 conversation_id,
@@ -148,6 +203,7 @@ impl RolloutRecorder {
                         instructions,
                         source,
                         model_provider: Some(config.model_provider_id.clone()),
+                        imported_from: None,
                     }),
                 )
             }
@@ -177,6 +233,22 @@ impl RolloutRecorder {
         Ok(Self { tx, rollout_path })
     }
 
+    /// A recorder that acknowledges every command without touching disk.
+    fn discarding(rollout_path: PathBuf) -> Self {
+        let (tx, mut rx) = mpsc::channel::<RolloutCmd>(256);
+        tokio::task::spawn(async move {
+            while let Some(cmd) = rx.recv().await {
+                match cmd {
+                    RolloutCmd::AddItems(_) => {}
+                    RolloutCmd::Flush { ack } | RolloutCmd::Shutdown { ack } => {
+                        let _ = ack.send(());
+                    }
+                }
+            }
+        });
+        Self { tx, rollout_path }
+    }
+
     pub(crate) async fn record_items(&self, items: &[RolloutItem]) -> std::io::Result<()> {
         let mut filtered = Vec::new();
         for item in items {
@@ -248,9 +320,15 @@ impl RolloutRecorder {
                     RolloutItem::TurnContext(item) => {
                         items.push(RolloutItem::TurnContext(item));
                     }
+                    RolloutItem::RepoState(item) => {
+                        items.push(RolloutItem::RepoState(item));
+                    }
                     RolloutItem::EventMsg(_ev) => {
                         items.push(RolloutItem::EventMsg(_ev));
                     }
+                    RolloutItem::SessionEnd(item) => {
+                        items.push(RolloutItem::SessionEnd(item));
+                    }
                 },
                 Err(e) => {
                     warn!("failed to parse rollout line: {v:?}, error: {e}");
@@ -312,10 +390,30 @@ fn create_log_file(
     config: &Config,
     conversation_id: ConversationId,
 ) -> std::io::Result<LogFileInfo> {
-    // Resolve ~/.codex/sessions/YYYY/MM/DD and create it if missing.
     let timestamp = OffsetDateTime::now_local()
         .map_err(|e| IoError::other(format!("failed to get local time: {e}")))?;
-    let mut dir = config.codex_home.clone();
+    let path = new_rollout_path(&config.codex_home, conversation_id, timestamp)?;
+    let file = std::fs::OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(&path)?;
+
+    Ok(LogFileInfo {
+        file,
+        path,
+        conversation_id,
+        timestamp,
+    })
+}
+
+/// Path for a new rollout file under `~/.codex/sessions/YYYY/MM/DD`, creating
+/// the dated directory if it is missing.
+pub(crate) fn new_rollout_path(
+    codex_home: &Path,
+    conversation_id: ConversationId,
+    timestamp: OffsetDateTime,
+) -> std::io::Result<PathBuf> {
+    let mut dir = codex_home.to_path_buf();
     dir.push(SESSIONS_SUBDIR);
     dir.push(timestamp.year().to_string());
     dir.push(format!("{:02}", u8::from(timestamp.month())));
@@ -330,20 +428,7 @@ fn create_log_file(
         .format(format)
         .map_err(|e| IoError::other(format!("failed to format timestamp: {e}")))?;
 
-    let filename = format!("rollout-{date_str}-{conversation_id}.jsonl");
-
-    let path = dir.join(filename);
-    let file = std::fs::OpenOptions::new()
-        .append(true)
-        .create(true)
-        .open(&path)?;
-
-    Ok(LogFileInfo {
-        file,
-        path,
-        conversation_id,
-        timestamp,
-    })
+    Ok(dir.join(format!("rollout-{date_str}-{conversation_id}.jsonl")))
 }
 
 async fn rollout_writer(
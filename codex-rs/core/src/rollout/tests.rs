@@ -4,6 +4,8 @@ use std::fs::File;
 use std::fs::{self};
 use std::io::Write;
 use std::path::Path;
+use std::time::Duration;
+use std::time::SystemTime;
 
 use tempfile::TempDir;
 use time::OffsetDateTime;
@@ -12,7 +14,16 @@ use time::format_description::FormatItem;
 use time::macros::format_description;
 use uuid::Uuid;
 
+use crate::config::Config;
+use crate::config::ConfigOverrides;
+use crate::config::ConfigToml;
+use crate::rollout::ARCHIVED_SESSIONS_SUBDIR;
 use crate::rollout::INTERACTIVE_SESSION_SOURCES;
+use crate::rollout::RolloutRecorder;
+use crate::rollout::RolloutRecorderParams;
+use crate::rollout::SESSIONS_SUBDIR;
+use crate::rollout::archive::ArchiveError;
+use crate::rollout::archive::prune_archived_sessions;
 use crate::rollout::list::ConversationItem;
 use crate::rollout::list::ConversationsPage;
 use crate::rollout::list::Cursor;
@@ -594,6 +605,7 @@ async fn test_tail_includes_last_response_items() -> Result<()> {
                 cli_version: "test_version".into(),
                 source: SessionSource::VSCode,
                 model_provider: Some("test-provider".into()),
+                imported_from: None,
             },
             git: None,
         }),
@@ -687,6 +699,7 @@ async fn test_tail_handles_short_sessions() -> Result<()> {
                 cli_version: "test_version".into(),
                 source: SessionSource::VSCode,
                 model_provider: Some("test-provider".into()),
+                imported_from: None,
             },
             git: None,
         }),
@@ -781,6 +794,7 @@ async fn test_tail_skips_trailing_non_responses() -> Result<()> {
                 cli_version: "test_version".into(),
                 source: SessionSource::VSCode,
                 model_provider: Some("test-provider".into()),
+                imported_from: None,
             },
             git: None,
         }),
@@ -1130,3 +1144,176 @@ async fn test_model_provider_filter_selects_only_matching_sessions() -> Result<(
 
     Ok(())
 }
+
+#[tokio::test]
+async fn ephemeral_recorder_writes_nothing() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let config = Config::load_from_base_config_with_overrides(
+        ConfigToml::default(),
+        ConfigOverrides {
+            ephemeral: Some(true),
+            ..Default::default()
+        },
+        codex_home.path().to_path_buf(),
+    )?;
+
+    let recorder = RolloutRecorder::new(
+        &config,
+        RolloutRecorderParams::new(ConversationId::new(), None, SessionSource::Exec),
+    )
+    .await?;
+    recorder
+        .record_items(&[RolloutItem::ResponseItem(ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: "secret".to_string(),
+            }],
+        })])
+        .await?;
+    recorder.flush().await?;
+    recorder.shutdown().await?;
+
+    assert!(!codex_home.path().join(SESSIONS_SUBDIR).exists());
+    assert!(!recorder.rollout_path.exists());
+    Ok(())
+}
+
+fn session_path(home: &Path, ts_str: &str, uuid: Uuid) -> std::path::PathBuf {
+    let (date, _) = ts_str.split_once('T').unwrap();
+    let mut dir = home.join(SESSIONS_SUBDIR);
+    for part in date.split('-') {
+        dir.push(part);
+    }
+    dir.join(format!("rollout-{ts_str}-{uuid}.jsonl"))
+}
+
+#[tokio::test]
+async fn archived_listing_paginates_and_skips_foreign_files() -> Result<()> {
+    let temp = TempDir::new()?;
+    let home = temp.path();
+    let stamps = [
+        ("2025-03-01T09-00-00", Uuid::from_u128(11)),
+        ("2025-03-02T09-00-00", Uuid::from_u128(22)),
+        ("2025-03-03T09-00-00", Uuid::from_u128(33)),
+    ];
+    let mut archived = Vec::new();
+    for (ts, uuid) in stamps {
+        write_session_file(home, ts, uuid, 1, Some(SessionSource::Cli))?;
+        archived.push(
+            RolloutRecorder::archive_conversation(home, &session_path(home, ts, uuid)).await?,
+        );
+    }
+    let archive_dir = home.join(ARCHIVED_SESSIONS_SUBDIR);
+    fs::write(archive_dir.join("notes.txt"), "not a rollout")?;
+    fs::write(archive_dir.join("rollout-not-a-timestamp.jsonl"), "{}")?;
+    fs::write(
+        archive_dir
+            .join("rollout-2025-03-04T09-00-00-00000000-0000-0000-0000-000000000044.jsonl.bak"),
+        "{}",
+    )?;
+
+    let active = get_conversations(home, 10, None, NO_SOURCE_FILTER, None, TEST_PROVIDER).await?;
+    assert_eq!(active, ConversationsPage::default());
+
+    let paths = |page: &ConversationsPage| {
+        page.items
+            .iter()
+            .map(|item| item.path.clone())
+            .collect::<Vec<_>>()
+    };
+    let page1 = RolloutRecorder::list_archived(home, 2, None).await?;
+    assert_eq!(
+        paths(&page1),
+        vec![archived[2].clone(), archived[1].clone()]
+    );
+    let expected_cursor: Cursor =
+        serde_json::from_str(&format!("\"2025-03-02T09-00-00|{}\"", stamps[1].1))?;
+    assert_eq!(page1.next_cursor, Some(expected_cursor));
+
+    let page2 = RolloutRecorder::list_archived(home, 2, page1.next_cursor.as_ref()).await?;
+    assert_eq!(paths(&page2), vec![archived[0].clone()]);
+    assert_eq!(page2.next_cursor, None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn delete_only_removes_archived_rollouts() -> Result<()> {
+    let temp = TempDir::new()?;
+    let home = temp.path();
+    let (ts, uuid) = ("2025-03-01T09-00-00", Uuid::from_u128(11));
+    write_session_file(home, ts, uuid, 1, Some(SessionSource::Cli))?;
+    let active = session_path(home, ts, uuid);
+
+    let err = RolloutRecorder::delete_conversation(home, &active)
+        .await
+        .expect_err("active rollouts must be archived first");
+    assert!(matches!(err, ArchiveError::OutsideDirectory { .. }));
+    assert!(active.exists());
+
+    let archived = RolloutRecorder::archive_conversation(home, &active).await?;
+    assert!(!active.exists());
+    RolloutRecorder::delete_conversation(home, &archived).await?;
+    assert!(!archived.exists());
+    Ok(())
+}
+
+#[tokio::test]
+async fn unarchive_restores_the_dated_session_path() -> Result<()> {
+    let temp = TempDir::new()?;
+    let home = temp.path();
+    let (ts, uuid) = ("2025-03-01T09-00-00", Uuid::from_u128(11));
+    write_session_file(home, ts, uuid, 1, Some(SessionSource::Cli))?;
+    let active = session_path(home, ts, uuid);
+    let archived = RolloutRecorder::archive_conversation(home, &active).await?;
+
+    let restored = RolloutRecorder::unarchive_conversation(home, &archived).await?;
+    assert_eq!(restored, active);
+    assert!(!archived.exists());
+
+    let err = RolloutRecorder::unarchive_conversation(home, &restored)
+        .await
+        .expect_err("only archived rollouts can be restored");
+    assert!(matches!(err, ArchiveError::OutsideDirectory { .. }));
+    Ok(())
+}
+
+#[tokio::test]
+async fn pruning_removes_archives_older_than_retention() -> Result<()> {
+    let temp = TempDir::new()?;
+    let home = temp.path();
+    let archive_dir = home.join(ARCHIVED_SESSIONS_SUBDIR);
+    fs::create_dir_all(&archive_dir)?;
+    let now = SystemTime::now();
+    let day = Duration::from_secs(24 * 60 * 60);
+    let files = [
+        (
+            "rollout-2025-01-01T09-00-00-00000000-0000-0000-0000-000000000011.jsonl",
+            40,
+        ),
+        (
+            "rollout-2025-02-20T09-00-00-00000000-0000-0000-0000-000000000022.jsonl",
+            1,
+        ),
+        ("notes.txt", 40),
+    ];
+    for (name, age_days) in files {
+        let file = File::create(archive_dir.join(name))?;
+        file.set_modified(now - day * age_days)?;
+    }
+
+    assert_eq!(prune_archived_sessions(home, day * 30, now).await?, 1);
+
+    let mut remaining: Vec<String> = fs::read_dir(&archive_dir)?
+        .map(|entry| entry.map(|entry| entry.file_name().to_string_lossy().into_owned()))
+        .collect::<std::io::Result<_>>()?;
+    remaining.sort();
+    assert_eq!(
+        remaining,
+        vec![
+            "notes.txt".to_string(),
+            "rollout-2025-02-20T09-00-00-00000000-0000-0000-0000-000000000022.jsonl".to_string(),
+        ]
+    );
+    Ok(())
+}
@@ -0,0 +1,201 @@
+//! Plain-English summaries of commands awaiting approval
+//! (`approvals.explain`).
+//!
+//! Each summary comes from a separate one-message request to a small model.
+//! The request carries only the command and its working directory, never the
+//! conversation, and the reply is never added to the conversation history.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::AuthManager;
+use crate::ModelProviderInfo;
+use crate::client::ModelClient;
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::config::Config;
+use crate::model_family::find_family_for_model;
+use crate::openai_model_info::get_model_info;
+use codex_otel::otel_event_manager::OtelEventManager;
+use codex_protocol::ConversationId;
+use codex_protocol::config_types::ReasoningEffort;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::SessionSource;
+use futures::StreamExt;
+use tokio::time::timeout;
+use tracing::warn;
+
+const DEFAULT_EXPLANATION_MODEL: &str = "gpt-5.1-codex-mini";
+const DEFAULT_EXPLANATION_TIMEOUT: Duration = Duration::from_secs(5);
+/// Replies are cut at this many characters; two sentences fit easily.
+const MAX_EXPLANATION_CHARS: usize = 600;
+const EXPLANATION_INSTRUCTIONS: &str =
+    include_str!("../../templates/sandboxing/explanation_prompt.md");
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExplanationSettings {
+    /// Model asked for the summary.
+    pub model: String,
+    /// How long to wait for the summary before giving up.
+    pub timeout: Duration,
+}
+
+impl Default for ExplanationSettings {
+    fn default() -> Self {
+        Self {
+            model: DEFAULT_EXPLANATION_MODEL.to_string(),
+            timeout: DEFAULT_EXPLANATION_TIMEOUT,
+        }
+    }
+}
+
+/// Ask `settings.model` what `command` does and what the worst case is.
+/// Returns `None` on any failure or when `settings.timeout` elapses.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn explain_command(
+    config: Arc<Config>,
+    settings: &ExplanationSettings,
+    provider: ModelProviderInfo,
+    auth_manager: Arc<AuthManager>,
+    parent_otel: &OtelEventManager,
+    conversation_id: ConversationId,
+    session_source: SessionSource,
+    command: &[String],
+    cwd: &Path,
+) -> Option<String> {
+    if command.is_empty() {
+        return None;
+    }
+    let command_joined =
+        shlex::try_join(command.iter().map(String::as_str)).unwrap_or_else(|_| command.join(" "));
+    let prompt = Prompt {
+        input: vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: format!(
+                    "Working directory: {}\nCommand: {command_joined}",
+                    cwd.display()
+                ),
+            }],
+        }],
+        tools: Vec::new(),
+        parallel_tool_calls: false,
+        base_instructions_override: Some(EXPLANATION_INSTRUCTIONS.to_string()),
+        output_schema: None,
+    };
+
+    let mut explain_config = (*config).clone();
+    explain_config.model = settings.model.clone();
+    explain_config.model_family =
+        find_family_for_model(&settings.model).unwrap_or_else(|| config.model_family.clone());
+    explain_config.model_reasoning_effort = Some(ReasoningEffort::Low);
+    if let Some(model_info) = get_model_info(&explain_config.model_family) {
+        explain_config.model_context_window = Some(model_info.context_window);
+    }
+    let explain_config = Arc::new(explain_config);
+    let otel = parent_otel.with_model(
+        explain_config.model.as_str(),
+        explain_config.model_family.slug.as_str(),
+    );
+    let client = ModelClient::new(
+        Arc::clone(&explain_config),
+        Some(auth_manager),
+        otel,
+        provider,
+        explain_config.model_reasoning_effort,
+        explain_config.model_reasoning_summary,
+        conversation_id,
+        session_source,
+    );
+
+    let result = timeout(settings.timeout, async move {
+        let mut stream = client.stream(&prompt).await?;
+        let mut reply: Option<String> = None;
+        while let Some(event) = stream.next().await {
+            match event {
+                Ok(ResponseEvent::OutputItemDone(item)) => {
+                    if let Some(text) = assistant_text(&item) {
+                        reply = Some(text);
+                    }
+                }
+                Ok(ResponseEvent::Completed { .. }) => break,
+                Ok(_) => continue,
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(reply)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(Some(reply))) => tidy_explanation(&reply),
+        Ok(Ok(None)) => {
+            warn!("command explanation response did not include any message");
+            None
+        }
+        Ok(Err(err)) => {
+            warn!("command explanation failed: {err}");
+            None
+        }
+        Err(_) => {
+            warn!(
+                "command explanation timed out after {}ms",
+                settings.timeout.as_millis()
+            );
+            None
+        }
+    }
+}
+
+fn assistant_text(item: &ResponseItem) -> Option<String> {
+    let ResponseItem::Message { role, content, .. } = item else {
+        return None;
+    };
+    if role != "assistant" {
+        return None;
+    }
+    let text: Vec<&str> = content
+        .iter()
+        .filter_map(|segment| match segment {
+            ContentItem::OutputText { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect();
+    (!text.is_empty()).then(|| text.join("\n"))
+}
+
+/// Collapse the reply onto one line and cap its length.
+fn tidy_explanation(reply: &str) -> Option<String> {
+    let collapsed = reply.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.is_empty() {
+        return None;
+    }
+    if collapsed.chars().count() <= MAX_EXPLANATION_CHARS {
+        return Some(collapsed);
+    }
+    let mut truncated: String = collapsed.chars().take(MAX_EXPLANATION_CHARS - 1).collect();
+    truncated.push('…');
+    Some(truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn replies_are_collapsed_and_capped() {
+        assert_eq!(
+            tidy_explanation("  Deletes the build folder.\n\nWorst case: nothing else.  "),
+            Some("Deletes the build folder. Worst case: nothing else.".to_string())
+        );
+        assert_eq!(tidy_explanation(" \n "), None);
+
+        let capped = tidy_explanation(&"a".repeat(MAX_EXPLANATION_CHARS + 10)).expect("reply");
+        assert_eq!(capped.chars().count(), MAX_EXPLANATION_CHARS);
+        assert!(capped.ends_with('…'));
+    }
+}
@@ -7,6 +7,9 @@ ready‑to‑spawn environment.
 */
 
 pub mod assessment;
+pub mod explanation;
+#[cfg(target_os = "linux")]
+pub mod network_approval;
 
 use crate::exec::ExecToolCallOutput;
 use crate::exec::SandboxType;
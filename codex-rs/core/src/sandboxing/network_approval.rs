@@ -0,0 +1,522 @@
+/*
+Module: sandboxing::network_approval
+
+Supervises outbound connections of commands running under the Linux sandbox
+helper. The helper installs a seccomp user-notification filter for
+`connect`/`sendto`/`sendmsg`/`sendmmsg` and hands the listener to this module
+over a private Unix socket (see `codex-linux-sandbox`). Each intercepted
+syscall is decoded here, the destination is passed to an async approver, and
+the syscall is either resumed or failed with `EPERM`.
+
+Only destinations off the host's own link are put to the approver. Link-local
+and unspecified addresses fail with `EPERM` as before, without a prompt.
+Loopback deliberately does not count as local here: the sandbox blocks it just
+the same, and a dev server on `localhost` is the most common thing a command
+reaches for, so loopback connections are prompted too.
+
+Decisions are cached per destination for the lifetime of the command. When the
+helper cannot install the filter it falls back to the plain `EPERM` filter and
+this supervisor simply never receives a listener.
+
+Limitation: `SECCOMP_USER_NOTIF_FLAG_CONTINUE` re-runs the syscall with
+whatever arguments are in the target's memory at that point, so a
+multi-threaded process could swap the address after it was approved. This is
+a prompt against accidental network use, not a boundary against a hostile
+process.
+*/
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
+use std::os::fd::AsFd;
+use std::os::fd::AsRawFd;
+use std::os::fd::BorrowedFd;
+use std::os::fd::FromRawFd;
+use std::os::fd::OwnedFd;
+use std::os::unix::fs::FileExt;
+use std::os::unix::net::UnixListener;
+use std::os::unix::net::UnixStream;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use codex_protocol::protocol::NetworkDestination;
+use tokio::sync::mpsc;
+use tokio::sync::oneshot;
+use tracing::warn;
+
+use crate::error::Result;
+use crate::exec::ExecToolCallOutput;
+use crate::exec::StdoutStream;
+use crate::protocol::SandboxPolicy;
+use crate::sandboxing::ExecEnv;
+use crate::sandboxing::execute_env;
+
+/// Flag understood by `codex-linux-sandbox`.
+pub const NETWORK_APPROVAL_SOCKET_FLAG: &str = "--network-approval-socket";
+
+/// How often blocking waits wake up to check whether the command finished.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// How long the helper has to hand over its listener once connected.
+const HANDOFF_TIMEOUT: Duration = Duration::from_secs(5);
+const HANDOFF_ACK: u8 = b'1';
+const HANDOFF_NACK: u8 = b'0';
+/// Upper bound on `sendmmsg` batches inspected (matches `UIO_MAXIOV`).
+const MAX_MMSG_BATCH: u64 = 1024;
+
+struct PendingApproval {
+    destination: NetworkDestination,
+    respond: oneshot::Sender<bool>,
+}
+
+/// Run `env` (a `codex-linux-sandbox` invocation) with network syscalls routed
+/// through `approve` instead of failing outright. Falls back to a plain run
+/// when the supervisor socket cannot be created.
+pub async fn execute_env_with_network_approval<A, Fut>(
+    env: &ExecEnv,
+    policy: &SandboxPolicy,
+    stdout_stream: Option<StdoutStream>,
+    mut approve: A,
+) -> Result<ExecToolCallOutput>
+where
+    A: FnMut(NetworkDestination) -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let socket_dir = match tempfile::Builder::new()
+        .prefix("codex-network-approval")
+        .tempdir()
+    {
+        Ok(dir) => dir,
+        Err(err) => {
+            warn!("failed to create network approval socket dir: {err}");
+            return execute_env(env, policy, stdout_stream).await;
+        }
+    };
+    let socket_path = socket_dir.path().join("supervisor.sock");
+    let listener = match UnixListener::bind(&socket_path) {
+        Ok(listener) => listener,
+        Err(err) => {
+            warn!("failed to bind network approval socket: {err}");
+            return execute_env(env, policy, stdout_stream).await;
+        }
+    };
+
+    let mut env = env.clone();
+    let insert_at = 1.min(env.command.len());
+    env.command.splice(
+        insert_at..insert_at,
+        [
+            NETWORK_APPROVAL_SOCKET_FLAG.to_string(),
+            socket_path.to_string_lossy().into_owned(),
+        ],
+    );
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let cancel = CancelOnDrop::default();
+    let supervisor_cancel = Arc::clone(&cancel.0);
+    std::thread::spawn(move || supervise(listener, tx, supervisor_cancel));
+
+    let approvals = async {
+        while let Some(PendingApproval {
+            destination,
+            respond,
+        }) = rx.recv().await
+        {
+            let _ = respond.send(approve(destination).await);
+        }
+    };
+    let exec = execute_env(&env, policy, stdout_stream);
+    tokio::pin!(exec);
+    tokio::select! {
+        output = &mut exec => return output,
+        () = approvals => {}
+    }
+    // The supervisor thread exited (all sandboxed processes are gone or the
+    // handoff failed); wait for the command itself.
+    exec.await
+}
+
+#[derive(Default)]
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+fn supervise(
+    listener: UnixListener,
+    tx: mpsc::UnboundedSender<PendingApproval>,
+    cancel: Arc<AtomicBool>,
+) {
+    let Some(notify_fd) = accept_listener_fd(&listener, &cancel) else {
+        return;
+    };
+    drop(listener);
+
+    let mut decisions: HashMap<NetworkDestination, bool> = HashMap::new();
+    while !cancel.load(Ordering::Relaxed) {
+        match wait_readable(notify_fd.as_fd(), POLL_INTERVAL) {
+            Ok(Readiness::Readable) => {}
+            Ok(Readiness::Timeout) => continue,
+            Ok(Readiness::Hangup) | Err(_) => break,
+        }
+        // Fails with ENOENT when the target died before we got to it.
+        let Ok(notif) = recv_notification(&notify_fd) else {
+            continue;
+        };
+        let allow = match syscall_targets(&notif) {
+            Ok(targets) => {
+                // The pid may have been recycled while we read its memory.
+                if !notification_id_valid(&notify_fd, notif.id) {
+                    continue;
+                }
+                targets.into_iter().all(|target| match target {
+                    Target::Inet(destination) => {
+                        if let Some(allowed) = decisions.get(&destination) {
+                            return *allowed;
+                        }
+                        let allowed = ask(&tx, destination.clone());
+                        decisions.insert(destination, allowed);
+                        allowed
+                    }
+                    Target::Local | Target::Unsupported => false,
+                })
+            }
+            Err(_) => false,
+        };
+        let _ = send_response(&notify_fd, notif.id, allow);
+    }
+}
+
+fn ask(tx: &mpsc::UnboundedSender<PendingApproval>, destination: NetworkDestination) -> bool {
+    let (respond, response) = oneshot::channel();
+    if tx
+        .send(PendingApproval {
+            destination,
+            respond,
+        })
+        .is_err()
+    {
+        return false;
+    }
+    response.blocking_recv().unwrap_or(false)
+}
+
+/// Waits for the helper to connect and copies the notification listener out
+/// of it with `pidfd_getfd(2)`.
+fn accept_listener_fd(listener: &UnixListener, cancel: &AtomicBool) -> Option<OwnedFd> {
+    let mut stream = loop {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        match wait_readable(listener.as_fd(), POLL_INTERVAL) {
+            Ok(Readiness::Readable) => break listener.accept().ok()?.0,
+            Ok(Readiness::Timeout) => continue,
+            Ok(Readiness::Hangup) | Err(_) => return None,
+        }
+    };
+    stream.set_read_timeout(Some(HANDOFF_TIMEOUT)).ok()?;
+    match take_listener_fd(&stream) {
+        Ok(fd) => {
+            stream.write_all(&[HANDOFF_ACK]).ok()?;
+            Some(fd)
+        }
+        Err(err) => {
+            warn!("network approval handoff failed: {err}");
+            let _ = stream.write_all(&[HANDOFF_NACK]);
+            None
+        }
+    }
+}
+
+fn take_listener_fd(stream: &UnixStream) -> io::Result<OwnedFd> {
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    let target_fd: libc::c_int = line.trim().parse().map_err(io::Error::other)?;
+    let pid = peer_pid(stream)?;
+
+    let pidfd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid, 0) };
+    if pidfd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let pidfd = unsafe { OwnedFd::from_raw_fd(pidfd as libc::c_int) };
+    let fd = unsafe { libc::syscall(libc::SYS_pidfd_getfd, pidfd.as_raw_fd(), target_fd, 0) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { OwnedFd::from_raw_fd(fd as libc::c_int) })
+}
+
+fn peer_pid(stream: &UnixStream) -> io::Result<libc::pid_t> {
+    let mut cred: libc::ucred = unsafe { std::mem::zeroed() };
+    let mut len = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+    let rc = unsafe {
+        libc::getsockopt(
+            stream.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PEERCRED,
+            (&mut cred as *mut libc::ucred).cast(),
+            &mut len,
+        )
+    };
+    if rc != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(cred.pid)
+}
+
+enum Readiness {
+    Readable,
+    Timeout,
+    Hangup,
+}
+
+fn wait_readable(fd: BorrowedFd<'_>, timeout: Duration) -> io::Result<Readiness> {
+    let mut pollfd = libc::pollfd {
+        fd: fd.as_raw_fd(),
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let rc = unsafe { libc::poll(&mut pollfd, 1, timeout.as_millis() as libc::c_int) };
+    if rc < 0 {
+        let err = io::Error::last_os_error();
+        return if err.kind() == io::ErrorKind::Interrupted {
+            Ok(Readiness::Timeout)
+        } else {
+            Err(err)
+        };
+    }
+    if rc == 0 {
+        return Ok(Readiness::Timeout);
+    }
+    if pollfd.revents & libc::POLLIN != 0 {
+        Ok(Readiness::Readable)
+    } else {
+        Ok(Readiness::Hangup)
+    }
+}
+
+fn recv_notification(fd: &OwnedFd) -> io::Result<libc::seccomp_notif> {
+    // The kernel requires the buffer to be zeroed.
+    let mut notif: libc::seccomp_notif = unsafe { std::mem::zeroed() };
+    let rc = unsafe { libc::ioctl(fd.as_raw_fd(), libc::SECCOMP_IOCTL_NOTIF_RECV, &mut notif) };
+    if rc != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(notif)
+}
+
+fn notification_id_valid(fd: &OwnedFd, id: u64) -> bool {
+    let mut id = id;
+    unsafe { libc::ioctl(fd.as_raw_fd(), libc::SECCOMP_IOCTL_NOTIF_ID_VALID, &mut id) == 0 }
+}
+
+fn send_response(fd: &OwnedFd, id: u64, allow: bool) -> io::Result<()> {
+    let mut resp = libc::seccomp_notif_resp {
+        id,
+        val: 0,
+        error: if allow { 0 } else { -libc::EPERM },
+        flags: if allow {
+            libc::SECCOMP_USER_NOTIF_FLAG_CONTINUE as u32
+        } else {
+            0
+        },
+    };
+    let rc = unsafe { libc::ioctl(fd.as_raw_fd(), libc::SECCOMP_IOCTL_NOTIF_SEND, &mut resp) };
+    if rc != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Target {
+    Inet(NetworkDestination),
+    /// Link-local or unspecified address; denied without prompting, as before
+    /// supervision existed.
+    Local,
+    /// Non-IP address family; denied as before supervision existed.
+    Unsupported,
+}
+
+impl Target {
+    fn ip(ip: IpAddr, port: u16) -> Self {
+        let local = match ip.to_canonical() {
+            IpAddr::V4(ip) => ip.is_link_local() || ip.is_unspecified(),
+            IpAddr::V6(ip) => ip.is_unicast_link_local() || ip.is_unspecified(),
+        };
+        if local {
+            Target::Local
+        } else {
+            Target::Inet(NetworkDestination {
+                host: ip.to_string(),
+                port,
+            })
+        }
+    }
+}
+
+/// Destinations named by the intercepted syscall. Empty when the syscall uses
+/// an already-connected socket.
+fn syscall_targets(notif: &libc::seccomp_notif) -> io::Result<Vec<Target>> {
+    let mem = File::open(format!("/proc/{}/mem", notif.pid))?;
+    let args = notif.data.args;
+    let nr = libc::c_long::from(notif.data.nr);
+    let mut targets = Vec::new();
+    if nr == libc::SYS_connect {
+        targets.extend(read_sockaddr(&mem, args[1], args[2])?);
+    } else if nr == libc::SYS_sendto {
+        targets.extend(read_sockaddr(&mem, args[4], args[5])?);
+    } else if nr == libc::SYS_sendmsg {
+        targets.extend(read_msghdr_target(&mem, args[1])?);
+    } else if nr == libc::SYS_sendmmsg {
+        let stride = std::mem::size_of::<libc::mmsghdr>() as u64;
+        for index in 0..args[2].min(MAX_MMSG_BATCH) {
+            targets.extend(read_msghdr_target(&mem, args[1] + index * stride)?);
+        }
+    } else {
+        targets.push(Target::Unsupported);
+    }
+    Ok(targets)
+}
+
+fn read_msghdr_target(mem: &File, addr: u64) -> io::Result<Option<Target>> {
+    let mut buf = [0u8; std::mem::size_of::<libc::msghdr>()];
+    mem.read_exact_at(&mut buf, addr)?;
+    let msghdr: libc::msghdr = unsafe { std::ptr::read_unaligned(buf.as_ptr().cast()) };
+    read_sockaddr(mem, msghdr.msg_name as u64, u64::from(msghdr.msg_namelen))
+}
+
+fn read_sockaddr(mem: &File, addr: u64, len: u64) -> io::Result<Option<Target>> {
+    if addr == 0 || len == 0 {
+        return Ok(None);
+    }
+    let len = len.min(std::mem::size_of::<libc::sockaddr_storage>() as u64) as usize;
+    let mut buf = vec![0u8; len];
+    mem.read_exact_at(&mut buf, addr)?;
+    Ok(parse_sockaddr(&buf))
+}
+
+fn parse_sockaddr(buf: &[u8]) -> Option<Target> {
+    let family = buf.get(0..2)?;
+    let family = libc::sa_family_t::from_ne_bytes([family[0], family[1]]) as libc::c_int;
+    let port = || {
+        buf.get(2..4)
+            .map(|port| u16::from_be_bytes([port[0], port[1]]))
+    };
+    match family {
+        // Dissolves a UDP association; there is no destination to approve.
+        libc::AF_UNSPEC => None,
+        libc::AF_INET => {
+            let octets: [u8; 4] = buf.get(4..8)?.try_into().ok()?;
+            Some(Target::ip(Ipv4Addr::from(octets).into(), port()?))
+        }
+        libc::AF_INET6 => {
+            let octets: [u8; 16] = buf.get(8..24)?.try_into().ok()?;
+            Some(Target::ip(Ipv6Addr::from(octets).into(), port()?))
+        }
+        _ => Some(Target::Unsupported),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn sockaddr_bytes<T>(addr: &T) -> Vec<u8> {
+        let ptr = (addr as *const T).cast::<u8>();
+        unsafe { std::slice::from_raw_parts(ptr, std::mem::size_of::<T>()) }.to_vec()
+    }
+
+    #[test]
+    fn parses_ipv4_destination() {
+        let addr = libc::sockaddr_in {
+            sin_family: libc::AF_INET as libc::sa_family_t,
+            sin_port: 8080u16.to_be(),
+            sin_addr: libc::in_addr {
+                s_addr: u32::from_ne_bytes([127, 0, 0, 1]),
+            },
+            sin_zero: [0; 8],
+        };
+        assert_eq!(
+            parse_sockaddr(&sockaddr_bytes(&addr)),
+            Some(Target::Inet(NetworkDestination {
+                host: "127.0.0.1".to_string(),
+                port: 8080,
+            }))
+        );
+    }
+
+    #[test]
+    fn parses_ipv6_destination() {
+        let addr = libc::sockaddr_in6 {
+            sin6_family: libc::AF_INET6 as libc::sa_family_t,
+            sin6_port: 443u16.to_be(),
+            sin6_flowinfo: 0,
+            sin6_addr: libc::in6_addr {
+                s6_addr: Ipv6Addr::LOCALHOST.octets(),
+            },
+            sin6_scope_id: 0,
+        };
+        assert_eq!(
+            parse_sockaddr(&sockaddr_bytes(&addr)),
+            Some(Target::Inet(NetworkDestination {
+                host: "::1".to_string(),
+                port: 443,
+            }))
+        );
+    }
+
+    #[test]
+    fn unix_and_unspec_addresses_are_not_approvable() {
+        let unix = (libc::AF_UNIX as libc::sa_family_t).to_ne_bytes();
+        let unspec = (libc::AF_UNSPEC as libc::sa_family_t).to_ne_bytes();
+        assert_eq!(parse_sockaddr(&unix), Some(Target::Unsupported));
+        assert_eq!(parse_sockaddr(&unspec), None);
+    }
+
+    #[test]
+    fn link_local_and_unspecified_addresses_are_denied_without_prompting() {
+        let ipv4 = |octets: [u8; 4]| libc::sockaddr_in {
+            sin_family: libc::AF_INET as libc::sa_family_t,
+            sin_port: 80u16.to_be(),
+            sin_addr: libc::in_addr {
+                s_addr: u32::from_ne_bytes(octets),
+            },
+            sin_zero: [0; 8],
+        };
+        let ipv6 = |addr: Ipv6Addr| libc::sockaddr_in6 {
+            sin6_family: libc::AF_INET6 as libc::sa_family_t,
+            sin6_port: 80u16.to_be(),
+            sin6_flowinfo: 0,
+            sin6_addr: libc::in6_addr {
+                s6_addr: addr.octets(),
+            },
+            sin6_scope_id: 0,
+        };
+
+        let targets = [
+            parse_sockaddr(&sockaddr_bytes(&ipv4([169, 254, 169, 254]))),
+            parse_sockaddr(&sockaddr_bytes(&ipv4([0, 0, 0, 0]))),
+            parse_sockaddr(&sockaddr_bytes(&ipv6(Ipv6Addr::new(
+                0xfe80, 0, 0, 0, 0, 0, 0, 1,
+            )))),
+            parse_sockaddr(&sockaddr_bytes(&ipv6(Ipv6Addr::UNSPECIFIED))),
+            parse_sockaddr(&sockaddr_bytes(&ipv6(
+                Ipv4Addr::new(169, 254, 0, 1).to_ipv6_mapped(),
+            ))),
+        ];
+
+        assert_eq!(targets, [const { Some(Target::Local) }; 5]);
+    }
+}
@@ -0,0 +1,534 @@
+//! Path-scoped instructions embedded in project docs.
+//!
+//! A project doc (`AGENTS.md` and friends) may contain one or more fenced
+//! blocks tagged `scopes`. The body of each block is TOML describing rules that
+//! only apply when the current turn touches matching paths:
+//!
+//! ````markdown
+//! ```scopes
+//! [[scope]]
+//! paths = ["migrations/**", "db/schema.sql"]
+//! instructions = "When touching migrations, also regenerate db/schema.sql."
+//! ```
+//! ````
+//!
+//! Patterns are matched relative to the directory containing the doc that
+//! declared them. A trailing `/` matches everything below that directory.
+//! Scope blocks are stripped from the regular project doc text; the matching
+//! snippets are appended to each model request and never recorded in history,
+//! so a scope drops out as soon as the turn stops touching its paths. If a
+//! block fails to parse, nothing from it is included and a warning is surfaced
+//! instead.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_apply_patch::ApplyPatchArgs;
+use codex_apply_patch::Hunk;
+use codex_apply_patch::MaybeApplyPatch;
+use codex_apply_patch::maybe_parse_apply_patch;
+use codex_apply_patch::parse_patch;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::user_input::UserInput;
+use serde::Deserialize;
+use serde_json::Value;
+use wildmatch::WildMatch;
+
+pub(crate) const SCOPED_INSTRUCTIONS_OPEN_TAG: &str = "<scoped_instructions>";
+pub(crate) const SCOPED_INSTRUCTIONS_CLOSE_TAG: &str = "</scoped_instructions>";
+
+const SCOPES_FENCE_INFO: &str = "scopes";
+
+/// Arguments of the file tools (`read_file`, `list_dir`, `grep_files`, ...)
+/// that name the path they operate on.
+const PATH_ARGUMENTS: [&str; 3] = ["path", "file_path", "dir_path"];
+
+/// A single instruction snippet that applies to a set of path globs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScopedInstruction {
+    pub patterns: Vec<String>,
+    pub text: String,
+    /// Project doc that declared this scope.
+    pub source: PathBuf,
+    /// Directory patterns are resolved against.
+    pub base_dir: PathBuf,
+}
+
+impl ScopedInstruction {
+    /// Returns true when `path` (absolute) matches any of the scope patterns.
+    pub fn matches(&self, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.base_dir) else {
+            return false;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        self.patterns
+            .iter()
+            .any(|pattern| pattern_matches(pattern, &relative))
+    }
+}
+
+/// All scoped instructions discovered for a session.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScopedInstructions {
+    scopes: Vec<ScopedInstruction>,
+    warnings: Vec<String>,
+}
+
+impl ScopedInstructions {
+    pub fn is_empty(&self) -> bool {
+        self.scopes.is_empty()
+    }
+
+    pub fn scopes(&self) -> &[ScopedInstruction] {
+        &self.scopes
+    }
+
+    /// Parse failures encountered while loading; each one caused a whole
+    /// `scopes` block to be ignored.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    pub(crate) fn extend(&mut self, parsed: ParsedProjectDoc) {
+        self.scopes.extend(parsed.scopes);
+        self.warnings.extend(parsed.warnings);
+    }
+
+    /// Indices of the scopes that match at least one of `files`.
+    pub fn active_indices<'a>(&self, files: impl IntoIterator<Item = &'a PathBuf>) -> Vec<usize> {
+        let files: Vec<&PathBuf> = files.into_iter().collect();
+        self.scopes
+            .iter()
+            .enumerate()
+            .filter(|(_, scope)| files.iter().any(|file| scope.matches(file)))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Render the scopes at `indices` as a single, clearly labeled message for
+    /// the model. Returns `None` when nothing is active.
+    pub(crate) fn render(&self, indices: &[usize]) -> Option<ResponseItem> {
+        let sections: Vec<String> = indices
+            .iter()
+            .filter_map(|idx| self.scopes.get(*idx))
+            .map(|scope| {
+                format!(
+                    "## Applies to {} (from {})\n\n{}",
+                    scope.patterns.join(", "),
+                    scope.source.display(),
+                    scope.text.trim()
+                )
+            })
+            .collect();
+        if sections.is_empty() {
+            return None;
+        }
+        let body = sections.join("\n\n");
+        Some(ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: format!(
+                    "{SCOPED_INSTRUCTIONS_OPEN_TAG}\nThese project instructions apply to files involved in this turn.\n\n{body}\n{SCOPED_INSTRUCTIONS_CLOSE_TAG}"
+                ),
+            }],
+        })
+    }
+}
+
+/// Result of splitting a project doc into its plain text and scoped rules.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct ParsedProjectDoc {
+    pub text: String,
+    pub scopes: Vec<ScopedInstruction>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ScopesBlockToml {
+    #[serde(default)]
+    scope: Vec<ScopeToml>,
+}
+
+#[derive(Deserialize)]
+struct ScopeToml {
+    paths: Vec<String>,
+    instructions: String,
+}
+
+/// Strip `scopes` fences out of `doc` and parse them. `source` is the path of
+/// the doc; its parent directory becomes the base for pattern matching.
+pub(crate) fn parse_project_doc(doc: &str, source: &Path) -> ParsedProjectDoc {
+    let base_dir = source.parent().map(Path::to_path_buf).unwrap_or_default();
+    let mut parsed = ParsedProjectDoc::default();
+    let mut text_lines: Vec<&str> = Vec::new();
+    let mut block: Option<Vec<&str>> = None;
+
+    for line in doc.lines() {
+        match block.as_mut() {
+            Some(body) => {
+                if line.trim() == "```" {
+                    let body = body.join("\n");
+                    block = None;
+                    match parse_scopes_block(&body, source, &base_dir) {
+                        Ok(scopes) => parsed.scopes.extend(scopes),
+                        Err(err) => parsed.warnings.push(format!(
+                            "Ignoring scoped instructions in {}: {err}",
+                            source.display()
+                        )),
+                    }
+                } else {
+                    body.push(line);
+                }
+            }
+            None => {
+                if is_scopes_fence(line) {
+                    block = Some(Vec::new());
+                } else {
+                    text_lines.push(line);
+                }
+            }
+        }
+    }
+
+    if block.is_some() {
+        parsed.warnings.push(format!(
+            "Ignoring scoped instructions in {}: unterminated ```scopes block",
+            source.display()
+        ));
+    }
+
+    parsed.text = text_lines.join("\n");
+    parsed
+}
+
+fn is_scopes_fence(line: &str) -> bool {
+    line.trim()
+        .strip_prefix("```")
+        .is_some_and(|info| info.trim() == SCOPES_FENCE_INFO)
+}
+
+fn parse_scopes_block(
+    body: &str,
+    source: &Path,
+    base_dir: &Path,
+) -> Result<Vec<ScopedInstruction>, String> {
+    let parsed: ScopesBlockToml = toml::from_str(body).map_err(|e| e.message().to_string())?;
+    let mut scopes = Vec::with_capacity(parsed.scope.len());
+    for scope in parsed.scope {
+        let patterns: Vec<String> = scope
+            .paths
+            .into_iter()
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect();
+        if patterns.is_empty() {
+            return Err("scope entry has no paths".to_string());
+        }
+        if scope.instructions.trim().is_empty() {
+            return Err(format!(
+                "scope for {} has empty instructions",
+                patterns.join(", ")
+            ));
+        }
+        scopes.push(ScopedInstruction {
+            patterns,
+            text: scope.instructions,
+            source: source.to_path_buf(),
+            base_dir: base_dir.to_path_buf(),
+        });
+    }
+    Ok(scopes)
+}
+
+fn pattern_matches(pattern: &str, relative: &str) -> bool {
+    let pattern = pattern.trim_start_matches("./");
+    if let Some(dir) = pattern.strip_suffix('/') {
+        return WildMatch::new(dir).matches(relative)
+            || WildMatch::new(&format!("{dir}/*")).matches(relative);
+    }
+    WildMatch::new(pattern).matches(relative)
+}
+
+/// Best-effort extraction of file paths mentioned in user input. Tokens that
+/// look like paths (contain `/` or a file extension) are resolved against
+/// `cwd`; they do not need to exist yet since the user may ask to create them.
+pub(crate) fn paths_mentioned_in(input: &[UserInput], cwd: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for item in input {
+        let UserInput::Text { text } = item else {
+            continue;
+        };
+        for token in text.split_whitespace() {
+            let token = token
+                .trim_start_matches(['@', '`', '"', '\'', '('])
+                .trim_end_matches(['`', '"', '\'', ')', ',', '.', ':', ';', '!', '?']);
+            if token.is_empty() || token.contains("://") {
+                continue;
+            }
+            if !token.contains('/') && !token.contains('.') {
+                continue;
+            }
+            let path = cwd.join(token);
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
+
+/// Files a tool call targets, as far as its arguments tell: the files an
+/// `apply_patch` call (direct or through the shell) edits, and the path given
+/// to the file tools. Relative paths are resolved against `cwd`.
+pub(crate) fn tool_call_targets(item: &ResponseItem, cwd: &Path) -> Vec<PathBuf> {
+    match item {
+        ResponseItem::FunctionCall {
+            name, arguments, ..
+        } => {
+            let Ok(args) = serde_json::from_str::<Value>(arguments) else {
+                return Vec::new();
+            };
+            let cwd = match args.get("workdir").and_then(Value::as_str) {
+                Some(workdir) => cwd.join(workdir),
+                None => cwd.to_path_buf(),
+            };
+            if name == "apply_patch" {
+                return args
+                    .get("input")
+                    .and_then(Value::as_str)
+                    .and_then(|patch| parse_patch(patch).ok())
+                    .map(|patch| patch_targets(&patch, &cwd))
+                    .unwrap_or_default();
+            }
+            if let Some(command) = args
+                .get("command")
+                .and_then(|command| serde_json::from_value::<Vec<String>>(command.clone()).ok())
+            {
+                return shell_patch_targets(&command, &cwd);
+            }
+            PATH_ARGUMENTS
+                .iter()
+                .filter_map(|key| args.get(*key).and_then(Value::as_str))
+                .map(|path| cwd.join(path))
+                .collect()
+        }
+        ResponseItem::CustomToolCall { name, input, .. } if name == "apply_patch" => {
+            parse_patch(input)
+                .map(|patch| patch_targets(&patch, cwd))
+                .unwrap_or_default()
+        }
+        ResponseItem::LocalShellCall {
+            action: LocalShellAction::Exec(exec),
+            ..
+        } => {
+            let cwd = match &exec.working_directory {
+                Some(workdir) => cwd.join(workdir),
+                None => cwd.to_path_buf(),
+            };
+            shell_patch_targets(&exec.command, &cwd)
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn shell_patch_targets(command: &[String], cwd: &Path) -> Vec<PathBuf> {
+    match maybe_parse_apply_patch(command) {
+        MaybeApplyPatch::Body(patch) => patch_targets(&patch, cwd),
+        _ => Vec::new(),
+    }
+}
+
+fn patch_targets(patch: &ApplyPatchArgs, cwd: &Path) -> Vec<PathBuf> {
+    let cwd = match &patch.workdir {
+        Some(workdir) => cwd.join(workdir),
+        None => cwd.to_path_buf(),
+    };
+    let mut paths = Vec::new();
+    for hunk in &patch.hunks {
+        match hunk {
+            Hunk::AddFile { path, .. } | Hunk::DeleteFile { path } => paths.push(cwd.join(path)),
+            Hunk::UpdateFile {
+                path, move_path, ..
+            } => {
+                paths.push(cwd.join(path));
+                paths.extend(move_path.iter().map(|path| cwd.join(path)));
+            }
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const DOC: &str = r#"# Repo rules
+
+Always run the tests.
+
+```scopes
+[[scope]]
+paths = ["migrations/"]
+instructions = "Also regenerate db/schema.sql."
+
+[[scope]]
+paths = ["docs/*.md", "README.md"]
+instructions = "Keep prose under 80 columns."
+```
+
+Trailing text.
+"#;
+
+    fn parsed(doc: &str) -> ParsedProjectDoc {
+        parse_project_doc(doc, Path::new("/repo/AGENTS.md"))
+    }
+
+    #[test]
+    fn strips_scopes_block_from_doc_text() {
+        let parsed = parsed(DOC);
+        assert_eq!(
+            parsed.text,
+            "# Repo rules\n\nAlways run the tests.\n\n\nTrailing text."
+        );
+        assert_eq!(parsed.scopes.len(), 2);
+        assert!(parsed.warnings.is_empty());
+    }
+
+    #[test]
+    fn matches_globs_against_turn_files() {
+        let mut scoped = ScopedInstructions::default();
+        scoped.extend(parsed(DOC));
+
+        let files = vec![PathBuf::from("/repo/migrations/0001_init.sql")];
+        assert_eq!(scoped.active_indices(&files), vec![0]);
+
+        let files = vec![
+            PathBuf::from("/repo/docs/guide.md"),
+            PathBuf::from("/repo/src/main.rs"),
+        ];
+        assert_eq!(scoped.active_indices(&files), vec![1]);
+
+        let files = vec![PathBuf::from("/elsewhere/migrations/0001.sql")];
+        assert_eq!(scoped.active_indices(&files), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn re_evaluates_for_each_file_set() {
+        let mut scoped = ScopedInstructions::default();
+        scoped.extend(parsed(DOC));
+
+        let first = scoped.active_indices(&[PathBuf::from("/repo/README.md")]);
+        let second = scoped.active_indices(&[PathBuf::from("/repo/migrations/a.sql")]);
+        let none = scoped.active_indices(&[PathBuf::from("/repo/src/lib.rs")]);
+        assert_eq!((first, second, none), (vec![1], vec![0], Vec::new()));
+    }
+
+    #[test]
+    fn parse_error_drops_block_with_warning() {
+        let doc = "Intro\n```scopes\n[[scope]]\npaths = \"not-a-list\"\n```\nOutro";
+        let parsed = parsed(doc);
+        assert_eq!(parsed.text, "Intro\nOutro");
+        assert!(parsed.scopes.is_empty());
+        assert_eq!(parsed.warnings.len(), 1);
+        assert!(parsed.warnings[0].starts_with("Ignoring scoped instructions in /repo/AGENTS.md"));
+    }
+
+    #[test]
+    fn unterminated_block_is_ignored_with_warning() {
+        let doc = "Intro\n```scopes\n[[scope]]\npaths = [\"a/\"]\ninstructions = \"x\"";
+        let parsed = parsed(doc);
+        assert_eq!(parsed.text, "Intro");
+        assert!(parsed.scopes.is_empty());
+        assert_eq!(parsed.warnings.len(), 1);
+    }
+
+    #[test]
+    fn render_labels_active_scopes_only() {
+        let mut scoped = ScopedInstructions::default();
+        scoped.extend(parsed(DOC));
+
+        let Some(ResponseItem::Message { role, content, .. }) = scoped.render(&[0]) else {
+            panic!("expected a rendered message");
+        };
+        assert_eq!(role, "user");
+        let [ContentItem::InputText { text }] = content.as_slice() else {
+            panic!("expected one text item");
+        };
+        assert!(text.starts_with(SCOPED_INSTRUCTIONS_OPEN_TAG));
+        assert!(text.contains("## Applies to migrations/ (from /repo/AGENTS.md)"));
+        assert!(!text.contains("Keep prose under 80 columns."));
+        assert_eq!(scoped.render(&[]), None);
+    }
+
+    #[test]
+    fn tool_calls_name_their_targets() {
+        let cwd = Path::new("/repo");
+        let patch = "*** Begin Patch\n*** Update File: migrations/0003.sql\n*** Move to: migrations/0004.sql\n@@\n-a\n+b\n*** End Patch";
+        let apply_patch = ResponseItem::CustomToolCall {
+            id: None,
+            status: None,
+            call_id: "call-1".to_string(),
+            name: "apply_patch".to_string(),
+            input: patch.to_string(),
+        };
+        let read_file = ResponseItem::FunctionCall {
+            id: None,
+            name: "read_file".to_string(),
+            arguments: r#"{"file_path":"docs/guide.md"}"#.to_string(),
+            call_id: "call-2".to_string(),
+        };
+        let shell = ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: serde_json::json!({
+                "command": ["apply_patch", "*** Begin Patch\n*** Add File: README.md\n+hi\n*** End Patch"],
+                "workdir": "sub",
+            })
+            .to_string(),
+            call_id: "call-3".to_string(),
+        };
+        let plain_shell = ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: r#"{"command":["ls","migrations"]}"#.to_string(),
+            call_id: "call-4".to_string(),
+        };
+
+        assert_eq!(
+            (
+                tool_call_targets(&apply_patch, cwd),
+                tool_call_targets(&read_file, cwd),
+                tool_call_targets(&shell, cwd),
+                tool_call_targets(&plain_shell, cwd),
+            ),
+            (
+                vec![
+                    PathBuf::from("/repo/migrations/0003.sql"),
+                    PathBuf::from("/repo/migrations/0004.sql"),
+                ],
+                vec![PathBuf::from("/repo/docs/guide.md")],
+                vec![PathBuf::from("/repo/sub/README.md")],
+                Vec::new(),
+            )
+        );
+    }
+
+    #[test]
+    fn extracts_path_mentions_from_user_text() {
+        let input = vec![UserInput::Text {
+            text: "Please edit @migrations/0002.sql and `README.md`, see https://x.y/z."
+                .to_string(),
+        }];
+        assert_eq!(
+            paths_mentioned_in(&input, Path::new("/repo")),
+            vec![
+                PathBuf::from("/repo/migrations/0002.sql"),
+                PathBuf::from("/repo/README.md"),
+            ]
+        );
+    }
+}
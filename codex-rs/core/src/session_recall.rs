@@ -0,0 +1,578 @@
+//! Search over earlier sessions for the opt-in `search_past_sessions` tool.
+//!
+//! Rollouts under `sessions/` are distilled into a small index of user
+//! messages and final assistant answers, stored as `recall/index.json` in the
+//! cache home. The index is refreshed incrementally: a rollout is only re-read
+//! when its size changed since it was last indexed. Sessions started under an
+//! excluded project are never indexed, and ephemeral sessions leave no rollout
+//! behind to index.
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::rollout::SESSIONS_SUBDIR;
+
+/// Directory holding the index, relative to the cache home.
+pub const RECALL_INDEX_DIR: &str = "recall";
+const INDEX_FILENAME: &str = "index.json";
+/// Bumped whenever the on-disk format changes; older indexes are rebuilt.
+const INDEX_VERSION: u32 = 1;
+
+pub const DEFAULT_RESULT_LIMIT: usize = 5;
+pub const MAX_RESULT_LIMIT: usize = 20;
+/// Characters of context shown around a match.
+const SNIPPET_CHARS: usize = 240;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecallRole {
+    User,
+    Assistant,
+}
+
+impl RecallRole {
+    fn as_str(self) -> &'static str {
+        match self {
+            RecallRole::User => "user",
+            RecallRole::Assistant => "assistant",
+        }
+    }
+}
+
+/// One indexed message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecallEntry {
+    pub session_id: String,
+    /// Session start time as recorded in the rollout (RFC 3339).
+    pub started_at: String,
+    /// Directory the session was started in.
+    pub cwd: PathBuf,
+    pub role: RecallRole,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct IndexedRollout {
+    /// Size of the rollout when it was indexed.
+    size: u64,
+    /// Directory the session was started in.
+    cwd: PathBuf,
+    /// Whether the session was left out because its project is excluded.
+    /// Kept so the rollout is re-read if the exclusion list changes.
+    excluded: bool,
+    entries: Vec<RecallEntry>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecallIndex {
+    version: u32,
+    rollouts: BTreeMap<PathBuf, IndexedRollout>,
+}
+
+impl Default for RecallIndex {
+    fn default() -> Self {
+        Self {
+            version: INDEX_VERSION,
+            rollouts: BTreeMap::new(),
+        }
+    }
+}
+
+/// What to look for and where.
+#[derive(Debug, Clone)]
+pub struct RecallQuery<'a> {
+    pub text: &'a str,
+    /// Only sessions started in or below this directory; `None` searches
+    /// every project.
+    pub project: Option<&'a Path>,
+    /// Usually the current session, whose messages are already in context.
+    pub exclude_session: Option<&'a str>,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecallHit {
+    pub session_id: String,
+    pub started_at: String,
+    pub cwd: PathBuf,
+    pub role: RecallRole,
+    pub snippet: String,
+}
+
+pub fn recall_index_dir(cache_home: &Path) -> PathBuf {
+    cache_home.join(RECALL_INDEX_DIR)
+}
+
+impl RecallIndex {
+    /// Load the index from `dir`, starting over when it is missing, corrupt,
+    /// or from an older version.
+    pub fn load(dir: &Path) -> Self {
+        std::fs::read_to_string(dir.join(INDEX_FILENAME))
+            .ok()
+            .and_then(|text| serde_json::from_str::<RecallIndex>(&text).ok())
+            .filter(|index| index.version == INDEX_VERSION)
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, dir: &Path) -> io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        let text = serde_json::to_string(self).map_err(io::Error::other)?;
+        let tmp = dir.join(format!("{INDEX_FILENAME}.tmp"));
+        std::fs::write(&tmp, text)?;
+        std::fs::rename(tmp, dir.join(INDEX_FILENAME))
+    }
+
+    /// Bring the index up to date with the rollouts under `codex_home`.
+    /// Returns whether anything changed.
+    pub fn refresh(&mut self, codex_home: &Path, exclude_projects: &[PathBuf]) -> io::Result<bool> {
+        let mut rollouts = Vec::new();
+        collect_rollouts(&codex_home.join(SESSIONS_SUBDIR), &mut rollouts)?;
+        let present: HashSet<&PathBuf> = rollouts.iter().collect();
+
+        let before = self.rollouts.len();
+        self.rollouts.retain(|path, indexed| {
+            present.contains(path)
+                && indexed.excluded == is_excluded(&indexed.cwd, exclude_projects)
+        });
+        let mut changed = self.rollouts.len() != before;
+
+        for path in &rollouts {
+            let Ok(size) = std::fs::metadata(path).map(|meta| meta.len()) else {
+                continue;
+            };
+            if self
+                .rollouts
+                .get(path)
+                .is_some_and(|indexed| indexed.size == size)
+            {
+                continue;
+            }
+            let (cwd, entries) = match read_rollout_entries(path) {
+                Ok(Some(read)) => read,
+                Ok(None) => continue,
+                Err(err) => {
+                    tracing::debug!("skipping rollout {}: {err}", path.display());
+                    continue;
+                }
+            };
+            // Excluded sessions are remembered without entries so they are
+            // not re-read on every refresh.
+            let excluded = is_excluded(&cwd, exclude_projects);
+            let entries = if excluded { Vec::new() } else { entries };
+            self.rollouts.insert(
+                path.clone(),
+                IndexedRollout {
+                    size,
+                    cwd,
+                    excluded,
+                    entries,
+                },
+            );
+            changed = true;
+        }
+        Ok(changed)
+    }
+
+    pub fn session_count(&self) -> usize {
+        self.rollouts
+            .values()
+            .filter(|indexed| !indexed.entries.is_empty())
+            .count()
+    }
+
+    pub fn entry_count(&self) -> usize {
+        self.rollouts
+            .values()
+            .map(|indexed| indexed.entries.len())
+            .sum()
+    }
+
+    /// Best matches for `query`, most matched terms first and newer sessions
+    /// before older ones.
+    pub fn search(&self, query: &RecallQuery<'_>) -> Vec<RecallHit> {
+        let terms = query_terms(query.text);
+        if terms.is_empty() {
+            return Vec::new();
+        }
+        let mut scored: Vec<(usize, &RecallEntry, usize)> = self
+            .rollouts
+            .values()
+            .flat_map(|indexed| &indexed.entries)
+            .filter(|entry| {
+                query
+                    .project
+                    .is_none_or(|project| entry.cwd.starts_with(project))
+            })
+            .filter(|entry| query.exclude_session != Some(entry.session_id.as_str()))
+            .filter_map(|entry| {
+                let lower = entry.text.to_ascii_lowercase();
+                let positions: Vec<usize> =
+                    terms.iter().filter_map(|term| lower.find(term)).collect();
+                let first = positions.iter().min().copied()?;
+                Some((positions.len(), entry, first))
+            })
+            .collect();
+        scored.sort_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then_with(|| b.1.started_at.cmp(&a.1.started_at))
+        });
+        scored
+            .into_iter()
+            .take(query.limit)
+            .map(|(_, entry, first)| RecallHit {
+                session_id: entry.session_id.clone(),
+                started_at: entry.started_at.clone(),
+                cwd: entry.cwd.clone(),
+                role: entry.role,
+                snippet: snippet_around(&entry.text, first),
+            })
+            .collect()
+    }
+}
+
+/// Delete the index under `cache_home`. Returns whether there was one.
+pub fn purge_index(cache_home: &Path) -> io::Result<bool> {
+    let dir = recall_index_dir(cache_home);
+    match std::fs::remove_dir_all(&dir) {
+        Ok(()) => Ok(true),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// Render hits as the tool response.
+pub fn format_hits(hits: &[RecallHit]) -> String {
+    if hits.is_empty() {
+        return "No matching messages in past sessions.".to_string();
+    }
+    let mut out = format!("Found {} matching messages in past sessions:\n", hits.len());
+    for (idx, hit) in hits.iter().enumerate() {
+        let date = hit.started_at.get(..10).unwrap_or(&hit.started_at);
+        out.push_str(&format!(
+            "\n{n}. {date} · session {id} · {role} · {cwd}\n   {snippet}\n",
+            n = idx + 1,
+            id = hit.session_id,
+            role = hit.role.as_str(),
+            cwd = hit.cwd.display(),
+            snippet = hit.snippet,
+        ));
+    }
+    out
+}
+
+fn is_excluded(cwd: &Path, exclude_projects: &[PathBuf]) -> bool {
+    exclude_projects
+        .iter()
+        .any(|project| cwd.starts_with(project))
+}
+
+fn collect_rollouts(dir: &Path, out: &mut Vec<PathBuf>) -> io::Result<()> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_rollouts(&path, out)?;
+        } else if path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("rollout-") && name.ends_with(".jsonl"))
+        {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// The session's `cwd` plus its user messages and the last assistant message
+/// of each turn. `None` when the rollout has no session metadata yet.
+fn read_rollout_entries(path: &Path) -> io::Result<Option<(PathBuf, Vec<RecallEntry>)>> {
+    let text = std::fs::read_to_string(path)?;
+    let mut session: Option<(String, String, PathBuf)> = None;
+    let mut entries = Vec::new();
+    let mut last_answer: Option<String> = None;
+
+    let push = |entries: &mut Vec<RecallEntry>,
+                session: &Option<(String, String, PathBuf)>,
+                role: RecallRole,
+                text: String| {
+        if let Some((session_id, started_at, cwd)) = session
+            && !text.trim().is_empty()
+        {
+            entries.push(RecallEntry {
+                session_id: session_id.clone(),
+                started_at: started_at.clone(),
+                cwd: cwd.clone(),
+                role,
+                text,
+            });
+        }
+    };
+
+    for line in text.lines() {
+        let Ok(line) = serde_json::from_str::<RolloutLine>(line) else {
+            continue;
+        };
+        match line.item {
+            RolloutItem::SessionMeta(meta) if session.is_none() => {
+                session = Some((meta.meta.id.to_string(), meta.meta.timestamp, meta.meta.cwd));
+            }
+            RolloutItem::EventMsg(EventMsg::UserMessage(event)) => {
+                if let Some(answer) = last_answer.take() {
+                    push(&mut entries, &session, RecallRole::Assistant, answer);
+                }
+                push(&mut entries, &session, RecallRole::User, event.message);
+            }
+            RolloutItem::EventMsg(EventMsg::AgentMessage(event)) => {
+                last_answer = Some(event.message);
+            }
+            _ => {}
+        }
+    }
+    if let Some(answer) = last_answer {
+        push(&mut entries, &session, RecallRole::Assistant, answer);
+    }
+    Ok(session.map(|(_, _, cwd)| (cwd, entries)))
+}
+
+fn query_terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|term| term.chars().count() >= 2)
+        .map(str::to_ascii_lowercase)
+        .collect();
+    terms.sort();
+    terms.dedup();
+    terms
+}
+
+/// Up to [`SNIPPET_CHARS`] of `text` around byte offset `at`, on one line.
+fn snippet_around(text: &str, at: usize) -> String {
+    let mut start = at.saturating_sub(SNIPPET_CHARS / 3);
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    let window: String = text[start..].chars().take(SNIPPET_CHARS).collect();
+    let truncated = start + window.len() < text.len();
+    let mut snippet = window.split_whitespace().collect::<Vec<_>>().join(" ");
+    if start > 0 {
+        snippet.insert_str(0, "…");
+    }
+    if truncated {
+        snippet.push('…');
+    }
+    snippet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::ConversationId;
+    use codex_protocol::protocol::AgentMessageEvent;
+    use codex_protocol::protocol::SessionMeta;
+    use codex_protocol::protocol::SessionMetaLine;
+    use codex_protocol::protocol::UserMessageEvent;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn write_rollout(
+        codex_home: &Path,
+        name: &str,
+        started_at: &str,
+        cwd: &str,
+        turns: &[(&str, &[&str])],
+    ) -> String {
+        let id = ConversationId::new();
+        let meta = RolloutItem::SessionMeta(SessionMetaLine {
+            meta: SessionMeta {
+                id,
+                timestamp: started_at.to_string(),
+                cwd: PathBuf::from(cwd),
+                ..Default::default()
+            },
+            git: None,
+        });
+        let mut items = vec![meta];
+        for (user, answers) in turns {
+            items.push(RolloutItem::EventMsg(EventMsg::UserMessage(
+                UserMessageEvent {
+                    message: user.to_string(),
+                    images: None,
+                },
+            )));
+            for answer in *answers {
+                items.push(RolloutItem::EventMsg(EventMsg::AgentMessage(
+                    AgentMessageEvent {
+                        message: answer.to_string(),
+                    },
+                )));
+            }
+        }
+        let dir = codex_home.join(SESSIONS_SUBDIR).join("2025/01/02");
+        std::fs::create_dir_all(&dir).unwrap();
+        let lines: Vec<String> = items
+            .into_iter()
+            .map(|item| {
+                serde_json::to_string(&RolloutLine {
+                    timestamp: started_at.to_string(),
+                    item,
+                })
+                .unwrap()
+            })
+            .collect();
+        std::fs::write(
+            dir.join(format!("rollout-{name}.jsonl")),
+            lines.join("\n") + "\n",
+        )
+        .unwrap();
+        id.to_string()
+    }
+
+    fn query(text: &str, project: Option<&Path>) -> RecallQuery<'_> {
+        RecallQuery {
+            text,
+            project,
+            exclude_session: None,
+            limit: DEFAULT_RESULT_LIMIT,
+        }
+    }
+
+    #[test]
+    fn indexes_user_messages_and_final_answers() {
+        let home = TempDir::new().unwrap();
+        let id = write_rollout(
+            home.path(),
+            "a",
+            "2025-01-02T10:00:00Z",
+            "/work/app",
+            &[(
+                "why does the build fail",
+                &["looking at it", "the linker flag is missing"],
+            )],
+        );
+        let mut index = RecallIndex::load(&home.path().join(RECALL_INDEX_DIR));
+        assert!(index.refresh(home.path(), &[]).unwrap());
+        assert!(!index.refresh(home.path(), &[]).unwrap());
+
+        let entry = |role, text: &str| RecallEntry {
+            session_id: id.clone(),
+            started_at: "2025-01-02T10:00:00Z".to_string(),
+            cwd: PathBuf::from("/work/app"),
+            role,
+            text: text.to_string(),
+        };
+        let entries: Vec<RecallEntry> = index
+            .rollouts
+            .values()
+            .flat_map(|indexed| indexed.entries.clone())
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                entry(RecallRole::User, "why does the build fail"),
+                entry(RecallRole::Assistant, "the linker flag is missing"),
+            ]
+        );
+
+        let dir = home.path().join(RECALL_INDEX_DIR);
+        index.save(&dir).unwrap();
+        assert_eq!(RecallIndex::load(&dir), index);
+        assert!(purge_index(home.path()).unwrap());
+        assert!(!purge_index(home.path()).unwrap());
+    }
+
+    #[test]
+    fn search_is_scoped_to_the_project_by_default() {
+        let home = TempDir::new().unwrap();
+        let app = write_rollout(
+            home.path(),
+            "a",
+            "2025-01-02T10:00:00Z",
+            "/work/app/server",
+            &[("deploy to staging", &["use the deploy script"])],
+        );
+        write_rollout(
+            home.path(),
+            "b",
+            "2025-01-03T10:00:00Z",
+            "/work/other",
+            &[("deploy docs", &[])],
+        );
+        let mut index = RecallIndex::default();
+        index.refresh(home.path(), &[]).unwrap();
+
+        let hits = index.search(&query("staging deploy", Some(Path::new("/work/app"))));
+        assert_eq!(
+            hits.iter()
+                .map(|hit| (hit.session_id.as_str(), hit.snippet.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (app.as_str(), "deploy to staging"),
+                (app.as_str(), "use the deploy script"),
+            ]
+        );
+        assert_eq!(index.search(&query("deploy", None)).len(), 3);
+    }
+
+    #[test]
+    fn excluded_projects_are_not_indexed() {
+        let home = TempDir::new().unwrap();
+        write_rollout(
+            home.path(),
+            "a",
+            "2025-01-02T10:00:00Z",
+            "/work/secret/api",
+            &[("the token is in vault", &[])],
+        );
+        let mut index = RecallIndex::default();
+        index.refresh(home.path(), &[]).unwrap();
+        assert_eq!(index.entry_count(), 1);
+
+        index
+            .refresh(home.path(), &[PathBuf::from("/work/secret")])
+            .unwrap();
+        assert_eq!(index.entry_count(), 0);
+        assert_eq!(index.search(&query("token", None)), Vec::new());
+    }
+
+    #[test]
+    fn hits_are_formatted_with_session_and_date() {
+        let hits = vec![RecallHit {
+            session_id: "abc".to_string(),
+            started_at: "2025-01-02T10:00:00Z".to_string(),
+            cwd: PathBuf::from("/work/app"),
+            role: RecallRole::Assistant,
+            snippet: "use the deploy script".to_string(),
+        }];
+        assert_eq!(
+            format_hits(&hits),
+            "Found 1 matching messages in past sessions:\n\
+             \n1. 2025-01-02 · session abc · assistant · /work/app\n   use the deploy script\n"
+        );
+        assert_eq!(format_hits(&[]), "No matching messages in past sessions.");
+    }
+
+    #[test]
+    fn snippets_are_windowed_around_the_match() {
+        let text = format!("{} needle {}", "a ".repeat(200), "b ".repeat(200));
+        let at = text.find("needle").unwrap();
+        let snippet = snippet_around(&text, at);
+        assert!(
+            snippet.starts_with('…') && snippet.ends_with('…'),
+            "{snippet}"
+        );
+        assert!(snippet.contains("needle"));
+    }
+}
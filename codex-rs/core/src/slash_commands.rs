@@ -0,0 +1,369 @@
+//! Registry of built-in slash commands shared by every frontend.
+//!
+//! The TUI popup and the app server's `commands/list` both read from here, so
+//! names, descriptions, and availability rules live in one place. Frontends
+//! still decide what a command *does*; this module only describes it.
+
+use std::collections::HashSet;
+
+use codex_protocol::custom_prompts::CustomPrompt;
+use codex_protocol::custom_prompts::PROMPTS_CMD_PREFIX;
+use strum::IntoEnumIterator;
+use strum_macros::AsRefStr;
+use strum_macros::EnumIter;
+use strum_macros::IntoStaticStr;
+
+use crate::config::Config;
+use crate::features::Feature;
+use crate::git_info::get_git_repo_root;
+
+/// Commands that can be invoked by starting a message with a leading slash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, AsRefStr, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum SlashCommand {
+    // DO NOT ALPHA-SORT! Enum order is presentation order in the popup, so
+    // more frequently used commands should be listed first.
+    Model,
+    Approvals,
+    Review,
+    New,
+    Init,
+    Compact,
+    Pin,
+    Plan,
+    Clear,
+    Unclear,
+    Undo,
+    SyncBase,
+    Diff,
+    Mention,
+    Status,
+    Context,
+    Env,
+    Cloud,
+    Mcp,
+    Logout,
+    Quit,
+    Feedback,
+    Rollout,
+    TestApproval,
+}
+
+/// Something a command needs before it is offered to the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandRequirement {
+    /// The session's working directory is inside a Git repository.
+    GitRepo,
+    /// The given feature flag is enabled.
+    Feature(Feature),
+    /// Development-only commands.
+    DebugBuild,
+}
+
+impl SlashCommand {
+    /// Command string without the leading '/'.
+    pub fn command(self) -> &'static str {
+        self.into()
+    }
+
+    /// Other names that invoke the same command.
+    pub fn aliases(self) -> &'static [&'static str] {
+        match self {
+            SlashCommand::Quit => &["exit"],
+            _ => &[],
+        }
+    }
+
+    /// User-visible description shown in the popup.
+    pub fn description(self) -> &'static str {
+        match self {
+            SlashCommand::Feedback => "send logs to maintainers",
+            SlashCommand::New => "start a new chat during a conversation",
+            SlashCommand::Init => "create an AGENTS.md file with instructions for Codex",
+            SlashCommand::Compact => "summarize conversation to prevent hitting the context limit",
+            SlashCommand::Pin => "keep your last message word for word when compacting",
+            SlashCommand::Plan => "pin the latest plan above the composer, or unpin it",
+            SlashCommand::Clear => "hide earlier output (the conversation is kept)",
+            SlashCommand::Unclear => "show output hidden by /clear",
+            SlashCommand::Review => "review my current changes and find issues",
+            SlashCommand::Undo => "ask Codex to undo a turn",
+            SlashCommand::SyncBase => "ask Codex to fetch and rebase onto the base branch",
+            SlashCommand::Quit => "exit Codex",
+            SlashCommand::Diff => "show git diff (including untracked files)",
+            SlashCommand::Mention => "mention a file",
+            SlashCommand::Status => "show current session configuration and token usage",
+            SlashCommand::Model => "choose what model and reasoning effort to use",
+            SlashCommand::Approvals => "choose what Codex can do without approval",
+            SlashCommand::Context => {
+                "inspect context; `/context scopes` lists path-scoped instructions"
+            }
+            SlashCommand::Env => "show the tool versions shared with Codex",
+            SlashCommand::Cloud => "show the status of your Codex Cloud tasks",
+            SlashCommand::Mcp => "list configured MCP tools",
+            SlashCommand::Logout => "log out of Codex",
+            SlashCommand::Rollout => "print the rollout file path",
+            SlashCommand::TestApproval => "test approval request",
+        }
+    }
+
+    /// Placeholder for the arguments the command accepts, if any.
+    pub fn argument_hint(self) -> Option<&'static str> {
+        match self {
+            SlashCommand::Context => Some("scopes"),
+            _ => None,
+        }
+    }
+
+    /// Whether this command can be run while a task is in progress.
+    pub fn available_during_task(self) -> bool {
+        match self {
+            SlashCommand::New
+            | SlashCommand::Init
+            | SlashCommand::Compact
+            | SlashCommand::Clear
+            | SlashCommand::Unclear
+            | SlashCommand::Undo
+            | SlashCommand::SyncBase
+            | SlashCommand::Model
+            | SlashCommand::Approvals
+            | SlashCommand::Review
+            | SlashCommand::Logout => false,
+            SlashCommand::Diff
+            | SlashCommand::Pin
+            | SlashCommand::Plan
+            | SlashCommand::Mention
+            | SlashCommand::Status
+            | SlashCommand::Context
+            | SlashCommand::Env
+            | SlashCommand::Cloud
+            | SlashCommand::Mcp
+            | SlashCommand::Feedback
+            | SlashCommand::Quit
+            | SlashCommand::Rollout
+            | SlashCommand::TestApproval => true,
+        }
+    }
+
+    pub fn requirements(self) -> &'static [CommandRequirement] {
+        match self {
+            SlashCommand::Review | SlashCommand::SyncBase | SlashCommand::Diff => {
+                &[CommandRequirement::GitRepo]
+            }
+            SlashCommand::Undo => &[CommandRequirement::Feature(Feature::GhostCommit)],
+            SlashCommand::Rollout | SlashCommand::TestApproval => &[CommandRequirement::DebugBuild],
+            _ => &[],
+        }
+    }
+
+    /// Look up a command by its name or one of its aliases.
+    pub fn from_name(name: &str) -> Option<Self> {
+        SlashCommand::iter()
+            .find(|command| command.command() == name || command.aliases().contains(&name))
+    }
+}
+
+/// Environment facts that decide which built-in commands are offered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandAvailability {
+    pub in_git_repo: bool,
+    pub ghost_commits: bool,
+}
+
+impl CommandAvailability {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            in_git_repo: get_git_repo_root(&config.cwd).is_some(),
+            ghost_commits: config.features.enabled(Feature::GhostCommit),
+        }
+    }
+
+    /// Offer every command regardless of the environment (debug-only
+    /// commands still follow the build profile).
+    pub fn all() -> Self {
+        Self {
+            in_git_repo: true,
+            ghost_commits: true,
+        }
+    }
+
+    pub fn allows(self, command: SlashCommand) -> bool {
+        command
+            .requirements()
+            .iter()
+            .all(|requirement| match requirement {
+                CommandRequirement::GitRepo => self.in_git_repo,
+                CommandRequirement::Feature(Feature::GhostCommit) => self.ghost_commits,
+                CommandRequirement::Feature(_) => true,
+                CommandRequirement::DebugBuild => cfg!(debug_assertions),
+            })
+    }
+}
+
+/// Built-in commands offered under `availability`, in presentation order.
+pub fn builtin_commands(availability: CommandAvailability) -> Vec<SlashCommand> {
+    SlashCommand::iter()
+        .filter(|command| availability.allows(*command))
+        .collect()
+}
+
+/// Where a listed command comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandSource {
+    Builtin,
+    /// A saved prompt from `$CODEX_HOME/prompts`.
+    CustomPrompt,
+}
+
+/// A command as presented to the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandInfo {
+    /// Name without the leading '/'.
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub description: String,
+    pub argument_hint: Option<String>,
+    pub available_during_task: bool,
+    pub source: CommandSource,
+}
+
+impl From<SlashCommand> for CommandInfo {
+    fn from(command: SlashCommand) -> Self {
+        Self {
+            name: command.command().to_string(),
+            aliases: command.aliases().iter().map(ToString::to_string).collect(),
+            description: command.description().to_string(),
+            argument_hint: command.argument_hint().map(str::to_string),
+            available_during_task: command.available_during_task(),
+            source: CommandSource::Builtin,
+        }
+    }
+}
+
+/// Every command a frontend should offer: the available built-ins followed by
+/// custom prompts sorted by name. Prompts named like a built-in are dropped.
+pub fn list_commands(
+    availability: CommandAvailability,
+    custom_prompts: &[CustomPrompt],
+) -> Vec<CommandInfo> {
+    let builtins = builtin_commands(availability);
+    let reserved: HashSet<&str> = SlashCommand::iter()
+        .flat_map(|command| {
+            std::iter::once(command.command()).chain(command.aliases().iter().copied())
+        })
+        .collect();
+    let mut prompts: Vec<&CustomPrompt> = custom_prompts
+        .iter()
+        .filter(|prompt| !reserved.contains(prompt.name.as_str()))
+        .collect();
+    prompts.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut commands: Vec<CommandInfo> = builtins.into_iter().map(CommandInfo::from).collect();
+    commands.extend(prompts.into_iter().map(|prompt| {
+        CommandInfo {
+            name: format!("{PROMPTS_CMD_PREFIX}:{}", prompt.name),
+            aliases: Vec::new(),
+            description: prompt
+                .description
+                .clone()
+                .unwrap_or_else(|| "send saved prompt".to_string()),
+            argument_hint: prompt.argument_hint.clone(),
+            // Prompts expand into a user message, which queues during a task.
+            available_during_task: true,
+            source: CommandSource::CustomPrompt,
+        }
+    }));
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn prompt(name: &str, description: Option<&str>) -> CustomPrompt {
+        CustomPrompt {
+            name: name.to_string(),
+            path: format!("/tmp/{name}.md").into(),
+            content: "body".to_string(),
+            description: description.map(str::to_string),
+            argument_hint: None,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn aliases_resolve_to_their_command() {
+        assert_eq!(SlashCommand::from_name("exit"), Some(SlashCommand::Quit));
+        assert_eq!(SlashCommand::from_name("quit"), Some(SlashCommand::Quit));
+        assert_eq!(
+            SlashCommand::from_name("sync-base"),
+            Some(SlashCommand::SyncBase)
+        );
+        assert_eq!(SlashCommand::from_name("nope"), None);
+    }
+
+    #[test]
+    fn unmet_requirements_hide_commands() {
+        let availability = CommandAvailability {
+            in_git_repo: false,
+            ghost_commits: false,
+        };
+        let hidden: Vec<SlashCommand> = builtin_commands(CommandAvailability::all())
+            .into_iter()
+            .filter(|command| !builtin_commands(availability).contains(command))
+            .collect();
+        assert_eq!(
+            hidden,
+            vec![
+                SlashCommand::Review,
+                SlashCommand::Undo,
+                SlashCommand::SyncBase,
+                SlashCommand::Diff,
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_prompts_follow_builtins_and_skip_reserved_names() {
+        let prompts = vec![
+            prompt("zeta", None),
+            prompt("init", Some("shadowed")),
+            prompt("exit", None),
+            prompt("alpha", Some("Draft a PR")),
+        ];
+        let commands = list_commands(CommandAvailability::all(), &prompts);
+        let custom: Vec<CommandInfo> = commands
+            .iter()
+            .filter(|command| command.source == CommandSource::CustomPrompt)
+            .cloned()
+            .collect();
+        assert_eq!(
+            custom,
+            vec![
+                CommandInfo {
+                    name: "prompts:alpha".to_string(),
+                    aliases: Vec::new(),
+                    description: "Draft a PR".to_string(),
+                    argument_hint: None,
+                    available_during_task: true,
+                    source: CommandSource::CustomPrompt,
+                },
+                CommandInfo {
+                    name: "prompts:zeta".to_string(),
+                    aliases: Vec::new(),
+                    description: "send saved prompt".to_string(),
+                    argument_hint: None,
+                    available_during_task: true,
+                    source: CommandSource::CustomPrompt,
+                },
+            ]
+        );
+        assert_eq!(
+            commands[..commands.len() - custom.len()].to_vec(),
+            builtin_commands(CommandAvailability::all())
+                .into_iter()
+                .map(CommandInfo::from)
+                .collect::<Vec<_>>()
+        );
+    }
+}
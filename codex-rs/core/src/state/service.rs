@@ -2,7 +2,13 @@ use std::sync::Arc;
 
 use crate::AuthManager;
 use crate::RolloutRecorder;
+use crate::command_allowlist::CommandAllowlist;
+use crate::git_worktree::SessionBaseCheck;
+use crate::input_prompt::InputPromptBroker;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::mcp_tool_cache::McpToolResultCache;
+use crate::resume_briefing::SessionRepoState;
+use crate::tool_inventory::SessionToolInventory;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::unified_exec::UnifiedExecSessionManager;
 use crate::user_notification::UserNotifier;
@@ -14,6 +20,7 @@ use tokio_util::sync::CancellationToken;
 pub(crate) struct SessionServices {
     pub(crate) mcp_connection_manager: Arc<RwLock<McpConnectionManager>>,
     pub(crate) mcp_startup_cancellation_token: CancellationToken,
+    pub(crate) mcp_tool_cache: Mutex<McpToolResultCache>,
     pub(crate) unified_exec_manager: UnifiedExecSessionManager,
     pub(crate) notifier: UserNotifier,
     pub(crate) rollout: Mutex<Option<RolloutRecorder>>,
@@ -22,4 +29,9 @@ pub(crate) struct SessionServices {
     pub(crate) auth_manager: Arc<AuthManager>,
     pub(crate) otel_event_manager: OtelEventManager,
     pub(crate) tool_approvals: Mutex<ApprovalStore>,
+    pub(crate) command_allowlist: Mutex<CommandAllowlist>,
+    pub(crate) tool_inventory: SessionToolInventory,
+    pub(crate) base_check: SessionBaseCheck,
+    pub(crate) repo_state: SessionRepoState,
+    pub(crate) input_prompts: Arc<InputPromptBroker>,
 }
@@ -1,19 +1,30 @@
 //! Session-wide mutable state.
 
 use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::RolloutItem;
 
 use crate::codex::SessionConfiguration;
 use crate::context_manager::ContextManager;
+use crate::environment_context::EnvironmentContext;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
-use crate::truncate::TruncationPolicy;
+use crate::truncate::HistoryTruncation;
 
 /// Persistent, session-scoped state previously stored directly on `Session`.
 pub(crate) struct SessionState {
     pub(crate) session_configuration: SessionConfiguration,
     pub(crate) history: ContextManager,
     pub(crate) latest_rate_limits: Option<RateLimitSnapshot>,
+    /// Model and estimation flag for the most recent usage update.
+    pub(crate) last_usage_model: Option<String>,
+    pub(crate) last_usage_estimated: bool,
+    /// Indices into the session's scoped instructions sent with the latest request.
+    pub(crate) active_scopes: Vec<usize>,
+    /// Environment recorded by the last turn of a resumed or forked rollout.
+    /// The first turn compares against it instead of the session's own
+    /// starting context, so changes made across the resume reach the model.
+    pub(crate) resumed_environment: Option<EnvironmentContext>,
 }
 
 impl SessionState {
@@ -24,16 +35,20 @@ impl SessionState {
             session_configuration,
             history,
             latest_rate_limits: None,
+            last_usage_model: None,
+            last_usage_estimated: false,
+            active_scopes: Vec::new(),
+            resumed_environment: None,
         }
     }
 
     // History helpers
-    pub(crate) fn record_items<I>(&mut self, items: I, policy: TruncationPolicy)
+    pub(crate) fn record_items<I>(&mut self, items: I, truncation: impl HistoryTruncation)
     where
         I: IntoIterator,
         I::Item: std::ops::Deref<Target = ResponseItem>,
     {
-        self.history.record_items(items, policy);
+        self.history.record_items(items, truncation);
     }
 
     pub(crate) fn clone_history(&self) -> ContextManager {
@@ -44,6 +59,23 @@ impl SessionState {
         self.history.replace(items);
     }
 
+    pub(crate) fn set_turn_irrelevant(&mut self, nth_user_message: usize, irrelevant: bool) {
+        self.history
+            .set_turn_irrelevant(nth_user_message, irrelevant);
+    }
+
+    pub(crate) fn set_turn_pinned(&mut self, nth_user_message: usize, pinned: bool) -> bool {
+        self.history.set_turn_pinned(nth_user_message, pinned)
+    }
+
+    pub(crate) fn pinned_messages(&mut self) -> Vec<ResponseItem> {
+        self.history.pinned_messages()
+    }
+
+    pub(crate) fn restore_turn_flags(&mut self, rollout_items: &[RolloutItem]) {
+        self.history.restore_turn_flags(rollout_items);
+    }
+
     pub(crate) fn set_token_info(&mut self, info: Option<TokenUsageInfo>) {
         self.history.set_token_info(info);
     }
@@ -57,6 +89,11 @@ impl SessionState {
         self.history.update_token_info(usage, model_context_window);
     }
 
+    pub(crate) fn set_last_usage_source(&mut self, model: String, estimated: bool) {
+        self.last_usage_model = Some(model);
+        self.last_usage_estimated = estimated;
+    }
+
     pub(crate) fn token_info(&self) -> Option<TokenUsageInfo> {
         self.history.token_info()
     }
@@ -71,6 +108,11 @@ impl SessionState {
         (self.token_info(), self.latest_rate_limits.clone())
     }
 
+    /// Record the scoped instructions included in the latest request.
+    pub(crate) fn set_active_scopes(&mut self, active: Vec<usize>) {
+        self.active_scopes = active;
+    }
+
     pub(crate) fn set_token_usage_full(&mut self, context_window: i64) {
         self.history.set_token_usage_full(context_window);
     }
@@ -2,6 +2,7 @@
 
 use indexmap::IndexMap;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tokio::sync::Notify;
@@ -12,6 +13,7 @@ use codex_protocol::models::ResponseInputItem;
 use tokio::sync::oneshot;
 
 use crate::codex::TurnContext;
+use crate::protocol::FileChange;
 use crate::protocol::ReviewDecision;
 use crate::tasks::SessionTask;
 
@@ -67,6 +69,8 @@ impl ActiveTurn {
 #[derive(Default)]
 pub(crate) struct TurnState {
     pending_approvals: HashMap<String, oneshot::Sender<ReviewDecision>>,
+    /// Changes the user picked when approving part of a patch.
+    patch_selections: HashMap<String, HashMap<PathBuf, FileChange>>,
     pending_input: Vec<ResponseInputItem>,
 }
 
@@ -86,8 +90,29 @@ impl TurnState {
         self.pending_approvals.remove(key)
     }
 
+    /// Removes every pending approval, e.g. to deny them all at once.
+    pub(crate) fn take_pending_approvals(&mut self) -> Vec<oneshot::Sender<ReviewDecision>> {
+        self.pending_approvals.drain().map(|(_, tx)| tx).collect()
+    }
+
+    pub(crate) fn insert_patch_selection(
+        &mut self,
+        key: String,
+        changes: HashMap<PathBuf, FileChange>,
+    ) {
+        self.patch_selections.insert(key, changes);
+    }
+
+    pub(crate) fn take_patch_selection(
+        &mut self,
+        key: &str,
+    ) -> Option<HashMap<PathBuf, FileChange>> {
+        self.patch_selections.remove(key)
+    }
+
     pub(crate) fn clear_pending(&mut self) {
         self.pending_approvals.clear();
+        self.patch_selections.clear();
         self.pending_input.clear();
     }
 
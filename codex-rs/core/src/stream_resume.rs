@@ -0,0 +1,485 @@
+//! Reconnects a model response stream that drops before completion.
+//!
+//! Neither wire API lets us pick a dropped response back up: the Responses
+//! API only resumes background responses, and Codex sends `store: false`.
+//! So a reconnect re-sends the same request and replays the new response
+//! against what was already forwarded. Events the caller has seen are
+//! skipped, text deltas are matched by prefix (the new response may chunk
+//! differently), and forwarding resumes where the old stream stopped. If the
+//! new response does not start with the same output, nothing can be spliced
+//! and the caller gets the original error, falling back to a turn retry.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use codex_protocol::models::ResponseItem;
+use futures::StreamExt;
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tracing::info;
+use tracing::warn;
+
+use crate::client_common::ResponseEvent;
+use crate::client_common::ResponseStream;
+use crate::error::CodexErr;
+use crate::error::Result;
+use crate::util::backoff;
+
+/// Streams resumed since startup, reported with each resume in the log.
+static RESUMED_STREAMS: AtomicU64 = AtomicU64::new(0);
+
+/// Stream retries shared by the reconnects here and the turn retries in
+/// `codex.rs`, so one dropped stream costs at most `max` extra requests
+/// however it ends up being retried.
+#[derive(Clone, Debug)]
+pub(crate) struct StreamRetryBudget {
+    used: Arc<AtomicU64>,
+    max: u64,
+}
+
+impl StreamRetryBudget {
+    pub(crate) fn new(max: u64) -> Self {
+        Self {
+            used: Arc::new(AtomicU64::new(0)),
+            max,
+        }
+    }
+
+    /// Spend one retry. Returns its number, starting at 1, or `None` once
+    /// the budget is used up.
+    pub(crate) fn take(&self) -> Option<u64> {
+        self.used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                (used < self.max).then_some(used + 1)
+            })
+            .ok()
+            .map(|used| used + 1)
+    }
+
+    pub(crate) fn max(&self) -> u64 {
+        self.max
+    }
+}
+
+/// Forward `first` to the returned stream. When it fails with a retryable
+/// stream error before `Completed`, call `reconnect` (while `budget` lasts)
+/// and continue from the new stream without repeating events. Errors from
+/// `reconnect` itself, such as a 4xx response, end the stream.
+pub(crate) fn resume_on_disconnect<F, Fut>(
+    first: ResponseStream,
+    budget: StreamRetryBudget,
+    mut reconnect: F,
+) -> ResponseStream
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = Result<ResponseStream>> + Send + 'static,
+{
+    let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+    tokio::spawn(async move {
+        let mut stream = first;
+        let mut filter = ReplayFilter::default();
+        while let Some(event) = stream.next().await {
+            let event = match event {
+                Ok(event) => event,
+                Err(CodexErr::Stream(message, delay)) => {
+                    let Some(attempt) = budget.take() else {
+                        let _ = tx_event.send(Err(CodexErr::Stream(message, delay))).await;
+                        return;
+                    };
+                    let total = RESUMED_STREAMS.fetch_add(1, Ordering::Relaxed) + 1;
+                    let delay = delay.unwrap_or_else(|| backoff(attempt));
+                    let max = budget.max();
+                    info!(
+                        resumed_streams = total,
+                        "stream disconnected ({message}); reconnecting ({attempt}/{max}) in {delay:?}"
+                    );
+                    tokio::time::sleep(delay).await;
+                    match reconnect().await {
+                        Ok(next) => {
+                            stream = next;
+                            filter.start_replay();
+                            continue;
+                        }
+                        Err(err) => {
+                            let _ = tx_event.send(Err(err)).await;
+                            return;
+                        }
+                    }
+                }
+                Err(err) => {
+                    let _ = tx_event.send(Err(err)).await;
+                    return;
+                }
+            };
+
+            let completed = matches!(event, ResponseEvent::Completed { .. });
+            match filter.filter(event) {
+                Replayed::Forward(event) => {
+                    if tx_event.send(Ok(event)).await.is_err() {
+                        return;
+                    }
+                }
+                Replayed::Skip => {}
+                Replayed::Diverged => {
+                    warn!("reconnected response diverged from output already streamed");
+                    let _ = tx_event
+                        .send(Err(CodexErr::Stream(
+                            "stream disconnected and the retried response did not match".into(),
+                            None,
+                        )))
+                        .await;
+                    return;
+                }
+            }
+            if completed {
+                return;
+            }
+        }
+    });
+    ResponseStream { rx_event }
+}
+
+#[derive(Debug, PartialEq)]
+enum Replayed {
+    Forward(ResponseEvent),
+    /// Already forwarded from an earlier stream.
+    Skip,
+    /// The new stream does not continue what was forwarded.
+    Diverged,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DeltaKind {
+    OutputText,
+    ReasoningSummary(i64),
+    ReasoningContent(i64),
+}
+
+/// What was forwarded, with consecutive deltas of one kind merged.
+#[derive(Debug, PartialEq)]
+enum Forwarded {
+    Event(Value),
+    Text(DeltaKind, String),
+}
+
+/// Where a replaying stream is within `ReplayFilter::forwarded`.
+#[derive(Debug, Default)]
+struct ReplayCursor {
+    index: usize,
+    /// Bytes of `forwarded[index]` matched so far, for text entries.
+    matched: usize,
+}
+
+#[derive(Debug, Default)]
+struct ReplayFilter {
+    forwarded: Vec<Forwarded>,
+    /// Set while a reconnected stream is still catching up.
+    replay: Option<ReplayCursor>,
+}
+
+impl ReplayFilter {
+    fn start_replay(&mut self) {
+        self.replay = (!self.forwarded.is_empty()).then(ReplayCursor::default);
+    }
+
+    fn filter(&mut self, event: ResponseEvent) -> Replayed {
+        // Rate limits describe the account, not the response; always pass
+        // them on.
+        if matches!(event, ResponseEvent::RateLimits(_)) {
+            return Replayed::Forward(event);
+        }
+        if self.replay.is_none() {
+            self.record(&event);
+            return Replayed::Forward(event);
+        }
+
+        match delta_parts(&event) {
+            Some((kind, delta)) => self.replay_delta(kind, delta),
+            None => self.replay_event(&event),
+        }
+    }
+
+    fn replay_delta(&mut self, kind: DeltaKind, delta: &str) -> Replayed {
+        let Some(cursor) = self.replay.as_mut() else {
+            return Replayed::Diverged;
+        };
+        let Some(Forwarded::Text(seen_kind, seen)) = self.forwarded.get(cursor.index) else {
+            return Replayed::Diverged;
+        };
+        if *seen_kind != kind {
+            return Replayed::Diverged;
+        }
+        let remaining = &seen[cursor.matched..];
+        if let Some(rest) = remaining.strip_prefix(delta) {
+            cursor.matched += delta.len();
+            if rest.is_empty() {
+                self.advance();
+            }
+            return Replayed::Skip;
+        }
+        let Some(new_text) = delta.strip_prefix(remaining) else {
+            return Replayed::Diverged;
+        };
+        // The delta runs past what was forwarded, which is only possible if
+        // this was the last thing forwarded before the drop.
+        if cursor.index + 1 != self.forwarded.len() {
+            return Replayed::Diverged;
+        }
+        let new_text = new_text.to_string();
+        self.advance();
+        let event = delta_event(kind, new_text);
+        self.record(&event);
+        Replayed::Forward(event)
+    }
+
+    fn replay_event(&mut self, event: &ResponseEvent) -> Replayed {
+        let Some(cursor) = self.replay.as_ref() else {
+            return Replayed::Diverged;
+        };
+        if cursor.matched != 0 {
+            return Replayed::Diverged;
+        }
+        match (self.forwarded.get(cursor.index), fingerprint(event)) {
+            (Some(Forwarded::Event(seen)), Some(current)) if *seen == current => {
+                self.advance();
+                Replayed::Skip
+            }
+            _ => Replayed::Diverged,
+        }
+    }
+
+    fn advance(&mut self) {
+        if let Some(cursor) = self.replay.as_mut() {
+            cursor.index += 1;
+            cursor.matched = 0;
+            if cursor.index >= self.forwarded.len() {
+                self.replay = None;
+            }
+        }
+    }
+
+    fn record(&mut self, event: &ResponseEvent) {
+        if let Some((kind, delta)) = delta_parts(event) {
+            match self.forwarded.last_mut() {
+                Some(Forwarded::Text(last_kind, text)) if *last_kind == kind => {
+                    text.push_str(delta);
+                }
+                _ => self
+                    .forwarded
+                    .push(Forwarded::Text(kind, delta.to_string())),
+            }
+        } else if let Some(fingerprint) = fingerprint(event) {
+            self.forwarded.push(Forwarded::Event(fingerprint));
+        }
+    }
+}
+
+fn delta_parts(event: &ResponseEvent) -> Option<(DeltaKind, &str)> {
+    match event {
+        ResponseEvent::OutputTextDelta(delta) => Some((DeltaKind::OutputText, delta)),
+        ResponseEvent::ReasoningSummaryDelta {
+            delta,
+            summary_index,
+        } => Some((DeltaKind::ReasoningSummary(*summary_index), delta)),
+        ResponseEvent::ReasoningContentDelta {
+            delta,
+            content_index,
+        } => Some((DeltaKind::ReasoningContent(*content_index), delta)),
+        _ => None,
+    }
+}
+
+fn delta_event(kind: DeltaKind, delta: String) -> ResponseEvent {
+    match kind {
+        DeltaKind::OutputText => ResponseEvent::OutputTextDelta(delta),
+        DeltaKind::ReasoningSummary(summary_index) => ResponseEvent::ReasoningSummaryDelta {
+            delta,
+            summary_index,
+        },
+        DeltaKind::ReasoningContent(content_index) => ResponseEvent::ReasoningContentDelta {
+            delta,
+            content_index,
+        },
+    }
+}
+
+/// A comparable form of a non-delta event. Ids and encrypted reasoning are
+/// left out because a re-sent request gets fresh ones.
+fn fingerprint(event: &ResponseEvent) -> Option<Value> {
+    match event {
+        ResponseEvent::Created => Some(Value::from("created")),
+        ResponseEvent::OutputItemAdded(item) => Some(item_fingerprint("added", item)),
+        ResponseEvent::OutputItemDone(item) => Some(item_fingerprint("done", item)),
+        ResponseEvent::ReasoningSummaryPartAdded { summary_index } => {
+            Some(serde_json::json!({ "summary_part_added": summary_index }))
+        }
+        ResponseEvent::Completed { .. }
+        | ResponseEvent::RateLimits(_)
+        | ResponseEvent::OutputTextDelta(_)
+        | ResponseEvent::ReasoningSummaryDelta { .. }
+        | ResponseEvent::ReasoningContentDelta { .. } => None,
+    }
+}
+
+fn item_fingerprint(stage: &str, item: &ResponseItem) -> Value {
+    let mut value = serde_json::to_value(item).unwrap_or(Value::Null);
+    strip_volatile_fields(&mut value);
+    serde_json::json!([stage, value])
+}
+
+fn strip_volatile_fields(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for key in ["id", "call_id", "encrypted_content"] {
+                map.remove(key);
+            }
+            map.values_mut().for_each(strip_volatile_fields);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_volatile_fields),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ContentItem;
+    use pretty_assertions::assert_eq;
+
+    fn message(id: &str, text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: Some(id.to_string()),
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    fn text(delta: &str) -> ResponseEvent {
+        ResponseEvent::OutputTextDelta(delta.to_string())
+    }
+
+    /// Run `events` through `filter`, returning what it forwarded, or
+    /// `None` once it reports divergence.
+    fn run(filter: &mut ReplayFilter, events: Vec<ResponseEvent>) -> Option<Vec<ResponseEvent>> {
+        let mut forwarded = Vec::new();
+        for event in events {
+            match filter.filter(event) {
+                Replayed::Forward(event) => forwarded.push(event),
+                Replayed::Skip => {}
+                Replayed::Diverged => return None,
+            }
+        }
+        Some(forwarded)
+    }
+
+    #[test]
+    fn retry_budget_is_shared_between_clones() {
+        let budget = StreamRetryBudget::new(3);
+        let resumes = budget.clone();
+
+        assert_eq!(
+            [resumes.take(), budget.take(), resumes.take(), budget.take()],
+            [Some(1), Some(2), Some(3), None]
+        );
+    }
+
+    #[test]
+    fn replay_forwards_only_what_was_not_seen() {
+        let mut filter = ReplayFilter::default();
+        run(
+            &mut filter,
+            vec![
+                ResponseEvent::Created,
+                ResponseEvent::OutputItemAdded(message("msg-1", "")),
+                text("Hel"),
+                text("lo, wo"),
+            ],
+        );
+
+        filter.start_replay();
+        let forwarded = run(
+            &mut filter,
+            vec![
+                ResponseEvent::Created,
+                ResponseEvent::OutputItemAdded(message("msg-2", "")),
+                text("Hello"),
+                text(", world"),
+                text("!"),
+                ResponseEvent::OutputItemDone(message("msg-2", "Hello, world!")),
+            ],
+        );
+
+        assert_eq!(
+            forwarded,
+            Some(vec![
+                text("rld"),
+                text("!"),
+                ResponseEvent::OutputItemDone(message("msg-2", "Hello, world!")),
+            ])
+        );
+    }
+
+    #[test]
+    fn replay_skips_completed_items() {
+        let mut filter = ReplayFilter::default();
+        run(
+            &mut filter,
+            vec![
+                ResponseEvent::Created,
+                ResponseEvent::OutputItemDone(message("msg-1", "first")),
+            ],
+        );
+
+        filter.start_replay();
+        let forwarded = run(
+            &mut filter,
+            vec![
+                ResponseEvent::Created,
+                ResponseEvent::OutputItemDone(message("msg-9", "first")),
+                ResponseEvent::OutputItemDone(message("msg-10", "second")),
+            ],
+        );
+
+        assert_eq!(
+            forwarded,
+            Some(vec![ResponseEvent::OutputItemDone(message(
+                "msg-10", "second"
+            ))])
+        );
+    }
+
+    #[test]
+    fn replay_with_different_text_diverges() {
+        let mut filter = ReplayFilter::default();
+        run(&mut filter, vec![ResponseEvent::Created, text("Hello")]);
+
+        filter.start_replay();
+        assert_eq!(
+            run(&mut filter, vec![ResponseEvent::Created, text("Howdy")]),
+            None
+        );
+    }
+
+    #[test]
+    fn replay_that_ends_early_diverges() {
+        let mut filter = ReplayFilter::default();
+        run(&mut filter, vec![ResponseEvent::Created, text("Hello")]);
+
+        filter.start_replay();
+        assert_eq!(
+            run(
+                &mut filter,
+                vec![
+                    ResponseEvent::Created,
+                    text("Hel"),
+                    ResponseEvent::OutputItemDone(message("msg-2", "Hel")),
+                ]
+            ),
+            None
+        );
+    }
+}
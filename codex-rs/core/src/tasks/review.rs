@@ -16,6 +16,7 @@ use tokio_util::sync::CancellationToken;
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::codex_delegate::run_codex_conversation_one_shot;
+use crate::review_anchor::anchor_findings;
 use crate::review_format::format_review_findings_block;
 use crate::state::TaskKind;
 use codex_protocol::user_input::UserInput;
@@ -103,6 +104,11 @@ async fn start_review_conversation(
         .disable(crate::features::Feature::WebSearchRequest)
         .disable(crate::features::Feature::ViewImageTool);
 
+    // The parent session already notifies for the events it forwards, so
+    // the delegate must not run the same hooks a second time.
+    sub_agent_config.notify = None;
+    sub_agent_config.notification_hooks = crate::config::types::NotificationHooks::default();
+
     // Set explicit review rubric for the sub-agent
     sub_agent_config.base_instructions = Some(crate::REVIEW_PROMPT.to_string());
     (run_codex_conversation_one_shot(
@@ -195,10 +201,15 @@ fn parse_review_output_event(text: &str) -> ReviewOutputEvent {
 /// and records a developer message with the review output.
 pub(crate) async fn exit_review_mode(
     session: Arc<Session>,
-    review_output: Option<ReviewOutputEvent>,
+    mut review_output: Option<ReviewOutputEvent>,
     ctx: Arc<TurnContext>,
     append_to_original_thread: bool,
 ) {
+    // Record what each finding points at now, so frontends can follow it
+    // through later edits.
+    if let Some(out) = review_output.as_mut() {
+        anchor_findings(&mut out.findings);
+    }
     if append_to_original_thread {
         let user_message = if let Some(out) = review_output.clone() {
             let mut findings_str = String::new();
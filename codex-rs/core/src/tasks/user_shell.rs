@@ -25,6 +25,7 @@ use crate::protocol::SandboxPolicy;
 use crate::protocol::TaskStartedEvent;
 use crate::sandboxing::ExecEnv;
 use crate::state::TaskKind;
+use crate::tools::events::filtered_env_vars;
 use crate::tools::format_exec_output_str;
 use crate::user_shell_command::user_shell_command_record_item;
 
@@ -85,6 +86,7 @@ impl SessionTask for UserShellCommandTask {
                     parsed_cmd: parsed_cmd.clone(),
                     source: ExecCommandSource::UserShell,
                     interaction_input: None,
+                    filtered_env_vars: filtered_env_vars(&turn_context.shell_environment_policy),
                 }),
             )
             .await;
@@ -104,6 +106,7 @@ impl SessionTask for UserShellCommandTask {
             sub_id: turn_context.sub_id.clone(),
             call_id: call_id.clone(),
             tx_event: session.get_tx_event(),
+            input_prompts: None,
         });
 
         let sandbox_policy = SandboxPolicy::DangerFullAccess;
@@ -119,6 +122,7 @@ impl SessionTask for UserShellCommandTask {
                     stdout: StreamOutput::new(String::new()),
                     stderr: StreamOutput::new(aborted_message.clone()),
                     aggregated_output: StreamOutput::new(aborted_message.clone()),
+                    stderr_ranges: Vec::new(),
                     duration: Duration::ZERO,
                     timed_out: false,
                 };
@@ -144,6 +148,7 @@ impl SessionTask for UserShellCommandTask {
                             stdout: String::new(),
                             stderr: aborted_message.clone(),
                             aggregated_output: aborted_message.clone(),
+                            stderr_ranges: Vec::new(),
                             exit_code: -1,
                             duration: Duration::ZERO,
                             formatted_output: aborted_message,
@@ -166,6 +171,7 @@ impl SessionTask for UserShellCommandTask {
                             stdout: output.stdout.text.clone(),
                             stderr: output.stderr.text.clone(),
                             aggregated_output: output.aggregated_output.text.clone(),
+                            stderr_ranges: output.stderr_ranges.clone(),
                             exit_code: output.exit_code,
                             duration: output.duration,
                             formatted_output: format_exec_output_str(
@@ -193,6 +199,7 @@ impl SessionTask for UserShellCommandTask {
                     stdout: StreamOutput::new(String::new()),
                     stderr: StreamOutput::new(message.clone()),
                     aggregated_output: StreamOutput::new(message.clone()),
+                    stderr_ranges: Vec::new(),
                     duration: Duration::ZERO,
                     timed_out: false,
                 };
@@ -210,6 +217,7 @@ impl SessionTask for UserShellCommandTask {
                             stdout: exec_output.stdout.text.clone(),
                             stderr: exec_output.stderr.text.clone(),
                             aggregated_output: exec_output.aggregated_output.text.clone(),
+                            stderr_ranges: Vec::new(),
                             exit_code: exec_output.exit_code,
                             duration: exec_output.duration,
                             formatted_output: format_exec_output_str(
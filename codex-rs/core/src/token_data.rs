@@ -1,4 +1,6 @@
 use base64::Engine;
+use chrono::DateTime;
+use chrono::Utc;
 use serde::Deserialize;
 use serde::Serialize;
 use thiserror::Error;
@@ -87,15 +89,33 @@ pub enum IdTokenInfoError {
     Json(#[from] serde_json::Error),
 }
 
-pub fn parse_id_token(id_token: &str) -> Result<IdTokenInfo, IdTokenInfoError> {
+#[derive(Deserialize)]
+struct ExpiryClaims {
+    #[serde(default)]
+    exp: Option<i64>,
+}
+
+fn decode_jwt_payload(jwt: &str) -> Result<Vec<u8>, IdTokenInfoError> {
     // JWT format: header.payload.signature
-    let mut parts = id_token.split('.');
+    let mut parts = jwt.split('.');
     let (_header_b64, payload_b64, _sig_b64) = match (parts.next(), parts.next(), parts.next()) {
         (Some(h), Some(p), Some(s)) if !h.is_empty() && !p.is_empty() && !s.is_empty() => (h, p, s),
         _ => return Err(IdTokenInfoError::InvalidFormat),
     };
 
-    let payload_bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(payload_b64)?;
+    Ok(base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(payload_b64)?)
+}
+
+/// The `exp` claim of a JWT such as the access token, or `None` when the
+/// token is not a JWT or carries no expiry.
+pub(crate) fn jwt_expiry(jwt: &str) -> Option<DateTime<Utc>> {
+    let payload_bytes = decode_jwt_payload(jwt).ok()?;
+    let claims: ExpiryClaims = serde_json::from_slice(&payload_bytes).ok()?;
+    DateTime::from_timestamp(claims.exp?, 0)
+}
+
+pub fn parse_id_token(id_token: &str) -> Result<IdTokenInfo, IdTokenInfoError> {
+    let payload_bytes = decode_jwt_payload(id_token)?;
     let claims: IdClaims = serde_json::from_slice(&payload_bytes)?;
 
     match claims.auth {
@@ -192,4 +212,26 @@ mod tests {
         assert!(info.email.is_none());
         assert!(info.get_chatgpt_plan_type().is_none());
     }
+
+    #[test]
+    fn jwt_expiry_reads_exp_claim() {
+        fn b64url_no_pad(bytes: &[u8]) -> String {
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+        }
+        let jwt = |payload: serde_json::Value| {
+            format!(
+                "{}.{}.{}",
+                b64url_no_pad(br#"{"alg":"none","typ":"JWT"}"#),
+                b64url_no_pad(&serde_json::to_vec(&payload).unwrap()),
+                b64url_no_pad(b"sig")
+            )
+        };
+
+        assert_eq!(
+            jwt_expiry(&jwt(serde_json::json!({ "exp": 1_700_000_000 }))),
+            DateTime::from_timestamp(1_700_000_000, 0)
+        );
+        assert_eq!(jwt_expiry(&jwt(serde_json::json!({ "sub": "123" }))), None);
+        assert_eq!(jwt_expiry("opaque-access-token"), None);
+    }
 }
@@ -0,0 +1,206 @@
+//! Per-request token usage normalization.
+//!
+//! Providers disagree on which usage fields they report: the Responses API
+//! sends a full breakdown, while Chat Completions providers may send only a
+//! total, only prompt/completion counts, or nothing at all. Every completed
+//! model request goes through [`normalize_usage`] so the `TokenCount` event
+//! always carries a consistent breakdown:
+//!
+//! - missing fields are derived from the ones that were reported
+//!   (`total = input + output`, `input = total - output`, ...);
+//! - cached and reasoning counts are clamped to the totals they belong to;
+//! - when the provider omits usage entirely, the request is estimated locally
+//!   from the serialized prompt and output (about four bytes per token) and
+//!   flagged as estimated.
+
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::TokenUsage;
+
+use crate::truncate::approx_token_count;
+
+/// Usage for a single model request after normalization.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct NormalizedUsage {
+    pub usage: TokenUsage,
+    /// True when some or all of `usage` was estimated locally instead of
+    /// reported by the provider.
+    pub estimated: bool,
+}
+
+/// Accumulates what is needed to estimate a request's usage when the provider
+/// does not report it. Only output sizes are tracked while streaming; the
+/// prompt is measured lazily in [`UsageEstimator::estimate`].
+#[derive(Debug, Default)]
+pub(crate) struct UsageEstimator {
+    output_tokens: i64,
+}
+
+impl UsageEstimator {
+    pub(crate) fn record_output(&mut self, item: &ResponseItem) {
+        self.output_tokens += approx_item_tokens(item);
+    }
+
+    pub(crate) fn estimate(&self, input: &[ResponseItem], instructions: &str) -> TokenUsage {
+        let input_tokens = input.iter().map(approx_item_tokens).sum::<i64>()
+            + approx_token_count(instructions) as i64;
+        TokenUsage {
+            input_tokens,
+            cached_input_tokens: 0,
+            output_tokens: self.output_tokens,
+            reasoning_output_tokens: 0,
+            total_tokens: input_tokens + self.output_tokens,
+        }
+    }
+}
+
+fn approx_item_tokens(item: &ResponseItem) -> i64 {
+    serde_json::to_string(item)
+        .map(|json| approx_token_count(&json) as i64)
+        .unwrap_or_default()
+}
+
+/// Normalize the usage reported by the provider for one request. `estimate`
+/// is only invoked when the provider omitted usage or reported just a total.
+pub(crate) fn normalize_usage(
+    reported: Option<&TokenUsage>,
+    estimate: impl FnOnce() -> TokenUsage,
+) -> NormalizedUsage {
+    let Some(reported) = reported else {
+        return NormalizedUsage {
+            usage: estimate(),
+            estimated: true,
+        };
+    };
+
+    let mut usage = reported.clone();
+    let mut estimated = false;
+    if usage.input_tokens == 0 && usage.output_tokens == 0 && usage.total_tokens > 0 {
+        // Only a total was reported: split it using the estimated output size.
+        let guess = estimate();
+        usage.output_tokens = guess.output_tokens.clamp(0, usage.total_tokens);
+        usage.input_tokens = usage.total_tokens - usage.output_tokens;
+        estimated = true;
+    } else if usage.total_tokens == 0 {
+        usage.total_tokens = usage.input_tokens + usage.output_tokens;
+    } else if usage.input_tokens == 0 {
+        usage.input_tokens = (usage.total_tokens - usage.output_tokens).max(0);
+    } else if usage.output_tokens == 0 {
+        usage.output_tokens = (usage.total_tokens - usage.input_tokens).max(0);
+    }
+    usage.cached_input_tokens = usage.cached_input_tokens.clamp(0, usage.input_tokens);
+    usage.reasoning_output_tokens = usage.reasoning_output_tokens.clamp(0, usage.output_tokens);
+
+    NormalizedUsage { usage, estimated }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ContentItem;
+    use pretty_assertions::assert_eq;
+
+    fn usage(input: i64, cached: i64, output: i64, reasoning: i64, total: i64) -> TokenUsage {
+        TokenUsage {
+            input_tokens: input,
+            cached_input_tokens: cached,
+            output_tokens: output,
+            reasoning_output_tokens: reasoning,
+            total_tokens: total,
+        }
+    }
+
+    fn no_estimate() -> TokenUsage {
+        panic!("estimate should not be needed when usage is reported")
+    }
+
+    #[test]
+    fn full_usage_is_kept() {
+        let reported = usage(100, 40, 20, 5, 120);
+        assert_eq!(
+            normalize_usage(Some(&reported), no_estimate),
+            NormalizedUsage {
+                usage: reported,
+                estimated: false,
+            }
+        );
+    }
+
+    #[test]
+    fn explicit_zero_usage_is_trusted() {
+        let reported = TokenUsage::default();
+        assert_eq!(
+            normalize_usage(Some(&reported), no_estimate),
+            NormalizedUsage {
+                usage: reported,
+                estimated: false,
+            }
+        );
+    }
+
+    #[test]
+    fn missing_fields_are_derived_from_reported_ones() {
+        assert_eq!(
+            normalize_usage(Some(&usage(100, 0, 20, 0, 0)), no_estimate),
+            NormalizedUsage {
+                usage: usage(100, 0, 20, 0, 120),
+                estimated: false,
+            }
+        );
+        assert_eq!(
+            normalize_usage(Some(&usage(0, 0, 20, 0, 120)), no_estimate),
+            NormalizedUsage {
+                usage: usage(100, 0, 20, 0, 120),
+                estimated: false,
+            }
+        );
+        assert_eq!(
+            normalize_usage(Some(&usage(100, 150, 0, 10, 120)), no_estimate),
+            NormalizedUsage {
+                usage: usage(100, 100, 20, 10, 120),
+                estimated: false,
+            }
+        );
+    }
+
+    #[test]
+    fn total_only_is_split_with_estimate() {
+        let normalized =
+            normalize_usage(Some(&usage(0, 0, 0, 0, 120)), || usage(90, 0, 30, 0, 120));
+        assert_eq!(
+            normalized,
+            NormalizedUsage {
+                usage: usage(90, 0, 30, 0, 120),
+                estimated: true,
+            }
+        );
+    }
+
+    #[test]
+    fn omitted_usage_falls_back_to_estimate() {
+        let mut estimator = UsageEstimator::default();
+        estimator.record_output(&ResponseItem::Message {
+            id: None,
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText {
+                text: "done".to_string(),
+            }],
+        });
+        let input = vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: "hello".to_string(),
+            }],
+        }];
+
+        let normalized = normalize_usage(None, || estimator.estimate(&input, "be brief"));
+
+        assert!(normalized.estimated);
+        assert!(normalized.usage.input_tokens > 0);
+        assert!(normalized.usage.output_tokens > 0);
+        assert_eq!(
+            normalized.usage.total_tokens,
+            normalized.usage.input_tokens + normalized.usage.output_tokens
+        );
+    }
+}
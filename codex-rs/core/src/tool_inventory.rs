@@ -0,0 +1,474 @@
+//! Versions of common developer tools on the user's `PATH`.
+//!
+//! The agent tends to open sessions by running `node --version`,
+//! `cargo --version` and friends. Instead, the session probes a fixed list of
+//! tools once, off the startup path, and shares the result with the model as
+//! part of the environment context. Results are cached under
+//! `$CODEX_HOME/internal_storage` keyed by a hash of `PATH`, so most sessions
+//! never spawn a probe at all.
+
+use std::ffi::OsStr;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::OnceLock;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+use tokio::process::Command;
+use tracing::warn;
+
+/// Tools probed in every session, in the order they are listed to the model.
+/// `[tool_inventory].extra_tools` in config.toml appends to this list.
+pub const TOOL_CANDIDATES: &[&str] = &[
+    "node", "npm", "pnpm", "yarn", "bun", "deno", "python3", "python", "pip3", "uv", "cargo",
+    "rustc", "go", "java", "ruby", "php", "dotnet", "git", "docker", "make", "cmake", "gcc",
+    "clang",
+];
+
+/// How long a single `--version` invocation may take before it is abandoned.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long cached results are trusted when `PATH` has not changed.
+const CACHE_TTL: chrono::Duration = chrono::Duration::hours(24);
+
+/// Version lines longer than this are cut, so a chatty tool cannot bloat the
+/// environment context.
+const MAX_VERSION_LEN: usize = 80;
+
+const CACHE_DIR: &str = "internal_storage";
+const CACHE_FILE: &str = "tool_inventory.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ToolStatus {
+    /// The first line the tool printed for its version flag.
+    Found { version: String },
+    /// Not on `PATH`, or the version command failed.
+    Absent,
+    /// The version command did not finish within the probe timeout.
+    TimedOut,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolVersion {
+    pub name: String,
+    pub status: ToolStatus,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolInventory {
+    pub tools: Vec<ToolVersion>,
+}
+
+impl ToolInventory {
+    /// One `name: version` line per tool, e.g. `node: v20.11.1` or
+    /// `deno: not found`.
+    pub fn format_lines(&self) -> Vec<String> {
+        self.tools
+            .iter()
+            .map(|tool| match &tool.status {
+                ToolStatus::Found { version } => format!("{}: {version}", tool.name),
+                ToolStatus::Absent => format!("{}: not found", tool.name),
+                ToolStatus::TimedOut => format!("{}: unknown (timed out)", tool.name),
+            })
+            .collect()
+    }
+}
+
+/// Returns the inventory for `tools`, from the cache when it is still valid for
+/// the current `PATH` and otherwise by probing each tool.
+pub async fn load_or_probe(codex_home: &Path, tools: &[String]) -> ToolInventory {
+    let path_var = std::env::var_os("PATH").unwrap_or_default();
+    load_or_probe_with(codex_home, tools, &path_var, Utc::now(), PROBE_TIMEOUT).await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedInventory {
+    path_hash: String,
+    probed_at: DateTime<Utc>,
+    inventory: ToolInventory,
+}
+
+async fn load_or_probe_with(
+    codex_home: &Path,
+    tools: &[String],
+    path_var: &OsStr,
+    now: DateTime<Utc>,
+    timeout: Duration,
+) -> ToolInventory {
+    let cache_path = codex_home.join(CACHE_DIR).join(CACHE_FILE);
+    let path_hash = hash_path_var(path_var);
+    if let Some(inventory) = read_cache(&cache_path, &path_hash, tools, now).await {
+        return inventory;
+    }
+
+    let inventory = probe(tools, path_var, timeout).await;
+    // A timeout is usually transient (cold disk, slow shim); don't pin it for a day.
+    let timed_out = inventory
+        .tools
+        .iter()
+        .any(|tool| tool.status == ToolStatus::TimedOut);
+    if !timed_out {
+        let cached = CachedInventory {
+            path_hash,
+            probed_at: now,
+            inventory: inventory.clone(),
+        };
+        if let Err(err) = write_cache(&cache_path, &cached).await {
+            warn!("failed to write tool inventory cache: {err}");
+        }
+    }
+    inventory
+}
+
+fn hash_path_var(path_var: &OsStr) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(path_var.to_string_lossy().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+async fn read_cache(
+    cache_path: &Path,
+    path_hash: &str,
+    tools: &[String],
+    now: DateTime<Utc>,
+) -> Option<ToolInventory> {
+    let contents = tokio::fs::read_to_string(cache_path).await.ok()?;
+    let cached: CachedInventory = serde_json::from_str(&contents).ok()?;
+    let same_tools = cached
+        .inventory
+        .tools
+        .iter()
+        .map(|tool| tool.name.as_str())
+        .eq(tools.iter().map(String::as_str));
+    let fresh = now.signed_duration_since(cached.probed_at) < CACHE_TTL;
+    (cached.path_hash == path_hash && same_tools && fresh).then_some(cached.inventory)
+}
+
+async fn write_cache(cache_path: &Path, cached: &CachedInventory) -> std::io::Result<()> {
+    if let Some(parent) = cache_path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let json = serde_json::to_vec_pretty(cached).map_err(std::io::Error::other)?;
+    tokio::fs::write(cache_path, json).await
+}
+
+async fn probe(tools: &[String], path_var: &OsStr, timeout: Duration) -> ToolInventory {
+    let probes = tools.iter().map(|name| async move {
+        ToolVersion {
+            name: name.clone(),
+            status: probe_tool(name, path_var, timeout).await,
+        }
+    });
+    ToolInventory {
+        tools: futures::future::join_all(probes).await,
+    }
+}
+
+async fn probe_tool(name: &str, path_var: &OsStr, timeout: Duration) -> ToolStatus {
+    let mut command = Command::new(name);
+    command
+        .args(version_args(name))
+        .env("PATH", path_var)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let output = match tokio::time::timeout(timeout, command.output()).await {
+        Err(_) => return ToolStatus::TimedOut,
+        Ok(Err(_)) => return ToolStatus::Absent,
+        Ok(Ok(output)) => output,
+    };
+    if !output.status.success() {
+        return ToolStatus::Absent;
+    }
+    // `java -version` and some Python builds report on stderr.
+    let version = first_line(&output.stdout).or_else(|| first_line(&output.stderr));
+    match version {
+        Some(version) => ToolStatus::Found { version },
+        None => ToolStatus::Absent,
+    }
+}
+
+fn version_args(name: &str) -> &'static [&'static str] {
+    match name {
+        "go" => &["version"],
+        "java" => &["-version"],
+        _ => &["--version"],
+    }
+}
+
+fn first_line(bytes: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(bytes);
+    let line = text.lines().map(str::trim).find(|line| !line.is_empty())?;
+    Some(line.chars().take(MAX_VERSION_LEN).collect())
+}
+
+/// The session's inventory, filled in by a background probe.
+#[derive(Debug, Default)]
+pub(crate) struct SessionToolInventory {
+    inventory: Arc<OnceLock<ToolInventory>>,
+    recorded: AtomicBool,
+}
+
+impl SessionToolInventory {
+    /// Starts probing `tools` on a background task. Does nothing when `tools`
+    /// is `None` (`[tool_inventory].enabled = false`).
+    pub(crate) fn spawn(codex_home: PathBuf, tools: Option<Vec<String>>) -> Self {
+        let this = Self::default();
+        if let Some(tools) = tools {
+            let inventory = Arc::clone(&this.inventory);
+            tokio::spawn(async move {
+                let _ = inventory.set(load_or_probe(&codex_home, &tools).await);
+            });
+        }
+        this
+    }
+
+    /// The inventory, once the probe has finished. Callers that put it in
+    /// front of the model should also call [`Self::mark_recorded`].
+    pub(crate) fn get(&self) -> Option<&ToolInventory> {
+        self.inventory.get()
+    }
+
+    pub(crate) fn mark_recorded(&self) {
+        self.recorded.store(true, Ordering::Relaxed);
+    }
+
+    /// The inventory if it is ready and the model has not been given it yet.
+    pub(crate) fn take_unrecorded(&self) -> Option<&ToolInventory> {
+        let inventory = self.inventory.get()?;
+        (!self.recorded.swap(true, Ordering::Relaxed)).then_some(inventory)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[cfg(unix)]
+    fn write_script(dir: &Path, name: &str, body: &str) {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{body}\n")).expect("write script");
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+            .expect("chmod script");
+    }
+
+    /// `dir` in front of the test process's own `PATH`, so scripts can still
+    /// find `sleep`.
+    #[cfg(unix)]
+    fn path_with(dir: &Path) -> std::ffi::OsString {
+        let inherited = std::env::var_os("PATH").unwrap_or_default();
+        let paths = std::iter::once(dir.to_path_buf()).chain(std::env::split_paths(&inherited));
+        std::env::join_paths(paths).expect("join PATH")
+    }
+
+    fn tools(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    fn found(name: &str, version: &str) -> ToolVersion {
+        ToolVersion {
+            name: name.to_string(),
+            status: ToolStatus::Found {
+                version: version.to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn format_lines_lists_each_tool_with_its_status() {
+        let inventory = ToolInventory {
+            tools: vec![
+                found("node", "v20.11.1"),
+                ToolVersion {
+                    name: "deno".to_string(),
+                    status: ToolStatus::Absent,
+                },
+                ToolVersion {
+                    name: "java".to_string(),
+                    status: ToolStatus::TimedOut,
+                },
+            ],
+        };
+
+        assert_eq!(
+            inventory.format_lines(),
+            vec![
+                "node: v20.11.1".to_string(),
+                "deno: not found".to_string(),
+                "java: unknown (timed out)".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn first_line_skips_blank_lines_and_truncates() {
+        assert_eq!(
+            first_line(b"\n  cargo 1.80.0 (376290515 2024-07-16)\nmore\n"),
+            Some("cargo 1.80.0 (376290515 2024-07-16)".to_string())
+        );
+        assert_eq!(first_line(b" \n"), None);
+        let long = "x".repeat(200);
+        assert_eq!(
+            first_line(long.as_bytes()),
+            Some("x".repeat(MAX_VERSION_LEN))
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn probe_reports_timeouts_and_missing_tools() {
+        let bin = TempDir::new().expect("tempdir");
+        write_script(bin.path(), "fast", "echo fast 1.2.3");
+        write_script(bin.path(), "slow", "sleep 5");
+        write_script(bin.path(), "broken", "echo oops >&2; exit 1");
+        write_script(bin.path(), "stderr-only", "echo stderr-only 9 >&2");
+
+        let inventory = probe(
+            &tools(&[
+                "fast",
+                "slow",
+                "broken",
+                "stderr-only",
+                "codex-missing-tool",
+            ]),
+            &path_with(bin.path()),
+            Duration::from_millis(500),
+        )
+        .await;
+
+        assert_eq!(
+            inventory,
+            ToolInventory {
+                tools: vec![
+                    found("fast", "fast 1.2.3"),
+                    ToolVersion {
+                        name: "slow".to_string(),
+                        status: ToolStatus::TimedOut,
+                    },
+                    ToolVersion {
+                        name: "broken".to_string(),
+                        status: ToolStatus::Absent,
+                    },
+                    found("stderr-only", "stderr-only 9"),
+                    ToolVersion {
+                        name: "codex-missing-tool".to_string(),
+                        status: ToolStatus::Absent,
+                    },
+                ],
+            }
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn timed_out_results_are_not_cached() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let bin = TempDir::new().expect("tempdir");
+        write_script(bin.path(), "slow", "sleep 5");
+        let now = Utc::now();
+
+        load_or_probe_with(
+            codex_home.path(),
+            &tools(&["slow"]),
+            &path_with(bin.path()),
+            now,
+            Duration::from_millis(200),
+        )
+        .await;
+
+        assert!(
+            !codex_home.path().join(CACHE_DIR).join(CACHE_FILE).exists(),
+            "a timed out probe should not be cached"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn cache_is_reused_until_path_changes_or_expires() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let bin_a = TempDir::new().expect("tempdir");
+        let bin_b = TempDir::new().expect("tempdir");
+        write_script(bin_a.path(), "fake", "echo fake 1.0");
+        write_script(bin_b.path(), "fake", "echo fake 2.0");
+        let names = tools(&["fake"]);
+        let now = Utc::now();
+        let timeout = Duration::from_secs(5);
+        let load = |path: &TempDir, now: DateTime<Utc>| {
+            let path_var = path_with(path.path());
+            let codex_home = codex_home.path().to_path_buf();
+            let names = names.clone();
+            async move { load_or_probe_with(&codex_home, &names, &path_var, now, timeout).await }
+        };
+
+        let first = load(&bin_a, now).await;
+        assert_eq!(first.tools, vec![found("fake", "fake 1.0")]);
+
+        // Same PATH: the cached result wins even though the tool changed.
+        write_script(bin_a.path(), "fake", "echo fake 1.1");
+        let cached = load(&bin_a, now + chrono::Duration::hours(1)).await;
+        assert_eq!(cached.tools, vec![found("fake", "fake 1.0")]);
+
+        // A different PATH invalidates the cache.
+        let other_path = load(&bin_b, now + chrono::Duration::hours(1)).await;
+        assert_eq!(other_path.tools, vec![found("fake", "fake 2.0")]);
+
+        // So does the TTL.
+        write_script(bin_b.path(), "fake", "echo fake 2.1");
+        let expired = load(&bin_b, now + chrono::Duration::hours(1) + CACHE_TTL).await;
+        assert_eq!(expired.tools, vec![found("fake", "fake 2.1")]);
+    }
+
+    #[tokio::test]
+    async fn cache_is_ignored_when_the_tool_list_changes() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cache_path = codex_home.path().join(CACHE_DIR).join(CACHE_FILE);
+        let path_var = OsStr::new("/nonexistent");
+        let now = Utc::now();
+        let cached = CachedInventory {
+            path_hash: hash_path_var(path_var),
+            probed_at: now,
+            inventory: ToolInventory {
+                tools: vec![found("node", "v20.11.1")],
+            },
+        };
+        write_cache(&cache_path, &cached)
+            .await
+            .expect("write cache");
+
+        assert_eq!(
+            read_cache(
+                &cache_path,
+                &hash_path_var(path_var),
+                &tools(&["node"]),
+                now
+            )
+            .await,
+            Some(cached.inventory.clone())
+        );
+        assert_eq!(
+            read_cache(
+                &cache_path,
+                &hash_path_var(path_var),
+                &tools(&["node", "bun"]),
+                now
+            )
+            .await,
+            None
+        );
+    }
+}
@@ -3,6 +3,9 @@ use crate::codex::TurnContext;
 use crate::tools::TELEMETRY_PREVIEW_MAX_BYTES;
 use crate::tools::TELEMETRY_PREVIEW_MAX_LINES;
 use crate::tools::TELEMETRY_PREVIEW_TRUNCATION_NOTICE;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::truncate_function_output_items_with_policy;
+use crate::truncate::truncate_tool_output;
 use crate::turn_diff_tracker::TurnDiffTracker;
 use codex_protocol::models::FunctionCallOutputContentItem;
 use codex_protocol::models::FunctionCallOutputPayload;
@@ -88,6 +91,34 @@ impl ToolOutput {
         }
     }
 
+    /// Cut the model-visible output down to `policy`, appending a truncation
+    /// manifest when anything was removed. MCP results are converted to the
+    /// function output the model sees for them.
+    pub(crate) fn fit_to_budget(self, policy: TruncationPolicy, tool_name: &str) -> ToolOutput {
+        let (content, content_items, success) = match self {
+            ToolOutput::Function {
+                content,
+                content_items,
+                success,
+            } => (content, content_items, success),
+            ToolOutput::Mcp { result: Ok(result) } => {
+                let FunctionCallOutputPayload {
+                    content,
+                    content_items,
+                    success,
+                } = FunctionCallOutputPayload::from(&result);
+                (content, content_items, success)
+            }
+            ToolOutput::Mcp { result: Err(err) } => (err, None, Some(false)),
+        };
+        ToolOutput::Function {
+            content: truncate_tool_output(&content, policy, tool_name),
+            content_items: content_items
+                .map(|items| truncate_function_output_items_with_policy(&items, policy)),
+            success,
+        }
+    }
+
     pub fn into_response(self, call_id: &str, payload: &ToolPayload) -> ResponseInputItem {
         match self {
             ToolOutput::Function {
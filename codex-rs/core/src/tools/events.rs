@@ -1,8 +1,10 @@
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::config::types::ShellEnvironmentPolicy;
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::exec::ExecToolCallOutput;
+use crate::exec_env::filtered_env_var_count;
 use crate::function_tool::FunctionCallError;
 use crate::parse_command::parse_command;
 use crate::protocol::EventMsg;
@@ -10,6 +12,7 @@ use crate::protocol::ExecCommandBeginEvent;
 use crate::protocol::ExecCommandEndEvent;
 use crate::protocol::ExecCommandSource;
 use crate::protocol::FileChange;
+use crate::protocol::OutputRange;
 use crate::protocol::PatchApplyBeginEvent;
 use crate::protocol::PatchApplyEndEvent;
 use crate::protocol::TurnDiffEvent;
@@ -66,6 +69,12 @@ pub(crate) async fn emit_exec_command_begin(
     source: ExecCommandSource,
     interaction_input: Option<String>,
 ) {
+    // Input written to a running session doesn't spawn a new environment.
+    let filtered_env_vars = if interaction_input.is_none() {
+        filtered_env_vars(&ctx.turn.shell_environment_policy)
+    } else {
+        None
+    };
     ctx.session
         .send_event(
             ctx.turn,
@@ -77,10 +86,20 @@ pub(crate) async fn emit_exec_command_begin(
                 parsed_cmd: parsed_cmd.to_vec(),
                 source,
                 interaction_input,
+                filtered_env_vars,
             }),
         )
         .await;
 }
+
+/// `filtered_env_vars` for an [`ExecCommandBeginEvent`]: `None` when the
+/// policy filtered nothing out.
+pub(crate) fn filtered_env_vars(policy: &ShellEnvironmentPolicy) -> Option<u32> {
+    match filtered_env_var_count(policy) {
+        0 => None,
+        count => Some(u32::try_from(count).unwrap_or(u32::MAX)),
+    }
+}
 // Concrete, allocation-free emitter: avoid trait objects and boxed futures.
 pub(crate) enum ToolEmitter {
     Shell {
@@ -257,11 +276,23 @@ impl ToolEmitter {
         output: &ExecToolCallOutput,
         ctx: ToolEventCtx<'_>,
     ) -> String {
+        let tool_name = self.budget_name();
+        let policy = ctx.turn.tool_output_budgets.policy_for(tool_name);
         match self {
             Self::Shell { freeform: true, .. } => {
-                super::format_exec_output_for_model_freeform(output, ctx.turn.truncation_policy)
+                super::format_exec_output_for_model_freeform(output, policy, tool_name)
             }
-            _ => super::format_exec_output_for_model_structured(output, ctx.turn.truncation_policy),
+            _ => super::format_exec_output_for_model_structured(output, policy, tool_name),
+        }
+    }
+
+    /// Name under which this tool's output budget is configured; every
+    /// shell-style tool shares the `shell` budget.
+    fn budget_name(&self) -> &'static str {
+        match self {
+            Self::Shell { .. } => "shell",
+            Self::ApplyPatch { .. } => "apply_patch",
+            Self::UnifiedExec { .. } => "exec_command",
         }
     }
 
@@ -289,6 +320,15 @@ impl ToolEmitter {
                 let result = Err(FunctionCallError::RespondToModel(response));
                 (event, result)
             }
+            Err(ToolError::Codex(CodexErr::CommandRequestedInput { prompt, output })) => {
+                let response = format!(
+                    "command was stopped while waiting for input ({prompt}); run it non-interactively instead\n{}",
+                    self.format_exec_output_for_model(&output, ctx)
+                );
+                let event = ToolEventStage::Failure(ToolEventFailure::Output(*output));
+                let result = Err(FunctionCallError::RespondToModel(response));
+                (event, result)
+            }
             Err(ToolError::Codex(err)) => {
                 let message = format!("execution error: {err:?}");
                 let event = ToolEventStage::Failure(ToolEventFailure::Message(message.clone()));
@@ -343,6 +383,7 @@ struct ExecCommandResult {
     stdout: String,
     stderr: String,
     aggregated_output: String,
+    stderr_ranges: Vec<OutputRange>,
     exit_code: i32,
     duration: Duration,
     formatted_output: String,
@@ -371,6 +412,7 @@ async fn emit_exec_stage(
                 stdout: output.stdout.text.clone(),
                 stderr: output.stderr.text.clone(),
                 aggregated_output: output.aggregated_output.text.clone(),
+                stderr_ranges: output.stderr_ranges.clone(),
                 exit_code: output.exit_code,
                 duration: output.duration,
                 formatted_output: format_exec_output_str(&output, ctx.turn.truncation_policy),
@@ -383,6 +425,7 @@ async fn emit_exec_stage(
                 stdout: String::new(),
                 stderr: text.clone(),
                 aggregated_output: text.clone(),
+                stderr_ranges: Vec::new(),
                 exit_code: -1,
                 duration: Duration::ZERO,
                 formatted_output: text,
@@ -411,6 +454,7 @@ async fn emit_exec_end(
                 stdout: exec_result.stdout,
                 stderr: exec_result.stderr,
                 aggregated_output: exec_result.aggregated_output,
+                stderr_ranges: exec_result.stderr_ranges,
                 exit_code: exec_result.exit_code,
                 duration: exec_result.duration,
                 formatted_output: exec_result.formatted_output,
@@ -441,13 +485,19 @@ async fn emit_patch_end(
         .await;
 
     if let Some(tracker) = ctx.turn_diff_tracker {
-        let unified_diff = {
+        let (unified_diff, files) = {
             let mut guard = tracker.lock().await;
-            guard.get_unified_diff()
+            (guard.get_unified_diff(), guard.changed_files())
         };
         if let Ok(Some(unified_diff)) = unified_diff {
             ctx.session
-                .send_event(ctx.turn, EventMsg::TurnDiff(TurnDiffEvent { unified_diff }))
+                .send_event(
+                    ctx.turn,
+                    EventMsg::TurnDiff(TurnDiffEvent {
+                        unified_diff,
+                        files,
+                    }),
+                )
                 .await;
         }
     }
@@ -42,6 +42,10 @@ impl ToolHandler for ApplyPatchHandler {
         )
     }
 
+    fn budgets_output(&self) -> bool {
+        true
+    }
+
     fn is_mutating(&self, _invocation: &ToolInvocation) -> bool {
         true
     }
@@ -128,7 +132,7 @@ impl ToolHandler for ApplyPatchHandler {
                             &call_id,
                             Some(&tracker),
                         );
-                        let content = emitter.finish(event_ctx, out).await?;
+                        let content = apply.annotate_output(emitter.finish(event_ctx, out).await?);
                         Ok(ToolOutput::Function {
                             content,
                             content_items: None,
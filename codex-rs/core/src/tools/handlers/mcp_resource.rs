@@ -594,6 +594,7 @@ async fn emit_tool_call_end(
                 invocation,
                 duration,
                 result,
+                cached: false,
             }),
         )
         .await;
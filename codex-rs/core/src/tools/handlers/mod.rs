@@ -5,6 +5,7 @@ mod mcp;
 mod mcp_resource;
 mod plan;
 mod read_file;
+mod session_recall;
 mod shell;
 mod test_sync;
 mod unified_exec;
@@ -19,6 +20,7 @@ pub use mcp::McpHandler;
 pub use mcp_resource::McpResourceHandler;
 pub use plan::PlanHandler;
 pub use read_file::ReadFileHandler;
+pub use session_recall::SessionRecallHandler;
 pub use shell::ShellCommandHandler;
 pub use shell::ShellHandler;
 pub use test_sync::TestSyncHandler;
@@ -0,0 +1,98 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::git_info::resolve_root_git_project_for_trust;
+use crate::session_recall::DEFAULT_RESULT_LIMIT;
+use crate::session_recall::MAX_RESULT_LIMIT;
+use crate::session_recall::RecallIndex;
+use crate::session_recall::RecallQuery;
+use crate::session_recall::format_hits;
+use crate::session_recall::recall_index_dir;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct SessionRecallHandler;
+
+#[derive(Deserialize)]
+struct SearchPastSessionsArgs {
+    query: String,
+    #[serde(default)]
+    limit: Option<usize>,
+    #[serde(default)]
+    all_projects: bool,
+}
+
+#[async_trait]
+impl ToolHandler for SessionRecallHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "search_past_sessions handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: SearchPastSessionsArgs = serde_json::from_str(&arguments).map_err(|e| {
+            FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e:?}"))
+        })?;
+        if args.query.trim().is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "query must not be empty".to_string(),
+            ));
+        }
+
+        let config = turn.client.config();
+        let project = (!args.all_projects).then(|| {
+            resolve_root_git_project_for_trust(&turn.cwd).unwrap_or_else(|| turn.cwd.clone())
+        });
+        let current_session = session.conversation_id().to_string();
+        let limit = args
+            .limit
+            .unwrap_or(DEFAULT_RESULT_LIMIT)
+            .clamp(1, MAX_RESULT_LIMIT);
+
+        let content = tokio::task::spawn_blocking(move || {
+            let dir = recall_index_dir(&config.persistence.cache_home(&config.codex_home));
+            let mut index = RecallIndex::load(&dir);
+            let changed = index
+                .refresh(&config.codex_home, &config.session_recall_exclude_projects)
+                .map_err(|err| format!("failed to index past sessions: {err}"))?;
+            if changed && let Err(err) = index.save(&dir) {
+                tracing::warn!("failed to save session recall index: {err}");
+            }
+            let hits = index.search(&RecallQuery {
+                text: &args.query,
+                project: project.as_deref(),
+                exclude_session: Some(&current_session),
+                limit,
+            });
+            Ok::<_, String>(format_hits(&hits))
+        })
+        .await
+        .map_err(|err| FunctionCallError::RespondToModel(format!("search failed: {err}")))?
+        .map_err(FunctionCallError::RespondToModel)?;
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
@@ -81,6 +81,10 @@ impl ToolHandler for ShellHandler {
         )
     }
 
+    fn budgets_output(&self) -> bool {
+        true
+    }
+
     fn is_mutating(&self, invocation: &ToolInvocation) -> bool {
         match &invocation.payload {
             ToolPayload::Function { arguments } => {
@@ -153,6 +157,10 @@ impl ToolHandler for ShellCommandHandler {
         matches!(payload, ToolPayload::Function { .. })
     }
 
+    fn budgets_output(&self) -> bool {
+        true
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
         let ToolInvocation {
             session,
@@ -264,7 +272,7 @@ impl ShellHandler {
                             &call_id,
                             Some(&tracker),
                         );
-                        let content = emitter.finish(event_ctx, out).await?;
+                        let content = apply.annotate_output(emitter.finish(event_ctx, out).await?);
                         return Ok(ToolOutput::Function {
                             content,
                             content_items: None,
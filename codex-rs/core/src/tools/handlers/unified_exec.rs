@@ -15,6 +15,7 @@ use crate::tools::events::ToolEventCtx;
 use crate::tools::events::ToolEventStage;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
+use crate::truncate::truncate_tool_output;
 use crate::unified_exec::ExecCommandRequest;
 use crate::unified_exec::UnifiedExecContext;
 use crate::unified_exec::UnifiedExecResponse;
@@ -84,6 +85,10 @@ impl ToolHandler for UnifiedExecHandler {
         )
     }
 
+    fn budgets_output(&self) -> bool {
+        true
+    }
+
     fn is_mutating(&self, invocation: &ToolInvocation) -> bool {
         let (ToolPayload::Function { arguments } | ToolPayload::UnifiedExec { arguments }) =
             &invocation.payload
@@ -121,7 +126,7 @@ impl ToolHandler for UnifiedExecHandler {
         let manager: &UnifiedExecSessionManager = &session.services.unified_exec_manager;
         let context = UnifiedExecContext::new(session.clone(), turn.clone(), call_id.clone());
 
-        let response = match tool_name.as_str() {
+        let mut response = match tool_name.as_str() {
             "exec_command" => {
                 let args: ExecCommandArgs = serde_json::from_str(&arguments).map_err(|err| {
                     FunctionCallError::RespondToModel(format!(
@@ -220,12 +225,18 @@ impl ToolHandler for UnifiedExecHandler {
                 call_id: response.event_call_id.clone(),
                 stream: ExecOutputStream::Stdout,
                 chunk: response.output.as_bytes().to_vec(),
+                offset: None,
             };
             session
                 .send_event(turn.as_ref(), EventMsg::ExecCommandOutputDelta(delta))
                 .await;
         }
 
+        // The delta above carries the full output; the model only sees what
+        // fits the tool's budget.
+        let policy = turn.tool_output_budgets.policy_for(&tool_name);
+        response.output = truncate_tool_output(&response.output, policy, &tool_name);
+
         let content = format_response(&response);
 
         Ok(ToolOutput::Function {
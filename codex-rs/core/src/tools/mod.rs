@@ -13,6 +13,7 @@ use crate::exec::ExecToolCallOutput;
 use crate::truncate::TruncationPolicy;
 use crate::truncate::formatted_truncate_text;
 use crate::truncate::truncate_text;
+use crate::truncate::with_truncation_manifest;
 pub use router::ToolRouter;
 use serde::Serialize;
 
@@ -27,6 +28,7 @@ pub(crate) const TELEMETRY_PREVIEW_TRUNCATION_NOTICE: &str =
 pub fn format_exec_output_for_model_structured(
     exec_output: &ExecToolCallOutput,
     truncation_policy: TruncationPolicy,
+    tool_name: &str,
 ) -> String {
     let ExecToolCallOutput {
         exit_code,
@@ -49,7 +51,13 @@ pub fn format_exec_output_for_model_structured(
     // round to 1 decimal place
     let duration_seconds = ((duration.as_secs_f32()) * 10.0).round() / 10.0;
 
-    let formatted_output = format_exec_output_str(exec_output, truncation_policy);
+    let body = exec_output_body(exec_output);
+    let formatted_output = with_truncation_manifest(
+        formatted_truncate_text(&body, truncation_policy),
+        &body,
+        truncation_policy,
+        tool_name,
+    );
 
     let payload = ExecOutput {
         output: &formatted_output,
@@ -66,13 +74,20 @@ pub fn format_exec_output_for_model_structured(
 pub fn format_exec_output_for_model_freeform(
     exec_output: &ExecToolCallOutput,
     truncation_policy: TruncationPolicy,
+    tool_name: &str,
 ) -> String {
     // round to 1 decimal place
     let duration_seconds = ((exec_output.duration.as_secs_f32()) * 10.0).round() / 10.0;
 
-    let total_lines = exec_output.aggregated_output.text.lines().count();
+    let transcript = exec_output.model_transcript();
+    let total_lines = transcript.lines().count();
 
-    let formatted_output = truncate_text(&exec_output.aggregated_output.text, truncation_policy);
+    let formatted_output = with_truncation_manifest(
+        truncate_text(&transcript, truncation_policy),
+        &transcript,
+        truncation_policy,
+        tool_name,
+    );
 
     let mut sections = Vec::new();
 
@@ -92,21 +107,19 @@ pub fn format_exec_output_str(
     exec_output: &ExecToolCallOutput,
     truncation_policy: TruncationPolicy,
 ) -> String {
-    let ExecToolCallOutput {
-        aggregated_output, ..
-    } = exec_output;
+    // Truncate for model consumption before serialization.
+    formatted_truncate_text(&exec_output_body(exec_output), truncation_policy)
+}
 
-    let content = aggregated_output.text.as_str();
+fn exec_output_body(exec_output: &ExecToolCallOutput) -> String {
+    let content = exec_output.model_transcript();
 
-    let body = if exec_output.timed_out {
+    if exec_output.timed_out {
         format!(
             "command timed out after {} milliseconds\n{content}",
             exec_output.duration.as_millis()
         )
     } else {
         content.to_string()
-    };
-
-    // Truncate for model consumption before serialization.
-    formatted_truncate_text(&body, truncation_policy)
+    }
 }
@@ -56,6 +56,11 @@ impl ToolOrchestrator {
         let requirement = tool.approval_requirement(req).unwrap_or_else(|| {
             default_approval_requirement(approval_policy, &turn_ctx.sandbox_policy)
         });
+        let always_allowed = matches!(
+            requirement,
+            ApprovalRequirement::NeedsApproval { reason: None }
+        ) && !tool.wants_escalated_first_attempt(req)
+            && is_always_allowed(tool_ctx, req).await;
         match requirement {
             ApprovalRequirement::Skip => {
                 otel.tool_decision(otel_tn, otel_ci, ReviewDecision::Approved, otel_cfg);
@@ -63,6 +68,12 @@ impl ToolOrchestrator {
             ApprovalRequirement::Forbidden { reason } => {
                 return Err(ToolError::Rejected(reason));
             }
+            // An always-allowed pattern only stands in for the first, sandboxed
+            // approval. Escalations still ask, and leaving `already_approved`
+            // unset keeps the unsandboxed retry gated.
+            ApprovalRequirement::NeedsApproval { .. } if always_allowed => {
+                otel.tool_decision(otel_tn, otel_ci, ReviewDecision::Approved, otel_cfg);
+            }
             ApprovalRequirement::NeedsApproval { reason } => {
                 let mut risk = None;
 
@@ -193,6 +204,18 @@ impl ToolOrchestrator {
     }
 }
 
+async fn is_always_allowed<Rq: ProvidesSandboxRetryData>(tool_ctx: &ToolCtx<'_>, req: &Rq) -> bool {
+    match req.sandbox_retry_data() {
+        Some(metadata) => {
+            tool_ctx
+                .session
+                .is_command_always_allowed(&metadata.command)
+                .await
+        }
+        None => false,
+    }
+}
+
 fn build_denial_reason_from_output(_output: &ExecToolCallOutput) -> String {
     // Keep approval reason terse and stable for UX/tests, but accept the
     // output so we can evolve heuristics later without touching call sites.
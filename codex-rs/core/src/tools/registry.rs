@@ -34,6 +34,12 @@ pub trait ToolHandler: Send + Sync {
         false
     }
 
+    /// True when the handler already cuts its output to the tool's budget
+    /// (exec-style tools format and truncate their own output).
+    fn budgets_output(&self) -> bool {
+        false
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError>;
 }
 
@@ -98,6 +104,8 @@ impl ToolRegistry {
             return Err(FunctionCallError::Fatal(message));
         }
 
+        let output_policy = (!handler.budgets_output())
+            .then(|| invocation.turn.tool_output_budgets.policy_for(&tool_name));
         let output_cell = tokio::sync::Mutex::new(None);
 
         let result = otel
@@ -133,9 +141,12 @@ impl ToolRegistry {
         match result {
             Ok(_) => {
                 let mut guard = output_cell.lock().await;
-                let output = guard.take().ok_or_else(|| {
+                let mut output = guard.take().ok_or_else(|| {
                     FunctionCallError::Fatal("tool produced no output".to_string())
                 })?;
+                if let Some(policy) = output_policy {
+                    output = output.fit_to_budget(policy, &tool_name);
+                }
                 Ok(output.into_response(&call_id_owned, &payload_for_response))
             }
             Err(err) => Err(err),
@@ -144,6 +144,14 @@ impl ToolRouter {
         let payload_outputs_custom = matches!(payload, ToolPayload::Custom { .. });
         let failure_call_id = call_id.clone();
 
+        if let Some(output) = turn
+            .client
+            .replay()
+            .and_then(|replay| replay.recorded_output(&call_id))
+        {
+            return Ok(output);
+        }
+
         let invocation = ToolInvocation {
             session,
             turn,
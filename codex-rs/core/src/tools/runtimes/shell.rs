@@ -5,7 +5,14 @@ Executes shell requests under the orchestrator: asks for approval when needed,
 builds a CommandSpec, and runs it under the current SandboxAttempt.
 */
 use crate::exec::ExecToolCallOutput;
+#[cfg(target_os = "linux")]
+use crate::exec::SandboxType;
+#[cfg(target_os = "linux")]
+use crate::features::Feature;
+use crate::input_prompt::PromptHook;
 use crate::sandboxing::execute_env;
+#[cfg(target_os = "linux")]
+use crate::sandboxing::network_approval::execute_env_with_network_approval;
 use crate::tools::runtimes::build_command_spec;
 use crate::tools::sandboxing::Approvable;
 use crate::tools::sandboxing::ApprovalCtx;
@@ -19,9 +26,20 @@ use crate::tools::sandboxing::ToolCtx;
 use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
 use crate::tools::sandboxing::with_cached_approval;
+#[cfg(target_os = "linux")]
+use codex_protocol::protocol::AskForApproval;
+#[cfg(target_os = "linux")]
+use codex_protocol::protocol::EventMsg;
+#[cfg(target_os = "linux")]
+use codex_protocol::protocol::NetworkDestination;
 use codex_protocol::protocol::ReviewDecision;
+#[cfg(target_os = "linux")]
+use codex_protocol::protocol::SandboxDenial;
+#[cfg(target_os = "linux")]
+use codex_protocol::protocol::SandboxDeniedEvent;
 use futures::future::BoxFuture;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 #[derive(Clone, Debug)]
 pub struct ShellRequest {
@@ -58,11 +76,15 @@ impl ShellRuntime {
         Self
     }
 
-    fn stdout_stream(ctx: &ToolCtx<'_>) -> Option<crate::exec::StdoutStream> {
+    fn stdout_stream(ctx: &ToolCtx<'_>, req: &ShellRequest) -> Option<crate::exec::StdoutStream> {
         Some(crate::exec::StdoutStream {
             sub_id: ctx.turn.sub_id.clone(),
             call_id: ctx.call_id.clone(),
             tx_event: ctx.session.get_tx_event(),
+            input_prompts: Some(PromptHook::new(
+                Arc::clone(&ctx.session.services.input_prompts),
+                req.command.clone(),
+            )),
         })
     }
 }
@@ -140,9 +162,70 @@ impl ToolRuntime<ShellRequest, ExecToolCallOutput> for ShellRuntime {
         let env = attempt
             .env_for(&spec)
             .map_err(|err| ToolError::Codex(err.into()))?;
-        let out = execute_env(&env, attempt.policy, Self::stdout_stream(ctx))
+        #[cfg(target_os = "linux")]
+        if attempt.sandbox == SandboxType::LinuxSeccomp
+            && !attempt.policy.has_full_network_access()
+            && ctx.session.enabled(Feature::NetworkApproval).await
+        {
+            let approve = |destination| {
+                approve_network_destination(ctx, req.command.clone(), req.cwd.clone(), destination)
+            };
+            return execute_env_with_network_approval(
+                &env,
+                attempt.policy,
+                Self::stdout_stream(ctx, req),
+                approve,
+            )
+            .await
+            .map_err(ToolError::Codex);
+        }
+        let out = execute_env(&env, attempt.policy, Self::stdout_stream(ctx, req))
             .await
             .map_err(ToolError::Codex)?;
         Ok(out)
     }
 }
+
+#[cfg(target_os = "linux")]
+#[derive(serde::Serialize, Clone, Debug, Eq, PartialEq, Hash)]
+struct NetworkApprovalKey {
+    network: NetworkDestination,
+}
+
+/// Decide whether a running sandboxed command may connect to `destination`.
+/// Without an interactive user (`approval_policy = never`) the connection is
+/// denied and reported with a [`EventMsg::SandboxDenied`] event.
+#[cfg(target_os = "linux")]
+async fn approve_network_destination(
+    ctx: &ToolCtx<'_>,
+    command: Vec<String>,
+    cwd: PathBuf,
+    destination: NetworkDestination,
+) -> bool {
+    let session = ctx.session;
+    let turn = ctx.turn;
+    if turn.approval_policy == AskForApproval::Never {
+        let event = EventMsg::SandboxDenied(SandboxDeniedEvent {
+            call_id: ctx.call_id.clone(),
+            command,
+            denial: SandboxDenial::Network(destination),
+        });
+        session.send_event(turn, event).await;
+        return false;
+    }
+
+    let key = NetworkApprovalKey {
+        network: destination.clone(),
+    };
+    let call_id = ctx.call_id.clone();
+    let decision = with_cached_approval(&session.services, key, move || async move {
+        session
+            .request_network_approval(turn, call_id, command, cwd, destination)
+            .await
+    })
+    .await;
+    matches!(
+        decision,
+        ReviewDecision::Approved | ReviewDecision::ApprovedForSession
+    )
+}
@@ -35,6 +35,7 @@ pub(crate) struct ToolsConfig {
     pub apply_patch_tool_type: Option<ApplyPatchToolType>,
     pub web_search_request: bool,
     pub include_view_image_tool: bool,
+    pub include_session_recall_tool: bool,
     pub experimental_supported_tools: Vec<String>,
 }
 
@@ -52,6 +53,7 @@ impl ToolsConfig {
         let include_apply_patch_tool = features.enabled(Feature::ApplyPatchFreeform);
         let include_web_search_request = features.enabled(Feature::WebSearchRequest);
         let include_view_image_tool = features.enabled(Feature::ViewImageTool);
+        let include_session_recall_tool = features.enabled(Feature::SessionRecall);
 
         let shell_type = if !features.enabled(Feature::ShellTool) {
             ConfigShellToolType::Disabled
@@ -80,6 +82,7 @@ impl ToolsConfig {
             apply_patch_tool_type,
             web_search_request: include_web_search_request,
             include_view_image_tool,
+            include_session_recall_tool,
             experimental_supported_tools: model_family.experimental_supported_tools.clone(),
         }
     }
@@ -417,6 +420,42 @@ fn create_view_image_tool() -> ToolSpec {
     })
 }
 
+fn create_search_past_sessions_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "query".to_string(),
+        JsonSchema::String {
+            description: Some("Words to look for in earlier messages".to_string()),
+        },
+    );
+    properties.insert(
+        "limit".to_string(),
+        JsonSchema::Number {
+            description: Some("Maximum number of results (defaults to 5, at most 20)".to_string()),
+        },
+    );
+    properties.insert(
+        "all_projects".to_string(),
+        JsonSchema::Boolean {
+            description: Some(
+                "Search sessions from every project instead of only the current one".to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "search_past_sessions".to_string(),
+        description: "Search the user's earlier sessions for messages matching a query. Use it when the user refers to previous work or decisions that are not in the current conversation."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["query".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_test_sync_tool() -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
@@ -982,6 +1021,7 @@ pub(crate) fn build_specs(
     use crate::tools::handlers::McpResourceHandler;
     use crate::tools::handlers::PlanHandler;
     use crate::tools::handlers::ReadFileHandler;
+    use crate::tools::handlers::SessionRecallHandler;
     use crate::tools::handlers::ShellCommandHandler;
     use crate::tools::handlers::ShellHandler;
     use crate::tools::handlers::TestSyncHandler;
@@ -1097,6 +1137,11 @@ pub(crate) fn build_specs(
         builder.register_handler("view_image", view_image_handler);
     }
 
+    if config.include_session_recall_tool {
+        builder.push_spec_with_parallel_support(create_search_past_sessions_tool(), true);
+        builder.register_handler("search_past_sessions", Arc::new(SessionRecallHandler));
+    }
+
     if let Some(mcp_tools) = mcp_tools {
         let mut entries: Vec<(String, mcp_types::Tool)> = mcp_tools.into_iter().collect();
         entries.sort_by(|a, b| a.0.cmp(&b.0));
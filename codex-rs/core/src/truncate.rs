@@ -2,11 +2,17 @@
 //! and suffix on UTF-8 boundaries, and helpers for line/token‑based truncation
 //! used across the core crate.
 
+use std::collections::HashMap;
+
 use crate::config::Config;
 use codex_protocol::models::FunctionCallOutputContentItem;
 
 const APPROX_BYTES_PER_TOKEN: usize = 4;
 
+/// Built-in token budgets for tools whose output deserves more (or less)
+/// room than the model family default.
+const BUILTIN_TOOL_OUTPUT_TOKENS: &[(&str, usize)] = &[("read_file", 20_000)];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TruncationPolicy {
     Bytes(usize),
@@ -74,6 +80,127 @@ impl TruncationPolicy {
     }
 }
 
+/// Model-visible output budgets for individual tools.
+///
+/// A tool's budget comes from, in order: `tool_output_limits.<tool>`, the
+/// global `tool_output_token_limit`, the built-in default for the tool, and
+/// finally the model family's policy. Budgets are in tokens and are converted
+/// to bytes for byte-based model families.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ToolOutputBudgets {
+    default_policy: TruncationPolicy,
+    global_token_limit: Option<usize>,
+    overrides: HashMap<String, usize>,
+}
+
+impl ToolOutputBudgets {
+    pub(crate) fn new(config: &Config) -> Self {
+        Self {
+            default_policy: TruncationPolicy::new(config),
+            global_token_limit: config.tool_output_token_limit,
+            overrides: config.tool_output_limits.clone(),
+        }
+    }
+
+    pub(crate) fn policy_for(&self, tool_name: &str) -> TruncationPolicy {
+        let tokens = self.overrides.get(tool_name).copied().or_else(|| {
+            if self.global_token_limit.is_some() {
+                return None;
+            }
+            BUILTIN_TOOL_OUTPUT_TOKENS
+                .iter()
+                .find(|(name, _)| *name == tool_name)
+                .map(|(_, tokens)| *tokens)
+        });
+        match (tokens, self.default_policy) {
+            (None, policy) => policy,
+            (Some(tokens), TruncationPolicy::Bytes(_)) => {
+                TruncationPolicy::Bytes(approx_bytes_for_tokens(tokens))
+            }
+            (Some(tokens), TruncationPolicy::Tokens(_)) => TruncationPolicy::Tokens(tokens),
+        }
+    }
+}
+
+/// Chooses how conversation history truncates a tool output it records.
+pub(crate) trait HistoryTruncation {
+    /// `tool_name` is the tool that produced the output, or `None` when its
+    /// call is not in history.
+    fn policy_for_output(&self, tool_name: Option<&str>) -> TruncationPolicy;
+}
+
+impl HistoryTruncation for TruncationPolicy {
+    fn policy_for_output(&self, _tool_name: Option<&str>) -> TruncationPolicy {
+        *self
+    }
+}
+
+/// Each output keeps its own tool's budget, so outputs already cut to it
+/// are not cut a second time and other tools are not raised to it.
+impl HistoryTruncation for &ToolOutputBudgets {
+    fn policy_for_output(&self, tool_name: Option<&str>) -> TruncationPolicy {
+        tool_name.map_or(self.default_policy, |tool_name| self.policy_for(tool_name))
+    }
+}
+
+/// Truncate the middle of a tool's output to `policy` and, when anything was
+/// cut, append a manifest line telling the model how much and how to get it.
+pub(crate) fn truncate_tool_output(
+    content: &str,
+    policy: TruncationPolicy,
+    tool_name: &str,
+) -> String {
+    with_truncation_manifest(truncate_text(content, policy), content, policy, tool_name)
+}
+
+/// Append the truncation manifest for `original` to its already truncated
+/// rendering, if `original` exceeded the budget.
+pub(crate) fn with_truncation_manifest(
+    truncated: String,
+    original: &str,
+    policy: TruncationPolicy,
+    tool_name: &str,
+) -> String {
+    if original.len() <= policy.byte_budget() {
+        return truncated;
+    }
+    format!(
+        "{truncated}\n{}",
+        truncation_manifest(original, policy, tool_name)
+    )
+}
+
+fn truncation_manifest(original: &str, policy: TruncationPolicy, tool_name: &str) -> String {
+    let removed_bytes = original.len().saturating_sub(policy.byte_budget());
+    let (removed, total, budget, unit) = match policy {
+        TruncationPolicy::Tokens(tokens) => (
+            approx_tokens_from_byte_count(removed_bytes),
+            approx_token_count(original) as u64,
+            tokens,
+            "tokens",
+        ),
+        TruncationPolicy::Bytes(bytes) => {
+            (removed_bytes as u64, original.len() as u64, bytes, "bytes")
+        }
+    };
+    let hint = retrieval_hint(tool_name);
+    format!(
+        "[{tool_name} output truncated: {removed} of {total} {unit} were cut from the middle to fit its budget of {budget} {unit}. To see the omitted part, {hint}.]"
+    )
+}
+
+fn retrieval_hint(tool_name: &str) -> &'static str {
+    match tool_name {
+        "read_file" => "re-read with a narrower offset and limit",
+        "grep_files" => "search with a narrower pattern or path",
+        "list_dir" => "list a subdirectory or use a smaller depth",
+        "shell" | "local_shell" | "container.exec" | "exec_command" | "write_stdin" => {
+            "re-run the command with narrower output (for example with head, tail, sed -n or grep)"
+        }
+        _ => "call the tool again with narrower arguments",
+    }
+}
+
 pub(crate) fn formatted_truncate_text(content: &str, policy: TruncationPolicy) -> String {
     if content.len() <= policy.byte_budget() {
         return content.to_string();
@@ -305,15 +432,125 @@ fn approx_tokens_from_byte_count(bytes: usize) -> u64 {
 #[cfg(test)]
 mod tests {
 
+    use super::ToolOutputBudgets;
     use super::TruncationPolicy;
     use super::approx_token_count;
     use super::formatted_truncate_text;
     use super::split_string;
     use super::truncate_function_output_items_with_policy;
     use super::truncate_text;
+    use super::truncate_tool_output;
     use super::truncate_with_token_budget;
     use codex_protocol::models::FunctionCallOutputContentItem;
     use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    fn budgets(
+        default_policy: TruncationPolicy,
+        global_token_limit: Option<usize>,
+        overrides: &[(&str, usize)],
+    ) -> ToolOutputBudgets {
+        ToolOutputBudgets {
+            default_policy,
+            global_token_limit,
+            overrides: overrides
+                .iter()
+                .map(|(name, tokens)| (name.to_string(), *tokens))
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn tool_output_budget_precedence() {
+        let configured = budgets(
+            TruncationPolicy::Tokens(10_000),
+            None,
+            &[("read_file", 4_000), ("docs__search", 500)],
+        );
+        assert_eq!(
+            configured.policy_for("read_file"),
+            TruncationPolicy::Tokens(4_000)
+        );
+        assert_eq!(
+            configured.policy_for("docs__search"),
+            TruncationPolicy::Tokens(500)
+        );
+        assert_eq!(
+            configured.policy_for("shell"),
+            TruncationPolicy::Tokens(10_000)
+        );
+
+        // Built-in defaults apply when nothing is configured...
+        let defaults = budgets(TruncationPolicy::Tokens(10_000), None, &[]);
+        assert_eq!(
+            defaults.policy_for("read_file"),
+            TruncationPolicy::Tokens(20_000)
+        );
+        // ...but an explicit global limit takes precedence over them.
+        let global = budgets(TruncationPolicy::Tokens(2_000), Some(2_000), &[]);
+        assert_eq!(
+            global.policy_for("read_file"),
+            TruncationPolicy::Tokens(2_000)
+        );
+
+        // Byte-based model families get the token budget converted to bytes.
+        let bytes = budgets(TruncationPolicy::Bytes(10_000), None, &[]);
+        assert_eq!(
+            bytes.policy_for("read_file"),
+            TruncationPolicy::Bytes(80_000)
+        );
+    }
+
+    #[test]
+    fn history_truncates_each_output_to_its_own_tool_budget() {
+        let configured = budgets(
+            TruncationPolicy::Tokens(10_000),
+            None,
+            &[("docs__search", 500)],
+        );
+        let history = &configured;
+        assert_eq!(
+            history.policy_for_output(Some("read_file")),
+            TruncationPolicy::Tokens(20_000)
+        );
+        assert_eq!(
+            history.policy_for_output(Some("docs__search")),
+            TruncationPolicy::Tokens(500)
+        );
+        assert_eq!(
+            history.policy_for_output(Some("shell")),
+            TruncationPolicy::Tokens(10_000)
+        );
+        assert_eq!(
+            history.policy_for_output(None),
+            TruncationPolicy::Tokens(10_000)
+        );
+    }
+
+    #[test]
+    fn truncated_tool_output_ends_with_manifest() {
+        let content = "a".repeat(1_000);
+        assert_eq!(
+            truncate_tool_output(&content, TruncationPolicy::Bytes(100), "read_file"),
+            format!(
+                "{}…900 chars truncated…{}\n[read_file output truncated: 900 of 1000 bytes were cut from the middle to fit its budget of 100 bytes. To see the omitted part, re-read with a narrower offset and limit.]",
+                "a".repeat(50),
+                "a".repeat(50)
+            )
+        );
+        assert_eq!(
+            truncate_tool_output(&content, TruncationPolicy::Tokens(50), "docs__search"),
+            format!(
+                "{}…200 tokens truncated…{}\n[docs__search output truncated: 200 of 250 tokens were cut from the middle to fit its budget of 50 tokens. To see the omitted part, call the tool again with narrower arguments.]",
+                "a".repeat(100),
+                "a".repeat(100)
+            )
+        );
+        assert_eq!(
+            truncate_tool_output("short", TruncationPolicy::Bytes(100), "read_file"),
+            "short"
+        );
+    }
 
     #[test]
     fn split_string_works() {
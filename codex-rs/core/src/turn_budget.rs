@@ -0,0 +1,274 @@
+//! Per-turn budget from `[turn_limits]`.
+//!
+//! A turn that runs past `max_turn_seconds` or produces more than
+//! `max_turn_output_tokens` is not cut off outright: the model gets one more
+//! request, ending with a developer message asking it to summarize where it
+//! stopped. That message is sent with the wrap-up request only and is not
+//! kept in the conversation history. Only the wrap-up request is bounded by
+//! [`WRAP_UP_GRACE`]. The budget covers a single task, so history from a
+//! resumed session does not count against it.
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_protocol::protocol::TokenUsage;
+use codex_protocol::protocol::TurnLimitKind;
+use codex_protocol::protocol::TurnLimitReachedEvent;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::types::TurnLimits;
+use crate::truncate::approx_token_count;
+
+/// How long the wrap-up request may run before the turn ends regardless.
+pub(crate) const WRAP_UP_GRACE: Duration = Duration::from_secs(60);
+
+#[derive(Debug)]
+pub(crate) struct TurnBudget {
+    limits: TurnLimits,
+    started: Instant,
+    output_tokens: u64,
+    /// The limit that was hit and when the wrap-up request started.
+    wrap_up: Option<(TurnLimitKind, Instant)>,
+}
+
+impl TurnBudget {
+    pub(crate) fn new(limits: TurnLimits, started: Instant) -> Self {
+        Self {
+            limits,
+            started,
+            output_tokens: 0,
+            wrap_up: None,
+        }
+    }
+
+    /// Adds the output tokens of one model request.
+    pub(crate) fn record_usage(&mut self, usage: Option<&TokenUsage>) {
+        if let Some(usage) = usage {
+            self.output_tokens = self
+                .output_tokens
+                .saturating_add(u64::try_from(usage.output_tokens).unwrap_or(0));
+        }
+    }
+
+    /// Adds output that streamed in before a request was cut off, and so
+    /// never reported its usage.
+    pub(crate) fn record_streamed(&mut self, output_tokens: u64) {
+        self.output_tokens = self.output_tokens.saturating_add(output_tokens);
+    }
+
+    /// Meter for the next model request that cancels it through
+    /// `cancellation_token` once its streamed output uses up what is left of
+    /// `max_turn_output_tokens`. `None` when there is no such limit or the
+    /// turn is already wrapping up.
+    pub(crate) fn output_meter(
+        &self,
+        cancellation_token: CancellationToken,
+    ) -> Option<OutputMeter> {
+        if self.wrap_up.is_some() {
+            return None;
+        }
+        let max = self.limits.max_turn_output_tokens?;
+        Some(OutputMeter {
+            streamed: Arc::new(AtomicU64::new(0)),
+            remaining: max.saturating_sub(self.output_tokens),
+            cancellation_token,
+        })
+    }
+
+    /// When the next model request must be cancelled: the wall-clock limit,
+    /// or the end of the grace period once wrapping up.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        match self.wrap_up {
+            Some((_, since)) => Some(since + WRAP_UP_GRACE),
+            None => self
+                .limits
+                .max_turn_seconds
+                .map(|seconds| self.started + Duration::from_secs(seconds)),
+        }
+    }
+
+    /// Starts wrapping up if a limit has been hit by `now`. Returns the
+    /// limit the first time only.
+    pub(crate) fn check(&mut self, now: Instant) -> Option<TurnLimitKind> {
+        if self.wrap_up.is_some() {
+            return None;
+        }
+        let out_of_time = self
+            .limits
+            .max_turn_seconds
+            .is_some_and(|seconds| now >= self.started + Duration::from_secs(seconds));
+        let out_of_tokens = self
+            .limits
+            .max_turn_output_tokens
+            .is_some_and(|max| self.output_tokens >= max);
+        let limit = if out_of_time {
+            TurnLimitKind::WallClock
+        } else if out_of_tokens {
+            TurnLimitKind::OutputTokens
+        } else {
+            return None;
+        };
+        self.wrap_up = Some((limit, now));
+        Some(limit)
+    }
+
+    /// Set once a limit was hit; the turn ends after the current request.
+    pub(crate) fn reached(&self) -> Option<TurnLimitReachedEvent> {
+        self.wrap_up.map(|(limit, _)| TurnLimitReachedEvent {
+            limit,
+            threshold: self.threshold(limit),
+        })
+    }
+
+    /// Developer message that asks the model to stop and summarize.
+    pub(crate) fn wrap_up_instructions(&self, limit: TurnLimitKind) -> String {
+        let threshold = self.threshold(limit);
+        let reason = match limit {
+            TurnLimitKind::WallClock => format!("its time limit of {threshold}s"),
+            TurnLimitKind::OutputTokens => format!("its limit of {threshold} output tokens"),
+        };
+        format!(
+            "This turn has reached {reason}. Do not start any new work or call any more tools. \
+             Reply now with a short summary of what you completed, what is left to do, and \
+             anything the user should check."
+        )
+    }
+
+    fn threshold(&self, limit: TurnLimitKind) -> u64 {
+        match limit {
+            TurnLimitKind::WallClock => self.limits.max_turn_seconds,
+            TurnLimitKind::OutputTokens => self.limits.max_turn_output_tokens,
+        }
+        .unwrap_or_default()
+    }
+}
+
+/// Estimates the output tokens of a response while it streams in, so a
+/// request that runs past the output limit is cut off rather than waited
+/// out until it reports its usage.
+#[derive(Clone, Debug)]
+pub(crate) struct OutputMeter {
+    streamed: Arc<AtomicU64>,
+    remaining: u64,
+    cancellation_token: CancellationToken,
+}
+
+impl OutputMeter {
+    pub(crate) fn record_delta(&self, delta: &str) {
+        let tokens = u64::try_from(approx_token_count(delta)).unwrap_or(u64::MAX);
+        let streamed = self
+            .streamed
+            .fetch_add(tokens, Ordering::Relaxed)
+            .saturating_add(tokens);
+        if streamed >= self.remaining {
+            self.cancellation_token.cancel();
+        }
+    }
+
+    /// Estimated output tokens streamed so far.
+    pub(crate) fn streamed(&self) -> u64 {
+        self.streamed.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn usage(output_tokens: i64) -> TokenUsage {
+        TokenUsage {
+            output_tokens,
+            ..TokenUsage::default()
+        }
+    }
+
+    #[test]
+    fn unlimited_budget_never_trips() {
+        let start = Instant::now();
+        let mut budget = TurnBudget::new(TurnLimits::default(), start);
+        budget.record_usage(Some(&usage(1_000_000)));
+
+        assert_eq!(budget.deadline(), None);
+        assert_eq!(budget.check(start + Duration::from_secs(86_400)), None);
+        assert_eq!(budget.reached(), None);
+    }
+
+    #[test]
+    fn output_tokens_add_up_across_requests() {
+        let start = Instant::now();
+        let limits = TurnLimits {
+            max_turn_seconds: None,
+            max_turn_output_tokens: Some(100),
+        };
+        let mut budget = TurnBudget::new(limits, start);
+
+        budget.record_usage(Some(&usage(60)));
+        budget.record_usage(None);
+        assert_eq!(budget.check(start), None);
+
+        budget.record_usage(Some(&usage(40)));
+        assert_eq!(budget.check(start), Some(TurnLimitKind::OutputTokens));
+        assert_eq!(
+            budget.reached(),
+            Some(TurnLimitReachedEvent {
+                limit: TurnLimitKind::OutputTokens,
+                threshold: 100,
+            })
+        );
+        // Trips only once; the wrap-up request ends the turn.
+        assert_eq!(budget.check(start), None);
+        assert_eq!(budget.deadline(), Some(start + WRAP_UP_GRACE));
+    }
+
+    #[test]
+    fn wall_clock_deadline_moves_to_the_grace_period() {
+        let start = Instant::now();
+        let limits = TurnLimits {
+            max_turn_seconds: Some(30),
+            max_turn_output_tokens: None,
+        };
+        let mut budget = TurnBudget::new(limits, start);
+        assert_eq!(budget.deadline(), Some(start + Duration::from_secs(30)));
+        assert_eq!(budget.check(start + Duration::from_secs(29)), None);
+
+        let tripped_at = start + Duration::from_secs(31);
+        assert_eq!(budget.check(tripped_at), Some(TurnLimitKind::WallClock));
+        assert_eq!(budget.deadline(), Some(tripped_at + WRAP_UP_GRACE));
+        assert_eq!(
+            budget.wrap_up_instructions(TurnLimitKind::WallClock),
+            "This turn has reached its time limit of 30s. Do not start any new work or \
+             call any more tools. Reply now with a short summary of what you completed, what is \
+             left to do, and anything the user should check."
+        );
+    }
+
+    #[test]
+    fn output_meter_cancels_once_the_rest_of_the_budget_streams_in() {
+        let start = Instant::now();
+        let limits = TurnLimits {
+            max_turn_seconds: None,
+            max_turn_output_tokens: Some(10),
+        };
+        let mut budget = TurnBudget::new(limits, start);
+        budget.record_usage(Some(&usage(6)));
+
+        let token = CancellationToken::new();
+        let meter = budget
+            .output_meter(token.clone())
+            .expect("output limit set");
+        meter.record_delta("abcd");
+        meter.record_delta("efgh");
+        meter.record_delta("ijkl");
+        assert!(!token.is_cancelled());
+        meter.record_delta("mnop");
+        assert!(token.is_cancelled());
+
+        budget.record_streamed(meter.streamed());
+        assert_eq!(budget.check(start), Some(TurnLimitKind::OutputTokens));
+        assert!(budget.output_meter(CancellationToken::new()).is_none());
+    }
+}
@@ -11,6 +11,8 @@ use sha1::digest::Output;
 use uuid::Uuid;
 
 use crate::protocol::FileChange;
+use crate::protocol::TurnFileChange;
+use crate::protocol::TurnFileChangeKind;
 
 const ZERO_OID: &str = "0000000000000000000000000000000000000000";
 const DEV_NULL: &str = "/dev/null";
@@ -219,6 +221,49 @@ impl TurnDiffTracker {
         if s.len() == 40 { Some(s) } else { None }
     }
 
+    /// Current external paths of every file touched so far, sorted for stable output.
+    pub fn touched_paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self.temp_name_to_current_path.values().cloned().collect();
+        paths.sort();
+        paths
+    }
+
+    /// Files whose contents or location differ from their baseline, sorted by
+    /// path. A renamed file is reported once, with its starting path.
+    pub fn changed_files(&self) -> Vec<TurnFileChange> {
+        let mut changes: Vec<TurnFileChange> = self
+            .baseline_file_info
+            .iter()
+            .filter_map(|(internal, baseline)| {
+                let current_path = self.get_path_for_internal(internal)?;
+                let mode = file_mode_for_path(&current_path).unwrap_or(FileMode::Regular);
+                let current_bytes = blob_bytes(&current_path, mode);
+                let existed = baseline.oid != ZERO_OID;
+                let (path, kind, old_path) = match current_bytes {
+                    None if !existed => return None,
+                    None => (baseline.path.clone(), TurnFileChangeKind::Deleted, None),
+                    Some(_) if !existed => (current_path, TurnFileChangeKind::Added, None),
+                    Some(_) if current_path != baseline.path => (
+                        current_path,
+                        TurnFileChangeKind::Renamed,
+                        Some(baseline.path.clone()),
+                    ),
+                    Some(bytes) if bytes != baseline.content => {
+                        (current_path, TurnFileChangeKind::Modified, None)
+                    }
+                    Some(_) => return None,
+                };
+                Some(TurnFileChange {
+                    path,
+                    kind,
+                    old_path,
+                })
+            })
+            .collect();
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        changes
+    }
+
     /// Recompute the aggregated unified diff by comparing all of the in-memory snapshots that were
     /// collected before the first time they were touched by apply_patch during this turn with
     /// the current repo state.
@@ -893,4 +938,82 @@ index {ZERO_OID}..{right_oid}
         };
         assert_eq!(combined, expected_combined);
     }
+
+    #[test]
+    fn changed_files_reports_each_file_once() {
+        let dir = tempdir().unwrap();
+        let added = dir.path().join("added.txt");
+        let modified = dir.path().join("modified.txt");
+        let deleted = dir.path().join("deleted.txt");
+        let untouched = dir.path().join("untouched.txt");
+        let src = dir.path().join("src.txt");
+        let dest = dir.path().join("dst.txt");
+        fs::write(&modified, "before\n").unwrap();
+        fs::write(&deleted, "gone\n").unwrap();
+        fs::write(&untouched, "same\n").unwrap();
+        fs::write(&src, "moved\n").unwrap();
+
+        let mut acc = TurnDiffTracker::new();
+        let update = || FileChange::Update {
+            unified_diff: "".to_owned(),
+            move_path: None,
+        };
+        acc.on_patch_begin(&HashMap::from([
+            (
+                added.clone(),
+                FileChange::Add {
+                    content: "new\n".to_string(),
+                },
+            ),
+            (modified.clone(), update()),
+            (
+                deleted.clone(),
+                FileChange::Delete {
+                    content: "gone\n".to_string(),
+                },
+            ),
+            (untouched.clone(), update()),
+            (
+                src.clone(),
+                FileChange::Update {
+                    unified_diff: "".to_owned(),
+                    move_path: Some(dest.clone()),
+                },
+            ),
+        ]));
+
+        fs::write(&added, "new\n").unwrap();
+        fs::write(&modified, "after\n").unwrap();
+        fs::remove_file(&deleted).unwrap();
+        fs::rename(&src, &dest).unwrap();
+        // A second patch to the same file is still a single change.
+        acc.on_patch_begin(&HashMap::from([(modified.clone(), update())]));
+        fs::write(&modified, "after again\n").unwrap();
+
+        assert_eq!(
+            acc.changed_files(),
+            vec![
+                TurnFileChange {
+                    path: added,
+                    kind: TurnFileChangeKind::Added,
+                    old_path: None,
+                },
+                TurnFileChange {
+                    path: deleted,
+                    kind: TurnFileChangeKind::Deleted,
+                    old_path: None,
+                },
+                TurnFileChange {
+                    path: dest,
+                    kind: TurnFileChangeKind::Renamed,
+                    old_path: Some(src),
+                },
+                TurnFileChange {
+                    path: modified,
+                    kind: TurnFileChangeKind::Modified,
+                    old_path: None,
+                },
+            ]
+        );
+    }
 }
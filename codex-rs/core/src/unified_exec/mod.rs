@@ -46,6 +46,8 @@ pub(crate) const MAX_YIELD_TIME_MS: u64 = 30_000;
 pub(crate) const DEFAULT_MAX_OUTPUT_TOKENS: usize = 10_000;
 pub(crate) const UNIFIED_EXEC_OUTPUT_MAX_BYTES: usize = 1024 * 1024; // 1 MiB
 pub(crate) const UNIFIED_EXEC_OUTPUT_MAX_TOKENS: usize = UNIFIED_EXEC_OUTPUT_MAX_BYTES / 4;
+/// Reported for a command killed at an input prompt (128 + SIGKILL).
+pub(crate) const PROMPT_TERMINATED_EXIT_CODE: i32 = 137;
 
 pub(crate) struct UnifiedExecContext {
     pub session: Arc<Session>,
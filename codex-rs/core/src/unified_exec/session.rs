@@ -162,6 +162,7 @@ impl UnifiedExecSession {
             stdout: StreamOutput::new(aggregated_text.clone()),
             stderr: StreamOutput::new(String::new()),
             aggregated_output: StreamOutput::new(aggregated_text.clone()),
+            stderr_ranges: Vec::new(),
             duration: Duration::ZERO,
             timed_out: false,
         };
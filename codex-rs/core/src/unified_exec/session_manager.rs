@@ -12,9 +12,11 @@ use crate::exec::ExecToolCallOutput;
 use crate::exec::StreamOutput;
 use crate::exec_env::create_env;
 use crate::exec_policy::create_approval_requirement_for_command;
+use crate::input_prompt::PROMPT_POLL_INTERVAL;
 use crate::protocol::BackgroundEventEvent;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecCommandSource;
+use crate::protocol::ExecInputResponse;
 use crate::sandboxing::ExecEnv;
 use crate::sandboxing::SandboxPermissions;
 use crate::tools::events::ToolEmitter;
@@ -30,6 +32,8 @@ use crate::truncate::approx_token_count;
 use crate::truncate::formatted_truncate_text;
 
 use super::ExecCommandRequest;
+use super::MIN_YIELD_TIME_MS;
+use super::PROMPT_TERMINATED_EXIT_CODE;
 use super::SessionEntry;
 use super::UnifiedExecContext;
 use super::UnifiedExecError;
@@ -69,25 +73,44 @@ impl UnifiedExecSessionManager {
         let start = Instant::now();
         let (output_buffer, output_notify) = session.output_handles();
         let deadline = start + Duration::from_millis(yield_time_ms);
-        let collected =
-            Self::collect_output_until_deadline(&output_buffer, &output_notify, deadline).await;
+        let (collected, stopped_at_prompt) = Self::collect_output_answering_prompts(
+            &session,
+            context,
+            &request.command,
+            &output_buffer,
+            &output_notify,
+            deadline,
+        )
+        .await?;
         let wall_time = Instant::now().saturating_duration_since(start);
 
-        let text = String::from_utf8_lossy(&collected).to_string();
+        let mut text = String::from_utf8_lossy(&collected).to_string();
+        if let Some(prompt) = &stopped_at_prompt {
+            text.push_str(&format!(
+                "\n[command was stopped while waiting for input: {prompt}]"
+            ));
+        }
         let output = formatted_truncate_text(&text, TruncationPolicy::Tokens(max_tokens));
         let chunk_id = generate_chunk_id();
-        let has_exited = session.has_exited();
-        let stored_id = self
-            .store_session(session, context, &request.command, cwd.clone(), start)
-            .await;
-        let exit_code = self
-            .sessions
-            .lock()
-            .await
-            .get(&stored_id)
-            .map(|entry| entry.session.exit_code());
-        // Only include a session_id in the response if the process is still alive.
-        let session_id = if has_exited { None } else { Some(stored_id) };
+        let (session_id, exit_code) = if stopped_at_prompt.is_some() {
+            // Dropping the session kills the process.
+            drop(session);
+            (None, Some(Some(PROMPT_TERMINATED_EXIT_CODE)))
+        } else {
+            let has_exited = session.has_exited();
+            let stored_id = self
+                .store_session(session, context, &request.command, cwd.clone(), start)
+                .await;
+            let exit_code = self
+                .sessions
+                .lock()
+                .await
+                .get(&stored_id)
+                .map(|entry| entry.session.exit_code());
+            // Only include a session_id in the response if the process is still alive.
+            let session_id = if has_exited { None } else { Some(stored_id) };
+            (session_id, exit_code)
+        };
 
         let original_token_count = approx_token_count(&text);
 
@@ -215,6 +238,7 @@ impl UnifiedExecSessionManager {
             stdout: StreamOutput::new(response.output.clone()),
             stderr: StreamOutput::new(String::new()),
             aggregated_output: StreamOutput::new(response.output.clone()),
+            stderr_ranges: Vec::new(),
             duration: response.wall_time,
             timed_out: false,
         };
@@ -350,6 +374,7 @@ impl UnifiedExecSessionManager {
             stdout: StreamOutput::new(aggregated_output.clone()),
             stderr: StreamOutput::new(String::new()),
             aggregated_output: StreamOutput::new(aggregated_output),
+            stderr_ranges: Vec::new(),
             duration,
             timed_out: false,
         };
@@ -383,6 +408,7 @@ impl UnifiedExecSessionManager {
             stdout: StreamOutput::new(aggregated_output.clone()),
             stderr: StreamOutput::new(String::new()),
             aggregated_output: StreamOutput::new(aggregated_output),
+            stderr_ranges: Vec::new(),
             duration,
             timed_out: false,
         };
@@ -477,6 +503,77 @@ impl UnifiedExecSessionManager {
             .map_err(|e| UnifiedExecError::create_session(format!("{e:?}")))
     }
 
+    /// Like [`Self::collect_output_until_deadline`], but asks the user when
+    /// the command stops at an input prompt and writes their answer to the
+    /// PTY. Returns the prompt text when the command should be terminated.
+    /// An unanswered prompt does not hold the call past `deadline` or the
+    /// command's exit; the request is withdrawn and the call yields as usual.
+    async fn collect_output_answering_prompts(
+        session: &UnifiedExecSession,
+        context: &UnifiedExecContext,
+        command: &[String],
+        output_buffer: &OutputBuffer,
+        output_notify: &Arc<Notify>,
+        mut deadline: Instant,
+    ) -> Result<(Vec<u8>, Option<String>), UnifiedExecError> {
+        let broker = &context.session.services.input_prompts;
+        let Some(mut watcher) = broker.watcher() else {
+            let collected =
+                Self::collect_output_until_deadline(output_buffer, output_notify, deadline).await;
+            return Ok((collected, None));
+        };
+        let tx_event = context.session.get_tx_event();
+        let mut collected = Vec::new();
+        loop {
+            let step = deadline.min(Instant::now() + PROMPT_POLL_INTERVAL);
+            let chunk =
+                Self::collect_output_until_deadline(output_buffer, output_notify, step).await;
+            watcher.observe(&chunk, std::time::Instant::now());
+            collected.extend_from_slice(&chunk);
+            if session.has_exited() || Instant::now() >= deadline {
+                return Ok((collected, None));
+            }
+            let Some(prompt) = watcher.poll(std::time::Instant::now()) else {
+                continue;
+            };
+            let request = broker.request(
+                &tx_event,
+                &context.turn.sub_id,
+                &context.call_id,
+                command,
+                &prompt,
+                true,
+            );
+            let response = tokio::select! {
+                response = request => response,
+                _ = tokio::time::sleep_until(deadline) => {
+                    broker.cancel(&context.call_id);
+                    continue;
+                }
+                _ = Self::wait_for_exit(session) => {
+                    broker.cancel(&context.call_id);
+                    continue;
+                }
+            };
+            match response {
+                ExecInputResponse::Input { text } => {
+                    Self::send_input(&session.writer_sender(), format!("{text}\n").as_bytes())
+                        .await?;
+                    // Give the command time to react before yielding.
+                    deadline =
+                        deadline.max(Instant::now() + Duration::from_millis(MIN_YIELD_TIME_MS));
+                }
+                ExecInputResponse::Terminate => return Ok((collected, Some(prompt.text))),
+            }
+        }
+    }
+
+    async fn wait_for_exit(session: &UnifiedExecSession) {
+        while !session.has_exited() {
+            tokio::time::sleep(PROMPT_POLL_INTERVAL).await;
+        }
+    }
+
     pub(super) async fn collect_output_until_deadline(
         output_buffer: &OutputBuffer,
         output_notify: &Arc<Notify>,
@@ -1,10 +1,19 @@
+use std::path::Path;
+
+use codex_protocol::protocol::EventMsg;
 use serde::Serialize;
 use tracing::error;
 use tracing::warn;
 
+use crate::config::types::NotificationHooks;
+use crate::notification_hooks::HookEvent;
+use crate::notification_hooks::HookVars;
+use crate::notification_hooks::NotificationHookRunner;
+
 #[derive(Debug, Default)]
 pub(crate) struct UserNotifier {
     notify_command: Option<Vec<String>>,
+    hooks: NotificationHookRunner,
 }
 
 impl UserNotifier {
@@ -34,9 +43,27 @@ impl UserNotifier {
         }
     }
 
-    pub(crate) fn new(notify: Option<Vec<String>>) -> Self {
+    /// Run the `[notifications]` hook for `msg`, if it has one. Never waits
+    /// for the hook.
+    pub(crate) fn on_event(&self, msg: &EventMsg, session_id: &str, cwd: &Path) {
+        let Some((event, summary)) = HookEvent::from_event_msg(msg) else {
+            return;
+        };
+        let cwd = cwd.display().to_string();
+        self.hooks.dispatch(
+            event,
+            &HookVars {
+                cwd: &cwd,
+                summary: &summary,
+                session_id,
+            },
+        );
+    }
+
+    pub(crate) fn new(notify: Option<Vec<String>>, hooks: NotificationHooks) -> Self {
         Self {
             notify_command: notify,
+            hooks: NotificationHookRunner::new(hooks),
         }
     }
 }
@@ -87,6 +87,7 @@ mod tests {
             stdout: StreamOutput::new("hi".to_string()),
             stderr: StreamOutput::new(String::new()),
             aggregated_output: StreamOutput::new("hi".to_string()),
+            stderr_ranges: Vec::new(),
             duration: Duration::from_secs(1),
             timed_out: false,
         };
@@ -111,6 +112,7 @@ mod tests {
             stdout: StreamOutput::new("stdout-only".to_string()),
             stderr: StreamOutput::new("stderr-only".to_string()),
             aggregated_output: StreamOutput::new("combined output wins".to_string()),
+            stderr_ranges: Vec::new(),
             duration: Duration::from_millis(120),
             timed_out: false,
         };
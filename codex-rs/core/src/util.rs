@@ -4,6 +4,20 @@ use rand::Rng;
 use tracing::debug;
 use tracing::error;
 
+/// Placeholder substituted for credentials in logs and exported bundles.
+pub(crate) const REDACTED: &str = "[REDACTED]";
+
+/// Fragments that mark a config key, header name, or query parameter name as
+/// carrying a credential. Matching is case-insensitive and treats `-` as `_`.
+const SENSITIVE_NAME_FRAGMENTS: &[&str] = &[
+    "token",
+    "secret",
+    "password",
+    "api_key",
+    "apikey",
+    "authorization",
+];
+
 const INITIAL_DELAY_MS: u64 = 200;
 const BACKOFF_FACTOR: f64 = 2.0;
 
@@ -37,6 +51,39 @@ pub(crate) fn try_parse_error_message(text: &str) -> String {
     text.to_string()
 }
 
+/// Whether a value stored under `name` should be treated as a secret.
+pub(crate) fn is_sensitive_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase().replace('-', "_");
+    // These name an environment variable rather than holding the secret.
+    if name.ends_with("env_var") || name.ends_with("env_key") {
+        return false;
+    }
+    SENSITIVE_NAME_FRAGMENTS
+        .iter()
+        .any(|needle| name.contains(needle))
+}
+
+/// Replace the values of credential-like query parameters in `url` so the
+/// URL can be logged.
+pub(crate) fn redact_url(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let query = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _))
+                if is_sensitive_name(&urlencoding::decode(name).unwrap_or_default()) =>
+            {
+                format!("{name}={REDACTED}")
+            }
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+    format!("{base}?{query}")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -64,4 +111,27 @@ mod tests {
         let message = try_parse_error_message(text);
         assert_eq!(message, r#"{"message": "test"}"#);
     }
+
+    #[test]
+    fn sensitive_names_ignore_case_and_dashes() {
+        assert!(is_sensitive_name("Api-Key"));
+        assert!(is_sensitive_name("X-Auth-Token"));
+        assert!(is_sensitive_name("Authorization"));
+        assert!(!is_sensitive_name("api-version"));
+        assert!(!is_sensitive_name("token_env_var"));
+    }
+
+    #[test]
+    fn redact_url_masks_only_sensitive_params() {
+        assert_eq!(
+            redact_url(
+                "https://example.com/v1/responses?api-version=2025&api_key=sk-123&token=abc"
+            ),
+            "https://example.com/v1/responses?api-version=2025&api_key=[REDACTED]&token=[REDACTED]"
+        );
+        assert_eq!(
+            redact_url("https://example.com/v1/responses"),
+            "https://example.com/v1/responses"
+        );
+    }
 }
@@ -25,6 +25,10 @@ use wiremock::matchers::method;
 use wiremock::matchers::path;
 
 async fn run_request(input: Vec<ResponseItem>) -> Value {
+    run_request_with_num_ctx(input, None).await
+}
+
+async fn run_request_with_num_ctx(input: Vec<ResponseItem>, num_ctx: Option<u32>) -> Value {
     let server = MockServer::start().await;
 
     let template = ResponseTemplate::new(200)
@@ -55,6 +59,7 @@ async fn run_request(input: Vec<ResponseItem>) -> Value {
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
         requires_openai_auth: false,
+        num_ctx,
     };
 
     let codex_home = match TempDir::new() {
@@ -317,3 +322,22 @@ async fn suppresses_duplicate_assistant_messages() {
         Value::String("dup".into())
     );
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn requests_usage_in_stream() {
+    skip_if_no_network!();
+
+    let body = run_request(vec![user_message("u1")]).await;
+    assert_eq!(
+        body["stream_options"],
+        serde_json::json!({ "include_usage": true })
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn sends_num_ctx_in_request_options() {
+    skip_if_no_network!();
+
+    let body = run_request_with_num_ctx(vec![user_message("u1")], Some(32_768)).await;
+    assert_eq!(body["options"], serde_json::json!({ "num_ctx": 32_768 }));
+}
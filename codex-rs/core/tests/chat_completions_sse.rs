@@ -13,6 +13,7 @@ use codex_core::WireApi;
 use codex_otel::otel_event_manager::OtelEventManager;
 use codex_protocol::ConversationId;
 use codex_protocol::models::ReasoningItemContent;
+use codex_protocol::protocol::TokenUsage;
 use core_test_support::load_default_config_for_test;
 use core_test_support::skip_if_no_network;
 use futures::StreamExt;
@@ -55,6 +56,7 @@ async fn run_stream_with_bytes(sse_body: &[u8]) -> Vec<ResponseEvent> {
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
         requires_openai_auth: false,
+        num_ctx: None,
     };
 
     let codex_home = match TempDir::new() {
@@ -381,6 +383,102 @@ async fn streams_reasoning_before_tool_call() {
     assert_matches!(events[4], ResponseEvent::Completed { .. });
 }
 
+fn completed_usage(events: &[ResponseEvent]) -> Option<TokenUsage> {
+    match events.last() {
+        Some(ResponseEvent::Completed { token_usage, .. }) => token_usage.clone(),
+        other => panic!("expected trailing Completed event, got {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn reports_usage_from_final_chunk() {
+    skip_if_no_network!();
+
+    let sse = concat!(
+        "data: {\"choices\":[{\"delta\":{\"content\":\"hi\"}}]}\n\n",
+        "data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}],\"usage\":{\"prompt_tokens\":12,\"completion_tokens\":3,\"total_tokens\":15,\"prompt_tokens_details\":{\"cached_tokens\":8},\"completion_tokens_details\":{\"reasoning_tokens\":1}}}\n\n",
+    );
+
+    let events = run_stream(sse).await;
+
+    assert_eq!(
+        completed_usage(&events),
+        Some(TokenUsage {
+            input_tokens: 12,
+            cached_input_tokens: 8,
+            output_tokens: 3,
+            reasoning_output_tokens: 1,
+            total_tokens: 15,
+        })
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn reports_usage_sent_after_finish_reason() {
+    skip_if_no_network!();
+
+    // With `stream_options.include_usage` OpenAI sends usage in its own chunk
+    // after the one carrying `finish_reason`.
+    let sse = concat!(
+        "data: {\"choices\":[{\"delta\":{\"content\":\"hi\"}}],\"usage\":null}\n\n",
+        "data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}],\"usage\":null}\n\n",
+        "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":20,\"completion_tokens\":2,\"total_tokens\":22}}\n\n",
+        "data: [DONE]\n\n",
+    );
+
+    let events = run_stream(sse).await;
+
+    assert_eq!(
+        completed_usage(&events),
+        Some(TokenUsage {
+            input_tokens: 20,
+            output_tokens: 2,
+            total_tokens: 22,
+            ..TokenUsage::default()
+        })
+    );
+    let completed = events
+        .iter()
+        .filter(|event| matches!(event, ResponseEvent::Completed { .. }))
+        .count();
+    assert_eq!(completed, 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn reports_partial_usage_as_is() {
+    skip_if_no_network!();
+
+    let sse = concat!(
+        "data: {\"choices\":[{\"delta\":{\"content\":\"hi\"}}],\"usage\":null}\n\n",
+        "data: {\"choices\":[],\"usage\":{\"total_tokens\":42}}\n\n",
+        "data: [DONE]\n\n",
+    );
+
+    let events = run_stream(sse).await;
+
+    assert_eq!(
+        completed_usage(&events),
+        Some(TokenUsage {
+            total_tokens: 42,
+            ..TokenUsage::default()
+        })
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn omits_usage_when_provider_does_not_report_it() {
+    skip_if_no_network!();
+
+    let sse = concat!(
+        "data: {\"choices\":[{\"delta\":{\"content\":\"hi\"}}]}\n\n",
+        "data: [DONE]\n\n",
+    );
+
+    let events = run_stream(sse).await;
+
+    assert_eq!(completed_usage(&events), None);
+}
+
 #[tokio::test]
 #[traced_test]
 async fn chat_sse_emits_failed_on_parse_error() {
@@ -29,12 +29,16 @@ pub fn assert_regex_match<'s>(pattern: &str, actual: &'s str) -> regex_lite::Cap
 /// temporary directory. Using a per-test directory keeps tests hermetic and
 /// avoids clobbering a developer’s real `~/.codex`.
 pub fn load_default_config_for_test(codex_home: &TempDir) -> Config {
-    Config::load_from_base_config_with_overrides(
+    let mut config = Config::load_from_base_config_with_overrides(
         ConfigToml::default(),
         default_test_overrides(),
         codex_home.path().to_path_buf(),
     )
-    .expect("defaults for test should always succeed")
+    .expect("defaults for test should always succeed");
+    // The inventory lands in the prompt whenever the background probe happens
+    // to finish, which would make request bodies nondeterministic.
+    config.tool_inventory = None;
+    config
 }
 
 #[cfg(target_os = "linux")]
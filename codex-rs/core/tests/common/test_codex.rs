@@ -17,6 +17,7 @@ use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol::SessionConfiguredEvent;
+use codex_core::protocol::SessionSource;
 use codex_protocol::config_types::ReasoningSummary;
 use codex_protocol::user_input::UserInput;
 use serde_json::Value;
@@ -50,6 +51,7 @@ pub enum ShellModelOutput {
 pub struct TestCodexBuilder {
     config_mutators: Vec<Box<ConfigMutator>>,
     auth: CodexAuth,
+    session_source: SessionSource,
 }
 
 impl TestCodexBuilder {
@@ -66,6 +68,12 @@ impl TestCodexBuilder {
         self
     }
 
+    /// Build the session as if started by `source` instead of `codex exec`.
+    pub fn with_session_source(mut self, source: SessionSource) -> Self {
+        self.session_source = source;
+        self
+    }
+
     pub fn with_model(self, model: &str) -> Self {
         let new_model = model.to_string();
         self.with_config(move |config| {
@@ -97,7 +105,10 @@ impl TestCodexBuilder {
         let (config, cwd) = self.prepare_config(server, &home).await?;
 
         let auth = self.auth.clone();
-        let conversation_manager = ConversationManager::with_auth(auth.clone());
+        let conversation_manager = ConversationManager::new(
+            codex_core::AuthManager::from_auth_for_testing(auth.clone()),
+            self.session_source.clone(),
+        );
 
         let new_conversation = match resume_from {
             Some(path) => {
@@ -351,5 +362,6 @@ pub fn test_codex() -> TestCodexBuilder {
     TestCodexBuilder {
         config_mutators: vec![],
         auth: CodexAuth::from_api_key("dummy"),
+        session_source: SessionSource::Exec,
     }
 }
@@ -0,0 +1,235 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::time::Duration;
+
+use anyhow::Result;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExecApprovalExplanationEvent;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_core::sandboxing::explanation::ExplanationSettings;
+use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::protocol::ReviewDecision;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::sse_response;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use core_test_support::wait_for_event_match;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::matchers::body_string_contains;
+use wiremock::matchers::method;
+use wiremock::matchers::path_regex;
+
+const EXPLAIN_MODEL: &str = "gpt-5.1-codex-mini";
+const CALL_ID: &str = "explained-call";
+const USER_PROMPT: &str = "please create the marker file";
+const EXPLANATION: &str = "Creates an empty file named explained.txt in the project. At worst it overwrites the timestamp of an existing file with that name.";
+
+/// Answer explanation requests (the only ones sent to `EXPLAIN_MODEL`) after
+/// `delay`. Mounted before the main-turn mocks so it wins for those requests.
+async fn mount_explanation(server: &MockServer, delay: Duration) {
+    Mock::given(method("POST"))
+        .and(path_regex(".*/responses$"))
+        .and(body_string_contains(EXPLAIN_MODEL))
+        .respond_with(
+            sse_response(sse(vec![
+                ev_response_created("explain-1"),
+                ev_assistant_message("explain-msg", EXPLANATION),
+                ev_completed("explain-1"),
+            ]))
+            .set_delay(delay),
+        )
+        .up_to_n_times(1)
+        .mount(server)
+        .await;
+}
+
+async fn mount_turn(server: &MockServer) -> Result<()> {
+    let args = json!({
+        "command": ["/bin/sh", "-c", "touch explained.txt"],
+        "timeout_ms": 5_000,
+    });
+    mount_sse_sequence(
+        server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(CALL_ID, "shell", &serde_json::to_string(&args)?),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-1", "done"),
+                ev_completed("resp-2"),
+            ]),
+        ],
+    )
+    .await;
+    Ok(())
+}
+
+async fn build(server: &MockServer, timeout: Duration) -> Result<TestCodex> {
+    let mut builder = test_codex()
+        .with_model("gpt-5.1")
+        .with_config(move |config| {
+            config.approval_explanation = Some(ExplanationSettings {
+                model: EXPLAIN_MODEL.to_string(),
+                timeout,
+            });
+        });
+    builder.build(server).await
+}
+
+async fn submit_turn(test: &TestCodex) -> Result<()> {
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: USER_PROMPT.into(),
+            }],
+            final_output_json_schema: None,
+            cwd: test.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::UnlessTrusted,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: test.session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+    Ok(())
+}
+
+/// The approval request is sent first, flagged as awaiting a summary.
+async fn expect_pending_approval(test: &TestCodex) {
+    let event = wait_for_event(&test.codex, |event| {
+        matches!(
+            event,
+            EventMsg::ExecApprovalRequest(_) | EventMsg::ExecApprovalExplanation(_)
+        )
+    })
+    .await;
+    let EventMsg::ExecApprovalRequest(request) = event else {
+        panic!("expected the approval request before its explanation, got {event:?}");
+    };
+    assert_eq!(request.call_id, CALL_ID);
+    assert!(request.explanation_pending);
+}
+
+async fn approve_and_finish(test: &TestCodex) -> Result<()> {
+    test.codex
+        .submit(Op::ExecApproval {
+            id: "0".into(),
+            decision: ReviewDecision::Approved,
+        })
+        .await?;
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn explanation_follows_approval_request_and_stays_out_of_history() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    mount_explanation(&server, Duration::from_millis(300)).await;
+    mount_turn(&server).await?;
+    let test = build(&server, Duration::from_secs(10)).await?;
+
+    submit_turn(&test).await?;
+    expect_pending_approval(&test).await;
+    let explanation = wait_for_event_match(&test.codex, |event| match event {
+        EventMsg::ExecApprovalExplanation(ev) => Some(ev.clone()),
+        _ => None,
+    })
+    .await;
+    assert_eq!(
+        explanation,
+        ExecApprovalExplanationEvent {
+            call_id: CALL_ID.to_string(),
+            explanation: Some(EXPLANATION.to_string()),
+        }
+    );
+    approve_and_finish(&test).await?;
+
+    let requests = server.received_requests().await.expect("recorded requests");
+    let (side, main): (Vec<_>, Vec<_>) = requests
+        .iter()
+        .map(|request| String::from_utf8_lossy(&request.body).into_owned())
+        .partition(|body| body.contains(EXPLAIN_MODEL));
+    assert_eq!(side.len(), 1);
+    assert!(
+        side[0].contains("touch explained.txt") && !side[0].contains(USER_PROMPT),
+        "explanation request must carry only the command: {}",
+        side[0]
+    );
+    assert_eq!(main.len(), 2);
+    for body in &main {
+        assert!(
+            !body.contains(EXPLANATION) && !body.contains("AI-generated"),
+            "main conversation must not see the explanation: {body}"
+        );
+    }
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn explanation_timeout_reports_no_summary() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    mount_explanation(&server, Duration::from_secs(5)).await;
+    mount_turn(&server).await?;
+    let test = build(&server, Duration::from_millis(200)).await?;
+
+    submit_turn(&test).await?;
+    expect_pending_approval(&test).await;
+    let explanation = wait_for_event_match(&test.codex, |event| match event {
+        EventMsg::ExecApprovalExplanation(ev) => Some(ev.clone()),
+        _ => None,
+    })
+    .await;
+    assert_eq!(
+        explanation,
+        ExecApprovalExplanationEvent {
+            call_id: CALL_ID.to_string(),
+            explanation: None,
+        }
+    );
+    approve_and_finish(&test).await?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn approval_does_not_wait_for_explanation() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    mount_explanation(&server, Duration::from_secs(30)).await;
+    mount_turn(&server).await?;
+    let test = build(&server, Duration::from_secs(60)).await?;
+
+    submit_turn(&test).await?;
+    expect_pending_approval(&test).await;
+    tokio::time::timeout(Duration::from_secs(10), approve_and_finish(&test))
+        .await
+        .expect("turn should finish without waiting for the explanation")?;
+    assert!(
+        test.cwd.path().join("explained.txt").exists(),
+        "approved command should have run"
+    );
+    Ok(())
+}
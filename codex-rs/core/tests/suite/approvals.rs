@@ -1447,6 +1447,69 @@ async fn approval_matrix_covers_all_modes() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn always_allowed_pattern_still_asks_before_leaving_the_sandbox() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let approval_policy = AskForApproval::OnRequest;
+    let sandbox_policy = SandboxPolicy::ReadOnly;
+    let sandbox_policy_for_config = sandbox_policy.clone();
+    let test = test_codex()
+        .with_config(move |config| {
+            config.approval_policy = approval_policy;
+            config.sandbox_policy = sandbox_policy_for_config;
+        })
+        .build(&server)
+        .await?;
+    test.codex
+        .submit(Op::AllowCommandPattern {
+            pattern: "echo".to_string(),
+        })
+        .await?;
+
+    let call_id = "always-allowed-escalation";
+    let command = vec!["echo".to_string(), "escalated".to_string()];
+    let _ = mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-1"),
+            shell_event(call_id, &command, 1_000, true)?,
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+    let results_mock = mount_sse_once(
+        &server,
+        sse(vec![
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-2"),
+        ]),
+    )
+    .await;
+
+    submit_turn(&test, call_id, approval_policy, sandbox_policy).await?;
+
+    expect_exec_approval(&test, &command).await;
+    test.codex
+        .submit(Op::ExecApproval {
+            id: "0".into(),
+            decision: ReviewDecision::Denied,
+        })
+        .await?;
+    wait_for_completion(&test).await;
+
+    let output_item = results_mock.single_request().function_call_output(call_id);
+    let result = parse_result(&output_item);
+    assert!(
+        result.stdout.contains("exec command rejected by user"),
+        "escalated command should wait for the user: {}",
+        result.stdout
+    );
+
+    Ok(())
+}
+
 async fn run_scenario(scenario: &ScenarioSpec) -> Result<()> {
     eprintln!("running approval scenario: {}", scenario.name);
     let server = start_mock_server().await;
@@ -10,6 +10,7 @@ use codex_core::auth::REFRESH_TOKEN_URL_OVERRIDE_ENV_VAR;
 use codex_core::auth::RefreshTokenError;
 use codex_core::auth::load_auth_dot_json;
 use codex_core::auth::save_auth;
+use codex_core::error::RefreshTokenFailedError;
 use codex_core::error::RefreshTokenFailedReason;
 use codex_core::token_data::IdTokenInfo;
 use codex_core::token_data::TokenData;
@@ -18,6 +19,7 @@ use pretty_assertions::assert_eq;
 use serde::Serialize;
 use serde_json::json;
 use std::ffi::OsString;
+use std::path::Path;
 use tempfile::TempDir;
 use wiremock::Mock;
 use wiremock::MockServer;
@@ -44,7 +46,7 @@ async fn refresh_token_succeeds_updates_storage() -> Result<()> {
         .mount(&server)
         .await;
 
-    let ctx = RefreshTokenTestContext::new(&server)?;
+    let ctx = RefreshTokenTestContext::new(&server, INITIAL_ACCESS_TOKEN.to_string())?;
     let auth = ctx.auth.clone();
 
     let access = auth
@@ -93,7 +95,7 @@ async fn refresh_token_returns_permanent_error_for_expired_refresh_token() -> Re
         .mount(&server)
         .await;
 
-    let ctx = RefreshTokenTestContext::new(&server)?;
+    let ctx = RefreshTokenTestContext::new(&server, INITIAL_ACCESS_TOKEN.to_string())?;
     let auth = ctx.auth.clone();
 
     let err = auth
@@ -134,7 +136,7 @@ async fn refresh_token_returns_transient_error_on_server_failure() -> Result<()>
         .mount(&server)
         .await;
 
-    let ctx = RefreshTokenTestContext::new(&server)?;
+    let ctx = RefreshTokenTestContext::new(&server, INITIAL_ACCESS_TOKEN.to_string())?;
     let auth = ctx.auth.clone();
 
     let err = auth
@@ -161,6 +163,141 @@ async fn refresh_token_returns_transient_error_on_server_failure() -> Result<()>
     Ok(())
 }
 
+#[serial_test::serial(auth_refresh)]
+#[tokio::test]
+async fn get_token_refreshes_inside_early_refresh_window() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let refreshed = access_token_expiring_in(Duration::hours(1));
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/oauth/token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "access_token": refreshed,
+            "refresh_token": "new-refresh-token"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // Still valid, but inside the default five-minute window.
+    let ctx =
+        RefreshTokenTestContext::new(&server, access_token_expiring_in(Duration::minutes(2)))?;
+
+    assert_eq!(ctx.auth.get_token().await?, refreshed);
+    // The refreshed token is far from expiry, so it is used as is.
+    assert_eq!(ctx.auth.get_token().await?, refreshed);
+
+    server.verify().await;
+    Ok(())
+}
+
+#[serial_test::serial(auth_refresh)]
+#[tokio::test]
+async fn concurrent_callers_share_a_single_refresh() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let refreshed = access_token_expiring_in(Duration::hours(1));
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/oauth/token"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({
+                    "access_token": refreshed,
+                    "refresh_token": "new-refresh-token"
+                }))
+                .set_delay(std::time::Duration::from_millis(200)),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let ctx =
+        RefreshTokenTestContext::new(&server, access_token_expiring_in(Duration::seconds(30)))?;
+    // Separate loads share nothing in memory, like a TUI and an exec
+    // process using the same auth.json; only the auth file lock
+    // serializes them.
+    let tui = ctx.load_codex_auth()?;
+    let exec = ctx.load_codex_auth()?;
+
+    let (tui_token, exec_token) = tokio::join!(tui.get_token(), exec.get_token());
+    assert_eq!(
+        (tui_token?, exec_token?),
+        (refreshed.clone(), refreshed.clone())
+    );
+
+    let stored = ctx.load_auth()?;
+    let tokens = stored.tokens.context("tokens should exist")?;
+    assert_eq!(
+        (tokens.access_token, tokens.refresh_token),
+        (refreshed, "new-refresh-token".to_string())
+    );
+
+    server.verify().await;
+    Ok(())
+}
+
+#[serial_test::serial(auth_refresh)]
+#[tokio::test]
+async fn transient_refresh_failure_keeps_unexpired_token() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/oauth/token"))
+        .respond_with(ResponseTemplate::new(503).set_body_json(json!({
+            "error": "temporary-failure"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let current = access_token_expiring_in(Duration::minutes(2));
+    let ctx = RefreshTokenTestContext::new(&server, current.clone())?;
+
+    assert_eq!(ctx.auth.get_token().await?, current);
+
+    server.verify().await;
+    Ok(())
+}
+
+#[serial_test::serial(auth_refresh)]
+#[tokio::test]
+async fn revoked_refresh_token_asks_for_login_before_expiry() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/oauth/token"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(json!({
+            "error": {
+                "code": "refresh_token_invalidated"
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let ctx =
+        RefreshTokenTestContext::new(&server, access_token_expiring_in(Duration::minutes(2)))?;
+
+    let err = ctx
+        .auth
+        .get_token()
+        .await
+        .err()
+        .context("revoked refresh token should fail")?;
+    let reason = err
+        .get_ref()
+        .and_then(|inner| inner.downcast_ref::<RefreshTokenFailedError>())
+        .map(|failed| failed.reason);
+    assert_eq!(reason, Some(RefreshTokenFailedReason::Revoked));
+
+    server.verify().await;
+    Ok(())
+}
+
 struct RefreshTokenTestContext {
     codex_home: TempDir,
     auth: CodexAuth,
@@ -169,14 +306,14 @@ struct RefreshTokenTestContext {
 }
 
 impl RefreshTokenTestContext {
-    fn new(server: &MockServer) -> Result<Self> {
+    fn new(server: &MockServer, access_token: String) -> Result<Self> {
         let codex_home = TempDir::new()?;
         let initial_last_refresh = Utc::now() - Duration::days(1);
         let mut id_token = IdTokenInfo::default();
         id_token.raw_jwt = minimal_jwt();
         let tokens = TokenData {
             id_token,
-            access_token: INITIAL_ACCESS_TOKEN.to_string(),
+            access_token,
             refresh_token: INITIAL_REFRESH_TOKEN.to_string(),
             account_id: Some("account-id".to_string()),
         };
@@ -194,8 +331,7 @@ impl RefreshTokenTestContext {
         let endpoint = format!("{}/oauth/token", server.uri());
         let env_guard = EnvGuard::set(REFRESH_TOKEN_URL_OVERRIDE_ENV_VAR, endpoint);
 
-        let auth = CodexAuth::from_auth_storage(codex_home.path(), AuthCredentialsStoreMode::File)?
-            .context("auth should load from storage")?;
+        let auth = load_codex_auth(codex_home.path())?;
 
         Ok(Self {
             codex_home,
@@ -205,6 +341,12 @@ impl RefreshTokenTestContext {
         })
     }
 
+    /// Another `CodexAuth` over the same auth file, as a second process
+    /// would load it.
+    fn load_codex_auth(&self) -> Result<CodexAuth> {
+        load_codex_auth(self.codex_home.path())
+    }
+
     fn load_auth(&self) -> Result<AuthDotJson> {
         load_auth_dot_json(self.codex_home.path(), AuthCredentialsStoreMode::File)
             .context("load auth.json")?
@@ -212,6 +354,11 @@ impl RefreshTokenTestContext {
     }
 }
 
+fn load_codex_auth(codex_home: &Path) -> Result<CodexAuth> {
+    CodexAuth::from_auth_storage(codex_home, AuthCredentialsStoreMode::File)?
+        .context("auth should load from storage")
+}
+
 struct EnvGuard {
     key: &'static str,
     original: Option<OsString>,
@@ -241,6 +388,15 @@ impl Drop for EnvGuard {
 }
 
 fn minimal_jwt() -> String {
+    jwt(json!({ "sub": "user-123" }))
+}
+
+/// An access token whose `exp` claim is `expires_in` from now.
+fn access_token_expiring_in(expires_in: Duration) -> String {
+    jwt(json!({ "sub": "user-123", "exp": (Utc::now() + expires_in).timestamp() }))
+}
+
+fn jwt(payload: serde_json::Value) -> String {
     #[derive(Serialize)]
     struct Header {
         alg: &'static str,
@@ -251,7 +407,6 @@ fn minimal_jwt() -> String {
         alg: "none",
         typ: "JWT",
     };
-    let payload = json!({ "sub": "user-123" });
 
     fn b64(data: &[u8]) -> String {
         base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(data)
@@ -659,6 +659,68 @@ async fn includes_configured_effort_in_request() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn token_count_emitted_per_request_with_estimate_fallback() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+    let server = MockServer::start().await;
+
+    // The first request reports usage; the follow-up after the tool call omits it.
+    responses::mount_sse_sequence(
+        &server,
+        vec![
+            responses::sse(vec![
+                responses::ev_function_call("call-1", "unknown_tool", "{}"),
+                responses::ev_completed_with_tokens("resp1", 100),
+            ]),
+            responses::sse(vec![
+                responses::ev_assistant_message("m1", "done"),
+                json!({"type": "response.completed", "response": {"id": "resp2"}}),
+            ]),
+        ],
+    )
+    .await;
+    let TestCodex { codex, .. } = test_codex()
+        .with_model("gpt-5.1-codex")
+        .build(&server)
+        .await?;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await?;
+
+    let mut usage_events = Vec::new();
+    loop {
+        match wait_for_event(&codex, |_| true).await {
+            EventMsg::TokenCount(ev) if ev.info.is_some() => usage_events.push(ev),
+            EventMsg::TaskComplete(_) => break,
+            _ => {}
+        }
+    }
+
+    assert_eq!(usage_events.len(), 2, "events: {usage_events:?}");
+    let reported = &usage_events[0];
+    let reported_info = reported.info.as_ref().expect("usage info");
+    assert_eq!(reported.model.as_deref(), Some("gpt-5.1-codex"));
+    assert!(!reported.estimated);
+    assert_eq!(reported_info.last_token_usage.total_tokens, 100);
+
+    let estimated = &usage_events[1];
+    let estimated_info = estimated.info.as_ref().expect("usage info");
+    assert_eq!(estimated.model.as_deref(), Some("gpt-5.1-codex"));
+    assert!(estimated.estimated);
+    assert!(estimated_info.last_token_usage.input_tokens > 0);
+    assert_eq!(
+        estimated_info.total_token_usage.total_tokens,
+        100 + estimated_info.last_token_usage.total_tokens
+    );
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn includes_no_effort_in_request() -> anyhow::Result<()> {
     skip_if_no_network!(Ok(()));
@@ -936,6 +998,7 @@ async fn azure_responses_request_includes_store_and_reasoning_ids() {
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
         requires_openai_auth: false,
+        num_ctx: None,
     };
 
     let codex_home = TempDir::new().unwrap();
@@ -1082,6 +1145,7 @@ async fn token_count_includes_rate_limits_snapshot() {
     let home = TempDir::new().unwrap();
     let mut config = load_default_config_for_test(&home);
     config.model_provider = provider;
+    let model = config.model.clone();
 
     let conversation_manager = ConversationManager::with_auth(CodexAuth::from_api_key("test"));
     let codex = conversation_manager
@@ -1111,6 +1175,7 @@ async fn token_count_includes_rate_limits_snapshot() {
         rate_limit_json,
         json!({
             "info": null,
+            "estimated": false,
             "rate_limits": {
                 "primary": {
                     "used_percent": 12.5,
@@ -1159,6 +1224,8 @@ async fn token_count_includes_rate_limits_snapshot() {
                 // Default model is gpt-5.1-codex-max in tests → 95% usable context window
                 "model_context_window": 258400
             },
+            "model": model,
+            "estimated": false,
             "rate_limits": {
                 "primary": {
                     "used_percent": 12.5,
@@ -1426,6 +1493,7 @@ async fn azure_overrides_assign_properties_used_for_responses_url() {
         stream_max_retries: None,
         stream_idle_timeout_ms: None,
         requires_openai_auth: false,
+        num_ctx: None,
     };
 
     // Init session
@@ -1504,6 +1572,7 @@ async fn env_var_overrides_loaded_auth() {
         stream_max_retries: None,
         stream_idle_timeout_ms: None,
         requires_openai_auth: false,
+        num_ctx: None,
     };
 
     // Init session
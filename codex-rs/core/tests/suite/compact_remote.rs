@@ -10,6 +10,7 @@ use codex_core::protocol::Op;
 use codex_core::protocol::RolloutItem;
 use codex_core::protocol::RolloutLine;
 use codex_protocol::models::ContentItem;
+use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::user_input::UserInput;
 use core_test_support::responses;
@@ -186,7 +187,12 @@ async fn remote_compact_runs_automatically() -> Result<()> {
     .await;
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 
-    assert_eq!(message, "Compact task completed");
+    // The call and its output are folded away; the first user message is
+    // pinned and the initial context is regenerated.
+    assert_eq!(
+        message,
+        "Compact task completed: compacted 2 items, kept 1 pinned"
+    );
     assert_eq!(compact_mock.requests().len(), 1);
     let follow_up_body = responses_mock.single_request().body_json().to_string();
     assert!(follow_up_body.contains("REMOTE_COMPACTED_SUMMARY"));
@@ -258,6 +264,17 @@ async fn remote_compact_persists_replacement_history_in_rollout() -> Result<()>
     assert_eq!(responses_mock.requests().len(), 1);
     assert_eq!(compact_mock.requests().len(), 1);
 
+    // The first user message is pinned, so it is put back in front of the
+    // server's replacement history.
+    let mut expected_history = vec![ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: "needs compaction".to_string(),
+        }],
+    }];
+    expected_history.extend(compacted_history);
+
     let rollout_text = fs::read_to_string(&rollout_path)?;
     let mut saw_compacted_history = false;
     for line in rollout_text
@@ -270,7 +287,7 @@ async fn remote_compact_persists_replacement_history_in_rollout() -> Result<()>
         };
         if let RolloutItem::Compacted(compacted) = entry.item
             && compacted.message.is_empty()
-            && compacted.replacement_history.as_ref() == Some(&compacted_history)
+            && compacted.replacement_history.as_ref() == Some(&expected_history)
         {
             saw_compacted_history = true;
             break;
@@ -284,3 +301,110 @@ async fn remote_compact_persists_replacement_history_in_rollout() -> Result<()>
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn remote_compact_keeps_tool_calls_paired_with_their_outputs() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    const CALL_ID: &str = "call-split";
+    const TOOL_NAME: &str = "unsupported_tool";
+
+    let harness = TestCodexHarness::with_builder(
+        test_codex()
+            .with_auth(CodexAuth::create_dummy_chatgpt_auth_for_testing())
+            .with_config(|config| {
+                config.features.enable(Feature::RemoteCompaction);
+            }),
+    )
+    .await?;
+    let codex = harness.test().codex.clone();
+
+    let responses_mock = responses::mount_sse_sequence(
+        harness.server(),
+        vec![
+            responses::sse(vec![
+                responses::ev_function_call(CALL_ID, TOOL_NAME, "{}"),
+                responses::ev_completed("resp-1"),
+            ]),
+            responses::sse(vec![
+                responses::ev_assistant_message("m1", "FIRST_REMOTE_REPLY"),
+                responses::ev_completed("resp-2"),
+            ]),
+            responses::sse(vec![
+                responses::ev_assistant_message("m2", "AFTER_COMPACT_REPLY"),
+                responses::ev_completed("resp-3"),
+            ]),
+        ],
+    )
+    .await;
+
+    // The server keeps the tool call but folds its output into the summary.
+    let call = ResponseItem::FunctionCall {
+        id: None,
+        name: TOOL_NAME.to_string(),
+        arguments: "{}".to_string(),
+        call_id: CALL_ID.to_string(),
+    };
+    let compacted_history = vec![
+        call.clone(),
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: "REMOTE_COMPACTED_SUMMARY".to_string(),
+            }],
+        },
+    ];
+    responses::mount_compact_json_once(
+        harness.server(),
+        serde_json::json!({ "output": compacted_history }),
+    )
+    .await;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "run the tool".into(),
+            }],
+        })
+        .await?;
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    codex.submit(Op::Compact).await?;
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "after compact".into(),
+            }],
+        })
+        .await?;
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    // The output recorded by the first turn comes back verbatim, right after
+    // its call, instead of the call being dropped or answered as aborted.
+    let follow_up = responses_mock
+        .requests()
+        .last()
+        .expect("follow-up request missing")
+        .body_json();
+    let input = follow_up["input"]
+        .as_array()
+        .expect("follow-up input is an array");
+    let call_json = serde_json::to_value(&call)?;
+    let call_idx = input
+        .iter()
+        .position(|item| item == &call_json)
+        .expect("tool call kept after compaction");
+    let expected_output = serde_json::to_value(ResponseItem::FunctionCallOutput {
+        call_id: CALL_ID.to_string(),
+        output: FunctionCallOutputPayload {
+            content: format!("unsupported call: {TOOL_NAME}"),
+            ..Default::default()
+        },
+    })?;
+    assert_eq!(input.get(call_idx + 1), Some(&expected_output));
+
+    Ok(())
+}
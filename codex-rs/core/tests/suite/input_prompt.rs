@@ -0,0 +1,284 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::time::Duration;
+
+use anyhow::Context;
+use anyhow::Result;
+use codex_core::features::Feature;
+use codex_core::input_prompt::PromptConfidence;
+use codex_core::input_prompt::PromptDetectionSettings;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExecInputRequestEvent;
+use codex_core::protocol::ExecInputResponse;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_core::protocol::SessionSource;
+use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ResponseMock;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::skip_if_sandbox;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use core_test_support::wait_for_event_match;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use wiremock::MockServer;
+
+/// `codex exec` has nobody to answer a prompt, so a command that stops at
+/// one is killed and the model is told to run it non-interactively.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn headless_session_stops_command_waiting_at_prompt() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let mut builder = test_codex().with_model("gpt-5.1").with_config(|config| {
+        config.input_prompt_detection = Some(PromptDetectionSettings {
+            idle: Duration::from_millis(500),
+            min_confidence: PromptConfidence::Medium,
+        });
+    });
+    let test = builder.build(&server).await?;
+
+    let call_id = "prompting-command";
+    let command = vec![
+        "/bin/sh".to_string(),
+        "-c".to_string(),
+        "printf 'Continue? [Y/n] '; sleep 30".to_string(),
+    ];
+    let args = json!({
+        "command": command,
+        "timeout_ms": 20_000,
+    });
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "shell", &serde_json::to_string(&args)?),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-1", "done"),
+                ev_completed("resp-2"),
+            ]),
+        ],
+    )
+    .await;
+
+    let session_model = test.session_configured.model.clone();
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "run the prompting command".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: test.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: session_model,
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+
+    let request = wait_for_event_match(&test.codex, |event| match event {
+        EventMsg::ExecInputRequest(request) => Some(request.clone()),
+        _ => None,
+    })
+    .await;
+    assert_eq!(
+        request,
+        ExecInputRequestEvent {
+            call_id: call_id.to_string(),
+            command,
+            prompt: "Continue? [Y/n]".to_string(),
+            accepts_input: false,
+            terminated: true,
+        }
+    );
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+
+    let output = mock
+        .function_call_output_text(call_id)
+        .context("function call output present")?;
+    assert!(
+        output.starts_with(
+            "command was stopped while waiting for input (Continue? [Y/n]); run it non-interactively instead"
+        ),
+        "unexpected output: {output}"
+    );
+
+    Ok(())
+}
+
+/// Start an interactive (TUI) session whose model runs `cmd` through
+/// `exec_command`, yielding after `yield_time_ms`.
+async fn run_interactive_exec_command(
+    server: &MockServer,
+    call_id: &str,
+    cmd: &str,
+    yield_time_ms: u64,
+) -> Result<(TestCodex, ResponseMock)> {
+    let mut builder = test_codex()
+        .with_model("gpt-5")
+        .with_session_source(SessionSource::Cli)
+        .with_config(|config| {
+            config.use_experimental_unified_exec_tool = true;
+            config.features.enable(Feature::UnifiedExec);
+            config.input_prompt_detection = Some(PromptDetectionSettings {
+                idle: Duration::from_millis(500),
+                min_confidence: PromptConfidence::Medium,
+            });
+        });
+    let test = builder.build(server).await?;
+
+    let args = json!({
+        "cmd": cmd,
+        "yield_time_ms": yield_time_ms,
+    });
+    let mock = mount_sse_sequence(
+        server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(call_id, "exec_command", &serde_json::to_string(&args)?),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-1", "done"),
+                ev_completed("resp-2"),
+            ]),
+        ],
+    )
+    .await;
+
+    let session_model = test.session_configured.model.clone();
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "run the prompting command".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: test.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: session_model,
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+
+    Ok((test, mock))
+}
+
+async fn wait_for_input_request(test: &TestCodex) -> ExecInputRequestEvent {
+    wait_for_event_match(&test.codex, |event| match event {
+        EventMsg::ExecInputRequest(request) => Some(request.clone()),
+        _ => None,
+    })
+    .await
+}
+
+/// In the TUI the user's answer to a password prompt is written to the
+/// command's PTY, and the command carries on with it.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn interactive_session_writes_answer_to_pty() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+    skip_if_sandbox!(Ok(()));
+
+    let server = start_mock_server().await;
+    let call_id = "password-prompt";
+    let (test, mock) = run_interactive_exec_command(
+        &server,
+        call_id,
+        "printf 'Password: '; read -r secret; echo \"got $secret\"",
+        10_000,
+    )
+    .await?;
+
+    let request = wait_for_input_request(&test).await;
+    assert_eq!(
+        (
+            request.call_id.as_str(),
+            request.prompt.as_str(),
+            request.accepts_input,
+            request.terminated
+        ),
+        (call_id, "Password:", true, false)
+    );
+    test.codex
+        .submit(Op::ExecInput {
+            id: call_id.to_string(),
+            response: ExecInputResponse::Input {
+                text: "hunter2".to_string(),
+            },
+        })
+        .await?;
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+
+    let output = mock
+        .function_call_output_text(call_id)
+        .context("function call output present")?;
+    assert!(
+        output.contains("got hunter2"),
+        "unexpected output: {output}"
+    );
+
+    Ok(())
+}
+
+/// Choosing "Keep waiting" sends nothing back; the call still yields at its
+/// deadline and leaves the command running.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn unanswered_prompt_yields_at_deadline() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+    skip_if_sandbox!(Ok(()));
+
+    let server = start_mock_server().await;
+    let call_id = "unanswered-prompt";
+    let (test, mock) = run_interactive_exec_command(
+        &server,
+        call_id,
+        "printf 'Continue? [Y/n] '; sleep 30",
+        2_000,
+    )
+    .await?;
+
+    let request = wait_for_input_request(&test).await;
+    assert_eq!(request.prompt, "Continue? [Y/n]");
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+
+    let output = mock
+        .function_call_output_text(call_id)
+        .context("function call output present")?;
+    assert!(
+        output.contains("Process running with session ID"),
+        "unexpected output: {output}"
+    );
+    assert!(
+        !output.contains("stopped while waiting for input"),
+        "unexpected output: {output}"
+    );
+
+    Ok(())
+}
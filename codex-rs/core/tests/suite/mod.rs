@@ -18,6 +18,8 @@ mod abort_tasks;
 #[cfg(not(target_os = "windows"))]
 mod apply_patch_cli;
 #[cfg(not(target_os = "windows"))]
+mod approval_explanation;
+#[cfg(not(target_os = "windows"))]
 mod approvals;
 mod auth_refresh;
 mod cli_stream;
@@ -31,14 +33,17 @@ mod exec;
 mod exec_policy;
 mod fork_conversation;
 mod grep_files;
+mod input_prompt;
 mod items;
 mod json_result;
 mod list_dir;
 mod live_cli;
 mod model_overrides;
+mod model_replay;
 mod model_tools;
 mod otel;
 mod prompt_caching;
+mod provider_headers;
 mod quota_exceeded;
 mod read_file;
 mod resume;
@@ -47,12 +52,15 @@ mod rmcp_client;
 mod rollout_list_find;
 mod seatbelt;
 mod shell_serialization;
+mod shutdown;
 mod stream_error_allows_next_turn;
 mod stream_no_completed;
+mod stream_resume;
 mod tool_harness;
 mod tool_parallelism;
 mod tools;
 mod truncation;
+mod turn_limits;
 mod undo;
 mod unified_exec;
 mod user_notification;
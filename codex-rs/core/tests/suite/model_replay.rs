@@ -0,0 +1,84 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use anyhow::Result;
+use codex_core::REPLAY_PROVIDER_ID;
+use codex_core::built_in_model_providers;
+use codex_core::protocol::EventMsg;
+use core_test_support::responses::start_mock_server;
+use core_test_support::test_codex::test_codex;
+use pretty_assertions::assert_eq;
+
+/// A turn in which the model ran a command, recorded as a rollout. The
+/// command would create `replayed.txt`; its recorded output is "recorded".
+const ROLLOUT: &str = r#"{"timestamp":"2025-10-01T12:00:00.000Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"touch a file"}]}}
+{"timestamp":"2025-10-01T12:00:01.000Z","type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{\"command\":[\"/bin/sh\",\"-c\",\"touch replayed.txt\"]}","call_id":"call-1"}}
+{"timestamp":"2025-10-01T12:00:02.000Z","type":"response_item","payload":{"type":"function_call_output","call_id":"call-1","output":"recorded"}}
+{"timestamp":"2025-10-01T12:00:03.000Z","type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"Touched it."}]}}
+"#;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn replays_a_rollout_without_network_or_running_tools() -> Result<()> {
+    let server = start_mock_server().await;
+    let recording = tempfile::tempdir()?;
+    let replay_path = recording.path().join("rollout.jsonl");
+    std::fs::write(&replay_path, ROLLOUT)?;
+
+    let mut builder = test_codex().with_config(move |config| {
+        config.model_provider_id = REPLAY_PROVIDER_ID.to_string();
+        config.model_provider = built_in_model_providers()[REPLAY_PROVIDER_ID].clone();
+        config.replay_path = Some(replay_path);
+        config.replay_speed = 0.0;
+    });
+    let test = builder.build(&server).await?;
+
+    test.submit_turn("touch a file").await?;
+    let mut trace = Vec::new();
+    loop {
+        match test.codex.next_event().await?.msg {
+            EventMsg::AgentMessage(event) => trace.push(format!("agent: {}", event.message)),
+            EventMsg::ExecCommandBegin(_) => trace.push("exec".to_string()),
+            EventMsg::Error(event) => trace.push(format!("error: {}", event.message)),
+            EventMsg::TaskComplete(event) => {
+                trace.push(format!("complete: {:?}", event.last_agent_message));
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    assert_eq!(
+        trace,
+        vec![
+            "agent: Touched it.".to_string(),
+            "complete: Some(\"Touched it.\")".to_string(),
+        ]
+    );
+    assert!(!test.workspace_path("replayed.txt").exists());
+    assert_eq!(
+        server.received_requests().await.unwrap_or_default().len(),
+        0
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn truncated_recording_fails_with_its_line_number() -> Result<()> {
+    let server = start_mock_server().await;
+    let recording = tempfile::tempdir()?;
+    let replay_path = recording.path().join("rollout.jsonl");
+    std::fs::write(&replay_path, &ROLLOUT[..ROLLOUT.len() - 30])?;
+    let expected = format!("{}:4:", replay_path.display());
+
+    let mut builder = test_codex().with_config(move |config| {
+        config.model_provider_id = REPLAY_PROVIDER_ID.to_string();
+        config.model_provider = built_in_model_providers()[REPLAY_PROVIDER_ID].clone();
+        config.replay_path = Some(replay_path);
+    });
+    let err = match builder.build(&server).await {
+        Ok(_) => panic!("a truncated recording must not start a session"),
+        Err(err) => err.to_string(),
+    };
+
+    assert!(err.contains(&expected), "unexpected error: {err}");
+    Ok(())
+}
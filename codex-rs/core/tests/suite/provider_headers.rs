@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+
+use codex_core::WireApi;
+use codex_core::config::Config;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::WarningEvent;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serial_test::serial;
+use tracing_test::traced_test;
+use wiremock::Mock;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::header;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+use wiremock::matchers::query_param;
+
+const SECRET_QUERY_VALUE: &str = "sk-query-secret-123";
+const MISSING_ENV_VAR: &str = "CODEX_TEST_PROVIDER_HEADER_UNSET";
+
+const ENV_HEADER_VAR: &str = "CODEX_TEST_PROVIDER_HEADER_VALUE";
+const ENV_HEADER_VALUE: &str = "from-env";
+
+struct EnvVarGuard {
+    key: &'static str,
+    original: Option<OsString>,
+}
+
+impl EnvVarGuard {
+    fn set(key: &'static str, value: &str) -> Self {
+        let original = std::env::var_os(key);
+        unsafe {
+            std::env::set_var(key, value);
+        }
+        Self { key, original }
+    }
+}
+
+impl Drop for EnvVarGuard {
+    fn drop(&mut self) {
+        unsafe {
+            match &self.original {
+                Some(value) => std::env::set_var(self.key, value),
+                None => std::env::remove_var(self.key),
+            }
+        }
+    }
+}
+
+fn configure_provider(config: &mut Config, wire_api: WireApi) {
+    // Missing env headers are only reported for user-defined providers.
+    config.model_provider_id = "custom".to_string();
+    let provider = &mut config.model_provider;
+    provider.name = "Custom".to_string();
+    provider.wire_api = wire_api;
+    provider.query_params = Some(HashMap::from([
+        ("api-version".to_string(), "2025-04-01-preview".to_string()),
+        ("api_key".to_string(), SECRET_QUERY_VALUE.to_string()),
+    ]));
+    provider.http_headers = Some(HashMap::from([(
+        "X-Static-Header".to_string(),
+        "static-value".to_string(),
+    )]));
+    provider.env_http_headers = Some(HashMap::from([
+        ("X-Env-Header".to_string(), ENV_HEADER_VAR.to_string()),
+        ("X-Missing-Header".to_string(), MISSING_ENV_VAR.to_string()),
+    ]));
+}
+
+async fn submit_and_wait(test: &TestCodex) {
+    test.codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await
+        .unwrap();
+    wait_for_event(&test.codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+}
+
+fn assert_secret_not_logged() {
+    logs_assert(|lines: &[&str]| {
+        if let Some(line) = lines.iter().find(|line| line.contains(SECRET_QUERY_VALUE)) {
+            return Err(format!("secret query value leaked into logs: {line}"));
+        }
+        lines
+            .iter()
+            .find(|line| line.contains("api_key=[REDACTED]"))
+            .map(|_| Ok(()))
+            .unwrap_or_else(|| Err("expected a redacted request URL in logs".to_string()))
+    });
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[traced_test]
+#[serial(provider_header_env)]
+async fn responses_requests_carry_provider_headers_and_params() {
+    skip_if_no_network!();
+    let _env = EnvVarGuard::set(ENV_HEADER_VAR, ENV_HEADER_VALUE);
+
+    let server = start_mock_server().await;
+    let response_mock = mount_sse_once(&server, sse(vec![ev_completed("resp1")])).await;
+
+    let test = test_codex()
+        .with_config(|config| configure_provider(config, WireApi::Responses))
+        .build(&server)
+        .await
+        .unwrap();
+
+    let EventMsg::Warning(WarningEvent { message }) =
+        wait_for_event(&test.codex, |ev| matches!(ev, EventMsg::Warning(_))).await
+    else {
+        unreachable!("wait_for_event returned a non-warning event");
+    };
+    assert_eq!(
+        message,
+        format!(
+            "Model provider `Custom` sets header `X-Missing-Header` from `{MISSING_ENV_VAR}`, but that environment variable is not set; requests will be sent without it."
+        )
+    );
+
+    submit_and_wait(&test).await;
+
+    let request = response_mock.single_request();
+    assert_eq!(request.path(), "/v1/responses");
+    assert_eq!(
+        request.query_param("api-version").as_deref(),
+        Some("2025-04-01-preview")
+    );
+    assert_eq!(
+        request.query_param("api_key").as_deref(),
+        Some(SECRET_QUERY_VALUE)
+    );
+    assert_eq!(
+        request.header("X-Static-Header").as_deref(),
+        Some("static-value")
+    );
+    assert_eq!(
+        request.header("X-Env-Header").as_deref(),
+        Some(ENV_HEADER_VALUE)
+    );
+    assert_eq!(request.header("X-Missing-Header"), None);
+
+    assert_secret_not_logged();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[traced_test]
+#[serial(provider_header_env)]
+async fn chat_requests_carry_provider_headers_and_params() {
+    skip_if_no_network!();
+    let _env = EnvVarGuard::set(ENV_HEADER_VAR, ENV_HEADER_VALUE);
+
+    let server = start_mock_server().await;
+    let body = concat!(
+        "data: {\"choices\":[{\"delta\":{\"content\":\"hi\"}}]}\n\n",
+        "data: {\"choices\":[{\"delta\":{} ,\"finish_reason\":\"stop\"}]}\n\n",
+    );
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .and(query_param("api-version", "2025-04-01-preview"))
+        .and(query_param("api_key", SECRET_QUERY_VALUE))
+        .and(header("X-Static-Header", "static-value"))
+        .and(header("X-Env-Header", ENV_HEADER_VALUE))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_raw(body, "text/event-stream"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let test = test_codex()
+        .with_config(|config| configure_provider(config, WireApi::Chat))
+        .build(&server)
+        .await
+        .unwrap();
+
+    submit_and_wait(&test).await;
+
+    assert_secret_not_logged();
+}
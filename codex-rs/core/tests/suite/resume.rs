@@ -1,12 +1,15 @@
 use anyhow::Result;
 use codex_core::protocol::EventMsg;
+use codex_core::protocol::HistoryFlag;
 use codex_core::protocol::Op;
 use codex_protocol::user_input::UserInput;
 use core_test_support::responses::ev_assistant_message;
 use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_function_call;
 use core_test_support::responses::ev_reasoning_item;
 use core_test_support::responses::ev_response_created;
 use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::mount_sse_sequence;
 use core_test_support::responses::sse;
 use core_test_support::responses::start_mock_server;
 use core_test_support::skip_if_no_network;
@@ -118,3 +121,153 @@ async fn resume_includes_initial_messages_from_reasoning_events() -> Result<()>
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn resume_keeps_irrelevant_turns_out_of_the_prompt() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let response_mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call("call-1", "unknown_tool", "{}"),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-1", "Looked around"),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-2", "Done"),
+                ev_completed("resp-3"),
+            ]),
+        ],
+    )
+    .await;
+
+    let mut builder = test_codex();
+    let initial = builder.build(&server).await?;
+    let codex = Arc::clone(&initial.codex);
+    let home = initial.home.clone();
+    let rollout_path = initial.session_configured.rollout_path.clone();
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "Explore the repo".into(),
+            }],
+        })
+        .await?;
+    wait_for_event(&codex, |event| matches!(event, EventMsg::TaskComplete(_))).await;
+
+    codex
+        .submit(Op::SetHistoryFlag {
+            flag: HistoryFlag::TurnRelevance {
+                nth_user_message: 0,
+                irrelevant: true,
+            },
+        })
+        .await?;
+    wait_for_event(&codex, |event| matches!(event, EventMsg::HistoryFlagSet(_))).await;
+
+    let resumed = builder.resume(&server, home, rollout_path).await?;
+    resumed
+        .codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "Carry on".into(),
+            }],
+        })
+        .await?;
+    wait_for_event(&resumed.codex, |event| {
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+
+    let requests = response_mock.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(
+        requests[1].function_call_output_text("call-1").as_deref(),
+        Some("unsupported call: unknown_tool")
+    );
+    assert_eq!(
+        requests[2].function_call_output_text("call-1").as_deref(),
+        Some("[output omitted: turn marked irrelevant by the user]")
+    );
+    // The user's own text is shortened at most, never dropped.
+    assert!(
+        requests[2]
+            .message_input_texts("user")
+            .contains(&"Explore the repo".to_string())
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn resume_in_new_cwd_sends_environment_update() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let response_mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_assistant_message("msg-1", "First"),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-2", "Second"),
+                ev_completed("resp-2"),
+            ]),
+        ],
+    )
+    .await;
+
+    let mut builder = test_codex();
+    let initial = builder.build(&server).await?;
+    let codex = Arc::clone(&initial.codex);
+    let home = initial.home.clone();
+    let rollout_path = initial.session_configured.rollout_path.clone();
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "First turn".into(),
+            }],
+        })
+        .await?;
+    wait_for_event(&codex, |event| matches!(event, EventMsg::TaskComplete(_))).await;
+
+    // Resuming builds a fresh working directory, so the model's last known cwd
+    // is stale and must be updated on the next turn.
+    let resumed = builder.resume(&server, home, rollout_path).await?;
+    resumed
+        .codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "Second turn".into(),
+            }],
+        })
+        .await?;
+    wait_for_event(&resumed.codex, |event| {
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+
+    let requests = response_mock.requests();
+    assert_eq!(requests.len(), 2);
+    let expected_update = format!(
+        "<environment_context>\n  <cwd>{}</cwd>\n</environment_context>",
+        resumed.cwd.path().display()
+    );
+    assert!(
+        requests[1]
+            .message_input_texts("user")
+            .contains(&expected_update)
+    );
+
+    Ok(())
+}
@@ -108,6 +108,7 @@ async fn review_op_emits_lifecycle_and_review_output() {
             code_location: ReviewCodeLocation {
                 absolute_file_path: PathBuf::from("/tmp/file.rs"),
                 line_range: ReviewLineRange { start: 10, end: 20 },
+                anchor: None,
             },
         }],
         overall_correctness: "good".to_string(),
@@ -98,6 +98,7 @@ async fn stdio_server_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    cache_tool_results: None,
                 },
             );
         })
@@ -236,6 +237,7 @@ async fn stdio_image_responses_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    cache_tool_results: None,
                 },
             );
         })
@@ -432,6 +434,7 @@ async fn stdio_image_completions_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    cache_tool_results: None,
                 },
             );
         })
@@ -572,6 +575,7 @@ async fn stdio_server_propagates_whitelisted_env_vars() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    cache_tool_results: None,
                 },
             );
         })
@@ -721,6 +725,7 @@ async fn streamable_http_tool_call_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    cache_tool_results: None,
                 },
             );
         })
@@ -902,6 +907,7 @@ async fn streamable_http_with_oauth_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    cache_tool_results: None,
                 },
             );
         })
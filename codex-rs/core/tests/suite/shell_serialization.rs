@@ -463,7 +463,8 @@ Output:
 398
 399
 400
-$"#;
+
+\[shell output truncated: \d+ of \d+ tokens were cut from the middle to fit its budget of \d+ tokens\. To see the omitted part, re-run the command with narrower output \(for example with head, tail, sed -n or grep\)\.\]$"#;
     assert_regex_match(truncated_pattern, output);
 
     Ok(())
@@ -909,7 +910,8 @@ async fn shell_command_output_is_not_truncated_over_10k_bytes() -> Result<()> {
     let expected_pattern = r"(?s)^Exit code: 0
 Wall time: [0-9]+(?:\.[0-9]+)? seconds
 Output:
-1*…1 chars truncated…1*$";
+1*…1 chars truncated…1*
+\[shell output truncated: 1 of 10001 bytes were cut from the middle to fit its budget of 10000 bytes\. To see the omitted part, re-run the command with narrower output \(for example with head, tail, sed -n or grep\)\.\]$";
     assert_regex_match(expected_pattern, output);
 
     Ok(())
@@ -0,0 +1,116 @@
+use std::path::Path;
+use std::time::Duration;
+
+#[cfg(not(target_os = "windows"))]
+use codex_core::config::types::McpServerConfig;
+#[cfg(not(target_os = "windows"))]
+use codex_core::config::types::McpServerTransportConfig;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+
+/// The last rollout line, without its timestamp.
+fn last_rollout_item(path: &Path) -> Value {
+    let text = std::fs::read_to_string(path).expect("read rollout");
+    let last = text.lines().last().expect("rollout has lines");
+    let mut line: Value = serde_json::from_str(last).expect("rollout line is JSON");
+    line.as_object_mut()
+        .expect("rollout line is an object")
+        .remove("timestamp");
+    line
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn idle_conversation_shuts_down_within_grace_period() {
+    skip_if_no_network!();
+
+    let server = start_mock_server().await;
+    mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+    let fixture = test_codex().build(&server).await.expect("build codex");
+    let codex = fixture.codex;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await
+        .expect("submit");
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    assert!(codex.shutdown(Duration::from_secs(10)).await);
+    assert_eq!(
+        last_rollout_item(&fixture.session_configured.rollout_path),
+        json!({ "type": "session_end", "payload": { "forced": false } })
+    );
+}
+
+// Uses `sleep` as an MCP server that never answers.
+#[cfg(not(target_os = "windows"))]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn hung_conversation_is_aborted_after_grace_period() {
+    skip_if_no_network!();
+
+    let server = start_mock_server().await;
+    // An MCP server that never answers the handshake keeps `ListMcpTools`,
+    // and every op queued behind it, waiting.
+    let fixture = test_codex()
+        .with_config(|config| {
+            config.mcp_servers.insert(
+                "silent".to_string(),
+                McpServerConfig {
+                    transport: McpServerTransportConfig::Stdio {
+                        command: "sleep".to_string(),
+                        args: vec!["600".to_string()],
+                        env: None,
+                        env_vars: Vec::new(),
+                        cwd: None,
+                    },
+                    enabled: true,
+                    startup_timeout_sec: Some(Duration::from_secs(600)),
+                    tool_timeout_sec: None,
+                    enabled_tools: None,
+                    disabled_tools: None,
+                    cache_tool_results: None,
+                },
+            );
+        })
+        .build(&server)
+        .await
+        .expect("build codex");
+    let codex = fixture.codex;
+    codex.submit(Op::ListMcpTools).await.expect("submit");
+
+    let shutdown = tokio::time::timeout(
+        Duration::from_secs(10),
+        codex.shutdown(Duration::from_millis(500)),
+    )
+    .await
+    .expect("forced shutdown should not wait for the MCP server");
+    assert!(!shutdown);
+    assert_eq!(
+        last_rollout_item(&fixture.session_configured.rollout_path),
+        json!({ "type": "session_end", "payload": { "forced": true } })
+    );
+}
@@ -0,0 +1,84 @@
+//! Verifies that a stream dropped mid-response is reconnected inside the
+//! model client, so the UI sees every delta exactly once.
+
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_message_item_added;
+use core_test_support::responses::ev_output_text_delta;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn dropped_stream_resumes_without_duplicate_deltas() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    // The first response ends after a few deltas, without response.completed.
+    let dropped = sse(vec![
+        ev_response_created("resp-1"),
+        ev_message_item_added("msg-1", ""),
+        ev_output_text_delta("Hel"),
+        ev_output_text_delta("lo, wo"),
+    ]);
+    // The retried response chunks the same text differently.
+    let retried = sse(vec![
+        ev_response_created("resp-2"),
+        ev_message_item_added("msg-2", ""),
+        ev_output_text_delta("Hello"),
+        ev_output_text_delta(", world"),
+        ev_output_text_delta("!"),
+        ev_assistant_message("msg-2", "Hello, world!"),
+        ev_completed("resp-2"),
+    ]);
+    let responses = mount_sse_sequence(&server, vec![dropped, retried]).await;
+
+    let TestCodex { codex, .. } = test_codex()
+        .with_config(|config| {
+            config.model_provider.request_max_retries = Some(0);
+            config.model_provider.stream_max_retries = Some(1);
+        })
+        .build(&server)
+        .await?;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await?;
+
+    let mut deltas = Vec::new();
+    let mut messages = Vec::new();
+    let mut stream_errors = Vec::new();
+    wait_for_event(&codex, |event| {
+        match event {
+            EventMsg::AgentMessageDelta(event) => deltas.push(event.delta.clone()),
+            EventMsg::AgentMessage(event) => messages.push(event.message.clone()),
+            EventMsg::StreamError(event) => stream_errors.push(event.message.clone()),
+            _ => {}
+        }
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+
+    assert_eq!(deltas, vec!["Hel", "lo, wo", "rld", "!"]);
+    assert_eq!(messages, vec!["Hello, world!"]);
+    assert_eq!(stream_errors, Vec::<String>::new());
+
+    let requests = responses.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].body_json(), requests[1].body_json());
+
+    Ok(())
+}
@@ -26,6 +26,7 @@ use core_test_support::responses::start_mock_server;
 use core_test_support::skip_if_no_network;
 use core_test_support::test_codex::test_codex;
 use core_test_support::wait_for_event;
+use core_test_support::wait_for_event_match;
 use escargot::CargoBuild;
 use serde_json::Value;
 use serde_json::json;
@@ -244,7 +245,7 @@ async fn tool_call_output_exceeds_limit_truncated_chars_limit() -> Result<()> {
         "expected truncated shell output to be plain text"
     );
 
-    assert_eq!(output.len(), 9976); // ~10k characters
+    assert_eq!(output.len(), 10196); // ~10k characters plus the truncation manifest
     let truncated_pattern = r#"(?s)^Exit code: 0\nWall time: 0 seconds\nTotal output lines: 100000\nOutput:\n.*?…\d+ chars truncated….*$"#;
 
     assert_regex_match(truncated_pattern, &output);
@@ -334,7 +335,8 @@ Output:
 .*…137224 tokens truncated.*
 99999
 100000
-$"#;
+
+\[shell output truncated: \d+ of \d+ tokens were cut from the middle to fit its budget of \d+ tokens\. To see the omitted part, re-run the command with narrower output \(for example with head, tail, sed -n or grep\)\.\]$"#;
     assert_regex_match(truncated_pattern, &output);
 
     Ok(())
@@ -467,6 +469,7 @@ async fn mcp_tool_call_output_exceeds_limit_truncated_for_model() -> Result<()>
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                cache_tool_results: None,
             },
         );
         config.tool_output_token_limit = Some(500);
@@ -560,6 +563,7 @@ async fn mcp_image_output_preserves_image_and_no_text_summary() -> Result<()> {
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                cache_tool_results: None,
             },
         );
     });
@@ -645,7 +649,7 @@ async fn token_policy_marker_reports_tokens() -> Result<()> {
         .function_call_output_text(call_id)
         .context("shell output present")?;
 
-    let pattern = r#"(?s)^\{"output":"Total output lines: 150\\n\\n1\\n2\\n3\\n4\\n5\\n.*?…\d+ tokens truncated…7\\n138\\n139\\n140\\n141\\n142\\n143\\n144\\n145\\n146\\n147\\n148\\n149\\n150\\n","metadata":\{"exit_code":0,"duration_seconds":0\.0\}\}$"#;
+    let pattern = r#"(?s)^\{"output":"Total output lines: 150\\n\\n1\\n2\\n3\\n4\\n5\\n.*?…\d+ tokens truncated…7\\n138\\n139\\n140\\n141\\n142\\n143\\n144\\n145\\n146\\n147\\n148\\n149\\n150\\n\\n\[shell output truncated: \d+ of \d+ tokens were cut from the middle to fit its budget of 50 tokens\. To see the omitted part, re-run the command with narrower output \(for example with head, tail, sed -n or grep\)\.\]","metadata":\{"exit_code":0,"duration_seconds":0\.0\}\}$"#;
 
     assert_regex_match(pattern, &output);
 
@@ -698,7 +702,7 @@ async fn byte_policy_marker_reports_bytes() -> Result<()> {
         .function_call_output_text(call_id)
         .context("shell output present")?;
 
-    let pattern = r#"(?s)^\{"output":"Total output lines: 150\\n\\n1\\n2\\n3\\n4\\n5.*?…\d+ chars truncated…7\\n138\\n139\\n140\\n141\\n142\\n143\\n144\\n145\\n146\\n147\\n148\\n149\\n150\\n","metadata":\{"exit_code":0,"duration_seconds":0\.0\}\}$"#;
+    let pattern = r#"(?s)^\{"output":"Total output lines: 150\\n\\n1\\n2\\n3\\n4\\n5.*?…\d+ chars truncated…7\\n138\\n139\\n140\\n141\\n142\\n143\\n144\\n145\\n146\\n147\\n148\\n149\\n150\\n\\n\[shell output truncated: \d+ of \d+ bytes were cut from the middle to fit its budget of 200 bytes\. To see the omitted part, re-run the command with narrower output \(for example with head, tail, sed -n or grep\)\.\]","metadata":\{"exit_code":0,"duration_seconds":0\.0\}\}$"#;
 
     assert_regex_match(pattern, &output);
 
@@ -825,6 +829,7 @@ async fn mcp_tool_call_output_not_truncated_with_custom_limit() -> Result<()> {
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                cache_tool_results: None,
             },
         );
     });
@@ -865,3 +870,84 @@ async fn mcp_tool_call_output_not_truncated_with_custom_limit() -> Result<()> {
 
     Ok(())
 }
+
+// A per-tool budget caps what the model sees while the displayed output stays complete.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn per_tool_budget_caps_model_output_but_not_display() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let mut builder = test_codex().with_config(|config| {
+        config.model = "gpt-5.1-codex".to_string(); // token policy
+        config.model_family =
+            find_family_for_model("gpt-5.1-codex").expect("model family for gpt-5.1-codex");
+        config.tool_output_limits.insert("shell".to_string(), 50);
+    });
+    let fixture = builder.build(&server).await?;
+
+    let call_id = "shell-per-tool-budget";
+    let args = json!({
+        "command": ["/bin/sh", "-c", "seq 1 2000"],
+        "timeout_ms": 5_000,
+    });
+
+    mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_function_call(call_id, "shell", &serde_json::to_string(&args)?),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+    let done_mock = mount_sse_once(
+        &server,
+        sse(vec![
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-2"),
+        ]),
+    )
+    .await;
+
+    let session_model = fixture.session_configured.model.clone();
+    fixture
+        .codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "run the shell tool".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: fixture.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: session_model,
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+
+    let displayed = wait_for_event_match(&fixture.codex, |ev| match ev {
+        EventMsg::ExecCommandEnd(end) => Some(end.aggregated_output.clone()),
+        _ => None,
+    })
+    .await;
+    let expected: String = (1..=2000).map(|n| format!("{n}\n")).collect();
+    assert_eq!(displayed, expected);
+
+    wait_for_event(&fixture.codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    let output = done_mock
+        .single_request()
+        .function_call_output_text(call_id)
+        .context("shell output present")?;
+    assert!(
+        output.contains("[shell output truncated: "),
+        "model output should end with a truncation manifest: {output}"
+    );
+    assert!(
+        output.len() < expected.len() / 4,
+        "model output should be capped by the shell budget: {} bytes",
+        output.len()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,350 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use anyhow::Result;
+use codex_core::config::types::TurnLimits;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_core::protocol::TurnLimitKind;
+use codex_core::protocol::TurnLimitReachedEvent;
+use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_message_item_added;
+use core_test_support::responses::ev_output_text_delta;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::sse_response;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event_match;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::matchers::method;
+use wiremock::matchers::path_regex;
+
+const SUMMARY: &str = "Stopped after listing the files; the refactor is still to do.";
+
+fn ev_completed_with_output_tokens(id: &str, output_tokens: i64) -> Value {
+    json!({
+        "type": "response.completed",
+        "response": {
+            "id": id,
+            "usage": {
+                "input_tokens": 0,
+                "input_tokens_details": null,
+                "output_tokens": output_tokens,
+                "output_tokens_details": null,
+                "total_tokens": output_tokens
+            }
+        }
+    })
+}
+
+fn summary_response() -> String {
+    sse(vec![
+        ev_assistant_message("msg-summary", SUMMARY),
+        ev_completed_with_output_tokens("resp-summary", 5),
+    ])
+}
+
+async fn build(server: &MockServer, limits: TurnLimits) -> Result<TestCodex> {
+    let mut builder = test_codex().with_config(move |config| {
+        config.turn_limits = limits;
+    });
+    builder.build(server).await
+}
+
+async fn submit_turn(test: &TestCodex) -> Result<()> {
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "refactor the project".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: test.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: test.session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+    Ok(())
+}
+
+/// Waits for the end of the turn and returns the limit event (if any) and
+/// the final agent message.
+async fn finish_turn(test: &TestCodex) -> (Option<TurnLimitReachedEvent>, Option<String>) {
+    let mut reached = None;
+    loop {
+        let event = wait_for_event_match(&test.codex, |event| match event {
+            EventMsg::TurnLimitReached(_) | EventMsg::TaskComplete(_) => Some(event.clone()),
+            _ => None,
+        })
+        .await;
+        match event {
+            EventMsg::TurnLimitReached(ev) => reached = Some(ev),
+            EventMsg::TaskComplete(ev) => return (reached, ev.last_agent_message),
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn output_token_limit_asks_for_a_summary_and_ends_the_turn() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let args = json!({ "command": ["/bin/sh", "-c", "true"], "timeout_ms": 5_000 });
+    let responses = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call("call-1", "shell", &serde_json::to_string(&args)?),
+                ev_completed_with_output_tokens("resp-1", 120),
+            ]),
+            summary_response(),
+            sse(vec![
+                ev_assistant_message("msg-next", "done"),
+                ev_completed_with_output_tokens("resp-next", 5),
+            ]),
+        ],
+    )
+    .await;
+    let test = build(
+        &server,
+        TurnLimits {
+            max_turn_seconds: None,
+            max_turn_output_tokens: Some(100),
+        },
+    )
+    .await?;
+
+    submit_turn(&test).await?;
+    let (reached, last_message) = finish_turn(&test).await;
+
+    assert_eq!(
+        reached,
+        Some(TurnLimitReachedEvent {
+            limit: TurnLimitKind::OutputTokens,
+            threshold: 100,
+        })
+    );
+    assert_eq!(last_message.as_deref(), Some(SUMMARY));
+
+    // The wrap-up message is sent once and not kept in the history.
+    submit_turn(&test).await?;
+    let (reached, last_message) = finish_turn(&test).await;
+    assert_eq!(reached, None);
+    assert_eq!(last_message.as_deref(), Some("done"));
+
+    let requests = responses.requests();
+    assert_eq!(requests.len(), 3);
+    let wrap_up = requests[1].message_input_texts("developer");
+    assert!(
+        wrap_up
+            .iter()
+            .any(|text| text.contains("limit of 100 output tokens")),
+        "wrap-up request must ask for a summary: {wrap_up:?}"
+    );
+    let next_turn = requests[2].message_input_texts("developer");
+    assert!(
+        !next_turn
+            .iter()
+            .any(|text| text.contains("limit of 100 output tokens")),
+        "wrap-up message must not be persisted: {next_turn:?}"
+    );
+    Ok(())
+}
+
+/// Reads one HTTP request and returns its request line, or `None` once the
+/// client has closed the connection.
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<Option<String>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 8192];
+    loop {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        let text = String::from_utf8_lossy(&buf);
+        let Some(end) = text.find("\r\n\r\n") else {
+            continue;
+        };
+        let content_length = text[..end]
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+            .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+            .unwrap_or(0);
+        if buf.len() >= end + 4 + content_length {
+            return Ok(text.lines().next().map(str::to_owned));
+        }
+    }
+}
+
+/// A provider whose first response streams message deltas forever, until
+/// the client hangs up; `hung_up` is set then. Later responses are
+/// `summary_response()`.
+async fn serve_endless_stream(listener: TcpListener, hung_up: Arc<AtomicBool>) {
+    let first = Arc::new(AtomicBool::new(true));
+    while let Ok((mut stream, _)) = listener.accept().await {
+        let first = Arc::clone(&first);
+        let hung_up = Arc::clone(&hung_up);
+        tokio::spawn(async move {
+            while let Ok(Some(request_line)) = read_request(&mut stream).await {
+                if !request_line.contains("/responses") {
+                    let not_found = "HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\n\r\n";
+                    if stream.write_all(not_found.as_bytes()).await.is_err() {
+                        return;
+                    }
+                    continue;
+                }
+                if !first.swap(false, Ordering::SeqCst) {
+                    let body = summary_response();
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ncontent-length: {}\r\n\r\n{body}",
+                        body.len()
+                    );
+                    if stream.write_all(response.as_bytes()).await.is_err() {
+                        return;
+                    }
+                    continue;
+                }
+
+                let head = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\nconnection: close\r\n\r\n{}",
+                    sse(vec![
+                        ev_response_created("resp-endless"),
+                        ev_message_item_added("msg-endless", ""),
+                    ])
+                );
+                if stream.write_all(head.as_bytes()).await.is_err() {
+                    return;
+                }
+                let delta = sse(vec![ev_output_text_delta(
+                    "and then the agent kept on writing, one more paragraph after another, ",
+                )]);
+                loop {
+                    if stream.write_all(delta.as_bytes()).await.is_err()
+                        || stream.flush().await.is_err()
+                    {
+                        hung_up.store(true, Ordering::SeqCst);
+                        return;
+                    }
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            }
+        });
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn output_token_limit_cuts_off_a_response_that_streams_forever() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let base_url = format!("http://{}/v1", listener.local_addr()?);
+    let hung_up = Arc::new(AtomicBool::new(false));
+    tokio::spawn(serve_endless_stream(listener, Arc::clone(&hung_up)));
+
+    let mut builder = test_codex().with_config(move |config| {
+        config.model_provider.base_url = Some(base_url);
+        config.turn_limits = TurnLimits {
+            max_turn_seconds: None,
+            max_turn_output_tokens: Some(200),
+        };
+    });
+    let test = builder.build(&server).await?;
+
+    submit_turn(&test).await?;
+    let (reached, last_message) = tokio::time::timeout(Duration::from_secs(30), finish_turn(&test))
+        .await
+        .expect("the endless response must be cut off");
+
+    assert_eq!(
+        reached,
+        Some(TurnLimitReachedEvent {
+            limit: TurnLimitKind::OutputTokens,
+            threshold: 200,
+        })
+    );
+    assert_eq!(last_message.as_deref(), Some(SUMMARY));
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !hung_up.load(Ordering::SeqCst) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("the endless stream must be dropped");
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn wall_clock_limit_cuts_off_a_stalled_request() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    // The first request stalls well past the limit and is cancelled.
+    Mock::given(method("POST"))
+        .and(path_regex(".*/responses$"))
+        .respond_with(
+            sse_response(sse(vec![
+                ev_assistant_message("msg-late", "too late"),
+                ev_completed_with_output_tokens("resp-late", 5),
+            ]))
+            .set_delay(Duration::from_secs(30)),
+        )
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    let summary = mount_sse_once(&server, summary_response()).await;
+    let test = build(
+        &server,
+        TurnLimits {
+            max_turn_seconds: Some(1),
+            max_turn_output_tokens: None,
+        },
+    )
+    .await?;
+
+    submit_turn(&test).await?;
+    let (reached, last_message) = finish_turn(&test).await;
+
+    assert_eq!(
+        reached,
+        Some(TurnLimitReachedEvent {
+            limit: TurnLimitKind::WallClock,
+            threshold: 1,
+        })
+    );
+    assert_eq!(last_message.as_deref(), Some(SUMMARY));
+    let wrap_up = summary.single_request().message_input_texts("developer");
+    assert!(
+        wrap_up.iter().any(|text| text.contains("time limit of 1s")),
+        "wrap-up request must ask for a summary: {wrap_up:?}"
+    );
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use crate::event_log::EventLogTarget;
 use clap::Parser;
 use clap::ValueEnum;
 use codex_common::CliConfigOverrides;
@@ -27,6 +28,21 @@ pub struct Cli {
     #[arg(long = "local-provider")]
     pub oss_provider: Option<String>,
 
+    /// Block all network access except requests to the model provider
+    /// (no update checks, web search, model downloads, cloud tasks, or sandbox network).
+    #[arg(long = "offline", default_value_t = false)]
+    pub offline: bool,
+
+    /// Persist nothing under CODEX_HOME: no rollout, prompt history, logs, or
+    /// caches. The session cannot be resumed.
+    #[arg(long = "ephemeral", default_value_t = false)]
+    pub ephemeral: bool,
+
+    /// Playback speed when `model_provider = "replay"`: recorded pauses are
+    /// divided by FACTOR, and 0 replays without pauses.
+    #[arg(long = "replay-speed", value_name = "FACTOR")]
+    pub replay_speed: Option<f64>,
+
     /// Select the sandbox policy to use when executing model-generated shell
     /// commands.
     #[arg(long = "sandbox", short = 's', value_enum)]
@@ -66,6 +82,39 @@ pub struct Cli {
     #[arg(long = "output-schema", value_name = "FILE")]
     pub output_schema: Option<PathBuf>,
 
+    /// Keep running after the first turn and re-run the prompt in the same
+    /// session whenever a file matching GLOB (relative to the working
+    /// directory) changes. May be repeated.
+    #[arg(long = "watch", value_name = "GLOB")]
+    pub watch: Vec<String>,
+
+    /// Interrupt the run after SECONDS and exit with status 124. 0 means no
+    /// limit.
+    #[arg(long = "timeout", value_name = "SECONDS", default_value_t = 0)]
+    pub timeout: u64,
+
+    /// Continue a recorded session instead of starting a new one: a session
+    /// id, or `last` for the most recent session. Same as the `resume`
+    /// subcommand.
+    #[arg(long = "resume", value_name = "SESSION_ID|last")]
+    pub resume: Option<String>,
+
+    /// Write every protocol event as JSON Lines, with a sequence number and
+    /// timestamp, to PATH or to an inherited file descriptor (`fd:N`). Works
+    /// with or without --json and leaves stdout untouched.
+    #[arg(long = "event-log", value_name = "PATH|fd:N")]
+    pub event_log: Option<EventLogTarget>,
+
+    /// Rotate the --event-log file to `<PATH>.1` before it grows past this
+    /// many bytes. 0 means no limit.
+    #[arg(
+        long = "event-log-max-bytes",
+        value_name = "BYTES",
+        default_value_t = 0,
+        requires = "event_log"
+    )]
+    pub event_log_max_bytes: u64,
+
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 
@@ -77,6 +126,16 @@ pub struct Cli {
     #[arg(long = "json", alias = "experimental-json", default_value_t = false)]
     pub json: bool,
 
+    /// Print the full text of every patch the agent applies. By default only
+    /// a per-file summary is shown for larger patches.
+    #[arg(long = "show-patches", default_value_t = false)]
+    pub show_patches: bool,
+
+    /// Patches touching fewer than this many lines are printed in full even
+    /// without --show-patches.
+    #[arg(long = "patch-preview-lines", value_name = "N", default_value_t = 20)]
+    pub patch_preview_lines: usize,
+
     /// Specifies file where the last message from the agent should be written.
     #[arg(long = "output-last-message", short = 'o', value_name = "FILE")]
     pub last_message_file: Option<PathBuf>,
@@ -93,7 +152,7 @@ pub enum Command {
     Resume(ResumeArgs),
 }
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Default)]
 pub struct ResumeArgs {
     /// Conversation/session id (UUID). When provided, resumes this session.
     /// If omitted, use --last to pick the most recent recorded session.
@@ -109,6 +168,24 @@ pub struct ResumeArgs {
     pub prompt: Option<String>,
 }
 
+impl ResumeArgs {
+    /// Interprets a `--resume` value: `last` picks the most recent session,
+    /// anything else is a session id.
+    pub fn from_flag(target: String) -> Self {
+        if target == "last" {
+            Self {
+                last: true,
+                ..Self::default()
+            }
+        } else {
+            Self {
+                session_id: Some(target),
+                ..Self::default()
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
 #[value(rename_all = "kebab-case")]
 pub enum Color {
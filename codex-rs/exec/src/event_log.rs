@@ -0,0 +1,476 @@
+//! `--event-log` support: every protocol `Event` as newline-delimited JSON,
+//! written to a file or an inherited file descriptor independently of what
+//! goes to stdout and stderr.
+//!
+//! Records are handed to a writer thread through a bounded queue so a slow
+//! consumer never stalls the agent loop. When the queue is full, events are
+//! dropped and counted, and the count is written as an `events_dropped`
+//! record as soon as there is room again.
+
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::SyncSender;
+use std::sync::mpsc::TryRecvError;
+use std::sync::mpsc::TrySendError;
+use std::thread::JoinHandle;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use codex_core::protocol::Event;
+use serde::Serialize;
+use tracing::warn;
+
+/// Records that may be queued for the writer before new events are dropped.
+const QUEUE_CAPACITY: usize = 1024;
+
+/// Where `--event-log` writes: a file path, or `fd:N` for a descriptor the
+/// parent process left open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventLogTarget {
+    File(PathBuf),
+    Fd(i32),
+}
+
+impl FromStr for EventLogTarget {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.strip_prefix("fd:") {
+            Some(fd) => match fd.parse::<i32>() {
+                Ok(fd) if fd > 2 => Ok(Self::Fd(fd)),
+                Ok(_) => Err("use a file descriptor above 2; stdio is not available".to_string()),
+                Err(_) => Err(format!("invalid file descriptor: {fd}")),
+            },
+            None if value.is_empty() => Err("path must not be empty".to_string()),
+            None => Ok(Self::File(PathBuf::from(value))),
+        }
+    }
+}
+
+impl fmt::Display for EventLogTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Fd(fd) => write!(f, "fd:{fd}"),
+        }
+    }
+}
+
+/// One line of the event log.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Record {
+    /// `seq` counts every event seen, so dropped events leave a gap.
+    Event {
+        seq: u64,
+        ts_ms: u64,
+        event: Box<Event>,
+    },
+    EventsDropped {
+        ts_ms: u64,
+        count: u64,
+    },
+}
+
+pub(crate) struct EventLog {
+    tx: Option<SyncSender<Record>>,
+    writer: Option<JoinHandle<()>>,
+    next_seq: u64,
+    dropped: u64,
+}
+
+impl EventLog {
+    /// Opens `target` and starts the writer thread. With `max_bytes > 0`, a
+    /// file target is rotated to `<path>.1` before it would grow past that
+    /// size.
+    pub(crate) fn open(target: &EventLogTarget, max_bytes: u64) -> io::Result<Self> {
+        let sink = match target {
+            EventLogTarget::File(path) => Sink::file(path, max_bytes)?,
+            EventLogTarget::Fd(fd) => Sink::fd(*fd)?,
+        };
+        Ok(Self::spawn(sink, QUEUE_CAPACITY))
+    }
+
+    fn spawn(sink: Sink, capacity: usize) -> Self {
+        let (tx, rx) = mpsc::sync_channel(capacity);
+        let writer = std::thread::spawn(move || write_records(rx, sink));
+        Self {
+            tx: Some(tx),
+            writer: Some(writer),
+            next_seq: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Queues `event` without blocking; it is dropped and counted when the
+    /// writer is behind.
+    pub(crate) fn record(&mut self, event: &Event) {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        if self.dropped > 0 {
+            let summary = Record::EventsDropped {
+                ts_ms: now_ms(),
+                count: self.dropped,
+            };
+            if !self.try_send(summary) {
+                self.dropped += 1;
+                return;
+            }
+            self.dropped = 0;
+        }
+        let record = Record::Event {
+            seq,
+            ts_ms: now_ms(),
+            event: Box::new(event.clone()),
+        };
+        if !self.try_send(record) {
+            self.dropped += 1;
+        }
+    }
+
+    fn try_send(&mut self, record: Record) -> bool {
+        let Some(tx) = &self.tx else {
+            return false;
+        };
+        match tx.try_send(record) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => false,
+            Err(TrySendError::Disconnected(_)) => {
+                // The writer hit an I/O error and already reported it.
+                self.tx = None;
+                false
+            }
+        }
+    }
+
+    /// Writes out everything still queued, including a final
+    /// `events_dropped` record if needed, and waits for the writer.
+    pub(crate) fn finish(mut self) {
+        self.close();
+    }
+
+    fn close(&mut self) {
+        if let Some(tx) = self.tx.take()
+            && self.dropped > 0
+        {
+            let _ = tx.send(Record::EventsDropped {
+                ts_ms: now_ms(),
+                count: self.dropped,
+            });
+        }
+        if let Some(writer) = self.writer.take()
+            && writer.join().is_err()
+        {
+            warn!("event log writer panicked");
+        }
+    }
+}
+
+impl Drop for EventLog {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+fn write_records(rx: Receiver<Record>, mut sink: Sink) {
+    loop {
+        let record = match rx.try_recv() {
+            Ok(record) => record,
+            Err(TryRecvError::Empty) => {
+                // Nothing else is queued: make what we have visible to the
+                // consumer before waiting.
+                if let Err(err) = sink.flush() {
+                    warn!("failed to write event log: {err}");
+                    return;
+                }
+                match rx.recv() {
+                    Ok(record) => record,
+                    Err(_) => break,
+                }
+            }
+            Err(TryRecvError::Disconnected) => break,
+        };
+        if let Err(err) = sink.write_record(&record) {
+            warn!("failed to write event log: {err}");
+            return;
+        }
+    }
+    if let Err(err) = sink.flush() {
+        warn!("failed to write event log: {err}");
+    }
+}
+
+struct Sink {
+    out: BufWriter<Box<dyn Write + Send>>,
+    rotation: Option<Rotation>,
+}
+
+struct Rotation {
+    path: PathBuf,
+    max_bytes: u64,
+    written: u64,
+}
+
+impl Sink {
+    fn new(out: Box<dyn Write + Send>) -> Self {
+        Self {
+            out: BufWriter::new(out),
+            rotation: None,
+        }
+    }
+
+    fn file(path: &Path, max_bytes: u64) -> io::Result<Self> {
+        let mut sink = Self::new(Box::new(File::create(path)?));
+        if max_bytes > 0 {
+            sink.rotation = Some(Rotation {
+                path: path.to_path_buf(),
+                max_bytes,
+                written: 0,
+            });
+        }
+        Ok(sink)
+    }
+
+    /// Takes ownership of an inherited descriptor. Descriptors this process
+    /// opens itself (the runtime's, for one) are close-on-exec, so one
+    /// without that flag can only have come from the parent.
+    #[cfg(unix)]
+    fn fd(fd: i32) -> io::Result<Self> {
+        use std::os::fd::FromRawFd;
+
+        if fd <= 2 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "use a file descriptor above 2; stdio is not available",
+            ));
+        }
+        // SAFETY: F_GETFD only reads the descriptor flags.
+        let flags = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+        if flags == -1 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "fd:{fd} is not an open file descriptor: {}",
+                    io::Error::last_os_error()
+                ),
+            ));
+        }
+        if flags & libc::FD_CLOEXEC != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("fd:{fd} was not inherited from the parent process"),
+            ));
+        }
+        // Keep it out of the commands Codex runs. SAFETY: only sets the flag.
+        unsafe { libc::fcntl(fd, libc::F_SETFD, flags | libc::FD_CLOEXEC) };
+        // SAFETY: the descriptor is open and was inherited, so nothing else
+        // in this process owns it.
+        let file = unsafe { File::from_raw_fd(fd) };
+        Ok(Self::new(Box::new(file)))
+    }
+
+    #[cfg(not(unix))]
+    fn fd(_fd: i32) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "fd: targets are only supported on Unix",
+        ))
+    }
+
+    fn write_record(&mut self, record: &Record) -> io::Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        let len = line.len() as u64;
+        if let Some(rotation) = self.rotation.as_mut() {
+            if rotation.written > 0 && rotation.written + len > rotation.max_bytes {
+                self.out.flush()?;
+                std::fs::rename(&rotation.path, rotated_path(&rotation.path))?;
+                self.out = BufWriter::new(Box::new(File::create(&rotation.path)?));
+                rotation.written = 0;
+            }
+            rotation.written += len;
+        }
+        self.out.write_all(&line)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+fn rotated_path(path: &Path) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    PathBuf::from(rotated)
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::AgentMessageEvent;
+    use codex_core::protocol::EventMsg;
+    use pretty_assertions::assert_eq;
+    use serde_json::Value;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    fn agent_message(text: &str) -> Event {
+        Event {
+            id: "1".to_string(),
+            msg: EventMsg::AgentMessage(AgentMessageEvent {
+                message: text.to_string(),
+            }),
+        }
+    }
+
+    /// Collects writes, but blocks the first one until released so the
+    /// queue can be filled deterministically.
+    struct GatedWriter {
+        written: Arc<Mutex<Vec<u8>>>,
+        entered: Option<mpsc::Sender<()>>,
+        release: Option<Receiver<()>>,
+    }
+
+    impl Write for GatedWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if let Some(entered) = self.entered.take() {
+                let _ = entered.send(());
+            }
+            if let Some(release) = self.release.take() {
+                let _ = release.recv();
+            }
+            self.written
+                .lock()
+                .map_err(|_| io::Error::other("poisoned"))?
+                .extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// `(type, seq or count)` for each line.
+    fn summarize(bytes: &[u8]) -> Vec<(String, u64)> {
+        String::from_utf8_lossy(bytes)
+            .lines()
+            .map(|line| {
+                let record: Value = serde_json::from_str(line).expect("valid JSON line");
+                let kind = record["type"].as_str().expect("type").to_string();
+                let n = record
+                    .get("seq")
+                    .or_else(|| record.get("count"))
+                    .and_then(Value::as_u64)
+                    .expect("seq or count");
+                (kind, n)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn overflow_is_counted_and_reported() {
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let (entered_tx, entered_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel();
+        let sink = Sink::new(Box::new(GatedWriter {
+            written: written.clone(),
+            entered: Some(entered_tx),
+            release: Some(release_rx),
+        }));
+        let mut log = EventLog::spawn(sink, 2);
+
+        // The writer takes event 0 and blocks writing it out.
+        log.record(&agent_message("0"));
+        entered_rx.recv().expect("writer started");
+        // Events 1 and 2 fill the queue; 3 and 4 are dropped.
+        for i in 1..=4 {
+            log.record(&agent_message(&i.to_string()));
+        }
+        release_tx.send(()).expect("release writer");
+        log.finish();
+
+        let bytes = written.lock().expect("lock").clone();
+        assert_eq!(
+            summarize(&bytes),
+            vec![
+                ("event".to_string(), 0),
+                ("event".to_string(), 1),
+                ("event".to_string(), 2),
+                ("events_dropped".to_string(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn rotates_before_exceeding_max_bytes() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("events.jsonl");
+        let mut log = EventLog::open(&EventLogTarget::File(path.clone()), 1).expect("open");
+        for i in 0..3 {
+            log.record(&agent_message(&i.to_string()));
+        }
+        log.finish();
+
+        let current = std::fs::read(&path).expect("current log");
+        let previous = std::fs::read(rotated_path(&path)).expect("rotated log");
+        assert_eq!(summarize(&current), vec![("event".to_string(), 2)]);
+        assert_eq!(summarize(&previous), vec![("event".to_string(), 1)]);
+    }
+
+    #[test]
+    fn parses_targets() {
+        assert_eq!("fd:3".parse::<EventLogTarget>(), Ok(EventLogTarget::Fd(3)));
+        assert_eq!(
+            "events.jsonl".parse::<EventLogTarget>(),
+            Ok(EventLogTarget::File(PathBuf::from("events.jsonl")))
+        );
+        assert!("fd:1".parse::<EventLogTarget>().is_err());
+        assert!("fd:x".parse::<EventLogTarget>().is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn fd_target_must_be_inherited() {
+        use std::os::fd::AsRawFd;
+        use std::os::fd::IntoRawFd;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("events.jsonl");
+        // Files opened here are close-on-exec, like the runtime's own.
+        let owned = File::create(&path).expect("create");
+        let err = EventLog::open(&EventLogTarget::Fd(owned.as_raw_fd()), 0)
+            .err()
+            .expect("close-on-exec fd is rejected");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        let err = EventLog::open(&EventLogTarget::Fd(1), 0)
+            .err()
+            .expect("stdout is rejected");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        // What a parent would pass: an open descriptor without the flag.
+        let fd = owned.into_raw_fd();
+        // SAFETY: clears the flag on a descriptor this test owns.
+        unsafe { libc::fcntl(fd, libc::F_SETFD, 0) };
+        let mut log = EventLog::open(&EventLogTarget::Fd(fd), 0).expect("inherited fd");
+        log.record(&agent_message("hi"));
+        log.finish();
+        let bytes = std::fs::read(&path).expect("read log");
+        assert_eq!(summarize(&bytes), vec![("event".to_string(), 0)]);
+    }
+}
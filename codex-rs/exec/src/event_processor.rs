@@ -22,6 +22,16 @@ pub(crate) trait EventProcessor {
     /// Handle a single event emitted by the agent.
     fn process_event(&mut self, event: Event) -> CodexStatus;
 
+    /// Record the final agent message parsed against `--output-schema`; it is
+    /// reported with the turn completion that follows.
+    fn set_final_output(&mut self, _final_output: serde_json::Value) {}
+
+    /// Report that changes to watched files (`--watch`) start another turn.
+    fn watch_triggered(&mut self, _paths: &[String]) {}
+
+    /// Report that `--timeout` expired and the run is being interrupted.
+    fn timed_out(&mut self, _seconds: u64) {}
+
     fn print_final_output(&mut self) {}
 }
 
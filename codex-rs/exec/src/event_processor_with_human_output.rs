@@ -1,6 +1,9 @@
+use codex_common::diff_summary::FileChangeSummary;
+use codex_common::diff_summary::summarize_file_changes;
 use codex_common::elapsed::format_duration;
 use codex_common::elapsed::format_elapsed;
 use codex_core::config::Config;
+use codex_core::git_worktree::base_divergence_note;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::BackgroundEventEvent;
@@ -10,24 +13,31 @@ use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
+use codex_core::protocol::ExecInputRequestEvent;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::McpInvocation;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::SandboxDenial;
+use codex_core::protocol::SandboxDeniedEvent;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TurnAbortReason;
 use codex_core::protocol::TurnDiffEvent;
+use codex_core::protocol::TurnLimitKind;
+use codex_core::protocol::TurnLimitReachedEvent;
 use codex_core::protocol::WarningEvent;
 use codex_core::protocol::WebSearchEndEvent;
+use codex_core::resume_briefing::resume_briefing_summary;
 use codex_protocol::num_format::format_with_separators;
 use owo_colors::OwoColorize;
 use owo_colors::Style;
 use shlex::try_join;
 use std::collections::HashMap;
+use std::path::Path;
 use std::path::PathBuf;
 use std::time::Instant;
 
@@ -41,8 +51,21 @@ use codex_protocol::plan_tool::UpdatePlanArgs;
 /// This should be configurable. When used in CI, users may not want to impose
 /// a limit so they can see the full transcript.
 const MAX_OUTPUT_LINES_FOR_EXEC_TOOL_CALL: usize = 20;
+
+/// How much of each `apply_patch` call to print.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PatchDisplay {
+    /// Print the full patch after the per-file summary.
+    pub show_patches: bool,
+    /// Patches touching fewer lines than this are printed in full anyway.
+    pub preview_lines: usize,
+}
+
 pub(crate) struct EventProcessorWithHumanOutput {
     call_id_to_patch: HashMap<String, PatchApplyBegin>,
+    patch_display: PatchDisplay,
+    /// Patch paths are shown relative to this directory.
+    cwd: PathBuf,
 
     // To ensure that --color=never is respected, ANSI escapes _must_ be added
     // using .style() with one of these fields. If you need a new style, add a
@@ -63,6 +86,8 @@ pub(crate) struct EventProcessorWithHumanOutput {
     last_message_path: Option<PathBuf>,
     last_total_token_usage: Option<codex_core::protocol::TokenUsageInfo>,
     final_message: Option<String>,
+    /// `--ephemeral` runs leave nothing to resume; say so at exit.
+    ephemeral: bool,
 }
 
 impl EventProcessorWithHumanOutput {
@@ -70,12 +95,16 @@ impl EventProcessorWithHumanOutput {
         with_ansi: bool,
         config: &Config,
         last_message_path: Option<PathBuf>,
+        patch_display: PatchDisplay,
     ) -> Self {
         let call_id_to_patch = HashMap::new();
+        let cwd = config.cwd.clone();
 
         if with_ansi {
             Self {
                 call_id_to_patch,
+                patch_display,
+                cwd,
                 bold: Style::new().bold(),
                 italic: Style::new().italic(),
                 dimmed: Style::new().dimmed(),
@@ -89,10 +118,13 @@ impl EventProcessorWithHumanOutput {
                 last_message_path,
                 last_total_token_usage: None,
                 final_message: None,
+                ephemeral: config.persistence.is_ephemeral(),
             }
         } else {
             Self {
                 call_id_to_patch,
+                patch_display,
+                cwd,
                 bold: Style::new(),
                 italic: Style::new(),
                 dimmed: Style::new(),
@@ -106,11 +138,158 @@ impl EventProcessorWithHumanOutput {
                 last_message_path,
                 last_total_token_usage: None,
                 final_message: None,
+                ephemeral: config.persistence.is_ephemeral(),
             }
         }
     }
 }
 
+impl EventProcessorWithHumanOutput {
+    /// One line per file with its operation and line counts, then a total.
+    /// The patch itself follows when requested or when it is small.
+    fn render_patch_begin(&self, changes: &HashMap<PathBuf, FileChange>) -> Vec<String> {
+        let summaries = summarize_file_changes(changes);
+        let mut lines = Vec::with_capacity(summaries.len() + 1);
+        for summary in &summaries {
+            let target = match &summary.move_path {
+                Some(dest) => format!(
+                    "{} -> {}",
+                    self.display_path(&summary.path),
+                    self.display_path(dest)
+                ),
+                None => self.display_path(&summary.path),
+            };
+            lines.push(format!(
+                "  {} {target} ({})",
+                format!("{:<6}", summary.kind.as_str()).style(self.magenta),
+                self.format_line_counts(summary.added, summary.removed),
+            ));
+        }
+
+        let added: usize = summaries.iter().map(|summary| summary.added).sum();
+        let removed: usize = summaries.iter().map(|summary| summary.removed).sum();
+        let noun = if summaries.len() == 1 {
+            "file"
+        } else {
+            "files"
+        };
+        lines.push(format!(
+            "{} {noun} changed ({})",
+            summaries.len(),
+            self.format_line_counts(added, removed),
+        ));
+
+        if self.patch_display.show_patches || added + removed < self.patch_display.preview_lines {
+            for summary in &summaries {
+                if let Some(change) = changes.get(&summary.path) {
+                    self.render_full_change(summary, change, &mut lines);
+                }
+            }
+        }
+        lines
+    }
+
+    /// Pretty-print a file's patch with colored diff markers so it's easy to
+    /// scan in the terminal output.
+    fn render_full_change(
+        &self,
+        summary: &FileChangeSummary,
+        change: &FileChange,
+        lines: &mut Vec<String>,
+    ) {
+        let header = match &summary.move_path {
+            Some(dest) => format!(
+                "{} {} -> {}",
+                format_file_change(change),
+                self.display_path(&summary.path),
+                self.display_path(dest)
+            ),
+            None => format!(
+                "{} {}",
+                format_file_change(change),
+                self.display_path(&summary.path)
+            ),
+        };
+        lines.push(header.style(self.magenta).to_string());
+        match change {
+            FileChange::Add { content } => {
+                lines.extend(
+                    content
+                        .lines()
+                        .map(|line| line.style(self.green).to_string()),
+                );
+            }
+            FileChange::Delete { content } => {
+                lines.extend(content.lines().map(|line| line.style(self.red).to_string()));
+            }
+            FileChange::Update { unified_diff, .. } => {
+                // Colorize diff lines. We keep file header lines (--- / +++)
+                // without extra coloring so they are still readable.
+                for diff_line in unified_diff.lines() {
+                    if diff_line.starts_with('+') && !diff_line.starts_with("+++") {
+                        lines.push(diff_line.style(self.green).to_string());
+                    } else if diff_line.starts_with('-') && !diff_line.starts_with("---") {
+                        lines.push(diff_line.style(self.red).to_string());
+                    } else {
+                        lines.push(diff_line.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Attribute each stderr line to the file it mentions so failures read
+    /// per file; anything unattributed is printed as-is afterwards.
+    fn render_patch_failure(
+        &self,
+        changes: &HashMap<PathBuf, FileChange>,
+        stderr: &str,
+    ) -> Vec<String> {
+        let summaries = summarize_file_changes(changes);
+        let mut errors: Vec<Vec<&str>> = vec![Vec::new(); summaries.len()];
+        let mut unattributed = Vec::new();
+        for line in stderr.lines().filter(|line| !line.trim().is_empty()) {
+            let mentioned = summaries.iter().position(|summary| {
+                line.contains(summary.path.to_string_lossy().as_ref())
+                    || line.contains(&self.display_path(&summary.path))
+            });
+            match mentioned {
+                Some(idx) => errors[idx].push(line),
+                None => unattributed.push(line),
+            }
+        }
+
+        let mut lines = Vec::new();
+        for (summary, file_errors) in summaries.iter().zip(errors) {
+            let path = self.display_path(&summary.path);
+            for error in file_errors {
+                lines.push(format!("  {}: {error}", path.style(self.red)));
+            }
+        }
+        lines.extend(
+            unattributed
+                .into_iter()
+                .map(|line| line.style(self.dimmed).to_string()),
+        );
+        lines
+    }
+
+    fn format_line_counts(&self, added: usize, removed: usize) -> String {
+        format!(
+            "{} {}",
+            format!("+{added}").style(self.green),
+            format!("-{removed}").style(self.red)
+        )
+    }
+
+    fn display_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.cwd)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
 struct PatchApplyBegin {
     start_time: Instant,
     auto_approved: bool,
@@ -158,6 +337,20 @@ impl EventProcessor for EventProcessorWithHumanOutput {
         ts_msg!(self, "{}\n{}", "user".style(self.cyan), prompt);
     }
 
+    fn watch_triggered(&mut self, paths: &[String]) {
+        ts_msg!(
+            self,
+            "{} {} changed; running the prompt again",
+            "watch:".style(self.cyan),
+            paths.join(", ")
+        );
+    }
+
+    fn timed_out(&mut self, seconds: u64) {
+        let message = format!("timed out after {seconds}s; interrupting the task");
+        ts_msg!(self, "{}", message.style(self.red));
+    }
+
     fn process_event(&mut self, event: Event) -> CodexStatus {
         let Event { id: _, msg } = event;
         match msg {
@@ -172,6 +365,47 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     "warning:".style(self.yellow).style(self.bold)
                 );
             }
+            EventMsg::SandboxDenied(SandboxDeniedEvent { denial, .. }) => match denial {
+                SandboxDenial::Network(destination) => {
+                    ts_msg!(
+                        self,
+                        "{} blocked a connection to {destination}",
+                        "sandbox:".style(self.red).style(self.bold)
+                    );
+                }
+            },
+            EventMsg::ExecInputRequest(ExecInputRequestEvent {
+                prompt, terminated, ..
+            }) => {
+                // Only `terminated` requests reach a headless run; nothing
+                // else can answer them.
+                if terminated {
+                    ts_msg!(
+                        self,
+                        "{} {prompt}",
+                        "command requested input:".style(self.red).style(self.bold)
+                    );
+                }
+            }
+            EventMsg::BaseDivergence(divergence) => {
+                ts_msg!(
+                    self,
+                    "{} {}",
+                    "worktree:".style(self.yellow).style(self.bold),
+                    base_divergence_note(&divergence)
+                );
+                for subject in divergence.tip_subjects {
+                    ts_msg!(self, "  {}", subject.style(self.dimmed));
+                }
+            }
+            EventMsg::ResumeBriefing(briefing) => {
+                ts_msg!(
+                    self,
+                    "{} since this session was last active, {}",
+                    "resumed:".style(self.yellow).style(self.bold),
+                    resume_briefing_summary(&briefing)
+                );
+            }
             EventMsg::DeprecationNotice(DeprecationNoticeEvent { summary, details }) => {
                 ts_msg!(
                     self,
@@ -316,9 +550,14 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     result,
                     invocation,
                     duration,
+                    cached,
                 } = tool_call_end_event;
 
-                let duration = format!(" in {}", format_duration(duration));
+                let duration = if cached {
+                    " (cached)".to_string()
+                } else {
+                    format!(" in {}", format_duration(duration))
+                };
 
                 let status_str = if is_success { "success" } else { "failed" };
                 let title_style = if is_success { self.green } else { self.red };
@@ -364,71 +603,15 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     "file update".style(self.magenta).style(self.italic),
                 );
 
-                // Pretty-print the patch summary with colored diff markers so
-                // it's easy to scan in the terminal output.
-                for (path, change) in changes.iter() {
-                    match change {
-                        FileChange::Add { content } => {
-                            let header = format!(
-                                "{} {}",
-                                format_file_change(change),
-                                path.to_string_lossy()
-                            );
-                            eprintln!("{}", header.style(self.magenta));
-                            for line in content.lines() {
-                                eprintln!("{}", line.style(self.green));
-                            }
-                        }
-                        FileChange::Delete { content } => {
-                            let header = format!(
-                                "{} {}",
-                                format_file_change(change),
-                                path.to_string_lossy()
-                            );
-                            eprintln!("{}", header.style(self.magenta));
-                            for line in content.lines() {
-                                eprintln!("{}", line.style(self.red));
-                            }
-                        }
-                        FileChange::Update {
-                            unified_diff,
-                            move_path,
-                        } => {
-                            let header = if let Some(dest) = move_path {
-                                format!(
-                                    "{} {} -> {}",
-                                    format_file_change(change),
-                                    path.to_string_lossy(),
-                                    dest.to_string_lossy()
-                                )
-                            } else {
-                                format!("{} {}", format_file_change(change), path.to_string_lossy())
-                            };
-                            eprintln!("{}", header.style(self.magenta));
-
-                            // Colorize diff lines. We keep file header lines
-                            // (--- / +++) without extra coloring so they are
-                            // still readable.
-                            for diff_line in unified_diff.lines() {
-                                if diff_line.starts_with('+') && !diff_line.starts_with("+++") {
-                                    eprintln!("{}", diff_line.style(self.green));
-                                } else if diff_line.starts_with('-')
-                                    && !diff_line.starts_with("---")
-                                {
-                                    eprintln!("{}", diff_line.style(self.red));
-                                } else {
-                                    eprintln!("{diff_line}");
-                                }
-                            }
-                        }
-                    }
+                for line in self.render_patch_begin(&changes) {
+                    eprintln!("{line}");
                 }
             }
             EventMsg::PatchApplyEnd(PatchApplyEndEvent {
                 call_id,
-                stdout,
                 stderr,
                 success,
+                changes,
                 ..
             }) => {
                 let patch_begin = self.call_id_to_patch.remove(&call_id);
@@ -447,19 +630,23 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     (String::new(), format!("apply_patch('{call_id}')"))
                 };
 
-                let (exit_code, output, title_style) = if success {
-                    (0, stdout, self.green)
+                let (exit_code, title_style) = if success {
+                    (0, self.green)
                 } else {
-                    (1, stderr, self.red)
+                    (1, self.red)
                 };
 
+                // On success apply_patch only lists the files it touched,
+                // which the begin summary already showed.
                 let title = format!("{label} exited {exit_code}{duration}:");
                 ts_msg!(self, "{}", title.style(title_style));
-                for line in output.lines() {
-                    eprintln!("{}", line.style(self.dimmed));
+                if !success {
+                    for line in self.render_patch_failure(&changes, &stderr) {
+                        eprintln!("{line}");
+                    }
                 }
             }
-            EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => {
+            EventMsg::TurnDiff(TurnDiffEvent { unified_diff, .. }) => {
                 ts_msg!(
                     self,
                     "{}",
@@ -546,14 +733,27 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     ts_msg!(self, "task aborted: review ended");
                 }
             },
+            EventMsg::TurnLimitReached(TurnLimitReachedEvent { limit, threshold }) => {
+                let reason = match limit {
+                    TurnLimitKind::WallClock => format!("{threshold}s time limit"),
+                    TurnLimitKind::OutputTokens => format!("{threshold} output token limit"),
+                };
+                ts_msg!(
+                    self,
+                    "{} turn stopped at its {reason}",
+                    "limit:".style(self.yellow).style(self.bold)
+                );
+            }
             EventMsg::ShutdownComplete => return CodexStatus::Shutdown,
             EventMsg::WebSearchBegin(_)
             | EventMsg::ExecApprovalRequest(_)
+            | EventMsg::ExecApprovalExplanation(_)
             | EventMsg::ApplyPatchApprovalRequest(_)
             | EventMsg::ExecCommandOutputDelta(_)
             | EventMsg::GetHistoryEntryResponse(_)
             | EventMsg::McpListToolsResponse(_)
             | EventMsg::ListCustomPromptsResponse(_)
+            | EventMsg::ListContextScopesResponse(_)
             | EventMsg::RawResponseItem(_)
             | EventMsg::UserMessage(_)
             | EventMsg::EnteredReviewMode(_)
@@ -567,6 +767,7 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::ReasoningContentDelta(_)
             | EventMsg::ReasoningRawContentDelta(_)
             | EventMsg::UndoCompleted(_)
+            | EventMsg::HistoryFlagSet(_)
             | EventMsg::UndoStarted(_) => {}
         }
         CodexStatus::Running
@@ -580,6 +781,12 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 format_with_separators(usage_info.total_token_usage.blended_total())
             );
         }
+        if self.ephemeral {
+            eprintln!(
+                "{}",
+                "ephemeral session: nothing was saved, so it cannot be resumed".style(self.dimmed)
+            );
+        }
 
         // If the user has not piped the final message to a file, they will see
         // it twice: once written to stderr as part of the normal event
@@ -630,3 +837,171 @@ fn format_mcp_invocation(invocation: &McpInvocation) -> String {
         format!("{fq_tool_name}({args_str})")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::config::ConfigOverrides;
+    use codex_core::config::ConfigToml;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn processor(patch_display: PatchDisplay) -> EventProcessorWithHumanOutput {
+        let codex_home = TempDir::new().unwrap();
+        let config = Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .unwrap();
+        let mut processor =
+            EventProcessorWithHumanOutput::create_with_ansi(false, &config, None, patch_display);
+        processor.cwd = PathBuf::from("/repo");
+        processor
+    }
+
+    fn multi_file_patch() -> HashMap<PathBuf, FileChange> {
+        HashMap::from([
+            (
+                PathBuf::from("/repo/src/lib.rs"),
+                FileChange::Update {
+                    unified_diff: "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,2 +1,3 @@\n fn main() {}\n-old\n+new\n+more\n"
+                        .to_string(),
+                    move_path: None,
+                },
+            ),
+            (
+                PathBuf::from("/repo/notes.md"),
+                FileChange::Add {
+                    content: "# Notes\nfirst\n".to_string(),
+                },
+            ),
+            (
+                PathBuf::from("/repo/old.txt"),
+                FileChange::Update {
+                    unified_diff: "--- a/old.txt\n+++ b/new.txt\n@@ -1 +1 @@\n-before\n+after\n"
+                        .to_string(),
+                    move_path: Some(PathBuf::from("/repo/new.txt")),
+                },
+            ),
+            (
+                PathBuf::from("/repo/gone.txt"),
+                FileChange::Delete {
+                    content: "bye\n".to_string(),
+                },
+            ),
+        ])
+    }
+
+    fn begin(call_id: &str) -> Event {
+        Event {
+            id: "1".to_string(),
+            msg: EventMsg::PatchApplyBegin(PatchApplyBeginEvent {
+                call_id: call_id.to_string(),
+                turn_id: "turn".to_string(),
+                auto_approved: true,
+                changes: multi_file_patch(),
+            }),
+        }
+    }
+
+    fn end(call_id: &str, success: bool, stderr: &str) -> Event {
+        Event {
+            id: "1".to_string(),
+            msg: EventMsg::PatchApplyEnd(PatchApplyEndEvent {
+                call_id: call_id.to_string(),
+                turn_id: "turn".to_string(),
+                stdout: String::new(),
+                stderr: stderr.to_string(),
+                success,
+                changes: multi_file_patch(),
+            }),
+        }
+    }
+
+    #[test]
+    fn patch_begin_prints_per_file_summary_and_total() {
+        let mut processor = processor(PatchDisplay {
+            show_patches: false,
+            preview_lines: 5,
+        });
+
+        assert_eq!(
+            processor.render_patch_begin(&multi_file_patch()),
+            vec![
+                "  delete gone.txt (+0 -1)".to_string(),
+                "  add    notes.md (+2 -0)".to_string(),
+                "  rename old.txt -> new.txt (+1 -1)".to_string(),
+                "  modify src/lib.rs (+2 -1)".to_string(),
+                "4 files changed (+5 -3)".to_string(),
+            ]
+        );
+
+        processor.process_event(begin("call-1"));
+        assert!(processor.call_id_to_patch.contains_key("call-1"));
+        processor.process_event(end("call-1", true, ""));
+        assert!(processor.call_id_to_patch.is_empty());
+    }
+
+    #[test]
+    fn full_patch_is_printed_with_flag_or_below_threshold() {
+        let expected_dump = vec![
+            "D gone.txt",
+            "bye",
+            "A notes.md",
+            "# Notes",
+            "first",
+            "R old.txt -> new.txt",
+            "--- a/old.txt",
+            "+++ b/new.txt",
+            "@@ -1 +1 @@",
+            "-before",
+            "+after",
+            "M src/lib.rs",
+            "--- a/src/lib.rs",
+            "+++ b/src/lib.rs",
+            "@@ -1,2 +1,3 @@",
+            " fn main() {}",
+            "-old",
+            "+new",
+            "+more",
+        ];
+
+        let flagged = processor(PatchDisplay {
+            show_patches: true,
+            preview_lines: 0,
+        })
+        .render_patch_begin(&multi_file_patch());
+        assert_eq!(flagged[5..].to_vec(), expected_dump);
+
+        let small = processor(PatchDisplay {
+            show_patches: false,
+            preview_lines: 9,
+        })
+        .render_patch_begin(&multi_file_patch());
+        assert_eq!(small, flagged);
+    }
+
+    #[test]
+    fn patch_failure_groups_errors_by_file() {
+        let mut processor = processor(PatchDisplay {
+            show_patches: false,
+            preview_lines: 20,
+        });
+        let stderr = "Failed to find expected lines in /repo/src/lib.rs:\nold\n\
+                      Failed to delete file /repo/gone.txt: permission denied\n";
+
+        assert_eq!(
+            processor.render_patch_failure(&multi_file_patch(), stderr),
+            vec![
+                "  gone.txt: Failed to delete file /repo/gone.txt: permission denied".to_string(),
+                "  src/lib.rs: Failed to find expected lines in /repo/src/lib.rs:".to_string(),
+                "old".to_string(),
+            ]
+        );
+
+        processor.process_event(begin("call-2"));
+        processor.process_event(end("call-2", false, stderr));
+        assert!(processor.call_id_to_patch.is_empty());
+    }
+}
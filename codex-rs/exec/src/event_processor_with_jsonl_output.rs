@@ -26,12 +26,17 @@ use crate::exec_events::ThreadEvent;
 use crate::exec_events::ThreadItem;
 use crate::exec_events::ThreadItemDetails;
 use crate::exec_events::ThreadStartedEvent;
+use crate::exec_events::TimeoutEvent;
 use crate::exec_events::TodoItem;
 use crate::exec_events::TodoListItem;
 use crate::exec_events::TurnCompletedEvent;
 use crate::exec_events::TurnFailedEvent;
+use crate::exec_events::TurnLimit;
+use crate::exec_events::TurnLimitReachedEvent;
 use crate::exec_events::TurnStartedEvent;
 use crate::exec_events::Usage;
+use crate::exec_events::UsageUpdatedEvent;
+use crate::exec_events::WatchTriggeredEvent;
 use crate::exec_events::WebSearchItem;
 use codex_core::config::Config;
 use codex_core::protocol::AgentMessageEvent;
@@ -45,9 +50,13 @@ use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::SandboxDenial;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TaskStartedEvent;
+use codex_core::protocol::TokenCountEvent;
+use codex_core::protocol::TokenUsage;
+use codex_core::protocol::TurnLimitKind;
 use codex_core::protocol::WebSearchEndEvent;
 use codex_protocol::plan_tool::StepStatus;
 use codex_protocol::plan_tool::UpdatePlanArgs;
@@ -63,9 +72,12 @@ pub struct EventProcessorWithJsonOutput {
     running_patch_applies: HashMap<String, PatchApplyBeginEvent>,
     // Tracks the todo list for the current turn (at most one per turn).
     running_todo_list: Option<RunningTodoList>,
-    last_total_token_usage: Option<codex_core::protocol::TokenUsage>,
+    last_total_token_usage: Option<TokenUsage>,
     running_mcp_tool_calls: HashMap<String, RunningMcpToolCall>,
     last_critical_error: Option<ThreadErrorEvent>,
+    // Final agent message parsed against `--output-schema`, reported with the
+    // next turn completion.
+    final_output: Option<JsonValue>,
 }
 
 #[derive(Debug, Clone)]
@@ -88,6 +100,14 @@ struct RunningMcpToolCall {
     arguments: JsonValue,
 }
 
+fn usage_from(usage: &TokenUsage) -> Usage {
+    Usage {
+        input_tokens: usage.input_tokens,
+        cached_input_tokens: usage.cached_input_tokens,
+        output_tokens: usage.output_tokens,
+    }
+}
+
 impl EventProcessorWithJsonOutput {
     pub fn new(last_message_path: Option<PathBuf>) -> Self {
         Self {
@@ -99,6 +119,7 @@ impl EventProcessorWithJsonOutput {
             last_total_token_usage: None,
             running_mcp_tool_calls: HashMap::new(),
             last_critical_error: None,
+            final_output: None,
         }
     }
 
@@ -115,12 +136,7 @@ impl EventProcessorWithJsonOutput {
             EventMsg::PatchApplyEnd(ev) => self.handle_patch_apply_end(ev),
             EventMsg::WebSearchBegin(_) => Vec::new(),
             EventMsg::WebSearchEnd(ev) => self.handle_web_search_end(ev),
-            EventMsg::TokenCount(ev) => {
-                if let Some(info) = &ev.info {
-                    self.last_total_token_usage = Some(info.total_token_usage.clone());
-                }
-                Vec::new()
-            }
+            EventMsg::TokenCount(ev) => self.handle_token_count(ev),
             EventMsg::TaskStarted(ev) => self.handle_task_started(ev),
             EventMsg::TaskComplete(_) => self.handle_task_complete(),
             EventMsg::Error(ev) => {
@@ -139,10 +155,39 @@ impl EventProcessorWithJsonOutput {
                 };
                 vec![ThreadEvent::ItemCompleted(ItemCompletedEvent { item })]
             }
+            EventMsg::SandboxDenied(ev) => {
+                let SandboxDenial::Network(destination) = &ev.denial;
+                let item = ThreadItem {
+                    id: self.get_next_item_id(),
+                    details: ThreadItemDetails::Error(ErrorItem {
+                        message: format!("sandbox blocked a connection to {destination}"),
+                    }),
+                };
+                vec![ThreadEvent::ItemCompleted(ItemCompletedEvent { item })]
+            }
+            EventMsg::ExecInputRequest(ev) if ev.terminated => {
+                let item = ThreadItem {
+                    id: self.get_next_item_id(),
+                    details: ThreadItemDetails::Error(ErrorItem {
+                        message: format!("command requested input: {}", ev.prompt),
+                    }),
+                };
+                vec![ThreadEvent::ItemCompleted(ItemCompletedEvent { item })]
+            }
             EventMsg::StreamError(ev) => vec![ThreadEvent::Error(ThreadErrorEvent {
                 message: ev.message.clone(),
             })],
             EventMsg::PlanUpdate(ev) => self.handle_plan_update(ev),
+            EventMsg::TurnLimitReached(ev) => {
+                let limit = match ev.limit {
+                    TurnLimitKind::WallClock => TurnLimit::WallClock,
+                    TurnLimitKind::OutputTokens => TurnLimit::OutputTokens,
+                };
+                vec![ThreadEvent::TurnLimitReached(TurnLimitReachedEvent {
+                    limit,
+                    threshold: ev.threshold,
+                })]
+            }
             _ => Vec::new(),
         }
     }
@@ -432,16 +477,29 @@ impl EventProcessorWithJsonOutput {
         vec![ThreadEvent::TurnStarted(TurnStartedEvent {})]
     }
 
-    fn handle_task_complete(&mut self) -> Vec<ThreadEvent> {
-        let usage = if let Some(u) = &self.last_total_token_usage {
-            Usage {
-                input_tokens: u.input_tokens,
-                cached_input_tokens: u.cached_input_tokens,
-                output_tokens: u.output_tokens,
-            }
-        } else {
-            Usage::default()
+    fn handle_token_count(&mut self, ev: &TokenCountEvent) -> Vec<ThreadEvent> {
+        let Some(info) = &ev.info else {
+            return Vec::new();
         };
+        // Rate limit updates re-send the current totals; only report new usage.
+        if self.last_total_token_usage.as_ref() == Some(&info.total_token_usage) {
+            return Vec::new();
+        }
+        self.last_total_token_usage = Some(info.total_token_usage.clone());
+        vec![ThreadEvent::UsageUpdated(UsageUpdatedEvent {
+            last: usage_from(&info.last_token_usage),
+            total: usage_from(&info.total_token_usage),
+            model: ev.model.clone(),
+            estimated: ev.estimated,
+        })]
+    }
+
+    fn handle_task_complete(&mut self) -> Vec<ThreadEvent> {
+        let usage = self
+            .last_total_token_usage
+            .as_ref()
+            .map(usage_from)
+            .unwrap_or_default();
 
         let mut items = Vec::new();
 
@@ -458,13 +516,24 @@ impl EventProcessorWithJsonOutput {
         if let Some(error) = self.last_critical_error.take() {
             items.push(ThreadEvent::TurnFailed(TurnFailedEvent { error }));
         } else {
-            items.push(ThreadEvent::TurnCompleted(TurnCompletedEvent { usage }));
+            items.push(ThreadEvent::TurnCompleted(TurnCompletedEvent {
+                usage,
+                final_output: self.final_output.take(),
+            }));
         }
 
         items
     }
 }
 
+#[allow(clippy::print_stdout)]
+fn print_thread_event(event: &ThreadEvent) {
+    match serde_json::to_string(event) {
+        Ok(line) => println!("{line}"),
+        Err(e) => error!("Failed to serialize event: {e:?}"),
+    }
+}
+
 impl EventProcessor for EventProcessorWithJsonOutput {
     fn print_config_summary(&mut self, _: &Config, _: &str, ev: &SessionConfiguredEvent) {
         self.process_event(Event {
@@ -473,18 +542,24 @@ impl EventProcessor for EventProcessorWithJsonOutput {
         });
     }
 
-    #[allow(clippy::print_stdout)]
+    fn set_final_output(&mut self, final_output: JsonValue) {
+        self.final_output = Some(final_output);
+    }
+
+    fn watch_triggered(&mut self, paths: &[String]) {
+        print_thread_event(&ThreadEvent::WatchTriggered(WatchTriggeredEvent {
+            paths: paths.to_vec(),
+        }));
+    }
+
+    fn timed_out(&mut self, seconds: u64) {
+        print_thread_event(&ThreadEvent::Timeout(TimeoutEvent { seconds }));
+    }
+
     fn process_event(&mut self, event: Event) -> CodexStatus {
         let aggregated = self.collect_thread_events(&event);
         for conv_event in aggregated {
-            match serde_json::to_string(&conv_event) {
-                Ok(line) => {
-                    println!("{line}");
-                }
-                Err(e) => {
-                    error!("Failed to serialize event: {e:?}");
-                }
-            }
+            print_thread_event(&conv_event);
         }
 
         let Event { msg, .. } = event;
@@ -21,6 +21,14 @@ pub enum ThreadEvent {
     /// Indicates that a turn failed with an error.
     #[serde(rename = "turn.failed")]
     TurnFailed(TurnFailedEvent),
+    /// Emitted when a `[turn_limits]` cap ends the turn early. The turn
+    /// still completes, usually with a summary of where the agent stopped.
+    #[serde(rename = "turn.limit_reached")]
+    TurnLimitReached(TurnLimitReachedEvent),
+    /// Emitted after every model request with that request's token usage and
+    /// the running total for the thread.
+    #[serde(rename = "usage.updated")]
+    UsageUpdated(UsageUpdatedEvent),
     /// Emitted when a new item is added to the thread. Typically the item will be in an "in progress" state.
     #[serde(rename = "item.started")]
     ItemStarted(ItemStartedEvent),
@@ -33,6 +41,13 @@ pub enum ThreadEvent {
     /// Represents an unrecoverable error emitted directly by the event stream.
     #[serde(rename = "error")]
     Error(ThreadErrorEvent),
+    /// Emitted in `--watch` mode when changes to watched files start a new turn.
+    #[serde(rename = "watch.triggered")]
+    WatchTriggered(WatchTriggeredEvent),
+    /// Emitted when `--timeout` expires; the running turn is interrupted and
+    /// exec exits with status 124.
+    #[serde(rename = "timeout")]
+    Timeout(TimeoutEvent),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -48,6 +63,23 @@ pub struct TurnStartedEvent {}
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub struct TurnCompletedEvent {
     pub usage: Usage,
+    /// The final agent message parsed as JSON, set when `--output-schema` is
+    /// used and the message matches the schema.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub final_output: Option<JsonValue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct WatchTriggeredEvent {
+    /// Changed paths, relative to the working directory.
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct TimeoutEvent {
+    /// The limit passed to `--timeout`.
+    pub seconds: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -55,6 +87,36 @@ pub struct TurnFailedEvent {
     pub error: ThreadErrorEvent,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct TurnLimitReachedEvent {
+    pub limit: TurnLimit,
+    /// The configured cap: seconds for `wall_clock`, tokens for
+    /// `output_tokens`.
+    pub threshold: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum TurnLimit {
+    WallClock,
+    OutputTokens,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct UsageUpdatedEvent {
+    /// Usage of the model request that just completed.
+    pub last: Usage,
+    /// Cumulative usage for the thread so far.
+    pub total: Usage,
+    /// The model that served the request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub model: Option<String>,
+    /// Whether `last` was estimated locally because the provider did not
+    /// report usage.
+    pub estimated: bool,
+}
+
 /// Describes the usage of tokens during a turn.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, Default)]
 pub struct Usage {
@@ -5,10 +5,13 @@
 #![deny(clippy::print_stdout)]
 
 mod cli;
+mod event_log;
 mod event_processor;
 mod event_processor_with_human_output;
 pub mod event_processor_with_jsonl_output;
 pub mod exec_events;
+mod output_schema;
+mod watch;
 
 pub use cli::Cli;
 use codex_common::oss::ensure_oss_provider_ready;
@@ -30,16 +33,23 @@ use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
 use codex_core::protocol::SessionSource;
+use codex_core::protocol::TaskCompleteEvent;
+use codex_core::protocol::TurnDiffEvent;
 use codex_protocol::config_types::SandboxMode;
 use codex_protocol::user_input::UserInput;
+pub use event_log::EventLogTarget;
 use event_processor_with_human_output::EventProcessorWithHumanOutput;
+use event_processor_with_human_output::PatchDisplay;
 use event_processor_with_jsonl_output::EventProcessorWithJsonOutput;
 use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
 use serde_json::Value;
 use std::io::IsTerminal;
 use std::io::Read;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 use supports_color::Stream;
+use tokio::time::Instant;
 use tracing::debug;
 use tracing::error;
 use tracing::info;
@@ -47,11 +57,24 @@ use tracing_subscriber::EnvFilter;
 use tracing_subscriber::prelude::*;
 
 use crate::cli::Command as ExecCommand;
+use crate::cli::ResumeArgs;
+use crate::event_log::EventLog;
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
 use codex_core::default_client::set_default_originator;
 use codex_core::find_conversation_path_by_id_str;
 
+/// How many times a final message that does not match `--output-schema` is
+/// sent back to the model before giving up.
+const OUTPUT_SCHEMA_RETRIES: usize = 1;
+
+/// Exit status when `--timeout` expires, matching GNU `timeout`.
+const TIMEOUT_EXIT_CODE: i32 = 124;
+
+/// How long to wait for the interrupted turn to wind down, and then for the
+/// session to shut down, after `--timeout` expires.
+const TIMEOUT_DRAIN_GRACE: Duration = Duration::from_secs(5);
+
 pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()> {
     if let Err(err) = set_default_originator("codex_exec".to_string()) {
         tracing::warn!(?err, "Failed to set codex exec originator override {err:?}");
@@ -63,6 +86,9 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         model: model_cli_arg,
         oss,
         oss_provider,
+        offline,
+        ephemeral,
+        replay_speed,
         config_profile,
         full_auto,
         dangerously_bypass_approvals_and_sandbox,
@@ -72,12 +98,28 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         color,
         last_message_file,
         json: json_mode,
+        show_patches,
+        patch_preview_lines,
         sandbox_mode: sandbox_mode_cli_arg,
         prompt,
         output_schema: output_schema_path,
+        watch: watch_patterns,
+        timeout: timeout_secs,
+        resume,
+        event_log: event_log_target,
+        event_log_max_bytes,
         config_overrides,
     } = cli;
 
+    let command = match (command, resume) {
+        (Some(ExecCommand::Resume(_)), Some(_)) => {
+            eprintln!("--resume cannot be combined with the `resume` subcommand.");
+            std::process::exit(2);
+        }
+        (None, Some(target)) => Some(ExecCommand::Resume(ResumeArgs::from_flag(target))),
+        (command, None) => command,
+    };
+
     // Determine the prompt source (parent or subcommand) and read from stdin if needed.
     let prompt_arg = match &command {
         // Allow prompt before the subcommand by falling back to the parent-level prompt
@@ -231,9 +273,12 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         tools_web_search_request: None,
         experimental_sandbox_command_assessment: None,
         additional_writable_roots: add_dir,
+        offline: offline.then_some(true),
+        ephemeral: ephemeral.then_some(true),
+        replay_speed,
     };
 
-    let config = Config::load_with_cli_overrides(cli_kv_overrides, overrides).await?;
+    let mut config = Config::load_with_cli_overrides(cli_kv_overrides, overrides).await?;
 
     if let Err(err) = enforce_login_restrictions(&config).await {
         eprintln!("{err}");
@@ -270,6 +315,10 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
             stdout_with_ansi,
             &config,
             last_message_file.clone(),
+            PatchDisplay {
+                show_patches,
+                preview_lines: patch_preview_lines,
+            },
         )),
     };
 
@@ -285,11 +334,22 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
                 ));
             }
         };
-        ensure_oss_provider_ready(provider_id, &config)
+        ensure_oss_provider_ready(provider_id, &mut config)
             .await
             .map_err(|e| anyhow::anyhow!("OSS setup failed: {e}"))?;
     }
 
+    let mut event_log = match event_log_target {
+        Some(target) => match EventLog::open(&target, event_log_max_bytes) {
+            Ok(event_log) => Some(event_log),
+            Err(err) => {
+                eprintln!("Failed to open event log {target}: {err}");
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
     let default_cwd = config.cwd.to_path_buf();
     let default_approval_policy = config.approval_policy;
     let default_sandbox_policy = config.sandbox_policy.clone();
@@ -302,11 +362,14 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         std::process::exit(1);
     }
 
-    let auth_manager = AuthManager::shared(
-        config.codex_home.clone(),
-        true,
-        config.cli_auth_credentials_store_mode,
-    );
+    let mut watcher = if watch_patterns.is_empty() {
+        None
+    } else {
+        Some(watch::PromptWatcher::start(&default_cwd, &watch_patterns)?)
+    };
+    let watch_stop = Arc::new(tokio::sync::Notify::new());
+
+    let auth_manager = AuthManager::shared_from_config(&config, true);
     let conversation_manager = ConversationManager::new(auth_manager.clone(), SessionSource::Exec);
 
     // Handle resume subcommand by resolving a rollout path and using explicit resume API.
@@ -317,6 +380,12 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     } = if let Some(ExecCommand::Resume(args)) = command {
         let resume_path = resolve_resume_path(&config, &args).await?;
 
+        if resume_path.is_none()
+            && let Some(id) = args.session_id.as_deref()
+        {
+            eprintln!("No recorded session found with id {id}.");
+            std::process::exit(2);
+        }
         if let Some(path) = resume_path {
             conversation_manager
                 .resume_conversation_from_rollout(config.clone(), path, auth_manager.clone())
@@ -334,16 +403,30 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     // Print the effective configuration and prompt so users can see what Codex
     // is using.
     event_processor.print_config_summary(&config, &prompt, &session_configured);
+    if let Some(event_log) = event_log.as_mut() {
+        event_log.record(&Event {
+            id: String::new(),
+            msg: EventMsg::SessionConfigured(session_configured.clone()),
+        });
+    }
 
     info!("Codex initialized with event: {session_configured:?}");
 
     let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Event>();
     {
         let conversation = conversation.clone();
+        let watch_mode = watcher.is_some();
+        let watch_stop = watch_stop.clone();
         tokio::spawn(async move {
             loop {
                 tokio::select! {
                     _ = tokio::signal::ctrl_c() => {
+                        if watch_mode {
+                            // Let the current turn finish, then exit.
+                            tracing::debug!("Keyboard interrupt; leaving watch mode");
+                            watch_stop.notify_one();
+                            continue;
+                        }
                         tracing::debug!("Keyboard interrupt");
                         // Immediately notify Codex to abort any in‑flight task.
                         conversation.submit(Op::Interrupt).await.ok();
@@ -381,33 +464,153 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         .into_iter()
         .map(|path| UserInput::LocalImage { path })
         .collect();
-    items.push(UserInput::Text { text: prompt });
-    let initial_prompt_task_id = conversation
-        .submit(Op::UserTurn {
-            items,
-            cwd: default_cwd,
-            approval_policy: default_approval_policy,
-            sandbox_policy: default_sandbox_policy,
-            model: default_model,
-            effort: default_effort,
-            summary: default_summary,
-            final_output_json_schema: output_schema,
-        })
-        .await?;
+    items.push(UserInput::Text {
+        text: prompt.clone(),
+    });
+    let user_turn = |items: Vec<UserInput>| Op::UserTurn {
+        items,
+        cwd: default_cwd.clone(),
+        approval_policy: default_approval_policy,
+        sandbox_policy: default_sandbox_policy.clone(),
+        model: default_model.clone(),
+        effort: default_effort,
+        summary: default_summary,
+        final_output_json_schema: output_schema.clone(),
+    };
+    let mut timeout_deadline =
+        (timeout_secs > 0).then(|| Instant::now() + Duration::from_secs(timeout_secs));
+    let mut timed_out = false;
+    let mut timeout_shutdown_sent = false;
+    let initial_prompt_task_id = conversation.submit(user_turn(items)).await?;
     info!("Sent prompt with event ID: {initial_prompt_task_id}");
 
     // Run the loop until the task is complete.
     // Track whether a fatal error was reported by the server so we can
     // exit with a non-zero status for automation-friendly signaling.
     let mut error_seen = false;
-    while let Some(event) = rx.recv().await {
+    let mut turn_error_seen = false;
+    let mut schema_retries_left = OUTPUT_SCHEMA_RETRIES;
+    // In watch mode the loop idles between turns until watched files change
+    // or Ctrl-C is pressed.
+    let mut turn_running = true;
+    let mut stop_requested = false;
+    loop {
+        let event = tokio::select! {
+            event = rx.recv() => match event {
+                Some(event) => event,
+                None => break,
+            },
+            paths = next_watch_trigger(watcher.as_mut()), if !turn_running && !stop_requested && !timed_out => {
+                event_processor.watch_triggered(&paths);
+                turn_error_seen = false;
+                schema_retries_left = OUTPUT_SCHEMA_RETRIES;
+                conversation
+                    .submit(user_turn(vec![UserInput::Text {
+                        text: prompt.clone(),
+                    }]))
+                    .await?;
+                turn_running = true;
+                continue;
+            }
+            _ = watch_stop.notified(), if !stop_requested => {
+                stop_requested = true;
+                if !turn_running {
+                    conversation.submit(Op::Shutdown).await?;
+                }
+                continue;
+            }
+            _ = sleep_until(timeout_deadline) => {
+                if timeout_shutdown_sent {
+                    // Shutdown did not complete in time.
+                    break;
+                }
+                let interrupt = !timed_out && turn_running;
+                if !timed_out {
+                    timed_out = true;
+                    event_processor.timed_out(timeout_secs);
+                }
+                if interrupt {
+                    conversation.submit(Op::Interrupt).await?;
+                } else {
+                    // Either the interrupted turn did not wind down in time or
+                    // watch mode was idle between turns; shut down so the
+                    // rollout is flushed.
+                    conversation.submit(Op::Shutdown).await?;
+                    timeout_shutdown_sent = true;
+                }
+                timeout_deadline = Some(Instant::now() + TIMEOUT_DRAIN_GRACE);
+                continue;
+            }
+        };
+        if let Some(event_log) = event_log.as_mut() {
+            event_log.record(&event);
+        }
+        let turn_ended = matches!(
+            event.msg,
+            EventMsg::TurnAborted(_) | EventMsg::TaskComplete(_)
+        );
         if matches!(event.msg, EventMsg::Error(_)) {
             error_seen = true;
+            turn_error_seen = true;
+        }
+        if let Some(watcher) = watcher.as_mut()
+            && let EventMsg::TurnDiff(TurnDiffEvent { unified_diff, .. }) = &event.msg
+        {
+            watcher.record_turn_diff(unified_diff);
+        }
+        let mut schema_retry = None;
+        if let Some(schema) = output_schema.as_ref()
+            && !turn_error_seen
+            && let EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) = &event.msg
+        {
+            let last_agent_message = last_agent_message.as_deref().unwrap_or_default();
+            match output_schema::validate_output(schema, last_agent_message) {
+                Ok(final_output) => event_processor.set_final_output(final_output),
+                Err(err) if schema_retries_left > 0 => {
+                    schema_retries_left -= 1;
+                    eprintln!("Final message does not match the output schema ({err}); retrying.");
+                    schema_retry = Some(err);
+                }
+                Err(err) => {
+                    eprintln!("Final message does not match the output schema: {err}");
+                    error_seen = true;
+                }
+            }
         }
         let shutdown: CodexStatus = event_processor.process_event(event);
+        if timed_out && turn_ended && !timeout_shutdown_sent {
+            conversation.submit(Op::Shutdown).await?;
+            timeout_shutdown_sent = true;
+            timeout_deadline = Some(Instant::now() + TIMEOUT_DRAIN_GRACE);
+            continue;
+        }
+        if timeout_shutdown_sent {
+            // Wait for ShutdownComplete, which closes the event channel.
+            continue;
+        }
+        if let Some(err) = schema_retry {
+            let text = format!(
+                "Your final message did not match the required output schema: {err}. Reply again with only the JSON output, matching the schema exactly."
+            );
+            conversation
+                .submit(user_turn(vec![UserInput::Text { text }]))
+                .await?;
+            continue;
+        }
         match shutdown {
             CodexStatus::Running => continue,
             CodexStatus::InitiateShutdown => {
+                if let Some(watcher) = watcher.as_mut()
+                    && !stop_requested
+                {
+                    watcher.finish_turn();
+                    turn_running = false;
+                    eprintln!(
+                        "Watching {} for changes; press Ctrl-C to exit.",
+                        watch_patterns.join(", ")
+                    );
+                    continue;
+                }
                 conversation.submit(Op::Shutdown).await?;
             }
             CodexStatus::Shutdown => {
@@ -415,7 +618,13 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
             }
         }
     }
+    if let Some(event_log) = event_log.take() {
+        event_log.finish();
+    }
     event_processor.print_final_output();
+    if timed_out {
+        std::process::exit(TIMEOUT_EXIT_CODE);
+    }
     if error_seen {
         std::process::exit(1);
     }
@@ -423,6 +632,20 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     Ok(())
 }
 
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn next_watch_trigger(watcher: Option<&mut watch::PromptWatcher>) -> Vec<String> {
+    match watcher {
+        Some(watcher) => watcher.next_trigger().await,
+        None => std::future::pending().await,
+    }
+}
+
 async fn resolve_resume_path(
     config: &Config,
     args: &crate::cli::ResumeArgs,
@@ -467,8 +690,8 @@ fn load_output_schema(path: Option<PathBuf>) -> Option<Value> {
         }
     };
 
-    match serde_json::from_str::<Value>(&schema_str) {
-        Ok(value) => Some(value),
+    let schema = match serde_json::from_str::<Value>(&schema_str) {
+        Ok(value) => value,
         Err(err) => {
             eprintln!(
                 "Output schema file {} is not valid JSON: {err}",
@@ -476,5 +699,14 @@ fn load_output_schema(path: Option<PathBuf>) -> Option<Value> {
             );
             std::process::exit(1);
         }
+    };
+
+    if let Err(err) = output_schema::check_schema(&schema) {
+        eprintln!(
+            "Output schema file {} is not a usable schema: {err}",
+            path.display()
+        );
+        std::process::exit(1);
     }
+    Some(schema)
 }
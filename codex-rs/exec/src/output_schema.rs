@@ -0,0 +1,318 @@
+//! Checks for `--output-schema`: the schema file itself is checked before the
+//! first model call, and the final agent message is validated against it.
+//!
+//! Only the subset of JSON Schema accepted by structured outputs is
+//! validated (`type`, `properties`, `required`, `additionalProperties`,
+//! `items`, `enum`, `const`, `anyOf` and local `$ref`s); other keywords are
+//! ignored.
+
+use serde_json::Map;
+use serde_json::Value;
+
+/// Upper bound on `$ref` hops, so reference cycles are reported instead of
+/// looping forever.
+const MAX_REF_HOPS: usize = 32;
+
+/// Check that `schema` can be used as an output schema: the root must be an
+/// object schema and every `$ref` must point into the same document.
+pub(crate) fn check_schema(schema: &Value) -> Result<(), String> {
+    let Some(root) = schema.as_object() else {
+        return Err("the schema must be a JSON object".to_string());
+    };
+    if let Some(ty) = root.get("type")
+        && ty != "object"
+    {
+        return Err(format!(
+            "the root schema must have \"type\": \"object\" (found {ty})"
+        ));
+    }
+    check_refs(schema, schema)
+}
+
+fn check_refs(root: &Value, node: &Value) -> Result<(), String> {
+    match node {
+        Value::Object(map) => {
+            if let Some(reference) = map.get("$ref") {
+                let Some(reference) = reference.as_str() else {
+                    return Err(format!("$ref must be a string (found {reference})"));
+                };
+                resolve_ref(root, reference)?;
+            }
+            map.values().try_for_each(|value| check_refs(root, value))
+        }
+        Value::Array(values) => values.iter().try_for_each(|value| check_refs(root, value)),
+        _ => Ok(()),
+    }
+}
+
+/// Follow `reference` (and any `$ref` it points at) to a schema that is not a
+/// bare reference.
+fn resolve_ref<'a>(root: &'a Value, original: &str) -> Result<&'a Value, String> {
+    let mut reference = original;
+    for _ in 0..MAX_REF_HOPS {
+        let Some(pointer) = reference.strip_prefix('#') else {
+            return Err(format!(
+                "$ref \"{reference}\" is not supported; only references within the schema (\"#/...\") are"
+            ));
+        };
+        let Some(target) = root.pointer(pointer) else {
+            return Err(format!("$ref \"{reference}\" does not resolve"));
+        };
+        match target.get("$ref").and_then(Value::as_str) {
+            Some(next) if target.as_object().is_some_and(|map| map.len() == 1) => {
+                reference = next;
+            }
+            _ => return Ok(target),
+        }
+    }
+    Err(format!("$ref \"{original}\" is part of a reference cycle"))
+}
+
+/// Parse the final agent message and validate it against `schema`. Returns the
+/// parsed value, or a description of the first violation.
+pub(crate) fn validate_output(schema: &Value, output: &str) -> Result<Value, String> {
+    let value: Value = serde_json::from_str(output.trim())
+        .map_err(|err| format!("the reply is not valid JSON: {err}"))?;
+    validate(schema, schema, &value, "$")?;
+    Ok(value)
+}
+
+fn validate(root: &Value, schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    let Some(schema) = schema.as_object() else {
+        // `true` accepts everything, `false` nothing.
+        return match schema {
+            Value::Bool(false) => Err(format!("{path} is not allowed")),
+            _ => Ok(()),
+        };
+    };
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        validate(root, resolve_ref(root, reference)?, value, path)?;
+    }
+
+    if let Some(ty) = schema.get("type") {
+        let allowed: Vec<&str> = match ty {
+            Value::String(ty) => vec![ty.as_str()],
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|ty| has_type(value, ty)) {
+            return Err(format!(
+                "{path} should be of type {} but is {}",
+                allowed.join(" or "),
+                type_name(value)
+            ));
+        }
+    }
+
+    if let Some(expected) = schema.get("const")
+        && expected != value
+    {
+        return Err(format!("{path} should be {expected}"));
+    }
+
+    if let Some(Value::Array(options)) = schema.get("enum")
+        && !options.contains(value)
+    {
+        return Err(format!(
+            "{path} should be one of {}",
+            Value::Array(options.clone())
+        ));
+    }
+
+    if let Some(Value::Array(variants)) = schema.get("anyOf") {
+        let mut errors = Vec::new();
+        for variant in variants {
+            match validate(root, variant, value, path) {
+                Ok(()) => {
+                    errors.clear();
+                    break;
+                }
+                Err(err) => errors.push(err),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(format!(
+                "{path} matches none of the allowed variants ({})",
+                errors.join("; ")
+            ));
+        }
+    }
+
+    match value {
+        Value::Object(fields) => validate_object(root, schema, fields, path),
+        Value::Array(values) => {
+            if let Some(items) = schema.get("items") {
+                for (index, item) in values.iter().enumerate() {
+                    validate(root, items, item, &format!("{path}[{index}]"))?;
+                }
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn validate_object(
+    root: &Value,
+    schema: &Map<String, Value>,
+    fields: &Map<String, Value>,
+    path: &str,
+) -> Result<(), String> {
+    if let Some(Value::Array(required)) = schema.get("required") {
+        for name in required.iter().filter_map(Value::as_str) {
+            if !fields.contains_key(name) {
+                return Err(format!("{path} is missing required property \"{name}\""));
+            }
+        }
+    }
+
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for (name, field) in fields {
+        let field_path = format!("{path}.{name}");
+        match properties.and_then(|properties| properties.get(name)) {
+            Some(field_schema) => validate(root, field_schema, field, &field_path)?,
+            None => match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => {
+                    return Err(format!("{path} has unexpected property \"{name}\""));
+                }
+                Some(additional @ Value::Object(_)) => {
+                    validate(root, additional, field, &field_path)?;
+                }
+                _ => {}
+            },
+        }
+    }
+    Ok(())
+}
+
+fn has_type(value: &Value, ty: &str) -> bool {
+    match ty {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn answer_schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "answer": { "$ref": "#/$defs/answer" },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["answer", "tags"],
+            "additionalProperties": false,
+            "$defs": {
+                "answer": {
+                    "anyOf": [
+                        { "type": "string" },
+                        { "type": "null" }
+                    ]
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn schema_with_local_refs_is_accepted() {
+        assert_eq!(check_schema(&answer_schema()), Ok(()));
+    }
+
+    #[test]
+    fn unresolvable_and_remote_refs_are_rejected() {
+        assert_eq!(
+            check_schema(&json!({
+                "type": "object",
+                "properties": { "a": { "$ref": "#/$defs/missing" } }
+            })),
+            Err("$ref \"#/$defs/missing\" does not resolve".to_string())
+        );
+        assert_eq!(
+            check_schema(&json!({
+                "type": "object",
+                "properties": { "a": { "$ref": "https://example.com/a.json" } }
+            })),
+            Err("$ref \"https://example.com/a.json\" is not supported; only references within the schema (\"#/...\") are".to_string())
+        );
+    }
+
+    #[test]
+    fn ref_cycles_are_rejected() {
+        assert_eq!(
+            check_schema(&json!({
+                "type": "object",
+                "properties": { "a": { "$ref": "#/$defs/a" } },
+                "$defs": {
+                    "a": { "$ref": "#/$defs/b" },
+                    "b": { "$ref": "#/$defs/a" }
+                }
+            })),
+            Err("$ref \"#/$defs/a\" is part of a reference cycle".to_string())
+        );
+    }
+
+    #[test]
+    fn non_object_root_is_rejected() {
+        assert_eq!(
+            check_schema(&json!({ "type": "string" })),
+            Err("the root schema must have \"type\": \"object\" (found \"string\")".to_string())
+        );
+    }
+
+    #[test]
+    fn matching_output_is_parsed() {
+        assert_eq!(
+            validate_output(&answer_schema(), r#"{"answer": null, "tags": ["a"]}"#),
+            Ok(json!({ "answer": null, "tags": ["a"] }))
+        );
+    }
+
+    #[test]
+    fn violations_are_described() {
+        let schema = answer_schema();
+        assert_eq!(
+            validate_output(&schema, "not json")
+                .map_err(|err| err.starts_with("the reply is not valid JSON")),
+            Err(true)
+        );
+        assert_eq!(
+            validate_output(&schema, r#"{"answer": "yes"}"#),
+            Err("$ is missing required property \"tags\"".to_string())
+        );
+        assert_eq!(
+            validate_output(&schema, r#"{"answer": "yes", "tags": [1]}"#),
+            Err("$.tags[0] should be of type string but is number".to_string())
+        );
+        assert_eq!(
+            validate_output(&schema, r#"{"answer": "yes", "tags": [], "extra": 1}"#),
+            Err("$ has unexpected property \"extra\"".to_string())
+        );
+        assert_eq!(
+            validate_output(&schema, r#"{"answer": 1, "tags": []}"#),
+            Err("$.answer matches none of the allowed variants ($.answer should be of type string but is number; $.answer should be of type null but is number)".to_string())
+        );
+    }
+}
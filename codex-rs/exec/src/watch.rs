@@ -0,0 +1,265 @@
+//! `--watch` support: collects filesystem changes matching the watch globs
+//! and turns them into debounced re-run triggers.
+
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Context;
+use codex_core::git_info::get_git_repo_root;
+use notify::EventKind;
+use notify::RecommendedWatcher;
+use notify::RecursiveMode;
+use notify::Watcher;
+use tokio::sync::mpsc::UnboundedReceiver;
+use wildmatch::WildMatch;
+
+/// Quiet period after the last change before a run is triggered.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+pub(crate) struct PromptWatcher {
+    root: PathBuf,
+    /// Directory the paths in turn diffs are relative to.
+    diff_root: PathBuf,
+    patterns: Vec<WildMatch>,
+    _watcher: RecommendedWatcher,
+    changes: UnboundedReceiver<PathBuf>,
+    pending: BTreeSet<PathBuf>,
+    turn_diff_paths: BTreeSet<PathBuf>,
+    /// Content hash (`None` when deleted) of every file the agent wrote,
+    /// as of the end of the turn that wrote it.
+    agent_writes: HashMap<PathBuf, Option<u64>>,
+}
+
+impl PromptWatcher {
+    pub(crate) fn start(cwd: &Path, patterns: &[String]) -> anyhow::Result<Self> {
+        let root = std::fs::canonicalize(cwd)
+            .with_context(|| format!("failed to resolve watch root {}", cwd.display()))?;
+        let (tx, changes) = tokio::sync::mpsc::unbounded_channel();
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+                if let Ok(event) = res
+                    && !matches!(event.kind, EventKind::Access(_))
+                {
+                    for path in event.paths {
+                        let _ = tx.send(path);
+                    }
+                }
+            })?;
+        watcher
+            .watch(&root, RecursiveMode::Recursive)
+            .with_context(|| format!("failed to watch {}", root.display()))?;
+        let diff_root = get_git_repo_root(&root).unwrap_or_else(|| root.clone());
+        Ok(Self {
+            root,
+            diff_root,
+            patterns: patterns
+                .iter()
+                .map(|pattern| WildMatch::new(pattern))
+                .collect(),
+            _watcher: watcher,
+            changes,
+            pending: BTreeSet::new(),
+            turn_diff_paths: BTreeSet::new(),
+            agent_writes: HashMap::new(),
+        })
+    }
+
+    /// Note the files touched by the running turn, from its unified diff.
+    pub(crate) fn record_turn_diff(&mut self, unified_diff: &str) {
+        for path in diff_paths(unified_diff) {
+            self.turn_diff_paths.insert(self.diff_root.join(path));
+        }
+    }
+
+    /// Remember what the agent left in the files it wrote during the turn that
+    /// just finished, so the resulting change events do not start another run.
+    pub(crate) fn finish_turn(&mut self) {
+        for path in std::mem::take(&mut self.turn_diff_paths) {
+            let hash = content_hash(&path);
+            self.agent_writes.insert(path, hash);
+        }
+    }
+
+    /// Wait for the next batch of changes to watched files. Changes that arrived
+    /// while a turn was running are coalesced into the first batch.
+    pub(crate) async fn next_trigger(&mut self) -> Vec<String> {
+        loop {
+            if self.pending.is_empty() {
+                match self.changes.recv().await {
+                    Some(path) => self.record_change(path),
+                    None => std::future::pending::<()>().await,
+                }
+            }
+            while let Ok(Some(path)) = tokio::time::timeout(DEBOUNCE, self.changes.recv()).await {
+                self.record_change(path);
+            }
+            let changed = self.take_pending();
+            if !changed.is_empty() {
+                return changed;
+            }
+        }
+    }
+
+    fn record_change(&mut self, path: PathBuf) {
+        if let Some(relative) = self.relative(&path)
+            && self
+                .patterns
+                .iter()
+                .any(|pattern| pattern.matches(&relative))
+        {
+            self.pending.insert(path);
+        }
+    }
+
+    fn take_pending(&mut self) -> Vec<String> {
+        let mut changed = Vec::new();
+        for path in std::mem::take(&mut self.pending) {
+            if self.is_agent_write(&path) {
+                continue;
+            }
+            if let Some(relative) = self.relative(&path) {
+                changed.push(relative);
+            }
+        }
+        changed
+    }
+
+    /// True when `path` still holds exactly what the agent wrote to it.
+    fn is_agent_write(&mut self, path: &Path) -> bool {
+        let Some(written) = self.agent_writes.get(path) else {
+            return false;
+        };
+        if *written == content_hash(path) {
+            return true;
+        }
+        self.agent_writes.remove(path);
+        false
+    }
+
+    fn relative(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        Some(relative.to_string_lossy().replace('\\', "/"))
+    }
+}
+
+/// Paths named by the `diff --git a/<old> b/<new>` headers of a unified diff.
+fn diff_paths(unified_diff: &str) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for header in unified_diff
+        .lines()
+        .filter_map(|line| line.strip_prefix("diff --git a/"))
+    {
+        // Prefer the split where both sides agree, which also handles names
+        // containing " b/".
+        let mut splits = header.match_indices(" b/").map(|(index, _)| index);
+        let Some(first) = splits.next() else {
+            continue;
+        };
+        let split = std::iter::once(first)
+            .chain(splits)
+            .find(|&index| header[..index] == header[index + 3..])
+            .unwrap_or(first);
+        let (old, new) = (&header[..split], &header[split + 3..]);
+        paths.push(PathBuf::from(old));
+        if new != old {
+            paths.push(PathBuf::from(new));
+        }
+    }
+    paths
+}
+
+fn content_hash(path: &Path) -> Option<u64> {
+    let contents = std::fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    async fn next_trigger(watcher: &mut PromptWatcher) -> Vec<String> {
+        tokio::time::timeout(Duration::from_secs(10), watcher.next_trigger())
+            .await
+            .expect("watch trigger")
+    }
+
+    #[test]
+    fn diff_paths_reads_git_headers() {
+        let diff = "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1 @@\n-a\n+b\ndiff --git a/old name.txt b/new name.txt\nsimilarity index 100%\ndiff --git a/a b/c.txt b/a b/c.txt\n";
+        assert_eq!(
+            diff_paths(diff),
+            vec![
+                PathBuf::from("src/lib.rs"),
+                PathBuf::from("old name.txt"),
+                PathBuf::from("new name.txt"),
+                PathBuf::from("a b/c.txt"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn changes_are_coalesced_into_one_trigger() {
+        let dir = TempDir::new().expect("tempdir");
+        std::fs::create_dir(dir.path().join("api")).expect("mkdir");
+        let mut watcher =
+            PromptWatcher::start(dir.path(), &["api/*.yaml".to_string()]).expect("start watcher");
+
+        // Changes made while a turn runs are buffered until the next wait.
+        std::fs::write(dir.path().join("api/openapi.yaml"), "a").expect("write");
+        std::fs::write(dir.path().join("api/extra.yaml"), "b").expect("write");
+        std::fs::write(dir.path().join("api/notes.txt"), "c").expect("write");
+        std::fs::write(dir.path().join("api/openapi.yaml"), "d").expect("write");
+
+        assert_eq!(
+            next_trigger(&mut watcher).await,
+            vec!["api/extra.yaml".to_string(), "api/openapi.yaml".to_string()]
+        );
+
+        std::fs::write(dir.path().join("api/openapi.yaml"), "e").expect("write");
+        assert_eq!(
+            next_trigger(&mut watcher).await,
+            vec!["api/openapi.yaml".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn agent_writes_do_not_trigger_a_run() {
+        let dir = TempDir::new().expect("tempdir");
+        let root = std::fs::canonicalize(dir.path()).expect("canonicalize");
+        let mut watcher =
+            PromptWatcher::start(&root, &["*.rs".to_string()]).expect("start watcher");
+
+        // The agent rewrites client.rs during its turn.
+        let client = root.join("client.rs");
+        std::fs::write(&client, "generated").expect("write");
+        watcher.record_turn_diff(&format!(
+            "diff --git a/{path} b/{path}\nnew file mode 100644\n",
+            path = client.display()
+        ));
+        watcher.finish_turn();
+
+        // Only the user's edit triggers the next run.
+        std::fs::write(root.join("main.rs"), "fn main() {}").expect("write");
+        assert_eq!(
+            next_trigger(&mut watcher).await,
+            vec!["main.rs".to_string()]
+        );
+
+        // Once the user edits the agent's file, it counts again.
+        std::fs::write(&client, "edited by hand").expect("write");
+        assert_eq!(
+            next_trigger(&mut watcher).await,
+            vec!["client.rs".to_string()]
+        );
+    }
+}
@@ -15,6 +15,7 @@ use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol::SessionConfiguredEvent;
+use codex_core::protocol::TurnLimitKind;
 use codex_core::protocol::WarningEvent;
 use codex_core::protocol::WebSearchEndEvent;
 use codex_exec::event_processor_with_jsonl_output::EventProcessorWithJsonOutput;
@@ -41,8 +42,11 @@ use codex_exec::exec_events::TodoItem as ExecTodoItem;
 use codex_exec::exec_events::TodoListItem as ExecTodoListItem;
 use codex_exec::exec_events::TurnCompletedEvent;
 use codex_exec::exec_events::TurnFailedEvent;
+use codex_exec::exec_events::TurnLimit;
+use codex_exec::exec_events::TurnLimitReachedEvent;
 use codex_exec::exec_events::TurnStartedEvent;
 use codex_exec::exec_events::Usage;
+use codex_exec::exec_events::UsageUpdatedEvent;
 use codex_exec::exec_events::WebSearchItem;
 use codex_protocol::plan_tool::PlanItemArg;
 use codex_protocol::plan_tool::StepStatus;
@@ -107,6 +111,26 @@ fn task_started_produces_turn_started_event() {
     assert_eq!(out, vec![ThreadEvent::TurnStarted(TurnStartedEvent {})]);
 }
 
+#[test]
+fn turn_limit_reached_produces_turn_limit_event() {
+    let mut ep = EventProcessorWithJsonOutput::new(None);
+    let out = ep.collect_thread_events(&event(
+        "t1",
+        EventMsg::TurnLimitReached(codex_core::protocol::TurnLimitReachedEvent {
+            limit: TurnLimitKind::OutputTokens,
+            threshold: 50_000,
+        }),
+    ));
+
+    assert_eq!(
+        out,
+        vec![ThreadEvent::TurnLimitReached(TurnLimitReachedEvent {
+            limit: TurnLimit::OutputTokens,
+            threshold: 50_000,
+        })]
+    );
+}
+
 #[test]
 fn web_search_end_emits_item_completed() {
     let mut ep = EventProcessorWithJsonOutput::new(None);
@@ -242,6 +266,7 @@ fn plan_update_emits_todo_list_started_updated_and_completed() {
             }),
             ThreadEvent::TurnCompleted(TurnCompletedEvent {
                 usage: Usage::default(),
+                final_output: None,
             }),
         ]
     );
@@ -292,6 +317,7 @@ fn mcp_tool_call_begin_and_end_emit_item_events() {
                 is_error: None,
                 structured_content: None,
             }),
+            cached: false,
         }),
     );
     let end_events = ep.collect_thread_events(&end);
@@ -341,6 +367,7 @@ fn mcp_tool_call_failure_sets_failed_status() {
             invocation,
             duration: Duration::from_millis(5),
             result: Err("tool exploded".to_string()),
+            cached: false,
         }),
     );
     let events = ep.collect_thread_events(&end);
@@ -413,6 +440,7 @@ fn mcp_tool_call_defaults_arguments_and_preserves_structured_content() {
                 is_error: None,
                 structured_content: Some(json!({ "status": "ok" })),
             }),
+            cached: false,
         }),
     );
     let events = ep.collect_thread_events(&end);
@@ -639,6 +667,7 @@ fn exec_command_end_success_produces_completed_command_item() {
             parsed_cmd: parsed_cmd.clone(),
             source: ExecCommandSource::Agent,
             interaction_input: None,
+            filtered_env_vars: None,
         }),
     );
     let out_begin = ep.collect_thread_events(&begin);
@@ -671,6 +700,7 @@ fn exec_command_end_success_produces_completed_command_item() {
             stdout: String::new(),
             stderr: String::new(),
             aggregated_output: "hi\n".to_string(),
+            stderr_ranges: Vec::new(),
             exit_code: 0,
             duration: Duration::from_millis(5),
             formatted_output: String::new(),
@@ -711,6 +741,7 @@ fn exec_command_end_failure_produces_failed_command_item() {
             parsed_cmd: parsed_cmd.clone(),
             source: ExecCommandSource::Agent,
             interaction_input: None,
+            filtered_env_vars: None,
         }),
     );
     assert_eq!(
@@ -742,6 +773,7 @@ fn exec_command_end_failure_produces_failed_command_item() {
             stdout: String::new(),
             stderr: String::new(),
             aggregated_output: String::new(),
+            stderr_ranges: Vec::new(),
             exit_code: 1,
             duration: Duration::from_millis(2),
             formatted_output: String::new(),
@@ -782,6 +814,7 @@ fn exec_command_end_without_begin_is_ignored() {
             stdout: String::new(),
             stderr: String::new(),
             aggregated_output: String::new(),
+            stderr_ranges: Vec::new(),
             exit_code: 0,
             duration: Duration::from_millis(1),
             formatted_output: String::new(),
@@ -955,8 +988,25 @@ fn task_complete_produces_turn_completed_with_usage() {
         EventMsg::TokenCount(codex_core::protocol::TokenCountEvent {
             info: Some(info),
             rate_limits: None,
+            model: Some("gpt-5.1-codex".to_string()),
+            estimated: false,
         }),
     );
+    let expected_usage = Usage {
+        input_tokens: 1200,
+        cached_input_tokens: 200,
+        output_tokens: 345,
+    };
+    assert_eq!(
+        ep.collect_thread_events(&token_count_event),
+        vec![ThreadEvent::UsageUpdated(UsageUpdatedEvent {
+            last: expected_usage.clone(),
+            total: expected_usage,
+            model: Some("gpt-5.1-codex".to_string()),
+            estimated: false,
+        })]
+    );
+    // A repeated snapshot (e.g. a rate limit refresh) does not re-emit usage.
     assert!(ep.collect_thread_events(&token_count_event).is_empty());
 
     // Then TaskComplete should produce turn.completed with the captured usage.
@@ -975,6 +1025,7 @@ fn task_complete_produces_turn_completed_with_usage() {
                 cached_input_tokens: 200,
                 output_tokens: 345,
             },
+            final_output: None,
         })]
     );
 }
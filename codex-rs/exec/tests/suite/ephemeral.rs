@@ -0,0 +1,53 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use core_test_support::responses;
+use core_test_support::test_codex_exec::test_codex_exec;
+use predicates::str::contains;
+use walkdir::WalkDir;
+
+/// Every entry under `root` with its length and modification time.
+fn snapshot(root: &Path) -> BTreeMap<PathBuf, (u64, SystemTime)> {
+    WalkDir::new(root)
+        .into_iter()
+        .map(|entry| {
+            let entry = entry.unwrap();
+            let metadata = entry.metadata().unwrap();
+            let relative = entry.path().strip_prefix(root).unwrap().to_path_buf();
+            (relative, (metadata.len(), metadata.modified().unwrap()))
+        })
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn ephemeral_run_leaves_codex_home_untouched() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+    std::fs::write(test.home_path().join("config.toml"), "")?;
+    let before = snapshot(test.home_path());
+
+    let server = responses::start_mock_server().await;
+    let body = responses::sse(vec![
+        responses::ev_response_created("resp1"),
+        responses::ev_assistant_message("m1", "fixture hello"),
+        responses::ev_completed("resp1"),
+    ]);
+    responses::mount_sse_once(&server, body).await;
+
+    test.cmd_with_server(&server)
+        .arg("--ephemeral")
+        .arg("--skip-git-repo-check")
+        .arg("-C")
+        .arg(test.cwd_path())
+        .arg("tell me a joke")
+        .assert()
+        .success()
+        .stderr(contains("ephemeral session"));
+
+    assert_eq!(snapshot(test.home_path()), before);
+    Ok(())
+}
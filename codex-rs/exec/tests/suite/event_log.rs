@@ -0,0 +1,134 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use core_test_support::responses;
+use core_test_support::test_codex_exec::test_codex_exec;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+
+/// Event types whose order in a plain one-turn run is part of the log's
+/// contract; other events may come and go between releases.
+const STABLE_EVENT_TYPES: &[&str] = &[
+    "session_configured",
+    "task_started",
+    "agent_message",
+    "task_complete",
+];
+
+/// Values that differ between runs.
+const VOLATILE_FIELDS: &[&str] = &[
+    "/seq",
+    "/ts_ms",
+    "/event/id",
+    "/event/msg/session_id",
+    "/event/msg/cwd",
+    "/event/msg/rollout_path",
+    "/event/msg/history_log_id",
+    "/event/msg/model_context_window",
+];
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn event_log_records_every_event_with_stable_schema() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+    let server = responses::start_mock_server().await;
+    let body = responses::sse(vec![
+        responses::ev_response_created("resp1"),
+        responses::ev_assistant_message("m1", "fixture hello"),
+        responses::ev_completed("resp1"),
+    ]);
+    responses::mount_sse_once(&server, body).await;
+    let log_path = test.cwd_path().join("events.jsonl");
+
+    let output = test
+        .cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--sandbox")
+        .arg("read-only")
+        .arg("-m")
+        .arg("gpt-5.1-codex")
+        .arg("-c")
+        .arg("model_reasoning_effort=\"high\"")
+        .arg("--event-log")
+        .arg(&log_path)
+        .arg("tell me a joke")
+        .output()?;
+    assert!(output.status.success(), "run failed: {output:?}");
+    // Human output is unaffected.
+    assert!(String::from_utf8(output.stdout)?.contains("fixture hello"));
+
+    let records: Vec<Value> = std::fs::read_to_string(&log_path)?
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("valid JSON line"))
+        .collect();
+    for (i, record) in records.iter().enumerate() {
+        let mut keys: Vec<&str> = record
+            .as_object()
+            .expect("record is an object")
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort_unstable();
+        assert_eq!(keys, vec!["event", "seq", "ts_ms", "type"]);
+        assert_eq!(record["type"], "event");
+        assert_eq!(record["seq"], i as u64);
+        assert!(record["ts_ms"].is_u64());
+        assert!(record["event"]["id"].is_string());
+    }
+
+    let stable: Vec<Value> = records
+        .into_iter()
+        .filter(|record| {
+            record["event"]["msg"]["type"]
+                .as_str()
+                .is_some_and(|ty| STABLE_EVENT_TYPES.contains(&ty))
+        })
+        .map(|mut record| {
+            for field in VOLATILE_FIELDS {
+                if let Some(value) = record.pointer_mut(field) {
+                    *value = json!("[redacted]");
+                }
+            }
+            record
+        })
+        .collect();
+    let envelope = |msg: Value| {
+        json!({
+            "type": "event",
+            "seq": "[redacted]",
+            "ts_ms": "[redacted]",
+            "event": { "id": "[redacted]", "msg": msg },
+        })
+    };
+    assert_eq!(
+        stable,
+        vec![
+            envelope(json!({
+                "type": "session_configured",
+                "session_id": "[redacted]",
+                "model": "gpt-5.1-codex",
+                "model_provider_id": "openai",
+                "approval_policy": "never",
+                "sandbox_policy": { "type": "read-only" },
+                "cwd": "[redacted]",
+                "reasoning_effort": "high",
+                "history_log_id": "[redacted]",
+                "history_entry_count": 0,
+                "rollout_path": "[redacted]",
+            })),
+            envelope(json!({
+                "type": "task_started",
+                "model_context_window": "[redacted]",
+            })),
+            envelope(json!({
+                "type": "agent_message",
+                "message": "fixture hello",
+            })),
+            envelope(json!({
+                "type": "task_complete",
+                "last_agent_message": "fixture hello",
+            })),
+        ]
+    );
+    Ok(())
+}
@@ -2,8 +2,11 @@
 mod add_dir;
 mod apply_patch;
 mod auth_env;
+mod ephemeral;
+mod event_log;
 mod originator;
 mod output_schema;
 mod resume;
 mod sandbox;
 mod server_error_exit;
+mod timeout;
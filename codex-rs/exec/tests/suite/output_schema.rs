@@ -24,7 +24,7 @@ async fn exec_includes_output_schema_in_request() -> anyhow::Result<()> {
     let server = responses::start_mock_server().await;
     let body = responses::sse(vec![
         responses::ev_response_created("resp1"),
-        responses::ev_assistant_message("m1", "fixture hello"),
+        responses::ev_assistant_message("m1", r#"{"answer":"fixture hello"}"#),
         responses::ev_completed("resp1"),
     ]);
     let response_mock = responses::mount_sse_once(&server, body).await;
@@ -60,3 +60,142 @@ async fn exec_includes_output_schema_in_request() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exec_rejects_unresolvable_schema_ref_before_model_call() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+
+    let schema_path = test.cwd_path().join("schema.json");
+    std::fs::write(
+        &schema_path,
+        serde_json::to_vec(&serde_json::json!({
+            "type": "object",
+            "properties": { "answer": { "$ref": "#/$defs/missing" } }
+        }))?,
+    )?;
+
+    let server = responses::start_mock_server().await;
+    test.cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--output-schema")
+        .arg(&schema_path)
+        .arg("tell me a joke")
+        .assert()
+        .code(1)
+        .stderr(predicates::str::contains(
+            "$ref \"#/$defs/missing\" does not resolve",
+        ));
+
+    let requests = server.received_requests().await.unwrap_or_default();
+    assert_eq!(requests.len(), 0);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exec_json_reports_parsed_final_output() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+
+    let schema_path = test.cwd_path().join("schema.json");
+    std::fs::write(
+        &schema_path,
+        serde_json::to_vec(&serde_json::json!({
+            "type": "object",
+            "properties": { "answer": { "$ref": "#/$defs/answer" } },
+            "required": ["answer"],
+            "additionalProperties": false,
+            "$defs": { "answer": { "type": "string" } }
+        }))?,
+    )?;
+    let last_message_path = test.cwd_path().join("last.json");
+
+    let reply = r#"{"answer": "fixture hello"}"#;
+    let server = responses::start_mock_server().await;
+    let body = responses::sse(vec![
+        responses::ev_response_created("resp1"),
+        responses::ev_assistant_message("m1", reply),
+        responses::ev_completed("resp1"),
+    ]);
+    responses::mount_sse_once(&server, body).await;
+
+    let output = test
+        .cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--json")
+        .arg("--output-schema")
+        .arg(&schema_path)
+        .arg("-o")
+        .arg(&last_message_path)
+        .arg("tell me a joke")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let turn_completed = String::from_utf8(output)?
+        .lines()
+        .map(serde_json::from_str::<Value>)
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .find(|event| event["type"] == "turn.completed")
+        .expect("turn.completed event");
+    assert_eq!(
+        turn_completed["final_output"],
+        serde_json::json!({ "answer": "fixture hello" })
+    );
+    assert_eq!(std::fs::read_to_string(&last_message_path)?, reply);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exec_retries_once_then_fails_on_schema_violation() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+
+    let schema_path = test.cwd_path().join("schema.json");
+    std::fs::write(
+        &schema_path,
+        serde_json::to_vec(&serde_json::json!({
+            "type": "object",
+            "properties": { "answer": { "type": "string" } },
+            "required": ["answer"],
+            "additionalProperties": false
+        }))?,
+    )?;
+
+    let server = responses::start_mock_server().await;
+    let reply = |id: &str| {
+        responses::sse(vec![
+            responses::ev_response_created(id),
+            responses::ev_assistant_message("m1", "not json"),
+            responses::ev_completed(id),
+        ])
+    };
+    let response_mock =
+        responses::mount_sse_sequence(&server, vec![reply("resp1"), reply("resp2")]).await;
+
+    test.cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--output-schema")
+        .arg(&schema_path)
+        .arg("tell me a joke")
+        .assert()
+        .code(1)
+        .stderr(predicates::str::contains(
+            "Final message does not match the output schema: the reply is not valid JSON",
+        ));
+
+    let requests = response_mock.requests();
+    assert_eq!(requests.len(), 2);
+    let retry_prompt = requests[1]
+        .message_input_texts("user")
+        .pop()
+        .expect("retry prompt");
+    assert!(
+        retry_prompt.starts_with("Your final message did not match the required output schema"),
+        "unexpected retry prompt: {retry_prompt}"
+    );
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 #![allow(clippy::unwrap_used, clippy::expect_used)]
 use anyhow::Context;
+use core_test_support::responses;
 use core_test_support::test_codex_exec::test_codex_exec;
 use serde_json::Value;
 use std::path::Path;
@@ -255,3 +256,200 @@ fn exec_resume_preserves_cli_configuration_overrides() -> anyhow::Result<()> {
     assert!(content.contains(&marker2));
     Ok(())
 }
+
+/// The `thread_id` from the `thread.started` line of `--json` output.
+fn thread_started_id(stdout: &[u8]) -> String {
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .find(|event| event.get("type").and_then(Value::as_str) == Some("thread.started"))
+        .and_then(|event| event.get("thread_id")?.as_str().map(ToString::to_string))
+        .expect("missing thread.started event")
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exec_resume_flag_sends_prior_transcript() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+    let server = responses::start_mock_server().await;
+    let response_mock = responses::mount_sse_sequence(
+        &server,
+        vec![
+            responses::sse(vec![
+                responses::ev_response_created("resp1"),
+                responses::ev_assistant_message("m1", "first answer"),
+                responses::ev_completed("resp1"),
+            ]),
+            responses::sse(vec![
+                responses::ev_response_created("resp2"),
+                responses::ev_assistant_message("m2", "second answer"),
+                responses::ev_completed("resp2"),
+            ]),
+        ],
+    )
+    .await;
+
+    let output = test
+        .cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--json")
+        .arg("first question")
+        .output()?;
+    assert!(output.status.success(), "first run failed: {output:?}");
+    let session_id = thread_started_id(&output.stdout);
+
+    let output = test
+        .cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--json")
+        .arg("--resume")
+        .arg(&session_id)
+        .arg("second question")
+        .output()?;
+    assert!(output.status.success(), "resumed run failed: {output:?}");
+    assert_eq!(thread_started_id(&output.stdout), session_id);
+
+    let requests = response_mock.requests();
+    assert_eq!(requests.len(), 2);
+    let user_texts = requests[1].message_input_texts("user");
+    let first = user_texts
+        .iter()
+        .position(|text| text == "first question")
+        .expect("first prompt missing from resumed request");
+    let second = user_texts
+        .iter()
+        .position(|text| text == "second question")
+        .expect("second prompt missing from resumed request");
+    assert!(first < second, "prompts out of order: {user_texts:?}");
+    let assistant_texts: Vec<Value> = requests[1]
+        .inputs_of_type("message")
+        .into_iter()
+        .filter(|item| item.get("role").and_then(Value::as_str) == Some("assistant"))
+        .map(|item| item["content"].clone())
+        .collect();
+    assert_eq!(
+        assistant_texts,
+        vec![serde_json::json!([{ "type": "output_text", "text": "first answer" }])]
+    );
+    Ok(())
+}
+
+/// Write a rollout the way the interactive TUI records a prompt with an
+/// attached image. Returns the session id.
+fn write_tui_rollout_with_image(codex_home: &Path, text: &str, image_url: &str) -> String {
+    let session_id = Uuid::new_v4().to_string();
+    let dir = codex_home.join("sessions/2025/01/02");
+    std::fs::create_dir_all(&dir).unwrap();
+    let timestamp = "2025-01-02T03:04:05Z";
+    let lines = [
+        serde_json::json!({
+            "timestamp": timestamp,
+            "type": "session_meta",
+            "payload": {
+                "id": session_id,
+                "timestamp": timestamp,
+                "cwd": "/",
+                "originator": "codex_cli_rs",
+                "cli_version": "0.0.0",
+                "instructions": null,
+                "source": "cli",
+                "model_provider": "openai"
+            }
+        }),
+        serde_json::json!({
+            "timestamp": timestamp,
+            "type": "response_item",
+            "payload": {
+                "type": "message",
+                "role": "user",
+                "content": [
+                    { "type": "input_text", "text": text },
+                    { "type": "input_image", "image_url": image_url }
+                ]
+            }
+        }),
+        serde_json::json!({
+            "timestamp": timestamp,
+            "type": "event_msg",
+            "payload": {
+                "type": "user_message",
+                "message": text,
+                "images": [image_url]
+            }
+        }),
+    ];
+    let contents: String = lines.iter().map(|line| format!("{line}\n")).collect();
+    std::fs::write(
+        dir.join(format!("rollout-2025-01-02T03-04-05-{session_id}.jsonl")),
+        contents,
+    )
+    .unwrap();
+    session_id
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exec_resume_replays_images_from_a_tui_session() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+    let server = responses::start_mock_server().await;
+    let response_mock = responses::mount_sse_sequence(
+        &server,
+        vec![responses::sse(vec![
+            responses::ev_response_created("resp1"),
+            responses::ev_assistant_message("m1", "looks fine"),
+            responses::ev_completed("resp1"),
+        ])],
+    )
+    .await;
+    let image_url = "data:image/png;base64,iVBORw0KGgo=";
+    let session_id =
+        write_tui_rollout_with_image(test.home_path(), "what is in this image?", image_url);
+
+    let output = test
+        .cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--json")
+        .arg("--resume")
+        .arg(&session_id)
+        .arg("and now?")
+        .output()?;
+    assert!(output.status.success(), "resumed run failed: {output:?}");
+    assert_eq!(thread_started_id(&output.stdout), session_id);
+
+    let request = response_mock.single_request();
+    let prior_prompt = request
+        .inputs_of_type("message")
+        .into_iter()
+        .find(|item| {
+            item["content"]
+                .as_array()
+                .is_some_and(|content| content.iter().any(|part| part["type"] == "input_image"))
+        })
+        .expect("image prompt missing from resumed request");
+    assert_eq!(
+        prior_prompt["content"],
+        serde_json::json!([
+            { "type": "input_text", "text": "what is in this image?" },
+            { "type": "input_image", "image_url": image_url }
+        ])
+    );
+    let user_texts = request.message_input_texts("user");
+    assert_eq!(user_texts.last().map(String::as_str), Some("and now?"));
+    Ok(())
+}
+
+#[test]
+fn exec_resume_unknown_id_exits_with_status_2() {
+    let test = test_codex_exec();
+    let missing = Uuid::new_v4().to_string();
+
+    test.cmd()
+        .env("OPENAI_BASE_URL", "http://unused.local")
+        .arg("--skip-git-repo-check")
+        .arg("--resume")
+        .arg(&missing)
+        .arg("hello")
+        .assert()
+        .code(2)
+        .stderr(predicates::str::contains(format!(
+            "No recorded session found with id {missing}."
+        )));
+}
@@ -0,0 +1,153 @@
+#![cfg(unix)]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use std::path::Path;
+use std::time::Duration;
+use std::time::Instant;
+
+use core_test_support::responses;
+use core_test_support::test_codex_exec::test_codex_exec;
+use serde_json::Value;
+use walkdir::WalkDir;
+use wiremock::Mock;
+use wiremock::matchers::method;
+use wiremock::matchers::path_regex;
+
+/// Contents of the single rollout recorded under `codex_home`.
+fn read_rollout(codex_home: &Path) -> String {
+    let rollouts: Vec<_> = WalkDir::new(codex_home.join("sessions"))
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(".jsonl"))
+        .collect();
+    assert_eq!(rollouts.len(), 1, "expected one rollout: {rollouts:?}");
+    std::fs::read_to_string(rollouts[0].path()).unwrap()
+}
+
+/// A provider that accepts the request but never finishes the response must
+/// not hang `codex-exec` past `--timeout`.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exits_124_when_provider_never_completes() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+
+    let server = responses::start_mock_server().await;
+    Mock::given(method("POST"))
+        .and(path_regex(".*/responses$"))
+        .respond_with(
+            responses::sse_response(responses::sse(vec![responses::ev_response_created(
+                "resp1",
+            )]))
+            .set_delay(Duration::from_secs(120)),
+        )
+        .mount(&server)
+        .await;
+
+    let output = test
+        .cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--json")
+        .arg("--timeout")
+        .arg("1")
+        .arg("tell me something")
+        .timeout(Duration::from_secs(60))
+        .assert()
+        .code(124)
+        .get_output()
+        .stdout
+        .clone();
+
+    let events = String::from_utf8(output)?
+        .lines()
+        .map(serde_json::from_str::<Value>)
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(
+        events
+            .iter()
+            .any(|event| event == &serde_json::json!({ "type": "timeout", "seconds": 1 })),
+        "missing timeout event in {events:?}"
+    );
+    assert!(read_rollout(test.home_path()).contains("tell me something"));
+
+    Ok(())
+}
+
+/// A timeout that fires while watch mode is idle between turns still shuts the
+/// session down, so the completed turn is flushed to the rollout.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn timeout_while_watching_flushes_rollout() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+
+    let server = responses::start_mock_server().await;
+    responses::mount_sse_once(
+        &server,
+        responses::sse(vec![
+            responses::ev_response_created("resp1"),
+            responses::ev_assistant_message("m1", "watched answer"),
+            responses::ev_completed("resp1"),
+        ]),
+    )
+    .await;
+
+    test.cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--watch")
+        .arg("never-changes.txt")
+        .arg("--timeout")
+        .arg("2")
+        .arg("watch this")
+        .timeout(Duration::from_secs(60))
+        .assert()
+        .code(124)
+        .stderr(predicates::str::contains("timed out after 2s"));
+
+    let rollout = read_rollout(test.home_path());
+    assert!(rollout.contains("watch this"), "prompt missing: {rollout}");
+    assert!(
+        rollout.contains("watched answer"),
+        "answer missing: {rollout}"
+    );
+
+    Ok(())
+}
+
+/// When the timeout fires while a command is running, the command is killed
+/// along with the interrupted turn.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn timeout_kills_running_command() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+    let marker = test.cwd_path().join("finished.txt");
+
+    let server = responses::start_mock_server().await;
+    responses::mount_sse_once(
+        &server,
+        responses::sse(vec![
+            responses::ev_response_created("resp1"),
+            responses::ev_shell_command_call("call-1", "sleep 6 && touch finished.txt"),
+            responses::ev_completed("resp1"),
+        ]),
+    )
+    .await;
+
+    let start = Instant::now();
+    test.cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        // The interrupt path is the same with or without a sandbox; bypass it
+        // so the test does not depend on sandbox support on the host.
+        .arg("--dangerously-bypass-approvals-and-sandbox")
+        .arg("-m")
+        .arg("gpt-5.1")
+        .arg("--timeout")
+        .arg("2")
+        .arg("run the command")
+        .timeout(Duration::from_secs(60))
+        .assert()
+        .code(124)
+        .stderr(predicates::str::contains("timed out after 2s"));
+    assert!(start.elapsed() < Duration::from_secs(6));
+
+    // Had the command survived, it would create the marker by now.
+    tokio::time::sleep(Duration::from_secs(6)).await;
+    assert!(!marker.exists(), "command kept running after the timeout");
+
+    Ok(())
+}
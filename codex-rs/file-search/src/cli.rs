@@ -4,6 +4,8 @@ use std::path::PathBuf;
 use clap::ArgAction;
 use clap::Parser;
 
+use crate::SortMode;
+
 /// Fuzzy matches filenames under a directory.
 #[derive(Parser)]
 #[command(version)]
@@ -33,10 +35,19 @@ pub struct Cli {
     #[clap(long, default_value = "2")]
     pub threads: NonZero<usize>,
 
+    /// Report the size and modification time of each match.
+    #[arg(long, default_value = "false")]
+    pub with_metadata: bool,
+
+    /// How to order the matches.
+    #[arg(long, value_enum, default_value_t = SortMode::Score)]
+    pub sort: SortMode,
+
     /// Exclude patterns
     #[arg(short, long, action = ArgAction::Append)]
     pub exclude: Vec<String>,
 
-    /// Search pattern.
-    pub pattern: Option<String>,
+    /// Search patterns. A file matches if it matches any of them; patterns
+    /// may be repeated or given as a comma-separated list.
+    pub patterns: Vec<String>,
 }
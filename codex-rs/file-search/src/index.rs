@@ -0,0 +1,409 @@
+//! In-memory index of the files under a directory, for callers that search the
+//! same tree over and over (e.g. the TUI `@` popup, once per keystroke).
+//!
+//! [`FileIndex::build`] walks the tree once with the same rules as
+//! [`run`](crate::run); [`FileIndex::query`] then scores the cached paths
+//! without touching the filesystem. The index does not watch for changes:
+//! callers check [`FileIndex::is_stale`] and call [`FileIndex::refresh`] when
+//! it returns `true`.
+
+use std::num::NonZero;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::SystemTime;
+
+use nucleo_matcher::Matcher;
+use nucleo_matcher::pattern::Pattern;
+
+use crate::BestMatchesList;
+use crate::CANCEL_CHECK_INTERVAL;
+use crate::FileBudget;
+use crate::FileSearchResults;
+use crate::SearchFilters;
+use crate::WorkerCount;
+use crate::build_walker;
+use crate::create_pattern;
+use crate::create_worker_count;
+use crate::merge_matches;
+use crate::relative_file_path;
+
+/// Smaller indexes are scored on fewer threads; spawning one costs more than
+/// scoring this many paths.
+const MIN_PATHS_PER_WORKER: usize = 4096;
+
+pub struct FileIndex {
+    search_directory: PathBuf,
+    filters: SearchFilters,
+    threads: NonZero<usize>,
+    respect_gitignore: bool,
+    paths: PathArena,
+    /// The last walk stopped at [`SearchFilters::max_file_count`].
+    truncated_walk: bool,
+    /// Modification time of every directory seen by the last walk. Adding,
+    /// removing, or renaming an entry updates its parent's mtime.
+    dir_mtimes: Vec<(PathBuf, SystemTime)>,
+}
+
+impl FileIndex {
+    /// Walk `search_directory` once, with the same settings [`run`](crate::run)
+    /// takes.
+    pub fn build(
+        search_directory: &Path,
+        filters: SearchFilters,
+        threads: NonZero<usize>,
+        respect_gitignore: bool,
+    ) -> anyhow::Result<Self> {
+        let mut index = Self {
+            search_directory: search_directory.to_path_buf(),
+            filters,
+            threads,
+            respect_gitignore,
+            paths: PathArena::default(),
+            truncated_walk: false,
+            dir_mtimes: Vec::new(),
+        };
+        index.refresh()?;
+        Ok(index)
+    }
+
+    /// Walk the directory again, replacing the cached paths.
+    pub fn refresh(&mut self) -> anyhow::Result<()> {
+        let WorkerCount {
+            num_walk_builder_threads,
+            ..
+        } = create_worker_count(self.threads);
+        let walker = build_walker(
+            &self.search_directory,
+            &self.filters,
+            num_walk_builder_threads,
+            self.respect_gitignore,
+        )?;
+        let file_budget = &FileBudget::new(self.filters.max_file_count);
+
+        let search_directory = self.search_directory.as_path();
+        let sink = Mutex::new(Vec::new());
+        walker.run(|| {
+            let mut collector = ChunkCollector {
+                chunk: WalkedChunk::default(),
+                sink: &sink,
+            };
+            Box::new(move |entry| {
+                match &entry {
+                    Ok(dir_entry) if dir_entry.file_type().is_some_and(|ft| ft.is_dir()) => {
+                        if let Ok(modified) = std::fs::metadata(dir_entry.path())
+                            .and_then(|metadata| metadata.modified())
+                        {
+                            collector
+                                .chunk
+                                .dir_mtimes
+                                .push((dir_entry.path().to_path_buf(), modified));
+                        }
+                    }
+                    _ => {
+                        if let Some(path) = relative_file_path(&entry, search_directory) {
+                            if !file_budget.take() {
+                                return ignore::WalkState::Quit;
+                            }
+                            collector.chunk.paths.push(path);
+                        }
+                    }
+                }
+                ignore::WalkState::Continue
+            })
+        });
+
+        let mut paths = PathArena::default();
+        let mut dir_mtimes = Vec::new();
+        for chunk in sink.into_inner().unwrap_or_else(PoisonError::into_inner) {
+            paths.append(chunk.paths);
+            dir_mtimes.extend(chunk.dir_mtimes);
+        }
+        self.paths = paths;
+        self.dir_mtimes = dir_mtimes;
+        self.truncated_walk = file_budget.exceeded();
+        Ok(())
+    }
+
+    /// Whether files may have been added, removed, or renamed since the last
+    /// walk. Only stats the directories seen by that walk, which is much
+    /// cheaper than walking again.
+    pub fn is_stale(&self) -> bool {
+        self.dir_mtimes.iter().any(|(dir, mtime)| {
+            !std::fs::metadata(dir)
+                .and_then(|metadata| metadata.modified())
+                .is_ok_and(|current| current == *mtime)
+        })
+    }
+
+    /// Number of files in the index.
+    pub fn file_count(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Score the cached paths against `pattern_texts` exactly like
+    /// [`run`](crate::run) would. Workers check `cancel_flag` periodically;
+    /// a cancelled query returns no matches.
+    pub fn query(
+        &self,
+        pattern_texts: &[&str],
+        limit: NonZero<usize>,
+        compute_indices: bool,
+        cancel_flag: &AtomicBool,
+    ) -> FileSearchResults {
+        let patterns: Vec<Pattern> = pattern_texts
+            .iter()
+            .map(|pattern_text| create_pattern(pattern_text))
+            .collect();
+        let total = self.paths.len();
+        let num_workers = self
+            .threads
+            .get()
+            .min(total.div_ceil(MIN_PATHS_PER_WORKER))
+            .max(1);
+        let chunk_size = total.div_ceil(num_workers).max(1);
+
+        let best_lists: Vec<BestMatchesList> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..total)
+                .step_by(chunk_size)
+                .map(|start| {
+                    let patterns = patterns.clone();
+                    scope.spawn(move || {
+                        let mut best_list = BestMatchesList::new(
+                            limit.get(),
+                            patterns,
+                            Matcher::new(nucleo_matcher::Config::DEFAULT),
+                        );
+                        let end = (start + chunk_size).min(total);
+                        for (processed, index) in (start..end).enumerate() {
+                            if processed % CANCEL_CHECK_INTERVAL == 0
+                                && cancel_flag.load(Ordering::Relaxed)
+                            {
+                                break;
+                            }
+                            best_list.insert(self.paths.get(index));
+                        }
+                        best_list
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .map(|worker| {
+                    worker
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect()
+        });
+
+        if cancel_flag.load(Ordering::Relaxed) {
+            return FileSearchResults {
+                matches: Vec::new(),
+                total_match_count: 0,
+                truncated_walk: false,
+            };
+        }
+        FileSearchResults {
+            truncated_walk: self.truncated_walk,
+            ..merge_matches(
+                &best_lists,
+                limit,
+                &patterns,
+                pattern_texts,
+                compute_indices,
+            )
+        }
+    }
+}
+
+/// Relative paths stored back to back in one string, so a large tree costs
+/// one allocation instead of one per path.
+#[derive(Default)]
+struct PathArena {
+    text: String,
+    /// End offset of each path in `text`.
+    ends: Vec<usize>,
+}
+
+impl PathArena {
+    fn push(&mut self, path: &str) {
+        self.text.push_str(path);
+        self.ends.push(self.text.len());
+    }
+
+    fn append(&mut self, other: PathArena) {
+        let offset = self.text.len();
+        self.text.push_str(&other.text);
+        self.ends.extend(other.ends.iter().map(|end| end + offset));
+    }
+
+    fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    fn get(&self, index: usize) -> &str {
+        let start = index.checked_sub(1).map_or(0, |prev| self.ends[prev]);
+        &self.text[start..self.ends[index]]
+    }
+}
+
+#[derive(Default)]
+struct WalkedChunk {
+    paths: PathArena,
+    dir_mtimes: Vec<(PathBuf, SystemTime)>,
+}
+
+/// One walker thread's results, handed over when the walker drops the
+/// thread's visitor.
+struct ChunkCollector<'s> {
+    chunk: WalkedChunk,
+    sink: &'s Mutex<Vec<WalkedChunk>>,
+}
+
+impl Drop for ChunkCollector<'_> {
+    fn drop(&mut self) {
+        let chunk = std::mem::take(&mut self.chunk);
+        self.sink
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(chunk);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run;
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn limit() -> NonZero<usize> {
+        NonZero::new(20).expect("limit")
+    }
+
+    fn threads() -> NonZero<usize> {
+        NonZero::new(2).expect("threads")
+    }
+
+    /// Score, path, and indices of each match, in order.
+    fn summarize(results: &FileSearchResults) -> Vec<(u32, String, Option<Vec<u32>>)> {
+        results
+            .matches
+            .iter()
+            .map(|m| (m.score, m.path.clone(), m.indices.clone()))
+            .collect()
+    }
+
+    fn query(index: &FileIndex, patterns: &[&str]) -> FileSearchResults {
+        index.query(patterns, limit(), true, &AtomicBool::new(false))
+    }
+
+    #[test]
+    fn query_scores_like_a_fresh_walk() {
+        let dir = TempDir::new().expect("tempdir");
+        std::fs::create_dir_all(dir.path().join("src/config")).expect("mkdir");
+        for name in [
+            "src/config/mod.rs",
+            "src/config/types.rs",
+            "src/main.rs",
+            "README.md",
+        ] {
+            std::fs::write(dir.path().join(name), "").expect("write");
+        }
+        let index =
+            FileIndex::build(dir.path(), SearchFilters::default(), threads(), true).expect("index");
+
+        for patterns in [&["config"][..], &["main", "types"][..]] {
+            let walked = run(
+                patterns,
+                limit(),
+                dir.path(),
+                &SearchFilters::default(),
+                threads(),
+                Arc::new(AtomicBool::new(false)),
+                true,
+                true,
+                false,
+                crate::SortMode::Score,
+            )
+            .expect("search");
+            let indexed = query(&index, patterns);
+            assert_eq!(summarize(&indexed), summarize(&walked));
+            assert_eq!(indexed.total_match_count, walked.total_match_count);
+        }
+    }
+
+    #[test]
+    fn second_query_does_not_touch_the_filesystem() {
+        let dir = TempDir::new().expect("tempdir");
+        for d in 0..250 {
+            let sub = dir.path().join(format!("dir_{d:03}"));
+            std::fs::create_dir(&sub).expect("mkdir");
+            for f in 0..200 {
+                std::fs::write(sub.join(format!("file_{f:03}.txt")), "").expect("write");
+            }
+        }
+        let mut index =
+            FileIndex::build(dir.path(), SearchFilters::default(), threads(), true).expect("index");
+        assert_eq!(index.file_count(), 50_000);
+        let first = query(&index, &["dir_042/file_017"]);
+        assert_eq!(
+            first.matches.first().map(|m| m.path.as_str()),
+            Some("dir_042/file_017.txt")
+        );
+
+        // With the tree gone, only the cache can answer.
+        for d in 0..250 {
+            std::fs::remove_dir_all(dir.path().join(format!("dir_{d:03}"))).expect("rm");
+        }
+        let second = query(&index, &["dir_042/file_017"]);
+        assert_eq!(summarize(&second), summarize(&first));
+        assert_eq!(second.total_match_count, first.total_match_count);
+
+        assert!(index.is_stale());
+        index.refresh().expect("refresh");
+        assert_eq!(index.file_count(), 0);
+        assert!(!index.is_stale());
+    }
+
+    #[test]
+    fn adding_a_file_makes_the_index_stale() {
+        let dir = TempDir::new().expect("tempdir");
+        std::fs::create_dir(dir.path().join("nested")).expect("mkdir");
+        std::fs::write(dir.path().join("nested/old.rs"), "").expect("write");
+        let mut index =
+            FileIndex::build(dir.path(), SearchFilters::default(), threads(), true).expect("index");
+        assert!(!index.is_stale());
+
+        std::fs::write(dir.path().join("nested/new.rs"), "").expect("write");
+        assert!(index.is_stale());
+        index.refresh().expect("refresh");
+        assert!(!index.is_stale());
+        assert_eq!(
+            query(&index, &["new"])
+                .matches
+                .iter()
+                .map(|m| m.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["nested/new.rs"]
+        );
+    }
+
+    #[test]
+    fn cancelled_query_returns_no_matches() {
+        let dir = TempDir::new().expect("tempdir");
+        std::fs::write(dir.path().join("config.rs"), "").expect("write");
+        let index =
+            FileIndex::build(dir.path(), SearchFilters::default(), threads(), true).expect("index");
+
+        let results = index.query(&["config"], limit(), false, &AtomicBool::new(true));
+
+        assert_eq!(summarize(&results), Vec::new());
+        assert_eq!(results.total_match_count, 0);
+    }
+}
@@ -1,4 +1,6 @@
 use ignore::WalkBuilder;
+use ignore::WalkParallel;
+use ignore::overrides::Override;
 use ignore::overrides::OverrideBuilder;
 use nucleo_matcher::Matcher;
 use nucleo_matcher::Utf32Str;
@@ -7,6 +9,7 @@ use nucleo_matcher::pattern::CaseMatching;
 use nucleo_matcher::pattern::Normalization;
 use nucleo_matcher::pattern::Pattern;
 use serde::Serialize;
+use serde::Serializer;
 use std::cell::UnsafeCell;
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
@@ -16,11 +19,18 @@ use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
 use tokio::process::Command;
 
 mod cli;
+mod index;
+mod path_filter;
 
 pub use cli::Cli;
+pub use index::FileIndex;
+pub use path_filter::CODEXIGNORE_FILENAME;
+pub use path_filter::PathFilter;
 
 /// A single match result returned from the search.
 ///
@@ -32,18 +42,97 @@ pub use cli::Cli;
 ///   guidance from `nucleo_matcher::Pattern::indices`: they are
 ///   unique and sorted in ascending order so that callers can use
 ///   them directly for highlighting.
+/// * `matched_pattern` – When several patterns were searched, the one that
+///   produced `score` (and `indices`).
+/// * `size`, `modified` – Only filled when the caller of [`run`] asks for
+///   metadata. `modified` stays `None` where the platform does not report
+///   modification times, and serializes as seconds since the Unix epoch.
 #[derive(Debug, Clone, Serialize)]
 pub struct FileMatch {
     pub score: u32,
     pub path: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub indices: Option<Vec<u32>>, // Sorted & deduplicated when present
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_epoch_seconds"
+    )]
+    pub modified: Option<SystemTime>,
+}
+
+fn serialize_epoch_seconds<S: Serializer>(
+    modified: &Option<SystemTime>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match modified.and_then(|modified| modified.duration_since(UNIX_EPOCH).ok()) {
+        Some(since_epoch) => serializer.serialize_some(&since_epoch.as_secs()),
+        None => serializer.serialize_none(),
+    }
 }
 
 #[derive(Debug)]
 pub struct FileSearchResults {
     pub matches: Vec<FileMatch>,
     pub total_match_count: usize,
+    /// The walk stopped at [`SearchFilters::max_file_count`], so some files
+    /// were never searched and the results are partial.
+    pub truncated_walk: bool,
+}
+
+/// Which files a search walks, on top of the ignore rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchFilters {
+    /// Globs a file must match to be searched; empty means every file.
+    pub include: Vec<String>,
+    /// Globs of files and directories to skip. As in `.gitignore`, the last
+    /// matching rule wins, and excludes come after includes.
+    pub exclude: Vec<String>,
+    /// Follow symlinks to search their contents.
+    pub follow_symlinks: bool,
+    /// Deepest level to descend to; files directly in the search directory
+    /// are at depth 1.
+    pub max_depth: Option<usize>,
+    /// Stop walking after this many files.
+    pub max_file_count: Option<usize>,
+    /// `.codexignore` rules. Unlike `exclude`, they apply even when ignore
+    /// files are not respected, and their `!` patterns only re-include.
+    pub path_filter: PathFilter,
+}
+
+impl Default for SearchFilters {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: Vec::new(),
+            follow_symlinks: true,
+            max_depth: None,
+            max_file_count: None,
+            path_filter: PathFilter::default(),
+        }
+    }
+}
+
+impl SearchFilters {
+    /// Check that every include and exclude glob compiles, so a bad pattern
+    /// is reported up front instead of failing each search.
+    pub fn validate(&self) -> Result<(), ignore::Error> {
+        build_overrides(Path::new("."), self).map(|_| ())
+    }
+}
+
+/// Order of the returned matches. The best `limit` matches are always picked
+/// by score; the mode only changes how they are listed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortMode {
+    /// Highest score first.
+    #[default]
+    Score,
+    /// Most recently modified first, then by score.
+    RecencyThenScore,
 }
 
 pub trait Reporter {
@@ -54,13 +143,15 @@ pub trait Reporter {
 
 pub async fn run_main<T: Reporter>(
     Cli {
-        pattern,
+        patterns: pattern_args,
         limit,
         cwd,
         compute_indices,
         json: _,
         exclude,
         threads,
+        with_metadata,
+        sort,
     }: Cli,
     reporter: T,
 ) -> anyhow::Result<()> {
@@ -68,45 +159,53 @@ pub async fn run_main<T: Reporter>(
         Some(dir) => dir,
         None => std::env::current_dir()?,
     };
-    let pattern_text = match pattern {
-        Some(pattern) => pattern,
-        None => {
-            reporter.warn_no_search_pattern(&search_directory);
-            #[cfg(unix)]
-            Command::new("ls")
-                .arg("-al")
-                .current_dir(search_directory)
+    let patterns: Vec<&str> = pattern_args
+        .iter()
+        .flat_map(|pattern| split_patterns(pattern))
+        .collect();
+    if patterns.is_empty() {
+        reporter.warn_no_search_pattern(&search_directory);
+        #[cfg(unix)]
+        Command::new("ls")
+            .arg("-al")
+            .current_dir(search_directory)
+            .stdout(std::process::Stdio::inherit())
+            .stderr(std::process::Stdio::inherit())
+            .status()
+            .await?;
+        #[cfg(windows)]
+        {
+            Command::new("cmd")
+                .arg("/c")
+                .arg(search_directory)
                 .stdout(std::process::Stdio::inherit())
                 .stderr(std::process::Stdio::inherit())
                 .status()
                 .await?;
-            #[cfg(windows)]
-            {
-                Command::new("cmd")
-                    .arg("/c")
-                    .arg(search_directory)
-                    .stdout(std::process::Stdio::inherit())
-                    .stderr(std::process::Stdio::inherit())
-                    .status()
-                    .await?;
-            }
-            return Ok(());
         }
-    };
+        return Ok(());
+    }
 
     let cancel_flag = Arc::new(AtomicBool::new(false));
+    let filters = SearchFilters {
+        exclude,
+        ..SearchFilters::default()
+    };
     let FileSearchResults {
         total_match_count,
         matches,
+        truncated_walk: _,
     } = run(
-        &pattern_text,
+        &patterns,
         limit,
         &search_directory,
-        exclude,
+        &filters,
         threads,
         cancel_flag,
         compute_indices,
         true,
+        with_metadata,
+        sort,
     )?;
     let match_count = matches.len();
     let matches_truncated = total_match_count > match_count;
@@ -121,20 +220,73 @@ pub async fn run_main<T: Reporter>(
     Ok(())
 }
 
+/// Split a comma-separated list of patterns, dropping empty entries.
+pub fn split_patterns(text: &str) -> Vec<&str> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .collect()
+}
+
+/// Search for a single pattern. See [`run`].
+#[allow(clippy::too_many_arguments)]
+pub fn run_pattern(
+    pattern_text: &str,
+    limit: NonZero<usize>,
+    search_directory: &Path,
+    exclude: Vec<String>,
+    threads: NonZero<usize>,
+    cancel_flag: Arc<AtomicBool>,
+    compute_indices: bool,
+    respect_gitignore: bool,
+) -> anyhow::Result<FileSearchResults> {
+    run(
+        &[pattern_text],
+        limit,
+        search_directory,
+        &SearchFilters {
+            exclude,
+            ..SearchFilters::default()
+        },
+        threads,
+        cancel_flag,
+        compute_indices,
+        respect_gitignore,
+        false,
+        SortMode::Score,
+    )
+}
+
+/// Find files matching any of `patterns`. Each file is scored against every
+/// pattern and reported once, with its best score; on a tie the earlier
+/// pattern wins.
+///
 /// The worker threads will periodically check `cancel_flag` to see if they
 /// should stop processing files.
+///
+/// With `with_metadata`, the returned matches are stat'ed for their size and
+/// modification time; matches that can no longer be stat'ed (e.g. deleted
+/// since the walk) are dropped and no longer counted in `total_match_count`.
+/// [`SortMode::RecencyThenScore`] needs the
+/// modification times, so it implies `with_metadata`. Without either, no file
+/// is stat'ed beyond what the walk itself does.
 #[allow(clippy::too_many_arguments)]
 pub fn run(
-    pattern_text: &str,
+    pattern_texts: &[&str],
     limit: NonZero<usize>,
     search_directory: &Path,
-    exclude: Vec<String>,
+    filters: &SearchFilters,
     threads: NonZero<usize>,
     cancel_flag: Arc<AtomicBool>,
     compute_indices: bool,
     respect_gitignore: bool,
+    with_metadata: bool,
+    sort_mode: SortMode,
 ) -> anyhow::Result<FileSearchResults> {
-    let pattern = create_pattern(pattern_text);
+    let patterns: Vec<Pattern> = pattern_texts
+        .iter()
+        .map(|pattern_text| create_pattern(pattern_text))
+        .collect();
     // Create one BestMatchesList per worker thread so that each worker can
     // operate independently. The results across threads will be merged when
     // the traversal is complete.
@@ -146,43 +298,19 @@ pub fn run(
         .map(|_| {
             UnsafeCell::new(BestMatchesList::new(
                 limit.get(),
-                pattern.clone(),
+                patterns.clone(),
                 Matcher::new(nucleo_matcher::Config::DEFAULT),
             ))
         })
         .collect();
 
-    // Use the same tree-walker library that ripgrep uses. We use it directly so
-    // that we can leverage the parallelism it provides.
-    let mut walk_builder = WalkBuilder::new(search_directory);
-    walk_builder
-        .threads(num_walk_builder_threads)
-        // Allow hidden entries.
-        .hidden(false)
-        // Follow symlinks to search their contents.
-        .follow_links(true)
-        // Don't require git to be present to apply to apply git-related ignore rules.
-        .require_git(false);
-    if !respect_gitignore {
-        walk_builder
-            .git_ignore(false)
-            .git_global(false)
-            .git_exclude(false)
-            .ignore(false)
-            .parents(false);
-    }
-
-    if !exclude.is_empty() {
-        let mut override_builder = OverrideBuilder::new(search_directory);
-        for exclude in exclude {
-            // The `!` prefix is used to indicate an exclude pattern.
-            let exclude_pattern = format!("!{exclude}");
-            override_builder.add(&exclude_pattern)?;
-        }
-        let override_matcher = override_builder.build()?;
-        walk_builder.overrides(override_matcher);
-    }
-    let walker = walk_builder.build_parallel();
+    let walker = build_walker(
+        search_directory,
+        filters,
+        num_walk_builder_threads,
+        respect_gitignore,
+    )?;
+    let file_budget = &FileBudget::new(filters.max_file_count);
 
     // Each worker created by `WalkParallel::run()` will have its own
     // `BestMatchesList` to update.
@@ -194,18 +322,20 @@ pub fn run(
 
         // Each worker keeps a local counter so we only read the atomic flag
         // every N entries which is cheaper than checking on every file.
-        const CHECK_INTERVAL: usize = 1024;
         let mut processed = 0;
 
         let cancel = cancel_flag.clone();
 
         Box::new(move |entry| {
-            if let Some(path) = get_file_path(&entry, search_directory) {
+            if let Some(path) = relative_file_path(&entry, search_directory) {
+                if !file_budget.take() {
+                    return ignore::WalkState::Quit;
+                }
                 best_list.insert(path);
             }
 
             processed += 1;
-            if processed % CHECK_INTERVAL == 0 && cancel.load(Ordering::Relaxed) {
+            if processed % CANCEL_CHECK_INTERVAL == 0 && cancel.load(Ordering::Relaxed) {
                 ignore::WalkState::Quit
             } else {
                 ignore::WalkState::Continue
@@ -213,52 +343,192 @@ pub fn run(
         })
     });
 
-    fn get_file_path<'a>(
-        entry_result: &'a Result<ignore::DirEntry, ignore::Error>,
-        search_directory: &std::path::Path,
-    ) -> Option<&'a str> {
-        let entry = match entry_result {
-            Ok(e) => e,
-            Err(_) => return None,
-        };
-        if entry.file_type().is_some_and(|ft| ft.is_dir()) {
-            return None;
-        }
-        let path = entry.path();
-        match path.strip_prefix(search_directory) {
-            Ok(rel_path) => rel_path.to_str(),
-            Err(_) => None,
-        }
-    }
-
     // If the cancel flag is set, we return early with an empty result.
     if cancel_flag.load(Ordering::Relaxed) {
         return Ok(FileSearchResults {
             matches: Vec::new(),
             total_match_count: 0,
+            truncated_walk: false,
         });
     }
 
-    // Merge results across best_matchers_per_worker.
-    let mut global_heap: BinaryHeap<Reverse<(u32, String)>> = BinaryHeap::new();
+    let mut results = merge_matches(
+        best_matchers_per_worker
+            .iter()
+            .map(|best_list_cell| unsafe { &*best_list_cell.get() }),
+        limit,
+        &patterns,
+        pattern_texts,
+        compute_indices,
+    );
+    results.truncated_walk = file_budget.exceeded();
+    if with_metadata || sort_mode != SortMode::Score {
+        add_file_metadata(&mut results, search_directory);
+        sort_matches(&mut results.matches, sort_mode);
+    }
+    Ok(results)
+}
+
+/// Fill in `size` and `modified`, dropping matches that cannot be stat'ed
+/// from both the list and `total_match_count`.
+fn add_file_metadata(results: &mut FileSearchResults, search_directory: &Path) {
+    let before = results.matches.len();
+    results.matches = std::mem::take(&mut results.matches)
+        .into_iter()
+        .filter_map(|file_match| {
+            let metadata = std::fs::metadata(search_directory.join(&file_match.path)).ok()?;
+            Some(FileMatch {
+                size: Some(metadata.len()),
+                modified: metadata.modified().ok(),
+                ..file_match
+            })
+        })
+        .collect();
+    let dropped = before - results.matches.len();
+    results.total_match_count = results.total_match_count.saturating_sub(dropped);
+}
+
+/// How many entries a worker handles between two reads of the cancel flag.
+const CANCEL_CHECK_INTERVAL: usize = 1024;
+
+/// Use the same tree-walker library that ripgrep uses. We use it directly so
+/// that we can leverage the parallelism it provides.
+fn build_walker(
+    search_directory: &Path,
+    filters: &SearchFilters,
+    threads: usize,
+    respect_gitignore: bool,
+) -> anyhow::Result<WalkParallel> {
+    let mut walk_builder = WalkBuilder::new(search_directory);
+    walk_builder
+        .threads(threads)
+        // Allow hidden entries.
+        .hidden(false)
+        .follow_links(filters.follow_symlinks)
+        .max_depth(filters.max_depth)
+        // Don't require git to be present to apply to apply git-related ignore rules.
+        .require_git(false);
+    if !respect_gitignore {
+        walk_builder
+            .git_ignore(false)
+            .git_global(false)
+            .git_exclude(false)
+            .ignore(false)
+            .parents(false);
+    }
+
+    if !filters.include.is_empty() || !filters.exclude.is_empty() {
+        walk_builder.overrides(build_overrides(search_directory, filters)?);
+    }
+    if !filters.path_filter.is_empty() {
+        let path_filter = filters.path_filter.clone();
+        walk_builder.filter_entry(move |entry| {
+            let is_dir = entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_dir());
+            !path_filter.is_excluded(entry.path(), is_dir)
+        });
+    }
+    Ok(walk_builder.build_parallel())
+}
+
+/// Compile the include and exclude globs of `filters` relative to `root`.
+fn build_overrides(root: &Path, filters: &SearchFilters) -> Result<Override, ignore::Error> {
+    // Overrides use gitignore semantics: the last matching glob wins, and
+    // once any plain (include) glob exists, files matching none are skipped.
+    let mut override_builder = OverrideBuilder::new(root);
+    for include in &filters.include {
+        override_builder.add(include)?;
+    }
+    for exclude in &filters.exclude {
+        // The `!` prefix is used to indicate an exclude pattern.
+        let exclude_pattern = format!("!{exclude}");
+        override_builder.add(&exclude_pattern)?;
+    }
+    override_builder.build()
+}
+
+/// Counts walked files against [`SearchFilters::max_file_count`], shared by
+/// all walker threads.
+struct FileBudget {
+    max_file_count: Option<usize>,
+    taken: AtomicUsize,
+    exceeded: AtomicBool,
+}
+
+impl FileBudget {
+    fn new(max_file_count: Option<usize>) -> Self {
+        Self {
+            max_file_count,
+            taken: AtomicUsize::new(0),
+            exceeded: AtomicBool::new(false),
+        }
+    }
+
+    /// Whether one more file may be searched. Once this returns `false` the
+    /// walk should stop.
+    fn take(&self) -> bool {
+        let Some(max_file_count) = self.max_file_count else {
+            return true;
+        };
+        if self.taken.fetch_add(1, Ordering::Relaxed) < max_file_count {
+            return true;
+        }
+        self.exceeded.store(true, Ordering::Relaxed);
+        false
+    }
+
+    /// Whether the walk found more files than it was allowed to search.
+    fn exceeded(&self) -> bool {
+        self.exceeded.load(Ordering::Relaxed)
+    }
+}
+
+/// Path of a walked file relative to `search_directory`; `None` for
+/// directories and errors.
+fn relative_file_path<'a>(
+    entry_result: &'a Result<ignore::DirEntry, ignore::Error>,
+    search_directory: &Path,
+) -> Option<&'a str> {
+    let entry = match entry_result {
+        Ok(e) => e,
+        Err(_) => return None,
+    };
+    if entry.file_type().is_some_and(|ft| ft.is_dir()) {
+        return None;
+    }
+    let path = entry.path();
+    match path.strip_prefix(search_directory) {
+        Ok(rel_path) => rel_path.to_str(),
+        Err(_) => None,
+    }
+}
+
+/// Merge the per-worker lists into the overall best `limit` matches.
+fn merge_matches<'a>(
+    best_lists: impl IntoIterator<Item = &'a BestMatchesList>,
+    limit: NonZero<usize>,
+    patterns: &[Pattern],
+    pattern_texts: &[&str],
+    compute_indices: bool,
+) -> FileSearchResults {
+    let mut global_heap: BinaryHeap<Reverse<ScoredPath>> = BinaryHeap::new();
     let mut total_match_count = 0;
-    for best_list_cell in best_matchers_per_worker.iter() {
-        let best_list = unsafe { &*best_list_cell.get() };
+    for best_list in best_lists {
         total_match_count += best_list.num_matches;
-        for &Reverse((score, ref line)) in best_list.binary_heap.iter() {
+        for Reverse(scored) in best_list.binary_heap.iter() {
             if global_heap.len() < limit.get() {
-                global_heap.push(Reverse((score, line.clone())));
+                global_heap.push(Reverse(scored.clone()));
             } else if let Some(min_element) = global_heap.peek()
-                && score > min_element.0.0
+                && scored.0 > min_element.0.0
             {
                 global_heap.pop();
-                global_heap.push(Reverse((score, line.clone())));
+                global_heap.push(Reverse(scored.clone()));
             }
         }
     }
 
-    let mut raw_matches: Vec<(u32, String)> = global_heap.into_iter().map(|r| r.0).collect();
-    sort_matches(&mut raw_matches);
+    let raw_matches: Vec<ScoredPath> = global_heap.into_iter().map(|r| r.0).collect();
 
     // Transform into `FileMatch`, optionally computing indices.
     let mut matcher = if compute_indices {
@@ -267,16 +537,18 @@ pub fn run(
         None
     };
 
-    let matches: Vec<FileMatch> = raw_matches
+    let mut matches: Vec<FileMatch> = raw_matches
         .into_iter()
-        .map(|(score, path)| {
+        .map(|(score, path, pattern_index)| {
             let indices = if compute_indices {
                 let mut buf = Vec::<char>::new();
                 let haystack: Utf32Str<'_> = Utf32Str::new(&path, &mut buf);
                 let mut idx_vec: Vec<u32> = Vec::new();
                 if let Some(ref mut m) = matcher {
                     // Ignore the score returned from indices – we already have `score`.
-                    pattern.indices(haystack, m, &mut idx_vec);
+                    // Use the pattern that produced `score` so the
+                    // highlighted characters agree with it.
+                    patterns[pattern_index].indices(haystack, m, &mut idx_vec);
                 }
                 idx_vec.sort_unstable();
                 idx_vec.dedup();
@@ -285,26 +557,39 @@ pub fn run(
                 None
             };
 
+            let matched_pattern =
+                (pattern_texts.len() > 1).then(|| pattern_texts[pattern_index].to_string());
             FileMatch {
                 score,
                 path,
                 indices,
+                matched_pattern,
+                size: None,
+                modified: None,
             }
         })
         .collect();
+    sort_matches(&mut matches, SortMode::Score);
 
-    Ok(FileSearchResults {
+    FileSearchResults {
         matches,
         total_match_count,
-    })
+        truncated_walk: false,
+    }
 }
 
-/// Sort matches in-place by descending score, then ascending path.
-fn sort_matches(matches: &mut [(u32, String)]) {
-    matches.sort_by(cmp_by_score_desc_then_path_asc::<(u32, String), _, _>(
-        |t| t.0,
-        |t| t.1.as_str(),
-    ));
+/// Sort matches in-place by descending score, then ascending path. With
+/// [`SortMode::RecencyThenScore`], the most recently modified files come
+/// first; files without a modification time go last.
+fn sort_matches(matches: &mut [FileMatch], sort_mode: SortMode) {
+    let mut by_score =
+        cmp_by_score_desc_then_path_asc::<FileMatch, _, _>(|m| m.score, |m| m.path.as_str());
+    match sort_mode {
+        SortMode::Score => matches.sort_by(by_score),
+        SortMode::RecencyThenScore => {
+            matches.sort_by(|a, b| b.modified.cmp(&a.modified).then_with(|| by_score(a, b)))
+        }
+    }
 }
 
 /// Returns a comparator closure suitable for `slice.sort_by(...)` that orders
@@ -324,24 +609,27 @@ where
     }
 }
 
-/// Maintains the `max_count` best matches for a given pattern.
+/// Score, path, and index of the pattern that produced the score.
+type ScoredPath = (u32, String, usize);
+
+/// Maintains the `max_count` best matches for the given patterns.
 struct BestMatchesList {
     max_count: usize,
     num_matches: usize,
-    pattern: Pattern,
+    patterns: Vec<Pattern>,
     matcher: Matcher,
-    binary_heap: BinaryHeap<Reverse<(u32, String)>>,
+    binary_heap: BinaryHeap<Reverse<ScoredPath>>,
 
     /// Internal buffer for converting strings to UTF-32.
     utf32buf: Vec<char>,
 }
 
 impl BestMatchesList {
-    fn new(max_count: usize, pattern: Pattern, matcher: Matcher) -> Self {
+    fn new(max_count: usize, patterns: Vec<Pattern>, matcher: Matcher) -> Self {
         Self {
             max_count,
             num_matches: 0,
-            pattern,
+            patterns,
             matcher,
             binary_heap: BinaryHeap::new(),
             utf32buf: Vec::<char>::new(),
@@ -349,21 +637,37 @@ impl BestMatchesList {
     }
 
     fn insert(&mut self, line: &str) {
-        let haystack: Utf32Str<'_> = Utf32Str::new(line, &mut self.utf32buf);
-        if let Some(score) = self.pattern.score(haystack, &mut self.matcher) {
+        if let Some((score, pattern_index)) = self.best_score(line) {
             // In the tests below, we verify that score() returns None for a
             // non-match, so we can categorically increment the count here.
             self.num_matches += 1;
 
             if self.binary_heap.len() < self.max_count {
-                self.binary_heap.push(Reverse((score, line.to_string())));
+                self.binary_heap
+                    .push(Reverse((score, line.to_string(), pattern_index)));
             } else if let Some(min_element) = self.binary_heap.peek()
                 && score > min_element.0.0
             {
                 self.binary_heap.pop();
-                self.binary_heap.push(Reverse((score, line.to_string())));
+                self.binary_heap
+                    .push(Reverse((score, line.to_string(), pattern_index)));
+            }
+        }
+    }
+
+    /// Best score of `line` across all patterns, with the index of the pattern
+    /// that produced it. The earliest pattern wins ties.
+    fn best_score(&mut self, line: &str) -> Option<(u32, usize)> {
+        let haystack: Utf32Str<'_> = Utf32Str::new(line, &mut self.utf32buf);
+        let mut best: Option<(u32, usize)> = None;
+        for (pattern_index, pattern) in self.patterns.iter().enumerate() {
+            if let Some(score) = pattern.score(haystack, &mut self.matcher)
+                && best.is_none_or(|(best_score, _)| score > best_score)
+            {
+                best = Some((score, pattern_index));
             }
         }
+        best
     }
 }
 
@@ -403,6 +707,71 @@ fn create_pattern(pattern: &str) -> Pattern {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn score(pattern_text: &str, path: &str) -> Option<u32> {
+        let mut utf32buf = Vec::<char>::new();
+        let mut matcher = Matcher::new(nucleo_matcher::Config::DEFAULT);
+        create_pattern(pattern_text).score(Utf32Str::new(path, &mut utf32buf), &mut matcher)
+    }
+
+    fn indices(pattern_text: &str, path: &str) -> Vec<u32> {
+        let mut utf32buf = Vec::<char>::new();
+        let mut matcher = Matcher::new(nucleo_matcher::Config::DEFAULT);
+        let mut indices = Vec::new();
+        create_pattern(pattern_text).indices(
+            Utf32Str::new(path, &mut utf32buf),
+            &mut matcher,
+            &mut indices,
+        );
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+
+    fn search(dir: &TempDir, patterns: &[&str]) -> FileSearchResults {
+        search_with_filters(dir, patterns, &SearchFilters::default())
+    }
+
+    fn search_with_filters(
+        dir: &TempDir,
+        patterns: &[&str],
+        filters: &SearchFilters,
+    ) -> FileSearchResults {
+        run(
+            patterns,
+            NonZero::new(16).expect("limit"),
+            dir.path(),
+            filters,
+            NonZero::new(2).expect("threads"),
+            Arc::new(AtomicBool::new(false)),
+            true,
+            true,
+            false,
+            SortMode::Score,
+        )
+        .expect("search")
+    }
+
+    fn file_match(score: u32, path: &str, modified: Option<SystemTime>) -> FileMatch {
+        FileMatch {
+            score,
+            path: path.to_string(),
+            indices: None,
+            matched_pattern: None,
+            size: None,
+            modified,
+        }
+    }
+
+    /// Path, score, and modification time of each match, in order.
+    fn summarize(matches: &[FileMatch]) -> Vec<(String, u32, Option<SystemTime>)> {
+        matches
+            .iter()
+            .map(|m| (m.path.clone(), m.score, m.modified))
+            .collect()
+    }
 
     #[test]
     fn verify_score_is_none_for_non_match() {
@@ -418,20 +787,305 @@ mod tests {
     #[test]
     fn tie_breakers_sort_by_path_when_scores_equal() {
         let mut matches = vec![
-            (100, "b_path".to_string()),
-            (100, "a_path".to_string()),
-            (90, "zzz".to_string()),
+            file_match(100, "b_path", None),
+            file_match(100, "a_path", None),
+            file_match(90, "zzz", None),
         ];
 
-        sort_matches(&mut matches);
+        sort_matches(&mut matches, SortMode::Score);
 
         // Highest score first; ties broken alphabetically.
         let expected = vec![
-            (100, "a_path".to_string()),
-            (100, "b_path".to_string()),
-            (90, "zzz".to_string()),
+            file_match(100, "a_path", None),
+            file_match(100, "b_path", None),
+            file_match(90, "zzz", None),
+        ];
+
+        assert_eq!(summarize(&matches), summarize(&expected));
+    }
+
+    #[test]
+    fn recency_sort_lists_newest_first_then_by_score() {
+        let old = UNIX_EPOCH + std::time::Duration::from_secs(1_000);
+        let new = UNIX_EPOCH + std::time::Duration::from_secs(2_000);
+        let mut matches = vec![
+            file_match(100, "best_but_old", Some(old)),
+            file_match(50, "no_mtime", None),
+            file_match(80, "new_low", Some(new)),
+            file_match(90, "new_high", Some(new)),
+        ];
+
+        sort_matches(&mut matches, SortMode::RecencyThenScore);
+
+        let expected = vec![
+            file_match(90, "new_high", Some(new)),
+            file_match(80, "new_low", Some(new)),
+            file_match(100, "best_but_old", Some(old)),
+            file_match(50, "no_mtime", None),
         ];
+        assert_eq!(summarize(&matches), summarize(&expected));
+    }
+
+    #[test]
+    fn metadata_is_only_collected_on_request() {
+        let dir = TempDir::new().expect("tempdir");
+        let older = UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        let newer = older + std::time::Duration::from_secs(60);
+        for (name, contents, modified) in [
+            ("config.rs", "fn main() {}", older),
+            ("config_old.rs", "", newer),
+        ] {
+            let path = dir.path().join(name);
+            std::fs::write(&path, contents).expect("write");
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .and_then(|file| file.set_modified(modified))
+                .expect("set mtime");
+        }
+        let search_with = |with_metadata, sort_mode| {
+            run(
+                &["config"],
+                NonZero::new(16).expect("limit"),
+                dir.path(),
+                &SearchFilters::default(),
+                NonZero::new(2).expect("threads"),
+                Arc::new(AtomicBool::new(false)),
+                false,
+                true,
+                with_metadata,
+                sort_mode,
+            )
+            .expect("search")
+        };
+
+        let by_score = search_with(false, SortMode::Score);
+        assert_eq!(
+            by_score
+                .matches
+                .iter()
+                .map(|m| (m.path.as_str(), m.size, m.modified))
+                .collect::<Vec<_>>(),
+            vec![("config.rs", None, None), ("config_old.rs", None, None)]
+        );
+
+        let by_recency = search_with(false, SortMode::RecencyThenScore);
+        assert_eq!(
+            by_recency
+                .matches
+                .iter()
+                .map(|m| (m.path.as_str(), m.size, m.modified))
+                .collect::<Vec<_>>(),
+            vec![
+                ("config_old.rs", Some(0), Some(newer)),
+                ("config.rs", Some(12), Some(older)),
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(&by_recency.matches[0]).expect("serialize")["modified"],
+            serde_json::json!(1_700_000_060)
+        );
+    }
+
+    fn sorted_paths(results: &FileSearchResults) -> Vec<&str> {
+        let mut paths: Vec<&str> = results.matches.iter().map(|m| m.path.as_str()).collect();
+        paths.sort_unstable();
+        paths
+    }
+
+    #[test]
+    fn max_depth_stops_descending() {
+        let dir = TempDir::new().expect("tempdir");
+        std::fs::create_dir_all(dir.path().join("a/b")).expect("mkdir");
+        for name in ["mod.rs", "a/mod.rs", "a/b/mod.rs"] {
+            std::fs::write(dir.path().join(name), "").expect("write");
+        }
+        let filters = SearchFilters {
+            max_depth: Some(2),
+            ..SearchFilters::default()
+        };
+
+        let results = search_with_filters(&dir, &["mod"], &filters);
+
+        assert_eq!(sorted_paths(&results), vec!["a/mod.rs", "mod.rs"]);
+        assert!(!results.truncated_walk);
+    }
+
+    #[test]
+    fn max_file_count_aborts_the_walk() {
+        let dir = TempDir::new().expect("tempdir");
+        for i in 0..50 {
+            std::fs::write(dir.path().join(format!("file_{i:02}.rs")), "").expect("write");
+        }
+
+        let capped = search_with_filters(
+            &dir,
+            &["file"],
+            &SearchFilters {
+                max_file_count: Some(10),
+                ..SearchFilters::default()
+            },
+        );
+        assert_eq!(capped.total_match_count, 10);
+        assert!(capped.truncated_walk);
+
+        // A budget the tree fits in is not a truncation.
+        let exact = search_with_filters(
+            &dir,
+            &["file"],
+            &SearchFilters {
+                max_file_count: Some(50),
+                ..SearchFilters::default()
+            },
+        );
+        assert_eq!(exact.total_match_count, 50);
+        assert!(!exact.truncated_walk);
+    }
+
+    #[test]
+    fn later_filter_rules_win() {
+        let dir = TempDir::new().expect("tempdir");
+        std::fs::create_dir_all(dir.path().join("target")).expect("mkdir");
+        for name in ["lib.rs", "notes.md", "target/gen.rs"] {
+            std::fs::write(dir.path().join(name), "").expect("write");
+        }
+        let filters = SearchFilters {
+            include: vec!["*.rs".to_string()],
+            exclude: vec!["target/".to_string()],
+            ..SearchFilters::default()
+        };
+
+        let results = search_with_filters(&dir, &["s"], &filters);
+
+        assert_eq!(sorted_paths(&results), vec!["lib.rs"]);
+    }
+
+    #[test]
+    fn codexignore_rules_apply_without_ignore_files() {
+        let dir = TempDir::new().expect("tempdir");
+        std::fs::create_dir_all(dir.path().join("private")).expect("mkdir");
+        for name in ["notes.md", "keep-me.md", "private/plan.rs", "src.rs"] {
+            std::fs::write(dir.path().join(name), "").expect("write");
+        }
+        let filters = SearchFilters {
+            path_filter: PathFilter::parse(dir.path(), "*.md\n!keep-me.md\nprivate/\n")
+                .expect("valid patterns"),
+            ..SearchFilters::default()
+        };
+
+        let results = run(
+            &["."],
+            NonZero::new(16).expect("limit"),
+            dir.path(),
+            &filters,
+            NonZero::new(2).expect("threads"),
+            Arc::new(AtomicBool::new(false)),
+            true,
+            false,
+            false,
+            SortMode::Score,
+        )
+        .expect("search");
+
+        assert_eq!(sorted_paths(&results), vec!["keep-me.md", "src.rs"]);
+    }
+
+    #[test]
+    fn matches_that_vanished_before_the_stat_are_skipped() {
+        let dir = TempDir::new().expect("tempdir");
+        std::fs::write(dir.path().join("kept.rs"), "").expect("write");
+
+        let mut results = FileSearchResults {
+            matches: vec![
+                file_match(100, "deleted.rs", None),
+                file_match(90, "kept.rs", None),
+            ],
+            total_match_count: 5,
+            truncated_walk: false,
+        };
+        add_file_metadata(&mut results, dir.path());
+
+        assert_eq!(
+            results
+                .matches
+                .iter()
+                .map(|m| (m.path.as_str(), m.size))
+                .collect::<Vec<_>>(),
+            vec![("kept.rs", Some(0))]
+        );
+        assert_eq!(results.total_match_count, 4);
+    }
+
+    #[test]
+    fn file_matching_several_patterns_is_listed_once() {
+        let dir = TempDir::new().expect("tempdir");
+        for name in [
+            "config.rs",
+            "settings.rs",
+            "config_settings.rs",
+            "readme.md",
+        ] {
+            std::fs::write(dir.path().join(name), "").expect("write");
+        }
+        let patterns = ["config", "settings"];
+
+        let results = search(&dir, &patterns);
+
+        let mut paths: Vec<&str> = results.matches.iter().map(|m| m.path.as_str()).collect();
+        paths.sort_unstable();
+        assert_eq!(
+            paths,
+            vec!["config.rs", "config_settings.rs", "settings.rs"]
+        );
+        assert_eq!(results.total_match_count, 3);
+
+        // Each file carries the best score over both patterns, and the
+        // indices of the pattern that produced it.
+        for file_match in &results.matches {
+            let (best_score, best_pattern) = patterns
+                .iter()
+                .filter_map(|pattern| Some((score(pattern, &file_match.path)?, *pattern)))
+                .fold(None, |best: Option<(u32, &str)>, candidate| match best {
+                    Some(best) if best.0 >= candidate.0 => Some(best),
+                    _ => Some(candidate),
+                })
+                .expect("a pattern matches");
+            assert_eq!(file_match.score, best_score);
+            assert_eq!(file_match.matched_pattern.as_deref(), Some(best_pattern));
+            assert_eq!(
+                file_match.indices,
+                Some(indices(best_pattern, &file_match.path))
+            );
+        }
+    }
+
+    #[test]
+    fn single_pattern_matches_do_not_report_the_pattern() {
+        let dir = TempDir::new().expect("tempdir");
+        std::fs::write(dir.path().join("config.rs"), "").expect("write");
+
+        let results = search(&dir, &["config"]);
+
+        assert_eq!(results.matches.len(), 1);
+        assert_eq!(results.matches[0].matched_pattern, None);
+    }
+
+    #[test]
+    fn score_ties_go_to_the_earlier_pattern() {
+        let mut list = BestMatchesList::new(
+            4,
+            vec![create_pattern("conf"), create_pattern("conf")],
+            Matcher::new(nucleo_matcher::Config::DEFAULT),
+        );
+        let conf_score = score("conf", "conf.rs").expect("match");
+        assert_eq!(list.best_score("conf.rs"), Some((conf_score, 0)));
 
-        assert_eq!(matches, expected);
+        let mut list = BestMatchesList::new(
+            4,
+            vec![create_pattern("zzz"), create_pattern("conf")],
+            Matcher::new(nucleo_matcher::Config::DEFAULT),
+        );
+        assert_eq!(list.best_score("conf.rs"), Some((conf_score, 1)));
+        assert_eq!(list.best_score("readme.md"), None);
     }
 }
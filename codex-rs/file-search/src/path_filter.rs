@@ -0,0 +1,227 @@
+//! Paths Codex should never read or surface, from a `.codexignore` file.
+//!
+//! The file lives at the repository root and uses gitignore syntax: the last
+//! matching pattern wins, `!pattern` re-includes, and a trailing `/` only
+//! matches directories. As with git, a file cannot be re-included once one of
+//! its parent directories is excluded. Nested `.codexignore` files are not
+//! read.
+
+use std::fmt;
+use std::io;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use ignore::gitignore::Gitignore;
+use ignore::gitignore::GitignoreBuilder;
+
+pub const CODEXIGNORE_FILENAME: &str = ".codexignore";
+
+/// Cheap-to-clone handle on the parsed `.codexignore` rules. The default
+/// excludes nothing.
+#[derive(Clone, Default)]
+pub struct PathFilter {
+    rules: Option<Arc<Rules>>,
+}
+
+struct Rules {
+    root: PathBuf,
+    /// `root` with symlinks resolved, so paths built from either form match.
+    canonical_root: Option<PathBuf>,
+    text: String,
+    matcher: Gitignore,
+}
+
+impl PathFilter {
+    /// Reads `root/.codexignore`. A missing file yields a filter that
+    /// excludes nothing; an invalid pattern is an `InvalidData` error.
+    pub fn load(root: &Path) -> io::Result<Self> {
+        match std::fs::read_to_string(root.join(CODEXIGNORE_FILENAME)) {
+            Ok(text) => Self::parse(root, &text),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Parses gitignore-syntax `text` with patterns anchored at `root`.
+    pub fn parse(root: &Path, text: &str) -> io::Result<Self> {
+        let mut builder = GitignoreBuilder::new(root);
+        for line in text.lines() {
+            builder
+                .add_line(None, line)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        }
+        let matcher = builder
+            .build()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        if matcher.is_empty() {
+            return Ok(Self::default());
+        }
+        Ok(Self {
+            rules: Some(Arc::new(Rules {
+                root: root.to_path_buf(),
+                canonical_root: canonical_if_different(root),
+                text: text.to_string(),
+                matcher,
+            })),
+        })
+    }
+
+    /// True when no pattern is set, so nothing is excluded.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_none()
+    }
+
+    /// Whether `path` is excluded, either directly or through one of its
+    /// parent directories. Relative paths are taken relative to the root;
+    /// absolute paths outside it are never excluded.
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        let Some(rules) = &self.rules else {
+            return false;
+        };
+        let Some(relative) = rules.relative(path) else {
+            return false;
+        };
+
+        let components: Vec<Component> = relative
+            .components()
+            .filter(|component| matches!(component, Component::Normal(_)))
+            .collect();
+        let mut prefix = PathBuf::new();
+        for (i, component) in components.iter().enumerate() {
+            prefix.push(component);
+            let is_last = i + 1 == components.len();
+            if rules
+                .matcher
+                .matched(&prefix, !is_last || is_dir)
+                .is_ignore()
+            {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl Rules {
+    fn relative<'a>(&self, path: &'a Path) -> Option<&'a Path> {
+        if !path.is_absolute() {
+            return Some(path);
+        }
+        path.strip_prefix(&self.root)
+            .ok()
+            .or_else(|| path.strip_prefix(self.canonical_root.as_ref()?).ok())
+    }
+}
+
+fn canonical_if_different(path: &Path) -> Option<PathBuf> {
+    let canonical = path.canonicalize().ok()?;
+    (canonical != path).then_some(canonical)
+}
+
+impl PartialEq for PathFilter {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.rules, &other.rules) {
+            (Some(a), Some(b)) => a.root == b.root && a.text == b.text,
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for PathFilter {}
+
+impl fmt::Debug for PathFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.rules {
+            Some(rules) => {
+                let patterns = rules.matcher.num_ignores() + rules.matcher.num_whitelists();
+                f.debug_struct("PathFilter")
+                    .field("root", &rules.root)
+                    .field("patterns", &patterns)
+                    .finish()
+            }
+            None => f.write_str("PathFilter(none)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn excluded(filter: &PathFilter, paths: &[(&str, bool)]) -> Vec<String> {
+        paths
+            .iter()
+            .filter(|(path, is_dir)| filter.is_excluded(Path::new(path), *is_dir))
+            .map(|(path, _)| (*path).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn last_matching_pattern_wins() {
+        let filter = PathFilter::parse(
+            Path::new("/repo"),
+            "# drafts stay private\n*.md\n!keep-me.md\nsecrets/\n!secrets/public.txt\n",
+        )
+        .expect("valid patterns");
+
+        assert_eq!(
+            excluded(
+                &filter,
+                &[
+                    ("notes.md", false),
+                    ("keep-me.md", false),
+                    ("docs/keep-me.md", false),
+                    ("src/main.rs", false),
+                    ("secrets", true),
+                    ("secrets/public.txt", false),
+                    ("/repo/secrets/key.pem", false),
+                    ("/elsewhere/notes.md", false),
+                ],
+            ),
+            vec![
+                "notes.md",
+                "secrets",
+                "secrets/public.txt",
+                "/repo/secrets/key.pem",
+            ]
+        );
+    }
+
+    #[test]
+    fn anchored_and_directory_only_patterns() {
+        let filter =
+            PathFilter::parse(Path::new("/repo"), "/build\nlogs/\n").expect("valid patterns");
+
+        assert_eq!(
+            excluded(
+                &filter,
+                &[
+                    ("build/out.o", false),
+                    ("crates/build/out.o", false),
+                    ("logs", false),
+                    ("crates/logs/today.txt", false),
+                ],
+            ),
+            vec!["build/out.o", "crates/logs/today.txt"]
+        );
+    }
+
+    #[test]
+    fn missing_or_empty_file_excludes_nothing() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        assert_eq!(
+            PathFilter::load(dir.path()).expect("load"),
+            PathFilter::default()
+        );
+
+        std::fs::write(dir.path().join(CODEXIGNORE_FILENAME), "# nothing yet\n").expect("write");
+        assert_eq!(
+            PathFilter::load(dir.path()).expect("load"),
+            PathFilter::default()
+        );
+    }
+}
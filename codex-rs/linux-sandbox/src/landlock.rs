@@ -7,6 +7,7 @@ use codex_core::error::Result;
 use codex_core::error::SandboxErr;
 use codex_core::protocol::SandboxPolicy;
 
+use crate::network_notify::hand_off_network_notifications;
 use landlock::ABI;
 use landlock::Access;
 use landlock::AccessFs;
@@ -27,12 +28,18 @@ use seccompiler::apply_filter;
 
 /// Apply sandbox policies inside this thread so only the child inherits
 /// them, not the entire CLI process.
+///
+/// When `network_approval_socket` is set and the kernel supports seccomp user
+/// notifications, outbound connections are handed to the supervisor listening
+/// on that socket instead of failing with `EPERM`.
 pub(crate) fn apply_sandbox_policy_to_current_thread(
     sandbox_policy: &SandboxPolicy,
     cwd: &Path,
+    network_approval_socket: Option<&Path>,
 ) -> Result<()> {
     if !sandbox_policy.has_full_network_access() {
-        install_network_seccomp_filter_on_current_thread()?;
+        let supervised = network_approval_socket.is_some_and(hand_off_network_notifications);
+        install_network_seccomp_filter_on_current_thread(supervised)?;
     }
 
     if !sandbox_policy.has_full_disk_write_access() {
@@ -84,7 +91,14 @@ fn install_filesystem_landlock_rules_on_current_thread(writable_roots: Vec<PathB
 
 /// Installs a seccomp filter that blocks outbound network access except for
 /// AF_UNIX domain sockets.
-fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(), SandboxErr> {
+///
+/// When `supervised` is true a notification filter already routes
+/// `connect`/`sendto`/`sendmsg`/`sendmmsg` to the approval supervisor, so
+/// those syscalls (and the socket plumbing a client needs around them) are
+/// left to it instead of failing with `EPERM` here.
+fn install_network_seccomp_filter_on_current_thread(
+    supervised: bool,
+) -> std::result::Result<(), SandboxErr> {
     // Build rule map.
     let mut rules: BTreeMap<i64, Vec<SeccompRule>> = BTreeMap::new();
 
@@ -93,25 +107,31 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
         rules.insert(nr, vec![]); // empty rule vec = unconditional match
     };
 
-    deny_syscall(libc::SYS_connect);
     deny_syscall(libc::SYS_accept);
     deny_syscall(libc::SYS_accept4);
     deny_syscall(libc::SYS_bind);
     deny_syscall(libc::SYS_listen);
-    deny_syscall(libc::SYS_getpeername);
-    deny_syscall(libc::SYS_getsockname);
-    deny_syscall(libc::SYS_shutdown);
-    deny_syscall(libc::SYS_sendto);
-    deny_syscall(libc::SYS_sendmsg);
-    deny_syscall(libc::SYS_sendmmsg);
-    // NOTE: allowing recvfrom allows some tools like: `cargo clippy` to run
-    // with their socketpair + child processes for sub-proc management
-    // deny_syscall(libc::SYS_recvfrom);
-    deny_syscall(libc::SYS_recvmsg);
-    deny_syscall(libc::SYS_recvmmsg);
-    deny_syscall(libc::SYS_getsockopt);
-    deny_syscall(libc::SYS_setsockopt);
     deny_syscall(libc::SYS_ptrace);
+    if supervised {
+        // io_uring can create and connect sockets without going through the
+        // syscalls the supervisor sees.
+        deny_syscall(libc::SYS_io_uring_setup);
+    } else {
+        deny_syscall(libc::SYS_connect);
+        deny_syscall(libc::SYS_getpeername);
+        deny_syscall(libc::SYS_getsockname);
+        deny_syscall(libc::SYS_shutdown);
+        deny_syscall(libc::SYS_sendto);
+        deny_syscall(libc::SYS_sendmsg);
+        deny_syscall(libc::SYS_sendmmsg);
+        // NOTE: allowing recvfrom allows some tools like: `cargo clippy` to run
+        // with their socketpair + child processes for sub-proc management
+        // deny_syscall(libc::SYS_recvfrom);
+        deny_syscall(libc::SYS_recvmsg);
+        deny_syscall(libc::SYS_recvmmsg);
+        deny_syscall(libc::SYS_getsockopt);
+        deny_syscall(libc::SYS_setsockopt);
+    }
 
     // For `socket` we allow AF_UNIX (arg0 == AF_UNIX) and deny everything else.
     let unix_only_rule = SeccompRule::new(vec![SeccompCondition::new(
@@ -121,7 +141,24 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
         libc::AF_UNIX as u64,
     )?])?;
 
-    rules.insert(libc::SYS_socket, vec![unix_only_rule.clone()]);
+    // Supervised commands may also create IP sockets; connecting them is
+    // still subject to approval.
+    let socket_rule = if supervised {
+        let mut conditions = Vec::new();
+        for domain in [libc::AF_UNIX, libc::AF_INET, libc::AF_INET6] {
+            conditions.push(SeccompCondition::new(
+                0,
+                SeccompCmpArgLen::Dword,
+                SeccompCmpOp::Ne,
+                domain as u64,
+            )?);
+        }
+        SeccompRule::new(conditions)?
+    } else {
+        unix_only_rule.clone()
+    };
+
+    rules.insert(libc::SYS_socket, vec![socket_rule]);
     rules.insert(libc::SYS_socketpair, vec![unix_only_rule]); // always deny (Unix can use socketpair but fine, keep open?)
 
     let filter = SeccompFilter::new(
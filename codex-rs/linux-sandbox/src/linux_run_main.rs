@@ -14,6 +14,12 @@ pub struct LandlockCommand {
     #[arg(long = "sandbox-policy")]
     pub sandbox_policy: codex_core::protocol::SandboxPolicy,
 
+    /// Unix socket of a supervisor that approves outbound connections. When
+    /// omitted (or unsupported by the kernel) network syscalls fail with
+    /// `EPERM`.
+    #[arg(long = "network-approval-socket")]
+    pub network_approval_socket: Option<PathBuf>,
+
     /// Full command args to run under landlock.
     #[arg(trailing_var_arg = true)]
     pub command: Vec<String>,
@@ -23,10 +29,15 @@ pub fn run_main() -> ! {
     let LandlockCommand {
         sandbox_policy_cwd,
         sandbox_policy,
+        network_approval_socket,
         command,
     } = LandlockCommand::parse();
 
-    if let Err(e) = apply_sandbox_policy_to_current_thread(&sandbox_policy, &sandbox_policy_cwd) {
+    if let Err(e) = apply_sandbox_policy_to_current_thread(
+        &sandbox_policy,
+        &sandbox_policy_cwd,
+        network_approval_socket.as_deref(),
+    ) {
         panic!("error running landlock: {e:?}");
     }
 
@@ -0,0 +1,165 @@
+//! Seccomp user-notification filter for supervised network access.
+//!
+//! Instead of failing `connect(2)` and friends with `EPERM`, the helper can
+//! install a filter that parks those syscalls until a supervisor in the parent
+//! Codex process decides whether to let them through. The listener fd for the
+//! filter is handed over by writing its number to the supervisor's Unix socket;
+//! the supervisor copies it out of this process with `pidfd_getfd(2)` and
+//! acknowledges with a single byte. Sending the fd with `SCM_RIGHTS` is not an
+//! option because `sendmsg` is one of the intercepted syscalls.
+
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::os::fd::AsRawFd;
+use std::os::fd::FromRawFd;
+use std::os::fd::OwnedFd;
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+
+#[cfg(target_arch = "x86_64")]
+const AUDIT_ARCH: u32 = 0xC000_003E;
+#[cfg(target_arch = "aarch64")]
+const AUDIT_ARCH: u32 = 0xC000_00B7;
+
+/// Offsets into `struct seccomp_data`.
+const SECCOMP_DATA_NR_OFFSET: u32 = 0;
+const SECCOMP_DATA_ARCH_OFFSET: u32 = 4;
+
+/// x32 syscalls share the x86_64 audit arch but set this bit in `nr`.
+#[cfg(target_arch = "x86_64")]
+const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+
+/// Byte the supervisor sends once it holds its own copy of the listener fd.
+const HANDOFF_ACK: u8 = b'1';
+
+/// Connects to the supervisor at `socket_path`, installs the notification
+/// filter and hands over its listener. Returns `false` when any step fails,
+/// in which case the caller must fall back to denying network syscalls
+/// outright.
+pub(crate) fn hand_off_network_notifications(socket_path: &Path) -> bool {
+    // Connect first: once the filter is installed `connect` itself would wait
+    // on a supervisor that does not have the listener yet.
+    let Ok(mut stream) = UnixStream::connect(socket_path) else {
+        return false;
+    };
+    // Installing a filter without CAP_SYS_ADMIN requires no_new_privs.
+    if unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) } != 0 {
+        return false;
+    }
+    let Ok(listener) = install_notify_filter() else {
+        return false;
+    };
+    // If the handoff fails, the EPERM filter installed next takes precedence
+    // over the notification filter, so nothing is left waiting on `listener`.
+    send_listener(&mut stream, &listener).is_ok()
+}
+
+fn send_listener(stream: &mut UnixStream, listener: &OwnedFd) -> io::Result<()> {
+    // `UnixStream`'s `Write` impl goes through `send(2)`, which the filter now
+    // intercepts; writing through a `File` uses plain `write(2)`.
+    let mut writer = File::from(OwnedFd::from(stream.try_clone()?));
+    writer.write_all(format!("{}\n", listener.as_raw_fd()).as_bytes())?;
+    let mut ack = [0u8; 1];
+    stream.read_exact(&mut ack)?;
+    if ack[0] == HANDOFF_ACK {
+        Ok(())
+    } else {
+        Err(io::Error::other("supervisor rejected the seccomp listener"))
+    }
+}
+
+fn install_notify_filter() -> io::Result<OwnedFd> {
+    let mut program = notify_program();
+    let prog = libc::sock_fprog {
+        len: program.len() as libc::c_ushort,
+        filter: program.as_mut_ptr(),
+    };
+    let fd = unsafe {
+        libc::syscall(
+            libc::SYS_seccomp,
+            libc::SECCOMP_SET_MODE_FILTER,
+            libc::SECCOMP_FILTER_FLAG_NEW_LISTENER,
+            &prog as *const libc::sock_fprog,
+        )
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { OwnedFd::from_raw_fd(fd as libc::c_int) })
+}
+
+/// BPF program returning `SECCOMP_RET_USER_NOTIF` for the syscalls that pick
+/// a destination and `SECCOMP_RET_ALLOW` for everything else.
+fn notify_program() -> Vec<libc::sock_filter> {
+    const NOTIFIED: [libc::c_long; 4] = [
+        libc::SYS_connect,
+        libc::SYS_sendto,
+        libc::SYS_sendmsg,
+        libc::SYS_sendmmsg,
+    ];
+    let remaining = NOTIFIED.len() as u8;
+
+    let mut program = vec![
+        bpf_stmt(
+            libc::BPF_LD | libc::BPF_W | libc::BPF_ABS,
+            SECCOMP_DATA_ARCH_OFFSET,
+        ),
+        bpf_jump(
+            libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K,
+            AUDIT_ARCH,
+            1,
+            0,
+        ),
+        bpf_stmt(libc::BPF_RET | libc::BPF_K, libc::SECCOMP_RET_ALLOW),
+        bpf_stmt(
+            libc::BPF_LD | libc::BPF_W | libc::BPF_ABS,
+            SECCOMP_DATA_NR_OFFSET,
+        ),
+    ];
+    // Deny x32 syscalls outright; their numbers would not match below.
+    #[cfg(target_arch = "x86_64")]
+    program.push(bpf_jump(
+        libc::BPF_JMP | libc::BPF_JGE | libc::BPF_K,
+        X32_SYSCALL_BIT,
+        remaining + 2,
+        0,
+    ));
+    for (index, nr) in NOTIFIED.iter().enumerate() {
+        // Skip the remaining comparisons and the ALLOW below to reach NOTIF.
+        program.push(bpf_jump(
+            libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K,
+            *nr as u32,
+            remaining - index as u8,
+            0,
+        ));
+    }
+    program.push(bpf_stmt(
+        libc::BPF_RET | libc::BPF_K,
+        libc::SECCOMP_RET_ALLOW,
+    ));
+    program.push(bpf_stmt(
+        libc::BPF_RET | libc::BPF_K,
+        libc::SECCOMP_RET_USER_NOTIF,
+    ));
+    #[cfg(target_arch = "x86_64")]
+    program.push(bpf_stmt(
+        libc::BPF_RET | libc::BPF_K,
+        libc::SECCOMP_RET_ERRNO | (libc::EPERM as u32 & libc::SECCOMP_RET_DATA),
+    ));
+    program
+}
+
+fn bpf_stmt(code: u32, k: u32) -> libc::sock_filter {
+    bpf_jump(code, k, 0, 0)
+}
+
+fn bpf_jump(code: u32, k: u32, jt: u8, jf: u8) -> libc::sock_filter {
+    libc::sock_filter {
+        code: code as u16,
+        jt,
+        jf,
+        k,
+    }
+}
@@ -0,0 +1,147 @@
+#![cfg(target_os = "linux")]
+use codex_core::config::types::ShellEnvironmentPolicy;
+use codex_core::error::CodexErr;
+use codex_core::error::SandboxErr;
+use codex_core::exec::ExecToolCallOutput;
+use codex_core::exec::SandboxType;
+use codex_core::exec_env::create_env;
+use codex_core::protocol::NetworkDestination;
+use codex_core::protocol::SandboxPolicy;
+use codex_core::sandboxing::ExecEnv;
+use codex_core::sandboxing::network_approval::execute_env_with_network_approval;
+use pretty_assertions::assert_eq;
+use std::io::Read;
+use std::io::Write;
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+const RESPONSE_BODY: &str = "hello from the local listener";
+
+/// Serves a single HTTP response on a loopback port. The supervisor prompts for
+/// loopback destinations even though the request only asked for non-local
+/// ones (see the `network_approval` module docs), which is what lets these
+/// tests exercise the approval path without reaching the internet.
+#[expect(clippy::unwrap_used)]
+fn spawn_local_listener() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    std::thread::spawn(move || {
+        if let Ok((mut stream, _)) = listener.accept() {
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request);
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{RESPONSE_BODY}",
+                RESPONSE_BODY.len()
+            );
+        }
+    });
+    port
+}
+
+fn curl_available() -> bool {
+    std::process::Command::new("curl")
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Runs curl against the local listener under the sandbox, answering every
+/// network approval with `allow`. Returns `None` when the kernel or
+/// environment does not support supervised network access.
+#[expect(clippy::expect_used)]
+async fn curl_with_approval(
+    allow: bool,
+) -> Option<(ExecToolCallOutput, Vec<NetworkDestination>, u16)> {
+    if !curl_available() {
+        eprintln!("skipping: curl is not installed");
+        return None;
+    }
+    let port = spawn_local_listener();
+    let cwd = std::env::current_dir().expect("cwd should exist");
+    let env = ExecEnv {
+        command: vec![
+            env!("CARGO_BIN_EXE_codex-linux-sandbox").to_string(),
+            "--sandbox-policy-cwd".to_string(),
+            cwd.to_string_lossy().into_owned(),
+            "--sandbox-policy".to_string(),
+            r#"{"type":"read-only"}"#.to_string(),
+            "--".to_string(),
+            "curl".to_string(),
+            "-sS".to_string(),
+            "--max-time".to_string(),
+            "5".to_string(),
+            format!("http://127.0.0.1:{port}/"),
+        ],
+        cwd,
+        env: create_env(&ShellEnvironmentPolicy::default()),
+        timeout_ms: Some(10_000),
+        sandbox: SandboxType::LinuxSeccomp,
+        with_escalated_permissions: None,
+        justification: None,
+        arg0: Some("codex-linux-sandbox".to_string()),
+    };
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let record = Arc::clone(&seen);
+    let result = execute_env_with_network_approval(
+        &env,
+        &SandboxPolicy::new_read_only_policy(),
+        None,
+        move |destination| {
+            record.lock().expect("approval log lock").push(destination);
+            async move { allow }
+        },
+    )
+    .await;
+    let output = match result {
+        Ok(output) => output,
+        Err(CodexErr::Sandbox(SandboxErr::Denied { output })) => *output,
+        Err(err) => panic!("unexpected exec error: {err:?}"),
+    };
+
+    let seen = seen.lock().expect("approval log lock").clone();
+    if seen.is_empty() {
+        eprintln!(
+            "skipping: seccomp user notifications unavailable (stderr: {})",
+            output.stderr.text
+        );
+        return None;
+    }
+    Some((output, seen, port))
+}
+
+#[tokio::test]
+async fn approved_connection_reaches_local_listener() {
+    let Some((output, seen, port)) = curl_with_approval(true).await else {
+        return;
+    };
+
+    assert_eq!(output.exit_code, 0, "stderr: {}", output.stderr.text);
+    assert_eq!(output.stdout.text, RESPONSE_BODY);
+    assert_eq!(
+        seen,
+        vec![NetworkDestination {
+            host: "127.0.0.1".to_string(),
+            port,
+        }]
+    );
+}
+
+#[tokio::test]
+async fn denied_connection_fails_without_reaching_listener() {
+    let Some((output, seen, port)) = curl_with_approval(false).await else {
+        return;
+    };
+
+    assert_ne!(output.exit_code, 0);
+    assert_eq!(output.stdout.text, "");
+    assert_eq!(
+        seen,
+        vec![NetworkDestination {
+            host: "127.0.0.1".to_string(),
+            port,
+        }]
+    );
+}
@@ -1,3 +1,9 @@
+//! Device code sign-in (RFC 8628) for machines without a usable browser.
+//!
+//! The caller receives the user code and verification URL through a
+//! [`DeviceCodeHandler`], so the CLI can print them and the TUI can render
+//! them on the onboarding screen.
+
 use reqwest::StatusCode;
 use serde::Deserialize;
 use serde::Serialize;
@@ -15,13 +21,50 @@ const ANSI_YELLOW: &str = "\x1b[93m";
 const ANSI_BOLD: &str = "\x1b[1m";
 const ANSI_RESET: &str = "\x1b[0m";
 
+/// Polling interval when the server does not send one.
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+/// Shortest polling interval, whatever the server sends.
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+/// Added to the polling interval on every `slow_down` response.
+const SLOW_DOWN_STEP: Duration = Duration::from_secs(5);
+/// Lifetime of a user code when the server does not send `expires_in`.
+const DEFAULT_CODE_LIFETIME: Duration = Duration::from_secs(15 * 60);
+/// How many fresh codes to request after the first one expires.
+const MAX_CODE_RESTARTS: usize = 1;
+
+/// Receives the user code and polling progress of a device code sign-in.
+pub trait DeviceCodeHandler: Send + Sync {
+    /// Called whenever a new user code is issued, including after the
+    /// previous one expired.
+    fn on_code(&self, user_code: &str, verification_url: &str, expires_in: Duration);
+
+    /// Called after every poll of the token endpoint.
+    fn on_poll_status(&self, _status: &DevicePollStatus) {}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DevicePollStatus {
+    /// The user has not finished signing in yet.
+    Pending,
+    /// The server asked us to poll less often; `interval` is the new delay.
+    SlowDown { interval: Duration },
+    /// The code expired before it was used.
+    Expired,
+    /// The user approved the sign-in.
+    Approved,
+}
+
 #[derive(Deserialize)]
 struct UserCodeResp {
     device_auth_id: String,
     #[serde(alias = "user_code", alias = "usercode")]
     user_code: String,
-    #[serde(default, deserialize_with = "deserialize_interval")]
-    interval: u64,
+    #[serde(default, deserialize_with = "deserialize_optional_secs")]
+    interval: Option<u64>,
+    #[serde(default, deserialize_with = "deserialize_optional_secs")]
+    expires_in: Option<u64>,
+    #[serde(default, alias = "verification_uri")]
+    verification_url: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -35,14 +78,27 @@ struct TokenPollReq {
     user_code: String,
 }
 
-fn deserialize_interval<'de, D>(deserializer: D) -> Result<u64, D::Error>
+/// Accepts a number of seconds sent either as a JSON number or a string.
+fn deserialize_optional_secs<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let s = String::deserialize(deserializer)?;
-    s.trim()
-        .parse::<u64>()
-        .map_err(|e| de::Error::custom(format!("invalid u64 string: {e}")))
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Secs {
+        Number(u64),
+        Text(String),
+    }
+
+    match Option::<Secs>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Secs::Number(secs)) => Ok(Some(secs)),
+        Some(Secs::Text(s)) => s
+            .trim()
+            .parse::<u64>()
+            .map(Some)
+            .map_err(|e| de::Error::custom(format!("invalid u64 string: {e}"))),
+    }
 }
 
 #[derive(Deserialize)]
@@ -88,22 +144,84 @@ async fn request_user_code(
     serde_json::from_str(&body).map_err(std::io::Error::other)
 }
 
-/// Poll token endpoint until a code is issued or timeout occurs.
+/// How the token endpoint answered a single poll.
+#[derive(Debug, PartialEq, Eq)]
+enum PollResponse {
+    Approved,
+    Pending,
+    SlowDown,
+    Expired,
+    Denied,
+    Failed(String),
+}
+
+/// Maps a token endpoint response onto the RFC 8628 error codes. The server
+/// also signals a pending authorization with a bare 403 or 404.
+fn classify_poll_response(status: StatusCode, body: &str) -> PollResponse {
+    if status.is_success() {
+        return PollResponse::Approved;
+    }
+
+    let error_code = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|value| match value.get("error")? {
+            serde_json::Value::String(code) => Some(code.clone()),
+            serde_json::Value::Object(error) => {
+                error.get("code")?.as_str().map(ToString::to_string)
+            }
+            _ => None,
+        });
+
+    match error_code.as_deref() {
+        Some("authorization_pending") => PollResponse::Pending,
+        Some("slow_down") => PollResponse::SlowDown,
+        Some("expired_token") => PollResponse::Expired,
+        Some("access_denied") => PollResponse::Denied,
+        Some(code) => {
+            PollResponse::Failed(format!("device auth failed with status {status}: {code}"))
+        }
+        None if status == StatusCode::FORBIDDEN || status == StatusCode::NOT_FOUND => {
+            PollResponse::Pending
+        }
+        None => PollResponse::Failed(format!("device auth failed with status {status}")),
+    }
+}
+
+/// Bounds on how often the token endpoint is polled.
+#[derive(Clone, Copy)]
+struct PollTiming {
+    /// Lower bound for the server's `interval`.
+    min_interval: Duration,
+    /// Added to the interval on every `slow_down` response.
+    slow_down_step: Duration,
+}
+
+const POLL_TIMING: PollTiming = PollTiming {
+    min_interval: MIN_INTERVAL,
+    slow_down_step: SLOW_DOWN_STEP,
+};
+
+/// Poll the token endpoint until the user approves the code. Returns
+/// `Ok(None)` once the code has expired.
 async fn poll_for_token(
     client: &reqwest::Client,
     auth_base_url: &str,
-    device_auth_id: &str,
-    user_code: &str,
-    interval: u64,
-) -> std::io::Result<CodeSuccessResp> {
+    uc: &UserCodeResp,
+    expires_in: Duration,
+    timing: PollTiming,
+    handler: &dyn DeviceCodeHandler,
+) -> std::io::Result<Option<CodeSuccessResp>> {
     let url = format!("{auth_base_url}/deviceauth/token");
-    let max_wait = Duration::from_secs(15 * 60);
-    let start = Instant::now();
+    let deadline = Instant::now() + expires_in;
+    let mut interval = uc
+        .interval
+        .map_or(DEFAULT_INTERVAL, Duration::from_secs)
+        .max(timing.min_interval);
 
     loop {
         let body = serde_json::to_string(&TokenPollReq {
-            device_auth_id: device_auth_id.to_string(),
-            user_code: user_code.to_string(),
+            device_auth_id: uc.device_auth_id.clone(),
+            user_code: uc.user_code.clone(),
         })
         .map_err(std::io::Error::other)?;
         let resp = client
@@ -115,26 +233,76 @@ async fn poll_for_token(
             .map_err(std::io::Error::other)?;
 
         let status = resp.status();
+        let body = resp.text().await.map_err(std::io::Error::other)?;
 
-        if status.is_success() {
-            return resp.json().await.map_err(std::io::Error::other);
-        }
-
-        if status == StatusCode::FORBIDDEN || status == StatusCode::NOT_FOUND {
-            if start.elapsed() >= max_wait {
-                return Err(std::io::Error::other(
-                    "device auth timed out after 15 minutes",
+        match classify_poll_response(status, &body) {
+            PollResponse::Approved => {
+                let code = serde_json::from_str(&body).map_err(std::io::Error::other)?;
+                handler.on_poll_status(&DevicePollStatus::Approved);
+                return Ok(Some(code));
+            }
+            PollResponse::Pending => handler.on_poll_status(&DevicePollStatus::Pending),
+            PollResponse::SlowDown => {
+                interval += timing.slow_down_step;
+                handler.on_poll_status(&DevicePollStatus::SlowDown { interval });
+            }
+            PollResponse::Expired => {
+                handler.on_poll_status(&DevicePollStatus::Expired);
+                return Ok(None);
+            }
+            PollResponse::Denied => {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "device authorization was denied",
                 ));
             }
-            let sleep_for = Duration::from_secs(interval).min(max_wait - start.elapsed());
-            tokio::time::sleep(sleep_for).await;
-            continue;
+            PollResponse::Failed(message) => return Err(std::io::Error::other(message)),
         }
 
-        return Err(std::io::Error::other(format!(
-            "device auth failed with status {}",
-            resp.status()
-        )));
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            handler.on_poll_status(&DevicePollStatus::Expired);
+            return Ok(None);
+        }
+        tokio::time::sleep(interval.min(remaining)).await;
+    }
+}
+
+/// Issue user codes and poll until one is approved, requesting a fresh code
+/// when the current one expires.
+async fn wait_for_approval(
+    client: &reqwest::Client,
+    base_url: &str,
+    client_id: &str,
+    timing: PollTiming,
+    handler: &dyn DeviceCodeHandler,
+) -> std::io::Result<CodeSuccessResp> {
+    let api_base_url = format!("{base_url}/api/accounts");
+    let mut restarts = 0;
+    loop {
+        let uc = request_user_code(client, &api_base_url, client_id).await?;
+        let verification_url = uc
+            .verification_url
+            .clone()
+            .unwrap_or_else(|| format!("{base_url}/codex/device"));
+        let expires_in = uc
+            .expires_in
+            .map_or(DEFAULT_CODE_LIFETIME, Duration::from_secs);
+        handler.on_code(&uc.user_code, &verification_url, expires_in);
+
+        if let Some(code) =
+            poll_for_token(client, &api_base_url, &uc, expires_in, timing, handler).await?
+        {
+            return Ok(code);
+        }
+
+        if restarts == MAX_CODE_RESTARTS {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "device code expired before sign-in was completed",
+            ));
+        }
+        restarts += 1;
     }
 }
 
@@ -148,27 +316,39 @@ fn print_colored_warning_device_code() {
     let _ = stdout.flush();
 }
 
-/// Full device code login flow.
+/// Prints the code and verification URL for a terminal user.
+struct StdoutHandler;
+
+impl DeviceCodeHandler for StdoutHandler {
+    fn on_code(&self, user_code: &str, verification_url: &str, expires_in: Duration) {
+        let minutes = expires_in.as_secs().div_ceil(60);
+        println!(
+            "To authenticate:\n  1. Open in your browser: {ANSI_BOLD}{verification_url}{ANSI_RESET}\n  2. Enter the one-time code below within {minutes} minutes:\n\n     {ANSI_BOLD}{user_code}{ANSI_RESET}\n"
+        );
+    }
+
+    fn on_poll_status(&self, status: &DevicePollStatus) {
+        if *status == DevicePollStatus::Expired {
+            println!("The code expired before it was used.");
+        }
+    }
+}
+
+/// Full device code login flow, printing the code to stdout.
 pub async fn run_device_code_login(opts: ServerOptions) -> std::io::Result<()> {
-    let client = reqwest::Client::new();
-    let base_url = opts.issuer.trim_end_matches('/');
-    let api_base_url = format!("{}/api/accounts", opts.issuer.trim_end_matches('/'));
     print_colored_warning_device_code();
-    let uc = request_user_code(&client, &api_base_url, &opts.client_id).await?;
-
-    println!(
-        "To authenticate:\n  1. Open in your browser: {ANSI_BOLD}https://auth.openai.com/codex/device{ANSI_RESET}\n  2. Enter the one-time code below within 15 minutes:\n\n     {ANSI_BOLD}{}{ANSI_RESET}\n",
-        uc.user_code
-    );
+    run_device_code_login_with_handler(opts, &StdoutHandler).await
+}
 
-    let code_resp = poll_for_token(
-        &client,
-        &api_base_url,
-        &uc.device_auth_id,
-        &uc.user_code,
-        uc.interval,
-    )
-    .await?;
+/// Full device code login flow, reporting the code and progress to `handler`.
+pub async fn run_device_code_login_with_handler(
+    opts: ServerOptions,
+    handler: &dyn DeviceCodeHandler,
+) -> std::io::Result<()> {
+    let client = reqwest::Client::new();
+    let base_url = opts.issuer.trim_end_matches('/');
+    let code_resp =
+        wait_for_approval(&client, base_url, &opts.client_id, POLL_TIMING, handler).await?;
 
     let pkce = PkceCodes {
         code_verifier: code_resp.code_verifier,
@@ -203,3 +383,126 @@ pub async fn run_device_code_login(opts: ServerOptions) -> std::io::Result<()> {
     )
     .await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Mutex;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+
+    #[derive(Default)]
+    struct RecordingHandler {
+        statuses: Mutex<Vec<DevicePollStatus>>,
+    }
+
+    impl DeviceCodeHandler for RecordingHandler {
+        fn on_code(&self, _user_code: &str, _verification_url: &str, _expires_in: Duration) {}
+
+        fn on_poll_status(&self, status: &DevicePollStatus) {
+            self.statuses.lock().unwrap().push(status.clone());
+        }
+    }
+
+    #[test]
+    fn classifies_rfc_8628_poll_responses() {
+        let cases = [
+            (StatusCode::OK, "{}", PollResponse::Approved),
+            (
+                StatusCode::BAD_REQUEST,
+                r#"{"error":"authorization_pending"}"#,
+                PollResponse::Pending,
+            ),
+            (
+                StatusCode::BAD_REQUEST,
+                r#"{"error":{"code":"slow_down"}}"#,
+                PollResponse::SlowDown,
+            ),
+            (
+                StatusCode::BAD_REQUEST,
+                r#"{"error":"expired_token"}"#,
+                PollResponse::Expired,
+            ),
+            (
+                StatusCode::BAD_REQUEST,
+                r#"{"error":"access_denied"}"#,
+                PollResponse::Denied,
+            ),
+            (StatusCode::NOT_FOUND, "", PollResponse::Pending),
+            (StatusCode::FORBIDDEN, "", PollResponse::Pending),
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "",
+                PollResponse::Failed(
+                    "device auth failed with status 500 Internal Server Error".to_string(),
+                ),
+            ),
+        ];
+
+        for (status, body, expected) in cases {
+            assert_eq!(classify_poll_response(status, body), expected, "{body}");
+        }
+    }
+
+    /// The server sends `interval: 0`, which must not mean polling in a
+    /// tight loop.
+    #[tokio::test]
+    async fn slow_down_lengthens_the_clamped_polling_interval() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/accounts/deviceauth/usercode"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "device_auth_id": "device-auth-123",
+                "user_code": "CODE-12345",
+                "interval": 0
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/accounts/deviceauth/token"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({ "error": "slow_down" })))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/accounts/deviceauth/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "authorization_code": "poll-code-321",
+                "code_challenge": "code-challenge-321",
+                "code_verifier": "code-verifier-321"
+            })))
+            .mount(&server)
+            .await;
+
+        let handler = RecordingHandler::default();
+        let step = Duration::from_millis(10);
+        let timing = PollTiming {
+            min_interval: step * 2,
+            slow_down_step: step,
+        };
+        let code = wait_for_approval(
+            &reqwest::Client::new(),
+            &server.uri(),
+            "client-id",
+            timing,
+            &handler,
+        )
+        .await
+        .expect("approval");
+
+        assert_eq!(code.authorization_code, "poll-code-321");
+        let statuses = handler.statuses.lock().unwrap().clone();
+        assert_eq!(
+            statuses,
+            vec![
+                DevicePollStatus::SlowDown { interval: step * 3 },
+                DevicePollStatus::SlowDown { interval: step * 4 },
+                DevicePollStatus::Approved,
+            ]
+        );
+    }
+}
@@ -5,12 +5,17 @@ use base64::Engine;
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use codex_core::auth::AuthCredentialsStoreMode;
 use codex_core::auth::load_auth_dot_json;
+use codex_login::DeviceCodeHandler;
+use codex_login::DevicePollStatus;
 use codex_login::ServerOptions;
 use codex_login::run_device_code_login;
+use codex_login::run_device_code_login_with_handler;
 use serde_json::json;
 use std::sync::Arc;
+use std::sync::Mutex;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
+use std::time::Duration;
 use tempfile::tempdir;
 use wiremock::Mock;
 use wiremock::MockServer;
@@ -316,3 +321,214 @@ async fn device_code_login_integration_handles_error_payload() -> anyhow::Result
     );
     Ok(())
 }
+
+// ---------- RFC 8628 polling responses ----------
+
+#[derive(Default)]
+struct RecordingHandler {
+    codes: Mutex<Vec<(String, String, Duration)>>,
+    statuses: Mutex<Vec<DevicePollStatus>>,
+}
+
+impl DeviceCodeHandler for RecordingHandler {
+    fn on_code(&self, user_code: &str, verification_url: &str, expires_in: Duration) {
+        self.codes.lock().unwrap().push((
+            user_code.to_string(),
+            verification_url.to_string(),
+            expires_in,
+        ));
+    }
+
+    fn on_poll_status(&self, status: &DevicePollStatus) {
+        self.statuses.lock().unwrap().push(status.clone());
+    }
+}
+
+/// Issues `CODE-1`, `CODE-2`, ... on successive user code requests.
+async fn mock_usercode_sequence(server: &MockServer) {
+    let counter = AtomicUsize::new(0);
+    Mock::given(method("POST"))
+        .and(path("/api/accounts/deviceauth/usercode"))
+        .respond_with(move |_: &Request| {
+            let n = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            ResponseTemplate::new(200).set_body_json(json!({
+                "device_auth_id": format!("device-auth-{n}"),
+                "user_code": format!("CODE-{n}"),
+                "interval": 0,
+                "expires_in": 600,
+                "verification_uri": "https://example.com/device"
+            }))
+        })
+        .mount(server)
+        .await;
+}
+
+/// Answers token polls with `responses` in order, then approves.
+async fn mock_poll_token_sequence(server: &MockServer, responses: Vec<ResponseTemplate>) {
+    let counter = AtomicUsize::new(0);
+    Mock::given(method("POST"))
+        .and(path("/api/accounts/deviceauth/token"))
+        .respond_with(move |_: &Request| {
+            let attempt = counter.fetch_add(1, Ordering::SeqCst);
+            responses.get(attempt).cloned().unwrap_or_else(|| {
+                ResponseTemplate::new(200).set_body_json(json!({
+                    "authorization_code": "poll-code-321",
+                    "code_challenge": "code-challenge-321",
+                    "code_verifier": "code-verifier-321"
+                }))
+            })
+        })
+        .mount(server)
+        .await;
+}
+
+fn poll_error(code: &str) -> ResponseTemplate {
+    ResponseTemplate::new(400).set_body_json(json!({ "error": code }))
+}
+
+#[tokio::test]
+async fn device_code_login_reports_code_and_pending_polls() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let codex_home = tempdir().unwrap();
+    let mock_server = MockServer::start().await;
+    mock_usercode_sequence(&mock_server).await;
+    mock_poll_token_sequence(
+        &mock_server,
+        vec![
+            poll_error("authorization_pending"),
+            poll_error("authorization_pending"),
+        ],
+    )
+    .await;
+    mock_oauth_token_single(&mock_server, make_jwt(json!({}))).await;
+
+    let opts = server_opts(
+        &codex_home,
+        mock_server.uri(),
+        AuthCredentialsStoreMode::File,
+    );
+    let handler = RecordingHandler::default();
+    run_device_code_login_with_handler(opts, &handler)
+        .await
+        .expect("device code login should succeed after pending polls");
+
+    assert_eq!(
+        *handler.codes.lock().unwrap(),
+        vec![(
+            "CODE-1".to_string(),
+            "https://example.com/device".to_string(),
+            Duration::from_secs(600),
+        )]
+    );
+    assert_eq!(
+        *handler.statuses.lock().unwrap(),
+        vec![
+            DevicePollStatus::Pending,
+            DevicePollStatus::Pending,
+            DevicePollStatus::Approved,
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn device_code_login_requests_new_code_after_expiry() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let codex_home = tempdir().unwrap();
+    let mock_server = MockServer::start().await;
+    mock_usercode_sequence(&mock_server).await;
+    mock_poll_token_sequence(&mock_server, vec![poll_error("expired_token")]).await;
+    mock_oauth_token_single(&mock_server, make_jwt(json!({}))).await;
+
+    let opts = server_opts(
+        &codex_home,
+        mock_server.uri(),
+        AuthCredentialsStoreMode::File,
+    );
+    let handler = RecordingHandler::default();
+    run_device_code_login_with_handler(opts, &handler)
+        .await
+        .expect("device code login should succeed with a fresh code");
+
+    let codes: Vec<String> = handler
+        .codes
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(code, _, _)| code.clone())
+        .collect();
+    assert_eq!(codes, vec!["CODE-1".to_string(), "CODE-2".to_string()]);
+    assert_eq!(
+        *handler.statuses.lock().unwrap(),
+        vec![DevicePollStatus::Expired, DevicePollStatus::Approved]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn device_code_login_fails_when_codes_keep_expiring() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let codex_home = tempdir().unwrap();
+    let mock_server = MockServer::start().await;
+    mock_usercode_sequence(&mock_server).await;
+    mock_poll_token_sequence(
+        &mock_server,
+        vec![poll_error("expired_token"), poll_error("expired_token")],
+    )
+    .await;
+
+    let opts = server_opts(
+        &codex_home,
+        mock_server.uri(),
+        AuthCredentialsStoreMode::File,
+    );
+    let err = run_device_code_login_with_handler(opts, &RecordingHandler::default())
+        .await
+        .expect_err("device code login should give up after the retry expires");
+    assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+
+    let auth = load_auth_dot_json(codex_home.path(), AuthCredentialsStoreMode::File)
+        .context("auth.json should load after login fails")?;
+    assert!(auth.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn device_code_login_stops_when_access_is_denied() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let codex_home = tempdir().unwrap();
+    let mock_server = MockServer::start().await;
+    mock_usercode_sequence(&mock_server).await;
+    mock_poll_token_sequence(
+        &mock_server,
+        vec![
+            poll_error("authorization_pending"),
+            poll_error("access_denied"),
+        ],
+    )
+    .await;
+
+    let opts = server_opts(
+        &codex_home,
+        mock_server.uri(),
+        AuthCredentialsStoreMode::File,
+    );
+    let handler = RecordingHandler::default();
+    let err = run_device_code_login_with_handler(opts, &handler)
+        .await
+        .expect_err("denied device code login should fail");
+    assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+    assert_eq!(
+        *handler.statuses.lock().unwrap(),
+        vec![DevicePollStatus::Pending]
+    );
+
+    let auth = load_auth_dot_json(codex_home.path(), AuthCredentialsStoreMode::File)
+        .context("auth.json should load after login fails")?;
+    assert!(auth.is_none());
+    Ok(())
+}
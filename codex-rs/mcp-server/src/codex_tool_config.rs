@@ -171,6 +171,9 @@ impl CodexToolCallParam {
             tools_web_search_request: None,
             experimental_sandbox_command_assessment: None,
             additional_writable_roots: Vec::new(),
+            offline: None,
+            ephemeral: None,
+            replay_speed: None,
         };
 
         let cli_overrides = cli_overrides
@@ -7,6 +7,7 @@ use std::sync::Arc;
 
 use crate::exec_approval::handle_exec_approval_request;
 use crate::outgoing_message::OutgoingMessageSender;
+use crate::outgoing_message::OutgoingNotification;
 use crate::outgoing_message::OutgoingNotificationMeta;
 use crate::patch_approval::handle_patch_approval_request;
 use codex_core::CodexConversation;
@@ -25,7 +26,9 @@ use codex_protocol::ConversationId;
 use codex_protocol::user_input::UserInput;
 use mcp_types::CallToolResult;
 use mcp_types::ContentBlock;
+use mcp_types::ModelContextProtocolNotification;
 use mcp_types::RequestId;
+use mcp_types::ResourceListChangedNotification;
 use mcp_types::TextContent;
 use serde_json::json;
 use tokio::sync::Mutex;
@@ -104,6 +107,14 @@ pub async fn run_codex_tool_session(
         return;
     }
 
+    // The new session is now recorded, so it shows up in `resources/list`.
+    outgoing
+        .send_notification(OutgoingNotification {
+            method: ResourceListChangedNotification::METHOD.to_string(),
+            params: None,
+        })
+        .await;
+
     run_codex_tool_session_inner(
         conversation,
         outgoing,
@@ -181,6 +192,8 @@ async fn run_codex_tool_session_inner(
                         reason: _,
                         risk,
                         parsed_cmd,
+                        network: _,
+                        explanation_pending: _,
                     }) => {
                         handle_exec_approval_request(
                             command,
@@ -276,6 +289,7 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::McpToolCallEnd(_)
                     | EventMsg::McpListToolsResponse(_)
                     | EventMsg::ListCustomPromptsResponse(_)
+                    | EventMsg::ListContextScopesResponse(_)
                     | EventMsg::ExecCommandBegin(_)
                     | EventMsg::ExecCommandOutputDelta(_)
                     | EventMsg::ExecCommandEnd(_)
@@ -289,9 +303,13 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::GetHistoryEntryResponse(_)
                     | EventMsg::PlanUpdate(_)
                     | EventMsg::TurnAborted(_)
+                    | EventMsg::TurnLimitReached(_)
                     | EventMsg::UserMessage(_)
                     | EventMsg::ShutdownComplete
                     | EventMsg::ViewImageToolCall(_)
+                    | EventMsg::SandboxDenied(_)
+                    | EventMsg::ExecInputRequest(_)
+                    | EventMsg::ExecApprovalExplanation(_)
                     | EventMsg::RawResponseItem(_)
                     | EventMsg::EnteredReviewMode(_)
                     | EventMsg::ItemStarted(_)
@@ -302,6 +320,9 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::UndoStarted(_)
                     | EventMsg::UndoCompleted(_)
                     | EventMsg::ExitedReviewMode(_)
+                    | EventMsg::BaseDivergence(_)
+                    | EventMsg::ResumeBriefing(_)
+                    | EventMsg::HistoryFlagSet(_)
                     | EventMsg::DeprecationNotice(_) => {
                         // For now, we do not do anything extra for these
                         // events. Note that
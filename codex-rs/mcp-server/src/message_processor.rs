@@ -7,6 +7,7 @@ use crate::codex_tool_config::create_tool_for_codex_tool_call_param;
 use crate::codex_tool_config::create_tool_for_codex_tool_call_reply_param;
 use crate::error_code::INVALID_REQUEST_ERROR_CODE;
 use crate::outgoing_message::OutgoingMessageSender;
+use crate::session_resources;
 use codex_protocol::ConversationId;
 use codex_protocol::protocol::SessionSource;
 
@@ -28,6 +29,7 @@ use mcp_types::JSONRPCResponse;
 use mcp_types::ListToolsResult;
 use mcp_types::ModelContextProtocolRequest;
 use mcp_types::RequestId;
+use mcp_types::ServerCapabilitiesResources;
 use mcp_types::ServerCapabilitiesTools;
 use mcp_types::ServerNotification;
 use mcp_types::TextContent;
@@ -40,6 +42,7 @@ pub(crate) struct MessageProcessor {
     outgoing: Arc<OutgoingMessageSender>,
     initialized: bool,
     codex_linux_sandbox_exe: Option<PathBuf>,
+    config: Arc<Config>,
     conversation_manager: Arc<ConversationManager>,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ConversationId>>>,
 }
@@ -53,17 +56,14 @@ impl MessageProcessor {
         config: Arc<Config>,
     ) -> Self {
         let outgoing = Arc::new(outgoing);
-        let auth_manager = AuthManager::shared(
-            config.codex_home.clone(),
-            false,
-            config.cli_auth_credentials_store_mode,
-        );
+        let auth_manager = AuthManager::shared_from_config(&config, false);
         let conversation_manager =
             Arc::new(ConversationManager::new(auth_manager, SessionSource::Mcp));
         Self {
             outgoing,
             initialized: false,
             codex_linux_sandbox_exe,
+            config,
             conversation_manager,
             running_requests_id_to_codex_uuid: Arc::new(Mutex::new(HashMap::new())),
         }
@@ -90,13 +90,13 @@ impl MessageProcessor {
                 self.handle_ping(request_id, params).await;
             }
             McpClientRequest::ListResourcesRequest(params) => {
-                self.handle_list_resources(params);
+                self.handle_list_resources(request_id, params).await;
             }
             McpClientRequest::ListResourceTemplatesRequest(params) => {
                 self.handle_list_resource_templates(params);
             }
             McpClientRequest::ReadResourceRequest(params) => {
-                self.handle_read_resource(params);
+                self.handle_read_resource(request_id, params).await;
             }
             McpClientRequest::SubscribeRequest(params) => {
                 self.handle_subscribe(params);
@@ -209,7 +209,10 @@ impl MessageProcessor {
                 experimental: None,
                 logging: None,
                 prompts: None,
-                resources: None,
+                resources: Some(ServerCapabilitiesResources {
+                    list_changed: Some(true),
+                    subscribe: None,
+                }),
                 tools: Some(ServerCapabilitiesTools {
                     list_changed: Some(true),
                 }),
@@ -246,11 +249,26 @@ impl MessageProcessor {
             .await;
     }
 
-    fn handle_list_resources(
+    async fn handle_list_resources(
         &self,
+        id: RequestId,
         params: <mcp_types::ListResourcesRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::info!("resources/list -> params: {:?}", params);
+        let cursor = params.and_then(|params| params.cursor);
+        match session_resources::list_sessions(
+            &self.config.codex_home,
+            &self.config.model_provider_id,
+            cursor.as_deref(),
+        )
+        .await
+        {
+            Ok(result) => {
+                self.send_response::<mcp_types::ListResourcesRequest>(id, result)
+                    .await;
+            }
+            Err(error) => self.outgoing.send_error(id, error).await,
+        }
     }
 
     fn handle_list_resource_templates(
@@ -261,11 +279,19 @@ impl MessageProcessor {
         tracing::info!("resources/templates/list -> params: {:?}", params);
     }
 
-    fn handle_read_resource(
+    async fn handle_read_resource(
         &self,
+        id: RequestId,
         params: <mcp_types::ReadResourceRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::info!("resources/read -> params: {:?}", params);
+        match session_resources::read_session(&self.config.codex_home, &params.uri).await {
+            Ok(result) => {
+                self.send_response::<mcp_types::ReadResourceRequest>(id, result)
+                    .await;
+            }
+            Err(error) => self.outgoing.send_error(id, error).await,
+        }
     }
 
     fn handle_subscribe(
@@ -0,0 +1,362 @@
+//! Recorded Codex sessions exposed as MCP resources so clients can browse
+//! past transcripts.
+//!
+//! Each session is listed as `codex://sessions/<id>`. Reading it returns a
+//! markdown transcript, or the raw rollout with `?format=jsonl`. Either form
+//! is cut off after [`MAX_TRANSCRIPT_BYTES`] with a marker saying how much
+//! was left out.
+
+use std::path::Path;
+
+use codex_core::RolloutRecorder;
+use codex_core::find_conversation_path_by_id_str;
+use codex_core::parse_cursor;
+use codex_core::parse_turn_item;
+use codex_protocol::items::TurnItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::USER_MESSAGE_BEGIN;
+use mcp_types::Annotations;
+use mcp_types::JSONRPCErrorError;
+use mcp_types::ListResourcesResult;
+use mcp_types::ReadResourceResult;
+use mcp_types::ReadResourceResultContents;
+use mcp_types::Resource;
+use mcp_types::TextResourceContents;
+use serde_json::json;
+
+use crate::codex_tool_runner::INVALID_PARAMS_ERROR_CODE;
+use crate::error_code::INTERNAL_ERROR_CODE;
+use crate::error_code::RESOURCE_NOT_FOUND_ERROR_CODE;
+
+pub(crate) const SESSION_URI_PREFIX: &str = "codex://sessions/";
+const LIST_PAGE_SIZE: usize = 50;
+/// Longest session name, taken from the first user message.
+const MAX_NAME_CHARS: usize = 80;
+/// Transcripts are cut off at this size.
+pub(crate) const MAX_TRANSCRIPT_BYTES: usize = 256 * 1024;
+
+const MARKDOWN_MIME_TYPE: &str = "text/markdown";
+const JSONL_MIME_TYPE: &str = "application/jsonl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TranscriptFormat {
+    Markdown,
+    Jsonl,
+}
+
+/// One page of recorded sessions, newest first.
+pub(crate) async fn list_sessions(
+    codex_home: &Path,
+    default_provider: &str,
+    cursor: Option<&str>,
+) -> Result<ListResourcesResult, JSONRPCErrorError> {
+    let cursor = match cursor {
+        Some(token) => Some(
+            parse_cursor(token)
+                .ok_or_else(|| invalid_params(format!("invalid cursor: {token}")))?,
+        ),
+        None => None,
+    };
+    let page = RolloutRecorder::list_conversations(
+        codex_home,
+        LIST_PAGE_SIZE,
+        cursor.as_ref(),
+        &[],
+        None,
+        default_provider,
+    )
+    .await
+    .map_err(|err| internal_error(format!("failed to list sessions: {err}")))?;
+
+    let resources =
+        page.items
+            .into_iter()
+            .filter_map(|item| {
+                let meta = item.head.first()?;
+                let id = meta.get("id")?.as_str()?.to_string();
+                let name = first_user_message(&item.head)
+                    .map(|message| truncate_chars(&message, MAX_NAME_CHARS))
+                    .unwrap_or_else(|| id.clone());
+                let size = std::fs::metadata(&item.path)
+                    .ok()
+                    .and_then(|metadata| i64::try_from(metadata.len()).ok());
+                Some(Resource {
+                    annotations: item.updated_at.or(item.created_at).map(|last_modified| {
+                        Annotations {
+                            audience: None,
+                            last_modified: Some(last_modified),
+                            priority: None,
+                        }
+                    }),
+                    description: meta
+                        .get("cwd")
+                        .and_then(serde_json::Value::as_str)
+                        .map(|cwd| format!("Codex session in {cwd}")),
+                    mime_type: Some(MARKDOWN_MIME_TYPE.to_string()),
+                    name,
+                    size,
+                    title: None,
+                    uri: format!("{SESSION_URI_PREFIX}{id}"),
+                })
+            })
+            .collect();
+    let next_cursor = page
+        .next_cursor
+        .and_then(|cursor| serde_json::to_value(&cursor).ok())
+        .and_then(|value| value.as_str().map(str::to_owned));
+
+    Ok(ListResourcesResult {
+        next_cursor,
+        resources,
+    })
+}
+
+/// The transcript behind a `codex://sessions/<id>` uri.
+pub(crate) async fn read_session(
+    codex_home: &Path,
+    uri: &str,
+) -> Result<ReadResourceResult, JSONRPCErrorError> {
+    let (id, format) = parse_session_uri(uri)?;
+    let path = find_conversation_path_by_id_str(codex_home, id)
+        .await
+        .map_err(|err| internal_error(format!("failed to look up session {id}: {err}")))?
+        .ok_or_else(|| resource_not_found(uri))?;
+    let rollout = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|err| internal_error(format!("failed to read session {id}: {err}")))?;
+
+    let (text, mime_type) = match format {
+        TranscriptFormat::Markdown => (render_markdown(&rollout), MARKDOWN_MIME_TYPE),
+        TranscriptFormat::Jsonl => (cap_jsonl(&rollout), JSONL_MIME_TYPE),
+    };
+    Ok(ReadResourceResult {
+        contents: vec![ReadResourceResultContents::TextResourceContents(
+            TextResourceContents {
+                mime_type: Some(mime_type.to_string()),
+                text,
+                uri: uri.to_string(),
+            },
+        )],
+    })
+}
+
+fn parse_session_uri(uri: &str) -> Result<(&str, TranscriptFormat), JSONRPCErrorError> {
+    let rest = uri
+        .strip_prefix(SESSION_URI_PREFIX)
+        .ok_or_else(|| resource_not_found(uri))?;
+    let (id, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let mut format = TranscriptFormat::Markdown;
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        format = match pair.split_once('=') {
+            Some(("format", "markdown")) => TranscriptFormat::Markdown,
+            Some(("format", "jsonl")) => TranscriptFormat::Jsonl,
+            _ => {
+                return Err(invalid_params(format!(
+                    "unsupported query `{pair}`; expected `format=markdown` or `format=jsonl`"
+                )));
+            }
+        };
+    }
+    Ok((id, format))
+}
+
+fn first_user_message(head: &[serde_json::Value]) -> Option<String> {
+    let message = head
+        .iter()
+        .filter_map(|value| serde_json::from_value::<ResponseItem>(value.clone()).ok())
+        .find_map(|item| match parse_turn_item(&item) {
+            Some(TurnItem::UserMessage(user)) => Some(user.message()),
+            _ => None,
+        })?;
+    let message = match message.find(USER_MESSAGE_BEGIN) {
+        Some(idx) => &message[idx + USER_MESSAGE_BEGIN.len()..],
+        None => message.as_str(),
+    };
+    Some(message.trim().to_string())
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    let line = text.lines().next().unwrap_or_default();
+    if line.chars().count() <= max_chars && line.len() == text.len() {
+        return line.to_string();
+    }
+    let mut truncated: String = line.chars().take(max_chars.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+fn render_markdown(rollout: &str) -> String {
+    let mut sections = Vec::new();
+    for line in rollout.lines() {
+        let Ok(RolloutLine { item, .. }) = serde_json::from_str(line) else {
+            continue;
+        };
+        let section = match item {
+            RolloutItem::SessionMeta(meta_line) => {
+                let meta = meta_line.meta;
+                format!(
+                    "# Codex session {}\n\n- Started: {}\n- Directory: `{}`",
+                    meta.id,
+                    meta.timestamp,
+                    meta.cwd.display()
+                )
+            }
+            RolloutItem::EventMsg(EventMsg::UserMessage(event)) => {
+                format!("## User\n\n{}", event.message.trim())
+            }
+            RolloutItem::EventMsg(EventMsg::AgentMessage(event)) => {
+                format!("## Codex\n\n{}", event.message.trim())
+            }
+            RolloutItem::EventMsg(EventMsg::TurnAborted(_)) => "_Turn interrupted._".to_string(),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCall { name, .. }) => {
+                format!("_Called `{name}`._")
+            }
+            _ => continue,
+        };
+        sections.push(section);
+    }
+
+    let mut transcript = String::new();
+    for (i, section) in sections.iter().enumerate() {
+        if transcript.len() + section.len() + 2 > MAX_TRANSCRIPT_BYTES {
+            let omitted = sections.len() - i;
+            transcript.push_str(&format!(
+                "_[Transcript truncated: {omitted} more entries not shown.]_\n"
+            ));
+            break;
+        }
+        transcript.push_str(section);
+        transcript.push_str("\n\n");
+    }
+    transcript
+}
+
+/// The rollout lines that fit, followed by a `truncated` line when some did
+/// not.
+fn cap_jsonl(rollout: &str) -> String {
+    let lines: Vec<&str> = rollout.lines().filter(|line| !line.is_empty()).collect();
+    let mut capped = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        if capped.len() + line.len() + 1 > MAX_TRANSCRIPT_BYTES {
+            let marker = json!({
+                "type": "truncated",
+                "payload": { "omitted_lines": lines.len() - i },
+            });
+            capped.push_str(&format!("{marker}\n"));
+            break;
+        }
+        capped.push_str(line);
+        capped.push('\n');
+    }
+    capped
+}
+
+fn resource_not_found(uri: &str) -> JSONRPCErrorError {
+    JSONRPCErrorError {
+        code: RESOURCE_NOT_FOUND_ERROR_CODE,
+        message: "Resource not found".to_string(),
+        data: Some(json!({ "uri": uri })),
+    }
+}
+
+fn invalid_params(message: String) -> JSONRPCErrorError {
+    JSONRPCErrorError {
+        code: INVALID_PARAMS_ERROR_CODE,
+        message,
+        data: None,
+    }
+}
+
+fn internal_error(message: String) -> JSONRPCErrorError {
+    JSONRPCErrorError {
+        code: INTERNAL_ERROR_CODE,
+        message,
+        data: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn rollout_line(item: serde_json::Value) -> String {
+        let mut line = json!({ "timestamp": "2025-01-02T03:04:05.000Z" });
+        if let (Some(line), Some(item)) = (line.as_object_mut(), item.as_object()) {
+            line.extend(item.clone());
+        }
+        line.to_string()
+    }
+
+    #[test]
+    fn parses_session_uris() {
+        assert_eq!(
+            parse_session_uri("codex://sessions/abc").ok(),
+            Some(("abc", TranscriptFormat::Markdown))
+        );
+        assert_eq!(
+            parse_session_uri("codex://sessions/abc?format=jsonl").ok(),
+            Some(("abc", TranscriptFormat::Jsonl))
+        );
+        assert_eq!(
+            parse_session_uri("codex://sessions/abc?format=html")
+                .err()
+                .map(|err| err.code),
+            Some(INVALID_PARAMS_ERROR_CODE)
+        );
+        assert_eq!(
+            parse_session_uri("file:///etc/passwd")
+                .err()
+                .map(|err| err.code),
+            Some(RESOURCE_NOT_FOUND_ERROR_CODE)
+        );
+    }
+
+    #[test]
+    fn markdown_transcript_is_capped_with_marker() {
+        let message = "x".repeat(MAX_TRANSCRIPT_BYTES / 2);
+        let rollout = [
+            rollout_line(json!({
+                "type": "event_msg",
+                "payload": { "type": "user_message", "message": "first" },
+            })),
+            rollout_line(json!({
+                "type": "event_msg",
+                "payload": { "type": "agent_message", "message": message },
+            })),
+            rollout_line(json!({
+                "type": "event_msg",
+                "payload": { "type": "user_message", "message": message },
+            })),
+            rollout_line(json!({
+                "type": "event_msg",
+                "payload": { "type": "agent_message", "message": "last" },
+            })),
+        ]
+        .join("\n");
+
+        assert_eq!(
+            render_markdown(&rollout),
+            format!(
+                "## User\n\nfirst\n\n## Codex\n\n{message}\n\n_[Transcript truncated: 2 more entries not shown.]_\n"
+            )
+        );
+    }
+
+    #[test]
+    fn jsonl_transcript_is_capped_at_a_line_boundary() {
+        let long = "y".repeat(MAX_TRANSCRIPT_BYTES);
+        let rollout = format!("short\n{long}\nafter\n");
+
+        assert_eq!(
+            cap_jsonl(&rollout),
+            format!(
+                "short\n{}\n",
+                json!({ "type": "truncated", "payload": { "omitted_lines": 2 } })
+            )
+        );
+    }
+}
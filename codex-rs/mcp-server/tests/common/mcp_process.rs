@@ -18,12 +18,15 @@ use mcp_types::ClientCapabilities;
 use mcp_types::Implementation;
 use mcp_types::InitializeRequestParams;
 use mcp_types::JSONRPC_VERSION;
+use mcp_types::JSONRPCError;
 use mcp_types::JSONRPCMessage;
 use mcp_types::JSONRPCNotification;
 use mcp_types::JSONRPCRequest;
 use mcp_types::JSONRPCResponse;
+use mcp_types::ListResourcesRequestParams;
 use mcp_types::ModelContextProtocolNotification;
 use mcp_types::ModelContextProtocolRequest;
+use mcp_types::ReadResourceRequestParams;
 use mcp_types::RequestId;
 use pretty_assertions::assert_eq;
 use serde_json::json;
@@ -156,6 +159,9 @@ impl McpProcess {
                 id: RequestId::Integer(request_id),
                 result: json!({
                     "capabilities": {
+                        "resources": {
+                            "listChanged": true
+                        },
                         "tools": {
                             "listChanged": true
                         },
@@ -200,6 +206,26 @@ impl McpProcess {
         .await
     }
 
+    pub async fn send_list_resources_request(
+        &mut self,
+        params: Option<ListResourcesRequestParams>,
+    ) -> anyhow::Result<i64> {
+        let params = params.map(serde_json::to_value).transpose()?;
+        self.send_request(mcp_types::ListResourcesRequest::METHOD, params)
+            .await
+    }
+
+    pub async fn send_read_resource_request(
+        &mut self,
+        params: ReadResourceRequestParams,
+    ) -> anyhow::Result<i64> {
+        self.send_request(
+            mcp_types::ReadResourceRequest::METHOD,
+            Some(serde_json::to_value(params)?),
+        )
+        .await
+    }
+
     async fn send_request(
         &mut self,
         method: &str,
@@ -297,6 +323,33 @@ impl McpProcess {
         }
     }
 
+    pub async fn read_stream_until_error_message(
+        &mut self,
+        request_id: RequestId,
+    ) -> anyhow::Result<JSONRPCError> {
+        eprintln!("in read_stream_until_error_message({request_id:?})");
+
+        loop {
+            let message = self.read_jsonrpc_message().await?;
+            match message {
+                JSONRPCMessage::Notification(_) => {
+                    eprintln!("notification: {message:?}");
+                }
+                JSONRPCMessage::Request(_) => {
+                    anyhow::bail!("unexpected JSONRPCMessage::Request: {message:?}");
+                }
+                JSONRPCMessage::Response(_) => {
+                    anyhow::bail!("unexpected JSONRPCMessage::Response: {message:?}");
+                }
+                JSONRPCMessage::Error(jsonrpc_error) => {
+                    if jsonrpc_error.id == request_id {
+                        return Ok(jsonrpc_error);
+                    }
+                }
+            }
+        }
+    }
+
     /// Reads notifications until a legacy TaskComplete event is observed:
     /// Method "codex/event" with params.msg.type == "task_complete".
     pub async fn read_stream_until_legacy_task_complete_notification(
@@ -0,0 +1,205 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use mcp_test_support::McpProcess;
+use mcp_test_support::to_response;
+use mcp_types::Annotations;
+use mcp_types::JSONRPCErrorError;
+use mcp_types::ListResourcesResult;
+use mcp_types::ReadResourceRequestParams;
+use mcp_types::ReadResourceResult;
+use mcp_types::ReadResourceResultContents;
+use mcp_types::RequestId;
+use mcp_types::Resource;
+use mcp_types::TextResourceContents;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+const SESSION_ID: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+const STARTED_AT: &str = "2025-01-02T03:04:05.000Z";
+const FINISHED_AT: &str = "2025-01-02T03:04:09.000Z";
+
+/// Writes a short recorded session under `CODEX_HOME/sessions/`.
+fn create_fixture_rollout(codex_home: &Path) -> anyhow::Result<PathBuf> {
+    let dir = codex_home
+        .join("sessions")
+        .join("2025")
+        .join("01")
+        .join("02");
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("rollout-2025-01-02T03-04-05-{SESSION_ID}.jsonl"));
+    let lines = [
+        json!({
+            "timestamp": STARTED_AT,
+            "type": "session_meta",
+            "payload": {
+                "id": SESSION_ID,
+                "timestamp": STARTED_AT,
+                "cwd": "/tmp/project",
+                "originator": "codex",
+                "cli_version": "0.0.0",
+                "instructions": null,
+                "source": "cli",
+                "model_provider": "openai",
+            },
+        }),
+        json!({
+            "timestamp": STARTED_AT,
+            "type": "response_item",
+            "payload": {
+                "type": "message",
+                "role": "user",
+                "content": [{ "type": "input_text", "text": "Count to three" }],
+            },
+        }),
+        json!({
+            "timestamp": STARTED_AT,
+            "type": "event_msg",
+            "payload": { "type": "user_message", "message": "Count to three", "kind": "plain" },
+        }),
+        json!({
+            "timestamp": FINISHED_AT,
+            "type": "event_msg",
+            "payload": { "type": "agent_message", "message": "1, 2, 3" },
+        }),
+    ];
+    let rollout = lines
+        .iter()
+        .map(|line| format!("{line}\n"))
+        .collect::<String>();
+    std::fs::write(&path, rollout)?;
+    Ok(path)
+}
+
+#[tokio::test]
+async fn list_resources_returns_recorded_sessions() -> anyhow::Result<()> {
+    let codex_home = TempDir::new()?;
+    let rollout_path = create_fixture_rollout(codex_home.path())?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let request_id = mcp.send_list_resources_request(None).await?;
+    let response = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+
+    assert_eq!(
+        to_response::<ListResourcesResult>(response)?,
+        ListResourcesResult {
+            next_cursor: None,
+            resources: vec![Resource {
+                annotations: Some(Annotations {
+                    audience: None,
+                    last_modified: Some(FINISHED_AT.to_string()),
+                    priority: None,
+                }),
+                description: Some("Codex session in /tmp/project".to_string()),
+                mime_type: Some("text/markdown".to_string()),
+                name: "Count to three".to_string(),
+                size: Some(i64::try_from(std::fs::metadata(&rollout_path)?.len())?),
+                title: None,
+                uri: format!("codex://sessions/{SESSION_ID}"),
+            }],
+        }
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn read_resource_renders_markdown_transcript() -> anyhow::Result<()> {
+    let codex_home = TempDir::new()?;
+    create_fixture_rollout(codex_home.path())?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let uri = format!("codex://sessions/{SESSION_ID}");
+    let request_id = mcp
+        .send_read_resource_request(ReadResourceRequestParams { uri: uri.clone() })
+        .await?;
+    let response = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+
+    assert_eq!(
+        to_response::<ReadResourceResult>(response)?,
+        ReadResourceResult {
+            contents: vec![ReadResourceResultContents::TextResourceContents(
+                TextResourceContents {
+                    mime_type: Some("text/markdown".to_string()),
+                    text: format!(
+                        "# Codex session {SESSION_ID}\n\n- Started: {STARTED_AT}\n- Directory: `/tmp/project`\n\n## User\n\nCount to three\n\n## Codex\n\n1, 2, 3\n\n"
+                    ),
+                    uri,
+                },
+            )],
+        }
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn read_resource_returns_jsonl_when_requested() -> anyhow::Result<()> {
+    let codex_home = TempDir::new()?;
+    let rollout_path = create_fixture_rollout(codex_home.path())?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let uri = format!("codex://sessions/{SESSION_ID}?format=jsonl");
+    let request_id = mcp
+        .send_read_resource_request(ReadResourceRequestParams { uri: uri.clone() })
+        .await?;
+    let response = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+
+    assert_eq!(
+        to_response::<ReadResourceResult>(response)?,
+        ReadResourceResult {
+            contents: vec![ReadResourceResultContents::TextResourceContents(
+                TextResourceContents {
+                    mime_type: Some("application/jsonl".to_string()),
+                    text: std::fs::read_to_string(&rollout_path)?,
+                    uri,
+                },
+            )],
+        }
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn read_unknown_session_returns_resource_not_found() -> anyhow::Result<()> {
+    let codex_home = TempDir::new()?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let uri = format!("codex://sessions/{SESSION_ID}");
+    let request_id = mcp
+        .send_read_resource_request(ReadResourceRequestParams { uri: uri.clone() })
+        .await?;
+    let error = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+
+    assert_eq!(
+        error.error,
+        JSONRPCErrorError {
+            code: -32002,
+            message: "Resource not found".to_string(),
+            data: Some(json!({ "uri": uri })),
+        }
+    );
+    Ok(())
+}
@@ -2,12 +2,13 @@ use bytes::BytesMut;
 use futures::StreamExt;
 use futures::stream::BoxStream;
 use serde_json::Value as JsonValue;
-use std::collections::VecDeque;
 use std::io;
 
 use crate::parser::pull_events_from_value;
 use crate::pull::PullEvent;
+use crate::pull::PullProgress;
 use crate::pull::PullProgressReporter;
+use crate::pull::PullRetryPolicy;
 use crate::url::base_url_to_host_root;
 use crate::url::is_openai_compatible_base_url;
 use codex_core::ModelProviderInfo;
@@ -22,6 +23,15 @@ pub struct OllamaClient {
     client: reqwest::Client,
     host_root: String,
     uses_openai_compat: bool,
+    pull_retry: PullRetryPolicy,
+}
+
+/// Why a single `/api/pull` request could not be started.
+enum PullStartError {
+    /// The request never reached the server; worth retrying.
+    Transport(io::Error),
+    /// The server rejected the request.
+    Rejected(io::Error),
 }
 
 impl OllamaClient {
@@ -71,6 +81,7 @@ impl OllamaClient {
             client,
             host_root,
             uses_openai_compat,
+            pull_retry: PullRetryPolicy::default(),
         };
         client.probe_server().await?;
         Ok(client)
@@ -125,12 +136,64 @@ impl OllamaClient {
         Ok(names)
     }
 
+    /// Maximum context length of `model` as reported by `/api/show`, or `None`
+    /// when the server does not report one.
+    pub async fn fetch_model_max_context(&self, model: &str) -> io::Result<Option<u32>> {
+        let show_url = format!("{}/api/show", self.host_root.trim_end_matches('/'));
+        let resp = self
+            .client
+            .post(show_url)
+            .json(&serde_json::json!({ "model": model }))
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            return Ok(None);
+        }
+        let val = resp.json::<JsonValue>().await.map_err(io::Error::other)?;
+        // Keys are prefixed with the architecture, e.g. `llama.context_length`.
+        let max_context = val
+            .get("model_info")
+            .and_then(|info| info.as_object())
+            .and_then(|info| {
+                info.iter()
+                    .find(|(key, _)| key.ends_with(".context_length"))
+                    .and_then(|(_, value)| value.as_u64())
+            })
+            .map(|len| u32::try_from(len).unwrap_or(u32::MAX));
+        Ok(max_context)
+    }
+
+    /// Replace the policy used by [`Self::pull_with_reporter`] to retry
+    /// interrupted pulls.
+    pub fn with_pull_retry(mut self, policy: PullRetryPolicy) -> Self {
+        self.pull_retry = policy;
+        self
+    }
+
     /// Start a model pull and emit streaming events. The returned stream ends when
     /// a Success event is observed or the server closes the connection.
     pub async fn pull_model_stream(
         &self,
         model: &str,
     ) -> io::Result<BoxStream<'static, PullEvent>> {
+        let stream = match self.start_pull(model).await {
+            Ok(stream) => stream,
+            Err(PullStartError::Transport(err) | PullStartError::Rejected(err)) => {
+                return Err(err);
+            }
+        };
+        Ok(Box::pin(
+            stream.scan((), |_, event| futures::future::ready(event.ok())),
+        ))
+    }
+
+    /// Issue one `/api/pull` request. A connection that fails or closes before
+    /// the server reports success ends the stream with an `Err` item.
+    async fn start_pull(
+        &self,
+        model: &str,
+    ) -> Result<BoxStream<'static, io::Result<PullEvent>>, PullStartError> {
         let url = format!("{}/api/pull", self.host_root.trim_end_matches('/'));
         let resp = self
             .client
@@ -138,17 +201,16 @@ impl OllamaClient {
             .json(&serde_json::json!({"model": model, "stream": true}))
             .send()
             .await
-            .map_err(io::Error::other)?;
+            .map_err(|err| PullStartError::Transport(io::Error::other(err)))?;
         if !resp.status().is_success() {
-            return Err(io::Error::other(format!(
+            return Err(PullStartError::Rejected(io::Error::other(format!(
                 "failed to start pull: HTTP {}",
                 resp.status()
-            )));
+            ))));
         }
 
         let mut stream = resp.bytes_stream();
         let mut buf = BytesMut::new();
-        let _pending: VecDeque<PullEvent> = VecDeque::new();
 
         // Using an async stream adaptor backed by unfold-like manual loop.
         let s = async_stream::stream! {
@@ -162,60 +224,127 @@ impl OllamaClient {
                                 let text = text.trim();
                                 if text.is_empty() { continue; }
                                 if let Ok(value) = serde_json::from_str::<JsonValue>(text) {
-                                    for ev in pull_events_from_value(&value) { yield ev; }
+                                    for ev in pull_events_from_value(&value) { yield Ok(ev); }
                                     if let Some(err_msg) = value.get("error").and_then(|e| e.as_str()) {
-                                        yield PullEvent::Error(err_msg.to_string());
+                                        yield Ok(PullEvent::Error(err_msg.to_string()));
                                         return;
                                     }
                                     if let Some(status) = value.get("status").and_then(|s| s.as_str())
-                                        && status == "success" { yield PullEvent::Success; return; }
+                                        && status == "success" { return; }
                                 }
                             }
                         }
                     }
-                    Err(_) => {
-                        // Connection error: end the stream.
+                    Err(err) => {
+                        yield Err(io::Error::other(err));
                         return;
                     }
                 }
             }
+            yield Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "connection closed before the pull finished",
+            ));
         };
 
         Ok(Box::pin(s))
     }
 
     /// High-level helper to pull a model and drive a progress reporter.
+    ///
+    /// Transient network errors restart the pull (Ollama keeps partially
+    /// downloaded layers) with exponential backoff, up to the configured
+    /// number of attempts. Layer progress is tracked across reconnects, and
+    /// success is only reported once every layer is complete and the server
+    /// holds a blob for each layer digest.
     pub async fn pull_with_reporter(
         &self,
         model: &str,
         reporter: &mut dyn PullProgressReporter,
     ) -> io::Result<()> {
         reporter.on_event(&PullEvent::Status(format!("Pulling model {model}...")))?;
-        let mut stream = self.pull_model_stream(model).await?;
-        while let Some(event) = stream.next().await {
-            reporter.on_event(&event)?;
-            match event {
-                PullEvent::Success => {
-                    return Ok(());
-                }
-                PullEvent::Error(err) => {
-                    // Empirically, ollama returns a 200 OK response even when
-                    // the output stream includes an error message. Verify with:
-                    //
-                    // `curl -i http://localhost:11434/api/pull -d '{ "model": "foobarbaz" }'`
-                    //
-                    // As such, we have to check the event stream, not the
-                    // HTTP response status, to determine whether to return Err.
-                    return Err(io::Error::other(format!("Pull failed: {err}")));
-                }
-                PullEvent::ChunkProgress { .. } | PullEvent::Status(_) => {
-                    continue;
-                }
+        let policy = self.pull_retry;
+        let mut progress = PullProgress::default();
+        let mut backoff = policy.initial_backoff;
+        let mut attempt = 1;
+        loop {
+            let failure = match self.start_pull(model).await {
+                Ok(mut stream) => loop {
+                    let Some(event) = stream.next().await else {
+                        break io::Error::other("Pull stream ended unexpectedly without success.");
+                    };
+                    match event {
+                        Ok(PullEvent::Success) => {
+                            progress.verify()?;
+                            self.verify_layer_digests(&progress).await?;
+                            reporter.on_event(&PullEvent::Success)?;
+                            return Ok(());
+                        }
+                        Ok(PullEvent::Error(err)) => {
+                            reporter.on_event(&PullEvent::Error(err.clone()))?;
+                            // Empirically, ollama returns a 200 OK response even when
+                            // the output stream includes an error message. Verify with:
+                            //
+                            // `curl -i http://localhost:11434/api/pull -d '{ "model": "foobarbaz" }'`
+                            //
+                            // As such, we have to check the event stream, not the
+                            // HTTP response status, to determine whether to return Err.
+                            return Err(io::Error::other(format!("Pull failed: {err}")));
+                        }
+                        Ok(event) => reporter.on_event(&progress.observe(event))?,
+                        Err(err) => break err,
+                    }
+                },
+                Err(PullStartError::Transport(err)) => err,
+                Err(PullStartError::Rejected(err)) => return Err(err),
+            };
+
+            if attempt >= policy.max_attempts {
+                let layer = match progress.in_progress() {
+                    Some((digest, offset)) => format!("layer {digest} (at {offset} bytes)"),
+                    None => "the pull".to_string(),
+                };
+                return Err(io::Error::other(format!(
+                    "Pull of {model} failed after {attempt} attempts; {layer} kept failing: {failure}"
+                )));
+            }
+            tracing::warn!(
+                "Pull of {model} interrupted (attempt {attempt}): {failure}; retrying in {backoff:?}"
+            );
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(policy.max_backoff);
+            attempt += 1;
+            if let Some((digest, offset)) = progress.in_progress() {
+                reporter.on_event(&PullEvent::Resumed {
+                    digest: digest.to_string(),
+                    offset,
+                })?;
             }
         }
-        Err(io::Error::other(
-            "Pull stream ended unexpectedly without success.",
-        ))
+    }
+
+    /// Ask the server for the blob of every pulled layer. Ollama checks each
+    /// layer's sha256 against its digest before storing it under that digest,
+    /// so a blob that is missing means the layer did not verify.
+    async fn verify_layer_digests(&self, progress: &PullProgress) -> io::Result<()> {
+        let host_root = self.host_root.trim_end_matches('/');
+        for digest in progress.layers() {
+            let resp = self
+                .client
+                .head(format!("{host_root}/api/blobs/{digest}"))
+                .send()
+                .await
+                .map_err(|err| {
+                    io::Error::other(format!("Failed to verify layer {digest}: {err}"))
+                })?;
+            if !resp.status().is_success() {
+                return Err(io::Error::other(format!(
+                    "Pull reported success but layer {digest} failed verification (HTTP {})",
+                    resp.status()
+                )));
+            }
+        }
+        Ok(())
     }
 
     /// Low-level constructor given a raw host root, e.g. "http://localhost:11434".
@@ -229,6 +358,7 @@ impl OllamaClient {
             client,
             host_root: host_root.into(),
             uses_openai_compat: false,
+            pull_retry: PullRetryPolicy::default(),
         }
     }
 }
@@ -236,6 +366,239 @@ impl OllamaClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
+
+    #[derive(Default)]
+    struct RecordingReporter(Vec<PullEvent>);
+
+    impl PullProgressReporter for RecordingReporter {
+        fn on_event(&mut self, event: &PullEvent) -> io::Result<()> {
+            self.0.push(event.clone());
+            Ok(())
+        }
+    }
+
+    /// Serve one scripted `/api/pull` response per pull request, and answer
+    /// `HEAD /api/blobs/<digest>` with 200 for the digests in `blobs`. A
+    /// response marked as dropped advertises more body than it sends and then
+    /// closes the connection, like a network failure in the middle of a layer.
+    async fn start_pull_server(
+        responses: Vec<(Vec<JsonValue>, bool)>,
+        blobs: &'static [&'static str],
+    ) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind");
+        let addr = listener.local_addr().expect("local addr");
+        tokio::spawn(async move {
+            let mut responses = responses.into_iter();
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let head = read_request(&mut socket).await;
+                if let Some(digest) = head.strip_prefix("head /api/blobs/") {
+                    let digest = digest.split_whitespace().next().unwrap_or_default();
+                    let status = if blobs.contains(&digest) {
+                        "200 OK"
+                    } else {
+                        "404 Not Found"
+                    };
+                    let head = format!(
+                        "HTTP/1.1 {status}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                    );
+                    let _ = socket.write_all(head.as_bytes()).await;
+                    let _ = socket.shutdown().await;
+                    continue;
+                }
+                let Some((lines, dropped)) = responses.next() else {
+                    return;
+                };
+                let body: String = lines.iter().map(|line| format!("{line}\n")).collect();
+                let advertised = if dropped {
+                    body.len() + 1024
+                } else {
+                    body.len()
+                };
+                let head = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/x-ndjson\r\ncontent-length: {advertised}\r\nconnection: close\r\n\r\n"
+                );
+                let _ = socket.write_all(head.as_bytes()).await;
+                let _ = socket.write_all(body.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+        format!("http://{addr}")
+    }
+
+    /// Read one request and return its lowercased head.
+    async fn read_request(socket: &mut tokio::net::TcpStream) -> String {
+        let mut request = Vec::new();
+        let mut chunk = [0u8; 1024];
+        loop {
+            match socket.read(&mut chunk).await {
+                Ok(0) | Err(_) => return String::from_utf8_lossy(&request).to_ascii_lowercase(),
+                Ok(n) => request.extend_from_slice(&chunk[..n]),
+            }
+            if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                let head = String::from_utf8_lossy(&request[..end]).to_ascii_lowercase();
+                let length = head
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length:"))
+                    .and_then(|value| value.trim().parse::<usize>().ok())
+                    .unwrap_or(0);
+                if request.len() >= end + 4 + length {
+                    return head;
+                }
+            }
+        }
+    }
+
+    fn layer_progress(completed: u64) -> JsonValue {
+        serde_json::json!({
+            "status": "pulling aaa",
+            "digest": "sha256:aaa",
+            "total": 100,
+            "completed": completed,
+        })
+    }
+
+    fn fast_retry(max_attempts: u32) -> PullRetryPolicy {
+        PullRetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+        }
+    }
+
+    #[tokio::test]
+    async fn pull_resumes_after_connection_drops_mid_layer() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} set; skipping pull_resumes_after_connection_drops_mid_layer",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let host_root = start_pull_server(
+            vec![
+                (vec![layer_progress(40)], true),
+                (
+                    vec![
+                        // Ollama restarts its byte count for the layer.
+                        layer_progress(10),
+                        layer_progress(100),
+                        serde_json::json!({"status": "success"}),
+                    ],
+                    false,
+                ),
+            ],
+            &["sha256:aaa"],
+        )
+        .await;
+        let client = OllamaClient::from_host_root(host_root).with_pull_retry(fast_retry(3));
+        let mut reporter = RecordingReporter::default();
+        client
+            .pull_with_reporter("gpt-oss:20b", &mut reporter)
+            .await
+            .expect("pull succeeds after resuming");
+
+        let pulling = || PullEvent::Status("pulling aaa".to_string());
+        let progress = |completed| PullEvent::ChunkProgress {
+            digest: "sha256:aaa".to_string(),
+            total: Some(100),
+            completed: Some(completed),
+        };
+        assert_eq!(
+            reporter.0,
+            vec![
+                PullEvent::Status("Pulling model gpt-oss:20b...".to_string()),
+                pulling(),
+                progress(40),
+                PullEvent::Resumed {
+                    digest: "sha256:aaa".to_string(),
+                    offset: 40,
+                },
+                pulling(),
+                progress(40),
+                pulling(),
+                progress(100),
+                PullEvent::Status("success".to_string()),
+                PullEvent::Success,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn pull_error_names_the_layer_that_kept_failing() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} set; skipping pull_error_names_the_layer_that_kept_failing",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let host_root = start_pull_server(
+            vec![
+                (vec![layer_progress(40)], true),
+                (vec![layer_progress(40)], true),
+            ],
+            &[],
+        )
+        .await;
+        let client = OllamaClient::from_host_root(host_root).with_pull_retry(fast_retry(2));
+        let mut reporter = RecordingReporter::default();
+        let err = client
+            .pull_with_reporter("gpt-oss:20b", &mut reporter)
+            .await
+            .expect_err("pull should give up");
+        let message = err.to_string();
+        assert!(
+            message.starts_with(
+                "Pull of gpt-oss:20b failed after 2 attempts; layer sha256:aaa (at 40 bytes) kept failing:"
+            ),
+            "unexpected error: {message}"
+        );
+    }
+
+    #[tokio::test]
+    async fn pull_fails_when_a_layer_blob_does_not_verify() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} set; skipping pull_fails_when_a_layer_blob_does_not_verify",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let host_root = start_pull_server(
+            vec![(
+                vec![
+                    layer_progress(100),
+                    serde_json::json!({"status": "success"}),
+                ],
+                false,
+            )],
+            &[],
+        )
+        .await;
+        let client = OllamaClient::from_host_root(host_root).with_pull_retry(fast_retry(1));
+        let mut reporter = RecordingReporter::default();
+        let err = client
+            .pull_with_reporter("gpt-oss:20b", &mut reporter)
+            .await
+            .expect_err("unverified layer fails the pull");
+        assert_eq!(
+            err.to_string(),
+            "Pull reported success but layer sha256:aaa failed verification (HTTP 404 Not Found)"
+        );
+        assert!(!reporter.0.contains(&PullEvent::Success));
+    }
 
     // Happy-path tests using a mock HTTP server; skip if sandbox network is disabled.
     #[tokio::test]
@@ -269,6 +632,54 @@ mod tests {
         assert!(models.contains(&"mistral".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_fetch_model_max_context_reads_model_info() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping test_fetch_model_max_context_reads_model_info",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/api/show"))
+            .and(wiremock::matchers::body_json(
+                serde_json::json!({ "model": "llama3.2:3b" }),
+            ))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_raw(
+                    serde_json::json!({
+                        "model_info": {
+                            "general.architecture": "llama",
+                            "llama.context_length": 131072
+                        }
+                    })
+                    .to_string(),
+                    "application/json",
+                ),
+            )
+            .mount(&server)
+            .await;
+
+        let client = OllamaClient::from_host_root(server.uri());
+        assert_eq!(
+            client
+                .fetch_model_max_context("llama3.2:3b")
+                .await
+                .expect("fetch context"),
+            Some(131_072)
+        );
+        assert_eq!(
+            client
+                .fetch_model_max_context("missing")
+                .await
+                .expect("fetch context"),
+            None
+        );
+    }
+
     #[tokio::test]
     async fn test_probe_server_happy_path_openai_compat_and_native() {
         if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
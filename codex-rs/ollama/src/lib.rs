@@ -5,29 +5,51 @@ mod url;
 
 pub use client::OllamaClient;
 use codex_core::config::Config;
+use codex_core::network_policy::NetworkCapability;
 pub use pull::CliProgressReporter;
 pub use pull::PullEvent;
 pub use pull::PullProgressReporter;
+pub use pull::PullRetryPolicy;
 pub use pull::TuiProgressReporter;
 
 /// Default OSS model to use when `--oss` is passed without an explicit `-m`.
 pub const DEFAULT_OSS_MODEL: &str = "gpt-oss:20b";
 
+/// Smallest context window requested for a detected model; some models report
+/// a tiny maximum that would make sessions unusable.
+pub const DEFAULT_MIN_NUM_CTX: u32 = 4096;
+
+/// Overrides [`DEFAULT_MIN_NUM_CTX`].
+pub const MIN_NUM_CTX_ENV_VAR: &str = "CODEX_OLLAMA_MIN_NUM_CTX";
+
+/// Overrides the number of attempts made to pull a missing model.
+pub const PULL_ATTEMPTS_ENV_VAR: &str = "CODEX_OLLAMA_PULL_ATTEMPTS";
+
 /// Prepare the local OSS environment when `--oss` is selected.
 ///
 /// - Ensures a local Ollama server is reachable.
 /// - Checks if the model exists locally and pulls it if missing.
-pub async fn ensure_oss_ready(config: &Config) -> std::io::Result<()> {
+/// - Sets the provider's `num_ctx` to the model's maximum context unless it
+///   is already configured.
+pub async fn ensure_oss_ready(config: &mut Config) -> std::io::Result<()> {
     // Only download when the requested model is the default OSS model (or when -m is not provided).
     let model = config.model.as_ref();
 
     // Verify local Ollama is reachable.
-    let ollama_client = crate::OllamaClient::try_from_oss_provider(config).await?;
+    let ollama_client = crate::OllamaClient::try_from_oss_provider(config)
+        .await?
+        .with_pull_retry(pull_retry_policy());
 
     // If the model is not present locally, pull it.
     match ollama_client.fetch_models().await {
         Ok(models) => {
             if !models.iter().any(|m| m == model) {
+                if let Err(blocked) = config.network_policy().check(NetworkCapability::Downloads) {
+                    // Leave the missing model for the provider to report; pulling
+                    // would contact the Ollama registry.
+                    tracing::warn!("Model `{model}` is not available locally: {blocked}.");
+                    return Ok(());
+                }
                 let mut reporter = crate::CliProgressReporter::new();
                 ollama_client
                     .pull_with_reporter(model, &mut reporter)
@@ -40,5 +62,109 @@ pub async fn ensure_oss_ready(config: &Config) -> std::io::Result<()> {
         }
     }
 
+    if config.model_provider.num_ctx.is_none() {
+        match ollama_client.fetch_model_max_context(&config.model).await {
+            Ok(Some(max_context)) => {
+                config.model_provider.num_ctx = Some(floor_num_ctx(max_context, min_num_ctx()));
+            }
+            Ok(None) => {}
+            Err(err) => {
+                tracing::warn!(
+                    "Failed to query the context length of `{}`: {err}.",
+                    config.model
+                );
+            }
+        }
+    }
+
     Ok(())
 }
+
+fn pull_retry_policy() -> PullRetryPolicy {
+    let mut policy = PullRetryPolicy::default();
+    if let Some(attempts) = std::env::var(PULL_ATTEMPTS_ENV_VAR)
+        .ok()
+        .and_then(|v| v.trim().parse::<u32>().ok())
+    {
+        policy.max_attempts = attempts.max(1);
+    }
+    policy
+}
+
+fn min_num_ctx() -> u32 {
+    std::env::var(MIN_NUM_CTX_ENV_VAR)
+        .ok()
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .unwrap_or(DEFAULT_MIN_NUM_CTX)
+}
+
+fn floor_num_ctx(max_context: u32, min_num_ctx: u32) -> u32 {
+    max_context.max(min_num_ctx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::config::ConfigOverrides;
+    use codex_core::config::ConfigToml;
+
+    #[test]
+    fn tiny_context_lengths_are_floored() {
+        assert_eq!(floor_num_ctx(2048, DEFAULT_MIN_NUM_CTX), 4096);
+        assert_eq!(floor_num_ctx(131_072, DEFAULT_MIN_NUM_CTX), 131_072);
+        assert_eq!(floor_num_ctx(2048, 1024), 2048);
+    }
+
+    #[tokio::test]
+    async fn offline_does_not_pull_missing_model() -> std::io::Result<()> {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} set; skipping offline_does_not_pull_missing_model",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return Ok(());
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/v1/models"))
+            .respond_with(wiremock::ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/api/tags"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200)
+                    .set_body_raw(r#"{"models":[]}"#, "application/json"),
+            )
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/api/pull"))
+            .respond_with(wiremock::ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let codex_home = tempfile::TempDir::new()?;
+        let mut config = Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides {
+                offline: Some(true),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+        config.model_providers.insert(
+            codex_core::OLLAMA_OSS_PROVIDER_ID.to_string(),
+            codex_core::create_oss_provider_with_base_url(
+                &format!("{}/v1", server.uri()),
+                codex_core::WireApi::Chat,
+            ),
+        );
+
+        ensure_oss_ready(&mut config).await?;
+        server.verify().await;
+        Ok(())
+    }
+}
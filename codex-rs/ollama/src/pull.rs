@@ -1,9 +1,11 @@
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::io;
 use std::io::Write;
+use std::time::Duration;
 
 /// Events emitted while pulling a model from Ollama.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PullEvent {
     /// A human-readable status message (e.g., "verifying", "writing").
     Status(String),
@@ -13,6 +15,9 @@ pub enum PullEvent {
         total: Option<u64>,
         completed: Option<u64>,
     },
+    /// The connection dropped and the pull was restarted; `offset` is the
+    /// number of bytes of `digest` already downloaded.
+    Resumed { digest: String, offset: u64 },
     /// The pull finished successfully.
     Success,
 
@@ -20,6 +25,110 @@ pub enum PullEvent {
     Error(String),
 }
 
+/// How [`OllamaClient::pull_with_reporter`](crate::OllamaClient::pull_with_reporter)
+/// retries a pull after transient network errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PullRetryPolicy {
+    /// Total number of requests made before giving up, including the first.
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled after each further failure.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for PullRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct LayerProgress {
+    total: Option<u64>,
+    completed: Option<u64>,
+}
+
+/// Per-layer progress of a pull, kept across reconnects.
+#[derive(Debug, Default)]
+pub(crate) struct PullProgress {
+    layers: BTreeMap<String, LayerProgress>,
+    /// Layer that most recently reported progress.
+    current: Option<String>,
+}
+
+impl PullProgress {
+    /// Fold `event` into the tracked progress. Ollama restarts its byte counts
+    /// when a pull is re-requested, so the returned event never reports fewer
+    /// completed bytes for a layer than were already seen.
+    pub(crate) fn observe(&mut self, event: PullEvent) -> PullEvent {
+        let PullEvent::ChunkProgress {
+            digest,
+            total,
+            completed,
+        } = event
+        else {
+            return event;
+        };
+        if digest.is_empty() {
+            return PullEvent::ChunkProgress {
+                digest,
+                total,
+                completed,
+            };
+        }
+        let layer = self.layers.entry(digest.clone()).or_default();
+        if total.is_some() {
+            layer.total = total;
+        }
+        if let Some(completed) = completed {
+            layer.completed = Some(layer.completed.unwrap_or(0).max(completed));
+        }
+        let event = PullEvent::ChunkProgress {
+            digest: digest.clone(),
+            total: layer.total,
+            completed: completed.and(layer.completed),
+        };
+        self.current = Some(digest);
+        event
+    }
+
+    /// The partially downloaded layer the pull was working on, with the bytes
+    /// completed so far.
+    pub(crate) fn in_progress(&self) -> Option<(&str, u64)> {
+        let digest = self.current.as_deref()?;
+        let layer = self.layers.get(digest)?;
+        let completed = layer.completed.unwrap_or(0);
+        match layer.total {
+            Some(total) if completed >= total => None,
+            _ => Some((digest, completed)),
+        }
+    }
+
+    /// Digests of every layer the server reported progress for.
+    pub(crate) fn layers(&self) -> impl Iterator<Item = &str> {
+        self.layers.keys().map(String::as_str)
+    }
+
+    /// Check that every layer the server reported progress for was fully
+    /// downloaded.
+    pub(crate) fn verify(&self) -> io::Result<()> {
+        for (digest, layer) in &self.layers {
+            if let (Some(total), Some(completed)) = (layer.total, layer.completed)
+                && completed < total
+            {
+                return Err(io::Error::other(format!(
+                    "Pull reported success but layer {digest} is incomplete ({completed} of {total} bytes)"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
 /// A simple observer for pull progress events. Implementations decide how to
 /// render progress (CLI, TUI, logs, ...).
 pub trait PullProgressReporter {
@@ -122,6 +231,29 @@ impl PullProgressReporter for CliProgressReporter {
                     Ok(())
                 }
             }
+            PullEvent::Resumed { digest, offset } => {
+                let layer = self
+                    .totals_by_digest
+                    .entry(digest.clone())
+                    .or_insert((0, 0));
+                layer.1 = *offset;
+                let short = digest.strip_prefix("sha256:").unwrap_or(digest);
+                let short = short.get(..12).unwrap_or(short);
+                let text = if layer.0 > 0 {
+                    let pct = (*offset as f64) * 100.0 / (layer.0 as f64);
+                    format!("Connection lost; resuming {short} at {pct:.0}%")
+                } else {
+                    format!("Connection lost; resuming {short} at {offset} bytes")
+                };
+                // Restart the speed estimate from the resumed position.
+                self.last_completed_sum = self.totals_by_digest.values().map(|(_, c)| *c).sum();
+                self.last_instant = std::time::Instant::now();
+                let pad = self.last_line_len.saturating_sub(text.len());
+                let line = format!("\r{text}{}", " ".repeat(pad));
+                self.last_line_len = text.len();
+                out.write_all(line.as_bytes())?;
+                out.flush()
+            }
             PullEvent::Error(_) => {
                 // This will be handled by the caller, so we don't do anything
                 // here or the error will be printed twice.
@@ -145,3 +277,52 @@ impl PullProgressReporter for TuiProgressReporter {
         self.0.on_event(event)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn progress(digest: &str, total: u64, completed: u64) -> PullEvent {
+        PullEvent::ChunkProgress {
+            digest: digest.to_string(),
+            total: Some(total),
+            completed: Some(completed),
+        }
+    }
+
+    #[test]
+    fn completed_bytes_do_not_go_backwards_after_reconnect() {
+        let mut tracker = PullProgress::default();
+        tracker.observe(progress("sha256:aaa", 100, 40));
+        assert_eq!(tracker.in_progress(), Some(("sha256:aaa", 40)));
+
+        // After a reconnect the server starts counting the layer from zero.
+        assert_matches!(
+            tracker.observe(progress("sha256:aaa", 100, 5)),
+            PullEvent::ChunkProgress {
+                completed: Some(40),
+                ..
+            }
+        );
+        tracker.observe(progress("sha256:aaa", 100, 100));
+        assert_eq!(tracker.in_progress(), None);
+        assert_eq!(tracker.verify().ok(), Some(()));
+    }
+
+    #[test]
+    fn verify_names_the_incomplete_layer() {
+        let mut tracker = PullProgress::default();
+        tracker.observe(progress("sha256:aaa", 100, 100));
+        tracker.observe(progress("sha256:bbb", 50, 20));
+        assert_eq!(
+            tracker.verify().map_err(|err| err.to_string()),
+            Err(
+                "Pull reported success but layer sha256:bbb is incomplete (20 of 50 bytes)"
+                    .to_string()
+            )
+        );
+    }
+}
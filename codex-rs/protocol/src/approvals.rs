@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::path::PathBuf;
 
 use crate::parse_command::ParsedCommand;
@@ -32,6 +33,24 @@ impl SandboxRiskLevel {
     }
 }
 
+/// Remote endpoint a sandboxed command tried to reach.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash, JsonSchema, TS)]
+pub struct NetworkDestination {
+    /// IP address as reported by the intercepted syscall.
+    pub host: String,
+    pub port: u16,
+}
+
+impl fmt::Display for NetworkDestination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.host.contains(':') {
+            write!(f, "[{}]:{}", self.host, self.port)
+        } else {
+            write!(f, "{}:{}", self.host, self.port)
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ExecApprovalRequestEvent {
     /// Identifier for the associated exec call, if available.
@@ -51,6 +70,25 @@ pub struct ExecApprovalRequestEvent {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub risk: Option<SandboxCommandAssessment>,
     pub parsed_cmd: Vec<ParsedCommand>,
+    /// Set when the command is already running in the sandbox and is asking
+    /// to open a connection to this destination.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub network: Option<NetworkDestination>,
+    /// Set when a plain-English summary of the command is being generated;
+    /// it follows in an [`ExecApprovalExplanationEvent`].
+    #[serde(default)]
+    pub explanation_pending: bool,
+}
+
+/// Model-generated summary of a command awaiting approval, sent after the
+/// matching [`ExecApprovalRequestEvent`] whose `explanation_pending` was set.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ExecApprovalExplanationEvent {
+    pub call_id: String,
+    /// What the command does and its worst-case impact, or `None` when the
+    /// summary could not be generated in time.
+    pub explanation: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -69,3 +107,23 @@ pub struct ApplyPatchApprovalRequestEvent {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub grant_root: Option<PathBuf>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn network_destination_brackets_ipv6_hosts() {
+        let v4 = NetworkDestination {
+            host: "127.0.0.1".to_string(),
+            port: 8080,
+        };
+        let v6 = NetworkDestination {
+            host: "::1".to_string(),
+            port: 443,
+        };
+        assert_eq!(v4.to_string(), "127.0.0.1:8080");
+        assert_eq!(v6.to_string(), "[::1]:443");
+    }
+}
@@ -1,3 +1,4 @@
+use crate::config_types::ReasoningEffort;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
@@ -10,11 +11,32 @@ use ts_rs::TS;
 /// - Full slash prefix: `"/{PROMPTS_CMD_PREFIX}:"`
 pub const PROMPTS_CMD_PREFIX: &str = "prompts";
 
-#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, TS)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema, TS)]
 pub struct CustomPrompt {
     pub name: String,
     pub path: PathBuf,
     pub content: String,
     pub description: Option<String>,
     pub argument_hint: Option<String>,
+    /// Named arguments declared in the front-matter, in invocation order.
+    /// When non-empty, these replace the `$NAME` placeholders found in the
+    /// body as the set of required arguments.
+    #[serde(default)]
+    pub arguments: Vec<CustomPromptArgument>,
+    /// Model to use for the turn that runs this prompt.
+    pub model: Option<String>,
+    /// Reasoning effort to use for the turn that runs this prompt.
+    pub effort: Option<ReasoningEffort>,
+    /// Approval preset id (e.g. `auto`) the prompt asks to run with. Clients
+    /// must confirm with the user before applying it.
+    pub approval_preset: Option<String>,
+    /// Set when the front-matter could not be parsed and the whole file was
+    /// used as the prompt body.
+    pub frontmatter_warning: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+pub struct CustomPromptArgument {
+    pub name: String,
+    pub hint: Option<String>,
 }
@@ -35,7 +35,9 @@ use strum_macros::Display;
 use ts_rs::TS;
 
 pub use crate::approvals::ApplyPatchApprovalRequestEvent;
+pub use crate::approvals::ExecApprovalExplanationEvent;
 pub use crate::approvals::ExecApprovalRequestEvent;
+pub use crate::approvals::NetworkDestination;
 pub use crate::approvals::SandboxCommandAssessment;
 pub use crate::approvals::SandboxRiskLevel;
 
@@ -145,6 +147,17 @@ pub enum Op {
         decision: ReviewDecision,
     },
 
+    /// Answer a running command that stopped to wait for input.
+    ExecInput {
+        /// The call id from the matching `ExecInputRequest` event.
+        id: String,
+        response: ExecInputResponse,
+    },
+
+    /// Approve, for the rest of the session, every command that starts with
+    /// the words of `pattern` (e.g. `cargo test`) without asking again.
+    AllowCommandPattern { pattern: String },
+
     /// Approve a code patch
     PatchApproval {
         /// The id of the submission we are approving
@@ -153,6 +166,15 @@ pub enum Op {
         decision: ReviewDecision,
     },
 
+    /// Approve only part of a proposed patch.
+    PartialPatchApproval {
+        /// The id of the submission we are approving
+        id: String,
+        /// The request's changes with rejected files left out and rejected
+        /// hunks removed from each `unified_diff`.
+        changes: HashMap<PathBuf, FileChange>,
+    },
+
     /// Append an entry to the persistent cross-session message history.
     ///
     /// Note the entry is not guaranteed to be logged if the user has
@@ -172,6 +194,11 @@ pub enum Op {
     /// Request the list of available custom prompts.
     ListCustomPrompts,
 
+    /// Request the path-scoped project doc instructions and which of them are
+    /// active for the current turn. Reply is delivered via
+    /// `EventMsg::ListContextScopesResponse`.
+    ListContextScopes,
+
     /// Request the agent to summarize the current conversation context.
     /// The agent will use its existing context (either conversation history or previous response id)
     /// to generate a summary which will be returned as an AgentMessage event.
@@ -180,6 +207,11 @@ pub enum Op {
     /// Request Codex to undo a turn (turn are stacked so it is the same effect as CMD + Z).
     Undo,
 
+    /// Set a display or context flag on recorded history (e.g. `/clear` or
+    /// marking a turn irrelevant). The flag is persisted in the rollout and
+    /// echoed back as [`EventMsg::HistoryFlagSet`].
+    SetHistoryFlag { flag: HistoryFlag },
+
     /// Request a code review from the agent.
     Review { review_request: ReviewRequest },
 
@@ -505,18 +537,38 @@ pub enum EventMsg {
 
     ExecApprovalRequest(ExecApprovalRequestEvent),
 
+    /// Plain-English summary of a command awaiting approval.
+    ExecApprovalExplanation(ExecApprovalExplanationEvent),
+
     ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent),
 
+    /// A running command appears to be waiting for input it will never get
+    /// (a `[Y/n]` question, a password prompt, ...).
+    ExecInputRequest(ExecInputRequestEvent),
+
+    /// The sandbox refused an operation without asking the user, e.g. a
+    /// network connection while running with `approval_policy = never`.
+    SandboxDenied(SandboxDeniedEvent),
+
     /// Notification advising the user that something they are using has been
     /// deprecated and should be phased out.
     DeprecationNotice(DeprecationNoticeEvent),
 
+    /// The session's git worktree is behind the upstream of its base branch.
+    BaseDivergence(BaseDivergenceEvent),
+
+    /// What changed in the repository since a resumed session was last active.
+    ResumeBriefing(ResumeBriefingEvent),
+
     BackgroundEvent(BackgroundEventEvent),
 
     UndoStarted(UndoStartedEvent),
 
     UndoCompleted(UndoCompletedEvent),
 
+    /// A history flag was recorded in response to [`Op::SetHistoryFlag`].
+    HistoryFlagSet(HistoryFlagSetEvent),
+
     /// Notification that a model stream experienced an error or disconnect
     /// and the system is handling it (e.g., retrying with backoff).
     StreamError(StreamErrorEvent),
@@ -539,10 +591,17 @@ pub enum EventMsg {
     /// List of custom prompts available to the agent.
     ListCustomPromptsResponse(ListCustomPromptsResponseEvent),
 
+    /// Path-scoped project doc instructions and their activation state.
+    ListContextScopesResponse(ListContextScopesResponseEvent),
+
     PlanUpdate(UpdatePlanArgs),
 
     TurnAborted(TurnAbortedEvent),
 
+    /// A `[turn_limits]` cap was reached: the agent was asked to wrap up and
+    /// the turn ended after its final response.
+    TurnLimitReached(TurnLimitReachedEvent),
+
     /// Notification that the agent is shutting down.
     ShutdownComplete,
 
@@ -703,7 +762,7 @@ pub struct TaskStartedEvent {
     pub model_context_window: Option<i64>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, Default, JsonSchema, TS)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Default, JsonSchema, TS)]
 pub struct TokenUsage {
     #[ts(type = "number")]
     pub input_tokens: i64,
@@ -784,6 +843,14 @@ impl TokenUsageInfo {
 pub struct TokenCountEvent {
     pub info: Option<TokenUsageInfo>,
     pub rate_limits: Option<RateLimitSnapshot>,
+    /// Model that served the request described by `info.last_token_usage`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub model: Option<String>,
+    /// True when `info.last_token_usage` was estimated locally because the
+    /// provider did not report (complete) usage for the request.
+    #[serde(default)]
+    pub estimated: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
@@ -983,6 +1050,10 @@ pub struct McpToolCallEndEvent {
     pub duration: Duration,
     /// Result of the tool call. Note this could be an error.
     pub result: Result<CallToolResult, String>,
+    /// True when the result was served from the session's tool result cache
+    /// instead of calling the server.
+    #[serde(default)]
+    pub cached: bool,
 }
 
 impl McpToolCallEndEvent {
@@ -1096,6 +1167,9 @@ pub struct SessionMeta {
     #[serde(default)]
     pub source: SessionSource,
     pub model_provider: Option<String>,
+    /// Original session id when this session was imported from a bundle.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub imported_from: Option<ConversationId>,
 }
 
 impl Default for SessionMeta {
@@ -1109,6 +1183,7 @@ impl Default for SessionMeta {
             instructions: None,
             source: SessionSource::default(),
             model_provider: None,
+            imported_from: None,
         }
     }
 }
@@ -1128,7 +1203,9 @@ pub enum RolloutItem {
     ResponseItem(ResponseItem),
     Compacted(CompactedItem),
     TurnContext(TurnContextItem),
+    RepoState(RepoStateItem),
     EventMsg(EventMsg),
+    SessionEnd(SessionEndItem),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, TS)]
@@ -1161,6 +1238,42 @@ pub struct TurnContextItem {
     pub summary: ReasoningSummaryConfig,
 }
 
+/// Repository state at the end of a task, compared against the working tree
+/// when the session is resumed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, TS)]
+pub struct RepoStateItem {
+    pub cwd: PathBuf,
+    /// Commit checked out, `None` outside a git repository.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Paths with uncommitted changes, relative to the repository root.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dirty_files: Vec<String>,
+    /// Files the session has changed so far.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub touched_files: Vec<TouchedFile>,
+}
+
+/// Last line of a rollout whose session was shut down rather than killed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, TS)]
+pub struct SessionEndItem {
+    /// True when the session did not stop within the shutdown grace period
+    /// and its running turn was aborted.
+    #[serde(default)]
+    pub forced: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, TS)]
+pub struct TouchedFile {
+    pub path: PathBuf,
+    /// SHA-256 of the contents the session left behind, `None` when the
+    /// session deleted the file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, JsonSchema)]
 pub struct RolloutLine {
     pub timestamp: String,
@@ -1225,6 +1338,22 @@ pub struct ReviewFinding {
 pub struct ReviewCodeLocation {
     pub absolute_file_path: PathBuf,
     pub line_range: ReviewLineRange,
+    /// Filled in when the review finishes so the finding can be relocated
+    /// after later edits. Never produced by the reviewer model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub anchor: Option<ReviewAnchor>,
+}
+
+/// The code a finding pointed at when the review finished.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ReviewAnchor {
+    /// Lines just above the flagged range.
+    pub context_before: Vec<String>,
+    /// Lines just below the flagged range.
+    pub context_after: Vec<String>,
+    /// SHA-1 of the flagged lines, ignoring trailing whitespace.
+    pub content_hash: String,
 }
 
 /// Inclusive line range in a file associated with the finding.
@@ -1267,6 +1396,11 @@ pub struct ExecCommandBeginEvent {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[ts(optional)]
     pub interaction_input: Option<String>,
+    /// Inherited environment variables that `shell_environment_policy` kept
+    /// out of the command's environment, when any were.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub filtered_env_vars: Option<u32>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -1295,6 +1429,10 @@ pub struct ExecCommandEndEvent {
     /// Captured aggregated output
     #[serde(default)]
     pub aggregated_output: String,
+    /// Byte ranges of `aggregated_output` that the command wrote to stderr, in
+    /// order. Empty when the output did not come from separate pipes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stderr_ranges: Vec<OutputRange>,
     /// The command's exit code.
     pub exit_code: i32,
     /// The duration of the command execution.
@@ -1304,6 +1442,84 @@ pub struct ExecCommandEndEvent {
     pub formatted_output: String,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct BaseDivergenceEvent {
+    /// Local base branch the worktree was created from, e.g. `main`.
+    pub base: String,
+    /// Remote-tracking ref the base is compared against, e.g. `origin/main`.
+    pub upstream: String,
+    /// Number of commits on `upstream` that are missing from `HEAD`.
+    pub behind: usize,
+    /// Subjects of the newest missing commits, newest first.
+    pub tip_subjects: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ResumeBriefingEvent {
+    /// Branch checked out when the session was last active.
+    pub previous_branch: Option<String>,
+    /// Branch checked out now.
+    pub branch: Option<String>,
+    pub previous_head: Option<String>,
+    pub head: Option<String>,
+    /// Commits reachable from `head` but not from `previous_head`. `None` when
+    /// the history needed to count them is not available locally, e.g. in a
+    /// shallow clone.
+    pub new_commits: Option<usize>,
+    /// Subjects of the newest of those commits, newest first.
+    pub commit_subjects: Vec<String>,
+    /// Files the session changed that have been modified since.
+    pub modified_files: Vec<String>,
+    /// Files the session changed that have been deleted since.
+    pub deleted_files: Vec<String>,
+    /// Files with uncommitted changes when the session was last active that
+    /// are clean now (committed or discarded).
+    pub cleaned_files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct SandboxDeniedEvent {
+    /// Identifier for the exec call whose operation was denied.
+    pub call_id: String,
+    /// The command that attempted the operation.
+    pub command: Vec<String>,
+    pub denial: SandboxDenial,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type")]
+pub enum SandboxDenial {
+    /// Outbound connection to the given destination.
+    Network(NetworkDestination),
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ExecInputRequestEvent {
+    /// Identifier for the exec call that is waiting.
+    pub call_id: String,
+    /// The command that is waiting.
+    pub command: Vec<String>,
+    /// The output line the command stopped on.
+    pub prompt: String,
+    /// Whether text can be written to the command's stdin. When `false` the
+    /// only useful answer is [`ExecInputResponse::Terminate`].
+    pub accepts_input: bool,
+    /// Set when nobody can answer (e.g. `codex exec`): the command has
+    /// already been stopped and no `Op::ExecInput` is expected.
+    pub terminated: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type")]
+pub enum ExecInputResponse {
+    /// Write `text` followed by a newline to the command's stdin.
+    Input { text: String },
+    /// Stop the command.
+    Terminate,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ViewImageToolCallEvent {
     /// Identifier for the originating tool call.
@@ -1319,6 +1535,13 @@ pub enum ExecOutputStream {
     Stderr,
 }
 
+/// A half-open byte range `[start, end)` into a command's output.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct OutputRange {
+    pub start: usize,
+    pub end: usize,
+}
+
 #[serde_as]
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
 pub struct ExecCommandOutputDeltaEvent {
@@ -1331,6 +1554,12 @@ pub struct ExecCommandOutputDeltaEvent {
     #[schemars(with = "String")]
     #[ts(type = "string")]
     pub chunk: Vec<u8>,
+    /// Byte offset of `chunk` within its stream, when known. A jump past the
+    /// end of the previous chunk means output was dropped while the consumer
+    /// was behind; the end event still carries all of it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub offset: Option<u64>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -1360,6 +1589,50 @@ pub struct UndoCompletedEvent {
     pub message: Option<String>,
 }
 
+/// Flags front-ends apply to recorded history. Turns are identified by the
+/// 0-based index of their user message within the session.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type")]
+pub enum HistoryFlag {
+    /// Hide everything rendered before this point. Display only.
+    ClearedAbove,
+    /// Show output hidden by earlier `ClearedAbove` flags again.
+    Uncleared,
+    /// Hide one message cell. `cell` counts the user and agent messages of
+    /// the turn, starting at 0 for the user message. Display only.
+    CellDeleted {
+        nth_user_message: usize,
+        cell: usize,
+    },
+    /// Hide one cell of a turn that is not a message (a tool call, notice or
+    /// reasoning). `first_line` is the first line of the cell's transcript
+    /// text and `occurrence` counts the earlier cells of the turn with the
+    /// same first line, so the flag only matches when that cell is rebuilt.
+    /// Display only.
+    OutputCellDeleted {
+        nth_user_message: usize,
+        first_line: String,
+        occurrence: usize,
+    },
+    /// Collapse a turn and leave its tool outputs out of future prompts.
+    TurnRelevance {
+        nth_user_message: usize,
+        irrelevant: bool,
+    },
+    /// Keep a turn's user message verbatim when the history is compacted.
+    /// The first user message is always kept.
+    Pinned {
+        nth_user_message: usize,
+        pinned: bool,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct HistoryFlagSetEvent {
+    pub flag: HistoryFlag,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct StreamErrorEvent {
     pub message: String,
@@ -1406,6 +1679,28 @@ pub struct PatchApplyEndEvent {
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct TurnDiffEvent {
     pub unified_diff: String,
+    /// Files whose contents or location differ from the start of the turn.
+    #[serde(default)]
+    pub files: Vec<TurnFileChange>,
+}
+
+/// A file changed during the current turn, as tracked by the turn diff.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct TurnFileChange {
+    /// Current path of the file; for deletions, the path it was removed from.
+    pub path: PathBuf,
+    pub kind: TurnFileChangeKind,
+    /// Path at the start of the turn when the file was renamed.
+    pub old_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum TurnFileChangeKind {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -1488,6 +1783,23 @@ pub struct ListCustomPromptsResponseEvent {
     pub custom_prompts: Vec<CustomPrompt>,
 }
 
+/// Response payload for `Op::ListContextScopes`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ListContextScopesResponseEvent {
+    pub scopes: Vec<ContextScope>,
+}
+
+/// A scoped instruction declared in a project doc.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct ContextScope {
+    /// Project doc that declared the scope.
+    pub source: PathBuf,
+    /// Glob patterns, relative to the directory of `source`.
+    pub patterns: Vec<String>,
+    /// Whether the scope matched the files involved in the latest turn.
+    pub active: bool,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct SessionConfiguredEvent {
     /// Name left as session_id instead of conversation_id for backwards compatibility.
@@ -1587,6 +1899,21 @@ pub enum TurnAbortReason {
     ReviewEnded,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct TurnLimitReachedEvent {
+    pub limit: TurnLimitKind,
+    /// The configured cap: seconds for `wall_clock`, tokens for
+    /// `output_tokens`.
+    pub threshold: u64,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum TurnLimitKind {
+    WallClock,
+    OutputTokens,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1678,6 +2005,7 @@ mod tests {
             call_id: "call21".to_string(),
             stream: ExecOutputStream::Stdout,
             chunk: vec![1, 2, 3, 4, 5],
+            offset: None,
         };
         let serialized = serde_json::to_string(&event)?;
         assert_eq!(
@@ -54,6 +54,7 @@ use crate::utils::convert_call_tool_result;
 use crate::utils::convert_to_mcp;
 use crate::utils::convert_to_rmcp;
 use crate::utils::create_env_for_mcp_server;
+use crate::utils::handshake_error;
 use crate::utils::run_with_timeout;
 
 enum PendingTransport {
@@ -233,10 +234,8 @@ impl RmcpClient {
             Some(duration) => time::timeout(duration, transport)
                 .await
                 .map_err(|_| anyhow!("timed out handshaking with MCP server after {duration:?}"))?
-                .map_err(|err| anyhow!("handshaking with MCP server failed: {err}"))?,
-            None => transport
-                .await
-                .map_err(|err| anyhow!("handshaking with MCP server failed: {err}"))?,
+                .map_err(|err| handshake_error(&err))?,
+            None => transport.await.map_err(|err| handshake_error(&err))?,
         };
 
         let initialize_result_rmcp = service
@@ -33,6 +33,32 @@ where
     }
 }
 
+/// rustls reports certificates it cannot verify, e.g. self-signed ones, as
+/// `invalid peer certificate: UnknownIssuer`.
+const UNTRUSTED_CERTIFICATE: &str = "invalid peer certificate";
+
+/// Describes a failed MCP handshake. rmcp's errors only display the outermost
+/// message, so the underlying causes are appended.
+pub(crate) fn handshake_error(err: &(dyn std::error::Error + 'static)) -> anyhow::Error {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        let cause_message = cause.to_string();
+        if !message.contains(&cause_message) {
+            message.push_str(": ");
+            message.push_str(&cause_message);
+        }
+        source = cause.source();
+    }
+    if message.contains(UNTRUSTED_CERTIFICATE) {
+        anyhow!(
+            "handshaking with MCP server failed: its TLS certificate is not trusted. Self-signed certificates are not supported; use a certificate issued by a trusted CA ({message})"
+        )
+    } else {
+        anyhow!("handshaking with MCP server failed: {message}")
+    }
+}
+
 pub(crate) fn convert_call_tool_result(result: RmcpCallToolResult) -> Result<CallToolResult> {
     let mut value = serde_json::to_value(result)?;
     if let Some(obj) = value.as_object_mut()
@@ -243,6 +269,65 @@ mod tests {
         }
     }
 
+    #[derive(Debug)]
+    struct ChainedError {
+        message: &'static str,
+        source: Option<Box<ChainedError>>,
+    }
+
+    impl std::fmt::Display for ChainedError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(self.message)
+        }
+    }
+
+    impl std::error::Error for ChainedError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            self.source
+                .as_deref()
+                .map(|source| source as &(dyn std::error::Error + 'static))
+        }
+    }
+
+    fn chain(messages: &[&'static str]) -> ChainedError {
+        messages
+            .iter()
+            .rev()
+            .fold(None, |source, &message| {
+                Some(ChainedError {
+                    message,
+                    source: source.map(Box::new),
+                })
+            })
+            .expect("at least one message")
+    }
+
+    #[test]
+    fn handshake_error_includes_the_causes() {
+        let err = chain(&[
+            "Transport error: Client error",
+            "error sending request for url (http://127.0.0.1:9/mcp)",
+            "tcp connect error",
+        ]);
+        assert_eq!(
+            handshake_error(&err).to_string(),
+            "handshaking with MCP server failed: Transport error: Client error: error sending request for url (http://127.0.0.1:9/mcp): tcp connect error"
+        );
+    }
+
+    #[test]
+    fn handshake_error_explains_untrusted_certificates() {
+        let err = chain(&[
+            "Transport error: Client error",
+            "error sending request for url (https://localhost:8443/mcp)",
+            "invalid peer certificate: UnknownIssuer",
+        ]);
+        assert_eq!(
+            handshake_error(&err).to_string(),
+            "handshaking with MCP server failed: its TLS certificate is not trusted. Self-signed certificates are not supported; use a certificate issued by a trusted CA (Transport error: Client error: error sending request for url (https://localhost:8443/mcp): invalid peer certificate: UnknownIssuer)"
+        );
+    }
+
     #[tokio::test]
     async fn create_env_honors_overrides() {
         let value = "custom".to_string();
@@ -0,0 +1,231 @@
+//! Central budget for decorative animations (shimmer, spinners, ASCII art).
+//!
+//! Animated widgets schedule their redraws through
+//! [`FrameRequester::schedule_animation_frame`](crate::tui::FrameRequester),
+//! which caps the rate according to the current [`AnimationLevel`]. The level
+//! starts at the configured `tui.animations` setting and is stepped down when
+//! recent draws are slow (e.g. over a high-latency SSH connection), then back
+//! up once they speed up again.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_core::config::types::Animations;
+
+/// Shortest interval between animation frames at full level.
+const FULL_FRAME_INTERVAL: Duration = Duration::from_millis(32);
+/// Shortest interval between animation frames at reduced level.
+const REDUCED_FRAME_INTERVAL: Duration = Duration::from_millis(250);
+/// Number of recent draws averaged when deciding whether to change level.
+const LATENCY_WINDOW: usize = 8;
+/// Average draw latency above which animations are stepped down.
+const DOWNGRADE_LATENCY: Duration = Duration::from_millis(50);
+/// Average draw latency below which animations are stepped back up.
+const RECOVER_LATENCY: Duration = Duration::from_millis(15);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum AnimationLevel {
+    /// No animation frames; animated widgets render a static frame.
+    Off,
+    /// Animations run at a lower frame rate.
+    Reduced,
+    Full,
+}
+
+impl AnimationLevel {
+    fn from_setting(setting: Animations) -> Self {
+        match setting {
+            Animations::Full => AnimationLevel::Full,
+            Animations::Reduced => AnimationLevel::Reduced,
+            Animations::Off => AnimationLevel::Off,
+        }
+    }
+
+    fn lower(self) -> Self {
+        match self {
+            AnimationLevel::Full => AnimationLevel::Reduced,
+            AnimationLevel::Reduced | AnimationLevel::Off => AnimationLevel::Off,
+        }
+    }
+
+    fn higher(self) -> Self {
+        match self {
+            AnimationLevel::Off => AnimationLevel::Reduced,
+            AnimationLevel::Reduced | AnimationLevel::Full => AnimationLevel::Full,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct BudgetState {
+    /// Upper bound from configuration; automatic recovery never exceeds it.
+    ceiling: AnimationLevel,
+    level: AnimationLevel,
+    recent: VecDeque<Duration>,
+}
+
+#[derive(Debug)]
+pub(crate) struct AnimationBudget {
+    state: Mutex<BudgetState>,
+}
+
+impl Default for AnimationBudget {
+    fn default() -> Self {
+        Self::new(Animations::Full)
+    }
+}
+
+impl AnimationBudget {
+    pub(crate) fn new(setting: Animations) -> Self {
+        let level = AnimationLevel::from_setting(setting);
+        Self {
+            state: Mutex::new(BudgetState {
+                ceiling: level,
+                level,
+                recent: VecDeque::with_capacity(LATENCY_WINDOW),
+            }),
+        }
+    }
+
+    /// Apply the `tui.animations` setting, resetting any automatic downgrade.
+    pub(crate) fn set_setting(&self, setting: Animations) {
+        let level = AnimationLevel::from_setting(setting);
+        let mut state = self.lock();
+        state.ceiling = level;
+        state.level = level;
+        state.recent.clear();
+    }
+
+    pub(crate) fn level(&self) -> AnimationLevel {
+        self.lock().level
+    }
+
+    /// Shortest interval between animation frames, or `None` when animations
+    /// are off.
+    pub(crate) fn frame_interval(&self) -> Option<Duration> {
+        match self.level() {
+            AnimationLevel::Full => Some(FULL_FRAME_INTERVAL),
+            AnimationLevel::Reduced => Some(REDUCED_FRAME_INTERVAL),
+            AnimationLevel::Off => None,
+        }
+    }
+
+    /// Record how long a draw (render plus flush) took, and step the level
+    /// down or up once a full window of draws is consistently slow or fast.
+    pub(crate) fn record_frame(&self, latency: Duration) {
+        let mut state = self.lock();
+        if state.recent.len() == LATENCY_WINDOW {
+            state.recent.pop_front();
+        }
+        state.recent.push_back(latency);
+        if state.recent.len() < LATENCY_WINDOW {
+            return;
+        }
+        let average = state.recent.iter().sum::<Duration>() / LATENCY_WINDOW as u32;
+        let next = if average > DOWNGRADE_LATENCY {
+            state.level.lower()
+        } else if average < RECOVER_LATENCY {
+            state.level.higher().min(state.ceiling)
+        } else {
+            state.level
+        };
+        if next != state.level {
+            tracing::debug!(
+                "animation level {:?} -> {next:?} (average draw {average:?})",
+                state.level
+            );
+            state.level = next;
+            state.recent.clear();
+        }
+    }
+
+    /// Run `draw` and record how long it took.
+    pub(crate) fn measure<T>(&self, draw: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = draw();
+        self.record_frame(start.elapsed());
+        result
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BudgetState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+static INSTALLED: OnceLock<Arc<AnimationBudget>> = OnceLock::new();
+
+/// Make `budget` the one consulted by [`current_level`]. Only the first call
+/// takes effect.
+pub(crate) fn install(budget: Arc<AnimationBudget>) {
+    let _ = INSTALLED.set(budget);
+}
+
+/// Animation level for widgets that have no frame requester at hand. Full
+/// when no terminal budget is installed (e.g. in tests).
+pub(crate) fn current_level() -> AnimationLevel {
+    INSTALLED
+        .get()
+        .map_or(AnimationLevel::Full, |budget| budget.level())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn record(budget: &AnimationBudget, latency_ms: u64) {
+        for _ in 0..LATENCY_WINDOW {
+            budget.record_frame(Duration::from_millis(latency_ms));
+        }
+    }
+
+    #[test]
+    fn slow_draws_step_animations_down_to_off() {
+        let budget = AnimationBudget::new(Animations::Full);
+        assert_eq!(budget.frame_interval(), Some(FULL_FRAME_INTERVAL));
+
+        record(&budget, 120);
+        assert_eq!(budget.level(), AnimationLevel::Reduced);
+        assert_eq!(budget.frame_interval(), Some(REDUCED_FRAME_INTERVAL));
+
+        record(&budget, 120);
+        assert_eq!(budget.level(), AnimationLevel::Off);
+        assert_eq!(budget.frame_interval(), None);
+    }
+
+    #[test]
+    fn fast_draws_recover_up_to_the_configured_level() {
+        let budget = AnimationBudget::new(Animations::Reduced);
+        record(&budget, 120);
+        assert_eq!(budget.level(), AnimationLevel::Off);
+
+        record(&budget, 2);
+        assert_eq!(budget.level(), AnimationLevel::Reduced);
+        record(&budget, 2);
+        assert_eq!(budget.level(), AnimationLevel::Reduced);
+    }
+
+    #[test]
+    fn occasional_slow_draw_does_not_downgrade() {
+        let budget = AnimationBudget::new(Animations::Full);
+        for latency_ms in [5, 5, 200, 5, 5, 5, 5, 5, 5] {
+            budget.record_frame(Duration::from_millis(latency_ms));
+        }
+        assert_eq!(budget.level(), AnimationLevel::Full);
+    }
+
+    #[test]
+    fn off_setting_never_recovers() {
+        let budget = AnimationBudget::new(Animations::Full);
+        budget.set_setting(Animations::Off);
+        record(&budget, 1);
+        assert_eq!(budget.level(), AnimationLevel::Off);
+        assert_eq!(budget.frame_interval(), None);
+    }
+}
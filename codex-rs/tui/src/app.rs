@@ -7,6 +7,7 @@ use crate::diff_render::DiffSummary;
 use crate::exec_command::strip_bash_lc_and_escape;
 use crate::file_search::FileSearchManager;
 use crate::history_cell::HistoryCell;
+use crate::history_flags::HistoryFlags;
 use crate::model_migration::ModelMigrationOutcome;
 use crate::model_migration::migration_copy_for_config;
 use crate::model_migration::run_model_migration_prompt;
@@ -14,8 +15,10 @@ use crate::pager_overlay::Overlay;
 use crate::render::highlight::highlight_bash_to_lines;
 use crate::render::renderable::Renderable;
 use crate::resume_picker::ResumeSelection;
+use crate::terminal_title::TerminalTitle;
 use crate::tui;
 use crate::tui::TuiEvent;
+use crate::turn_separators::TurnSeparatorState;
 use crate::update_action::UpdateAction;
 use codex_ansi_escape::ansi_escape_line;
 use codex_app_server_protocol::AuthMode;
@@ -29,7 +32,9 @@ use codex_core::config::Config;
 use codex_core::config::edit::ConfigEditsBuilder;
 #[cfg(target_os = "windows")]
 use codex_core::features::Feature;
+use codex_core::git_info::resolve_root_git_project_for_trust;
 use codex_core::model_family::find_family_for_model;
+use codex_core::persistence_policy::PersistenceKind;
 use codex_core::protocol::FinalOutput;
 #[cfg(target_os = "windows")]
 use codex_core::protocol::Op;
@@ -50,12 +55,19 @@ use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::thread;
 use std::time::Duration;
+use std::time::Instant;
 use tokio::select;
+use tokio::sync::mpsc::UnboundedReceiver;
 use tokio::sync::mpsc::unbounded_channel;
 
 #[cfg(not(debug_assertions))]
 use crate::history_cell::UpdateAvailableHistoryCell;
 
+/// Most history cells inserted in one layout pass. Bounds the latency of a
+/// pathological burst while still coalescing the cells that arrive at the end
+/// of a turn.
+const HISTORY_BATCH_MAX: usize = 50;
+
 const GPT_5_1_MIGRATION_AUTH_MODES: [AuthMode; 2] = [AuthMode::ChatGPT, AuthMode::ApiKey];
 const GPT_5_1_CODEX_MIGRATION_AUTH_MODES: [AuthMode; 1] = [AuthMode::ChatGPT];
 
@@ -64,6 +76,8 @@ pub struct AppExitInfo {
     pub token_usage: TokenUsage,
     pub conversation_id: Option<ConversationId>,
     pub update_action: Option<UpdateAction>,
+    /// Set for `--ephemeral` runs, which cannot be resumed.
+    pub ephemeral: bool,
 }
 
 fn session_summary(
@@ -182,6 +196,7 @@ async fn handle_model_migration_prompt_if_needed(
                     token_usage: TokenUsage::default(),
                     conversation_id: None,
                     update_action: None,
+                    ephemeral: config.persistence.is_ephemeral(),
                 });
             }
         }
@@ -207,7 +222,15 @@ pub(crate) struct App {
     // Pager overlay state (Transcript or Static like Diff)
     pub(crate) overlay: Option<Overlay>,
     pub(crate) deferred_history_lines: Vec<Line<'static>>,
-    has_emitted_history_lines: bool,
+    pub(crate) has_emitted_history_lines: bool,
+    turn_separators: TurnSeparatorState,
+    /// `None` when `tui.set_terminal_title` is disabled.
+    terminal_title: Option<TerminalTitle>,
+
+    /// `/clear`, deleted cells, and irrelevant turns applied to the scrollback.
+    pub(crate) history_flags: HistoryFlags,
+    /// A history flag changed while an overlay was open; redraw on close.
+    pub(crate) history_redraw_pending: bool,
 
     pub(crate) enhanced_keys_supported: bool,
 
@@ -235,6 +258,7 @@ impl App {
         initial_images: Vec<PathBuf>,
         resume_selection: ResumeSelection,
         feedback: codex_feedback::CodexFeedback,
+        trust_summary: Option<Vec<Line<'static>>>,
     ) -> Result<AppExitInfo> {
         use tokio_stream::StreamExt;
         let (app_event_tx, mut app_event_rx) = unbounded_channel();
@@ -299,8 +323,20 @@ impl App {
         };
 
         chat_widget.maybe_prompt_windows_sandbox_enable();
+        if let Some(lines) = trust_summary {
+            chat_widget.set_trust_summary(lines);
+        }
 
-        let file_search = FileSearchManager::new(config.cwd.clone(), app_event_tx.clone());
+        let file_search = FileSearchManager::new(
+            config.cwd.clone(),
+            config.file_search.clone(),
+            config.path_filter.clone(),
+            app_event_tx.clone(),
+        );
+        let turn_separators = TurnSeparatorState::new(config.tui_turn_separators);
+        let terminal_title = config
+            .tui_set_terminal_title
+            .then(|| TerminalTitle::new(&config.cwd));
         #[cfg(not(debug_assertions))]
         let upgrade_version = crate::updates::get_upgrade_version(&config);
 
@@ -317,6 +353,10 @@ impl App {
             overlay: None,
             deferred_history_lines: Vec::new(),
             has_emitted_history_lines: false,
+            turn_separators,
+            terminal_title,
+            history_flags: HistoryFlags::default(),
+            history_redraw_pending: false,
             commit_anim_running: Arc::new(AtomicBool::new(false)),
             backtrack: BacktrackState::default(),
             feedback: feedback.clone(),
@@ -365,9 +405,17 @@ impl App {
         tui.frame_requester().schedule_frame();
 
         while select! {
-            Some(event) = app_event_rx.recv() => {
-                app.handle_event(tui, event).await?
-            }
+            Some(event) = app_event_rx.recv() => match event {
+                AppEvent::InsertHistoryCell(cell) => {
+                    let burst = drain_history_burst(cell, &mut app_event_rx);
+                    app.insert_history_cells(tui, burst.cells);
+                    match burst.next {
+                        Some(event) => app.handle_event(tui, event).await?,
+                        None => true,
+                    }
+                }
+                event => app.handle_event(tui, event).await?,
+            },
             Some(event) = tui_events.next() => {
                 app.handle_tui_event(tui, event).await?
             }
@@ -377,14 +425,33 @@ impl App {
             token_usage: app.token_usage(),
             conversation_id: app.chat_widget.conversation_id(),
             update_action: app.pending_update_action,
+            ephemeral: app.config.persistence.is_ephemeral(),
         })
     }
 
+    fn update_terminal_title(&mut self, tui: &mut tui::Tui) {
+        let Some(title) = self.terminal_title.as_mut() else {
+            return;
+        };
+        let status = self.chat_widget.terminal_title_status();
+        match title.update(&mut std::io::stdout(), status, Instant::now()) {
+            Ok(Some(retry_in)) => tui.frame_requester().schedule_frame_in(retry_in),
+            Ok(None) => {}
+            Err(err) => tracing::debug!("failed to update terminal title: {err}"),
+        }
+    }
+
     pub(crate) async fn handle_tui_event(
         &mut self,
         tui: &mut tui::Tui,
         event: TuiEvent,
     ) -> Result<bool> {
+        // Keep the title and file links current while the transcript overlay
+        // is open too.
+        if matches!(event, TuiEvent::Draw) {
+            self.update_terminal_title(tui);
+            tui.set_file_links_cwd(self.chat_widget.session_cwd());
+        }
         if self.overlay.is_some() {
             let _ = self.handle_backtrack_overlay_event(tui, event).await?;
         } else {
@@ -400,6 +467,8 @@ impl App {
                     let pasted = pasted.replace("\r", "\n");
                     self.chat_widget.handle_paste(pasted);
                 }
+                // The mouse is only captured while an overlay is open.
+                TuiEvent::Mouse(_) => {}
                 TuiEvent::Draw => {
                     self.chat_widget.maybe_post_pending_notification(tui);
                     if self
@@ -423,12 +492,82 @@ impl App {
         Ok(true)
     }
 
+    /// Record `cells` in the transcript and insert their lines above the
+    /// viewport in a single pass.
+    fn insert_history_cells(&mut self, tui: &mut tui::Tui, cells: Vec<Box<dyn HistoryCell>>) {
+        if matches!(self.overlay, Some(Overlay::Transcript(_))) {
+            tui.frame_requester().schedule_frame();
+        }
+        let width = tui.terminal.last_known_screen_size.width;
+        let display = self.history_lines_for_cells(cells, width);
+        if display.is_empty() {
+            return;
+        }
+        if self.overlay.is_some() {
+            self.deferred_history_lines.extend(display);
+        } else {
+            tui.insert_history_lines(display);
+        }
+    }
+
+    fn history_lines_for_cells(
+        &mut self,
+        cells: Vec<Box<dyn HistoryCell>>,
+        width: u16,
+    ) -> Vec<Line<'static>> {
+        let mut lines = Vec::new();
+        for cell in cells {
+            let cell: Arc<dyn HistoryCell> = cell.into();
+            if let Some(Overlay::Transcript(t)) = &mut self.overlay {
+                t.insert_cell(cell.clone());
+            }
+            self.transcript_cells.push(cell.clone());
+            if self.history_flags.hides_last_cell(&self.transcript_cells) {
+                continue;
+            }
+            let mut display = cell.display_lines(width);
+            if display.is_empty() {
+                continue;
+            }
+            // Only insert a separating blank line for new cells that are not
+            // part of an ongoing stream. Streaming continuations should not
+            // accrue extra blank lines between chunks.
+            let mut has_separator = false;
+            if !cell.is_stream_continuation() {
+                if self.has_emitted_history_lines {
+                    display.insert(0, Line::from(""));
+                    has_separator = true;
+                } else {
+                    self.has_emitted_history_lines = true;
+                }
+            }
+            self.turn_separators
+                .decorate(&mut display, has_separator, width);
+            lines.extend(display);
+        }
+        lines
+    }
+
+    /// Write `edits` to `config.toml`, or drop them in ephemeral mode so the
+    /// change only lasts for this session.
+    async fn persist_config_edits(&self, edits: ConfigEditsBuilder) -> anyhow::Result<()> {
+        if let Err(blocked) = self.config.persistence.check(PersistenceKind::ConfigEdits) {
+            tracing::info!("{blocked}; keeping the change for this session only");
+            return Ok(());
+        }
+        edits.apply().await
+    }
+
     async fn handle_event(&mut self, tui: &mut tui::Tui, event: AppEvent) -> Result<bool> {
         match event {
             AppEvent::NewSession => {
+                // Ephemeral sessions leave no rollout behind, so there is
+                // nothing to resume.
                 let summary = session_summary(
                     self.chat_widget.token_usage(),
-                    self.chat_widget.conversation_id(),
+                    self.chat_widget
+                        .conversation_id()
+                        .filter(|_| !self.config.persistence.is_ephemeral()),
                 );
                 let init = crate::chatwidget::ChatWidgetInit {
                     config: self.config.clone(),
@@ -441,6 +580,7 @@ impl App {
                     feedback: self.feedback.clone(),
                 };
                 self.chat_widget = ChatWidget::new(init, self.server.clone());
+                self.history_flags.start_session();
                 if let Some(summary) = summary {
                     let mut lines: Vec<Line<'static>> = vec![summary.usage_line.clone().into()];
                     if let Some(command) = summary.resume_command {
@@ -452,31 +592,19 @@ impl App {
                 tui.frame_requester().schedule_frame();
             }
             AppEvent::InsertHistoryCell(cell) => {
-                let cell: Arc<dyn HistoryCell> = cell.into();
-                if let Some(Overlay::Transcript(t)) = &mut self.overlay {
-                    t.insert_cell(cell.clone());
-                    tui.frame_requester().schedule_frame();
-                }
-                self.transcript_cells.push(cell.clone());
-                let mut display = cell.display_lines(tui.terminal.last_known_screen_size.width);
-                if !display.is_empty() {
-                    // Only insert a separating blank line for new cells that are not
-                    // part of an ongoing stream. Streaming continuations should not
-                    // accrue extra blank lines between chunks.
-                    if !cell.is_stream_continuation() {
-                        if self.has_emitted_history_lines {
-                            display.insert(0, Line::from(""));
-                        } else {
-                            self.has_emitted_history_lines = true;
-                        }
-                    }
-                    if self.overlay.is_some() {
-                        self.deferred_history_lines.extend(display);
-                    } else {
-                        tui.insert_history_lines(display);
-                    }
+                self.insert_history_cells(tui, vec![cell]);
+            }
+            AppEvent::TurnStarted(marker) => {
+                self.turn_separators.start_turn(marker);
+            }
+            AppEvent::ApplyHistoryFlag(flag) => {
+                if self.history_flags.apply(&flag, &self.transcript_cells) {
+                    self.redraw_history(tui)?;
                 }
             }
+            AppEvent::PinLastUserMessage => {
+                self.pin_last_user_message();
+            }
             AppEvent::StartCommitAnimation => {
                 if self
                     .commit_anim_running
@@ -513,7 +641,7 @@ impl App {
                 // Clear the in-progress state in the bottom pane
                 self.chat_widget.on_diff_complete();
                 // Enter alternate screen using TUI helper and build pager lines
-                let _ = tui.enter_alt_screen();
+                let _ = tui.enter_overlay_screen();
                 let pager_lines: Vec<ratatui::text::Line<'static>> = if text.trim().is_empty() {
                     vec!["No changes detected.".italic().into()]
                 } else {
@@ -530,12 +658,27 @@ impl App {
                     self.file_search.on_user_query(query);
                 }
             }
-            AppEvent::FileSearchResult { query, matches } => {
-                self.chat_widget.apply_file_search_result(query, matches);
+            AppEvent::FileSearchResult {
+                query,
+                matches,
+                truncated_walk,
+            } => {
+                self.chat_widget
+                    .apply_file_search_result(query, matches, truncated_walk);
+            }
+            AppEvent::ClipboardImagePasted {
+                result,
+                from_empty_paste,
+            } => {
+                self.chat_widget
+                    .on_clipboard_image_pasted(result, from_empty_paste);
             }
             AppEvent::RateLimitSnapshotFetched(snapshot) => {
                 self.chat_widget.on_rate_limit_snapshot(Some(snapshot));
             }
+            AppEvent::CloudTasksPolled { tasks, transitions } => {
+                self.chat_widget.on_cloud_tasks_polled(tasks, transitions);
+            }
             AppEvent::UpdateReasoningEffort(effort) => {
                 self.on_update_reasoning_effort(effort);
             }
@@ -552,6 +695,9 @@ impl App {
             AppEvent::OpenFullAccessConfirmation { preset } => {
                 self.chat_widget.open_full_access_confirmation(preset);
             }
+            AppEvent::ResolvePromptApprovalPreset(decision) => {
+                self.chat_widget.resolve_prompt_approval_preset(decision);
+            }
             AppEvent::OpenWorldWritableWarningConfirmation {
                 preset,
                 sample_paths,
@@ -582,10 +728,12 @@ impl App {
                 {
                     let profile = self.active_profile.as_deref();
                     let feature_key = Feature::WindowsSandbox.key();
-                    match ConfigEditsBuilder::new(&self.config.codex_home)
-                        .with_profile(profile)
-                        .set_feature_enabled(feature_key, true)
-                        .apply()
+                    match self
+                        .persist_config_edits(
+                            ConfigEditsBuilder::new(&self.config.codex_home)
+                                .with_profile(profile)
+                                .set_feature_enabled(feature_key, true),
+                        )
                         .await
                     {
                         Ok(()) => {
@@ -641,10 +789,12 @@ impl App {
             }
             AppEvent::PersistModelSelection { model, effort } => {
                 let profile = self.active_profile.as_deref();
-                match ConfigEditsBuilder::new(&self.config.codex_home)
-                    .with_profile(profile)
-                    .set_model(Some(model.as_str()), effort)
-                    .apply()
+                match self
+                    .persist_config_edits(
+                        ConfigEditsBuilder::new(&self.config.codex_home)
+                            .with_profile(profile)
+                            .set_model(Some(model.as_str()), effort),
+                    )
                     .await
                 {
                     Ok(()) => {
@@ -735,9 +885,11 @@ impl App {
                 self.chat_widget.set_rate_limit_switch_prompt_hidden(hidden);
             }
             AppEvent::PersistFullAccessWarningAcknowledged => {
-                if let Err(err) = ConfigEditsBuilder::new(&self.config.codex_home)
-                    .set_hide_full_access_warning(true)
-                    .apply()
+                if let Err(err) = self
+                    .persist_config_edits(
+                        ConfigEditsBuilder::new(&self.config.codex_home)
+                            .set_hide_full_access_warning(true),
+                    )
                     .await
                 {
                     tracing::error!(
@@ -750,9 +902,11 @@ impl App {
                 }
             }
             AppEvent::PersistWorldWritableWarningAcknowledged => {
-                if let Err(err) = ConfigEditsBuilder::new(&self.config.codex_home)
-                    .set_hide_world_writable_warning(true)
-                    .apply()
+                if let Err(err) = self
+                    .persist_config_edits(
+                        ConfigEditsBuilder::new(&self.config.codex_home)
+                            .set_hide_world_writable_warning(true),
+                    )
                     .await
                 {
                     tracing::error!(
@@ -765,9 +919,11 @@ impl App {
                 }
             }
             AppEvent::PersistRateLimitSwitchPromptHidden => {
-                if let Err(err) = ConfigEditsBuilder::new(&self.config.codex_home)
-                    .set_hide_rate_limit_model_nudge(true)
-                    .apply()
+                if let Err(err) = self
+                    .persist_config_edits(
+                        ConfigEditsBuilder::new(&self.config.codex_home)
+                            .set_hide_rate_limit_model_nudge(true),
+                    )
                     .await
                 {
                     tracing::error!(
@@ -780,9 +936,11 @@ impl App {
                 }
             }
             AppEvent::PersistModelMigrationPromptAcknowledged { migration_config } => {
-                if let Err(err) = ConfigEditsBuilder::new(&self.config.codex_home)
-                    .set_hide_model_migration_prompt(&migration_config, true)
-                    .apply()
+                if let Err(err) = self
+                    .persist_config_edits(
+                        ConfigEditsBuilder::new(&self.config.codex_home)
+                            .set_hide_model_migration_prompt(&migration_config, true),
+                    )
                     .await
                 {
                     tracing::error!(error = %err, "failed to persist model migration prompt acknowledgement");
@@ -791,6 +949,31 @@ impl App {
                     ));
                 }
             }
+            AppEvent::PersistAlwaysAllowCommand { pattern } => {
+                // Keyed like the trust decision, so worktrees share the list.
+                let project = resolve_root_git_project_for_trust(&self.config.cwd)
+                    .unwrap_or_else(|| self.config.cwd.clone());
+                match self
+                    .persist_config_edits(
+                        ConfigEditsBuilder::new(&self.config.codex_home)
+                            .add_project_always_allow_command(&project, &pattern),
+                    )
+                    .await
+                {
+                    Ok(()) => {
+                        let allowed = &mut self.config.active_project.always_allow_commands;
+                        if !allowed.contains(&pattern) {
+                            allowed.push(pattern);
+                        }
+                    }
+                    Err(err) => {
+                        tracing::error!(error = %err, "failed to persist always-allowed command");
+                        self.chat_widget.add_error_message(format!(
+                            "Failed to save always-allowed command `{pattern}`: {err}"
+                        ));
+                    }
+                }
+            }
             AppEvent::OpenApprovalsPopup => {
                 self.chat_widget.open_approvals_popup();
             }
@@ -803,17 +986,40 @@ impl App {
             AppEvent::OpenReviewCustomPrompt => {
                 self.chat_widget.show_review_custom_prompt();
             }
+            AppEvent::RecallPrompt(text) => {
+                self.chat_widget.set_composer_text(text);
+            }
+            AppEvent::HistoryEntriesLoaded(entries) => {
+                self.chat_widget.show_history_search(entries);
+            }
+            AppEvent::OpenExecInputPrompt { call_id, prompt } => {
+                self.chat_widget.show_exec_input_prompt(call_id, prompt);
+            }
+            AppEvent::OpenPatchHunkSelection { id, cwd, changes } => {
+                let _ = tui.enter_overlay_screen();
+                self.overlay = Some(Overlay::new_patch_hunks(
+                    id,
+                    cwd,
+                    &changes,
+                    self.app_event_tx.clone(),
+                ));
+                tui.frame_requester().schedule_frame();
+            }
+            AppEvent::PatchHunksSelected { id, selection } => {
+                self.chat_widget.apply_patch_selection(&id, &selection);
+            }
             AppEvent::FullScreenApprovalRequest(request) => match request {
                 ApprovalRequest::ApplyPatch { cwd, changes, .. } => {
-                    let _ = tui.enter_alt_screen();
+                    let _ = tui.enter_overlay_screen();
                     let diff_summary = DiffSummary::new(changes, cwd);
                     self.overlay = Some(Overlay::new_static_with_renderables(
                         vec![diff_summary.into()],
                         "P A T C H".to_string(),
                     ));
                 }
-                ApprovalRequest::Exec { command, .. } => {
-                    let _ = tui.enter_alt_screen();
+                ApprovalRequest::Exec { command, .. }
+                | ApprovalRequest::Network { command, .. } => {
+                    let _ = tui.enter_overlay_screen();
                     let full_cmd = strip_bash_lc_and_escape(&command);
                     let full_cmd_lines = highlight_bash_to_lines(&full_cmd);
                     self.overlay = Some(Overlay::new_static_with_lines(
@@ -855,7 +1061,7 @@ impl App {
                 ..
             } => {
                 // Enter alternate screen and set viewport to full size.
-                let _ = tui.enter_alt_screen();
+                let _ = tui.enter_overlay_screen();
                 self.overlay = Some(Overlay::new_transcript(self.transcript_cells.clone()));
                 tui.frame_requester().schedule_frame();
             }
@@ -981,6 +1187,36 @@ fn migration_prompt_allows_auth_mode(
     }
 }
 
+/// History cells that arrived back to back, plus the event that ended the
+/// burst when it was something other than another insert.
+struct HistoryBurst {
+    cells: Vec<Box<dyn HistoryCell>>,
+    next: Option<AppEvent>,
+}
+
+/// Collect the insert events already queued behind `first` so they share one
+/// layout pass. Stops at the first other event, which is handed back so
+/// events are still handled in the order they were sent.
+fn drain_history_burst(
+    first: Box<dyn HistoryCell>,
+    app_event_rx: &mut UnboundedReceiver<AppEvent>,
+) -> HistoryBurst {
+    let mut cells = vec![first];
+    while cells.len() < HISTORY_BATCH_MAX {
+        match app_event_rx.try_recv() {
+            Ok(AppEvent::InsertHistoryCell(cell)) => cells.push(cell),
+            Ok(event) => {
+                return HistoryBurst {
+                    cells,
+                    next: Some(event),
+                };
+            }
+            Err(_) => break,
+        }
+    }
+    HistoryBurst { cells, next: None }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -990,15 +1226,19 @@ mod tests {
     use crate::file_search::FileSearchManager;
     use crate::history_cell::AgentMessageCell;
     use crate::history_cell::HistoryCell;
+    use crate::history_cell::PlainHistoryCell;
     use crate::history_cell::UserHistoryCell;
     use crate::history_cell::new_session_info;
+    use crate::test_backend::VT100Backend;
     use codex_core::AuthManager;
     use codex_core::CodexAuth;
     use codex_core::ConversationManager;
+    use codex_core::persistence_policy::PersistencePolicy;
     use codex_core::protocol::AskForApproval;
     use codex_core::protocol::SandboxPolicy;
     use codex_core::protocol::SessionConfiguredEvent;
     use codex_protocol::ConversationId;
+    use ratatui::layout::Rect;
     use ratatui::prelude::Line;
     use std::path::PathBuf;
     use std::sync::Arc;
@@ -1012,7 +1252,13 @@ mod tests {
         )));
         let auth_manager =
             AuthManager::from_auth_for_testing(CodexAuth::from_api_key("Test API Key"));
-        let file_search = FileSearchManager::new(config.cwd.clone(), app_event_tx.clone());
+        let file_search = FileSearchManager::new(
+            config.cwd.clone(),
+            config.file_search.clone(),
+            config.path_filter.clone(),
+            app_event_tx.clone(),
+        );
+        let turn_separators = TurnSeparatorState::new(config.tui_turn_separators);
 
         App {
             server,
@@ -1026,6 +1272,10 @@ mod tests {
             overlay: None,
             deferred_history_lines: Vec::new(),
             has_emitted_history_lines: false,
+            turn_separators,
+            terminal_title: None,
+            history_flags: HistoryFlags::default(),
+            history_redraw_pending: false,
             enhanced_keys_supported: false,
             commit_anim_running: Arc::new(AtomicBool::new(false)),
             backtrack: BacktrackState::default(),
@@ -1035,6 +1285,93 @@ mod tests {
         }
     }
 
+    fn plain_cell_events(count: usize) -> UnboundedReceiver<AppEvent> {
+        let (tx, rx) = unbounded_channel();
+        for i in 0..count {
+            let cell = PlainHistoryCell::new(vec![Line::from(format!("cell {i}"))]);
+            tx.send(AppEvent::InsertHistoryCell(Box::new(cell)))
+                .expect("send");
+        }
+        tx.send(AppEvent::CommitTick).expect("send");
+        rx
+    }
+
+    /// Insert `batches` into an off-screen terminal one `insert_history_lines`
+    /// call per batch and return the rendered rows between the first and last
+    /// non-blank ones.
+    fn render_history(batches: Vec<Vec<Line<'static>>>) -> Vec<String> {
+        let (width, height) = (80, 64);
+        let backend = VT100Backend::new(width, height);
+        let mut term = crate::custom_terminal::Terminal::with_options(backend).expect("terminal");
+        term.set_viewport_area(Rect::new(0, height - 1, width, 1));
+        for lines in batches {
+            crate::insert_history::insert_history_lines(&mut term, lines).expect("insert history");
+        }
+        let rows: Vec<String> = term
+            .backend()
+            .vt100()
+            .screen()
+            .rows(0, width)
+            .map(|row| row.trim_end().to_string())
+            .collect();
+        let first = rows.iter().position(|row| !row.is_empty()).unwrap_or(0);
+        let last = rows
+            .iter()
+            .rposition(|row| !row.is_empty())
+            .map_or(0, |i| i + 1);
+        rows[first..last].to_vec()
+    }
+
+    #[test]
+    fn history_burst_renders_like_one_insert_per_cell() {
+        let mut one_by_one = make_test_app();
+        let mut rx = plain_cell_events(20);
+        let mut batches = Vec::new();
+        while let Ok(AppEvent::InsertHistoryCell(cell)) = rx.try_recv() {
+            batches.push(one_by_one.history_lines_for_cells(vec![cell], 80));
+        }
+        let expected = render_history(batches);
+
+        let mut batched = make_test_app();
+        let mut rx = plain_cell_events(20);
+        let Ok(AppEvent::InsertHistoryCell(first)) = rx.try_recv() else {
+            panic!("expected a history cell");
+        };
+        let burst = drain_history_burst(first, &mut rx);
+        assert_eq!(burst.cells.len(), 20);
+        assert!(matches!(burst.next, Some(AppEvent::CommitTick)));
+        let rendered = render_history(vec![batched.history_lines_for_cells(burst.cells, 80)]);
+
+        assert_eq!(rendered, expected);
+        assert_eq!(
+            rendered,
+            (0..20)
+                .flat_map(|i| [String::new(), format!("cell {i}")])
+                .skip(1)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(batched.transcript_cells.len(), 20);
+    }
+
+    #[test]
+    fn history_burst_stops_at_the_batch_cap() {
+        let mut rx = plain_cell_events(HISTORY_BATCH_MAX + 10);
+
+        let Ok(AppEvent::InsertHistoryCell(first)) = rx.try_recv() else {
+            panic!("expected a history cell");
+        };
+        let burst = drain_history_burst(first, &mut rx);
+        assert_eq!(burst.cells.len(), HISTORY_BATCH_MAX);
+        assert!(burst.next.is_none());
+
+        let Ok(AppEvent::InsertHistoryCell(first)) = rx.try_recv() else {
+            panic!("expected a history cell");
+        };
+        let burst = drain_history_burst(first, &mut rx);
+        assert_eq!(burst.cells.len(), 10);
+        assert!(matches!(burst.next, Some(AppEvent::CommitTick)));
+    }
+
     #[test]
     fn model_migration_prompt_only_shows_for_deprecated_models() {
         assert!(should_show_model_migration_prompt("gpt-5", "gpt-5.1", None));
@@ -1072,6 +1409,29 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn config_edits_are_not_written_in_ephemeral_mode() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let config_toml = codex_home.path().join("config.toml");
+        let mut app = make_test_app();
+        app.config.codex_home = codex_home.path().to_path_buf();
+        let edits = |app: &App| {
+            ConfigEditsBuilder::new(&app.config.codex_home).set_hide_full_access_warning(true)
+        };
+
+        app.config.persistence = PersistencePolicy::ephemeral().expect("scratch dir");
+        app.persist_config_edits(edits(&app))
+            .await
+            .expect("ephemeral edits are dropped");
+        assert!(!config_toml.exists());
+
+        app.config.persistence = PersistencePolicy::persistent();
+        app.persist_config_edits(edits(&app))
+            .await
+            .expect("persist edits");
+        assert!(config_toml.exists());
+    }
+
     #[test]
     fn update_reasoning_effort_updates_config() {
         let mut app = make_test_app();
@@ -1,14 +1,20 @@
 use std::any::TypeId;
+use std::ops::Range;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::app::App;
+use crate::app_event::AppEvent;
 use crate::history_cell::SessionInfoCell;
 use crate::history_cell::UserHistoryCell;
+use crate::history_flags::message_cell_key;
+use crate::history_flags::output_cell_key;
 use crate::pager_overlay::Overlay;
 use crate::tui;
 use crate::tui::TuiEvent;
 use codex_core::protocol::ConversationPathResponseEvent;
+use codex_core::protocol::HistoryFlag;
+use codex_core::protocol::Op;
 use codex_protocol::ConversationId;
 use color_eyre::eyre::Result;
 use crossterm::event::KeyCode;
@@ -28,11 +34,18 @@ pub(crate) struct BacktrackState {
     pub(crate) overlay_preview_active: bool,
     /// Pending fork request: (base_id, nth_user_message, prefill).
     pub(crate) pending: Option<(ConversationId, usize, String)>,
+    /// Transcript index of the cell highlighted in the preview; Tab moves it
+    /// through the selected turn.
+    pub(crate) selected_cell: Option<usize>,
 }
 
 impl App {
     /// Route overlay events when transcript overlay is active.
-    /// - If backtrack preview is active: Esc steps selection; Enter confirms.
+    /// - While find is open in the overlay, or the overlay is the hunk picker,
+    ///   every event goes to the overlay.
+    /// - If backtrack preview is active: Esc steps selection; Enter confirms;
+    ///   Tab selects a cell within the turn; `d` deletes the selected cell and
+    ///   `x` toggles whether the turn is marked irrelevant.
     /// - Otherwise: Esc begins preview; all other events forward to overlay.
     ///   interactions (Esc to step target, Enter to confirm) and overlay lifecycle.
     pub(crate) async fn handle_backtrack_overlay_event(
@@ -40,7 +53,12 @@ impl App {
         tui: &mut tui::Tui,
         event: TuiEvent,
     ) -> Result<bool> {
-        if self.backtrack.overlay_preview_active {
+        if self.overlay.as_ref().is_some_and(Overlay::owns_keys) {
+            // Find mode and the hunk picker handle their own Esc/Enter and
+            // typed characters.
+            self.overlay_forward_event(tui, event)?;
+            Ok(true)
+        } else if self.backtrack.overlay_preview_active {
             match event {
                 TuiEvent::Key(KeyEvent {
                     code: KeyCode::Esc,
@@ -58,6 +76,38 @@ impl App {
                     self.overlay_confirm_backtrack(tui);
                     Ok(true)
                 }
+                TuiEvent::Key(KeyEvent {
+                    code: KeyCode::Tab,
+                    kind: KeyEventKind::Press | KeyEventKind::Repeat,
+                    ..
+                }) => {
+                    self.select_next_cell_in_turn(tui);
+                    Ok(true)
+                }
+                TuiEvent::Key(KeyEvent {
+                    code: KeyCode::Char('d'),
+                    kind: KeyEventKind::Press,
+                    ..
+                }) => {
+                    self.delete_selected_cell();
+                    Ok(true)
+                }
+                TuiEvent::Key(KeyEvent {
+                    code: KeyCode::Char('x'),
+                    kind: KeyEventKind::Press,
+                    ..
+                }) => {
+                    self.toggle_selected_turn_relevance();
+                    Ok(true)
+                }
+                TuiEvent::Key(KeyEvent {
+                    code: KeyCode::Char('p'),
+                    kind: KeyEventKind::Press,
+                    ..
+                }) => {
+                    self.toggle_selected_turn_pin();
+                    Ok(true)
+                }
                 // Catchall: forward any other events to the overlay widget.
                 _ => {
                     self.overlay_forward_event(tui, event)?;
@@ -117,7 +167,7 @@ impl App {
 
     /// Open transcript overlay (enters alternate screen and shows full transcript).
     pub(crate) fn open_transcript_overlay(&mut self, tui: &mut tui::Tui) {
-        let _ = tui.enter_alt_screen();
+        let _ = tui.enter_overlay_screen();
         self.overlay = Some(Overlay::new_transcript(self.transcript_cells.clone()));
         tui.frame_requester().schedule_frame();
     }
@@ -126,7 +176,13 @@ impl App {
     pub(crate) fn close_transcript_overlay(&mut self, tui: &mut tui::Tui) {
         let _ = tui.leave_alt_screen();
         let was_backtrack = self.backtrack.overlay_preview_active;
-        if !self.deferred_history_lines.is_empty() {
+        if std::mem::take(&mut self.history_redraw_pending) {
+            // The redraw re-emits every cell, including the deferred ones.
+            self.deferred_history_lines.clear();
+            if let Err(err) = self.redraw_history(tui) {
+                tracing::warn!("failed to redraw history: {err}");
+            }
+        } else if !self.deferred_history_lines.is_empty() {
             let lines = std::mem::take(&mut self.deferred_history_lines);
             tui.insert_history_lines(lines);
         }
@@ -143,12 +199,25 @@ impl App {
     pub(crate) fn render_transcript_once(&mut self, tui: &mut tui::Tui) {
         if !self.transcript_cells.is_empty() {
             let width = tui.terminal.last_known_screen_size.width;
-            for cell in &self.transcript_cells {
-                tui.insert_history_lines(cell.display_lines(width));
-            }
+            tui.insert_history_lines(self.history_flags.render(&self.transcript_cells, width));
         }
     }
 
+    /// Wipe the scrollback and re-emit the transcript with history flags
+    /// applied. Deferred while an overlay owns the screen.
+    pub(crate) fn redraw_history(&mut self, tui: &mut tui::Tui) -> Result<()> {
+        if self.overlay.is_some() {
+            self.history_redraw_pending = true;
+            return Ok(());
+        }
+        tui.clear_history()?;
+        let width = tui.terminal.last_known_screen_size.width;
+        let lines = self.history_flags.render(&self.transcript_cells, width);
+        self.has_emitted_history_lines = !lines.is_empty();
+        tui.insert_history_lines(lines);
+        Ok(())
+    }
+
     /// Initialize backtrack state and show composer hint.
     fn prime_backtrack(&mut self) {
         self.backtrack.primed = true;
@@ -205,14 +274,124 @@ impl App {
     fn apply_backtrack_selection(&mut self, nth_user_message: usize) {
         if let Some(cell_idx) = nth_user_position(&self.transcript_cells, nth_user_message) {
             self.backtrack.nth_user_message = nth_user_message;
-            if let Some(Overlay::Transcript(t)) = &mut self.overlay {
-                t.set_highlight_cell(Some(cell_idx));
-            }
+            self.set_selected_cell(Some(cell_idx));
         } else {
             self.backtrack.nth_user_message = usize::MAX;
-            if let Some(Overlay::Transcript(t)) = &mut self.overlay {
-                t.set_highlight_cell(None);
+            self.set_selected_cell(None);
+        }
+    }
+
+    fn set_selected_cell(&mut self, cell_idx: Option<usize>) {
+        self.backtrack.selected_cell = cell_idx;
+        if let Some(Overlay::Transcript(t)) = &mut self.overlay {
+            t.set_highlight_cell(cell_idx);
+        }
+    }
+
+    /// Handle Tab in overlay backtrack preview: move the highlight to the next
+    /// cell of the selected turn, wrapping back to its user message.
+    fn select_next_cell_in_turn(&mut self, tui: &mut tui::Tui) {
+        let Some(selected) = self.backtrack.selected_cell else {
+            return;
+        };
+        let Some(turn) = turn_cell_range(&self.transcript_cells, self.backtrack.nth_user_message)
+        else {
+            return;
+        };
+        let next = (selected + 1..turn.end)
+            .find(|idx| !self.transcript_cells[*idx].is_stream_continuation())
+            .unwrap_or(turn.start);
+        self.set_selected_cell(Some(next));
+        tui.frame_requester().schedule_frame();
+    }
+
+    /// Handle `d` in overlay backtrack preview. User and agent messages are
+    /// rebuilt on resume, so their deletion is recorded in the rollout; other
+    /// cells are only hidden for this run.
+    fn delete_selected_cell(&mut self) {
+        let Some(selected) = self.backtrack.selected_cell else {
+            return;
+        };
+        // Deletions go through the agent so they are recorded in the rollout
+        // and replayed on resume.
+        let flag = if let Some((nth_user_message, cell)) =
+            message_cell_key(&self.transcript_cells, selected)
+        {
+            HistoryFlag::CellDeleted {
+                nth_user_message,
+                cell,
+            }
+        } else if let Some((nth_user_message, first_line, occurrence)) =
+            output_cell_key(&self.transcript_cells, selected)
+        {
+            HistoryFlag::OutputCellDeleted {
+                nth_user_message,
+                first_line,
+                occurrence,
+            }
+        } else {
+            self.chat_widget.add_info_message(
+                "Only output after your first message can be deleted.".to_string(),
+                None,
+            );
+            return;
+        };
+        self.app_event_tx
+            .send(AppEvent::CodexOp(Op::SetHistoryFlag { flag }));
+    }
+
+    /// Handle `x` in overlay backtrack preview: toggle whether the selected
+    /// turn is collapsed and left out of future prompts.
+    fn toggle_selected_turn_relevance(&mut self) {
+        let nth_user_message = self.backtrack.nth_user_message;
+        if nth_user_message == usize::MAX {
+            return;
+        }
+        let irrelevant = !self.history_flags.is_turn_irrelevant(nth_user_message);
+        self.app_event_tx
+            .send(AppEvent::CodexOp(Op::SetHistoryFlag {
+                flag: HistoryFlag::TurnRelevance {
+                    nth_user_message,
+                    irrelevant,
+                },
+            }));
+    }
+
+    /// Handle `p` in overlay backtrack preview: pin the selected turn's user
+    /// message so compaction keeps it, or unpin it if it already is.
+    fn toggle_selected_turn_pin(&mut self) {
+        let nth_user_message = self.backtrack.nth_user_message;
+        if nth_user_message == usize::MAX {
+            return;
+        }
+        let pinned = !self.history_flags.is_turn_pinned(nth_user_message);
+        self.app_event_tx
+            .send(AppEvent::CodexOp(Op::SetHistoryFlag {
+                flag: HistoryFlag::Pinned {
+                    nth_user_message,
+                    pinned,
+                },
+            }));
+    }
+
+    /// Handle `/pin`: pin the latest user message so compaction keeps it.
+    pub(crate) fn pin_last_user_message(&mut self) {
+        match user_positions_iter(&self.transcript_cells)
+            .count()
+            .checked_sub(1)
+        {
+            Some(nth_user_message) => {
+                self.app_event_tx
+                    .send(AppEvent::CodexOp(Op::SetHistoryFlag {
+                        flag: HistoryFlag::Pinned {
+                            nth_user_message,
+                            pinned: true,
+                        },
+                    }));
             }
+            None => self
+                .chat_widget
+                .add_info_message("There is no message to pin yet.".to_string(), None),
         }
     }
 
@@ -273,6 +452,7 @@ impl App {
         self.backtrack.primed = false;
         self.backtrack.base_id = None;
         self.backtrack.nth_user_message = usize::MAX;
+        self.backtrack.selected_cell = None;
         // In case a hint is somehow still visible (e.g., race with overlay open/close).
         self.chat_widget.clear_esc_backtrack_hint();
     }
@@ -362,6 +542,10 @@ impl App {
     /// Trim transcript_cells to preserve only content up to the selected user message.
     fn trim_transcript_for_backtrack(&mut self, nth_user_message: usize) {
         trim_transcript_cells_to_nth_user(&mut self.transcript_cells, nth_user_message);
+        self.history_flags.truncate(
+            self.transcript_cells.len(),
+            user_count(&self.transcript_cells),
+        );
     }
 }
 
@@ -382,6 +566,16 @@ pub(crate) fn user_count(cells: &[Arc<dyn crate::history_cell::HistoryCell>]) ->
     user_positions_iter(cells).count()
 }
 
+/// Transcript indices covered by the `nth` user turn.
+fn turn_cell_range(
+    cells: &[Arc<dyn crate::history_cell::HistoryCell>],
+    nth: usize,
+) -> Option<Range<usize>> {
+    let start = nth_user_position(cells, nth)?;
+    let end = nth_user_position(cells, nth + 1).unwrap_or(cells.len());
+    Some(start..end)
+}
+
 fn nth_user_position(
     cells: &[Arc<dyn crate::history_cell::HistoryCell>],
     nth: usize,
@@ -391,7 +585,7 @@ fn nth_user_position(
         .find_map(|(i, idx)| (i == nth).then_some(idx))
 }
 
-fn user_positions_iter(
+pub(crate) fn user_positions_iter(
     cells: &[Arc<dyn crate::history_cell::HistoryCell>],
 ) -> impl Iterator<Item = usize> + '_ {
     let session_start_type = TypeId::of::<SessionInfoCell>();
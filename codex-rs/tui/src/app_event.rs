@@ -1,14 +1,23 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 
+use codex_cloud_tasks_client::TaskSummary;
 use codex_common::approval_presets::ApprovalPreset;
 use codex_common::model_presets::ModelPreset;
+use codex_core::patch_selection::PatchSelection;
 use codex_core::protocol::ConversationPathResponseEvent;
 use codex_core::protocol::Event;
+use codex_core::protocol::FileChange;
+use codex_core::protocol::HistoryFlag;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_file_search::FileMatch;
 
 use crate::bottom_pane::ApprovalRequest;
+use crate::clipboard_paste::PasteImageError;
+use crate::clipboard_paste::PastedImageInfo;
+use crate::cloud_tasks_service::CloudTaskTransition;
 use crate::history_cell::HistoryCell;
+use crate::turn_separators::TurnMarker;
 
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
@@ -40,16 +49,48 @@ pub(crate) enum AppEvent {
     FileSearchResult {
         query: String,
         matches: Vec<FileMatch>,
+        /// The walk hit `file_search.max_file_count`; matches are partial.
+        truncated_walk: bool,
     },
 
     /// Result of refreshing rate limits
     RateLimitSnapshotFetched(RateLimitSnapshot),
 
+    /// Result of a background cloud task poll, with the status changes since
+    /// the previous poll.
+    CloudTasksPolled {
+        tasks: Vec<TaskSummary>,
+        transitions: Vec<CloudTaskTransition>,
+    },
+
+    /// Result of reading an image off the clipboard. `from_empty_paste` is
+    /// set when an empty bracketed paste (rather than Ctrl+V) triggered it.
+    ClipboardImagePasted {
+        result: Result<(PathBuf, PastedImageInfo), PasteImageError>,
+        from_empty_paste: bool,
+    },
+
     /// Result of computing a `/diff` command.
     DiffResult(String),
 
     InsertHistoryCell(Box<dyn HistoryCell>),
 
+    /// Replace the composer text with a prompt picked in the Ctrl+R search.
+    RecallPrompt(String),
+
+    /// Persisted prompt history finished loading for the Ctrl+R search.
+    HistoryEntriesLoaded(Vec<String>),
+
+    /// The user started a new turn; history cells inserted after this belong to it.
+    TurnStarted(TurnMarker),
+
+    /// A history display flag was recorded (or replayed on resume); re-render
+    /// the scrollback accordingly.
+    ApplyHistoryFlag(HistoryFlag),
+
+    /// `/pin`: keep the most recent user message through compaction.
+    PinLastUserMessage,
+
     StartCommitAnimation,
     StopCommitAnimation,
     CommitTick,
@@ -134,6 +175,11 @@ pub(crate) enum AppEvent {
         migration_config: String,
     },
 
+    /// Add a command pattern to the project's `always_allow_commands`.
+    PersistAlwaysAllowCommand {
+        pattern: String,
+    },
+
     /// Skip the next world-writable scan (one-shot) after a user-confirmed continue.
     #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
     SkipNextWorldWritableScan,
@@ -141,6 +187,10 @@ pub(crate) enum AppEvent {
     /// Re-open the approval presets popup.
     OpenApprovalsPopup,
 
+    /// The user answered the confirmation for an approval preset requested by
+    /// a saved prompt's front-matter.
+    ResolvePromptApprovalPreset(PromptPresetDecision),
+
     /// Forwarded conversation history snapshot from the current conversation.
     ConversationHistory(ConversationPathResponseEvent),
 
@@ -153,9 +203,29 @@ pub(crate) enum AppEvent {
     /// Open the custom prompt option from the review popup.
     OpenReviewCustomPrompt,
 
+    /// Ask the user what to type into a command that is waiting for input.
+    OpenExecInputPrompt {
+        call_id: String,
+        prompt: String,
+    },
+
     /// Open the approval popup.
     FullScreenApprovalRequest(ApprovalRequest),
 
+    /// Open the hunk picker for the patch approval request `id`.
+    OpenPatchHunkSelection {
+        id: String,
+        cwd: PathBuf,
+        changes: HashMap<PathBuf, FileChange>,
+    },
+
+    /// The user picked which hunks of the patch approval request `id` to
+    /// apply.
+    PatchHunksSelected {
+        id: String,
+        selection: PatchSelection,
+    },
+
     /// Open the feedback note entry overlay after the user selects a category.
     OpenFeedbackNote {
         category: FeedbackCategory,
@@ -168,6 +238,16 @@ pub(crate) enum AppEvent {
     },
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PromptPresetDecision {
+    /// Run the prompt's turn with the requested preset.
+    Apply,
+    /// Run the prompt's turn with the current approval mode.
+    Ignore,
+    /// Do not run the prompt; put its text back in the composer.
+    Cancel,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum FeedbackCategory {
     BadResult,
@@ -4,6 +4,7 @@ use std::time::Instant;
 
 use rand::Rng as _;
 
+use crate::animation::AnimationLevel;
 use crate::frames::ALL_VARIANTS;
 use crate::frames::FRAME_TICK_DEFAULT;
 use crate::tui::FrameRequester;
@@ -42,6 +43,9 @@ impl AsciiAnimation {
     }
 
     pub(crate) fn schedule_next_frame(&self) {
+        if self.request_frame.animation_level() == AnimationLevel::Off {
+            return;
+        }
         let tick_ms = self.frame_tick.as_millis();
         if tick_ms == 0 {
             self.request_frame.schedule_frame();
@@ -56,7 +60,7 @@ impl AsciiAnimation {
         };
         if let Ok(delay_ms_u64) = u64::try_from(delay_ms) {
             self.request_frame
-                .schedule_frame_in(Duration::from_millis(delay_ms_u64));
+                .schedule_animation_frame(Duration::from_millis(delay_ms_u64));
         } else {
             self.request_frame.schedule_frame();
         }
@@ -68,7 +72,7 @@ impl AsciiAnimation {
             return "";
         }
         let tick_ms = self.frame_tick.as_millis();
-        if tick_ms == 0 {
+        if tick_ms == 0 || self.request_frame.animation_level() == AnimationLevel::Off {
             return frames[0];
         }
         let elapsed_ms = self.start.elapsed().as_millis();
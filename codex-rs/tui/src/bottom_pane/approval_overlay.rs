@@ -14,9 +14,14 @@ use crate::history_cell;
 use crate::key_hint;
 use crate::key_hint::KeyBinding;
 use crate::render::highlight::highlight_bash_to_lines;
+use crate::render::line_utils::truncate_line_with_ellipsis;
 use crate::render::renderable::ColumnRenderable;
 use crate::render::renderable::Renderable;
+use codex_core::command_allowlist::is_broad_pattern;
+use codex_core::command_allowlist::pattern_for_command;
+use codex_core::patch_selection::PatchSelection;
 use codex_core::protocol::FileChange;
+use codex_core::protocol::NetworkDestination;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDecision;
 use codex_core::protocol::SandboxCommandAssessment;
@@ -31,6 +36,7 @@ use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::text::Span;
 use ratatui::widgets::Paragraph;
+use ratatui::widgets::Widget;
 use ratatui::widgets::Wrap;
 
 /// Request coming from the agent that needs user approval.
@@ -38,9 +44,19 @@ use ratatui::widgets::Wrap;
 pub(crate) enum ApprovalRequest {
     Exec {
         id: String,
+        /// The exec call this approval is for; explanations are matched on it.
+        call_id: String,
         command: Vec<String>,
         reason: Option<String>,
         risk: Option<SandboxCommandAssessment>,
+        /// `None` when `approvals.explain` is off.
+        explanation: Option<CommandExplanation>,
+    },
+    /// A command already running in the sandbox wants to open a connection.
+    Network {
+        id: String,
+        command: Vec<String>,
+        destination: NetworkDestination,
     },
     ApplyPatch {
         id: String,
@@ -50,6 +66,22 @@ pub(crate) enum ApprovalRequest {
     },
 }
 
+/// Model-generated summary shown under a command awaiting approval.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum CommandExplanation {
+    /// Requested; core sends it once the model answers.
+    Pending,
+    Ready(String),
+    /// The summary could not be generated in time.
+    Unavailable,
+}
+
+impl From<Option<String>> for CommandExplanation {
+    fn from(explanation: Option<String>) -> Self {
+        explanation.map_or(CommandExplanation::Unavailable, CommandExplanation::Ready)
+    }
+}
+
 /// Modal overlay asking the user to approve or deny one or more requests.
 pub(crate) struct ApprovalOverlay {
     current_request: Option<ApprovalRequest>,
@@ -58,6 +90,8 @@ pub(crate) struct ApprovalOverlay {
     app_event_tx: AppEventSender,
     list: ListSelectionView,
     options: Vec<ApprovalOption>,
+    /// Showing the extra confirmation for a broad always-allow pattern.
+    confirming_pattern: bool,
     current_complete: bool,
     done: bool,
 }
@@ -71,6 +105,7 @@ impl ApprovalOverlay {
             app_event_tx: app_event_tx.clone(),
             list: ListSelectionView::new(Default::default(), app_event_tx),
             options: Vec::new(),
+            confirming_pattern: false,
             current_complete: false,
             done: false,
         };
@@ -82,10 +117,36 @@ impl ApprovalOverlay {
         self.queue.push(req);
     }
 
+    /// Fill in the summary of the exec request for `call_id`, whether it is
+    /// on screen or still queued. Returns `false` when no such request is
+    /// waiting.
+    pub fn set_command_explanation(
+        &mut self,
+        call_id: &str,
+        explanation: &CommandExplanation,
+    ) -> bool {
+        if !self.current_complete
+            && let Some(request) = self.current_request.as_mut()
+            && set_explanation(request, call_id, explanation)
+        {
+            // Swap only the header so the highlighted option stays put.
+            if !self.confirming_pattern {
+                let ApprovalRequestState { variant, header } =
+                    ApprovalRequestState::from(request.clone());
+                self.list.set_header(titled_header(&variant, header));
+            }
+            return true;
+        }
+        self.queue
+            .iter_mut()
+            .any(|request| set_explanation(request, call_id, explanation))
+    }
+
     fn set_current(&mut self, request: ApprovalRequest) {
         self.current_request = Some(request.clone());
         let ApprovalRequestState { variant, header } = ApprovalRequestState::from(request);
         self.current_variant = Some(variant.clone());
+        self.confirming_pattern = false;
         self.current_complete = false;
         let (options, params) = Self::build_options(variant, header);
         self.options = options;
@@ -96,51 +157,35 @@ impl ApprovalOverlay {
         variant: ApprovalVariant,
         header: Box<dyn Renderable>,
     ) -> (Vec<ApprovalOption>, SelectionViewParams) {
-        let (options, title) = match &variant {
-            ApprovalVariant::Exec { .. } => (
-                exec_options(),
-                "Would you like to run the following command?".to_string(),
-            ),
-            ApprovalVariant::ApplyPatch { .. } => (
-                patch_options(),
-                "Would you like to make the following edits?".to_string(),
-            ),
-        };
-
-        let header = Box::new(ColumnRenderable::with([
-            Line::from(title.bold()).into(),
-            Line::from("").into(),
-            header,
-        ]));
-
-        let items = options
-            .iter()
-            .map(|opt| SelectionItem {
-                name: opt.label.clone(),
-                display_shortcut: opt
-                    .display_shortcut
-                    .or_else(|| opt.additional_shortcuts.first().copied()),
-                dismiss_on_select: false,
-                ..Default::default()
-            })
-            .collect();
-
-        let params = SelectionViewParams {
-            footer_hint: Some(Line::from(vec![
-                "Press ".into(),
-                key_hint::plain(KeyCode::Enter).into(),
-                " to confirm or ".into(),
-                key_hint::plain(KeyCode::Esc).into(),
-                " to cancel".into(),
-            ])),
-            items,
-            header,
-            ..Default::default()
+        let options = match &variant {
+            ApprovalVariant::Exec { always_allow, .. } => exec_options(always_allow.as_deref()),
+            ApprovalVariant::Network { .. } => network_options(),
+            ApprovalVariant::ApplyPatch { changes, .. } => {
+                patch_options(PatchSelection::new(changes).unit_count() > 1)
+            }
         };
-
+        let params = selection_params(&options, titled_header(&variant, header));
         (options, params)
     }
 
+    /// Ask once more before storing `pattern`, which would allow almost any
+    /// command.
+    fn confirm_broad_pattern(&mut self, pattern: &str) {
+        let header = Paragraph::new(vec![
+            Line::from(format!("Always allow every `{pattern}` command in this project?").bold()),
+            Line::from(""),
+            Line::from(format!(
+                "`{pattern}` can run almost anything. Every command starting with it will run without asking, in this session and future ones."
+            )),
+        ])
+        .wrap(Wrap { trim: false });
+        let options = broad_pattern_options(pattern);
+        let params = selection_params(&options, Box::new(header));
+        self.options = options;
+        self.list = ListSelectionView::new(params, self.app_event_tx.clone());
+        self.confirming_pattern = true;
+    }
+
     fn apply_selection(&mut self, actual_idx: usize) {
         if self.current_complete {
             return;
@@ -148,13 +193,63 @@ impl ApprovalOverlay {
         let Some(option) = self.options.get(actual_idx) else {
             return;
         };
-        if let Some(variant) = self.current_variant.as_ref() {
-            match (&variant, option.decision) {
-                (ApprovalVariant::Exec { id, command }, decision) => {
-                    self.handle_exec_decision(id, command, decision);
+        match option.action.clone() {
+            ApprovalAction::Back => {
+                if let Some(request) = self.current_request.clone() {
+                    self.set_current(request);
                 }
-                (ApprovalVariant::ApplyPatch { id, .. }, decision) => {
-                    self.handle_patch_decision(id, decision);
+                return;
+            }
+            ApprovalAction::ChooseHunks => {
+                // The prompt stays up; the picker answers it once the user
+                // confirms a selection.
+                if let Some(ApprovalVariant::ApplyPatch { id, cwd, changes }) =
+                    self.current_variant.as_ref()
+                {
+                    self.app_event_tx.send(AppEvent::OpenPatchHunkSelection {
+                        id: id.clone(),
+                        cwd: cwd.clone(),
+                        changes: changes.clone(),
+                    });
+                }
+                return;
+            }
+            ApprovalAction::AlwaysAllow {
+                pattern,
+                confirmed: false,
+            } if is_broad_pattern(&pattern) => {
+                self.confirm_broad_pattern(&pattern);
+                return;
+            }
+            action => {
+                if let Some(variant) = self.current_variant.as_ref() {
+                    match (variant, action) {
+                        (
+                            ApprovalVariant::Exec { id, command, .. },
+                            ApprovalAction::AlwaysAllow { pattern, .. },
+                        ) => {
+                            self.handle_always_allow(id, command, pattern);
+                        }
+                        (
+                            ApprovalVariant::Exec { id, command, .. },
+                            ApprovalAction::Decide(decision),
+                        ) => {
+                            self.handle_exec_decision(id, command, decision);
+                        }
+                        (
+                            ApprovalVariant::Network { id, destination },
+                            ApprovalAction::Decide(decision),
+                        ) => {
+                            self.handle_network_decision(id, destination, decision);
+                        }
+                        (
+                            ApprovalVariant::ApplyPatch { id, .. },
+                            ApprovalAction::Decide(decision),
+                        ) => {
+                            self.handle_patch_decision(id, decision);
+                        }
+                        _ => {}
+                    }
                 }
             }
         }
@@ -172,6 +267,36 @@ impl ApprovalOverlay {
         }));
     }
 
+    /// Run the command and keep allowing commands that match `pattern`, now
+    /// and in future sessions of this project.
+    fn handle_always_allow(&self, id: &str, command: &[String], pattern: String) {
+        let cell = history_cell::new_always_allow_decision_cell(&pattern);
+        self.app_event_tx.send(AppEvent::InsertHistoryCell(cell));
+        self.app_event_tx.send(AppEvent::PersistAlwaysAllowCommand {
+            pattern: pattern.clone(),
+        });
+        self.app_event_tx
+            .send(AppEvent::CodexOp(Op::AllowCommandPattern { pattern }));
+        self.app_event_tx.send(AppEvent::CodexOp(Op::ExecApproval {
+            id: id.to_string(),
+            decision: ReviewDecision::Approved,
+        }));
+    }
+
+    fn handle_network_decision(
+        &self,
+        id: &str,
+        destination: &NetworkDestination,
+        decision: ReviewDecision,
+    ) {
+        let cell = history_cell::new_network_approval_decision_cell(destination, decision);
+        self.app_event_tx.send(AppEvent::InsertHistoryCell(cell));
+        self.app_event_tx.send(AppEvent::CodexOp(Op::ExecApproval {
+            id: id.to_string(),
+            decision,
+        }));
+    }
+
     fn handle_patch_decision(&self, id: &str, decision: ReviewDecision) {
         self.app_event_tx.send(AppEvent::CodexOp(Op::PatchApproval {
             id: id.to_string(),
@@ -179,6 +304,36 @@ impl ApprovalOverlay {
         }));
     }
 
+    /// Decide the patch request `id` with the hunks picked for it. Returns
+    /// `false` when that request is not the one on screen.
+    pub fn apply_patch_selection(&mut self, id: &str, selection: &PatchSelection) -> bool {
+        let is_current = matches!(
+            self.current_variant.as_ref(),
+            Some(ApprovalVariant::ApplyPatch { id: current, .. }) if current == id
+        );
+        if self.current_complete || !is_current {
+            return false;
+        }
+        let applied = selection.selected_count();
+        let total = selection.unit_count();
+        if applied == total {
+            self.handle_patch_decision(id, ReviewDecision::Approved);
+        } else if applied == 0 {
+            self.handle_patch_decision(id, ReviewDecision::Denied);
+        } else {
+            let cell = history_cell::new_patch_hunks_decision_cell(applied, total);
+            self.app_event_tx.send(AppEvent::InsertHistoryCell(cell));
+            self.app_event_tx
+                .send(AppEvent::CodexOp(Op::PartialPatchApproval {
+                    id: id.to_string(),
+                    changes: selection.selected_changes(),
+                }));
+        }
+        self.current_complete = true;
+        self.advance_queue();
+        true
+    }
+
     fn advance_queue(&mut self) {
         if let Some(next) = self.queue.pop() {
             self.set_current(next);
@@ -238,9 +393,12 @@ impl BottomPaneView for ApprovalOverlay {
             && let Some(variant) = self.current_variant.as_ref()
         {
             match &variant {
-                ApprovalVariant::Exec { id, command } => {
+                ApprovalVariant::Exec { id, command, .. } => {
                     self.handle_exec_decision(id, command, ReviewDecision::Abort);
                 }
+                ApprovalVariant::Network { id, destination } => {
+                    self.handle_network_decision(id, destination, ReviewDecision::Abort);
+                }
                 ApprovalVariant::ApplyPatch { id, .. } => {
                     self.handle_patch_decision(id, ReviewDecision::Abort);
                 }
@@ -255,6 +413,10 @@ impl BottomPaneView for ApprovalOverlay {
         self.done
     }
 
+    fn is_awaiting_approval(&self) -> bool {
+        !self.done
+    }
+
     fn try_consume_approval_request(
         &mut self,
         request: ApprovalRequest,
@@ -262,6 +424,18 @@ impl BottomPaneView for ApprovalOverlay {
         self.enqueue_request(request);
         None
     }
+
+    fn try_set_command_explanation(
+        &mut self,
+        call_id: &str,
+        explanation: &CommandExplanation,
+    ) -> bool {
+        self.set_command_explanation(call_id, explanation)
+    }
+
+    fn try_apply_patch_selection(&mut self, id: &str, selection: &PatchSelection) -> bool {
+        self.apply_patch_selection(id, selection)
+    }
 }
 
 impl Renderable for ApprovalOverlay {
@@ -278,6 +452,87 @@ impl Renderable for ApprovalOverlay {
     }
 }
 
+fn selection_params(
+    options: &[ApprovalOption],
+    header: Box<dyn Renderable>,
+) -> SelectionViewParams {
+    let items = options
+        .iter()
+        .map(|opt| SelectionItem {
+            name: opt.label.clone(),
+            display_shortcut: opt
+                .display_shortcut
+                .or_else(|| opt.additional_shortcuts.first().copied()),
+            dismiss_on_select: false,
+            ..Default::default()
+        })
+        .collect();
+
+    SelectionViewParams {
+        footer_hint: Some(Line::from(vec![
+            "Press ".into(),
+            key_hint::plain(KeyCode::Enter).into(),
+            " to confirm or ".into(),
+            key_hint::plain(KeyCode::Esc).into(),
+            " to cancel".into(),
+        ])),
+        items,
+        header,
+        ..Default::default()
+    }
+}
+
+fn titled_header(variant: &ApprovalVariant, header: Box<dyn Renderable>) -> Box<dyn Renderable> {
+    let title = match variant {
+        ApprovalVariant::Exec { .. } => "Would you like to run the following command?".to_string(),
+        ApprovalVariant::Network { destination, .. } => {
+            format!("Allow this command to connect to {destination}?")
+        }
+        ApprovalVariant::ApplyPatch { .. } => {
+            "Would you like to make the following edits?".to_string()
+        }
+    };
+    Box::new(ColumnRenderable::with([
+        PromptTitle(Line::from(title.bold())).into(),
+        Line::from("").into(),
+        header,
+    ]))
+}
+
+/// A one-line prompt title that ends in "…" when the pane is too narrow for
+/// it.
+struct PromptTitle(Line<'static>);
+
+impl Renderable for PromptTitle {
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        truncate_line_with_ellipsis(self.0.clone(), usize::from(area.width)).render(area, buf);
+    }
+
+    fn desired_height(&self, _width: u16) -> u16 {
+        1
+    }
+}
+
+/// Store `explanation` on `request` if it is the exec request for `call_id`
+/// and asked for one.
+fn set_explanation(
+    request: &mut ApprovalRequest,
+    call_id: &str,
+    explanation: &CommandExplanation,
+) -> bool {
+    match request {
+        ApprovalRequest::Exec {
+            call_id: request_call_id,
+            explanation: slot @ Some(_),
+            ..
+        } if *request_call_id == call_id => {
+            *slot = Some(explanation.clone());
+            true
+        }
+        _ => false,
+    }
+}
+
 struct ApprovalRequestState {
     variant: ApprovalVariant,
     header: Box<dyn Renderable>,
@@ -288,9 +543,11 @@ impl From<ApprovalRequest> for ApprovalRequestState {
         match value {
             ApprovalRequest::Exec {
                 id,
+                call_id: _,
                 command,
                 reason,
                 risk,
+                explanation,
             } => {
                 let reason = reason.filter(|item| !item.is_empty());
                 let has_reason = reason.is_some();
@@ -309,8 +566,37 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                     first.spans.insert(0, Span::from("$ "));
                 }
                 header.extend(full_cmd_lines);
+                if let Some(explanation) = explanation.as_ref() {
+                    header.push(Line::from(""));
+                    header.push(render_explanation_line(explanation));
+                }
+                let always_allow = pattern_for_command(&command);
+                Self {
+                    variant: ApprovalVariant::Exec {
+                        id,
+                        command,
+                        always_allow,
+                    },
+                    header: Box::new(Paragraph::new(header).wrap(Wrap { trim: false })),
+                }
+            }
+            ApprovalRequest::Network {
+                id,
+                command,
+                destination,
+            } => {
+                let mut header: Vec<Line<'static>> = vec![
+                    Line::from(vec!["Destination: ".into(), destination.to_string().bold()]),
+                    Line::from(""),
+                ];
+                let full_cmd = strip_bash_lc_and_escape(&command);
+                let mut full_cmd_lines = highlight_bash_to_lines(&full_cmd);
+                if let Some(first) = full_cmd_lines.first_mut() {
+                    first.spans.insert(0, Span::from("$ "));
+                }
+                header.extend(full_cmd_lines);
                 Self {
-                    variant: ApprovalVariant::Exec { id, command },
+                    variant: ApprovalVariant::Network { id, destination },
                     header: Box::new(Paragraph::new(header).wrap(Wrap { trim: false })),
                 }
             }
@@ -330,9 +616,9 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                     ));
                     header.push(Box::new(Line::from("")));
                 }
-                header.push(DiffSummary::new(changes, cwd).into());
+                header.push(DiffSummary::new(changes.clone(), cwd.clone()).into());
                 Self {
-                    variant: ApprovalVariant::ApplyPatch { id },
+                    variant: ApprovalVariant::ApplyPatch { id, cwd, changes },
                     header: Box::new(ColumnRenderable::with(header)),
                 }
             }
@@ -362,16 +648,53 @@ fn render_risk_lines(risk: &SandboxCommandAssessment) -> Vec<Line<'static>> {
     lines
 }
 
+fn render_explanation_line(explanation: &CommandExplanation) -> Line<'static> {
+    let text = match explanation {
+        CommandExplanation::Pending => "generating…".dim().italic(),
+        CommandExplanation::Ready(text) => text.clone().into(),
+        CommandExplanation::Unavailable => "unavailable".dim().italic(),
+    };
+    Line::from(vec!["AI-generated summary: ".dim(), text])
+}
+
 #[derive(Clone)]
 enum ApprovalVariant {
-    Exec { id: String, command: Vec<String> },
-    ApplyPatch { id: String },
+    Exec {
+        id: String,
+        command: Vec<String>,
+        /// Pattern offered for the project's `always_allow_commands`.
+        always_allow: Option<String>,
+    },
+    Network {
+        id: String,
+        destination: NetworkDestination,
+    },
+    ApplyPatch {
+        id: String,
+        cwd: PathBuf,
+        changes: HashMap<PathBuf, FileChange>,
+    },
+}
+
+#[derive(Clone)]
+enum ApprovalAction {
+    Decide(ReviewDecision),
+    /// Approve and add `pattern` to the project's `always_allow_commands`.
+    /// Broad patterns are only stored once `confirmed`.
+    AlwaysAllow {
+        pattern: String,
+        confirmed: bool,
+    },
+    /// Leave the broad pattern confirmation for the request's options.
+    Back,
+    /// Open the hunk picker to approve only part of the patch.
+    ChooseHunks,
 }
 
 #[derive(Clone)]
 struct ApprovalOption {
     label: String,
-    decision: ReviewDecision,
+    action: ApprovalAction,
     display_shortcut: Option<KeyBinding>,
     additional_shortcuts: Vec<KeyBinding>,
 }
@@ -384,46 +707,109 @@ impl ApprovalOption {
     }
 }
 
-fn exec_options() -> Vec<ApprovalOption> {
-    vec![
+fn exec_options(always_allow: Option<&str>) -> Vec<ApprovalOption> {
+    let mut options = vec![
         ApprovalOption {
             label: "Yes, proceed".to_string(),
-            decision: ReviewDecision::Approved,
+            action: ApprovalAction::Decide(ReviewDecision::Approved),
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
         },
         ApprovalOption {
             label: "Yes, and don't ask again for this command".to_string(),
-            decision: ReviewDecision::ApprovedForSession,
+            action: ApprovalAction::Decide(ReviewDecision::ApprovedForSession),
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
         },
+    ];
+    if let Some(pattern) = always_allow {
+        options.push(ApprovalOption {
+            label: format!("Yes, and always allow `{pattern}` in this project"),
+            action: ApprovalAction::AlwaysAllow {
+                pattern: pattern.to_string(),
+                confirmed: false,
+            },
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('p'))],
+        });
+    }
+    options.push(ApprovalOption {
+        label: "No, and tell Codex what to do differently".to_string(),
+        action: ApprovalAction::Decide(ReviewDecision::Abort),
+        display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
+        additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
+    });
+    options
+}
+
+fn broad_pattern_options(pattern: &str) -> Vec<ApprovalOption> {
+    vec![
+        ApprovalOption {
+            label: format!("Yes, always allow every `{pattern}` command"),
+            action: ApprovalAction::AlwaysAllow {
+                pattern: pattern.to_string(),
+                confirmed: true,
+            },
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+        },
         ApprovalOption {
-            label: "No, and tell Codex what to do differently".to_string(),
-            decision: ReviewDecision::Abort,
+            label: "No, go back".to_string(),
+            action: ApprovalAction::Back,
             display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
         },
     ]
 }
 
-fn patch_options() -> Vec<ApprovalOption> {
+fn network_options() -> Vec<ApprovalOption> {
     vec![
         ApprovalOption {
-            label: "Yes, proceed".to_string(),
-            decision: ReviewDecision::Approved,
+            label: "Yes, allow this connection".to_string(),
+            action: ApprovalAction::Decide(ReviewDecision::Approved),
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
         },
         ApprovalOption {
-            label: "No, and tell Codex what to do differently".to_string(),
-            decision: ReviewDecision::Abort,
+            label: "Yes, and don't ask again for this destination".to_string(),
+            action: ApprovalAction::Decide(ReviewDecision::ApprovedForSession),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
+        },
+        ApprovalOption {
+            label: "No, block it".to_string(),
+            action: ApprovalAction::Decide(ReviewDecision::Denied),
             display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
         },
     ]
 }
 
+/// `offer_hunks` when the patch has more than one hunk to choose from.
+fn patch_options(offer_hunks: bool) -> Vec<ApprovalOption> {
+    let mut options = vec![ApprovalOption {
+        label: "Yes, proceed".to_string(),
+        action: ApprovalAction::Decide(ReviewDecision::Approved),
+        display_shortcut: None,
+        additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+    }];
+    if offer_hunks {
+        options.push(ApprovalOption {
+            label: "Yes, but only the hunks I choose".to_string(),
+            action: ApprovalAction::ChooseHunks,
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('h'))],
+        });
+    }
+    options.push(ApprovalOption {
+        label: "No, and tell Codex what to do differently".to_string(),
+        action: ApprovalAction::Decide(ReviewDecision::Abort),
+        display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
+        additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
+    });
+    options
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -434,12 +820,26 @@ mod tests {
     fn make_exec_request() -> ApprovalRequest {
         ApprovalRequest::Exec {
             id: "test".to_string(),
+            call_id: "call-test".to_string(),
             command: vec!["echo".to_string(), "hi".to_string()],
             reason: Some("reason".to_string()),
             risk: None,
+            explanation: None,
         }
     }
 
+    fn renders_line_containing(view: &ApprovalOverlay, needle: &str) -> bool {
+        let area = Rect::new(0, 0, 80, view.desired_height(80));
+        let mut buf = Buffer::empty(area);
+        view.render(area, &mut buf);
+        (0..buf.area.height).any(|row| {
+            (0..buf.area.width)
+                .map(|col| buf[(col, row)].symbol().to_string())
+                .collect::<String>()
+                .contains(needle)
+        })
+    }
+
     #[test]
     fn ctrl_c_aborts_and_clears_queue() {
         let (tx, _rx) = unbounded_channel::<AppEvent>();
@@ -476,9 +876,11 @@ mod tests {
         let command = vec!["echo".into(), "hello".into(), "world".into()];
         let exec_request = ApprovalRequest::Exec {
             id: "test".into(),
+            call_id: "call-test".into(),
             command,
             reason: None,
             risk: None,
+            explanation: None,
         };
 
         let view = ApprovalOverlay::new(exec_request, tx);
@@ -549,4 +951,275 @@ mod tests {
         }
         assert_eq!(decision, Some(ReviewDecision::ApprovedForSession));
     }
+
+    #[test]
+    fn network_request_shows_destination_and_escape_denies() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let request = ApprovalRequest::Network {
+            id: "sub-1".into(),
+            call_id: "call-1".into(),
+            command: vec!["curl".into(), "http://127.0.0.1:8080".into()],
+            destination: NetworkDestination {
+                host: "127.0.0.1".into(),
+                port: 8080,
+            },
+        };
+        let mut view = ApprovalOverlay::new(request, tx);
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 80, view.desired_height(80)));
+        view.render(Rect::new(0, 0, 80, view.desired_height(80)), &mut buf);
+        let rendered: Vec<String> = (0..buf.area.height)
+            .map(|row| {
+                (0..buf.area.width)
+                    .map(|col| buf[(col, row)].symbol().to_string())
+                    .collect()
+            })
+            .collect();
+        assert!(
+            rendered
+                .iter()
+                .any(|line| line.contains("Allow this command to connect to 127.0.0.1:8080?")),
+            "expected title to name the destination, got {rendered:?}"
+        );
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        let mut decision = None;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CodexOp(Op::ExecApproval { id, decision: d }) = ev {
+                assert_eq!(id, "sub-1");
+                decision = Some(d);
+            }
+        }
+        assert_eq!(decision, Some(ReviewDecision::Denied));
+    }
+
+    #[test]
+    fn explanation_fills_in_without_moving_the_selection() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let request = ApprovalRequest::Exec {
+            id: "sub-1".into(),
+            call_id: "call-1".into(),
+            command: vec!["rm".into(), "-rf".into(), "build".into()],
+            reason: None,
+            risk: None,
+            explanation: Some(CommandExplanation::Pending),
+        };
+        let mut view = ApprovalOverlay::new(request, tx);
+        assert!(
+            renders_line_containing(&view, "AI-generated summary: generating…"),
+            "expected a placeholder while the summary is pending"
+        );
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        assert!(
+            !view.set_command_explanation("other", &CommandExplanation::Ready("Unrelated.".into()))
+        );
+        // Matched on the call id; the sub id is shared by every request in a turn.
+        assert!(
+            !view.set_command_explanation("sub-1", &CommandExplanation::Ready("Unrelated.".into()))
+        );
+        assert!(view.set_command_explanation(
+            "call-1",
+            &CommandExplanation::Ready("Deletes the build folder.".into())
+        ));
+        assert!(
+            renders_line_containing(&view, "AI-generated summary: Deletes the build folder."),
+            "expected the summary under the command"
+        );
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        let mut decision = None;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CodexOp(Op::ExecApproval { decision: d, .. }) = ev {
+                decision = Some(d);
+            }
+        }
+        assert_eq!(decision, Some(ReviewDecision::ApprovedForSession));
+    }
+
+    #[test]
+    fn explanation_reaches_queued_requests_that_asked_for_one() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        for call_id in ["call-make", "call-lint"] {
+            view.enqueue_request(ApprovalRequest::Exec {
+                id: "test".into(),
+                call_id: call_id.into(),
+                command: vec!["make".into()],
+                reason: None,
+                risk: None,
+                explanation: Some(CommandExplanation::Pending),
+            });
+        }
+
+        assert!(!view.set_command_explanation("call-test", &CommandExplanation::Unavailable));
+        assert!(view.set_command_explanation("call-lint", &CommandExplanation::Unavailable));
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert!(
+            renders_line_containing(&view, "AI-generated summary: generating…"),
+            "expected the first queued request to keep waiting for its own summary"
+        );
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert!(
+            renders_line_containing(&view, "AI-generated summary: unavailable"),
+            "expected the second queued request to show the fallback"
+        );
+    }
+
+    /// Patterns persisted, patterns allowed for the session and exec
+    /// decisions sent so far.
+    fn always_allow_events(
+        rx: &mut tokio::sync::mpsc::UnboundedReceiver<AppEvent>,
+    ) -> (Vec<String>, Vec<String>, Vec<ReviewDecision>) {
+        let mut events = (Vec::new(), Vec::new(), Vec::new());
+        while let Ok(ev) = rx.try_recv() {
+            match ev {
+                AppEvent::PersistAlwaysAllowCommand { pattern } => events.0.push(pattern),
+                AppEvent::CodexOp(Op::AllowCommandPattern { pattern }) => events.1.push(pattern),
+                AppEvent::CodexOp(Op::ExecApproval { decision, .. }) => events.2.push(decision),
+                _ => {}
+            }
+        }
+        events
+    }
+
+    #[test]
+    fn always_allow_stores_the_normalized_pattern() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let request = ApprovalRequest::Exec {
+            id: "sub-1".into(),
+            call_id: "call-1".into(),
+            command: vec![
+                "bash".into(),
+                "-lc".into(),
+                "cargo test -p codex-core".into(),
+            ],
+            reason: None,
+            risk: None,
+            explanation: None,
+        };
+        let mut view = ApprovalOverlay::new(request, tx);
+        assert!(
+            renders_line_containing(&view, "Yes, and always allow `cargo test` in this project"),
+            "expected the option to name the stored pattern"
+        );
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE));
+
+        assert!(view.is_complete());
+        assert_eq!(
+            always_allow_events(&mut rx),
+            (
+                vec!["cargo test".to_string()],
+                vec!["cargo test".to_string()],
+                vec![ReviewDecision::Approved],
+            )
+        );
+    }
+
+    #[test]
+    fn broad_pattern_asks_again_and_can_go_back() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let request = ApprovalRequest::Exec {
+            id: "sub-1".into(),
+            call_id: "call-1".into(),
+            command: vec!["rm".into(), "-rf".into(), "build".into()],
+            reason: None,
+            risk: None,
+            explanation: None,
+        };
+        let mut view = ApprovalOverlay::new(request, tx);
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE));
+        assert!(!view.is_complete());
+        assert!(
+            renders_line_containing(&view, "Always allow every `rm` command in this project?"),
+            "expected a second confirmation for a broad pattern"
+        );
+        view.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(!view.is_complete());
+        assert!(renders_line_containing(
+            &view,
+            "Would you like to run the following command?"
+        ));
+        assert_eq!(
+            always_allow_events(&mut rx),
+            (Vec::new(), Vec::new(), Vec::new())
+        );
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert!(view.is_complete());
+        assert_eq!(
+            always_allow_events(&mut rx),
+            (
+                vec!["rm".to_string()],
+                vec!["rm".to_string()],
+                vec![ReviewDecision::Approved],
+            )
+        );
+    }
+
+    #[test]
+    fn partial_hunk_selection_approves_only_the_chosen_hunks() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let changes = HashMap::from([(
+            PathBuf::from("src/lib.rs"),
+            FileChange::Update {
+                unified_diff: "@@ -1 +1 @@\n-a\n+b\n@@ -9 +9 @@\n-c\n+d\n".to_string(),
+                move_path: None,
+            },
+        )]);
+        let request = ApprovalRequest::ApplyPatch {
+            id: "sub-1".into(),
+            reason: None,
+            cwd: PathBuf::from("/"),
+            changes: changes.clone(),
+        };
+        let mut view = ApprovalOverlay::new(request, tx);
+        assert!(renders_line_containing(
+            &view,
+            "Yes, but only the hunks I choose"
+        ));
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('h'), KeyModifiers::NONE));
+        assert!(!view.is_complete());
+        let Ok(AppEvent::OpenPatchHunkSelection { id, .. }) = rx.try_recv() else {
+            panic!("expected the hunk picker to open");
+        };
+        assert_eq!(id, "sub-1");
+
+        let mut selection = PatchSelection::new(&changes);
+        selection.toggle_hunk(0, 1);
+        assert!(!view.apply_patch_selection("other", &selection));
+        assert!(view.apply_patch_selection("sub-1", &selection));
+        assert!(view.is_complete());
+
+        let mut ops = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CodexOp(op) = ev {
+                ops.push(op);
+            }
+        }
+        assert_eq!(
+            ops,
+            vec![Op::PartialPatchApproval {
+                id: "sub-1".to_string(),
+                changes: HashMap::from([(
+                    PathBuf::from("src/lib.rs"),
+                    FileChange::Update {
+                        unified_diff: "@@ -1 +1 @@\n-a\n+b\n".to_string(),
+                        move_path: None,
+                    },
+                )]),
+            }]
+        );
+    }
 }
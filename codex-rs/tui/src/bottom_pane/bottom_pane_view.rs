@@ -1,5 +1,7 @@
 use crate::bottom_pane::ApprovalRequest;
+use crate::bottom_pane::CommandExplanation;
 use crate::render::renderable::Renderable;
+use codex_core::patch_selection::PatchSelection;
 use crossterm::event::KeyEvent;
 
 use super::CancellationEvent;
@@ -26,6 +28,12 @@ pub(crate) trait BottomPaneView: Renderable {
         false
     }
 
+    /// Return `true` while the view is waiting for the user to decide on an
+    /// approval request.
+    fn is_awaiting_approval(&self) -> bool {
+        false
+    }
+
     /// Try to handle approval request; return the original value if not
     /// consumed.
     fn try_consume_approval_request(
@@ -34,4 +42,20 @@ pub(crate) trait BottomPaneView: Renderable {
     ) -> Option<ApprovalRequest> {
         Some(request)
     }
+
+    /// Fill in the summary of the exec approval for `call_id`; return `true`
+    /// if this view holds that request.
+    fn try_set_command_explanation(
+        &mut self,
+        _call_id: &str,
+        _explanation: &CommandExplanation,
+    ) -> bool {
+        false
+    }
+
+    /// Answer the patch approval `id` with the hunks picked for it; return
+    /// `true` if this view holds that request.
+    fn try_apply_patch_selection(&mut self, _id: &str, _selection: &PatchSelection) -> bool {
+        false
+    }
 }
@@ -33,15 +33,18 @@ use crate::bottom_pane::paste_burst::FlushResult;
 use crate::bottom_pane::prompt_args::expand_custom_prompt;
 use crate::bottom_pane::prompt_args::expand_if_numeric_with_positional_args;
 use crate::bottom_pane::prompt_args::parse_slash_name;
-use crate::bottom_pane::prompt_args::prompt_argument_names;
 use crate::bottom_pane::prompt_args::prompt_command_with_arg_placeholders;
 use crate::bottom_pane::prompt_args::prompt_has_numeric_placeholders;
+use crate::bottom_pane::prompt_args::prompt_named_args;
 use crate::render::Insets;
 use crate::render::RectExt;
+use crate::render::line_utils::truncate_line_with_ellipsis;
 use crate::render::renderable::Renderable;
 use crate::slash_command::SlashCommand;
-use crate::slash_command::built_in_slash_commands;
+use crate::slash_command::find_built_in_slash_command;
+use crate::status::RateLimitHeadroom;
 use crate::style::user_message_style;
+use codex_core::slash_commands::CommandAvailability;
 use codex_protocol::custom_prompts::CustomPrompt;
 use codex_protocol::custom_prompts::PROMPTS_CMD_PREFIX;
 
@@ -70,6 +73,8 @@ const LARGE_PASTE_CHAR_THRESHOLD: usize = 1000;
 pub enum InputResult {
     Submitted(String),
     Command(SlashCommand),
+    /// A built-in command that takes arguments, with the text after its name.
+    CommandWithArgs(SlashCommand, String),
     None,
 }
 
@@ -110,9 +115,15 @@ pub(crate) struct ChatComposer {
     // When true, disables paste-burst logic and inserts characters immediately.
     disable_paste_burst: bool,
     custom_prompts: Vec<CustomPrompt>,
+    // Saved prompt behind the last submission; ChatWidget drains it via take_submitted_prompt().
+    submitted_prompt: Option<CustomPrompt>,
+    command_availability: CommandAvailability,
     footer_mode: FooterMode,
     footer_hint_override: Option<Vec<(String, String)>>,
     context_window_percent: Option<i64>,
+    context_window_estimated: bool,
+    cloud_task_updates: usize,
+    rate_limits: Option<RateLimitHeadroom>,
 }
 
 /// Popup state – at most one can be visible at any time.
@@ -153,9 +164,14 @@ impl ChatComposer {
             paste_burst: PasteBurst::default(),
             disable_paste_burst: false,
             custom_prompts: Vec::new(),
+            submitted_prompt: None,
+            command_availability: CommandAvailability::all(),
             footer_mode: FooterMode::ShortcutSummary,
             footer_hint_override: None,
             context_window_percent: None,
+            context_window_estimated: false,
+            cloud_task_updates: 0,
+            rate_limits: None,
         };
         // Apply configuration via the setter to keep side-effects centralized.
         this.set_disable_paste_burst(disable_paste_burst);
@@ -201,6 +217,11 @@ impl ChatComposer {
         self.history.set_metadata(log_id, entry_count);
     }
 
+    /// Messages submitted during this session, oldest first.
+    pub(crate) fn local_history(&self) -> &[String] {
+        self.history.local_entries()
+    }
+
     /// Integrate an asynchronous response to an on-demand history lookup. If
     /// the entry is present and the offset matches the current cursor we
     /// immediately populate the textarea.
@@ -309,6 +330,16 @@ impl ChatComposer {
             .map(|name| name.to_string_lossy().into_owned())
             .unwrap_or_else(|| "image".to_string());
         let placeholder = format!("[{file_label} {width}x{height}]");
+        self.attach_image_with_placeholder(path, placeholder);
+    }
+
+    /// Attach an image pasted from the clipboard, shown as a chip such as
+    /// `[image: 1.2 MB png]`.
+    pub(crate) fn attach_pasted_image(&mut self, path: PathBuf, chip: String) {
+        self.attach_image_with_placeholder(path, chip);
+    }
+
+    fn attach_image_with_placeholder(&mut self, path: PathBuf, placeholder: String) {
         // Insert as an element to match large paste placeholder behavior:
         // styled distinctly and treated atomically for cursor/mutations.
         self.textarea.insert_element(&placeholder);
@@ -321,6 +352,16 @@ impl ChatComposer {
         images.into_iter().map(|img| img.path).collect()
     }
 
+    pub(crate) fn take_submitted_prompt(&mut self) -> Option<CustomPrompt> {
+        self.submitted_prompt.take()
+    }
+
+    fn prompt_for_line(&self, line: &str) -> Option<&CustomPrompt> {
+        let (name, _rest) = parse_slash_name(line)?;
+        let prompt_name = name.strip_prefix(&format!("{PROMPTS_CMD_PREFIX}:"))?;
+        self.custom_prompts.iter().find(|p| p.name == prompt_name)
+    }
+
     pub(crate) fn flush_paste_burst_if_due(&mut self) -> bool {
         self.handle_paste_burst_flush(Instant::now())
     }
@@ -334,7 +375,12 @@ impl ChatComposer {
     }
 
     /// Integrate results from an asynchronous file search.
-    pub(crate) fn on_file_search_result(&mut self, query: String, matches: Vec<FileMatch>) {
+    pub(crate) fn on_file_search_result(
+        &mut self,
+        query: String,
+        matches: Vec<FileMatch>,
+        truncated_walk: bool,
+    ) {
         // Only apply if user is still editing a token starting with `query`.
         let current_opt = Self::current_at_token(&self.textarea);
         let Some(current_token) = current_opt else {
@@ -346,7 +392,7 @@ impl ChatComposer {
         }
 
         if let ActivePopup::File(popup) = &mut self.active_popup {
-            popup.set_matches(&query, matches);
+            popup.set_matches(&query, matches, truncated_walk);
         }
     }
 
@@ -498,12 +544,20 @@ impl ChatComposer {
                     && let Some(expanded) =
                         expand_if_numeric_with_positional_args(prompt, first_line)
                 {
+                    self.submitted_prompt = Some(prompt.clone());
                     self.textarea.set_text("");
                     return (InputResult::Submitted(expanded), true);
                 }
 
                 if let Some(sel) = popup.selected_item() {
                     match sel {
+                        CommandItem::Builtin(cmd) if cmd.argument_hint().is_some() => {
+                            // Complete the name so the user can type the arguments.
+                            let text = format!("/{} ", cmd.command());
+                            self.textarea.set_text(&text);
+                            self.textarea.set_cursor(text.len());
+                            return (InputResult::None, true);
+                        }
                         CommandItem::Builtin(cmd) => {
                             self.textarea.set_text("");
                             return (InputResult::Command(cmd), true);
@@ -516,6 +570,7 @@ impl ChatComposer {
                                     PromptSelectionMode::Submit,
                                 ) {
                                     PromptSelectionAction::Submit { text } => {
+                                        self.submitted_prompt = Some(prompt.clone());
                                         self.textarea.set_text("");
                                         return (InputResult::Submitted(text), true);
                                     }
@@ -929,13 +984,17 @@ impl ChatComposer {
                 // literal text.
                 let first_line = self.textarea.text().lines().next().unwrap_or("");
                 if let Some((name, rest)) = parse_slash_name(first_line)
-                    && rest.is_empty()
-                    && let Some((_n, cmd)) = built_in_slash_commands()
-                        .into_iter()
-                        .find(|(n, _)| *n == name)
+                    && let Some(cmd) = find_built_in_slash_command(name, self.command_availability)
                 {
-                    self.textarea.set_text("");
-                    return (InputResult::Command(cmd), true);
+                    if rest.is_empty() {
+                        self.textarea.set_text("");
+                        return (InputResult::Command(cmd), true);
+                    }
+                    if cmd.argument_hint().is_some() {
+                        let args = rest.trim().to_string();
+                        self.textarea.set_text("");
+                        return (InputResult::CommandWithArgs(cmd, args), true);
+                    }
                 }
                 // If we're in a paste-like burst capture, treat Enter as part of the burst
                 // and accumulate it rather than submitting or inserting immediately.
@@ -1001,9 +1060,8 @@ impl ChatComposer {
                 if let Some((name, _rest)) = parse_slash_name(&text) {
                     let treat_as_plain_text = input_starts_with_space || name.contains('/');
                     if !treat_as_plain_text {
-                        let is_builtin = built_in_slash_commands()
-                            .into_iter()
-                            .any(|(command_name, _)| command_name == name);
+                        let is_builtin =
+                            find_built_in_slash_command(name, self.command_availability).is_some();
                         let prompt_prefix = format!("{PROMPTS_CMD_PREFIX}:");
                         let is_known_prompt = name
                             .strip_prefix(&prompt_prefix)
@@ -1039,6 +1097,7 @@ impl ChatComposer {
                     }
                 };
                 if let Some(expanded) = expanded_prompt {
+                    self.submitted_prompt = self.prompt_for_line(&text).cloned();
                     text = expanded;
                 }
                 if text.is_empty() && !has_attachments {
@@ -1391,6 +1450,9 @@ impl ChatComposer {
             use_shift_enter_hint: self.use_shift_enter_hint,
             is_task_running: self.is_task_running,
             context_window_percent: self.context_window_percent,
+            context_window_estimated: self.context_window_estimated,
+            cloud_task_updates: self.cloud_task_updates,
+            rate_limits: self.rate_limits,
         }
     }
 
@@ -1452,7 +1514,8 @@ impl ChatComposer {
             }
             _ => {
                 if is_editing_slash_command_name {
-                    let mut command_popup = CommandPopup::new(self.custom_prompts.clone());
+                    let mut command_popup =
+                        CommandPopup::new(self.custom_prompts.clone(), self.command_availability);
                     command_popup.on_composer_text_change(first_line.to_string());
                     self.active_popup = ActivePopup::Command(command_popup);
                 }
@@ -1460,6 +1523,17 @@ impl ChatComposer {
         }
     }
 
+    /// Limit built-in commands to those available in the current environment.
+    pub(crate) fn set_command_availability(&mut self, availability: CommandAvailability) {
+        self.command_availability = availability;
+        if matches!(self.active_popup, ActivePopup::Command(_)) {
+            let mut popup = CommandPopup::new(self.custom_prompts.clone(), availability);
+            let first_line = self.textarea.text().lines().next().unwrap_or("");
+            popup.on_composer_text_change(first_line.to_string());
+            self.active_popup = ActivePopup::Command(popup);
+        }
+    }
+
     pub(crate) fn set_custom_prompts(&mut self, prompts: Vec<CustomPrompt>) {
         self.custom_prompts = prompts.clone();
         if let ActivePopup::Command(popup) = &mut self.active_popup {
@@ -1527,6 +1601,22 @@ impl ChatComposer {
         }
     }
 
+    pub(crate) fn set_context_window_estimated(&mut self, estimated: bool) {
+        self.context_window_estimated = estimated;
+    }
+
+    pub(crate) fn cloud_task_updates(&self) -> usize {
+        self.cloud_task_updates
+    }
+
+    pub(crate) fn set_cloud_task_updates(&mut self, count: usize) {
+        self.cloud_task_updates = count;
+    }
+
+    pub(crate) fn set_rate_limits(&mut self, headroom: Option<RateLimitHeadroom>) {
+        self.rate_limits = headroom;
+    }
+
     pub(crate) fn set_esc_backtrack_hint(&mut self, show: bool) {
         self.esc_backtrack_hint = show;
         if show {
@@ -1624,8 +1714,9 @@ impl Renderable for ChatComposer {
         let mut state = self.textarea_state.borrow_mut();
         StatefulWidgetRef::render_ref(&(&self.textarea), textarea_rect, buf, &mut state);
         if self.textarea.text().is_empty() {
-            let placeholder = Span::from(self.placeholder_text.as_str()).dim();
-            Line::from(vec![placeholder]).render_ref(textarea_rect.inner(Margin::new(0, 0)), buf);
+            let placeholder = Line::from(self.placeholder_text.clone().dim());
+            truncate_line_with_ellipsis(placeholder, usize::from(textarea_rect.width))
+                .render_ref(textarea_rect.inner(Margin::new(0, 0)), buf);
         }
     }
 }
@@ -1635,7 +1726,7 @@ fn prompt_selection_action(
     first_line: &str,
     mode: PromptSelectionMode,
 ) -> PromptSelectionAction {
-    let named_args = prompt_argument_names(&prompt.content);
+    let named_args = prompt_named_args(prompt);
     let has_numeric = prompt_has_numeric_placeholders(&prompt.content);
 
     match mode {
@@ -2405,7 +2496,9 @@ mod tests {
             InputResult::Submitted(text) => {
                 panic!("expected command dispatch, but composer submitted literal text: {text}")
             }
-            InputResult::None => panic!("expected Command result for '/init'"),
+            InputResult::None | InputResult::CommandWithArgs(..) => {
+                panic!("expected Command result for '/init'")
+            }
         }
         assert!(composer.textarea.is_empty(), "composer should be cleared");
     }
@@ -2442,7 +2535,7 @@ mod tests {
             false,
         );
 
-        type_chars_humanlike(&mut composer, &['/', 'c']);
+        type_chars_humanlike(&mut composer, &['/', 'c', 'o']);
 
         let (_result, _needs_redraw) =
             composer.handle_key_event(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
@@ -2478,11 +2571,45 @@ mod tests {
             InputResult::Submitted(text) => {
                 panic!("expected command dispatch after Tab completion, got literal submit: {text}")
             }
-            InputResult::None => panic!("expected Command result for '/diff'"),
+            InputResult::None | InputResult::CommandWithArgs(..) => {
+                panic!("expected Command result for '/diff'")
+            }
         }
         assert!(composer.textarea.is_empty());
     }
 
+    #[test]
+    fn slash_command_with_arguments_dispatches_them() {
+        use crossterm::event::KeyCode;
+        use crossterm::event::KeyEvent;
+        use crossterm::event::KeyModifiers;
+
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+
+        type_chars_humanlike(
+            &mut composer,
+            &[
+                '/', 'c', 'o', 'n', 't', 'e', 'x', 't', ' ', 's', 'c', 'o', 'p', 'e', 's',
+            ],
+        );
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert_eq!(
+            result,
+            InputResult::CommandWithArgs(SlashCommand::Context, "scopes".to_string())
+        );
+        assert!(composer.textarea.is_empty());
+    }
+
     #[test]
     fn slash_mention_dispatches_command_and_inserts_at() {
         use crossterm::event::KeyCode;
@@ -2511,7 +2638,9 @@ mod tests {
             InputResult::Submitted(text) => {
                 panic!("expected command dispatch, but composer submitted literal text: {text}")
             }
-            InputResult::None => panic!("expected Command result for '/mention'"),
+            InputResult::None | InputResult::CommandWithArgs(..) => {
+                panic!("expected Command result for '/mention'")
+            }
         }
         assert!(composer.textarea.is_empty(), "composer should be cleared");
         composer.insert_str("@");
@@ -2745,6 +2874,32 @@ mod tests {
         assert_eq!(vec![path], imgs);
     }
 
+    #[test]
+    fn pasted_image_chip_can_be_deleted_before_send() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+        composer.attach_pasted_image(
+            PathBuf::from("/tmp/codex-clipboard-1.png"),
+            "[image: 1.2 MB png]".to_string(),
+        );
+        assert_eq!(composer.textarea.text(), "[image: 1.2 MB png]");
+
+        composer.handle_key_event(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+        assert_eq!(composer.textarea.text(), "");
+        assert!(composer.attached_images.is_empty());
+        assert_eq!(
+            composer.take_recent_submission_images(),
+            Vec::<PathBuf>::new()
+        );
+    }
+
     #[test]
     fn attach_image_without_text_submits_empty_text_and_images() {
         let (tx, _rx) = unbounded_channel::<AppEvent>();
@@ -2945,6 +3100,7 @@ mod tests {
             content: prompt_text.to_string(),
             description: None,
             argument_hint: None,
+            ..Default::default()
         }]);
 
         type_chars_humanlike(
@@ -2980,6 +3136,7 @@ mod tests {
             content: "Review $USER changes on $BRANCH".to_string(),
             description: None,
             argument_hint: None,
+            ..Default::default()
         }]);
 
         composer
@@ -3014,6 +3171,7 @@ mod tests {
             content: "Pair $USER with $BRANCH".to_string(),
             description: None,
             argument_hint: None,
+            ..Default::default()
         }]);
 
         composer
@@ -3118,6 +3276,7 @@ mod tests {
             content: "Review $USER changes".to_string(),
             description: None,
             argument_hint: None,
+            ..Default::default()
         }]);
 
         composer
@@ -3168,6 +3327,7 @@ mod tests {
             content: "Review $USER changes on $BRANCH".to_string(),
             description: None,
             argument_hint: None,
+            ..Default::default()
         }]);
 
         // Provide only one of the required args
@@ -3221,6 +3381,7 @@ mod tests {
             content: prompt_text.to_string(),
             description: None,
             argument_hint: None,
+            ..Default::default()
         }]);
 
         // Type the slash command with two args and hit Enter to submit.
@@ -3258,6 +3419,7 @@ mod tests {
             content: "Echo: $ARGUMENTS".to_string(),
             description: None,
             argument_hint: None,
+            ..Default::default()
         }]);
 
         // Type positional args; should submit with numeric expansion, no errors.
@@ -3289,6 +3451,7 @@ mod tests {
             content: prompt_text.to_string(),
             description: None,
             argument_hint: None,
+            ..Default::default()
         }]);
 
         type_chars_humanlike(
@@ -3325,6 +3488,7 @@ mod tests {
             content: prompt_text.to_string(),
             description: None,
             argument_hint: None,
+            ..Default::default()
         }]);
 
         type_chars_humanlike(
@@ -3362,6 +3526,7 @@ mod tests {
             content: prompt_text.to_string(),
             description: None,
             argument_hint: None,
+            ..Default::default()
         }]);
 
         type_chars_humanlike(
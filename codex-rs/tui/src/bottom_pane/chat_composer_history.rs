@@ -70,6 +70,11 @@ impl ChatComposerHistory {
         self.local_history.push(text.to_string());
     }
 
+    /// Messages submitted during this UI session, oldest first.
+    pub fn local_entries(&self) -> &[String] {
+        &self.local_history
+    }
+
     /// Reset navigation tracking so the next Up key resumes from the latest entry.
     pub fn reset_navigation(&mut self) {
         self.history_cursor = None;
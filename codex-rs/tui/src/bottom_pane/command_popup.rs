@@ -11,6 +11,7 @@ use crate::render::RectExt;
 use crate::slash_command::SlashCommand;
 use crate::slash_command::built_in_slash_commands;
 use codex_common::fuzzy_match::fuzzy_match;
+use codex_core::slash_commands::CommandAvailability;
 use codex_protocol::custom_prompts::CustomPrompt;
 use codex_protocol::custom_prompts::PROMPTS_CMD_PREFIX;
 use std::collections::HashSet;
@@ -31,8 +32,8 @@ pub(crate) struct CommandPopup {
 }
 
 impl CommandPopup {
-    pub(crate) fn new(mut prompts: Vec<CustomPrompt>) -> Self {
-        let builtins = built_in_slash_commands();
+    pub(crate) fn new(mut prompts: Vec<CustomPrompt>, availability: CommandAvailability) -> Self {
+        let builtins = built_in_slash_commands(availability);
         // Exclude prompts that collide with builtin command names and sort by name.
         let exclude: HashSet<String> = builtins.iter().map(|(n, _)| (*n).to_string()).collect();
         prompts.retain(|p| !exclude.contains(&p.name));
@@ -121,6 +122,14 @@ impl CommandPopup {
         for (_, cmd) in self.builtins.iter() {
             if let Some((indices, score)) = fuzzy_match(cmd.command(), filter) {
                 out.push((CommandItem::Builtin(*cmd), Some(indices), score));
+            } else if let Some(score) = cmd
+                .aliases()
+                .iter()
+                .filter_map(|alias| fuzzy_match(alias, filter).map(|(_, score)| score))
+                .min()
+            {
+                // Matched through an alias; nothing in the shown name to highlight.
+                out.push((CommandItem::Builtin(*cmd), None, score));
             }
         }
         // Support both search styles:
@@ -170,6 +179,10 @@ impl CommandPopup {
                             .description
                             .clone()
                             .unwrap_or_else(|| "send saved prompt".to_string());
+                        let description = match &prompt.argument_hint {
+                            Some(hint) => format!("{description} {hint}"),
+                            None => description,
+                        };
                         (
                             format!("/{PROMPTS_CMD_PREFIX}:{}", prompt.name),
                             description,
@@ -232,7 +245,7 @@ mod tests {
 
     #[test]
     fn filter_includes_init_when_typing_prefix() {
-        let mut popup = CommandPopup::new(Vec::new());
+        let mut popup = CommandPopup::new(Vec::new(), CommandAvailability::all());
         // Simulate the composer line starting with '/in' so the popup filters
         // matching commands by prefix.
         popup.on_composer_text_change("/in".to_string());
@@ -252,7 +265,7 @@ mod tests {
 
     #[test]
     fn selecting_init_by_exact_match() {
-        let mut popup = CommandPopup::new(Vec::new());
+        let mut popup = CommandPopup::new(Vec::new(), CommandAvailability::all());
         popup.on_composer_text_change("/init".to_string());
 
         // When an exact match exists, the selected command should be that
@@ -267,7 +280,7 @@ mod tests {
 
     #[test]
     fn model_is_first_suggestion_for_mo() {
-        let mut popup = CommandPopup::new(Vec::new());
+        let mut popup = CommandPopup::new(Vec::new(), CommandAvailability::all());
         popup.on_composer_text_change("/mo".to_string());
         let matches = popup.filtered_items();
         match matches.first() {
@@ -288,6 +301,7 @@ mod tests {
                 content: "hello from foo".to_string(),
                 description: None,
                 argument_hint: None,
+                ..Default::default()
             },
             CustomPrompt {
                 name: "bar".to_string(),
@@ -295,9 +309,10 @@ mod tests {
                 content: "hello from bar".to_string(),
                 description: None,
                 argument_hint: None,
+                ..Default::default()
             },
         ];
-        let popup = CommandPopup::new(prompts);
+        let popup = CommandPopup::new(prompts, CommandAvailability::all());
         let items = popup.filtered_items();
         let mut prompt_names: Vec<String> = items
             .into_iter()
@@ -313,13 +328,17 @@ mod tests {
     #[test]
     fn prompt_name_collision_with_builtin_is_ignored() {
         // Create a prompt named like a builtin (e.g. "init").
-        let popup = CommandPopup::new(vec![CustomPrompt {
-            name: "init".to_string(),
-            path: "/tmp/init.md".to_string().into(),
-            content: "should be ignored".to_string(),
-            description: None,
-            argument_hint: None,
-        }]);
+        let popup = CommandPopup::new(
+            vec![CustomPrompt {
+                name: "init".to_string(),
+                path: "/tmp/init.md".to_string().into(),
+                content: "should be ignored".to_string(),
+                description: None,
+                argument_hint: None,
+                ..Default::default()
+            }],
+            CommandAvailability::all(),
+        );
         let items = popup.filtered_items();
         let has_collision_prompt = items.into_iter().any(|it| match it {
             CommandItem::UserPrompt(i) => popup.prompt(i).is_some_and(|p| p.name == "init"),
@@ -333,13 +352,17 @@ mod tests {
 
     #[test]
     fn prompt_description_uses_frontmatter_metadata() {
-        let popup = CommandPopup::new(vec![CustomPrompt {
-            name: "draftpr".to_string(),
-            path: "/tmp/draftpr.md".to_string().into(),
-            content: "body".to_string(),
-            description: Some("Create feature branch, commit and open draft PR.".to_string()),
-            argument_hint: None,
-        }]);
+        let popup = CommandPopup::new(
+            vec![CustomPrompt {
+                name: "draftpr".to_string(),
+                path: "/tmp/draftpr.md".to_string().into(),
+                content: "body".to_string(),
+                description: Some("Create feature branch, commit and open draft PR.".to_string()),
+                argument_hint: None,
+                ..Default::default()
+            }],
+            CommandAvailability::all(),
+        );
         let rows = popup.rows_from_matches(vec![(CommandItem::UserPrompt(0), None, 0)]);
         let description = rows.first().and_then(|row| row.description.as_deref());
         assert_eq!(
@@ -350,15 +373,53 @@ mod tests {
 
     #[test]
     fn prompt_description_falls_back_when_missing() {
-        let popup = CommandPopup::new(vec![CustomPrompt {
-            name: "foo".to_string(),
-            path: "/tmp/foo.md".to_string().into(),
-            content: "body".to_string(),
-            description: None,
-            argument_hint: None,
-        }]);
+        let popup = CommandPopup::new(
+            vec![CustomPrompt {
+                name: "foo".to_string(),
+                path: "/tmp/foo.md".to_string().into(),
+                content: "body".to_string(),
+                description: None,
+                argument_hint: None,
+                ..Default::default()
+            }],
+            CommandAvailability::all(),
+        );
         let rows = popup.rows_from_matches(vec![(CommandItem::UserPrompt(0), None, 0)]);
         let description = rows.first().and_then(|row| row.description.as_deref());
         assert_eq!(description, Some("send saved prompt"));
     }
+
+    #[test]
+    fn unfiltered_popup_lists_the_core_registry() {
+        let popup = CommandPopup::new(Vec::new(), CommandAvailability::all());
+        let expected: Vec<CommandItem> =
+            codex_core::slash_commands::builtin_commands(CommandAvailability::all())
+                .into_iter()
+                .map(CommandItem::Builtin)
+                .collect();
+        assert_eq!(popup.filtered_items(), expected);
+    }
+
+    #[test]
+    fn unavailable_commands_are_left_out() {
+        let availability = CommandAvailability {
+            in_git_repo: false,
+            ghost_commits: false,
+        };
+        let mut popup = CommandPopup::new(Vec::new(), availability);
+        popup.on_composer_text_change("/undo".to_string());
+        assert_eq!(popup.filtered_items(), Vec::new());
+        popup.on_composer_text_change("/diff".to_string());
+        assert_eq!(popup.filtered_items(), Vec::new());
+    }
+
+    #[test]
+    fn alias_selects_its_command() {
+        let mut popup = CommandPopup::new(Vec::new(), CommandAvailability::all());
+        popup.on_composer_text_change("/exit".to_string());
+        assert_eq!(
+            popup.selected_item(),
+            Some(CommandItem::Builtin(SlashCommand::Quit))
+        );
+    }
 }
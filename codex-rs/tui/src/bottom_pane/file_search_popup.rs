@@ -22,6 +22,8 @@ pub(crate) struct FileSearchPopup {
     waiting: bool,
     /// Cached matches; paths relative to the search dir.
     matches: Vec<FileMatch>,
+    /// The search behind `matches` stopped at `file_search.max_file_count`.
+    truncated_walk: bool,
     /// Shared selection/scroll state.
     state: ScrollState,
 }
@@ -33,6 +35,7 @@ impl FileSearchPopup {
             pending_query: String::new(),
             waiting: true,
             matches: Vec::new(),
+            truncated_walk: false,
             state: ScrollState::new(),
         }
     }
@@ -70,13 +73,19 @@ impl FileSearchPopup {
 
     /// Replace matches when a `FileSearchResult` arrives.
     /// Replace matches. Only applied when `query` matches `pending_query`.
-    pub(crate) fn set_matches(&mut self, query: &str, matches: Vec<FileMatch>) {
+    pub(crate) fn set_matches(
+        &mut self,
+        query: &str,
+        matches: Vec<FileMatch>,
+        truncated_walk: bool,
+    ) {
         if query != self.pending_query {
             return; // stale
         }
 
         self.display_query = query.to_string();
         self.matches = matches;
+        self.truncated_walk = truncated_walk;
         self.waiting = false;
         let len = self.matches.len();
         self.state.clamp_selection(len);
@@ -131,13 +140,15 @@ impl WidgetRef for &FileSearchPopup {
                         .map(|v| v.iter().map(|&i| i as usize).collect()),
                     is_current: false,
                     display_shortcut: None,
-                    description: None,
+                    description: m.matched_pattern.clone(),
                 })
                 .collect()
         };
 
         let empty_message = if self.waiting {
             "loading..."
+        } else if self.truncated_walk {
+            "no matches (stopped at file_search.max_file_count)"
         } else {
             "no matches"
         };
@@ -1,6 +1,8 @@
 use crate::key_hint;
 use crate::key_hint::KeyBinding;
 use crate::render::line_utils::prefix_lines;
+use crate::status::HeadroomLevel;
+use crate::status::RateLimitHeadroom;
 use crate::ui_consts::FOOTER_INDENT_COLS;
 use crossterm::event::KeyCode;
 use ratatui::buffer::Buffer;
@@ -18,6 +20,12 @@ pub(crate) struct FooterProps {
     pub(crate) use_shift_enter_hint: bool,
     pub(crate) is_task_running: bool,
     pub(crate) context_window_percent: Option<i64>,
+    /// The context figure is based on locally estimated usage.
+    pub(crate) context_window_estimated: bool,
+    /// Cloud task status changes not yet seen in `/cloud`.
+    pub(crate) cloud_task_updates: usize,
+    /// `None` when the provider reports no rate limits.
+    pub(crate) rate_limits: Option<RateLimitHeadroom>,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -59,19 +67,20 @@ pub(crate) fn reset_mode_after_activity(current: FooterMode) -> FooterMode {
 }
 
 pub(crate) fn footer_height(props: FooterProps) -> u16 {
-    footer_lines(props).len() as u16
+    footer_lines(props, usize::MAX).len() as u16
 }
 
 pub(crate) fn render_footer(area: Rect, buf: &mut Buffer, props: FooterProps) {
+    let width = usize::from(area.width).saturating_sub(FOOTER_INDENT_COLS);
     Paragraph::new(prefix_lines(
-        footer_lines(props),
+        footer_lines(props, width),
         " ".repeat(FOOTER_INDENT_COLS).into(),
         " ".repeat(FOOTER_INDENT_COLS).into(),
     ))
     .render(area, buf);
 }
 
-fn footer_lines(props: FooterProps) -> Vec<Line<'static>> {
+fn footer_lines(props: FooterProps, width: usize) -> Vec<Line<'static>> {
     // Show the context indicator on the left, appended after the primary hint
     // (e.g., "? for shortcuts"). Keep it visible even when typing (i.e., when
     // the shortcut hint is hidden). Hide it only for the multi-line
@@ -81,12 +90,24 @@ fn footer_lines(props: FooterProps) -> Vec<Line<'static>> {
             is_task_running: props.is_task_running,
         })],
         FooterMode::ShortcutSummary => {
-            let mut line = context_window_line(props.context_window_percent);
-            line.push_span(" · ".dim());
-            line.extend(vec![
+            let hint = Line::from(vec![
+                " · ".dim(),
                 key_hint::plain(KeyCode::Char('?')).into(),
                 " for shortcuts".dim(),
             ]);
+            // The rate limits win over the hint, and the hint is only shown
+            // when it fits whole.
+            let mut line = status_line(props);
+            if !push_rate_limits(
+                &mut line,
+                props.rate_limits,
+                width.saturating_sub(hint.width()),
+            ) {
+                line = status_with_rate_limits(props, width);
+            }
+            if line.width() + hint.width() <= width {
+                line.extend(hint.spans);
+            }
             vec![line]
         }
         FooterMode::ShortcutOverlay => shortcut_overlay_lines(ShortcutsState {
@@ -94,7 +115,7 @@ fn footer_lines(props: FooterProps) -> Vec<Line<'static>> {
             esc_backtrack_hint: props.esc_backtrack_hint,
         }),
         FooterMode::EscHint => vec![esc_hint_line(props.esc_backtrack_hint)],
-        FooterMode::ContextOnly => vec![context_window_line(props.context_window_percent)],
+        FooterMode::ContextOnly => vec![status_with_rate_limits(props, width)],
     }
 }
 
@@ -221,9 +242,108 @@ fn build_columns(entries: Vec<Line<'static>>) -> Vec<Line<'static>> {
         .collect()
 }
 
-fn context_window_line(percent: Option<i64>) -> Line<'static> {
+/// Context indicator followed by the cloud task badge, when there is one.
+fn status_line(props: FooterProps) -> Line<'static> {
+    let mut line =
+        context_window_line(props.context_window_percent, props.context_window_estimated);
+    if props.cloud_task_updates > 0 {
+        let noun = if props.cloud_task_updates == 1 {
+            "update"
+        } else {
+            "updates"
+        };
+        line.push_span(" · ".dim());
+        line.push_span(format!("{} cloud task {noun}", props.cloud_task_updates).cyan());
+    }
+    line
+}
+
+/// The status line followed by the rate-limit indicator. When the indicator
+/// does not fit after the context label, the label is dropped for it.
+fn status_with_rate_limits(props: FooterProps, width: usize) -> Line<'static> {
+    let mut line = status_line(props);
+    if push_rate_limits(&mut line, props.rate_limits, width) {
+        return line;
+    }
+    let mut limits_only = Line::default();
+    if push_rate_limits(&mut limits_only, props.rate_limits, width) {
+        return limits_only;
+    }
+    line
+}
+
+/// Append the longest form of the rate-limit indicator that keeps `line`
+/// within `width`, after a separator unless `line` is empty. Returns whether
+/// anything was appended.
+fn push_rate_limits(
+    line: &mut Line<'static>,
+    headroom: Option<RateLimitHeadroom>,
+    width: usize,
+) -> bool {
+    let Some(headroom) = headroom else {
+        return false;
+    };
+    let separator = (line.width() > 0).then(|| " · ".dim());
+    let separator_width = separator.as_ref().map_or(0, Span::width);
+    let available = width.saturating_sub(line.width() + separator_width);
+    let Some(spans) = rate_limit_forms(&headroom)
+        .into_iter()
+        .find(|spans| spans.iter().map(Span::width).sum::<usize>() <= available)
+    else {
+        return false;
+    };
+    if let Some(separator) = separator {
+        line.push_span(separator);
+    }
+    line.extend(spans);
+    true
+}
+
+/// The indicator from longest to shortest: `limits 5h 62% · weekly 88%
+/// left`, `5h 62% · weekly 88%`, the tightest window alone, and its bare
+/// percentage.
+fn rate_limit_forms(headroom: &RateLimitHeadroom) -> Vec<Vec<Span<'static>>> {
+    let labeled: Vec<Vec<Span<'static>>> = headroom
+        .windows()
+        .map(|window| {
+            vec![
+                format!("{} ", window.label()).dim(),
+                headroom_percent(window.percent_left, window.level),
+            ]
+        })
+        .collect();
+    let joined = labeled.join(&" · ".dim());
+    let mut full = vec!["limits ".dim()];
+    full.extend(joined.iter().cloned());
+    full.push(" left".dim());
+
+    let mut forms = vec![full, joined];
+    if let Some(tightest) = headroom.tightest() {
+        let percent = headroom_percent(tightest.percent_left, tightest.level);
+        forms.push(vec![
+            format!("{} ", tightest.label()).dim(),
+            percent.clone(),
+        ]);
+        forms.push(vec![percent]);
+    }
+    forms
+}
+
+fn headroom_percent(percent_left: i64, level: HeadroomLevel) -> Span<'static> {
+    let text = format!("{percent_left}%");
+    match level {
+        HeadroomLevel::Ok => text.green(),
+        HeadroomLevel::Warn => text.cyan(),
+        HeadroomLevel::Critical => text.red().bold(),
+    }
+}
+
+fn context_window_line(percent: Option<i64>, estimated: bool) -> Line<'static> {
     let percent = percent.unwrap_or(100).clamp(0, 100);
-    Line::from(vec![Span::from(format!("{percent}% context left")).dim()])
+    let approx = if estimated { "~" } else { "" };
+    Line::from(vec![
+        Span::from(format!("{approx}{percent}% context left")).dim(),
+    ])
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -374,13 +494,20 @@ const SHORTCUTS: &[ShortcutDescriptor] = &[
 #[cfg(test)]
 mod tests {
     use super::*;
+    use codex_core::config::types::RateLimitWarnings;
+    use codex_core::protocol::RateLimitSnapshot;
+    use codex_core::protocol::RateLimitWindow;
     use insta::assert_snapshot;
     use ratatui::Terminal;
     use ratatui::backend::TestBackend;
 
     fn snapshot_footer(name: &str, props: FooterProps) {
+        snapshot_footer_width(name, props, 80);
+    }
+
+    fn snapshot_footer_width(name: &str, props: FooterProps, width: u16) {
         let height = footer_height(props).max(1);
-        let mut terminal = Terminal::new(TestBackend::new(80, height)).unwrap();
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
         terminal
             .draw(|f| {
                 let area = Rect::new(0, 0, f.area().width, height);
@@ -400,6 +527,9 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: false,
                 context_window_percent: None,
+                context_window_estimated: false,
+                cloud_task_updates: 0,
+                rate_limits: None,
             },
         );
 
@@ -411,6 +541,9 @@ mod tests {
                 use_shift_enter_hint: true,
                 is_task_running: false,
                 context_window_percent: None,
+                context_window_estimated: false,
+                cloud_task_updates: 0,
+                rate_limits: None,
             },
         );
 
@@ -422,6 +555,9 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: false,
                 context_window_percent: None,
+                context_window_estimated: false,
+                cloud_task_updates: 0,
+                rate_limits: None,
             },
         );
 
@@ -433,6 +569,9 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: true,
                 context_window_percent: None,
+                context_window_estimated: false,
+                cloud_task_updates: 0,
+                rate_limits: None,
             },
         );
 
@@ -444,6 +583,9 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: false,
                 context_window_percent: None,
+                context_window_estimated: false,
+                cloud_task_updates: 0,
+                rate_limits: None,
             },
         );
 
@@ -455,6 +597,9 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: false,
                 context_window_percent: None,
+                context_window_estimated: false,
+                cloud_task_updates: 0,
+                rate_limits: None,
             },
         );
 
@@ -466,7 +611,66 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: true,
                 context_window_percent: Some(72),
+                context_window_estimated: false,
+                cloud_task_updates: 0,
+                rate_limits: None,
+            },
+        );
+
+        snapshot_footer(
+            "footer_shortcuts_context_estimated",
+            FooterProps {
+                mode: FooterMode::ShortcutSummary,
+                esc_backtrack_hint: false,
+                use_shift_enter_hint: false,
+                is_task_running: true,
+                context_window_percent: Some(72),
+                context_window_estimated: true,
+                cloud_task_updates: 0,
+                rate_limits: None,
             },
         );
+
+        snapshot_footer(
+            "footer_shortcuts_cloud_task_updates",
+            FooterProps {
+                mode: FooterMode::ShortcutSummary,
+                esc_backtrack_hint: false,
+                use_shift_enter_hint: false,
+                is_task_running: false,
+                context_window_percent: Some(72),
+                context_window_estimated: false,
+                cloud_task_updates: 3,
+                rate_limits: None,
+            },
+        );
+    }
+
+    #[test]
+    fn rate_limit_indicator_shortens_at_narrow_widths() {
+        let window = |used_percent, window_minutes| RateLimitWindow {
+            used_percent,
+            window_minutes: Some(window_minutes),
+            resets_at: None,
+        };
+        let snapshot = RateLimitSnapshot {
+            primary: Some(window(38.0, 300)),
+            secondary: Some(window(92.0, 10_080)),
+            credits: None,
+        };
+        let props = FooterProps {
+            mode: FooterMode::ShortcutSummary,
+            esc_backtrack_hint: false,
+            use_shift_enter_hint: false,
+            is_task_running: false,
+            context_window_percent: Some(72),
+            context_window_estimated: false,
+            cloud_task_updates: 0,
+            rate_limits: RateLimitHeadroom::from_snapshot(&snapshot, RateLimitWarnings::default()),
+        };
+
+        for width in [80, 40, 28, 20] {
+            snapshot_footer_width(&format!("footer_rate_limits_{width}_cols"), props, width);
+        }
     }
 }
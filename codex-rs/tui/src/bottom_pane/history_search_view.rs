@@ -0,0 +1,342 @@
+use codex_common::fuzzy_match::fuzzy_match;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Widget;
+use unicode_width::UnicodeWidthStr;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+use crate::key_hint;
+use crate::render::renderable::Renderable;
+
+use super::CancellationEvent;
+use super::bottom_pane_view::BottomPaneView;
+use super::popup_consts::MAX_POPUP_ROWS;
+use super::scroll_state::ScrollState;
+use super::selection_popup_common::GenericDisplayRow;
+use super::selection_popup_common::measure_rows_height;
+use super::selection_popup_common::render_rows;
+
+const PROMPT: &str = "reverse-search: ";
+
+/// Ctrl+R search over previously submitted prompts. Typing fuzzy-filters the
+/// list; Enter puts the selected prompt into the composer.
+pub(crate) struct HistorySearchView {
+    /// Distinct prompts, newest first.
+    prompts: Vec<String>,
+    query: String,
+    /// Indices into `prompts` with the matched character positions, best
+    /// match first.
+    matches: Vec<(usize, Vec<usize>)>,
+    state: ScrollState,
+    app_event_tx: AppEventSender,
+    complete: bool,
+}
+
+impl HistorySearchView {
+    /// `history` is oldest first. Repeated prompts keep only their newest
+    /// occurrence and `!` shell commands are left out.
+    pub(crate) fn new(history: Vec<String>, app_event_tx: AppEventSender) -> Self {
+        let mut prompts: Vec<String> = Vec::new();
+        for text in history.into_iter().rev() {
+            if text.trim().is_empty() || text.starts_with('!') || prompts.contains(&text) {
+                continue;
+            }
+            prompts.push(text);
+        }
+        let mut view = Self {
+            prompts,
+            query: String::new(),
+            matches: Vec::new(),
+            state: ScrollState::new(),
+            app_event_tx,
+            complete: false,
+        };
+        view.apply_filter();
+        view
+    }
+
+    fn apply_filter(&mut self) {
+        let mut matches: Vec<(usize, Vec<usize>, i32)> = self
+            .prompts
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, prompt)| {
+                fuzzy_match(prompt, &self.query).map(|(indices, score)| (idx, indices, score))
+            })
+            .collect();
+        // Stable, so equally good matches stay newest first.
+        matches.sort_by_key(|(_, _, score)| *score);
+        self.matches = matches
+            .into_iter()
+            .map(|(idx, indices, _)| (idx, indices))
+            .collect();
+        self.state.reset();
+        let len = self.matches.len();
+        self.state.clamp_selection(len);
+        self.state.ensure_visible(len, len.min(MAX_POPUP_ROWS));
+    }
+
+    fn move_up(&mut self) {
+        let len = self.matches.len();
+        self.state.move_up_wrap(len);
+        self.state.ensure_visible(len, len.min(MAX_POPUP_ROWS));
+    }
+
+    fn move_down(&mut self) {
+        let len = self.matches.len();
+        self.state.move_down_wrap(len);
+        self.state.ensure_visible(len, len.min(MAX_POPUP_ROWS));
+    }
+
+    fn selected_prompt(&self) -> Option<&str> {
+        let (idx, _) = self.matches.get(self.state.selected_idx?)?;
+        self.prompts.get(*idx).map(String::as_str)
+    }
+
+    fn accept(&mut self) {
+        if let Some(prompt) = self.selected_prompt() {
+            self.app_event_tx
+                .send(AppEvent::RecallPrompt(prompt.to_string()));
+        }
+        self.complete = true;
+    }
+
+    fn rows(&self) -> Vec<GenericDisplayRow> {
+        self.matches
+            .iter()
+            .map(|(idx, indices)| GenericDisplayRow {
+                name: single_line(&self.prompts[*idx]),
+                display_shortcut: None,
+                match_indices: (!indices.is_empty()).then(|| indices.clone()),
+                is_current: false,
+                description: None,
+            })
+            .collect()
+    }
+
+    fn hint_line() -> Line<'static> {
+        Line::from(vec![
+            key_hint::plain(KeyCode::Enter).into(),
+            " to insert · ".into(),
+            key_hint::plain(KeyCode::Esc).into(),
+            " to cancel".into(),
+        ])
+        .dim()
+    }
+}
+
+/// Show a prompt on one row without shifting the character indices used for
+/// match highlighting.
+fn single_line(prompt: &str) -> String {
+    prompt
+        .chars()
+        .map(|c| match c {
+            '\n' => '⏎',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect()
+}
+
+impl BottomPaneView for HistorySearchView {
+    fn handle_key_event(&mut self, key_event: KeyEvent) {
+        match key_event {
+            KeyEvent {
+                code: KeyCode::Esc, ..
+            } => {
+                self.complete = true;
+            }
+            KeyEvent {
+                code: KeyCode::Enter,
+                ..
+            } => self.accept(),
+            KeyEvent {
+                code: KeyCode::Up, ..
+            }
+            | KeyEvent {
+                code: KeyCode::Char('p'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => self.move_up(),
+            KeyEvent {
+                code: KeyCode::Down,
+                ..
+            }
+            | KeyEvent {
+                code: KeyCode::Char('n' | 'r'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => self.move_down(),
+            KeyEvent {
+                code: KeyCode::Backspace,
+                ..
+            } => {
+                if self.query.pop().is_some() {
+                    self.apply_filter();
+                }
+            }
+            KeyEvent {
+                code: KeyCode::Char(c),
+                modifiers,
+                ..
+            } if !key_hint::has_ctrl_or_alt(modifiers) => {
+                self.query.push(c);
+                self.apply_filter();
+            }
+            _ => {}
+        }
+    }
+
+    fn on_ctrl_c(&mut self) -> CancellationEvent {
+        self.complete = true;
+        CancellationEvent::Handled
+    }
+
+    fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    fn handle_paste(&mut self, pasted: String) -> bool {
+        let pasted = pasted.replace(['\r', '\n'], " ");
+        if pasted.is_empty() {
+            return false;
+        }
+        self.query.push_str(&pasted);
+        self.apply_filter();
+        true
+    }
+}
+
+impl Renderable for HistorySearchView {
+    fn desired_height(&self, width: u16) -> u16 {
+        let rows = self.rows();
+        // Query line, matches, blank line, key hints.
+        1 + measure_rows_height(&rows, &self.state, MAX_POPUP_ROWS, width) + 2
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        if area.height == 0 || area.width == 0 {
+            return;
+        }
+        let query_line = if self.query.is_empty() {
+            Line::from(vec![
+                PROMPT.cyan(),
+                "type to search your prompts".dim().italic(),
+            ])
+        } else {
+            Line::from(vec![PROMPT.cyan(), Span::from(self.query.clone())])
+        };
+        query_line.render(Rect::new(area.x, area.y, area.width, 1), buf);
+
+        let list_height = area.height.saturating_sub(3);
+        if list_height > 0 {
+            let empty_message = if self.prompts.is_empty() {
+                "no prompts in history yet"
+            } else {
+                "no matches"
+            };
+            render_rows(
+                Rect::new(area.x, area.y + 1, area.width, list_height),
+                buf,
+                &self.rows(),
+                &self.state,
+                MAX_POPUP_ROWS,
+                empty_message,
+            );
+        }
+
+        if area.height >= 2 {
+            Self::hint_line().render(Rect::new(area.x, area.bottom() - 1, area.width, 1), buf);
+        }
+    }
+
+    fn cursor_pos(&self, area: Rect) -> Option<(u16, u16)> {
+        let prompt_width = PROMPT.width() + self.query.width();
+        let x = area.x.saturating_add(u16::try_from(prompt_width).ok()?);
+        (area.height > 0 && x < area.right()).then_some((x, area.y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+    use pretty_assertions::assert_eq;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn view(history: &[&str]) -> HistorySearchView {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        HistorySearchView::new(
+            history.iter().map(ToString::to_string).collect(),
+            AppEventSender::new(tx),
+        )
+    }
+
+    fn type_query(view: &mut HistorySearchView, query: &str) {
+        for c in query.chars() {
+            view.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+    }
+
+    fn listed(view: &HistorySearchView) -> Vec<&str> {
+        view.matches
+            .iter()
+            .map(|(idx, _)| view.prompts[*idx].as_str())
+            .collect()
+    }
+
+    #[test]
+    fn lists_distinct_prompts_newest_first_without_shell_commands() {
+        let view = view(&["fix the build", "!git status", "add tests", "fix the build"]);
+
+        assert_eq!(listed(&view), vec!["fix the build", "add tests"]);
+    }
+
+    #[test]
+    fn fuzzy_query_ranks_tighter_matches_first() {
+        let mut view = view(&["refactor the tokenizer", "fix tests", "format code"]);
+
+        type_query(&mut view, "ft");
+
+        assert_eq!(
+            listed(&view),
+            vec!["fix tests", "format code", "refactor the tokenizer"]
+        );
+        assert_eq!(view.selected_prompt(), Some("fix tests"));
+    }
+
+    #[test]
+    fn history_search_view_render() {
+        let mut view = view(&[
+            "explain the\nretry loop",
+            "!cargo test",
+            "write release notes",
+            "explain codex-rs/core",
+        ]);
+        type_query(&mut view, "expl");
+
+        let width = 40;
+        let area = Rect::new(0, 0, width, view.desired_height(width));
+        let mut buf = Buffer::empty(area);
+        view.render(area, &mut buf);
+
+        let rendered = (area.top()..area.bottom())
+            .map(|y| {
+                (area.left()..area.right())
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_snapshot!(rendered);
+    }
+}
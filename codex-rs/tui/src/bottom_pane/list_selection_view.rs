@@ -17,6 +17,7 @@ use crate::app_event_sender::AppEventSender;
 use crate::key_hint::KeyBinding;
 use crate::render::Insets;
 use crate::render::RectExt as _;
+use crate::render::line_utils::truncate_line_with_ellipsis;
 use crate::render::renderable::ColumnRenderable;
 use crate::render::renderable::Renderable;
 use crate::style::user_message_style;
@@ -247,6 +248,11 @@ impl ListSelectionView {
         self.apply_filter();
     }
 
+    /// Replace the header without touching the items or the selection.
+    pub(crate) fn set_header(&mut self, header: Box<dyn Renderable>) {
+        self.header = header;
+    }
+
     pub(crate) fn take_last_selected_index(&mut self) -> Option<usize> {
         self.last_selected_actual_idx.take()
     }
@@ -435,7 +441,9 @@ impl Renderable for ListSelectionView {
                 width: footer_area.width.saturating_sub(2),
                 height: footer_area.height,
             };
-            hint.clone().dim().render(hint_area, buf);
+            truncate_line_with_ellipsis(hint.clone(), usize::from(hint_area.width))
+                .dim()
+                .render(hint_area, buf);
         }
     }
 }
@@ -2,13 +2,17 @@
 use std::path::PathBuf;
 
 use crate::app_event_sender::AppEventSender;
+use crate::bottom_pane::pinned_plan::PinnedPlan;
 use crate::bottom_pane::queued_user_messages::QueuedUserMessages;
 use crate::render::renderable::FlexRenderable;
 use crate::render::renderable::Renderable;
 use crate::render::renderable::RenderableItem;
+use crate::status::RateLimitHeadroom;
 use crate::tui::FrameRequester;
 use bottom_pane_view::BottomPaneView;
+use codex_core::patch_selection::PatchSelection;
 use codex_file_search::FileMatch;
+use codex_protocol::plan_tool::PlanItemArg;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use ratatui::buffer::Buffer;
@@ -18,6 +22,7 @@ use std::time::Duration;
 mod approval_overlay;
 pub(crate) use approval_overlay::ApprovalOverlay;
 pub(crate) use approval_overlay::ApprovalRequest;
+pub(crate) use approval_overlay::CommandExplanation;
 mod bottom_pane_view;
 mod chat_composer;
 mod chat_composer_history;
@@ -25,6 +30,8 @@ mod command_popup;
 pub mod custom_prompt_view;
 mod file_search_popup;
 mod footer;
+mod history_search_view;
+pub(crate) use history_search_view::HistorySearchView;
 mod list_selection_view;
 mod prompt_args;
 pub(crate) use list_selection_view::SelectionViewParams;
@@ -32,6 +39,7 @@ mod feedback_view;
 pub(crate) use feedback_view::feedback_selection_params;
 pub(crate) use feedback_view::feedback_upload_consent_params;
 mod paste_burst;
+mod pinned_plan;
 pub mod popup_consts;
 mod queued_user_messages;
 mod scroll_state;
@@ -47,6 +55,7 @@ pub(crate) enum CancellationEvent {
 
 pub(crate) use chat_composer::ChatComposer;
 pub(crate) use chat_composer::InputResult;
+use codex_core::slash_commands::CommandAvailability;
 use codex_protocol::custom_prompts::CustomPrompt;
 
 use crate::status_indicator_widget::StatusIndicatorWidget;
@@ -74,6 +83,8 @@ pub(crate) struct BottomPane {
     status: Option<StatusIndicatorWidget>,
     /// Queued user messages to show above the composer while a turn is running.
     queued_user_messages: QueuedUserMessages,
+    /// Latest plan, shown above the composer while pinned with `/plan`.
+    pinned_plan: PinnedPlan,
     context_window_percent: Option<i64>,
 }
 
@@ -105,6 +116,7 @@ impl BottomPane {
             ctrl_c_quit_hint: false,
             status: None,
             queued_user_messages: QueuedUserMessages::new(),
+            pinned_plan: PinnedPlan::new(),
             esc_backtrack_hint: false,
             context_window_percent: None,
         }
@@ -341,6 +353,37 @@ impl BottomPane {
         self.request_redraw();
     }
 
+    /// Mark the context indicator as based on estimated (not provider
+    /// reported) usage.
+    pub(crate) fn set_context_window_estimated(&mut self, estimated: bool) {
+        self.composer.set_context_window_estimated(estimated);
+        self.request_redraw();
+    }
+
+    /// Count `count` more cloud task status changes in the footer badge.
+    pub(crate) fn add_cloud_task_updates(&mut self, count: usize) {
+        let total = self.composer.cloud_task_updates().saturating_add(count);
+        self.composer.set_cloud_task_updates(total);
+        self.request_redraw();
+    }
+
+    /// Show the rate-limit headroom in the footer; `None` hides it.
+    pub(crate) fn set_rate_limits(&mut self, headroom: Option<RateLimitHeadroom>) {
+        self.composer.set_rate_limits(headroom);
+        self.request_redraw();
+    }
+
+    /// Reset the cloud task badge once the user has seen the updates.
+    pub(crate) fn clear_cloud_task_updates(&mut self) {
+        self.composer.set_cloud_task_updates(0);
+        self.request_redraw();
+    }
+
+    #[cfg(test)]
+    pub(crate) fn cloud_task_updates(&self) -> usize {
+        self.composer.cloud_task_updates()
+    }
+
     /// Show a generic list selection view with the provided items.
     pub(crate) fn show_selection_view(&mut self, params: list_selection_view::SelectionViewParams) {
         let view = list_selection_view::ListSelectionView::new(params, self.app_event_tx.clone());
@@ -353,6 +396,26 @@ impl BottomPane {
         self.request_redraw();
     }
 
+    /// Replace the steps shown by the pinned plan.
+    pub(crate) fn set_plan(&mut self, steps: Vec<PlanItemArg>) {
+        self.pinned_plan.set_steps(steps);
+        self.request_redraw();
+    }
+
+    /// Pin or unpin the plan above the composer; returns whether it is now
+    /// pinned.
+    pub(crate) fn toggle_plan_pinned(&mut self) -> bool {
+        let pinned = self.pinned_plan.toggle_pinned();
+        self.request_redraw();
+        pinned
+    }
+
+    /// Limit the slash popup to commands available in this session.
+    pub(crate) fn set_command_availability(&mut self, availability: CommandAvailability) {
+        self.composer.set_command_availability(availability);
+        self.request_redraw();
+    }
+
     /// Update custom prompts available for the slash popup.
     pub(crate) fn set_custom_prompts(&mut self, prompts: Vec<CustomPrompt>) {
         self.composer.set_custom_prompts(prompts);
@@ -367,6 +430,12 @@ impl BottomPane {
         self.is_task_running
     }
 
+    pub(crate) fn is_awaiting_approval(&self) -> bool {
+        self.view_stack
+            .iter()
+            .any(|view| view.is_awaiting_approval())
+    }
+
     /// Return true when the pane is in the regular composer state without any
     /// overlays or popups and not running a task. This is the safe context to
     /// use Esc-Esc for backtracking from the main view.
@@ -398,6 +467,32 @@ impl BottomPane {
         self.push_view(Box::new(modal));
     }
 
+    /// Fill in the model-generated summary of the exec approval for `call_id`.
+    pub(crate) fn set_command_explanation(
+        &mut self,
+        call_id: &str,
+        explanation: CommandExplanation,
+    ) {
+        if let Some(view) = self.view_stack.last_mut()
+            && view.try_set_command_explanation(call_id, &explanation)
+        {
+            self.request_redraw();
+        }
+    }
+
+    /// Answer the patch approval `id` with the hunks the user picked.
+    pub(crate) fn apply_patch_selection(&mut self, id: &str, selection: &PatchSelection) {
+        if let Some(view) = self.view_stack.last_mut()
+            && view.try_apply_patch_selection(id, selection)
+        {
+            if view.is_complete() {
+                self.view_stack.pop();
+                self.on_active_view_complete();
+            }
+            self.request_redraw();
+        }
+    }
+
     fn on_active_view_complete(&mut self) {
         self.resume_status_timer_after_modal();
     }
@@ -429,6 +524,16 @@ impl BottomPane {
         self.composer.set_history_metadata(log_id, entry_count);
     }
 
+    /// Messages submitted in the composer during this session, oldest first.
+    pub(crate) fn local_history(&self) -> &[String] {
+        self.composer.local_history()
+    }
+
+    /// True when keys reach the composer itself: no view or popup is open.
+    pub(crate) fn composer_owns_keys(&self) -> bool {
+        self.view_stack.is_empty() && !self.composer.popup_active()
+    }
+
     pub(crate) fn flush_paste_burst_if_due(&mut self) -> bool {
         self.composer.flush_paste_burst_if_due()
     }
@@ -452,8 +557,14 @@ impl BottomPane {
         }
     }
 
-    pub(crate) fn on_file_search_result(&mut self, query: String, matches: Vec<FileMatch>) {
-        self.composer.on_file_search_result(query, matches);
+    pub(crate) fn on_file_search_result(
+        &mut self,
+        query: String,
+        matches: Vec<FileMatch>,
+        truncated_walk: bool,
+    ) {
+        self.composer
+            .on_file_search_result(query, matches, truncated_walk);
         self.request_redraw();
     }
 
@@ -471,10 +582,26 @@ impl BottomPane {
         }
     }
 
+    pub(crate) fn attach_pasted_image(&mut self, path: PathBuf, chip: String) {
+        if self.view_stack.is_empty() {
+            self.composer.attach_pasted_image(path, chip);
+            self.request_redraw();
+        }
+    }
+
     pub(crate) fn take_recent_submission_images(&mut self) -> Vec<PathBuf> {
         self.composer.take_recent_submission_images()
     }
 
+    pub(crate) fn take_submitted_prompt(&mut self) -> Option<CustomPrompt> {
+        self.composer.take_submitted_prompt()
+    }
+
+    /// Space priority, highest first: an active view (e.g. an approval modal)
+    /// replaces everything else; then the composer and its footer, including
+    /// rate limits; then the status indicator. The pinned plan and queued
+    /// messages share whatever is left, and the plan collapses to one line
+    /// when it does not fit.
     fn as_renderable(&'_ self) -> RenderableItem<'_> {
         if let Some(view) = self.active_view() {
             RenderableItem::Borrowed(view)
@@ -483,8 +610,15 @@ impl BottomPane {
             if let Some(status) = &self.status {
                 flex.push(0, RenderableItem::Borrowed(status));
             }
-            flex.push(1, RenderableItem::Borrowed(&self.queued_user_messages));
-            if self.status.is_some() || !self.queued_user_messages.messages.is_empty() {
+            let has_plan = self.pinned_plan.is_visible();
+            let has_queued = !self.queued_user_messages.messages.is_empty();
+            if has_plan {
+                flex.push(1, RenderableItem::Borrowed(&self.pinned_plan));
+            }
+            if has_queued {
+                flex.push(1, RenderableItem::Borrowed(&self.queued_user_messages));
+            }
+            if self.status.is_some() || has_plan || has_queued {
                 flex.push(0, RenderableItem::Owned("".into()));
             }
             let mut flex2 = FlexRenderable::new();
@@ -511,6 +645,7 @@ impl Renderable for BottomPane {
 mod tests {
     use super::*;
     use crate::app_event::AppEvent;
+    use codex_protocol::plan_tool::StepStatus;
     use insta::assert_snapshot;
     use ratatui::buffer::Buffer;
     use ratatui::layout::Rect;
@@ -537,9 +672,11 @@ mod tests {
     fn exec_request() -> ApprovalRequest {
         ApprovalRequest::Exec {
             id: "1".to_string(),
+            call_id: "call-1".to_string(),
             command: vec!["echo".into(), "ok".into()],
             reason: None,
             risk: None,
+            explanation: None,
         }
     }
 
@@ -763,4 +900,85 @@ mod tests {
             render_snapshot(&pane, area)
         );
     }
+
+    fn plan_item(step: &str, status: StepStatus) -> PlanItemArg {
+        PlanItemArg {
+            step: step.to_string(),
+            status,
+        }
+    }
+
+    fn pane_with_pinned_plan() -> BottomPane {
+        let (tx_raw, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let mut pane = BottomPane::new(BottomPaneParams {
+            app_event_tx: tx,
+            frame_requester: FrameRequester::test_dummy(),
+            has_input_focus: true,
+            enhanced_keys_supported: false,
+            placeholder_text: "Ask Codex to do anything".to_string(),
+            disable_paste_burst: false,
+        });
+        assert!(pane.toggle_plan_pinned());
+        pane.set_plan(vec![
+            plan_item("Explore the bottom pane", StepStatus::Completed),
+            plan_item("Write the pinned plan widget", StepStatus::InProgress),
+            plan_item("Add snapshot tests", StepStatus::Pending),
+        ]);
+        pane
+    }
+
+    #[test]
+    fn pinned_plan_tall_snapshot() {
+        let pane = pane_with_pinned_plan();
+
+        let width = 48;
+        let height = pane.desired_height(width);
+        let area = Rect::new(0, 0, width, height);
+        assert_snapshot!("pinned_plan_tall", render_snapshot(&pane, area));
+    }
+
+    #[test]
+    fn pinned_plan_collapses_when_short_snapshot() {
+        let pane = pane_with_pinned_plan();
+
+        // Room for the composer, the spacer, and a single plan line.
+        let area = Rect::new(0, 0, 48, 6);
+        assert_snapshot!("pinned_plan_short", render_snapshot(&pane, area));
+    }
+
+    #[test]
+    fn new_plan_replaces_pinned_plan() {
+        let mut pane = pane_with_pinned_plan();
+        pane.set_plan(vec![
+            plan_item("Run the test suite", StepStatus::InProgress),
+            plan_item("Open a pull request", StepStatus::Pending),
+        ]);
+
+        let area = Rect::new(0, 0, 48, pane.desired_height(48));
+        let rendered = render_snapshot(&pane, area);
+        let top: Vec<&str> = rendered.lines().take(3).map(str::trim_end).collect();
+        assert_eq!(
+            top,
+            vec![
+                "Plan · 0/2 done",
+                "  □ Run the test suite",
+                "  □ Open a pull request",
+            ]
+        );
+        assert!(!rendered.contains("Explore the bottom pane"));
+    }
+
+    #[test]
+    fn pinned_plan_hidden_behind_approval_modal() {
+        let mut pane = pane_with_pinned_plan();
+        pane.push_approval_request(exec_request());
+
+        let area = Rect::new(0, 0, 60, pane.desired_height(60));
+        let rendered = render_snapshot(&pane, area);
+        assert!(
+            !rendered.contains("Write the pinned plan widget"),
+            "pinned plan should not render with a modal open:\n{rendered}"
+        );
+    }
 }
@@ -0,0 +1,168 @@
+use codex_protocol::plan_tool::PlanItemArg;
+use codex_protocol::plan_tool::StepStatus;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::Widget;
+
+use crate::render::renderable::Renderable;
+
+/// Latest plan from the `update_plan` tool, pinned above the composer with
+/// `/plan`. Shows one line per step when there is room, otherwise a single
+/// `Step 3/7: …` summary.
+pub(crate) struct PinnedPlan {
+    pinned: bool,
+    steps: Vec<PlanItemArg>,
+}
+
+impl PinnedPlan {
+    pub(crate) fn new() -> Self {
+        Self {
+            pinned: false,
+            steps: Vec::new(),
+        }
+    }
+
+    pub(crate) fn set_steps(&mut self, steps: Vec<PlanItemArg>) {
+        self.steps = steps;
+    }
+
+    /// Flip whether the plan is pinned; returns the new state.
+    pub(crate) fn toggle_pinned(&mut self) -> bool {
+        self.pinned = !self.pinned;
+        self.pinned
+    }
+
+    pub(crate) fn is_visible(&self) -> bool {
+        self.pinned && !self.steps.is_empty()
+    }
+
+    fn full_lines(&self) -> Vec<Line<'static>> {
+        let done = self
+            .steps
+            .iter()
+            .filter(|item| matches!(item.status, StepStatus::Completed))
+            .count();
+        let mut lines: Vec<Line<'static>> = vec![
+            vec![
+                "Plan".bold(),
+                format!(" · {done}/{} done", self.steps.len()).dim(),
+            ]
+            .into(),
+        ];
+        for PlanItemArg { step, status } in &self.steps {
+            let (glyph, style) = match status {
+                StepStatus::Completed => ("✔ ", Style::default().crossed_out().dim()),
+                StepStatus::InProgress => ("□ ", Style::default().cyan().bold()),
+                StepStatus::Pending => ("□ ", Style::default().dim()),
+            };
+            lines.push(vec!["  ".into(), glyph.into(), step.clone().set_style(style)].into());
+        }
+        lines
+    }
+
+    /// The step in progress, or else the first pending one.
+    fn summary_line(&self) -> Line<'static> {
+        let total = self.steps.len();
+        let current = self
+            .steps
+            .iter()
+            .position(|item| matches!(item.status, StepStatus::InProgress))
+            .or_else(|| {
+                self.steps
+                    .iter()
+                    .position(|item| matches!(item.status, StepStatus::Pending))
+            });
+        match current {
+            Some(index) => vec![
+                format!("Step {}/{total}: ", index + 1).bold(),
+                self.steps[index].step.clone().cyan(),
+            ]
+            .into(),
+            None => vec![
+                "Plan complete".bold(),
+                format!(" · {total}/{total} done").dim(),
+            ]
+            .into(),
+        }
+    }
+}
+
+impl Renderable for PinnedPlan {
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() || !self.is_visible() {
+            return;
+        }
+        let full = self.full_lines();
+        let lines = if full.len() <= area.height as usize {
+            full
+        } else {
+            vec![self.summary_line()]
+        };
+        Paragraph::new(lines).render(area, buf);
+    }
+
+    fn desired_height(&self, _width: u16) -> u16 {
+        if self.is_visible() {
+            u16::try_from(self.steps.len() + 1).unwrap_or(u16::MAX)
+        } else {
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn item(step: &str, status: StepStatus) -> PlanItemArg {
+        PlanItemArg {
+            step: step.to_string(),
+            status,
+        }
+    }
+
+    fn summary_text(plan: &PinnedPlan) -> String {
+        plan.summary_line()
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn hidden_until_pinned_with_steps() {
+        let mut plan = PinnedPlan::new();
+        assert_eq!(plan.desired_height(40), 0);
+
+        assert!(plan.toggle_pinned());
+        assert_eq!(plan.desired_height(40), 0);
+
+        plan.set_steps(vec![item("Read the code", StepStatus::Pending)]);
+        assert_eq!(plan.desired_height(40), 2);
+
+        assert!(!plan.toggle_pinned());
+        assert_eq!(plan.desired_height(40), 0);
+    }
+
+    #[test]
+    fn summary_names_the_current_step() {
+        let mut plan = PinnedPlan::new();
+        plan.set_steps(vec![
+            item("Read the code", StepStatus::Completed),
+            item("Write tests", StepStatus::Pending),
+            item("Update docs", StepStatus::Pending),
+        ]);
+        assert_eq!(summary_text(&plan), "Step 2/3: Write tests");
+
+        plan.set_steps(vec![
+            item("Read the code", StepStatus::Completed),
+            item("Write tests", StepStatus::Completed),
+        ]);
+        assert_eq!(summary_text(&plan), "Plan complete · 2/2 done");
+    }
+}
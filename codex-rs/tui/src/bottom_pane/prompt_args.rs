@@ -40,6 +40,11 @@ pub enum PromptExpansionError {
         command: String,
         missing: Vec<String>,
     },
+    UnknownArgs {
+        command: String,
+        unknown: Vec<String>,
+        expected: Vec<String>,
+    },
 }
 
 impl PromptExpansionError {
@@ -52,6 +57,15 @@ impl PromptExpansionError {
                     "Missing required args for {command}: {list}. Provide as key=value (quote values with spaces)."
                 )
             }
+            PromptExpansionError::UnknownArgs {
+                command,
+                unknown,
+                expected,
+            } => {
+                let unknown = unknown.join(", ");
+                let expected = expected.join(", ");
+                format!("Unknown args for {command}: {unknown}. Expected: {expected}.")
+            }
         }
     }
 }
@@ -106,6 +120,19 @@ pub fn prompt_argument_names(content: &str) -> Vec<String> {
     names
 }
 
+/// Returns the named arguments a prompt expects: the `arguments` declared in
+/// its front-matter, or else the `$NAME` placeholders in its body.
+pub fn prompt_named_args(prompt: &CustomPrompt) -> Vec<String> {
+    if prompt.arguments.is_empty() {
+        return prompt_argument_names(&prompt.content);
+    }
+    prompt
+        .arguments
+        .iter()
+        .map(|argument| argument.name.clone())
+        .collect()
+}
+
 /// Parses the `key=value` pairs that follow a custom prompt name.
 ///
 /// The input is split using shlex rules, so quoted values are supported
@@ -152,16 +179,43 @@ pub fn expand_custom_prompt(
         None => return Ok(None),
     };
     // If there are named placeholders, expect key=value inputs.
-    let required = prompt_argument_names(&prompt.content);
+    let required = prompt_named_args(prompt);
     if !required.is_empty() {
         let inputs = parse_prompt_inputs(rest).map_err(|error| PromptExpansionError::Args {
             command: format!("/{name}"),
             error,
         })?;
-        let missing: Vec<String> = required
-            .into_iter()
-            .filter(|k| !inputs.contains_key(k))
-            .collect();
+        if !prompt.arguments.is_empty() {
+            let mut unknown: Vec<String> = inputs
+                .keys()
+                .filter(|key| !required.contains(key))
+                .cloned()
+                .collect();
+            if !unknown.is_empty() {
+                unknown.sort();
+                return Err(PromptExpansionError::UnknownArgs {
+                    command: format!("/{name}"),
+                    unknown,
+                    expected: required,
+                });
+            }
+        }
+        let missing: Vec<String> = if prompt.arguments.is_empty() {
+            required
+                .into_iter()
+                .filter(|k| !inputs.contains_key(k))
+                .collect()
+        } else {
+            prompt
+                .arguments
+                .iter()
+                .filter(|argument| !inputs.contains_key(&argument.name))
+                .map(|argument| match &argument.hint {
+                    Some(hint) => format!("{} ({hint})", argument.name),
+                    None => argument.name.clone(),
+                })
+                .collect()
+        };
         if !missing.is_empty() {
             return Err(PromptExpansionError::MissingArgs {
                 command: format!("/{name}"),
@@ -313,6 +367,7 @@ pub fn prompt_command_with_arg_placeholders(name: &str, args: &[String]) -> (Str
 #[cfg(test)]
 mod tests {
     use super::*;
+    use codex_protocol::custom_prompts::CustomPromptArgument;
 
     #[test]
     fn expand_arguments_basic() {
@@ -322,6 +377,7 @@ mod tests {
             content: "Review $USER changes on $BRANCH".to_string(),
             description: None,
             argument_hint: None,
+            ..Default::default()
         }];
 
         let out =
@@ -337,6 +393,7 @@ mod tests {
             content: "Pair $USER with $BRANCH".to_string(),
             description: None,
             argument_hint: None,
+            ..Default::default()
         }];
 
         let out = expand_custom_prompt(
@@ -355,6 +412,7 @@ mod tests {
             content: "Review $USER changes".to_string(),
             description: None,
             argument_hint: None,
+            ..Default::default()
         }];
         let err = expand_custom_prompt("/prompts:my-prompt USER=Alice stray", &prompts)
             .unwrap_err()
@@ -370,6 +428,7 @@ mod tests {
             content: "Review $USER changes on $BRANCH".to_string(),
             description: None,
             argument_hint: None,
+            ..Default::default()
         }];
         let err = expand_custom_prompt("/prompts:my-prompt USER=Alice", &prompts)
             .unwrap_err()
@@ -398,9 +457,64 @@ mod tests {
             content: "literal $$USER".to_string(),
             description: None,
             argument_hint: None,
+            ..Default::default()
         }];
 
         let out = expand_custom_prompt("/prompts:my-prompt", &prompts).unwrap();
         assert_eq!(out, Some("literal $$USER".to_string()));
     }
+
+    fn declared_prompt() -> CustomPrompt {
+        CustomPrompt {
+            name: "fix".to_string(),
+            path: "/tmp/fix.md".to_string().into(),
+            content: "Fix $FILE so that $GOAL".to_string(),
+            arguments: vec![
+                CustomPromptArgument {
+                    name: "FILE".to_string(),
+                    hint: Some("path to the file".to_string()),
+                },
+                CustomPromptArgument {
+                    name: "GOAL".to_string(),
+                    hint: None,
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn declared_arguments_expand() {
+        let out = expand_custom_prompt(
+            "/prompts:fix FILE=src/lib.rs GOAL=\"tests pass\"",
+            &[declared_prompt()],
+        )
+        .unwrap();
+        assert_eq!(out, Some("Fix src/lib.rs so that tests pass".to_string()));
+    }
+
+    #[test]
+    fn declared_arguments_reject_unknown_keys() {
+        let err = expand_custom_prompt(
+            "/prompts:fix FILE=a GOAL=b TARGET=c PATH=d",
+            &[declared_prompt()],
+        )
+        .unwrap_err()
+        .user_message();
+        assert_eq!(
+            err,
+            "Unknown args for /prompts:fix: PATH, TARGET. Expected: FILE, GOAL."
+        );
+    }
+
+    #[test]
+    fn missing_declared_arguments_include_hints() {
+        let err = expand_custom_prompt("/prompts:fix", &[declared_prompt()])
+            .unwrap_err()
+            .user_message();
+        assert_eq!(
+            err,
+            "Missing required args for /prompts:fix: FILE (path to the file), GOAL. Provide as key=value (quote values with spaces)."
+        );
+    }
 }
@@ -9,6 +9,7 @@ use ratatui::text::Line;
 use ratatui::text::Span;
 use ratatui::widgets::Widget;
 use unicode_width::UnicodeWidthChar;
+use unicode_width::UnicodeWidthStr;
 
 use crate::key_hint::KeyBinding;
 
@@ -50,10 +51,20 @@ fn compute_desc_col(
 /// Build the full display line for a row with the description padded to start
 /// at `desc_col`. Applies fuzzy-match bolding when indices are present and
 /// dims the description.
-fn build_full_line(row: &GenericDisplayRow, desc_col: usize) -> Line<'static> {
+fn build_full_line(row: &GenericDisplayRow, desc_col: usize, max_width: usize) -> Line<'static> {
     // Enforce single-line name: allow at most desc_col - 2 cells for name,
     // reserving two spaces before the description column.
-    let name_limit = desc_col.saturating_sub(2);
+    let mut name_limit = desc_col.saturating_sub(2);
+    // Keep the shortcut on the name's line: when both don't fit in
+    // `max_width`, cut the name further to leave room for the shortcut and
+    // the ellipsis.
+    let shortcut_width = row
+        .display_shortcut
+        .map(|shortcut| Span::from(shortcut).width() + " ()".len())
+        .unwrap_or(0);
+    if row.name.width() + shortcut_width > max_width {
+        name_limit = name_limit.min(max_width.saturating_sub(shortcut_width + 1));
+    }
 
     let mut name_spans: Vec<Span> = Vec::with_capacity(row.name.len());
     let mut used_width = 0usize;
@@ -89,6 +100,9 @@ fn build_full_line(row: &GenericDisplayRow, desc_col: usize) -> Line<'static> {
     }
 
     if truncated {
+        while name_spans.last().is_some_and(|span| span.content == " ") {
+            name_spans.pop();
+        }
         // If there is at least one cell available, add an ellipsis.
         // When name_limit is 0, we still show an ellipsis to indicate truncation.
         name_spans.push("…".into());
@@ -178,6 +192,7 @@ pub(crate) fn render_rows(
                 description: description.clone(),
             },
             desc_col,
+            area.width as usize,
         );
         if Some(i) == state.selected_idx {
             // Match previous behavior: cyan + bold for the selected row.
@@ -255,7 +270,7 @@ pub(crate) fn measure_rows_height(
         .take(visible_items)
         .map(|(_, r)| r)
     {
-        let full_line = build_full_line(row, desc_col);
+        let full_line = build_full_line(row, desc_col, content_width as usize);
         let opts = RtOptions::new(content_width as usize)
             .initial_indent(Line::from(""))
             .subsequent_indent(Line::from(" ".repeat(desc_col)));
@@ -6,10 +6,13 @@ use std::time::Duration;
 
 use codex_app_server_protocol::AuthMode;
 use codex_backend_client::Client as BackendClient;
+use codex_cloud_tasks_client::TaskSummary;
 use codex_core::config::Config;
 use codex_core::config::types::Notifications;
 use codex_core::git_info::current_branch_name;
 use codex_core::git_info::local_git_branches;
+use codex_core::message_history::load_entries;
+use codex_core::patch_selection::PatchSelection;
 use codex_core::project_doc::DEFAULT_PROJECT_DOC_FILENAME;
 use codex_core::protocol::AgentMessageDeltaEvent;
 use codex_core::protocol::AgentMessageEvent;
@@ -19,15 +22,22 @@ use codex_core::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::BaseDivergenceEvent;
 use codex_core::protocol::DeprecationNoticeEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExecApprovalExplanationEvent;
 use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::ExecCommandSource;
+use codex_core::protocol::ExecInputRequestEvent;
+use codex_core::protocol::ExecInputResponse;
 use codex_core::protocol::ExitedReviewModeEvent;
+use codex_core::protocol::HistoryFlag;
+use codex_core::protocol::HistoryFlagSetEvent;
+use codex_core::protocol::ListContextScopesResponseEvent;
 use codex_core::protocol::ListCustomPromptsResponseEvent;
 use codex_core::protocol::McpListToolsResponseEvent;
 use codex_core::protocol::McpStartupCompleteEvent;
@@ -38,13 +48,18 @@ use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::RateLimitSnapshot;
+use codex_core::protocol::ResumeBriefingEvent;
 use codex_core::protocol::ReviewRequest;
+use codex_core::protocol::SandboxDenial;
+use codex_core::protocol::SandboxDeniedEvent;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol::TokenUsageInfo;
 use codex_core::protocol::TurnAbortReason;
 use codex_core::protocol::TurnDiffEvent;
+use codex_core::protocol::TurnLimitKind;
+use codex_core::protocol::TurnLimitReachedEvent;
 use codex_core::protocol::UndoCompletedEvent;
 use codex_core::protocol::UndoStartedEvent;
 use codex_core::protocol::UserMessageEvent;
@@ -52,7 +67,11 @@ use codex_core::protocol::ViewImageToolCallEvent;
 use codex_core::protocol::WarningEvent;
 use codex_core::protocol::WebSearchBeginEvent;
 use codex_core::protocol::WebSearchEndEvent;
+use codex_core::rate_limit_history::load_samples;
+use codex_core::tool_inventory::load_or_probe;
 use codex_protocol::ConversationId;
+use codex_protocol::custom_prompts::CustomPrompt;
+use codex_protocol::custom_prompts::PROMPTS_CMD_PREFIX;
 use codex_protocol::parse_command::ParsedCommand;
 use codex_protocol::user_input::UserInput;
 use crossterm::event::KeyCode;
@@ -67,27 +86,37 @@ use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::widgets::Paragraph;
 use ratatui::widgets::Wrap;
+use tokio::sync::mpsc::UnboundedReceiver;
 use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::mpsc::unbounded_channel;
 use tokio::task::JoinHandle;
 use tracing::debug;
 
 use crate::app_event::AppEvent;
+use crate::app_event::PromptPresetDecision;
 use crate::app_event_sender::AppEventSender;
 use crate::bottom_pane::ApprovalRequest;
 use crate::bottom_pane::BottomPane;
 use crate::bottom_pane::BottomPaneParams;
 use crate::bottom_pane::CancellationEvent;
+use crate::bottom_pane::CommandExplanation;
+use crate::bottom_pane::HistorySearchView;
 use crate::bottom_pane::InputResult;
 use crate::bottom_pane::SelectionAction;
 use crate::bottom_pane::SelectionItem;
 use crate::bottom_pane::SelectionViewParams;
 use crate::bottom_pane::custom_prompt_view::CustomPromptView;
 use crate::bottom_pane::popup_consts::standard_popup_hint_line;
-use crate::clipboard_paste::paste_image_to_temp_png;
+use crate::clipboard_paste::PasteImageError;
+use crate::clipboard_paste::PastedImageInfo;
+use crate::clipboard_paste::paste_image_to_temp_file;
+use crate::cloud_tasks_service::CloudTaskTransition;
+use crate::cloud_tasks_service::spawn_cloud_task_poller;
 use crate::diff_render::display_path_for;
 use crate::exec_cell::CommandOutput;
 use crate::exec_cell::ExecCell;
 use crate::exec_cell::new_active_exec_command;
+use crate::exec_command::strip_bash_lc_and_escape;
 use crate::get_git_diff::get_git_diff;
 use crate::history_cell;
 use crate::history_cell::AgentMessageCell;
@@ -102,9 +131,15 @@ use crate::render::renderable::Renderable;
 use crate::render::renderable::RenderableExt;
 use crate::render::renderable::RenderableItem;
 use crate::slash_command::SlashCommand;
+use crate::status::CriticalHeadroomAlerts;
+use crate::status::RateLimitHeadroom;
 use crate::status::RateLimitSnapshotDisplay;
+use crate::status_indicator_widget::StatusIndicatorWidget;
+use crate::terminal_title::TitleStatus;
 use crate::text_formatting::truncate_text;
 use crate::tui::FrameRequester;
+use crate::turn_separators::TurnMarker;
+use crate::ui_consts::MIN_RENDER_WIDTH;
 mod interrupts;
 use self::interrupts::InterruptManager;
 mod agent;
@@ -116,6 +151,7 @@ use crate::streaming::controller::StreamController;
 use std::path::Path;
 
 use chrono::Local;
+use chrono::Utc;
 use codex_common::approval_presets::ApprovalPreset;
 use codex_common::approval_presets::builtin_approval_presets;
 use codex_common::model_presets::ModelPreset;
@@ -126,6 +162,7 @@ use codex_core::ConversationManager;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
+use codex_core::slash_commands::CommandAvailability;
 use codex_file_search::FileMatch;
 use codex_protocol::plan_tool::UpdatePlanArgs;
 use strum::IntoEnumIterator;
@@ -260,8 +297,14 @@ pub(crate) struct ChatWidget {
     token_info: Option<TokenUsageInfo>,
     rate_limit_snapshot: Option<RateLimitSnapshotDisplay>,
     rate_limit_warnings: RateLimitWarningState,
+    rate_limit_alerts: CriticalHeadroomAlerts,
     rate_limit_switch_prompt: RateLimitSwitchPromptState,
     rate_limit_poller: Option<JoinHandle<()>>,
+    cloud_task_poller: Option<JoinHandle<()>>,
+    // Latest cloud task snapshot from the background poller, shown by /cloud.
+    cloud_tasks: Option<Vec<TaskSummary>>,
+    // Latest report that the worktree is behind its base branch; used by /sync-base.
+    base_divergence: Option<BaseDivergenceEvent>,
     // Stream lifecycle controller
     stream_controller: Option<StreamController>,
     running_commands: HashMap<String, RunningCommand>,
@@ -281,6 +324,8 @@ pub(crate) struct ChatWidget {
     frame_requester: FrameRequester,
     // Whether to include the initial welcome banner on session configured
     show_welcome_banner: bool,
+    // Trust decision made during onboarding, shown once the session header is in.
+    pending_trust_summary: Option<Vec<Line<'static>>>,
     // When resuming an existing session (selected via resume picker), avoid an
     // immediate redraw on SessionConfigured to prevent a gratuitous UI flicker.
     suppress_session_configured_redraw: bool,
@@ -294,17 +339,30 @@ pub(crate) struct ChatWidget {
     pre_review_token_info: Option<Option<TokenUsageInfo>>,
     // Whether to add a final message separator after the last message
     needs_final_message_separator: bool,
+    // Number of user turns started in this session; tags history for turn separators.
+    turns_started: usize,
+    // Whether the last turn ended with an error; shown in the terminal title.
+    last_turn_failed: bool,
+    // Prompt message waiting for the user to confirm its requested approval preset.
+    pending_prompt_preset: Option<(UserMessage, ApprovalPreset)>,
+    // Front-matter overrides in effect for the running turn; reverted when it ends.
+    active_turn_overrides: Option<TurnOverrides>,
 
     last_rendered_width: std::cell::Cell<Option<usize>>,
     // Feedback sink for /feedback
     feedback: codex_feedback::CodexFeedback,
     // Current session rollout path (if known)
     current_rollout_path: Option<PathBuf>,
+    // Working directory reported by the current session (if configured)
+    session_cwd: Option<PathBuf>,
+    // Whether we already explained how to enable clipboard image paste
+    clipboard_tools_hint_shown: bool,
 }
 
 struct UserMessage {
     text: String,
     image_paths: Vec<PathBuf>,
+    overrides: TurnOverrides,
 }
 
 impl From<String> for UserMessage {
@@ -312,6 +370,7 @@ impl From<String> for UserMessage {
         Self {
             text,
             image_paths: Vec::new(),
+            overrides: TurnOverrides::default(),
         }
     }
 }
@@ -321,15 +380,35 @@ impl From<&str> for UserMessage {
         Self {
             text: text.to_string(),
             image_paths: Vec::new(),
+            overrides: TurnOverrides::default(),
         }
     }
 }
 
+/// Settings a saved prompt's front-matter asks for, applied only to the turn
+/// that runs the prompt.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct TurnOverrides {
+    model: Option<String>,
+    effort: Option<ReasoningEffortConfig>,
+    approval: Option<(AskForApproval, SandboxPolicy)>,
+}
+
+impl TurnOverrides {
+    fn is_empty(&self) -> bool {
+        self.model.is_none() && self.effort.is_none() && self.approval.is_none()
+    }
+}
+
 fn create_initial_user_message(text: String, image_paths: Vec<PathBuf>) -> Option<UserMessage> {
     if text.is_empty() && image_paths.is_empty() {
         None
     } else {
-        Some(UserMessage { text, image_paths })
+        Some(UserMessage {
+            text,
+            image_paths,
+            overrides: TurnOverrides::default(),
+        })
     }
 }
 
@@ -353,6 +432,7 @@ impl ChatWidget {
             .set_history_metadata(event.history_log_id, event.history_entry_count);
         self.conversation_id = Some(event.session_id);
         self.current_rollout_path = Some(event.rollout_path.clone());
+        self.session_cwd = Some(event.cwd.clone());
         let initial_messages = event.initial_messages.clone();
         let model_for_header = event.model.clone();
         self.session_header.set_model(&model_for_header);
@@ -361,6 +441,9 @@ impl ChatWidget {
             event,
             self.show_welcome_banner,
         ));
+        if let Some(lines) = self.pending_trust_summary.take() {
+            self.add_to_history(history_cell::PlainHistoryCell::new(lines));
+        }
         if let Some(messages) = initial_messages {
             self.replay_initial_messages(messages);
         }
@@ -464,6 +547,7 @@ impl ChatWidget {
     fn on_task_started(&mut self) {
         self.bottom_pane.clear_ctrl_c_quit_hint();
         self.bottom_pane.set_task_running(true);
+        self.last_turn_failed = false;
         self.retry_status_header = None;
         self.bottom_pane.set_interrupt_hint_visible(true);
         self.set_status_header(String::from("Working"));
@@ -478,6 +562,7 @@ impl ChatWidget {
         // Mark task stopped and request redraw now that all content is in history.
         self.bottom_pane.set_task_running(false);
         self.running_commands.clear();
+        self.restore_turn_overrides();
         self.request_redraw();
 
         // If there is a queued user message, send exactly one now to begin the next turn.
@@ -567,6 +652,11 @@ impl ChatWidget {
                 self.rate_limit_switch_prompt = RateLimitSwitchPromptState::Pending;
             }
 
+            let thresholds = self.config.tui_rate_limit_warnings;
+            self.bottom_pane
+                .set_rate_limits(RateLimitHeadroom::from_snapshot(&snapshot, thresholds));
+            let alerts = self.rate_limit_alerts.take_alerts(&snapshot, thresholds);
+
             let display = crate::status::rate_limit_snapshot_display(&snapshot, Local::now());
             self.rate_limit_snapshot = Some(display);
 
@@ -576,8 +666,18 @@ impl ChatWidget {
                 }
                 self.request_redraw();
             }
+            if !alerts.is_empty() {
+                for alert in alerts {
+                    self.add_to_history(history_cell::new_info_event(
+                        alert,
+                        Some("Run /status for details.".to_string()),
+                    ));
+                }
+                self.request_redraw();
+            }
         } else {
             self.rate_limit_snapshot = None;
+            self.bottom_pane.set_rate_limits(None);
         }
     }
     /// Finalize any active exec as failed and stop/clear running UI state.
@@ -588,11 +688,13 @@ impl ChatWidget {
         self.bottom_pane.set_task_running(false);
         self.running_commands.clear();
         self.stream_controller = None;
+        self.restore_turn_overrides();
         self.maybe_show_pending_rate_limit_prompt();
     }
 
     fn on_error(&mut self, message: String) {
         self.finalize_turn();
+        self.last_turn_failed = true;
         self.add_to_history(history_cell::new_error_event(message));
         self.request_redraw();
 
@@ -605,6 +707,98 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    fn on_turn_limit_reached(&mut self, ev: TurnLimitReachedEvent) {
+        let threshold = ev.threshold;
+        let message = match ev.limit {
+            TurnLimitKind::WallClock => format!("Turn stopped after its {threshold}s time limit"),
+            TurnLimitKind::OutputTokens => {
+                format!("Turn stopped after its {threshold} output token limit")
+            }
+        };
+        self.add_to_history(history_cell::new_info_event(
+            message,
+            Some("Adjust [turn_limits] in config.toml to change this.".to_string()),
+        ));
+        self.request_redraw();
+    }
+
+    fn on_sandbox_denied(&mut self, ev: SandboxDeniedEvent) {
+        match ev.denial {
+            SandboxDenial::Network(destination) => {
+                self.on_warning(format!("Sandbox blocked a connection to {destination}"));
+            }
+        }
+    }
+
+    fn on_exec_input_request(&mut self, ev: ExecInputRequestEvent) {
+        let command = strip_bash_lc_and_escape(&ev.command);
+        self.on_warning(format!(
+            "`{command}` is waiting for input: {prompt}",
+            prompt = ev.prompt
+        ));
+        if ev.terminated {
+            return;
+        }
+
+        let mut items: Vec<SelectionItem> = Vec::new();
+        if ev.accepts_input {
+            let call_id = ev.call_id.clone();
+            let prompt = ev.prompt.clone();
+            items.push(SelectionItem {
+                name: "Send a response".to_string(),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::OpenExecInputPrompt {
+                        call_id: call_id.clone(),
+                        prompt: prompt.clone(),
+                    });
+                })],
+                dismiss_on_select: false,
+                ..Default::default()
+            });
+        }
+        let call_id = ev.call_id;
+        items.push(SelectionItem {
+            name: "Terminate the command".to_string(),
+            actions: vec![Box::new(move |tx| {
+                tx.send(AppEvent::CodexOp(Op::ExecInput {
+                    id: call_id.clone(),
+                    response: ExecInputResponse::Terminate,
+                }));
+            })],
+            dismiss_on_select: true,
+            ..Default::default()
+        });
+        items.push(SelectionItem {
+            name: "Keep waiting".to_string(),
+            dismiss_on_select: true,
+            ..Default::default()
+        });
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some(format!("Command is waiting for input: {}", ev.prompt)),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+        self.request_redraw();
+    }
+
+    pub(crate) fn show_exec_input_prompt(&mut self, call_id: String, prompt: String) {
+        let tx = self.app_event_tx.clone();
+        let view = CustomPromptView::new(
+            prompt,
+            "Type a response and press Enter".to_string(),
+            None,
+            Box::new(move |text: String| {
+                tx.send(AppEvent::CodexOp(Op::ExecInput {
+                    id: call_id.clone(),
+                    response: ExecInputResponse::Input { text },
+                }));
+            }),
+        );
+        self.bottom_pane.show_view(Box::new(view));
+    }
+
     fn on_mcp_startup_update(&mut self, ev: McpStartupUpdateEvent) {
         let mut status = self.mcp_startup_status.take().unwrap_or_default();
         if let McpStartupStatus::Failed { error } = &ev.status {
@@ -712,6 +906,7 @@ impl ChatWidget {
     }
 
     fn on_plan_update(&mut self, update: UpdatePlanArgs) {
+        self.bottom_pane.set_plan(update.plan.clone());
         self.add_to_history(history_cell::new_plan_update(update));
     }
 
@@ -724,6 +919,15 @@ impl ChatWidget {
         );
     }
 
+    /// Queued behind its approval request so the summary never arrives first.
+    fn on_exec_approval_explanation(&mut self, ev: ExecApprovalExplanationEvent) {
+        let ev2 = ev.clone();
+        self.defer_or_handle(
+            |q| q.push_exec_approval_explanation(ev),
+            |s| s.handle_exec_approval_explanation_now(ev2),
+        );
+    }
+
     fn on_apply_patch_approval_request(&mut self, id: String, ev: ApplyPatchApprovalRequestEvent) {
         let id2 = id.clone();
         let ev2 = ev.clone();
@@ -741,15 +945,37 @@ impl ChatWidget {
 
     fn on_exec_command_output_delta(
         &mut self,
-        _ev: codex_core::protocol::ExecCommandOutputDeltaEvent,
+        ev: codex_core::protocol::ExecCommandOutputDeltaEvent,
     ) {
-        // TODO: Handle streaming exec output if/when implemented
+        // Chunks for a command whose begin event is still queued are dropped;
+        // the full output is shown once it completes.
+        if let Some(cell) = self
+            .active_cell
+            .as_mut()
+            .and_then(|c| c.as_any_mut().downcast_mut::<ExecCell>())
+            && cell.push_output_chunk(&ev.call_id, ev.stream, ev.offset, &ev.chunk)
+        {
+            self.request_redraw();
+        }
+    }
+
+    /// Ctrl+O: expand or collapse the output of the running command.
+    fn toggle_exec_output_expanded(&mut self) {
+        if let Some(cell) = self
+            .active_cell
+            .as_mut()
+            .and_then(|c| c.as_any_mut().downcast_mut::<ExecCell>())
+        {
+            cell.toggle_output_expanded();
+            self.request_redraw();
+        }
     }
 
     fn on_patch_apply_begin(&mut self, event: PatchApplyBeginEvent) {
         self.add_to_history(history_cell::new_patch_event(
             event.changes,
             &self.config.cwd,
+            &self.config.tui_word_diff_extensions,
         ));
     }
 
@@ -824,6 +1050,17 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    fn on_base_divergence(&mut self, event: BaseDivergenceEvent) {
+        self.add_to_history(history_cell::new_base_divergence_notice(&event));
+        self.base_divergence = Some(event);
+        self.request_redraw();
+    }
+
+    fn on_resume_briefing(&mut self, event: ResumeBriefingEvent) {
+        self.add_to_history(history_cell::new_resume_briefing(&event));
+        self.request_redraw();
+    }
+
     fn on_background_event(&mut self, message: String) {
         debug!("BackgroundEvent: {message}");
         self.bottom_pane.ensure_status_indicator();
@@ -840,6 +1077,19 @@ impl ChatWidget {
         self.set_status_header(message);
     }
 
+    fn on_history_flag_set(&mut self, event: HistoryFlagSetEvent) {
+        if let HistoryFlag::Pinned { pinned, .. } = event.flag {
+            let (message, hint) = if pinned {
+                ("Message pinned", "Compaction will keep it word for word.")
+            } else {
+                ("Message unpinned", "Compaction may summarize it.")
+            };
+            self.add_info_message(message.to_string(), Some(hint.to_string()));
+        }
+        self.app_event_tx
+            .send(AppEvent::ApplyHistoryFlag(event.flag));
+    }
+
     fn on_undo_completed(&mut self, event: UndoCompletedEvent) {
         let UndoCompletedEvent { success, message } = event;
         self.bottom_pane.hide_status_indicator();
@@ -956,13 +1206,10 @@ impl ChatWidget {
             .unwrap_or(true);
         if needs_new {
             self.flush_active_cell();
-            self.active_cell = Some(Box::new(new_active_exec_command(
-                ev.call_id.clone(),
-                command,
-                parsed,
-                source,
-                None,
-            )));
+            self.active_cell = Some(Box::new(
+                new_active_exec_command(ev.call_id.clone(), command, parsed, source, None)
+                    .with_follow_lines(self.config.tui_exec_follow_lines),
+            ));
         }
 
         if let Some(cell) = self
@@ -975,12 +1222,14 @@ impl ChatWidget {
                     exit_code: ev.exit_code,
                     formatted_output: String::new(),
                     aggregated_output: String::new(),
+                    stderr_ranges: Vec::new(),
                 }
             } else {
                 CommandOutput {
                     exit_code: ev.exit_code,
                     formatted_output: ev.formatted_output.clone(),
                     aggregated_output: ev.aggregated_output.clone(),
+                    stderr_ranges: ev.stderr_ranges.clone(),
                 }
             };
             cell.complete_call(&ev.call_id, output, ev.duration);
@@ -1007,16 +1256,35 @@ impl ChatWidget {
             .unwrap_or_else(|_| ev.command.join(" "));
         self.notify(Notification::ExecApprovalRequested { command });
 
-        let request = ApprovalRequest::Exec {
-            id,
-            command: ev.command,
-            reason: ev.reason,
-            risk: ev.risk,
+        let request = match ev.network {
+            Some(destination) => ApprovalRequest::Network {
+                id,
+                command: ev.command,
+                destination,
+            },
+            None => ApprovalRequest::Exec {
+                id,
+                call_id: ev.call_id,
+                command: ev.command,
+                reason: ev.reason,
+                risk: ev.risk,
+                explanation: ev
+                    .explanation_pending
+                    .then_some(CommandExplanation::Pending),
+            },
         };
         self.bottom_pane.push_approval_request(request);
         self.request_redraw();
     }
 
+    pub(crate) fn handle_exec_approval_explanation_now(
+        &mut self,
+        ev: ExecApprovalExplanationEvent,
+    ) {
+        self.bottom_pane
+            .set_command_explanation(&ev.call_id, CommandExplanation::from(ev.explanation));
+    }
+
     pub(crate) fn handle_apply_patch_approval_now(
         &mut self,
         id: String,
@@ -1065,13 +1333,20 @@ impl ChatWidget {
         } else {
             self.flush_active_cell();
 
-            self.active_cell = Some(Box::new(new_active_exec_command(
-                ev.call_id.clone(),
-                ev.command.clone(),
-                ev.parsed_cmd,
-                ev.source,
-                interaction_input,
-            )));
+            self.active_cell = Some(Box::new(
+                new_active_exec_command(
+                    ev.call_id.clone(),
+                    ev.command.clone(),
+                    ev.parsed_cmd,
+                    ev.source,
+                    interaction_input,
+                )
+                .with_follow_lines(self.config.tui_exec_follow_lines)
+                .with_filtered_env_vars(
+                    ev.filtered_env_vars
+                        .filter(|_| self.config.tui_show_filtered_env_vars),
+                ),
+            ));
         }
 
         self.request_redraw();
@@ -1094,6 +1369,7 @@ impl ChatWidget {
             invocation,
             duration,
             result,
+            cached,
         } = ev;
 
         let extra_cell = match self
@@ -1101,11 +1377,11 @@ impl ChatWidget {
             .as_mut()
             .and_then(|cell| cell.as_any_mut().downcast_mut::<McpToolCallCell>())
         {
-            Some(cell) if cell.call_id() == call_id => cell.complete(duration, result),
+            Some(cell) if cell.call_id() == call_id => cell.complete(duration, result, cached),
             _ => {
                 self.flush_active_cell();
                 let mut cell = history_cell::new_active_mcp_tool_call(call_id, invocation);
-                let extra_cell = cell.complete(duration, result);
+                let extra_cell = cell.complete(duration, result, cached);
                 self.active_cell = Some(Box::new(cell));
                 extra_cell
             }
@@ -1121,68 +1397,18 @@ impl ChatWidget {
         common: ChatWidgetInit,
         conversation_manager: Arc<ConversationManager>,
     ) -> Self {
-        let ChatWidgetInit {
-            config,
-            frame_requester,
-            app_event_tx,
-            initial_prompt,
-            initial_images,
-            enhanced_keys_supported,
-            auth_manager,
-            feedback,
-        } = common;
-        let mut rng = rand::rng();
-        let placeholder = EXAMPLE_PROMPTS[rng.random_range(0..EXAMPLE_PROMPTS.len())].to_string();
-        let codex_op_tx = spawn_agent(config.clone(), app_event_tx.clone(), conversation_manager);
-
-        let mut widget = Self {
-            app_event_tx: app_event_tx.clone(),
-            frame_requester: frame_requester.clone(),
-            codex_op_tx,
-            bottom_pane: BottomPane::new(BottomPaneParams {
-                frame_requester,
-                app_event_tx,
-                has_input_focus: true,
-                enhanced_keys_supported,
-                placeholder_text: placeholder,
-                disable_paste_burst: config.disable_paste_burst,
-            }),
-            active_cell: None,
-            config: config.clone(),
-            auth_manager,
-            session_header: SessionHeader::new(config.model),
-            initial_user_message: create_initial_user_message(
-                initial_prompt.unwrap_or_default(),
-                initial_images,
-            ),
-            token_info: None,
-            rate_limit_snapshot: None,
-            rate_limit_warnings: RateLimitWarningState::default(),
-            rate_limit_switch_prompt: RateLimitSwitchPromptState::default(),
-            rate_limit_poller: None,
-            stream_controller: None,
-            running_commands: HashMap::new(),
-            task_complete_pending: false,
-            mcp_startup_status: None,
-            interrupts: InterruptManager::new(),
-            reasoning_buffer: String::new(),
-            full_reasoning_buffer: String::new(),
-            current_status_header: String::from("Working"),
-            retry_status_header: None,
-            conversation_id: None,
-            queued_user_messages: VecDeque::new(),
-            show_welcome_banner: true,
-            suppress_session_configured_redraw: false,
-            pending_notification: None,
-            is_review_mode: false,
-            pre_review_token_info: None,
-            needs_final_message_separator: false,
-            last_rendered_width: std::cell::Cell::new(None),
-            feedback,
-            current_rollout_path: None,
-        };
+        let codex_op_tx = spawn_agent(
+            common.config.clone(),
+            common.app_event_tx.clone(),
+            conversation_manager,
+        );
+        let mut widget = Self::from_init(common, codex_op_tx, true);
 
+        widget
+            .bottom_pane
+            .set_command_availability(CommandAvailability::from_config(&widget.config));
         widget.prefetch_rate_limits();
+        widget.start_cloud_task_poller();
 
         widget
     }
@@ -1192,6 +1418,41 @@ impl ChatWidget {
         common: ChatWidgetInit,
         conversation: std::sync::Arc<codex_core::CodexConversation>,
         session_configured: codex_core::protocol::SessionConfiguredEvent,
+    ) -> Self {
+        let codex_op_tx = spawn_agent_from_existing(
+            conversation,
+            session_configured,
+            common.app_event_tx.clone(),
+        );
+        let mut widget = Self::from_init(common, codex_op_tx, true);
+        widget.suppress_session_configured_redraw = true;
+
+        widget
+            .bottom_pane
+            .set_command_availability(CommandAvailability::from_config(&widget.config));
+        widget.prefetch_rate_limits();
+        widget.start_cloud_task_poller();
+
+        widget
+    }
+
+    /// Create a read-only ChatWidget for `codex replay`. No agent is spawned
+    /// and no background pollers start; any ops the widget tries to submit are
+    /// delivered to the returned receiver so the caller can discard them.
+    pub(crate) fn new_replay(common: ChatWidgetInit) -> (Self, UnboundedReceiver<Op>) {
+        let (codex_op_tx, codex_op_rx) = unbounded_channel::<Op>();
+        let mut widget = Self::from_init(common, codex_op_tx, false);
+        widget.suppress_session_configured_redraw = true;
+        (widget, codex_op_rx)
+    }
+
+    /// Builds the widget state shared by every constructor. Non-interactive
+    /// widgets (replay) take no input focus, show no placeholder and skip the
+    /// welcome banner.
+    fn from_init(
+        common: ChatWidgetInit,
+        codex_op_tx: UnboundedSender<Op>,
+        interactive: bool,
     ) -> Self {
         let ChatWidgetInit {
             config,
@@ -1203,20 +1464,21 @@ impl ChatWidget {
             auth_manager,
             feedback,
         } = common;
-        let mut rng = rand::rng();
-        let placeholder = EXAMPLE_PROMPTS[rng.random_range(0..EXAMPLE_PROMPTS.len())].to_string();
-
-        let codex_op_tx =
-            spawn_agent_from_existing(conversation, session_configured, app_event_tx.clone());
+        let placeholder = if interactive {
+            let mut rng = rand::rng();
+            EXAMPLE_PROMPTS[rng.random_range(0..EXAMPLE_PROMPTS.len())].to_string()
+        } else {
+            String::new()
+        };
 
-        let mut widget = Self {
+        Self {
             app_event_tx: app_event_tx.clone(),
             frame_requester: frame_requester.clone(),
             codex_op_tx,
             bottom_pane: BottomPane::new(BottomPaneParams {
                 frame_requester,
                 app_event_tx,
-                has_input_focus: true,
+                has_input_focus: interactive,
                 enhanced_keys_supported,
                 placeholder_text: placeholder,
                 disable_paste_burst: config.disable_paste_burst,
@@ -1232,8 +1494,12 @@ impl ChatWidget {
             token_info: None,
             rate_limit_snapshot: None,
             rate_limit_warnings: RateLimitWarningState::default(),
+            rate_limit_alerts: CriticalHeadroomAlerts::default(),
             rate_limit_switch_prompt: RateLimitSwitchPromptState::default(),
             rate_limit_poller: None,
+            cloud_task_poller: None,
+            cloud_tasks: None,
+            base_divergence: None,
             stream_controller: None,
             running_commands: HashMap::new(),
             task_complete_pending: false,
@@ -1245,20 +1511,23 @@ impl ChatWidget {
             retry_status_header: None,
             conversation_id: None,
             queued_user_messages: VecDeque::new(),
-            show_welcome_banner: true,
-            suppress_session_configured_redraw: true,
+            show_welcome_banner: interactive,
+            pending_trust_summary: None,
+            suppress_session_configured_redraw: false,
             pending_notification: None,
             is_review_mode: false,
             pre_review_token_info: None,
             needs_final_message_separator: false,
+            turns_started: 0,
+            last_turn_failed: false,
+            pending_prompt_preset: None,
+            active_turn_overrides: None,
             last_rendered_width: std::cell::Cell::new(None),
             feedback,
             current_rollout_path: None,
-        };
-
-        widget.prefetch_rate_limits();
-
-        widget
+            session_cwd: None,
+            clipboard_tools_hint_shown: false,
+        }
     }
 
     pub(crate) fn handle_key_event(&mut self, key_event: KeyEvent) {
@@ -1278,22 +1547,7 @@ impl ChatWidget {
                 kind: KeyEventKind::Press,
                 ..
             } if modifiers.contains(KeyModifiers::CONTROL) && c.eq_ignore_ascii_case(&'v') => {
-                match paste_image_to_temp_png() {
-                    Ok((path, info)) => {
-                        self.attach_image(
-                            path,
-                            info.width,
-                            info.height,
-                            info.encoded_format.label(),
-                        );
-                    }
-                    Err(err) => {
-                        tracing::warn!("failed to paste image: {err}");
-                        self.add_to_history(history_cell::new_error_event(format!(
-                            "Failed to paste image: {err}",
-                        )));
-                    }
-                }
+                self.paste_image_from_clipboard(false);
                 return;
             }
             other if other.kind == KeyEventKind::Press => {
@@ -1316,6 +1570,22 @@ impl ChatWidget {
                     self.request_redraw();
                 }
             }
+            KeyEvent {
+                code: KeyCode::Char('r'),
+                modifiers: KeyModifiers::CONTROL,
+                kind: KeyEventKind::Press,
+                ..
+            } if self.bottom_pane.composer_owns_keys() => {
+                self.open_history_search();
+            }
+            KeyEvent {
+                code: KeyCode::Char('o'),
+                modifiers: KeyModifiers::CONTROL,
+                kind: KeyEventKind::Press,
+                ..
+            } if self.bottom_pane.composer_owns_keys() => {
+                self.toggle_exec_output_expanded();
+            }
             _ => {
                 match self.bottom_pane.handle_key_event(key_event) {
                     InputResult::Submitted(text) => {
@@ -1323,12 +1593,19 @@ impl ChatWidget {
                         let user_message = UserMessage {
                             text,
                             image_paths: self.bottom_pane.take_recent_submission_images(),
+                            overrides: TurnOverrides::default(),
                         };
-                        self.queue_user_message(user_message);
+                        match self.bottom_pane.take_submitted_prompt() {
+                            Some(prompt) => self.queue_prompt_message(&prompt, user_message),
+                            None => self.queue_user_message(user_message),
+                        }
                     }
                     InputResult::Command(cmd) => {
                         self.dispatch_command(cmd);
                     }
+                    InputResult::CommandWithArgs(cmd, args) => {
+                        self.dispatch_command_with_args(cmd, &args);
+                    }
                     InputResult::None => {}
                 }
             }
@@ -1350,6 +1627,19 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    fn dispatch_command_with_args(&mut self, cmd: SlashCommand, args: &str) {
+        match (cmd, args) {
+            (SlashCommand::Context, "scopes") => self.submit_op(Op::ListContextScopes),
+            _ => {
+                let usage = match cmd.argument_hint() {
+                    Some(hint) => format!("Usage: /{} {hint}", cmd.command()),
+                    None => format!("'/{}' does not take arguments.", cmd.command()),
+                };
+                self.add_info_message(usage, None);
+            }
+        }
+    }
+
     fn dispatch_command(&mut self, cmd: SlashCommand) {
         if !cmd.available_during_task() && self.bottom_pane.is_task_running() {
             let message = format!(
@@ -1383,10 +1673,32 @@ impl ChatWidget {
                 const INIT_PROMPT: &str = include_str!("../prompt_for_init_command.md");
                 self.submit_user_message(INIT_PROMPT.to_string().into());
             }
+            SlashCommand::SyncBase => {
+                let Some(divergence) = self.base_divergence.as_ref() else {
+                    self.add_info_message(
+                        "No base branch divergence has been detected in this session.".to_string(),
+                        None,
+                    );
+                    return;
+                };
+                let prompt = sync_base_prompt(divergence);
+                self.submit_user_message(prompt.into());
+            }
             SlashCommand::Compact => {
                 self.clear_token_usage();
                 self.app_event_tx.send(AppEvent::CodexOp(Op::Compact));
             }
+            SlashCommand::Pin => {
+                self.app_event_tx.send(AppEvent::PinLastUserMessage);
+            }
+            SlashCommand::Plan => {
+                let message = if self.bottom_pane.toggle_plan_pinned() {
+                    "Plan pinned above the composer; run /plan again to unpin it."
+                } else {
+                    "Plan unpinned."
+                };
+                self.add_info_message(message.to_string(), None);
+            }
             SlashCommand::Review => {
                 self.open_review_popup();
             }
@@ -1396,7 +1708,7 @@ impl ChatWidget {
             SlashCommand::Approvals => {
                 self.open_approvals_popup();
             }
-            SlashCommand::Quit | SlashCommand::Exit => {
+            SlashCommand::Quit => {
                 self.request_exit();
             }
             SlashCommand::Logout => {
@@ -1434,9 +1746,42 @@ impl ChatWidget {
             SlashCommand::Status => {
                 self.add_status_output();
             }
+            SlashCommand::Context => {
+                self.dispatch_command_with_args(cmd, "");
+            }
+            SlashCommand::Env => {
+                let Some(tools) = self.config.tool_inventory.clone() else {
+                    self.add_info_message(
+                        "Tool inventory is disabled.".to_string(),
+                        Some("Set `[tool_inventory] enabled = true` in config.toml.".to_string()),
+                    );
+                    return;
+                };
+                let codex_home = self.config.persistence.cache_home(&self.config.codex_home);
+                let tx = self.app_event_tx.clone();
+                tokio::spawn(async move {
+                    let inventory = load_or_probe(&codex_home, &tools).await;
+                    tx.send(AppEvent::InsertHistoryCell(Box::new(
+                        history_cell::new_tool_inventory_output(&inventory),
+                    )));
+                });
+            }
+            SlashCommand::Cloud => {
+                self.add_cloud_tasks_output();
+            }
             SlashCommand::Mcp => {
                 self.add_mcp_output();
             }
+            SlashCommand::Clear => {
+                self.submit_op(Op::SetHistoryFlag {
+                    flag: HistoryFlag::ClearedAbove,
+                });
+            }
+            SlashCommand::Unclear => {
+                self.submit_op(Op::SetHistoryFlag {
+                    flag: HistoryFlag::Uncleared,
+                });
+            }
             SlashCommand::Rollout => {
                 if let Some(path) = self.rollout_path() {
                     self.add_info_message(
@@ -1489,9 +1834,65 @@ impl ChatWidget {
     }
 
     pub(crate) fn handle_paste(&mut self, text: String) {
+        // Terminals send an empty bracketed paste when the clipboard holds
+        // only an image (e.g. a screenshot).
+        if text.is_empty() {
+            self.paste_image_from_clipboard(true);
+            return;
+        }
         self.bottom_pane.handle_paste(text);
     }
 
+    /// Read an image off the clipboard on a background thread; large images
+    /// and slow clipboard tools must not stall the UI. The result comes back
+    /// as [`AppEvent::ClipboardImagePasted`].
+    fn paste_image_from_clipboard(&self, from_empty_paste: bool) {
+        let tx = self.app_event_tx.clone();
+        let max_bytes = self.config.tui_paste_image_max_bytes;
+        let dir = self.pasted_images_dir();
+        std::thread::spawn(move || {
+            let result = paste_image_to_temp_file(max_bytes, &dir);
+            tx.send(AppEvent::ClipboardImagePasted {
+                result,
+                from_empty_paste,
+            });
+        });
+    }
+
+    pub(crate) fn on_clipboard_image_pasted(
+        &mut self,
+        result: Result<(PathBuf, PastedImageInfo), PasteImageError>,
+        from_empty_paste: bool,
+    ) {
+        match result {
+            Ok((path, info)) => {
+                tracing::info!("attach pasted image path={path:?} {}", info.chip_label());
+                self.bottom_pane
+                    .attach_pasted_image(path, info.chip_label());
+                self.request_redraw();
+            }
+            Err(err @ PasteImageError::ClipboardToolsMissing) => {
+                tracing::warn!("failed to paste image: {err}");
+                if !self.clipboard_tools_hint_shown {
+                    self.clipboard_tools_hint_shown = true;
+                    self.add_info_message(
+                        "Pasting images is unavailable".to_string(),
+                        Some(err.to_string()),
+                    );
+                }
+            }
+            // An empty paste with nothing on the clipboard is not worth an error.
+            Err(PasteImageError::NoImage(_) | PasteImageError::ClipboardUnavailable(_))
+                if from_empty_paste => {}
+            Err(err) => {
+                tracing::warn!("failed to paste image: {err}");
+                self.add_to_history(history_cell::new_error_event(format!(
+                    "Failed to paste image: {err}",
+                )));
+            }
+        }
+    }
+
     // Returns true if caller should skip rendering this frame (a future frame is scheduled).
     pub(crate) fn handle_paste_burst_tick(&mut self, frame_requester: FrameRequester) -> bool {
         if self.bottom_pane.flush_paste_burst_if_due() {
@@ -1530,6 +1931,14 @@ impl ChatWidget {
         self.app_event_tx.send(AppEvent::InsertHistoryCell(cell));
     }
 
+    fn begin_turn(&mut self) {
+        self.app_event_tx.send(AppEvent::TurnStarted(TurnMarker {
+            turn_index: self.turns_started,
+            started_at: Local::now(),
+        }));
+        self.turns_started += 1;
+    }
+
     fn queue_user_message(&mut self, user_message: UserMessage) {
         if self.bottom_pane.is_task_running() {
             self.queued_user_messages.push_back(user_message);
@@ -1539,8 +1948,138 @@ impl ChatWidget {
         }
     }
 
+    /// Queue a message expanded from a saved prompt. A model or effort from
+    /// the prompt's front-matter applies to its turn right away; an approval
+    /// preset is only applied after the user confirms it.
+    fn queue_prompt_message(&mut self, prompt: &CustomPrompt, mut user_message: UserMessage) {
+        user_message.overrides.model = prompt.model.clone();
+        user_message.overrides.effort = prompt.effort;
+
+        let Some(preset_id) = prompt.approval_preset.as_deref() else {
+            self.queue_user_message(user_message);
+            return;
+        };
+        let Some(preset) = builtin_approval_presets()
+            .into_iter()
+            .find(|preset| preset.id == preset_id)
+        else {
+            self.on_warning(format!(
+                "/{PROMPTS_CMD_PREFIX}:{} asks for unknown approval preset `{preset_id}`; running with the current approval mode.",
+                prompt.name
+            ));
+            self.queue_user_message(user_message);
+            return;
+        };
+        if preset.approval == self.config.approval_policy
+            && preset.sandbox == self.config.sandbox_policy
+        {
+            self.queue_user_message(user_message);
+            return;
+        }
+
+        self.open_prompt_approval_preset_confirmation(&prompt.name, &preset);
+        self.pending_prompt_preset = Some((user_message, preset));
+    }
+
+    fn open_prompt_approval_preset_confirmation(
+        &mut self,
+        prompt_name: &str,
+        preset: &ApprovalPreset,
+    ) {
+        let decision_actions = |decision: PromptPresetDecision| -> Vec<SelectionAction> {
+            vec![Box::new(move |tx| {
+                tx.send(AppEvent::ResolvePromptApprovalPreset(decision));
+            })]
+        };
+        let label = preset.label;
+        let items = vec![
+            SelectionItem {
+                name: format!("Run with {label} for this turn"),
+                description: Some(preset.description.to_string()),
+                actions: decision_actions(PromptPresetDecision::Apply),
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Run with the current approval mode".to_string(),
+                actions: decision_actions(PromptPresetDecision::Ignore),
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Cancel".to_string(),
+                description: Some("Put the prompt back in the composer".to_string()),
+                actions: decision_actions(PromptPresetDecision::Cancel),
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+        ];
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some(format!(
+                "/{PROMPTS_CMD_PREFIX}:{prompt_name} asks to run in {label} mode"
+            )),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn resolve_prompt_approval_preset(&mut self, decision: PromptPresetDecision) {
+        let Some((mut user_message, preset)) = self.pending_prompt_preset.take() else {
+            return;
+        };
+        match decision {
+            PromptPresetDecision::Apply => {
+                user_message.overrides.approval = Some((preset.approval, preset.sandbox));
+                self.queue_user_message(user_message);
+            }
+            PromptPresetDecision::Ignore => self.queue_user_message(user_message),
+            PromptPresetDecision::Cancel => {
+                self.bottom_pane.set_composer_text(user_message.text);
+            }
+        }
+    }
+
+    /// Send the front-matter overrides for the turn about to start.
+    fn apply_turn_overrides(&mut self, overrides: TurnOverrides) {
+        if overrides.is_empty() {
+            return;
+        }
+        let (approval_policy, sandbox_policy) = overrides.approval.clone().unzip();
+        self.submit_op(Op::OverrideTurnContext {
+            cwd: None,
+            approval_policy,
+            sandbox_policy,
+            model: overrides.model.clone(),
+            effort: overrides.effort.map(Some),
+            summary: None,
+        });
+        self.active_turn_overrides = Some(overrides);
+    }
+
+    /// Put back the configured settings that a prompt overrode for the turn
+    /// that just ended.
+    fn restore_turn_overrides(&mut self) {
+        let Some(overrides) = self.active_turn_overrides.take() else {
+            return;
+        };
+        let restore_approval = overrides.approval.is_some();
+        self.submit_op(Op::OverrideTurnContext {
+            cwd: None,
+            approval_policy: restore_approval.then_some(self.config.approval_policy),
+            sandbox_policy: restore_approval.then(|| self.config.sandbox_policy.clone()),
+            model: overrides.model.map(|_| self.config.model.clone()),
+            effort: overrides.effort.map(|_| self.config.model_reasoning_effort),
+            summary: None,
+        });
+    }
+
     fn submit_user_message(&mut self, user_message: UserMessage) {
-        let UserMessage { text, image_paths } = user_message;
+        let UserMessage {
+            text,
+            image_paths,
+            overrides,
+        } = user_message;
         if text.is_empty() && image_paths.is_empty() {
             return;
         }
@@ -1573,6 +2112,8 @@ impl ChatWidget {
             items.push(UserInput::LocalImage { path });
         }
 
+        self.apply_turn_overrides(overrides);
+        self.begin_turn();
         self.codex_op_tx
             .send(Op::UserInput { items })
             .unwrap_or_else(|e| {
@@ -1610,6 +2151,15 @@ impl ChatWidget {
         }
     }
 
+    /// Feed one recorded event through the same path used for resumed
+    /// sessions. Used by `codex replay`.
+    pub(crate) fn replay_event(&mut self, msg: EventMsg) {
+        if matches!(msg, EventMsg::SessionConfigured(_)) {
+            return;
+        }
+        self.dispatch_event_msg(None, msg, true);
+    }
+
     pub(crate) fn handle_codex_event(&mut self, event: Event) {
         let Event { id, msg } = event;
         self.dispatch_event_msg(Some(id), msg, false);
@@ -1651,10 +2201,12 @@ impl ChatWidget {
                 self.on_task_complete(last_agent_message)
             }
             EventMsg::TokenCount(ev) => {
+                self.bottom_pane.set_context_window_estimated(ev.estimated);
                 self.set_token_info(ev.info);
                 self.on_rate_limit_snapshot(ev.rate_limits);
             }
             EventMsg::Warning(WarningEvent { message }) => self.on_warning(message),
+            EventMsg::TurnLimitReached(ev) => self.on_turn_limit_reached(ev),
             EventMsg::Error(ErrorEvent { message }) => self.on_error(message),
             EventMsg::McpStartupUpdate(ev) => self.on_mcp_startup_update(ev),
             EventMsg::McpStartupComplete(ev) => self.on_mcp_startup_complete(ev),
@@ -1674,9 +2226,12 @@ impl ChatWidget {
                 // For replayed events, synthesize an empty id (these should not occur).
                 self.on_exec_approval_request(id.unwrap_or_default(), ev)
             }
+            EventMsg::ExecApprovalExplanation(ev) => self.on_exec_approval_explanation(ev),
             EventMsg::ApplyPatchApprovalRequest(ev) => {
                 self.on_apply_patch_approval_request(id.unwrap_or_default(), ev)
             }
+            EventMsg::SandboxDenied(ev) => self.on_sandbox_denied(ev),
+            EventMsg::ExecInputRequest(ev) => self.on_exec_input_request(ev),
             EventMsg::ExecCommandBegin(ev) => self.on_exec_command_begin(ev),
             EventMsg::ExecCommandOutputDelta(delta) => self.on_exec_command_output_delta(delta),
             EventMsg::PatchApplyBegin(ev) => self.on_patch_apply_begin(ev),
@@ -1690,14 +2245,20 @@ impl ChatWidget {
             EventMsg::GetHistoryEntryResponse(ev) => self.on_get_history_entry_response(ev),
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
+            EventMsg::ListContextScopesResponse(ev) => self.on_list_context_scopes(ev),
             EventMsg::ShutdownComplete => self.on_shutdown_complete(),
-            EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => self.on_turn_diff(unified_diff),
+            EventMsg::TurnDiff(TurnDiffEvent { unified_diff, .. }) => {
+                self.on_turn_diff(unified_diff)
+            }
             EventMsg::DeprecationNotice(ev) => self.on_deprecation_notice(ev),
+            EventMsg::BaseDivergence(ev) => self.on_base_divergence(ev),
+            EventMsg::ResumeBriefing(ev) => self.on_resume_briefing(ev),
             EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
                 self.on_background_event(message)
             }
             EventMsg::UndoStarted(ev) => self.on_undo_started(ev),
             EventMsg::UndoCompleted(ev) => self.on_undo_completed(ev),
+            EventMsg::HistoryFlagSet(ev) => self.on_history_flag_set(ev),
             EventMsg::StreamError(StreamErrorEvent { message }) => self.on_stream_error(message),
             EventMsg::UserMessage(ev) => {
                 if from_replay {
@@ -1751,13 +2312,10 @@ impl ChatWidget {
                         .send(AppEvent::InsertHistoryCell(Box::new(body_cell)));
                 }
             } else {
-                let message_text =
-                    codex_core::review_format::format_review_findings_block(&output.findings, None);
-                let mut message_lines: Vec<ratatui::text::Line<'static>> = Vec::new();
-                append_markdown(&message_text, None, &mut message_lines);
-                let body_cell = AgentMessageCell::new(message_lines, true);
+                // Findings are relocated to where the code is now.
+                let findings_cell = history_cell::ReviewFindingsCell::new(output.findings);
                 self.app_event_tx
-                    .send(AppEvent::InsertHistoryCell(Box::new(body_cell)));
+                    .send(AppEvent::InsertHistoryCell(Box::new(findings_cell)));
             }
         }
 
@@ -1771,6 +2329,7 @@ impl ChatWidget {
     }
 
     fn on_user_message_event(&mut self, event: UserMessageEvent) {
+        self.begin_turn();
         let message = event.message.trim();
         if !message.is_empty() {
             self.add_to_history(history_cell::new_user_prompt(message.to_string()));
@@ -1793,6 +2352,23 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    pub(crate) fn terminal_title_status(&self) -> TitleStatus {
+        if self.bottom_pane.is_awaiting_approval() {
+            TitleStatus::AwaitingApproval
+        } else if self.bottom_pane.is_task_running() {
+            TitleStatus::Working {
+                elapsed_secs: self
+                    .bottom_pane
+                    .status_widget()
+                    .map_or(0, StatusIndicatorWidget::elapsed_seconds),
+            }
+        } else if self.last_turn_failed {
+            TitleStatus::Error
+        } else {
+            TitleStatus::Idle
+        }
+    }
+
     pub(crate) fn maybe_post_pending_notification(&mut self, tui: &mut crate::tui::Tui) {
         if let Some(notif) = self.pending_notification.take() {
             tui.notify(notif.display());
@@ -1849,13 +2425,22 @@ impl ChatWidget {
         } else {
             (&default_usage, Some(&default_usage))
         };
+        let rate_limits = self.rate_limit_snapshot.clone().map(|mut display| {
+            let samples = load_samples(&self.config.codex_home);
+            display.trend = crate::status::compose_rate_limit_trend(
+                &samples,
+                &self.config.model_provider_id,
+                Utc::now(),
+            );
+            display
+        });
         self.add_to_history(crate::status::new_status_output(
             &self.config,
             self.auth_manager.as_ref(),
             total_usage,
             context_usage,
             &self.conversation_id,
-            self.rate_limit_snapshot.as_ref(),
+            rate_limits.as_ref(),
             Local::now(),
         ));
     }
@@ -1892,6 +2477,60 @@ impl ChatWidget {
         self.rate_limit_poller = Some(handle);
     }
 
+    fn start_cloud_task_poller(&mut self) {
+        self.stop_cloud_task_poller();
+        let Some(interval) = self.config.tui_cloud_tasks_poll_interval else {
+            return;
+        };
+        self.cloud_task_poller = Some(spawn_cloud_task_poller(
+            interval,
+            self.config.chatgpt_base_url.clone(),
+            self.config.network_policy(),
+            self.auth_manager.clone(),
+            self.app_event_tx.clone(),
+        ));
+    }
+
+    fn stop_cloud_task_poller(&mut self) {
+        if let Some(handle) = self.cloud_task_poller.take() {
+            handle.abort();
+        }
+    }
+
+    pub(crate) fn on_cloud_tasks_polled(
+        &mut self,
+        tasks: Vec<TaskSummary>,
+        transitions: Vec<CloudTaskTransition>,
+    ) {
+        self.cloud_tasks = Some(tasks);
+        if transitions.is_empty() {
+            return;
+        }
+        self.bottom_pane.add_cloud_task_updates(transitions.len());
+        for transition in &transitions {
+            self.add_to_history(history_cell::new_cloud_task_transition(transition));
+        }
+        self.request_redraw();
+    }
+
+    fn add_cloud_tasks_output(&mut self) {
+        self.bottom_pane.clear_cloud_task_updates();
+        if self.config.tui_cloud_tasks_poll_interval.is_none() {
+            self.add_info_message(
+                "Cloud task polling is disabled.".to_string(),
+                Some("Set `[tui] cloud_tasks_poll_interval_secs` in config.toml.".to_string()),
+            );
+            return;
+        }
+        let Some(tasks) = self.cloud_tasks.as_deref() else {
+            self.add_info_message("Cloud tasks have not been fetched yet.".to_string(), None);
+            return;
+        };
+        let cell = history_cell::new_cloud_tasks_output(tasks);
+        self.add_to_history(cell);
+        self.request_redraw();
+    }
+
     fn lower_cost_preset(&self) -> Option<ModelPreset> {
         let auth_mode = self.auth_manager.auth().map(|auth| auth.mode);
         builtin_model_presets(auth_mode)
@@ -2648,6 +3287,11 @@ impl ChatWidget {
         self.config.model = model.to_string();
     }
 
+    /// Show the onboarding trust decision right after the session header.
+    pub(crate) fn set_trust_summary(&mut self, lines: Vec<Line<'static>>) {
+        self.pending_trust_summary = Some(lines);
+    }
+
     pub(crate) fn add_info_message(&mut self, message: String, hint: Option<String>) {
         self.add_to_history(history_cell::new_info_event(message, hint));
         self.request_redraw();
@@ -2672,8 +3316,14 @@ impl ChatWidget {
     }
 
     /// Forward file-search results to the bottom pane.
-    pub(crate) fn apply_file_search_result(&mut self, query: String, matches: Vec<FileMatch>) {
-        self.bottom_pane.on_file_search_result(query, matches);
+    pub(crate) fn apply_file_search_result(
+        &mut self,
+        query: String,
+        matches: Vec<FileMatch>,
+        truncated_walk: bool,
+    ) {
+        self.bottom_pane
+            .on_file_search_result(query, matches, truncated_walk);
     }
 
     /// Handle Ctrl-C key press.
@@ -2711,6 +3361,12 @@ impl ChatWidget {
         self.bottom_pane.set_composer_text(text);
     }
 
+    /// Answer the patch approval `id` with the hunks picked in the hunk
+    /// picker.
+    pub(crate) fn apply_patch_selection(&mut self, id: &str, selection: &PatchSelection) {
+        self.bottom_pane.apply_patch_selection(id, selection);
+    }
+
     pub(crate) fn show_esc_backtrack_hint(&mut self) {
         self.bottom_pane.show_esc_backtrack_hint();
     }
@@ -2740,10 +3396,19 @@ impl ChatWidget {
     fn on_list_custom_prompts(&mut self, ev: ListCustomPromptsResponseEvent) {
         let len = ev.custom_prompts.len();
         debug!("received {len} custom prompts");
+        for prompt in &ev.custom_prompts {
+            if let Some(warning) = &prompt.frontmatter_warning {
+                self.on_warning(format!("{}: {warning}", prompt.path.display()));
+            }
+        }
         // Forward to bottom pane so the slash popup can show them now.
         self.bottom_pane.set_custom_prompts(ev.custom_prompts);
     }
 
+    fn on_list_context_scopes(&mut self, ev: ListContextScopesResponseEvent) {
+        self.add_to_history(history_cell::new_context_scopes_output(ev.scopes));
+    }
+
     pub(crate) fn open_review_popup(&mut self) {
         let mut items: Vec<SelectionItem> = Vec::new();
 
@@ -2886,6 +3551,33 @@ impl ChatWidget {
         });
     }
 
+    /// Open the Ctrl+R search over prompts from the history file and from this
+    /// session, whose prompts are not persisted when history is disabled.
+    /// Load the persisted prompt history in the background; the search view
+    /// opens once [`AppEvent::HistoryEntriesLoaded`] comes back.
+    fn open_history_search(&mut self) {
+        let codex_home = self.config.codex_home.clone();
+        let tx = self.app_event_tx.clone();
+        tokio::spawn(async move {
+            let entries = load_entries(&codex_home)
+                .await
+                .into_iter()
+                .map(|entry| entry.text)
+                .collect();
+            tx.send(AppEvent::HistoryEntriesLoaded(entries));
+        });
+    }
+
+    /// Open the Ctrl+R search over the persisted `entries` followed by the
+    /// prompts submitted in this session.
+    pub(crate) fn show_history_search(&mut self, mut history: Vec<String>) {
+        history.extend(self.bottom_pane.local_history().iter().cloned());
+        self.bottom_pane.show_view(Box::new(HistorySearchView::new(
+            history,
+            self.app_event_tx.clone(),
+        )));
+    }
+
     pub(crate) fn show_review_custom_prompt(&mut self) {
         let tx = self.app_event_tx.clone();
         let view = CustomPromptView::new(
@@ -2924,6 +3616,28 @@ impl ChatWidget {
         self.current_rollout_path.clone()
     }
 
+    /// Working directory of the current session, which may differ from the
+    /// one Codex was started in (e.g. after resuming a session).
+    pub(crate) fn session_cwd(&self) -> &Path {
+        self.session_cwd.as_deref().unwrap_or(&self.config.cwd)
+    }
+
+    /// Where clipboard images are written: next to the session's rollout
+    /// (`rollout-….jsonl` → `rollout-…-images/`), or the system temp dir
+    /// before the session is configured.
+    fn pasted_images_dir(&self) -> PathBuf {
+        match &self.current_rollout_path {
+            Some(rollout) => {
+                let stem = rollout
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "session".to_string());
+                rollout.with_file_name(format!("{stem}-images"))
+            }
+            None => std::env::temp_dir(),
+        }
+    }
+
     /// Return a reference to the widget's current config (includes any
     /// runtime overrides applied via TUI, e.g., model or approval policy).
     pub(crate) fn config_ref(&self) -> &Config {
@@ -2952,20 +3666,36 @@ impl ChatWidget {
 impl Drop for ChatWidget {
     fn drop(&mut self) {
         self.stop_rate_limit_poller();
+        self.stop_cloud_task_poller();
     }
 }
 
+/// Shown instead of the chat view below [`MIN_RENDER_WIDTH`].
+fn too_narrow_notice() -> Paragraph<'static> {
+    Paragraph::new(Line::from("Terminal too narrow").dim()).wrap(Wrap { trim: true })
+}
+
 impl Renderable for ChatWidget {
     fn render(&self, area: Rect, buf: &mut Buffer) {
-        self.as_renderable().render(area, buf);
+        if area.width < MIN_RENDER_WIDTH {
+            too_narrow_notice().render(area, buf);
+        } else {
+            self.as_renderable().render(area, buf);
+        }
         self.last_rendered_width.set(Some(area.width as usize));
     }
 
     fn desired_height(&self, width: u16) -> u16 {
+        if width < MIN_RENDER_WIDTH {
+            return too_narrow_notice().desired_height(width);
+        }
         self.as_renderable().desired_height(width)
     }
 
     fn cursor_pos(&self, area: Rect) -> Option<(u16, u16)> {
+        if area.width < MIN_RENDER_WIDTH {
+            return None;
+        }
         self.as_renderable().cursor_pos(area)
     }
 }
@@ -3073,6 +3803,21 @@ fn extract_first_bold(s: &str) -> Option<String> {
     None
 }
 
+/// Prompt for `/sync-base`. The agent runs the fetch and rebase itself so the
+/// commands go through the usual approval flow.
+fn sync_base_prompt(divergence: &BaseDivergenceEvent) -> String {
+    let upstream = &divergence.upstream;
+    let fetch = match upstream.split_once('/') {
+        Some((remote, _)) => format!("git fetch {remote}"),
+        None => "git fetch".to_string(),
+    };
+    format!(
+        "This worktree is behind `{upstream}`. Run `{fetch}`, then `git rebase {upstream}`. \
+         If there are uncommitted changes, or the rebase stops on conflicts, stop \
+         (running `git rebase --abort` if needed) and tell me which files are affected."
+    )
+}
+
 async fn fetch_rate_limits(base_url: String, auth: CodexAuth) -> Option<RateLimitSnapshot> {
     match BackendClient::from_auth(base_url, &auth).await {
         Ok(client) => match client.get_rate_limits().await {
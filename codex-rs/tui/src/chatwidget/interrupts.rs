@@ -1,6 +1,7 @@
 use std::collections::VecDeque;
 
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
+use codex_core::protocol::ExecApprovalExplanationEvent;
 use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
@@ -13,6 +14,7 @@ use super::ChatWidget;
 #[derive(Debug)]
 pub(crate) enum QueuedInterrupt {
     ExecApproval(String, ExecApprovalRequestEvent),
+    ExecApprovalExplanation(ExecApprovalExplanationEvent),
     ApplyPatchApproval(String, ApplyPatchApprovalRequestEvent),
     ExecBegin(ExecCommandBeginEvent),
     ExecEnd(ExecCommandEndEvent),
@@ -42,6 +44,11 @@ impl InterruptManager {
         self.queue.push_back(QueuedInterrupt::ExecApproval(id, ev));
     }
 
+    pub(crate) fn push_exec_approval_explanation(&mut self, ev: ExecApprovalExplanationEvent) {
+        self.queue
+            .push_back(QueuedInterrupt::ExecApprovalExplanation(ev));
+    }
+
     pub(crate) fn push_apply_patch_approval(
         &mut self,
         id: String,
@@ -75,6 +82,9 @@ impl InterruptManager {
         while let Some(q) = self.queue.pop_front() {
             match q {
                 QueuedInterrupt::ExecApproval(id, ev) => chat.handle_exec_approval_now(id, ev),
+                QueuedInterrupt::ExecApprovalExplanation(ev) => {
+                    chat.handle_exec_approval_explanation_now(ev)
+                }
                 QueuedInterrupt::ApplyPatchApproval(id, ev) => {
                     chat.handle_apply_patch_approval_now(id, ev)
                 }
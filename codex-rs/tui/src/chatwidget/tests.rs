@@ -4,6 +4,9 @@ use crate::app_event_sender::AppEventSender;
 use crate::test_backend::VT100Backend;
 use crate::tui::FrameRequester;
 use assert_matches::assert_matches;
+use codex_cloud_tasks_client::DiffSummary;
+use codex_cloud_tasks_client::TaskId;
+use codex_cloud_tasks_client::TaskStatus;
 use codex_common::approval_presets::builtin_approval_presets;
 use codex_common::model_presets::ModelPreset;
 use codex_common::model_presets::ReasoningEffortPreset;
@@ -18,18 +21,22 @@ use codex_core::protocol::AgentReasoningDeltaEvent;
 use codex_core::protocol::AgentReasoningEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::BaseDivergenceEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::ExecCommandSource;
+use codex_core::protocol::ExecInputRequestEvent;
+use codex_core::protocol::ExecInputResponse;
 use codex_core::protocol::ExitedReviewModeEvent;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
 use codex_core::protocol::RateLimitWindow;
+use codex_core::protocol::ResumeBriefingEvent;
 use codex_core::protocol::ReviewCodeLocation;
 use codex_core::protocol::ReviewFinding;
 use codex_core::protocol::ReviewLineRange;
@@ -41,6 +48,8 @@ use codex_core::protocol::TaskStartedEvent;
 use codex_core::protocol::TokenCountEvent;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol::TokenUsageInfo;
+use codex_core::protocol::TurnLimitKind;
+use codex_core::protocol::TurnLimitReachedEvent;
 use codex_core::protocol::UndoCompletedEvent;
 use codex_core::protocol::UndoStartedEvent;
 use codex_core::protocol::ViewImageToolCallEvent;
@@ -198,6 +207,7 @@ fn exited_review_mode_emits_results_and_finishes() {
             code_location: ReviewCodeLocation {
                 absolute_file_path: PathBuf::from("src/lib.rs"),
                 line_range: ReviewLineRange { start: 10, end: 12 },
+                anchor: None,
             },
         }],
         overall_correctness: "needs work".to_string(),
@@ -232,6 +242,8 @@ fn review_restores_context_window_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(make_token_info(pre_review_tokens, context_window)),
             rate_limits: None,
+            model: None,
+            estimated: false,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), Some(30));
@@ -250,6 +262,8 @@ fn review_restores_context_window_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(make_token_info(review_tokens, context_window)),
             rate_limits: None,
+            model: None,
+            estimated: false,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), Some(97));
@@ -279,6 +293,8 @@ fn token_count_none_resets_context_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(make_token_info(pre_compact_tokens, context_window)),
             rate_limits: None,
+            model: None,
+            estimated: false,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), Some(30));
@@ -288,6 +304,8 @@ fn token_count_none_resets_context_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: None,
             rate_limits: None,
+            model: None,
+            estimated: false,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), None);
@@ -352,8 +370,12 @@ fn make_chatwidget_manual() -> (
         token_info: None,
         rate_limit_snapshot: None,
         rate_limit_warnings: RateLimitWarningState::default(),
+        rate_limit_alerts: CriticalHeadroomAlerts::default(),
         rate_limit_switch_prompt: RateLimitSwitchPromptState::default(),
         rate_limit_poller: None,
+        cloud_task_poller: None,
+        cloud_tasks: None,
+        base_divergence: None,
         stream_controller: None,
         running_commands: HashMap::new(),
         task_complete_pending: false,
@@ -366,15 +388,22 @@ fn make_chatwidget_manual() -> (
         conversation_id: None,
         frame_requester: FrameRequester::test_dummy(),
         show_welcome_banner: true,
+        pending_trust_summary: None,
         queued_user_messages: VecDeque::new(),
         suppress_session_configured_redraw: false,
         pending_notification: None,
         is_review_mode: false,
         pre_review_token_info: None,
         needs_final_message_separator: false,
+        turns_started: 0,
+        last_turn_failed: false,
+        pending_prompt_preset: None,
+        active_turn_overrides: None,
         last_rendered_width: std::cell::Cell::new(None),
         feedback: codex_feedback::CodexFeedback::new(),
         current_rollout_path: None,
+        session_cwd: None,
+        clipboard_tools_hint_shown: false,
     };
     (widget, rx, op_rx)
 }
@@ -588,6 +617,8 @@ fn exec_approval_emits_proposed_command_and_decision_history() {
         ),
         risk: None,
         parsed_cmd: vec![],
+        network: None,
+        explanation_pending: false,
     };
     chat.handle_codex_event(Event {
         id: "sub-short".into(),
@@ -632,6 +663,8 @@ fn exec_approval_decision_truncates_multiline_and_long_commands() {
         ),
         risk: None,
         parsed_cmd: vec![],
+        network: None,
+        explanation_pending: false,
     };
     chat.handle_codex_event(Event {
         id: "sub-multi".into(),
@@ -682,6 +715,8 @@ fn exec_approval_decision_truncates_multiline_and_long_commands() {
         reason: None,
         risk: None,
         parsed_cmd: vec![],
+        network: None,
+        explanation_pending: false,
     };
     chat.handle_codex_event(Event {
         id: "sub-long".into(),
@@ -723,6 +758,7 @@ fn begin_exec_with_source(
         parsed_cmd,
         source,
         interaction_input,
+        filtered_env_vars: None,
     };
     chat.handle_codex_event(Event {
         id: call_id.to_string(),
@@ -755,6 +791,7 @@ fn end_exec(
         parsed_cmd,
         source,
         interaction_input,
+        filtered_env_vars: _,
     } = begin_event;
     chat.handle_codex_event(Event {
         id: call_id.clone(),
@@ -769,6 +806,7 @@ fn end_exec(
             stdout: stdout.to_string(),
             stderr: stderr.to_string(),
             aggregated_output: aggregated.clone(),
+            stderr_ranges: Vec::new(),
             exit_code,
             duration: std::time::Duration::from_millis(5),
             formatted_output: aggregated,
@@ -934,6 +972,51 @@ fn ctrl_c_cleared_prompt_is_recoverable_via_history() {
     );
 }
 
+#[tokio::test]
+async fn ctrl_r_recalls_a_persisted_prompt_into_the_composer() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    let codex_home = tempdir().expect("tempdir");
+    let multi_line = "explain the\nretry loop\n\n  in detail";
+    let history: String = ["fix the build", multi_line, "write release notes"]
+        .into_iter()
+        .enumerate()
+        .map(|(ts, text)| {
+            let entry = codex_core::message_history::HistoryEntry {
+                version: codex_core::message_history::HISTORY_FORMAT_VERSION,
+                session_id: "session".to_string(),
+                ts: ts as u64,
+                text: text.to_string(),
+            };
+            format!("{}\n", serde_json::to_string(&entry).expect("serialize"))
+        })
+        .collect();
+    std::fs::write(codex_home.path().join("history.jsonl"), history).expect("seed history");
+    chat.config.codex_home = codex_home.path().to_path_buf();
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+    let entries = loop {
+        match rx.recv().await.expect("history entries") {
+            AppEvent::HistoryEntriesLoaded(entries) => break entries,
+            _ => continue,
+        }
+    };
+    chat.show_history_search(entries);
+    for c in "retry".chars() {
+        chat.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    let recalled = std::iter::from_fn(|| rx.try_recv().ok())
+        .find_map(|event| match event {
+            AppEvent::RecallPrompt(text) => Some(text),
+            _ => None,
+        })
+        .expect("recalled prompt");
+    assert_eq!(recalled, multi_line);
+    chat.set_composer_text(recalled);
+    assert_eq!(chat.bottom_pane.composer_text(), multi_line);
+}
+
 #[test]
 fn exec_history_cell_shows_working_then_completed() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
@@ -1082,11 +1165,160 @@ fn slash_quit_requests_exit() {
     assert_matches!(rx.try_recv(), Ok(AppEvent::ExitRequest));
 }
 
+fn cloud_task(id: &str, title: &str, status: TaskStatus) -> TaskSummary {
+    TaskSummary {
+        id: TaskId(id.to_string()),
+        title: title.to_string(),
+        status,
+        updated_at: chrono::Utc::now(),
+        environment_id: None,
+        environment_label: None,
+        summary: DiffSummary::default(),
+        is_review: false,
+        attempt_total: None,
+    }
+}
+
+#[test]
+fn cloud_task_transitions_badge_until_cloud_view_opens() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui_cloud_tasks_poll_interval = Some(Duration::from_secs(60));
+    let tasks = vec![
+        cloud_task("a", "Fix flaky test", TaskStatus::Running),
+        cloud_task("b", "Bump deps", TaskStatus::Error),
+    ];
+
+    chat.on_cloud_tasks_polled(
+        tasks.clone(),
+        vec![
+            CloudTaskTransition {
+                title: "Fix flaky test".to_string(),
+                from: Some(TaskStatus::Pending),
+                to: TaskStatus::Running,
+            },
+            CloudTaskTransition {
+                title: "Bump deps".to_string(),
+                from: Some(TaskStatus::Running),
+                to: TaskStatus::Error,
+            },
+        ],
+    );
+    assert_eq!(chat.bottom_pane.cloud_task_updates(), 2);
+    let rendered: Vec<String> = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect();
+    assert_eq!(
+        rendered,
+        vec![
+            "• Cloud task Fix flaky test queued → running\n".to_string(),
+            "\n• Cloud task Bump deps running → failed\n".to_string(),
+        ]
+    );
+
+    // Polls without changes leave the badge alone.
+    chat.on_cloud_tasks_polled(tasks, Vec::new());
+    assert_eq!(chat.bottom_pane.cloud_task_updates(), 2);
+    assert!(drain_insert_history(&mut rx).is_empty());
+
+    chat.dispatch_command(SlashCommand::Cloud);
+    assert_eq!(chat.bottom_pane.cloud_task_updates(), 0);
+    let rendered = lines_to_single_string(&drain_insert_history(&mut rx).concat());
+    assert!(rendered.contains("running Fix flaky test"), "{rendered}");
+    assert!(rendered.contains("failed Bump deps"), "{rendered}");
+}
+
+#[test]
+fn slash_cloud_explains_when_polling_is_disabled() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui_cloud_tasks_poll_interval = None;
+
+    chat.dispatch_command(SlashCommand::Cloud);
+
+    let rendered = lines_to_single_string(&drain_insert_history(&mut rx).concat());
+    assert!(
+        rendered.contains("Cloud task polling is disabled."),
+        "{rendered}"
+    );
+}
+
+#[test]
+fn base_divergence_notice_enables_sync_base() {
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual();
+
+    chat.dispatch_command(SlashCommand::SyncBase);
+    let rendered = lines_to_single_string(&drain_insert_history(&mut rx).concat());
+    assert!(
+        rendered.contains("No base branch divergence has been detected"),
+        "{rendered}"
+    );
+    assert_matches!(op_rx.try_recv(), Err(TryRecvError::Empty));
+
+    let divergence = BaseDivergenceEvent {
+        base: "main".to_string(),
+        upstream: "origin/main".to_string(),
+        behind: 14,
+        tip_subjects: vec![
+            "Merge pull request #42".to_string(),
+            "Bump deps".to_string(),
+        ],
+    };
+    chat.handle_codex_event(Event {
+        id: "initial".to_string(),
+        msg: EventMsg::BaseDivergence(divergence.clone()),
+    });
+    let rendered = lines_to_single_string(&drain_insert_history(&mut rx).concat());
+    assert_eq!(
+        rendered,
+        "⚠ This worktree is 14 commits behind origin/main (base main)\n  • Merge pull request #42\n  • Bump deps\n  … and 12 more\n  Run /sync-base to fetch and rebase.\n"
+    );
+
+    chat.dispatch_command(SlashCommand::SyncBase);
+    match op_rx.try_recv() {
+        Ok(Op::UserInput { items }) => assert_eq!(
+            items,
+            vec![UserInput::Text {
+                text: sync_base_prompt(&divergence),
+            }]
+        ),
+        other => panic!("expected Op::UserInput, got {other:?}"),
+    }
+    assert!(
+        sync_base_prompt(&divergence).contains("`git fetch origin`, then `git rebase origin/main`")
+    );
+}
+
+#[test]
+fn resume_briefing_lists_commits_and_conflicting_files() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+
+    chat.handle_codex_event(Event {
+        id: "initial".to_string(),
+        msg: EventMsg::ResumeBriefing(ResumeBriefingEvent {
+            previous_branch: Some("main".to_string()),
+            branch: Some("main".to_string()),
+            previous_head: Some("a".repeat(40)),
+            head: Some("b".repeat(40)),
+            new_commits: Some(3),
+            commit_subjects: vec!["Update CI".to_string(), "Merge #42".to_string()],
+            modified_files: vec!["src/lib.rs".to_string()],
+            deleted_files: vec!["old.rs".to_string()],
+            cleaned_files: Vec::new(),
+        }),
+    });
+
+    let rendered = lines_to_single_string(&drain_insert_history(&mut rx).concat());
+    assert_eq!(
+        rendered,
+        "↻ Since this session was last active\n  3 new commits\n    • Update CI\n    • Merge #42\n    … and 1 more\n  Changed by someone else since this session edited them:\n    • src/lib.rs\n  Deleted since this session edited them:\n    • old.rs\n"
+    );
+}
+
 #[test]
-fn slash_exit_requests_exit() {
+fn slash_exit_alias_requests_exit() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
 
-    chat.dispatch_command(SlashCommand::Exit);
+    chat.dispatch_command(SlashCommand::from_name("exit").expect("exit is an alias of quit"));
 
     assert_matches!(rx.try_recv(), Ok(AppEvent::ExitRequest));
 }
@@ -1736,6 +1968,36 @@ fn feedback_upload_consent_popup_snapshot() {
     assert_snapshot!("feedback_upload_consent_popup", popup);
 }
 
+#[test]
+fn exec_input_request_popup_snapshot() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+
+    chat.handle_codex_event(Event {
+        id: "sub-1".into(),
+        msg: EventMsg::ExecInputRequest(ExecInputRequestEvent {
+            call_id: "call-1".into(),
+            command: vec!["sudo".into(), "true".into()],
+            prompt: "Password:".into(),
+            accepts_input: true,
+            terminated: false,
+        }),
+    });
+
+    let popup = render_bottom_popup(&chat, 80);
+    assert_snapshot!("exec_input_request_popup", popup);
+
+    while rx.try_recv().is_ok() {}
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert_matches!(
+        rx.try_recv(),
+        Ok(AppEvent::CodexOp(Op::ExecInput {
+            id,
+            response: ExecInputResponse::Terminate,
+        })) if id == "call-1"
+    );
+}
+
 #[test]
 fn reasoning_popup_escape_returns_to_model_popup() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
@@ -1831,6 +2093,8 @@ fn approval_modal_exec_snapshot() {
         ),
         risk: None,
         parsed_cmd: vec![],
+        network: None,
+        explanation_pending: false,
     };
     chat.handle_codex_event(Event {
         id: "sub-approve".into(),
@@ -1877,6 +2141,8 @@ fn approval_modal_exec_without_reason_snapshot() {
         reason: None,
         risk: None,
         parsed_cmd: vec![],
+        network: None,
+        explanation_pending: false,
     };
     chat.handle_codex_event(Event {
         id: "sub-approve-noreason".into(),
@@ -2090,6 +2356,8 @@ fn status_widget_and_approval_modal_snapshot() {
         ),
         risk: None,
         parsed_cmd: vec![],
+        network: None,
+        explanation_pending: false,
     };
     chat.handle_codex_event(Event {
         id: "sub-approve-exec".into(),
@@ -2106,6 +2374,215 @@ fn status_widget_and_approval_modal_snapshot() {
     assert_snapshot!("status_widget_and_approval_modal", terminal.backend());
 }
 
+#[test]
+fn terminal_title_status_follows_session_state() {
+    use codex_core::protocol::ErrorEvent;
+    use codex_core::protocol::ExecApprovalRequestEvent;
+
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    assert_eq!(chat.terminal_title_status(), TitleStatus::Idle);
+
+    chat.handle_codex_event(Event {
+        id: "task-1".into(),
+        msg: EventMsg::TaskStarted(TaskStartedEvent {
+            model_context_window: None,
+        }),
+    });
+    assert!(matches!(
+        chat.terminal_title_status(),
+        TitleStatus::Working { .. }
+    ));
+
+    chat.handle_codex_event(Event {
+        id: "sub-approve".into(),
+        msg: EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
+            call_id: "call-approve".into(),
+            turn_id: "turn-approve".into(),
+            command: vec!["echo".into(), "hi".into()],
+            cwd: PathBuf::from("/tmp"),
+            reason: None,
+            risk: None,
+            parsed_cmd: vec![],
+            network: None,
+            explanation_pending: false,
+        }),
+    });
+    assert_eq!(chat.terminal_title_status(), TitleStatus::AwaitingApproval);
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+    assert!(matches!(
+        chat.terminal_title_status(),
+        TitleStatus::Working { .. }
+    ));
+
+    chat.handle_codex_event(Event {
+        id: "task-1".into(),
+        msg: EventMsg::Error(ErrorEvent {
+            message: "boom".into(),
+        }),
+    });
+    assert_eq!(chat.terminal_title_status(), TitleStatus::Error);
+
+    chat.handle_codex_event(Event {
+        id: "task-2".into(),
+        msg: EventMsg::TaskStarted(TaskStartedEvent {
+            model_context_window: None,
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "task-2".into(),
+        msg: EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message: None,
+        }),
+    });
+    assert_eq!(chat.terminal_title_status(), TitleStatus::Idle);
+}
+
+/// Ops that affect the turn, skipping history bookkeeping.
+fn drain_turn_ops(op_rx: &mut tokio::sync::mpsc::UnboundedReceiver<Op>) -> Vec<Op> {
+    let mut ops = Vec::new();
+    while let Ok(op) = op_rx.try_recv() {
+        if matches!(op, Op::OverrideTurnContext { .. } | Op::UserInput { .. }) {
+            ops.push(op);
+        }
+    }
+    ops
+}
+
+fn submit_custom_prompt(chat: &mut ChatWidget, prompt: CustomPrompt) {
+    let command = format!("/{PROMPTS_CMD_PREFIX}:{}", prompt.name);
+    chat.bottom_pane.set_custom_prompts(vec![prompt]);
+    chat.bottom_pane.set_composer_text(command);
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+}
+
+#[test]
+fn prompt_model_override_applies_to_its_turn_only() {
+    let (mut chat, _rx, mut op_rx) = make_chatwidget_manual();
+    submit_custom_prompt(
+        &mut chat,
+        CustomPrompt {
+            name: "quick".to_string(),
+            path: PathBuf::from("/tmp/quick.md"),
+            content: "Summarize the diff".to_string(),
+            model: Some("gpt-5-codex-mini".to_string()),
+            effort: Some(ReasoningEffortConfig::Low),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(
+        drain_turn_ops(&mut op_rx),
+        vec![
+            Op::OverrideTurnContext {
+                cwd: None,
+                approval_policy: None,
+                sandbox_policy: None,
+                model: Some("gpt-5-codex-mini".to_string()),
+                effort: Some(Some(ReasoningEffortConfig::Low)),
+                summary: None,
+            },
+            Op::UserInput {
+                items: vec![UserInput::Text {
+                    text: "Summarize the diff".to_string(),
+                }],
+            },
+        ]
+    );
+
+    chat.handle_codex_event(Event {
+        id: "task-1".into(),
+        msg: EventMsg::TaskStarted(TaskStartedEvent {
+            model_context_window: None,
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "task-1".into(),
+        msg: EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message: None,
+        }),
+    });
+
+    assert_eq!(
+        drain_turn_ops(&mut op_rx),
+        vec![Op::OverrideTurnContext {
+            cwd: None,
+            approval_policy: None,
+            sandbox_policy: None,
+            model: Some(chat.config.model.clone()),
+            effort: Some(chat.config.model_reasoning_effort),
+            summary: None,
+        }]
+    );
+}
+
+fn next_prompt_preset_decision(
+    rx: &mut tokio::sync::mpsc::UnboundedReceiver<AppEvent>,
+) -> PromptPresetDecision {
+    while let Ok(event) = rx.try_recv() {
+        if let AppEvent::ResolvePromptApprovalPreset(decision) = event {
+            return decision;
+        }
+    }
+    panic!("expected AppEvent::ResolvePromptApprovalPreset");
+}
+
+#[test]
+fn prompt_approval_preset_waits_for_confirmation() {
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual();
+    let preset = builtin_approval_presets()
+        .into_iter()
+        .find(|preset| preset.id == "full-access")
+        .expect("full-access preset");
+    let prompt = CustomPrompt {
+        name: "refactor".to_string(),
+        path: PathBuf::from("/tmp/refactor.md"),
+        content: "Refactor the module".to_string(),
+        approval_preset: Some("full-access".to_string()),
+        ..Default::default()
+    };
+
+    submit_custom_prompt(&mut chat, prompt.clone());
+    assert_eq!(drain_turn_ops(&mut op_rx), Vec::<Op>::new());
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    chat.resolve_prompt_approval_preset(next_prompt_preset_decision(&mut rx));
+    assert_eq!(
+        drain_turn_ops(&mut op_rx),
+        vec![
+            Op::OverrideTurnContext {
+                cwd: None,
+                approval_policy: Some(preset.approval),
+                sandbox_policy: Some(preset.sandbox.clone()),
+                model: None,
+                effort: None,
+                summary: None,
+            },
+            Op::UserInput {
+                items: vec![UserInput::Text {
+                    text: "Refactor the module".to_string(),
+                }],
+            },
+        ]
+    );
+
+    chat.handle_codex_event(Event {
+        id: "task-1".into(),
+        msg: EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message: None,
+        }),
+    });
+    drain_turn_ops(&mut op_rx);
+
+    submit_custom_prompt(&mut chat, prompt);
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    chat.resolve_prompt_approval_preset(next_prompt_preset_decision(&mut rx));
+    assert_eq!(drain_turn_ops(&mut op_rx), Vec::<Op>::new());
+    assert_eq!(chat.bottom_pane.composer_text(), "Refactor the module");
+}
+
 // Snapshot test: status widget active (StatusIndicatorView)
 // Ensures the VT100 rendering of the status indicator is stable when active.
 #[test]
@@ -2658,6 +3135,53 @@ fn warning_event_adds_warning_history_cell() {
     );
 }
 
+#[test]
+fn turn_limit_reached_adds_notice() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.handle_codex_event(Event {
+        id: "sub-1".into(),
+        msg: EventMsg::TurnLimitReached(TurnLimitReachedEvent {
+            limit: TurnLimitKind::WallClock,
+            threshold: 600,
+        }),
+    });
+
+    let cells = drain_insert_history(&mut rx);
+    assert_eq!(cells.len(), 1, "expected one notice history cell");
+    assert_eq!(
+        lines_to_single_string(&cells[0]),
+        "• Turn stopped after its 600s time limit Adjust [turn_limits] in config.toml to change this.\n"
+    );
+}
+
+#[test]
+fn pin_and_unpin_are_confirmed() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    for pinned in [true, false] {
+        chat.handle_codex_event(Event {
+            id: "sub-1".into(),
+            msg: EventMsg::HistoryFlagSet(HistoryFlagSetEvent {
+                flag: HistoryFlag::Pinned {
+                    nth_user_message: 2,
+                    pinned,
+                },
+            }),
+        });
+    }
+
+    let notices: Vec<String> = drain_insert_history(&mut rx)
+        .iter()
+        .map(|cell| lines_to_single_string(cell))
+        .collect();
+    assert_eq!(
+        notices,
+        vec![
+            "• Message pinned Compaction will keep it word for word.\n".to_string(),
+            "• Message unpinned Compaction may summarize it.\n".to_string(),
+        ]
+    );
+}
+
 #[test]
 fn multiple_agent_messages_in_single_turn_emit_multiple_headers() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
@@ -2835,6 +3359,7 @@ fn chatwidget_exec_and_status_layout_vt100_snapshot() {
             parsed_cmd: parsed_cmd.clone(),
             source: ExecCommandSource::Agent,
             interaction_input: None,
+            filtered_env_vars: None,
         }),
     });
     chat.handle_codex_event(Event {
@@ -2850,6 +3375,7 @@ fn chatwidget_exec_and_status_layout_vt100_snapshot() {
             stdout: String::new(),
             stderr: String::new(),
             aggregated_output: String::new(),
+            stderr_ranges: Vec::new(),
             exit_code: 0,
             duration: std::time::Duration::from_millis(16000),
             formatted_output: String::new(),
@@ -3008,3 +3534,116 @@ fn chatwidget_tall() {
     .unwrap();
     assert_snapshot!(term.backend().vt100().screen().contents());
 }
+
+#[test]
+fn too_narrow_terminal_shows_placeholder() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    chat.bottom_pane
+        .set_composer_text("Summarize recent commits".to_string());
+
+    assert_eq!(chat.desired_height(18), 2);
+    assert_eq!(render_bottom_popup(&chat, 18), "Terminal too\nnarrow");
+    assert_eq!(chat.cursor_pos(Rect::new(0, 0, 18, 2)), None);
+}
+
+/// Render `chat` at its desired height into a VT100 screen `width` columns
+/// wide, without trailing spaces.
+fn render_vt100_at_width(chat: &ChatWidget, width: u16) -> String {
+    let height = chat.desired_height(width);
+    let backend = VT100Backend::new(width, height);
+    let mut term = crate::custom_terminal::Terminal::with_options(backend).expect("terminal");
+    term.set_viewport_area(Rect::new(0, 0, width, height));
+    term.draw(|f| {
+        chat.render(f.area(), f.buffer_mut());
+    })
+    .expect("draw");
+    term.backend()
+        .vt100()
+        .screen()
+        .contents()
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn narrow_terminals_render_busy_chat_approval_and_file_picker() {
+    for width in [38, 25, 18] {
+        let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+        chat.handle_codex_event(Event {
+            id: "t1".into(),
+            msg: EventMsg::TaskStarted(TaskStartedEvent {
+                model_context_window: None,
+            }),
+        });
+        begin_exec(&mut chat, "call-1", "cargo test --workspace --all-features");
+        assert_snapshot!(
+            format!("narrow_busy_chat_{width}"),
+            render_vt100_at_width(&chat, width)
+        );
+
+        chat.handle_codex_event(Event {
+            id: "approve".into(),
+            msg: EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
+                call_id: "call-approve".into(),
+                turn_id: "turn-approve".into(),
+                command: vec!["bash".into(), "-lc".into(), "rm -rf target/debug".into()],
+                cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+                reason: Some("free disk space before rebuilding".into()),
+                risk: None,
+                parsed_cmd: vec![],
+                network: None,
+                explanation_pending: false,
+            }),
+        });
+        assert_snapshot!(
+            format!("narrow_exec_approval_{width}"),
+            render_vt100_at_width(&chat, width)
+        );
+
+        let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+        chat.bottom_pane.set_composer_text("@src".to_string());
+        chat.apply_file_search_result(
+            "src".to_string(),
+            vec![FileMatch {
+                score: 42,
+                path: "codex-rs/tui/src/bottom_pane/chat_composer.rs".to_string(),
+                indices: Some(vec![15, 16, 17]),
+                matched_pattern: None,
+                size: None,
+                modified: None,
+            }],
+            false,
+        );
+        assert_snapshot!(
+            format!("narrow_file_picker_{width}"),
+            render_vt100_at_width(&chat, width)
+        );
+    }
+}
+
+#[test]
+fn missing_clipboard_tools_are_explained_once() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+
+    chat.on_clipboard_image_pasted(
+        Err(crate::clipboard_paste::PasteImageError::ClipboardToolsMissing),
+        false,
+    );
+    chat.on_clipboard_image_pasted(
+        Err(crate::clipboard_paste::PasteImageError::ClipboardToolsMissing),
+        false,
+    );
+    // Nothing on the clipboard behind an empty paste is not an error.
+    chat.on_clipboard_image_pasted(
+        Err(crate::clipboard_paste::PasteImageError::NoImage(
+            "empty".to_string(),
+        )),
+        true,
+    );
+
+    let cells = drain_insert_history(&mut rx);
+    assert_eq!(cells.len(), 1, "expected a single hint, got {cells:?}");
+    assert!(lines_to_single_string(&cells[0]).contains("wl-clipboard"));
+}
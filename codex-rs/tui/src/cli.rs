@@ -4,6 +4,8 @@ use codex_common::ApprovalModeCliArg;
 use codex_common::CliConfigOverrides;
 use std::path::PathBuf;
 
+use crate::replay::ReplaySpeed;
+
 #[derive(Parser, Debug)]
 #[command(version)]
 pub struct Cli {
@@ -81,6 +83,21 @@ pub struct Cli {
     #[arg(long = "search", default_value_t = false)]
     pub web_search: bool,
 
+    /// Block all network access except requests to the model provider
+    /// (no update checks, web search, model downloads, cloud tasks, or sandbox network).
+    #[arg(long = "offline", default_value_t = false)]
+    pub offline: bool,
+
+    /// Persist nothing under CODEX_HOME: no rollout, prompt history, logs, or
+    /// caches. The session cannot be resumed.
+    #[arg(long = "ephemeral", default_value_t = false)]
+    pub ephemeral: bool,
+
+    /// Playback speed when `model_provider = "replay"`: recorded pauses are
+    /// divided by FACTOR, and 0 replays without pauses.
+    #[arg(long = "replay-speed", value_name = "FACTOR")]
+    pub replay_speed: Option<f64>,
+
     /// Additional directories that should be writable alongside the primary workspace.
     #[arg(long = "add-dir", value_name = "DIR", value_hint = ValueHint::DirPath)]
     pub add_dir: Vec<PathBuf>,
@@ -88,3 +105,27 @@ pub struct Cli {
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 }
+
+/// Arguments for `codex replay`.
+#[derive(Parser, Debug)]
+pub struct ReplayCli {
+    /// Session id (UUID) or path to a rollout `.jsonl` file.
+    #[arg(value_name = "SESSION_ID")]
+    pub session: String,
+
+    /// Playback speed: a multiplier such as `4x` or `0.5x`, or `instant`.
+    #[arg(long = "speed", value_name = "SPEED", default_value = "1x")]
+    pub speed: ReplaySpeed,
+
+    /// Render instantly without a terminal UI and print the final VT100
+    /// screen, for golden comparisons in CI.
+    #[arg(long = "diff-against-live", default_value_t = false)]
+    pub diff_against_live: bool,
+
+    /// Screen width used with --diff-against-live.
+    #[arg(long = "width", value_name = "COLUMNS", default_value_t = 80)]
+    pub width: u16,
+
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+}
@@ -8,6 +8,12 @@ pub enum PasteImageError {
     NoImage(String),
     EncodeFailed(String),
     IoError(String),
+    /// Neither `wl-paste` nor `xclip` is installed.
+    ClipboardToolsMissing,
+    /// The clipboard image is larger than `tui.paste_image_max_bytes`.
+    TooLarge {
+        limit: usize,
+    },
 }
 
 impl std::fmt::Display for PasteImageError {
@@ -17,6 +23,15 @@ impl std::fmt::Display for PasteImageError {
             PasteImageError::NoImage(msg) => write!(f, "no image on clipboard: {msg}"),
             PasteImageError::EncodeFailed(msg) => write!(f, "could not encode image: {msg}"),
             PasteImageError::IoError(msg) => write!(f, "io error: {msg}"),
+            PasteImageError::ClipboardToolsMissing => write!(
+                f,
+                "install wl-clipboard (Wayland) or xclip (X11) to paste images from the clipboard"
+            ),
+            PasteImageError::TooLarge { limit } => write!(
+                f,
+                "clipboard image is larger than {:.1} MB (tui.paste_image_max_bytes)",
+                *limit as f64 / (1024.0 * 1024.0)
+            ),
         }
     }
 }
@@ -44,6 +59,23 @@ pub struct PastedImageInfo {
     pub width: u32,
     pub height: u32,
     pub encoded_format: EncodedImageFormat, // Always PNG for now.
+    /// Size of the encoded image written to disk.
+    pub byte_len: usize,
+}
+
+impl PastedImageInfo {
+    /// Composer chip for a pasted image, e.g. `[image: 1.2 MB png]`.
+    pub fn chip_label(&self) -> String {
+        let size = if self.byte_len >= 1024 * 1024 {
+            format!("{:.1} MB", self.byte_len as f64 / (1024.0 * 1024.0))
+        } else if self.byte_len >= 1024 {
+            format!("{} KB", self.byte_len.div_ceil(1024))
+        } else {
+            format!("{} B", self.byte_len)
+        };
+        let format = self.encoded_format.label().to_ascii_lowercase();
+        format!("[image: {size} {format}]")
+    }
 }
 
 /// Capture image from system clipboard, encode to PNG, and return bytes + info.
@@ -98,12 +130,14 @@ pub fn paste_image_as_png() -> Result<(Vec<u8>, PastedImageInfo), PasteImageErro
         span.record("byte_length", png.len());
     }
 
+    let byte_len = png.len();
     Ok((
         png,
         PastedImageInfo {
             width: dyn_img.width(),
             height: dyn_img.height(),
             encoded_format: EncodedImageFormat::Png,
+            byte_len,
         },
     ))
 }
@@ -116,26 +150,78 @@ pub fn paste_image_as_png() -> Result<(Vec<u8>, PastedImageInfo), PasteImageErro
     ))
 }
 
-/// Convenience: write to a temp file and return its path + info.
+/// Convenience: write the clipboard image to a new file in `dir` (the
+/// session's attachment directory) and return its path + info. Images larger
+/// than `max_bytes` are rejected.
+///
+/// On Linux, when arboard finds no image (it cannot read images on every
+/// Wayland compositor), falls back to `wl-paste` or `xclip`.
+#[cfg(not(target_os = "android"))]
+pub fn paste_image_to_temp_file(
+    max_bytes: usize,
+    dir: &Path,
+) -> Result<(PathBuf, PastedImageInfo), PasteImageError> {
+    match paste_image_as_png() {
+        Ok((png, info)) => {
+            if png.len() > max_bytes {
+                return Err(PasteImageError::TooLarge { limit: max_bytes });
+            }
+            let path = write_temp_image(&png, "png", dir)?;
+            Ok((path, info))
+        }
+        #[cfg(target_os = "linux")]
+        Err(err) => {
+            tracing::debug!("arboard returned no image ({err}); trying clipboard tools");
+            let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+            let (bytes, encoded_format) = clipboard_tools::read_image_with_clipboard_tools(
+                &clipboard_tools::SystemClipboardCommand,
+                wayland,
+                max_bytes,
+            )?;
+            let extension = match encoded_format {
+                EncodedImageFormat::Jpeg => "jpg",
+                EncodedImageFormat::Png | EncodedImageFormat::Other => "png",
+            };
+            let path = write_temp_image(&bytes, extension, dir)?;
+            let (width, height) = image::image_dimensions(&path)
+                .map_err(|e| PasteImageError::EncodeFailed(e.to_string()))?;
+            Ok((
+                path,
+                PastedImageInfo {
+                    width,
+                    height,
+                    encoded_format,
+                    byte_len: bytes.len(),
+                },
+            ))
+        }
+        #[cfg(not(target_os = "linux"))]
+        Err(err) => Err(err),
+    }
+}
+
 #[cfg(not(target_os = "android"))]
-pub fn paste_image_to_temp_png() -> Result<(PathBuf, PastedImageInfo), PasteImageError> {
-    let (png, info) = paste_image_as_png()?;
-    // Create a unique temporary file with a .png suffix to avoid collisions.
+fn write_temp_image(bytes: &[u8], extension: &str, dir: &Path) -> Result<PathBuf, PasteImageError> {
+    std::fs::create_dir_all(dir).map_err(|e| PasteImageError::IoError(e.to_string()))?;
+    // Create a unique file with the image's suffix to avoid collisions.
     let tmp = Builder::new()
         .prefix("codex-clipboard-")
-        .suffix(".png")
-        .tempfile()
+        .suffix(&format!(".{extension}"))
+        .tempfile_in(dir)
         .map_err(|e| PasteImageError::IoError(e.to_string()))?;
-    std::fs::write(tmp.path(), &png).map_err(|e| PasteImageError::IoError(e.to_string()))?;
+    std::fs::write(tmp.path(), bytes).map_err(|e| PasteImageError::IoError(e.to_string()))?;
     // Persist the file (so it remains after the handle is dropped) and return its PathBuf.
     let (_file, path) = tmp
         .keep()
         .map_err(|e| PasteImageError::IoError(e.error.to_string()))?;
-    Ok((path, info))
+    Ok(path)
 }
 
 #[cfg(target_os = "android")]
-pub fn paste_image_to_temp_png() -> Result<(PathBuf, PastedImageInfo), PasteImageError> {
+pub fn paste_image_to_temp_file(
+    _max_bytes: usize,
+    _dir: &Path,
+) -> Result<(PathBuf, PastedImageInfo), PasteImageError> {
     // Keep error consistent with paste_image_as_png.
     Err(PasteImageError::ClipboardUnavailable(
         "clipboard image paste is unsupported on Android".into(),
@@ -255,6 +341,28 @@ mod pasted_paths_tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn chip_label_reports_size_and_format() {
+        let info = |byte_len, encoded_format| PastedImageInfo {
+            width: 10,
+            height: 10,
+            encoded_format,
+            byte_len,
+        };
+        assert_eq!(
+            info(1_258_291, EncodedImageFormat::Png).chip_label(),
+            "[image: 1.2 MB png]"
+        );
+        assert_eq!(
+            info(3_000, EncodedImageFormat::Jpeg).chip_label(),
+            "[image: 3 KB jpeg]"
+        );
+        assert_eq!(
+            info(512, EncodedImageFormat::Png).chip_label(),
+            "[image: 512 B png]"
+        );
+    }
+
     #[test]
     fn pasted_image_format_png_jpeg_unknown() {
         assert_eq!(
@@ -323,3 +431,269 @@ mod pasted_paths_tests {
         );
     }
 }
+
+/// Fallback for reading clipboard images through the `wl-clipboard` and
+/// `xclip` command-line tools.
+#[cfg(any(target_os = "linux", test))]
+mod clipboard_tools {
+    use super::EncodedImageFormat;
+    use super::PasteImageError;
+    #[cfg(target_os = "linux")]
+    use std::io::Read;
+    #[cfg(target_os = "linux")]
+    use std::process::Command;
+    #[cfg(target_os = "linux")]
+    use std::process::Stdio;
+
+    /// Runs an external clipboard program. A trait so tests can stub the
+    /// programs out.
+    pub(super) trait ClipboardCommand {
+        /// Stdout of `program args`. Stops reading after `max_bytes + 1` bytes,
+        /// so callers can tell an oversized clipboard apart without buffering it.
+        fn output(
+            &self,
+            program: &str,
+            args: &[&str],
+            max_bytes: usize,
+        ) -> std::io::Result<Vec<u8>>;
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(super) struct SystemClipboardCommand;
+
+    #[cfg(target_os = "linux")]
+    impl ClipboardCommand for SystemClipboardCommand {
+        fn output(
+            &self,
+            program: &str,
+            args: &[&str],
+            max_bytes: usize,
+        ) -> std::io::Result<Vec<u8>> {
+            let mut child = Command::new(program)
+                .args(args)
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()?;
+            let mut bytes = Vec::new();
+            if let Some(stdout) = child.stdout.take() {
+                stdout.take(max_bytes as u64 + 1).read_to_end(&mut bytes)?;
+            }
+            if bytes.len() > max_bytes {
+                // Don't wait for the rest of an oversized image.
+                let _ = child.kill();
+                let _ = child.wait();
+                return Ok(bytes);
+            }
+            let status = child.wait()?;
+            if status.success() {
+                Ok(bytes)
+            } else {
+                Err(std::io::Error::other(format!(
+                    "{program} exited with {status}"
+                )))
+            }
+        }
+    }
+
+    /// A program that lists the clipboard's MIME types and reads one of them.
+    struct ClipboardTool {
+        program: &'static str,
+        list_types_args: &'static [&'static str],
+        /// Arguments to read the clipboard; the MIME type is appended.
+        read_args: &'static [&'static str],
+    }
+
+    const WL_PASTE: ClipboardTool = ClipboardTool {
+        program: "wl-paste",
+        list_types_args: &["--list-types"],
+        read_args: &["--no-newline", "--type"],
+    };
+
+    const XCLIP: ClipboardTool = ClipboardTool {
+        program: "xclip",
+        list_types_args: &["-selection", "clipboard", "-t", "TARGETS", "-o"],
+        read_args: &["-selection", "clipboard", "-o", "-t"],
+    };
+
+    /// Image MIME types we can attach, most preferred first.
+    const IMAGE_MIME_TYPES: [(&str, EncodedImageFormat); 2] = [
+        ("image/png", EncodedImageFormat::Png),
+        ("image/jpeg", EncodedImageFormat::Jpeg),
+    ];
+
+    /// Generous bound for a list of MIME types.
+    const MAX_TYPE_LIST_BYTES: usize = 64 * 1024;
+
+    /// Read a PNG or JPEG image from the clipboard with `wl-paste` (tried first
+    /// under Wayland) or `xclip`.
+    pub(super) fn read_image_with_clipboard_tools(
+        command: &dyn ClipboardCommand,
+        wayland: bool,
+        max_bytes: usize,
+    ) -> Result<(Vec<u8>, EncodedImageFormat), PasteImageError> {
+        let tools: &[ClipboardTool] = if wayland {
+            &[WL_PASTE, XCLIP]
+        } else {
+            &[XCLIP]
+        };
+        for tool in tools {
+            let types =
+                match command.output(tool.program, tool.list_types_args, MAX_TYPE_LIST_BYTES) {
+                    Ok(types) => types,
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                    Err(err) => {
+                        return Err(PasteImageError::ClipboardUnavailable(format!(
+                            "{}: {err}",
+                            tool.program
+                        )));
+                    }
+                };
+            let types = String::from_utf8_lossy(&types);
+            let Some(&(mime_type, encoded_format)) = IMAGE_MIME_TYPES
+                .iter()
+                .find(|(mime_type, _)| types.lines().any(|line| line.trim() == *mime_type))
+            else {
+                return Err(PasteImageError::NoImage(format!(
+                    "{} lists no PNG or JPEG data",
+                    tool.program
+                )));
+            };
+
+            let mut args = tool.read_args.to_vec();
+            args.push(mime_type);
+            let bytes = command
+                .output(tool.program, &args, max_bytes)
+                .map_err(|err| PasteImageError::IoError(format!("{}: {err}", tool.program)))?;
+            if bytes.len() > max_bytes {
+                return Err(PasteImageError::TooLarge { limit: max_bytes });
+            }
+            return Ok((bytes, encoded_format));
+        }
+        Err(PasteImageError::ClipboardToolsMissing)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use pretty_assertions::assert_eq;
+        use std::collections::HashMap;
+
+        const PNG_BYTES: &[u8] = b"\x89PNG fake";
+
+        /// Canned stdout per program invocation; programs without any entry are
+        /// "not installed".
+        #[derive(Default)]
+        struct StubCommand {
+            outputs: HashMap<String, Vec<u8>>,
+        }
+
+        impl StubCommand {
+            fn with(mut self, program: &str, args: &[&str], stdout: &[u8]) -> Self {
+                self.outputs
+                    .insert(format!("{program} {}", args.join(" ")), stdout.to_vec());
+                self
+            }
+        }
+
+        impl ClipboardCommand for StubCommand {
+            fn output(
+                &self,
+                program: &str,
+                args: &[&str],
+                max_bytes: usize,
+            ) -> std::io::Result<Vec<u8>> {
+                if !self
+                    .outputs
+                    .keys()
+                    .any(|key| key.split(' ').next() == Some(program))
+                {
+                    return Err(std::io::ErrorKind::NotFound.into());
+                }
+                let stdout = self
+                    .outputs
+                    .get(&format!("{program} {}", args.join(" ")))
+                    .cloned()
+                    .unwrap_or_default();
+                Ok(stdout.into_iter().take(max_bytes + 1).collect())
+            }
+        }
+
+        fn xclip_with_png() -> StubCommand {
+            StubCommand::default()
+                .with(
+                    "xclip",
+                    XCLIP.list_types_args,
+                    b"TARGETS\nimage/jpeg\nimage/png\n",
+                )
+                .with(
+                    "xclip",
+                    &["-selection", "clipboard", "-o", "-t", "image/png"],
+                    PNG_BYTES,
+                )
+        }
+
+        #[test]
+        fn wayland_prefers_wl_paste_and_png() {
+            let command = StubCommand::default()
+                .with("wl-paste", &["--list-types"], b"image/jpeg\nimage/png\n")
+                .with(
+                    "wl-paste",
+                    &["--no-newline", "--type", "image/png"],
+                    PNG_BYTES,
+                );
+
+            let (bytes, format) =
+                read_image_with_clipboard_tools(&command, true, 1024).expect("image");
+
+            assert_eq!(
+                (bytes.as_slice(), format),
+                (PNG_BYTES, EncodedImageFormat::Png)
+            );
+        }
+
+        #[test]
+        fn missing_wl_paste_falls_back_to_xclip() {
+            let (bytes, format) =
+                read_image_with_clipboard_tools(&xclip_with_png(), true, 1024).expect("image");
+
+            assert_eq!(
+                (bytes.as_slice(), format),
+                (PNG_BYTES, EncodedImageFormat::Png)
+            );
+        }
+
+        #[test]
+        fn no_tools_installed_is_reported() {
+            let result = read_image_with_clipboard_tools(&StubCommand::default(), true, 1024);
+
+            assert!(matches!(
+                result,
+                Err(PasteImageError::ClipboardToolsMissing)
+            ));
+        }
+
+        #[test]
+        fn text_only_clipboard_has_no_image() {
+            let command = StubCommand::default().with(
+                "xclip",
+                XCLIP.list_types_args,
+                b"TARGETS\nUTF8_STRING\ntext/plain\n",
+            );
+
+            let result = read_image_with_clipboard_tools(&command, false, 1024);
+
+            assert!(matches!(result, Err(PasteImageError::NoImage(_))));
+        }
+
+        #[test]
+        fn oversized_image_is_rejected() {
+            let result = read_image_with_clipboard_tools(&xclip_with_png(), false, 4);
+
+            assert!(matches!(
+                result,
+                Err(PasteImageError::TooLarge { limit: 4 })
+            ));
+        }
+    }
+}
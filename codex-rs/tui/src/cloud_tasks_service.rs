@@ -0,0 +1,410 @@
+//! Background polling of Codex Cloud tasks.
+//!
+//! While a session is open the poller lists the user's cloud tasks every
+//! `tui.cloud_tasks_poll_interval_secs`, diffs the result against the previous
+//! snapshot and reports status transitions to the UI through
+//! [`AppEvent::CloudTasksPolled`]. Polls are skipped (not failed) while offline
+//! mode is active or no ChatGPT login is available, and consecutive errors back
+//! off exponentially so a flaky backend is not hammered.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use codex_app_server_protocol::AuthMode;
+use codex_cloud_tasks_client::CloudBackend;
+use codex_cloud_tasks_client::HttpClient;
+use codex_cloud_tasks_client::TaskStatus;
+use codex_cloud_tasks_client::TaskSummary;
+use codex_core::AuthManager;
+use codex_core::CodexAuth;
+use codex_core::default_client::get_codex_user_agent;
+use codex_core::network_policy::NetworkCapability;
+use codex_core::network_policy::NetworkPolicy;
+use rand::Rng;
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+
+/// Upper bound for the delay between polls after repeated errors.
+const MAX_BACKOFF: Duration = Duration::from_secs(30 * 60);
+
+/// Fraction of the delay added or removed at random so that several sessions
+/// do not poll in lockstep.
+const JITTER: f64 = 0.1;
+
+/// A task whose status changed between two polls. `from` is `None` for tasks
+/// that appeared since the previous snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CloudTaskTransition {
+    pub title: String,
+    pub from: Option<TaskStatus>,
+    pub to: TaskStatus,
+}
+
+/// User-facing name for a task status.
+pub(crate) fn status_label(status: &TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Pending => "queued",
+        TaskStatus::Running => "running",
+        TaskStatus::Ready => "done",
+        TaskStatus::Applied => "applied",
+        TaskStatus::Error => "failed",
+    }
+}
+
+/// Diff state carried between polls.
+#[derive(Debug, Default)]
+pub(crate) struct CloudTaskTracker {
+    /// Status per task id from the last successful poll; `None` until the
+    /// first poll, which only establishes a baseline.
+    statuses: Option<HashMap<String, TaskStatus>>,
+    consecutive_errors: u32,
+}
+
+impl CloudTaskTracker {
+    /// Record a successful poll and return the transitions since the previous
+    /// one, in the order the backend listed the tasks.
+    pub(crate) fn record_tasks(&mut self, tasks: &[TaskSummary]) -> Vec<CloudTaskTransition> {
+        self.consecutive_errors = 0;
+        let current: HashMap<String, TaskStatus> = tasks
+            .iter()
+            .map(|task| (task.id.0.clone(), task.status.clone()))
+            .collect();
+        let Some(previous) = self.statuses.replace(current) else {
+            return Vec::new();
+        };
+
+        tasks
+            .iter()
+            .filter_map(|task| {
+                let from = previous.get(&task.id.0);
+                if from == Some(&task.status) {
+                    return None;
+                }
+                Some(CloudTaskTransition {
+                    title: task.title.clone(),
+                    from: from.cloned(),
+                    to: task.status.clone(),
+                })
+            })
+            .collect()
+    }
+
+    pub(crate) fn record_error(&mut self) {
+        self.consecutive_errors = self.consecutive_errors.saturating_add(1);
+    }
+
+    /// Delay before the next poll: `interval`, doubled for every consecutive
+    /// error up to [`MAX_BACKOFF`], then scaled by `1 + jitter`.
+    pub(crate) fn next_delay(&self, interval: Duration, jitter: f64) -> Duration {
+        let factor = 2u32.saturating_pow(self.consecutive_errors.min(16));
+        let base = interval
+            .saturating_mul(factor)
+            .min(MAX_BACKOFF.max(interval));
+        base.mul_f64((1.0 + jitter).max(0.0))
+    }
+}
+
+/// Poll `backend` once, update `tracker` and report the result to the UI.
+pub(crate) async fn poll_once(
+    backend: &dyn CloudBackend,
+    tracker: &mut CloudTaskTracker,
+    app_event_tx: &AppEventSender,
+) {
+    match backend.list_tasks(None).await {
+        Ok(tasks) => {
+            let transitions = tracker.record_tasks(&tasks);
+            app_event_tx.send(AppEvent::CloudTasksPolled { tasks, transitions });
+        }
+        Err(err) => {
+            tracker.record_error();
+            debug!(error = %err, "failed to poll cloud tasks");
+        }
+    }
+}
+
+/// Returns the auth to poll with, or `None` when polling should pause.
+fn poll_auth(network: &NetworkPolicy, auth_manager: &AuthManager) -> Option<CodexAuth> {
+    network.check(NetworkCapability::CloudTasks).ok()?;
+    auth_manager
+        .auth()
+        .filter(|auth| auth.mode == AuthMode::ChatGPT)
+}
+
+async fn backend_for(base_url: &str, auth: &CodexAuth) -> anyhow::Result<HttpClient> {
+    let token = auth.get_token().await?;
+    let mut client = HttpClient::new(base_url)?
+        .with_user_agent(get_codex_user_agent())
+        .with_bearer_token(token);
+    if let Some(account_id) = auth.get_account_id() {
+        client = client.with_chatgpt_account_id(account_id);
+    }
+    Ok(client)
+}
+
+/// Spawn the background poller. The returned handle must be aborted when the
+/// session ends.
+pub(crate) fn spawn_cloud_task_poller(
+    interval: Duration,
+    base_url: String,
+    network: NetworkPolicy,
+    auth_manager: Arc<AuthManager>,
+    app_event_tx: AppEventSender,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut tracker = CloudTaskTracker::default();
+        loop {
+            if let Some(auth) = poll_auth(&network, &auth_manager) {
+                match backend_for(&base_url, &auth).await {
+                    Ok(backend) => poll_once(&backend, &mut tracker, &app_event_tx).await,
+                    Err(err) => {
+                        tracker.record_error();
+                        debug!(error = %err, "failed to build cloud tasks client");
+                    }
+                }
+            }
+            let jitter = rand::rng().random_range(-JITTER..=JITTER);
+            tokio::time::sleep(tracker.next_delay(interval, jitter)).await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::Utc;
+    use codex_cloud_tasks_client::ApplyOutcome;
+    use codex_cloud_tasks_client::CloudTaskError;
+    use codex_cloud_tasks_client::CreatedTask;
+    use codex_cloud_tasks_client::DiffSummary;
+    use codex_cloud_tasks_client::TaskId;
+    use codex_cloud_tasks_client::TaskText;
+    use codex_cloud_tasks_client::TurnAttempt;
+    use pretty_assertions::assert_eq;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    /// Backend that replays a scripted sequence of `list_tasks` results.
+    struct ScriptedBackend {
+        responses: Mutex<VecDeque<codex_cloud_tasks_client::Result<Vec<TaskSummary>>>>,
+    }
+
+    impl ScriptedBackend {
+        fn new(responses: Vec<codex_cloud_tasks_client::Result<Vec<TaskSummary>>>) -> Self {
+            Self {
+                responses: Mutex::new(responses.into()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl CloudBackend for ScriptedBackend {
+        async fn list_tasks(
+            &self,
+            _env: Option<&str>,
+        ) -> codex_cloud_tasks_client::Result<Vec<TaskSummary>> {
+            self.responses
+                .lock()
+                .expect("responses lock")
+                .pop_front()
+                .unwrap_or_else(|| Err(CloudTaskError::Msg("script exhausted".to_string())))
+        }
+
+        async fn get_task_diff(
+            &self,
+            _id: TaskId,
+        ) -> codex_cloud_tasks_client::Result<Option<String>> {
+            Err(CloudTaskError::Unimplemented("get_task_diff"))
+        }
+
+        async fn get_task_messages(
+            &self,
+            _id: TaskId,
+        ) -> codex_cloud_tasks_client::Result<Vec<String>> {
+            Err(CloudTaskError::Unimplemented("get_task_messages"))
+        }
+
+        async fn get_task_text(&self, _id: TaskId) -> codex_cloud_tasks_client::Result<TaskText> {
+            Err(CloudTaskError::Unimplemented("get_task_text"))
+        }
+
+        async fn list_sibling_attempts(
+            &self,
+            _task: TaskId,
+            _turn_id: String,
+        ) -> codex_cloud_tasks_client::Result<Vec<TurnAttempt>> {
+            Err(CloudTaskError::Unimplemented("list_sibling_attempts"))
+        }
+
+        async fn apply_task_preflight(
+            &self,
+            _id: TaskId,
+            _diff_override: Option<String>,
+        ) -> codex_cloud_tasks_client::Result<ApplyOutcome> {
+            Err(CloudTaskError::Unimplemented("apply_task_preflight"))
+        }
+
+        async fn apply_task(
+            &self,
+            _id: TaskId,
+            _diff_override: Option<String>,
+        ) -> codex_cloud_tasks_client::Result<ApplyOutcome> {
+            Err(CloudTaskError::Unimplemented("apply_task"))
+        }
+
+        async fn create_task(
+            &self,
+            _env_id: &str,
+            _prompt: &str,
+            _git_ref: &str,
+            _qa_mode: bool,
+            _best_of_n: usize,
+        ) -> codex_cloud_tasks_client::Result<CreatedTask> {
+            Err(CloudTaskError::Unimplemented("create_task"))
+        }
+    }
+
+    fn task(id: &str, title: &str, status: TaskStatus) -> TaskSummary {
+        TaskSummary {
+            id: TaskId(id.to_string()),
+            title: title.to_string(),
+            status,
+            updated_at: Utc::now(),
+            environment_id: None,
+            environment_label: None,
+            summary: DiffSummary::default(),
+            is_review: false,
+            attempt_total: None,
+        }
+    }
+
+    fn transition(title: &str, from: Option<TaskStatus>, to: TaskStatus) -> CloudTaskTransition {
+        CloudTaskTransition {
+            title: title.to_string(),
+            from,
+            to,
+        }
+    }
+
+    #[tokio::test]
+    async fn polls_report_status_transitions_after_baseline() {
+        let backend = ScriptedBackend::new(vec![
+            Ok(vec![task("a", "Fix flaky test", TaskStatus::Pending)]),
+            Ok(vec![task("a", "Fix flaky test", TaskStatus::Pending)]),
+            Ok(vec![
+                task("a", "Fix flaky test", TaskStatus::Running),
+                task("b", "Bump deps", TaskStatus::Pending),
+            ]),
+            Ok(vec![
+                task("a", "Fix flaky test", TaskStatus::Ready),
+                task("b", "Bump deps", TaskStatus::Error),
+            ]),
+        ]);
+        let (tx, mut rx) = unbounded_channel();
+        let app_event_tx = AppEventSender::new(tx);
+        let mut tracker = CloudTaskTracker::default();
+
+        let mut polled = Vec::new();
+        for _ in 0..4 {
+            poll_once(&backend, &mut tracker, &app_event_tx).await;
+            match rx.try_recv() {
+                Ok(AppEvent::CloudTasksPolled { transitions, .. }) => polled.push(transitions),
+                other => panic!("expected CloudTasksPolled, got {other:?}"),
+            }
+        }
+
+        assert_eq!(
+            polled,
+            vec![
+                vec![],
+                vec![],
+                vec![
+                    transition(
+                        "Fix flaky test",
+                        Some(TaskStatus::Pending),
+                        TaskStatus::Running
+                    ),
+                    transition("Bump deps", None, TaskStatus::Pending),
+                ],
+                vec![
+                    transition(
+                        "Fix flaky test",
+                        Some(TaskStatus::Running),
+                        TaskStatus::Ready
+                    ),
+                    transition("Bump deps", Some(TaskStatus::Pending), TaskStatus::Error),
+                ],
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn consecutive_errors_back_off_until_a_poll_succeeds() {
+        let backend = ScriptedBackend::new(vec![
+            Err(CloudTaskError::Http("502".to_string())),
+            Err(CloudTaskError::Http("502".to_string())),
+            Err(CloudTaskError::Http("502".to_string())),
+            Ok(vec![task("a", "Fix flaky test", TaskStatus::Pending)]),
+        ]);
+        let (tx, mut rx) = unbounded_channel();
+        let app_event_tx = AppEventSender::new(tx);
+        let mut tracker = CloudTaskTracker::default();
+        let interval = Duration::from_secs(60);
+
+        let mut delays = vec![tracker.next_delay(interval, 0.0)];
+        for _ in 0..4 {
+            poll_once(&backend, &mut tracker, &app_event_tx).await;
+            delays.push(tracker.next_delay(interval, 0.0));
+        }
+
+        assert_eq!(
+            delays,
+            vec![
+                Duration::from_secs(60),
+                Duration::from_secs(120),
+                Duration::from_secs(240),
+                Duration::from_secs(480),
+                Duration::from_secs(60),
+            ]
+        );
+        // Only the successful poll reaches the UI.
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(AppEvent::CloudTasksPolled { .. })
+        ));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn backoff_is_capped_and_jittered() {
+        let mut tracker = CloudTaskTracker::default();
+        for _ in 0..40 {
+            tracker.record_error();
+        }
+        let interval = Duration::from_secs(60);
+        assert_eq!(tracker.next_delay(interval, 0.0), MAX_BACKOFF);
+        assert_eq!(
+            tracker.next_delay(interval, JITTER),
+            MAX_BACKOFF.mul_f64(1.0 + JITTER)
+        );
+    }
+
+    #[test]
+    fn polling_pauses_offline_or_without_chatgpt_login() {
+        let online = NetworkPolicy::new(false);
+        let offline = NetworkPolicy::new(true);
+        let chatgpt =
+            AuthManager::from_auth_for_testing(CodexAuth::create_dummy_chatgpt_auth_for_testing());
+        let api_key = AuthManager::from_auth_for_testing(CodexAuth::from_api_key("sk-test"));
+
+        assert!(poll_auth(&online, &chatgpt).is_some());
+        assert!(poll_auth(&offline, &chatgpt).is_none());
+        assert!(poll_auth(&online, &api_key).is_none());
+    }
+}
@@ -1,4 +1,3 @@
-use diffy::Hunk;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::Color;
@@ -8,6 +7,8 @@ use ratatui::style::Stylize;
 use ratatui::text::Line as RtLine;
 use ratatui::text::Span as RtSpan;
 use ratatui::widgets::Paragraph;
+use similar::Algorithm;
+use similar::DiffTag;
 use std::collections::HashMap;
 use std::path::Path;
 use std::path::PathBuf;
@@ -18,6 +19,7 @@ use crate::render::line_utils::prefix_lines;
 use crate::render::renderable::ColumnRenderable;
 use crate::render::renderable::InsetRenderable;
 use crate::render::renderable::Renderable;
+use codex_common::diff_summary::summarize_file_changes;
 use codex_core::git_info::get_git_repo_root;
 use codex_core::protocol::FileChange;
 
@@ -28,6 +30,73 @@ enum DiffLineType {
     Context,
 }
 
+/// Modified line pairs less similar than this are shown as separate lines.
+const WORD_DIFF_MIN_SIMILARITY: f64 = 0.5;
+/// Lines longer than this (in chars) skip word diffing.
+const WORD_DIFF_MAX_LINE_CHARS: usize = 500;
+
+/// Which files render modified line pairs as a single inline word diff.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum WordDiff<'a> {
+    Off,
+    /// Files whose extension (compared case-insensitively) is listed.
+    Extensions(&'a [String]),
+    All,
+}
+
+impl WordDiff<'_> {
+    fn applies_to(self, path: &Path) -> bool {
+        match self {
+            WordDiff::Off => false,
+            WordDiff::All => true,
+            WordDiff::Extensions(extensions) => path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| {
+                    extensions
+                        .iter()
+                        .any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(ext))
+                }),
+        }
+    }
+}
+
+/// Word diff setting cycled from the transcript overlay.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum WordDiffMode {
+    /// Word diffs for the extensions in `tui.word_diff_extensions`.
+    #[default]
+    Auto,
+    On,
+    Off,
+}
+
+impl WordDiffMode {
+    pub(crate) fn next(self) -> Self {
+        match self {
+            WordDiffMode::Auto => WordDiffMode::On,
+            WordDiffMode::On => WordDiffMode::Off,
+            WordDiffMode::Off => WordDiffMode::Auto,
+        }
+    }
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            WordDiffMode::Auto => "auto",
+            WordDiffMode::On => "on",
+            WordDiffMode::Off => "off",
+        }
+    }
+
+    pub(crate) fn resolve(self, extensions: &[String]) -> WordDiff<'_> {
+        match self {
+            WordDiffMode::Auto => WordDiff::Extensions(extensions),
+            WordDiffMode::On => WordDiff::All,
+            WordDiffMode::Off => WordDiff::Off,
+        }
+    }
+}
+
 pub struct DiffSummary {
     changes: HashMap<PathBuf, FileChange>,
     cwd: PathBuf,
@@ -42,13 +111,13 @@ impl DiffSummary {
 impl Renderable for FileChange {
     fn render(&self, area: Rect, buf: &mut Buffer) {
         let mut lines = vec![];
-        render_change(self, &mut lines, area.width as usize);
+        render_change(self, &mut lines, area.width as usize, false);
         Paragraph::new(lines).render(area, buf);
     }
 
     fn desired_height(&self, width: u16) -> u16 {
         let mut lines = vec![];
-        render_change(self, &mut lines, width as usize);
+        render_change(self, &mut lines, width as usize, false);
         lines.len() as u16
     }
 }
@@ -76,13 +145,24 @@ impl From<DiffSummary> for Box<dyn Renderable> {
     }
 }
 
+/// Draw `changes` the way the TUI shows a patch, for frontends that print to
+/// a plain terminal (e.g. `codex cloud apply --preview`).
+pub fn render_file_changes(
+    changes: &HashMap<PathBuf, FileChange>,
+    cwd: &Path,
+    wrap_cols: usize,
+) -> Vec<RtLine<'static>> {
+    create_diff_summary(changes, cwd, wrap_cols, WordDiff::Off)
+}
+
 pub(crate) fn create_diff_summary(
     changes: &HashMap<PathBuf, FileChange>,
     cwd: &Path,
     wrap_cols: usize,
+    word_diff: WordDiff<'_>,
 ) -> Vec<RtLine<'static>> {
     let rows = collect_rows(changes);
-    render_changes_block(rows, wrap_cols, cwd)
+    render_changes_block(rows, wrap_cols, cwd, word_diff)
 }
 
 // Shared row for per-file presentation
@@ -97,30 +177,19 @@ struct Row {
 }
 
 fn collect_rows(changes: &HashMap<PathBuf, FileChange>) -> Vec<Row> {
-    let mut rows: Vec<Row> = Vec::new();
-    for (path, change) in changes.iter() {
-        let (added, removed) = match change {
-            FileChange::Add { content } => (content.lines().count(), 0),
-            FileChange::Delete { content } => (0, content.lines().count()),
-            FileChange::Update { unified_diff, .. } => calculate_add_remove_from_diff(unified_diff),
-        };
-        let move_path = match change {
-            FileChange::Update {
-                move_path: Some(new),
-                ..
-            } => Some(new.clone()),
-            _ => None,
-        };
-        rows.push(Row {
-            path: path.clone(),
-            move_path,
-            added,
-            removed,
-            change: change.clone(),
-        });
-    }
-    rows.sort_by_key(|r| r.path.clone());
-    rows
+    summarize_file_changes(changes)
+        .into_iter()
+        .filter_map(|summary| {
+            let change = changes.get(&summary.path)?.clone();
+            Some(Row {
+                path: summary.path,
+                move_path: summary.move_path,
+                added: summary.added,
+                removed: summary.removed,
+                change,
+            })
+        })
+        .collect()
 }
 
 fn render_line_count_summary(added: usize, removed: usize) -> Vec<RtSpan<'static>> {
@@ -133,7 +202,12 @@ fn render_line_count_summary(added: usize, removed: usize) -> Vec<RtSpan<'static
     spans
 }
 
-fn render_changes_block(rows: Vec<Row>, wrap_cols: usize, cwd: &Path) -> Vec<RtLine<'static>> {
+fn render_changes_block(
+    rows: Vec<Row>,
+    wrap_cols: usize,
+    cwd: &Path,
+    word_diff: WordDiff<'_>,
+) -> Vec<RtLine<'static>> {
     let mut out: Vec<RtLine<'static>> = Vec::new();
 
     let render_path = |row: &Row| -> Vec<RtSpan<'static>> {
@@ -186,14 +260,25 @@ fn render_changes_block(rows: Vec<Row>, wrap_cols: usize, cwd: &Path) -> Vec<RtL
         }
 
         let mut lines = vec![];
-        render_change(&r.change, &mut lines, wrap_cols - 4);
+        let word_diff = word_diff.applies_to(&r.path);
+        render_change(
+            &r.change,
+            &mut lines,
+            wrap_cols.saturating_sub(4).max(1),
+            word_diff,
+        );
         out.extend(prefix_lines(lines, "    ".into(), "    ".into()));
     }
 
     out
 }
 
-fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usize) {
+fn render_change(
+    change: &FileChange,
+    out: &mut Vec<RtLine<'static>>,
+    width: usize,
+    word_diff: bool,
+) {
     match change {
         FileChange::Add { content } => {
             let line_number_width = line_number_width(content.lines().count());
@@ -255,8 +340,10 @@ fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usi
 
                     let mut old_ln = h.old_range().start();
                     let mut new_ln = h.new_range().start();
-                    for l in h.lines() {
-                        match l {
+                    let lines = h.lines();
+                    let mut idx = 0;
+                    while idx < lines.len() {
+                        match &lines[idx] {
                             diffy::Line::Insert(text) => {
                                 let s = text.trim_end_matches('\n');
                                 out.extend(push_wrapped_diff_line(
@@ -267,17 +354,83 @@ fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usi
                                     line_number_width,
                                 ));
                                 new_ln += 1;
+                                idx += 1;
                             }
-                            diffy::Line::Delete(text) => {
-                                let s = text.trim_end_matches('\n');
-                                out.extend(push_wrapped_diff_line(
-                                    old_ln,
-                                    DiffLineType::Delete,
-                                    s,
-                                    width,
-                                    line_number_width,
-                                ));
-                                old_ln += 1;
+                            diffy::Line::Delete(_) => {
+                                // A run of deletions directly followed by a run of
+                                // insertions is a block of modified lines.
+                                let deleted: Vec<&str> = lines[idx..]
+                                    .iter()
+                                    .map_while(|l| match l {
+                                        diffy::Line::Delete(text) => {
+                                            Some(text.trim_end_matches('\n'))
+                                        }
+                                        _ => None,
+                                    })
+                                    .collect();
+                                let inserted: Vec<&str> = if word_diff {
+                                    lines[idx + deleted.len()..]
+                                        .iter()
+                                        .map_while(|l| match l {
+                                            diffy::Line::Insert(text) => {
+                                                Some(text.trim_end_matches('\n'))
+                                            }
+                                            _ => None,
+                                        })
+                                        .collect()
+                                } else {
+                                    Vec::new()
+                                };
+                                idx += deleted.len() + inserted.len();
+
+                                let paired = deleted.len().min(inserted.len());
+                                for (old, new) in deleted.iter().zip(&inserted) {
+                                    if let Some(segments) = word_diff_segments(old, new) {
+                                        out.extend(push_wrapped_word_diff_line(
+                                            new_ln,
+                                            &segments,
+                                            width,
+                                            line_number_width,
+                                        ));
+                                    } else {
+                                        out.extend(push_wrapped_diff_line(
+                                            old_ln,
+                                            DiffLineType::Delete,
+                                            old,
+                                            width,
+                                            line_number_width,
+                                        ));
+                                        out.extend(push_wrapped_diff_line(
+                                            new_ln,
+                                            DiffLineType::Insert,
+                                            new,
+                                            width,
+                                            line_number_width,
+                                        ));
+                                    }
+                                    old_ln += 1;
+                                    new_ln += 1;
+                                }
+                                for old in &deleted[paired..] {
+                                    out.extend(push_wrapped_diff_line(
+                                        old_ln,
+                                        DiffLineType::Delete,
+                                        old,
+                                        width,
+                                        line_number_width,
+                                    ));
+                                    old_ln += 1;
+                                }
+                                for new in &inserted[paired..] {
+                                    out.extend(push_wrapped_diff_line(
+                                        new_ln,
+                                        DiffLineType::Insert,
+                                        new,
+                                        width,
+                                        line_number_width,
+                                    ));
+                                    new_ln += 1;
+                                }
                             }
                             diffy::Line::Context(text) => {
                                 let s = text.trim_end_matches('\n');
@@ -290,6 +443,7 @@ fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usi
                                 ));
                                 old_ln += 1;
                                 new_ln += 1;
+                                idx += 1;
                             }
                         }
                     }
@@ -314,23 +468,6 @@ pub(crate) fn display_path_for(path: &Path, cwd: &Path) -> String {
     chosen.display().to_string()
 }
 
-fn calculate_add_remove_from_diff(diff: &str) -> (usize, usize) {
-    if let Ok(patch) = diffy::Patch::from_str(diff) {
-        patch
-            .hunks()
-            .iter()
-            .flat_map(Hunk::lines)
-            .fold((0, 0), |(a, d), l| match l {
-                diffy::Line::Insert(_) => (a + 1, d),
-                diffy::Line::Delete(_) => (a, d + 1),
-                diffy::Line::Context(_) => (a, d),
-            })
-    } else {
-        // For unparsable diffs, return 0 for both counts.
-        (0, 0)
-    }
-}
-
 fn push_wrapped_diff_line(
     line_number: usize,
     kind: DiffLineType,
@@ -392,6 +529,167 @@ fn push_wrapped_diff_line(
     lines
 }
 
+/// Piece of a modified line in a word diff.
+#[derive(Debug, PartialEq)]
+enum WordSegment<'a> {
+    Equal(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Splits a line into runs of word characters, runs of whitespace, and
+/// single punctuation characters.
+fn tokenize_words(text: &str) -> Vec<&str> {
+    #[derive(PartialEq)]
+    enum Class {
+        Word,
+        Space,
+        Punct,
+    }
+    let class_of = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            Class::Word
+        } else if c.is_whitespace() {
+            Class::Space
+        } else {
+            Class::Punct
+        }
+    };
+
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut prev: Option<Class> = None;
+    for (i, c) in text.char_indices() {
+        let class = class_of(c);
+        if let Some(prev) = &prev
+            && (*prev != class || class == Class::Punct)
+        {
+            tokens.push(&text[start..i]);
+            start = i;
+        }
+        prev = Some(class);
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+/// Word-level diff of a modified line pair, or `None` when the lines are too
+/// long or too different for an inline rendering to be readable.
+fn word_diff_segments<'a>(old: &'a str, new: &'a str) -> Option<Vec<WordSegment<'a>>> {
+    let old_chars = old.chars().count();
+    let new_chars = new.chars().count();
+    if old_chars.max(new_chars) > WORD_DIFF_MAX_LINE_CHARS {
+        return None;
+    }
+
+    let old_tokens = tokenize_words(old);
+    let new_tokens = tokenize_words(new);
+    let old_offsets = token_offsets(&old_tokens);
+    let new_offsets = token_offsets(&new_tokens);
+    let mut segments = Vec::new();
+    let mut equal_chars = 0;
+    for op in similar::capture_diff_slices(Algorithm::Myers, &old_tokens, &new_tokens) {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        let removed = &old[old_offsets[old_range.start]..old_offsets[old_range.end]];
+        let added = &new[new_offsets[new_range.start]..new_offsets[new_range.end]];
+        match tag {
+            DiffTag::Equal => {
+                equal_chars += removed.chars().count();
+                segments.push(WordSegment::Equal(removed));
+            }
+            DiffTag::Delete => segments.push(WordSegment::Removed(removed)),
+            DiffTag::Insert => segments.push(WordSegment::Added(added)),
+            DiffTag::Replace => {
+                segments.push(WordSegment::Removed(removed));
+                segments.push(WordSegment::Added(added));
+            }
+        }
+    }
+
+    let total_chars = old_chars + new_chars;
+    let similarity = if total_chars == 0 {
+        1.0
+    } else {
+        (2 * equal_chars) as f64 / total_chars as f64
+    };
+    (similarity >= WORD_DIFF_MIN_SIMILARITY).then_some(segments)
+}
+
+/// Byte offset of each token's start, plus the end of the line.
+fn token_offsets(tokens: &[&str]) -> Vec<usize> {
+    let mut offsets = Vec::with_capacity(tokens.len() + 1);
+    let mut offset = 0;
+    offsets.push(offset);
+    for token in tokens {
+        offset += token.len();
+        offsets.push(offset);
+    }
+    offsets
+}
+
+fn push_wrapped_word_diff_line(
+    line_number: usize,
+    segments: &[WordSegment<'_>],
+    width: usize,
+    line_number_width: usize,
+) -> Vec<RtLine<'static>> {
+    let gutter_width = line_number_width.max(1);
+    let prefix_cols = gutter_width + 1;
+    let available_content_cols = width.saturating_sub(prefix_cols + 1).max(1);
+
+    // Split the styled segments into rows of at most `available_content_cols`
+    // chars, the same budget `push_wrapped_diff_line` uses.
+    let mut rows: Vec<Vec<RtSpan<'static>>> = vec![Vec::new()];
+    let mut used_cols = 0;
+    for segment in segments {
+        let (text, style) = match segment {
+            WordSegment::Equal(text) => (*text, style_context()),
+            WordSegment::Removed(text) => (*text, style_del().add_modifier(Modifier::CROSSED_OUT)),
+            WordSegment::Added(text) => (*text, style_add()),
+        };
+        let mut remaining_text = text;
+        while !remaining_text.is_empty() {
+            if used_cols == available_content_cols {
+                rows.push(Vec::new());
+                used_cols = 0;
+            }
+            let split_at_byte_index = remaining_text
+                .char_indices()
+                .nth(available_content_cols - used_cols)
+                .map(|(i, _)| i)
+                .unwrap_or_else(|| remaining_text.len());
+            let (chunk, rest) = remaining_text.split_at(split_at_byte_index);
+            remaining_text = rest;
+            used_cols += chunk.chars().count();
+            if let Some(row) = rows.last_mut() {
+                row.push(RtSpan::styled(chunk.to_string(), style));
+            }
+        }
+    }
+
+    rows.into_iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut spans = if i == 0 {
+                // '~' marks a line whose removed and added words share one row.
+                vec![
+                    RtSpan::styled(format!("{line_number:>gutter_width$} "), style_gutter()),
+                    RtSpan::styled("~", style_context()),
+                ]
+            } else {
+                vec![RtSpan::styled(
+                    format!("{:gutter_width$}  ", ""),
+                    style_gutter(),
+                )]
+            };
+            spans.extend(row);
+            RtLine::from(spans)
+        })
+        .collect()
+}
+
 fn line_number_width(max_line_number: usize) -> usize {
     if max_line_number == 0 {
         1
@@ -427,7 +725,7 @@ mod tests {
     use ratatui::widgets::WidgetRef;
     use ratatui::widgets::Wrap;
     fn diff_summary_for_tests(changes: &HashMap<PathBuf, FileChange>) -> Vec<RtLine<'static>> {
-        create_diff_summary(changes, &PathBuf::from("/"), 80)
+        create_diff_summary(changes, &PathBuf::from("/"), 80, WordDiff::Off)
     }
 
     fn snapshot_lines(name: &str, lines: Vec<RtLine<'static>>, width: u16, height: u16) {
@@ -593,7 +891,7 @@ mod tests {
             },
         );
 
-        let lines = create_diff_summary(&changes, &PathBuf::from("/"), 72);
+        let lines = create_diff_summary(&changes, &PathBuf::from("/"), 72, WordDiff::Off);
 
         // Render with backend width wider than wrap width to avoid Paragraph auto-wrap.
         snapshot_lines("apply_update_block_wraps_long_lines", lines, 80, 12);
@@ -616,7 +914,7 @@ mod tests {
             },
         );
 
-        let lines = create_diff_summary(&changes, &PathBuf::from("/"), 28);
+        let lines = create_diff_summary(&changes, &PathBuf::from("/"), 28, WordDiff::Off);
         snapshot_lines_text("apply_update_block_wraps_long_lines_text", &lines);
     }
 
@@ -643,7 +941,7 @@ mod tests {
             },
         );
 
-        let lines = create_diff_summary(&changes, &PathBuf::from("/"), 80);
+        let lines = create_diff_summary(&changes, &PathBuf::from("/"), 80, WordDiff::Off);
         snapshot_lines_text("apply_update_block_line_numbers_three_digits_text", &lines);
     }
 
@@ -666,8 +964,121 @@ mod tests {
             },
         );
 
-        let lines = create_diff_summary(&changes, &cwd, 80);
+        let lines = create_diff_summary(&changes, &cwd, 80, WordDiff::Off);
 
         snapshot_lines("apply_update_block_relativizes_path", lines, 80, 10);
     }
+
+    fn markdown_changes() -> HashMap<PathBuf, FileChange> {
+        let original =
+            "# Setup\n\nRun the installer and restart your shell.\nSee the FAQ for help.\n";
+        let modified =
+            "# Setup\n\nRun the new installer, then restart your terminal.\nAsk in the forum.\n";
+        let mut changes: HashMap<PathBuf, FileChange> = HashMap::new();
+        changes.insert(
+            PathBuf::from("README.md"),
+            FileChange::Update {
+                unified_diff: diffy::create_patch(original, modified).to_string(),
+                move_path: None,
+            },
+        );
+        changes
+    }
+
+    #[test]
+    fn tokenize_words_splits_words_spaces_and_punctuation() {
+        assert_eq!(
+            tokenize_words("key = \"a_b\",  next"),
+            vec!["key", " ", "=", " ", "\"", "a_b", "\"", ",", "  ", "next"]
+        );
+        assert_eq!(tokenize_words(""), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn word_diff_segments_marks_changed_words() {
+        assert_eq!(
+            word_diff_segments("the quick fox", "the slow fox"),
+            Some(vec![
+                WordSegment::Equal("the "),
+                WordSegment::Removed("quick"),
+                WordSegment::Added("slow"),
+                WordSegment::Equal(" fox"),
+            ])
+        );
+    }
+
+    #[test]
+    fn word_diff_segments_falls_back_for_dissimilar_or_long_lines() {
+        assert_eq!(
+            word_diff_segments("See the FAQ for help.", "Ask in the forum."),
+            None
+        );
+
+        let long = "word ".repeat(WORD_DIFF_MAX_LINE_CHARS);
+        assert_eq!(word_diff_segments(&long, &format!("{long}more")), None);
+    }
+
+    #[test]
+    fn word_diff_line_strikes_removed_words() {
+        let segments = [
+            WordSegment::Equal("a "),
+            WordSegment::Removed("b"),
+            WordSegment::Added("c"),
+        ];
+        assert_eq!(
+            push_wrapped_word_diff_line(7, &segments, 80, 1),
+            vec![RtLine::from(vec![
+                RtSpan::styled("7 ", style_gutter()),
+                RtSpan::styled("~", style_context()),
+                RtSpan::styled("a ", style_context()),
+                RtSpan::styled("b", style_del().add_modifier(Modifier::CROSSED_OUT)),
+                RtSpan::styled("c", style_add()),
+            ])]
+        );
+    }
+
+    #[test]
+    fn word_diff_applies_by_extension() {
+        let extensions = vec!["md".to_string(), ".toml".to_string()];
+        let auto = WordDiffMode::Auto.resolve(&extensions);
+        assert!(auto.applies_to(Path::new("docs/README.MD")));
+        assert!(auto.applies_to(Path::new("Cargo.toml")));
+        assert!(!auto.applies_to(Path::new("src/main.rs")));
+        assert!(
+            WordDiffMode::On
+                .resolve(&extensions)
+                .applies_to(Path::new("src/main.rs"))
+        );
+        assert!(
+            !WordDiffMode::Off
+                .resolve(&extensions)
+                .applies_to(Path::new("README.md"))
+        );
+    }
+
+    #[test]
+    fn ui_snapshot_markdown_hunk_line_diff_text() {
+        let lines =
+            create_diff_summary(&markdown_changes(), &PathBuf::from("/"), 80, WordDiff::Off);
+        snapshot_lines_text("markdown_hunk_line_diff_text", &lines);
+    }
+
+    #[test]
+    fn ui_snapshot_markdown_hunk_word_diff_text() {
+        let extensions = vec!["md".to_string()];
+        let lines = create_diff_summary(
+            &markdown_changes(),
+            &PathBuf::from("/"),
+            80,
+            WordDiff::Extensions(&extensions),
+        );
+        snapshot_lines_text("markdown_hunk_word_diff_text", &lines);
+    }
+
+    #[test]
+    fn ui_snapshot_markdown_hunk_word_diff() {
+        let lines =
+            create_diff_summary(&markdown_changes(), &PathBuf::from("/"), 40, WordDiff::All);
+        snapshot_lines("markdown_hunk_word_diff", lines, 40, 10);
+    }
 }
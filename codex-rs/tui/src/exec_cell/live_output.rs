@@ -0,0 +1,265 @@
+//! Output streamed by a command while it runs.
+//!
+//! Every line is kept so the output can be expanded at any point. Wrapping is
+//! cached for the last width rendered, so a new chunk only wraps the lines it
+//! completes instead of the whole output.
+
+use std::cell::RefCell;
+
+use codex_ansi_escape::ansi_escape_line;
+use codex_core::protocol::ExecOutputStream;
+use ratatui::text::Line;
+use textwrap::WordSplitter;
+
+use super::render::style_output_line;
+use crate::render::line_utils::push_owned_lines;
+use crate::wrapping::RtOptions;
+use crate::wrapping::word_wrap_line;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LiveOutput {
+    /// Complete lines, each with whether it was written to stderr.
+    lines: Vec<(String, bool)>,
+    /// Text after the last newline.
+    partial: String,
+    partial_is_stderr: bool,
+    /// Leading bytes of a UTF-8 character split across chunks.
+    pending: Vec<u8>,
+    /// Stream offsets just past the last stdout and stderr chunks.
+    stdout_end: u64,
+    stderr_end: u64,
+    wrap_cache: RefCell<Option<WrapCache>>,
+}
+
+#[derive(Debug, Clone)]
+struct WrapCache {
+    width: usize,
+    rows: Vec<Line<'static>>,
+    /// Index into `rows` of the first row of each wrapped line.
+    line_starts: Vec<usize>,
+}
+
+impl LiveOutput {
+    /// Appends `chunk`; `offset` is where it starts in its stream, when known.
+    pub(crate) fn push_chunk(
+        &mut self,
+        stream: ExecOutputStream,
+        offset: Option<u64>,
+        chunk: &[u8],
+    ) {
+        let is_stderr = matches!(stream, ExecOutputStream::Stderr);
+        let end = if is_stderr {
+            &mut self.stderr_end
+        } else {
+            &mut self.stdout_end
+        };
+        let skipped = offset.map_or(0, |offset| offset.saturating_sub(*end));
+        *end = offset.unwrap_or(*end) + chunk.len() as u64;
+        if skipped > 0 {
+            // Output was dropped upstream while we were behind.
+            self.pending.clear();
+            if !self.partial.is_empty() {
+                self.lines
+                    .push((std::mem::take(&mut self.partial), self.partial_is_stderr));
+            }
+            self.lines
+                .push((format!("… {skipped} bytes skipped"), is_stderr));
+        }
+        let text = self.decode(chunk);
+        for piece in text.split_inclusive('\n') {
+            if self.partial.is_empty() {
+                self.partial_is_stderr = is_stderr;
+            }
+            match piece.strip_suffix('\n') {
+                Some(rest) => {
+                    let mut line = std::mem::take(&mut self.partial);
+                    line.push_str(rest);
+                    if line.ends_with('\r') {
+                        line.pop();
+                    }
+                    self.lines.push((line, self.partial_is_stderr));
+                }
+                None => self.partial.push_str(piece),
+            }
+        }
+    }
+
+    /// Lines received so far, counting an unterminated last line.
+    pub(crate) fn line_count(&self) -> usize {
+        self.lines.len() + usize::from(!self.partial.is_empty())
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.line_count() == 0
+    }
+
+    /// Rows for the last `last_lines` lines wrapped to `width`.
+    pub(crate) fn rows(&self, width: usize, last_lines: usize) -> Vec<Line<'static>> {
+        self.sync_wrap_cache(width);
+        let partial_rows = if self.partial.is_empty() || last_lines == 0 {
+            Vec::new()
+        } else {
+            wrap_line(&self.partial, self.partial_is_stderr, width)
+        };
+        let complete = last_lines.saturating_sub(usize::from(!partial_rows.is_empty()));
+        let first_line = self.lines.len().saturating_sub(complete);
+
+        let cache = self.wrap_cache.borrow();
+        let mut rows = cache
+            .as_ref()
+            .and_then(|cache| {
+                let start = cache.line_starts.get(first_line)?;
+                Some(cache.rows[*start..].to_vec())
+            })
+            .unwrap_or_default();
+        rows.extend(partial_rows);
+        rows
+    }
+
+    /// Every line received so far, styled but not wrapped; for the
+    /// transcript, which wraps lines itself.
+    pub(crate) fn unwrapped_lines(&self) -> Vec<Line<'static>> {
+        let partial = (!self.partial.is_empty()).then_some((&self.partial, self.partial_is_stderr));
+        self.lines
+            .iter()
+            .map(|(text, is_stderr)| (text, *is_stderr))
+            .chain(partial)
+            .map(|(text, is_stderr)| {
+                let mut line = ansi_escape_line(text);
+                style_output_line(&mut line, is_stderr);
+                line
+            })
+            .collect()
+    }
+
+    /// Wrap the complete lines not yet in the cache, starting over when the
+    /// width changed. Returns how many lines were wrapped.
+    fn sync_wrap_cache(&self, width: usize) -> usize {
+        let mut cache = self.wrap_cache.borrow_mut();
+        if cache.as_ref().is_none_or(|cache| cache.width != width) {
+            *cache = Some(WrapCache {
+                width,
+                rows: Vec::new(),
+                line_starts: Vec::new(),
+            });
+        }
+        let Some(cache) = cache.as_mut() else {
+            return 0;
+        };
+        let wrapped = cache.line_starts.len();
+        for (text, is_stderr) in &self.lines[wrapped..] {
+            cache.line_starts.push(cache.rows.len());
+            cache.rows.extend(wrap_line(text, *is_stderr, width));
+        }
+        self.lines.len() - wrapped
+    }
+
+    fn decode(&mut self, chunk: &[u8]) -> String {
+        let mut bytes = std::mem::take(&mut self.pending);
+        bytes.extend_from_slice(chunk);
+        let complete = match std::str::from_utf8(&bytes) {
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            _ => bytes.len(),
+        };
+        self.pending = bytes.split_off(complete);
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+fn wrap_line(text: &str, is_stderr: bool, width: usize) -> Vec<Line<'static>> {
+    let mut line = ansi_escape_line(text);
+    style_output_line(&mut line, is_stderr);
+    let mut rows = Vec::new();
+    push_owned_lines(
+        &word_wrap_line(
+            &line,
+            RtOptions::new(width).word_splitter(WordSplitter::NoHyphenation),
+        ),
+        &mut rows,
+    );
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn text(rows: &[Line<'static>]) -> Vec<String> {
+        rows.iter()
+            .map(|row| row.spans.iter().map(|span| span.content.as_ref()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn joins_lines_and_characters_split_across_chunks() {
+        let mut output = LiveOutput::default();
+        let bytes = "héllo\r\nwor".as_bytes();
+        output.push_chunk(ExecOutputStream::Stdout, None, &bytes[..2]);
+        output.push_chunk(ExecOutputStream::Stdout, None, &bytes[2..]);
+        output.push_chunk(ExecOutputStream::Stderr, None, b"ld\nerror\n");
+
+        assert_eq!(
+            output.lines,
+            vec![
+                ("héllo".to_string(), false),
+                ("world".to_string(), false),
+                ("error".to_string(), true),
+            ]
+        );
+        assert_eq!(
+            text(&output.rows(80, 2)),
+            vec!["world".to_string(), "error".to_string()]
+        );
+    }
+
+    #[test]
+    fn appending_only_wraps_the_new_lines() {
+        let mut output = LiveOutput::default();
+        for i in 0..100_000 {
+            output.push_chunk(
+                ExecOutputStream::Stdout,
+                None,
+                format!("line {i}\n").as_bytes(),
+            );
+        }
+        assert_eq!(output.sync_wrap_cache(40), 100_000);
+
+        output.push_chunk(ExecOutputStream::Stdout, None, b"one more\nand a partial");
+        assert_eq!(output.sync_wrap_cache(40), 1);
+        assert_eq!(
+            text(&output.rows(40, 3)),
+            vec![
+                "line 99999".to_string(),
+                "one more".to_string(),
+                "and a partial".to_string(),
+            ]
+        );
+        // The partial line is wrapped on every render until it completes.
+        assert_eq!(output.sync_wrap_cache(40), 0);
+
+        // A new width starts over.
+        assert_eq!(output.sync_wrap_cache(20), 100_001);
+    }
+
+    #[test]
+    fn notes_output_skipped_between_offsets() {
+        let mut output = LiveOutput::default();
+        output.push_chunk(ExecOutputStream::Stdout, Some(0), b"start\n");
+        output.push_chunk(ExecOutputStream::Stderr, Some(0), b"warn\n");
+        output.push_chunk(ExecOutputStream::Stdout, Some(6), b"part");
+        output.push_chunk(ExecOutputStream::Stdout, Some(10), b"ial\n");
+        output.push_chunk(ExecOutputStream::Stdout, Some(100), b"end\n");
+
+        assert_eq!(
+            output.lines,
+            vec![
+                ("start".to_string(), false),
+                ("warn".to_string(), true),
+                ("partial".to_string(), false),
+                ("… 86 bytes skipped".to_string(), false),
+                ("end".to_string(), false),
+            ]
+        );
+    }
+}
@@ -1,6 +1,9 @@
+mod live_output;
 mod model;
 mod render;
 
+#[cfg(test)]
+pub(crate) use live_output::LiveOutput;
 pub(crate) use model::CommandOutput;
 #[cfg(test)]
 pub(crate) use model::ExecCall;
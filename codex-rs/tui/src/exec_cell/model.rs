@@ -1,9 +1,14 @@
 use std::time::Duration;
 use std::time::Instant;
 
+use codex_core::config::types::DEFAULT_EXEC_FOLLOW_LINES;
 use codex_core::protocol::ExecCommandSource;
+use codex_core::protocol::ExecOutputStream;
+use codex_core::protocol::OutputRange;
 use codex_protocol::parse_command::ParsedCommand;
 
+use super::live_output::LiveOutput;
+
 #[derive(Clone, Debug, Default)]
 pub(crate) struct CommandOutput {
     pub(crate) exit_code: i32,
@@ -11,6 +16,8 @@ pub(crate) struct CommandOutput {
     pub(crate) aggregated_output: String,
     /// The formatted output of the command, as seen by the model.
     pub(crate) formatted_output: String,
+    /// Byte ranges of `aggregated_output` that were written to stderr.
+    pub(crate) stderr_ranges: Vec<OutputRange>,
 }
 
 #[derive(Debug, Clone)]
@@ -23,16 +30,41 @@ pub(crate) struct ExecCall {
     pub(crate) start_time: Option<Instant>,
     pub(crate) duration: Option<Duration>,
     pub(crate) interaction_input: Option<String>,
+    /// Output streamed while the command runs.
+    pub(crate) live_output: LiveOutput,
 }
 
 #[derive(Debug)]
 pub(crate) struct ExecCell {
     pub(crate) calls: Vec<ExecCall>,
+    /// Output lines shown for a running command before earlier lines are
+    /// collapsed.
+    pub(crate) follow_lines: usize,
+    /// Show all output instead of the collapsed tail.
+    pub(crate) output_expanded: bool,
+    /// Inherited environment variables the shell environment policy filtered
+    /// out, noted under the command when set.
+    pub(crate) filtered_env_vars: Option<u32>,
 }
 
 impl ExecCell {
     pub(crate) fn new(call: ExecCall) -> Self {
-        Self { calls: vec![call] }
+        Self {
+            calls: vec![call],
+            follow_lines: DEFAULT_EXEC_FOLLOW_LINES,
+            output_expanded: false,
+            filtered_env_vars: None,
+        }
+    }
+
+    pub(crate) fn with_follow_lines(mut self, follow_lines: usize) -> Self {
+        self.follow_lines = follow_lines;
+        self
+    }
+
+    pub(crate) fn with_filtered_env_vars(mut self, filtered_env_vars: Option<u32>) -> Self {
+        self.filtered_env_vars = filtered_env_vars;
+        self
     }
 
     pub(crate) fn with_added_call(
@@ -52,10 +84,14 @@ impl ExecCell {
             start_time: Some(Instant::now()),
             duration: None,
             interaction_input,
+            live_output: LiveOutput::default(),
         };
         if self.is_exploring_cell() && Self::is_exploring_call(&call) {
             Some(Self {
                 calls: [self.calls.clone(), vec![call]].concat(),
+                follow_lines: self.follow_lines,
+                output_expanded: self.output_expanded,
+                filtered_env_vars: self.filtered_env_vars,
             })
         } else {
             None
@@ -75,6 +111,33 @@ impl ExecCell {
         }
     }
 
+    /// Append streamed output to the running call `call_id`. Returns false
+    /// when this cell has no such call.
+    pub(crate) fn push_output_chunk(
+        &mut self,
+        call_id: &str,
+        stream: ExecOutputStream,
+        offset: Option<u64>,
+        chunk: &[u8],
+    ) -> bool {
+        match self
+            .calls
+            .iter_mut()
+            .rev()
+            .find(|c| c.call_id == call_id && c.output.is_none())
+        {
+            Some(call) => {
+                call.live_output.push_chunk(stream, offset, chunk);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub(crate) fn toggle_output_expanded(&mut self) {
+        self.output_expanded = !self.output_expanded;
+    }
+
     pub(crate) fn should_flush(&self) -> bool {
         !self.is_exploring_cell() && self.calls.iter().all(|c| c.output.is_some())
     }
@@ -92,6 +155,7 @@ impl ExecCell {
                     exit_code: 1,
                     formatted_output: String::new(),
                     aggregated_output: String::new(),
+                    stderr_ranges: Vec::new(),
                 });
             }
         }
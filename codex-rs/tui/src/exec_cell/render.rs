@@ -1,10 +1,14 @@
 use std::time::Instant;
 
+use super::live_output::LiveOutput;
 use super::model::CommandOutput;
 use super::model::ExecCall;
 use super::model::ExecCell;
+use crate::animation::AnimationLevel;
+use crate::animation::current_level;
 use crate::exec_command::strip_bash_lc_and_escape;
 use crate::history_cell::HistoryCell;
+use crate::key_hint;
 use crate::render::highlight::highlight_bash_to_lines;
 use crate::render::line_utils::prefix_lines;
 use crate::render::line_utils::push_owned_lines;
@@ -14,8 +18,11 @@ use crate::wrapping::word_wrap_line;
 use crate::wrapping::word_wrap_lines;
 use codex_ansi_escape::ansi_escape_line;
 use codex_common::elapsed::format_duration;
+use codex_core::parse_command::parse_compound_command;
 use codex_core::protocol::ExecCommandSource;
+use codex_protocol::num_format::format_with_separators;
 use codex_protocol::parse_command::ParsedCommand;
+use crossterm::event::KeyCode;
 use itertools::Itertools;
 use ratatui::prelude::*;
 use ratatui::style::Modifier;
@@ -26,6 +33,7 @@ use unicode_width::UnicodeWidthStr;
 pub(crate) const TOOL_CALL_MAX_LINES: usize = 5;
 const USER_SHELL_TOOL_CALL_MAX_LINES: usize = 50;
 const MAX_INTERACTION_PREVIEW_CHARS: usize = 80;
+const MIN_SUMMARY_PROGRAMS: usize = 3;
 
 pub(crate) struct OutputLinesParams {
     pub(crate) line_limit: usize,
@@ -50,9 +58,18 @@ pub(crate) fn new_active_exec_command(
         start_time: Some(Instant::now()),
         duration: None,
         interaction_input,
+        live_output: LiveOutput::default(),
     })
 }
 
+/// Program names of a compound shell command, e.g. `mkdir, cd, cmake, make`,
+/// when it chains at least [`MIN_SUMMARY_PROGRAMS`] of them.
+fn compound_command_summary(command: &[String]) -> Option<String> {
+    let compound = parse_compound_command(command)?;
+    let programs = compound.programs();
+    (programs.len() >= MIN_SUMMARY_PROGRAMS).then(|| programs.join(", "))
+}
+
 fn format_unified_exec_interaction(command: &[String], input: Option<&str>) -> String {
     let command_display = command.join(" ");
     match input {
@@ -96,7 +113,9 @@ pub(crate) fn output_lines(
         include_prefix,
     } = params;
     let CommandOutput {
-        aggregated_output, ..
+        aggregated_output,
+        stderr_ranges,
+        ..
     } = match output {
         Some(output) if only_err && output.exit_code == 0 => {
             return OutputLines {
@@ -113,13 +132,27 @@ pub(crate) fn output_lines(
         }
     };
 
-    let src = aggregated_output;
-    let lines: Vec<&str> = src.lines().collect();
+    // Pair each line with its byte offset so stderr output can be told apart
+    // from stdout. Matches `str::lines`, which also strips a trailing `\r`.
+    let mut offset = 0;
+    let lines: Vec<(&str, bool)> = aggregated_output
+        .split_inclusive('\n')
+        .map(|piece| {
+            let start = offset;
+            offset += piece.len();
+            let raw = piece.strip_suffix('\n').unwrap_or(piece);
+            let raw = raw.strip_suffix('\r').unwrap_or(raw);
+            let is_stderr = stderr_ranges
+                .iter()
+                .any(|range| range.start <= start && start < range.end);
+            (raw, is_stderr)
+        })
+        .collect();
     let total = lines.len();
     let mut out: Vec<Line<'static>> = Vec::new();
 
     let head_end = total.min(line_limit);
-    for (i, raw) in lines[..head_end].iter().enumerate() {
+    for (i, (raw, is_stderr)) in lines[..head_end].iter().enumerate() {
         let mut line = ansi_escape_line(raw);
         let prefix = if !include_prefix {
             ""
@@ -128,10 +161,8 @@ pub(crate) fn output_lines(
         } else {
             "    "
         };
-        line.spans.insert(0, prefix.into());
-        line.spans.iter_mut().for_each(|span| {
-            span.style = span.style.add_modifier(Modifier::DIM);
-        });
+        style_output_line(&mut line, *is_stderr);
+        line.spans.insert(0, Span::from(prefix).dim());
         out.push(line);
     }
 
@@ -151,14 +182,12 @@ pub(crate) fn output_lines(
     } else {
         head_end
     };
-    for raw in lines[tail_start..].iter() {
+    for (raw, is_stderr) in lines[tail_start..].iter() {
         let mut line = ansi_escape_line(raw);
+        style_output_line(&mut line, *is_stderr);
         if include_prefix {
-            line.spans.insert(0, "    ".into());
+            line.spans.insert(0, Span::from("    ").dim());
         }
-        line.spans.iter_mut().for_each(|span| {
-            span.style = span.style.add_modifier(Modifier::DIM);
-        });
         out.push(line);
     }
 
@@ -168,7 +197,23 @@ pub(crate) fn output_lines(
     }
 }
 
+/// Dims command output, tinting stderr lines red unless the command already
+/// colored them.
+pub(super) fn style_output_line(line: &mut Line<'static>, is_stderr: bool) {
+    for span in &mut line.spans {
+        if is_stderr && span.style.fg.is_none() {
+            span.style = span.style.fg(Color::Red);
+        }
+        span.style = span.style.add_modifier(Modifier::DIM);
+    }
+}
+
 pub(crate) fn spinner(start_time: Option<Instant>) -> Span<'static> {
+    // With animations reduced or off the spinner is a steady glyph; the cell
+    // it belongs to still changes when the command finishes.
+    if current_level() != AnimationLevel::Full {
+        return "•".into();
+    }
     let elapsed = start_time.map(|st| st.elapsed()).unwrap_or_default();
     if supports_color::on_cached(supports_color::Stream::Stdout)
         .map(|level| level.has_16m)
@@ -228,6 +273,8 @@ impl HistoryCell for ExecCell {
                 };
                 result.push_span(format!(" • {duration}").dim());
                 lines.push(result);
+            } else {
+                lines.extend(call.live_output.unwrapped_lines());
             }
         }
         lines
@@ -402,10 +449,29 @@ impl ExecCell {
 
         let mut lines: Vec<Line<'static>> = vec![header_line];
 
-        let continuation_lines = Self::limit_lines_from_start(
+        let truncated = continuation_lines.len() > layout.command_continuation_max_lines;
+        let mut continuation_lines = Self::limit_lines_from_start(
             &continuation_lines,
             layout.command_continuation_max_lines,
         );
+        // When a chain of commands is cut off, still name every program it runs.
+        if truncated
+            && !is_interaction
+            && let Some(summary) = compound_command_summary(&call.command)
+            && let Some(mut ellipsis) = continuation_lines.pop()
+        {
+            ellipsis.push_span(format!(" ({summary})").dim());
+            push_owned_lines(
+                &word_wrap_line(&ellipsis, continuation_opts),
+                &mut continuation_lines,
+            );
+        }
+        if let Some(filtered) = self.filtered_env_vars
+            && !is_interaction
+        {
+            let noun = if filtered == 1 { "var" } else { "vars" };
+            continuation_lines.push(Line::from(format!("env: {filtered} {noun} filtered").dim()));
+        }
         if !continuation_lines.is_empty() {
             lines.extend(prefix_lines(
                 continuation_lines,
@@ -415,7 +481,9 @@ impl ExecCell {
         }
 
         if let Some(output) = call.output.as_ref() {
-            let line_limit = if call.is_user_shell_command() {
+            let line_limit = if self.output_expanded {
+                output.aggregated_output.lines().count().max(1)
+            } else if call.is_user_shell_command() {
                 USER_SHELL_TOOL_CALL_MAX_LINES
             } else {
                 TOOL_CALL_MAX_LINES
@@ -429,7 +497,9 @@ impl ExecCell {
                     include_prefix: false,
                 },
             );
-            let display_limit = if call.is_user_shell_command() {
+            let display_limit = if self.output_expanded {
+                usize::MAX
+            } else if call.is_user_shell_command() {
                 USER_SHELL_TOOL_CALL_MAX_LINES
             } else {
                 layout.output_max_lines
@@ -469,11 +539,50 @@ impl ExecCell {
                     ));
                 }
             }
+        } else if !call.live_output.is_empty() {
+            lines.extend(prefix_lines(
+                self.live_output_lines(call, layout.output_block.wrap_width(width)),
+                Span::from(layout.output_block.initial_prefix).dim(),
+                Span::from(layout.output_block.subsequent_prefix),
+            ));
         }
 
         lines
     }
 
+    /// The tail of a running command's output, or all of it when expanded,
+    /// below a header counting the lines left out.
+    fn live_output_lines(&self, call: &ExecCall, wrap_width: usize) -> Vec<Line<'static>> {
+        let total = call.live_output.line_count();
+        let shown = if self.output_expanded {
+            total
+        } else {
+            total.min(self.follow_lines)
+        };
+        let mut out: Vec<Line<'static>> = Vec::new();
+        if shown < total {
+            let earlier = total - shown;
+            let noun = if earlier == 1 { "line" } else { "lines" };
+            out.push(Line::from(vec![
+                format!(
+                    "… {} earlier {noun} (",
+                    format_with_separators(i64::try_from(earlier).unwrap_or(i64::MAX))
+                )
+                .dim(),
+                key_hint::ctrl(KeyCode::Char('o')).into(),
+                " to expand)".dim(),
+            ]));
+        } else if total > self.follow_lines {
+            out.push(Line::from(vec![
+                "(".dim(),
+                key_hint::ctrl(KeyCode::Char('o')).into(),
+                " to collapse)".dim(),
+            ]));
+        }
+        out.extend(call.live_output.rows(wrap_width, shown));
+        out
+    }
+
     fn limit_lines_from_start(lines: &[Line<'static>], keep: usize) -> Vec<Line<'static>> {
         if lines.len() <= keep {
             return lines.to_vec();
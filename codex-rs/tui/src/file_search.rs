@@ -17,16 +17,23 @@
 //!    recent query.
 //! 4. If there is a in-flight search that is not a prefix of the latest thing
 //!    the user typed, it is cancelled.
+//!
+//! The first search walks the directory into a [`file_search::FileIndex`];
+//! later searches only score the cached paths, re-walking when the index
+//! reports that the tree changed.
 
 use codex_file_search as file_search;
 use std::num::NonZeroUsize;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::sync::PoisonError;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::thread;
 use std::time::Duration;
+use std::time::Instant;
 
 use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
@@ -40,15 +47,29 @@ const FILE_SEARCH_DEBOUNCE: Duration = Duration::from_millis(100);
 
 const ACTIVE_SEARCH_COMPLETE_POLL_INTERVAL: Duration = Duration::from_millis(20);
 
+/// Checking staleness stats every indexed directory, so skip it for searches
+/// this close to the previous check.
+const INDEX_STALENESS_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
 /// State machine for file-search orchestration.
 pub(crate) struct FileSearchManager {
     /// Unified state guarded by one mutex.
     state: Arc<Mutex<SearchState>>,
 
+    /// Paths under `search_dir`, built by the first search.
+    index: Arc<Mutex<Option<CachedIndex>>>,
+
     search_dir: PathBuf,
+    /// `[file_search]` rules from config, plus the session's `.codexignore`.
+    filters: file_search::SearchFilters,
     app_tx: AppEventSender,
 }
 
+struct CachedIndex {
+    index: file_search::FileIndex,
+    checked_at: Instant,
+}
+
 struct SearchState {
     /// Latest query typed by user (updated every keystroke).
     latest_query: String,
@@ -66,14 +87,24 @@ struct ActiveSearch {
 }
 
 impl FileSearchManager {
-    pub fn new(search_dir: PathBuf, tx: AppEventSender) -> Self {
+    pub fn new(
+        search_dir: PathBuf,
+        filters: file_search::SearchFilters,
+        path_filter: file_search::PathFilter,
+        tx: AppEventSender,
+    ) -> Self {
         Self {
             state: Arc::new(Mutex::new(SearchState {
                 latest_query: String::new(),
                 is_search_scheduled: false,
                 active_search: None,
             })),
+            index: Arc::new(Mutex::new(None)),
             search_dir,
+            filters: file_search::SearchFilters {
+                path_filter,
+                ..filters
+            },
             app_tx: tx,
         }
     }
@@ -115,7 +146,9 @@ impl FileSearchManager {
         // dropping the lock. This means we are the only thread that can spawn a
         // debounce timer.
         let state = self.state.clone();
+        let index = self.index.clone();
         let search_dir = self.search_dir.clone();
+        let filters = self.filters.clone();
         let tx_clone = self.app_tx.clone();
         thread::spawn(move || {
             // Always do a minimum debounce, but then poll until the
@@ -148,6 +181,8 @@ impl FileSearchManager {
             FileSearchManager::spawn_file_search(
                 query,
                 search_dir,
+                filters,
+                index,
                 tx_clone,
                 cancellation_token,
                 state,
@@ -158,28 +193,42 @@ impl FileSearchManager {
     fn spawn_file_search(
         query: String,
         search_dir: PathBuf,
+        filters: file_search::SearchFilters,
+        index: Arc<Mutex<Option<CachedIndex>>>,
         tx: AppEventSender,
         cancellation_token: Arc<AtomicBool>,
         search_state: Arc<Mutex<SearchState>>,
     ) {
         let compute_indices = true;
         std::thread::spawn(move || {
-            let matches = file_search::run(
-                &query,
-                MAX_FILE_SEARCH_RESULTS,
-                &search_dir,
-                Vec::new(),
-                NUM_FILE_SEARCH_THREADS,
-                cancellation_token.clone(),
-                compute_indices,
-                true,
-            )
-            .map(|res| res.matches)
-            .unwrap_or_default();
+            // `@config,settings` finds files matching either fragment.
+            let patterns = file_search::split_patterns(&query);
+            let (matches, truncated_walk) = {
+                let mut cached = index.lock().unwrap_or_else(PoisonError::into_inner);
+                match refreshed_index(&mut cached, &search_dir, filters) {
+                    Ok(index) => {
+                        let results = index.query(
+                            &patterns,
+                            MAX_FILE_SEARCH_RESULTS,
+                            compute_indices,
+                            &cancellation_token,
+                        );
+                        (results.matches, results.truncated_walk)
+                    }
+                    Err(err) => {
+                        tracing::warn!("failed to index {}: {err}", search_dir.display());
+                        (Vec::new(), false)
+                    }
+                }
+            };
 
             let is_cancelled = cancellation_token.load(Ordering::Relaxed);
             if !is_cancelled {
-                tx.send(AppEvent::FileSearchResult { query, matches });
+                tx.send(AppEvent::FileSearchResult {
+                    query,
+                    matches,
+                    truncated_walk,
+                });
             }
 
             // Reset the active search state. Do a pointer comparison to verify
@@ -197,3 +246,78 @@ impl FileSearchManager {
         });
     }
 }
+
+/// The cached index, built on first use and re-walked when the tree changed
+/// since it was last checked.
+fn refreshed_index<'a>(
+    cached: &'a mut Option<CachedIndex>,
+    search_dir: &Path,
+    filters: file_search::SearchFilters,
+) -> anyhow::Result<&'a file_search::FileIndex> {
+    match cached {
+        Some(entry) if entry.checked_at.elapsed() < INDEX_STALENESS_CHECK_INTERVAL => {}
+        Some(entry) => {
+            if entry.index.is_stale() {
+                entry.index.refresh()?;
+            }
+            entry.checked_at = Instant::now();
+        }
+        None => {
+            let index =
+                file_search::FileIndex::build(search_dir, filters, NUM_FILE_SEARCH_THREADS, true)?;
+            *cached = Some(CachedIndex {
+                index,
+                checked_at: Instant::now(),
+            });
+        }
+    }
+    cached
+        .as_ref()
+        .map(|entry| &entry.index)
+        .ok_or_else(|| anyhow::anyhow!("file index missing after build"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    #[test]
+    fn codexignore_keeps_files_out_of_results() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::create_dir_all(dir.path().join("notes")).expect("mkdir");
+        for name in ["notes/draft.md", "notes/keep-me.md", "readme.md"] {
+            std::fs::write(dir.path().join(name), "").expect("write");
+        }
+        std::fs::write(
+            dir.path().join(file_search::CODEXIGNORE_FILENAME),
+            "notes/*.md\n!keep-me.md\n",
+        )
+        .expect("write");
+        let path_filter = file_search::PathFilter::load(dir.path()).expect("load");
+        let (tx, mut rx) = unbounded_channel();
+        let manager = FileSearchManager::new(
+            dir.path().to_path_buf(),
+            file_search::SearchFilters::default(),
+            path_filter,
+            AppEventSender::new(tx),
+        );
+
+        manager.on_user_query("md".to_string());
+
+        let mut paths = loop {
+            match rx.blocking_recv().expect("search result") {
+                AppEvent::FileSearchResult { matches, .. } => {
+                    break matches
+                        .into_iter()
+                        .map(|file_match| file_match.path)
+                        .collect::<Vec<_>>();
+                }
+                _ => continue,
+            }
+        };
+        paths.sort();
+        assert_eq!(paths, vec!["notes/keep-me.md", "readme.md"]);
+    }
+}
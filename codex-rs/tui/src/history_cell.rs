@@ -1,3 +1,6 @@
+use crate::cloud_tasks_service::CloudTaskTransition;
+use crate::cloud_tasks_service::status_label;
+use crate::diff_render::WordDiffMode;
 use crate::diff_render::create_diff_summary;
 use crate::diff_render::display_path_for;
 use crate::exec_cell::CommandOutput;
@@ -22,15 +25,28 @@ use crate::wrapping::RtOptions;
 use crate::wrapping::word_wrap_line;
 use crate::wrapping::word_wrap_lines;
 use base64::Engine;
+use codex_cloud_tasks_client::TaskStatus;
+use codex_cloud_tasks_client::TaskSummary;
 use codex_common::format_env_display::format_env_display;
 use codex_core::config::Config;
 use codex_core::config::types::McpServerTransportConfig;
 use codex_core::config::types::ReasoningSummaryFormat;
+use codex_core::protocol::BaseDivergenceEvent;
+use codex_core::protocol::ContextScope;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::McpAuthStatus;
 use codex_core::protocol::McpInvocation;
+use codex_core::protocol::NetworkDestination;
+use codex_core::protocol::ResumeBriefingEvent;
+use codex_core::protocol::ReviewFinding;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
+use codex_core::review_anchor::AnchorResolution;
+use codex_core::review_anchor::resolve_finding;
+use codex_core::review_format::format_review_finding;
+use codex_core::review_format::review_findings_header;
+use codex_core::tool_inventory::ToolInventory;
+use codex_core::tool_inventory::ToolStatus;
 use codex_protocol::plan_tool::PlanItemArg;
 use codex_protocol::plan_tool::StepStatus;
 use codex_protocol::plan_tool::UpdatePlanArgs;
@@ -263,6 +279,52 @@ impl HistoryCell for AgentMessageCell {
     }
 }
 
+/// Review findings shown at their position in the file when the review
+/// finished. Findings whose code had already been removed are grayed out.
+#[derive(Debug)]
+pub(crate) struct ReviewFindingsCell {
+    lines: Vec<Line<'static>>,
+}
+
+impl ReviewFindingsCell {
+    /// Relocate `findings` against the files on disk and render them once.
+    pub(crate) fn new(findings: Vec<ReviewFinding>) -> Self {
+        let mut lines: Vec<Line<'static>> = vec![review_findings_header(findings.len()).into()];
+        for mut finding in findings {
+            let stale = match resolve_finding(&finding) {
+                AnchorResolution::Current(range) => {
+                    finding.code_location.line_range = range;
+                    false
+                }
+                AnchorResolution::Stale => {
+                    finding.title.push_str(" (stale)");
+                    true
+                }
+            };
+            let mut rendered = Vec::new();
+            append_markdown(&format_review_finding(&finding, None), None, &mut rendered);
+            lines.push("".into());
+            if stale {
+                lines.extend(rendered.into_iter().map(|line| line.dim()));
+            } else {
+                lines.extend(rendered);
+            }
+        }
+        Self { lines }
+    }
+}
+
+impl HistoryCell for ReviewFindingsCell {
+    fn display_lines(&self, width: u16) -> Vec<Line<'static>> {
+        word_wrap_lines(
+            &self.lines,
+            RtOptions::new(width as usize)
+                .initial_indent("• ".dim().into())
+                .subsequent_indent("  ".into()),
+        )
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct PlainHistoryCell {
     lines: Vec<Line<'static>>,
@@ -454,6 +516,92 @@ pub fn new_approval_decision_cell(
     ))
 }
 
+/// Recorded when the user always allows commands matching `pattern` in the
+/// project.
+pub fn new_always_allow_decision_cell(pattern: &str) -> Box<dyn HistoryCell> {
+    Box::new(PrefixedWrappedHistoryCell::new(
+        Line::from(vec![
+            "You ".into(),
+            "always allowed".bold(),
+            " codex to run ".into(),
+            Span::from(pattern.to_string()).dim(),
+            " in this project".bold(),
+        ]),
+        "✔ ".green(),
+        "  ",
+    ))
+}
+
+/// Recorded when the user approves only some hunks of a patch.
+pub fn new_patch_hunks_decision_cell(applied: usize, total: usize) -> Box<dyn HistoryCell> {
+    let rejected = total.saturating_sub(applied);
+    Box::new(PrefixedWrappedHistoryCell::new(
+        Line::from(vec![
+            "You ".into(),
+            "applied".bold(),
+            format!(" {applied} of {total} hunks").into(),
+            format!(" ({rejected} rejected)").dim(),
+        ]),
+        "✔ ".green(),
+        "  ",
+    ))
+}
+
+pub fn new_network_approval_decision_cell(
+    destination: &NetworkDestination,
+    decision: codex_core::protocol::ReviewDecision,
+) -> Box<dyn HistoryCell> {
+    use codex_core::protocol::ReviewDecision::*;
+
+    let target = Span::from(destination.to_string()).dim();
+    let (symbol, summary): (Span<'static>, Vec<Span<'static>>) = match decision {
+        Approved => (
+            "✔ ".green(),
+            vec![
+                "You ".into(),
+                "approved".bold(),
+                " a connection to ".into(),
+                target,
+                " this time".bold(),
+            ],
+        ),
+        ApprovedForSession => (
+            "✔ ".green(),
+            vec![
+                "You ".into(),
+                "approved".bold(),
+                " connections to ".into(),
+                target,
+                " every time this session".bold(),
+            ],
+        ),
+        Denied => (
+            "✗ ".red(),
+            vec![
+                "You ".into(),
+                "blocked".bold(),
+                " a connection to ".into(),
+                target,
+            ],
+        ),
+        Abort => (
+            "✗ ".red(),
+            vec![
+                "You ".into(),
+                "canceled".bold(),
+                " the connection to ".into(),
+                target,
+            ],
+        ),
+    };
+
+    Box::new(PrefixedWrappedHistoryCell::new(
+        Line::from(summary),
+        symbol,
+        "  ",
+    ))
+}
+
 /// Cyan history cell line showing the current review status.
 pub(crate) fn new_review_status_line(message: String) -> PlainHistoryCell {
     PlainHistoryCell {
@@ -465,11 +613,28 @@ pub(crate) fn new_review_status_line(message: String) -> PlainHistoryCell {
 pub(crate) struct PatchHistoryCell {
     changes: HashMap<PathBuf, FileChange>,
     cwd: PathBuf,
+    word_diff_extensions: Vec<String>,
+}
+
+impl PatchHistoryCell {
+    /// Renders the patch using the transcript overlay's word diff setting.
+    pub(crate) fn lines_with_word_diff(
+        &self,
+        width: u16,
+        mode: WordDiffMode,
+    ) -> Vec<Line<'static>> {
+        create_diff_summary(
+            &self.changes,
+            &self.cwd,
+            width as usize,
+            mode.resolve(&self.word_diff_extensions),
+        )
+    }
 }
 
 impl HistoryCell for PatchHistoryCell {
     fn display_lines(&self, width: u16) -> Vec<Line<'static>> {
-        create_diff_summary(&self.changes, &self.cwd, width as usize)
+        self.lines_with_word_diff(width, WordDiffMode::Auto)
     }
 }
 
@@ -596,7 +761,16 @@ pub(crate) fn new_session_info(
         );
 
         // Help lines below the header (new copy and list)
-        let help_lines: Vec<Line<'static>> = vec![
+        let mut help_lines: Vec<Line<'static>> = Vec::new();
+        if config.persistence.is_ephemeral() {
+            help_lines.push(Line::from(vec![
+                "  ".into(),
+                "Ephemeral session".magenta().bold(),
+                " - nothing is saved to CODEX_HOME and it cannot be resumed".dim(),
+            ]));
+            help_lines.push(Line::from(""));
+        }
+        help_lines.extend([
             "  To get started, describe a task or try one of these commands:"
                 .dim()
                 .into(),
@@ -626,7 +800,7 @@ pub(crate) fn new_session_info(
                 "/review".into(),
                 " - review any changes and find issues".dim(),
             ]),
-        ];
+        ]);
 
         CompositeHistoryCell {
             parts: vec![
@@ -806,6 +980,8 @@ pub(crate) struct McpToolCallCell {
     start_time: Instant,
     duration: Option<Duration>,
     result: Option<Result<mcp_types::CallToolResult, String>>,
+    /// The result came from the session's tool result cache.
+    cached: bool,
 }
 
 impl McpToolCallCell {
@@ -816,6 +992,7 @@ impl McpToolCallCell {
             start_time: Instant::now(),
             duration: None,
             result: None,
+            cached: false,
         }
     }
 
@@ -827,11 +1004,13 @@ impl McpToolCallCell {
         &mut self,
         duration: Duration,
         result: Result<mcp_types::CallToolResult, String>,
+        cached: bool,
     ) -> Option<Box<dyn HistoryCell>> {
         let image_cell = try_new_completed_mcp_tool_call_with_image_output(&result)
             .map(|cell| Box::new(cell) as Box<dyn HistoryCell>);
         self.duration = Some(duration);
         self.result = Some(result);
+        self.cached = cached;
         image_cell
     }
 
@@ -885,7 +1064,10 @@ impl HistoryCell for McpToolCallCell {
             "Calling"
         };
 
-        let invocation_line = line_to_static(&format_mcp_invocation(self.invocation.clone()));
+        let mut invocation_line = line_to_static(&format_mcp_invocation(self.invocation.clone()));
+        if self.cached {
+            invocation_line.push_span(" (cached)".dim());
+        }
         let mut compact_spans = vec![bullet.clone(), " ".into(), header_text.bold(), " ".into()];
         let mut compact_header = Line::from(compact_spans.clone());
         let reserved = compact_header.width();
@@ -1051,6 +1233,141 @@ impl HistoryCell for DeprecationNoticeCell {
     }
 }
 
+/// Warning that the session's worktree is behind the upstream of its base branch.
+#[allow(clippy::disallowed_methods)]
+pub(crate) fn new_base_divergence_notice(divergence: &BaseDivergenceEvent) -> PlainHistoryCell {
+    let BaseDivergenceEvent {
+        base,
+        upstream,
+        behind,
+        tip_subjects,
+    } = divergence;
+    let commits = if *behind == 1 { "commit" } else { "commits" };
+    let mut lines: Vec<Line<'static>> = vec![
+        vec![
+            "⚠ ".yellow().bold(),
+            format!("This worktree is {behind} {commits} behind {upstream} (base {base})").yellow(),
+        ]
+        .into(),
+    ];
+    for subject in tip_subjects {
+        lines.push(vec!["  • ".dim(), subject.clone().dim()].into());
+    }
+    let unlisted = behind.saturating_sub(tip_subjects.len());
+    if unlisted > 0 {
+        lines.push(format!("  … and {unlisted} more").dim().into());
+    }
+    lines.push(
+        vec![
+            "  Run ".dim(),
+            "/sync-base".cyan(),
+            " to fetch and rebase.".dim(),
+        ]
+        .into(),
+    );
+    PlainHistoryCell { lines }
+}
+
+/// Rendered when a session is resumed after the repository moved on.
+pub(crate) fn new_resume_briefing(briefing: &ResumeBriefingEvent) -> PlainHistoryCell {
+    let ResumeBriefingEvent {
+        previous_branch,
+        branch,
+        previous_head,
+        head,
+        new_commits,
+        commit_subjects,
+        modified_files,
+        deleted_files,
+        cleaned_files,
+    } = briefing;
+    let short = |sha: &Option<String>| {
+        sha.as_deref()
+            .map(|sha| sha.get(..7).unwrap_or(sha).to_string())
+            .unwrap_or_else(|| "?".to_string())
+    };
+    let mut lines: Vec<Line<'static>> = vec![
+        vec![
+            "↻ ".cyan().bold(),
+            "Since this session was last active".bold(),
+        ]
+        .into(),
+    ];
+    if previous_branch != branch {
+        let name = |branch: &Option<String>| {
+            branch
+                .clone()
+                .unwrap_or_else(|| "detached HEAD".to_string())
+        };
+        lines.push(
+            format!(
+                "  Checkout moved from {} to {}",
+                name(previous_branch),
+                name(branch)
+            )
+            .into(),
+        );
+    }
+    if previous_head != head {
+        match new_commits {
+            Some(count) if *count > 0 => {
+                let commits = if *count == 1 { "commit" } else { "commits" };
+                lines.push(format!("  {count} new {commits}").into());
+                for subject in commit_subjects {
+                    lines.push(vec!["    • ".dim(), subject.clone().dim()].into());
+                }
+                let unlisted = count.saturating_sub(commit_subjects.len());
+                if unlisted > 0 {
+                    lines.push(format!("    … and {unlisted} more").dim().into());
+                }
+            }
+            Some(_) => lines.push(
+                format!(
+                    "  HEAD moved from {} to {} without new commits",
+                    short(previous_head),
+                    short(head)
+                )
+                .into(),
+            ),
+            None => lines.push(
+                format!(
+                    "  HEAD moved from {} to {} (history not available locally)",
+                    short(previous_head),
+                    short(head)
+                )
+                .into(),
+            ),
+        }
+    }
+    let file_section = |lines: &mut Vec<Line<'static>>, title: Line<'static>, files: &[String]| {
+        if files.is_empty() {
+            return;
+        }
+        lines.push(title);
+        for file in files {
+            lines.push(vec!["    • ".dim(), file.clone().into()].into());
+        }
+    };
+    file_section(
+        &mut lines,
+        "  Changed by someone else since this session edited them:"
+            .bold()
+            .into(),
+        modified_files,
+    );
+    file_section(
+        &mut lines,
+        "  Deleted since this session edited them:".red().into(),
+        deleted_files,
+    );
+    file_section(
+        &mut lines,
+        "  Committed or discarded since:".into(),
+        cleaned_files,
+    );
+    PlainHistoryCell { lines }
+}
+
 /// Render a summary of configured MCP servers from the current `Config`.
 pub(crate) fn empty_mcp_output() -> PlainHistoryCell {
     let lines: Vec<Line<'static>> = vec![
@@ -1070,6 +1387,124 @@ pub(crate) fn empty_mcp_output() -> PlainHistoryCell {
     PlainHistoryCell { lines }
 }
 
+/// Render the path-scoped instructions declared in project docs, active ones first.
+pub(crate) fn new_context_scopes_output(scopes: Vec<ContextScope>) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> = vec![
+        "/context scopes".magenta().into(),
+        "".into(),
+        vec!["📐  ".into(), "Scoped instructions".bold()].into(),
+        "".into(),
+    ];
+
+    if scopes.is_empty() {
+        lines.push(
+            "  • No scoped instructions found in project docs."
+                .italic()
+                .into(),
+        );
+        lines.push("".into());
+        return PlainHistoryCell { lines };
+    }
+
+    let (active, inactive): (Vec<_>, Vec<_>) = scopes.into_iter().partition(|s| s.active);
+    for scope in active.into_iter().chain(inactive) {
+        let status = if scope.active {
+            "active".green()
+        } else {
+            "inactive".dim()
+        };
+        lines.push(
+            vec![
+                "  • ".into(),
+                scope.patterns.join(", ").into(),
+                " ".into(),
+                status,
+            ]
+            .into(),
+        );
+        lines.push(vec!["    ".into(), scope.source.display().to_string().dim()].into());
+    }
+    lines.push("".into());
+
+    PlainHistoryCell { lines }
+}
+
+/// Render the tool versions shared with the model, as listed by `/env`.
+pub(crate) fn new_tool_inventory_output(inventory: &ToolInventory) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> = vec![
+        "/env".magenta().into(),
+        "".into(),
+        vec!["🧰  ".into(), "Tool versions".bold()].into(),
+        "".into(),
+    ];
+    for (tool, line) in inventory.tools.iter().zip(inventory.format_lines()) {
+        let line: Line<'static> = vec!["  • ".into(), line.into()].into();
+        lines.push(match tool.status {
+            ToolStatus::Found { .. } => line,
+            ToolStatus::Absent | ToolStatus::TimedOut => line.dim(),
+        });
+    }
+    lines.push("".into());
+
+    PlainHistoryCell { lines }
+}
+
+fn styled_task_status(status: &TaskStatus) -> Span<'static> {
+    let label = status_label(status);
+    match status {
+        TaskStatus::Pending => label.magenta(),
+        TaskStatus::Running => label.cyan(),
+        TaskStatus::Ready => label.green(),
+        TaskStatus::Applied => label.blue(),
+        TaskStatus::Error => label.red(),
+    }
+}
+
+/// One-line notice for a cloud task status change seen by the background poller.
+pub(crate) fn new_cloud_task_transition(transition: &CloudTaskTransition) -> PlainHistoryCell {
+    let mut line: Vec<Span<'static>> = vec![
+        "• ".dim(),
+        "Cloud task ".into(),
+        transition.title.clone().bold(),
+        " ".into(),
+    ];
+    if let Some(from) = &transition.from {
+        line.push(status_label(from).dim());
+        line.push(" → ".dim());
+    }
+    line.push(styled_task_status(&transition.to));
+    PlainHistoryCell {
+        lines: vec![line.into()],
+    }
+}
+
+/// Render the latest cloud task snapshot, as listed by `/cloud`.
+pub(crate) fn new_cloud_tasks_output(tasks: &[TaskSummary]) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> = vec![
+        "/cloud".magenta().into(),
+        "".into(),
+        vec!["☁️  ".into(), "Cloud tasks".bold()].into(),
+        "".into(),
+    ];
+    if tasks.is_empty() {
+        lines.push("  • No cloud tasks found.".italic().into());
+    }
+    for task in tasks {
+        lines.push(
+            vec![
+                "  • ".into(),
+                styled_task_status(&task.status),
+                " ".into(),
+                task.title.clone().into(),
+            ]
+            .into(),
+        );
+    }
+    lines.push("".into());
+
+    PlainHistoryCell { lines }
+}
+
 /// Render MCP tools grouped by connection using the fully-qualified tool names.
 pub(crate) fn new_mcp_tools_output(
     config: &Config,
@@ -1323,10 +1758,12 @@ impl HistoryCell for PlanUpdateCell {
 pub(crate) fn new_patch_event(
     changes: HashMap<PathBuf, FileChange>,
     cwd: &Path,
+    word_diff_extensions: &[String],
 ) -> PatchHistoryCell {
     PatchHistoryCell {
         changes,
         cwd: cwd.to_path_buf(),
+        word_diff_extensions: word_diff_extensions.to_vec(),
     }
 }
 
@@ -1342,6 +1779,7 @@ pub(crate) fn new_patch_apply_failure(stderr: String) -> PlainHistoryCell {
                 exit_code: 1,
                 formatted_output: String::new(),
                 aggregated_output: stderr,
+                stderr_ranges: Vec::new(),
             }),
             OutputLinesParams {
                 line_limit: TOOL_CALL_MAX_LINES,
@@ -1461,12 +1899,15 @@ mod tests {
     use crate::exec_cell::CommandOutput;
     use crate::exec_cell::ExecCall;
     use crate::exec_cell::ExecCell;
+    use crate::exec_cell::LiveOutput;
     use codex_core::config::Config;
     use codex_core::config::ConfigOverrides;
     use codex_core::config::ConfigToml;
     use codex_core::config::types::McpServerConfig;
     use codex_core::config::types::McpServerTransportConfig;
     use codex_core::protocol::McpAuthStatus;
+    use codex_core::protocol::OutputRange;
+    use codex_core::tool_inventory::ToolVersion;
     use codex_protocol::parse_command::ParsedCommand;
     use dirs::home_dir;
     use pretty_assertions::assert_eq;
@@ -1474,6 +1915,7 @@ mod tests {
     use std::collections::HashMap;
 
     use codex_core::protocol::ExecCommandSource;
+    use codex_core::protocol::ExecOutputStream;
     use mcp_types::CallToolResult;
     use mcp_types::ContentBlock;
     use mcp_types::TextContent;
@@ -1505,6 +1947,50 @@ mod tests {
         render_lines(&cell.transcript_lines(u16::MAX))
     }
 
+    #[test]
+    fn review_findings_follow_edits_and_gray_out_stale_ones() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("lib.rs");
+        std::fs::write(
+            &path,
+            "fn a() {}\nfn a2() {}\nfn a3() {}\nfn a4() {}\n\nfn b() {\n    bug();\n}\n",
+        )
+        .expect("write");
+        let finding = |title: &str, line: u32| ReviewFinding {
+            title: title.to_string(),
+            body: "Explain".to_string(),
+            confidence_score: 0.5,
+            priority: 1,
+            code_location: codex_core::protocol::ReviewCodeLocation {
+                absolute_file_path: path.clone(),
+                line_range: codex_core::protocol::ReviewLineRange {
+                    start: line,
+                    end: line,
+                },
+                anchor: None,
+            },
+        };
+        let mut findings = vec![finding("Moved", 7), finding("Removed", 2)];
+        codex_core::review_anchor::anchor_findings(&mut findings);
+
+        std::fs::write(&path, "// header\n// more\n\nfn b() {\n    bug();\n}\n").expect("write");
+        let cell = ReviewFindingsCell::new(findings);
+        let lines = cell.display_lines(400);
+        let rendered = render_lines(&lines);
+
+        let path = path.display();
+        let moved = rendered
+            .iter()
+            .position(|line| line.contains(&format!("Moved — {path}:5-5")))
+            .expect("moved finding shows its new line");
+        let stale = rendered
+            .iter()
+            .position(|line| line.contains(&format!("Removed (stale) — {path}:2-2")))
+            .expect("removed finding is marked stale");
+        assert!(!lines[moved].style.add_modifier.contains(Modifier::DIM));
+        assert!(lines[stale].style.add_modifier.contains(Modifier::DIM));
+    }
+
     #[test]
     fn mcp_tools_output_masks_sensitive_values() {
         let mut config = test_config();
@@ -1523,6 +2009,7 @@ mod tests {
             tool_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
+            cache_tool_results: None,
         };
         config.mcp_servers.insert("docs".to_string(), stdio_config);
 
@@ -1542,6 +2029,7 @@ mod tests {
             tool_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
+            cache_tool_results: None,
         };
         config.mcp_servers.insert("http".to_string(), http_config);
 
@@ -1590,6 +2078,38 @@ mod tests {
         insta::assert_snapshot!(rendered);
     }
 
+    #[test]
+    fn tool_inventory_output_lists_every_tool() {
+        let inventory = ToolInventory {
+            tools: vec![
+                ToolVersion {
+                    name: "node".to_string(),
+                    status: ToolStatus::Found {
+                        version: "v20.11.1".to_string(),
+                    },
+                },
+                ToolVersion {
+                    name: "deno".to_string(),
+                    status: ToolStatus::Absent,
+                },
+            ],
+        };
+        let cell = new_tool_inventory_output(&inventory);
+
+        assert_eq!(
+            render_lines(&cell.display_lines(80)),
+            vec![
+                "/env".to_string(),
+                "".to_string(),
+                "🧰  Tool versions".to_string(),
+                "".to_string(),
+                "  • node: v20.11.1".to_string(),
+                "  • deno: not found".to_string(),
+                "".to_string(),
+            ]
+        );
+    }
+
     #[test]
     fn empty_agent_message_cell_transcript() {
         let cell = AgentMessageCell::new(vec![Line::default()], false);
@@ -1660,7 +2180,7 @@ mod tests {
 
         let mut cell = new_active_mcp_tool_call("call-2".into(), invocation);
         assert!(
-            cell.complete(Duration::from_millis(1420), Ok(result))
+            cell.complete(Duration::from_millis(1420), Ok(result), false)
                 .is_none()
         );
 
@@ -1682,7 +2202,7 @@ mod tests {
 
         let mut cell = new_active_mcp_tool_call("call-3".into(), invocation);
         assert!(
-            cell.complete(Duration::from_secs(2), Err("network timeout".into()))
+            cell.complete(Duration::from_secs(2), Err("network timeout".into()), false)
                 .is_none()
         );
 
@@ -1691,6 +2211,36 @@ mod tests {
         insta::assert_snapshot!(rendered);
     }
 
+    #[test]
+    fn cached_mcp_tool_call_is_labeled() {
+        let invocation = McpInvocation {
+            server: "search".into(),
+            tool: "find_docs".into(),
+            arguments: Some(json!({"query": "ratatui"})),
+        };
+        let result = CallToolResult {
+            content: vec![ContentBlock::TextContent(TextContent {
+                annotations: None,
+                text: "styles.md".into(),
+                r#type: "text".into(),
+            })],
+            is_error: None,
+            structured_content: None,
+        };
+
+        let mut cell = new_active_mcp_tool_call("call-cached".into(), invocation);
+        assert!(
+            cell.complete(Duration::from_millis(0), Ok(result), true)
+                .is_none()
+        );
+
+        let rendered = render_lines(&cell.display_lines(80));
+        assert_eq!(
+            rendered.first().map(String::as_str),
+            Some("• Called search.find_docs({\"query\":\"ratatui\"}) (cached)")
+        );
+    }
+
     #[test]
     fn completed_mcp_tool_call_multiple_outputs_snapshot() {
         let invocation = McpInvocation {
@@ -1726,7 +2276,7 @@ mod tests {
 
         let mut cell = new_active_mcp_tool_call("call-4".into(), invocation);
         assert!(
-            cell.complete(Duration::from_millis(640), Ok(result))
+            cell.complete(Duration::from_millis(640), Ok(result), false)
                 .is_none()
         );
 
@@ -1758,7 +2308,7 @@ mod tests {
 
         let mut cell = new_active_mcp_tool_call("call-5".into(), invocation);
         assert!(
-            cell.complete(Duration::from_millis(1280), Ok(result))
+            cell.complete(Duration::from_millis(1280), Ok(result), false)
                 .is_none()
         );
 
@@ -1797,7 +2347,7 @@ mod tests {
 
         let mut cell = new_active_mcp_tool_call("call-6".into(), invocation);
         assert!(
-            cell.complete(Duration::from_millis(320), Ok(result))
+            cell.complete(Duration::from_millis(320), Ok(result), false)
                 .is_none()
         );
 
@@ -1878,6 +2428,7 @@ mod tests {
             start_time: Some(Instant::now()),
             duration: None,
             interaction_input: None,
+            live_output: LiveOutput::default(),
         });
         // Mark call complete so markers are ✓
         cell.complete_call(&call_id, CommandOutput::default(), Duration::from_millis(1));
@@ -1902,6 +2453,7 @@ mod tests {
             start_time: Some(Instant::now()),
             duration: None,
             interaction_input: None,
+            live_output: LiveOutput::default(),
         });
         // Call 1: Search only
         cell.complete_call("c1", CommandOutput::default(), Duration::from_millis(1));
@@ -1968,6 +2520,7 @@ mod tests {
             start_time: Some(Instant::now()),
             duration: None,
             interaction_input: None,
+            live_output: LiveOutput::default(),
         });
         cell.complete_call("c1", CommandOutput::default(), Duration::from_millis(1));
         let lines = cell.display_lines(80);
@@ -1989,6 +2542,7 @@ mod tests {
             start_time: Some(Instant::now()),
             duration: None,
             interaction_input: None,
+            live_output: LiveOutput::default(),
         });
         // Mark call complete so it renders as "Ran"
         cell.complete_call(&call_id, CommandOutput::default(), Duration::from_millis(1));
@@ -2000,6 +2554,95 @@ mod tests {
         insta::assert_snapshot!(rendered);
     }
 
+    #[test]
+    fn filtered_env_vars_are_noted_under_the_command() {
+        let mut cell = ExecCell::new(ExecCall {
+            call_id: "c1".to_string(),
+            command: vec!["env".into()],
+            parsed: Vec::new(),
+            output: None,
+            source: ExecCommandSource::Agent,
+            start_time: Some(Instant::now()),
+            duration: None,
+            interaction_input: None,
+            live_output: LiveOutput::default(),
+        })
+        .with_filtered_env_vars(Some(3));
+        cell.complete_call("c1", CommandOutput::default(), Duration::from_millis(1));
+
+        let rendered = render_lines(&cell.display_lines(80));
+        assert_eq!(
+            rendered,
+            vec![
+                "• Ran env".to_string(),
+                "  │ env: 3 vars filtered".to_string(),
+                "  └ (no output)".to_string(),
+            ]
+        );
+    }
+
+    /// A running `cargo build` that has printed eight lines, the last one
+    /// unterminated, and shows three.
+    fn streaming_exec_cell() -> ExecCell {
+        let mut cell = ExecCell::new(ExecCall {
+            call_id: "c1".to_string(),
+            command: vec!["cargo".into(), "build".into()],
+            parsed: Vec::new(),
+            output: None,
+            source: ExecCommandSource::Agent,
+            start_time: None,
+            duration: None,
+            interaction_input: None,
+            live_output: LiveOutput::default(),
+        })
+        .with_follow_lines(3);
+        let output = (1..=8).map(|i| format!("line {i}")).collect::<Vec<_>>();
+        let output = output.join("\n");
+        for chunk in output.as_bytes().chunks(5) {
+            assert!(cell.push_output_chunk("c1", ExecOutputStream::Stdout, None, chunk));
+        }
+        cell
+    }
+
+    #[test]
+    fn running_exec_output_collapses_to_tail() {
+        let cell = streaming_exec_cell();
+        let rendered = render_lines(&cell.display_lines(80)).join("\n");
+        insta::assert_snapshot!(rendered);
+    }
+
+    #[test]
+    fn running_exec_output_expanded() {
+        let mut cell = streaming_exec_cell();
+        cell.toggle_output_expanded();
+        let rendered = render_lines(&cell.display_lines(80)).join("\n");
+        insta::assert_snapshot!(rendered);
+    }
+
+    #[test]
+    fn expanded_exec_output_stays_expanded_after_completion() {
+        let mut cell = streaming_exec_cell();
+        cell.toggle_output_expanded();
+        let output = (1..=8).map(|i| format!("line {i}\n")).collect::<String>();
+        cell.complete_call(
+            "c1",
+            CommandOutput {
+                exit_code: 0,
+                aggregated_output: output.clone(),
+                formatted_output: output,
+                stderr_ranges: Vec::new(),
+            },
+            Duration::from_millis(1),
+        );
+
+        let mut expected = vec!["• Ran cargo build".to_string()];
+        expected.extend((1..=8).map(|i| {
+            let prefix = if i == 1 { "  └ " } else { "    " };
+            format!("{prefix}line {i}")
+        }));
+        assert_eq!(render_lines(&cell.display_lines(80)), expected);
+    }
+
     #[test]
     fn single_line_command_compact_when_fits() {
         let call_id = "c1".to_string();
@@ -2012,6 +2655,7 @@ mod tests {
             start_time: Some(Instant::now()),
             duration: None,
             interaction_input: None,
+            live_output: LiveOutput::default(),
         });
         cell.complete_call(&call_id, CommandOutput::default(), Duration::from_millis(1));
         // Wide enough that it fits inline
@@ -2033,6 +2677,7 @@ mod tests {
             start_time: Some(Instant::now()),
             duration: None,
             interaction_input: None,
+            live_output: LiveOutput::default(),
         });
         cell.complete_call(&call_id, CommandOutput::default(), Duration::from_millis(1));
         let lines = cell.display_lines(24);
@@ -2053,6 +2698,7 @@ mod tests {
             start_time: Some(Instant::now()),
             duration: None,
             interaction_input: None,
+            live_output: LiveOutput::default(),
         });
         cell.complete_call(&call_id, CommandOutput::default(), Duration::from_millis(1));
         let lines = cell.display_lines(80);
@@ -2074,6 +2720,7 @@ mod tests {
             start_time: Some(Instant::now()),
             duration: None,
             interaction_input: None,
+            live_output: LiveOutput::default(),
         });
         cell.complete_call(&call_id, CommandOutput::default(), Duration::from_millis(1));
         let lines = cell.display_lines(28);
@@ -2081,6 +2728,34 @@ mod tests {
         insta::assert_snapshot!(rendered);
     }
 
+    #[test]
+    fn truncated_command_chain_names_every_program() {
+        let call_id = "c1".to_string();
+        let cmd = "mkdir -p build\ncd build\ncmake ..\nmake -j8".to_string();
+        let mut cell = ExecCell::new(ExecCall {
+            call_id: call_id.clone(),
+            command: vec!["bash".into(), "-lc".into(), cmd],
+            parsed: Vec::new(),
+            output: None,
+            source: ExecCommandSource::Agent,
+            start_time: Some(Instant::now()),
+            duration: None,
+            interaction_input: None,
+            live_output: LiveOutput::default(),
+        });
+        cell.complete_call(&call_id, CommandOutput::default(), Duration::from_millis(1));
+        assert_eq!(
+            render_lines(&cell.display_lines(80)),
+            vec![
+                "• Ran mkdir -p build",
+                "  │ cd build",
+                "  │ cmake ..",
+                "  │ … +1 lines (mkdir, cd, cmake, make)",
+                "  └ (no output)",
+            ]
+        );
+    }
+
     #[test]
     fn stderr_tail_more_than_five_lines_snapshot() {
         // Build an exec cell with a non-zero exit and 10 lines on stderr to exercise
@@ -2095,6 +2770,7 @@ mod tests {
             start_time: Some(Instant::now()),
             duration: None,
             interaction_input: None,
+            live_output: LiveOutput::default(),
         });
         let stderr: String = (1..=10)
             .map(|n| n.to_string())
@@ -2106,6 +2782,7 @@ mod tests {
                 exit_code: 1,
                 formatted_output: String::new(),
                 aggregated_output: stderr,
+                stderr_ranges: Vec::new(),
             },
             Duration::from_millis(1),
         );
@@ -2142,6 +2819,7 @@ mod tests {
             start_time: Some(Instant::now()),
             duration: None,
             interaction_input: None,
+            live_output: LiveOutput::default(),
         });
 
         let stderr = "error: first line on stderr\nerror: second line on stderr".to_string();
@@ -2151,6 +2829,7 @@ mod tests {
                 exit_code: 1,
                 formatted_output: String::new(),
                 aggregated_output: stderr,
+                stderr_ranges: Vec::new(),
             },
             Duration::from_millis(5),
         );
@@ -2170,6 +2849,56 @@ mod tests {
             .join("\n");
         insta::assert_snapshot!(rendered);
     }
+
+    #[test]
+    fn interleaved_stderr_lines_are_tinted_red() {
+        let call_id = "c_interleaved".to_string();
+        let mut cell = ExecCell::new(ExecCall {
+            call_id: call_id.clone(),
+            command: vec!["bash".into(), "-lc".into(), "make".into()],
+            parsed: Vec::new(),
+            output: None,
+            source: ExecCommandSource::Agent,
+            start_time: Some(Instant::now()),
+            duration: None,
+            interaction_input: None,
+            live_output: LiveOutput::default(),
+        });
+        cell.complete_call(
+            &call_id,
+            CommandOutput {
+                exit_code: 1,
+                formatted_output: String::new(),
+                aggregated_output: "out1\nerr1\nout2\n".to_string(),
+                stderr_ranges: vec![OutputRange { start: 5, end: 10 }],
+            },
+            Duration::from_millis(5),
+        );
+
+        let output: Vec<(String, Option<Color>)> = cell
+            .display_lines(80)
+            .iter()
+            .skip(1)
+            .map(|line| {
+                let text = line
+                    .spans
+                    .iter()
+                    .map(|s| s.content.as_ref())
+                    .collect::<String>();
+                let fg = line.spans.last().and_then(|span| span.style.fg);
+                (text, fg)
+            })
+            .collect();
+        assert_eq!(
+            output,
+            vec![
+                ("  └ out1".to_string(), None),
+                ("    err1".to_string(), Some(Color::Red)),
+                ("    out2".to_string(), None),
+            ]
+        );
+    }
+
     #[test]
     fn user_history_cell_wraps_and_prefixes_each_line_snapshot() {
         let msg = "one two three four five six seven";
@@ -0,0 +1,475 @@
+//! Display state for `/clear`, deleted cells, and turns marked irrelevant.
+//!
+//! Flags arrive as [`HistoryFlag`]s echoed by the agent (live or replayed from
+//! the rollout on resume) and are applied against `App::transcript_cells`.
+//! Turns are numbered like backtracking does: by user message, counting from
+//! the most recent session header.
+
+use std::collections::BTreeSet;
+use std::ops::Range;
+use std::sync::Arc;
+
+use codex_core::protocol::HistoryFlag;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+
+use crate::app_backtrack::user_positions_iter;
+use crate::history_cell::AgentMessageCell;
+use crate::history_cell::HistoryCell;
+use crate::history_cell::UserHistoryCell;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CellDisplay {
+    Shown,
+    /// User message of an irrelevant turn; the rest of the turn is folded
+    /// into a one-line note.
+    CollapsedTurn,
+    Hidden,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct HistoryFlags {
+    /// Cells before this index are hidden by `/clear`.
+    cleared_above: Option<usize>,
+    /// Transcript indices hidden by a per-cell delete.
+    deleted: BTreeSet<usize>,
+    irrelevant_turns: BTreeSet<usize>,
+    /// Turns pinned for compaction. Not rendered; tracked so `p` can toggle.
+    pinned_turns: BTreeSet<usize>,
+}
+
+impl HistoryFlags {
+    /// Apply `flag` to the transcript as it stands now. Returns true when the
+    /// rendered history needs to be redrawn.
+    pub(crate) fn apply(&mut self, flag: &HistoryFlag, cells: &[Arc<dyn HistoryCell>]) -> bool {
+        match *flag {
+            HistoryFlag::OutputCellDeleted {
+                nth_user_message,
+                ref first_line,
+                occurrence,
+            } => match output_cell_position(cells, nth_user_message, first_line, occurrence) {
+                Some(idx) => {
+                    self.delete_cell(idx, cells);
+                    true
+                }
+                None => false,
+            },
+            HistoryFlag::ClearedAbove => {
+                self.cleared_above = Some(cells.len());
+                true
+            }
+            HistoryFlag::Uncleared => self.cleared_above.take().is_some(),
+            HistoryFlag::CellDeleted {
+                nth_user_message,
+                cell,
+            } => match message_cell_position(cells, nth_user_message, cell) {
+                Some(idx) => {
+                    self.delete_cell(idx, cells);
+                    true
+                }
+                None => false,
+            },
+            HistoryFlag::TurnRelevance {
+                nth_user_message,
+                irrelevant,
+            } => {
+                if irrelevant {
+                    self.irrelevant_turns.insert(nth_user_message)
+                } else {
+                    self.irrelevant_turns.remove(&nth_user_message)
+                }
+            }
+            // Only affects what compaction keeps.
+            HistoryFlag::Pinned {
+                nth_user_message,
+                pinned,
+            } => {
+                if pinned {
+                    self.pinned_turns.insert(nth_user_message);
+                } else {
+                    self.pinned_turns.remove(&nth_user_message);
+                }
+                false
+            }
+        }
+    }
+
+    /// Hide the cell at `idx` together with its stream continuations.
+    pub(crate) fn delete_cell(&mut self, idx: usize, cells: &[Arc<dyn HistoryCell>]) {
+        self.deleted.extend(cell_span(cells, idx));
+    }
+
+    pub(crate) fn is_turn_irrelevant(&self, nth_user_message: usize) -> bool {
+        self.irrelevant_turns.contains(&nth_user_message)
+    }
+
+    pub(crate) fn is_turn_pinned(&self, nth_user_message: usize) -> bool {
+        self.pinned_turns.contains(&nth_user_message)
+    }
+
+    /// Whether the most recently pushed cell should stay off screen.
+    pub(crate) fn hides_last_cell(&self, cells: &[Arc<dyn HistoryCell>]) -> bool {
+        if self.irrelevant_turns.is_empty() {
+            return false;
+        }
+        self.layout(cells).last() == Some(&CellDisplay::Hidden)
+    }
+
+    /// Drop state that points past the end of a transcript trimmed to `len`
+    /// cells holding `turns` user turns.
+    pub(crate) fn truncate(&mut self, len: usize, turns: usize) {
+        self.deleted.retain(|idx| *idx < len);
+        self.irrelevant_turns.retain(|turn| *turn < turns);
+        self.pinned_turns.retain(|turn| *turn < turns);
+        if let Some(cleared_above) = self.cleared_above.as_mut() {
+            *cleared_above = (*cleared_above).min(len);
+        }
+    }
+
+    /// Turn numbering restarts with every session header.
+    pub(crate) fn start_session(&mut self) {
+        self.irrelevant_turns.clear();
+        self.pinned_turns.clear();
+    }
+
+    /// Render the transcript as it should appear in the scrollback.
+    pub(crate) fn render(&self, cells: &[Arc<dyn HistoryCell>], width: u16) -> Vec<Line<'static>> {
+        let mut lines: Vec<Line<'static>> = Vec::new();
+        if self.cleared_above.is_some_and(|idx| idx > 0) {
+            lines.push("⋯ earlier output hidden — /unclear to restore".dim().into());
+        }
+        for (cell, display) in cells.iter().zip(self.layout(cells)) {
+            if display == CellDisplay::Hidden {
+                continue;
+            }
+            let display_lines = cell.display_lines(width);
+            if display_lines.is_empty() {
+                continue;
+            }
+            if !cell.is_stream_continuation() && !lines.is_empty() {
+                lines.push(Line::from(""));
+            }
+            lines.extend(display_lines);
+            if display == CellDisplay::CollapsedTurn {
+                lines.push(
+                    "  ↳ marked irrelevant — output collapsed and left out of context"
+                        .dim()
+                        .into(),
+                );
+            }
+        }
+        lines
+    }
+
+    fn layout(&self, cells: &[Arc<dyn HistoryCell>]) -> Vec<CellDisplay> {
+        let user_positions: Vec<usize> = user_positions_iter(cells).collect();
+        let cleared_above = self.cleared_above.unwrap_or(0);
+        (0..cells.len())
+            .map(|idx| {
+                if idx < cleared_above || self.deleted.contains(&idx) {
+                    return CellDisplay::Hidden;
+                }
+                match turn_of(&user_positions, idx) {
+                    Some(turn) if self.irrelevant_turns.contains(&turn) => {
+                        if user_positions[turn] == idx {
+                            CellDisplay::CollapsedTurn
+                        } else {
+                            CellDisplay::Hidden
+                        }
+                    }
+                    _ => CellDisplay::Shown,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Key identifying the message cell at `idx` across resume: the turn it
+/// belongs to and its index among that turn's user and agent messages.
+/// Returns `None` for cells that are not rebuilt on resume (tool calls,
+/// notices) or that precede the first user message.
+pub(crate) fn message_cell_key(
+    cells: &[Arc<dyn HistoryCell>],
+    idx: usize,
+) -> Option<(usize, usize)> {
+    if !is_message_cell(cells.get(idx)?) {
+        return None;
+    }
+    let user_positions: Vec<usize> = user_positions_iter(cells).collect();
+    let turn = turn_of(&user_positions, idx)?;
+    let ordinal = cells[user_positions[turn]..idx]
+        .iter()
+        .filter(|cell| is_message_cell(cell))
+        .count();
+    Some((turn, ordinal))
+}
+
+/// Key identifying the non-message cell at `idx` across resume: its turn,
+/// the first line of its transcript text, and how many earlier non-message
+/// cells of the turn start with the same line. Returns `None` for message
+/// cells and for cells that precede the first user message.
+pub(crate) fn output_cell_key(
+    cells: &[Arc<dyn HistoryCell>],
+    idx: usize,
+) -> Option<(usize, String, usize)> {
+    let cell = cells.get(idx)?;
+    if !is_output_cell(cell) {
+        return None;
+    }
+    let user_positions: Vec<usize> = user_positions_iter(cells).collect();
+    let turn = turn_of(&user_positions, idx)?;
+    let first_line = first_transcript_line(cell);
+    let occurrence = cells[user_positions[turn]..idx]
+        .iter()
+        .filter(|cell| is_output_cell(cell) && first_transcript_line(cell) == first_line)
+        .count();
+    Some((turn, first_line, occurrence))
+}
+
+fn output_cell_position(
+    cells: &[Arc<dyn HistoryCell>],
+    nth_user_message: usize,
+    first_line: &str,
+    occurrence: usize,
+) -> Option<usize> {
+    let (start, end) = turn_range(cells, nth_user_message)?;
+    (start..end)
+        .filter(|idx| {
+            is_output_cell(&cells[*idx]) && first_transcript_line(&cells[*idx]) == first_line
+        })
+        .nth(occurrence)
+}
+
+fn turn_range(cells: &[Arc<dyn HistoryCell>], nth_user_message: usize) -> Option<(usize, usize)> {
+    let user_positions: Vec<usize> = user_positions_iter(cells).collect();
+    let start = *user_positions.get(nth_user_message)?;
+    let end = user_positions
+        .get(nth_user_message + 1)
+        .copied()
+        .unwrap_or(cells.len());
+    Some((start, end))
+}
+
+/// Width used to render cells for [`output_cell_key`]; any fixed width keeps
+/// the key stable across terminal sizes.
+const KEY_WIDTH: u16 = 80;
+
+fn first_transcript_line(cell: &Arc<dyn HistoryCell>) -> String {
+    cell.transcript_lines(KEY_WIDTH)
+        .first()
+        .map(|line| {
+            line.spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect::<String>()
+        })
+        .unwrap_or_default()
+}
+
+fn message_cell_position(
+    cells: &[Arc<dyn HistoryCell>],
+    nth_user_message: usize,
+    ordinal: usize,
+) -> Option<usize> {
+    let (start, end) = turn_range(cells, nth_user_message)?;
+    (start..end)
+        .filter(|idx| is_message_cell(&cells[*idx]))
+        .nth(ordinal)
+}
+
+/// Index of the turn containing `idx`, given the positions of user messages.
+fn turn_of(user_positions: &[usize], idx: usize) -> Option<usize> {
+    user_positions
+        .partition_point(|position| *position <= idx)
+        .checked_sub(1)
+}
+
+/// The cell at `idx` plus the stream continuation cells that follow it.
+fn cell_span(cells: &[Arc<dyn HistoryCell>], idx: usize) -> Range<usize> {
+    let end = cells
+        .iter()
+        .enumerate()
+        .skip(idx + 1)
+        .find(|(_, cell)| !cell.is_stream_continuation())
+        .map_or(cells.len(), |(end, _)| end);
+    idx..end.max(idx + 1)
+}
+
+fn is_message_cell(cell: &Arc<dyn HistoryCell>) -> bool {
+    let any = cell.as_any();
+    (any.is::<UserHistoryCell>() || any.is::<AgentMessageCell>()) && !cell.is_stream_continuation()
+}
+
+fn is_output_cell(cell: &Arc<dyn HistoryCell>) -> bool {
+    let any = cell.as_any();
+    !(any.is::<UserHistoryCell>() || any.is::<AgentMessageCell>()) && !cell.is_stream_continuation()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn user(message: &str) -> Arc<dyn HistoryCell> {
+        Arc::new(UserHistoryCell {
+            message: message.to_string(),
+        })
+    }
+
+    fn agent(text: &str, is_first_line: bool) -> Arc<dyn HistoryCell> {
+        Arc::new(AgentMessageCell::new(
+            vec![Line::from(text.to_string())],
+            is_first_line,
+        ))
+    }
+
+    fn transcript() -> Vec<Arc<dyn HistoryCell>> {
+        vec![
+            user("first"),
+            agent("streamed", true),
+            agent("continued", false),
+            agent("second reply", true),
+            user("second"),
+            agent("last reply", true),
+        ]
+    }
+
+    fn rendered_text(flags: &HistoryFlags, cells: &[Arc<dyn HistoryCell>]) -> String {
+        flags
+            .render(cells, 80)
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn message_cell_keys_count_streamed_messages_once() {
+        let cells = transcript();
+        let keys: Vec<_> = (0..cells.len())
+            .map(|idx| message_cell_key(&cells, idx))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                Some((0, 0)),
+                Some((0, 1)),
+                None,
+                Some((0, 2)),
+                Some((1, 0)),
+                Some((1, 1)),
+            ]
+        );
+        assert_eq!(message_cell_position(&cells, 0, 2), Some(3));
+        assert_eq!(message_cell_position(&cells, 1, 2), None);
+    }
+
+    #[test]
+    fn deleting_a_streamed_message_hides_its_continuations() {
+        let cells = transcript();
+        let mut flags = HistoryFlags::default();
+
+        let changed = flags.apply(
+            &HistoryFlag::CellDeleted {
+                nth_user_message: 0,
+                cell: 1,
+            },
+            &cells,
+        );
+
+        assert!(changed);
+        assert_eq!(
+            flags.layout(&cells),
+            vec![
+                CellDisplay::Shown,
+                CellDisplay::Hidden,
+                CellDisplay::Hidden,
+                CellDisplay::Shown,
+                CellDisplay::Shown,
+                CellDisplay::Shown,
+            ]
+        );
+    }
+
+    #[test]
+    fn output_cells_are_keyed_by_their_first_line() {
+        let note = |text: &str| -> Arc<dyn HistoryCell> {
+            Arc::new(crate::history_cell::new_info_event(text.to_string(), None))
+        };
+        let mut cells = transcript();
+        cells.extend([note("ran ls"), note("ran pwd"), note("ran ls")]);
+
+        let key = output_cell_key(&cells, 8);
+        assert_eq!(key, Some((1, "• ran ls".to_string(), 1)));
+        assert_eq!(output_cell_key(&cells, 5), None);
+
+        let mut flags = HistoryFlags::default();
+        let changed = flags.apply(
+            &HistoryFlag::OutputCellDeleted {
+                nth_user_message: 1,
+                first_line: "• ran ls".to_string(),
+                occurrence: 1,
+            },
+            &cells,
+        );
+        assert!(changed);
+        assert_eq!(
+            flags.layout(&cells)[6..].to_vec(),
+            vec![CellDisplay::Shown, CellDisplay::Shown, CellDisplay::Hidden]
+        );
+    }
+
+    #[test]
+    fn irrelevant_turn_collapses_to_its_user_message() {
+        let mut cells = transcript();
+        let mut flags = HistoryFlags::default();
+        flags.apply(
+            &HistoryFlag::TurnRelevance {
+                nth_user_message: 1,
+                irrelevant: true,
+            },
+            &cells,
+        );
+
+        cells.push(agent("late output", true));
+        assert!(flags.hides_last_cell(&cells));
+        assert_eq!(
+            flags.layout(&cells),
+            vec![
+                CellDisplay::Shown,
+                CellDisplay::Shown,
+                CellDisplay::Shown,
+                CellDisplay::Shown,
+                CellDisplay::CollapsedTurn,
+                CellDisplay::Hidden,
+                CellDisplay::Hidden,
+            ]
+        );
+        assert!(rendered_text(&flags, &cells).contains("marked irrelevant"));
+    }
+
+    #[test]
+    fn unclear_restores_output_hidden_by_clear() {
+        let mut cells = transcript();
+        let mut flags = HistoryFlags::default();
+
+        flags.apply(&HistoryFlag::ClearedAbove, &cells);
+        cells.push(user("after clear"));
+        let cleared = rendered_text(&flags, &cells);
+        assert!(cleared.starts_with("⋯ earlier output hidden — /unclear to restore"));
+        assert!(!cleared.contains("last reply"));
+        assert!(cleared.contains("after clear"));
+
+        assert!(flags.apply(&HistoryFlag::Uncleared, &cells));
+        assert_eq!(
+            rendered_text(&flags, &cells),
+            rendered_text(&HistoryFlags::default(), &cells)
+        );
+        assert!(!flags.apply(&HistoryFlag::Uncleared, &cells));
+    }
+}
@@ -0,0 +1,284 @@
+//! OSC 8 hyperlinks for file references such as `src/foo.rs:42` in history
+//! written to the terminal scrollback.
+//!
+//! Terminals without OSC 8 support print the escape sequence as text, so with
+//! `tui.hyperlinks = "auto"` links are only emitted when `TERM_PROGRAM` names
+//! a terminal known to render them; `on` overrides the check. Alternate-screen
+//! views underline references instead and open them in the `file_opener`
+//! editor.
+
+use std::ops::Range;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::Stdio;
+
+use codex_core::config::types::Hyperlinks;
+use codex_core::config::types::UriBasedFileOpener;
+use lazy_static::lazy_static;
+use regex_lite::Regex;
+use url::Url;
+
+/// `TERM_PROGRAM` values of terminals that render OSC 8 hyperlinks.
+const SUPPORTED_TERM_PROGRAMS: &[&str] = &["iTerm.app", "WezTerm", "vscode", "ghostty"];
+
+lazy_static! {
+    /// A path with a file extension, optionally followed by `:line[:column]`.
+    static ref FILE_REFERENCE: Regex = Regex::new(
+        r"((?:\.{1,2}/|/)?(?:[\w.\-]+/)*[\w\-][\w.\-]*\.[A-Za-z]\w*)(?::(\d+)(?::\d+)?)?"
+    )
+    .unwrap_or_else(|_| std::process::abort());
+}
+
+/// Whether `setting` allows OSC 8 escapes in this terminal.
+pub(crate) fn osc8_enabled(setting: Hyperlinks) -> bool {
+    let term_program = std::env::var("TERM_PROGRAM").ok();
+    hyperlinks_enabled(setting, term_program.as_deref())
+}
+
+fn hyperlinks_enabled(setting: Hyperlinks, term_program: Option<&str>) -> bool {
+    match setting {
+        Hyperlinks::On => true,
+        Hyperlinks::Off => false,
+        Hyperlinks::Auto => term_program.is_some_and(|program| {
+            SUPPORTED_TERM_PROGRAMS
+                .iter()
+                .any(|supported| supported.eq_ignore_ascii_case(program))
+        }),
+    }
+}
+
+/// A file reference found in text, resolved against the session cwd.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FileReference {
+    /// Byte range of the reference in the searched text.
+    pub(crate) range: Range<usize>,
+    pub(crate) path: PathBuf,
+    pub(crate) line: Option<u32>,
+}
+
+impl FileReference {
+    /// `file://` URI of the reference, with the line as a fragment.
+    fn file_uri(&self) -> Option<String> {
+        let mut url = Url::from_file_path(&self.path).ok()?;
+        if let Some(line) = self.line {
+            url.set_fragment(Some(&format!("L{line}")));
+        }
+        Some(url.into())
+    }
+
+    /// URI that opens the reference in `opener`'s editor, e.g.
+    /// `vscode://file/repo/src/foo.rs:42`.
+    fn editor_uri(&self, opener: UriBasedFileOpener) -> Option<String> {
+        let scheme = opener.get_scheme()?;
+        let path = self.path.to_string_lossy().replace('\\', "/");
+        let path = path.strip_prefix('/').unwrap_or(&path);
+        Some(match self.line {
+            Some(line) => format!("{scheme}://file/{path}:{line}"),
+            None => format!("{scheme}://file/{path}"),
+        })
+    }
+}
+
+/// Finds file references in text and resolves them to `file://` URIs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FileLinker {
+    cwd: PathBuf,
+    opener: UriBasedFileOpener,
+}
+
+impl FileLinker {
+    pub(crate) fn new(cwd: &Path) -> Self {
+        Self {
+            cwd: cwd.to_path_buf(),
+            opener: UriBasedFileOpener::None,
+        }
+    }
+
+    /// Open references with `opener` (the `file_opener` setting).
+    pub(crate) fn with_opener(mut self, opener: UriBasedFileOpener) -> Self {
+        self.opener = opener;
+        self
+    }
+
+    /// Resolve later references against `cwd`, e.g. after the session moved.
+    pub(crate) fn set_cwd(&mut self, cwd: &Path) {
+        if self.cwd != cwd {
+            self.cwd = cwd.to_path_buf();
+        }
+    }
+
+    /// File references in `text`, in order.
+    ///
+    /// Only references that contain a directory or a line number count; a
+    /// bare `name.ext` is too often just prose.
+    pub(crate) fn references(&self, text: &str) -> Vec<FileReference> {
+        FILE_REFERENCE
+            .captures_iter(text)
+            .filter_map(|captures| {
+                let (whole, path) = (captures.get(0)?, captures.get(1)?);
+                let line = captures.get(2).map(|m| m.as_str());
+                if !starts_reference(&text[..whole.start()])
+                    || (line.is_none() && !path.as_str().contains('/'))
+                {
+                    return None;
+                }
+                let path = Path::new(path.as_str());
+                let path = if path.is_absolute() {
+                    path.to_path_buf()
+                } else {
+                    self.cwd.join(path.strip_prefix("./").unwrap_or(path))
+                };
+                Some(FileReference {
+                    range: whole.range(),
+                    path,
+                    line: line.and_then(|line| line.parse().ok()),
+                })
+            })
+            .collect()
+    }
+
+    /// Split `text` into runs, each paired with the URI it links to, if any.
+    pub(crate) fn segments<'a>(&self, text: &'a str) -> Vec<(&'a str, Option<String>)> {
+        let mut segments = Vec::new();
+        let mut last = 0;
+        for reference in self.references(text) {
+            let Some(uri) = reference.file_uri() else {
+                continue;
+            };
+            if last < reference.range.start {
+                segments.push((&text[last..reference.range.start], None));
+            }
+            segments.push((&text[reference.range.clone()], Some(uri)));
+            last = reference.range.end;
+        }
+        if last < text.len() {
+            segments.push((&text[last..], None));
+        }
+        segments
+    }
+
+    /// Open `reference` in the `file_opener` editor through the system URI
+    /// handler. The editor runs on its own, so the TUI keeps the terminal.
+    pub(crate) fn open(&self, reference: &FileReference) -> std::io::Result<()> {
+        let uri = reference.editor_uri(self.opener).ok_or_else(|| {
+            std::io::Error::other("set `file_opener` in config.toml to open files")
+        })?;
+        #[cfg(target_os = "macos")]
+        let mut command = Command::new("open");
+        #[cfg(windows)]
+        let mut command = {
+            let mut command = Command::new("cmd");
+            command.args(["/C", "start", ""]);
+            command
+        };
+        #[cfg(not(any(target_os = "macos", windows)))]
+        let mut command = Command::new("xdg-open");
+        command
+            .arg(uri)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        Ok(())
+    }
+}
+
+/// True when a reference may start right after `before`; this keeps URLs
+/// (`https://host/a.html`) and words glued to the match from being linked.
+fn starts_reference(before: &str) -> bool {
+    before
+        .chars()
+        .next_back()
+        .is_none_or(|c| !(c.is_alphanumeric() || matches!(c, '_' | '/' | ':' | '.' | '-' | '@')))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn uri(cwd: &Path, path: &str, fragment: Option<&str>) -> String {
+        let mut url = Url::from_file_path(cwd.join(path)).expect("absolute path");
+        url.set_fragment(fragment);
+        url.into()
+    }
+
+    #[test]
+    fn links_paths_and_line_references() {
+        let cwd = std::env::temp_dir().join("repo");
+        let linker = FileLinker::new(&cwd);
+
+        assert_eq!(
+            linker.segments("See src/foo.rs:42 and ./docs/a.md, not lib.rs."),
+            vec![
+                ("See ", None),
+                ("src/foo.rs:42", Some(uri(&cwd, "src/foo.rs", Some("L42")))),
+                (" and ", None),
+                ("./docs/a.md", Some(uri(&cwd, "docs/a.md", None))),
+                (", not lib.rs.", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn editor_uri_includes_the_line() {
+        let cwd = std::env::temp_dir().join("repo");
+        let linker = FileLinker::new(&cwd);
+        let references = linker.references("see src/foo.rs:42");
+        let path = cwd.join("src/foo.rs");
+
+        assert_eq!(
+            references,
+            vec![FileReference {
+                range: 4..17,
+                path: path.clone(),
+                line: Some(42),
+            }]
+        );
+        let path = path.to_string_lossy().replace('\\', "/");
+        assert_eq!(
+            references[0].editor_uri(UriBasedFileOpener::VsCode),
+            Some(format!(
+                "vscode://file/{}:42",
+                path.strip_prefix('/').unwrap_or(&path)
+            ))
+        );
+        assert_eq!(references[0].editor_uri(UriBasedFileOpener::None), None);
+    }
+
+    #[test]
+    fn set_cwd_moves_later_links() {
+        let old = std::env::temp_dir().join("old");
+        let new = std::env::temp_dir().join("new");
+        let mut linker = FileLinker::new(&old);
+        linker.set_cwd(&new);
+
+        assert_eq!(
+            linker.segments("src/a.rs:1"),
+            vec![("src/a.rs:1", Some(uri(&new, "src/a.rs", Some("L1"))))]
+        );
+    }
+
+    #[test]
+    fn skips_urls_and_version_numbers() {
+        let linker = FileLinker::new(&std::env::temp_dir());
+        let text = "Fetched https://example.com/a.html with v1.2.3";
+
+        assert_eq!(linker.segments(text), vec![(text, None)]);
+    }
+
+    #[test]
+    fn auto_only_links_in_known_terminals() {
+        assert_eq!(
+            [
+                hyperlinks_enabled(Hyperlinks::Auto, Some("iTerm.app")),
+                hyperlinks_enabled(Hyperlinks::Auto, Some("Apple_Terminal")),
+                hyperlinks_enabled(Hyperlinks::Auto, None),
+                hyperlinks_enabled(Hyperlinks::On, None),
+                hyperlinks_enabled(Hyperlinks::Off, Some("WezTerm")),
+            ],
+            [true, false, false, true, false]
+        );
+    }
+}
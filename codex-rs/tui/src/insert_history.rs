@@ -2,6 +2,7 @@ use std::fmt;
 use std::io;
 use std::io::Write;
 
+use crate::hyperlinks::FileLinker;
 use crate::wrapping::word_wrap_lines_borrowed;
 use crossterm::Command;
 use crossterm::cursor::MoveTo;
@@ -28,6 +29,19 @@ pub fn insert_history_lines<B>(
     terminal: &mut crate::custom_terminal::Terminal<B>,
     lines: Vec<Line>,
 ) -> io::Result<()>
+where
+    B: Backend + Write,
+{
+    insert_history_lines_with_links(terminal, lines, None)
+}
+
+/// Like [`insert_history_lines`], but with `links` file references become
+/// OSC 8 hyperlinks.
+pub(crate) fn insert_history_lines_with_links<B>(
+    terminal: &mut crate::custom_terminal::Terminal<B>,
+    lines: Vec<Line>,
+    links: Option<&FileLinker>,
+) -> io::Result<()>
 where
     B: Backend + Write,
 {
@@ -118,7 +132,7 @@ where
                 content: s.content.clone(),
             })
             .collect();
-        write_spans(writer, merged_spans.iter())?;
+        write_spans(writer, merged_spans.iter(), links)?;
     }
 
     queue!(writer, ResetScrollRegion)?;
@@ -134,6 +148,24 @@ where
     Ok(())
 }
 
+/// Write `lines` to `writer` as ANSI-styled text, one output line each,
+/// without touching the cursor or scroll region.
+pub fn write_lines_ansi(writer: &mut impl Write, lines: &[Line]) -> io::Result<()> {
+    for line in lines {
+        let merged_spans: Vec<Span> = line
+            .spans
+            .iter()
+            .map(|s| Span {
+                style: s.style.patch(line.style),
+                content: s.content.clone(),
+            })
+            .collect();
+        write_spans(writer, merged_spans.iter(), None)?;
+        queue!(writer, Print("\n"))?;
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SetScrollRegion(pub std::ops::Range<u16>);
 
@@ -154,6 +186,26 @@ impl Command for SetScrollRegion {
     }
 }
 
+/// Start an OSC 8 hyperlink to the given URI, or end the current one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetHyperlink(pub Option<String>);
+
+impl Command for SetHyperlink {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        write!(f, "\x1b]8;;{}\x1b\\", self.0.as_deref().unwrap_or_default())
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        panic!("tried to execute SetHyperlink command using WinAPI, use ANSI instead");
+    }
+
+    #[cfg(windows)]
+    fn is_ansi_code_supported(&self) -> bool {
+        true
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ResetScrollRegion;
 
@@ -241,7 +293,11 @@ impl ModifierDiff {
     }
 }
 
-fn write_spans<'a, I>(mut writer: &mut impl Write, content: I) -> io::Result<()>
+fn write_spans<'a, I>(
+    mut writer: &mut impl Write,
+    content: I,
+    links: Option<&FileLinker>,
+) -> io::Result<()>
 where
     I: IntoIterator<Item = &'a Span<'a>>,
 {
@@ -271,7 +327,22 @@ where
             bg = next_bg;
         }
 
-        queue!(writer, Print(span.content.clone()))?;
+        match links {
+            Some(links) => {
+                for (text, uri) in links.segments(&span.content) {
+                    match uri {
+                        Some(uri) => queue!(
+                            writer,
+                            SetHyperlink(Some(uri)),
+                            Print(text),
+                            SetHyperlink(None)
+                        )?,
+                        None => queue!(writer, Print(text))?,
+                    }
+                }
+            }
+            None => queue!(writer, Print(span.content.clone()))?,
+        }
     }
 
     queue!(
@@ -297,7 +368,7 @@ mod tests {
         let spans = ["A".bold(), "B".into()];
 
         let mut actual: Vec<u8> = Vec::new();
-        write_spans(&mut actual, spans.iter()).unwrap();
+        write_spans(&mut actual, spans.iter(), None).unwrap();
 
         let mut expected: Vec<u8> = Vec::new();
         queue!(
@@ -318,6 +389,91 @@ mod tests {
         );
     }
 
+    #[test]
+    fn writes_lines_with_line_style_and_newlines() {
+        use ratatui::style::Stylize;
+
+        let lines = [Line::from("+a").green(), Line::from("b")];
+
+        let mut actual: Vec<u8> = Vec::new();
+        write_lines_ansi(&mut actual, &lines).unwrap();
+
+        let mut expected: Vec<u8> = Vec::new();
+        queue!(
+            expected,
+            SetColors(Colors::new(CColor::DarkGreen, CColor::Reset)),
+            Print("+a"),
+            SetForegroundColor(CColor::Reset),
+            SetBackgroundColor(CColor::Reset),
+            SetAttribute(crossterm::style::Attribute::Reset),
+            Print("\n"),
+            Print("b"),
+            SetForegroundColor(CColor::Reset),
+            SetBackgroundColor(CColor::Reset),
+            SetAttribute(crossterm::style::Attribute::Reset),
+            Print("\n"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(actual).unwrap(),
+            String::from_utf8(expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn writes_osc8_links_for_file_references() {
+        let cwd = std::env::temp_dir();
+        let links = FileLinker::new(&cwd);
+        let text = render_markdown_text("See `src/foo.rs:42` for details.");
+        let mut uri = url::Url::from_file_path(cwd.join("src/foo.rs")).expect("absolute path");
+        uri.set_fragment(Some("L42"));
+
+        let mut actual: Vec<u8> = Vec::new();
+        write_spans(&mut actual, text.lines[0].spans.iter(), Some(&links)).unwrap();
+
+        let mut expected: Vec<u8> = Vec::new();
+        queue!(
+            expected,
+            Print("See "),
+            SetAttribute(crossterm::style::Attribute::Dim),
+            SetHyperlink(Some(uri.to_string())),
+            Print("src/foo.rs:42"),
+            SetHyperlink(None),
+            SetAttribute(crossterm::style::Attribute::NormalIntensity),
+            Print(" for details."),
+            SetForegroundColor(CColor::Reset),
+            SetBackgroundColor(CColor::Reset),
+            SetAttribute(crossterm::style::Attribute::Reset),
+        )
+        .unwrap();
+
+        let actual = String::from_utf8(actual).unwrap();
+        assert_eq!(actual, String::from_utf8(expected).unwrap());
+        assert!(actual.contains(&format!("\x1b]8;;{uri}\x1b\\src/foo.rs:42\x1b]8;;\x1b\\")));
+    }
+
+    #[test]
+    fn vt100_hyperlinks_leave_only_the_text_on_screen() {
+        let width: u16 = 40;
+        let height: u16 = 6;
+        let backend = VT100Backend::new(width, height);
+        let mut term = crate::custom_terminal::Terminal::with_options(backend).expect("terminal");
+        term.set_viewport_area(Rect::new(0, height - 1, width, 1));
+        let links = FileLinker::new(&std::env::temp_dir());
+
+        insert_history_lines_with_links(
+            &mut term,
+            vec![Line::from("Edited src/foo.rs:42 today")],
+            Some(&links),
+        )
+        .expect("insert history");
+
+        let screen = term.backend().vt100().screen().contents();
+        assert!(screen.contains("Edited src/foo.rs:42 today"), "{screen:?}");
+        assert!(!screen.contains("8;;"), "{screen:?}");
+    }
+
     #[test]
     fn vt100_blockquote_line_emits_green_fg() {
         // Set up a small off-screen terminal
@@ -21,6 +21,7 @@ use codex_core::config::load_config_as_toml_with_cli_overrides;
 use codex_core::config::resolve_oss_provider;
 use codex_core::find_conversation_path_by_id_str;
 use codex_core::get_platform_sandbox;
+use codex_core::persistence_policy::PersistenceKind;
 use codex_core::protocol::AskForApproval;
 use codex_protocol::config_types::SandboxMode;
 use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
@@ -33,6 +34,7 @@ use tracing_subscriber::filter::Targets;
 use tracing_subscriber::prelude::*;
 
 mod additional_dirs;
+mod animation;
 mod app;
 mod app_backtrack;
 mod app_event;
@@ -42,6 +44,7 @@ mod bottom_pane;
 mod chatwidget;
 mod cli;
 mod clipboard_paste;
+mod cloud_tasks_service;
 mod color;
 pub mod custom_terminal;
 mod diff_render;
@@ -50,7 +53,10 @@ mod exec_command;
 mod file_search;
 mod frames;
 mod get_git_diff;
+mod headless_backend;
 mod history_cell;
+mod history_flags;
+mod hyperlinks;
 pub mod insert_history;
 mod key_hint;
 pub mod live_wrap;
@@ -61,8 +67,10 @@ mod model_migration;
 pub mod onboarding;
 mod oss_selection;
 mod pager_overlay;
+mod patch_hunk_overlay;
 pub mod public_widgets;
 mod render;
+mod replay;
 mod resume_picker;
 mod selection_list;
 mod session_log;
@@ -73,8 +81,10 @@ mod status_indicator_widget;
 mod streaming;
 mod style;
 mod terminal_palette;
+mod terminal_title;
 mod text_formatting;
 mod tui;
+mod turn_separators;
 mod ui_consts;
 pub mod update_action;
 mod update_prompt;
@@ -86,14 +96,19 @@ mod wrapping;
 #[cfg(test)]
 pub mod test_backend;
 
-use crate::onboarding::TrustDirectorySelection;
 use crate::onboarding::onboarding_screen::OnboardingScreenArgs;
 use crate::onboarding::onboarding_screen::run_onboarding_app;
+use crate::onboarding::preview_trust_choices;
+use crate::onboarding::trust_summary_lines;
 use crate::tui::Tui;
 pub use cli::Cli;
+pub use cli::ReplayCli;
+pub use diff_render::render_file_changes;
 pub use markdown_render::render_markdown_text;
 pub use public_widgets::composer_input::ComposerAction;
 pub use public_widgets::composer_input::ComposerInput;
+pub use replay::ReplaySpeed;
+pub use replay::run_main as run_replay;
 use std::io::Write as _;
 
 // (tests access modules directly within the crate)
@@ -218,9 +233,12 @@ pub async fn run_main(
         tools_web_search_request: None,
         experimental_sandbox_command_assessment: None,
         additional_writable_roots: additional_dirs,
+        offline: cli.offline.then_some(true),
+        ephemeral: cli.ephemeral.then_some(true),
+        replay_speed: cli.replay_speed,
     };
 
-    let config = load_config_or_exit(cli_kv_overrides.clone(), overrides.clone()).await;
+    let mut config = load_config_or_exit(cli_kv_overrides.clone(), overrides.clone()).await;
 
     if let Some(warning) = add_dir_warning_message(&cli.add_dir, &config.sandbox_policy) {
         #[allow(clippy::print_stderr)]
@@ -237,27 +255,6 @@ pub async fn run_main(
     }
 
     let active_profile = config.active_profile.clone();
-    let log_dir = codex_core::config::log_dir(&config)?;
-    std::fs::create_dir_all(&log_dir)?;
-    // Open (or create) your log file, appending to it.
-    let mut log_file_opts = OpenOptions::new();
-    log_file_opts.create(true).append(true);
-
-    // Ensure the file is only readable and writable by the current user.
-    // Doing the equivalent to `chmod 600` on Windows is quite a bit more code
-    // and requires the Windows API crates, so we can reconsider that when
-    // Codex CLI is officially supported on Windows.
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::OpenOptionsExt;
-        log_file_opts.mode(0o600);
-    }
-
-    let log_file = log_file_opts.open(log_dir.join("codex-tui.log"))?;
-
-    // Wrap file in non‑blocking writer.
-    let (non_blocking, _guard) = non_blocking(log_file);
-
     // use RUST_LOG env var, default to info for codex crates.
     let env_filter = || {
         EnvFilter::try_from_default_env().unwrap_or_else(|_| {
@@ -265,20 +262,49 @@ pub async fn run_main(
         })
     };
 
-    let file_layer = tracing_subscriber::fmt::layer()
-        .with_writer(non_blocking)
-        .with_target(false)
-        .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
-        .with_filter(env_filter());
+    // Ephemeral runs keep no debug or feedback logs.
+    let persist_logs = config.persistence.check(PersistenceKind::Logs).is_ok();
+    let (file_layer, _guard) = if persist_logs {
+        let log_dir = codex_core::config::log_dir(&config)?;
+        std::fs::create_dir_all(&log_dir)?;
+        // Open (or create) your log file, appending to it.
+        let mut log_file_opts = OpenOptions::new();
+        log_file_opts.create(true).append(true);
+
+        // Ensure the file is only readable and writable by the current user.
+        // Doing the equivalent to `chmod 600` on Windows is quite a bit more code
+        // and requires the Windows API crates, so we can reconsider that when
+        // Codex CLI is officially supported on Windows.
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            log_file_opts.mode(0o600);
+        }
+
+        let log_file = log_file_opts.open(log_dir.join("codex-tui.log"))?;
+
+        // Wrap file in non‑blocking writer.
+        let (non_blocking, guard) = non_blocking(log_file);
+        let file_layer = tracing_subscriber::fmt::layer()
+            .with_writer(non_blocking)
+            .with_target(false)
+            .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+            .with_filter(env_filter());
+        (Some(file_layer), Some(guard))
+    } else {
+        (None, None)
+    };
 
     let feedback = codex_feedback::CodexFeedback::new();
     let targets = Targets::new().with_default(tracing::Level::TRACE);
 
-    let feedback_layer = tracing_subscriber::fmt::layer()
-        .with_writer(feedback.make_writer())
-        .with_ansi(false)
-        .with_target(false)
-        .with_filter(targets);
+    let feedback_layer = persist_logs.then(|| {
+        tracing_subscriber::fmt::layer()
+            .with_writer(feedback.make_writer())
+            .with_ansi(false)
+            .with_target(false)
+            .with_filter(targets)
+    });
 
     if cli.oss && model_provider_override.is_some() {
         // We're in the oss section, so provider_id should be Some
@@ -292,7 +318,7 @@ pub async fn run_main(
                 ));
             }
         };
-        ensure_oss_provider_ready(provider_id, &config).await?;
+        ensure_oss_provider_ready(provider_id, &mut config).await?;
     }
 
     let otel = codex_core::otel_init::build_provider(&config, env!("CARGO_PKG_VERSION"));
@@ -358,6 +384,12 @@ async fn run_ratatui_app(
     terminal.clear()?;
 
     let mut tui = Tui::new(terminal);
+    tui.set_animation_setting(initial_config.tui_animations);
+    tui.set_file_links(
+        initial_config.tui_hyperlinks,
+        hyperlinks::FileLinker::new(&initial_config.cwd).with_opener(initial_config.file_opener),
+    );
+    tui.set_mouse_enabled(initial_config.tui_mouse);
 
     #[cfg(not(debug_assertions))]
     {
@@ -373,6 +405,7 @@ async fn run_ratatui_app(
                         token_usage: codex_core::protocol::TokenUsage::default(),
                         conversation_id: None,
                         update_action: Some(action),
+                        ephemeral: initial_config.persistence.is_ephemeral(),
                     });
                 }
             }
@@ -382,16 +415,22 @@ async fn run_ratatui_app(
     // Initialize high-fidelity session event logging if enabled.
     session_log::maybe_init(&initial_config);
 
-    let auth_manager = AuthManager::shared(
-        initial_config.codex_home.clone(),
-        false,
-        initial_config.cli_auth_credentials_store_mode,
-    );
+    let auth_manager = AuthManager::shared_from_config(&initial_config, false);
     let login_status = get_login_status(&initial_config);
     let should_show_trust_screen = should_show_trust_screen(&initial_config);
     let should_show_onboarding =
         should_show_onboarding(login_status, &initial_config, should_show_trust_screen);
 
+    let trust_previews = if should_show_trust_screen {
+        load_config_as_toml_with_cli_overrides(&initial_config.codex_home, cli_kv_overrides.clone())
+            .await
+            .ok()
+            .and_then(|cfg| preview_trust_choices(&cfg, &overrides, &initial_config))
+    } else {
+        None
+    };
+
+    let mut trust_summary = None;
     let config = if should_show_onboarding {
         let onboarding_result = run_onboarding_app(
             OnboardingScreenArgs {
@@ -400,6 +439,7 @@ async fn run_ratatui_app(
                 login_status,
                 auth_manager: auth_manager.clone(),
                 config: initial_config.clone(),
+                trust_previews,
             },
             &mut tui,
         )
@@ -412,15 +452,15 @@ async fn run_ratatui_app(
                 token_usage: codex_core::protocol::TokenUsage::default(),
                 conversation_id: None,
                 update_action: None,
+                ephemeral: initial_config.persistence.is_ephemeral(),
             });
         }
-        // if the user acknowledged windows or made an explicit decision ato trust the directory, reload the config accordingly
-        if onboarding_result
-            .directory_trust_decision
-            .map(|d| d == TrustDirectorySelection::Trust)
-            .unwrap_or(false)
-        {
-            load_config_or_exit(cli_kv_overrides, overrides).await
+        // If the user made a trust decision, reload the config so the session
+        // runs with the policy the trust screen previewed.
+        if let Some(decision) = onboarding_result.directory_trust_decision {
+            let config = load_config_or_exit(cli_kv_overrides, overrides).await;
+            trust_summary = Some(trust_summary_lines(decision, &config));
+            config
         } else {
             initial_config
         }
@@ -447,6 +487,7 @@ async fn run_ratatui_app(
                     token_usage: codex_core::protocol::TokenUsage::default(),
                     conversation_id: None,
                     update_action: None,
+                    ephemeral: config.persistence.is_ephemeral(),
                 });
             }
         }
@@ -485,6 +526,7 @@ async fn run_ratatui_app(
                     token_usage: codex_core::protocol::TokenUsage::default(),
                     conversation_id: None,
                     update_action: None,
+                    ephemeral: config.persistence.is_ephemeral(),
                 });
             }
             other => other,
@@ -504,6 +546,7 @@ async fn run_ratatui_app(
         images,
         resume_selection,
         feedback,
+        trust_summary,
     )
     .await;
 
@@ -621,7 +664,7 @@ mod tests {
             temp_dir.path().to_path_buf(),
         )?;
         config.did_user_set_custom_approval_policy_or_sandbox_mode = false;
-        config.active_project = ProjectConfig { trust_level: None };
+        config.active_project = ProjectConfig::default();
         config.set_windows_sandbox_globally(false);
 
         let should_show = should_show_trust_screen(&config);
@@ -648,7 +691,7 @@ mod tests {
             temp_dir.path().to_path_buf(),
         )?;
         config.did_user_set_custom_approval_policy_or_sandbox_mode = false;
-        config.active_project = ProjectConfig { trust_level: None };
+        config.active_project = ProjectConfig::default();
         config.set_windows_sandbox_globally(true);
 
         let should_show = should_show_trust_screen(&config);
@@ -677,6 +720,7 @@ mod tests {
         config.did_user_set_custom_approval_policy_or_sandbox_mode = false;
         config.active_project = ProjectConfig {
             trust_level: Some(TrustLevel::Untrusted),
+            always_allow_commands: Vec::new(),
         };
 
         let should_show = should_show_trust_screen(&config);
@@ -14,6 +14,13 @@ use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::text::Span;
 use ratatui::text::Text;
+use unicode_width::UnicodeWidthStr;
+
+/// Deeper list levels reuse this level's indent so pathological nesting
+/// cannot push item text off the right edge.
+const MAX_LIST_DEPTH: usize = 6;
+const TASK_UNCHECKED: &str = "☐ ";
+const TASK_CHECKED: &str = "☑ ";
 
 #[derive(Clone, Debug)]
 struct IndentContext {
@@ -39,6 +46,7 @@ pub fn render_markdown_text(input: &str) -> Text<'static> {
 pub(crate) fn render_markdown_text_with_width(input: &str, width: Option<usize>) -> Text<'static> {
     let mut options = Options::empty();
     options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
     let parser = Parser::new_ext(input, options);
     let mut w = Writer::new(parser, width);
     w.run();
@@ -118,7 +126,7 @@ where
             Event::Html(html) => self.html(html, false),
             Event::InlineHtml(html) => self.html(html, true),
             Event::FootnoteReference(_) => {}
-            Event::TaskListMarker(_) => {}
+            Event::TaskListMarker(checked) => self.task_list_marker(checked),
         }
     }
 
@@ -318,12 +326,7 @@ where
 
     fn start_item(&mut self) {
         self.pending_marker_line = true;
-        let depth = self.list_indices.len();
-        let is_ordered = self
-            .list_indices
-            .last()
-            .map(Option::is_some)
-            .unwrap_or(false);
+        let depth = self.list_indices.len().min(MAX_LIST_DEPTH);
         let width = depth * 4 - 3;
         let marker = if let Some(last_index) = self.list_indices.last_mut() {
             match last_index {
@@ -336,17 +339,39 @@ where
         } else {
             None
         };
-        let indent_prefix = if depth == 0 {
-            Vec::new()
-        } else {
-            let indent_len = if is_ordered { width + 2 } else { width + 1 };
-            vec![Span::from(" ".repeat(indent_len))]
+        // Continuation lines hang under the item text, so the indent matches
+        // the rendered marker (including numbers wider than the padding).
+        let indent_prefix = match &marker {
+            Some(marker) => {
+                let indent_len: usize = marker.iter().map(|s| s.content.width()).sum();
+                vec![Span::from(" ".repeat(indent_len))]
+            }
+            None => Vec::new(),
         };
         self.indent_stack
             .push(IndentContext::new(indent_prefix, marker, true));
         self.needs_newline = false;
     }
 
+    fn task_list_marker(&mut self, checked: bool) {
+        let glyph = if checked {
+            Span::from(TASK_CHECKED).green()
+        } else {
+            Span::from(TASK_UNCHECKED)
+        };
+        if let Some(ctx) = self.indent_stack.iter_mut().rev().find(|ctx| ctx.is_list) {
+            ctx.prefix
+                .push(Span::from(" ".repeat(glyph.content.width())));
+        }
+        if self.pending_marker_line {
+            self.push_line(Line::default());
+        } else if self.current_line_content.is_some() {
+            self.current_subsequent_indent = self.prefix_spans(false);
+        }
+        self.pending_marker_line = false;
+        self.push_span(glyph);
+    }
+
     fn start_codeblock(&mut self, _lang: Option<String>, indent: Option<Span<'static>>) {
         self.flush_current_line();
         if !self.text.lines.is_empty() {
@@ -489,6 +514,22 @@ where
     }
 }
 
+/// Four-level nesting mixed with task items, shared by the static and
+/// streaming renderer tests.
+#[cfg(test)]
+pub(crate) const NESTED_TASK_LIST_FIXTURE: &str = "\
+- [ ] Write the migration plan and circulate it to the team for review
+- [x] Land the schema change behind a feature flag
+  - Nested bullet that carries enough words to wrap at narrow widths
+    1. Third level ordered item with a long description that wraps
+       - Fourth level bullet that is also long enough to wrap around at fifty columns
+- [ ] Follow up
+
+8. Eight
+9. Nine
+10. Ten has a wide marker and a continuation line that wraps at narrow widths
+";
+
 #[cfg(test)]
 mod markdown_render_tests {
     include!("markdown_render_tests.rs");
@@ -498,6 +539,7 @@ mod markdown_render_tests {
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use ratatui::text::Line;
     use ratatui::text::Text;
 
     fn lines_to_strings(text: &Text<'_>) -> Vec<String> {
@@ -573,6 +615,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn wraps_wide_ordered_markers_under_item_text() {
+        let markdown = "10. ordered item with a two digit number";
+        let rendered = render_markdown_text_with_width(markdown, Some(20));
+        let lines = lines_to_strings(&rendered);
+        assert_eq!(
+            lines,
+            vec![
+                "10. ordered item".to_string(),
+                "    with a two digit".to_string(),
+                "    number".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn wraps_task_items_under_item_text() {
+        let markdown = "- [x] done item with enough words to wrap\n- [ ] todo";
+        let rendered = render_markdown_text_with_width(markdown, Some(20));
+        assert_eq!(
+            rendered,
+            Text::from_iter([
+                Line::from_iter(["- ".into(), "☑ ".green(), "done item with".into()]),
+                Line::from_iter(["  ", "  ", "enough words to"]),
+                Line::from_iter(["  ", "  ", "wrap"]),
+                Line::from_iter(["- ", "☐ ", "todo"]),
+            ])
+        );
+    }
+
+    #[test]
+    fn caps_list_indent_depth() {
+        let markdown = "- 1\n  - 2\n    - 3\n      - 4\n        - 5\n          - 6\n            - 7\n              - 8\n";
+        let rendered = render_markdown_text_with_width(markdown, None);
+        let lines = lines_to_strings(&rendered);
+        assert_eq!(
+            lines,
+            vec![
+                "- 1".to_string(),
+                "    - 2".to_string(),
+                "        - 3".to_string(),
+                "            - 4".to_string(),
+                "                - 5".to_string(),
+                "                    - 6".to_string(),
+                "                    - 7".to_string(),
+                "                    - 8".to_string(),
+            ]
+        );
+    }
+
     #[test]
     fn wraps_blockquotes() {
         let markdown = "> block quote with content that should wrap nicely";
@@ -4,7 +4,9 @@ use ratatui::text::Line;
 use ratatui::text::Span;
 use ratatui::text::Text;
 
+use crate::markdown_render::NESTED_TASK_LIST_FIXTURE;
 use crate::markdown_render::render_markdown_text;
+use crate::markdown_render::render_markdown_text_with_width;
 use insta::assert_snapshot;
 
 #[test]
@@ -993,3 +995,28 @@ fn nested_item_continuation_paragraph_is_indented() {
     ]);
     assert_eq!(text, expected);
 }
+
+#[test]
+fn nested_task_list_snapshot_50_columns() {
+    let text = render_markdown_text_with_width(NESTED_TASK_LIST_FIXTURE, Some(50));
+    assert_snapshot!(plain_lines(&text));
+}
+
+#[test]
+fn nested_task_list_snapshot_100_columns() {
+    let text = render_markdown_text_with_width(NESTED_TASK_LIST_FIXTURE, Some(100));
+    assert_snapshot!(plain_lines(&text));
+}
+
+fn plain_lines(text: &Text<'_>) -> String {
+    text.lines
+        .iter()
+        .map(|line| {
+            line.spans
+                .iter()
+                .map(|span| span.content.clone())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -667,4 +667,27 @@ mod tests {
         ])
         .await;
     }
+
+    #[tokio::test]
+    async fn streaming_nested_task_list_matches_full_at_narrow_width() {
+        let full = crate::markdown_render::NESTED_TASK_LIST_FIXTURE;
+        let width = Some(50);
+        let mut collector = super::MarkdownStreamCollector::new(width);
+        let mut streamed = Vec::new();
+        // Word-sized deltas so every item is committed while its indent
+        // context is still being built up.
+        for delta in full.split_inclusive(' ') {
+            collector.push_delta(delta);
+            if delta.contains('\n') {
+                streamed.extend(collector.commit_complete_lines());
+            }
+        }
+        streamed.extend(collector.finalize_and_drain());
+        let mut rendered = Vec::new();
+        crate::markdown::append_markdown(full, width, &mut rendered);
+        assert_eq!(
+            lines_to_plain_strings(&streamed),
+            lines_to_plain_strings(&rendered)
+        );
+    }
 }
@@ -5,8 +5,11 @@ use codex_core::auth::AuthCredentialsStoreMode;
 use codex_core::auth::CLIENT_ID;
 use codex_core::auth::login_with_api_key;
 use codex_core::auth::read_openai_api_key_from_env;
+use codex_login::DeviceCodeHandler;
+use codex_login::DevicePollStatus;
 use codex_login::ServerOptions;
 use codex_login::ShutdownHandle;
+use codex_login::run_device_code_login_with_handler;
 use codex_login::run_login_server;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
@@ -40,6 +43,8 @@ use crate::shimmer::shimmer_spans;
 use crate::tui::FrameRequester;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::AbortHandle;
 
 use super::onboarding_screen::StepState;
 
@@ -47,6 +52,7 @@ use super::onboarding_screen::StepState;
 pub(crate) enum SignInState {
     PickMode,
     ChatGptContinueInBrowser(ContinueInBrowserState),
+    ChatGptDeviceCode(DeviceCodeState),
     ChatGptSuccessMessage,
     ChatGptSuccess,
     ApiKeyEntry(ApiKeyInputState),
@@ -76,6 +82,66 @@ impl Drop for ContinueInBrowserState {
     }
 }
 
+/// Progress of a device code sign-in, filled in by [`TuiDeviceCodeHandler`].
+#[derive(Clone, Default)]
+pub(crate) struct DeviceCodeState {
+    code: Option<DeviceCode>,
+    expired: bool,
+    error: Option<String>,
+    task: Option<Arc<DeviceCodeTask>>,
+}
+
+#[derive(Clone)]
+struct DeviceCode {
+    user_code: String,
+    verification_url: String,
+    expires_in: Duration,
+}
+
+/// Aborts the polling task once the last copy of the state is dropped.
+struct DeviceCodeTask(AbortHandle);
+
+impl Drop for DeviceCodeTask {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Forwards device code updates into the sign-in state, ignoring them once
+/// the user has left the device code screen.
+struct TuiDeviceCodeHandler {
+    sign_in_state: Arc<RwLock<SignInState>>,
+    request_frame: FrameRequester,
+}
+
+impl TuiDeviceCodeHandler {
+    fn update(&self, f: impl FnOnce(&mut DeviceCodeState)) {
+        if let SignInState::ChatGptDeviceCode(state) = &mut *self.sign_in_state.write().unwrap() {
+            f(state);
+            self.request_frame.schedule_frame();
+        }
+    }
+}
+
+impl DeviceCodeHandler for TuiDeviceCodeHandler {
+    fn on_code(&self, user_code: &str, verification_url: &str, expires_in: Duration) {
+        self.update(|state| {
+            state.code = Some(DeviceCode {
+                user_code: user_code.to_string(),
+                verification_url: verification_url.to_string(),
+                expires_in,
+            });
+            state.expired = false;
+        });
+    }
+
+    fn on_poll_status(&self, status: &DevicePollStatus) {
+        if *status == DevicePollStatus::Expired {
+            self.update(|state| state.expired = true);
+        }
+    }
+}
+
 impl KeyboardHandler for AuthModeWidget {
     fn handle_key_event(&mut self, key_event: KeyEvent) {
         if self.handle_api_key_entry_key_event(&key_event) {
@@ -126,10 +192,22 @@ impl KeyboardHandler for AuthModeWidget {
                     _ => {}
                 }
             }
+            KeyCode::Char('d') => {
+                let in_browser_flow = matches!(
+                    &*self.sign_in_state.read().unwrap(),
+                    SignInState::ChatGptContinueInBrowser(_)
+                );
+                if in_browser_flow {
+                    self.start_device_code_login();
+                }
+            }
             KeyCode::Esc => {
                 tracing::info!("Esc pressed");
                 let sign_in_state = { (*self.sign_in_state.read().unwrap()).clone() };
-                if matches!(sign_in_state, SignInState::ChatGptContinueInBrowser(_)) {
+                if matches!(
+                    sign_in_state,
+                    SignInState::ChatGptContinueInBrowser(_) | SignInState::ChatGptDeviceCode(_)
+                ) {
                     *self.sign_in_state.write().unwrap() = SignInState::PickMode;
                     self.request_frame.schedule_frame();
                 }
@@ -262,7 +340,7 @@ impl AuthModeWidget {
         let mut spans = vec!["  ".into()];
         // Schedule a follow-up frame to keep the shimmer animation going.
         self.request_frame
-            .schedule_frame_in(std::time::Duration::from_millis(100));
+            .schedule_animation_frame(std::time::Duration::from_millis(100));
         spans.extend(shimmer_spans("Finish signing in via your browser"));
         let mut lines = vec![spans.into(), "".into()];
 
@@ -276,12 +354,90 @@ impl AuthModeWidget {
             lines.push("".into());
         }
 
+        lines.push(
+            "  Press d to sign in with a device code instead (e.g. over SSH)"
+                .dim()
+                .into(),
+        );
         lines.push("  Press Esc to cancel".dim().into());
         Paragraph::new(lines)
             .wrap(Wrap { trim: false })
             .render(area, buf);
     }
 
+    fn render_device_code(&self, area: Rect, buf: &mut Buffer, state: &DeviceCodeState) {
+        let [intro_area, code_area, footer_area] = Layout::vertical([
+            Constraint::Length(6),
+            Constraint::Length(3),
+            Constraint::Min(3),
+        ])
+        .areas(area);
+
+        let mut intro_lines: Vec<Line> = vec![
+            Line::from(vec!["> ".into(), "Sign in with a device code".bold()]),
+            "".into(),
+        ];
+        match &state.code {
+            Some(code) => {
+                let minutes = code.expires_in.as_secs().div_ceil(60);
+                intro_lines.push("  1. Open this link on any device and sign in:".into());
+                intro_lines.push(Line::from(vec![
+                    "     ".into(),
+                    code.verification_url.as_str().cyan().underlined(),
+                ]));
+                intro_lines.push(
+                    format!("  2. Enter this one-time code within {minutes} minutes:").into(),
+                );
+            }
+            None => intro_lines.push("  Requesting a code…".dim().into()),
+        }
+        Paragraph::new(intro_lines)
+            .wrap(Wrap { trim: false })
+            .render(intro_area, buf);
+
+        if let Some(code) = &state.code {
+            // Pad the code inside the box so it is easy to select and copy.
+            let width = code.user_code.chars().count() as u16 + 6;
+            let box_area = Rect {
+                x: code_area.x + 2,
+                width: width.min(code_area.width.saturating_sub(2)),
+                ..code_area
+            };
+            Paragraph::new(Line::from(vec![" ".into(), code.user_code.as_str().bold()]))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::default().fg(Color::Cyan)),
+                )
+                .render(box_area, buf);
+        }
+
+        let mut footer_lines: Vec<Line> = Vec::new();
+        if let Some(error) = &state.error {
+            footer_lines.push(Line::from(vec!["  ".into(), error.as_str().red()]));
+            footer_lines.push("".into());
+            footer_lines.push("  Press Esc to go back".dim().into());
+        } else {
+            if state.expired {
+                footer_lines.push("  The code expired; requesting a new one…".dim().into());
+            } else {
+                // Schedule a follow-up frame to keep the shimmer animation going.
+                self.request_frame
+                    .schedule_animation_frame(Duration::from_millis(100));
+                let mut spans = vec!["  ".into()];
+                spans.extend(shimmer_spans("Waiting for you to finish signing in"));
+                footer_lines.push(spans.into());
+            }
+            footer_lines.push("".into());
+            footer_lines.push("  Keep the code secret; do not share it.".dim().into());
+            footer_lines.push("  Press Esc to cancel".dim().into());
+        }
+        Paragraph::new(footer_lines)
+            .wrap(Wrap { trim: false })
+            .render(footer_area, buf);
+    }
+
     fn render_chatgpt_success_message(&self, area: Rect, buf: &mut Buffer) {
         let lines = vec![
             "✓ Signed in with your ChatGPT account".fg(Color::Green).into(),
@@ -589,7 +745,13 @@ impl AuthModeWidget {
                             request_frame.schedule_frame();
                         }
                         _ => {
-                            *sign_in_state.write().unwrap() = SignInState::PickMode;
+                            // Leave the state alone if the user switched to
+                            // the device code flow.
+                            let mut guard = sign_in_state.write().unwrap();
+                            if matches!(&*guard, SignInState::ChatGptContinueInBrowser(_)) {
+                                *guard = SignInState::PickMode;
+                            }
+                            drop(guard);
                             // self.error = Some(e.to_string());
                             request_frame.schedule_frame();
                         }
@@ -605,6 +767,51 @@ impl AuthModeWidget {
     }
 }
 
+impl AuthModeWidget {
+    fn start_device_code_login(&mut self) {
+        self.error = None;
+        let opts = ServerOptions::new(
+            self.codex_home.clone(),
+            CLIENT_ID.to_string(),
+            self.forced_chatgpt_workspace_id.clone(),
+            self.cli_auth_credentials_store_mode,
+        );
+        *self.sign_in_state.write().unwrap() =
+            SignInState::ChatGptDeviceCode(DeviceCodeState::default());
+        self.request_frame.schedule_frame();
+
+        let sign_in_state = self.sign_in_state.clone();
+        let request_frame = self.request_frame.clone();
+        let auth_manager = self.auth_manager.clone();
+        let task = tokio::spawn(async move {
+            let handler = TuiDeviceCodeHandler {
+                sign_in_state: sign_in_state.clone(),
+                request_frame: request_frame.clone(),
+            };
+            let result = run_device_code_login_with_handler(opts, &handler).await;
+
+            let mut guard = sign_in_state.write().unwrap();
+            let SignInState::ChatGptDeviceCode(state) = &mut *guard else {
+                return;
+            };
+            match result {
+                Ok(()) => {
+                    // Force the auth manager to reload the new auth information.
+                    auth_manager.reload();
+                    *guard = SignInState::ChatGptSuccessMessage;
+                }
+                Err(err) => state.error = Some(format!("Sign-in failed: {err}")),
+            }
+            drop(guard);
+            request_frame.schedule_frame();
+        });
+
+        if let SignInState::ChatGptDeviceCode(state) = &mut *self.sign_in_state.write().unwrap() {
+            state.task = Some(Arc::new(DeviceCodeTask(task.abort_handle())));
+        }
+    }
+}
+
 impl StepStateProvider for AuthModeWidget {
     fn get_step_state(&self) -> StepState {
         let sign_in_state = self.sign_in_state.read().unwrap();
@@ -612,6 +819,7 @@ impl StepStateProvider for AuthModeWidget {
             SignInState::PickMode
             | SignInState::ApiKeyEntry(_)
             | SignInState::ChatGptContinueInBrowser(_)
+            | SignInState::ChatGptDeviceCode(_)
             | SignInState::ChatGptSuccessMessage => StepState::InProgress,
             SignInState::ChatGptSuccess | SignInState::ApiKeyConfigured => StepState::Complete,
         }
@@ -628,6 +836,9 @@ impl WidgetRef for AuthModeWidget {
             SignInState::ChatGptContinueInBrowser(_) => {
                 self.render_continue_in_browser(area, buf);
             }
+            SignInState::ChatGptDeviceCode(state) => {
+                self.render_device_code(area, buf, state);
+            }
             SignInState::ChatGptSuccessMessage => {
                 self.render_chatgpt_success_message(area, buf);
             }
@@ -700,4 +911,70 @@ mod tests {
         ));
         assert_eq!(widget.login_status, LoginStatus::NotAuthenticated);
     }
+
+    fn render_to_string(widget: &AuthModeWidget) -> String {
+        let area = Rect::new(0, 0, 72, 16);
+        let mut buf = Buffer::empty(area);
+        widget.render_ref(area, &mut buf);
+        (0..area.height)
+            .map(|y| {
+                let row: String = (0..area.width).map(|x| buf[(x, y)].symbol()).collect();
+                format!("{}\n", row.trim_end())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn device_code_is_rendered_in_a_box() {
+        let (widget, _tmp) = widget_forced_chatgpt();
+        *widget.sign_in_state.write().unwrap() =
+            SignInState::ChatGptDeviceCode(DeviceCodeState::default());
+        let handler = TuiDeviceCodeHandler {
+            sign_in_state: widget.sign_in_state.clone(),
+            request_frame: FrameRequester::test_dummy(),
+        };
+
+        handler.on_code(
+            "ABCD-1234",
+            "https://auth.openai.com/codex/device",
+            Duration::from_secs(15 * 60),
+        );
+
+        let rendered = render_to_string(&widget);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(
+            lines[..9],
+            [
+                "> Sign in with a device code",
+                "",
+                "  1. Open this link on any device and sign in:",
+                "     https://auth.openai.com/codex/device",
+                "  2. Enter this one-time code within 15 minutes:",
+                "",
+                "  ╭─────────────╮",
+                "  │ ABCD-1234   │",
+                "  ╰─────────────╯",
+            ]
+        );
+    }
+
+    #[test]
+    fn device_code_updates_are_ignored_after_cancel() {
+        let (widget, _tmp) = widget_forced_chatgpt();
+        let handler = TuiDeviceCodeHandler {
+            sign_in_state: widget.sign_in_state.clone(),
+            request_frame: FrameRequester::test_dummy(),
+        };
+
+        handler.on_code(
+            "ABCD-1234",
+            "https://auth.openai.com/codex/device",
+            Duration::from_secs(60),
+        );
+
+        assert!(matches!(
+            &*widget.sign_in_state.read().unwrap(),
+            SignInState::PickMode
+        ));
+    }
 }
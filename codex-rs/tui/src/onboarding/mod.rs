@@ -2,4 +2,6 @@ mod auth;
 pub mod onboarding_screen;
 mod trust_directory;
 pub use trust_directory::TrustDirectorySelection;
+pub(crate) use trust_directory::preview_trust_choices;
+pub(crate) use trust_directory::trust_summary_lines;
 mod welcome;
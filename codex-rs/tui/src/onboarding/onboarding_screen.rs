@@ -6,10 +6,15 @@ use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
+use ratatui::layout::Size;
 use ratatui::prelude::Widget;
 use ratatui::style::Color;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
 use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
 use ratatui::widgets::WidgetRef;
+use ratatui::widgets::Wrap;
 
 use codex_app_server_protocol::AuthMode;
 use codex_protocol::config_types::ForcedLoginMethod;
@@ -19,6 +24,7 @@ use crate::onboarding::auth::AuthModeWidget;
 use crate::onboarding::auth::SignInState;
 use crate::onboarding::trust_directory::TrustDirectorySelection;
 use crate::onboarding::trust_directory::TrustDirectoryWidget;
+use crate::onboarding::trust_directory::TrustPreviews;
 use crate::onboarding::welcome::WelcomeWidget;
 use crate::tui::FrameRequester;
 use crate::tui::Tui;
@@ -27,6 +33,11 @@ use color_eyre::eyre::Result;
 use std::sync::Arc;
 use std::sync::RwLock;
 
+/// Below this size the steps cannot lay out their choices, so a placeholder
+/// asking for a larger terminal is shown instead.
+const MIN_WIDTH: u16 = 60;
+const MIN_HEIGHT: u16 = 15;
+
 #[allow(clippy::large_enum_variant)]
 enum Step {
     Welcome(WelcomeWidget),
@@ -55,6 +66,9 @@ pub(crate) struct OnboardingScreen {
     steps: Vec<Step>,
     is_done: bool,
     should_exit: bool,
+    /// Set while the "Terminal too small" placeholder is on screen; the
+    /// hidden steps get no input then.
+    too_small: std::cell::Cell<bool>,
 }
 
 pub(crate) struct OnboardingScreenArgs {
@@ -63,6 +77,7 @@ pub(crate) struct OnboardingScreenArgs {
     pub login_status: LoginStatus,
     pub auth_manager: Arc<AuthManager>,
     pub config: Config,
+    pub trust_previews: Option<TrustPreviews>,
 }
 
 pub(crate) struct OnboardingResult {
@@ -78,6 +93,7 @@ impl OnboardingScreen {
             login_status,
             auth_manager,
             config,
+            trust_previews,
         } = args;
         let cwd = config.cwd.clone();
         let forced_chatgpt_workspace_id = config.forced_chatgpt_workspace_id.clone();
@@ -122,6 +138,7 @@ impl OnboardingScreen {
                 selection: None,
                 highlighted,
                 error: None,
+                previews: trust_previews,
             }))
         }
         // TODO: add git warning.
@@ -130,6 +147,7 @@ impl OnboardingScreen {
             steps,
             is_done: false,
             should_exit: false,
+            too_small: std::cell::Cell::new(false),
         }
     }
 
@@ -222,6 +240,7 @@ impl KeyboardHandler for OnboardingScreen {
                 }
                 self.is_done = true;
             }
+            _ if self.too_small.get() => {}
             _ => {
                 if let Some(Step::Welcome(widget)) = self
                     .steps
@@ -239,7 +258,7 @@ impl KeyboardHandler for OnboardingScreen {
     }
 
     fn handle_paste(&mut self, pasted: String) {
-        if pasted.is_empty() {
+        if pasted.is_empty() || self.too_small.get() {
             return;
         }
 
@@ -253,6 +272,12 @@ impl KeyboardHandler for OnboardingScreen {
 impl WidgetRef for &OnboardingScreen {
     fn render_ref(&self, area: Rect, buf: &mut Buffer) {
         Clear.render(area, buf);
+        self.too_small
+            .set(area.width < MIN_WIDTH || area.height < MIN_HEIGHT);
+        if self.too_small.get() {
+            render_too_small(area, buf);
+            return;
+        }
         // Render steps top-to-bottom, measuring each step's height dynamically.
         let mut y = area.y;
         let bottom = area.y.saturating_add(area.height);
@@ -284,19 +309,30 @@ impl WidgetRef for &OnboardingScreen {
             last_non_empty.map(|v| v + 2).unwrap_or(0)
         }
 
-        let mut i = 0usize;
         let current_steps = self.current_steps();
+        let heights: Vec<u16> = current_steps
+            .iter()
+            .map(|step| {
+                let scratch_area = Rect::new(0, 0, width, area.height);
+                let mut scratch = Buffer::empty(scratch_area);
+                step.render_ref(scratch_area, &mut scratch);
+                used_rows(&scratch, width, area.height)
+            })
+            .collect();
+
+        // Keep the active step's choices on screen: when the steps don't all
+        // fit, drop the earliest ones first.
+        let mut i = 0usize;
+        while i + 1 < current_steps.len()
+            && heights[i..].iter().copied().map(u32::from).sum::<u32>() > u32::from(area.height)
+        {
+            i += 1;
+        }
 
         while i < current_steps.len() && y < bottom {
             let step = &current_steps[i];
             let max_h = bottom.saturating_sub(y);
-            if max_h == 0 || width == 0 {
-                break;
-            }
-            let scratch_area = Rect::new(0, 0, width, max_h);
-            let mut scratch = Buffer::empty(scratch_area);
-            step.render_ref(scratch_area, &mut scratch);
-            let h = used_rows(&scratch, width, max_h).min(max_h);
+            let h = heights[i].min(max_h);
             if h > 0 {
                 let target = Rect {
                     x: area.x,
@@ -313,6 +349,23 @@ impl WidgetRef for &OnboardingScreen {
     }
 }
 
+fn render_too_small(area: Rect, buf: &mut Buffer) {
+    let lines = vec![
+        Line::from(vec!["  ".into(), "Terminal too small".bold()]),
+        Line::from(vec![
+            "  ".into(),
+            format!(
+                "Resize to at least {MIN_WIDTH}x{MIN_HEIGHT} to continue (now {}x{}).",
+                area.width, area.height
+            )
+            .dim(),
+        ]),
+    ];
+    Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .render(area, buf);
+}
+
 impl KeyboardHandler for Step {
     fn handle_key_event(&mut self, key_event: KeyEvent) {
         match self {
@@ -366,6 +419,7 @@ pub(crate) async fn run_onboarding_app(
     let mut onboarding_screen = OnboardingScreen::new(tui, args);
     // One-time guard to fully clear the screen after ChatGPT login success message is shown
     let mut did_full_clear_after_success = false;
+    let mut last_size: Option<Size> = tui.terminal.size().ok();
 
     tui.draw(u16::MAX, |frame| {
         frame.render_widget_ref(&onboarding_screen, frame.area());
@@ -383,6 +437,7 @@ pub(crate) async fn run_onboarding_app(
                 TuiEvent::Paste(text) => {
                     onboarding_screen.handle_paste(text);
                 }
+                TuiEvent::Mouse(_) => {}
                 TuiEvent::Draw => {
                     if !did_full_clear_after_success
                         && onboarding_screen.steps.iter().any(|step| {
@@ -414,6 +469,13 @@ pub(crate) async fn run_onboarding_app(
                         let _ = tui.terminal.clear();
                         did_full_clear_after_success = true;
                     }
+                    // A resize reflows every step, so wipe rows left over from
+                    // the previous layout before redrawing.
+                    let size = tui.terminal.size().ok();
+                    if size != last_size {
+                        let _ = tui.terminal.clear();
+                        last_size = size;
+                    }
                     let _ = tui.draw(u16::MAX, |frame| {
                         frame.render_widget_ref(&onboarding_screen, frame.area());
                     });
@@ -426,3 +488,103 @@ pub(crate) async fn run_onboarding_app(
         should_exit: onboarding_screen.should_exit(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_backend::VT100Backend;
+    use crossterm::event::KeyModifiers;
+    use ratatui::Terminal;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn trust_screen(codex_home: PathBuf) -> OnboardingScreen {
+        OnboardingScreen {
+            request_frame: FrameRequester::test_dummy(),
+            steps: vec![
+                Step::Welcome(WelcomeWidget::new(false, FrameRequester::test_dummy())),
+                Step::TrustDirectory(TrustDirectoryWidget {
+                    codex_home,
+                    cwd: PathBuf::from("/workspace/project"),
+                    is_git_repo: true,
+                    selection: None,
+                    highlighted: TrustDirectorySelection::Trust,
+                    error: None,
+                    previews: None,
+                }),
+            ],
+            is_done: false,
+            should_exit: false,
+            too_small: std::cell::Cell::new(false),
+        }
+    }
+
+    fn render_at(
+        terminal: &mut Terminal<VT100Backend>,
+        screen: &OnboardingScreen,
+        width: u16,
+        height: u16,
+    ) -> String {
+        terminal.backend_mut().resize(width, height);
+        terminal
+            .draw(|f| screen.render_ref(f.area(), f.buffer_mut()))
+            .expect("draw");
+        terminal.backend().vt100().screen().contents()
+    }
+
+    #[test]
+    fn trust_screen_reflows_across_resizes() {
+        let codex_home = TempDir::new().expect("temp home");
+        let screen = trust_screen(codex_home.path().to_path_buf());
+        let mut terminal = Terminal::new(VT100Backend::new(120, 40)).expect("terminal");
+
+        let large = render_at(&mut terminal, &screen, 120, 40);
+        assert!(large.contains("Welcome to Codex"), "{large}");
+        assert!(large.contains("1. Yes, allow Codex"), "{large}");
+        assert!(large.contains("Press enter to continue"), "{large}");
+
+        let small = render_at(&mut terminal, &screen, 50, 12);
+        assert!(small.contains("Terminal too small"), "{small}");
+        assert!(!small.contains("allow Codex"), "{small}");
+
+        let restored = render_at(&mut terminal, &screen, 120, 40);
+        assert!(!restored.contains("Terminal too small"), "{restored}");
+        assert!(restored.contains("1. Yes, allow Codex"), "{restored}");
+        assert!(restored.contains("Press enter to continue"), "{restored}");
+    }
+
+    #[test]
+    fn keys_are_ignored_under_the_too_small_placeholder() {
+        let codex_home = TempDir::new().expect("temp home");
+        let mut screen = trust_screen(codex_home.path().to_path_buf());
+        let mut terminal = Terminal::new(VT100Backend::new(50, 12)).expect("terminal");
+        let trust_selection = |screen: &OnboardingScreen| {
+            screen.steps.iter().find_map(|step| match step {
+                Step::TrustDirectory(widget) => Some(widget.selection),
+                _ => None,
+            })
+        };
+
+        render_at(&mut terminal, &screen, 50, 12);
+        screen.handle_key_event(KeyEvent::new(KeyCode::Char('1'), KeyModifiers::NONE));
+        assert_eq!(trust_selection(&screen), Some(None));
+
+        render_at(&mut terminal, &screen, 120, 40);
+        screen.handle_key_event(KeyEvent::new(KeyCode::Char('1'), KeyModifiers::NONE));
+        assert_eq!(
+            trust_selection(&screen),
+            Some(Some(TrustDirectorySelection::Trust))
+        );
+    }
+
+    #[test]
+    fn short_terminal_keeps_the_active_choices_visible() {
+        let codex_home = TempDir::new().expect("temp home");
+        let screen = trust_screen(codex_home.path().to_path_buf());
+        let mut terminal = Terminal::new(VT100Backend::new(80, 24)).expect("terminal");
+
+        let rendered = render_at(&mut terminal, &screen, 80, 24);
+        assert!(rendered.contains("2. No, ask me"), "{rendered}");
+        assert!(rendered.contains("Press enter to continue"), "{rendered}");
+    }
+}
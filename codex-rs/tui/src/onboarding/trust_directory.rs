@@ -1,7 +1,14 @@
+use std::path::Path;
 use std::path::PathBuf;
 
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::config::ConfigToml;
 use codex_core::config::set_project_trust_level;
 use codex_core::git_info::resolve_root_git_project_for_trust;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::SandboxPolicy;
+use codex_core::protocol::WritableRoot;
 use codex_protocol::config_types::TrustLevel;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
@@ -10,6 +17,7 @@ use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
+use ratatui::text::Span;
 use ratatui::widgets::Paragraph;
 use ratatui::widgets::WidgetRef;
 use ratatui::widgets::Wrap;
@@ -31,6 +39,176 @@ pub(crate) struct TrustDirectoryWidget {
     pub selection: Option<TrustDirectorySelection>,
     pub highlighted: TrustDirectorySelection,
     pub error: Option<String>,
+    /// What each choice resolves to. `None` when the previews could not be
+    /// computed, in which case the screen falls back to the bare choices.
+    pub previews: Option<TrustPreviews>,
+}
+
+/// Effective policy for each trust choice.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct TrustPreviews {
+    pub trust: PolicyPreview,
+    pub dont_trust: PolicyPreview,
+}
+
+impl TrustPreviews {
+    fn for_selection(&self, selection: TrustDirectorySelection) -> &PolicyPreview {
+        match selection {
+            TrustDirectorySelection::Trust => &self.trust,
+            TrustDirectorySelection::DontTrust => &self.dont_trust,
+        }
+    }
+}
+
+/// The policy a session gets once the project has a trust decision.
+///
+/// Previews are computed by loading the config with the decision applied in
+/// memory, the same way it is loaded after the decision is written, so the
+/// screen cannot disagree with what the session actually does.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct PolicyPreview {
+    pub approval_policy: AskForApproval,
+    pub sandbox_policy: SandboxPolicy,
+    pub writable_roots: Vec<WritableRoot>,
+}
+
+impl PolicyPreview {
+    pub(crate) fn from_config(config: &Config) -> Self {
+        Self {
+            approval_policy: config.approval_policy,
+            sandbox_policy: config.sandbox_policy.clone(),
+            writable_roots: config
+                .sandbox_policy
+                .get_writable_roots_with_cwd(&config.cwd),
+        }
+    }
+
+    /// One line per aspect of the policy, label first.
+    pub(crate) fn lines(&self) -> Vec<Line<'static>> {
+        let approval = match self.approval_policy {
+            AskForApproval::UnlessTrusted => "asks before anything but known read-only commands",
+            AskForApproval::OnFailure => "runs commands in the sandbox, asks if one fails",
+            AskForApproval::OnRequest => "runs commands in the sandbox, asks to go beyond it",
+            AskForApproval::Never => "never asks",
+        };
+        let (sandbox, writable, git) = match &self.sandbox_policy {
+            SandboxPolicy::ReadOnly => (
+                "read-only".to_string(),
+                "nothing".to_string(),
+                "read-only".to_string(),
+            ),
+            SandboxPolicy::DangerFullAccess => (
+                "danger-full-access (no sandbox)".to_string(),
+                "everything".to_string(),
+                "not protected".to_string(),
+            ),
+            SandboxPolicy::WorkspaceWrite { .. } => {
+                let protected: Vec<String> = self
+                    .writable_roots
+                    .iter()
+                    .flat_map(|root| &root.read_only_subpaths)
+                    .map(|path| path.display().to_string())
+                    .collect();
+                let git = if protected.is_empty() {
+                    "no .git directory under the writable roots".to_string()
+                } else {
+                    format!("read-only ({})", protected.join(", "))
+                };
+                (
+                    "workspace-write".to_string(),
+                    join_paths(self.writable_roots.iter().map(|root| root.root.as_path())),
+                    git,
+                )
+            }
+        };
+        let network = if self.sandbox_policy.has_full_network_access() {
+            "allowed"
+        } else {
+            "blocked"
+        };
+        vec![
+            preview_line("Approval", format!("{} · {approval}", self.approval_policy)),
+            preview_line("Sandbox", sandbox),
+            preview_line("Writable", writable),
+            preview_line("Network", network.to_string()),
+            preview_line("Git", git),
+        ]
+    }
+}
+
+fn preview_line(label: &'static str, value: String) -> Line<'static> {
+    Line::from(vec![format!("{label:<10}").dim(), Span::from(value)])
+}
+
+fn join_paths<'a>(paths: impl Iterator<Item = &'a Path>) -> String {
+    paths
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// History lines recording a trust decision and the policy it produced.
+pub(crate) fn trust_summary_lines(
+    selection: TrustDirectorySelection,
+    config: &Config,
+) -> Vec<Line<'static>> {
+    let project =
+        resolve_root_git_project_for_trust(&config.cwd).unwrap_or_else(|| config.cwd.clone());
+    let decision = match selection {
+        TrustDirectorySelection::Trust => "Trusted",
+        TrustDirectorySelection::DontTrust => "Not trusted",
+    };
+    let mut lines = vec![Line::from(vec![
+        "• ".dim(),
+        format!("{decision}: {}", project.display()).into(),
+    ])];
+    lines.extend(
+        PolicyPreview::from_config(config)
+            .lines()
+            .into_iter()
+            .map(|line| {
+                let mut spans = vec![Span::from("  ")];
+                spans.extend(line.spans);
+                Line::from(spans)
+            }),
+    );
+    lines
+}
+
+/// Load the config once per trust choice with that choice recorded in
+/// memory. `cfg` and `overrides` must be what the session is loaded from.
+pub(crate) fn preview_trust_choices(
+    cfg: &ConfigToml,
+    overrides: &ConfigOverrides,
+    config: &Config,
+) -> Option<TrustPreviews> {
+    let project =
+        resolve_root_git_project_for_trust(&config.cwd).unwrap_or_else(|| config.cwd.clone());
+    let preview = |trust_level: TrustLevel| {
+        let mut cfg = cfg.clone();
+        cfg.set_project_trust_level(&project, trust_level);
+        let overrides = ConfigOverrides {
+            cwd: Some(config.cwd.clone()),
+            // Only the policy is needed; skip creating scratch directories.
+            ephemeral: None,
+            ..overrides.clone()
+        };
+        match Config::load_from_base_config_with_overrides(
+            cfg,
+            overrides,
+            config.codex_home.clone(),
+        ) {
+            Ok(config) => Some(PolicyPreview::from_config(&config)),
+            Err(err) => {
+                tracing::warn!("failed to preview trust level {trust_level}: {err}");
+                None
+            }
+        }
+    };
+    Some(TrustPreviews {
+        trust: preview(TrustLevel::Trusted)?,
+        dont_trust: preview(TrustLevel::Untrusted)?,
+    })
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -94,6 +272,22 @@ impl WidgetRef for &TrustDirectoryWidget {
 
         column.push("");
 
+        if let Some(previews) = &self.previews {
+            column
+                .push(Line::from("What this choice means:".dim()).inset(Insets::tlbr(0, 2, 0, 0)));
+            column.push(
+                Paragraph::new(previews.for_selection(self.highlighted).lines())
+                    .wrap(Wrap { trim: false })
+                    .inset(Insets::tlbr(0, 4, 0, 0)),
+            );
+            column.push("");
+            column.push(
+                Line::from("You can change this later under [projects] in config.toml.".dim())
+                    .inset(Insets::tlbr(0, 2, 0, 0)),
+            );
+            column.push("");
+        }
+
         if let Some(error) = &self.error {
             column.push(
                 Paragraph::new(error.to_string())
@@ -202,6 +396,7 @@ mod tests {
             selection: None,
             highlighted: TrustDirectorySelection::DontTrust,
             error: None,
+            previews: None,
         };
 
         let release = KeyEvent {
@@ -226,6 +421,7 @@ mod tests {
             selection: None,
             highlighted: TrustDirectorySelection::Trust,
             error: None,
+            previews: None,
         };
 
         let mut terminal = Terminal::new(VT100Backend::new(70, 14)).expect("terminal");
@@ -235,4 +431,58 @@ mod tests {
 
         insta::assert_snapshot!(terminal.backend());
     }
+
+    /// Previews for a project whose config overrides the workspace-write
+    /// sandbox, rendered with one of the choices highlighted.
+    #[cfg(not(target_os = "windows"))]
+    fn render_with_previews(highlighted: TrustDirectorySelection) -> String {
+        let codex_home = TempDir::new().expect("temp home");
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+[sandbox_workspace_write]
+writable_roots = ["/workspace/shared-cache"]
+network_access = true
+exclude_tmpdir_env_var = true
+exclude_slash_tmp = true
+"#,
+        )
+        .expect("config should parse");
+        let overrides = ConfigOverrides {
+            cwd: Some(PathBuf::from("/workspace/project")),
+            ..Default::default()
+        };
+        let config = Config::load_from_base_config_with_overrides(
+            cfg.clone(),
+            overrides.clone(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect("config should load");
+        let widget = TrustDirectoryWidget {
+            codex_home: codex_home.path().to_path_buf(),
+            cwd: config.cwd.clone(),
+            is_git_repo: true,
+            selection: None,
+            highlighted,
+            error: None,
+            previews: preview_trust_choices(&cfg, &overrides, &config),
+        };
+
+        let mut terminal = Terminal::new(VT100Backend::new(90, 24)).expect("terminal");
+        terminal
+            .draw(|f| (&widget).render_ref(f.area(), f.buffer_mut()))
+            .expect("draw");
+        terminal.backend().to_string()
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn renders_policy_preview_for_trust() {
+        insta::assert_snapshot!(render_with_previews(TrustDirectorySelection::Trust));
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn renders_policy_preview_for_dont_trust() {
+        insta::assert_snapshot!(render_with_previews(TrustDirectorySelection::DontTrust));
+    }
 }
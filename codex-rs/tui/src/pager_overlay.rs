@@ -1,19 +1,32 @@
+use std::collections::HashMap;
 use std::io::Result;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
+use crate::app_event_sender::AppEventSender;
+use crate::diff_render::WordDiffMode;
 use crate::history_cell::HistoryCell;
+use crate::history_cell::PatchHistoryCell;
 use crate::history_cell::UserHistoryCell;
+use crate::hyperlinks::FileLinker;
+use crate::hyperlinks::FileReference;
 use crate::key_hint;
 use crate::key_hint::KeyBinding;
+use crate::patch_hunk_overlay::PatchHunkOverlay;
 use crate::render::Insets;
 use crate::render::renderable::InsetRenderable;
 use crate::render::renderable::Renderable;
 use crate::style::user_message_style;
 use crate::tui;
 use crate::tui::TuiEvent;
+use codex_core::protocol::FileChange;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::MouseButton;
+use crossterm::event::MouseEvent;
+use crossterm::event::MouseEventKind;
 use ratatui::buffer::Buffer;
 use ratatui::buffer::Cell;
 use ratatui::layout::Rect;
@@ -31,6 +44,7 @@ use ratatui::widgets::Wrap;
 pub(crate) enum Overlay {
     Transcript(TranscriptOverlay),
     Static(StaticOverlay),
+    PatchHunks(PatchHunkOverlay),
 }
 
 impl Overlay {
@@ -49,10 +63,20 @@ impl Overlay {
         Self::Static(StaticOverlay::with_renderables(renderables, title))
     }
 
+    pub(crate) fn new_patch_hunks(
+        id: String,
+        cwd: PathBuf,
+        changes: &HashMap<PathBuf, FileChange>,
+        app_event_tx: AppEventSender,
+    ) -> Self {
+        Self::PatchHunks(PatchHunkOverlay::new(id, cwd, changes, app_event_tx))
+    }
+
     pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
         match self {
             Overlay::Transcript(o) => o.handle_event(tui, event),
             Overlay::Static(o) => o.handle_event(tui, event),
+            Overlay::PatchHunks(o) => o.handle_event(tui, event),
         }
     }
 
@@ -60,6 +84,17 @@ impl Overlay {
         match self {
             Overlay::Transcript(o) => o.is_done(),
             Overlay::Static(o) => o.is_done(),
+            Overlay::PatchHunks(o) => o.is_done(),
+        }
+    }
+
+    /// True while the overlay handles Esc and Enter itself: in the
+    /// transcript's find mode and in the hunk picker.
+    pub(crate) fn owns_keys(&self) -> bool {
+        match self {
+            Overlay::Transcript(o) => o.is_finding(),
+            Overlay::Static(_) => false,
+            Overlay::PatchHunks(_) => true,
         }
     }
 }
@@ -74,8 +109,24 @@ const KEY_END: KeyBinding = key_hint::plain(KeyCode::End);
 const KEY_Q: KeyBinding = key_hint::plain(KeyCode::Char('q'));
 const KEY_ESC: KeyBinding = key_hint::plain(KeyCode::Esc);
 const KEY_ENTER: KeyBinding = key_hint::plain(KeyCode::Enter);
+const KEY_W: KeyBinding = key_hint::plain(KeyCode::Char('w'));
+const KEY_TAB: KeyBinding = key_hint::plain(KeyCode::Tab);
+const KEY_D: KeyBinding = key_hint::plain(KeyCode::Char('d'));
+const KEY_X: KeyBinding = key_hint::plain(KeyCode::Char('x'));
+const KEY_P: KeyBinding = key_hint::plain(KeyCode::Char('p'));
 const KEY_CTRL_T: KeyBinding = key_hint::ctrl(KeyCode::Char('t'));
 const KEY_CTRL_C: KeyBinding = key_hint::ctrl(KeyCode::Char('c'));
+const KEY_CTRL_F: KeyBinding = key_hint::ctrl(KeyCode::Char('f'));
+const KEY_SLASH: KeyBinding = key_hint::plain(KeyCode::Char('/'));
+const KEY_N: KeyBinding = key_hint::plain(KeyCode::Char('n'));
+const KEY_SHIFT_N: KeyBinding = key_hint::shift(KeyCode::Char('N'));
+const KEY_ALT_C: KeyBinding = key_hint::alt(KeyCode::Char('c'));
+const KEY_BACKSPACE: KeyBinding = key_hint::plain(KeyCode::Backspace);
+const KEY_M: KeyBinding = key_hint::plain(KeyCode::Char('m'));
+const KEY_O: KeyBinding = key_hint::plain(KeyCode::Char('o'));
+
+/// Lines scrolled per mouse wheel notch.
+const WHEEL_SCROLL_LINES: usize = 3;
 
 // Common pager navigation hints rendered on the first line
 const PAGER_KEY_HINTS: &[(&[KeyBinding], &str)] = &[
@@ -85,7 +136,7 @@ const PAGER_KEY_HINTS: &[(&[KeyBinding], &str)] = &[
 ];
 
 // Render a single line of key hints from (key(s), description) pairs.
-fn render_key_hints(area: Rect, buf: &mut Buffer, pairs: &[(&[KeyBinding], &str)]) {
+pub(crate) fn render_key_hints(area: Rect, buf: &mut Buffer, pairs: &[(&[KeyBinding], &str)]) {
     let mut spans: Vec<Span<'static>> = vec![" ".into()];
     let mut first = true;
     for (keys, desc) in pairs {
@@ -114,6 +165,101 @@ struct PagerView {
     last_rendered_height: Option<usize>,
     /// If set, on next render ensure this chunk is visible.
     pending_scroll_chunk: Option<usize>,
+    /// If set, on next render ensure this row is visible.
+    pending_scroll_row: Option<PagerRow>,
+    last_content_width: Option<u16>,
+    /// Search matches to highlight, refreshed by the owner before each render.
+    search: Option<SearchHighlight>,
+    /// Mouse capture was released with `m` so the terminal can select text.
+    mouse_released: bool,
+    /// Finds file references to underline; synced from the [`tui::Tui`]
+    /// before each draw.
+    links: Option<FileLinker>,
+    /// File references underlined in the last render, top to bottom.
+    visible_links: Vec<VisibleLink>,
+    /// Outcome of the last attempt to open a file, shown in the bottom bar.
+    link_status: Option<String>,
+}
+
+/// A file reference drawn on screen at row `y`, columns `start..=end`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct VisibleLink {
+    y: u16,
+    start: u16,
+    end: u16,
+    reference: FileReference,
+}
+
+/// A row inside one of the pager's renderables.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct PagerRow {
+    chunk: usize,
+    row: u16,
+}
+
+/// Text searched for in the rendered rows of a pager.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct SearchQuery {
+    chars: Vec<char>,
+    case_sensitive: bool,
+}
+
+impl SearchQuery {
+    fn new(text: &str, case_sensitive: bool) -> Self {
+        Self {
+            chars: text.chars().map(|c| fold_case(c, case_sensitive)).collect(),
+            case_sensitive,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    /// Inclusive column ranges of non-overlapping matches in row `y`. Works on
+    /// the rendered symbols so cells with custom rendering (exec output, diffs)
+    /// are searched exactly as they are shown.
+    fn ranges_in_row(&self, buf: &Buffer, area: Rect, y: u16) -> Vec<(u16, u16)> {
+        if self.chars.is_empty() {
+            return Vec::new();
+        }
+        // Wide characters leave an empty continuation cell behind them.
+        let columns: Vec<(u16, char)> = (area.x..area.right())
+            .filter_map(|x| {
+                let c = buf[(x, y)].symbol().chars().next()?;
+                Some((x, fold_case(c, self.case_sensitive)))
+            })
+            .collect();
+        let len = self.chars.len();
+        let mut ranges = Vec::new();
+        let mut i = 0;
+        while i + len <= columns.len() {
+            if columns[i..i + len]
+                .iter()
+                .map(|(_, c)| *c)
+                .eq(self.chars.iter().copied())
+            {
+                ranges.push((columns[i].0, columns[i + len - 1].0));
+                i += len;
+            } else {
+                i += 1;
+            }
+        }
+        ranges
+    }
+}
+
+fn fold_case(c: char, case_sensitive: bool) -> char {
+    if case_sensitive {
+        c
+    } else {
+        c.to_lowercase().next().unwrap_or(c)
+    }
+}
+
+struct SearchHighlight {
+    query: SearchQuery,
+    current: Option<PagerRow>,
 }
 
 impl PagerView {
@@ -125,9 +271,20 @@ impl PagerView {
             last_content_height: None,
             last_rendered_height: None,
             pending_scroll_chunk: None,
+            pending_scroll_row: None,
+            last_content_width: None,
+            search: None,
+            mouse_released: false,
+            links: None,
+            visible_links: Vec::new(),
+            link_status: None,
         }
     }
 
+    fn sync_links(&mut self, tui: &tui::Tui) {
+        self.links = tui.file_links().cloned();
+    }
+
     fn content_height(&self, width: u16) -> usize {
         self.renderables
             .iter()
@@ -140,6 +297,7 @@ impl PagerView {
         self.render_header(area, buf);
         let content_area = self.content_area(area);
         self.update_last_content_height(content_area.height);
+        self.last_content_width = Some(content_area.width);
         let content_height = self.content_height(content_area.width);
         self.last_rendered_height = Some(content_height);
         // If there is a pending request to scroll a specific chunk into view,
@@ -147,11 +305,18 @@ impl PagerView {
         if let Some(idx) = self.pending_scroll_chunk.take() {
             self.ensure_chunk_visible(idx, content_area);
         }
+        if let Some(row) = self.pending_scroll_row.take() {
+            self.ensure_row_visible(row, content_area, content_height);
+        }
         self.scroll_offset = self
             .scroll_offset
             .min(content_height.saturating_sub(content_area.height as usize));
 
         self.render_content(content_area, buf);
+        if let Some(search) = &self.search {
+            self.render_search_highlights(search, content_area, buf);
+        }
+        self.render_file_links(content_area, buf);
 
         self.render_bottom_bar(area, content_area, buf, content_height);
     }
@@ -226,13 +391,32 @@ impl PagerView {
         };
         let pct_text = format!(" {percent}% ");
         let pct_w = pct_text.chars().count() as u16;
+        if sep_rect.width <= pct_w {
+            return;
+        }
         let pct_x = sep_rect.x + sep_rect.width - pct_w - 1;
         Span::from(pct_text)
             .dim()
             .render_ref(Rect::new(pct_x, sep_rect.y, pct_w, 1), buf);
+
+        let hint = match &self.link_status {
+            Some(status) => Some(format!(" {status} ")),
+            None => self
+                .mouse_released
+                .then(|| " mouse released for text selection · m to capture ".to_string()),
+        };
+        if let Some(hint) = hint {
+            let hint_w = hint.chars().count() as u16;
+            if hint_w + 2 <= pct_x - sep_rect.x {
+                Span::from(hint)
+                    .dim()
+                    .render_ref(Rect::new(sep_rect.x + 1, sep_rect.y, hint_w, 1), buf);
+            }
+        }
     }
 
     fn handle_key_event(&mut self, tui: &mut tui::Tui, key_event: KeyEvent) -> Result<()> {
+        self.link_status = None;
         match key_event {
             e if KEY_UP.is_press(e) => {
                 self.scroll_offset = self.scroll_offset.saturating_sub(1);
@@ -254,6 +438,14 @@ impl PagerView {
             e if KEY_END.is_press(e) => {
                 self.scroll_offset = usize::MAX;
             }
+            e if KEY_M.is_press(e) && tui.mouse_enabled() => {
+                tui.set_mouse_capture(!tui.mouse_captured());
+                self.mouse_released = !tui.mouse_captured();
+            }
+            e if KEY_O.is_press(e) && !self.visible_links.is_empty() => {
+                let reference = self.visible_links[0].reference.clone();
+                self.open_link(&reference);
+            }
             _ => {
                 return Ok(());
             }
@@ -263,6 +455,143 @@ impl PagerView {
         Ok(())
     }
 
+    fn handle_mouse_event(&mut self, tui: &mut tui::Tui, mouse_event: MouseEvent) -> Result<()> {
+        let area = self.content_area(tui.terminal.viewport_area);
+        let handled = if let MouseEventKind::Down(MouseButton::Left) = mouse_event.kind {
+            match self.link_at(mouse_event.column, mouse_event.row) {
+                Some(reference) => {
+                    self.open_link(&reference);
+                    true
+                }
+                None => false,
+            }
+        } else {
+            self.scroll_by_wheel(mouse_event, area.height)
+        };
+        if handled {
+            tui.frame_requester()
+                .schedule_frame_in(Duration::from_millis(16));
+        }
+        Ok(())
+    }
+
+    fn link_at(&self, x: u16, y: u16) -> Option<FileReference> {
+        self.visible_links
+            .iter()
+            .find(|link| link.y == y && (link.start..=link.end).contains(&x))
+            .map(|link| link.reference.clone())
+    }
+
+    fn open_link(&mut self, reference: &FileReference) {
+        let Some(links) = &self.links else {
+            return;
+        };
+        let name = reference.path.display();
+        self.link_status = Some(match links.open(reference) {
+            Ok(()) => format!("opened {name}"),
+            Err(err) => {
+                tracing::warn!("failed to open {name}: {err}");
+                format!("could not open {name}: {err}")
+            }
+        });
+    }
+
+    /// Underline the file references on screen and remember where they are
+    /// for `o` and mouse clicks.
+    fn render_file_links(&mut self, area: Rect, buf: &mut Buffer) {
+        self.visible_links.clear();
+        let Some(links) = &self.links else {
+            return;
+        };
+        for y in area.y..area.bottom() {
+            // Wide characters leave an empty continuation cell behind them.
+            let mut text = String::new();
+            let mut columns = Vec::new();
+            for x in area.x..area.right() {
+                let symbol = buf[(x, y)].symbol();
+                if symbol.is_empty() {
+                    continue;
+                }
+                columns.push((text.len(), x));
+                text.push_str(symbol);
+            }
+            let column_at = |byte: usize| {
+                columns
+                    .iter()
+                    .rev()
+                    .find(|(start, _)| *start <= byte)
+                    .map_or(area.x, |(_, x)| *x)
+            };
+            for reference in links.references(&text) {
+                let start = column_at(reference.range.start);
+                let end = column_at(reference.range.end.saturating_sub(1));
+                for x in start..=end {
+                    buf[(x, y)].set_style(Style::default().underlined());
+                }
+                self.visible_links.push(VisibleLink {
+                    y,
+                    start,
+                    end,
+                    reference,
+                });
+            }
+        }
+    }
+
+    /// Scroll for a wheel event; returns false for any other mouse event.
+    fn scroll_by_wheel(&mut self, mouse_event: MouseEvent, page_height: u16) -> bool {
+        let Some(delta) = wheel_scroll_delta(mouse_event, page_height) else {
+            return false;
+        };
+        // Resolve "pinned to the bottom" so scrolling up moves from the end.
+        if let (Some(total), Some(height)) = (self.last_rendered_height, self.last_content_height) {
+            self.scroll_offset = self.scroll_offset.min(total.saturating_sub(height));
+        }
+        self.scroll_offset = self.scroll_offset.saturating_add_signed(delta);
+        true
+    }
+
+    /// Restyle the visible matches of `search`; the current match stands out.
+    fn render_search_highlights(&self, search: &SearchHighlight, area: Rect, buf: &mut Buffer) {
+        let current_y = search.current.and_then(|current| {
+            let row = self.chunk_top(current.chunk, area.width) + current.row as usize;
+            let y = u16::try_from(row.checked_sub(self.scroll_offset)?).ok()?;
+            (y < area.height).then_some(area.y + y)
+        });
+        for y in area.y..area.bottom() {
+            let style = if current_y == Some(y) {
+                Style::default().cyan().reversed()
+            } else {
+                Style::default().cyan().underlined()
+            };
+            for (start, end) in search.query.ranges_in_row(buf, area, y) {
+                for x in start..=end {
+                    buf[(x, y)].set_style(style);
+                }
+            }
+        }
+    }
+
+    /// Rows whose rendered text contains `query`, in display order.
+    fn find_rows(&self, query: &SearchQuery, width: u16) -> Vec<PagerRow> {
+        let mut rows = Vec::new();
+        for (chunk, renderable) in self.renderables.iter().enumerate() {
+            let height = renderable.desired_height(width);
+            if height == 0 || width == 0 {
+                continue;
+            }
+            let area = Rect::new(0, 0, width, height);
+            let mut buf = Buffer::empty(area);
+            renderable.render(area, &mut buf);
+            rows.extend(
+                (0..height)
+                    .filter(|&row| !query.ranges_in_row(&buf, area, row).is_empty())
+                    .map(|row| PagerRow { chunk, row }),
+            );
+        }
+        rows
+    }
+
     fn update_last_content_height(&mut self, height: u16) {
         self.last_content_height = Some(height as usize);
     }
@@ -301,16 +630,24 @@ impl PagerView {
         self.pending_scroll_chunk = Some(chunk_index);
     }
 
+    /// Request that the given row be scrolled into view on next render.
+    fn scroll_row_into_view(&mut self, row: PagerRow) {
+        self.pending_scroll_row = Some(row);
+    }
+
+    fn chunk_top(&self, idx: usize, width: u16) -> usize {
+        self.renderables
+            .iter()
+            .take(idx)
+            .map(|r| r.desired_height(width) as usize)
+            .sum()
+    }
+
     fn ensure_chunk_visible(&mut self, idx: usize, area: Rect) {
         if area.height == 0 || idx >= self.renderables.len() {
             return;
         }
-        let first = self
-            .renderables
-            .iter()
-            .take(idx)
-            .map(|r| r.desired_height(area.width) as usize)
-            .sum();
+        let first = self.chunk_top(idx, area.width);
         let last = first + self.renderables[idx].desired_height(area.width) as usize;
         let current_top = self.scroll_offset;
         let current_bottom = current_top.saturating_add(area.height.saturating_sub(1) as usize);
@@ -320,6 +657,22 @@ impl PagerView {
             self.scroll_offset = last.saturating_sub(area.height.saturating_sub(1) as usize);
         }
     }
+
+    /// Scroll so `row` sits a third of the way down the page, unless it is
+    /// already visible.
+    fn ensure_row_visible(&mut self, row: PagerRow, area: Rect, content_height: usize) {
+        if area.height == 0 || row.chunk >= self.renderables.len() {
+            return;
+        }
+        let target = self.chunk_top(row.chunk, area.width) + row.row as usize;
+        let height = area.height as usize;
+        let top = self
+            .scroll_offset
+            .min(content_height.saturating_sub(height));
+        if target < top || target >= top + height {
+            self.scroll_offset = target.saturating_sub(height / 3);
+        }
+    }
 }
 
 /// A renderable that caches its desired height.
@@ -370,23 +723,124 @@ impl Renderable for CellRenderable {
     }
 }
 
+/// Patch cell rendered with the overlay's word diff setting.
+struct PatchCellRenderable {
+    cell: Arc<dyn HistoryCell>,
+    word_diff: WordDiffMode,
+}
+
+impl PatchCellRenderable {
+    fn lines(&self, width: u16) -> Vec<Line<'static>> {
+        match self.cell.as_any().downcast_ref::<PatchHistoryCell>() {
+            Some(patch) => patch.lines_with_word_diff(width, self.word_diff),
+            None => self.cell.transcript_lines(width),
+        }
+    }
+}
+
+impl Renderable for PatchCellRenderable {
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        Paragraph::new(Text::from(self.lines(area.width))).render(area, buf);
+    }
+
+    fn desired_height(&self, width: u16) -> u16 {
+        Paragraph::new(Text::from(self.lines(width)))
+            .wrap(Wrap { trim: false })
+            .line_count(width)
+            .try_into()
+            .unwrap_or(0)
+    }
+}
+
 pub(crate) struct TranscriptOverlay {
     view: PagerView,
     cells: Vec<Arc<dyn HistoryCell>>,
     highlight_cell: Option<usize>,
+    word_diff: WordDiffMode,
+    find: Option<TranscriptFind>,
     is_done: bool,
 }
 
+/// Find-in-transcript state, opened with Ctrl+F or `/`.
+struct TranscriptFind {
+    query: String,
+    case_sensitive: bool,
+    /// Keystrokes edit the query; otherwise `n`/`N` step between matches.
+    editing: bool,
+    matches: Vec<PagerRow>,
+    current: Option<usize>,
+    /// Width `matches` were computed for; `None` when they are out of date.
+    matched_width: Option<u16>,
+    /// Jump to the most recent match once matches are recomputed.
+    jump_to_latest: bool,
+}
+
+impl TranscriptFind {
+    fn new() -> Self {
+        Self {
+            query: String::new(),
+            case_sensitive: false,
+            editing: true,
+            matches: Vec::new(),
+            current: None,
+            matched_width: None,
+            jump_to_latest: true,
+        }
+    }
+
+    fn query_changed(&mut self) {
+        self.matched_width = None;
+        self.jump_to_latest = true;
+    }
+
+    fn current_match(&self) -> Option<PagerRow> {
+        self.current.and_then(|i| self.matches.get(i).copied())
+    }
+
+    fn status(&self) -> String {
+        let case = if self.case_sensitive {
+            "match case"
+        } else {
+            "ignore case"
+        };
+        if self.query.is_empty() {
+            return case.to_string();
+        }
+        match self.current {
+            Some(i) if !self.matches.is_empty() => {
+                format!("{}/{} · {case}", i + 1, self.matches.len())
+            }
+            _ => format!("no matches · {case}"),
+        }
+    }
+}
+
+/// Index of the match after (or before) `current`, wrapping around at either
+/// end. Without a current match, stepping starts from the first (or last) one.
+fn wrapped_match_index(current: Option<usize>, len: usize, forward: bool) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    Some(match (current.map(|i| i.min(len - 1)), forward) {
+        (None, true) => 0,
+        (None, false) => len - 1,
+        (Some(i), true) => (i + 1) % len,
+        (Some(i), false) => (i + len - 1) % len,
+    })
+}
+
 impl TranscriptOverlay {
     pub(crate) fn new(transcript_cells: Vec<Arc<dyn HistoryCell>>) -> Self {
         Self {
             view: PagerView::new(
-                Self::render_cells(&transcript_cells, None),
+                Self::render_cells(&transcript_cells, None, WordDiffMode::default()),
                 "T R A N S C R I P T".to_string(),
                 usize::MAX,
             ),
             cells: transcript_cells,
             highlight_cell: None,
+            word_diff: WordDiffMode::default(),
+            find: None,
             is_done: false,
         }
     }
@@ -394,6 +848,7 @@ impl TranscriptOverlay {
     fn render_cells(
         cells: &[Arc<dyn HistoryCell>],
         highlight_cell: Option<usize>,
+        word_diff: WordDiffMode,
     ) -> Vec<Box<dyn Renderable>> {
         cells
             .iter()
@@ -409,10 +864,19 @@ impl TranscriptOverlay {
                             user_message_style()
                         },
                     })) as Box<dyn Renderable>
+                } else if c.as_any().is::<PatchHistoryCell>() {
+                    Box::new(CachedRenderable::new(PatchCellRenderable {
+                        cell: c.clone(),
+                        word_diff,
+                    })) as Box<dyn Renderable>
                 } else {
                     Box::new(CachedRenderable::new(CellRenderable {
                         cell: c.clone(),
-                        style: Style::default(),
+                        style: if highlight_cell == Some(i) {
+                            Style::default().reversed()
+                        } else {
+                            Style::default()
+                        },
                     })) as Box<dyn Renderable>
                 };
                 if !c.is_stream_continuation() && i > 0 {
@@ -430,7 +894,7 @@ impl TranscriptOverlay {
     pub(crate) fn insert_cell(&mut self, cell: Arc<dyn HistoryCell>) {
         let follow_bottom = self.view.is_scrolled_to_bottom();
         self.cells.push(cell);
-        self.view.renderables = Self::render_cells(&self.cells, self.highlight_cell);
+        self.rebuild_renderables();
         if follow_bottom {
             self.view.scroll_offset = usize::MAX;
         }
@@ -438,13 +902,17 @@ impl TranscriptOverlay {
 
     pub(crate) fn set_highlight_cell(&mut self, cell: Option<usize>) {
         self.highlight_cell = cell;
-        self.view.renderables = Self::render_cells(&self.cells, self.highlight_cell);
+        self.rebuild_renderables();
         if let Some(idx) = self.highlight_cell {
             self.view.scroll_chunk_into_view(idx);
         }
     }
 
     fn render_hints(&self, area: Rect, buf: &mut Buffer) {
+        if let Some(find) = &self.find {
+            self.render_find_hints(find, area, buf);
+            return;
+        }
         let line1 = Rect::new(area.x, area.y, area.width, 1);
         let line2 = Rect::new(area.x, area.y.saturating_add(1), area.width, 1);
         render_key_hints(line1, buf, PAGER_KEY_HINTS);
@@ -453,14 +921,191 @@ impl TranscriptOverlay {
             vec![(&[KEY_Q], "to quit"), (&[KEY_ESC], "to edit prev")];
         if self.highlight_cell.is_some() {
             pairs.push((&[KEY_ENTER], "to edit message"));
+            pairs.push((&[KEY_TAB], "to select output"));
+            pairs.push((&[KEY_D], "to delete"));
+            pairs.push((&[KEY_X], "to mark irrelevant"));
+            pairs.push((&[KEY_P], "to pin or unpin"));
+        }
+        let word_diff_hint = format!("to toggle word diff ({})", self.word_diff.label());
+        if self.has_patch_cells() {
+            pairs.push((&[KEY_W], word_diff_hint.as_str()));
+        }
+        pairs.push((&[KEY_SLASH], "to find"));
+        if !self.view.visible_links.is_empty() {
+            pairs.push((&[KEY_O], "to open file"));
         }
         render_key_hints(line2, buf, &pairs);
     }
 
+    /// The query row followed by the find key hints.
+    fn render_find_hints(&self, find: &TranscriptFind, area: Rect, buf: &mut Buffer) {
+        let query_row = Rect::new(area.x, area.y, area.width, 1);
+        let line1 = Rect::new(area.x, area.y.saturating_add(1), area.width, 1);
+        let line2 = Rect::new(area.x, area.y.saturating_add(2), area.width, 1);
+        Line::from(vec![
+            " find: ".dim(),
+            find.query.clone().into(),
+            "   ".into(),
+            find.status().dim(),
+        ])
+        .render(query_row, buf);
+        if find.editing {
+            // Up/Down step between matches while typing.
+            render_key_hints(line1, buf, &PAGER_KEY_HINTS[1..]);
+            render_key_hints(
+                line2,
+                buf,
+                &[
+                    (&[KEY_ENTER], "to confirm"),
+                    (&[KEY_UP, KEY_DOWN], "prev/next match"),
+                    (&[KEY_ALT_C], "to toggle case"),
+                    (&[KEY_ESC], "to close find"),
+                ],
+            );
+        } else {
+            render_key_hints(line1, buf, PAGER_KEY_HINTS);
+            render_key_hints(
+                line2,
+                buf,
+                &[
+                    (&[KEY_N, KEY_SHIFT_N], "next/prev match"),
+                    (&[KEY_ESC], "to close find"),
+                    (&[KEY_Q], "to quit"),
+                ],
+            );
+        }
+    }
+
+    fn has_patch_cells(&self) -> bool {
+        self.cells
+            .iter()
+            .any(|c| c.as_any().is::<PatchHistoryCell>())
+    }
+
+    /// Cycles word diffs for patches between auto (by extension), on, and off.
+    fn toggle_word_diff(&mut self) {
+        self.word_diff = self.word_diff.next();
+        self.rebuild_renderables();
+    }
+
+    fn rebuild_renderables(&mut self) {
+        self.view.renderables =
+            Self::render_cells(&self.cells, self.highlight_cell, self.word_diff);
+        if let Some(find) = &mut self.find {
+            find.matched_width = None;
+        }
+    }
+
+    pub(crate) fn is_finding(&self) -> bool {
+        self.find.is_some()
+    }
+
+    fn open_find(&mut self) {
+        self.find.get_or_insert_with(TranscriptFind::new).editing = true;
+    }
+
+    fn close_find(&mut self) {
+        self.find = None;
+        self.view.search = None;
+    }
+
+    /// Recompute matches for `width` if the query or the cells changed, and
+    /// hand the highlights to the pager.
+    fn refresh_find_matches(&mut self, width: u16) {
+        let Some(find) = self.find.as_mut() else {
+            self.view.search = None;
+            return;
+        };
+        let query = SearchQuery::new(&find.query, find.case_sensitive);
+        if find.matched_width != Some(width) {
+            find.matches = if query.is_empty() {
+                Vec::new()
+            } else {
+                self.view.find_rows(&query, width)
+            };
+            find.matched_width = Some(width);
+            let len = find.matches.len();
+            find.current = match find.current {
+                Some(i) if !find.jump_to_latest && i < len => Some(i),
+                _ => len.checked_sub(1),
+            };
+            if std::mem::take(&mut find.jump_to_latest)
+                && let Some(row) = find.current_match()
+            {
+                self.view.scroll_row_into_view(row);
+            }
+        }
+        self.view.search = (!query.is_empty()).then(|| SearchHighlight {
+            query,
+            current: find.current_match(),
+        });
+    }
+
+    fn step_find(&mut self, forward: bool) {
+        if let Some(width) = self.view.last_content_width {
+            self.refresh_find_matches(width);
+        }
+        let Some(find) = self.find.as_mut() else {
+            return;
+        };
+        find.current = wrapped_match_index(find.current, find.matches.len(), forward);
+        if let Some(row) = find.current_match() {
+            self.view.scroll_row_into_view(row);
+        }
+    }
+
+    /// Handle a key while find is open. Returns false for keys the find mode
+    /// leaves to the pager.
+    fn handle_find_key(&mut self, key_event: KeyEvent) -> bool {
+        let Some(find) = self.find.as_mut() else {
+            return false;
+        };
+        match key_event {
+            e if KEY_ESC.is_press(e) => self.close_find(),
+            e if KEY_ALT_C.is_press(e) => {
+                find.case_sensitive = !find.case_sensitive;
+                find.query_changed();
+            }
+            e if find.editing && KEY_ENTER.is_press(e) => {
+                if find.query.is_empty() {
+                    self.close_find();
+                } else {
+                    find.editing = false;
+                }
+            }
+            e if find.editing && KEY_BACKSPACE.is_press(e) => {
+                find.query.pop();
+                find.query_changed();
+            }
+            e if find.editing && KEY_UP.is_press(e) => self.step_find(false),
+            e if find.editing && KEY_DOWN.is_press(e) => self.step_find(true),
+            KeyEvent {
+                code: KeyCode::Char(c),
+                modifiers,
+                kind: KeyEventKind::Press | KeyEventKind::Repeat,
+                ..
+            } if find.editing && !key_hint::has_ctrl_or_alt(modifiers) => {
+                find.query.push(c);
+                find.query_changed();
+            }
+            e if KEY_N.is_press(e) => self.step_find(true),
+            KeyEvent {
+                code: KeyCode::Char('N'),
+                kind: KeyEventKind::Press | KeyEventKind::Repeat,
+                ..
+            } => self.step_find(false),
+            _ => return false,
+        }
+        true
+    }
+
     pub(crate) fn render(&mut self, area: Rect, buf: &mut Buffer) {
-        let top_h = area.height.saturating_sub(3);
+        // The find query takes an extra row above the key hints.
+        let bottom_h = if self.find.is_some() { 4 } else { 3 };
+        let top_h = area.height.saturating_sub(bottom_h);
         let top = Rect::new(area.x, area.y, area.width, top_h);
-        let bottom = Rect::new(area.x, area.y + top_h, area.width, 3);
+        let bottom = Rect::new(area.x, area.y + top_h, area.width, bottom_h);
+        self.refresh_find_matches(top.width);
         self.view.render(top, buf);
         self.render_hints(bottom, buf);
     }
@@ -469,14 +1114,33 @@ impl TranscriptOverlay {
 impl TranscriptOverlay {
     pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
         match event {
+            TuiEvent::Key(key_event) if self.handle_find_key(key_event) => {
+                tui.frame_requester()
+                    .schedule_frame_in(Duration::from_millis(16));
+                Ok(())
+            }
             TuiEvent::Key(key_event) => match key_event {
                 e if KEY_Q.is_press(e) || KEY_CTRL_C.is_press(e) || KEY_CTRL_T.is_press(e) => {
                     self.is_done = true;
                     Ok(())
                 }
+                e if KEY_CTRL_F.is_press(e) || KEY_SLASH.is_press(e) => {
+                    self.open_find();
+                    tui.frame_requester()
+                        .schedule_frame_in(Duration::from_millis(16));
+                    Ok(())
+                }
+                e if KEY_W.is_press(e) && self.has_patch_cells() => {
+                    self.toggle_word_diff();
+                    tui.frame_requester()
+                        .schedule_frame_in(Duration::from_millis(16));
+                    Ok(())
+                }
                 other => self.view.handle_key_event(tui, other),
             },
+            TuiEvent::Mouse(mouse_event) => self.view.handle_mouse_event(tui, mouse_event),
             TuiEvent::Draw => {
+                self.view.sync_links(tui);
                 tui.draw(u16::MAX, |frame| {
                     self.render(frame.area(), frame.buffer);
                 })?;
@@ -512,7 +1176,10 @@ impl StaticOverlay {
         let line1 = Rect::new(area.x, area.y, area.width, 1);
         let line2 = Rect::new(area.x, area.y.saturating_add(1), area.width, 1);
         render_key_hints(line1, buf, PAGER_KEY_HINTS);
-        let pairs: Vec<(&[KeyBinding], &str)> = vec![(&[KEY_Q], "to quit")];
+        let mut pairs: Vec<(&[KeyBinding], &str)> = vec![(&[KEY_Q], "to quit")];
+        if !self.view.visible_links.is_empty() {
+            pairs.push((&[KEY_O], "to open file"));
+        }
         render_key_hints(line2, buf, &pairs);
     }
 
@@ -535,7 +1202,9 @@ impl StaticOverlay {
                 }
                 other => self.view.handle_key_event(tui, other),
             },
+            TuiEvent::Mouse(mouse_event) => self.view.handle_mouse_event(tui, mouse_event),
             TuiEvent::Draw => {
+                self.view.sync_links(tui);
                 tui.draw(u16::MAX, |frame| {
                     self.render(frame.area(), frame.buffer);
                 })?;
@@ -549,6 +1218,17 @@ impl StaticOverlay {
     }
 }
 
+/// Rows to scroll for a wheel event: up is negative. A notch never moves more
+/// than half of a short page so the reader keeps their place.
+fn wheel_scroll_delta(mouse_event: MouseEvent, page_height: u16) -> Option<isize> {
+    let lines = WHEEL_SCROLL_LINES.min((page_height as usize / 2).max(1)) as isize;
+    match mouse_event.kind {
+        MouseEventKind::ScrollUp => Some(-lines),
+        MouseEventKind::ScrollDown => Some(lines),
+        _ => None,
+    }
+}
+
 fn render_offset_content(
     area: Rect,
     buf: &mut Buffer,
@@ -593,8 +1273,11 @@ mod tests {
     use crate::history_cell::new_patch_event;
     use codex_core::protocol::FileChange;
     use codex_protocol::parse_command::ParsedCommand;
+    use crossterm::event::KeyModifiers;
+    use pretty_assertions::assert_eq;
     use ratatui::Terminal;
     use ratatui::backend::TestBackend;
+    use ratatui::style::Modifier;
     use ratatui::text::Text;
 
     #[derive(Debug)]
@@ -646,6 +1329,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn transcript_overlay_underlines_file_references() {
+        let cwd = std::env::temp_dir().join("repo");
+        let mut overlay = TranscriptOverlay::new(vec![Arc::new(TestCell {
+            lines: vec![Line::from("see src/foo.rs:42 now")],
+        })]);
+        overlay.view.links = Some(FileLinker::new(&cwd));
+
+        let area = Rect::new(0, 0, 60, 10);
+        let mut buf = Buffer::empty(area);
+        overlay.render(area, &mut buf);
+
+        let [link] = overlay.view.visible_links.as_slice() else {
+            panic!("expected one link, got {:?}", overlay.view.visible_links);
+        };
+        assert_eq!(
+            link.reference,
+            FileReference {
+                range: 4..17,
+                path: cwd.join("src/foo.rs"),
+                line: Some(42),
+            }
+        );
+        let linked: String = (link.start..=link.end)
+            .map(|x| buf[(x, link.y)].symbol())
+            .collect();
+        assert_eq!(linked, "src/foo.rs:42");
+        assert!(
+            (link.start..=link.end)
+                .all(|x| buf[(x, link.y)].modifier.contains(Modifier::UNDERLINED))
+        );
+        assert!(
+            !buf[(link.end + 1, link.y)]
+                .modifier
+                .contains(Modifier::UNDERLINED)
+        );
+        assert_eq!(
+            overlay.view.link_at(link.start, link.y),
+            Some(link.reference.clone())
+        );
+        assert!(buffer_to_text(&buf, area).contains("o to open file"));
+    }
+
     #[test]
     fn transcript_overlay_snapshot_basic() {
         // Prepare a transcript overlay with a few lines
@@ -686,6 +1412,127 @@ mod tests {
         out
     }
 
+    /// Like `buffer_to_text`, but wraps the current find match in `[]` and the
+    /// other matches in `<>`.
+    fn buffer_to_marked_text(buf: &Buffer, area: Rect) -> String {
+        let mut out = String::new();
+        for y in area.y..area.bottom() {
+            let mut row = String::new();
+            let mut close: Option<char> = None;
+            for x in area.x..area.right() {
+                let cell = &buf[(x, y)];
+                let modifiers = cell.style().add_modifier;
+                let marks = if modifiers.contains(Modifier::REVERSED) {
+                    Some(('[', ']'))
+                } else if modifiers.contains(Modifier::UNDERLINED) {
+                    Some(('<', '>'))
+                } else {
+                    None
+                };
+                if close != marks.map(|(_, c)| c) {
+                    row.extend(close);
+                    row.extend(marks.map(|(open, _)| open));
+                    close = marks.map(|(_, c)| c);
+                }
+                row.push(cell.symbol().chars().next().unwrap_or(' '));
+            }
+            row.extend(close);
+            out.push_str(row.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+
+    fn press(overlay: &mut TranscriptOverlay, code: KeyCode) -> bool {
+        overlay.handle_find_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn find_match_index_wraps_in_both_directions() {
+        let steps = [
+            wrapped_match_index(None, 0, true),
+            wrapped_match_index(None, 3, true),
+            wrapped_match_index(None, 3, false),
+            wrapped_match_index(Some(1), 3, true),
+            wrapped_match_index(Some(2), 3, true),
+            wrapped_match_index(Some(0), 3, false),
+            // An index left over from a longer match list steps from the end.
+            wrapped_match_index(Some(7), 3, true),
+        ];
+
+        assert_eq!(
+            steps,
+            [None, Some(0), Some(2), Some(2), Some(0), Some(2), Some(0)]
+        );
+    }
+
+    #[test]
+    fn transcript_overlay_find_highlights_current_match() {
+        let mut overlay = TranscriptOverlay::new(
+            ["alpha one", "beta two", "Alpha three", "gamma"]
+                .into_iter()
+                .map(|text| {
+                    Arc::new(TestCell {
+                        lines: vec![Line::from(text)],
+                    }) as Arc<dyn HistoryCell>
+                })
+                .collect(),
+        );
+        let area = Rect::new(0, 0, 70, 13);
+        overlay.render(area, &mut Buffer::empty(area));
+
+        overlay.open_find();
+        for c in "alpha".chars() {
+            assert!(press(&mut overlay, KeyCode::Char(c)));
+        }
+        overlay.render(area, &mut Buffer::empty(area));
+        // Matching ignores case and starts at the most recent match.
+        let find = overlay.find.as_ref().expect("find open");
+        assert_eq!(
+            (find.matches.clone(), find.current),
+            (
+                vec![PagerRow { chunk: 0, row: 0 }, PagerRow { chunk: 2, row: 1 }],
+                Some(1)
+            )
+        );
+
+        assert!(press(&mut overlay, KeyCode::Enter));
+        assert!(press(&mut overlay, KeyCode::Char('n')));
+        let mut buf = Buffer::empty(area);
+        overlay.render(area, &mut buf);
+        assert_snapshot!(
+            "transcript_overlay_find_highlights_current_match",
+            buffer_to_marked_text(&buf, area)
+        );
+
+        assert!(press(&mut overlay, KeyCode::Esc));
+        let mut buf = Buffer::empty(area);
+        overlay.render(area, &mut buf);
+        assert!(!overlay.is_finding());
+        let text = buffer_to_marked_text(&buf, area);
+        assert!(!text.contains('[') && !text.contains('<'), "{text}");
+    }
+
+    #[test]
+    fn find_can_match_case() {
+        let mut overlay = TranscriptOverlay::new(vec![Arc::new(TestCell {
+            lines: vec![Line::from("Alpha alpha")],
+        })]);
+        let area = Rect::new(0, 0, 40, 10);
+        overlay.open_find();
+        for c in "Alpha".chars() {
+            press(&mut overlay, KeyCode::Char(c));
+        }
+        overlay.handle_find_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::ALT));
+        let mut buf = Buffer::empty(area);
+        overlay.render(area, &mut buf);
+
+        assert_eq!(
+            buffer_to_marked_text(&buf, area).lines().nth(1),
+            Some("[Alpha] alpha")
+        );
+    }
+
     #[test]
     fn transcript_overlay_apply_patch_scroll_vt100_clears_previous_page() {
         let cwd = PathBuf::from("/repo");
@@ -698,7 +1545,8 @@ mod tests {
                 content: "hello\nworld\n".to_string(),
             },
         );
-        let approval_cell: Arc<dyn HistoryCell> = Arc::new(new_patch_event(approval_changes, &cwd));
+        let approval_cell: Arc<dyn HistoryCell> =
+            Arc::new(new_patch_event(approval_changes, &cwd, &[]));
         cells.push(approval_cell);
 
         let mut apply_changes = HashMap::new();
@@ -708,7 +1556,8 @@ mod tests {
                 content: "hello\nworld\n".to_string(),
             },
         );
-        let apply_begin_cell: Arc<dyn HistoryCell> = Arc::new(new_patch_event(apply_changes, &cwd));
+        let apply_begin_cell: Arc<dyn HistoryCell> =
+            Arc::new(new_patch_event(apply_changes, &cwd, &[]));
         cells.push(apply_begin_cell);
 
         let apply_end_cell: Arc<dyn HistoryCell> =
@@ -729,6 +1578,7 @@ mod tests {
                 exit_code: 0,
                 aggregated_output: "src\nREADME.md\n".into(),
                 formatted_output: "src\nREADME.md\n".into(),
+                stderr_ranges: Vec::new(),
             },
             Duration::from_millis(420),
         );
@@ -747,6 +1597,43 @@ mod tests {
         assert_snapshot!("transcript_overlay_apply_patch_scroll_vt100", snapshot);
     }
 
+    #[test]
+    fn transcript_overlay_toggles_word_diff_for_patches() {
+        let mut changes = HashMap::new();
+        changes.insert(
+            PathBuf::from("notes.md"),
+            FileChange::Update {
+                unified_diff: diffy::create_patch("the quick fox\n", "the slow fox\n").to_string(),
+                move_path: None,
+            },
+        );
+        let cell: Arc<dyn HistoryCell> = Arc::new(new_patch_event(
+            changes,
+            &PathBuf::from("/repo"),
+            &["md".to_string()],
+        ));
+        let mut overlay = TranscriptOverlay::new(vec![cell]);
+        let area = Rect::new(0, 0, 60, 10);
+
+        let rendered = |overlay: &mut TranscriptOverlay| {
+            let mut buf = Buffer::empty(area);
+            overlay.render(area, &mut buf);
+            buffer_to_text(&buf, area)
+        };
+
+        let auto = rendered(&mut overlay);
+        assert!(auto.contains("1 ~the quickslow fox"), "{auto}");
+        assert!(auto.contains("w to toggle word diff (auto)"), "{auto}");
+
+        overlay.toggle_word_diff();
+        overlay.toggle_word_diff();
+        assert_eq!(overlay.word_diff, WordDiffMode::Off);
+        let off = rendered(&mut overlay);
+        assert!(off.contains("1 -the quick fox"), "{off}");
+        assert!(off.contains("1 +the slow fox"), "{off}");
+        assert!(off.contains("w to toggle word diff (off)"), "{off}");
+    }
+
     #[test]
     fn transcript_overlay_keeps_scroll_pinned_at_bottom() {
         let mut overlay = TranscriptOverlay::new(
@@ -909,4 +1796,44 @@ mod tests {
             "expected view to report at bottom after scrolling to end"
         );
     }
+
+    fn mouse(kind: MouseEventKind) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column: 0,
+            row: 0,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn wheel_events_translate_to_scroll_deltas() {
+        assert_eq!(
+            [
+                wheel_scroll_delta(mouse(MouseEventKind::ScrollUp), 20),
+                wheel_scroll_delta(mouse(MouseEventKind::ScrollDown), 20),
+                wheel_scroll_delta(mouse(MouseEventKind::ScrollDown), 4),
+                wheel_scroll_delta(mouse(MouseEventKind::ScrollUp), 1),
+                wheel_scroll_delta(mouse(MouseEventKind::Moved), 20),
+            ],
+            [Some(-3), Some(3), Some(2), Some(-1), None]
+        );
+    }
+
+    #[test]
+    fn pager_view_wheel_scrolls_from_the_bottom() {
+        let mut pv = PagerView::new(vec![paragraph_block("a", 20)], "T".to_string(), usize::MAX);
+        let area = Rect::new(0, 0, 20, 10);
+        let mut buf = Buffer::empty(area);
+        pv.render(area, &mut buf);
+        assert_eq!(pv.scroll_offset, 12);
+
+        assert!(pv.scroll_by_wheel(mouse(MouseEventKind::ScrollUp), 8));
+        assert_eq!(pv.scroll_offset, 9);
+
+        assert!(pv.scroll_by_wheel(mouse(MouseEventKind::ScrollDown), 8));
+        pv.render(area, &mut buf);
+        assert_eq!(pv.scroll_offset, 12);
+        assert!(!pv.scroll_by_wheel(mouse(MouseEventKind::Moved), 8));
+    }
 }
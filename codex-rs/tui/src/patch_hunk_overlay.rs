@@ -0,0 +1,418 @@
+//! Full-screen picker for the hunks of a proposed patch.
+//!
+//! Opened from the patch approval prompt. Every file and hunk starts out
+//! selected; Enter sends the selection back to the prompt, which approves the
+//! selected hunks and rejects the rest. Esc returns to the prompt without
+//! deciding.
+
+use std::collections::HashMap;
+use std::io::Result;
+use std::path::PathBuf;
+
+use codex_core::patch_selection::PatchSelection;
+use codex_core::patch_selection::SelectableFile;
+use codex_core::protocol::FileChange;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::MouseEventKind;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Widget;
+use ratatui::widgets::WidgetRef;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+use crate::diff_render::display_path_for;
+use crate::key_hint;
+use crate::key_hint::KeyBinding;
+use crate::pager_overlay::render_key_hints;
+use crate::tui;
+use crate::tui::TuiEvent;
+
+const KEY_UP: KeyBinding = key_hint::plain(KeyCode::Up);
+const KEY_DOWN: KeyBinding = key_hint::plain(KeyCode::Down);
+const KEY_K: KeyBinding = key_hint::plain(KeyCode::Char('k'));
+const KEY_J: KeyBinding = key_hint::plain(KeyCode::Char('j'));
+const KEY_SPACE: KeyBinding = key_hint::plain(KeyCode::Char(' '));
+const KEY_A: KeyBinding = key_hint::plain(KeyCode::Char('a'));
+const KEY_ENTER: KeyBinding = key_hint::plain(KeyCode::Enter);
+const KEY_ESC: KeyBinding = key_hint::plain(KeyCode::Esc);
+const KEY_Q: KeyBinding = key_hint::plain(KeyCode::Char('q'));
+const KEY_CTRL_C: KeyBinding = key_hint::ctrl(KeyCode::Char('c'));
+
+/// Rows below the list: the selection count and two lines of key hints.
+const FOOTER_HEIGHT: u16 = 3;
+
+pub(crate) struct PatchHunkOverlay {
+    /// The approval request the selection answers.
+    id: String,
+    cwd: PathBuf,
+    selection: PatchSelection,
+    /// One row per file, followed by one per hunk of its diff.
+    rows: Vec<(usize, Option<usize>)>,
+    cursor: usize,
+    scroll_offset: usize,
+    app_event_tx: AppEventSender,
+    is_done: bool,
+}
+
+impl PatchHunkOverlay {
+    pub(crate) fn new(
+        id: String,
+        cwd: PathBuf,
+        changes: &HashMap<PathBuf, FileChange>,
+        app_event_tx: AppEventSender,
+    ) -> Self {
+        let selection = PatchSelection::new(changes);
+        let rows = selection
+            .files()
+            .iter()
+            .enumerate()
+            .flat_map(|(file_idx, file)| {
+                std::iter::once((file_idx, None))
+                    .chain((0..file.hunks.len()).map(move |hunk| (file_idx, Some(hunk))))
+            })
+            .collect();
+        Self {
+            id,
+            cwd,
+            selection,
+            rows,
+            cursor: 0,
+            scroll_offset: 0,
+            app_event_tx,
+            is_done: false,
+        }
+    }
+
+    pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
+        match event {
+            TuiEvent::Key(key_event) => {
+                self.handle_key_event(key_event);
+                tui.frame_requester().schedule_frame();
+                Ok(())
+            }
+            TuiEvent::Mouse(mouse_event) => {
+                match mouse_event.kind {
+                    MouseEventKind::ScrollUp => self.move_cursor(-1),
+                    MouseEventKind::ScrollDown => self.move_cursor(1),
+                    _ => return Ok(()),
+                }
+                tui.frame_requester().schedule_frame();
+                Ok(())
+            }
+            TuiEvent::Draw => {
+                tui.draw(u16::MAX, |frame| {
+                    self.render(frame.area(), frame.buffer);
+                })?;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.is_done
+    }
+
+    fn handle_key_event(&mut self, key_event: KeyEvent) {
+        match key_event {
+            e if KEY_UP.is_press(e) || KEY_K.is_press(e) => self.move_cursor(-1),
+            e if KEY_DOWN.is_press(e) || KEY_J.is_press(e) => self.move_cursor(1),
+            e if KEY_SPACE.is_press(e) => match self.rows.get(self.cursor) {
+                Some((file, Some(hunk))) => self.selection.toggle_hunk(*file, *hunk),
+                Some((file, None)) => self.selection.toggle_file(*file),
+                None => {}
+            },
+            e if KEY_A.is_press(e) => {
+                if let Some((file, _)) = self.rows.get(self.cursor) {
+                    self.selection.toggle_file(*file);
+                }
+            }
+            e if KEY_ENTER.is_press(e) => {
+                self.app_event_tx.send(AppEvent::PatchHunksSelected {
+                    id: self.id.clone(),
+                    selection: self.selection.clone(),
+                });
+                self.is_done = true;
+            }
+            e if KEY_ESC.is_press(e) || KEY_Q.is_press(e) || KEY_CTRL_C.is_press(e) => {
+                self.is_done = true;
+            }
+            _ => {}
+        }
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let last = self.rows.len().saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+    }
+
+    /// Every row's lines, and the index of each row's first line.
+    fn lines(&self) -> (Vec<Line<'static>>, Vec<usize>) {
+        let files = self.selection.files();
+        let mut lines = Vec::new();
+        let mut starts = Vec::with_capacity(self.rows.len());
+        for (row, (file_idx, hunk)) in self.rows.iter().enumerate() {
+            starts.push(lines.len());
+            let pointer: Span<'static> = if row == self.cursor {
+                "› ".cyan().bold()
+            } else {
+                "  ".into()
+            };
+            let file = &files[*file_idx];
+            match hunk {
+                None => {
+                    if *file_idx > 0 {
+                        lines.push(Line::from(""));
+                    }
+                    lines.push(self.file_line(pointer, file));
+                }
+                Some(hunk) => lines.extend(hunk_lines(pointer, file, *hunk)),
+            }
+        }
+        (lines, starts)
+    }
+
+    fn file_line(&self, pointer: Span<'static>, file: &SelectableFile) -> Line<'static> {
+        let mut spans = vec![
+            pointer,
+            checkbox(file.selected_count(), file.unit_count()),
+            " ".into(),
+            display_path_for(&file.path, &self.cwd).bold(),
+        ];
+        match &file.change {
+            FileChange::Add { .. } => spans.push(" (new file)".dim()),
+            FileChange::Delete { .. } => spans.push(" (deleted)".dim()),
+            FileChange::Update { move_path, .. } => {
+                if let Some(dest) = move_path {
+                    spans.push(format!(" → {}", display_path_for(dest, &self.cwd)).into());
+                }
+                if file.hunks.len() > 1 {
+                    spans.push(
+                        format!(
+                            " ({} of {} hunks)",
+                            file.selected_count(),
+                            file.unit_count()
+                        )
+                        .dim(),
+                    );
+                }
+            }
+        }
+        if !file.is_toggleable() {
+            spans.push(" (binary, always applied)".dim());
+        }
+        Line::from(spans)
+    }
+
+    /// Scroll so the cursor row is on screen; `height` is the list's height.
+    fn ensure_cursor_visible(&mut self, starts: &[usize], total: usize, height: usize) {
+        let Some(&top) = starts.get(self.cursor) else {
+            return;
+        };
+        let bottom = starts.get(self.cursor + 1).copied().unwrap_or(total);
+        if top < self.scroll_offset {
+            self.scroll_offset = top;
+        } else if bottom > self.scroll_offset + height {
+            // Show the whole row if it fits, otherwise its first lines.
+            self.scroll_offset = bottom.saturating_sub(height).min(top);
+        }
+        self.scroll_offset = self.scroll_offset.min(total.saturating_sub(height));
+    }
+
+    pub(crate) fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        if area.height <= FOOTER_HEIGHT + 1 {
+            return;
+        }
+        let header = Rect::new(area.x, area.y, area.width, 1);
+        Span::from("/ ".repeat(area.width as usize / 2))
+            .dim()
+            .render_ref(header, buf);
+        "/ P A T C H   H U N K S".dim().render_ref(header, buf);
+
+        let list_height = area.height - FOOTER_HEIGHT - 1;
+        let (lines, starts) = self.lines();
+        self.ensure_cursor_visible(&starts, lines.len(), list_height as usize);
+        for (offset, line) in lines
+            .iter()
+            .skip(self.scroll_offset)
+            .take(list_height as usize)
+            .enumerate()
+        {
+            let y = area.y + 1 + offset as u16;
+            line.render_ref(Rect::new(area.x, y, area.width, 1), buf);
+        }
+
+        let footer_y = area.bottom() - FOOTER_HEIGHT;
+        Line::from(vec![
+            " ".into(),
+            format!(
+                "{} of {} hunks selected",
+                self.selection.selected_count(),
+                self.selection.unit_count()
+            )
+            .bold(),
+        ])
+        .render_ref(Rect::new(area.x, footer_y, area.width, 1), buf);
+        render_key_hints(
+            Rect::new(area.x, footer_y + 1, area.width, 1),
+            buf,
+            &[
+                (&[KEY_UP, KEY_DOWN], "to move"),
+                (&[KEY_SPACE], "to toggle"),
+                (&[KEY_A], "to toggle file"),
+            ],
+        );
+        render_key_hints(
+            Rect::new(area.x, footer_y + 2, area.width, 1),
+            buf,
+            &[
+                (&[KEY_ENTER], "to apply selected"),
+                (&[KEY_ESC], "to go back"),
+            ],
+        );
+    }
+}
+
+fn checkbox(selected: usize, total: usize) -> Span<'static> {
+    if selected == 0 {
+        "[ ]".dim()
+    } else if selected < total {
+        "[~]".cyan()
+    } else {
+        "[x]".green()
+    }
+}
+
+/// The hunk's `@@` line with its checkbox, then its diff lines; dimmed when
+/// the hunk is deselected.
+fn hunk_lines(pointer: Span<'static>, file: &SelectableFile, hunk: usize) -> Vec<Line<'static>> {
+    let selected = file.is_hunk_selected(hunk);
+    let mut diff = file.hunks[hunk].lines();
+    let mut lines = vec![Line::from(vec![
+        pointer,
+        "  ".into(),
+        checkbox(usize::from(selected), 1),
+        " ".into(),
+        diff.next().unwrap_or_default().to_string().cyan(),
+    ])];
+    for text in diff.filter(|line| !line.starts_with('\\')) {
+        let span = Span::from(text.to_string());
+        let span = if !selected {
+            span.dim()
+        } else if text.starts_with('+') {
+            span.green()
+        } else if text.starts_with('-') {
+            span.red()
+        } else {
+            span
+        };
+        lines.push(Line::from(vec!["        ".into(), span]));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use insta::assert_snapshot;
+    use pretty_assertions::assert_eq;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn changes() -> HashMap<PathBuf, FileChange> {
+        HashMap::from([
+            (
+                PathBuf::from("src/lib.rs"),
+                FileChange::Update {
+                    unified_diff: "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,2 +1,2 @@\n use std::fmt;\n-use std::io;\n+use std::io::Write;\n@@ -20,2 +20,3 @@\n fn main() {\n+    run();\n }\n".to_string(),
+                    move_path: None,
+                },
+            ),
+            (
+                PathBuf::from("README.md"),
+                FileChange::Add {
+                    content: "# Demo\n".to_string(),
+                },
+            ),
+        ])
+    }
+
+    fn overlay() -> (
+        PatchHunkOverlay,
+        tokio::sync::mpsc::UnboundedReceiver<AppEvent>,
+    ) {
+        let (tx, rx) = unbounded_channel::<AppEvent>();
+        let overlay = PatchHunkOverlay::new(
+            "call-1".to_string(),
+            PathBuf::from("/"),
+            &changes(),
+            AppEventSender::new(tx),
+        );
+        (overlay, rx)
+    }
+
+    fn press(overlay: &mut PatchHunkOverlay, code: KeyCode) {
+        overlay.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn enter_sends_the_toggled_selection() {
+        let (mut overlay, mut rx) = overlay();
+        // README.md, then src/lib.rs and its two hunks.
+        press(&mut overlay, KeyCode::Char(' '));
+        press(&mut overlay, KeyCode::Down);
+        press(&mut overlay, KeyCode::Down);
+        press(&mut overlay, KeyCode::Down);
+        press(&mut overlay, KeyCode::Char(' '));
+        press(&mut overlay, KeyCode::Enter);
+
+        let mut expected = PatchSelection::new(&changes());
+        expected.toggle_file(0);
+        expected.toggle_hunk(1, 1);
+        let Ok(AppEvent::PatchHunksSelected { id, selection }) = rx.try_recv() else {
+            panic!("expected a PatchHunksSelected event");
+        };
+        assert_eq!((id, selection), ("call-1".to_string(), expected));
+        assert!(overlay.is_done());
+    }
+
+    #[test]
+    fn esc_goes_back_without_deciding() {
+        let (mut overlay, mut rx) = overlay();
+        press(&mut overlay, KeyCode::Esc);
+
+        assert!(overlay.is_done());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn patch_hunk_overlay_render() {
+        let (mut overlay, _rx) = overlay();
+        press(&mut overlay, KeyCode::Down);
+        press(&mut overlay, KeyCode::Down);
+        press(&mut overlay, KeyCode::Char(' '));
+
+        let area = Rect::new(0, 0, 60, 18);
+        let mut buf = Buffer::empty(area);
+        overlay.render(area, &mut buf);
+
+        let rendered = (area.top()..area.bottom())
+            .map(|y| {
+                (area.left()..area.right())
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_snapshot!(rendered);
+    }
+}
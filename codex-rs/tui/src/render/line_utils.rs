@@ -1,5 +1,7 @@
+use ratatui::style::Style;
 use ratatui::text::Line;
 use ratatui::text::Span;
+use unicode_width::UnicodeWidthChar;
 
 /// Clone a borrowed ratatui `Line` into an owned `'static` line.
 pub fn line_to_static(line: &Line<'_>) -> Line<'static> {
@@ -17,6 +19,61 @@ pub fn line_to_static(line: &Line<'_>) -> Line<'static> {
     }
 }
 
+/// Cut `line` to at most `max_width` columns, ending it with "…" when
+/// anything was dropped. Spaces before the ellipsis are dropped too, and the
+/// ellipsis takes the style of the span it cuts.
+pub fn truncate_line_with_ellipsis(line: Line<'static>, max_width: usize) -> Line<'static> {
+    if line.width() <= max_width {
+        return line;
+    }
+    let Line {
+        style,
+        alignment,
+        spans,
+    } = line;
+    let budget = max_width.saturating_sub(1);
+    let mut kept_spans = Vec::with_capacity(spans.len());
+    let mut ellipsis_style = Style::default();
+    let mut used = 0;
+    for span in spans {
+        ellipsis_style = span.style;
+        let mut kept = String::new();
+        let mut cut = false;
+        for ch in span.content.chars() {
+            let ch_width = UnicodeWidthChar::width(ch).unwrap_or(0);
+            if used + ch_width > budget {
+                cut = true;
+                break;
+            }
+            used += ch_width;
+            kept.push(ch);
+        }
+        if !kept.is_empty() {
+            kept_spans.push(Span::styled(kept, span.style));
+        }
+        if cut {
+            break;
+        }
+    }
+    while let Some(last) = kept_spans.last_mut() {
+        let trimmed = last.content.trim_end_matches(' ');
+        if trimmed.is_empty() {
+            kept_spans.pop();
+        } else {
+            last.content = trimmed.to_string().into();
+            break;
+        }
+    }
+    if max_width > 0 {
+        kept_spans.push(Span::styled("…", ellipsis_style));
+    }
+    Line {
+        style,
+        alignment,
+        spans: kept_spans,
+    }
+}
+
 /// Append owned copies of borrowed lines to `out`.
 pub fn push_owned_lines<'a>(src: &[Line<'a>], out: &mut Vec<Line<'static>>) {
     for l in src {
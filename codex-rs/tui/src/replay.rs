@@ -0,0 +1,914 @@
+//! `codex replay`: re-render a recorded session in a read-only TUI.
+//!
+//! Replay never creates a conversation. Events are read from the rollout file
+//! and handed to [`ChatWidget::replay_event`], the same path used to seed the
+//! transcript of a resumed session, and any ops the widget tries to submit are
+//! dropped. No model request, command, or MCP server is ever started.
+
+use std::fmt;
+use std::io::Write as _;
+use std::ops::Range;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::DateTime;
+use codex_core::AuthManager;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::find_conversation_path_by_id_str;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::RolloutItem;
+use codex_core::protocol::RolloutLine;
+use codex_core::protocol::SessionMeta;
+use codex_core::protocol::SessionMetaLine;
+use color_eyre::eyre::Result;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Paragraph;
+use tokio::select;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::sync::mpsc::unbounded_channel;
+use tokio::time::Instant;
+
+use crate::ReplayCli;
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+use crate::chatwidget::ChatWidget;
+use crate::chatwidget::ChatWidgetInit;
+use crate::custom_terminal::Terminal;
+use crate::headless_backend::HeadlessBackend;
+use crate::history_cell::HistoryCell;
+use crate::insert_history::insert_history_lines;
+use crate::render::renderable::Renderable;
+use crate::tui;
+use crate::tui::FrameRequester;
+use crate::tui::Tui;
+use crate::tui::TuiEvent;
+use crate::turn_separators::TurnSeparatorState;
+
+/// Longest recorded pause replayed between two events, before speed scaling.
+/// A session left idle overnight should not stall playback.
+const MAX_EVENT_GAP: Duration = Duration::from_secs(5);
+
+/// Rows used by the replay controls in place of the composer.
+const CONTROLS_HEIGHT: u16 = 2;
+
+/// Playback speed for `codex replay --speed`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// Emit every event immediately.
+    Instant,
+    /// Divide the recorded gaps between events by this factor.
+    Factor(f64),
+}
+
+impl FromStr for ReplaySpeed {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("instant") {
+            return Ok(ReplaySpeed::Instant);
+        }
+        let factor = s.strip_suffix(['x', 'X']).unwrap_or(s);
+        match factor.parse::<f64>() {
+            Ok(factor) if factor.is_finite() && factor > 0.0 => Ok(ReplaySpeed::Factor(factor)),
+            _ => Err(format!(
+                "invalid replay speed `{s}`; expected a multiplier like `4x` or `instant`"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ReplaySpeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplaySpeed::Instant => write!(f, "instant"),
+            ReplaySpeed::Factor(factor) => write!(f, "{factor}x"),
+        }
+    }
+}
+
+/// A recorded event and when it happened relative to the first one.
+#[derive(Debug, Clone)]
+pub(crate) struct ReplayEvent {
+    pub(crate) offset: Duration,
+    pub(crate) msg: EventMsg,
+}
+
+/// The replayable contents of a rollout file.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ReplayScript {
+    pub(crate) meta: Option<SessionMeta>,
+    pub(crate) model: Option<String>,
+    pub(crate) events: Vec<ReplayEvent>,
+}
+
+impl ReplayScript {
+    /// Parse a rollout file. Unreadable lines are skipped, as when resuming.
+    pub(crate) fn parse(text: &str) -> Self {
+        let mut script = Self::default();
+        let mut start = None;
+        let mut last_offset = Duration::ZERO;
+        for line in text.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let RolloutLine { timestamp, item } = match serde_json::from_str(line) {
+                Ok(line) => line,
+                Err(err) => {
+                    tracing::warn!("skipping unreadable rollout line: {err}");
+                    continue;
+                }
+            };
+            match item {
+                RolloutItem::SessionMeta(SessionMetaLine { meta, .. }) => {
+                    script.meta.get_or_insert(meta);
+                }
+                RolloutItem::TurnContext(context) => {
+                    script.model.get_or_insert(context.model);
+                }
+                RolloutItem::EventMsg(EventMsg::SessionConfigured(_)) => {}
+                RolloutItem::EventMsg(msg) => {
+                    let offset = match DateTime::parse_from_rfc3339(&timestamp) {
+                        Ok(recorded) => {
+                            let start = *start.get_or_insert(recorded);
+                            (recorded - start).to_std().unwrap_or(Duration::ZERO)
+                        }
+                        Err(_) => last_offset,
+                    };
+                    // File order is authoritative: a timestamp that went
+                    // backwards plays back-to-back instead of reordering.
+                    last_offset = offset.max(last_offset);
+                    script.events.push(ReplayEvent {
+                        offset: last_offset,
+                        msg,
+                    });
+                }
+                RolloutItem::ResponseItem(_)
+                | RolloutItem::Compacted(_)
+                | RolloutItem::RepoState(_)
+                | RolloutItem::SessionEnd(_) => {}
+            }
+        }
+        script
+    }
+
+    /// Index of the event that opens each turn, in order.
+    pub(crate) fn turn_starts(&self) -> Vec<usize> {
+        self.events
+            .iter()
+            .enumerate()
+            .filter(|(_, event)| matches!(event.msg, EventMsg::UserMessage(_)))
+            .map(|(index, _)| index)
+            .collect()
+    }
+}
+
+/// A playback control issued from the replay key bindings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ReplayCommand {
+    TogglePause,
+    Step,
+    /// Jump to the start of a 1-based turn.
+    JumpToTurn(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ReplayCommandError {
+    NoSuchTurn {
+        turn: usize,
+        turns: usize,
+    },
+    /// History already written to the scrollback cannot be taken back.
+    AlreadyPlayed {
+        turn: usize,
+    },
+}
+
+impl fmt::Display for ReplayCommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayCommandError::NoSuchTurn { turn, turns } => {
+                write!(f, "no turn {turn}; this session has {turns}")
+            }
+            ReplayCommandError::AlreadyPlayed { turn } => {
+                write!(f, "turn {turn} has already been replayed")
+            }
+        }
+    }
+}
+
+/// Playback state: which event is next, whether playback is paused, and how
+/// long to wait before the next event is due.
+#[derive(Debug)]
+pub(crate) struct ReplayController {
+    offsets: Vec<Duration>,
+    turn_starts: Vec<usize>,
+    speed: ReplaySpeed,
+    cursor: usize,
+    paused: bool,
+}
+
+impl ReplayController {
+    pub(crate) fn new(script: &ReplayScript, speed: ReplaySpeed) -> Self {
+        Self {
+            offsets: script.events.iter().map(|event| event.offset).collect(),
+            turn_starts: script.turn_starts(),
+            speed,
+            cursor: 0,
+            paused: false,
+        }
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        self.cursor >= self.offsets.len()
+    }
+
+    /// Number of events emitted so far.
+    pub(crate) fn emitted(&self) -> usize {
+        self.cursor
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    pub(crate) fn turns(&self) -> usize {
+        self.turn_starts.len()
+    }
+
+    /// The 1-based turn the last emitted event belongs to, or 0 before the
+    /// first turn has started.
+    pub(crate) fn current_turn(&self) -> usize {
+        self.turn_starts
+            .partition_point(|&start| start < self.cursor)
+    }
+
+    /// How long to wait before the next event, or `None` while paused or
+    /// once every event has been emitted.
+    pub(crate) fn next_delay(&self) -> Option<Duration> {
+        if self.paused || self.is_finished() {
+            return None;
+        }
+        let ReplaySpeed::Factor(factor) = self.speed else {
+            return Some(Duration::ZERO);
+        };
+        let gap = match self.cursor.checked_sub(1) {
+            Some(previous) => self.offsets[self.cursor].saturating_sub(self.offsets[previous]),
+            None => Duration::ZERO,
+        };
+        Some(gap.min(MAX_EVENT_GAP).div_f64(factor))
+    }
+
+    /// Take the next event once its delay has elapsed.
+    pub(crate) fn advance(&mut self) -> Option<usize> {
+        if self.paused || self.is_finished() {
+            return None;
+        }
+        self.cursor += 1;
+        Some(self.cursor - 1)
+    }
+
+    /// Apply a control and return the events to emit immediately.
+    pub(crate) fn apply(
+        &mut self,
+        command: ReplayCommand,
+    ) -> std::result::Result<Range<usize>, ReplayCommandError> {
+        match command {
+            ReplayCommand::TogglePause => {
+                if !self.is_finished() {
+                    self.paused = !self.paused;
+                }
+                Ok(self.cursor..self.cursor)
+            }
+            ReplayCommand::Step => {
+                self.paused = true;
+                let start = self.cursor;
+                if !self.is_finished() {
+                    self.cursor += 1;
+                }
+                Ok(start..self.cursor)
+            }
+            ReplayCommand::JumpToTurn(turn) => {
+                let Some(&target) = turn
+                    .checked_sub(1)
+                    .and_then(|index| self.turn_starts.get(index))
+                else {
+                    return Err(ReplayCommandError::NoSuchTurn {
+                        turn,
+                        turns: self.turns(),
+                    });
+                };
+                if target < self.cursor {
+                    return Err(ReplayCommandError::AlreadyPlayed { turn });
+                }
+                let skipped = self.cursor..target;
+                self.cursor = target;
+                self.paused = true;
+                Ok(skipped)
+            }
+        }
+    }
+}
+
+/// Drives a replay-only [`ChatWidget`] and collects the history it emits,
+/// laid out the way `App` inserts it into the scrollback.
+struct ReplayRenderer {
+    chat: ChatWidget,
+    app_event_rx: UnboundedReceiver<AppEvent>,
+    op_rx: UnboundedReceiver<Op>,
+    turn_separators: TurnSeparatorState,
+    has_emitted_history_lines: bool,
+}
+
+impl ReplayRenderer {
+    fn new(
+        config: Config,
+        auth_manager: Arc<AuthManager>,
+        frame_requester: FrameRequester,
+    ) -> Self {
+        let (app_event_tx, app_event_rx) = unbounded_channel();
+        let turn_separators = TurnSeparatorState::new(config.tui_turn_separators);
+        let (chat, op_rx) = ChatWidget::new_replay(ChatWidgetInit {
+            config,
+            frame_requester,
+            app_event_tx: AppEventSender::new(app_event_tx),
+            initial_prompt: None,
+            initial_images: Vec::new(),
+            enhanced_keys_supported: false,
+            auth_manager,
+            feedback: codex_feedback::CodexFeedback::new(),
+        });
+        Self {
+            chat,
+            app_event_rx,
+            op_rx,
+            turn_separators,
+            has_emitted_history_lines: false,
+        }
+    }
+
+    fn header(&mut self, script: &ReplayScript, width: u16) -> Vec<Line<'static>> {
+        self.chat
+            .add_plain_history_lines(replay_header_lines(script));
+        self.drain(width)
+    }
+
+    fn play(&mut self, msg: EventMsg, width: u16) -> Vec<Line<'static>> {
+        // Streamed markdown wraps to the last rendered width, so lay the
+        // widget out once at the target width first, as a live frame would.
+        let area = Rect::new(0, 0, width, self.chat.desired_height(width));
+        self.chat.render(area, &mut Buffer::empty(area));
+        self.chat.replay_event(msg);
+        self.drain(width)
+    }
+
+    /// Collect emitted history, driving commit ticks until any open stream
+    /// has flushed.
+    fn drain(&mut self, width: u16) -> Vec<Line<'static>> {
+        let mut lines = Vec::new();
+        loop {
+            self.chat.on_commit_tick();
+            let mut inserted_any = false;
+            while let Ok(event) = self.app_event_rx.try_recv() {
+                match event {
+                    AppEvent::InsertHistoryCell(cell) => {
+                        lines.extend(self.display_lines(cell.as_ref(), width));
+                        inserted_any = true;
+                    }
+                    AppEvent::TurnStarted(marker) => self.turn_separators.start_turn(marker),
+                    _ => {}
+                }
+            }
+            if !inserted_any {
+                break;
+            }
+        }
+        while let Ok(op) = self.op_rx.try_recv() {
+            tracing::debug!("replay: dropping op {op:?}");
+        }
+        lines
+    }
+
+    /// Mirrors `App`'s handling of `AppEvent::InsertHistoryCell`.
+    fn display_lines(&mut self, cell: &dyn HistoryCell, width: u16) -> Vec<Line<'static>> {
+        let mut display = cell.display_lines(width);
+        if display.is_empty() {
+            return display;
+        }
+        let mut has_separator = false;
+        if !cell.is_stream_continuation() {
+            if self.has_emitted_history_lines {
+                display.insert(0, Line::from(""));
+                has_separator = true;
+            } else {
+                self.has_emitted_history_lines = true;
+            }
+        }
+        self.turn_separators
+            .decorate(&mut display, has_separator, width);
+        display
+    }
+}
+
+fn replay_header_lines(script: &ReplayScript) -> Vec<Line<'static>> {
+    let session = script
+        .meta
+        .as_ref()
+        .map_or_else(|| "(unknown)".to_string(), |meta| meta.id.to_string());
+    let mut lines = vec![Line::from(format!("Replay of session {session}")).bold()];
+
+    let mut details = Vec::new();
+    if let Some(model) = &script.model {
+        details.push(format!("model: {model}"));
+    }
+    if let Some(meta) = &script.meta {
+        details.push(format!("directory: {}", meta.cwd.display()));
+    }
+    if !details.is_empty() {
+        lines.push(Line::from(format!("  {}", details.join(" · "))).dim());
+    }
+
+    let turns = script.turn_starts().len();
+    let events = script.events.len();
+    let mut summary = format!(
+        "  {turns} turn{} · {events} event{}",
+        if turns == 1 { "" } else { "s" },
+        if events == 1 { "" } else { "s" },
+    );
+    if let Some(meta) = &script.meta {
+        summary.push_str(&format!(" · recorded {}", meta.timestamp));
+    }
+    lines.push(Line::from(summary).dim());
+    lines
+}
+
+/// Play every event instantly into an off-screen VT100 terminal and return
+/// the final screen, without leading or trailing blank rows.
+pub(crate) fn render_snapshot(
+    config: Config,
+    auth_manager: Arc<AuthManager>,
+    script: ReplayScript,
+    width: u16,
+) -> std::io::Result<String> {
+    let width = width.max(1);
+    let mut renderer = ReplayRenderer::new(config, auth_manager, FrameRequester::detached());
+    let mut lines = renderer.header(&script, width);
+    for event in script.events {
+        lines.extend(renderer.play(event.msg, width));
+    }
+
+    // Size the screen so nothing scrolls off the top. Word wrapping can need
+    // more rows than a hard wrap, so leave generous room; blank rows are
+    // trimmed below.
+    let rows: usize = lines
+        .iter()
+        .map(|line| line.width().div_ceil(usize::from(width)).max(1))
+        .sum();
+    let height = u16::try_from(rows.saturating_mul(2).saturating_add(1)).unwrap_or(u16::MAX);
+    let mut terminal = Terminal::with_options(HeadlessBackend::new(width, height))?;
+    terminal.set_viewport_area(Rect::new(0, height - 1, width, 1));
+    insert_history_lines(&mut terminal, lines)?;
+
+    let contents = terminal.backend().vt100().screen().contents();
+    let rows: Vec<&str> = contents.lines().map(str::trim_end).collect();
+    let first = rows.iter().position(|row| !row.is_empty()).unwrap_or(0);
+    let last = rows
+        .iter()
+        .rposition(|row| !row.is_empty())
+        .map_or(first, |index| index + 1);
+    Ok(rows[first..last].join("\n"))
+}
+
+pub async fn run_main(cli: ReplayCli) -> std::io::Result<()> {
+    let ReplayCli {
+        session,
+        speed,
+        diff_against_live,
+        width,
+        config_overrides,
+    } = cli;
+    let cli_kv_overrides = config_overrides
+        .parse_overrides()
+        .map_err(std::io::Error::other)?;
+    let config =
+        Config::load_with_cli_overrides(cli_kv_overrides, ConfigOverrides::default()).await?;
+
+    let path = resolve_rollout_path(&config.codex_home, &session).await?;
+    let script = ReplayScript::parse(&tokio::fs::read_to_string(&path).await?);
+    if script.events.is_empty() {
+        return Err(std::io::Error::other(format!(
+            "No events to replay in {}",
+            path.display()
+        )));
+    }
+
+    let auth_manager = AuthManager::shared_from_config(&config, false);
+
+    if diff_against_live {
+        let snapshot = render_snapshot(config, auth_manager, script, width)?;
+        writeln!(std::io::stdout(), "{snapshot}")?;
+        return Ok(());
+    }
+
+    run_replay_app(config, auth_manager, script, speed)
+        .await
+        .map_err(|err| std::io::Error::other(err.to_string()))
+}
+
+/// Accept either a path to a rollout file or a recorded session id.
+async fn resolve_rollout_path(codex_home: &Path, session: &str) -> std::io::Result<PathBuf> {
+    let candidate = Path::new(session);
+    if candidate.is_file() {
+        return Ok(candidate.to_path_buf());
+    }
+    find_conversation_path_by_id_str(codex_home, session)
+        .await?
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("No saved session found with ID {session}."),
+            )
+        })
+}
+
+async fn run_replay_app(
+    config: Config,
+    auth_manager: Arc<AuthManager>,
+    script: ReplayScript,
+    speed: ReplaySpeed,
+) -> Result<()> {
+    color_eyre::install()?;
+    let mut terminal = tui::init()?;
+    terminal.clear()?;
+    let mut tui = Tui::new(terminal);
+
+    let app = ReplayApp::new(&tui, config, auth_manager, script, speed);
+    let result = app.run(&mut tui).await;
+
+    crate::restore();
+    let _ = tui.terminal.clear();
+    result
+}
+
+/// The interactive replay loop: plays events on a timer and renders the
+/// replay controls where the composer would be.
+struct ReplayApp {
+    renderer: ReplayRenderer,
+    header: Vec<Line<'static>>,
+    events: Vec<Option<EventMsg>>,
+    controller: ReplayController,
+    speed: ReplaySpeed,
+    /// Digits typed so far for a jump to turn N.
+    pending_turn: String,
+    notice: Option<String>,
+}
+
+impl ReplayApp {
+    fn new(
+        tui: &Tui,
+        config: Config,
+        auth_manager: Arc<AuthManager>,
+        script: ReplayScript,
+        speed: ReplaySpeed,
+    ) -> Self {
+        let mut renderer = ReplayRenderer::new(config, auth_manager, tui.frame_requester());
+        let header = renderer.header(&script, tui.terminal.last_known_screen_size.width);
+        let controller = ReplayController::new(&script, speed);
+        Self {
+            renderer,
+            header,
+            events: script
+                .events
+                .into_iter()
+                .map(|event| Some(event.msg))
+                .collect(),
+            controller,
+            speed,
+            pending_turn: String::new(),
+            notice: None,
+        }
+    }
+
+    async fn run(mut self, tui: &mut Tui) -> Result<()> {
+        use tokio_stream::StreamExt;
+
+        let tui_events = tui.event_stream();
+        tokio::pin!(tui_events);
+
+        tui.insert_history_lines(std::mem::take(&mut self.header));
+        let mut deadline = self.next_deadline();
+
+        loop {
+            let sleep = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now));
+            select! {
+                _ = sleep, if deadline.is_some() => {
+                    if let Some(index) = self.controller.advance() {
+                        self.emit(tui, index);
+                    }
+                    deadline = self.next_deadline();
+                }
+                Some(event) = tui_events.next() => match event {
+                    TuiEvent::Key(key_event) => {
+                        if !self.handle_key_event(tui, key_event) {
+                            break;
+                        }
+                        deadline = self.next_deadline();
+                    }
+                    TuiEvent::Paste(_) | TuiEvent::Mouse(_) => {}
+                    TuiEvent::Draw => {
+                        let controls = self.controls_lines();
+                        tui.draw(CONTROLS_HEIGHT, |frame| {
+                            frame.render_widget_ref(Paragraph::new(controls), frame.area());
+                        })?;
+                    }
+                },
+                else => break,
+            }
+        }
+        Ok(())
+    }
+
+    fn next_deadline(&self) -> Option<Instant> {
+        self.controller
+            .next_delay()
+            .map(|delay| Instant::now() + delay)
+    }
+
+    fn emit(&mut self, tui: &mut Tui, index: usize) {
+        let Some(msg) = self.events.get_mut(index).and_then(Option::take) else {
+            return;
+        };
+        let width = tui.terminal.last_known_screen_size.width;
+        let lines = self.renderer.play(msg, width);
+        if !lines.is_empty() {
+            tui.insert_history_lines(lines);
+        }
+        tui.frame_requester().schedule_frame();
+    }
+
+    /// Returns `false` when the user asked to quit.
+    fn handle_key_event(&mut self, tui: &mut Tui, key_event: KeyEvent) -> bool {
+        if key_event.kind == KeyEventKind::Release {
+            return true;
+        }
+        let command = match key_event.code {
+            KeyCode::Char('c') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                return false;
+            }
+            KeyCode::Char('q') => return false,
+            KeyCode::Esc if self.pending_turn.is_empty() => return false,
+            KeyCode::Esc => {
+                self.pending_turn.clear();
+                None
+            }
+            KeyCode::Char(' ') => Some(ReplayCommand::TogglePause),
+            KeyCode::Char('s') | KeyCode::Right => Some(ReplayCommand::Step),
+            KeyCode::Char(digit) if digit.is_ascii_digit() => {
+                self.pending_turn.push(digit);
+                None
+            }
+            KeyCode::Backspace => {
+                self.pending_turn.pop();
+                None
+            }
+            KeyCode::Enter => std::mem::take(&mut self.pending_turn)
+                .parse()
+                .ok()
+                .map(ReplayCommand::JumpToTurn),
+            _ => None,
+        };
+
+        if let Some(command) = command {
+            match self.controller.apply(command) {
+                Ok(range) => {
+                    self.notice = None;
+                    for index in range {
+                        self.emit(tui, index);
+                    }
+                }
+                Err(err) => self.notice = Some(err.to_string()),
+            }
+        }
+        tui.frame_requester().schedule_frame();
+        true
+    }
+
+    fn controls_lines(&self) -> Vec<Line<'static>> {
+        let controller = &self.controller;
+        let progress = format!(
+            "event {}/{} · turn {}/{}",
+            controller.emitted(),
+            controller.len(),
+            controller.current_turn(),
+            controller.turns()
+        );
+        let mut status: Vec<Span<'static>> = if controller.is_finished() {
+            vec!["■ Replay finished".bold(), format!(" · {progress}").dim()]
+        } else if controller.is_paused() {
+            vec!["⏸ Paused".bold(), format!(" · {progress}").dim()]
+        } else {
+            vec![
+                format!("▶ Replaying at {}", self.speed).bold(),
+                format!(" · {progress}").dim(),
+            ]
+        };
+        if !self.pending_turn.is_empty() {
+            status.push(format!(" · jump to turn {}", self.pending_turn).cyan());
+        }
+        if let Some(notice) = &self.notice {
+            status.push(format!(" · {notice}").red());
+        }
+
+        let hints = vec![
+            "space".cyan(),
+            " pause/resume  ".dim(),
+            "s".cyan(),
+            " step  ".dim(),
+            "N enter".cyan(),
+            " jump to turn N  ".dim(),
+            "q".cyan(),
+            " quit".dim(),
+        ];
+        vec![Line::from(status), Line::from(hints)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::CodexAuth;
+    use codex_core::config::ConfigToml;
+    use insta::assert_snapshot;
+    use pretty_assertions::assert_eq;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/replay-rollout.jsonl");
+
+    fn test_config() -> Config {
+        Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides::default(),
+            std::env::temp_dir(),
+        )
+        .expect("config")
+    }
+
+    fn offsets(script: &ReplayScript) -> Vec<Duration> {
+        script.events.iter().map(|event| event.offset).collect()
+    }
+
+    #[test]
+    fn parses_events_turns_and_offsets_from_rollout() {
+        let script = ReplayScript::parse(FIXTURE);
+
+        assert_eq!(
+            script.meta.as_ref().map(|meta| meta.id.to_string()),
+            Some("67e55044-10b1-426f-9247-bb680e5fe0c8".to_string())
+        );
+        assert_eq!(script.model.as_deref(), Some("gpt-5-codex"));
+        assert_eq!(
+            offsets(&script),
+            vec![
+                Duration::ZERO,
+                Duration::from_millis(8_500),
+                Duration::from_secs(59),
+                Duration::from_millis(63_250),
+            ]
+        );
+        assert_eq!(script.turn_starts(), vec![0, 2]);
+    }
+
+    #[test]
+    fn timestamps_that_go_backwards_keep_file_order() {
+        let text = [
+            r#"{"timestamp":"2025-10-01T12:00:05.000Z","type":"event_msg","payload":{"type":"user_message","message":"first"}}"#,
+            r#"{"timestamp":"2025-10-01T12:00:03.000Z","type":"event_msg","payload":{"type":"agent_message","message":"second"}}"#,
+            "not json",
+            r#"{"timestamp":"2025-10-01T12:00:07.000Z","type":"event_msg","payload":{"type":"agent_message","message":"third"}}"#,
+        ]
+        .join("\n");
+        let script = ReplayScript::parse(&text);
+
+        let messages: Vec<String> = script
+            .events
+            .iter()
+            .filter_map(|event| match &event.msg {
+                EventMsg::UserMessage(ev) => Some(ev.message.clone()),
+                EventMsg::AgentMessage(ev) => Some(ev.message.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(messages, vec!["first", "second", "third"]);
+        assert_eq!(
+            offsets(&script),
+            vec![Duration::ZERO, Duration::ZERO, Duration::from_secs(2)]
+        );
+    }
+
+    #[test]
+    fn parses_speed() {
+        assert_eq!("4x".parse(), Ok(ReplaySpeed::Factor(4.0)));
+        assert_eq!("0.5X".parse(), Ok(ReplaySpeed::Factor(0.5)));
+        assert_eq!("2".parse(), Ok(ReplaySpeed::Factor(2.0)));
+        assert_eq!("instant".parse(), Ok(ReplaySpeed::Instant));
+        assert!("0x".parse::<ReplaySpeed>().is_err());
+        assert!("fast".parse::<ReplaySpeed>().is_err());
+    }
+
+    #[test]
+    fn delays_are_capped_and_scaled() {
+        let script = ReplayScript::parse(FIXTURE);
+        let mut controller = ReplayController::new(&script, ReplaySpeed::Factor(2.0));
+
+        let mut delays = Vec::new();
+        while let Some(delay) = controller.next_delay() {
+            delays.push(delay);
+            controller.advance();
+        }
+        assert_eq!(
+            delays,
+            vec![
+                Duration::ZERO,
+                Duration::from_millis(2_500),
+                Duration::from_millis(2_500),
+                Duration::from_millis(2_125),
+            ]
+        );
+        assert!(controller.is_finished());
+
+        let instant = ReplayController::new(&script, ReplaySpeed::Instant);
+        assert_eq!(instant.next_delay(), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn step_pause_and_jump_controls() {
+        let script = ReplayScript::parse(FIXTURE);
+        let mut controller = ReplayController::new(&script, ReplaySpeed::Factor(1.0));
+
+        // Jumping fast-forwards to the start of the turn and pauses there.
+        assert_eq!(controller.apply(ReplayCommand::JumpToTurn(2)), Ok(0..2));
+        assert!(controller.is_paused());
+        assert_eq!(controller.current_turn(), 1);
+        assert_eq!(controller.next_delay(), None);
+        assert_eq!(controller.advance(), None);
+
+        assert_eq!(
+            controller.apply(ReplayCommand::JumpToTurn(1)),
+            Err(ReplayCommandError::AlreadyPlayed { turn: 1 })
+        );
+        assert_eq!(
+            controller.apply(ReplayCommand::JumpToTurn(3)),
+            Err(ReplayCommandError::NoSuchTurn { turn: 3, turns: 2 })
+        );
+        assert_eq!(
+            controller.apply(ReplayCommand::JumpToTurn(0)),
+            Err(ReplayCommandError::NoSuchTurn { turn: 0, turns: 2 })
+        );
+
+        // Stepping emits exactly one event and stays paused.
+        assert_eq!(controller.apply(ReplayCommand::Step), Ok(2..3));
+        assert!(controller.is_paused());
+        assert_eq!(controller.current_turn(), 2);
+
+        // Resuming schedules the next event from its recorded gap.
+        assert_eq!(controller.apply(ReplayCommand::TogglePause), Ok(3..3));
+        assert_eq!(controller.next_delay(), Some(Duration::from_millis(4_250)));
+        assert_eq!(controller.advance(), Some(3));
+        assert!(controller.is_finished());
+        assert_eq!(controller.next_delay(), None);
+
+        // Nothing is left to step through or resume once finished.
+        assert_eq!(controller.apply(ReplayCommand::Step), Ok(4..4));
+        assert_eq!(controller.apply(ReplayCommand::TogglePause), Ok(4..4));
+        assert_eq!(controller.emitted(), controller.len());
+    }
+
+    #[test]
+    fn replay_fixture_matches_golden() {
+        let auth_manager = AuthManager::from_auth_for_testing(CodexAuth::from_api_key("test"));
+        let snapshot = render_snapshot(
+            test_config(),
+            auth_manager,
+            ReplayScript::parse(FIXTURE),
+            80,
+        )
+        .expect("render replay");
+        assert_snapshot!(snapshot);
+    }
+}
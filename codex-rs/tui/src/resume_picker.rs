@@ -52,6 +52,7 @@ struct PageLoadRequest {
     request_token: usize,
     search_token: Option<usize>,
     default_provider: String,
+    archived: bool,
 }
 
 type PageLoader = Arc<dyn Fn(PageLoadRequest) + Send + Sync>;
@@ -87,16 +88,25 @@ pub async fn run_resume_picker(
     let page_loader: PageLoader = Arc::new(move |request: PageLoadRequest| {
         let tx = loader_tx.clone();
         tokio::spawn(async move {
-            let provider_filter = vec![request.default_provider.clone()];
-            let page = RolloutRecorder::list_conversations(
-                &request.codex_home,
-                PAGE_SIZE,
-                request.cursor.as_ref(),
-                INTERACTIVE_SESSION_SOURCES,
-                Some(provider_filter.as_slice()),
-                request.default_provider.as_str(),
-            )
-            .await;
+            let page = if request.archived {
+                RolloutRecorder::list_archived(
+                    &request.codex_home,
+                    PAGE_SIZE,
+                    request.cursor.as_ref(),
+                )
+                .await
+            } else {
+                let provider_filter = vec![request.default_provider.clone()];
+                RolloutRecorder::list_conversations(
+                    &request.codex_home,
+                    PAGE_SIZE,
+                    request.cursor.as_ref(),
+                    INTERACTIVE_SESSION_SOURCES,
+                    Some(provider_filter.as_slice()),
+                    request.default_provider.as_str(),
+                )
+                .await
+            };
             let _ = tx.send(BackgroundEvent::PageLoaded {
                 request_token: request.request_token,
                 search_token: request.search_token,
@@ -189,6 +199,12 @@ struct PickerState {
     default_provider: String,
     show_all: bool,
     filter_cwd: Option<PathBuf>,
+    /// Listing archived sessions instead of recent ones.
+    archived: bool,
+    /// Outcome of the last archive or delete, shown in the header.
+    notice: Option<String>,
+    /// Archived session that the next delete press removes for good.
+    pending_delete: Option<PathBuf>,
 }
 
 struct PaginationState {
@@ -282,6 +298,9 @@ impl PickerState {
             default_provider,
             show_all,
             filter_cwd,
+            archived: false,
+            notice: None,
+            pending_delete: None,
         }
     }
 
@@ -290,6 +309,17 @@ impl PickerState {
     }
 
     async fn handle_key(&mut self, key: KeyEvent) -> Result<Option<ResumeSelection>> {
+        // `del` is fn+delete on macOS laptops, so ctrl+d works too.
+        let is_delete =
+            key.code == KeyCode::Delete || key_hint::ctrl(KeyCode::Char('d')).is_press(key);
+        if is_delete {
+            self.remove_selected().await;
+            return Ok(None);
+        }
+        if self.pending_delete.take().is_some() {
+            self.notice = None;
+            self.request_frame();
+        }
         match key.code {
             KeyCode::Esc => return Ok(Some(ResumeSelection::StartFresh)),
             KeyCode::Char('c')
@@ -301,7 +331,19 @@ impl PickerState {
             }
             KeyCode::Enter => {
                 if let Some(row) = self.filtered_rows.get(self.selected) {
-                    return Ok(Some(ResumeSelection::Resume(row.path.clone())));
+                    let path = row.path.clone();
+                    if !self.archived {
+                        return Ok(Some(ResumeSelection::Resume(path)));
+                    }
+                    // Resuming appends to the rollout; bring it back to the
+                    // recent list instead of writing into the archive.
+                    match RolloutRecorder::unarchive_conversation(&self.codex_home, &path).await {
+                        Ok(restored) => return Ok(Some(ResumeSelection::Resume(restored))),
+                        Err(err) => {
+                            self.notice = Some(err.to_string());
+                            self.request_frame();
+                        }
+                    }
                 }
             }
             KeyCode::Up => {
@@ -337,6 +379,12 @@ impl PickerState {
                     self.request_frame();
                 }
             }
+            KeyCode::Tab => {
+                self.archived = !self.archived;
+                self.notice = None;
+                self.load_initial_page().await?;
+                self.request_frame();
+            }
             KeyCode::Backspace => {
                 let mut new_query = self.query.clone();
                 new_query.pop();
@@ -359,17 +407,61 @@ impl PickerState {
         Ok(None)
     }
 
+    /// Archives the selected session, or deletes it for good on the archived
+    /// tab, so nothing is unlinked straight from the recent list. Deleting
+    /// takes a second press on the same session.
+    async fn remove_selected(&mut self) {
+        let Some(path) = self
+            .filtered_rows
+            .get(self.selected)
+            .map(|row| row.path.clone())
+        else {
+            return;
+        };
+        if self.archived && self.pending_delete.as_ref() != Some(&path) {
+            self.pending_delete = Some(path);
+            self.notice = Some("Press delete again to delete this session for good".to_string());
+            self.request_frame();
+            return;
+        }
+        self.pending_delete = None;
+        let result = if self.archived {
+            RolloutRecorder::delete_conversation(&self.codex_home, &path)
+                .await
+                .map(|()| "Deleted session")
+        } else {
+            RolloutRecorder::archive_conversation(&self.codex_home, &path)
+                .await
+                .map(|_| "Archived session; press tab to see archived sessions")
+        };
+        match result {
+            Ok(notice) => {
+                self.all_rows.retain(|row| row.path != path);
+                self.notice = Some(notice.to_string());
+                self.apply_filter();
+            }
+            Err(err) => {
+                self.notice = Some(err.to_string());
+                self.request_frame();
+            }
+        }
+    }
+
     async fn load_initial_page(&mut self) -> Result<()> {
-        let provider_filter = vec![self.default_provider.clone()];
-        let page = RolloutRecorder::list_conversations(
-            &self.codex_home,
-            PAGE_SIZE,
-            None,
-            INTERACTIVE_SESSION_SOURCES,
-            Some(provider_filter.as_slice()),
-            self.default_provider.as_str(),
-        )
-        .await?;
+        let page = if self.archived {
+            RolloutRecorder::list_archived(&self.codex_home, PAGE_SIZE, None).await?
+        } else {
+            let provider_filter = vec![self.default_provider.clone()];
+            RolloutRecorder::list_conversations(
+                &self.codex_home,
+                PAGE_SIZE,
+                None,
+                INTERACTIVE_SESSION_SOURCES,
+                Some(provider_filter.as_slice()),
+                self.default_provider.as_str(),
+            )
+            .await?
+        };
         self.reset_pagination();
         self.all_rows.clear();
         self.filtered_rows.clear();
@@ -607,6 +699,7 @@ impl PickerState {
             request_token,
             search_token,
             default_provider: self.default_provider.clone(),
+            archived: self.archived,
         });
     }
 
@@ -711,10 +804,25 @@ fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
         .areas(area);
 
         // Header
-        frame.render_widget_ref(
-            Line::from(vec!["Resume a previous session".bold().cyan()]),
-            header,
-        );
+        let tab = |label: &'static str, active: bool| {
+            if active {
+                label.bold().underlined()
+            } else {
+                label.dim()
+            }
+        };
+        let mut header_spans = vec![
+            "Resume a previous session".bold().cyan(),
+            "  ".into(),
+            tab("Recent", !state.archived),
+            " · ".dim(),
+            tab("Archived", state.archived),
+        ];
+        if let Some(notice) = &state.notice {
+            header_spans.push("  ".into());
+            header_spans.push(Span::from(notice.clone()).dim());
+        }
+        frame.render_widget_ref(Line::from(header_spans), header);
 
         // Search line
         let q = if state.query.is_empty() {
@@ -744,7 +852,19 @@ fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
             key_hint::plain(KeyCode::Up).into(),
             "/".dim(),
             key_hint::plain(KeyCode::Down).into(),
-            " to browse".dim(),
+            " to browse ".dim(),
+            "    ".dim(),
+            key_hint::plain(KeyCode::Tab).into(),
+            " to switch tabs ".dim(),
+            "    ".dim(),
+            key_hint::plain(KeyCode::Delete).into(),
+            "/".dim(),
+            key_hint::ctrl(KeyCode::Char('d')).into(),
+            if state.archived {
+                " to delete".dim()
+            } else {
+                " to archive".dim()
+            },
         ]
         .into();
         frame.render_widget_ref(hint_line, hint);
@@ -884,6 +1004,10 @@ fn render_empty_state_line(state: &PickerState) -> Line<'static> {
         return vec!["No results for your search".italic().dim()].into();
     }
 
+    if state.archived && !state.pagination.loading.is_pending() {
+        return vec!["No archived sessions".italic().dim()].into();
+    }
+
     if state.all_rows.is_empty() && state.pagination.num_scanned_files == 0 {
         return vec!["No sessions yet".italic().dim()].into();
     }
@@ -1577,4 +1701,118 @@ mod tests {
         assert!(!state.search_state.is_active());
         assert!(state.pagination.reached_scan_cap);
     }
+
+    fn write_rollout(codex_home: &Path, uuid: &str) -> PathBuf {
+        let dir = codex_home.join("sessions/2025/01/01");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("rollout-2025-01-01T09-00-00-{uuid}.jsonl"));
+        let meta = json!({
+            "timestamp": "2025-01-01T09:00:00Z",
+            "type": "session_meta",
+            "payload": {
+                "id": uuid,
+                "timestamp": "2025-01-01T09:00:00Z",
+                "instructions": null,
+                "cwd": ".",
+                "originator": "test",
+                "cli_version": "0.0.0",
+                "source": "cli",
+                "model_provider": "openai",
+            },
+        });
+        let user = json!({
+            "timestamp": "2025-01-01T09:00:00Z",
+            "type": "event_msg",
+            "payload": { "type": "user_message", "message": "hello", "kind": "plain" },
+        });
+        std::fs::write(&path, format!("{meta}\n{user}\n")).unwrap();
+        path
+    }
+
+    #[test]
+    fn delete_archives_first_and_archived_tab_deletes_for_good() {
+        let codex_home = tempfile::TempDir::new().unwrap();
+        let active = write_rollout(codex_home.path(), "00000000-0000-0000-0000-000000000001");
+        let archived = codex_home
+            .path()
+            .join(codex_core::ARCHIVED_SESSIONS_SUBDIR)
+            .join(active.file_name().unwrap());
+        let loader: PageLoader = Arc::new(|_| {});
+        let mut state = PickerState::new(
+            codex_home.path().to_path_buf(),
+            FrameRequester::test_dummy(),
+            loader,
+            String::from("openai"),
+            true,
+            None,
+        );
+        let press = |state: &mut PickerState, code: KeyCode| {
+            block_on_future(state.handle_key(KeyEvent::new(code, KeyModifiers::NONE))).unwrap();
+        };
+
+        block_on_future(state.load_initial_page()).unwrap();
+        assert_eq!(state.filtered_rows.len(), 1);
+
+        press(&mut state, KeyCode::Delete);
+        assert!(state.filtered_rows.is_empty());
+        assert!(!active.exists());
+        assert!(archived.exists());
+
+        press(&mut state, KeyCode::Tab);
+        let paths: Vec<PathBuf> = state
+            .filtered_rows
+            .iter()
+            .map(|row| row.path.clone())
+            .collect();
+        assert_eq!(paths, vec![archived.clone()]);
+
+        // Deleting for good needs a second press; any other key cancels.
+        press(&mut state, KeyCode::Delete);
+        press(&mut state, KeyCode::Down);
+        press(&mut state, KeyCode::Delete);
+        assert!(archived.exists());
+        assert_eq!(
+            state.notice.as_deref(),
+            Some("Press delete again to delete this session for good")
+        );
+
+        block_on_future(state.handle_key(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL)))
+            .unwrap();
+        assert!(state.filtered_rows.is_empty());
+        assert!(!archived.exists());
+        assert_eq!(state.notice.as_deref(), Some("Deleted session"));
+    }
+
+    #[test]
+    fn resuming_an_archived_session_restores_it_first() {
+        let codex_home = tempfile::TempDir::new().unwrap();
+        let active = write_rollout(codex_home.path(), "00000000-0000-0000-0000-000000000002");
+        let archived = block_on_future(RolloutRecorder::archive_conversation(
+            codex_home.path(),
+            &active,
+        ))
+        .unwrap();
+        let loader: PageLoader = Arc::new(|_| {});
+        let mut state = PickerState::new(
+            codex_home.path().to_path_buf(),
+            FrameRequester::test_dummy(),
+            loader,
+            String::from("openai"),
+            true,
+            None,
+        );
+        state.archived = true;
+        block_on_future(state.load_initial_page()).unwrap();
+
+        let selection =
+            block_on_future(state.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)))
+                .unwrap();
+        let Some(ResumeSelection::Resume(path)) = selection else {
+            panic!("expected a resume selection, got {selection:?}");
+        };
+        assert_eq!(path.file_name(), active.file_name());
+        assert!(path.starts_with(codex_home.path().join("sessions")));
+        assert!(path.exists());
+        assert!(!archived.exists());
+    }
 }
@@ -7,6 +7,7 @@ use std::sync::Mutex;
 use std::sync::OnceLock;
 
 use codex_core::config::Config;
+use codex_core::persistence_policy::PersistenceKind;
 use codex_core::protocol::Op;
 use serde::Serialize;
 use serde_json::json;
@@ -78,26 +79,12 @@ fn now_ts() -> String {
 }
 
 pub(crate) fn maybe_init(config: &Config) {
-    let enabled = std::env::var("CODEX_TUI_RECORD_SESSION")
-        .map(|v| matches!(v.as_str(), "1" | "true" | "TRUE" | "yes" | "YES"))
-        .unwrap_or(false);
-    if !enabled {
+    let Some(path) = session_log_path(
+        config,
+        std::env::var("CODEX_TUI_RECORD_SESSION").ok().as_deref(),
+        std::env::var("CODEX_TUI_SESSION_LOG_PATH").ok(),
+    ) else {
         return;
-    }
-
-    let path = if let Ok(path) = std::env::var("CODEX_TUI_SESSION_LOG_PATH") {
-        PathBuf::from(path)
-    } else {
-        let mut p = match codex_core::config::log_dir(config) {
-            Ok(dir) => dir,
-            Err(_) => std::env::temp_dir(),
-        };
-        let filename = format!(
-            "session-{}.jsonl",
-            chrono::Utc::now().format("%Y%m%dT%H%M%SZ")
-        );
-        p.push(filename);
-        p
     };
 
     if let Err(e) = LOGGER.open(path.clone()) {
@@ -118,6 +105,37 @@ pub(crate) fn maybe_init(config: &Config) {
     LOGGER.write_json_line(header);
 }
 
+/// Where to record the session, or `None` when recording is off or the
+/// persistence policy forbids it.
+fn session_log_path(
+    config: &Config,
+    record_session: Option<&str>,
+    path_override: Option<String>,
+) -> Option<PathBuf> {
+    let enabled = matches!(record_session, Some("1" | "true" | "TRUE" | "yes" | "YES"));
+    if !enabled {
+        return None;
+    }
+    if let Err(blocked) = config.persistence.check(PersistenceKind::SessionLog) {
+        tracing::info!("{blocked}; CODEX_TUI_RECORD_SESSION is ignored");
+        return None;
+    }
+
+    if let Some(path) = path_override {
+        return Some(PathBuf::from(path));
+    }
+    let mut p = match codex_core::config::log_dir(config) {
+        Ok(dir) => dir,
+        Err(_) => std::env::temp_dir(),
+    };
+    let filename = format!(
+        "session-{}.jsonl",
+        chrono::Utc::now().format("%Y%m%dT%H%M%SZ")
+    );
+    p.push(filename);
+    Some(p)
+}
+
 pub(crate) fn log_inbound_app_event(event: &AppEvent) {
     // Log only if enabled
     if !LOGGER.is_enabled() {
@@ -154,13 +172,18 @@ pub(crate) fn log_inbound_app_event(event: &AppEvent) {
             });
             LOGGER.write_json_line(value);
         }
-        AppEvent::FileSearchResult { query, matches } => {
+        AppEvent::FileSearchResult {
+            query,
+            matches,
+            truncated_walk,
+        } => {
             let value = json!({
                 "ts": now_ts(),
                 "dir": "to_tui",
                 "kind": "file_search_result",
                 "query": query,
                 "matches": matches.len(),
+                "truncated_walk": truncated_walk,
             });
             LOGGER.write_json_line(value);
         }
@@ -208,3 +231,40 @@ where
     });
     LOGGER.write_json_line(value);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::config::ConfigOverrides;
+    use codex_core::config::ConfigToml;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn config_for(codex_home: &TempDir, ephemeral: bool) -> Config {
+        Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides {
+                ephemeral: Some(ephemeral),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )
+        .expect("config")
+    }
+
+    #[test]
+    fn session_log_path_consults_persistence_policy() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let override_path = || Some("/tmp/session.jsonl".to_string());
+
+        let config = config_for(&codex_home, false);
+        assert_eq!(session_log_path(&config, None, override_path()), None);
+        assert_eq!(
+            session_log_path(&config, Some("1"), override_path()),
+            Some(PathBuf::from("/tmp/session.jsonl"))
+        );
+
+        let config = config_for(&codex_home, true);
+        assert_eq!(session_log_path(&config, Some("1"), override_path()), None);
+    }
+}
@@ -7,6 +7,8 @@ use ratatui::style::Modifier;
 use ratatui::style::Style;
 use ratatui::text::Span;
 
+use crate::animation::AnimationLevel;
+use crate::animation::current_level;
 use crate::color::blend;
 use crate::terminal_palette::default_bg;
 use crate::terminal_palette::default_fg;
@@ -23,6 +25,9 @@ pub(crate) fn shimmer_spans(text: &str) -> Vec<Span<'static>> {
     if chars.is_empty() {
         return Vec::new();
     }
+    if current_level() == AnimationLevel::Off {
+        return chars.iter().map(|ch| Span::raw(ch.to_string())).collect();
+    }
     // Use time-based sweep synchronized to process start.
     let padding = 10usize;
     let period = chars.len() + padding * 2;
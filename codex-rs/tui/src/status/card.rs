@@ -105,11 +105,14 @@ impl StatusHistoryCell {
             .find(|(k, _)| *k == "approval")
             .map(|(_, v)| v.clone())
             .unwrap_or_else(|| "<unknown>".to_string());
-        let sandbox = match &config.sandbox_policy {
+        let mut sandbox = match &config.sandbox_policy {
             SandboxPolicy::DangerFullAccess => "danger-full-access".to_string(),
             SandboxPolicy::ReadOnly => "read-only".to_string(),
             SandboxPolicy::WorkspaceWrite { .. } => "workspace-write".to_string(),
         };
+        if config.offline {
+            sandbox.push_str(" (offline)");
+        }
         let agents_summary = compose_agents_summary(config);
         let account = compose_account_display(auth_manager);
         let session_id = session_id.as_ref().map(std::string::ToString::to_string);
@@ -248,6 +251,20 @@ impl StatusHistoryCell {
                         lines.push(base_line);
                     }
                 }
+                StatusRateLimitValue::Trend {
+                    sparkline,
+                    lowest_percent_left,
+                } => {
+                    let spans = formatter.full_spans(
+                        row.label.as_str(),
+                        vec![
+                            Span::from(sparkline.clone()).cyan(),
+                            Span::from(" "),
+                            Span::from(format!("lowest {lowest_percent_left:.0}% left")).dim(),
+                        ],
+                    );
+                    lines.push(Line::from(spans));
+                }
                 StatusRateLimitValue::Text(text) => {
                     let label = row.label.clone();
                     let spans =
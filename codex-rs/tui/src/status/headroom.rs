@@ -0,0 +1,233 @@
+//! Rate-limit headroom for the footer indicator, and the one-time suggestion
+//! to check `/status` when a window runs low.
+
+use codex_core::config::types::RateLimitWarnings;
+use codex_core::protocol::RateLimitSnapshot;
+use codex_core::protocol::RateLimitWindow;
+
+use crate::chatwidget::get_limits_duration;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum HeadroomLevel {
+    Ok,
+    Warn,
+    Critical,
+}
+
+/// One rate-limit window as the footer shows it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct WindowHeadroom {
+    pub percent_left: i64,
+    pub level: HeadroomLevel,
+    window_minutes: Option<i64>,
+    /// Label when the window length is unknown.
+    fallback_label: &'static str,
+}
+
+impl WindowHeadroom {
+    fn new(
+        window: &RateLimitWindow,
+        fallback_label: &'static str,
+        warnings: RateLimitWarnings,
+    ) -> Self {
+        let left = percent_left(window);
+        let level = if left < warnings.critical {
+            HeadroomLevel::Critical
+        } else if left < warnings.warn {
+            HeadroomLevel::Warn
+        } else {
+            HeadroomLevel::Ok
+        };
+        Self {
+            percent_left: left.round() as i64,
+            level,
+            window_minutes: window.window_minutes,
+            fallback_label,
+        }
+    }
+
+    /// Window name such as `5h` or `weekly`.
+    pub(crate) fn label(&self) -> String {
+        self.window_minutes
+            .map(get_limits_duration)
+            .unwrap_or_else(|| self.fallback_label.to_string())
+    }
+}
+
+/// Headroom left in the primary and secondary windows.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct RateLimitHeadroom {
+    pub primary: Option<WindowHeadroom>,
+    pub secondary: Option<WindowHeadroom>,
+}
+
+impl RateLimitHeadroom {
+    /// `None` when the provider reported no windows, so the indicator hides.
+    pub(crate) fn from_snapshot(
+        snapshot: &RateLimitSnapshot,
+        warnings: RateLimitWarnings,
+    ) -> Option<Self> {
+        let primary = snapshot
+            .primary
+            .as_ref()
+            .map(|window| WindowHeadroom::new(window, "5h", warnings));
+        let secondary = snapshot
+            .secondary
+            .as_ref()
+            .map(|window| WindowHeadroom::new(window, "weekly", warnings));
+        (primary.is_some() || secondary.is_some()).then_some(Self { primary, secondary })
+    }
+
+    pub(crate) fn windows(&self) -> impl Iterator<Item = &WindowHeadroom> {
+        self.primary.iter().chain(self.secondary.iter())
+    }
+
+    /// The window with the least headroom.
+    pub(crate) fn tightest(&self) -> Option<&WindowHeadroom> {
+        self.windows().min_by_key(|window| window.percent_left)
+    }
+}
+
+/// Suggests `/status` once per window whose headroom drops below the
+/// critical threshold.
+#[derive(Debug, Default)]
+pub(crate) struct CriticalHeadroomAlerts {
+    /// `resets_at` of the window already alerted for, per window.
+    primary: Option<Option<i64>>,
+    secondary: Option<Option<i64>>,
+}
+
+impl CriticalHeadroomAlerts {
+    /// Messages for windows that just became critical.
+    pub(crate) fn take_alerts(
+        &mut self,
+        snapshot: &RateLimitSnapshot,
+        warnings: RateLimitWarnings,
+    ) -> Vec<String> {
+        let mut alerts = Vec::new();
+        for (alerted, window, fallback_label) in [
+            (&mut self.primary, snapshot.primary.as_ref(), "5h"),
+            (&mut self.secondary, snapshot.secondary.as_ref(), "weekly"),
+        ] {
+            let Some(window) = window else {
+                continue;
+            };
+            let left = percent_left(window);
+            if left >= warnings.critical {
+                // Headroom only grows back when the window resets.
+                *alerted = None;
+                continue;
+            }
+            if *alerted == Some(window.resets_at) {
+                continue;
+            }
+            *alerted = Some(window.resets_at);
+            let label = window
+                .window_minutes
+                .map(get_limits_duration)
+                .unwrap_or_else(|| fallback_label.to_string());
+            alerts.push(format!("Only {left:.0}% of your {label} limit is left."));
+        }
+        alerts
+    }
+}
+
+fn percent_left(window: &RateLimitWindow) -> f64 {
+    (100.0 - window.used_percent).clamp(0.0, 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn snapshot(primary_used: f64, primary_resets_at: i64) -> RateLimitSnapshot {
+        RateLimitSnapshot {
+            primary: Some(RateLimitWindow {
+                used_percent: primary_used,
+                window_minutes: Some(300),
+                resets_at: Some(primary_resets_at),
+            }),
+            secondary: Some(RateLimitWindow {
+                used_percent: 30.0,
+                window_minutes: Some(10_080),
+                resets_at: Some(2_000_000),
+            }),
+            credits: None,
+        }
+    }
+
+    #[test]
+    fn levels_follow_the_thresholds() {
+        let warnings = RateLimitWarnings {
+            warn: 40.0,
+            critical: 15.0,
+        };
+        let levels: Vec<HeadroomLevel> = [50.0, 70.0, 90.0]
+            .into_iter()
+            .filter_map(|used| RateLimitHeadroom::from_snapshot(&snapshot(used, 1), warnings))
+            .filter_map(|headroom| headroom.primary.map(|window| window.level))
+            .collect();
+
+        assert_eq!(
+            levels,
+            vec![
+                HeadroomLevel::Ok,
+                HeadroomLevel::Warn,
+                HeadroomLevel::Critical
+            ]
+        );
+    }
+
+    #[test]
+    fn hidden_without_windows() {
+        let snapshot = RateLimitSnapshot {
+            primary: None,
+            secondary: None,
+            credits: None,
+        };
+        assert_eq!(
+            RateLimitHeadroom::from_snapshot(&snapshot, RateLimitWarnings::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn critical_alert_fires_once_per_window() {
+        let warnings = RateLimitWarnings::default();
+        let mut alerts = CriticalHeadroomAlerts::default();
+
+        assert_eq!(
+            alerts.take_alerts(&snapshot(80.0, 1), warnings),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            alerts.take_alerts(&snapshot(92.0, 1), warnings),
+            vec!["Only 8% of your 5h limit is left.".to_string()]
+        );
+        assert_eq!(
+            alerts.take_alerts(&snapshot(97.0, 1), warnings),
+            Vec::<String>::new()
+        );
+
+        // The next window starts out critical too: alert again, once.
+        assert_eq!(
+            alerts.take_alerts(&snapshot(95.0, 2), warnings),
+            vec!["Only 5% of your 5h limit is left.".to_string()]
+        );
+        assert_eq!(
+            alerts.take_alerts(&snapshot(96.0, 2), warnings),
+            Vec::<String>::new()
+        );
+
+        // After headroom recovers, dropping again alerts again.
+        assert_eq!(
+            alerts.take_alerts(&snapshot(10.0, 3), warnings),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            alerts.take_alerts(&snapshot(91.0, 3), warnings),
+            vec!["Only 9% of your 5h limit is left.".to_string()]
+        );
+    }
+}
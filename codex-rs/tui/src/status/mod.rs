@@ -1,12 +1,18 @@
 mod account;
 mod card;
 mod format;
+mod headroom;
 mod helpers;
 mod rate_limits;
+mod usage_trend;
 
 pub(crate) use card::new_status_output;
+pub(crate) use headroom::CriticalHeadroomAlerts;
+pub(crate) use headroom::HeadroomLevel;
+pub(crate) use headroom::RateLimitHeadroom;
 pub(crate) use rate_limits::RateLimitSnapshotDisplay;
 pub(crate) use rate_limits::rate_limit_snapshot_display;
+pub(crate) use usage_trend::compose_rate_limit_trend;
 
 #[cfg(test)]
 mod tests;
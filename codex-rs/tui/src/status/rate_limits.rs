@@ -1,6 +1,8 @@
 use crate::chatwidget::get_limits_duration;
 
 use super::helpers::format_reset_timestamp;
+use super::usage_trend::RateLimitTrend;
+use super::usage_trend::TREND_HOURS;
 use chrono::DateTime;
 use chrono::Duration as ChronoDuration;
 use chrono::Local;
@@ -25,6 +27,11 @@ pub(crate) enum StatusRateLimitValue {
         percent_used: f64,
         resets_at: Option<String>,
     },
+    /// Sparkline of the remaining capacity and the lowest value it reached.
+    Trend {
+        sparkline: String,
+        lowest_percent_left: f64,
+    },
     Text(String),
 }
 
@@ -66,6 +73,8 @@ pub(crate) struct RateLimitSnapshotDisplay {
     pub primary: Option<RateLimitWindowDisplay>,
     pub secondary: Option<RateLimitWindowDisplay>,
     pub credits: Option<CreditsSnapshotDisplay>,
+    /// Recent history of the primary window, when enough samples exist.
+    pub trend: Option<RateLimitTrend>,
 }
 
 #[derive(Debug, Clone)]
@@ -90,6 +99,7 @@ pub(crate) fn rate_limit_snapshot_display(
             .as_ref()
             .map(|window| RateLimitWindowDisplay::from_window(window, captured_at)),
         credits: snapshot.credits.as_ref().map(CreditsSnapshotDisplay::from),
+        trend: None,
     }
 }
 
@@ -124,6 +134,21 @@ pub(crate) fn compose_rate_limit_data(
                         resets_at: primary.resets_at.clone(),
                     },
                 });
+                if let Some(trend) = snapshot.trend.as_ref() {
+                    rows.push(StatusRateLimitRow {
+                        label: format!("Last {TREND_HOURS}h"),
+                        value: StatusRateLimitValue::Trend {
+                            sparkline: trend.sparkline.clone(),
+                            lowest_percent_left: trend.lowest_percent_left,
+                        },
+                    });
+                    if let Some(projection) = trend.projection {
+                        rows.push(StatusRateLimitRow {
+                            label: "Pace".to_string(),
+                            value: StatusRateLimitValue::Text(projection.summary()),
+                        });
+                    }
+                }
             }
 
             if let Some(secondary) = snapshot.secondary.as_ref() {
@@ -1,3 +1,4 @@
+use super::compose_rate_limit_trend;
 use super::new_status_output;
 use super::rate_limit_snapshot_display;
 use crate::history_cell::HistoryCell;
@@ -13,6 +14,7 @@ use codex_core::protocol::RateLimitSnapshot;
 use codex_core::protocol::RateLimitWindow;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol::TokenUsage;
+use codex_core::rate_limit_history::RateLimitSample;
 use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::config_types::ReasoningSummary;
 use insta::assert_snapshot;
@@ -142,6 +144,85 @@ fn status_snapshot_includes_reasoning_details() {
     assert_snapshot!(sanitized);
 }
 
+#[test]
+fn status_snapshot_includes_usage_trend() {
+    let temp_home = TempDir::new().expect("temp home");
+    let mut config = test_config(&temp_home);
+    config.model = "gpt-5.1-codex-max".to_string();
+    config.model_provider_id = "openai".to_string();
+    config.model_reasoning_effort = Some(ReasoningEffort::High);
+    config.model_reasoning_summary = ReasoningSummary::Detailed;
+    config.sandbox_policy = SandboxPolicy::WorkspaceWrite {
+        writable_roots: Vec::new(),
+        network_access: false,
+        exclude_tmpdir_env_var: false,
+        exclude_slash_tmp: false,
+    };
+
+    config.cwd = PathBuf::from("/workspace/tests");
+
+    let auth_manager = test_auth_manager(&config);
+    let usage = TokenUsage {
+        input_tokens: 1_200,
+        cached_input_tokens: 200,
+        output_tokens: 900,
+        reasoning_output_tokens: 150,
+        total_tokens: 2_250,
+    };
+
+    let captured_at = chrono::Local
+        .with_ymd_and_hms(2024, 1, 2, 3, 4, 5)
+        .single()
+        .expect("timestamp");
+    let window = |used_percent: f64, resets_in_seconds: i64| RateLimitWindow {
+        used_percent,
+        window_minutes: Some(300),
+        resets_at: Some(reset_at_from(&captured_at, resets_in_seconds)),
+    };
+    let sample = |minutes_ago: i64, window: RateLimitWindow| RateLimitSample {
+        ts: (captured_at - ChronoDuration::minutes(minutes_ago)).timestamp(),
+        provider: "openai".to_string(),
+        primary: Some(window),
+        secondary: None,
+    };
+    // A window that reset two hours ago, a quiet stretch, then steady use of
+    // the current window.
+    let samples = vec![
+        sample(690, window(10.0, -7_200)),
+        sample(660, window(30.0, -7_200)),
+        sample(100, window(5.0, 10_800)),
+        sample(60, window(25.0, 10_800)),
+        sample(30, window(50.0, 10_800)),
+        sample(0, window(72.5, 10_800)),
+    ];
+    let snapshot = RateLimitSnapshot {
+        primary: Some(window(72.5, 10_800)),
+        secondary: None,
+        credits: None,
+    };
+    let mut rate_display = rate_limit_snapshot_display(&snapshot, captured_at);
+    rate_display.trend =
+        compose_rate_limit_trend(&samples, "openai", captured_at.with_timezone(&Utc));
+
+    let composite = new_status_output(
+        &config,
+        &auth_manager,
+        &usage,
+        Some(&usage),
+        &None,
+        Some(&rate_display),
+        captured_at,
+    );
+    let mut rendered_lines = render_lines(&composite.display_lines(80));
+    if cfg!(windows) {
+        for line in &mut rendered_lines {
+            *line = line.replace('\\', "/");
+        }
+    }
+    let sanitized = sanitize_directory(rendered_lines).join("\n");
+    assert_snapshot!(sanitized);
+}
+
 #[test]
 fn status_snapshot_includes_monthly_limit() {
     let temp_home = TempDir::new().expect("temp home");
@@ -0,0 +1,257 @@
+//! Usage trend for the primary rate-limit window, built from the samples core
+//! records in `rate_limit_history.jsonl`: a sparkline of the remaining
+//! capacity over the last hours, the lowest headroom seen, and a projection of
+//! whether the window runs out before it resets at the current burn rate.
+
+use chrono::DateTime;
+use chrono::Duration as ChronoDuration;
+use chrono::Utc;
+use codex_core::protocol::RateLimitWindow;
+use codex_core::rate_limit_history::RateLimitSample;
+
+/// How far back the sparkline reaches.
+pub(crate) const TREND_HOURS: i64 = 12;
+/// Columns in the sparkline; each covers `TREND_HOURS / SPARKLINE_BUCKETS`.
+const SPARKLINE_BUCKETS: i64 = 24;
+const SPARKLINE_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+/// Drawn for stretches with no samples (no requests were made).
+const SPARKLINE_GAP: char = '·';
+/// Only usage this recent counts towards the burn rate.
+const BURN_RATE_LOOKBACK: ChronoDuration = ChronoDuration::hours(2);
+/// Shorter spans are too noisy to project from.
+const MIN_BURN_RATE_SPAN: ChronoDuration = ChronoDuration::minutes(10);
+/// Reset times reported for the same window can drift by a few seconds.
+const RESET_TOLERANCE_SECONDS: i64 = 120;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RateLimitTrend {
+    pub sparkline: String,
+    pub lowest_percent_left: f64,
+    pub projection: Option<UsageProjection>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum UsageProjection {
+    /// At the current pace the window is used up after this long.
+    RunsOutIn(ChronoDuration),
+    /// The window resets before the current pace would use it up.
+    LastsUntilReset,
+}
+
+impl UsageProjection {
+    pub(crate) fn summary(&self) -> String {
+        match self {
+            UsageProjection::RunsOutIn(duration) => {
+                format!(
+                    "runs out in ~{} at current pace",
+                    format_duration(*duration)
+                )
+            }
+            UsageProjection::LastsUntilReset => "lasts until reset at current pace".to_string(),
+        }
+    }
+}
+
+/// Trend of the primary window over the last [`TREND_HOURS`] for `provider`.
+/// Samples from other providers are ignored; `None` when fewer than two
+/// samples remain.
+pub(crate) fn compose_rate_limit_trend(
+    samples: &[RateLimitSample],
+    provider: &str,
+    now: DateTime<Utc>,
+) -> Option<RateLimitTrend> {
+    let now_ts = now.timestamp();
+    let start = now_ts - ChronoDuration::hours(TREND_HOURS).num_seconds();
+    let points: Vec<(i64, &RateLimitWindow)> = samples
+        .iter()
+        .filter(|sample| sample.provider == provider && (start..=now_ts).contains(&sample.ts))
+        .filter_map(|sample| Some((sample.ts, sample.primary.as_ref()?)))
+        .collect();
+    if points.len() < 2 {
+        return None;
+    }
+
+    let lowest_percent_left = points
+        .iter()
+        .map(|(_, window)| percent_left(window))
+        .fold(100.0, f64::min);
+    Some(RateLimitTrend {
+        sparkline: sparkline(&points, start, now_ts),
+        lowest_percent_left,
+        projection: project(&points, now_ts),
+    })
+}
+
+fn sparkline(points: &[(i64, &RateLimitWindow)], start: i64, end: i64) -> String {
+    let bucket_seconds = ((end - start) / SPARKLINE_BUCKETS).max(1);
+    let mut lowest: Vec<Option<f64>> = vec![None; SPARKLINE_BUCKETS as usize];
+    for (ts, window) in points {
+        let bucket = ((ts - start) / bucket_seconds).clamp(0, SPARKLINE_BUCKETS - 1) as usize;
+        let left = percent_left(window);
+        lowest[bucket] = Some(lowest[bucket].map_or(left, |current| current.min(left)));
+    }
+    lowest
+        .into_iter()
+        .map(|left| match left {
+            Some(left) => {
+                let top = (SPARKLINE_LEVELS.len() - 1) as f64;
+                SPARKLINE_LEVELS[(left / 100.0 * top).round() as usize]
+            }
+            None => SPARKLINE_GAP,
+        })
+        .collect()
+}
+
+/// Project from the burn rate since the earliest recent sample of the current
+/// window. Samples from before a reset (a different reset time, or a drop in
+/// usage) are not part of the current window.
+fn project(points: &[(i64, &RateLimitWindow)], now: i64) -> Option<UsageProjection> {
+    let &(latest_ts, latest) = points.last()?;
+    let lookback_start = latest_ts - BURN_RATE_LOOKBACK.num_seconds();
+    let mut earliest = (latest_ts, latest);
+    for &(ts, window) in points.iter().rev().skip(1) {
+        if ts < lookback_start
+            || !same_window(window, latest)
+            || window.used_percent > earliest.1.used_percent
+        {
+            break;
+        }
+        earliest = (ts, window);
+    }
+
+    let span = latest_ts - earliest.0;
+    if span < MIN_BURN_RATE_SPAN.num_seconds() {
+        return None;
+    }
+    let used = latest.used_percent - earliest.1.used_percent;
+    if used <= 0.0 {
+        return latest.resets_at.map(|_| UsageProjection::LastsUntilReset);
+    }
+    let seconds_left = (100.0 - latest.used_percent).max(0.0) / (used / span as f64);
+    let runs_out_at = latest_ts + seconds_left.round() as i64;
+    if latest
+        .resets_at
+        .is_some_and(|resets_at| runs_out_at >= resets_at)
+    {
+        return Some(UsageProjection::LastsUntilReset);
+    }
+    Some(UsageProjection::RunsOutIn(ChronoDuration::seconds(
+        (runs_out_at - now).max(0),
+    )))
+}
+
+fn same_window(a: &RateLimitWindow, b: &RateLimitWindow) -> bool {
+    a.window_minutes == b.window_minutes
+        && match (a.resets_at, b.resets_at) {
+            (Some(a), Some(b)) => (a - b).abs() <= RESET_TOLERANCE_SECONDS,
+            (None, None) => true,
+            _ => false,
+        }
+}
+
+fn percent_left(window: &RateLimitWindow) -> f64 {
+    (100.0 - window.used_percent).clamp(0.0, 100.0)
+}
+
+fn format_duration(duration: ChronoDuration) -> String {
+    let minutes = duration.num_minutes().max(1);
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{minutes}m"),
+        (hours, 0) => format!("{hours}h"),
+        (hours, minutes) => format!("{hours}h {minutes}m"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const WINDOW_MINUTES: i64 = 300;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::<Utc>::from_timestamp(1_700_000_000, 0).expect("timestamp")
+    }
+
+    fn sample(minutes_ago: i64, used_percent: f64, resets_in_minutes: i64) -> RateLimitSample {
+        let now = now().timestamp();
+        RateLimitSample {
+            ts: now - minutes_ago * 60,
+            provider: "openai".to_string(),
+            primary: Some(RateLimitWindow {
+                used_percent,
+                window_minutes: Some(WINDOW_MINUTES),
+                resets_at: Some(now + resets_in_minutes * 60),
+            }),
+            secondary: None,
+        }
+    }
+
+    #[test]
+    fn steady_burn_projects_when_the_window_runs_out() {
+        // 10% per 30 minutes; 60% used leaves 40%, i.e. two hours.
+        let samples = vec![
+            sample(60, 40.0, 180),
+            sample(30, 50.0, 180),
+            sample(0, 60.0, 180),
+        ];
+
+        let trend = compose_rate_limit_trend(&samples, "openai", now()).expect("trend");
+
+        assert_eq!(
+            trend.projection,
+            Some(UsageProjection::RunsOutIn(ChronoDuration::hours(2)))
+        );
+        assert_eq!(trend.lowest_percent_left, 40.0);
+    }
+
+    #[test]
+    fn window_that_resets_first_lasts_until_reset() {
+        let samples = vec![sample(60, 40.0, 60), sample(0, 60.0, 60)];
+
+        let trend = compose_rate_limit_trend(&samples, "openai", now()).expect("trend");
+
+        assert_eq!(trend.projection, Some(UsageProjection::LastsUntilReset));
+    }
+
+    #[test]
+    fn burn_rate_ignores_usage_before_a_reset() {
+        // The window reset 40 minutes ago; only the 5% used since counts.
+        let samples = vec![
+            sample(90, 90.0, -40),
+            sample(40, 0.0, 260),
+            sample(0, 5.0, 260),
+        ];
+
+        let trend = compose_rate_limit_trend(&samples, "openai", now()).expect("trend");
+
+        // 5% per 40 minutes leaves 95% for 12h 40m, well past the reset.
+        assert_eq!(trend.projection, Some(UsageProjection::LastsUntilReset));
+        assert_eq!(trend.lowest_percent_left, 10.0);
+    }
+
+    #[test]
+    fn other_providers_and_short_spans_do_not_project() {
+        let mut other = sample(30, 10.0, 180);
+        other.provider = "azure".to_string();
+        let samples = vec![other, sample(5, 50.0, 180), sample(0, 51.0, 180)];
+
+        let trend = compose_rate_limit_trend(&samples, "openai", now()).expect("trend");
+
+        assert_eq!(trend.projection, None);
+        assert_eq!(trend.lowest_percent_left, 49.0);
+        assert_eq!(
+            compose_rate_limit_trend(&samples[..1], "openai", now()),
+            None
+        );
+    }
+
+    #[test]
+    fn sparkline_marks_gaps_between_samples() {
+        let samples = vec![sample(TREND_HOURS * 60, 0.0, 60), sample(0, 100.0, 60)];
+
+        let trend = compose_rate_limit_trend(&samples, "openai", now()).expect("trend");
+
+        assert_eq!(trend.sparkline, format!("█{}▁", "·".repeat(22)));
+    }
+}
@@ -12,13 +12,16 @@ use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::widgets::WidgetRef;
 
+use crate::animation::AnimationLevel;
 use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
 use crate::exec_cell::spinner;
 use crate::key_hint;
+use crate::render::line_utils::truncate_line_with_ellipsis;
 use crate::render::renderable::Renderable;
 use crate::shimmer::shimmer_spans;
 use crate::tui::FrameRequester;
+use crate::ui_consts::MIN_CONTENT_WIDTH;
 
 pub(crate) struct StatusIndicatorWidget {
     /// Animated header text (defaults to "Working").
@@ -50,6 +53,11 @@ pub fn fmt_elapsed_compact(elapsed_secs: u64) -> String {
 }
 
 impl StatusIndicatorWidget {
+    /// Below [`MIN_CONTENT_WIDTH`] the interrupt hint gets its own line.
+    fn stacks_interrupt_hint(&self, width: u16) -> bool {
+        self.show_interrupt_hint && width < MIN_CONTENT_WIDTH
+    }
+
     pub(crate) fn new(app_event_tx: AppEventSender, frame_requester: FrameRequester) -> Self {
         Self {
             header: String::from("Working"),
@@ -129,8 +137,12 @@ impl StatusIndicatorWidget {
 }
 
 impl Renderable for StatusIndicatorWidget {
-    fn desired_height(&self, _width: u16) -> u16 {
-        1
+    fn desired_height(&self, width: u16) -> u16 {
+        if self.stacks_interrupt_hint(width) {
+            2
+        } else {
+            1
+        }
     }
 
     fn render(&self, area: Rect, buf: &mut Buffer) {
@@ -138,29 +150,64 @@ impl Renderable for StatusIndicatorWidget {
             return;
         }
 
-        // Schedule next animation frame.
-        self.frame_requester
-            .schedule_frame_in(Duration::from_millis(32));
         let now = Instant::now();
         let elapsed_duration = self.elapsed_duration_at(now);
+        // Schedule next animation frame. When animations are off, still tick
+        // once per second so the elapsed time (here and in the terminal
+        // title) keeps counting.
+        if self.frame_requester.animation_level() == AnimationLevel::Off {
+            if !self.is_paused {
+                let into_second = Duration::from_nanos(u64::from(elapsed_duration.subsec_nanos()));
+                self.frame_requester
+                    .schedule_frame_in(Duration::from_secs(1) - into_second);
+            }
+        } else {
+            self.frame_requester
+                .schedule_animation_frame(Duration::from_millis(32));
+        }
         let pretty_elapsed = fmt_elapsed_compact(elapsed_duration.as_secs());
 
         let mut spans = Vec::with_capacity(5);
         spans.push(spinner(Some(self.last_resume_at)));
         spans.push(" ".into());
-        spans.extend(shimmer_spans(&self.header));
-        spans.push(" ".into());
-        if self.show_interrupt_hint {
+        if self.show_interrupt_hint && area.width >= MIN_CONTENT_WIDTH {
+            spans.extend(shimmer_spans(&self.header));
+            spans.push(" ".into());
             spans.extend(vec![
                 format!("({pretty_elapsed} • ").dim(),
                 key_hint::plain(KeyCode::Esc).into(),
                 " to interrupt)".dim(),
             ]);
-        } else {
-            spans.push(format!("({pretty_elapsed})").dim());
+            Line::from(spans).render_ref(area, buf);
+            return;
         }
 
+        // Without the inline hint, cut the header rather than the elapsed time.
+        // Narrow terminals move the interrupt hint to a line of its own.
+        let elapsed = format!(" ({pretty_elapsed})");
+        let header_width = usize::from(area.width)
+            .saturating_sub(2)
+            .saturating_sub(elapsed.len());
+        let header = Line::from(shimmer_spans(&self.header));
+        spans.extend(truncate_line_with_ellipsis(header, header_width).spans);
+        spans.push(elapsed.dim());
         Line::from(spans).render_ref(area, buf);
+
+        if self.stacks_interrupt_hint(area.width) && area.height > 1 {
+            let hint = Line::from(vec![
+                "  ".into(),
+                key_hint::plain(KeyCode::Esc).into(),
+                " to interrupt".dim(),
+            ]);
+            hint.render_ref(
+                Rect {
+                    y: area.y + 1,
+                    height: 1,
+                    ..area
+                },
+                buf,
+            );
+        }
     }
 }
 
@@ -191,6 +238,73 @@ mod tests {
         assert_eq!(fmt_elapsed_compact(25 * 3600 + 2 * 60 + 3), "25h 02m 03s");
     }
 
+    #[test]
+    fn slow_draws_throttle_then_stop_animation_frames() {
+        let (tx_raw, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let budget = std::sync::Arc::new(crate::animation::AnimationBudget::default());
+        let (frame_requester, mut frames) =
+            crate::tui::FrameRequester::test_with_animations(budget.clone());
+        let w = StatusIndicatorWidget::new(tx, frame_requester);
+        let mut terminal = Terminal::new(TestBackend::new(80, 1)).expect("terminal");
+        // Each draw renders the widget and then stalls like a slow link would
+        // while flushing, returning the delay until the frame it scheduled.
+        let mut draw = |flush: Duration| {
+            let before = Instant::now();
+            budget.measure(|| {
+                terminal
+                    .draw(|f| w.render(f.area(), f.buffer_mut()))
+                    .expect("draw");
+                std::thread::sleep(flush);
+            });
+            let mut deadline = None;
+            while let Ok(next) = frames.try_recv() {
+                deadline = Some(next - before);
+            }
+            deadline.expect("frame scheduled")
+        };
+
+        assert!(draw(Duration::ZERO) < Duration::from_millis(100));
+
+        for _ in 0..8 {
+            draw(Duration::from_millis(60));
+        }
+        assert_eq!(budget.level(), crate::animation::AnimationLevel::Reduced);
+        let delay = draw(Duration::ZERO);
+        assert!(delay >= Duration::from_millis(250) && delay < Duration::from_secs(1));
+
+        for _ in 0..8 {
+            draw(Duration::from_millis(60));
+        }
+        assert_eq!(budget.level(), crate::animation::AnimationLevel::Off);
+        // Animation frames stop, but the elapsed timer still ticks once a second.
+        assert!(draw(Duration::ZERO) <= Duration::from_secs(1));
+    }
+
+    #[test]
+    fn paused_timer_schedules_no_frames_when_animations_are_off() {
+        let (tx_raw, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let budget = std::sync::Arc::new(crate::animation::AnimationBudget::new(
+            codex_core::config::types::Animations::Off,
+        ));
+        let (frame_requester, mut frames) =
+            crate::tui::FrameRequester::test_with_animations(budget);
+        let mut w = StatusIndicatorWidget::new(tx, frame_requester);
+        let area = Rect::new(0, 0, 80, 1);
+        let mut buf = Buffer::empty(area);
+
+        w.render(area, &mut buf);
+        assert!(frames.try_recv().is_ok(), "running timer keeps ticking");
+
+        w.pause_timer();
+        w.render(area, &mut buf);
+        assert!(
+            frames.try_recv().is_err(),
+            "paused timer has nothing to redraw"
+        );
+    }
+
     #[test]
     fn renders_with_working_header() {
         let (tx_raw, _rx) = unbounded_channel::<AppEvent>();
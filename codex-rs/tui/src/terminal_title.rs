@@ -0,0 +1,299 @@
+//! Reflects the session status in the terminal window title.
+//!
+//! The original title is saved on the terminal's title stack (`CSI 22;0 t`)
+//! before the first update and popped again by [`restore_title`]. Terminals
+//! without a title stack ignore the push/pop; for those the title is cleared
+//! on restore so the shell can set its own again.
+
+use std::fmt;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+use crossterm::Command;
+use crossterm::queue;
+
+use crate::status_indicator_widget::fmt_elapsed_compact;
+
+/// Minimum time between two title updates.
+const MIN_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Set once a title has been written; cleared by [`restore_title`] so the
+/// next update pushes the (shell's) title again, e.g. after resuming from
+/// Ctrl-Z.
+static TITLE_PUSHED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TitleStatus {
+    Idle,
+    Working { elapsed_secs: u64 },
+    AwaitingApproval,
+    Error,
+}
+
+pub(crate) fn format_title(project: &str, status: TitleStatus) -> String {
+    match status {
+        TitleStatus::Idle => format!("○ {project}"),
+        TitleStatus::Working { elapsed_secs } => {
+            let elapsed = fmt_elapsed_compact(elapsed_secs);
+            format!("● {project} · {elapsed}")
+        }
+        TitleStatus::AwaitingApproval => format!("◐ {project} · approval needed"),
+        TitleStatus::Error => format!("✗ {project} · error"),
+    }
+}
+
+pub(crate) struct TerminalTitle {
+    project: String,
+    current: Option<String>,
+    pushed: bool,
+    last_update: Option<Instant>,
+}
+
+impl TerminalTitle {
+    pub(crate) fn new(cwd: &Path) -> Self {
+        let project = cwd
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| cwd.display().to_string());
+        Self {
+            project,
+            current: None,
+            pushed: false,
+            last_update: None,
+        }
+    }
+
+    /// Write the title for `status` if it changed. Updates are throttled to one
+    /// per [`MIN_UPDATE_INTERVAL`]; a throttled call returns how long to wait
+    /// before trying again.
+    pub(crate) fn update(
+        &mut self,
+        out: &mut impl Write,
+        status: TitleStatus,
+        now: Instant,
+    ) -> io::Result<Option<Duration>> {
+        let needs_push = !self.pushed || !TITLE_PUSHED.load(Ordering::Relaxed);
+        let title = format_title(&self.project, status);
+        if !needs_push {
+            if self.current.as_deref() == Some(title.as_str()) {
+                return Ok(None);
+            }
+            if let Some(last) = self.last_update {
+                let since = now.saturating_duration_since(last);
+                if since < MIN_UPDATE_INTERVAL {
+                    return Ok(Some(MIN_UPDATE_INTERVAL - since));
+                }
+            }
+        }
+
+        if needs_push {
+            queue!(out, PushWindowTitle)?;
+        }
+        queue!(out, SetWindowTitle(&title))?;
+        out.flush()?;
+        TITLE_PUSHED.store(true, Ordering::Relaxed);
+        self.pushed = true;
+        self.current = Some(title);
+        self.last_update = Some(now);
+        Ok(None)
+    }
+}
+
+/// Restore the title that was active before the first update. Does nothing if
+/// no title has been written.
+pub(crate) fn restore_title(out: &mut impl Write) -> io::Result<()> {
+    if TITLE_PUSHED.swap(false, Ordering::Relaxed) {
+        write_restore_sequence(out)?;
+    }
+    Ok(())
+}
+
+fn write_restore_sequence(out: &mut impl Write) -> io::Result<()> {
+    queue!(out, SetWindowTitle(""), PopWindowTitle)?;
+    out.flush()
+}
+
+/// OSC 0: set the window and icon title.
+#[derive(Debug, Clone, Copy)]
+struct SetWindowTitle<'a>(&'a str);
+
+impl Command for SetWindowTitle<'_> {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        // Control characters would end the sequence early.
+        let title: String = self.0.chars().filter(|c| !c.is_control()).collect();
+        write!(f, "\x1b]0;{title}\x07")
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        Err(std::io::Error::other(
+            "tried to execute SetWindowTitle using WinAPI; use ANSI instead",
+        ))
+    }
+
+    #[cfg(windows)]
+    fn is_ansi_code_supported(&self) -> bool {
+        true
+    }
+}
+
+/// XTWINOPS 22: save the window and icon title on the terminal's title stack.
+#[derive(Debug, Clone, Copy)]
+struct PushWindowTitle;
+
+impl Command for PushWindowTitle {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        write!(f, "\x1b[22;0t")
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        Err(std::io::Error::other(
+            "tried to execute PushWindowTitle using WinAPI; use ANSI instead",
+        ))
+    }
+
+    #[cfg(windows)]
+    fn is_ansi_code_supported(&self) -> bool {
+        true
+    }
+}
+
+/// XTWINOPS 23: restore the window and icon title from the title stack.
+#[derive(Debug, Clone, Copy)]
+struct PopWindowTitle;
+
+impl Command for PopWindowTitle {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        write!(f, "\x1b[23;0t")
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        Err(std::io::Error::other(
+            "tried to execute PopWindowTitle using WinAPI; use ANSI instead",
+        ))
+    }
+
+    #[cfg(windows)]
+    fn is_ansi_code_supported(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn update(
+        title: &mut TerminalTitle,
+        status: TitleStatus,
+        now: Instant,
+    ) -> (String, Option<Duration>) {
+        let mut out = Vec::new();
+        let retry = title.update(&mut out, status, now).expect("write title");
+        (String::from_utf8(out).expect("utf8"), retry)
+    }
+
+    #[test]
+    fn emits_title_for_each_state_transition() {
+        let mut title = TerminalTitle::new(Path::new("/home/me/codex"));
+        let start = Instant::now();
+
+        assert_eq!(
+            update(&mut title, TitleStatus::Idle, start),
+            ("\x1b[22;0t\x1b]0;○ codex\x07".to_string(), None)
+        );
+        assert_eq!(
+            update(
+                &mut title,
+                TitleStatus::Working { elapsed_secs: 65 },
+                start + Duration::from_secs(1)
+            ),
+            ("\x1b]0;● codex · 1m 05s\x07".to_string(), None)
+        );
+        assert_eq!(
+            update(
+                &mut title,
+                TitleStatus::AwaitingApproval,
+                start + Duration::from_secs(2)
+            ),
+            ("\x1b]0;◐ codex · approval needed\x07".to_string(), None)
+        );
+        assert_eq!(
+            update(
+                &mut title,
+                TitleStatus::Error,
+                start + Duration::from_secs(3)
+            ),
+            ("\x1b]0;✗ codex · error\x07".to_string(), None)
+        );
+    }
+
+    #[test]
+    fn unchanged_title_is_not_rewritten() {
+        let mut title = TerminalTitle::new(Path::new("/work/repo"));
+        let start = Instant::now();
+        update(&mut title, TitleStatus::Idle, start);
+
+        assert_eq!(
+            update(
+                &mut title,
+                TitleStatus::Idle,
+                start + Duration::from_secs(5)
+            ),
+            (String::new(), None)
+        );
+    }
+
+    #[test]
+    fn updates_are_throttled_to_one_per_second() {
+        let mut title = TerminalTitle::new(Path::new("/work/repo"));
+        let start = Instant::now();
+        update(&mut title, TitleStatus::Working { elapsed_secs: 0 }, start);
+
+        assert_eq!(
+            update(
+                &mut title,
+                TitleStatus::Working { elapsed_secs: 1 },
+                start + Duration::from_millis(400)
+            ),
+            (String::new(), Some(Duration::from_millis(600)))
+        );
+        assert_eq!(
+            update(
+                &mut title,
+                TitleStatus::Working { elapsed_secs: 1 },
+                start + Duration::from_secs(1)
+            ),
+            ("\x1b]0;● repo · 1s\x07".to_string(), None)
+        );
+    }
+
+    #[test]
+    fn control_characters_are_stripped_from_title() {
+        let mut title = TerminalTitle::new(Path::new("/work/evil\x07name"));
+
+        assert_eq!(
+            update(&mut title, TitleStatus::Idle, Instant::now()),
+            ("\x1b[22;0t\x1b]0;○ evilname\x07".to_string(), None)
+        );
+    }
+
+    #[test]
+    fn restore_clears_title_and_pops_title_stack() {
+        let mut out = Vec::new();
+        write_restore_sequence(&mut out).expect("write restore");
+
+        assert_eq!(
+            String::from_utf8(out).expect("utf8"),
+            "\x1b]0;\x07\x1b[23;0t"
+        );
+    }
+}
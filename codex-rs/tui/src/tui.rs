@@ -4,6 +4,7 @@ use std::io::Result;
 use std::io::Stdout;
 use std::io::stdout;
 use std::panic;
+use std::path::Path;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
@@ -11,17 +12,23 @@ use std::sync::atomic::Ordering;
 use std::time::Duration;
 use std::time::Instant;
 
+use codex_core::config::types::Hyperlinks;
 use crossterm::Command;
 use crossterm::SynchronizedUpdate;
 use crossterm::event::DisableBracketedPaste;
 use crossterm::event::DisableFocusChange;
+use crossterm::event::DisableMouseCapture;
 use crossterm::event::EnableBracketedPaste;
 use crossterm::event::EnableFocusChange;
+use crossterm::event::EnableMouseCapture;
 use crossterm::event::Event;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyboardEnhancementFlags;
+use crossterm::event::MouseEvent;
 use crossterm::event::PopKeyboardEnhancementFlags;
 use crossterm::event::PushKeyboardEnhancementFlags;
+use crossterm::terminal::Clear;
+use crossterm::terminal::ClearType;
 use crossterm::terminal::EnterAlternateScreen;
 use crossterm::terminal::LeaveAlternateScreen;
 use crossterm::terminal::supports_keyboard_enhancement;
@@ -35,8 +42,11 @@ use ratatui::text::Line;
 use tokio::select;
 use tokio_stream::Stream;
 
+use crate::animation::AnimationBudget;
+use crate::animation::AnimationLevel;
 use crate::custom_terminal;
 use crate::custom_terminal::Terminal as CustomTerminal;
+use crate::hyperlinks::FileLinker;
 #[cfg(unix)]
 use crate::tui::job_control::SUSPEND_KEY;
 #[cfg(unix)]
@@ -120,8 +130,10 @@ pub fn restore() -> Result<()> {
     let _ = execute!(stdout(), PopKeyboardEnhancementFlags);
     execute!(stdout(), DisableBracketedPaste)?;
     let _ = execute!(stdout(), DisableFocusChange);
+    let _ = execute!(stdout(), DisableMouseCapture);
     disable_raw_mode()?;
     let _ = execute!(stdout(), crossterm::cursor::Show);
+    let _ = crate::terminal_title::restore_title(&mut stdout());
     Ok(())
 }
 
@@ -151,6 +163,8 @@ fn set_panic_hook() {
 pub enum TuiEvent {
     Key(KeyEvent),
     Paste(String),
+    /// Only delivered while the mouse is captured (see [`Tui::set_mouse_capture`]).
+    Mouse(MouseEvent),
     Draw,
 }
 
@@ -167,11 +181,20 @@ pub struct Tui {
     // True when terminal/tab is focused; updated internally from crossterm events
     terminal_focused: Arc<AtomicBool>,
     enhanced_keys_supported: bool,
+    animations: Arc<AnimationBudget>,
+    /// Finds file references; `None` when `tui.hyperlinks` is off.
+    file_links: Option<FileLinker>,
+    /// Whether inserted history gets OSC 8 hyperlinks for file references.
+    osc8_links: bool,
+    /// `tui.mouse`: capture the mouse while the alternate screen is shown.
+    mouse_enabled: bool,
+    mouse_captured: bool,
 }
 
 #[derive(Clone, Debug)]
 pub struct FrameRequester {
     frame_schedule_tx: tokio::sync::mpsc::UnboundedSender<Instant>,
+    animations: Arc<AnimationBudget>,
 }
 impl FrameRequester {
     pub fn schedule_frame(&self) {
@@ -180,19 +203,55 @@ impl FrameRequester {
     pub fn schedule_frame_in(&self, dur: Duration) {
         let _ = self.frame_schedule_tx.send(Instant::now() + dur);
     }
+
+    /// Schedule the next frame of a decorative animation. The delay is
+    /// stretched to the animation budget's current frame interval, and no
+    /// frame is scheduled at all while animations are off.
+    pub(crate) fn schedule_animation_frame(&self, dur: Duration) {
+        if let Some(min_interval) = self.animations.frame_interval() {
+            self.schedule_frame_in(dur.max(min_interval));
+        }
+    }
+
+    pub(crate) fn animation_level(&self) -> AnimationLevel {
+        self.animations.level()
+    }
 }
 
-#[cfg(test)]
 impl FrameRequester {
-    /// Create a no-op frame requester for tests.
-    pub(crate) fn test_dummy() -> Self {
+    /// Create a frame requester that is not attached to a terminal; used when
+    /// rendering headlessly (e.g. `codex replay --diff-against-live`).
+    pub(crate) fn detached() -> Self {
         let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
         FrameRequester {
             frame_schedule_tx: tx,
+            animations: Arc::new(AnimationBudget::default()),
         }
     }
 }
 
+#[cfg(test)]
+impl FrameRequester {
+    /// Create a no-op frame requester for tests.
+    pub(crate) fn test_dummy() -> Self {
+        Self::detached()
+    }
+
+    /// Create a frame requester whose scheduled frames can be observed.
+    pub(crate) fn test_with_animations(
+        animations: Arc<AnimationBudget>,
+    ) -> (Self, tokio::sync::mpsc::UnboundedReceiver<Instant>) {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        (
+            FrameRequester {
+                frame_schedule_tx: tx,
+                animations,
+            },
+            rx,
+        )
+    }
+}
+
 impl Tui {
     pub fn new(terminal: Terminal) -> Self {
         let (frame_schedule_tx, frame_schedule_rx) = tokio::sync::mpsc::unbounded_channel();
@@ -207,6 +266,9 @@ impl Tui {
         supports_color::on_cached(supports_color::Stream::Stdout);
         let _ = crate::terminal_palette::default_colors();
 
+        let animations = Arc::new(AnimationBudget::default());
+        crate::animation::install(animations.clone());
+
         Self {
             frame_schedule_tx,
             draw_tx,
@@ -218,13 +280,75 @@ impl Tui {
             alt_screen_active: Arc::new(AtomicBool::new(false)),
             terminal_focused: Arc::new(AtomicBool::new(true)),
             enhanced_keys_supported,
+            animations,
+            file_links: None,
+            osc8_links: false,
+            mouse_enabled: false,
+            mouse_captured: false,
         }
     }
 
     pub fn frame_requester(&self) -> FrameRequester {
         FrameRequester {
             frame_schedule_tx: self.frame_schedule_tx.clone(),
+            animations: self.animations.clone(),
+        }
+    }
+
+    /// Apply the `tui.animations` setting.
+    pub fn set_animation_setting(&self, setting: codex_core::config::types::Animations) {
+        self.animations.set_setting(setting);
+    }
+
+    /// Apply the `tui.hyperlinks` setting: file references in history written
+    /// to the scrollback become OSC 8 hyperlinks where the terminal supports
+    /// them, and alternate-screen views underline them.
+    pub(crate) fn set_file_links(&mut self, setting: Hyperlinks, links: FileLinker) {
+        self.osc8_links = crate::hyperlinks::osc8_enabled(setting);
+        self.file_links = (setting != Hyperlinks::Off).then_some(links);
+    }
+
+    /// Resolve file references against the session's current cwd.
+    pub(crate) fn set_file_links_cwd(&mut self, cwd: &Path) {
+        if let Some(links) = self.file_links.as_mut() {
+            links.set_cwd(cwd);
+        }
+    }
+
+    pub(crate) fn file_links(&self) -> Option<&FileLinker> {
+        self.file_links.as_ref()
+    }
+
+    /// Apply the `tui.mouse` setting.
+    pub fn set_mouse_enabled(&mut self, enabled: bool) {
+        self.mouse_enabled = enabled;
+    }
+
+    pub fn mouse_enabled(&self) -> bool {
+        self.mouse_enabled
+    }
+
+    pub fn mouse_captured(&self) -> bool {
+        self.mouse_captured
+    }
+
+    /// Capture the mouse so wheel events reach the app, or release it so the
+    /// terminal's native text selection works. Capture only happens on the
+    /// alternate screen with `tui.mouse` enabled; inline mode keeps the
+    /// terminal's own scrollback. Terminals without mouse reporting ignore
+    /// the request.
+    pub fn set_mouse_capture(&mut self, capture: bool) {
+        let capture =
+            capture && self.mouse_enabled && self.alt_screen_active.load(Ordering::Relaxed);
+        if capture == self.mouse_captured {
+            return;
         }
+        let _ = if capture {
+            execute!(self.terminal.backend_mut(), EnableMouseCapture)
+        } else {
+            execute!(self.terminal.backend_mut(), DisableMouseCapture)
+        };
+        self.mouse_captured = capture;
     }
 
     pub fn enhanced_keys_supported(&self) -> bool {
@@ -276,6 +400,9 @@ impl Tui {
                             Event::Paste(pasted) => {
                                 yield TuiEvent::Paste(pasted);
                             }
+                            Event::Mouse(mouse_event) => {
+                                yield TuiEvent::Mouse(mouse_event);
+                            }
                             Event::FocusGained => {
                                 terminal_focused.store(true, Ordering::Relaxed);
                                 crate::terminal_palette::requery_default_colors();
@@ -327,8 +454,19 @@ impl Tui {
         Ok(())
     }
 
+    /// Enter the alternate screen for a pager overlay, which handles mouse
+    /// events itself, and capture the mouse when `tui.mouse` is on. Other
+    /// full-screen prompts use [`Tui::enter_alt_screen`] so the terminal keeps
+    /// turning the wheel into arrow keys for them.
+    pub fn enter_overlay_screen(&mut self) -> Result<()> {
+        self.enter_alt_screen()?;
+        self.set_mouse_capture(true);
+        Ok(())
+    }
+
     /// Leave alternate screen and restore the previously saved inline viewport, if any.
     pub fn leave_alt_screen(&mut self) -> Result<()> {
+        self.set_mouse_capture(false);
         // Disable alternate scroll when leaving alt-screen
         let _ = execute!(self.terminal.backend_mut(), DisableAlternateScroll);
         let _ = execute!(self.terminal.backend_mut(), LeaveAlternateScreen);
@@ -339,6 +477,25 @@ impl Tui {
         Ok(())
     }
 
+    /// Wipe the visible screen and move the inline viewport to the top so
+    /// history can be inserted again from scratch.
+    pub fn clear_history(&mut self) -> Result<()> {
+        self.pending_history_lines.clear();
+        // `ClearType::Purge` would also wipe the scrollback from before the
+        // session, which is not ours to remove.
+        execute!(self.terminal.backend_mut(), Clear(ClearType::All))?;
+        let area = self.terminal.viewport_area;
+        self.terminal.set_viewport_area(ratatui::layout::Rect::new(
+            area.x,
+            0,
+            area.width,
+            area.height,
+        ));
+        self.terminal.clear()?;
+        self.frame_requester().schedule_frame();
+        Ok(())
+    }
+
     pub fn insert_history_lines(&mut self, lines: Vec<Line<'static>>) {
         self.pending_history_lines.extend(lines);
         self.frame_requester().schedule_frame();
@@ -381,60 +538,71 @@ impl Tui {
             }
         }
 
-        stdout().sync_update(|_| {
-            #[cfg(unix)]
-            if let Some(prepared) = prepared_resume.take() {
-                prepared.apply(&mut self.terminal)?;
-            }
-            let terminal = &mut self.terminal;
-            if let Some(new_area) = pending_viewport_area.take() {
-                terminal.set_viewport_area(new_area);
-                terminal.clear()?;
-            }
+        // Render plus flush time tells us whether the terminal (or the link to
+        // it) keeps up with animation frames.
+        let animations = self.animations.clone();
+        let result = animations.measure(|| {
+            stdout().sync_update(|_| {
+                #[cfg(unix)]
+                if let Some(prepared) = prepared_resume.take() {
+                    prepared.apply(&mut self.terminal)?;
+                    // Suspending released the mouse along with the other modes.
+                    if self.mouse_captured {
+                        let _ = execute!(self.terminal.backend_mut(), EnableMouseCapture);
+                    }
+                }
+                let terminal = &mut self.terminal;
+                if let Some(new_area) = pending_viewport_area.take() {
+                    terminal.set_viewport_area(new_area);
+                    terminal.clear()?;
+                }
 
-            let size = terminal.size()?;
-
-            let mut area = terminal.viewport_area;
-            area.height = height.min(size.height);
-            area.width = size.width;
-            // If the viewport has expanded, scroll everything else up to make room.
-            if area.bottom() > size.height {
-                terminal
-                    .backend_mut()
-                    .scroll_region_up(0..area.top(), area.bottom() - size.height)?;
-                area.y = size.height - area.height;
-            }
-            if area != terminal.viewport_area {
-                // TODO(nornagon): probably this could be collapsed with the clear + set_viewport_area above.
-                terminal.clear()?;
-                terminal.set_viewport_area(area);
-            }
+                let size = terminal.size()?;
+
+                let mut area = terminal.viewport_area;
+                area.height = height.min(size.height);
+                area.width = size.width;
+                // If the viewport has expanded, scroll everything else up to make room.
+                if area.bottom() > size.height {
+                    terminal
+                        .backend_mut()
+                        .scroll_region_up(0..area.top(), area.bottom() - size.height)?;
+                    area.y = size.height - area.height;
+                }
+                if area != terminal.viewport_area {
+                    // TODO(nornagon): probably this could be collapsed with the clear + set_viewport_area above.
+                    terminal.clear()?;
+                    terminal.set_viewport_area(area);
+                }
 
-            if !self.pending_history_lines.is_empty() {
-                crate::insert_history::insert_history_lines(
-                    terminal,
-                    self.pending_history_lines.clone(),
-                )?;
-                self.pending_history_lines.clear();
-            }
+                if !self.pending_history_lines.is_empty() {
+                    crate::insert_history::insert_history_lines_with_links(
+                        terminal,
+                        self.pending_history_lines.clone(),
+                        self.file_links.as_ref().filter(|_| self.osc8_links),
+                    )?;
+                    self.pending_history_lines.clear();
+                }
 
-            // Update the y position for suspending so Ctrl-Z can place the cursor correctly.
-            #[cfg(unix)]
-            {
-                let inline_area_bottom = if self.alt_screen_active.load(Ordering::Relaxed) {
-                    self.alt_saved_viewport
-                        .map(|r| r.bottom().saturating_sub(1))
-                        .unwrap_or_else(|| area.bottom().saturating_sub(1))
-                } else {
-                    area.bottom().saturating_sub(1)
-                };
-                self.suspend_context.set_cursor_y(inline_area_bottom);
-            }
+                // Update the y position for suspending so Ctrl-Z can place the cursor correctly.
+                #[cfg(unix)]
+                {
+                    let inline_area_bottom = if self.alt_screen_active.load(Ordering::Relaxed) {
+                        self.alt_saved_viewport
+                            .map(|r| r.bottom().saturating_sub(1))
+                            .unwrap_or_else(|| area.bottom().saturating_sub(1))
+                    } else {
+                        area.bottom().saturating_sub(1)
+                    };
+                    self.suspend_context.set_cursor_y(inline_area_bottom);
+                }
 
-            terminal.draw(|frame| {
-                draw_fn(frame);
+                terminal.draw(|frame| {
+                    draw_fn(frame);
+                })
             })
-        })?
+        })?;
+        result
     }
 }
 
@@ -0,0 +1,264 @@
+//! Optional visual separation between turns in the scrollback, configured by
+//! `tui.turn_separators`.
+//!
+//! Turns are identified by the [`TurnMarker`] the chat widget emits when the
+//! user starts one, so every history cell inserted afterwards belongs to that
+//! turn. Decoration is applied to a cell's lines before they are inserted:
+//! stripes set the line background, which `insert_history_lines` already
+//! fills to the full terminal width, so scrolling costs nothing extra.
+
+use chrono::DateTime;
+use chrono::Local;
+use codex_core::config::types::TurnSeparators;
+use ratatui::style::Color;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use unicode_width::UnicodeWidthStr;
+
+use crate::color::blend;
+use crate::terminal_palette::best_color;
+use crate::terminal_palette::default_bg;
+use crate::ui_consts::MIN_CONTENT_WIDTH;
+
+/// Cyan, the color `styles.md` reserves for hints and other info text.
+const INFO_RGB: (u8, u8, u8) = (0, 255, 255);
+/// How far striped turns shift the background toward [`INFO_RGB`]. Kept well
+/// below the user message tint so the stripe never competes with it.
+const STRIPE_ALPHA: f32 = 0.04;
+
+/// Metadata for the turn that subsequent history cells belong to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct TurnMarker {
+    pub(crate) turn_index: usize,
+    pub(crate) started_at: DateTime<Local>,
+}
+
+/// `tui.turn_separators` resolved against what the terminal can display.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum TurnDecoration {
+    Stripe(Color),
+    Line,
+    Off,
+}
+
+impl TurnDecoration {
+    pub(crate) fn resolve(mode: TurnSeparators) -> Self {
+        Self::resolve_with(mode, default_bg(), best_color)
+    }
+
+    fn resolve_with(
+        mode: TurnSeparators,
+        terminal_bg: Option<(u8, u8, u8)>,
+        to_color: impl Fn((u8, u8, u8)) -> Color,
+    ) -> Self {
+        match mode {
+            TurnSeparators::Off => TurnDecoration::Off,
+            TurnSeparators::Line => TurnDecoration::Line,
+            // Monochrome terminals, NO_COLOR, and an unknown background cannot
+            // show a tint, so fall back to rules.
+            TurnSeparators::Stripe => match terminal_bg.map(|bg| to_color(stripe_rgb(bg))) {
+                Some(color) if color != Color::default() => TurnDecoration::Stripe(color),
+                _ => TurnDecoration::Line,
+            },
+        }
+    }
+}
+
+fn stripe_rgb(terminal_bg: (u8, u8, u8)) -> (u8, u8, u8) {
+    blend(INFO_RGB, terminal_bg, STRIPE_ALPHA)
+}
+
+/// Tracks the current turn and decorates history lines as they are inserted.
+#[derive(Debug)]
+pub(crate) struct TurnSeparatorState {
+    decoration: TurnDecoration,
+    current: Option<TurnMarker>,
+    /// Set until the first lines of the current turn have been inserted.
+    turn_pending: bool,
+}
+
+impl TurnSeparatorState {
+    pub(crate) fn new(mode: TurnSeparators) -> Self {
+        Self::with_decoration(TurnDecoration::resolve(mode))
+    }
+
+    fn with_decoration(decoration: TurnDecoration) -> Self {
+        Self {
+            decoration,
+            current: None,
+            turn_pending: false,
+        }
+    }
+
+    pub(crate) fn start_turn(&mut self, marker: TurnMarker) {
+        self.current = Some(marker);
+        self.turn_pending = true;
+    }
+
+    /// Decorates the lines of one history cell. `has_separator` is set when
+    /// `lines` starts with the blank line separating it from the previous cell.
+    pub(crate) fn decorate(
+        &mut self,
+        lines: &mut Vec<Line<'static>>,
+        has_separator: bool,
+        width: u16,
+    ) {
+        let Some(turn) = self.current else {
+            return;
+        };
+        let starts_turn = std::mem::take(&mut self.turn_pending);
+        match self.decoration {
+            TurnDecoration::Off => {}
+            TurnDecoration::Line => {
+                if !starts_turn {
+                    return;
+                }
+                let rule = separator_line(width, turn.started_at);
+                match lines.first_mut() {
+                    Some(first) if has_separator => *first = rule,
+                    _ => lines.insert(0, rule),
+                }
+            }
+            TurnDecoration::Stripe(bg) => {
+                if turn.turn_index % 2 == 0 {
+                    return;
+                }
+                for line in lines.iter_mut().filter(|line| line.style.bg.is_none()) {
+                    line.style.bg = Some(bg);
+                }
+            }
+        }
+    }
+}
+
+/// A dim rule spanning `width` with the turn's start time centered.
+fn separator_line(width: u16, started_at: DateTime<Local>) -> Line<'static> {
+    let width = usize::from(width);
+    let label = format!(" {} ", started_at.format("%H:%M:%S"));
+    let label_width = label.width();
+    if width < usize::from(MIN_CONTENT_WIDTH) || width < label_width + 2 {
+        return Line::from("─".repeat(width)).dim();
+    }
+    let left = (width - label_width) / 2;
+    let right = width - label_width - left;
+    Line::from_iter(["─".repeat(left), label, "─".repeat(right)]).dim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history_cell::HistoryCell;
+    use crate::history_cell::new_user_prompt;
+    use chrono::TimeZone;
+    use insta::assert_snapshot;
+    use pretty_assertions::assert_eq;
+
+    const WIDTH: u16 = 40;
+
+    #[allow(clippy::disallowed_methods)]
+    fn stripe_color() -> Color {
+        Color::Rgb(10, 20, 30)
+    }
+
+    fn marker(turn_index: usize) -> TurnMarker {
+        let started_at = Local
+            .with_ymd_and_hms(2025, 1, 1, 9, 30 + turn_index as u32, 0)
+            .single()
+            .expect("valid local time");
+        TurnMarker {
+            turn_index,
+            started_at,
+        }
+    }
+
+    /// Feeds three turns through `state` the way `App` inserts history cells
+    /// and renders each row, marking rows that carry the stripe background.
+    fn render_three_turns(mut state: TurnSeparatorState) -> String {
+        let mut rows = Vec::new();
+        let mut emitted = false;
+        for turn_index in 0..3 {
+            state.start_turn(marker(turn_index));
+            let user = new_user_prompt(format!("question {turn_index}")).display_lines(WIDTH);
+            let agent = vec![Line::from(format!("• answer {turn_index}"))];
+            for cell_lines in [user, agent] {
+                let mut lines = cell_lines;
+                if emitted {
+                    lines.insert(0, Line::from(""));
+                }
+                state.decorate(&mut lines, emitted, WIDTH);
+                emitted = true;
+                rows.extend(lines);
+            }
+        }
+        rows.iter()
+            .map(|line| {
+                let text: String = line
+                    .spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect();
+                let marker = if line.style.bg == Some(stripe_color()) {
+                    "▌"
+                } else {
+                    " "
+                };
+                format!("{marker}{text}")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn three_turns_with_stripes() {
+        let state = TurnSeparatorState::with_decoration(TurnDecoration::Stripe(stripe_color()));
+        assert_snapshot!(render_three_turns(state));
+    }
+
+    #[test]
+    fn three_turns_with_lines() {
+        let state = TurnSeparatorState::with_decoration(TurnDecoration::Line);
+        assert_snapshot!(render_three_turns(state));
+    }
+
+    #[test]
+    fn three_turns_without_separators() {
+        let state = TurnSeparatorState::with_decoration(TurnDecoration::Off);
+        assert_snapshot!(render_three_turns(state));
+    }
+
+    #[test]
+    fn three_turns_with_stripes_on_monochrome_terminal() {
+        let decoration =
+            TurnDecoration::resolve_with(TurnSeparators::Stripe, Some((0, 0, 0)), |_| {
+                Color::default()
+            });
+        assert_eq!(decoration, TurnDecoration::Line);
+        let state = TurnSeparatorState::with_decoration(decoration);
+        assert_snapshot!(render_three_turns(state));
+    }
+
+    #[test]
+    fn narrow_separator_drops_timestamp() {
+        let line = separator_line(38, marker(0).started_at);
+        let text: String = line
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(text, "─".repeat(38));
+    }
+
+    #[test]
+    fn stripe_falls_back_to_line_without_known_background() {
+        assert_eq!(
+            TurnDecoration::resolve_with(TurnSeparators::Stripe, None, |_| stripe_color()),
+            TurnDecoration::Line
+        );
+        assert_eq!(
+            TurnDecoration::resolve_with(TurnSeparators::Stripe, Some((0, 0, 0)), |_| {
+                stripe_color()
+            }),
+            TurnDecoration::Stripe(stripe_color())
+        );
+    }
+}
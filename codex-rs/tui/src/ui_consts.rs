@@ -9,3 +9,12 @@
 /// - User history lines account for this many columns (e.g., "▌ ") when wrapping.
 pub(crate) const LIVE_PREFIX_COLS: u16 = 2;
 pub(crate) const FOOTER_INDENT_COLS: usize = LIVE_PREFIX_COLS as usize;
+
+/// Narrowest width at which widgets use their full layout. Below it they
+/// degrade: the status line moves its interrupt hint to a second line and
+/// turn separators drop their timestamp.
+pub(crate) const MIN_CONTENT_WIDTH: u16 = 40;
+
+/// Below this width the chat view is replaced by a "terminal too narrow"
+/// notice rather than rendering clipped, overlapping widgets.
+pub(crate) const MIN_RENDER_WIDTH: u16 = 20;
@@ -6,7 +6,8 @@ use chrono::DateTime;
 use chrono::Duration;
 use chrono::Utc;
 use codex_core::config::Config;
-use codex_core::default_client::create_client;
+use codex_core::default_client::CodexHttpClient;
+use codex_core::network_policy::NetworkCapability;
 use serde::Deserialize;
 use serde::Serialize;
 use std::path::Path;
@@ -15,6 +16,11 @@ use std::path::PathBuf;
 use crate::version::CODEX_CLI_VERSION;
 
 pub fn get_upgrade_version(config: &Config) -> Option<String> {
+    // Offline mode skips both the background check and the banner.
+    let client = config
+        .network_policy()
+        .client(NetworkCapability::Updates)
+        .ok()?;
     let version_file = version_filepath(config);
     let info = read_version_info(&version_file).ok();
 
@@ -26,7 +32,7 @@ pub fn get_upgrade_version(config: &Config) -> Option<String> {
         // isn’t blocked by a network call. The UI reads the previously cached
         // value (if any) for this run; the next run shows the banner if needed.
         tokio::spawn(async move {
-            check_for_update(&version_file)
+            check_for_update(&client, &version_file)
                 .await
                 .inspect_err(|e| tracing::error!("Failed to update version: {e}"))
         });
@@ -62,7 +68,10 @@ struct ReleaseInfo {
 }
 
 fn version_filepath(config: &Config) -> PathBuf {
-    config.codex_home.join(VERSION_FILENAME)
+    config
+        .persistence
+        .cache_home(&config.codex_home)
+        .join(VERSION_FILENAME)
 }
 
 fn read_version_info(version_file: &Path) -> anyhow::Result<VersionInfo> {
@@ -70,10 +79,10 @@ fn read_version_info(version_file: &Path) -> anyhow::Result<VersionInfo> {
     Ok(serde_json::from_str(&contents)?)
 }
 
-async fn check_for_update(version_file: &Path) -> anyhow::Result<()> {
+async fn check_for_update(client: &CodexHttpClient, version_file: &Path) -> anyhow::Result<()> {
     let latest_version = match update_action::get_update_action() {
         Some(UpdateAction::BrewUpgrade) => {
-            let cask_contents = create_client()
+            let cask_contents = client
                 .get(HOMEBREW_CASK_URL)
                 .send()
                 .await?
@@ -85,7 +94,7 @@ async fn check_for_update(version_file: &Path) -> anyhow::Result<()> {
         _ => {
             let ReleaseInfo {
                 tag_name: latest_tag_name,
-            } = create_client()
+            } = client
                 .get(LATEST_RELEASE_URL)
                 .send()
                 .await?
@@ -1,4 +1,4 @@
-#[path = "../src/test_backend.rs"]
+#[path = "../src/headless_backend.rs"]
 mod inner;
 
-pub use inner::VT100Backend;
+pub use inner::HeadlessBackend as VT100Backend;
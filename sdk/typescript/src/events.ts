@@ -31,6 +31,8 @@ export type Usage = {
 export type TurnCompletedEvent = {
   type: "turn.completed";
   usage: Usage;
+  /** The final response parsed as JSON, present when an output schema was provided. */
+  final_output?: unknown;
 };
 
 /** Indicates that a turn failed with an error. */
@@ -39,6 +41,27 @@ export type TurnFailedEvent = {
   error: ThreadError;
 };
 
+/** Emitted when a `[turn_limits]` cap stopped the turn early. The turn still completes. */
+export type TurnLimitReachedEvent = {
+  type: "turn.limit_reached";
+  limit: "wall_clock" | "output_tokens";
+  /** The configured cap: seconds for `wall_clock`, tokens for `output_tokens`. */
+  threshold: number;
+};
+
+/** Emitted after every model request with that request's token usage. */
+export type UsageUpdatedEvent = {
+  type: "usage.updated";
+  /** Usage of the model request that just completed. */
+  last: Usage;
+  /** Cumulative usage for the thread so far. */
+  total: Usage;
+  /** The model that served the request. */
+  model?: string;
+  /** Whether `last` was estimated locally because the provider did not report usage. */
+  estimated: boolean;
+};
+
 /** Emitted when a new item is added to the thread. Typically the item is initially "in progress". */
 export type ItemStartedEvent = {
   type: "item.started";
@@ -68,13 +91,31 @@ export type ThreadErrorEvent = {
   message: string;
 };
 
+/** Emitted in watch mode when changes to watched files start a new turn. */
+export type WatchTriggeredEvent = {
+  type: "watch.triggered";
+  /** Changed paths, relative to the working directory. */
+  paths: string[];
+};
+
+/** Emitted when the `--timeout` limit expires and the run is interrupted. */
+export type TimeoutEvent = {
+  type: "timeout";
+  /** The configured limit in seconds. */
+  seconds: number;
+};
+
 /** Top-level JSONL events emitted by codex exec. */
 export type ThreadEvent =
   | ThreadStartedEvent
   | TurnStartedEvent
   | TurnCompletedEvent
   | TurnFailedEvent
+  | TurnLimitReachedEvent
+  | UsageUpdatedEvent
   | ItemStartedEvent
   | ItemUpdatedEvent
   | ItemCompletedEvent
-  | ThreadErrorEvent;
+  | ThreadErrorEvent
+  | WatchTriggeredEvent
+  | TimeoutEvent;
@@ -4,12 +4,16 @@ export type {
   TurnStartedEvent,
   TurnCompletedEvent,
   TurnFailedEvent,
+  TurnLimitReachedEvent,
+  UsageUpdatedEvent,
   ItemStartedEvent,
   ItemUpdatedEvent,
   ItemCompletedEvent,
   ThreadError,
   ThreadErrorEvent,
   Usage,
+  WatchTriggeredEvent,
+  TimeoutEvent,
 } from "./events";
 export type {
   ThreadItem,